@@ -0,0 +1,78 @@
+//! Criterion benchmarks for real kernel paths.
+//!
+//! Requires the docker-compose Postgres and Redis services; see
+//! `docs/benchmarks/KERNEL-BENCHMARKS.md`.
+
+#![allow(clippy::expect_used)]
+
+use std::time::{Duration, Instant};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use tokio::runtime::Runtime;
+use trovato_kernel_bench::{BenchApp, FIXTURE_GATHER_QUERY, FIXTURE_SEARCH_TERM};
+
+fn kernel_benches(c: &mut Criterion) {
+    let rt = Runtime::new().expect("failed to build tokio runtime");
+    let app = rt
+        .block_on(BenchApp::boot())
+        .expect("failed to boot kernel benchmark app");
+
+    let mut group = c.benchmark_group("kernel");
+    group.measurement_time(Duration::from_secs(10));
+
+    // Full HTML render: load, access check, tap_item_view, theme.
+    let item_id = app.item_ids[0];
+    let item_uri = format!("/item/{item_id}");
+    group.bench_function("item_get_html", |b| {
+        b.to_async(&rt).iter(|| async {
+            app.get(&item_uri).await.expect("item GET failed");
+        });
+    });
+
+    let item_api_uri = format!("/api/item/{item_id}");
+    group.bench_function("item_get_api", |b| {
+        b.to_async(&rt).iter(|| async {
+            app.get(&item_api_uri).await.expect("item API GET failed");
+        });
+    });
+
+    let gather_uri = format!("/api/query/{FIXTURE_GATHER_QUERY}/execute");
+    group.bench_function("gather_query", |b| {
+        b.to_async(&rt).iter(|| async {
+            app.get(&gather_uri).await.expect("gather query failed");
+        });
+    });
+
+    let search_uri = format!("/api/search?q={FIXTURE_SEARCH_TERM}");
+    group.bench_function("search", |b| {
+        b.to_async(&rt).iter(|| async {
+            app.get(&search_uri).await.expect("search failed");
+        });
+    });
+
+    // Each iteration stages a fresh item, then times only the publish.
+    group.bench_function("stage_publish", |b| {
+        b.to_async(&rt).iter_custom(|iters| {
+            let app = &app;
+            async move {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    app.stage_item().await.expect("failed to stage item");
+                    let start = Instant::now();
+                    app.state
+                        .stage()
+                        .publish(app.stage_id)
+                        .await
+                        .expect("stage publish failed");
+                    total += start.elapsed();
+                }
+                total
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, kernel_benches);
+criterion_main!(benches);
@@ -0,0 +1,199 @@
+//! End-to-end kernel benchmark harness.
+//!
+//! Boots the real [`AppState`] against the docker-compose Postgres and Redis
+//! services, seeds fixture content, and serves requests through the kernel
+//! router, so benchmarks exercise the same taps, caches, and queries as
+//! production traffic.
+//!
+//! Usage:
+//!   docker compose up -d
+//!   cargo bench -p trovato-kernel-bench
+//!
+//! See `docs/benchmarks/KERNEL-BENCHMARKS.md` for details.
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+use trovato_kernel::models::CreateItem;
+use trovato_kernel::models::stage::CreateStage;
+use trovato_kernel::tap::UserContext;
+use trovato_kernel::{AppState, Config};
+use uuid::Uuid;
+
+/// Number of published items seeded for read benchmarks.
+pub const FIXTURE_ITEM_COUNT: usize = 200;
+
+/// Content type used for fixture items.
+pub const FIXTURE_ITEM_TYPE: &str = "page";
+
+/// Word present in every fixture title and body, for search benchmarks.
+pub const FIXTURE_SEARCH_TERM: &str = "benchmark";
+
+/// Gather query used for listing benchmarks (registered by the kernel).
+pub const FIXTURE_GATHER_QUERY: &str = "core.published_items";
+
+/// Machine name of the stage used for publish benchmarks.
+pub const FIXTURE_STAGE: &str = "kernel_bench";
+
+/// Title prefix identifying fixture items, so reruns can clean them up.
+const FIXTURE_TITLE_PREFIX: &str = "Kernel benchmark item";
+
+/// A booted kernel with seeded fixture content.
+pub struct BenchApp {
+    /// Application state, for service-level benchmarks.
+    pub state: AppState,
+    /// Published fixture item IDs.
+    pub item_ids: Vec<Uuid>,
+    /// Stage used for publish benchmarks.
+    pub stage_id: Uuid,
+    router: Router,
+    admin: UserContext,
+}
+
+impl BenchApp {
+    /// Boot the kernel from the environment (`.env` is honoured) and seed
+    /// fixtures.
+    pub async fn boot() -> Result<Self> {
+        dotenvy::dotenv().ok();
+
+        let config = Config::from_env().context("failed to load config")?;
+        let state = AppState::new(&config)
+            .await
+            .context("failed to initialize AppState (is `docker compose up` running?)")?;
+
+        let session_settings = trovato_kernel::session::SessionSettings::from_config(&config)
+            .context("invalid session settings")?;
+        let session_layer = trovato_kernel::session::create_session_layer(
+            &session_settings,
+            state.redis().settings(),
+            state.db(),
+            tower_sessions::cookie::SameSite::Strict,
+        )
+        .await
+        .context("failed to create session layer")?;
+
+        // Only the routes under benchmark, with the middleware they rely on.
+        let router = Router::new()
+            .merge(trovato_kernel::routes::item::router())
+            .merge(trovato_kernel::routes::gather::router())
+            .merge(trovato_kernel::routes::search::router())
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                trovato_kernel::middleware::negotiate_language,
+            ))
+            .layer(session_layer)
+            .with_state(state.clone());
+
+        let admin = UserContext::authenticated(Uuid::nil(), vec!["administer site".to_string()]);
+
+        let mut app = Self {
+            state,
+            item_ids: Vec::new(),
+            stage_id: Uuid::nil(),
+            router,
+            admin,
+        };
+        app.seed().await?;
+        Ok(app)
+    }
+
+    /// Send a GET request through the kernel router and drain the body.
+    pub async fn get(&self, uri: &str) -> Result<StatusCode> {
+        let request = Request::get(uri)
+            .body(Body::empty())
+            .context("failed to build request")?;
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .context("router error")?;
+        let status = response.status();
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .context("failed to read response body")?;
+        Ok(status)
+    }
+
+    /// Create an unpublished item in the benchmark stage, ready to publish.
+    pub async fn stage_item(&self) -> Result<Uuid> {
+        let item = self
+            .state
+            .items()
+            .create(self.fixture_input(0, Some(self.stage_id)), &self.admin)
+            .await?;
+        Ok(item.id)
+    }
+
+    /// Remove fixtures from earlier runs and seed fresh ones.
+    async fn seed(&mut self) -> Result<()> {
+        remove_fixtures(&self.state).await?;
+
+        self.stage_id = match self
+            .state
+            .stage()
+            .get_stage_by_machine_name(FIXTURE_STAGE)
+            .await?
+        {
+            Some(stage) => stage.id,
+            None => {
+                self.state
+                    .stage()
+                    .create_stage(CreateStage {
+                        label: "Kernel benchmark".to_string(),
+                        machine_name: FIXTURE_STAGE.to_string(),
+                        description: Some("Items staged by the kernel benchmarks".to_string()),
+                        visibility: None,
+                        is_default: None,
+                        weight: None,
+                    })
+                    .await?
+                    .id
+            }
+        };
+
+        for n in 0..FIXTURE_ITEM_COUNT {
+            let item = self
+                .state
+                .items()
+                .create(self.fixture_input(n, None), &self.admin)
+                .await?;
+            self.item_ids.push(item.id);
+        }
+        Ok(())
+    }
+
+    fn fixture_input(&self, n: usize, stage_id: Option<Uuid>) -> CreateItem {
+        CreateItem {
+            item_type: FIXTURE_ITEM_TYPE.to_string(),
+            title: format!("{FIXTURE_TITLE_PREFIX} {n}"),
+            author_id: self.admin.id,
+            status: Some(1),
+            promote: None,
+            sticky: None,
+            fields: Some(serde_json::json!({
+                "body": {
+                    "value": format!(
+                        "Fixture body {n} for {FIXTURE_SEARCH_TERM} runs across kernel routes."
+                    ),
+                    "format": "plain_text",
+                },
+            })),
+            stage_id,
+            language: None,
+            log: Some("Seeded by kernel benchmarks".to_string()),
+        }
+    }
+}
+
+/// Delete fixture items left behind by earlier runs.
+async fn remove_fixtures(state: &AppState) -> Result<()> {
+    sqlx::query("DELETE FROM item WHERE title LIKE $1")
+        .bind(format!("{FIXTURE_TITLE_PREFIX} %"))
+        .execute(state.db())
+        .await
+        .context("failed to remove old fixtures")?;
+    Ok(())
+}
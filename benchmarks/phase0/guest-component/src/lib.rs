@@ -0,0 +1,120 @@
+//! Component-model variant of the Phase 0 benchmark plugin.
+//!
+//! Runs the same workloads as `phase0-guest`, but through WIT bindings
+//! (`wit/phase0.wit`) instead of the raw pointer-packing ABI:
+//! - `tap-item-view`: handle-based (receives a handle, calls imported functions)
+//! - `tap-item-view-full`: full-serialization (receives JSON, returns JSON)
+//!
+//! Strings cross the boundary through the canonical ABI, so there are no
+//! fixed-size read buffers or packed return values.
+//!
+//! Compile with: `cargo build --target wasm32-wasip2 -p phase0-guest-component`
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+
+use trovato::phase0::item_api;
+
+wit_bindgen::generate!({
+    world: "bench-plugin",
+    path: "../wit",
+});
+
+/// Global allocator for no_std WASM
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+struct BenchPlugin;
+
+impl Guest for BenchPlugin {
+    /// Reads 3 fields via imports, writes 1 computed field,
+    /// returns a RenderElement JSON string.
+    fn tap_item_view(handle: i32) -> String {
+        // Read 3 fields (the benchmark workload)
+        let title = item_api::get_title(handle).unwrap_or_default();
+        let body = item_api::get_field_string(handle, "field_body").unwrap_or_default();
+        let _summary = item_api::get_field_string(handle, "field_summary").unwrap_or_default();
+
+        // Write 1 computed field
+        let computed = format!("Processed: {}", title);
+        item_api::set_field_string(handle, "field_computed", &computed);
+
+        format!(
+            r#"{{"type":"container","children":[{{"type":"heading","level":1,"text":"{}"}},{{"type":"markup","value":"{}"}}]}}"#,
+            escape_json(&title),
+            escape_json(&body.chars().take(100).collect::<String>())
+        )
+    }
+
+    /// Receives full item JSON, parses it (simulated), modifies a field,
+    /// returns a RenderElement JSON string.
+    fn tap_item_view_full(item_json: String) -> String {
+        let title = extract_json_string(&item_json, "title").unwrap_or_default();
+        let body = extract_json_string(&item_json, "field_body").unwrap_or_default();
+        let _summary = extract_json_string(&item_json, "field_summary").unwrap_or_default();
+
+        format!(
+            r#"{{"type":"container","children":[{{"type":"heading","level":1,"text":"{}"}},{{"type":"markup","value":"{}"}},{{"field_computed":"Processed: {}"}}]}}"#,
+            escape_json(&title),
+            escape_json(&body.chars().take(100).collect::<String>()),
+            escape_json(&title)
+        )
+    }
+}
+
+export!(BenchPlugin);
+
+/// Minimal JSON string extraction (no serde dependency).
+///
+/// Same as `phase0-guest` so both variants do identical guest-side work.
+fn extract_json_string(json: &str, key: &str) -> Option<String> {
+    let pattern = format!(r#""{}":"#, key);
+    let start = json.find(&pattern)?;
+    let after_key = &json[start + pattern.len()..];
+
+    // Handle nested objects like {"value": "..."}
+    if after_key.starts_with('{') {
+        let value_pattern = r#""value":""#;
+        let value_start = after_key.find(value_pattern)?;
+        let after_value = &after_key[value_start + value_pattern.len()..];
+        let end = after_value.find('"')?;
+        return Some(after_value[..end].into());
+    }
+
+    // Direct string value
+    if let Some(content) = after_key.strip_prefix('"') {
+        let end = content.find('"')?;
+        return Some(content[..end].into());
+    }
+
+    None
+}
+
+/// Escape special characters for JSON strings.
+fn escape_json(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str(r#"\""#),
+            '\\' => result.push_str(r#"\\"#),
+            '\n' => result.push_str(r#"\n"#),
+            '\r' => result.push_str(r#"\r"#),
+            '\t' => result.push_str(r#"\t"#),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// =============================================================================
+// Panic Handler (required for no_std)
+// =============================================================================
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
@@ -4,6 +4,9 @@
 //! - `tap_item_view`: handle-based (receives i32 handle, calls host functions)
 //! - `tap_item_view_full`: full-serialization (receives JSON, parses, returns JSON)
 //!
+//! The host calls `set_payload_size` after instantiation so field reads
+//! aren't truncated for larger payloads.
+//!
 //! Compile with: `cargo build --target wasm32-wasip1 -p phase0-guest`
 
 #![no_std]
@@ -11,6 +14,8 @@
 extern crate alloc;
 
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
 use core::slice;
 
 // =============================================================================
@@ -35,6 +40,16 @@ pub extern "C" fn dealloc(ptr: i32, size: i32) {
     unsafe { alloc::alloc::dealloc(ptr as *mut u8, layout) }
 }
 
+/// Size the field read buffer for the payload the host is about to pass.
+///
+/// Called once per instance before the first tap; `size` is the payload
+/// size in bytes.
+#[unsafe(no_mangle)]
+pub extern "C" fn set_payload_size(size: i32) {
+    let len = (size.max(0) as usize).max(DEFAULT_READ_BUF_LEN);
+    read_buf().resize(len, 0);
+}
+
 // =============================================================================
 // Host Function Imports (Handle-Based API)
 // =============================================================================
@@ -70,24 +85,41 @@ unsafe extern "C" {
 // Helper Functions
 // =============================================================================
 
-/// Static buffer for reading field values (4KB should be plenty for benchmarks).
-/// Using raw pointer to avoid Rust 2024's static_mut_refs restrictions.
-static mut READ_BUF: [u8; 4096] = [0u8; 4096];
+/// Read buffer size before the host calls `set_payload_size` (enough for the
+/// small payload's fields).
+const DEFAULT_READ_BUF_LEN: usize = 4096;
 
-/// Get the read buffer pointer and length.
+/// Buffer for reading field values, grown by `set_payload_size` so large
+/// payloads aren't truncated at the boundary.
+struct ReadBuf(UnsafeCell<Vec<u8>>);
+
+// SAFETY: This is single-threaded WASM, no data races possible.
+unsafe impl Sync for ReadBuf {}
+
+static READ_BUF: ReadBuf = ReadBuf(UnsafeCell::new(Vec::new()));
+
+/// Get the read buffer, allocating the default size on first use.
 /// SAFETY: This is single-threaded WASM, no data races possible.
 #[inline]
+fn read_buf() -> &'static mut Vec<u8> {
+    let buf = unsafe { &mut *READ_BUF.0.get() };
+    if buf.is_empty() {
+        buf.resize(DEFAULT_READ_BUF_LEN, 0);
+    }
+    buf
+}
+
+/// Get the read buffer pointer and length.
+#[inline]
 fn read_buf_ptr_len() -> (i32, i32) {
-    let ptr = unsafe { core::ptr::addr_of_mut!(READ_BUF) } as *mut u8;
-    (ptr as i32, 4096)
+    let buf = read_buf();
+    (buf.as_mut_ptr() as i32, buf.len() as i32)
 }
 
 /// Read from the read buffer as a slice.
-/// SAFETY: This is single-threaded WASM, no data races possible.
 #[inline]
-unsafe fn read_buf_slice(len: usize) -> &'static [u8] {
-    let ptr = core::ptr::addr_of!(READ_BUF) as *const u8;
-    unsafe { slice::from_raw_parts(ptr, len) }
+fn read_buf_slice(len: usize) -> &'static [u8] {
+    &read_buf()[..len]
 }
 
 /// Read title using host function.
@@ -95,7 +127,7 @@ fn read_title(handle: i32) -> String {
     let (buf_ptr, buf_len) = read_buf_ptr_len();
     let len = unsafe { host_get_title(handle, buf_ptr, buf_len) };
     if len > 0 {
-        unsafe { String::from_utf8_lossy(read_buf_slice(len as usize)).into_owned() }
+        String::from_utf8_lossy(read_buf_slice(len as usize)).into_owned()
     } else {
         String::new()
     }
@@ -0,0 +1,209 @@
+//! Benchmark 1c: Component-model data access.
+//!
+//! Runs the handle-based and full-serialization workloads through WIT
+//! bindings (`wit/phase0.wit`) so they can be compared against the raw
+//! pointer-packing ABI used by the other benchmarks.
+//!
+//! Unlike the raw full-serialization benchmark, copying the input JSON into
+//! guest memory happens inside the canonical ABI lowering, so it is counted
+//! in the tap time rather than in instantiation.
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use wasmtime::Engine;
+use wasmtime::component::{Component, HasSelf, Linker};
+
+use crate::BenchResult;
+use crate::fixture::{PayloadSize, synthetic_item_sized};
+use crate::host::{BenchHost, StubHostState};
+
+wasmtime::component::bindgen!({
+    world: "bench-plugin",
+    path: "wit",
+});
+
+use trovato::phase0::item_api;
+
+/// Data access mode exercised through the component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentMode {
+    /// `tap-item-view`: field access through imported functions.
+    Handle,
+    /// `tap-item-view-full`: item JSON passed as a WIT string.
+    Serialize,
+}
+
+impl ComponentMode {
+    /// Label used in benchmark result names.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComponentMode::Handle => "component handle-based",
+            ComponentMode::Serialize => "component full-serialization",
+        }
+    }
+}
+
+/// Benchmark results including separate instantiation and tap call timing.
+pub struct ComponentBenchmarkResults {
+    pub total: BenchResult,
+    pub tap_only: BenchResult,
+    pub instantiation_only: BenchResult,
+}
+
+impl item_api::Host for StubHostState {
+    fn get_title(&mut self, handle: i32) -> Option<String> {
+        StubHostState::get_title(self, handle)
+    }
+
+    fn get_field_string(&mut self, handle: i32, field_name: String) -> Option<String> {
+        StubHostState::get_field_string(self, handle, &field_name)
+    }
+
+    fn set_field_string(&mut self, handle: i32, field_name: String, value: String) {
+        StubHostState::set_field_string(self, handle, &field_name, &value);
+    }
+}
+
+/// Component benchmark host, sharing the pooling engine of [`BenchHost`].
+pub struct ComponentBenchHost {
+    pub engine: Engine,
+    pub linker: Linker<StubHostState>,
+}
+
+impl ComponentBenchHost {
+    /// Create a component host on the same engine as the raw ABI benchmarks.
+    pub fn new(host: &BenchHost) -> Result<Self> {
+        let engine = host.engine.clone();
+        let mut linker = Linker::new(&engine);
+        BenchPlugin::add_to_linker::<_, HasSelf<_>>(&mut linker, |state| state)
+            .map_err(|e| anyhow::anyhow!("{e:#}"))
+            .context("failed to register component host functions")?;
+        Ok(Self { engine, linker })
+    }
+
+    /// Compile a WASM component from a file.
+    pub fn compile_from_file(&self, path: &std::path::Path) -> Result<Component> {
+        Component::from_file(&self.engine, path)
+            .map_err(|e| anyhow::anyhow!("{e:#}"))
+            .with_context(|| format!("failed to compile WASM component from {}", path.display()))
+    }
+}
+
+/// Run the component-model benchmark for one data access mode.
+///
+/// Executes the mode's tap N times, measuring:
+/// 1. Total time (instantiation + tap call)
+/// 2. Tap call time only
+/// 3. Instantiation time only
+pub fn run_component_benchmark(
+    host: &ComponentBenchHost,
+    component: &Component,
+    mode: ComponentMode,
+    payload: PayloadSize,
+    iterations: u32,
+) -> Result<ComponentBenchmarkResults> {
+    let mut total_durations = Vec::with_capacity(iterations as usize);
+    let mut tap_durations = Vec::with_capacity(iterations as usize);
+    let mut instantiation_durations = Vec::with_capacity(iterations as usize);
+
+    let item = synthetic_item_sized(payload);
+    let item_json = serde_json::to_string(&item)?;
+
+    for _ in 0..iterations {
+        let total_start = Instant::now();
+
+        // Create fresh store; only the handle-based mode reads host state
+        let mut state = StubHostState::new();
+        if mode == ComponentMode::Handle {
+            state.load_item(0, item.clone());
+        }
+        let mut store = wasmtime::Store::new(&host.engine, state);
+
+        // Instantiate the plugin
+        let plugin = BenchPlugin::instantiate(&mut store, component, &host.linker)
+            .map_err(|e| anyhow::anyhow!("{e:#}"))
+            .context("failed to instantiate component")?;
+
+        let instantiation_elapsed = total_start.elapsed();
+        instantiation_durations.push(instantiation_elapsed);
+
+        // Time just the tap call
+        let tap_start = Instant::now();
+        let result = match mode {
+            ComponentMode::Handle => plugin.call_tap_item_view(&mut store, 0),
+            ComponentMode::Serialize => plugin.call_tap_item_view_full(&mut store, &item_json),
+        }
+        .map_err(|e| anyhow::anyhow!("{e:#}"))?;
+        let tap_elapsed = tap_start.elapsed();
+        tap_durations.push(tap_elapsed);
+
+        let total_elapsed = total_start.elapsed();
+        total_durations.push(total_elapsed);
+
+        assert!(
+            !result.is_empty(),
+            "component tap should return non-empty JSON"
+        );
+    }
+
+    // Sort for percentile calculation
+    total_durations.sort();
+    tap_durations.sort();
+    instantiation_durations.sort();
+
+    Ok(ComponentBenchmarkResults {
+        total: BenchResult::from_durations(format!("{} (total)", mode.name()), &total_durations),
+        tap_only: BenchResult::from_durations(
+            format!("{} (tap only)", mode.name()),
+            &tap_durations,
+        ),
+        instantiation_only: BenchResult::from_durations(
+            format!("{} (instantiation)", mode.name()),
+            &instantiation_durations,
+        ),
+    })
+}
+
+/// Run a quick verification that both component data access modes work.
+pub fn verify_component_access(
+    host: &ComponentBenchHost,
+    component: &Component,
+    payload: PayloadSize,
+) -> Result<()> {
+    let item = synthetic_item_sized(payload);
+    let item_json = serde_json::to_string(&item)?;
+
+    let mut state = StubHostState::new();
+    state.load_item(0, item);
+    let mut store = wasmtime::Store::new(&host.engine, state);
+
+    let plugin = BenchPlugin::instantiate(&mut store, component, &host.linker)
+        .map_err(|e| anyhow::anyhow!("{e:#}"))
+        .context("failed to instantiate component for verification")?;
+
+    let handle_json = plugin
+        .call_tap_item_view(&mut store, 0)
+        .map_err(|e| anyhow::anyhow!("{e:#}"))?;
+    println!(
+        "  Component handle-based result preview: {}...",
+        &handle_json[..handle_json.len().min(80)]
+    );
+
+    // Verify the computed field was set
+    let computed = store.data().get_field_string(0, "field_computed");
+    assert!(
+        computed.is_some(),
+        "field_computed should have been set by the component"
+    );
+
+    let full_json = plugin
+        .call_tap_item_view_full(&mut store, &item_json)
+        .map_err(|e| anyhow::anyhow!("{e:#}"))?;
+    println!(
+        "  Component full-serialization result preview: {}...",
+        &full_json[..full_json.len().min(80)]
+    );
+
+    Ok(())
+}
@@ -12,7 +12,7 @@ use anyhow::{Context, Result};
 use wasmtime::{Module, TypedFunc};
 
 use crate::BenchResult;
-use crate::fixture::synthetic_item;
+use crate::fixture::{PayloadSize, synthetic_item_sized};
 use crate::host::{BenchHost, StubHostState};
 
 /// Benchmark results including separate instantiation and tap call timing.
@@ -31,26 +31,26 @@ pub struct HandleBenchmarkResults {
 pub fn run_handle_benchmark(
     host: &BenchHost,
     module: &Module,
+    payload: PayloadSize,
     iterations: u32,
 ) -> Result<HandleBenchmarkResults> {
     let mut total_durations = Vec::with_capacity(iterations as usize);
     let mut tap_durations = Vec::with_capacity(iterations as usize);
     let mut instantiation_durations = Vec::with_capacity(iterations as usize);
 
+    // Build the fixture once; large payloads are slow to generate
+    let item = synthetic_item_sized(payload);
+
     for _ in 0..iterations {
         let total_start = Instant::now();
 
         // Create fresh store with fixture data
         let mut state = StubHostState::new();
-        state.load_item(0, synthetic_item());
+        state.load_item(0, item.clone());
         let mut store = host.create_store_with_state(state);
 
         // Instantiate the plugin
-        let instance = host
-            .linker
-            .instantiate(&mut store, module)
-            .map_err(|e| anyhow::anyhow!("{e:#}"))
-            .context("failed to instantiate plugin")?;
+        let instance = host.instantiate(&mut store, module, payload)?;
 
         // Get the tap_item_view function
         let tap_item_view: TypedFunc<i32, i64> = instance
@@ -94,16 +94,12 @@ pub fn run_handle_benchmark(
 }
 
 /// Run a quick verification that handle-based access works.
-pub fn verify_handle_access(host: &BenchHost, module: &Module) -> Result<()> {
+pub fn verify_handle_access(host: &BenchHost, module: &Module, payload: PayloadSize) -> Result<()> {
     let mut state = StubHostState::new();
-    state.load_item(0, synthetic_item());
+    state.load_item(0, synthetic_item_sized(payload));
     let mut store = host.create_store_with_state(state);
 
-    let instance = host
-        .linker
-        .instantiate(&mut store, module)
-        .map_err(|e| anyhow::anyhow!("{e:#}"))
-        .context("failed to instantiate plugin for verification")?;
+    let instance = host.instantiate(&mut store, module, payload)?;
 
     let tap_item_view: TypedFunc<i32, i64> = instance
         .get_typed_func(&mut store, "tap_item_view")
@@ -14,7 +14,7 @@ use anyhow::{Context, Result};
 use wasmtime::{Module, TypedFunc};
 
 use crate::BenchResult;
-use crate::fixture::synthetic_item;
+use crate::fixture::{PayloadSize, synthetic_item_sized};
 use crate::host::BenchHost;
 
 /// Benchmark results including separate instantiation and tap call timing.
@@ -33,6 +33,7 @@ pub struct SerializeBenchmarkResults {
 pub fn run_serialize_benchmark(
     host: &BenchHost,
     module: &Module,
+    payload: PayloadSize,
     iterations: u32,
 ) -> Result<SerializeBenchmarkResults> {
     let mut total_durations = Vec::with_capacity(iterations as usize);
@@ -40,7 +41,7 @@ pub fn run_serialize_benchmark(
     let mut instantiation_durations = Vec::with_capacity(iterations as usize);
 
     // Pre-serialize the fixture item
-    let item_json = serde_json::to_string(&synthetic_item())?;
+    let item_json = serde_json::to_string(&synthetic_item_sized(payload))?;
     let json_bytes = item_json.as_bytes();
 
     for _ in 0..iterations {
@@ -50,11 +51,7 @@ pub fn run_serialize_benchmark(
         let mut store = host.create_store();
 
         // Instantiate the plugin
-        let instance = host
-            .linker
-            .instantiate(&mut store, module)
-            .map_err(|e| anyhow::anyhow!("{e:#}"))
-            .context("failed to instantiate plugin")?;
+        let instance = host.instantiate(&mut store, module, payload)?;
 
         // Get the alloc function to allocate memory for the JSON
         let alloc_fn: TypedFunc<i32, i32> = instance
@@ -116,17 +113,17 @@ pub fn run_serialize_benchmark(
 }
 
 /// Run a quick verification that full-serialization access works.
-pub fn verify_serialize_access(host: &BenchHost, module: &Module) -> Result<()> {
-    let item_json = serde_json::to_string(&synthetic_item())?;
+pub fn verify_serialize_access(
+    host: &BenchHost,
+    module: &Module,
+    payload: PayloadSize,
+) -> Result<()> {
+    let item_json = serde_json::to_string(&synthetic_item_sized(payload))?;
     let json_bytes = item_json.as_bytes();
 
     let mut store = host.create_store();
 
-    let instance = host
-        .linker
-        .instantiate(&mut store, module)
-        .map_err(|e| anyhow::anyhow!("{e:#}"))
-        .context("failed to instantiate plugin for verification")?;
+    let instance = host.instantiate(&mut store, module, payload)?;
 
     // Allocate and write JSON
     let alloc_fn: TypedFunc<i32, i32> = instance
@@ -10,10 +10,12 @@ use std::collections::HashMap;
 
 use anyhow::{Context, Result};
 use wasmtime::{
-    Config, Engine, InstanceAllocationStrategy, Linker, Memory, Module, PoolingAllocationConfig,
-    Store,
+    Config, Engine, Instance, InstanceAllocationStrategy, Linker, Memory, Module,
+    PoolingAllocationConfig, Store, TypedFunc,
 };
 
+use crate::fixture::PayloadSize;
+
 /// Configuration for the benchmark host environment.
 #[derive(Debug, Clone)]
 pub struct HostConfig {
@@ -525,6 +527,33 @@ impl BenchHost {
     pub fn create_store_with_state(&self, state: StubHostState) -> Store<StubHostState> {
         create_store_with_state(&self.engine, state)
     }
+
+    /// Instantiate the guest plugin and size its buffers for the payload.
+    ///
+    /// The guest reads fields into a fixed buffer, so without this larger
+    /// payloads would be truncated at the boundary.
+    pub fn instantiate(
+        &self,
+        store: &mut Store<StubHostState>,
+        module: &Module,
+        payload: PayloadSize,
+    ) -> Result<Instance> {
+        let instance = self
+            .linker
+            .instantiate(&mut *store, module)
+            .map_err(|e| anyhow::anyhow!("{e:#}"))
+            .context("failed to instantiate plugin")?;
+
+        let set_payload_size: TypedFunc<i32, ()> = instance
+            .get_typed_func(&mut *store, "set_payload_size")
+            .map_err(|e| anyhow::anyhow!("{e:#}"))
+            .context("failed to get set_payload_size export (rebuild phase0-guest)")?;
+        set_payload_size
+            .call(&mut *store, payload.target_bytes() as i32)
+            .map_err(|e| anyhow::anyhow!("{e:#}"))?;
+
+        Ok(instance)
+    }
 }
 
 #[cfg(test)]
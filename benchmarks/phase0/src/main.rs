@@ -6,15 +6,17 @@
 //! Extended benchmark suite with configurable parameters for:
 //! - Payload sizes (small/medium/large/xlarge)
 //! - Concurrency levels (100/500/1000+)
-//! - Benchmark types (handle, serialize, concurrency, async, mutation)
+//! - Benchmark types (handle, serialize, component, concurrency, async, mutation)
 //!
 //! Usage:
 //!   cargo run --release -p trovato-phase0 -- --help
 //!   cargo run --release -p trovato-phase0 -- --benchmark all
 //!   cargo run --release -p trovato-phase0 -- --benchmark serialize --payload large
 //!   cargo run --release -p trovato-phase0 -- --benchmark concurrency --concurrency 1000
+//!   cargo run --release -p trovato-phase0 -- --benchmark component --payload large
 
 mod bench_async;
+mod bench_component;
 mod bench_concurrency;
 mod bench_handle;
 mod bench_serialize;
@@ -30,6 +32,7 @@ use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
 use bench_async::AsyncBenchHost;
+use bench_component::{ComponentBenchHost, ComponentMode};
 use fixture::PayloadSize;
 use host::{BenchHost, HostConfig};
 
@@ -46,6 +49,7 @@ enum BenchmarkType {
     All,
     Handle,
     Serialize,
+    Component, // Component model / WIT bindings vs raw ABI
     Concurrency,
     Async,
     PayloadScaling, // Tests all payload sizes
@@ -59,12 +63,13 @@ impl std::str::FromStr for BenchmarkType {
             "all" => Ok(BenchmarkType::All),
             "handle" => Ok(BenchmarkType::Handle),
             "serialize" | "serialization" => Ok(BenchmarkType::Serialize),
+            "component" | "component-model" | "wit" => Ok(BenchmarkType::Component),
             "concurrency" | "concurrent" => Ok(BenchmarkType::Concurrency),
             "async" => Ok(BenchmarkType::Async),
             "payload" | "payload-scaling" | "payloads" => Ok(BenchmarkType::PayloadScaling),
             "mutation" | "mutations" | "write" => Ok(BenchmarkType::Mutation),
             _ => Err(format!(
-                "Unknown benchmark: {s}. Use: all, handle, serialize, concurrency, async, payload, mutation"
+                "Unknown benchmark: {s}. Use: all, handle, serialize, component, concurrency, async, payload, mutation"
             )),
         }
     }
@@ -143,8 +148,8 @@ fn print_help() {
     println!();
     println!("OPTIONS:");
     println!("    -b, --benchmark <TYPE>     Benchmark to run [default: all]");
-    println!("                               Types: all, handle, serialize, concurrency,");
-    println!("                                      async, payload, mutation");
+    println!("                               Types: all, handle, serialize, component,");
+    println!("                                      concurrency, async, payload, mutation");
     println!("    -p, --payload <SIZE>       Payload size [default: small]");
     println!("                               Sizes: small (~2.4KB), medium (~10KB),");
     println!("                                      large (~50KB), xlarge (~100KB)");
@@ -164,11 +169,15 @@ fn print_help() {
         "    cargo run --release -p trovato-phase0 -- --benchmark concurrency --concurrency 1000"
     );
     println!();
+    println!("    # Component model vs raw ABI");
+    println!("    cargo run --release -p trovato-phase0 -- --benchmark component --payload large");
+    println!();
     println!("    # Mutation-heavy workload");
     println!("    cargo run --release -p trovato-phase0 -- --benchmark mutation --iterations 500");
     println!();
     println!("BUILDING GUEST WASM:");
     println!("    cargo build --target wasm32-wasip1 -p phase0-guest --release");
+    println!("    cargo build --target wasm32-wasip2 -p phase0-guest-component --release");
 }
 
 /// Path to the compiled guest plugin WASM (debug or release based on availability).
@@ -183,6 +192,18 @@ fn guest_wasm_path() -> &'static str {
     }
 }
 
+/// Path to the compiled guest component (debug or release based on availability).
+fn guest_component_path() -> &'static str {
+    let release_path = "target/wasm32-wasip2/release/phase0_guest_component.wasm";
+    let debug_path = "target/wasm32-wasip2/debug/phase0_guest_component.wasm";
+
+    if std::path::Path::new(release_path).exists() {
+        release_path
+    } else {
+        debug_path
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let config = parse_args();
@@ -247,6 +268,9 @@ async fn main() -> Result<()> {
         BenchmarkType::Serialize => {
             run_serialize_benchmark(&host, &module, &config)?;
         }
+        BenchmarkType::Component => {
+            run_component_benchmark(&host, &module, &config)?;
+        }
         BenchmarkType::Concurrency => {
             run_concurrency_benchmark(Arc::clone(&host), Arc::clone(&module), &config).await?;
         }
@@ -273,8 +297,8 @@ async fn run_gate_benchmarks(
 ) -> Result<()> {
     // Verify both modes work before benchmarking
     println!("Verifying plugin functionality...");
-    bench_handle::verify_handle_access(&host, &module)?;
-    bench_serialize::verify_serialize_access(&host, &module)?;
+    bench_handle::verify_handle_access(&host, &module, config.payload_size)?;
+    bench_serialize::verify_serialize_access(&host, &module, config.payload_size)?;
     println!("  ✓ Both data access modes verified\n");
 
     // Gate 1: Handle vs Serialize
@@ -285,7 +309,8 @@ async fn run_gate_benchmarks(
         config.iterations,
         config.payload_size.name()
     );
-    let handle_results = bench_handle::run_handle_benchmark(&host, &module, config.iterations)?;
+    let handle_results =
+        bench_handle::run_handle_benchmark(&host, &module, config.payload_size, config.iterations)?;
     println!("  {}", handle_results.total);
     println!("  {}", handle_results.tap_only);
     println!();
@@ -295,8 +320,12 @@ async fn run_gate_benchmarks(
         config.iterations,
         config.payload_size.name()
     );
-    let serialize_results =
-        bench_serialize::run_serialize_benchmark(&host, &module, config.iterations)?;
+    let serialize_results = bench_serialize::run_serialize_benchmark(
+        &host,
+        &module,
+        config.payload_size,
+        config.iterations,
+    )?;
     println!("  {}", serialize_results.total);
     println!("  {}", serialize_results.tap_only);
     println!();
@@ -427,8 +456,9 @@ fn run_handle_benchmark(
     println!("Payload: {}", config.payload_size.name());
     println!("Iterations: {}\n", config.iterations);
 
-    bench_handle::verify_handle_access(host, module)?;
-    let results = bench_handle::run_handle_benchmark(host, module, config.iterations)?;
+    bench_handle::verify_handle_access(host, module, config.payload_size)?;
+    let results =
+        bench_handle::run_handle_benchmark(host, module, config.payload_size, config.iterations)?;
 
     println!("Results:");
     println!("  {}", results.total);
@@ -448,8 +478,13 @@ fn run_serialize_benchmark(
     println!("Payload: {}", config.payload_size.name());
     println!("Iterations: {}\n", config.iterations);
 
-    bench_serialize::verify_serialize_access(host, module)?;
-    let results = bench_serialize::run_serialize_benchmark(host, module, config.iterations)?;
+    bench_serialize::verify_serialize_access(host, module, config.payload_size)?;
+    let results = bench_serialize::run_serialize_benchmark(
+        host,
+        module,
+        config.payload_size,
+        config.iterations,
+    )?;
 
     println!("Results:");
     println!("  {}", results.total);
@@ -459,6 +494,91 @@ fn run_serialize_benchmark(
     Ok(())
 }
 
+/// Compare the component model against the raw pointer-packing ABI.
+fn run_component_benchmark(
+    host: &Arc<BenchHost>,
+    module: &Arc<wasmtime::Module>,
+    config: &BenchConfig,
+) -> Result<()> {
+    println!("=== Component Model vs Raw ABI Benchmark ===\n");
+    println!("Payload: {}", config.payload_size.name());
+    println!("Iterations: {}\n", config.iterations);
+
+    let component_path = PathBuf::from(guest_component_path());
+    if !component_path.exists() {
+        println!(
+            "  ✗ Guest component not found at: {}",
+            component_path.display()
+        );
+        println!(
+            "  Build it with: cargo build --target wasm32-wasip2 -p phase0-guest-component --release"
+        );
+        return Ok(());
+    }
+
+    let component_host = ComponentBenchHost::new(host)?;
+    let component = component_host.compile_from_file(&component_path)?;
+
+    bench_handle::verify_handle_access(host, module, config.payload_size)?;
+    bench_serialize::verify_serialize_access(host, module, config.payload_size)?;
+    bench_component::verify_component_access(&component_host, &component, config.payload_size)?;
+    println!();
+
+    let raw_handle =
+        bench_handle::run_handle_benchmark(host, module, config.payload_size, config.iterations)?;
+    let raw_serialize = bench_serialize::run_serialize_benchmark(
+        host,
+        module,
+        config.payload_size,
+        config.iterations,
+    )?;
+    let component_handle = bench_component::run_component_benchmark(
+        &component_host,
+        &component,
+        ComponentMode::Handle,
+        config.payload_size,
+        config.iterations,
+    )?;
+    let component_serialize = bench_component::run_component_benchmark(
+        &component_host,
+        &component,
+        ComponentMode::Serialize,
+        config.payload_size,
+        config.iterations,
+    )?;
+
+    println!("Results:");
+    for result in [
+        &raw_handle.tap_only,
+        &raw_serialize.tap_only,
+        &component_handle.tap_only,
+        &component_serialize.tap_only,
+    ] {
+        println!("  {result}");
+    }
+    println!("  {}", component_handle.instantiation_only);
+    println!();
+
+    let ratio = |raw: &BenchResult, component: &BenchResult| {
+        component.per_call_avg.as_nanos() as f64 / raw.per_call_avg.as_nanos() as f64
+    };
+    println!(
+        "  Handle-based: component/raw tap time {:.2}x",
+        ratio(&raw_handle.tap_only, &component_handle.tap_only)
+    );
+    println!(
+        "  Full-serialization: component/raw tap time {:.2}x",
+        ratio(&raw_serialize.tap_only, &component_serialize.tap_only)
+    );
+    println!();
+    println!("Note: the raw full-serialization tap time excludes copying the JSON into");
+    println!("guest memory; the component copies it during the call. Compare totals too:");
+    println!("  {}", raw_serialize.total);
+    println!("  {}", component_serialize.total);
+
+    Ok(())
+}
+
 /// Run concurrency benchmark only.
 async fn run_concurrency_benchmark(
     host: Arc<BenchHost>,
@@ -526,7 +646,7 @@ fn run_payload_scaling_benchmark(
     config: &BenchConfig,
 ) -> Result<()> {
     println!("=== Payload Scaling Benchmark ===\n");
-    println!("Testing serialization performance across payload sizes\n");
+    println!("Testing data access performance across payload sizes\n");
 
     let sizes = [
         PayloadSize::Small,
@@ -535,31 +655,32 @@ fn run_payload_scaling_benchmark(
         PayloadSize::XLarge,
     ];
 
-    println!("| Payload Size | Actual Bytes | Avg Tap Time | p95 | p99 |");
-    println!("|--------------|--------------|--------------|-----|-----|");
+    println!(
+        "| Payload Size | Actual Bytes | Handle Avg | Serialize Avg | Serialize p95 | Serialize p99 |"
+    );
+    println!(
+        "|--------------|--------------|------------|---------------|---------------|---------------|"
+    );
 
     for size in sizes {
         let actual_bytes = fixture::synthetic_item_size_for(size);
 
-        // Run benchmark with this payload size
-        // Note: Current implementation uses fixture::synthetic_item() which is Small
-        // We'd need to pass size through to the benchmark functions
-        let results = bench_serialize::run_serialize_benchmark(host, module, config.iterations)?;
+        let handle_results =
+            bench_handle::run_handle_benchmark(host, module, size, config.iterations)?;
+        let serialize_results =
+            bench_serialize::run_serialize_benchmark(host, module, size, config.iterations)?;
 
         println!(
-            "| {:12} | {:>12} | {:>12?} | {:>12?} | {:>12?} |",
+            "| {:12} | {:>12} | {:>10?} | {:>13?} | {:>13?} | {:>13?} |",
             size.name(),
             actual_bytes,
-            results.tap_only.per_call_avg,
-            results.tap_only.p95,
-            results.tap_only.p99,
+            handle_results.tap_only.per_call_avg,
+            serialize_results.tap_only.per_call_avg,
+            serialize_results.tap_only.p95,
+            serialize_results.tap_only.p99,
         );
     }
 
-    println!();
-    println!("Note: This benchmark currently uses the small payload for all tests.");
-    println!("Full payload scaling requires passing size to guest WASM.");
-
     Ok(())
 }
 
@@ -578,14 +699,19 @@ fn run_mutation_benchmark(
     // - Full-serialization: Modify JSON and return full payload
 
     println!("Handle-based (3 reads + 1 write per call):");
-    let handle_results = bench_handle::run_handle_benchmark(host, module, config.iterations)?;
+    let handle_results =
+        bench_handle::run_handle_benchmark(host, module, config.payload_size, config.iterations)?;
     println!("  Tap avg: {:?}", handle_results.tap_only.per_call_avg);
     println!("  Tap p95: {:?}", handle_results.tap_only.p95);
     println!();
 
     println!("Full-serialization (parse + modify + serialize per call):");
-    let serialize_results =
-        bench_serialize::run_serialize_benchmark(host, module, config.iterations)?;
+    let serialize_results = bench_serialize::run_serialize_benchmark(
+        host,
+        module,
+        config.payload_size,
+        config.iterations,
+    )?;
     println!("  Tap avg: {:?}", serialize_results.tap_only.per_call_avg);
     println!("  Tap p95: {:?}", serialize_results.tap_only.p95);
     println!();
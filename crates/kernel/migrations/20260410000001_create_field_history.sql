@@ -0,0 +1,19 @@
+-- Per-field change log for fields declared with `track_history`.
+--
+-- Records old/new values, the user who made the change, and the revision
+-- that introduced it. Compact alternative to diffing full revisions when
+-- only a handful of sensitive fields need an audit timeline.
+
+CREATE TABLE field_history (
+    id          UUID PRIMARY KEY,
+    item_id     UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+    field_name  VARCHAR(128) NOT NULL,
+    old_value   JSONB,
+    new_value   JSONB,
+    changed_by  UUID NOT NULL,
+    revision_id UUID,
+    changed     BIGINT NOT NULL
+);
+
+CREATE INDEX idx_field_history_item_field
+    ON field_history(item_id, field_name, changed DESC);
@@ -0,0 +1,9 @@
+-- Track where each search field configuration came from.
+--
+-- 'plugin' rows are synced from FieldDefinition.search_weight declared in
+-- tap_item_info and are replaced on every sync. 'manual' rows are created
+-- by administrators and are never overwritten by plugin sync.
+
+ALTER TABLE search_field_config
+    ADD COLUMN source VARCHAR(16) NOT NULL DEFAULT 'manual'
+    CHECK (source IN ('manual', 'plugin'));
@@ -0,0 +1,19 @@
+-- Sampled read access log for sensitive content types.
+--
+-- Opt-in per item type via the `read_log` site_config key. Kept separate
+-- from audit_log because read volume is far higher than mutation volume
+-- and retention is much shorter. No foreign keys: entries must outlive
+-- deleted items and users until they age out.
+
+CREATE TABLE item_read_log (
+    id          UUID PRIMARY KEY,
+    item_id     UUID NOT NULL,
+    item_type   VARCHAR(32) NOT NULL,
+    user_id     UUID,
+    ip_address  VARCHAR(45),
+    created     BIGINT NOT NULL
+);
+
+CREATE INDEX idx_item_read_log_item ON item_read_log(item_id, created DESC);
+CREATE INDEX idx_item_read_log_user ON item_read_log(user_id, created DESC);
+CREATE INDEX idx_item_read_log_created ON item_read_log(created);
@@ -0,0 +1,14 @@
+-- Session records for the PostgreSQL session store (SESSION_STORE=postgres).
+--
+-- Unused with the default Redis store. `data` holds the tower-sessions
+-- record map; `expires` and `updated` are Unix timestamps. Expired rows are
+-- removed by the cleanup_expired_sessions cron task.
+
+CREATE TABLE sessions (
+    id       TEXT PRIMARY KEY,
+    data     JSONB NOT NULL DEFAULT '{}',
+    expires  BIGINT NOT NULL,
+    updated  BIGINT NOT NULL
+);
+
+CREATE INDEX idx_sessions_expires ON sessions(expires);
@@ -0,0 +1,25 @@
+-- Structured context for item status changes (unpublish reason, embargo).
+--
+-- One row per publish/unpublish transition, recording the reason code,
+-- free-text note, optional embargo end, and the user responsible. The
+-- latest row for an item describes why it is in its current state.
+
+CREATE TABLE item_status_change (
+    id            UUID PRIMARY KEY,
+    item_id       UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+    old_status    SMALLINT NOT NULL,
+    new_status    SMALLINT NOT NULL,
+    reason_code   VARCHAR(64),
+    note          TEXT,
+    embargo_until BIGINT,
+    changed_by    UUID NOT NULL,
+    revision_id   UUID,
+    changed       BIGINT NOT NULL
+);
+
+CREATE INDEX idx_item_status_change_item
+    ON item_status_change(item_id, changed DESC);
+
+CREATE INDEX idx_item_status_change_reason
+    ON item_status_change(reason_code)
+    WHERE reason_code IS NOT NULL;
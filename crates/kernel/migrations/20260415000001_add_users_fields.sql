@@ -0,0 +1,6 @@
+-- Plugin-defined profile fields on user accounts.
+--
+-- Plugins declare the fields through `tap_user_info`; values are stored
+-- here keyed by field name, mirroring `item.fields`.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS fields JSONB NOT NULL DEFAULT '{}'::jsonb;
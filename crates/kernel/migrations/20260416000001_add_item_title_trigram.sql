@@ -0,0 +1,9 @@
+-- Trigram index on item titles for fuzzy search.
+--
+-- `SearchService::search` falls back to trigram word similarity on titles
+-- when the full-text query matches nothing (typically a typo), and the
+-- "did you mean" suggestion API uses the same index.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_item_title_trgm ON item USING GIN (title gin_trgm_ops);
@@ -0,0 +1,21 @@
+-- Role-conditional field defaults for newly created items.
+--
+-- When an item of `item_type` is created without a value for `field_name`,
+-- the first matching rule (lowest weight) supplies one. A rule matches when
+-- the creating user holds `role_id`; a NULL role matches every creator.
+-- `default_value` describes where the value comes from, e.g.
+-- {"source": "value", "value": [...]}, {"source": "creator"} or
+-- {"source": "creator_field", "field": "ng_person"}.
+
+CREATE TABLE item_type_field_default (
+    id            UUID PRIMARY KEY,
+    item_type     VARCHAR(32) NOT NULL REFERENCES item_type(type) ON DELETE CASCADE,
+    field_name    VARCHAR(64) NOT NULL,
+    role_id       UUID REFERENCES roles(id) ON DELETE CASCADE,
+    default_value JSONB NOT NULL,
+    weight        INTEGER NOT NULL DEFAULT 0,
+    created       BIGINT NOT NULL
+);
+
+CREATE INDEX idx_item_type_field_default_type
+    ON item_type_field_default(item_type, weight);
@@ -0,0 +1,27 @@
+-- Site contact form: categories with recipients, and stored submissions.
+--
+-- Each category routes messages to one or more recipient addresses.
+-- Submissions keep a copy of the category name so the listing and export
+-- still make sense after a category is deleted.
+
+CREATE TABLE contact_category (
+    id          UUID PRIMARY KEY,
+    name        VARCHAR(128) NOT NULL UNIQUE,
+    recipients  TEXT[] NOT NULL,
+    weight      INTEGER NOT NULL DEFAULT 0,
+    created     BIGINT NOT NULL
+);
+
+CREATE TABLE contact_submission (
+    id            UUID PRIMARY KEY,
+    category_id   UUID REFERENCES contact_category(id) ON DELETE SET NULL,
+    category_name VARCHAR(128) NOT NULL,
+    name          VARCHAR(255) NOT NULL,
+    mail          VARCHAR(254) NOT NULL,
+    subject       VARCHAR(255) NOT NULL,
+    message       TEXT NOT NULL,
+    user_id       UUID REFERENCES users(id) ON DELETE SET NULL,
+    created       BIGINT NOT NULL
+);
+
+CREATE INDEX idx_contact_submission_created ON contact_submission(created DESC);
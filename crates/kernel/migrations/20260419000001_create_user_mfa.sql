@@ -0,0 +1,21 @@
+-- Two-factor authentication: TOTP authenticators and recovery codes.
+
+CREATE TABLE IF NOT EXISTS user_mfa (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    last_step BIGINT NOT NULL DEFAULT 0,
+    created BIGINT NOT NULL,
+    changed BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS user_mfa_recovery_code (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used BIGINT,
+    created BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_mfa_recovery_code_user
+    ON user_mfa_recovery_code (user_id, code_hash);
@@ -0,0 +1,15 @@
+-- Personal workspaces: one internal stage per editor.
+--
+-- Provisioned at login when the `personal_workspaces` policy is enabled.
+-- `last_used` is the owner's last login; workspaces unused for longer than
+-- the configured expiry are removed by the `cleanup_personal_stages` cron
+-- task. Rows go away with the stage or the user.
+
+CREATE TABLE stage_workspace (
+    stage_id    UUID PRIMARY KEY REFERENCES category_tag(id) ON DELETE CASCADE,
+    user_id     UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    created     BIGINT NOT NULL,
+    last_used   BIGINT NOT NULL
+);
+
+CREATE INDEX idx_stage_workspace_last_used ON stage_workspace(last_used);
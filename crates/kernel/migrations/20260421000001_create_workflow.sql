@@ -0,0 +1,53 @@
+-- Content moderation workflows.
+--
+-- A workflow lists the editorial states of the item types it moderates and
+-- the transitions allowed between them (each gated by the permission
+-- "transition {workflow} from {from} to {to}"). Moderated items record
+-- their current state in item.moderation_state; NULL means the item has not
+-- entered a workflow.
+--
+-- The editorial workflow is installed unassigned; add item types to it to
+-- put them under moderation.
+
+CREATE TABLE workflow (
+    id                  VARCHAR(64) PRIMARY KEY,
+    label               VARCHAR(255) NOT NULL,
+    initial_state       VARCHAR(64) NOT NULL,
+    states              JSONB NOT NULL DEFAULT '[]',
+    transitions         JSONB NOT NULL DEFAULT '[]',
+    item_types          TEXT[] NOT NULL DEFAULT '{}',
+    require_publishable BOOLEAN NOT NULL DEFAULT FALSE,
+    created             BIGINT NOT NULL,
+    changed             BIGINT NOT NULL
+);
+
+CREATE INDEX idx_workflow_item_types ON workflow USING GIN (item_types);
+
+ALTER TABLE item ADD COLUMN moderation_state VARCHAR(64);
+
+CREATE INDEX idx_item_moderation_state
+    ON item(moderation_state)
+    WHERE moderation_state IS NOT NULL;
+
+INSERT INTO workflow (id, label, initial_state, states, transitions, created, changed)
+VALUES (
+    'editorial',
+    'Editorial',
+    'draft',
+    '[
+        {"id": "draft", "label": "Draft", "published": false, "publishable": false},
+        {"id": "review", "label": "In review", "published": false, "publishable": false},
+        {"id": "published", "label": "Published", "published": true, "publishable": true},
+        {"id": "archived", "label": "Archived", "published": false, "publishable": true}
+    ]',
+    '[
+        {"from": "draft", "to": "review"},
+        {"from": "review", "to": "draft"},
+        {"from": "review", "to": "published"},
+        {"from": "published", "to": "draft"},
+        {"from": "published", "to": "archived"},
+        {"from": "archived", "to": "draft"}
+    ]',
+    EXTRACT(EPOCH FROM NOW())::BIGINT,
+    EXTRACT(EPOCH FROM NOW())::BIGINT
+);
@@ -0,0 +1,17 @@
+-- Usage of deprecated API endpoints, fields and parameters.
+--
+-- One row per deprecation and client. The client is "token:<id>" for API
+-- tokens, "oauth:<client_id>" for OAuth access tokens, "user:<id>" for
+-- session users and "anonymous" otherwise. Counts are buffered in memory by
+-- each instance and added here periodically.
+
+CREATE TABLE api_deprecation_usage (
+    deprecation   VARCHAR(64) NOT NULL,
+    client        VARCHAR(255) NOT NULL,
+    token_id      UUID,
+    user_id       UUID,
+    request_count BIGINT NOT NULL DEFAULT 0,
+    first_seen    BIGINT NOT NULL,
+    last_seen     BIGINT NOT NULL,
+    PRIMARY KEY (deprecation, client)
+);
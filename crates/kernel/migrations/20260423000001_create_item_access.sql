@@ -0,0 +1,62 @@
+-- Item access grants written from tap_item_grants.
+--
+-- Each row grants the members of a realm/gid pair view, update or delete
+-- access to an item. Realms are "all" (gid "0", everyone), "role" (gid is a
+-- role ID) and "user" (gid is a user ID). Items without rows are governed by
+-- the regular access checks only.
+
+CREATE TABLE item_access (
+    item_id      UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+    realm        VARCHAR(32) NOT NULL,
+    gid          VARCHAR(64) NOT NULL,
+    grant_view   BOOLEAN NOT NULL DEFAULT FALSE,
+    grant_update BOOLEAN NOT NULL DEFAULT FALSE,
+    grant_delete BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (item_id, realm, gid)
+);
+
+CREATE INDEX idx_item_access_realm_gid ON item_access(realm, gid);
+
+-- Whether a user may perform an operation on an item according to its
+-- grants. True for items without grants and for administrators (the
+-- is_admin flag or a role with "administer site"), so list
+-- and search queries can filter with it directly. The anonymous user
+-- (nil UUID) holds the anonymous role; everyone else holds the
+-- authenticated role plus their assigned roles.
+CREATE OR REPLACE FUNCTION item_access_granted(p_item_id UUID, p_user_id UUID, p_op TEXT)
+RETURNS BOOLEAN AS $$
+    SELECT NOT EXISTS (SELECT 1 FROM item_access WHERE item_id = p_item_id)
+        OR EXISTS (SELECT 1 FROM users WHERE id = p_user_id AND is_admin)
+        OR EXISTS (
+            SELECT 1
+            FROM user_roles ur
+            JOIN role_permissions rp ON rp.role_id = ur.role_id
+            WHERE ur.user_id = p_user_id AND rp.permission = 'administer site'
+        )
+        OR EXISTS (
+            SELECT 1
+            FROM item_access a
+            WHERE a.item_id = p_item_id
+              AND CASE p_op
+                      WHEN 'view' THEN a.grant_view
+                      WHEN 'update' THEN a.grant_update
+                      WHEN 'delete' THEN a.grant_delete
+                      ELSE FALSE
+                  END
+              AND (
+                  a.realm = 'all'
+                  OR (a.realm = 'user'
+                      AND p_user_id <> '00000000-0000-0000-0000-000000000000'
+                      AND a.gid = p_user_id::text)
+                  OR (a.realm = 'role' AND a.gid IN (
+                      SELECT CASE
+                                 WHEN p_user_id = '00000000-0000-0000-0000-000000000000'
+                                 THEN '00000000-0000-0000-0000-000000000001'
+                                 ELSE '00000000-0000-0000-0000-000000000002'
+                             END
+                      UNION ALL
+                      SELECT role_id::text FROM user_roles WHERE user_id = p_user_id
+                  ))
+              )
+        );
+$$ LANGUAGE sql STABLE;
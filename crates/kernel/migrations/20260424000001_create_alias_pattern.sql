@@ -0,0 +1,33 @@
+-- URL alias patterns (pathauto) as config entities.
+--
+-- One pattern per item type, optionally overridden per language. The id is
+-- the item type, or "{item_type}__{language}" for a language override.
+-- Patterns previously lived in the site_config key 'pathauto_patterns'
+-- (with the same key convention); they are moved here.
+
+CREATE TABLE alias_pattern (
+    id        VARCHAR(128) PRIMARY KEY,
+    item_type VARCHAR(64) NOT NULL,
+    language  VARCHAR(12),
+    pattern   VARCHAR(255) NOT NULL,
+    created   BIGINT NOT NULL,
+    changed   BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX uq_alias_pattern_type_language
+    ON alias_pattern (item_type, COALESCE(language, ''));
+
+INSERT INTO alias_pattern (id, item_type, language, pattern, created, changed)
+SELECT p.key,
+       split_part(p.key, '__', 1),
+       NULLIF(split_part(p.key, '__', 2), ''),
+       p.value #>> '{}',
+       EXTRACT(EPOCH FROM NOW())::BIGINT,
+       EXTRACT(EPOCH FROM NOW())::BIGINT
+FROM site_config sc, jsonb_each(sc.value) AS p
+WHERE sc.key = 'pathauto_patterns'
+  AND jsonb_typeof(sc.value) = 'object'
+  AND jsonb_typeof(p.value) = 'string'
+ON CONFLICT DO NOTHING;
+
+DELETE FROM site_config WHERE key = 'pathauto_patterns';
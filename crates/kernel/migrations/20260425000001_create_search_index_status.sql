@@ -0,0 +1,42 @@
+-- Per-item search index freshness.
+--
+-- `indexed` is when the item's search_vector was last rebuilt; `queued` is
+-- set when a reindex was requested and cleared once the vector is rebuilt.
+-- Rows are maintained by a trigger alongside the search_vector trigger, so
+-- every save of an item marks it fresh. The search_reindex cron task feeds
+-- queued items to the search:reindex Redis queue in batches.
+
+CREATE TABLE search_index_status (
+    item_id UUID PRIMARY KEY REFERENCES item(id) ON DELETE CASCADE,
+    bundle  VARCHAR(32) NOT NULL,
+    indexed BIGINT NOT NULL DEFAULT 0,
+    queued  BIGINT
+);
+
+CREATE INDEX idx_search_index_status_bundle ON search_index_status (bundle);
+CREATE INDEX idx_search_index_status_queued ON search_index_status (queued)
+    WHERE queued IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION item_search_status_update() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO search_index_status (item_id, bundle, indexed, queued)
+    VALUES (NEW.id, NEW.type, EXTRACT(EPOCH FROM NOW())::BIGINT, NULL)
+    ON CONFLICT (item_id) DO UPDATE SET
+        bundle = EXCLUDED.bundle,
+        indexed = EXCLUDED.indexed,
+        queued = NULL;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Fires on the same columns as trg_item_search, after the vector is stored.
+CREATE TRIGGER trg_item_search_status
+    AFTER INSERT OR UPDATE OF title, fields, type ON item
+    FOR EACH ROW
+    EXECUTE FUNCTION item_search_status_update();
+
+COMMENT ON FUNCTION item_search_status_update() IS 'Marks an item''s search index entry fresh after its search_vector is rebuilt';
+
+-- Existing items were indexed when last saved.
+INSERT INTO search_index_status (item_id, bundle, indexed, queued)
+SELECT id, type, changed, NULL FROM item;
@@ -0,0 +1,28 @@
+-- Multisite: serve several sites from one deployment.
+--
+-- A site is a tenant reached through one or more hostnames. Requests are
+-- matched to a site by their Host header when TENANT_RESOLUTION_METHOD is
+-- "host"; unknown hostnames are served by the default tenant.
+
+CREATE TABLE site (
+    hostname  VARCHAR(255) PRIMARY KEY,
+    tenant_id UUID NOT NULL REFERENCES tenant(id) ON DELETE CASCADE,
+    created   BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())::BIGINT
+);
+
+CREATE INDEX idx_site_tenant_id ON site(tenant_id);
+
+-- Config variables are per site: a site only stores the keys it overrides
+-- and falls back to the default tenant's value for the rest.
+ALTER TABLE site_config DROP CONSTRAINT site_config_pkey;
+ALTER TABLE site_config ADD PRIMARY KEY (tenant_id, key);
+
+-- Per-site plugin switches. Plugins are enabled for the deployment as a
+-- whole; a row with enabled = FALSE turns one off for a single site.
+CREATE TABLE site_plugin (
+    tenant_id   UUID NOT NULL REFERENCES tenant(id) ON DELETE CASCADE,
+    plugin_name VARCHAR(64) NOT NULL,
+    enabled     BOOLEAN NOT NULL,
+    changed     BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())::BIGINT,
+    PRIMARY KEY (tenant_id, plugin_name)
+);
@@ -0,0 +1,20 @@
+-- Cron run history: one row per task executed by a cron cycle.
+--
+-- Rows older than 30 days are pruned by the cron service after each cycle.
+-- Failure streaks (for alerting) are counted from the latest 'ok' row of a
+-- task, so rows are ordered by id rather than by start time.
+
+CREATE TABLE cron_run (
+    id          BIGSERIAL PRIMARY KEY,
+    run_id      UUID NOT NULL,
+    task        VARCHAR(64) NOT NULL,
+    hostname    VARCHAR(255) NOT NULL,
+    started     BIGINT NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    status      VARCHAR(16) NOT NULL CHECK (status IN ('ok', 'failed')),
+    processed   BIGINT NOT NULL DEFAULT 0,
+    error       TEXT
+);
+
+CREATE INDEX idx_cron_run_task ON cron_run(task, id DESC);
+CREATE INDEX idx_cron_run_started ON cron_run(started DESC);
@@ -0,0 +1,36 @@
+-- Password policies as config entities, password history, and expiry.
+--
+-- A policy applies to accounts holding one of its roles, or to every
+-- account when its role list is empty. When several policies apply the
+-- strictest setting of each rule wins.
+
+CREATE TABLE password_policy (
+    id                VARCHAR(64) PRIMARY KEY,
+    label             VARCHAR(255) NOT NULL,
+    roles             UUID[] NOT NULL DEFAULT '{}',
+    min_length        INTEGER NOT NULL DEFAULT 12,
+    require_uppercase BOOLEAN NOT NULL DEFAULT FALSE,
+    require_lowercase BOOLEAN NOT NULL DEFAULT FALSE,
+    require_digit     BOOLEAN NOT NULL DEFAULT FALSE,
+    require_symbol    BOOLEAN NOT NULL DEFAULT FALSE,
+    breach_check      BOOLEAN NOT NULL DEFAULT FALSE,
+    history_count     INTEGER NOT NULL DEFAULT 0,
+    max_age_days      INTEGER NOT NULL DEFAULT 0,
+    created           BIGINT NOT NULL,
+    changed           BIGINT NOT NULL
+);
+
+-- Previous password hashes, newest first by created. Only the most recent
+-- ones are kept (see PasswordPolicy::MAX_HISTORY_COUNT).
+CREATE TABLE password_history (
+    id      UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    pass    VARCHAR(255) NOT NULL,
+    created TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_password_history_user ON password_history (user_id, created DESC);
+
+-- Existing passwords count as set now, so enabling max_age does not
+-- expire every account at once.
+ALTER TABLE users ADD COLUMN password_changed TIMESTAMPTZ NOT NULL DEFAULT NOW();
@@ -0,0 +1,18 @@
+-- Invitations for invite-only registration.
+--
+-- An invitation is sent to one email address and may grant roles when it
+-- is accepted. Only the SHA-256 hash of the token is stored.
+
+CREATE TABLE user_invitation (
+    id         UUID PRIMARY KEY,
+    mail       VARCHAR(254) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    role_ids   UUID[] NOT NULL DEFAULT '{}',
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at    TIMESTAMPTZ,
+    user_id    UUID REFERENCES users(id) ON DELETE SET NULL,
+    created    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_invitation_pending ON user_invitation (expires_at) WHERE used_at IS NULL;
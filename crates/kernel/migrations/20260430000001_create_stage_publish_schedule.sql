@@ -0,0 +1,24 @@
+-- Scheduled stage publishes.
+--
+-- One pending publish per stage. Cron publishes the stage to live once
+-- publish_at has passed and deletes the row; an hour before, it mails the
+-- conflict report to whoever scheduled it.
+
+CREATE TABLE stage_publish_schedule (
+    -- Stage to publish
+    stage_id UUID PRIMARY KEY REFERENCES category_tag(id) ON DELETE CASCADE,
+
+    -- Unix timestamp after which the stage is published
+    publish_at BIGINT NOT NULL,
+
+    -- User who scheduled the publish (receives the conflict report)
+    scheduled_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    -- Unix timestamp the pre-publish conflict report was sent
+    report_sent_at BIGINT,
+
+    -- Unix timestamp the schedule was created or last changed
+    created BIGINT NOT NULL
+);
+
+CREATE INDEX idx_stage_publish_schedule_publish_at ON stage_publish_schedule (publish_at);
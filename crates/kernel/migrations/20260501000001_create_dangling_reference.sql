@@ -0,0 +1,20 @@
+-- Dangling record references.
+--
+-- Rewritten by the check_dangling_references cron task: one row per
+-- reference field value that points at an item which no longer exists.
+
+CREATE TABLE dangling_reference (
+    -- Item holding the reference
+    item_id UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+
+    -- Reference field machine name
+    field_name VARCHAR(128) NOT NULL,
+
+    -- Missing target, as stored in the field
+    target_id TEXT NOT NULL,
+
+    -- Unix timestamp of the run that found it
+    detected BIGINT NOT NULL,
+
+    PRIMARY KEY (item_id, field_name, target_id)
+);
@@ -0,0 +1,39 @@
+-- Item flags.
+--
+-- `flag` holds the flags administrators add; kernel and plugin flags are
+-- not stored. `flagging` holds one row per marked item: per user for
+-- per-user flags, with the nil user for global ones.
+
+CREATE TABLE flag (
+    -- Machine name (e.g. "featured")
+    name VARCHAR(64) PRIMARY KEY,
+
+    -- Human-readable label
+    label VARCHAR(255) NOT NULL,
+
+    -- "user" (each user flags for themselves) or "global" (one shared flag)
+    scope VARCHAR(16) NOT NULL DEFAULT 'user' CHECK (scope IN ('user', 'global')),
+
+    -- Unix timestamp the flag was added
+    created BIGINT NOT NULL
+);
+
+CREATE TABLE flagging (
+    -- Flag machine name (kernel, plugin or admin flag)
+    flag_name VARCHAR(64) NOT NULL,
+
+    -- Flagged item
+    item_id UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+
+    -- User who flagged the item; the nil user for global flags
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Unix timestamp the item was flagged
+    created BIGINT NOT NULL,
+
+    PRIMARY KEY (flag_name, item_id, user_id)
+);
+
+-- Flag counts per item (gather sorts) and a user's flagged items.
+CREATE INDEX idx_flagging_item ON flagging (item_id, flag_name);
+CREATE INDEX idx_flagging_user ON flagging (user_id, flag_name, created DESC);
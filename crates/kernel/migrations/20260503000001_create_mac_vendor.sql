@@ -0,0 +1,12 @@
+-- MAC address vendors.
+--
+-- The IEEE MA-L registry as last downloaded by the `refresh_mac_vendors`
+-- cron task. While empty, lookups use the table bundled with the kernel.
+
+CREATE TABLE mac_vendor (
+    -- OUI: first three octets of the MAC address as uppercase hex
+    prefix CHAR(6) PRIMARY KEY,
+
+    -- Organization the OUI is assigned to
+    vendor TEXT NOT NULL
+);
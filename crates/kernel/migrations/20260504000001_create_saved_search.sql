@@ -0,0 +1,40 @@
+-- Saved searches and query alerts.
+--
+-- Users save a search query with optional item type filters. Searches with
+-- `alert` set are re-run by the `check_saved_searches` cron task; matches
+-- not in `last_results` are mailed to the user and, if set, posted to
+-- `webhook`.
+
+CREATE TABLE saved_search (
+    id UUID PRIMARY KEY,
+
+    -- Owner
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Human-readable name
+    name VARCHAR(255) NOT NULL,
+
+    -- Search terms, as typed into the search box
+    query TEXT NOT NULL,
+
+    -- Item types to match; empty matches all types
+    item_types TEXT[] NOT NULL DEFAULT '{}',
+
+    -- Whether cron notifies the owner of new matches
+    alert BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- URL new matches are posted to, in addition to mail
+    webhook TEXT,
+
+    -- IDs of the matches found by the last check
+    last_results UUID[] NOT NULL DEFAULT '{}',
+
+    -- Unix timestamp of the last check; NULL until first checked
+    last_checked BIGINT,
+
+    -- Unix timestamp the search was saved
+    created BIGINT NOT NULL
+);
+
+CREATE INDEX idx_saved_search_user ON saved_search (user_id, created DESC);
+CREATE INDEX idx_saved_search_alert ON saved_search (last_checked) WHERE alert;
@@ -0,0 +1,22 @@
+-- Per-plugin tap_cron state.
+--
+-- Each cron cycle passes a plugin the time of its last successful tap_cron
+-- run and the `state` value it returned then. The kernel owns the row; the
+-- state is opaque JSON defined by the plugin.
+
+CREATE TABLE plugin_cron_state (
+    -- Plugin the state belongs to.
+    plugin_name VARCHAR(64) PRIMARY KEY,
+
+    -- Cron cycle timestamp of the last successful tap_cron run.
+    last_run BIGINT NOT NULL,
+
+    -- State returned by that run; NULL if the plugin returned none.
+    state JSONB,
+
+    -- Serialized size of `state` in bytes.
+    state_bytes INTEGER NOT NULL DEFAULT 0,
+
+    -- Unix timestamp the row was last written.
+    updated BIGINT NOT NULL
+);
@@ -0,0 +1,28 @@
+-- Spam detections on saved comments.
+--
+-- When the comment spam check catches a comment that is still saved
+-- (action "moderate" or "flag"), the detection is kept here for the
+-- moderation screen. Rejected comments are never saved and leave no row.
+
+CREATE TABLE comment_spam (
+    -- The comment that was caught.
+    comment_id UUID PRIMARY KEY REFERENCES comment(id) ON DELETE CASCADE,
+
+    -- What caught it: "honeypot", "time_trap", or the plugin name.
+    source VARCHAR(64) NOT NULL,
+
+    -- Reason given by the check, if any.
+    reason TEXT,
+
+    -- Score reported by the plugin, if any.
+    score DOUBLE PRECISION,
+
+    -- Action taken: "moderate" or "flag".
+    action VARCHAR(16) NOT NULL,
+
+    -- Client IP address the comment was posted from.
+    client_ip VARCHAR(64) NOT NULL,
+
+    -- Unix timestamp of the detection.
+    created BIGINT NOT NULL
+);
@@ -0,0 +1,44 @@
+-- Scheduled field changes ("timed updates").
+--
+-- Each row holds a JSON merge patch for one item's fields and the time to
+-- apply it. The `apply_scheduled_updates` cron task applies due updates
+-- as the user who scheduled them, through the same path as
+-- `PATCH /item/{id}`, so presave, validate and update taps all run.
+
+CREATE TABLE scheduled_update (
+    -- Unique identifier (UUIDv7).
+    id UUID PRIMARY KEY,
+
+    -- Item whose fields change.
+    item_id UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+
+    -- JSON merge patch applied to the item's fields.
+    fields JSONB NOT NULL,
+
+    -- Unix timestamp after which the update is applied.
+    update_at BIGINT NOT NULL,
+
+    -- Short description shown in the listing and revision log.
+    label VARCHAR(255),
+
+    -- User who scheduled the update; it is applied with their access.
+    scheduled_by UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    -- 'pending', 'applied' or 'failed'.
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+
+    -- Why applying failed.
+    error TEXT,
+
+    -- Unix timestamp the update was applied or failed.
+    processed BIGINT,
+
+    -- Unix timestamp when created.
+    created BIGINT NOT NULL
+);
+
+-- Index for finding due updates
+CREATE INDEX idx_scheduled_update_due ON scheduled_update(update_at) WHERE status = 'pending';
+
+-- Index for listing an item's updates
+CREATE INDEX idx_scheduled_update_item ON scheduled_update(item_id);
@@ -0,0 +1,33 @@
+-- External identities linked to local accounts.
+--
+-- A user who logs in through an OpenID Connect provider is recognized by
+-- the provider's `sub` claim. Each (provider, subject) pair belongs to one
+-- account; an account may be linked to several providers.
+
+CREATE TABLE user_identity (
+    -- Unique identifier (UUIDv7).
+    id UUID PRIMARY KEY,
+
+    -- Local account.
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Provider machine name from the `oidc_providers` config.
+    provider VARCHAR(64) NOT NULL,
+
+    -- The provider's stable identifier of the user (`sub` claim).
+    subject VARCHAR(255) NOT NULL,
+
+    -- Email address the provider reported at the last login.
+    email VARCHAR(255),
+
+    -- Unix timestamp when linked.
+    created BIGINT NOT NULL,
+
+    -- Unix timestamp of the last login through this identity.
+    last_login BIGINT,
+
+    UNIQUE (provider, subject)
+);
+
+-- Index for listing a user's identities
+CREATE INDEX idx_user_identity_user ON user_identity(user_id);
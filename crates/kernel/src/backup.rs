@@ -0,0 +1,806 @@
+//! Backup and restore of content, config and the managed file manifest.
+//!
+//! A backup is a single `.tar.gz` archive holding:
+//!
+//! - `content.sql` — a data-only `pg_dump` of [`CONTENT_TABLES`]
+//! - `config/` — every config entity as YAML, as `trovato config export`
+//!   writes it
+//! - `files.json` — each managed file with its size and SHA-256; file
+//!   contents stay in file storage
+//! - `manifest.json` — the archive's parts, the schema version it was taken
+//!   at, and the SHA-256 of every other entry
+//!
+//! `trovato backup create` and `POST /admin/backups` write archives;
+//! `trovato backup restore` verifies every checksum before changing
+//! anything, then imports the config and/or replaces the content tables,
+//! and reports managed files that are missing or changed in storage.
+//!
+//! Content is restored by emptying the content tables and loading the dump
+//! in one transaction, so it needs the schema the backup was taken at; the
+//! restore refuses archives from another kernel migration version.
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config_storage::ConfigStorage;
+use crate::config_storage::yaml::{self, ConfigFilter, ConfigOpResult};
+use crate::file::FileStorage;
+
+/// Archive format version written to the manifest.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// File name suffix of backup archives.
+pub const ARCHIVE_SUFFIX: &str = ".tar.gz";
+
+/// Tables holding content: accounts, items and everything attached to
+/// them. Config lives in the YAML part instead.
+///
+/// A content restore empties these tables together (cascading to tables
+/// that reference them, such as sessions' tokens and search status) before
+/// loading the dump.
+pub const CONTENT_TABLES: &[&str] = &[
+    "users",
+    "user_roles",
+    "user_tenant",
+    "user_mfa",
+    "user_mfa_recovery_code",
+    "user_invitation",
+    "user_subscriptions",
+    "password_history",
+    "api_tokens",
+    "item",
+    "item_revision",
+    "item_status_change",
+    "item_access",
+    "field_history",
+    "dangling_reference",
+    "comment",
+    "file_managed",
+    "flag",
+    "flagging",
+    "contact_submission",
+    "config_revision",
+    "stage_deletion",
+    "stage_publish_schedule",
+    "stage_workspace",
+];
+
+const MANIFEST: &str = "manifest.json";
+const CONTENT_DUMP: &str = "content.sql";
+const CONFIG_DIR: &str = "config";
+const FILE_MANIFEST: &str = "files.json";
+
+/// Which parts a backup holds or a restore applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupParts {
+    pub content: bool,
+    pub config: bool,
+}
+
+impl BackupParts {
+    /// Content and config.
+    pub const ALL: Self = Self {
+        content: true,
+        config: true,
+    };
+
+    /// Parse an `--only` value: `content`, `config`, or none for both.
+    pub fn only(part: Option<&str>) -> Result<Self> {
+        match part.map(str::trim) {
+            None | Some("") | Some("all") => Ok(Self::ALL),
+            Some("content") => Ok(Self {
+                content: true,
+                config: false,
+            }),
+            Some("config") => Ok(Self {
+                content: false,
+                config: true,
+            }),
+            Some(other) => bail!("unknown backup part '{other}'; expected content or config"),
+        }
+    }
+
+    /// Human-readable list of the parts.
+    pub fn describe(self) -> &'static str {
+        match (self.content, self.config) {
+            (true, true) => "content and config",
+            (true, false) => "content",
+            (false, true) => "config",
+            (false, false) => "nothing",
+        }
+    }
+}
+
+/// `manifest.json` of an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Archive format version.
+    pub format: u32,
+    /// Unix timestamp the backup was taken.
+    pub created: i64,
+    /// Kernel version that wrote the archive.
+    pub kernel_version: String,
+    /// Latest kernel migration applied when the backup was taken.
+    pub schema_version: Option<i64>,
+    pub parts: BackupParts,
+    /// Tables in `content.sql`.
+    #[serde(default)]
+    pub tables: Vec<String>,
+    /// Entries of `files.json`.
+    #[serde(default)]
+    pub files: usize,
+    /// SHA-256 of every other entry, by path within the archive.
+    pub checksums: BTreeMap<String, String>,
+}
+
+/// A managed file in `files.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FileManifestEntry {
+    pub id: Uuid,
+    pub uri: String,
+    pub filename: String,
+    pub filemime: String,
+    pub filesize: i64,
+    /// `None` when the file could not be read at backup time.
+    #[sqlx(default)]
+    pub sha256: Option<String>,
+}
+
+/// Result of creating a backup.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupSummary {
+    pub name: String,
+    pub size: u64,
+    pub parts: BackupParts,
+    pub tables: usize,
+    pub config_entities: usize,
+    pub files: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Result of restoring a backup.
+#[derive(Debug, Default)]
+pub struct RestoreSummary {
+    /// Config import result, when config was restored.
+    pub config: Option<ConfigOpResult>,
+    /// Content tables reloaded.
+    pub tables: usize,
+    /// Managed files not found in storage.
+    pub missing_files: Vec<String>,
+    /// Managed files whose contents differ from the backup.
+    pub changed_files: Vec<String>,
+}
+
+/// A backup archive in the backup directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub size: u64,
+    /// Unix timestamp of the archive's last modification.
+    pub created: i64,
+}
+
+/// Everything a backup or restore reads from and writes to.
+pub struct BackupSources<'a> {
+    pub pool: &'a PgPool,
+    /// Connection URL for `pg_dump` and `psql`.
+    pub database_url: &'a str,
+    pub config: &'a dyn ConfigStorage,
+    pub files: &'a dyn FileStorage,
+}
+
+/// Archive name for a backup taken at `timestamp`.
+pub fn backup_name(timestamp: i64) -> String {
+    let time = chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+    format!(
+        "trovato-backup-{}{ARCHIVE_SUFFIX}",
+        time.format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Whether `name` is a plain archive file name, safe to join to the backup
+/// directory.
+pub fn is_valid_backup_name(name: &str) -> bool {
+    name.ends_with(ARCHIVE_SUFFIX)
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Backup archives in `dir`, newest first.
+pub async fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>> {
+    let mut backups = Vec::new();
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(backups),
+        Err(e) => {
+            return Err(e).with_context(|| format!("failed to read {}", dir.display()));
+        }
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !is_valid_backup_name(&name) {
+            continue;
+        }
+        let metadata = entry.metadata().await?;
+        let created = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs() as i64);
+        backups.push(BackupInfo {
+            name,
+            size: metadata.len(),
+            created,
+        });
+    }
+    backups.sort_by(|a, b| b.created.cmp(&a.created).then(b.name.cmp(&a.name)));
+    Ok(backups)
+}
+
+/// Write a backup of `parts` to `archive`.
+///
+/// The archive is assembled in a staging directory next to it and only
+/// appears under its final name once complete.
+pub async fn create_backup(
+    sources: &BackupSources<'_>,
+    parts: BackupParts,
+    archive: &Path,
+) -> Result<BackupSummary> {
+    let name = archive
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("backup archive path has no file name")?
+        .to_string();
+    if let Some(dir) = archive.parent().filter(|d| !d.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    if archive.exists() {
+        bail!("{} already exists", archive.display());
+    }
+    let staging = archive.with_file_name(format!(".{name}.partial"));
+    tokio::fs::create_dir(&staging)
+        .await
+        .with_context(|| format!("failed to create {}", staging.display()))?;
+
+    let result = stage_backup(sources, parts, &staging, archive).await;
+    tokio::fs::remove_dir_all(&staging).await.ok();
+    let mut summary = result?;
+    summary.name = name;
+
+    info!(
+        archive = %archive.display(),
+        parts = parts.describe(),
+        size = summary.size,
+        "backup created"
+    );
+    Ok(summary)
+}
+
+async fn stage_backup(
+    sources: &BackupSources<'_>,
+    parts: BackupParts,
+    staging: &Path,
+    archive: &Path,
+) -> Result<BackupSummary> {
+    let mut summary = BackupSummary {
+        name: String::new(),
+        size: 0,
+        parts,
+        tables: 0,
+        config_entities: 0,
+        files: 0,
+        warnings: Vec::new(),
+    };
+    let mut manifest = Manifest {
+        format: FORMAT_VERSION,
+        created: chrono::Utc::now().timestamp(),
+        kernel_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: schema_version(sources.pool).await?,
+        parts,
+        tables: Vec::new(),
+        files: 0,
+        checksums: BTreeMap::new(),
+    };
+
+    if parts.content {
+        pg_dump(sources.database_url, &staging.join(CONTENT_DUMP)).await?;
+        manifest.tables = CONTENT_TABLES.iter().map(|t| t.to_string()).collect();
+        summary.tables = CONTENT_TABLES.len();
+
+        let files = file_manifest(sources.pool, sources.files, &mut summary.warnings).await?;
+        manifest.files = files.len();
+        summary.files = files.len();
+        let json =
+            serde_json::to_vec_pretty(&files).context("failed to serialize file manifest")?;
+        tokio::fs::write(staging.join(FILE_MANIFEST), json)
+            .await
+            .context("failed to write file manifest")?;
+    }
+
+    if parts.config {
+        let result = yaml::export_config(
+            sources.config,
+            sources.pool,
+            &staging.join(CONFIG_DIR),
+            false,
+        )
+        .await?;
+        summary.config_entities = result.total();
+        summary.warnings.extend(result.warnings);
+    }
+
+    let staging_dir = staging.to_path_buf();
+    let archive_path = archive.to_path_buf();
+    summary.size = tokio::task::spawn_blocking(move || -> Result<u64> {
+        manifest.checksums = checksum_tree(&staging_dir)?;
+        let json = serde_json::to_vec_pretty(&manifest).context("failed to serialize manifest")?;
+        std::fs::write(staging_dir.join(MANIFEST), json).context("failed to write manifest")?;
+        write_archive(&staging_dir, &archive_path)
+    })
+    .await
+    .context("backup archive task panicked")??;
+
+    Ok(summary)
+}
+
+/// Restore `parts` from `archive`.
+///
+/// Every entry is checked against the manifest before anything is
+/// written. Config is imported first, so roles and types exist when the
+/// content tables are reloaded.
+pub async fn restore_backup(
+    sources: &BackupSources<'_>,
+    archive: &Path,
+    parts: BackupParts,
+) -> Result<RestoreSummary> {
+    let staging = std::env::temp_dir().join(format!("trovato-restore-{}", Uuid::now_v7()));
+    let result = restore_staged(sources, archive, parts, &staging).await;
+    tokio::fs::remove_dir_all(&staging).await.ok();
+    result
+}
+
+async fn restore_staged(
+    sources: &BackupSources<'_>,
+    archive: &Path,
+    parts: BackupParts,
+    staging: &Path,
+) -> Result<RestoreSummary> {
+    let archive_path = archive.to_path_buf();
+    let staging_dir = staging.to_path_buf();
+    let manifest = tokio::task::spawn_blocking(move || -> Result<Manifest> {
+        extract_archive(&archive_path, &staging_dir)?;
+        let manifest = read_manifest(&staging_dir)?;
+        verify_checksums(&staging_dir, &manifest)?;
+        Ok(manifest)
+    })
+    .await
+    .context("backup extraction task panicked")??;
+
+    if parts.content && !manifest.parts.content {
+        bail!("the backup has no content");
+    }
+    if parts.config && !manifest.parts.config {
+        bail!("the backup has no config");
+    }
+    if parts.content {
+        let current = schema_version(sources.pool).await?;
+        if current != manifest.schema_version {
+            bail!(
+                "the backup was taken at schema version {} but the database is at {}; \
+                 restore it with the kernel version that wrote it ({})",
+                display_version(manifest.schema_version),
+                display_version(current),
+                manifest.kernel_version
+            );
+        }
+    }
+
+    let mut summary = RestoreSummary::default();
+
+    if parts.config {
+        let result = yaml::import_config(
+            sources.config,
+            sources.pool,
+            &staging.join(CONFIG_DIR),
+            false,
+            &ConfigFilter::default(),
+        )
+        .await?;
+        summary.config = Some(result);
+    }
+
+    if parts.content {
+        let preamble = staging.join("truncate.sql");
+        tokio::fs::write(&preamble, truncate_sql(&manifest.tables)?)
+            .await
+            .context("failed to write restore preamble")?;
+        psql(
+            sources.database_url,
+            &[preamble.as_path(), &staging.join(CONTENT_DUMP)],
+        )
+        .await?;
+        summary.tables = manifest.tables.len();
+
+        let json = tokio::fs::read(staging.join(FILE_MANIFEST))
+            .await
+            .context("failed to read file manifest")?;
+        let files: Vec<FileManifestEntry> =
+            serde_json::from_slice(&json).context("invalid file manifest")?;
+        check_files(sources.files, &files, &mut summary).await;
+    }
+
+    info!(
+        archive = %archive.display(),
+        parts = parts.describe(),
+        tables = summary.tables,
+        missing_files = summary.missing_files.len(),
+        changed_files = summary.changed_files.len(),
+        "backup restored"
+    );
+    Ok(summary)
+}
+
+/// Latest kernel migration applied to the database.
+async fn schema_version(pool: &PgPool) -> Result<Option<i64>> {
+    sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+        .fetch_one(pool)
+        .await
+        .context("failed to load schema version")
+}
+
+fn display_version(version: Option<i64>) -> String {
+    version.map_or_else(|| "none".to_string(), |v| v.to_string())
+}
+
+/// Managed files with the SHA-256 of their stored contents.
+async fn file_manifest(
+    pool: &PgPool,
+    storage: &dyn FileStorage,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<FileManifestEntry>> {
+    let mut files = sqlx::query_as::<_, FileManifestEntry>(
+        "SELECT id, uri, filename, filemime, filesize FROM file_managed ORDER BY created, id",
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to list managed files")?;
+
+    let prefix = format!("{}://", storage.scheme());
+    for file in &mut files {
+        if !file.uri.starts_with(&prefix) {
+            warnings.push(format!(
+                "file {} is not on {} storage; no checksum recorded",
+                file.id,
+                storage.scheme()
+            ));
+            continue;
+        }
+        match storage.read(&file.uri).await {
+            Ok(data) => file.sha256 = Some(hex::encode(Sha256::digest(&data))),
+            Err(e) => warnings.push(format!("failed to read file {}: {e}", file.id)),
+        }
+    }
+    Ok(files)
+}
+
+/// Compare restored file records with storage.
+async fn check_files(
+    storage: &dyn FileStorage,
+    files: &[FileManifestEntry],
+    summary: &mut RestoreSummary,
+) {
+    for file in files {
+        let Some(expected) = &file.sha256 else {
+            continue;
+        };
+        match storage.read(&file.uri).await {
+            Ok(data) if hex::encode(Sha256::digest(&data)) == *expected => {}
+            Ok(_) => summary.changed_files.push(file.uri.clone()),
+            Err(e) => {
+                warn!(uri = %file.uri, error = %e, "restored file record has no stored file");
+                summary.missing_files.push(file.uri.clone());
+            }
+        }
+    }
+}
+
+/// Password-free connection URL and the password, so the password reaches
+/// `pg_dump` and `psql` through the environment rather than their command
+/// line.
+fn split_password(database_url: &str) -> Result<(String, Option<String>)> {
+    let mut url = url::Url::parse(database_url).context("invalid DATABASE_URL")?;
+    let password = url
+        .password()
+        .map(|p| urlencoding::decode(p).map(|p| p.into_owned()))
+        .transpose()
+        .context("invalid password in DATABASE_URL")?;
+    url.set_password(None)
+        .map_err(|()| anyhow::anyhow!("invalid DATABASE_URL"))?;
+    Ok((url.to_string(), password))
+}
+
+/// Run a PostgreSQL client tool against the database.
+async fn run_pg_tool(tool: &str, database_url: &str, args: &[&OsStr]) -> Result<()> {
+    let (url, password) = split_password(database_url)?;
+    let mut command = tokio::process::Command::new(tool);
+    command.arg("--dbname").arg(url).args(args);
+    if let Some(password) = password {
+        command.env("PGPASSWORD", password);
+    }
+
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("failed to run {tool}; is the PostgreSQL client installed?"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{tool} failed: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Dump the data of the content tables to `path`.
+async fn pg_dump(database_url: &str, path: &Path) -> Result<()> {
+    let tables: Vec<String> = CONTENT_TABLES
+        .iter()
+        .map(|t| format!("--table=public.{t}"))
+        .collect();
+    let mut args: Vec<&OsStr> = ["--data-only", "--no-owner", "--no-privileges", "--file"]
+        .into_iter()
+        .map(OsStr::new)
+        .collect();
+    args.push(path.as_os_str());
+    args.extend(tables.iter().map(OsStr::new));
+    run_pg_tool("pg_dump", database_url, &args).await
+}
+
+/// Run SQL files in one transaction, stopping at the first error.
+async fn psql(database_url: &str, files: &[&Path]) -> Result<()> {
+    let mut args: Vec<&OsStr> = [
+        "--single-transaction",
+        "--quiet",
+        "--set",
+        "ON_ERROR_STOP=1",
+    ]
+    .into_iter()
+    .map(OsStr::new)
+    .collect();
+    for file in files {
+        args.push(OsStr::new("--file"));
+        args.push(file.as_os_str());
+    }
+    run_pg_tool("psql", database_url, &args).await
+}
+
+/// `TRUNCATE` statement emptying the content tables before a restore.
+fn truncate_sql(tables: &[String]) -> Result<String> {
+    if tables.is_empty() {
+        bail!("the backup lists no content tables");
+    }
+    if let Some(table) = tables
+        .iter()
+        .find(|t| !CONTENT_TABLES.contains(&t.as_str()))
+    {
+        bail!("the backup lists unknown content table '{table}'");
+    }
+    let list: Vec<String> = tables.iter().map(|t| format!("public.{t}")).collect();
+    Ok(format!("TRUNCATE TABLE {} CASCADE;\n", list.join(", ")))
+}
+
+/// Relative paths of the files under `dir`, with `/` separators.
+fn relative_files(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)
+            .with_context(|| format!("failed to read {}", current.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let relative = path
+                .strip_prefix(dir)
+                .context("entry outside the backup directory")?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// SHA-256 of a file, read in chunks.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// SHA-256 of every file under `dir` except the manifest.
+fn checksum_tree(dir: &Path) -> Result<BTreeMap<String, String>> {
+    relative_files(dir)?
+        .into_iter()
+        .filter(|(name, _)| name != MANIFEST)
+        .map(|(name, path)| Ok((name, sha256_file(&path)?)))
+        .collect()
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest> {
+    let json = std::fs::read(dir.join(MANIFEST)).context("the backup has no manifest.json")?;
+    let manifest: Manifest = serde_json::from_slice(&json).context("invalid manifest.json")?;
+    if manifest.format > FORMAT_VERSION {
+        bail!(
+            "backup format {} is newer than this kernel supports ({FORMAT_VERSION})",
+            manifest.format
+        );
+    }
+    Ok(manifest)
+}
+
+/// Check that the extracted entries are exactly those in the manifest,
+/// with matching checksums.
+fn verify_checksums(dir: &Path, manifest: &Manifest) -> Result<()> {
+    let actual = checksum_tree(dir)?;
+    let mut problems = Vec::new();
+    for (name, expected) in &manifest.checksums {
+        match actual.get(name) {
+            Some(sum) if sum == expected => {}
+            Some(_) => problems.push(format!("{name}: checksum mismatch")),
+            None => problems.push(format!("{name}: missing")),
+        }
+    }
+    for name in actual.keys() {
+        if !manifest.checksums.contains_key(name) {
+            problems.push(format!("{name}: not in the manifest"));
+        }
+    }
+    if !problems.is_empty() {
+        bail!("backup integrity check failed: {}", problems.join("; "));
+    }
+    Ok(())
+}
+
+/// Pack `dir` into a gzipped tar at `archive`, through a temporary file.
+fn write_archive(dir: &Path, archive: &Path) -> Result<u64> {
+    let tmp = archive.with_extension("tmp");
+    let file = std::fs::File::create(&tmp)
+        .with_context(|| format!("failed to create {}", tmp.display()))?;
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+    for (name, path) in relative_files(dir)? {
+        builder
+            .append_path_with_name(&path, &name)
+            .with_context(|| format!("failed to add {name} to the backup"))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|gz| gz.finish())
+        .context("failed to finish backup archive")?;
+
+    std::fs::rename(&tmp, archive)
+        .with_context(|| format!("failed to write {}", archive.display()))?;
+    Ok(std::fs::metadata(archive)?.len())
+}
+
+/// Unpack a backup archive into `dir`.
+///
+/// `tar` refuses entries that would land outside `dir`.
+fn extract_archive(archive: &Path, dir: &Path) -> Result<()> {
+    let file = std::fs::File::open(archive)
+        .with_context(|| format!("failed to open {}", archive.display()))?;
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    tar::Archive::new(flate2::read::GzDecoder::new(file))
+        .unpack(dir)
+        .context("failed to extract backup archive")
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("trovato-backup-{}", Uuid::now_v7()));
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_selects_parts() {
+        assert_eq!(BackupParts::only(None).unwrap(), BackupParts::ALL);
+        let content = BackupParts::only(Some("content")).unwrap();
+        assert!(content.content && !content.config);
+        let config = BackupParts::only(Some("config")).unwrap();
+        assert!(!config.content && config.config);
+        assert!(BackupParts::only(Some("files")).is_err());
+    }
+
+    #[test]
+    fn backup_names_are_checked() {
+        let name = backup_name(1_791_849_600);
+        assert_eq!(name, "trovato-backup-20261013-000000.tar.gz");
+        assert!(is_valid_backup_name(&name));
+        assert!(!is_valid_backup_name("../etc/passwd.tar.gz"));
+        assert!(!is_valid_backup_name(".hidden.tar.gz"));
+        assert!(!is_valid_backup_name("backup.zip"));
+    }
+
+    #[test]
+    fn passwords_are_kept_off_the_command_line() {
+        let (url, password) =
+            split_password("postgres://trovato:s%40cret@db:5432/trovato").unwrap();
+        assert_eq!(url, "postgres://trovato@db:5432/trovato");
+        assert_eq!(password.as_deref(), Some("s@cret"));
+
+        let (url, password) = split_password("postgres://db/trovato").unwrap();
+        assert_eq!(url, "postgres://db/trovato");
+        assert_eq!(password, None);
+    }
+
+    #[test]
+    fn restores_only_truncate_content_tables() {
+        let sql = truncate_sql(&["item".to_string(), "comment".to_string()]).unwrap();
+        assert_eq!(sql, "TRUNCATE TABLE public.item, public.comment CASCADE;\n");
+        assert!(truncate_sql(&["item; DROP TABLE users".to_string()]).is_err());
+        assert!(truncate_sql(&[]).is_err());
+    }
+
+    #[test]
+    fn checksums_cover_every_entry() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("content.sql"), "COPY item FROM stdin;\n").unwrap();
+        std::fs::write(dir.join("config/role.editor.yml"), "name: editor\n").unwrap();
+
+        let checksums = checksum_tree(&dir).unwrap();
+        assert_eq!(
+            checksums.keys().collect::<Vec<_>>(),
+            ["config/role.editor.yml", "content.sql"]
+        );
+        let manifest = Manifest {
+            format: FORMAT_VERSION,
+            created: 0,
+            kernel_version: String::new(),
+            schema_version: None,
+            parts: BackupParts::ALL,
+            tables: Vec::new(),
+            files: 0,
+            checksums,
+        };
+        std::fs::write(dir.join(MANIFEST), "{}").unwrap();
+        verify_checksums(&dir, &manifest).unwrap();
+
+        std::fs::write(dir.join("content.sql"), "DROP TABLE item;\n").unwrap();
+        std::fs::write(dir.join("extra.sql"), "").unwrap();
+        let err = verify_checksums(&dir, &manifest).unwrap_err().to_string();
+        assert!(err.contains("content.sql: checksum mismatch"));
+        assert!(err.contains("extra.sql: not in the manifest"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
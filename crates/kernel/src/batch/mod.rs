@@ -3,9 +3,15 @@
 //! This module provides an API for starting, monitoring, and retrieving
 //! results of long-running background operations like bulk reindexing,
 //! content migrations, and file processing.
+//!
+//! Plugins declare their own multi-step batches through `tap_batch_define`;
+//! the kernel then drives them via `tap_batch_process`, persisting progress
+//! after each step and continuing unfinished batches on cron.
 
+mod plugin;
 mod service;
 mod types;
 
+pub use plugin::{BatchDefinitionRegistry, PluginBatchDefinition};
 pub use service::BatchService;
 pub use types::{BatchOperation, BatchProgress, BatchStatus, CreateBatch};
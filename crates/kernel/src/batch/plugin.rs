@@ -0,0 +1,126 @@
+//! Registry of plugin-declared batches.
+//!
+//! Built at startup from `tap_batch_define` results. Each definition is
+//! owned by the plugin that declared it; the kernel dispatches
+//! `tap_batch_process` to that plugin only.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tracing::warn;
+use trovato_sdk::types::BatchDefinition;
+
+/// A batch definition together with its declaring plugin.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginBatchDefinition {
+    /// Plugin that declared the batch.
+    pub plugin: String,
+    /// The definition as returned by the plugin.
+    #[serde(flatten)]
+    pub definition: BatchDefinition,
+}
+
+/// Plugin batch definitions keyed by name.
+#[derive(Debug, Default)]
+pub struct BatchDefinitionRegistry {
+    by_name: HashMap<String, PluginBatchDefinition>,
+}
+
+impl BatchDefinitionRegistry {
+    /// Build the registry from `(plugin_name, output_json)` tap results.
+    ///
+    /// Results are expected in weight order. Malformed output is logged
+    /// and skipped; if two plugins declare the same name the first wins.
+    pub fn from_tap_results(results: Vec<(String, String)>) -> Self {
+        let mut by_name: HashMap<String, PluginBatchDefinition> = HashMap::new();
+        for (plugin, output) in results {
+            let definitions: Vec<BatchDefinition> = match serde_json::from_str(&output) {
+                Ok(defs) => defs,
+                Err(e) => {
+                    warn!(plugin = %plugin, error = %e, "failed to parse tap_batch_define response");
+                    continue;
+                }
+            };
+            for definition in definitions {
+                if definition.name.trim().is_empty() {
+                    warn!(plugin = %plugin, "ignoring batch definition with empty name");
+                    continue;
+                }
+                if let Some(existing) = by_name.get(&definition.name) {
+                    warn!(
+                        batch = %definition.name,
+                        plugin = %plugin,
+                        owner = %existing.plugin,
+                        "duplicate batch definition ignored"
+                    );
+                    continue;
+                }
+                by_name.insert(
+                    definition.name.clone(),
+                    PluginBatchDefinition {
+                        plugin: plugin.clone(),
+                        definition,
+                    },
+                );
+            }
+        }
+        Self { by_name }
+    }
+
+    /// Look up a definition by name.
+    pub fn get(&self, name: &str) -> Option<&PluginBatchDefinition> {
+        self.by_name.get(name)
+    }
+
+    /// All definitions, sorted by name.
+    pub fn list(&self) -> Vec<&PluginBatchDefinition> {
+        let mut defs: Vec<_> = self.by_name.values().collect();
+        defs.sort_by(|a, b| a.definition.name.cmp(&b.definition.name));
+        defs
+    }
+
+    /// Whether no plugin declared a batch.
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_parses_definitions() {
+        let registry = BatchDefinitionRegistry::from_tap_results(vec![(
+            "argus".to_string(),
+            r#"[{"name":"argus_reembed","label":"Re-embed articles"}]"#.to_string(),
+        )]);
+        let def = registry.get("argus_reembed").unwrap();
+        assert_eq!(def.plugin, "argus");
+        assert_eq!(def.definition.label, "Re-embed articles");
+        assert_eq!(registry.list().len(), 1);
+    }
+
+    #[test]
+    fn registry_first_declaration_wins() {
+        let registry = BatchDefinitionRegistry::from_tap_results(vec![
+            ("a".to_string(), r#"[{"name":"x","label":"A"}]"#.to_string()),
+            ("b".to_string(), r#"[{"name":"x","label":"B"}]"#.to_string()),
+        ]);
+        assert_eq!(registry.get("x").unwrap().plugin, "a");
+    }
+
+    #[test]
+    fn registry_skips_malformed_output() {
+        let registry = BatchDefinitionRegistry::from_tap_results(vec![
+            ("bad".to_string(), "not json".to_string()),
+            (
+                "empty".to_string(),
+                r#"[{"name":" ","label":"E"}]"#.to_string(),
+            ),
+        ]);
+        assert!(registry.is_empty());
+    }
+}
@@ -1,31 +1,77 @@
 //! Batch operations service.
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use redis::{AsyncCommands, Client as RedisClient};
-use tracing::{debug, error, info};
+use redis::AsyncCommands;
+use tracing::{debug, error, info, warn};
+use trovato_sdk::types::{BatchStepInput, BatchStepResult};
 use uuid::Uuid;
 
+use super::plugin::BatchDefinitionRegistry;
 use super::types::{BatchOperation, BatchProgress, BatchStatus, CreateBatch};
+use crate::redis_manager::RedisManager;
+use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
 
 const BATCH_KEY_PREFIX: &str = "batch:";
 const BATCH_TTL_SECS: i64 = 86400; // 24 hours
 
+/// Redis set of plugin batch IDs that still have steps to run.
+const ACTIVE_BATCHES_KEY: &str = "batch:active";
+
+/// Prefix for per-batch step locks.
+const STEP_LOCK_PREFIX: &str = "batch:lock:";
+
+/// Step lock TTL in seconds.
+///
+/// Longer than the 150-second background tap deadline so a lock never
+/// expires while a step is still running.
+const STEP_LOCK_TTL_SECS: u64 = 300;
+
+/// Dispatcher and services used to drive plugin batches.
+#[derive(Clone)]
+struct PluginBatchRunner {
+    dispatcher: Arc<TapDispatcher>,
+    services: RequestServices,
+    definitions: Arc<BatchDefinitionRegistry>,
+}
+
 /// Service for managing batch operations.
 #[derive(Clone)]
 pub struct BatchService {
-    redis: Arc<RedisClient>,
+    redis: Arc<RedisManager>,
+    runner: Option<PluginBatchRunner>,
 }
 
 impl BatchService {
     /// Create a new batch service.
-    pub fn new(redis: RedisClient) -> Self {
+    pub fn new(redis: RedisManager) -> Self {
         Self {
             redis: Arc::new(redis),
+            runner: None,
         }
     }
 
+    /// Enable plugin batches declared via `tap_batch_define`.
+    pub fn set_plugin_batches(
+        &mut self,
+        dispatcher: Arc<TapDispatcher>,
+        services: RequestServices,
+        definitions: BatchDefinitionRegistry,
+    ) {
+        self.runner = Some(PluginBatchRunner {
+            dispatcher,
+            services,
+            definitions: Arc::new(definitions),
+        });
+    }
+
+    /// Plugin batch definitions, if plugin batches are enabled.
+    pub fn definitions(&self) -> Option<&BatchDefinitionRegistry> {
+        self.runner.as_ref().map(|r| r.definitions.as_ref())
+    }
+
     /// Create a new batch operation.
     pub async fn create(&self, input: CreateBatch) -> Result<BatchOperation> {
         let id = Uuid::now_v7();
@@ -39,6 +85,8 @@ impl BatchService {
             params: input.params,
             result: None,
             error: None,
+            plugin: None,
+            sandbox: serde_json::Value::Null,
             created: now,
             updated: now,
         };
@@ -60,7 +108,7 @@ impl BatchService {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -181,7 +229,7 @@ impl BatchService {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -191,6 +239,234 @@ impl BatchService {
         Ok(deleted > 0)
     }
 
+    /// Start a plugin-declared batch.
+    ///
+    /// Creates the operation and marks it active so cron continues it if
+    /// the caller does not run it to completion. Returns an error if the
+    /// batch name is unknown.
+    pub async fn start_plugin_batch(
+        &self,
+        name: &str,
+        params: serde_json::Value,
+    ) -> Result<BatchOperation> {
+        let definition = self
+            .definitions()
+            .and_then(|d| d.get(name))
+            .with_context(|| format!("unknown batch: {name}"))?;
+
+        let mut operation = self
+            .create(CreateBatch {
+                operation_type: name.to_string(),
+                params,
+            })
+            .await?;
+        operation.plugin = Some(definition.plugin.clone());
+        self.save(&operation).await?;
+
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+        conn.sadd::<_, _, ()>(ACTIVE_BATCHES_KEY, operation.id.to_string())
+            .await
+            .context("failed to mark batch active")?;
+
+        Ok(operation)
+    }
+
+    /// Run steps of a plugin batch until it finishes or `budget` elapses.
+    ///
+    /// The budget is checked between steps, so a run may overshoot by the
+    /// duration of one step. Returns the operation as of the last step, or
+    /// `None` if it no longer exists. If another caller is already running
+    /// steps for this batch, returns the current state without running any.
+    pub async fn run_steps(&self, id: Uuid, budget: Duration) -> Result<Option<BatchOperation>> {
+        let Some(runner) = self.runner.as_ref() else {
+            return self.get(id).await;
+        };
+
+        if !self.acquire_step_lock(id).await? {
+            debug!(batch_id = %id, "batch steps already running elsewhere");
+            return self.get(id).await;
+        }
+
+        let result = self.run_steps_locked(runner, id, budget).await;
+
+        if let Err(e) = self.release_step_lock(id).await {
+            warn!(batch_id = %id, error = %e, "failed to release batch step lock");
+        }
+
+        result
+    }
+
+    async fn run_steps_locked(
+        &self,
+        runner: &PluginBatchRunner,
+        id: Uuid,
+        budget: Duration,
+    ) -> Result<Option<BatchOperation>> {
+        let start = Instant::now();
+        loop {
+            let Some(mut operation) = self.get(id).await? else {
+                self.deactivate(id).await?;
+                return Ok(None);
+            };
+            let Some(plugin) = operation.plugin.clone() else {
+                // Kernel operations report their own progress.
+                return Ok(Some(operation));
+            };
+            if !operation.status.is_active() {
+                self.deactivate(id).await?;
+                return Ok(Some(operation));
+            }
+            if start.elapsed() >= budget {
+                return Ok(Some(operation));
+            }
+
+            let input = BatchStepInput {
+                batch_id: id.to_string(),
+                name: operation.operation_type.clone(),
+                params: operation.params.clone(),
+                sandbox: operation.sandbox.clone(),
+                processed: operation.progress.processed,
+                total: operation.progress.total,
+            };
+            let input_json = serde_json::to_string(&input).context("failed to serialize step")?;
+            let state = RequestState::new(UserContext::anonymous(), runner.services.clone());
+
+            let step = runner
+                .dispatcher
+                .dispatch_to_plugin("tap_batch_process", &input_json, &plugin, state)
+                .await
+                .map(|r| serde_json::from_str::<BatchStepResult>(&r.output));
+
+            let step = match step {
+                Some(Ok(step)) => step,
+                Some(Err(e)) => {
+                    self.fail(id, &format!("invalid tap_batch_process response: {e}"))
+                        .await?;
+                    self.deactivate(id).await?;
+                    return self.get(id).await;
+                }
+                None => {
+                    self.fail(id, "tap_batch_process failed").await?;
+                    self.deactivate(id).await?;
+                    return self.get(id).await;
+                }
+            };
+
+            if step.finished {
+                self.complete(id, step.result).await?;
+                self.deactivate(id).await?;
+                return self.get(id).await;
+            }
+
+            // Re-read so a cancel issued during the step is not overwritten.
+            if let Some(current) = self.get(id).await?
+                && !current.status.is_active()
+            {
+                continue;
+            }
+
+            operation.status = BatchStatus::Running;
+            operation.progress.total = step.total;
+            operation.progress.update(step.processed, step.message);
+            operation.sandbox = step.sandbox;
+            operation.updated = chrono::Utc::now().timestamp();
+            self.save(&operation).await?;
+        }
+    }
+
+    /// Continue all active plugin batches, sharing `budget` between them.
+    ///
+    /// Called from cron so batches keep progressing after the request that
+    /// started them has returned or timed out. Returns the number of
+    /// batches that were advanced.
+    pub async fn continue_active(&self, budget: Duration) -> Result<usize> {
+        if self.runner.is_none() {
+            return Ok(0);
+        }
+
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+        let ids: Vec<String> = conn
+            .smembers(ACTIVE_BATCHES_KEY)
+            .await
+            .context("failed to list active batches")?;
+
+        let start = Instant::now();
+        let mut advanced = 0;
+        for id in ids {
+            let remaining = budget.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            let Ok(id) = Uuid::parse_str(&id) else {
+                conn.srem::<_, _, ()>(ACTIVE_BATCHES_KEY, &id)
+                    .await
+                    .context("failed to remove invalid batch id")?;
+                continue;
+            };
+            match self.run_steps(id, remaining).await {
+                Ok(Some(_)) => advanced += 1,
+                Ok(None) => {}
+                Err(e) => warn!(batch_id = %id, error = %e, "failed to continue batch"),
+            }
+        }
+
+        Ok(advanced)
+    }
+
+    /// Remove a batch from the active set.
+    async fn deactivate(&self, id: Uuid) -> Result<()> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+        conn.srem::<_, _, ()>(ACTIVE_BATCHES_KEY, id.to_string())
+            .await
+            .context("failed to mark batch inactive")
+    }
+
+    /// Acquire the per-batch step lock. Returns `false` if already held.
+    async fn acquire_step_lock(&self, id: Uuid) -> Result<bool> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        // SET NX EX - set only if not exists, with expiry
+        let result: Option<String> = redis::cmd("SET")
+            .arg(format!("{STEP_LOCK_PREFIX}{id}"))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(STEP_LOCK_TTL_SECS)
+            .query_async(&mut conn)
+            .await
+            .context("failed to acquire batch step lock")?;
+
+        Ok(result.is_some())
+    }
+
+    /// Release the per-batch step lock.
+    async fn release_step_lock(&self, id: Uuid) -> Result<()> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+        conn.del::<_, ()>(format!("{STEP_LOCK_PREFIX}{id}"))
+            .await
+            .context("failed to release batch step lock")
+    }
+
     /// Save a batch operation to Redis.
     async fn save(&self, operation: &BatchOperation) -> Result<()> {
         let key = self.operation_key(operation.id);
@@ -198,7 +474,7 @@ impl BatchService {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -30,6 +30,18 @@ pub struct BatchOperation {
     #[serde(default)]
     pub error: Option<String>,
 
+    /// Plugin that declared this batch via `tap_batch_define`.
+    ///
+    /// Set for plugin batches, which the kernel drives step by step through
+    /// `tap_batch_process`; `None` for kernel operations that report their
+    /// own progress.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+
+    /// Plugin-owned state carried between steps.
+    #[serde(default)]
+    pub sandbox: serde_json::Value,
+
     /// Unix timestamp when operation was created.
     pub created: i64,
 
@@ -74,6 +86,13 @@ pub struct BatchProgress {
     pub percentage: u8,
 }
 
+impl BatchStatus {
+    /// Whether the operation can still make progress.
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Pending | Self::Running)
+    }
+}
+
 impl BatchProgress {
     /// Create a new progress tracker.
     pub fn new(total: u64) -> Self {
@@ -0,0 +1,232 @@
+//! HTTP conditional requests.
+//!
+//! Responses carry an `ETag` and, where the content has a modification
+//! time, a `Last-Modified` header. A `GET` whose `If-None-Match` (or,
+//! without it, `If-Modified-Since`) shows the client already has the
+//! current representation is answered with `304 Not Modified` and no body.
+//!
+//! Item ETags are derived from the item's ID, revision and `changed`
+//! timestamp, so they can be checked against the cached item without
+//! rendering. Gather and search results use a hash of the response body.
+//! The page cache stores each page's ETag with the page, so revalidating an
+//! anonymous page never reaches Postgres.
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Format of HTTP dates (RFC 9110 `IMF-fixdate`).
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// `Cache-Control` of conditional API responses: browsers may keep them
+/// but must revalidate before each use.
+const REVALIDATE: &str = "private, no-cache";
+
+/// Validators of one representation of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validators {
+    /// Quoted strong entity tag.
+    pub etag: String,
+    /// Unix timestamp of the last change, if known.
+    pub last_modified: Option<i64>,
+}
+
+impl Validators {
+    /// Validators from the values identifying a representation, such as an
+    /// item's ID, revision and `changed` timestamp.
+    pub fn from_parts(parts: &[&str], last_modified: Option<i64>) -> Self {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        Self::from_digest(hasher.finalize().as_slice(), last_modified)
+    }
+
+    /// Validators from a response body.
+    pub fn from_body(body: &[u8], last_modified: Option<i64>) -> Self {
+        Self::from_digest(Sha256::digest(body).as_slice(), last_modified)
+    }
+
+    fn from_digest(digest: &[u8], last_modified: Option<i64>) -> Self {
+        // 128 bits is plenty to tell representations apart.
+        Self {
+            etag: format!("\"{}\"", hex::encode(&digest[..16])),
+            last_modified,
+        }
+    }
+
+    /// Whether the request's preconditions show the client's copy is
+    /// current, so a `304 Not Modified` may be sent.
+    ///
+    /// `If-None-Match` takes precedence; `If-Modified-Since` is only
+    /// consulted without it.
+    pub fn is_fresh(&self, request: &HeaderMap) -> bool {
+        if let Some(if_none_match) = request.get(header::IF_NONE_MATCH) {
+            return if_none_match
+                .to_str()
+                .is_ok_and(|value| etag_matches(value, &self.etag));
+        }
+        let since = request
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_http_date);
+        matches!((self.last_modified, since), (Some(modified), Some(since)) if modified <= since)
+    }
+
+    /// Add `ETag` and `Last-Modified` to response headers.
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Some(date) = self.last_modified.and_then(http_date)
+            && let Ok(date) = HeaderValue::from_str(&date)
+        {
+            headers.insert(header::LAST_MODIFIED, date);
+        }
+    }
+
+    /// An empty `304 Not Modified` response carrying the validators.
+    pub fn not_modified(&self) -> Response {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        self.apply(response.headers_mut());
+        response
+    }
+
+    /// `304 Not Modified` if the client's copy is current, otherwise
+    /// `response` with the validators added.
+    ///
+    /// API responses depend on the session, so they are marked private and
+    /// must be revalidated before reuse.
+    pub fn respond(&self, request: &HeaderMap, response: impl IntoResponse) -> Response {
+        let mut response = if self.is_fresh(request) {
+            self.not_modified()
+        } else {
+            let mut response = response.into_response();
+            self.apply(response.headers_mut());
+            response
+        };
+        response
+            .headers_mut()
+            .entry(header::CACHE_CONTROL)
+            .or_insert(HeaderValue::from_static(REVALIDATE));
+        response
+    }
+}
+
+/// A JSON response validated by a hash of its body.
+pub fn json_response<T: Serialize>(request: &HeaderMap, value: &T) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to serialize response");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let validators = Validators::from_body(&body, None);
+    let response = (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )],
+        body,
+    );
+    validators.respond(request, response)
+}
+
+/// Whether an `If-None-Match` value matches `etag`, using the weak
+/// comparison RFC 9110 requires for `GET`.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque(candidate) == etag)
+}
+
+/// Format a Unix timestamp as an HTTP date.
+pub fn http_date(timestamp: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(timestamp, 0).map(|t| t.format(HTTP_DATE_FORMAT).to_string())
+}
+
+/// Parse an HTTP date into a Unix timestamp.
+pub fn parse_http_date(value: &str) -> Option<i64> {
+    chrono::NaiveDateTime::parse_from_str(value.trim(), HTTP_DATE_FORMAT)
+        .ok()
+        .map(|t| t.and_utc().timestamp())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn request(name: header::HeaderName, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn etags_identify_representations() {
+        let a = Validators::from_parts(&["item", "1", "10"], None);
+        assert_eq!(a, Validators::from_parts(&["item", "1", "10"], None));
+        // Part boundaries count.
+        assert_ne!(a, Validators::from_parts(&["item", "11", "0"], None));
+        assert!(a.etag.starts_with('"') && a.etag.ends_with('"'));
+        assert_eq!(a.etag.len(), 34);
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let v = Validators::from_body(b"{}", None);
+        assert!(v.is_fresh(&request(header::IF_NONE_MATCH, &v.etag)));
+        assert!(v.is_fresh(&request(
+            header::IF_NONE_MATCH,
+            &format!("\"other\", W/{}", v.etag)
+        )));
+        assert!(v.is_fresh(&request(header::IF_NONE_MATCH, "*")));
+        assert!(!v.is_fresh(&request(header::IF_NONE_MATCH, "\"other\"")));
+        assert!(!v.is_fresh(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn if_modified_since_applies_without_if_none_match() {
+        let v = Validators::from_parts(&["item"], Some(1_791_849_600));
+        let date = http_date(1_791_849_600).unwrap();
+        assert_eq!(date, "Tue, 13 Oct 2026 00:00:00 GMT");
+        assert_eq!(parse_http_date(&date), Some(1_791_849_600));
+
+        assert!(v.is_fresh(&request(header::IF_MODIFIED_SINCE, &date)));
+        let earlier = http_date(1_791_849_599).unwrap();
+        assert!(!v.is_fresh(&request(header::IF_MODIFIED_SINCE, &earlier)));
+
+        // A non-matching ETag wins over a current date.
+        let mut headers = request(header::IF_MODIFIED_SINCE, &date);
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"x\""));
+        assert!(!v.is_fresh(&headers));
+    }
+
+    #[test]
+    fn fresh_requests_get_an_empty_304() {
+        let v = Validators::from_parts(&["item"], Some(0));
+        let response = v.respond(&request(header::IF_NONE_MATCH, &v.etag), "body");
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], v.etag.as_str());
+        assert_eq!(response.headers()[header::CACHE_CONTROL], REVALIDATE);
+
+        let response = v.respond(&HeaderMap::new(), "body");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            "Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+    }
+}
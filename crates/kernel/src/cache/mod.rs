@@ -1,15 +1,26 @@
 //! Two-tier cache with Moka (L1) and Redis (L2).
 //!
 //! Supports tag-based invalidation for efficient cache management.
+//!
+//! Inside a multisite request (see [`crate::services::site`]) keys are
+//! prefixed with the site's tenant, so sites never see each other's
+//! entries. Tags stay global: invalidating a tag clears it on every site.
+
+pub mod conditional;
+pub mod page;
 
+use std::borrow::Cow;
 use std::sync::Arc;
 use std::time::Duration;
 
 use moka::future::Cache;
 use redis::AsyncCommands;
-use redis::Client as RedisClient;
 use tracing::{debug, warn};
 
+use crate::metrics::{CacheRegion, CacheStage, CacheTier, Metrics};
+use crate::profiling::{self, Phase};
+use crate::redis_manager::RedisManager;
+
 /// Default TTL for L1 cache (60 seconds).
 const L1_TTL_SECS: u64 = 60;
 
@@ -19,6 +30,10 @@ const L2_TTL_SECS: u64 = 300;
 /// Maximum L1 cache capacity.
 const L1_MAX_CAPACITY: u64 = 10_000;
 
+/// Tag invalidated whenever an item is created, updated, reverted or
+/// deleted. Attach it to cached output derived from item listings.
+pub const ITEM_LISTING_TAG: &str = "item_listing";
+
 /// Two-tier cache layer.
 ///
 /// L1 (Moka): In-process, short TTL, per-instance
@@ -26,19 +41,21 @@ const L1_MAX_CAPACITY: u64 = 10_000;
 #[derive(Clone)]
 pub struct CacheLayer {
     inner: Arc<CacheLayerInner>,
+    /// Records hits, misses and invalidations when set.
+    metrics: Option<Arc<Metrics>>,
 }
 
 struct CacheLayerInner {
     /// L1 in-process cache.
     local: Cache<String, String>,
 
-    /// L2 Redis client.
-    redis: RedisClient,
+    /// L2 Redis connection manager.
+    redis: RedisManager,
 }
 
 impl CacheLayer {
     /// Create a new cache layer.
-    pub fn new(redis: RedisClient) -> Self {
+    pub fn new(redis: RedisManager) -> Self {
         let local = Cache::builder()
             .max_capacity(L1_MAX_CAPACITY)
             .time_to_live(Duration::from_secs(L1_TTL_SECS))
@@ -46,6 +63,24 @@ impl CacheLayer {
 
         Self {
             inner: Arc::new(CacheLayerInner { local, redis }),
+            metrics: None,
+        }
+    }
+
+    /// Record cache metrics, labelled by the region and stage of each key.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn record_lookup(&self, key: &str, tier: Option<CacheTier>) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        let (region, stage) = (CacheRegion::of_key(key), CacheStage::of_key(key));
+        match tier {
+            Some(tier) => metrics.record_cache_hit(region, stage, tier),
+            None => metrics.record_cache_miss(region, stage),
         }
     }
 
@@ -53,26 +88,35 @@ impl CacheLayer {
     ///
     /// Checks L1 first, then L2. On L2 hit, populates L1.
     pub async fn get(&self, key: &str) -> Option<String> {
+        let _cache_timer = profiling::Timer::start(Phase::Cache);
+        let key = scoped_key(key);
+        let key = key.as_ref();
+
         // Check L1 first
         if let Some(val) = self.inner.local.get(key).await {
             debug!(key = %key, "cache L1 hit");
+            self.record_lookup(key, Some(CacheTier::L1));
             return Some(val);
         }
 
         // Check L2
-        let mut conn = match self.inner.redis.get_multiplexed_async_connection().await {
+        let mut conn = match self.inner.redis.get().await {
             Ok(c) => c,
             Err(e) => {
                 warn!(error = %e, "failed to get Redis connection for cache");
+                self.record_lookup(key, None);
                 return None;
             }
         };
 
-        let val: Option<String> = conn.get(key).await.ok()?;
+        let val = conn.get::<_, Option<String>>(key).await.ok().flatten();
 
         if let Some(ref v) = val {
             debug!(key = %key, "cache L2 hit, populating L1");
+            self.record_lookup(key, Some(CacheTier::L2));
             self.inner.local.insert(key.to_string(), v.clone()).await;
+        } else {
+            self.record_lookup(key, None);
         }
 
         val
@@ -82,6 +126,9 @@ impl CacheLayer {
     ///
     /// Writes to both L1 and L2.
     pub async fn set(&self, key: &str, value: &str, ttl_secs: u64, tags: &[&str]) {
+        let key = scoped_key(key);
+        let key = key.as_ref();
+
         // Set in L1
         self.inner
             .local
@@ -89,7 +136,7 @@ impl CacheLayer {
             .await;
 
         // Set in L2 with TTL
-        let Ok(mut conn) = self.inner.redis.get_multiplexed_async_connection().await else {
+        let Ok(mut conn) = self.inner.redis.get().await else {
             warn!("failed to get Redis connection for cache set");
             return;
         };
@@ -114,11 +161,14 @@ impl CacheLayer {
 
     /// Invalidate a single cache key.
     pub async fn invalidate(&self, key: &str) {
+        let key = scoped_key(key);
+        let key = key.as_ref();
+
         // Invalidate L1
         self.inner.local.invalidate(key).await;
 
         // Invalidate L2
-        let Ok(mut conn) = self.inner.redis.get_multiplexed_async_connection().await else {
+        let Ok(mut conn) = self.inner.redis.get().await else {
             warn!("failed to get Redis connection for cache invalidate");
             return;
         };
@@ -136,7 +186,7 @@ impl CacheLayer {
     pub async fn invalidate_tag(&self, tag: &str) {
         let tag_key = format!("tag:{tag}");
 
-        let Ok(mut conn) = self.inner.redis.get_multiplexed_async_connection().await else {
+        let Ok(mut conn) = self.inner.redis.get().await else {
             warn!("failed to get Redis connection for tag invalidation");
             return;
         };
@@ -162,9 +212,60 @@ impl CacheLayer {
             return;
         }
 
+        if let Some(metrics) = &self.metrics {
+            metrics.record_cache_invalidation(tag, keys.len());
+        }
         debug!(tag = %tag, keys_invalidated = %keys.len(), "tag invalidated");
     }
 
+    /// Count the keys registered with a tag.
+    ///
+    /// Expired keys stay registered until [`prune_tag`](Self::prune_tag)
+    /// removes them. Returns `None` if Redis is unavailable.
+    pub async fn tag_len(&self, tag: &str) -> Option<u64> {
+        let tag_key = format!("tag:{tag}");
+        let mut conn = self.inner.redis.get().await.ok()?;
+        match conn.scard(&tag_key).await {
+            Ok(len) => Some(len),
+            Err(e) => {
+                warn!(error = %e, tag = %tag, "failed to count tag members");
+                None
+            }
+        }
+    }
+
+    /// Check whether a key is registered with a tag.
+    pub async fn tag_contains(&self, tag: &str, key: &str) -> bool {
+        let tag_key = format!("tag:{tag}");
+        let Ok(mut conn) = self.inner.redis.get().await else {
+            return false;
+        };
+        conn.sismember(&tag_key, &*scoped_key(key))
+            .await
+            .unwrap_or(false)
+    }
+
+    /// Unregister expired keys from a tag.
+    ///
+    /// Returns the number of keys still registered, or `None` if Redis is
+    /// unavailable.
+    pub async fn prune_tag(&self, tag: &str) -> Option<u64> {
+        let tag_key = format!("tag:{tag}");
+        let mut conn = self.inner.redis.get().await.ok()?;
+
+        let script = redis::Script::new(PRUNE_TAG_SCRIPT);
+        match script.key(&tag_key).invoke_async::<u64>(&mut conn).await {
+            Ok(remaining) => {
+                debug!(tag = %tag, remaining = %remaining, "tag pruned");
+                Some(remaining)
+            }
+            Err(e) => {
+                warn!(error = %e, tag = %tag, "failed to prune tag in Redis");
+                None
+            }
+        }
+    }
+
     /// Generate a stage-scoped cache key.
     ///
     /// Live stage uses bare keys for maximum cache hit rates.
@@ -205,14 +306,27 @@ impl CacheLayer {
             return;
         }
 
-        let pattern = format!("st:{stage_id}:*");
-
-        let Ok(mut conn) = self.inner.redis.get_multiplexed_async_connection().await else {
+        let Ok(mut conn) = self.inner.redis.get().await else {
             warn!("failed to get Redis connection for stage invalidation");
             return;
         };
 
-        // Use SCAN to find and delete all matching keys
+        // Use SCAN to find and delete all matching keys, on every site
+        let mut total_deleted = 0usize;
+
+        for pattern in [format!("st:{stage_id}:*"), format!("t:*:st:{stage_id}:*")] {
+            total_deleted += self.delete_matching(&mut conn, &pattern).await;
+        }
+
+        debug!(stage_id = %stage_id, keys_deleted = %total_deleted, "stage cache invalidated");
+    }
+
+    /// Delete all keys matching a `SCAN` pattern from both tiers.
+    async fn delete_matching(
+        &self,
+        conn: &mut crate::redis_manager::ManagedConnection,
+        pattern: &str,
+    ) -> usize {
         let mut cursor = 0u64;
         let mut total_deleted = 0usize;
 
@@ -220,10 +334,10 @@ impl CacheLayer {
             let (next_cursor, keys): (u64, Vec<String>) = match redis::cmd("SCAN")
                 .arg(cursor)
                 .arg("MATCH")
-                .arg(&pattern)
+                .arg(pattern)
                 .arg("COUNT")
                 .arg(100)
-                .query_async(&mut conn)
+                .query_async(conn)
                 .await
             {
                 Ok(result) => result,
@@ -253,7 +367,7 @@ impl CacheLayer {
             }
         }
 
-        debug!(stage_id = %stage_id, keys_deleted = %total_deleted, "stage cache invalidated");
+        total_deleted
     }
 
     /// Get cache statistics (for monitoring).
@@ -275,6 +389,16 @@ pub struct CacheStats {
     pub l1_weighted_size: u64,
 }
 
+/// Prefix a key with the current site's tenant, if any.
+///
+/// Uses the same `t:{tenant_id}:` prefix as [`CacheLayer::tenant_stage_key`].
+fn scoped_key(key: &str) -> Cow<'_, str> {
+    match crate::services::site::scoped_tenant_id() {
+        Some(tenant_id) => Cow::Owned(format!("t:{tenant_id}:{key}")),
+        None => Cow::Borrowed(key),
+    }
+}
+
 /// Lua script for atomic tag invalidation.
 ///
 /// Gets all keys in the tag set, deletes them, then deletes the tag set.
@@ -287,6 +411,20 @@ redis.call("DEL", KEYS[1])
 return #keys
 "#;
 
+/// Lua script for atomic tag pruning.
+///
+/// Removes tag members whose keys no longer exist, then returns the
+/// number of members left.
+const PRUNE_TAG_SCRIPT: &str = r#"
+local keys = redis.call("SMEMBERS", KEYS[1])
+for _, key in ipairs(keys) do
+    if redis.call("EXISTS", key) == 0 then
+        redis.call("SREM", KEYS[1], key)
+    end
+end
+return redis.call("SCARD", KEYS[1])
+"#;
+
 impl std::fmt::Debug for CacheLayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CacheLayer").finish()
@@ -298,6 +436,7 @@ impl std::fmt::Debug for CacheLayer {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::redis_manager::RedisSettings;
 
     #[test]
     fn test_stage_key_live() {
@@ -317,11 +456,33 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_scoped_key_prefixes_site_tenant() {
+        use crate::services::site::{self, SiteScope};
+
+        assert_eq!(scoped_key("page:en:/"), "page:en:/");
+
+        let tenant_id = uuid::Uuid::now_v7();
+        let scope = SiteScope {
+            tenant_id,
+            ..SiteScope::default_site()
+        };
+        site::scope(scope, async {
+            assert_eq!(scoped_key("page:en:/"), format!("t:{tenant_id}:page:en:/"));
+        })
+        .await;
+        site::scope(SiteScope::default_site(), async {
+            assert_eq!(scoped_key("page:en:/"), "page:en:/");
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_cache_layer_creation() {
         // This test requires Redis, so we just verify the struct can be created
         // In a real test environment, we'd use a mock or test Redis instance
-        let client = RedisClient::open("redis://127.0.0.1:6379").unwrap();
+        let client =
+            RedisManager::new(RedisSettings::standalone("redis://127.0.0.1:6379")).unwrap();
         let cache = CacheLayer::new(client);
 
         let stats = cache.stats().await;
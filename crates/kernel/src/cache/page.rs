@@ -0,0 +1,196 @@
+//! Cache tags and cache metadata for rendered pages.
+//!
+//! While the page cache middleware renders a response it opens a collection
+//! scope. Code on the render path (item loads, gather execution) emits tags
+//! into that scope with [`add_tag`]; the middleware stores the collected
+//! tags with the cached page so that saving an item or a gather query
+//! invalidates exactly the pages that displayed it.
+//!
+//! Item views also emit their content type's [`CacheMetadata`] with
+//! [`add_cache_metadata`]. The page takes the shortest lifetimes and the
+//! union of the contexts of everything it displayed.
+//!
+//! Emitting outside a collection scope is a no-op, so services can emit
+//! unconditionally regardless of whether the current request is cacheable.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use trovato_sdk::types::{CacheContext, CacheMetadata};
+use uuid::Uuid;
+
+use super::conditional::Validators;
+
+/// Tag attached to every cached page.
+///
+/// Invalidated when a stage is published, since that can change any page.
+pub const PAGE_TAG: &str = "page";
+
+tokio::task_local! {
+    static RENDER_SCOPE: RefCell<RenderMetadata>;
+}
+
+/// Tags and cache metadata collected while rendering a page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderMetadata {
+    /// Cache tags, sorted and de-duplicated.
+    pub tags: BTreeSet<String>,
+    /// Shortest declared lifetime, in seconds.
+    pub max_age: Option<u64>,
+    /// Shortest declared shared cache lifetime, in seconds.
+    pub shared_max_age: Option<u64>,
+    /// Contexts the page varies by.
+    pub contexts: BTreeSet<CacheContext>,
+}
+
+impl RenderMetadata {
+    /// Whether any displayed content declared cache metadata.
+    pub fn has_cache_metadata(&self) -> bool {
+        self.max_age.is_some() || self.shared_max_age.is_some() || !self.contexts.is_empty()
+    }
+
+    /// Fold one content type's metadata into the page's.
+    fn merge(&mut self, meta: &CacheMetadata) {
+        let shortest = |a: Option<u64>, b: Option<u64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max_age = shortest(self.max_age, meta.max_age);
+        // A shared lifetime defaults to the type's own max-age.
+        self.shared_max_age = shortest(self.shared_max_age, meta.shared_max_age.or(meta.max_age));
+        self.contexts.extend(meta.contexts.iter().copied());
+    }
+}
+
+/// Tag for output that displays an item.
+pub fn item_tag(id: Uuid) -> String {
+    format!("item:{id}")
+}
+
+/// Tag for output that displays the results of a gather query.
+pub fn gather_tag(query_id: &str) -> String {
+    format!("gather:{query_id}")
+}
+
+/// Record a cache tag for the page currently being rendered.
+pub fn add_tag(tag: impl Into<String>) {
+    let tag = tag.into();
+    // Err means no collection scope is active.
+    let _ = RENDER_SCOPE.try_with(|scope| {
+        scope.borrow_mut().tags.insert(tag);
+    });
+}
+
+/// Record a content type's cache metadata for the page currently being
+/// rendered.
+pub fn add_cache_metadata(meta: &CacheMetadata) {
+    let _ = RENDER_SCOPE.try_with(|scope| scope.borrow_mut().merge(meta));
+}
+
+/// Run a future inside a collection scope, returning its output and the
+/// tags and cache metadata emitted while it ran.
+pub async fn collect_render_metadata<F: Future>(fut: F) -> (F::Output, RenderMetadata) {
+    RENDER_SCOPE
+        .scope(RefCell::new(RenderMetadata::default()), async {
+            let output = fut.await;
+            let metadata = RENDER_SCOPE.with(|scope| scope.take());
+            (output, metadata)
+        })
+        .await
+}
+
+/// A cached page response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPage {
+    /// `Content-Type` header of the original response.
+    pub content_type: String,
+    /// Response body.
+    pub body: String,
+    /// `Cache-Control` header sent with the page, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<String>,
+    /// `Surrogate-Control` header sent with the page, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surrogate_control: Option<String>,
+    /// `Vary` header sent with the page, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vary: Option<String>,
+    /// `ETag` sent with the page; absent on pages cached before ETags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// When the page was rendered, sent as `Last-Modified`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<i64>,
+}
+
+impl CachedPage {
+    /// Validators answering conditional requests for the page.
+    pub fn validators(&self) -> Option<Validators> {
+        Some(Validators {
+            etag: self.etag.clone()?,
+            last_modified: self.last_modified,
+        })
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn collects_tags_emitted_in_scope() {
+        let id = Uuid::nil();
+        let ((), metadata) = collect_render_metadata(async {
+            add_tag(gather_tag("front_page"));
+            add_tag(item_tag(id));
+            add_tag(item_tag(id));
+        })
+        .await;
+
+        assert_eq!(
+            metadata.tags.into_iter().collect::<Vec<_>>(),
+            vec!["gather:front_page".to_string(), format!("item:{id}")]
+        );
+    }
+
+    #[tokio::test]
+    async fn add_tag_outside_scope_is_noop() {
+        add_tag("item:orphan");
+        add_cache_metadata(&CacheMetadata {
+            max_age: Some(10),
+            ..Default::default()
+        });
+        let ((), metadata) = collect_render_metadata(async {}).await;
+        assert!(metadata.tags.is_empty());
+        assert!(!metadata.has_cache_metadata());
+    }
+
+    #[tokio::test]
+    async fn cache_metadata_takes_shortest_lifetimes_and_all_contexts() {
+        let ((), metadata) = collect_render_metadata(async {
+            add_cache_metadata(&CacheMetadata {
+                max_age: Some(600),
+                shared_max_age: Some(3600),
+                contexts: vec![CacheContext::Language],
+            });
+            add_cache_metadata(&CacheMetadata {
+                max_age: Some(60),
+                shared_max_age: None,
+                contexts: vec![CacheContext::Role],
+            });
+        })
+        .await;
+
+        assert_eq!(metadata.max_age, Some(60));
+        // The second type's shared lifetime defaults to its max-age.
+        assert_eq!(metadata.shared_max_age, Some(60));
+        assert_eq!(
+            metadata.contexts.into_iter().collect::<Vec<_>>(),
+            vec![CacheContext::Language, CacheContext::Role]
+        );
+    }
+}
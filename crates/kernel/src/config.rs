@@ -6,6 +6,8 @@ use std::time::Duration;
 
 use anyhow::{Context, Result};
 
+use crate::lockout::IpNetwork;
+
 /// Default cache TTL in seconds (1 minute).
 const DEFAULT_CACHE_TTL: u64 = 60;
 
@@ -27,6 +29,13 @@ pub struct CacheConfig {
     pub ttl_items: Duration,
     /// Category cache entry TTL.
     pub ttl_categories: Duration,
+    /// Config entity cache entry TTL.
+    pub ttl_config: Duration,
+    /// Anonymous page cache entry TTL (zero disables the page cache).
+    pub ttl_pages: Duration,
+    /// Reference hops followed when invalidating cached pages of items that
+    /// reference a changed item (zero disables).
+    pub reference_depth: u32,
 }
 
 impl CacheConfig {
@@ -51,6 +60,13 @@ impl CacheConfig {
             ttl_categories: Duration::from_secs(
                 Self::parse_env_u64("CACHE_TTL_CATEGORIES").unwrap_or(300),
             ),
+            ttl_config: Duration::from_secs(
+                Self::parse_env_u64("CACHE_TTL_CONFIG").unwrap_or(global),
+            ),
+            ttl_pages: Duration::from_secs(Self::parse_env_u64("CACHE_TTL_PAGES").unwrap_or(300)),
+            reference_depth: Self::parse_env_u64("CACHE_REFERENCE_DEPTH")
+                .and_then(|d| u32::try_from(d).ok())
+                .unwrap_or(crate::content::references::DEFAULT_REFERENCE_DEPTH),
         }
     }
 
@@ -72,30 +88,94 @@ pub struct Config {
     /// Redis connection URL.
     pub redis_url: String,
 
+    /// Redis topology: "standalone" (default), "sentinel", or "cluster".
+    pub redis_topology: String,
+
+    /// Redis node URLs (comma-separated `REDIS_NODES`): sentinels or cluster
+    /// seed nodes. Defaults to `redis_url` when empty.
+    pub redis_nodes: Vec<String>,
+
+    /// Master name monitored by the sentinels (required for "sentinel").
+    pub redis_sentinel_master: Option<String>,
+
     /// Maximum database connections in pool (default: 10).
     pub database_max_connections: u32,
 
+    /// Read replica URLs (comma-separated `DATABASE_REPLICA_URLS`).
+    ///
+    /// Read-only paths are spread over healthy replicas; empty means every
+    /// query goes to `database_url`.
+    pub database_replica_urls: Vec<String>,
+
     /// Path to plugins directory (default: ./plugins).
     pub plugins_dir: PathBuf,
 
+    /// Directory for compiled plugin modules (default: ./cache/plugins).
+    ///
+    /// Set via `PLUGIN_CACHE_DIR`; an empty value disables the cache so
+    /// every start recompiles all plugins.
+    pub plugin_cache_dir: Option<PathBuf>,
+
     /// Path to uploads directory (default: ./uploads).
     pub uploads_dir: PathBuf,
 
+    /// Directory for backup archives written from the admin API
+    /// (default: ./backups).
+    pub backup_dir: PathBuf,
+
     /// Base URL for serving uploaded files (default: /files).
     pub files_url: String,
 
+    /// File storage backend: "local" (default) or "s3".
+    ///
+    /// Set via `FILE_STORAGE`. S3 storage needs the `s3` feature and
+    /// `S3_BUCKET`.
+    pub file_storage: String,
+
+    /// S3 bucket for file storage (`S3_BUCKET`).
+    pub s3_bucket: Option<String>,
+
+    /// Key prefix within the S3 bucket (`S3_PREFIX`).
+    pub s3_prefix: Option<String>,
+
+    /// Endpoint for S3-compatible services such as MinIO (`S3_ENDPOINT_URL`).
+    pub s3_endpoint_url: Option<String>,
+
+    /// Public base URL of the bucket or its CDN (`S3_PUBLIC_URL`).
+    ///
+    /// When unset, file URLs point at `files_url`, which redirects to
+    /// short-lived presigned download URLs (for private buckets).
+    pub s3_public_url: Option<String>,
+
     /// CORS allowed origins (comma-separated, default: "*").
     pub cors_allowed_origins: Vec<String>,
 
     /// Cookie SameSite policy: "strict", "lax", or "none" (default: "strict").
     pub cookie_same_site: String,
 
+    /// Session store: "redis" (default) or "postgres".
+    pub session_store: String,
+
+    /// Session lifetime in hours (default: 24).
+    pub session_ttl_hours: i64,
+
+    /// Lifetime in days of sessions created with "remember me" (default: 30).
+    pub session_remember_me_days: i64,
+
+    /// Whether activity extends a session (default: true). When false,
+    /// sessions expire a fixed time after login.
+    pub session_sliding: bool,
+
     /// Plugin names to force-disable on first install (from DISABLED_PLUGINS env var).
     pub disabled_plugins: Vec<String>,
 
     /// SMTP host for email delivery. When None, email is disabled.
     pub smtp_host: Option<String>,
 
+    /// Mail transport: "smtp" (default when `smtp_host` is set) or "log"
+    /// (development: messages are logged instead of sent).
+    pub mail_transport: Option<String>,
+
     /// SMTP port (default: 587).
     pub smtp_port: u16,
 
@@ -128,6 +208,39 @@ pub struct Config {
     /// Default: `url_prefix,accept_header`.
     /// Single-language sites skip negotiation entirely regardless of this setting.
     pub language_negotiation_methods: Vec<String>,
+
+    /// Maximum random delay in seconds before a cron run acquires its lock
+    /// (default: 0, no jitter).
+    ///
+    /// Set via `CRON_JITTER_SECS` to spread simultaneous triggers across
+    /// multiple instances.
+    pub cron_jitter_secs: u64,
+
+    /// Force read-only mode for this process (default: false).
+    ///
+    /// Set via `READ_ONLY`. When on, mutating requests are rejected even if
+    /// the runtime `read_only` setting is off.
+    pub read_only: bool,
+
+    /// Announce deprecated API usage in response headers (default: false).
+    ///
+    /// Set via `DEPRECATION_HEADERS`. When on, responses to deprecated
+    /// endpoints and parameters carry `Deprecation` and `Sunset` headers.
+    pub deprecation_headers: bool,
+
+    /// How requests are matched to a tenant (default: `"default"`).
+    ///
+    /// Set via `TENANT_RESOLUTION_METHOD`: `default` serves everything as
+    /// the default tenant, `host` looks the `Host` header up in the `site`
+    /// table (multisite), `header` reads an `X-Tenant-ID` UUID.
+    pub tenant_resolution_method: String,
+
+    /// Reverse proxies whose forwarding headers are trusted (default: none).
+    ///
+    /// Set via `TRUSTED_PROXIES` as comma-separated addresses or CIDR ranges.
+    /// `X-Forwarded-For` and `X-Real-IP` are ignored on connections from
+    /// anywhere else, so clients cannot pick their own address.
+    pub trusted_proxies: Vec<IpNetwork>,
 }
 
 impl Config {
@@ -144,21 +257,62 @@ impl Config {
         let redis_url =
             env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
 
+        let redis_topology =
+            env::var("REDIS_TOPOLOGY").unwrap_or_else(|_| "standalone".to_string());
+
+        let redis_nodes = env::var("REDIS_NODES")
+            .map(|v| {
+                v.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let redis_sentinel_master = env::var("REDIS_SENTINEL_MASTER").ok();
+
         let database_max_connections = env::var("DATABASE_MAX_CONNECTIONS")
             .unwrap_or_else(|_| "10".to_string())
             .parse()
             .context("DATABASE_MAX_CONNECTIONS must be a valid u32")?;
 
+        let database_replica_urls = env::var("DATABASE_REPLICA_URLS")
+            .map(|v| {
+                v.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let plugins_dir = env::var("PLUGINS_DIR")
             .map(PathBuf::from)
             .unwrap_or_else(|_| PathBuf::from("./plugins"));
 
+        let plugin_cache_dir = match env::var("PLUGIN_CACHE_DIR") {
+            Ok(v) if v.trim().is_empty() => None,
+            Ok(v) => Some(PathBuf::from(v)),
+            Err(_) => Some(PathBuf::from("./cache/plugins")),
+        };
+
         let uploads_dir = env::var("UPLOADS_DIR")
             .map(PathBuf::from)
             .unwrap_or_else(|_| PathBuf::from("./uploads"));
 
+        let backup_dir = env::var("BACKUP_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./backups"));
+
         let files_url = env::var("FILES_URL").unwrap_or_else(|_| "/files".to_string());
 
+        let file_storage = env::var("FILE_STORAGE")
+            .map(|v| v.trim().to_lowercase())
+            .unwrap_or_else(|_| "local".to_string());
+        let s3_bucket = env::var("S3_BUCKET").ok().filter(|v| !v.is_empty());
+        let s3_prefix = env::var("S3_PREFIX").ok().filter(|v| !v.is_empty());
+        let s3_endpoint_url = env::var("S3_ENDPOINT_URL").ok().filter(|v| !v.is_empty());
+        let s3_public_url = env::var("S3_PUBLIC_URL").ok().filter(|v| !v.is_empty());
+
         let cors_allowed_origins = env::var("CORS_ALLOWED_ORIGINS")
             .map(|v| v.split(',').map(|s| s.trim().to_string()).collect())
             .unwrap_or_else(|_| Vec::new());
@@ -167,6 +321,24 @@ impl Config {
             .unwrap_or_else(|_| "strict".to_string())
             .to_lowercase();
 
+        let session_store = env::var("SESSION_STORE")
+            .unwrap_or_else(|_| "redis".to_string())
+            .to_lowercase();
+
+        let session_ttl_hours = env::var("SESSION_TTL_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .context("SESSION_TTL_HOURS must be a valid integer")?;
+
+        let session_remember_me_days = env::var("SESSION_REMEMBER_ME_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .context("SESSION_REMEMBER_ME_DAYS must be a valid integer")?;
+
+        let session_sliding = env::var("SESSION_SLIDING_EXPIRATION")
+            .map(|v| !matches!(v.to_lowercase().as_str(), "false" | "0" | "no"))
+            .unwrap_or(true);
+
         let disabled_plugins = env::var("DISABLED_PLUGINS")
             .map(|v| {
                 v.split(',')
@@ -178,6 +350,8 @@ impl Config {
 
         let smtp_host = env::var("SMTP_HOST").ok();
 
+        let mail_transport = env::var("MAIL_TRANSPORT").ok().map(|v| v.to_lowercase());
+
         let smtp_port = env::var("SMTP_PORT")
             .unwrap_or_else(|_| "587".to_string())
             .parse()
@@ -209,18 +383,64 @@ impl Config {
             })
             .unwrap_or_else(|_| vec!["url_prefix".to_string(), "accept_header".to_string()]);
 
+        let cron_jitter_secs = env::var("CRON_JITTER_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+
+        let read_only = env::var("READ_ONLY")
+            .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"))
+            .unwrap_or(false);
+
+        let deprecation_headers = env::var("DEPRECATION_HEADERS")
+            .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"))
+            .unwrap_or(false);
+
+        let tenant_resolution_method = env::var("TENANT_RESOLUTION_METHOD")
+            .map(|v| v.trim().to_lowercase())
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "default".to_string());
+
+        let trusted_proxies = env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<IpNetwork>()
+                    .with_context(|| format!("TRUSTED_PROXIES entry '{s}' is not an IP or CIDR"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(Self {
             port,
             database_url,
             redis_url,
+            redis_topology,
+            redis_nodes,
+            redis_sentinel_master,
             database_max_connections,
+            database_replica_urls,
             plugins_dir,
+            plugin_cache_dir,
             uploads_dir,
+            backup_dir,
             files_url,
+            file_storage,
+            s3_bucket,
+            s3_prefix,
+            s3_endpoint_url,
+            s3_public_url,
             cors_allowed_origins,
             cookie_same_site,
+            session_store,
+            session_ttl_hours,
+            session_remember_me_days,
+            session_sliding,
             disabled_plugins,
             smtp_host,
+            mail_transport,
             smtp_port,
             smtp_username,
             smtp_password,
@@ -229,6 +449,11 @@ impl Config {
             site_url,
             gather_max_page_size,
             language_negotiation_methods,
+            cron_jitter_secs,
+            read_only,
+            deprecation_headers,
+            tenant_resolution_method,
+            trusted_proxies,
         })
     }
 }
@@ -0,0 +1,368 @@
+//! In-memory caching decorator for ConfigStorage.
+//!
+//! Keeps config entities in process memory so request paths don't query
+//! the database for the same item types, categories and variables again
+//! and again:
+//!
+//! - **Load / Load many**: Served from memory; misses are loaded from the
+//!   inner storage (in one bulk load) and cached
+//! - **List**: Unfiltered lists are cached per entity type
+//! - **Save / Delete**: Pass through, then drop the entity and the cached
+//!   lists of its type
+//!
+//! Entries also expire after a TTL, so changes made by another instance or
+//! outside ConfigStorage show up without a restart. Content items (`item`)
+//! are not config and always pass through.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use moka::sync::Cache;
+use tracing::warn;
+use uuid::Uuid;
+
+use super::{
+    ConfigEntity, ConfigFilter, ConfigStorage, ConfigViolation, entity_types, in_id_order,
+};
+use crate::metrics::{CacheRegion, CacheStage, CacheTier, Metrics};
+use crate::services::site::current_tenant_id;
+
+/// Maximum cached entities.
+const MAX_ENTITIES: u64 = 10_000;
+
+/// Maximum cached lists (one per site and entity type).
+const MAX_LISTS: u64 = 1_000;
+
+/// Cache key for an entity: site tenant, entity type, entity ID.
+type EntityKey = (Uuid, String, String);
+
+/// Cache key for an unfiltered list: site tenant, entity type.
+type ListKey = (Uuid, String);
+
+/// Config storage decorator that caches reads in memory.
+#[derive(Clone)]
+pub struct CachedConfigStorage {
+    /// The storage entities are read from and written to.
+    inner: Arc<dyn ConfigStorage>,
+    entities: Cache<EntityKey, ConfigEntity>,
+    lists: Cache<ListKey, Arc<Vec<ConfigEntity>>>,
+    /// Records hits and misses when set.
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl CachedConfigStorage {
+    /// Create a new cached config storage.
+    ///
+    /// # Arguments
+    /// * `inner` - The storage to cache
+    /// * `ttl` - How long an entry is served before it is reloaded
+    pub fn new(inner: Arc<dyn ConfigStorage>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            entities: Cache::builder()
+                .max_capacity(MAX_ENTITIES)
+                .time_to_live(ttl)
+                .support_invalidation_closures()
+                .build(),
+            lists: Cache::builder()
+                .max_capacity(MAX_LISTS)
+                .time_to_live(ttl)
+                .support_invalidation_closures()
+                .build(),
+            metrics: None,
+        }
+    }
+
+    /// Record config cache metrics.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Record `hits` and `misses` cache lookups.
+    ///
+    /// The wrapped storage reads the live stage.
+    fn record_lookups(&self, hits: usize, misses: usize) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        for _ in 0..hits {
+            metrics.record_cache_hit(CacheRegion::Config, CacheStage::Live, CacheTier::L1);
+        }
+        for _ in 0..misses {
+            metrics.record_cache_miss(CacheRegion::Config, CacheStage::Live);
+        }
+    }
+
+    /// Drop every cached entity and list.
+    ///
+    /// For writes that bypass ConfigStorage, such as a config import.
+    pub fn clear(&self) {
+        self.entities.invalidate_all();
+        self.lists.invalidate_all();
+    }
+
+    /// Drop an entity and the lists of its type.
+    ///
+    /// Every site is invalidated: sites fall back to default-tenant
+    /// variables, so one change can show through in all of them.
+    fn invalidate(&self, entity_type: &str, id: &str) {
+        let (et, eid) = (entity_type.to_string(), id.to_string());
+        let entities = self
+            .entities
+            .invalidate_entries_if(move |(_, t, i), _| *t == et && *i == eid);
+        let et = entity_type.to_string();
+        let lists = self.lists.invalidate_entries_if(move |(_, t), _| *t == et);
+        if let Err(e) = entities.and(lists) {
+            // Invalidation closures are enabled in `new`, so this can't
+            // happen; fall back to dropping everything.
+            warn!(error = %e, "config cache invalidation failed; clearing");
+            self.clear();
+        }
+    }
+}
+
+/// Whether entities of a type are cached.
+fn cacheable(entity_type: &str) -> bool {
+    entity_type != entity_types::ITEM
+}
+
+#[async_trait]
+impl ConfigStorage for CachedConfigStorage {
+    async fn load(&self, entity_type: &str, id: &str) -> Result<Option<ConfigEntity>> {
+        if !cacheable(entity_type) {
+            return self.inner.load(entity_type, id).await;
+        }
+
+        let key = (current_tenant_id(), entity_type.to_string(), id.to_string());
+        if let Some(entity) = self.entities.get(&key) {
+            self.record_lookups(1, 0);
+            return Ok(Some(entity));
+        }
+        self.record_lookups(0, 1);
+
+        let entity = self.inner.load(entity_type, id).await?;
+        if let Some(ref e) = entity {
+            self.entities.insert(key, e.clone());
+        }
+        Ok(entity)
+    }
+
+    async fn save(&self, entity: &ConfigEntity) -> Result<()> {
+        // Invalidate even on failure: the write may have partly landed.
+        let result = self.inner.save(entity).await;
+        self.invalidate(entity.entity_type(), &entity.id());
+        result
+    }
+
+    async fn delete(&self, entity_type: &str, id: &str) -> Result<bool> {
+        let result = self.inner.delete(entity_type, id).await;
+        self.invalidate(entity_type, id);
+        result
+    }
+
+    async fn list(
+        &self,
+        entity_type: &str,
+        filter: Option<&ConfigFilter>,
+    ) -> Result<Vec<ConfigEntity>> {
+        if filter.is_some() || !cacheable(entity_type) {
+            return self.inner.list(entity_type, filter).await;
+        }
+
+        let key = (current_tenant_id(), entity_type.to_string());
+        if let Some(entities) = self.lists.get(&key) {
+            self.record_lookups(1, 0);
+            return Ok(entities.as_ref().clone());
+        }
+        self.record_lookups(0, 1);
+
+        let entities = self.inner.list(entity_type, None).await?;
+        self.lists.insert(key, Arc::new(entities.clone()));
+        Ok(entities)
+    }
+
+    async fn load_many(&self, entity_type: &str, ids: &[String]) -> Result<Vec<ConfigEntity>> {
+        if !cacheable(entity_type) {
+            return self.inner.load_many(entity_type, ids).await;
+        }
+
+        let tenant = current_tenant_id();
+        let mut entities = Vec::with_capacity(ids.len());
+        let mut missing = Vec::new();
+        for id in ids {
+            match self
+                .entities
+                .get(&(tenant, entity_type.to_string(), id.clone()))
+            {
+                Some(entity) => entities.push(entity),
+                None => missing.push(id.clone()),
+            }
+        }
+        self.record_lookups(entities.len(), missing.len());
+
+        if !missing.is_empty() {
+            for entity in self.inner.load_many(entity_type, &missing).await? {
+                self.entities.insert(
+                    (tenant, entity_type.to_string(), entity.id()),
+                    entity.clone(),
+                );
+                entities.push(entity);
+            }
+        }
+        Ok(in_id_order(entities, ids))
+    }
+
+    async fn validate(&self, entity: &ConfigEntity) -> Result<Vec<ConfigViolation>> {
+        self.inner.validate(entity).await
+    }
+}
+
+impl std::fmt::Debug for CachedConfigStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedConfigStorage")
+            .field("entities", &self.entities.entry_count())
+            .field("lists", &self.lists.entry_count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    /// Variable storage that counts reads.
+    #[derive(Default)]
+    struct CountingStorage {
+        variables: Mutex<BTreeMap<String, serde_json::Value>>,
+        reads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ConfigStorage for CountingStorage {
+        async fn load(&self, _entity_type: &str, id: &str) -> Result<Option<ConfigEntity>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .variables
+                .lock()
+                .get(id)
+                .map(|value| variable(id, value.clone())))
+        }
+
+        async fn save(&self, entity: &ConfigEntity) -> Result<()> {
+            let (key, value) = entity.as_variable().unwrap();
+            self.variables.lock().insert(key.to_string(), value.clone());
+            Ok(())
+        }
+
+        async fn delete(&self, _entity_type: &str, id: &str) -> Result<bool> {
+            Ok(self.variables.lock().remove(id).is_some())
+        }
+
+        async fn list(
+            &self,
+            _entity_type: &str,
+            _filter: Option<&ConfigFilter>,
+        ) -> Result<Vec<ConfigEntity>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .variables
+                .lock()
+                .iter()
+                .map(|(k, v)| variable(k, v.clone()))
+                .collect())
+        }
+    }
+
+    fn variable(key: &str, value: serde_json::Value) -> ConfigEntity {
+        ConfigEntity::Variable {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    fn cached() -> (Arc<CountingStorage>, CachedConfigStorage) {
+        let inner = Arc::new(CountingStorage::default());
+        let storage = CachedConfigStorage::new(inner.clone(), Duration::from_secs(60));
+        (inner, storage)
+    }
+
+    #[tokio::test]
+    async fn loads_are_cached_until_saved() {
+        let (inner, storage) = cached();
+        storage
+            .save(&variable("site_name", serde_json::json!("One")))
+            .await
+            .unwrap();
+
+        storage.load("variable", "site_name").await.unwrap();
+        storage.load("variable", "site_name").await.unwrap();
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 1);
+
+        storage
+            .save(&variable("site_name", serde_json::json!("Two")))
+            .await
+            .unwrap();
+        let loaded = storage.load("variable", "site_name").await.unwrap();
+        assert_eq!(loaded.unwrap().as_variable().unwrap().1, "Two");
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn load_many_only_fetches_uncached_ids() {
+        let (inner, storage) = cached();
+        for key in ["a", "b"] {
+            storage
+                .save(&variable(key, serde_json::json!(key)))
+                .await
+                .unwrap();
+        }
+        storage.load("variable", "a").await.unwrap();
+
+        let ids = vec!["b".to_string(), "missing".to_string(), "a".to_string()];
+        let loaded = storage.load_many("variable", &ids).await.unwrap();
+
+        let got: Vec<String> = loaded.iter().map(ConfigEntity::id).collect();
+        assert_eq!(got, vec!["b", "a"]);
+        // "a" came from the cache; "b" and "missing" were loaded.
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn unfiltered_lists_are_cached_until_delete() {
+        let (inner, storage) = cached();
+        storage
+            .save(&variable("a", serde_json::json!(1)))
+            .await
+            .unwrap();
+
+        assert_eq!(storage.list("variable", None).await.unwrap().len(), 1);
+        assert_eq!(storage.list("variable", None).await.unwrap().len(), 1);
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 1);
+
+        let filter = ConfigFilter::new().with_limit(1);
+        storage.list("variable", Some(&filter)).await.unwrap();
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 2);
+
+        assert!(storage.delete("variable", "a").await.unwrap());
+        assert!(storage.list("variable", None).await.unwrap().is_empty());
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn items_are_not_cached() {
+        let (inner, storage) = cached();
+        storage.load("item", "x").await.unwrap();
+        storage.load("item", "x").await.unwrap();
+        assert_eq!(inner.reads.load(Ordering::SeqCst), 2);
+    }
+}
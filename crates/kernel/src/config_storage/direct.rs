@@ -3,6 +3,8 @@
 //! This is the v1.0 implementation - no stage awareness, just a clean interface.
 //! Post-MVP, a `StageAwareConfigStorage` decorator can wrap this to add stage context.
 
+use std::sync::Arc;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
@@ -10,15 +12,17 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::{
-    ConfigEntity, ConfigFilter, ConfigItem, ConfigStorage, SearchFieldConfig, entity_types,
-    parse_tag_id,
+    ConfigEntity, ConfigFilter, ConfigItem, ConfigStorage, ConfigValidationFailed, ConfigViolation,
+    SearchFieldConfig, entity_types, in_id_order, parse_tag_id, validate_entity,
 };
+use crate::db::DbPools;
 use crate::gather::types::{GatherQuery, QueryDefinition, QueryDisplay};
 use crate::models::stage::LIVE_STAGE_ID;
 use crate::models::{
-    Category, CreateCategory, CreateLanguage, ItemType, Language, Tag, UpdateCategory, UpdateTag,
-    UrlAlias,
+    AliasPattern, Category, CreateCategory, CreateLanguage, ItemType, Language, PasswordPolicy,
+    Tag, UpdateCategory, UpdateTag, UrlAlias, Workflow,
 };
+use crate::tap::TapDispatcher;
 
 /// Direct database implementation of ConfigStorage.
 ///
@@ -27,12 +31,99 @@ use crate::models::{
 #[derive(Clone)]
 pub struct DirectConfigStorage {
     pool: PgPool,
+    /// Read replicas for `list`; `None` lists from `pool`.
+    read_pools: Option<DbPools>,
+    /// Dispatcher for `tap_config_validate`; `None` skips plugin validation.
+    validator: Option<Arc<TapDispatcher>>,
 }
 
 impl DirectConfigStorage {
     /// Create a new DirectConfigStorage with a database connection pool.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            read_pools: None,
+            validator: None,
+        }
+    }
+
+    /// List entities from read replicas when available.
+    ///
+    /// Loads and writes stay on the primary so a save is immediately
+    /// visible to the code that made it.
+    pub fn with_read_pools(mut self, read_pools: DbPools) -> Self {
+        self.read_pools = Some(read_pools);
+        self
+    }
+
+    /// Let plugins veto saves through `tap_config_validate`.
+    pub fn with_validation(mut self, dispatcher: Arc<TapDispatcher>) -> Self {
+        self.validator = Some(dispatcher);
+        self
+    }
+
+    async fn list_entities(
+        &self,
+        entity_type: &str,
+        filter: Option<&ConfigFilter>,
+    ) -> Result<Vec<ConfigEntity>> {
+        match entity_type {
+            entity_types::ITEM_TYPE => self.list_item_types(filter).await,
+            entity_types::SEARCH_FIELD_CONFIG => self.list_search_field_configs(filter).await,
+            entity_types::CATEGORY => self.list_categories(filter).await,
+            entity_types::TAG => self.list_tags(filter).await,
+            entity_types::VARIABLE => self.list_variables(filter).await,
+            entity_types::LANGUAGE => self.list_languages(filter).await,
+            entity_types::GATHER_QUERY => self.list_gather_queries(filter).await,
+            entity_types::URL_ALIAS => self.list_url_aliases(filter).await,
+            entity_types::ITEM => self.list_items(filter).await,
+            entity_types::ROLE => self.list_roles(filter).await,
+            entity_types::STAGE => self.list_stages(filter).await,
+            entity_types::TILE => self.list_tiles(filter).await,
+            entity_types::MENU_LINK => self.list_menu_links(filter).await,
+            entity_types::WORKFLOW => self.list_workflows(filter).await,
+            entity_types::ALIAS_PATTERN => self.list_alias_patterns(filter).await,
+            entity_types::PASSWORD_POLICY => self.list_password_policies(filter).await,
+            _ => Err(anyhow::anyhow!("unknown entity type: {entity_type}")),
+        }
+    }
+
+    async fn load_entities(&self, entity_type: &str, ids: &[String]) -> Result<Vec<ConfigEntity>> {
+        let entities = match entity_type {
+            entity_types::ITEM_TYPE => ItemType::find_many(&self.pool, ids)
+                .await?
+                .into_iter()
+                .map(ConfigEntity::ItemType)
+                .collect(),
+            entity_types::CATEGORY => Category::find_many(&self.pool, ids)
+                .await?
+                .into_iter()
+                .map(ConfigEntity::Category)
+                .collect(),
+            entity_types::TAG => {
+                let uuids = ids
+                    .iter()
+                    .map(|id| parse_tag_id(id))
+                    .collect::<Result<Vec<_>>>()?;
+                Tag::find_many(&self.pool, &uuids)
+                    .await?
+                    .into_iter()
+                    .map(ConfigEntity::Tag)
+                    .collect()
+            }
+            entity_types::VARIABLE => self.load_variables(ids).await?,
+            _ => {
+                // Rarely loaded in bulk; one query per ID is fine.
+                let mut entities = Vec::with_capacity(ids.len());
+                for id in ids {
+                    if let Some(entity) = self.load(entity_type, id).await? {
+                        entities.push(entity);
+                    }
+                }
+                entities
+            }
+        };
+        Ok(in_id_order(entities, ids))
     }
 
     // ---- ItemType helpers ----
@@ -372,13 +463,10 @@ impl DirectConfigStorage {
     // ---- Variable helpers ----
 
     async fn load_variable(&self, key: &str) -> Result<Option<ConfigEntity>> {
-        let value = sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT value FROM site_config WHERE key = $1",
-        )
-        .bind(key)
-        .fetch_optional(&self.pool)
-        .await
-        .context("failed to fetch variable")?;
+        // Variables belong to the current site (see `crate::services::site`).
+        let value = crate::models::SiteConfig::get(&self.pool, key)
+            .await
+            .context("failed to fetch variable")?;
 
         Ok(value.map(|v| ConfigEntity::Variable {
             key: key.to_string(),
@@ -387,25 +475,15 @@ impl DirectConfigStorage {
     }
 
     async fn save_variable(&self, key: &str, value: &serde_json::Value) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO site_config (key, value, updated)
-            VALUES ($1, $2, NOW())
-            ON CONFLICT (key) DO UPDATE SET value = $2, updated = NOW()
-            "#,
-        )
-        .bind(key)
-        .bind(value)
-        .execute(&self.pool)
-        .await
-        .context("failed to save variable")?;
-
-        Ok(())
+        crate::models::SiteConfig::set(&self.pool, key, value.clone())
+            .await
+            .context("failed to save variable")
     }
 
     async fn delete_variable(&self, key: &str) -> Result<bool> {
-        let result = sqlx::query("DELETE FROM site_config WHERE key = $1")
+        let result = sqlx::query("DELETE FROM site_config WHERE key = $1 AND tenant_id = $2")
             .bind(key)
+            .bind(crate::services::site::current_tenant_id())
             .execute(&self.pool)
             .await
             .context("failed to delete variable")?;
@@ -663,12 +741,43 @@ impl DirectConfigStorage {
         Ok(entities)
     }
 
+    async fn load_variables(&self, keys: &[String]) -> Result<Vec<ConfigEntity>> {
+        let rows = sqlx::query_as::<_, VariableRow>(
+            r#"
+            SELECT DISTINCT ON (key) key, value FROM site_config
+            WHERE (tenant_id = $1 OR tenant_id = $2) AND key = ANY($3)
+            ORDER BY key, (tenant_id = $1) DESC
+            "#,
+        )
+        .bind(crate::services::site::current_tenant_id())
+        .bind(crate::models::tenant::DEFAULT_TENANT_ID)
+        .bind(keys)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to fetch variables")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| ConfigEntity::Variable {
+                key: r.key,
+                value: r.value,
+            })
+            .collect())
+    }
+
     async fn list_variables(&self, filter: Option<&ConfigFilter>) -> Result<Vec<ConfigEntity>> {
-        let rows =
-            sqlx::query_as::<_, VariableRow>("SELECT key, value FROM site_config ORDER BY key")
-                .fetch_all(&self.pool)
-                .await
-                .context("failed to list variables")?;
+        let rows = sqlx::query_as::<_, VariableRow>(
+            r#"
+            SELECT DISTINCT ON (key) key, value FROM site_config
+            WHERE tenant_id = $1 OR tenant_id = $2
+            ORDER BY key, (tenant_id = $1) DESC
+            "#,
+        )
+        .bind(crate::services::site::current_tenant_id())
+        .bind(crate::models::tenant::DEFAULT_TENANT_ID)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list variables")?;
 
         let mut entities: Vec<ConfigEntity> = rows
             .into_iter()
@@ -699,7 +808,7 @@ impl DirectConfigStorage {
 
         let row = sqlx::query_as::<_, crate::models::Item>(
             "SELECT id, current_revision_id, type, title, author_id, status, created, changed, \
-             promote, sticky, fields, stage_id, language, item_group_id, retention_days \
+             promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id \
              FROM item WHERE id = $1",
         )
         .bind(uuid)
@@ -782,7 +891,7 @@ impl DirectConfigStorage {
         let rows: Vec<crate::models::Item> = if let Some(item_type) = item_type_filter {
             sqlx::query_as(
                 "SELECT id, current_revision_id, type, title, author_id, status, created, changed, \
-                 promote, sticky, fields, stage_id, language, item_group_id, retention_days \
+                 promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id \
                  FROM item WHERE type = $1 ORDER BY created",
             )
             .bind(item_type)
@@ -792,7 +901,7 @@ impl DirectConfigStorage {
         } else {
             sqlx::query_as(
                 "SELECT id, current_revision_id, type, title, author_id, status, created, changed, \
-                 promote, sticky, fields, stage_id, language, item_group_id, retention_days \
+                 promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id \
                  FROM item ORDER BY created",
             )
             .fetch_all(&self.pool)
@@ -1031,6 +1140,81 @@ impl DirectConfigStorage {
                 .context("failed to list menu links")?;
         Ok(rows.into_iter().map(ConfigEntity::MenuLink).collect())
     }
+
+    // ---- Workflow helpers ----
+
+    async fn load_workflow(&self, id: &str) -> Result<Option<ConfigEntity>> {
+        let workflow = Workflow::find_by_id(&self.pool, id).await?;
+        Ok(workflow.map(ConfigEntity::Workflow))
+    }
+
+    async fn save_workflow(&self, workflow: &Workflow) -> Result<()> {
+        Workflow::upsert(&self.pool, workflow).await?;
+        Ok(())
+    }
+
+    async fn delete_workflow(&self, id: &str) -> Result<bool> {
+        Workflow::delete(&self.pool, id).await
+    }
+
+    async fn list_workflows(&self, _filter: Option<&ConfigFilter>) -> Result<Vec<ConfigEntity>> {
+        let workflows = Workflow::list_all(&self.pool).await?;
+        Ok(workflows.into_iter().map(ConfigEntity::Workflow).collect())
+    }
+
+    // ---- Alias pattern helpers ----
+
+    async fn load_alias_pattern(&self, id: &str) -> Result<Option<ConfigEntity>> {
+        let pattern = AliasPattern::find_by_id(&self.pool, id).await?;
+        Ok(pattern.map(ConfigEntity::AliasPattern))
+    }
+
+    async fn save_alias_pattern(&self, pattern: &AliasPattern) -> Result<()> {
+        AliasPattern::upsert(&self.pool, pattern).await?;
+        Ok(())
+    }
+
+    async fn delete_alias_pattern(&self, id: &str) -> Result<bool> {
+        AliasPattern::delete(&self.pool, id).await
+    }
+
+    async fn list_alias_patterns(
+        &self,
+        _filter: Option<&ConfigFilter>,
+    ) -> Result<Vec<ConfigEntity>> {
+        let patterns = AliasPattern::list_all(&self.pool).await?;
+        Ok(patterns
+            .into_iter()
+            .map(ConfigEntity::AliasPattern)
+            .collect())
+    }
+
+    // ---- Password policy helpers ----
+
+    async fn load_password_policy(&self, id: &str) -> Result<Option<ConfigEntity>> {
+        let policy = PasswordPolicy::find_by_id(&self.pool, id).await?;
+        Ok(policy.map(ConfigEntity::PasswordPolicy))
+    }
+
+    async fn save_password_policy(&self, policy: &PasswordPolicy) -> Result<()> {
+        PasswordPolicy::upsert(&self.pool, policy).await?;
+        Ok(())
+    }
+
+    async fn delete_password_policy(&self, id: &str) -> Result<bool> {
+        PasswordPolicy::delete(&self.pool, id).await
+    }
+
+    async fn list_password_policies(
+        &self,
+        _filter: Option<&ConfigFilter>,
+    ) -> Result<Vec<ConfigEntity>> {
+        let policies = PasswordPolicy::list_all(&self.pool).await?;
+        Ok(policies
+            .into_iter()
+            .map(ConfigEntity::PasswordPolicy)
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -1050,11 +1234,23 @@ impl ConfigStorage for DirectConfigStorage {
             entity_types::STAGE => self.load_stage(id).await,
             entity_types::TILE => self.load_tile(id).await,
             entity_types::MENU_LINK => self.load_menu_link(id).await,
+            entity_types::WORKFLOW => self.load_workflow(id).await,
+            entity_types::ALIAS_PATTERN => self.load_alias_pattern(id).await,
+            entity_types::PASSWORD_POLICY => self.load_password_policy(id).await,
             _ => Err(anyhow::anyhow!("unknown entity type: {entity_type}")),
         }
     }
 
     async fn save(&self, entity: &ConfigEntity) -> Result<()> {
+        let violations = self.validate(entity).await?;
+        if !violations.is_empty() {
+            return Err(ConfigValidationFailed {
+                entity: entity.to_string(),
+                violations,
+            }
+            .into());
+        }
+
         match entity {
             ConfigEntity::ItemType(t) => self.save_item_type(t).await,
             ConfigEntity::SearchFieldConfig(f) => self.save_search_field_config(f).await,
@@ -1069,6 +1265,9 @@ impl ConfigStorage for DirectConfigStorage {
             ConfigEntity::Stage(s) => self.save_stage(s).await,
             ConfigEntity::Tile(t) => self.save_tile(t).await,
             ConfigEntity::MenuLink(m) => self.save_menu_link(m).await,
+            ConfigEntity::Workflow(w) => self.save_workflow(w).await,
+            ConfigEntity::AliasPattern(p) => self.save_alias_pattern(p).await,
+            ConfigEntity::PasswordPolicy(p) => self.save_password_policy(p).await,
         }
     }
 
@@ -1087,6 +1286,9 @@ impl ConfigStorage for DirectConfigStorage {
             entity_types::STAGE => self.delete_stage(id).await,
             entity_types::TILE => self.delete_tile(id).await,
             entity_types::MENU_LINK => self.delete_menu_link(id).await,
+            entity_types::WORKFLOW => self.delete_workflow(id).await,
+            entity_types::ALIAS_PATTERN => self.delete_alias_pattern(id).await,
+            entity_types::PASSWORD_POLICY => self.delete_password_policy(id).await,
             _ => Err(anyhow::anyhow!("unknown entity type: {entity_type}")),
         }
     }
@@ -1096,21 +1298,29 @@ impl ConfigStorage for DirectConfigStorage {
         entity_type: &str,
         filter: Option<&ConfigFilter>,
     ) -> Result<Vec<ConfigEntity>> {
-        match entity_type {
-            entity_types::ITEM_TYPE => self.list_item_types(filter).await,
-            entity_types::SEARCH_FIELD_CONFIG => self.list_search_field_configs(filter).await,
-            entity_types::CATEGORY => self.list_categories(filter).await,
-            entity_types::TAG => self.list_tags(filter).await,
-            entity_types::VARIABLE => self.list_variables(filter).await,
-            entity_types::LANGUAGE => self.list_languages(filter).await,
-            entity_types::GATHER_QUERY => self.list_gather_queries(filter).await,
-            entity_types::URL_ALIAS => self.list_url_aliases(filter).await,
-            entity_types::ITEM => self.list_items(filter).await,
-            entity_types::ROLE => self.list_roles(filter).await,
-            entity_types::STAGE => self.list_stages(filter).await,
-            entity_types::TILE => self.list_tiles(filter).await,
-            entity_types::MENU_LINK => self.list_menu_links(filter).await,
-            _ => Err(anyhow::anyhow!("unknown entity type: {entity_type}")),
+        match &self.read_pools {
+            Some(read_pools) => {
+                read_pools
+                    .read_with_fallback(|pool| async move {
+                        Self::new(pool).list_entities(entity_type, filter).await
+                    })
+                    .await
+            }
+            None => self.list_entities(entity_type, filter).await,
+        }
+    }
+
+    async fn load_many(&self, entity_type: &str, ids: &[String]) -> Result<Vec<ConfigEntity>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.load_entities(entity_type, ids).await
+    }
+
+    async fn validate(&self, entity: &ConfigEntity) -> Result<Vec<ConfigViolation>> {
+        match &self.validator {
+            Some(dispatcher) => validate_entity(dispatcher, entity).await,
+            None => Ok(Vec::new()),
         }
     }
 }
@@ -4,7 +4,10 @@
 //! The key principle: **all config reads/writes must go through ConfigStorage**.
 //!
 //! This enables future stage-aware config by simply swapping the implementation
-//! with a decorator, without changing any call sites.
+//! with a decorator, without changing any call sites. The same approach
+//! serves translated config: [`TranslatedConfigStorage`] overlays
+//! `trovato_config_translation` strings for a language, and
+//! [`CachedConfigStorage`] keeps hot entities in memory.
 //!
 //! # Entity Types
 //!
@@ -19,6 +22,8 @@
 //! - `stage` - Stage definitions (editorial workflow stages)
 //! - `tile` - Tile definitions (UI region components)
 //! - `menu_link` - Menu link definitions
+//! - `workflow` - Content moderation workflows (states and transitions)
+//! - `alias_pattern` - URL alias patterns (pathauto) per content type
 //!
 //! # Usage
 //!
@@ -30,8 +35,11 @@
 //! let categories = storage.list("category", None).await?;
 //! ```
 
+mod cached;
 mod direct;
 mod stage_aware;
+mod translated;
+mod validation;
 pub mod yaml;
 
 use std::fmt;
@@ -41,12 +49,20 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub use cached::CachedConfigStorage;
 pub use direct::DirectConfigStorage;
 pub use stage_aware::StageAwareConfigStorage;
+pub use translated::{
+    TranslatedConfigStorage, apply_translation, source_value, translatable_properties,
+};
+pub use validation::{ConfigEntityInput, ConfigValidationFailed, ConfigViolation, validate_entity};
 
 use crate::gather::types::GatherQuery;
 use crate::models::tile::Tile;
-use crate::models::{Category, ItemType, Language, MenuLink, Role, Stage, Tag, UrlAlias};
+use crate::models::{
+    AliasPattern, Category, ItemType, Language, MenuLink, PasswordPolicy, Role, Stage, Tag,
+    UrlAlias, Workflow,
+};
 
 /// A content item as represented in config YAML for import/export.
 ///
@@ -161,6 +177,18 @@ pub enum ConfigEntity {
     /// Menu link definition.
     #[serde(rename = "menu_link")]
     MenuLink(MenuLink),
+
+    /// Content moderation workflow.
+    #[serde(rename = "workflow")]
+    Workflow(Workflow),
+
+    /// URL alias pattern (pathauto).
+    #[serde(rename = "alias_pattern")]
+    AliasPattern(AliasPattern),
+
+    /// Password policy.
+    #[serde(rename = "password_policy")]
+    PasswordPolicy(PasswordPolicy),
 }
 
 impl ConfigEntity {
@@ -180,6 +208,9 @@ impl ConfigEntity {
             Self::Stage(_) => "stage",
             Self::Tile(_) => "tile",
             Self::MenuLink(_) => "menu_link",
+            Self::Workflow(_) => "workflow",
+            Self::AliasPattern(_) => "alias_pattern",
+            Self::PasswordPolicy(_) => "password_policy",
         }
     }
 
@@ -199,6 +230,9 @@ impl ConfigEntity {
             Self::Stage(s) => s.id.to_string(),
             Self::Tile(t) => t.id.to_string(),
             Self::MenuLink(m) => m.id.to_string(),
+            Self::Workflow(w) => w.id.clone(),
+            Self::AliasPattern(p) => p.id.clone(),
+            Self::PasswordPolicy(p) => p.id.clone(),
         }
     }
 
@@ -412,8 +446,17 @@ pub trait ConfigStorage: Send + Sync {
     /// Save a config entity (insert or update).
     ///
     /// The entity type and ID are extracted from the entity itself.
+    /// Fails with [`ConfigValidationFailed`] if [`Self::validate`] reports
+    /// violations.
     async fn save(&self, entity: &ConfigEntity) -> Result<()>;
 
+    /// Ask plugins (`tap_config_validate`) whether an entity may be saved.
+    ///
+    /// Storage without a tap dispatcher accepts everything.
+    async fn validate(&self, _entity: &ConfigEntity) -> Result<Vec<ConfigViolation>> {
+        Ok(Vec::new())
+    }
+
     /// Delete a config entity by type and ID.
     ///
     /// Returns `true` if an entity was deleted, `false` if it didn't exist.
@@ -426,6 +469,31 @@ pub trait ConfigStorage: Send + Sync {
         filter: Option<&ConfigFilter>,
     ) -> Result<Vec<ConfigEntity>>;
 
+    /// Load several config entities of one type.
+    ///
+    /// IDs that don't exist are skipped; the rest come back in the order of
+    /// `ids`. The default loads them one by one, so storages override it
+    /// with a fixed number of queries.
+    async fn load_many(&self, entity_type: &str, ids: &[String]) -> Result<Vec<ConfigEntity>> {
+        let mut entities = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(entity) = self.load(entity_type, id).await? {
+                entities.push(entity);
+            }
+        }
+        Ok(entities)
+    }
+
+    /// List every content type definition.
+    async fn list_all_types(&self) -> Result<Vec<ItemType>> {
+        Ok(self
+            .list(entity_types::ITEM_TYPE, None)
+            .await?
+            .into_iter()
+            .filter_map(ConfigEntity::into_item_type)
+            .collect())
+    }
+
     /// Check if a config entity exists.
     async fn exists(&self, entity_type: &str, id: &str) -> Result<bool> {
         Ok(self.load(entity_type, id).await?.is_some())
@@ -472,6 +540,15 @@ pub mod entity_types {
 
     /// Menu link definitions.
     pub const MENU_LINK: &str = "menu_link";
+
+    /// Content moderation workflows.
+    pub const WORKFLOW: &str = "workflow";
+
+    /// URL alias patterns (pathauto).
+    pub const ALIAS_PATTERN: &str = "alias_pattern";
+
+    /// Password policies.
+    pub const PASSWORD_POLICY: &str = "password_policy";
 }
 
 /// Helper to parse a tag ID from a string (UUID format).
@@ -480,6 +557,13 @@ pub fn parse_tag_id(id: &str) -> Result<Uuid> {
         .map_err(|e| anyhow::anyhow!("invalid tag ID '{id}': {e}"))
 }
 
+/// Order entities as their IDs appear in `ids`, dropping unrequested ones.
+fn in_id_order(entities: Vec<ConfigEntity>, ids: &[String]) -> Vec<ConfigEntity> {
+    let mut by_id: std::collections::HashMap<String, ConfigEntity> =
+        entities.into_iter().map(|e| (e.id(), e)).collect();
+    ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -517,6 +601,20 @@ mod tests {
         assert_eq!(value, &serde_json::json!("My Site"));
     }
 
+    #[test]
+    fn load_many_results_follow_requested_order() {
+        let var = |key: &str| ConfigEntity::Variable {
+            key: key.to_string(),
+            value: serde_json::json!(1),
+        };
+        let ids = vec!["c".to_string(), "a".to_string(), "missing".to_string()];
+
+        let ordered = in_id_order(vec![var("a"), var("b"), var("c")], &ids);
+
+        let got: Vec<String> = ordered.iter().map(ConfigEntity::id).collect();
+        assert_eq!(got, vec!["c", "a"]);
+    }
+
     #[test]
     fn config_filter_builder() {
         let filter = ConfigFilter::new()
@@ -8,6 +8,8 @@
 //! - **Save**: Write to config_revision, update config_stage_association
 //! - **Delete**: Record in stage_deletion (don't actually delete until publish)
 //! - **List**: Merge stage changes with live, respecting deletions
+//! - **Load many**: Same as load, with one query each for deletions and
+//!   staged revisions before the live bulk load
 
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -18,7 +20,10 @@ use sqlx::PgPool;
 use tracing::debug;
 use uuid::Uuid;
 
-use super::{ConfigEntity, ConfigFilter, ConfigStorage, DirectConfigStorage};
+use super::{
+    ConfigEntity, ConfigFilter, ConfigStorage, ConfigValidationFailed, ConfigViolation,
+    DirectConfigStorage, in_id_order,
+};
 
 /// Stage-aware config storage decorator.
 ///
@@ -246,6 +251,60 @@ impl StageAwareConfigStorage {
         Ok(ids.into_iter().collect())
     }
 
+    /// Of the given IDs, those deleted in this stage.
+    async fn get_deleted_among(
+        &self,
+        entity_type: &str,
+        ids: &[String],
+    ) -> Result<HashSet<String>> {
+        let deleted: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT entity_id FROM stage_deletion
+            WHERE stage_id = $1 AND entity_type = $2 AND entity_id = ANY($3)
+            "#,
+        )
+        .bind(self.stage_id)
+        .bind(entity_type)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to fetch deleted IDs")?;
+
+        Ok(deleted.into_iter().collect())
+    }
+
+    /// Staged revisions in this stage for the given IDs.
+    async fn get_staged_among(
+        &self,
+        entity_type: &str,
+        ids: &[String],
+    ) -> Result<Vec<ConfigEntity>> {
+        let revisions = sqlx::query_as::<_, ConfigRevisionRow>(
+            r#"
+            SELECT cr.id, cr.entity_type, cr.entity_id, cr.data, cr.created, cr.author_id
+            FROM config_revision cr
+            INNER JOIN config_stage_association csa
+                ON cr.id = csa.target_revision_id
+            WHERE csa.stage_id = $1
+                AND csa.entity_type = $2
+                AND csa.entity_id = ANY($3)
+            "#,
+        )
+        .bind(self.stage_id)
+        .bind(entity_type)
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to fetch staged revisions")?;
+
+        revisions
+            .into_iter()
+            .map(|rev| {
+                serde_json::from_value(rev.data).context("failed to deserialize staged revision")
+            })
+            .collect()
+    }
+
     /// Get all staged entities of a type.
     async fn get_staged_entities(&self, entity_type: &str) -> Result<Vec<ConfigEntity>> {
         let revisions = sqlx::query_as::<_, ConfigRevisionRow>(
@@ -312,6 +371,17 @@ impl ConfigStorage for StageAwareConfigStorage {
     }
 
     async fn save(&self, entity: &ConfigEntity) -> Result<()> {
+        // Staged changes are validated like live ones, so publishing
+        // never carries an entity plugins would have refused.
+        let violations = self.validate(entity).await?;
+        if !violations.is_empty() {
+            return Err(ConfigValidationFailed {
+                entity: entity.to_string(),
+                violations,
+            }
+            .into());
+        }
+
         // Create a staged revision (don't touch live)
         self.create_staged_revision(entity, None).await
     }
@@ -367,6 +437,29 @@ impl ConfigStorage for StageAwareConfigStorage {
         Ok(result)
     }
 
+    async fn load_many(&self, entity_type: &str, ids: &[String]) -> Result<Vec<ConfigEntity>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Deleted IDs are dropped, staged ones override live
+        let deleted = self.get_deleted_among(entity_type, ids).await?;
+        let mut entities = self.get_staged_among(entity_type, ids).await?;
+        let staged_ids: HashSet<String> = entities.iter().map(|e| e.id()).collect();
+
+        let live_ids: Vec<String> = ids
+            .iter()
+            .filter(|id| !deleted.contains(*id) && !staged_ids.contains(*id))
+            .cloned()
+            .collect();
+        if !live_ids.is_empty() {
+            entities.extend(self.direct.load_many(entity_type, &live_ids).await?);
+        }
+
+        entities.retain(|e| !deleted.contains(&e.id()));
+        Ok(in_id_order(entities, ids))
+    }
+
     async fn exists(&self, entity_type: &str, id: &str) -> Result<bool> {
         // Deleted in stage means doesn't exist
         if self.is_deleted(entity_type, id).await? {
@@ -381,6 +474,10 @@ impl ConfigStorage for StageAwareConfigStorage {
         // Fall back to live
         self.direct.exists(entity_type, id).await
     }
+
+    async fn validate(&self, entity: &ConfigEntity) -> Result<Vec<ConfigViolation>> {
+        self.direct.validate(entity).await
+    }
 }
 
 impl std::fmt::Debug for StageAwareConfigStorage {
@@ -0,0 +1,253 @@
+//! Language-aware decorator for ConfigStorage.
+//!
+//! Wraps another storage and applies the `trovato_config_translation`
+//! overlays for one language to everything it reads:
+//!
+//! - **Load / Load many / List**: Load from the inner storage, then replace translatable
+//!   properties with their translations where one exists
+//! - **Save / Delete**: Pass through untouched, so writes always change the
+//!   source-language entity
+//!
+//! Because saves pass through, don't save entities read through this
+//! storage: the translated strings would overwrite the source values.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{ConfigEntity, ConfigFilter, ConfigStorage, ConfigViolation, entity_types};
+use crate::models::ConfigTranslation;
+
+/// Translatable properties of each config entity type.
+///
+/// Only string-valued properties are listed; a variable is translatable
+/// only when its value is a string.
+pub fn translatable_properties(entity_type: &str) -> &'static [&'static str] {
+    match entity_type {
+        entity_types::ITEM_TYPE => &["label", "description", "title_label"],
+        entity_types::CATEGORY => &["label", "description"],
+        entity_types::VARIABLE => &["value"],
+        _ => &[],
+    }
+}
+
+/// Read the source value of a translatable property.
+///
+/// Returns `None` for unknown properties and for properties that are
+/// unset or, for variables, not a string.
+pub fn source_value(entity: &ConfigEntity, property: &str) -> Option<String> {
+    match (entity, property) {
+        (ConfigEntity::ItemType(t), "label") => Some(t.label.clone()),
+        (ConfigEntity::ItemType(t), "description") => t.description.clone(),
+        (ConfigEntity::ItemType(t), "title_label") => t.title_label.clone(),
+        (ConfigEntity::Category(c), "label") => Some(c.label.clone()),
+        (ConfigEntity::Category(c), "description") => c.description.clone(),
+        (ConfigEntity::Variable { value, .. }, "value") => value.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Replace a translatable property with its translation.
+///
+/// Returns `false` (leaving the entity unchanged) when the property isn't
+/// translatable for the entity.
+pub fn apply_translation(entity: &mut ConfigEntity, property: &str, value: &str) -> bool {
+    let value = value.to_string();
+    match (entity, property) {
+        (ConfigEntity::ItemType(t), "label") => t.label = value,
+        (ConfigEntity::ItemType(t), "description") => t.description = Some(value),
+        (ConfigEntity::ItemType(t), "title_label") => t.title_label = Some(value),
+        (ConfigEntity::Category(c), "label") => c.label = value,
+        (ConfigEntity::Category(c), "description") => c.description = Some(value),
+        (ConfigEntity::Variable { value: v, .. }, "value") if v.is_string() => {
+            *v = serde_json::Value::String(value);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Config storage decorator that translates entities into one language.
+#[derive(Clone)]
+pub struct TranslatedConfigStorage {
+    /// The storage holding source-language entities.
+    inner: Arc<dyn ConfigStorage>,
+    /// Database pool for overlay queries.
+    pool: PgPool,
+    /// The language overlays are applied for.
+    language: String,
+}
+
+impl TranslatedConfigStorage {
+    /// Create a new translated config storage.
+    ///
+    /// # Arguments
+    /// * `inner` - The storage to read source entities from
+    /// * `pool` - Database pool for overlay queries
+    /// * `language` - The language to translate into
+    pub fn new(inner: Arc<dyn ConfigStorage>, pool: PgPool, language: impl Into<String>) -> Self {
+        Self {
+            inner,
+            pool,
+            language: language.into(),
+        }
+    }
+
+    /// Get the language this storage translates into.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Apply this language's overlays to entities of one type.
+    async fn translate_all(&self, entity_type: &str, entities: &mut [ConfigEntity]) -> Result<()> {
+        if translatable_properties(entity_type).is_empty() || entities.is_empty() {
+            return Ok(());
+        }
+
+        let mut by_entity: HashMap<String, Vec<ConfigTranslation>> = HashMap::new();
+        for t in ConfigTranslation::for_type(&self.pool, entity_type, &self.language).await? {
+            by_entity.entry(t.entity_id.clone()).or_default().push(t);
+        }
+        for entity in entities.iter_mut() {
+            if let Some(translations) = by_entity.get(&entity.id()) {
+                for t in translations {
+                    apply_translation(entity, &t.property, &t.value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ConfigStorage for TranslatedConfigStorage {
+    async fn load(&self, entity_type: &str, id: &str) -> Result<Option<ConfigEntity>> {
+        let Some(mut entity) = self.inner.load(entity_type, id).await? else {
+            return Ok(None);
+        };
+        if translatable_properties(entity_type).is_empty() {
+            return Ok(Some(entity));
+        }
+
+        let translations =
+            ConfigTranslation::for_entity(&self.pool, entity_type, id, &self.language).await?;
+        for t in &translations {
+            apply_translation(&mut entity, &t.property, &t.value);
+        }
+        Ok(Some(entity))
+    }
+
+    async fn save(&self, entity: &ConfigEntity) -> Result<()> {
+        self.inner.save(entity).await
+    }
+
+    async fn delete(&self, entity_type: &str, id: &str) -> Result<bool> {
+        self.inner.delete(entity_type, id).await
+    }
+
+    async fn list(
+        &self,
+        entity_type: &str,
+        filter: Option<&ConfigFilter>,
+    ) -> Result<Vec<ConfigEntity>> {
+        let mut entities = self.inner.list(entity_type, filter).await?;
+        self.translate_all(entity_type, &mut entities).await?;
+        Ok(entities)
+    }
+
+    async fn load_many(&self, entity_type: &str, ids: &[String]) -> Result<Vec<ConfigEntity>> {
+        let mut entities = self.inner.load_many(entity_type, ids).await?;
+        self.translate_all(entity_type, &mut entities).await?;
+        Ok(entities)
+    }
+
+    async fn exists(&self, entity_type: &str, id: &str) -> Result<bool> {
+        self.inner.exists(entity_type, id).await
+    }
+
+    async fn validate(&self, entity: &ConfigEntity) -> Result<Vec<ConfigViolation>> {
+        self.inner.validate(entity).await
+    }
+}
+
+impl std::fmt::Debug for TranslatedConfigStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranslatedConfigStorage")
+            .field("language", &self.language)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::models::{Category, ItemType};
+
+    #[test]
+    fn translated_config_storage_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TranslatedConfigStorage>();
+    }
+
+    #[test]
+    fn translations_replace_translatable_properties() {
+        let mut item_type = ConfigEntity::ItemType(ItemType {
+            type_name: "blog".to_string(),
+            label: "Blog".to_string(),
+            description: None,
+            has_title: true,
+            title_label: None,
+            plugin: "blog".to_string(),
+            settings: serde_json::json!({}),
+        });
+        assert!(apply_translation(&mut item_type, "label", "Blogue"));
+        assert!(apply_translation(&mut item_type, "title_label", "Titre"));
+        assert!(!apply_translation(&mut item_type, "plugin", "autre"));
+        assert_eq!(source_value(&item_type, "label").as_deref(), Some("Blogue"));
+        assert_eq!(
+            source_value(&item_type, "title_label").as_deref(),
+            Some("Titre")
+        );
+        assert_eq!(item_type.id(), "blog");
+
+        let mut category = ConfigEntity::Category(Category {
+            id: "tags".to_string(),
+            label: "Tags".to_string(),
+            description: Some("Free tagging".to_string()),
+            hierarchy: 0,
+            weight: 0,
+        });
+        assert!(apply_translation(
+            &mut category,
+            "description",
+            "Étiquettes libres"
+        ));
+        assert_eq!(
+            source_value(&category, "description").as_deref(),
+            Some("Étiquettes libres")
+        );
+    }
+
+    #[test]
+    fn only_string_variables_are_translated() {
+        let mut name = ConfigEntity::Variable {
+            key: "site_name".to_string(),
+            value: serde_json::json!("My Site"),
+        };
+        assert!(apply_translation(&mut name, "value", "Mon site"));
+        assert_eq!(source_value(&name, "value").as_deref(), Some("Mon site"));
+
+        let mut limit = ConfigEntity::Variable {
+            key: "items_per_page".to_string(),
+            value: serde_json::json!(10),
+        };
+        assert!(!apply_translation(&mut limit, "value", "dix"));
+        assert_eq!(source_value(&limit, "value"), None);
+        assert_eq!(limit.as_variable().unwrap().1, &serde_json::json!(10));
+    }
+}
@@ -0,0 +1,155 @@
+//! Plugin validation of config entities.
+//!
+//! Before a config entity is saved, plugins implementing
+//! `tap_config_validate` receive it as a [`ConfigEntityInput`] and may
+//! return violations (e.g. a plugin-owned variable out of range). Any
+//! violation blocks the save with [`ConfigValidationFailed`].
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use super::ConfigEntity;
+use crate::tap::{RequestState, TapDispatcher, UserContext};
+
+/// Input for `tap_config_validate`, sent before a config entity is saved.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. The kernel serializes this; plugins deserialize
+/// it. If you change fields here, update the SDK copy to match.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConfigEntityInput {
+    /// Entity type (e.g., "item_type", "variable").
+    pub entity_type: String,
+    /// Entity ID (type machine name, variable key, ...).
+    pub id: String,
+    /// The entity as it would be saved, in its config export shape.
+    pub data: serde_json::Value,
+}
+
+impl ConfigEntityInput {
+    /// Build the tap input for an entity.
+    pub fn from_entity(entity: &ConfigEntity) -> Result<Self> {
+        let mut value =
+            serde_json::to_value(entity).context("failed to serialize config entity")?;
+        let data = value
+            .get_mut("data")
+            .map(serde_json::Value::take)
+            .unwrap_or_default();
+        Ok(Self {
+            entity_type: entity.entity_type().to_string(),
+            id: entity.id(),
+            data,
+        })
+    }
+}
+
+/// A validation failure returned from `tap_config_validate`.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConfigViolation {
+    /// Offending key within the entity data (e.g., "value", "settings.threshold").
+    pub field: String,
+    /// Human-readable explanation shown to the administrator.
+    pub message: String,
+    /// Machine-readable code (e.g., "out_of_range").
+    pub code: String,
+}
+
+/// Error returned when plugins reject a config entity from `tap_config_validate`.
+///
+/// Converted to a 422 response by [`crate::error::AppError`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{entity} failed validation: {}", join_messages(.violations))]
+pub struct ConfigValidationFailed {
+    /// The rejected entity, as `type:id`.
+    pub entity: String,
+    /// Violations from all plugins, in dispatch order.
+    pub violations: Vec<ConfigViolation>,
+}
+
+fn join_messages(violations: &[ConfigViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| v.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Collect violations from every plugin implementing `tap_config_validate`.
+///
+/// Plugins return a JSON list of violations; empty or unparseable output
+/// counts as no objection.
+pub async fn validate_entity(
+    dispatcher: &TapDispatcher,
+    entity: &ConfigEntity,
+) -> Result<Vec<ConfigViolation>> {
+    let input = ConfigEntityInput::from_entity(entity)?;
+    let input_json = serde_json::to_string(&input).context("serialize config validate input")?;
+    let state = RequestState::without_services(UserContext::anonymous());
+    let results = dispatcher
+        .dispatch("tap_config_validate", &input_json, state)
+        .await;
+
+    let mut violations = Vec::new();
+    for result in results {
+        if result.output.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Vec<ConfigViolation>>(&result.output) {
+            Ok(found) => violations.extend(found),
+            Err(e) => warn!(
+                plugin = %result.plugin_name,
+                error = %e,
+                "ignoring malformed tap_config_validate output"
+            ),
+        }
+    }
+    if !violations.is_empty() {
+        info!(
+            entity = %entity,
+            violations = violations.len(),
+            "config entity rejected by tap_config_validate"
+        );
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_carries_entity_data() {
+        let entity = ConfigEntity::Variable {
+            key: "argus_threshold".to_string(),
+            value: serde_json::json!(1.5),
+        };
+
+        let input = ConfigEntityInput::from_entity(&entity).unwrap();
+        assert_eq!(input.entity_type, "variable");
+        assert_eq!(input.id, "argus_threshold");
+        assert_eq!(
+            input.data,
+            serde_json::json!({"key": "argus_threshold", "value": 1.5})
+        );
+    }
+
+    #[test]
+    fn failure_lists_messages() {
+        let failed = ConfigValidationFailed {
+            entity: "variable:argus_threshold".to_string(),
+            violations: vec![ConfigViolation {
+                field: "value".to_string(),
+                message: "Threshold must be between 0 and 1".to_string(),
+                code: "out_of_range".to_string(),
+            }],
+        };
+        assert_eq!(
+            failed.to_string(),
+            "variable:argus_threshold failed validation: Threshold must be between 0 and 1"
+        );
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! Exports all config entities to individual YAML files (one per entity)
 //! and re-imports them. File naming: `{entity_type}.{id}.yml`.
+//! [`diff_config`] compares a directory against the database property by
+//! property, and a [`ConfigFilter`] limits an import or diff to some
+//! entity types or entities.
 //!
 //! Import is idempotent: `ConfigStorage::save()` performs upsert, so
 //! re-running import on a partially-imported database converges to the
@@ -14,7 +17,7 @@
 //! If the process is interrupted mid-import, simply re-run the import to
 //! converge to the correct state.
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::Path;
 
 use anyhow::{Context, Result};
@@ -26,7 +29,10 @@ use uuid::Uuid;
 use super::{ConfigEntity, ConfigStorage, SearchFieldConfig, entity_types};
 use crate::gather::types::GatherQuery;
 use crate::models::tile::Tile;
-use crate::models::{Category, ItemType, Language, MenuLink, Role, Stage, Tag, UrlAlias};
+use crate::models::{
+    AliasPattern, Category, ItemType, Language, MenuLink, PasswordPolicy, Role, Stage, Tag,
+    UrlAlias, Workflow,
+};
 
 /// Entity type ordering used for both validation and dependency-ordered import.
 ///
@@ -37,6 +43,9 @@ const ENTITY_TYPE_ORDER: &[&str] = &[
     entity_types::LANGUAGE,
     entity_types::ROLE,
     entity_types::ITEM_TYPE,
+    entity_types::WORKFLOW,
+    entity_types::ALIAS_PATTERN,
+    entity_types::PASSWORD_POLICY,
     entity_types::CATEGORY,
     entity_types::TAG,
     entity_types::SEARCH_FIELD_CONFIG,
@@ -106,6 +115,98 @@ impl ConfigOpResult {
     }
 }
 
+/// Which entities a config import or diff applies to.
+///
+/// Built from `--only` and `--skip` rules of the form `entity_type` or
+/// `entity_type:id`. An entity is included if it matches an `only` rule
+/// (or there are none) and no `skip` rule.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigFilter {
+    only: Vec<FilterRule>,
+    skip: Vec<FilterRule>,
+}
+
+/// One `entity_type[:id]` rule of a [`ConfigFilter`].
+#[derive(Debug, Clone, PartialEq)]
+struct FilterRule {
+    entity_type: String,
+    id: Option<String>,
+}
+
+impl FilterRule {
+    fn parse(rule: &str) -> Result<Self> {
+        let (entity_type, id) = match rule.split_once(':') {
+            Some((entity_type, id)) => (entity_type, Some(id)),
+            None => (rule, None),
+        };
+        if !ENTITY_TYPE_ORDER.contains(&entity_type) {
+            anyhow::bail!(
+                "unknown entity type '{entity_type}' in filter '{rule}'; expected one of: {}",
+                ENTITY_TYPE_ORDER.join(", ")
+            );
+        }
+        if id.is_some_and(str::is_empty) {
+            anyhow::bail!("empty entity ID in filter '{rule}'");
+        }
+        Ok(Self {
+            entity_type: entity_type.to_string(),
+            id: id.map(str::to_string),
+        })
+    }
+
+    fn matches(&self, entity_type: &str, id: &str) -> bool {
+        self.entity_type == entity_type && self.id.as_deref().is_none_or(|rule_id| rule_id == id)
+    }
+}
+
+impl ConfigFilter {
+    /// Parse `--only` and `--skip` rules.
+    pub fn new(only: &[String], skip: &[String]) -> Result<Self> {
+        Ok(Self {
+            only: only
+                .iter()
+                .map(|r| FilterRule::parse(r))
+                .collect::<Result<_>>()?,
+            skip: skip
+                .iter()
+                .map(|r| FilterRule::parse(r))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Whether the filter has any rules.
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.skip.is_empty()
+    }
+
+    /// Whether an entity is included.
+    pub fn includes(&self, entity_type: &str, id: &str) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|r| r.matches(entity_type, id)))
+            && !self.skip.iter().any(|r| r.matches(entity_type, id))
+    }
+
+    /// Whether any entity of a type may be included.
+    fn includes_type(&self, entity_type: &str) -> bool {
+        (self.only.is_empty() || self.only.iter().any(|r| r.entity_type == entity_type))
+            && !self
+                .skip
+                .iter()
+                .any(|r| r.entity_type == entity_type && r.id.is_none())
+    }
+
+    /// Drop parsed entities the filter excludes, returning how many.
+    fn retain(&self, parsed: &mut BTreeMap<String, Vec<ParsedEntity>>) -> usize {
+        let mut excluded = 0;
+        for (entity_type, entities) in parsed.iter_mut() {
+            let before = entities.len();
+            entities.retain(|pe| self.includes(entity_type, &pe.entity.id()));
+            excluded += before - entities.len();
+        }
+        parsed.retain(|_, entities| !entities.is_empty());
+        excluded
+    }
+}
+
 /// Generate the filename for a config entity.
 fn entity_filename(entity_type: &str, id: &str) -> String {
     format!("{entity_type}.{id}.yml")
@@ -192,6 +293,9 @@ fn serialize_entity(entity: &ConfigEntity, warnings: &mut Vec<String>) -> Option
         ConfigEntity::Stage(s) => serde_yml::to_string(s),
         ConfigEntity::Tile(t) => serde_yml::to_string(t),
         ConfigEntity::MenuLink(m) => serde_yml::to_string(m),
+        ConfigEntity::Workflow(w) => serde_yml::to_string(w),
+        ConfigEntity::AliasPattern(p) => serde_yml::to_string(p),
+        ConfigEntity::PasswordPolicy(p) => serde_yml::to_string(p),
         // Tags need parent hierarchy — callers must use serialize_tag_entity.
         ConfigEntity::Tag(tag) => {
             warnings.push(format!(
@@ -372,7 +476,10 @@ pub async fn export_config(
 /// 1. **Validation pass**: reads and parses all YAML files, checking for errors.
 /// 2. **Save pass**: writes parsed entities to storage in dependency order.
 ///
-/// When `dry_run` is true, only the validation pass runs (no database writes).
+/// When `dry_run` is true, only the validation pass runs (no database writes);
+/// it also reports what plugins would refuse through `tap_config_validate`.
+/// Entities refused during the save pass are skipped with a warning.
+/// Files whose entity `filter` excludes are left out of both passes.
 ///
 /// Import is idempotent — `ConfigStorage::save()` performs upsert, so
 /// re-running import on a partially-imported database converges correctly.
@@ -387,16 +494,19 @@ pub async fn import_config(
     pool: &PgPool,
     dir: &Path,
     dry_run: bool,
+    filter: &ConfigFilter,
 ) -> Result<ConfigOpResult> {
     info!(dir = %dir.display(), dry_run, "Starting config import");
 
     let mut result = ConfigOpResult::default();
 
     // Phase 1: Read and validate all files
-    let parsed = read_and_validate_files(dir, &mut result.warnings).await?;
+    let mut parsed = read_and_validate_files(dir, &mut result.warnings).await?;
+    let excluded = filter.retain(&mut parsed);
     let parsed_total: usize = parsed.values().map(|v| v.len()).sum();
     debug!(
         files = parsed_total,
+        excluded,
         warnings = result.warnings.len(),
         "Validation complete"
     );
@@ -409,6 +519,24 @@ pub async fn import_config(
             }
         }
 
+        // Report entities plugins would refuse on save
+        for pe in parsed.values().flatten() {
+            match storage.validate(&pe.entity).await {
+                Ok(violations) => {
+                    for v in violations {
+                        result
+                            .warnings
+                            .push(format!("{}: {} ({})", pe.filename, v.message, v.field));
+                    }
+                }
+                Err(e) => {
+                    result
+                        .warnings
+                        .push(format!("{}: failed to validate: {e}", pe.filename));
+                }
+            }
+        }
+
         // Validate tag hierarchy references within the import set
         if let Some(tag_entities) = parsed.get(entity_types::TAG) {
             let all_tag_ids: HashSet<Uuid> = tag_entities
@@ -622,6 +750,238 @@ pub async fn import_config(
     Ok(result)
 }
 
+/// How an entity or property in the config directory compares to the
+/// database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only in the config files; import adds it.
+    Added,
+    /// In both, with different values; import overwrites the database.
+    Changed,
+    /// Only in the database. Import removes a property but never deletes
+    /// an entity.
+    Removed,
+}
+
+/// A property that differs between a config file and the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyDiff {
+    /// Dotted path to the property, e.g. `settings.pathauto`.
+    pub path: String,
+    /// Value in the database, if it has the property.
+    pub database: Option<serde_json::Value>,
+    /// Value in the config file, if it has the property.
+    pub file: Option<serde_json::Value>,
+}
+
+impl PropertyDiff {
+    /// Whether the file adds, changes or removes the property.
+    pub fn kind(&self) -> DiffKind {
+        match (&self.database, &self.file) {
+            (None, _) => DiffKind::Added,
+            (_, None) => DiffKind::Removed,
+            _ => DiffKind::Changed,
+        }
+    }
+}
+
+/// An entity that differs between the config files and the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityDiff {
+    pub entity_type: String,
+    pub id: String,
+    pub kind: DiffKind,
+    /// Differing properties of a changed entity, sorted by path.
+    pub properties: Vec<PropertyDiff>,
+}
+
+/// Result of [`diff_config`].
+#[derive(Debug, Default)]
+pub struct ConfigDiff {
+    /// Differing entities, in import order.
+    pub entities: Vec<EntityDiff>,
+    /// Entities identical in the files and the database.
+    pub unchanged: usize,
+    pub warnings: Vec<String>,
+}
+
+impl ConfigDiff {
+    /// Number of differing entities of a kind.
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.entities.iter().filter(|e| e.kind == kind).count()
+    }
+}
+
+/// Compare config files in a directory with the database.
+///
+/// Both sides are compared in their export shape, so the diff shows what
+/// `config import` would change and what `config export` would write.
+/// Entities only in the database are reported as removed although import
+/// keeps them. Nothing is written.
+pub async fn diff_config(
+    storage: &dyn ConfigStorage,
+    pool: &PgPool,
+    dir: &Path,
+    filter: &ConfigFilter,
+) -> Result<ConfigDiff> {
+    let mut diff = ConfigDiff::default();
+    let mut parsed = read_and_validate_files(dir, &mut diff.warnings).await?;
+    filter.retain(&mut parsed);
+
+    for &entity_type in ENTITY_TYPE_ORDER {
+        if !filter.includes_type(entity_type) {
+            continue;
+        }
+
+        let entities = storage
+            .list(entity_type, None)
+            .await
+            .with_context(|| format!("failed to list {entity_type} entities"))?;
+        let mut stored = Vec::with_capacity(entities.len());
+        for entity in entities {
+            if !filter.includes(entity_type, &entity.id()) {
+                continue;
+            }
+            let parents = match &entity {
+                ConfigEntity::Tag(tag) => match Tag::get_parents(pool, tag.id).await {
+                    Ok(p) => p.into_iter().map(|t| t.id).collect(),
+                    Err(e) => {
+                        diff.warnings
+                            .push(format!("failed to get parents for tag {}: {e}", tag.id));
+                        Vec::new()
+                    }
+                },
+                _ => Vec::new(),
+            };
+            stored.push((entity, parents));
+        }
+
+        let files = parsed.get(entity_type).map_or(&[][..], Vec::as_slice);
+        diff_entity_type(entity_type, files, stored, &mut diff);
+    }
+
+    Ok(diff)
+}
+
+/// Compare the files and stored entities (with tag parents) of one type.
+fn diff_entity_type(
+    entity_type: &str,
+    files: &[ParsedEntity],
+    stored: Vec<(ConfigEntity, Vec<Uuid>)>,
+    diff: &mut ConfigDiff,
+) {
+    let mut stored: BTreeMap<String, (ConfigEntity, Vec<Uuid>)> = stored
+        .into_iter()
+        .map(|(entity, parents)| (entity.id(), (entity, parents)))
+        .collect();
+
+    for pe in files {
+        let id = pe.entity.id();
+        let Some((db_entity, db_parents)) = stored.remove(&id) else {
+            diff.entities.push(EntityDiff {
+                entity_type: entity_type.to_string(),
+                id,
+                kind: DiffKind::Added,
+                properties: Vec::new(),
+            });
+            continue;
+        };
+
+        let (Some(database), Some(file)) = (
+            entity_value(&db_entity, db_parents, &mut diff.warnings),
+            entity_value(&pe.entity, pe.tag_parents.clone(), &mut diff.warnings),
+        ) else {
+            continue;
+        };
+
+        let mut properties = Vec::new();
+        diff_values("", &database, &file, &mut properties);
+        if properties.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.entities.push(EntityDiff {
+                entity_type: entity_type.to_string(),
+                id,
+                kind: DiffKind::Changed,
+                properties,
+            });
+        }
+    }
+
+    for id in stored.into_keys() {
+        diff.entities.push(EntityDiff {
+            entity_type: entity_type.to_string(),
+            id,
+            kind: DiffKind::Removed,
+            properties: Vec::new(),
+        });
+    }
+}
+
+/// An entity in its export shape, as JSON. Returns `None` and records a
+/// warning if it cannot be serialized.
+fn entity_value(
+    entity: &ConfigEntity,
+    mut tag_parents: Vec<Uuid>,
+    warnings: &mut Vec<String>,
+) -> Option<serde_json::Value> {
+    let yaml = match entity {
+        ConfigEntity::Tag(tag) => {
+            // Parent order carries no meaning.
+            tag_parents.sort();
+            serialize_tag_entity(tag, tag_parents, warnings)?
+        }
+        other => serialize_entity(other, warnings)?,
+    };
+    match serde_yml::from_str(&yaml) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warnings.push(format!(
+                "failed to compare {} {}: {e}",
+                entity.entity_type(),
+                entity.id()
+            ));
+            None
+        }
+    }
+}
+
+/// Collect the properties that differ between two values, descending into
+/// objects. Arrays and scalars are compared whole.
+fn diff_values(
+    path: &str,
+    database: &serde_json::Value,
+    file: &serde_json::Value,
+    out: &mut Vec<PropertyDiff>,
+) {
+    match (database, file) {
+        (serde_json::Value::Object(db), serde_json::Value::Object(f)) => {
+            let keys: BTreeSet<&String> = db.keys().chain(f.keys()).collect();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match (db.get(key), f.get(key)) {
+                    (Some(d), Some(f)) => diff_values(&child, d, f, out),
+                    (d, f) => out.push(PropertyDiff {
+                        path: child,
+                        database: d.cloned(),
+                        file: f.cloned(),
+                    }),
+                }
+            }
+        }
+        _ if database != file => out.push(PropertyDiff {
+            path: path.to_string(),
+            database: Some(database.clone()),
+            file: Some(file.clone()),
+        }),
+        _ => {}
+    }
+}
+
 /// A parsed entity with metadata from its source file.
 struct ParsedEntity {
     filename: String,
@@ -846,6 +1206,24 @@ fn deserialize_entity(entity_type: &str, content: &str) -> Result<(ConfigEntity,
             let link: MenuLink = serde_yml::from_str(content).context("invalid menu_link YAML")?;
             Ok((ConfigEntity::MenuLink(link), Vec::new()))
         }
+        entity_types::WORKFLOW => {
+            let workflow: Workflow =
+                serde_yml::from_str(content).context("invalid workflow YAML")?;
+            workflow.validate()?;
+            Ok((ConfigEntity::Workflow(workflow), Vec::new()))
+        }
+        entity_types::ALIAS_PATTERN => {
+            let pattern: AliasPattern =
+                serde_yml::from_str(content).context("invalid alias_pattern YAML")?;
+            pattern.validate()?;
+            Ok((ConfigEntity::AliasPattern(pattern), Vec::new()))
+        }
+        entity_types::PASSWORD_POLICY => {
+            let policy: PasswordPolicy =
+                serde_yml::from_str(content).context("invalid password_policy YAML")?;
+            policy.validate()?;
+            Ok((ConfigEntity::PasswordPolicy(policy), Vec::new()))
+        }
         _ => anyhow::bail!("unknown entity type: {entity_type}"),
     }
 }
@@ -1226,6 +1604,9 @@ mod tests {
             entity_types::STAGE,
             entity_types::TILE,
             entity_types::MENU_LINK,
+            entity_types::WORKFLOW,
+            entity_types::ALIAS_PATTERN,
+            entity_types::PASSWORD_POLICY,
         ]
         .into_iter()
         .collect();
@@ -1298,6 +1679,47 @@ parents:
         assert!(tag_parents.is_empty());
     }
 
+    #[test]
+    fn deserialize_entity_workflow() {
+        let yaml = "id: simple\nlabel: Simple\ninitial_state: draft\nstates:\n  - id: draft\n    label: Draft\n  - id: live\n    label: Live\n    published: true\n    publishable: true\ntransitions:\n  - from: draft\n    to: live\nitem_types: [page]\n";
+        let (entity, tag_parents) = deserialize_entity("workflow", yaml).unwrap();
+        assert_eq!(entity.entity_type(), "workflow");
+        assert_eq!(entity.id(), "simple");
+        assert!(tag_parents.is_empty());
+
+        let dangling = yaml.replace("to: live", "to: gone");
+        assert!(deserialize_entity("workflow", &dangling).is_err());
+    }
+
+    #[test]
+    fn deserialize_entity_alias_pattern() {
+        let yaml =
+            "id: story\nitem_type: story\npattern: stories/[field_topic:name]/[title-slug]\n";
+        let (entity, _) = deserialize_entity("alias_pattern", yaml).unwrap();
+        assert_eq!(entity.entity_type(), "alias_pattern");
+        assert_eq!(entity.id(), "story");
+
+        let unknown_token = yaml.replace("[title-slug]", "[author]");
+        assert!(deserialize_entity("alias_pattern", &unknown_token).is_err());
+    }
+
+    #[test]
+    fn deserialize_entity_password_policy() {
+        let yaml = "id: editors\nlabel: Editors\nroles:\n  - 00000000-0000-0000-0000-000000000002\n\
+                    min_length: 16\nrequire_symbol: true\nhistory_count: 5\n";
+        let (entity, _) = deserialize_entity("password_policy", yaml).unwrap();
+        assert_eq!(entity.entity_type(), "password_policy");
+        assert_eq!(entity.id(), "editors");
+        let ConfigEntity::PasswordPolicy(policy) = entity else {
+            panic!("expected a password policy");
+        };
+        assert!(policy.require_symbol && !policy.breach_check);
+        assert_eq!(policy.max_age_days, 0);
+
+        let too_short = yaml.replace("min_length: 16", "min_length: 6");
+        assert!(deserialize_entity("password_policy", &too_short).is_err());
+    }
+
     #[test]
     fn deserialize_entity_rejects_empty_variable_key() {
         let yaml = "key: \"\"\nvalue: test\n";
@@ -1671,4 +2093,140 @@ parents:
         assert_eq!(vars[0].filename, "variable.aaa_first.yml");
         assert_eq!(vars[1].filename, "variable.zzz_last.yml");
     }
+
+    // ── Filters and diffs ──────────────────────────────────────────
+
+    fn variable(key: &str, value: serde_json::Value) -> ConfigEntity {
+        ConfigEntity::Variable {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    fn parsed_variable(key: &str, value: serde_json::Value) -> ParsedEntity {
+        ParsedEntity {
+            filename: entity_filename(entity_types::VARIABLE, key),
+            entity: variable(key, value),
+            tag_parents: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn config_filter_only_and_skip() {
+        let filter = ConfigFilter::new(
+            &["variable".to_string(), "role:editor".to_string()],
+            &["variable:secret".to_string()],
+        )
+        .unwrap();
+
+        assert!(filter.includes("variable", "site_name"));
+        assert!(!filter.includes("variable", "secret"));
+        assert!(filter.includes("role", "editor"));
+        assert!(!filter.includes("role", "admin"));
+        assert!(!filter.includes("item_type", "blog"));
+        assert!(filter.includes_type("role"));
+        assert!(!filter.includes_type("item_type"));
+
+        let everything = ConfigFilter::new(&[], &[]).unwrap();
+        assert!(everything.is_empty());
+        assert!(everything.includes("item_type", "blog"));
+    }
+
+    #[test]
+    fn config_filter_rejects_bad_rules() {
+        assert!(ConfigFilter::new(&["widget".to_string()], &[]).is_err());
+        assert!(ConfigFilter::new(&[], &["role:".to_string()]).is_err());
+    }
+
+    #[test]
+    fn diff_values_reports_nested_properties() {
+        let database = serde_json::json!({
+            "label": "Blog",
+            "settings": {"pathauto": "/blog/[title]", "preview": true},
+            "gone": 1,
+        });
+        let file = serde_json::json!({
+            "label": "Articles",
+            "settings": {"pathauto": "/blog/[title]", "revisions": true},
+            "gone": 1,
+        });
+        let mut out = Vec::new();
+        diff_values("", &database, &file, &mut out);
+
+        let summary: Vec<(&str, DiffKind)> =
+            out.iter().map(|p| (p.path.as_str(), p.kind())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("label", DiffKind::Changed),
+                ("settings.preview", DiffKind::Removed),
+                ("settings.revisions", DiffKind::Added),
+            ]
+        );
+        assert_eq!(out[0].file, Some(serde_json::json!("Articles")));
+    }
+
+    #[test]
+    fn diff_entity_type_classifies_entities() {
+        let files = vec![
+            parsed_variable("new_key", serde_json::json!(1)),
+            parsed_variable("same", serde_json::json!("x")),
+            parsed_variable("site_name", serde_json::json!("New")),
+        ];
+        let stored = vec![
+            (variable("same", serde_json::json!("x")), Vec::new()),
+            (variable("site_name", serde_json::json!("Old")), Vec::new()),
+            (variable("stale", serde_json::json!(true)), Vec::new()),
+        ];
+
+        let mut diff = ConfigDiff::default();
+        diff_entity_type(entity_types::VARIABLE, &files, stored, &mut diff);
+
+        assert_eq!(diff.unchanged, 1);
+        assert!(diff.warnings.is_empty(), "{:?}", diff.warnings);
+        let summary: Vec<(&str, DiffKind)> = diff
+            .entities
+            .iter()
+            .map(|e| (e.id.as_str(), e.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("new_key", DiffKind::Added),
+                ("site_name", DiffKind::Changed),
+                ("stale", DiffKind::Removed),
+            ]
+        );
+        assert_eq!(diff.entities[1].properties.len(), 1);
+        assert_eq!(diff.entities[1].properties[0].path, "value");
+        assert_eq!(diff.count(DiffKind::Changed), 1);
+    }
+
+    #[tokio::test]
+    async fn filter_drops_excluded_files() {
+        let dir = TestDir::new("filter");
+        tokio::fs::write(dir.join("variable.a.yml"), "key: a\nvalue: 1\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("variable.b.yml"), "key: b\nvalue: 2\n")
+            .await
+            .unwrap();
+        tokio::fs::write(
+            dir.join("category.topics.yml"),
+            "id: topics\nlabel: Topics\ndescription: null\nhierarchy: 0\nweight: 0\n",
+        )
+        .await
+        .unwrap();
+
+        let mut warnings = Vec::new();
+        let mut parsed = read_and_validate_files(&dir, &mut warnings).await.unwrap();
+        assert!(warnings.is_empty(), "unexpected warnings: {warnings:?}");
+        let filter =
+            ConfigFilter::new(&["variable".to_string()], &["variable:b".to_string()]).unwrap();
+
+        assert_eq!(filter.retain(&mut parsed), 2);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed["variable"].len(), 1);
+        assert_eq!(parsed["variable"][0].entity.id(), "a");
+    }
 }
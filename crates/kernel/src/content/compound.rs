@@ -614,6 +614,13 @@ mod tests {
                 "section_types": section_types,
             }),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: Default::default(),
         }
     }
 
@@ -915,6 +922,13 @@ mod tests {
             cardinality: 1,
             settings: serde_json::json!({}),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: Default::default(),
         }];
         let fields = serde_json::Map::new();
         let errors = validate_required_fields(&fields, &field_defs);
@@ -932,6 +946,13 @@ mod tests {
             cardinality: 1,
             settings: serde_json::json!({}),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: Default::default(),
         }];
         let mut fields = serde_json::Map::new();
         fields.insert("summary".to_string(), serde_json::json!("A summary"));
@@ -1055,6 +1076,13 @@ mod tests {
                 "section_types": [text_schema()],
             }),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: Default::default(),
         }];
         let mut fields = serde_json::Map::new();
         fields.insert(
@@ -1084,6 +1112,13 @@ mod tests {
                 "section_types": [text_schema()],
             }),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: Default::default(),
         }
     }
 
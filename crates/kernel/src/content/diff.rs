@@ -0,0 +1,612 @@
+//! Server-side HTML diffs between two versions of an item.
+//!
+//! Text fields (`Text`, `TextLong`, and the title) get a word-level diff
+//! wrapped in `<del>`/`<ins>`. Other field types get a structured diff:
+//! objects key by key, arrays element by element, scalars as old → new.
+//!
+//! All output is escaped: `Text` values are HTML-escaped and `TextLong`
+//! values have their markup stripped before diffing, so the fragment is
+//! safe to embed as-is. Alongside the HTML, [`DiffSummary`] gives a
+//! machine-readable list of what changed.
+
+use serde::Serialize;
+use serde_json::Value;
+use trovato_sdk::types::{FieldDefinition, FieldType};
+
+use crate::routes::helpers::html_escape;
+
+/// Above this many token pairs, a text change is shown as a whole-block
+/// replacement instead of a word-level diff (bounds LCS memory and time).
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// One side of a diff.
+#[derive(Debug, Clone, Copy)]
+pub struct DiffInput<'a> {
+    /// Item title.
+    pub title: &'a str,
+    /// Publication status (0 = unpublished, 1 = published).
+    pub status: i16,
+    /// Field values as stored in `item.fields`.
+    pub fields: &'a Value,
+}
+
+/// How a single field changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The field has a value only in the newer version.
+    Added,
+    /// The field has a value only in the older version.
+    Removed,
+    /// The field's value differs between the versions.
+    Changed,
+}
+
+/// Summary entry for one changed field.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Machine name of the field.
+    pub field_name: String,
+    /// Field label from the content type, or the machine name.
+    pub label: String,
+    /// How the field changed.
+    pub change: ChangeKind,
+    /// Words inserted (text fields only).
+    pub words_added: usize,
+    /// Words deleted (text fields only).
+    pub words_removed: usize,
+}
+
+/// Machine-readable summary of a diff.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DiffSummary {
+    /// Whether the title differs.
+    pub title_changed: bool,
+    /// Whether the publication status differs.
+    pub status_changed: bool,
+    /// Changed fields, in diff order.
+    pub fields: Vec<FieldChange>,
+    /// Total words inserted across the title and text fields.
+    pub words_added: usize,
+    /// Total words deleted across the title and text fields.
+    pub words_removed: usize,
+}
+
+impl DiffSummary {
+    /// Whether anything changed.
+    pub fn is_empty(&self) -> bool {
+        !self.title_changed && !self.status_changed && self.fields.is_empty()
+    }
+}
+
+/// A rendered diff: sanitized HTML fragment plus summary.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemDiff {
+    /// Escaped HTML fragment showing the changes.
+    pub html: String,
+    /// What changed, for clients that render their own view.
+    pub summary: DiffSummary,
+}
+
+/// Diff two versions of an item.
+///
+/// `before` is `None` when the item has no earlier version (e.g. a staged
+/// item with no live counterpart); everything is then shown as added.
+/// `field_defs` supplies labels, order, and field types; fields not in the
+/// definition are diffed structurally after the defined ones.
+pub fn diff_items(
+    before: Option<&DiffInput<'_>>,
+    after: &DiffInput<'_>,
+    field_defs: &[FieldDefinition],
+) -> ItemDiff {
+    let mut summary = DiffSummary::default();
+    let mut rows = String::new();
+
+    let old_title = before.map(|b| b.title);
+    if old_title != Some(after.title) {
+        let text = text_diff(
+            old_title.map(html_escape).as_deref(),
+            Some(&html_escape(after.title)),
+        );
+        summary.title_changed = true;
+        summary.words_added += text.added;
+        summary.words_removed += text.removed;
+        push_row(&mut rows, "title", "Title", &text.html);
+    }
+
+    let old_status = before.map(|b| b.status);
+    if old_status != Some(after.status) {
+        summary.status_changed = true;
+        let html = scalar_diff(
+            old_status.map(status_label).as_deref(),
+            Some(&status_label(after.status)),
+        );
+        push_row(&mut rows, "status", "Status", &html);
+    }
+
+    let empty = serde_json::Map::new();
+    let old_fields = before.and_then(|b| b.fields.as_object()).unwrap_or(&empty);
+    let new_fields = after.fields.as_object().unwrap_or(&empty);
+
+    // Defined fields in definition order, then any others by name.
+    let mut names: Vec<(&str, &str, Option<&FieldType>)> = field_defs
+        .iter()
+        .map(|d| (d.field_name.as_str(), d.label.as_str(), Some(&d.field_type)))
+        .collect();
+    let mut extra: Vec<&str> = old_fields
+        .keys()
+        .chain(new_fields.keys())
+        .map(String::as_str)
+        .filter(|k| !field_defs.iter().any(|d| d.field_name == *k))
+        .collect();
+    extra.sort_unstable();
+    extra.dedup();
+    names.extend(extra.into_iter().map(|k| (k, k, None)));
+
+    for (name, label, field_type) in names {
+        let old = old_fields.get(name).filter(|v| !v.is_null());
+        let new = new_fields.get(name).filter(|v| !v.is_null());
+        if old == new {
+            continue;
+        }
+
+        let change = match (old, new) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        };
+
+        let text = match field_type {
+            Some(FieldType::Text { .. }) => text_pair(old, new, html_escape),
+            Some(FieldType::TextLong) => text_pair(old, new, strip_markup),
+            _ => None,
+        };
+
+        let (html, words_added, words_removed) = match text {
+            Some((old_text, new_text)) => {
+                let text = text_diff(old_text.as_deref(), new_text.as_deref());
+                (text.html, text.added, text.removed)
+            }
+            None => (structured_diff(old, new), 0, 0),
+        };
+
+        summary.words_added += words_added;
+        summary.words_removed += words_removed;
+        summary.fields.push(FieldChange {
+            field_name: name.to_string(),
+            label: label.to_string(),
+            change,
+            words_added,
+            words_removed,
+        });
+        push_row(&mut rows, name, label, &html);
+    }
+
+    let html = if summary.is_empty() {
+        r#"<p class="item-diff-empty">No changes.</p>"#.to_string()
+    } else {
+        format!(r#"<table class="item-diff"><tbody>{rows}</tbody></table>"#)
+    };
+
+    ItemDiff { html, summary }
+}
+
+fn push_row(rows: &mut String, name: &str, label: &str, html: &str) {
+    rows.push_str(&format!(
+        r#"<tr data-field="{}"><th>{}</th><td>{html}</td></tr>"#,
+        html_escape(name),
+        html_escape(label)
+    ));
+}
+
+fn status_label(status: i16) -> String {
+    match status {
+        1 => "Published".to_string(),
+        0 => "Unpublished".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Plain text of a text field value: a string or `{"value": "..."}`.
+fn text_value(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Object(obj) => obj.get("value").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Safe text for both sides of a text field, or `None` if either side is
+/// not text-shaped (then the field falls back to a structured diff).
+fn text_pair(
+    old: Option<&Value>,
+    new: Option<&Value>,
+    make_safe: impl Fn(&str) -> String,
+) -> Option<(Option<String>, Option<String>)> {
+    let side = |v: Option<&Value>| match v {
+        None => Some(None),
+        Some(v) => text_value(v).map(|s| Some(make_safe(s))),
+    };
+    Some((side(old)?, side(new)?))
+}
+
+/// Strip markup, leaving escaped text. Tags become word breaks so that
+/// adjacent paragraphs don't run together; whitespace is then collapsed.
+fn strip_markup(html: &str) -> String {
+    ammonia::Builder::empty()
+        .clean(&html.replace('<', " <"))
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+struct TextDiff {
+    html: String,
+    added: usize,
+    removed: usize,
+}
+
+/// Word-level diff of two already-escaped strings.
+fn text_diff(old: Option<&str>, new: Option<&str>) -> TextDiff {
+    let old_tokens = tokenize(old.unwrap_or(""));
+    let new_tokens = tokenize(new.unwrap_or(""));
+    let ops = diff_sequence(&old_tokens, &new_tokens);
+
+    let mut html = String::new();
+    let mut added = 0;
+    let mut removed = 0;
+    let mut run = String::new();
+    let mut run_kind: Option<Op> = None;
+
+    let flush = |html: &mut String, run: &mut String, kind: Option<Op>| {
+        if run.is_empty() {
+            return;
+        }
+        match kind {
+            Some(Op::Delete(_)) => html.push_str(&format!("<del>{run}</del>")),
+            Some(Op::Insert(_)) => html.push_str(&format!("<ins>{run}</ins>")),
+            _ => html.push_str(run),
+        }
+        run.clear();
+    };
+
+    for op in ops {
+        let token = match op {
+            Op::Equal(i, _) | Op::Delete(i) => old_tokens[i],
+            Op::Insert(j) => new_tokens[j],
+        };
+        let is_word = !token.trim().is_empty();
+        match op {
+            Op::Delete(_) if is_word => removed += 1,
+            Op::Insert(_) if is_word => added += 1,
+            _ => {}
+        }
+        if run_kind.as_ref().map(std::mem::discriminant) != Some(std::mem::discriminant(&op)) {
+            flush(&mut html, &mut run, run_kind);
+            run_kind = Some(op);
+        }
+        run.push_str(token);
+    }
+    flush(&mut html, &mut run, run_kind);
+
+    TextDiff {
+        html,
+        added,
+        removed,
+    }
+}
+
+/// Split text into alternating word and whitespace tokens.
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space: Option<bool> = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|s| s != space) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// One step of an edit script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// Same element at (old index, new index).
+    Equal(usize, usize),
+    /// Old element removed.
+    Delete(usize),
+    /// New element inserted.
+    Insert(usize),
+}
+
+/// Edit script between two sequences via longest common subsequence.
+///
+/// The common prefix and suffix are trimmed first; if the remaining middle
+/// is too large, it is reported as a full delete + insert.
+fn diff_sequence<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Op> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+    let (n, m) = (a_mid.len(), b_mid.len());
+
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        ops.extend((0..n).map(|i| Op::Delete(prefix + i)));
+        ops.extend((0..m).map(|j| Op::Insert(prefix + j)));
+    } else {
+        // lcs[i][j] = LCS length of a_mid[i..] and b_mid[j..].
+        let width = m + 1;
+        let mut lcs = vec![0u32; (n + 1) * width];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * width + j] = if a_mid[i] == b_mid[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && a_mid[i] == b_mid[j] {
+                ops.push(Op::Equal(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+                // Prefer deletions on ties so removed text precedes its replacement.
+                ops.push(Op::Delete(prefix + i));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(prefix + j));
+                j += 1;
+            }
+        }
+    }
+
+    let (a_tail, b_tail) = (a.len() - suffix, b.len() - suffix);
+    ops.extend((0..suffix).map(|k| Op::Equal(a_tail + k, b_tail + k)));
+    ops
+}
+
+/// Structured diff for non-text values.
+fn structured_diff(old: Option<&Value>, new: Option<&Value>) -> String {
+    match (old, new) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+
+            let mut html = String::from(r#"<dl class="diff-object">"#);
+            for key in keys {
+                let (x, y) = (a.get(key), b.get(key));
+                if x == y {
+                    continue;
+                }
+                html.push_str(&format!(
+                    "<dt>{}</dt><dd>{}</dd>",
+                    html_escape(key),
+                    structured_diff(x, y)
+                ));
+            }
+            html.push_str("</dl>");
+            html
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            let mut html = String::from(r#"<ul class="diff-list">"#);
+            for op in diff_sequence(a, b) {
+                match op {
+                    Op::Equal(..) => {}
+                    Op::Delete(i) => html.push_str(&format!(
+                        r#"<li class="diff-removed"><del>{}</del></li>"#,
+                        html_escape(&display_value(&a[i]))
+                    )),
+                    Op::Insert(j) => html.push_str(&format!(
+                        r#"<li class="diff-added"><ins>{}</ins></li>"#,
+                        html_escape(&display_value(&b[j]))
+                    )),
+                }
+            }
+            html.push_str("</ul>");
+            html
+        }
+        _ => scalar_diff(
+            old.map(display_value).as_deref(),
+            new.map(display_value).as_deref(),
+        ),
+    }
+}
+
+/// `old → new` for unescaped display strings.
+fn scalar_diff(old: Option<&str>, new: Option<&str>) -> String {
+    match (old, new) {
+        (Some(old), Some(new)) => format!(
+            "<del>{}</del> &rarr; <ins>{}</ins>",
+            html_escape(old),
+            html_escape(new)
+        ),
+        (Some(old), None) => format!("<del>{}</del>", html_escape(old)),
+        (None, Some(new)) => format!("<ins>{}</ins>", html_escape(new)),
+        (None, None) => String::new(),
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(name: &str, field_type: FieldType) -> FieldDefinition {
+        FieldDefinition {
+            label: name.to_uppercase(),
+            ..FieldDefinition::new(name, field_type)
+        }
+    }
+
+    #[test]
+    fn word_diff_marks_changed_words() {
+        let diff = text_diff(Some("the quick brown fox"), Some("the slow brown fox"));
+        assert_eq!(diff.html, "the <del>quick</del><ins>slow</ins> brown fox");
+        assert_eq!((diff.added, diff.removed), (1, 1));
+    }
+
+    #[test]
+    fn word_diff_groups_runs() {
+        let diff = text_diff(Some("a b"), Some("a x y b"));
+        assert_eq!(diff.html, "a <ins>x y </ins>b");
+        assert_eq!((diff.added, diff.removed), (2, 0));
+    }
+
+    #[test]
+    fn identical_items_have_no_changes() {
+        let fields = json!({"field_body": "same"});
+        let input = DiffInput {
+            title: "T",
+            status: 1,
+            fields: &fields,
+        };
+        let diff = diff_items(Some(&input), &input, &[]);
+        assert!(diff.summary.is_empty());
+        assert!(diff.html.contains("No changes"));
+    }
+
+    #[test]
+    fn text_fields_are_escaped() {
+        let defs = [field(
+            "field_subtitle",
+            FieldType::Text { max_length: None },
+        )];
+        let old = json!({"field_subtitle": "a <b>"});
+        let new = json!({"field_subtitle": "a <script>"});
+        let diff = diff_items(
+            Some(&DiffInput {
+                title: "T",
+                status: 1,
+                fields: &old,
+            }),
+            &DiffInput {
+                title: "T",
+                status: 1,
+                fields: &new,
+            },
+            &defs,
+        );
+        assert!(!diff.html.contains("<script>"));
+        assert!(diff.html.contains("&lt;script&gt;"));
+        assert_eq!(diff.summary.fields[0].change, ChangeKind::Changed);
+        assert_eq!(diff.summary.fields[0].label, "FIELD_SUBTITLE");
+    }
+
+    #[test]
+    fn long_text_markup_is_stripped() {
+        let defs = [field("field_body", FieldType::TextLong)];
+        let old = json!({"field_body": {"value": "<p>Hello world</p>", "format": "filtered_html"}});
+        let new = json!({"field_body": {"value": "<p>Hello there</p><script>x()</script>", "format": "filtered_html"}});
+        let diff = diff_items(
+            Some(&DiffInput {
+                title: "T",
+                status: 1,
+                fields: &old,
+            }),
+            &DiffInput {
+                title: "T",
+                status: 1,
+                fields: &new,
+            },
+            &defs,
+        );
+        assert!(!diff.html.contains("<p>"));
+        assert!(!diff.html.contains("script"));
+        assert!(diff.html.contains("<del>world</del>"));
+        assert!(diff.html.contains("<ins>there</ins>"));
+    }
+
+    #[test]
+    fn structured_fields_and_status() {
+        let defs = [field("field_count", FieldType::Integer)];
+        let old = json!({"field_count": 3, "field_tags": ["a", "b"]});
+        let new = json!({"field_count": 4, "field_tags": ["a", "c"]});
+        let diff = diff_items(
+            Some(&DiffInput {
+                title: "T",
+                status: 0,
+                fields: &old,
+            }),
+            &DiffInput {
+                title: "T",
+                status: 1,
+                fields: &new,
+            },
+            &defs,
+        );
+        assert!(diff.summary.status_changed);
+        assert!(!diff.summary.title_changed);
+        let names: Vec<_> = diff
+            .summary
+            .fields
+            .iter()
+            .map(|f| f.field_name.as_str())
+            .collect();
+        assert_eq!(names, ["field_count", "field_tags"]);
+        assert!(diff.html.contains("<del>3</del> &rarr; <ins>4</ins>"));
+        assert!(
+            diff.html
+                .contains(r#"<li class="diff-removed"><del>b</del></li>"#)
+        );
+        assert!(
+            diff.html
+                .contains(r#"<li class="diff-added"><ins>c</ins></li>"#)
+        );
+    }
+
+    #[test]
+    fn missing_before_shows_everything_added() {
+        let fields = json!({"field_x": true});
+        let diff = diff_items(
+            None,
+            &DiffInput {
+                title: "New item",
+                status: 1,
+                fields: &fields,
+            },
+            &[],
+        );
+        assert!(diff.summary.title_changed);
+        assert_eq!(diff.summary.words_added, 2);
+        assert_eq!(diff.summary.fields[0].change, ChangeKind::Added);
+    }
+
+    #[test]
+    fn oversized_text_falls_back_to_replace() {
+        let a: Vec<u32> = (0..3000).collect();
+        let b: Vec<u32> = (5000..8000).collect();
+        let ops = diff_sequence(&a, &b);
+        assert_eq!(ops.len(), 6000);
+        assert!(ops[..3000].iter().all(|op| matches!(op, Op::Delete(_))));
+    }
+}
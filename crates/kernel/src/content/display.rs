@@ -0,0 +1,317 @@
+//! Field display for item pages and teasers.
+//!
+//! Applies the per-view-mode display settings declared on field
+//! definitions ([`FieldDefinition::display`]): which fields are shown, in
+//! what order, with which label and formatter, and how they are wrapped in
+//! field groups. Fields without display settings keep the built-in
+//! rendering of the route that displays them.
+
+use serde_json::{Map, Value};
+use trovato_sdk::types::{
+    ContentTypeDefinition, FieldDefinition, FieldFormatter, FieldGroup, FormatterType,
+    LabelDisplay, ViewMode,
+};
+
+use crate::content::FilterPipeline;
+use crate::routes::helpers::html_escape;
+
+/// Default length of the `trimmed` formatter, in characters.
+const DEFAULT_TRIM_LENGTH: usize = 200;
+
+/// An item field selected for display.
+#[derive(Debug)]
+pub struct DisplayField<'a> {
+    pub name: &'a str,
+    pub value: &'a Value,
+    /// The field's definition, if the content type declares it.
+    pub definition: Option<&'a FieldDefinition>,
+    /// Formatter from the field's display settings; `None` when it has
+    /// none and the caller's built-in rendering applies.
+    pub formatter: Option<FieldFormatter>,
+}
+
+/// Select and order the fields of an item for a view mode.
+///
+/// Declared fields hidden in `mode` are left out; the rest are ordered by
+/// formatter weight, ties keeping the definition order. In full view,
+/// fields the content type does not declare follow in stored order; teasers
+/// only show declared fields.
+pub fn fields_to_display<'a>(
+    content_type: Option<&'a ContentTypeDefinition>,
+    fields: &'a Map<String, Value>,
+    mode: ViewMode,
+) -> Vec<DisplayField<'a>> {
+    let definitions = content_type
+        .map(|ct| ct.fields.as_slice())
+        .unwrap_or_default();
+
+    let mut declared: Vec<(i32, DisplayField<'a>)> = definitions
+        .iter()
+        .filter_map(|def| {
+            let formatter = def.formatter(mode)?;
+            let (name, value) = fields.get_key_value(&def.field_name)?;
+            let weight = formatter.weight;
+            let field = DisplayField {
+                name,
+                value,
+                definition: Some(def),
+                formatter: def.display.is_some().then_some(formatter),
+            };
+            Some((weight, field))
+        })
+        .collect();
+    declared.sort_by_key(|(weight, _)| *weight);
+
+    let mut displayed: Vec<DisplayField<'a>> = declared.into_iter().map(|(_, f)| f).collect();
+    if mode == ViewMode::Full {
+        displayed.extend(
+            fields
+                .iter()
+                .filter(|(name, _)| !definitions.iter().any(|d| d.field_name == **name))
+                .map(|(name, value)| DisplayField {
+                    name,
+                    value,
+                    definition: None,
+                    formatter: None,
+                }),
+        );
+    }
+    displayed
+}
+
+/// Format a field value as safe HTML.
+///
+/// Returns `None` for structured values (blocks, compounds, references)
+/// that formatters do not handle.
+pub fn format_value(value: &Value, formatter: &FieldFormatter) -> Option<String> {
+    let (text, format) = match value {
+        Value::Object(obj) => (
+            scalar_text(obj.get("value")?)?,
+            obj.get("format")
+                .and_then(|f| f.as_str())
+                .unwrap_or("plain_text"),
+        ),
+        other => (scalar_text(other)?, "plain_text"),
+    };
+    let html = FilterPipeline::for_format_safe(format).process(&text);
+
+    match formatter.formatter {
+        FormatterType::Default => Some(html),
+        FormatterType::Plain => Some(strip_tags(&html)),
+        FormatterType::Trimmed => {
+            let length = formatter
+                .settings
+                .get("trim_length")
+                .and_then(|v| v.as_u64())
+                .map_or(DEFAULT_TRIM_LENGTH, |n| n as usize);
+            Some(trim_text(&strip_tags(&html), length))
+        }
+        // Needs the file and image style services; rendered by the route.
+        FormatterType::ResponsiveImage | FormatterType::Hidden => None,
+    }
+}
+
+/// Wrap formatted field HTML with its label.
+pub fn render_field(
+    definition: &FieldDefinition,
+    formatter: &FieldFormatter,
+    html: &str,
+) -> String {
+    let name = html_escape(&definition.field_name);
+    let label = html_escape(&definition.label);
+    match formatter.label {
+        LabelDisplay::Above => format!(
+            "<div class=\"field field-{name} field--label-above\"><div class=\"field__label\">{label}</div><div class=\"field__item\">{html}</div></div>"
+        ),
+        LabelDisplay::Inline => format!(
+            "<div class=\"field field-{name} field--label-inline\"><strong class=\"field__label\">{label}</strong>: {html}</div>"
+        ),
+        LabelDisplay::Hidden => format!("<div class=\"field field-{name}\">{html}</div>"),
+    }
+}
+
+/// Join rendered fields, wrapping grouped ones in their field group.
+///
+/// `rendered` pairs each field name with its HTML, in display order. A
+/// group is placed where its first field appears.
+pub fn group_fields(
+    content_type: Option<&ContentTypeDefinition>,
+    rendered: &[(&str, String)],
+) -> String {
+    let group_of = |name: &str| {
+        let ct = content_type?;
+        let group = ct
+            .fields
+            .iter()
+            .find(|f| f.field_name == name)?
+            .field_group
+            .as_deref()?;
+        ct.field_groups.iter().find(|g| g.name == group)
+    };
+
+    let mut slots: Vec<(Option<&FieldGroup>, String)> = Vec::new();
+    for (name, html) in rendered {
+        match group_of(name) {
+            Some(group) => {
+                match slots
+                    .iter_mut()
+                    .find(|(g, _)| g.is_some_and(|g| g.name == group.name))
+                {
+                    Some((_, slot)) => slot.push_str(html),
+                    None => slots.push((Some(group), html.clone())),
+                }
+            }
+            None => slots.push((None, html.clone())),
+        }
+    }
+
+    slots
+        .into_iter()
+        .map(|(group, html)| match group {
+            Some(group) => format!(
+                "<div class=\"field-group field-group--{}\"><h3 class=\"field-group__label\">{}</h3>{html}</div>",
+                html_escape(&group.name),
+                html_escape(&group.label)
+            ),
+            None => html,
+        })
+        .collect()
+}
+
+/// Text of a scalar JSON value.
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Drop all tags from sanitized HTML, keeping its (escaped) text.
+fn strip_tags(html: &str) -> String {
+    ammonia::Builder::default()
+        .tags(std::collections::HashSet::new())
+        .clean(html)
+        .to_string()
+}
+
+/// Cut escaped text to at most `length` characters, adding an ellipsis.
+///
+/// Never cuts inside a character entity.
+fn trim_text(text: &str, length: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= length {
+        return text.to_string();
+    }
+    let mut trimmed: String = text.chars().take(length).collect();
+    if let Some(amp) = trimmed.rfind('&')
+        && !trimmed[amp..].contains(';')
+    {
+        trimmed.truncate(amp);
+    }
+    format!("{}…", trimmed.trim_end())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use trovato_sdk::types::{FieldDisplay, FieldType};
+
+    fn content_type() -> ContentTypeDefinition {
+        ContentTypeDefinition {
+            machine_name: "event".to_string(),
+            label: "Event".to_string(),
+            description: String::new(),
+            title_label: None,
+            fields: vec![
+                FieldDefinition::new("field_body", FieldType::TextLong),
+                FieldDefinition::new("field_venue", FieldType::Text { max_length: None })
+                    .label("Venue")
+                    .group("details")
+                    .display(FieldDisplay {
+                        full: FieldFormatter::default().weight(-1),
+                        teaser: FieldFormatter::new(FormatterType::Plain),
+                    }),
+                FieldDefinition::new("field_secret", FieldType::Text { max_length: None }).display(
+                    FieldDisplay {
+                        full: FieldFormatter::hidden(),
+                        teaser: FieldFormatter::hidden(),
+                    },
+                ),
+            ],
+            cache: None,
+            field_groups: vec![FieldGroup {
+                name: "details".to_string(),
+                label: "Details".to_string(),
+                weight: 0,
+                collapsed: false,
+            }],
+            extends: None,
+            template: false,
+        }
+    }
+
+    fn names<'a>(fields: &[DisplayField<'a>]) -> Vec<&'a str> {
+        fields.iter().map(|f| f.name).collect()
+    }
+
+    #[test]
+    fn full_view_orders_by_weight_and_skips_hidden() {
+        let ct = content_type();
+        let fields = json!({
+            "field_body": {"value": "Hi"},
+            "field_secret": "s",
+            "field_venue": "Hall",
+            "legacy": "x",
+        });
+        let fields = fields.as_object().unwrap();
+
+        let full = fields_to_display(Some(&ct), fields, ViewMode::Full);
+        assert_eq!(names(&full), ["field_venue", "field_body", "legacy"]);
+        assert!(full[0].formatter.is_some());
+        assert!(full[1].formatter.is_none());
+
+        let teaser = fields_to_display(Some(&ct), fields, ViewMode::Teaser);
+        assert_eq!(names(&teaser), ["field_venue"]);
+    }
+
+    #[test]
+    fn formatters_strip_and_trim() {
+        let value = json!({"value": "<p>Tom &amp; <b>Jerry</b></p>", "format": "filtered_html"});
+        let plain = format_value(&value, &FieldFormatter::new(FormatterType::Plain)).unwrap();
+        assert_eq!(plain, "Tom &amp; Jerry");
+
+        let mut trimmed = FieldFormatter::new(FormatterType::Trimmed);
+        trimmed.settings = json!({"trim_length": 5});
+        assert_eq!(format_value(&value, &trimmed).unwrap(), "Tom…");
+
+        assert_eq!(
+            format_value(&json!([1, 2]), &FieldFormatter::default()),
+            None
+        );
+        assert_eq!(
+            format_value(&json!(42), &FieldFormatter::default()).unwrap(),
+            "42"
+        );
+    }
+
+    #[test]
+    fn group_fields_wraps_grouped_fields() {
+        let ct = content_type();
+        let html = group_fields(
+            Some(&ct),
+            &[
+                ("field_venue", "<v>".to_string()),
+                ("field_body", "<b>".to_string()),
+            ],
+        );
+        assert_eq!(
+            html,
+            "<div class=\"field-group field-group--details\"><h3 class=\"field-group__label\">Details</h3><v></div><b>"
+        );
+    }
+}
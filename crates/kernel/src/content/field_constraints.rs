@@ -0,0 +1,258 @@
+//! Field default values and value constraints.
+//!
+//! Plugins declare a `default_value` and [`FieldConstraints`] on their
+//! field definitions. [`ItemService`](super::ItemService) fills in defaults
+//! when an item is created and refuses saves whose values break a
+//! constraint, with one [`ItemViolation`] per offending field.
+
+use regex::Regex;
+use serde_json::{Map, Value};
+use tracing::warn;
+use trovato_sdk::types::{FieldConstraints, FieldDefinition, FieldType};
+
+use super::ItemViolation;
+
+/// Fill fields that are absent or empty with their declared default.
+///
+/// Returns the names of the fields that were filled in.
+pub fn apply_default_values(
+    fields: &mut Map<String, Value>,
+    definitions: &[FieldDefinition],
+) -> Vec<String> {
+    let mut applied = Vec::new();
+    for def in definitions {
+        let Some(ref default) = def.default_value else {
+            continue;
+        };
+        if fields.get(&def.field_name).is_some_and(|v| !is_empty(v)) {
+            continue;
+        }
+        fields.insert(def.field_name.clone(), default.clone());
+        applied.push(def.field_name.clone());
+    }
+    applied
+}
+
+/// Store unchecked checkboxes of boolean fields that have a default.
+///
+/// Browsers leave unchecked checkboxes out of the submission, which would
+/// otherwise let [`apply_default_values`] check them again.
+pub fn fill_unchecked_booleans(fields: &mut Map<String, Value>, definitions: &[FieldDefinition]) {
+    for def in definitions {
+        if matches!(def.field_type, FieldType::Boolean)
+            && def.default_value.is_some()
+            && !fields.contains_key(&def.field_name)
+        {
+            fields.insert(def.field_name.clone(), Value::Bool(false));
+        }
+    }
+}
+
+/// Check field values against their constraints.
+///
+/// Only fields in `only` are checked when it is given. Empty values pass;
+/// `required` covers them.
+pub fn validate_constraints(
+    fields: &Map<String, Value>,
+    definitions: &[FieldDefinition],
+    only: Option<&[String]>,
+) -> Vec<ItemViolation> {
+    let mut violations = Vec::new();
+    for def in definitions {
+        if def.constraints.is_empty() || only.is_some_and(|names| !names.contains(&def.field_name))
+        {
+            continue;
+        }
+        let Some(value) = fields.get(&def.field_name) else {
+            continue;
+        };
+        let values: Vec<&Value> = match value {
+            Value::Array(items) => items.iter().collect(),
+            single => vec![single],
+        };
+        if let Some(violation) = values
+            .into_iter()
+            .filter_map(scalar)
+            .filter(|v| !v.is_empty())
+            .find_map(|v| check_value(def, &v))
+        {
+            violations.push(violation);
+        }
+    }
+    violations
+}
+
+/// Check one non-empty value, returning the first constraint it breaks.
+fn check_value(def: &FieldDefinition, value: &str) -> Option<ItemViolation> {
+    let FieldConstraints {
+        min,
+        max,
+        allowed_values,
+        pattern,
+    } = &def.constraints;
+    let label = &def.label;
+    let violation = |code: &str, message: String| ItemViolation {
+        field: def.field_name.clone(),
+        message,
+        code: code.to_string(),
+    };
+
+    if matches!(def.field_type, FieldType::Integer | FieldType::Float)
+        && let Ok(number) = value.parse::<f64>()
+    {
+        if let Some(min) = min
+            && number < *min
+        {
+            return Some(violation(
+                "below_min",
+                format!("{label} must be at least {min}."),
+            ));
+        }
+        if let Some(max) = max
+            && number > *max
+        {
+            return Some(violation(
+                "above_max",
+                format!("{label} must be at most {max}."),
+            ));
+        }
+    }
+
+    if !allowed_values.is_empty() && !allowed_values.iter().any(|v| v == value) {
+        return Some(violation(
+            "not_allowed",
+            format!("{label} must be one of: {}.", allowed_values.join(", ")),
+        ));
+    }
+
+    if let Some(pattern) = pattern {
+        match Regex::new(&format!("^(?:{pattern})$")) {
+            Ok(re) if !re.is_match(value) => {
+                return Some(violation(
+                    "pattern_mismatch",
+                    format!("{label} is not in the expected format."),
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                field = %def.field_name,
+                error = %e,
+                "ignoring invalid field pattern"
+            ),
+        }
+    }
+
+    None
+}
+
+/// A field value as a string: plain scalars, or the `value` of a
+/// `{"value": ...}` object. Other values have nothing to constrain.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Object(obj) => obj.get("value").and_then(scalar),
+        _ => None,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definitions() -> Vec<FieldDefinition> {
+        vec![
+            FieldDefinition::new("state", FieldType::Text { max_length: None })
+                .label("State")
+                .allowed_values(&["online", "offline"])
+                .default_value("offline"),
+            FieldDefinition::new("port", FieldType::Integer)
+                .label("Port")
+                .min(1.0)
+                .max(65535.0),
+            FieldDefinition::new("mac", FieldType::Text { max_length: None })
+                .label("MAC")
+                .pattern("[0-9a-f]{2}(:[0-9a-f]{2}){5}"),
+            FieldDefinition::new("notify", FieldType::Boolean).default_value(true),
+        ]
+    }
+
+    fn codes(fields: Value) -> Vec<(String, String)> {
+        validate_constraints(fields.as_object().unwrap(), &definitions(), None)
+            .into_iter()
+            .map(|v| (v.field, v.code))
+            .collect()
+    }
+
+    #[test]
+    fn valid_and_empty_values_pass() {
+        assert!(
+            codes(json!({
+                "state": "online",
+                "port": "8080",
+                "mac": {"value": "aa:bb:cc:dd:ee:ff"},
+            }))
+            .is_empty()
+        );
+        assert!(codes(json!({"state": "", "port": null, "mac": " "})).is_empty());
+    }
+
+    #[test]
+    fn each_constraint_is_enforced() {
+        let found = codes(json!({
+            "state": "sleeping",
+            "port": 70000,
+            "mac": "aa:bb:cc:dd:ee:ff:00",
+        }));
+        assert_eq!(
+            found,
+            vec![
+                ("state".to_string(), "not_allowed".to_string()),
+                ("port".to_string(), "above_max".to_string()),
+                ("mac".to_string(), "pattern_mismatch".to_string()),
+            ]
+        );
+        assert_eq!(
+            codes(json!({"port": [80, 0]})),
+            vec![("port".to_string(), "below_min".to_string())]
+        );
+    }
+
+    #[test]
+    fn only_listed_fields_are_checked() {
+        let fields = json!({"state": "sleeping", "port": 0});
+        let only = vec!["port".to_string()];
+        let found = validate_constraints(fields.as_object().unwrap(), &definitions(), Some(&only));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].field, "port");
+        assert_eq!(found[0].message, "Port must be at least 1.");
+    }
+
+    #[test]
+    fn defaults_fill_missing_fields_only() {
+        let mut fields = json!({"state": "online"}).as_object().unwrap().clone();
+        let applied = apply_default_values(&mut fields, &definitions());
+        assert_eq!(applied, vec!["notify".to_string()]);
+        assert_eq!(fields["state"], "online");
+        assert_eq!(fields["notify"], true);
+
+        let mut fields = json!({"state": ""}).as_object().unwrap().clone();
+        fill_unchecked_booleans(&mut fields, &definitions());
+        apply_default_values(&mut fields, &definitions());
+        assert_eq!(fields["state"], "offline");
+        assert_eq!(fields["notify"], false);
+    }
+}
@@ -3,9 +3,259 @@
 //! Generates HTML forms from content type field definitions.
 //! This is a temporary solution until the full Form API is built in Epic 9.
 
+use serde::Serialize;
+
+use crate::form::{Form, FormElement};
 use crate::models::Item;
 use crate::routes::helpers::html_escape;
-use trovato_sdk::types::{ContentTypeDefinition, FieldDefinition, FieldType};
+use trovato_sdk::types::{
+    ContentTypeDefinition, FieldDefinition, FieldGroup, FieldType, WidgetType,
+};
+
+/// Default number of rows for the textarea widget.
+const DEFAULT_TEXTAREA_ROWS: u64 = 5;
+
+/// An option of a select or radios widget.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WidgetOption {
+    pub value: String,
+    pub label: String,
+}
+
+/// A field as placed on a form, with its widget settings resolved.
+#[derive(Debug, Serialize)]
+pub struct FormField<'a> {
+    #[serde(flatten)]
+    pub definition: &'a FieldDefinition,
+    /// Widget to render; `default` uses the field type's own input.
+    pub widget_type: WidgetType,
+    pub placeholder: Option<String>,
+    pub rows: u64,
+    pub options: Vec<WidgetOption>,
+    /// `min` and `max` input attributes from the field's constraints.
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// `pattern` input attribute from the field's constraints.
+    pub pattern: Option<String>,
+    /// Declared default value, prefilled on add forms.
+    pub default_value: String,
+}
+
+impl<'a> FormField<'a> {
+    /// Resolve the widget of a field definition.
+    ///
+    /// Widgets only replace the input of single-value fields; structured
+    /// fields (files, references, blocks, compounds) keep their editors.
+    /// A select or radios widget without options falls back to the default.
+    /// Fields restricted to allowed values offer them as options, in a
+    /// select list unless another widget was chosen.
+    pub fn new(definition: &'a FieldDefinition) -> Self {
+        let settings = definition.widget.as_ref().map(|w| &w.settings);
+        let setting = |key: &str| settings.and_then(|s| s.get(key));
+        let constraints = &definition.constraints;
+
+        let mut options: Vec<WidgetOption> = setting("options")
+            .and_then(|v| v.as_array())
+            .map(|opts| opts.iter().filter_map(parse_option).collect())
+            .unwrap_or_default();
+        if options.is_empty() {
+            options = constraints
+                .allowed_values
+                .iter()
+                .map(|value| WidgetOption {
+                    value: value.clone(),
+                    label: value.clone(),
+                })
+                .collect();
+        }
+
+        let widget_type = match definition.widget_type() {
+            _ if !is_scalar(&definition.field_type) => WidgetType::Default,
+            WidgetType::Select | WidgetType::Radios if options.is_empty() => WidgetType::Default,
+            WidgetType::Default if !constraints.allowed_values.is_empty() => WidgetType::Select,
+            widget_type => widget_type,
+        };
+
+        Self {
+            definition,
+            widget_type,
+            placeholder: setting("placeholder")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            rows: setting("rows")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_TEXTAREA_ROWS),
+            options,
+            min: constraints.min.map(|m| m.to_string()),
+            max: constraints.max.map(|m| m.to_string()),
+            pattern: constraints.pattern.clone(),
+            default_value: extract_scalar_value(declared_default(definition).as_ref()),
+        }
+    }
+}
+
+/// Fields of one form section: a field group, or the ungrouped fields.
+#[derive(Debug, Serialize)]
+pub struct FieldSection<'a> {
+    /// `None` for fields outside any group.
+    pub group: Option<&'a FieldGroup>,
+    pub fields: Vec<FormField<'a>>,
+}
+
+/// Lay the fields of a content type out in sections.
+///
+/// Ungrouped fields come first, followed by each field group in weight
+/// order. Fields naming an undeclared group are treated as ungrouped, and
+/// empty groups are left out. Fields keep their definition order.
+pub fn field_sections(content_type: &ContentTypeDefinition) -> Vec<FieldSection<'_>> {
+    let mut groups: Vec<&FieldGroup> = content_type.field_groups.iter().collect();
+    groups.sort_by_key(|g| g.weight);
+
+    let declared = |name: &str| groups.iter().any(|g| g.name == name);
+    let fields_in = |group: Option<&str>| {
+        content_type
+            .fields
+            .iter()
+            .filter(|f| f.field_group.as_deref().filter(|g| declared(g)) == group)
+            .map(FormField::new)
+            .collect::<Vec<_>>()
+    };
+
+    let mut sections = vec![FieldSection {
+        group: None,
+        fields: fields_in(None),
+    }];
+    for &group in &groups {
+        sections.push(FieldSection {
+            group: Some(group),
+            fields: fields_in(Some(&group.name)),
+        });
+    }
+    sections.retain(|s| !s.fields.is_empty());
+    sections
+}
+
+/// Describe an item form to `tap_form_alter`.
+///
+/// The admin content form template lays out the title and fields itself;
+/// this lists them as Form API elements holding their current values, so
+/// plugins can read them, decorate them, and add elements of their own.
+/// Fields edited with typed or structured inputs (numbers, dates, files,
+/// references, blocks) are described as `hidden` elements.
+pub fn item_form(
+    content_type: &ContentTypeDefinition,
+    form_id: impl Into<String>,
+    title: &str,
+    fields: &serde_json::Map<String, serde_json::Value>,
+) -> Form {
+    let title_label = content_type.title_label.as_deref().unwrap_or("Title");
+    let mut form = Form::new(form_id).element(
+        "title",
+        FormElement::textfield()
+            .title(title_label)
+            .default_value(title)
+            .required(),
+    );
+
+    for (weight, definition) in (1..).zip(&content_type.fields) {
+        let field = FormField::new(definition);
+        let options = || {
+            field
+                .options
+                .iter()
+                .map(|o| (o.value.clone(), o.label.clone()))
+                .collect()
+        };
+        let rows = u32::try_from(field.rows).unwrap_or(DEFAULT_TEXTAREA_ROWS as u32);
+        let mut element = match (field.widget_type, &definition.field_type) {
+            (WidgetType::Select, _) => FormElement::select(options()),
+            (WidgetType::Radios, _) => FormElement::radio(options()),
+            (WidgetType::Checkbox, _) | (WidgetType::Default, FieldType::Boolean) => {
+                FormElement::checkbox()
+            }
+            (WidgetType::Textarea, _) | (WidgetType::Default, FieldType::TextLong) => {
+                FormElement::textarea(rows)
+            }
+            (WidgetType::Textfield, _) => FormElement::textfield(),
+            (WidgetType::Default, FieldType::Text { max_length }) => match max_length {
+                Some(max) => FormElement::textfield().max_length(*max),
+                None => FormElement::textfield(),
+            },
+            _ => FormElement::hidden(),
+        }
+        .title(&definition.label)
+        .weight(weight);
+        if definition.required {
+            element = element.required();
+        }
+        if let Some(value) = fields.get(&definition.field_name) {
+            element = element.default_value(value.clone());
+        }
+        form = form.element(&definition.field_name, element);
+    }
+    form
+}
+
+/// Field types storing a single scalar value, which any widget can edit.
+fn is_scalar(field_type: &FieldType) -> bool {
+    matches!(
+        field_type,
+        FieldType::Text { .. }
+            | FieldType::TextLong
+            | FieldType::Integer
+            | FieldType::Float
+            | FieldType::Boolean
+            | FieldType::Date
+            | FieldType::Email
+    )
+}
+
+/// The declared default of a field in the `{"value": ...}` shape forms
+/// read. A `false` default is left out so checkboxes start unchecked.
+fn declared_default(definition: &FieldDefinition) -> Option<serde_json::Value> {
+    match definition.default_value.as_ref()? {
+        serde_json::Value::Bool(false) | serde_json::Value::Null => None,
+        value @ serde_json::Value::Object(_) => Some(value.clone()),
+        value => Some(serde_json::json!({ "value": value })),
+    }
+}
+
+/// `min`, `max` and `pattern` attributes for a field's constraints.
+fn constraint_attrs(definition: &FieldDefinition) -> String {
+    let constraints = &definition.constraints;
+    let mut attrs = String::new();
+    if let Some(min) = constraints.min {
+        attrs.push_str(&format!(r#" min="{min}""#));
+    }
+    if let Some(max) = constraints.max {
+        attrs.push_str(&format!(r#" max="{max}""#));
+    }
+    if let Some(ref pattern) = constraints.pattern {
+        attrs.push_str(&format!(r#" pattern="{}""#, html_escape(pattern)));
+    }
+    attrs
+}
+
+/// Parse a widget option: a plain value or a `{value, label}` object.
+fn parse_option(option: &serde_json::Value) -> Option<WidgetOption> {
+    let scalar = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    };
+    match option {
+        serde_json::Value::Object(obj) => {
+            let value = obj.get("value").and_then(scalar)?;
+            let label = obj.get("label").and_then(scalar).unwrap_or(value.clone());
+            Some(WidgetOption { value, label })
+        }
+        other => scalar(other).map(|value| WidgetOption {
+            label: value.clone(),
+            value,
+        }),
+    }
+}
 
 /// Builder for auto-generated forms.
 pub struct FormBuilder {
@@ -65,9 +315,7 @@ impl FormBuilder {
         );
 
         // Dynamic fields
-        for field in &self.content_type.fields {
-            html.push_str(&self.render_field(field, None));
-        }
+        html.push_str(&self.render_fields(None));
 
         // Status field
         html.push_str(
@@ -115,10 +363,7 @@ impl FormBuilder {
         ));
 
         // Dynamic fields with existing values
-        for field in &self.content_type.fields {
-            let value = item.fields.get(&field.field_name);
-            html.push_str(&self.render_field(field, value));
-        }
+        html.push_str(&self.render_fields(Some(item)));
 
         // Status field
         let checked = if item.is_published() { "checked" } else { "" };
@@ -154,12 +399,55 @@ impl FormBuilder {
         html
     }
 
+    /// Render all fields, wrapping grouped fields in a fieldset (or a
+    /// `<details>` element for collapsed groups).
+    fn render_fields(&self, item: Option<&Item>) -> String {
+        let mut html = String::new();
+        for section in field_sections(&self.content_type) {
+            let fields: String = section
+                .fields
+                .iter()
+                .map(|field| {
+                    let default = match item {
+                        Some(_) => None,
+                        None => declared_default(field.definition),
+                    };
+                    let value = item
+                        .and_then(|i| i.fields.get(&field.definition.field_name))
+                        .or(default.as_ref());
+                    match field.widget_type {
+                        WidgetType::Default => self.render_field(field.definition, value),
+                        _ => render_widget(field, value),
+                    }
+                })
+                .collect();
+
+            let Some(group) = section.group else {
+                html.push_str(&fields);
+                continue;
+            };
+            let name = html_escape(&group.name);
+            let label = html_escape(&group.label);
+            if group.collapsed {
+                html.push_str(&format!(
+                    r#"<details class="field-group field-group--{name}"><summary>{label}</summary>{fields}</details>"#
+                ));
+            } else {
+                html.push_str(&format!(
+                    r#"<fieldset class="field-group field-group--{name}"><legend>{label}</legend>{fields}</fieldset>"#
+                ));
+            }
+        }
+        html
+    }
+
     /// Render a single field based on its type.
     fn render_field(&self, field: &FieldDefinition, value: Option<&serde_json::Value>) -> String {
         let field_name = &field.field_name;
         let label = &field.label;
         let required = if field.required { "required" } else { "" };
         let required_star = if field.required { " *" } else { "" };
+        let constraints = constraint_attrs(field);
 
         match &field.field_type {
             FieldType::Text { max_length } => {
@@ -171,7 +459,7 @@ impl FormBuilder {
                     r#"
                     <div class="form-group">
                         <label for="{field_name}">{label}{required_star}</label>
-                        <input type="text" id="{field_name}" name="{field_name}" value="{val}" {required} {max} class="form-control">
+                        <input type="text" id="{field_name}" name="{field_name}" value="{val}" {required} {max}{constraints} class="form-control">
                     </div>
                     "#
                 )
@@ -239,7 +527,7 @@ impl FormBuilder {
                     r#"
                     <div class="form-group">
                         <label for="{field_name}">{label}{required_star}</label>
-                        <input type="number" id="{field_name}" name="{field_name}" value="{val}" {required} class="form-control">
+                        <input type="number" id="{field_name}" name="{field_name}" value="{val}" {required}{constraints} class="form-control">
                     </div>
                     "#
                 )
@@ -255,7 +543,7 @@ impl FormBuilder {
                     r#"
                     <div class="form-group">
                         <label for="{field_name}">{label}{required_star}</label>
-                        <input type="number" id="{field_name}" name="{field_name}" value="{val}" step="any" {required} class="form-control">
+                        <input type="number" id="{field_name}" name="{field_name}" value="{val}" step="any" {required}{constraints} class="form-control">
                     </div>
                     "#
                 )
@@ -431,6 +719,128 @@ impl FormBuilder {
     }
 }
 
+/// Render a field with an explicit widget.
+fn render_widget(field: &FormField<'_>, value: Option<&serde_json::Value>) -> String {
+    let field_name = &field.definition.field_name;
+    let label = html_escape(&field.definition.label);
+    let required = if field.definition.required {
+        "required"
+    } else {
+        ""
+    };
+    let required_star = if field.definition.required { " *" } else { "" };
+    let placeholder = field
+        .placeholder
+        .as_deref()
+        .map(|p| format!(r#"placeholder="{}""#, html_escape(p)))
+        .unwrap_or_default();
+    let current = extract_scalar_value(value);
+    let val = html_escape(&current);
+
+    match field.widget_type {
+        WidgetType::Hidden => {
+            format!(r#"<input type="hidden" id="{field_name}" name="{field_name}" value="{val}">"#)
+        }
+        WidgetType::Textarea => {
+            let rows = field.rows;
+            format!(
+                r#"
+                <div class="form-group">
+                    <label for="{field_name}">{label}{required_star}</label>
+                    <textarea id="{field_name}" name="{field_name}" rows="{rows}" {placeholder} {required} class="form-control">{val}</textarea>
+                </div>
+                "#
+            )
+        }
+        WidgetType::Checkbox => {
+            let checked = if matches!(current.as_str(), "1" | "true") {
+                "checked"
+            } else {
+                ""
+            };
+            format!(
+                r#"
+                <div class="form-group">
+                    <label>
+                        <input type="checkbox" id="{field_name}" name="{field_name}" value="1" {checked}>
+                        {label}
+                    </label>
+                </div>
+                "#
+            )
+        }
+        WidgetType::Select => {
+            let mut options = String::new();
+            if !field.definition.required {
+                options.push_str(r#"<option value="">- None -</option>"#);
+            }
+            for option in &field.options {
+                let sel = if option.value == current {
+                    "selected"
+                } else {
+                    ""
+                };
+                options.push_str(&format!(
+                    r#"<option value="{}" {sel}>{}</option>"#,
+                    html_escape(&option.value),
+                    html_escape(&option.label)
+                ));
+            }
+            format!(
+                r#"
+                <div class="form-group">
+                    <label for="{field_name}">{label}{required_star}</label>
+                    <select id="{field_name}" name="{field_name}" {required} class="form-control">{options}</select>
+                </div>
+                "#
+            )
+        }
+        WidgetType::Radios => {
+            let mut options = String::new();
+            for option in &field.options {
+                let checked = if option.value == current {
+                    "checked"
+                } else {
+                    ""
+                };
+                options.push_str(&format!(
+                    r#"<label><input type="radio" name="{field_name}" value="{}" {checked} {required}> {}</label>"#,
+                    html_escape(&option.value),
+                    html_escape(&option.label)
+                ));
+            }
+            format!(
+                r#"
+                <div class="form-group">
+                    <span class="form-label">{label}{required_star}</span>
+                    <div class="form-radios">{options}</div>
+                </div>
+                "#
+            )
+        }
+        WidgetType::Textfield | WidgetType::Default => {
+            format!(
+                r#"
+                <div class="form-group">
+                    <label for="{field_name}">{label}{required_star}</label>
+                    <input type="text" id="{field_name}" name="{field_name}" value="{val}" {placeholder} {required} class="form-control">
+                </div>
+                "#
+            )
+        }
+    }
+}
+
+/// Extract a scalar field value as an unescaped string.
+fn extract_scalar_value(value: Option<&serde_json::Value>) -> String {
+    match value.and_then(|v| v.get("value")) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Number(n)) => n.to_string(),
+        Some(serde_json::Value::Bool(b)) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
 /// Extract text value from field JSON.
 fn extract_text_value(value: Option<&serde_json::Value>) -> String {
     value
@@ -454,6 +864,8 @@ fn extract_format_value(value: Option<&serde_json::Value>) -> String {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::form::ElementType;
+    use crate::models::DEFAULT_TENANT_ID;
 
     fn test_content_type() -> ContentTypeDefinition {
         ContentTypeDefinition {
@@ -461,6 +873,8 @@ mod tests {
             label: "Blog Post".to_string(),
             description: "A blog article".to_string(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition {
                     field_name: "body".to_string(),
@@ -470,6 +884,13 @@ mod tests {
                     cardinality: 1,
                     settings: serde_json::json!({}),
                     personal_data: false,
+                    track_history: false,
+                    search_weight: None,
+                    widget: None,
+                    display: None,
+                    field_group: None,
+                    default_value: None,
+                    constraints: Default::default(),
                 },
                 FieldDefinition {
                     field_name: "summary".to_string(),
@@ -481,8 +902,17 @@ mod tests {
                     cardinality: 1,
                     settings: serde_json::json!({}),
                     personal_data: false,
+                    track_history: false,
+                    search_weight: None,
+                    widget: None,
+                    display: None,
+                    field_group: None,
+                    default_value: None,
+                    constraints: Default::default(),
                 },
             ],
+            extends: None,
+            template: false,
         }
     }
 
@@ -503,6 +933,37 @@ mod tests {
         assert!(form.contains("textarea")); // TextLong
     }
 
+    #[test]
+    fn item_form_describes_fields() {
+        let mut ct = test_content_type();
+        ct.fields
+            .push(FieldDefinition::new("published_on", FieldType::Date));
+        let fields = serde_json::json!({"body": {"value": "Hi", "format": "plain_text"}});
+        let form = item_form(&ct, "item_edit_blog", "Post", fields.as_object().unwrap());
+
+        assert_eq!(form.form_id, "item_edit_blog");
+        let title = &form.elements["title"];
+        assert_eq!(title.element_type.type_name(), "textfield");
+        assert_eq!(title.default_value, Some(serde_json::json!("Post")));
+        assert!(title.required);
+
+        let body = &form.elements["body"];
+        assert_eq!(body.element_type.type_name(), "textarea");
+        assert_eq!(body.default_value.as_ref().unwrap()["value"], "Hi");
+        assert!(body.required);
+        assert!(matches!(
+            form.elements["summary"].element_type,
+            ElementType::Textfield {
+                max_length: Some(255)
+            }
+        ));
+        assert!(form.elements["summary"].default_value.is_none());
+        assert_eq!(
+            form.elements["published_on"].element_type.type_name(),
+            "hidden"
+        );
+    }
+
     #[test]
     fn html_escape_works() {
         assert_eq!(html_escape("<script>"), "&lt;script&gt;");
@@ -571,6 +1032,8 @@ mod tests {
             label: "Page".to_string(),
             description: "A page".to_string(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![FieldDefinition {
                 field_name: "sections".to_string(),
                 field_type: FieldType::Compound {
@@ -589,7 +1052,16 @@ mod tests {
                     }]
                 }),
                 personal_data: false,
+                track_history: false,
+                search_weight: None,
+                widget: None,
+                display: None,
+                field_group: None,
+                default_value: None,
+                constraints: Default::default(),
             }],
+            extends: None,
+            template: false,
         };
         let builder = FormBuilder::new(ct);
         let form = builder.build_add_form("/item/add/page");
@@ -634,9 +1106,113 @@ mod tests {
             language: "en".to_string(),
             item_group_id: uuid::Uuid::now_v7(),
             retention_days: None,
+            moderation_state: None,
+            tenant_id: DEFAULT_TENANT_ID,
         };
         let form = builder.build_edit_form(&item, "/item/123/edit");
         assert!(form.contains(r#"name="log""#));
         assert!(form.contains("Revision log"));
     }
+
+    #[test]
+    fn select_widget_renders_options() {
+        let mut ct = test_content_type();
+        ct.fields[1] = ct.fields[1].clone().widget(
+            WidgetType::Select,
+            serde_json::json!({"options": ["short", {"value": "long", "label": "Long form"}]}),
+        );
+        let form = FormBuilder::new(ct).build_add_form("/item/add/blog");
+        assert!(form.contains(r#"<select id="summary" name="summary""#));
+        assert!(form.contains(r#"<option value="long" >Long form</option>"#));
+        assert!(form.contains(r#"<option value="">- None -</option>"#));
+    }
+
+    #[test]
+    fn constraints_and_defaults_surface_on_add_form() {
+        let mut ct = test_content_type();
+        ct.fields[1] = ct.fields[1]
+            .clone()
+            .allowed_values(&["short", "long"])
+            .default_value("long");
+        ct.fields.push(
+            FieldDefinition::new("rating", FieldType::Integer)
+                .min(1.0)
+                .max(5.0)
+                .default_value(3),
+        );
+        let form = FormBuilder::new(ct).build_add_form("/item/add/blog");
+        assert!(form.contains(r#"<select id="summary" name="summary""#));
+        assert!(form.contains(r#"<option value="long" selected>long</option>"#));
+        assert!(form.contains(r#"name="rating" value="3""#));
+        assert!(form.contains(r#" min="1" max="5""#));
+    }
+
+    #[test]
+    fn widget_ignored_for_structured_fields_and_without_options() {
+        let field = FieldDefinition::new("ref", FieldType::RecordReference("page".into()))
+            .widget(WidgetType::Textfield, serde_json::json!({}));
+        assert_eq!(FormField::new(&field).widget_type, WidgetType::Default);
+
+        let field = FieldDefinition::new("kind", FieldType::Text { max_length: None })
+            .widget(WidgetType::Radios, serde_json::json!({}));
+        assert_eq!(FormField::new(&field).widget_type, WidgetType::Default);
+    }
+
+    #[test]
+    fn field_sections_follow_group_weight() {
+        let mut ct = test_content_type();
+        ct.fields[0].field_group = Some("main".into());
+        ct.fields.push(
+            FieldDefinition::new("seo", FieldType::Text { max_length: None }).group("missing"),
+        );
+        ct.field_groups = vec![
+            FieldGroup {
+                name: "meta".into(),
+                label: "Meta".into(),
+                weight: 5,
+                collapsed: true,
+            },
+            FieldGroup {
+                name: "main".into(),
+                label: "Main".into(),
+                weight: -5,
+                collapsed: false,
+            },
+        ];
+        ct.fields[1].field_group = Some("meta".into());
+
+        let sections = field_sections(&ct);
+        let layout: Vec<(Option<&str>, Vec<&str>)> = sections
+            .iter()
+            .map(|s| {
+                (
+                    s.group.map(|g| g.name.as_str()),
+                    s.fields
+                        .iter()
+                        .map(|f| f.definition.field_name.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                (None, vec!["seo"]),
+                (Some("main"), vec!["body"]),
+                (Some("meta"), vec!["summary"]),
+            ]
+        );
+
+        let form = FormBuilder::new(ct).build_add_form("/item/add/blog");
+        assert!(
+            form.contains(
+                r#"<fieldset class="field-group field-group--main"><legend>Main</legend>"#
+            )
+        );
+        assert!(
+            form.contains(
+                r#"<details class="field-group field-group--meta"><summary>Meta</summary>"#
+            )
+        );
+    }
 }
@@ -0,0 +1,460 @@
+//! Structured item queries for plugins.
+//!
+//! Backs the `item-query` host function. Plugins describe what they want
+//! with an [`ItemQuery`]; the kernel resolves the stage overlay, restricts
+//! results to the current site and what the caller may see, and builds a
+//! prepared statement.
+//! Field names are bound as parameters as well, so nothing supplied by a
+//! plugin is ever interpolated into the SQL text.
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use trovato_sdk::types::{
+    ITEM_QUERY_MAX_LIMIT, ItemFieldPredicate, ItemQuery, ItemQueryOp, SortDirection,
+};
+use uuid::Uuid;
+
+use crate::models::Item;
+use crate::models::stage::{LIVE_STAGE_ID, Stage, StageVisibility};
+use crate::services::site::current_tenant_id;
+use crate::tap::UserContext;
+
+/// Item columns selected by every item query (matches [`Item`]).
+const ITEM_COLUMNS: &str = "id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id";
+
+/// Item columns plugins may sort by directly.
+pub const SORT_COLUMNS: &[&str] = &["created", "changed", "title", "status", "sticky", "promote"];
+
+/// Maximum predicates or sort keys accepted in one query.
+const MAX_CLAUSES: usize = 16;
+
+/// Which items the caller is allowed to see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemQueryAccess {
+    /// Admins and background contexts: every item in the resolved stages.
+    Unrestricted,
+    /// Users with "access content": published items only.
+    Published,
+    /// Users without "access content": nothing.
+    Denied,
+}
+
+impl ItemQueryAccess {
+    /// Determine access for the calling user.
+    pub fn for_user(user: &UserContext, background: bool) -> Self {
+        if background || user.is_admin() {
+            Self::Unrestricted
+        } else if user.has_permission("access content") {
+            Self::Published
+        } else {
+            Self::Denied
+        }
+    }
+
+    /// User whose item grants restrict the results, if any.
+    ///
+    /// Unrestricted callers bypass grants; anonymous users check grants as
+    /// the nil user, as gathers do.
+    pub fn grants_user(self, user: &UserContext) -> Option<Uuid> {
+        (self != Self::Unrestricted).then_some(user.id)
+    }
+}
+
+/// A query that could not be built from plugin input.
+#[derive(Debug, thiserror::Error)]
+pub enum ItemQueryError {
+    /// A field or sort name is not a valid identifier.
+    #[error("invalid field name: {0}")]
+    InvalidField(String),
+    /// A predicate value does not fit its operator.
+    #[error("invalid value for {field}: {reason}")]
+    InvalidValue {
+        /// Field the predicate applies to.
+        field: String,
+        /// Why the value was rejected.
+        reason: &'static str,
+    },
+    /// Too many predicates or sort keys.
+    #[error("too many clauses (max {MAX_CLAUSES})")]
+    TooManyClauses,
+}
+
+/// A value bound to a placeholder.
+#[derive(Debug, Clone, PartialEq)]
+enum Bind {
+    Text(String),
+    SmallInt(i16),
+    BigInt(i64),
+    Float(f64),
+    Bool(bool),
+    Uuid(Uuid),
+    Uuids(Vec<Uuid>),
+}
+
+/// SQL text and its parameters, ready to execute.
+#[derive(Debug)]
+pub struct BuiltItemQuery {
+    sql: String,
+    binds: Vec<Bind>,
+}
+
+impl BuiltItemQuery {
+    /// Execute the query.
+    pub async fn fetch(self, pool: &PgPool) -> Result<Vec<Item>> {
+        let mut query = sqlx::query_as::<_, Item>(&self.sql);
+        for bind in self.binds {
+            query = match bind {
+                Bind::Text(v) => query.bind(v),
+                Bind::SmallInt(v) => query.bind(v),
+                Bind::BigInt(v) => query.bind(v),
+                Bind::Float(v) => query.bind(v),
+                Bind::Bool(v) => query.bind(v),
+                Bind::Uuid(v) => query.bind(v),
+                Bind::Uuids(v) => query.bind(v),
+            };
+        }
+        query
+            .fetch_all(pool)
+            .await
+            .context("failed to execute item query")
+    }
+}
+
+/// Resolve the stages an item query should cover.
+///
+/// `None` or `"live"` means the live stage only. Any other stage is given
+/// by machine name or UUID and overlaid on live. Anonymous callers cannot
+/// see internal stages and silently get live only. Returns `Ok(None)` if
+/// the stage does not exist.
+pub async fn resolve_stage_ids(
+    pool: &PgPool,
+    stage: Option<&str>,
+    user: &UserContext,
+    access: ItemQueryAccess,
+) -> Result<Option<Vec<Uuid>>> {
+    let Some(stage) = stage.filter(|s| !s.is_empty() && *s != "live") else {
+        return Ok(Some(vec![LIVE_STAGE_ID]));
+    };
+
+    let found = match stage.parse::<Uuid>() {
+        Ok(id) if id == LIVE_STAGE_ID => return Ok(Some(vec![LIVE_STAGE_ID])),
+        Ok(id) => Stage::find_by_id(pool, id).await?,
+        Err(_) => Stage::find_by_machine_name(pool, stage).await?,
+    };
+    let Some(found) = found else {
+        return Ok(None);
+    };
+
+    if found.visibility == StageVisibility::Internal
+        && access != ItemQueryAccess::Unrestricted
+        && !user.authenticated
+    {
+        return Ok(Some(vec![LIVE_STAGE_ID]));
+    }
+    Ok(Some(vec![found.id, LIVE_STAGE_ID]))
+}
+
+/// Build the SQL for an item query.
+///
+/// Only items of the current site's tenant are matched. With a
+/// `grants_user`, items restricted by `tap_item_grants` are only returned
+/// when that user holds a view grant.
+pub fn build(
+    query: &ItemQuery,
+    stage_ids: Vec<Uuid>,
+    access: ItemQueryAccess,
+    grants_user: Option<Uuid>,
+) -> Result<BuiltItemQuery, ItemQueryError> {
+    if query.fields.len() > MAX_CLAUSES || query.sort.len() > MAX_CLAUSES {
+        return Err(ItemQueryError::TooManyClauses);
+    }
+
+    let mut sql =
+        format!("SELECT {ITEM_COLUMNS} FROM item WHERE stage_id = ANY($1) AND tenant_id = $2");
+    let mut binds = vec![Bind::Uuids(stage_ids), Bind::Uuid(current_tenant_id())];
+
+    match access {
+        ItemQueryAccess::Unrestricted => {}
+        ItemQueryAccess::Published => sql.push_str(" AND status = 1"),
+        ItemQueryAccess::Denied => sql.push_str(" AND FALSE"),
+    }
+    if let Some(user_id) = grants_user {
+        binds.push(Bind::Uuid(user_id));
+        sql.push_str(&format!(
+            " AND item_access_granted(id, ${}, 'view')",
+            binds.len()
+        ));
+    }
+
+    if let Some(item_type) = &query.item_type {
+        binds.push(Bind::Text(item_type.clone()));
+        sql.push_str(&format!(" AND type = ${}", binds.len()));
+    }
+    if let Some(status) = query.status {
+        binds.push(Bind::SmallInt(status));
+        sql.push_str(&format!(" AND status = ${}", binds.len()));
+    }
+    for predicate in &query.fields {
+        let condition = predicate_sql(predicate, &mut binds)?;
+        sql.push_str(" AND ");
+        sql.push_str(&condition);
+    }
+
+    let mut order = Vec::new();
+    for sort in &query.sort {
+        let direction = match sort.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        if SORT_COLUMNS.contains(&sort.field.as_str()) {
+            order.push(format!("{} {direction}", sort.field));
+        } else if is_valid_field_name(&sort.field) {
+            binds.push(Bind::Text(sort.field.clone()));
+            order.push(format!("fields->>${} {direction}", binds.len()));
+        } else {
+            return Err(ItemQueryError::InvalidField(sort.field.clone()));
+        }
+    }
+    if order.is_empty() {
+        order.push("changed DESC".to_string());
+    }
+    // Stable pagination regardless of the requested order.
+    order.push("id DESC".to_string());
+    sql.push_str(" ORDER BY ");
+    sql.push_str(&order.join(", "));
+
+    let limit = query
+        .limit
+        .unwrap_or(ITEM_QUERY_MAX_LIMIT)
+        .clamp(1, ITEM_QUERY_MAX_LIMIT);
+    binds.push(Bind::BigInt(limit));
+    sql.push_str(&format!(" LIMIT ${}", binds.len()));
+    binds.push(Bind::BigInt(query.offset.max(0)));
+    sql.push_str(&format!(" OFFSET ${}", binds.len()));
+
+    Ok(BuiltItemQuery { sql, binds })
+}
+
+/// Build the condition for one field predicate, pushing its parameters.
+fn predicate_sql(
+    predicate: &ItemFieldPredicate,
+    binds: &mut Vec<Bind>,
+) -> Result<String, ItemQueryError> {
+    let field = &predicate.field;
+    if !is_valid_field_name(field) {
+        return Err(ItemQueryError::InvalidField(field.clone()));
+    }
+    let invalid = |reason| ItemQueryError::InvalidValue {
+        field: field.clone(),
+        reason,
+    };
+
+    binds.push(Bind::Text(field.clone()));
+    let key = binds.len();
+
+    let op = match predicate.op {
+        ItemQueryOp::Exists => return Ok(format!("fields->>${key} IS NOT NULL")),
+        ItemQueryOp::NotExists => return Ok(format!("fields->>${key} IS NULL")),
+        ItemQueryOp::Eq => "=",
+        ItemQueryOp::Ne => "<>",
+        ItemQueryOp::Lt => "<",
+        ItemQueryOp::Lte => "<=",
+        ItemQueryOp::Gt => ">",
+        ItemQueryOp::Gte => ">=",
+    };
+
+    match &predicate.value {
+        serde_json::Value::String(s) => {
+            binds.push(Bind::Text(s.clone()));
+            Ok(format!("fields->>${key} {op} ${}", binds.len()))
+        }
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                binds.push(Bind::BigInt(i));
+            } else if let Some(f) = n.as_f64() {
+                binds.push(Bind::Float(f));
+            } else {
+                return Err(invalid("number out of range"));
+            }
+            // Values may be stored as JSON numbers or numeric strings; only
+            // cast when the text is numeric so malformed data never errors.
+            Ok(format!(
+                "(CASE WHEN fields->>${key} ~ '^-?[0-9]+(\\.[0-9]+)?$' \
+                 THEN (fields->>${key})::numeric END) {op} ${}::numeric",
+                binds.len()
+            ))
+        }
+        serde_json::Value::Bool(b) => {
+            if !matches!(predicate.op, ItemQueryOp::Eq | ItemQueryOp::Ne) {
+                return Err(invalid("booleans support only eq and ne"));
+            }
+            binds.push(Bind::Bool(*b));
+            Ok(format!(
+                "fields->${key} {op} to_jsonb(${}::boolean)",
+                binds.len()
+            ))
+        }
+        serde_json::Value::Null => Err(invalid("use exists or not_exists to test for null")),
+        _ => Err(invalid("arrays and objects cannot be compared")),
+    }
+}
+
+/// Whether a field name is a plain identifier.
+fn is_valid_field_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::models::DEFAULT_TENANT_ID;
+
+    fn live() -> Vec<Uuid> {
+        vec![LIVE_STAGE_ID]
+    }
+
+    #[test]
+    fn default_query_sorts_by_changed_with_capped_limit() {
+        let built = build(
+            &ItemQuery::new(),
+            live(),
+            ItemQueryAccess::Unrestricted,
+            None,
+        )
+        .unwrap();
+        assert!(built.sql.contains("stage_id = ANY($1) AND tenant_id = $2"));
+        assert_eq!(built.binds[1], Bind::Uuid(DEFAULT_TENANT_ID));
+        assert!(
+            built
+                .sql
+                .contains("ORDER BY changed DESC, id DESC LIMIT $3 OFFSET $4")
+        );
+        assert_eq!(built.binds[2], Bind::BigInt(ITEM_QUERY_MAX_LIMIT));
+        assert!(!built.sql.contains("AND status"));
+    }
+
+    #[test]
+    fn published_access_forces_status() {
+        let built = build(&ItemQuery::new(), live(), ItemQueryAccess::Published, None).unwrap();
+        assert!(built.sql.contains("AND status = 1"));
+
+        let built = build(&ItemQuery::new(), live(), ItemQueryAccess::Denied, None).unwrap();
+        assert!(built.sql.contains("AND FALSE"));
+    }
+
+    #[test]
+    fn grants_user_adds_view_grant_check() {
+        let user = Uuid::from_u128(7);
+        let built = build(
+            &ItemQuery::new().item_type("page"),
+            live(),
+            ItemQueryAccess::Published,
+            Some(user),
+        )
+        .unwrap();
+        assert!(built.sql.contains("item_access_granted(id, $3, 'view')"));
+        assert!(built.sql.contains("type = $4"));
+        assert_eq!(built.binds[2], Bind::Uuid(user));
+
+        let built = build(
+            &ItemQuery::new(),
+            live(),
+            ItemQueryAccess::Unrestricted,
+            None,
+        )
+        .unwrap();
+        assert!(!built.sql.contains("item_access_granted"));
+    }
+
+    #[test]
+    fn grants_user_follows_access() {
+        let reader =
+            UserContext::authenticated(Uuid::from_u128(7), vec!["access content".to_string()]);
+        assert_eq!(
+            ItemQueryAccess::Published.grants_user(&reader),
+            Some(reader.id)
+        );
+        assert_eq!(ItemQueryAccess::Unrestricted.grants_user(&reader), None);
+        assert_eq!(
+            ItemQueryAccess::Published.grants_user(&UserContext::anonymous()),
+            Some(Uuid::nil())
+        );
+    }
+
+    #[test]
+    fn field_names_and_values_are_bound() {
+        let query = ItemQuery::new()
+            .item_type("article")
+            .status(0)
+            .field("field_publish_on", ItemQueryOp::Lte, serde_json::json!(100))
+            .field("field_topic", ItemQueryOp::Eq, serde_json::json!("rust"))
+            .sort("field_topic", SortDirection::Asc);
+        let built = build(&query, live(), ItemQueryAccess::Unrestricted, None).unwrap();
+
+        assert!(built.sql.contains("type = $3"));
+        assert!(built.sql.contains("status = $4"));
+        assert!(
+            built
+                .sql
+                .contains("(fields->>$5)::numeric END) <= $6::numeric")
+        );
+        assert!(built.sql.contains("fields->>$7 = $8"));
+        assert!(built.sql.contains("ORDER BY fields->>$9 ASC, id DESC"));
+        assert!(!built.sql.contains("field_publish_on"));
+        assert_eq!(built.binds[4], Bind::Text("field_publish_on".to_string()));
+        assert_eq!(built.binds[5], Bind::BigInt(100));
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        let bad_field =
+            ItemQuery::new().field("x'; --", ItemQueryOp::Exists, serde_json::Value::Null);
+        assert!(matches!(
+            build(&bad_field, live(), ItemQueryAccess::Unrestricted, None),
+            Err(ItemQueryError::InvalidField(_))
+        ));
+
+        let bad_sort = ItemQuery::new().sort("title desc", SortDirection::Asc);
+        assert!(build(&bad_sort, live(), ItemQueryAccess::Unrestricted, None).is_err());
+
+        let bool_range =
+            ItemQuery::new().field("field_flag", ItemQueryOp::Gt, serde_json::json!(true));
+        assert!(matches!(
+            build(&bool_range, live(), ItemQueryAccess::Unrestricted, None),
+            Err(ItemQueryError::InvalidValue { .. })
+        ));
+
+        let null_eq =
+            ItemQuery::new().field("field_flag", ItemQueryOp::Eq, serde_json::Value::Null);
+        assert!(build(&null_eq, live(), ItemQueryAccess::Unrestricted, None).is_err());
+    }
+
+    #[test]
+    fn access_follows_user_context() {
+        let admin = UserContext::authenticated(Uuid::nil(), vec!["administer site".to_string()]);
+        let reader = UserContext::authenticated(Uuid::nil(), vec!["access content".to_string()]);
+        let anonymous = UserContext::anonymous();
+
+        assert_eq!(
+            ItemQueryAccess::for_user(&admin, false),
+            ItemQueryAccess::Unrestricted
+        );
+        assert_eq!(
+            ItemQueryAccess::for_user(&reader, false),
+            ItemQueryAccess::Published
+        );
+        assert_eq!(
+            ItemQueryAccess::for_user(&anonymous, false),
+            ItemQueryAccess::Denied
+        );
+        assert_eq!(
+            ItemQueryAccess::for_user(&anonymous, true),
+            ItemQueryAccess::Unrestricted
+        );
+    }
+}
@@ -9,13 +9,77 @@ use std::time::Duration;
 use anyhow::{Context, Result};
 use moka::sync::Cache;
 use sqlx::PgPool;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::cache::{ITEM_LISTING_TAG, page};
+use crate::content::item_query::ItemQueryAccess;
+use crate::content::{ContentTypeRegistry, compound, field_constraints, references};
+use crate::db::DbPools;
+use crate::metrics::{CacheRegion, CacheStage, CacheTier, Metrics};
+use crate::models::field_default::{Creator, FieldDefaultRule, apply_field_defaults};
+use crate::models::field_history::{FieldChange, FieldHistoryEntry, diff_tracked_fields};
+use crate::models::item::ItemModified;
+use crate::models::item_access::{self, ItemAccess};
+use crate::models::item_status::{ItemStatusChange, StatusChangeMeta};
+use crate::models::role::well_known::{ANONYMOUS_ROLE_ID, AUTHENTICATED_ROLE_ID};
 use crate::models::stage::{LIVE_STAGE_ID, Stage, StageVisibility};
-use crate::models::{CreateItem, Item, ItemRevision, UpdateItem};
+use crate::models::workflow::transition_permission;
+use crate::models::{
+    CreateItem, Item, ItemRevision, ItemType, Role, UpdateItem, User, Workflow, WorkflowTransition,
+};
+use crate::services::audit::AuditService;
+use crate::services::cascade::{self, DeleteRestricted};
+use crate::services::read_only::ReadOnlyService;
 use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
-use trovato_sdk::types::AccessResult;
+use trovato_sdk::types::{AccessResult, ItemGrant};
+
+/// Permission to reassign items to other authors and move them between
+/// stages, on top of edit access to each item.
+pub const ADMINISTER_CONTENT_PERMISSION: &str = "administer content";
+
+/// Whether `user` holds [`ADMINISTER_CONTENT_PERMISSION`] (admins always do).
+pub fn can_administer_content(user: &UserContext) -> bool {
+    user.is_admin() || user.has_permission(ADMINISTER_CONTENT_PERMISSION)
+}
+
+/// Apply a JSON merge patch (RFC 7386) to `target`.
+///
+/// Objects are merged recursively, `null` removes a key, and any other
+/// value replaces the target outright.
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::json!({});
+    }
+    if let serde_json::Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(
+                    target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
+        }
+    }
+}
+
+/// Changes between two field maps, over every field on either side.
+fn field_changes(old: &serde_json::Value, new: &serde_json::Value) -> Vec<FieldChange> {
+    let mut names: Vec<&str> = [old, new]
+        .into_iter()
+        .filter_map(serde_json::Value::as_object)
+        .flat_map(|fields| fields.keys().map(String::as_str))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    diff_tracked_fields(old, new, &names)
+}
 
 /// Maximum entries in the item cache.
 const MAX_CAPACITY: u64 = 50_000;
@@ -33,13 +97,25 @@ const STAGE_CACHE_TTL: Duration = Duration::from_secs(30);
 #[derive(Clone)]
 pub struct ItemService {
     inner: Arc<ItemServiceInner>,
+    /// Records item cache hits and misses when set.
+    metrics: Option<Arc<Metrics>>,
 }
 
 struct ItemServiceInner {
     pool: PgPool,
+    /// Pools for item view reads (may include read replicas).
+    read_pools: DbPools,
     dispatcher: Arc<TapDispatcher>,
     /// Services template for tap dispatch — cloned per invocation.
     tap_services: RequestServices,
+    /// Content type definitions, used to find fields with `track_history`.
+    content_types: Arc<ContentTypeRegistry>,
+    /// Audit trail for status changes (present when the audit_log plugin is enabled).
+    audit: Option<Arc<AuditService>>,
+    /// Read-only mode and per-type write locks.
+    read_only: Arc<ReadOnlyService>,
+    /// Reference hops followed by [`ItemService::invalidate_pages`].
+    reference_depth: u32,
     cache: Cache<Uuid, Item>,
     /// Cached stage lookups — stages rarely change and there are typically only 3.
     stage_cache: Cache<Uuid, Stage>,
@@ -86,6 +162,11 @@ pub struct ItemAccessInput {
     #[serde(default)]
     pub user_permissions: Vec<String>,
 
+    /// Names of the user's roles, including the built-in "anonymous user"
+    /// or "authenticated user" role.
+    #[serde(default)]
+    pub user_roles: Vec<String>,
+
     /// Stage UUID (None if item has no explicit stage).
     #[serde(default)]
     pub stage_id: Option<Uuid>,
@@ -95,19 +176,146 @@ pub struct ItemAccessInput {
     pub stage_machine_name: Option<String>,
 }
 
+/// Input for `tap_transition`, sent after an item changes moderation state.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. The kernel serializes this; plugins deserialize
+/// it. If you change fields here, update the SDK copy to match.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TransitionInput {
+    pub item_id: Uuid,
+    pub item_type: String,
+    /// Workflow machine name.
+    pub workflow: String,
+    /// State the item left.
+    pub from: String,
+    /// State the item entered.
+    pub to: String,
+    /// User who made the transition.
+    pub user_id: Uuid,
+}
+
+/// Input for `tap_item_validate`, sent before an item is created or updated.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. The kernel serializes this; plugins deserialize
+/// it. If you change fields here, update the SDK copy to match.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ItemValidateInput {
+    /// Item ID (`None` while the item is being created).
+    #[serde(default)]
+    pub item_id: Option<Uuid>,
+    pub item_type: String,
+    pub title: String,
+    /// Field values keyed by field machine name.
+    pub fields: serde_json::Value,
+    pub status: i16,
+    /// User saving the item.
+    pub user_id: Uuid,
+}
+
+/// Input for `tap_item_clone`, sent before a copy of an item is created.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. The kernel serializes this; plugins deserialize
+/// it. If you change fields here, update the SDK copy to match.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ItemCloneInput {
+    /// The item being copied.
+    pub source_id: Uuid,
+    pub item_type: String,
+    /// Title of the copy.
+    pub title: String,
+    /// Field values of the copy, keyed by field machine name.
+    pub fields: serde_json::Value,
+    /// Stage the copy is created in.
+    pub stage_id: Uuid,
+    /// Language of the copy.
+    pub language: String,
+    /// User making the copy.
+    pub user_id: Uuid,
+}
+
+/// A partial update of an item's fields, for [`ItemService::patch`].
+#[derive(Debug, Clone)]
+pub struct ItemPatch {
+    /// JSON merge patch (RFC 7386) applied to the item's `fields`.
+    pub fields: serde_json::Value,
+    /// Refuse the patch if the item changed after this Unix timestamp.
+    pub unmodified_since: Option<i64>,
+    /// Revision log message.
+    pub log: Option<String>,
+}
+
+/// Options for [`ItemService::clone_item`].
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct CloneOptions {
+    /// Title of the copy (default: the source title).
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Stage to create the copy in (default: the source stage).
+    #[serde(default)]
+    pub stage_id: Option<Uuid>,
+    /// Language of the copy (default: the source language).
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// A validation failure returned from `tap_item_validate`.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ItemViolation {
+    /// Field machine name, or `title`.
+    pub field: String,
+    /// Human-readable explanation shown to the editor.
+    pub message: String,
+    /// Machine-readable code (e.g., "required", "invalid_isbn").
+    pub code: String,
+}
+
+/// Error returned when plugins reject an item from `tap_item_validate`.
+///
+/// Converted to a 422 response by [`crate::error::AppError`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("item failed validation ({} violation(s))", .violations.len())]
+pub struct ItemValidationFailed {
+    /// Violations from all plugins, in dispatch order.
+    pub violations: Vec<ItemViolation>,
+}
+
+impl ItemValidationFailed {
+    /// Violation messages, for forms that list errors without field context.
+    pub fn messages(&self) -> Vec<String> {
+        self.violations.iter().map(|v| v.message.clone()).collect()
+    }
+}
+
 impl ItemService {
     /// Create a new item service.
+    ///
+    /// Writes and edit-path loads use the primary; [`Self::load_for_view`]
+    /// reads through `db`, which may spread them over read replicas.
     pub fn new(
-        pool: PgPool,
+        db: DbPools,
         dispatcher: Arc<TapDispatcher>,
         tap_services: RequestServices,
+        content_types: Arc<ContentTypeRegistry>,
+        audit: Option<Arc<AuditService>>,
+        read_only: Arc<ReadOnlyService>,
         ttl: Duration,
+        reference_depth: u32,
     ) -> Self {
         Self {
             inner: Arc::new(ItemServiceInner {
-                pool,
+                pool: db.primary().clone(),
+                read_pools: db,
                 dispatcher,
                 tap_services,
+                content_types,
+                audit,
+                read_only,
+                reference_depth,
                 cache: Cache::builder()
                     .max_capacity(MAX_CAPACITY)
                     .time_to_live(ttl)
@@ -121,6 +329,25 @@ impl ItemService {
                     .time_to_live(Duration::from_secs(300))
                     .build(),
             }),
+            metrics: None,
+        }
+    }
+
+    /// Record item cache metrics.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Record an item cache lookup.
+    fn record_lookup(&self, stage: CacheStage, hit: bool) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        if hit {
+            metrics.record_cache_hit(CacheRegion::Item, stage, CacheTier::L1);
+        } else {
+            metrics.record_cache_miss(CacheRegion::Item, stage);
         }
     }
 
@@ -134,7 +361,29 @@ impl ItemService {
     /// The presave tap fires before the item is persisted, allowing plugins
     /// to modify fields (e.g., AI content enrichment). The insert tap fires
     /// after persistence for post-save side effects.
+    ///
+    /// Role-conditional field defaults for the content type are applied
+    /// first, then the fields' declared default values, so presave plugins
+    /// see the defaulted values. Items staged in
+    /// a full personal workspace are refused. Items of a moderated type
+    /// start in the workflow's initial state, which also sets their status.
+    /// Write-locked types are refused.
     pub async fn create(&self, mut input: CreateItem, user: &UserContext) -> Result<Item> {
+        self.inner
+            .read_only
+            .check_item_type(&input.item_type)
+            .await?;
+        if let Some(stage_id) = input.stage_id {
+            crate::services::workspace::check_quota(&self.inner.pool, stage_id).await?;
+        }
+        self.apply_field_defaults(&mut input, user).await?;
+        self.apply_declared_defaults(&mut input).await?;
+
+        let workflow = Workflow::find_for_type(&self.inner.pool, &input.item_type).await?;
+        if let Some(initial) = workflow.as_ref().and_then(|wf| wf.state(&wf.initial_state)) {
+            input.status = Some(i16::from(initial.published));
+        }
+
         // Invoke tap_item_presave — plugins can modify fields before save.
         // Serialize the input as a JSON object so plugins can read/modify fields.
         let presave_json = serde_json::json!({
@@ -170,8 +419,29 @@ impl ItemService {
             }
         }
 
+        self.check_valid(
+            ItemValidateInput {
+                item_id: None,
+                item_type: input.item_type.clone(),
+                title: input.title.clone(),
+                fields: input
+                    .fields
+                    .clone()
+                    .unwrap_or_else(|| serde_json::json!({})),
+                status: input.status.unwrap_or(0),
+                user_id: user.id,
+            },
+            user,
+        )
+        .await?;
+
         // Create the item in the database
-        let item = Item::create(&self.inner.pool, input).await?;
+        let mut item = Item::create(&self.inner.pool, input).await?;
+
+        if let Some(wf) = workflow {
+            Item::set_moderation_state(&self.inner.pool, item.id, Some(&wf.initial_state)).await?;
+            item.moderation_state = Some(wf.initial_state);
+        }
 
         // Invoke tap_item_insert for post-insert taps
         let item_json = serde_json::to_string(&item).context("serialize item")?;
@@ -185,19 +455,30 @@ impl ItemService {
 
         // Tap errors are logged by the dispatcher
 
+        self.write_grants(item.id, &item_json, user).await;
+
+        self.invalidate_listings().await;
+
         info!(item_id = %item.id, item_type = %item.item_type, "item created");
         Ok(item)
     }
 
     /// Load an item by ID.
     pub async fn load(&self, id: Uuid) -> Result<Option<Item>> {
+        page::add_tag(page::item_tag(id));
+
         // Check cache first
         if let Some(item) = self.inner.cache.get(&id) {
+            self.record_lookup(CacheStage::of(item.stage_id), true);
             return Ok(Some(item));
         }
 
         // Load from database
         let item = Item::find_by_id(&self.inner.pool, id).await?;
+        self.record_lookup(
+            CacheStage::of(item.as_ref().map_or(LIVE_STAGE_ID, |i| i.stage_id)),
+            false,
+        );
 
         // Cache if found
         if let Some(ref i) = item {
@@ -216,13 +497,17 @@ impl ItemService {
     /// Falls back to `load()` if the item exists but isn't in any of the given stages
     /// (e.g., it was loaded by a direct UUID link).
     pub async fn load_with_overlay(&self, id: Uuid, stage_ids: &[Uuid]) -> Result<Option<Item>> {
+        page::add_tag(page::item_tag(id));
+
         // Check cache first (cache is stage-agnostic — items have single stage_id)
         if let Some(item) = self.inner.cache.get(&id) {
             // Verify the item's stage is in our overlay list
             if stage_ids.contains(&item.stage_id) {
+                self.record_lookup(CacheStage::of(item.stage_id), true);
                 return Ok(Some(item));
             }
         }
+        self.record_lookup(CacheStage::of_all(stage_ids), false);
 
         // Load from database — the item has a single stage_id
         let item = Item::find_by_id(&self.inner.pool, id).await?;
@@ -274,111 +559,1018 @@ impl ItemService {
         Ok(rows)
     }
 
-    /// Load an item and invoke tap_item_view for rendering.
-    pub async fn load_for_view(
-        &self,
-        id: Uuid,
-        user: &UserContext,
-    ) -> Result<Option<(Item, Vec<String>)>> {
-        let Some(item) = self.load(id).await? else {
+    /// Create or replace an item's translation into `language`.
+    ///
+    /// `fields` holds the translated values overlaid on the item's own
+    /// fields; fields left out fall back to the source values. Requires
+    /// edit access, and the overlaid item is validated like a patch: the
+    /// translated fields must belong to the type and meet its constraints,
+    /// and `tap_item_validate` objections to them (or the title) refuse it.
+    pub async fn save_translation(
+        &self,
+        item: &Item,
+        language: &str,
+        title: &str,
+        fields: serde_json::Value,
+        user: &UserContext,
+    ) -> Result<ItemTranslation> {
+        if !self.check_access(item, "edit", user).await? {
+            anyhow::bail!("access denied");
+        }
+        self.inner
+            .read_only
+            .check_item_type(&item.item_type)
+            .await?;
+        let Some(translated) = fields.as_object() else {
+            anyhow::bail!("translated fields must be a JSON object");
+        };
+        let touched: Vec<String> = translated.keys().cloned().collect();
+
+        let mut overlaid = match &item.fields {
+            serde_json::Value::Object(_) => item.fields.clone(),
+            _ => serde_json::json!({}),
+        };
+        if let Some(values) = overlaid.as_object_mut() {
+            for (name, value) in translated {
+                values.insert(name.clone(), value.clone());
+            }
+        }
+        self.check_patched_fields(&item.item_type, &overlaid, &touched)
+            .await?;
+
+        // As for patches, only objections to what was translated count.
+        let violations: Vec<ItemViolation> = self
+            .validate(
+                &ItemValidateInput {
+                    item_id: Some(item.id),
+                    item_type: item.item_type.clone(),
+                    title: if title.is_empty() {
+                        item.title.as_str()
+                    } else {
+                        title
+                    }
+                    .to_string(),
+                    fields: overlaid,
+                    status: item.status,
+                    user_id: user.id,
+                },
+                user,
+            )
+            .await?
+            .into_iter()
+            .filter(|v| v.field == "title" || touched.contains(&v.field))
+            .collect();
+        if !violations.is_empty() {
+            info!(
+                item_type = %item.item_type,
+                language,
+                violations = violations.len(),
+                "item translation rejected by tap_item_validate"
+            );
+            return Err(ItemValidationFailed { violations }.into());
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let translation = sqlx::query_as::<_, ItemTranslation>(
+            "INSERT INTO item_translation (item_id, language, title, fields, created, changed) \
+             VALUES ($1, $2, $3, $4, $5, $5) \
+             ON CONFLICT (item_id, language) \
+             DO UPDATE SET title = EXCLUDED.title, fields = EXCLUDED.fields, changed = EXCLUDED.changed \
+             RETURNING item_id, language, title, fields, created, changed",
+        )
+        .bind(item.id)
+        .bind(language)
+        .bind(title)
+        .bind(&fields)
+        .bind(now)
+        .fetch_one(&self.inner.pool)
+        .await
+        .context("failed to save item translation")?;
+
+        self.invalidate_pages(item.id, &item.item_type).await;
+        self.invalidate_listings().await;
+        Ok(translation)
+    }
+
+    /// Delete an item's translation into `language`.
+    ///
+    /// Requires edit access. Returns `false` if the item had no such
+    /// translation.
+    pub async fn delete_translation(
+        &self,
+        item: &Item,
+        language: &str,
+        user: &UserContext,
+    ) -> Result<bool> {
+        if !self.check_access(item, "edit", user).await? {
+            anyhow::bail!("access denied");
+        }
+        self.inner
+            .read_only
+            .check_item_type(&item.item_type)
+            .await?;
+        let result =
+            sqlx::query("DELETE FROM item_translation WHERE item_id = $1 AND language = $2")
+                .bind(item.id)
+                .bind(language)
+                .execute(&self.inner.pool)
+                .await
+                .context("failed to delete item translation")?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        self.invalidate_pages(item.id, &item.item_type).await;
+        self.invalidate_listings().await;
+        Ok(true)
+    }
+
+    /// Translated titles of `ids` in `language`, for items that have one.
+    pub async fn translated_titles(
+        &self,
+        ids: &[Uuid],
+        language: &str,
+    ) -> Result<std::collections::HashMap<Uuid, String>> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT item_id, title FROM item_translation \
+             WHERE item_id = ANY($1) AND language = $2 AND title <> ''",
+        )
+        .bind(ids)
+        .bind(language)
+        .fetch_all(&self.inner.pool)
+        .await
+        .context("failed to load translated titles")?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Load an item and invoke tap_item_view for rendering.
+    ///
+    /// With read replicas configured, a cache miss reads from a replica and
+    /// the result is not cached, so replication lag can't pin a stale copy
+    /// for the edit path.
+    pub async fn load_for_view(
+        &self,
+        id: Uuid,
+        user: &UserContext,
+    ) -> Result<Option<(Item, Vec<String>)>> {
+        let item = if self.inner.read_pools.has_replicas() {
+            page::add_tag(page::item_tag(id));
+            match self.inner.cache.get(&id) {
+                Some(item) => {
+                    self.record_lookup(CacheStage::of(item.stage_id), true);
+                    Some(item)
+                }
+                None => {
+                    let item = self
+                        .inner
+                        .read_pools
+                        .read_with_fallback(|pool| async move { Item::find_by_id(&pool, id).await })
+                        .await?;
+                    self.record_lookup(
+                        CacheStage::of(item.as_ref().map_or(LIVE_STAGE_ID, |i| i.stage_id)),
+                        false,
+                    );
+                    item
+                }
+            }
+        } else {
+            self.load(id).await?
+        };
+        let Some(item) = item else {
+            return Ok(None);
+        };
+
+        // Check access
+        if !self.check_access(&item, "view", user).await? {
+            return Ok(None); // Return None for access denied (shows as 404)
+        }
+
+        if let Some(cache) = self
+            .inner
+            .content_types
+            .get(&item.item_type)
+            .and_then(|def| def.cache)
+        {
+            page::add_cache_metadata(&cache);
+        }
+
+        // Invoke tap_item_view for rendering transformations
+        let item_json = serde_json::to_string(&item).context("serialize item")?;
+        let state = self.tap_state(user);
+
+        let results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_view", &item_json, state)
+            .await;
+
+        // Collect render outputs
+        let render_outputs: Vec<String> = results.into_iter().map(|r| r.output).collect();
+
+        Ok(Some((item, render_outputs)))
+    }
+
+    /// Update an item with tap_item_update invocation.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        mut input: UpdateItem,
+        user: &UserContext,
+    ) -> Result<Option<Item>> {
+        // Load existing item
+        let Some(existing) = self.load(id).await? else {
+            return Ok(None);
+        };
+
+        // Check access
+        if !self.check_access(&existing, "edit", user).await? {
+            anyhow::bail!("access denied");
+        }
+        self.inner
+            .read_only
+            .check_item_type(&existing.item_type)
+            .await?;
+
+        // Invoke tap_item_presave — plugins can modify fields before save.
+        let presave_json = serde_json::json!({
+            "item_type": existing.item_type,
+            "title": input.title.as_deref().unwrap_or(&existing.title),
+            "fields": input.fields.as_ref().unwrap_or(&existing.fields),
+            "status": input.status.unwrap_or(existing.status),
+        });
+        let presave_input = serde_json::to_string(&presave_json).context("serialize presave")?;
+        let presave_state = self.tap_state(user);
+
+        let presave_results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_presave", &presave_input, presave_state)
+            .await;
+
+        // Apply presave modifications — merge plugin-returned fields into input.
+        for result in presave_results {
+            if let Ok(modified) = serde_json::from_str::<serde_json::Value>(&result.output)
+                && let Some(fields) = modified.get("fields")
+                && let Some(obj) = fields.as_object()
+            {
+                let input_fields = input.fields.get_or_insert_with(|| existing.fields.clone());
+                if let Some(input_obj) = input_fields.as_object_mut() {
+                    for (k, v) in obj {
+                        input_obj.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+        }
+
+        self.check_valid(
+            ItemValidateInput {
+                item_id: Some(id),
+                item_type: existing.item_type.clone(),
+                title: input
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| existing.title.clone()),
+                fields: input
+                    .fields
+                    .clone()
+                    .unwrap_or_else(|| existing.fields.clone()),
+                status: input.status.unwrap_or(existing.status),
+                user_id: user.id,
+            },
+            user,
+        )
+        .await?;
+
+        // Status changes may carry reason/embargo context, which some types require.
+        let status_meta = input.status_meta.take().unwrap_or_default();
+        let status_changing = input.status.is_some_and(|s| s != existing.status);
+        if status_changing
+            && Workflow::find_for_type(&self.inner.pool, &existing.item_type)
+                .await?
+                .is_some()
+        {
+            anyhow::bail!(
+                "{} items are moderated; change their status with a workflow transition",
+                existing.item_type
+            );
+        }
+        if status_changing {
+            self.check_status_meta(&existing.item_type, &status_meta)
+                .await?;
+        }
+
+        // Update the item
+        let item = Item::update(&self.inner.pool, id, user.id, input).await?;
+
+        if let Some(ref i) = item {
+            if status_changing
+                && let Err(e) = self
+                    .record_status_change(existing.status, i, &status_meta, user)
+                    .await
+            {
+                warn!(item_id = %id, error = %e, "failed to record status change");
+            }
+
+            self.after_update(&existing, i, None, user).await?;
+            info!(item_id = %id, "item updated");
+        }
+
+        Ok(item)
+    }
+
+    /// Apply a JSON merge patch to an item's fields.
+    ///
+    /// Only patched fields are validated: they must be defined by the
+    /// item's type, required ones may not be emptied, and objections from
+    /// `tap_item_validate` count only for them. `tap_item_presave` and
+    /// `tap_item_update` receive the field changes under `changes`.
+    ///
+    /// Fails with [`ItemModified`] if the item changed after
+    /// `unmodified_since`, or was saved by someone else during the patch.
+    pub async fn patch(
+        &self,
+        id: Uuid,
+        patch: ItemPatch,
+        user: &UserContext,
+    ) -> Result<Option<Item>> {
+        // Read past the cache: the revision read here guards the write.
+        let Some(existing) = Item::find_by_id(&self.inner.pool, id).await? else {
+            return Ok(None);
+        };
+
+        if !self.check_access(&existing, "edit", user).await? {
+            anyhow::bail!("access denied");
+        }
+        self.inner
+            .read_only
+            .check_item_type(&existing.item_type)
+            .await?;
+        if patch
+            .unmodified_since
+            .is_some_and(|since| existing.changed > since)
+        {
+            return Err(ItemModified { id }.into());
+        }
+        let Some(patched) = patch.fields.as_object() else {
+            anyhow::bail!("field patch must be a JSON object");
+        };
+        let touched: Vec<String> = patched.keys().cloned().collect();
+
+        let mut fields = match &existing.fields {
+            serde_json::Value::Object(_) => existing.fields.clone(),
+            _ => serde_json::json!({}),
+        };
+        merge_patch(&mut fields, &patch.fields);
+        self.check_patched_fields(&existing.item_type, &fields, &touched)
+            .await?;
+        if field_changes(&existing.fields, &fields).is_empty() {
+            return Ok(Some(existing));
+        }
+
+        // Invoke tap_item_presave — plugins can modify fields before save.
+        let presave_json = serde_json::json!({
+            "item_type": existing.item_type,
+            "title": existing.title,
+            "fields": fields,
+            "status": existing.status,
+            "changes": field_changes(&existing.fields, &fields),
+        });
+        let presave_input = serde_json::to_string(&presave_json).context("serialize presave")?;
+        let presave_results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_presave", &presave_input, self.tap_state(user))
+            .await;
+        for result in presave_results {
+            if let Ok(modified) = serde_json::from_str::<serde_json::Value>(&result.output)
+                && let Some(obj) = modified.get("fields").and_then(|f| f.as_object())
+                && let Some(fields_obj) = fields.as_object_mut()
+            {
+                for (k, v) in obj {
+                    fields_obj.insert(k.clone(), v.clone());
+                }
+            }
+        }
+
+        // Untouched fields are not the caller's to fix, so plugin objections
+        // to them do not block the patch.
+        let violations: Vec<ItemViolation> = self
+            .validate(
+                &ItemValidateInput {
+                    item_id: Some(id),
+                    item_type: existing.item_type.clone(),
+                    title: existing.title.clone(),
+                    fields: fields.clone(),
+                    status: existing.status,
+                    user_id: user.id,
+                },
+                user,
+            )
+            .await?
+            .into_iter()
+            .filter(|v| touched.contains(&v.field))
+            .collect();
+        if !violations.is_empty() {
+            info!(
+                item_type = %existing.item_type,
+                violations = violations.len(),
+                "item patch rejected by tap_item_validate"
+            );
+            return Err(ItemValidationFailed { violations }.into());
+        }
+
+        let changes = field_changes(&existing.fields, &fields);
+        let input = UpdateItem {
+            title: None,
+            status: None,
+            promote: None,
+            sticky: None,
+            fields: Some(fields),
+            log: patch.log,
+            status_meta: None,
+        };
+        let item = Item::update_if_current(
+            &self.inner.pool,
+            id,
+            user.id,
+            input,
+            existing.current_revision_id,
+        )
+        .await?;
+
+        if let Some(ref i) = item {
+            self.after_update(&existing, i, Some(&changes), user)
+                .await?;
+            info!(item_id = %id, fields = changes.len(), "item patched");
+        }
+
+        Ok(item)
+    }
+
+    /// Check patched fields against the item type: each must be defined,
+    /// and required fields may not be emptied.
+    async fn check_patched_fields(
+        &self,
+        item_type: &str,
+        fields: &serde_json::Value,
+        touched: &[String],
+    ) -> Result<()> {
+        let definitions = self
+            .inner
+            .content_types
+            .get_or_load(item_type)
+            .await?
+            .map(|def| def.fields)
+            .unwrap_or_default();
+        let empty = serde_json::Map::new();
+        let values = fields.as_object().unwrap_or(&empty);
+
+        let mut violations = Vec::new();
+        for name in touched {
+            let Some(def) = definitions.iter().find(|d| &d.field_name == name) else {
+                violations.push(ItemViolation {
+                    field: name.clone(),
+                    message: format!("{item_type} items have no field {name}."),
+                    code: "unknown_field".to_string(),
+                });
+                continue;
+            };
+            for message in compound::validate_required_fields(values, std::slice::from_ref(def)) {
+                violations.push(ItemViolation {
+                    field: name.clone(),
+                    message,
+                    code: "required".to_string(),
+                });
+            }
+        }
+        violations.extend(field_constraints::validate_constraints(
+            values,
+            &definitions,
+            Some(touched),
+        ));
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ItemValidationFailed { violations }.into())
+        }
+    }
+
+    /// Record field history, invoke `tap_item_update`, refresh grants, and
+    /// invalidate caches after an item was saved.
+    ///
+    /// `changes`, when given, is added to the tap input under `changes`.
+    async fn after_update(
+        &self,
+        existing: &Item,
+        item: &Item,
+        changes: Option<&[FieldChange]>,
+        user: &UserContext,
+    ) -> Result<()> {
+        // Record changes to history-tracked fields
+        if let Err(e) = self.record_field_history(existing, item, user).await {
+            warn!(item_id = %item.id, error = %e, "failed to record field history");
+        }
+
+        // Invoke tap_item_update
+        let item_json = serde_json::to_string(item).context("serialize item")?;
+        let tap_input = match changes {
+            Some(changes) => {
+                let mut value = serde_json::to_value(item).context("serialize item")?;
+                value["changes"] = serde_json::to_value(changes).context("serialize changes")?;
+                value.to_string()
+            }
+            None => item_json.clone(),
+        };
+        let _results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_update", &tap_input, self.tap_state(user))
+            .await;
+
+        // Tap errors are logged by the dispatcher
+
+        self.write_grants(item.id, &item_json, user).await;
+
+        // Invalidate cache
+        self.invalidate(item.id);
+        self.invalidate_pages(item.id, &existing.item_type).await;
+        self.invalidate_listings().await;
+        Ok(())
+    }
+
+    /// Collect violations from every plugin implementing `tap_item_validate`.
+    ///
+    /// Plugins return a JSON list of violations; empty or unparseable output
+    /// counts as no objection.
+    pub async fn validate(
+        &self,
+        input: &ItemValidateInput,
+        user: &UserContext,
+    ) -> Result<Vec<ItemViolation>> {
+        let input_json = serde_json::to_string(input).context("serialize validate input")?;
+        let results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_validate", &input_json, self.tap_state(user))
+            .await;
+
+        let mut violations = Vec::new();
+        for result in results {
+            if result.output.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Vec<ItemViolation>>(&result.output) {
+                Ok(found) => violations.extend(found),
+                Err(e) => warn!(
+                    plugin = %result.plugin_name,
+                    error = %e,
+                    "ignoring malformed tap_item_validate output"
+                ),
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Refuse the save with [`ItemValidationFailed`] if a field breaks its
+    /// declared constraints or any plugin objects.
+    async fn check_valid(&self, input: ItemValidateInput, user: &UserContext) -> Result<()> {
+        let mut violations = match (
+            input.fields.as_object(),
+            self.inner
+                .content_types
+                .get_or_load(&input.item_type)
+                .await?,
+        ) {
+            (Some(fields), Some(def)) => {
+                field_constraints::validate_constraints(fields, &def.fields, None)
+            }
+            _ => Vec::new(),
+        };
+        violations.extend(self.validate(&input, user).await?);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        info!(
+            item_type = %input.item_type,
+            violations = violations.len(),
+            "item rejected by field constraints or tap_item_validate"
+        );
+        Err(ItemValidationFailed { violations }.into())
+    }
+
+    /// Record changes to fields declared with `track_history`.
+    async fn record_field_history(
+        &self,
+        before: &Item,
+        after: &Item,
+        user: &UserContext,
+    ) -> Result<()> {
+        let Some(def) = self
+            .inner
+            .content_types
+            .get_or_load(&after.item_type)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let tracked: Vec<&str> = def
+            .fields
+            .iter()
+            .filter(|f| f.track_history)
+            .map(|f| f.field_name.as_str())
+            .collect();
+        if tracked.is_empty() {
+            return Ok(());
+        }
+
+        let changes = diff_tracked_fields(&before.fields, &after.fields, &tracked);
+        FieldHistoryEntry::record(
+            &self.inner.pool,
+            after.id,
+            user.id,
+            after.current_revision_id,
+            &changes,
+        )
+        .await
+    }
+
+    /// Fill fields missing from `input` using the content type's default rules.
+    async fn apply_field_defaults(&self, input: &mut CreateItem, user: &UserContext) -> Result<()> {
+        let rules = FieldDefaultRule::list_for_type(&self.inner.pool, &input.item_type).await?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let (role_ids, profile) = if user.authenticated {
+            let roles = Role::get_user_roles(&self.inner.pool, user.id).await?;
+            let profile = User::find_by_id(&self.inner.pool, user.id)
+                .await?
+                .map(|u| u.fields)
+                .unwrap_or_default();
+            let mut role_ids: Vec<Uuid> = roles.into_iter().map(|r| r.id).collect();
+            role_ids.push(AUTHENTICATED_ROLE_ID);
+            (role_ids, profile)
+        } else {
+            (vec![ANONYMOUS_ROLE_ID], serde_json::Value::Null)
+        };
+        let creator = Creator {
+            id: user.id,
+            authenticated: user.authenticated,
+            role_ids: &role_ids,
+            fields: &profile,
+        };
+
+        let fields = input
+            .fields
+            .get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        let Some(obj) = fields.as_object_mut() else {
+            return Ok(());
+        };
+        let applied = apply_field_defaults(&rules, &creator, obj);
+        if !applied.is_empty() {
+            tracing::debug!(item_type = %input.item_type, fields = ?applied, "field defaults applied");
+        }
+        Ok(())
+    }
+
+    /// Fill fields still missing after the default rules with the
+    /// `default_value` declared on their definition.
+    async fn apply_declared_defaults(&self, input: &mut CreateItem) -> Result<()> {
+        let Some(def) = self
+            .inner
+            .content_types
+            .get_or_load(&input.item_type)
+            .await?
+        else {
+            return Ok(());
+        };
+        let fields = input
+            .fields
+            .get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        let Some(obj) = fields.as_object_mut() else {
+            return Ok(());
+        };
+        let applied = field_constraints::apply_default_values(obj, &def.fields);
+        if !applied.is_empty() {
+            debug!(item_type = %input.item_type, fields = ?applied, "declared field defaults applied");
+        }
+        Ok(())
+    }
+
+    /// Validate status change metadata against the item type's requirements.
+    async fn check_status_meta(&self, item_type: &str, meta: &StatusChangeMeta) -> Result<()> {
+        meta.validate()?;
+        if meta.reason_code.is_none()
+            && let Some(it) = ItemType::find_by_type(&self.inner.pool, item_type).await?
+            && it.requires_status_reason()
+        {
+            anyhow::bail!("a status reason is required for {item_type} items");
+        }
+        Ok(())
+    }
+
+    /// Record a status change with its metadata and write it to the audit log.
+    async fn record_status_change(
+        &self,
+        old_status: i16,
+        after: &Item,
+        meta: &StatusChangeMeta,
+        user: &UserContext,
+    ) -> Result<()> {
+        let change = ItemStatusChange::record(
+            &self.inner.pool,
+            after.id,
+            old_status,
+            after.status,
+            meta,
+            user.id,
+            after.current_revision_id,
+        )
+        .await?;
+
+        if let Some(audit) = &self.inner.audit {
+            let action = if after.is_published() {
+                "item.publish"
+            } else {
+                "item.unpublish"
+            };
+            audit
+                .log(
+                    action,
+                    "item",
+                    &after.id.to_string(),
+                    Some(user.id),
+                    "",
+                    serde_json::json!({
+                        "item_type": after.item_type,
+                        "old_status": change.old_status,
+                        "new_status": change.new_status,
+                        "reason_code": change.reason_code,
+                        "note": change.note,
+                        "embargo_until": change.embargo_until,
+                        "revision_id": change.revision_id,
+                    }),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// List recorded status changes for an item, newest first.
+    pub async fn status_changes(&self, item_id: Uuid, limit: i64) -> Result<Vec<ItemStatusChange>> {
+        ItemStatusChange::list_for_item(&self.inner.pool, item_id, limit).await
+    }
+
+    /// The workflow moderating an item and the transitions the user may take.
+    ///
+    /// Returns `None` if the item's type is not moderated. Items that have
+    /// not entered the workflow are treated as being in its initial state.
+    pub async fn available_transitions(
+        &self,
+        item: &Item,
+        user: &UserContext,
+    ) -> Result<Option<(Workflow, Vec<WorkflowTransition>)>> {
+        let Some(workflow) = Workflow::find_for_type(&self.inner.pool, &item.item_type).await?
+        else {
+            return Ok(None);
+        };
+        let from = item
+            .moderation_state
+            .as_deref()
+            .unwrap_or(&workflow.initial_state);
+        let allowed = workflow
+            .transitions_from(from)
+            .filter(|t| {
+                user.is_admin()
+                    || user.has_permission(&transition_permission(&workflow.id, &t.from, &t.to))
+            })
+            .cloned()
+            .collect();
+        Ok(Some((workflow, allowed)))
+    }
+
+    /// Move an item to another moderation state with tap_transition invocation.
+    ///
+    /// The transition must exist in the item's workflow and the user needs
+    /// its permission (`transition {workflow} from {from} to {to}`) as well
+    /// as edit access. The item's status follows the target state's
+    /// `published` flag; a status change records a revision and `meta`.
+    pub async fn transition(
+        &self,
+        id: Uuid,
+        to: &str,
+        meta: StatusChangeMeta,
+        user: &UserContext,
+    ) -> Result<Option<Item>> {
+        let Some(existing) = self.load(id).await? else {
+            return Ok(None);
+        };
+
+        if !self.check_access(&existing, "edit", user).await? {
+            anyhow::bail!("access denied");
+        }
+
+        let Some(workflow) = Workflow::find_for_type(&self.inner.pool, &existing.item_type).await?
+        else {
+            anyhow::bail!(
+                "invalid transition: {} items are not moderated",
+                existing.item_type
+            );
+        };
+        let from = existing
+            .moderation_state
+            .clone()
+            .unwrap_or_else(|| workflow.initial_state.clone());
+        let Some(target) = workflow.state(to) else {
+            anyhow::bail!(
+                "invalid transition: '{to}' is not a state of workflow '{}'",
+                workflow.id
+            );
+        };
+        if !workflow.allows(&from, to) {
+            anyhow::bail!(
+                "invalid transition: workflow '{}' has no transition from {from} to {to}",
+                workflow.id
+            );
+        }
+        if !user.is_admin() && !user.has_permission(&transition_permission(&workflow.id, &from, to))
+        {
+            anyhow::bail!("access denied");
+        }
+        self.inner
+            .read_only
+            .check_item_type(&existing.item_type)
+            .await?;
+
+        let status = i16::from(target.published);
+        let status_changing = status != existing.status;
+        if status_changing {
+            self.check_status_meta(&existing.item_type, &meta).await?;
+            let input = UpdateItem {
+                title: None,
+                status: Some(status),
+                promote: None,
+                sticky: None,
+                fields: None,
+                log: Some(format!("Moderation: {from} → {to}")),
+                status_meta: None,
+            };
+            Item::update(&self.inner.pool, id, user.id, input).await?;
+        }
+        Item::set_moderation_state(&self.inner.pool, id, Some(to)).await?;
+
+        self.invalidate(id);
+        let Some(item) = Item::find_by_id(&self.inner.pool, id).await? else {
             return Ok(None);
         };
 
-        // Check access
-        if !self.check_access(&item, "view", user).await? {
-            return Ok(None); // Return None for access denied (shows as 404)
+        if status_changing
+            && let Err(e) = self
+                .record_status_change(existing.status, &item, &meta, user)
+                .await
+        {
+            warn!(item_id = %id, error = %e, "failed to record status change");
         }
 
-        // Invoke tap_item_view for rendering transformations
-        let item_json = serde_json::to_string(&item).context("serialize item")?;
-        let state = self.tap_state(user);
-
-        let results = self
+        let input = TransitionInput {
+            item_id: id,
+            item_type: item.item_type.clone(),
+            workflow: workflow.id.clone(),
+            from: from.clone(),
+            to: to.to_string(),
+            user_id: user.id,
+        };
+        let input_json = serde_json::to_string(&input).context("serialize transition")?;
+        let _results = self
             .inner
             .dispatcher
-            .dispatch("tap_item_view", &item_json, state)
+            .dispatch("tap_transition", &input_json, self.tap_state(user))
             .await;
 
-        // Collect render outputs
-        let render_outputs: Vec<String> = results.into_iter().map(|r| r.output).collect();
+        self.invalidate_pages(id, &item.item_type).await;
+        self.invalidate_listings().await;
 
-        Ok(Some((item, render_outputs)))
+        info!(item_id = %id, workflow = %workflow.id, %from, %to, "item transitioned");
+        Ok(Some(item))
     }
 
-    /// Update an item with tap_item_update invocation.
-    pub async fn update(
+    /// Get the change timeline for a history-tracked field, newest first.
+    pub async fn field_history(
+        &self,
+        item_id: Uuid,
+        field_name: &str,
+        limit: i64,
+    ) -> Result<Vec<FieldHistoryEntry>> {
+        FieldHistoryEntry::list_for_field(&self.inner.pool, item_id, field_name, limit).await
+    }
+
+    /// Reassign an item to another author.
+    ///
+    /// Requires [`ADMINISTER_CONTENT_PERMISSION`] as well as edit access.
+    /// Authorship is not content, so no revision is created;
+    /// `tap_item_update` still fires and grants are recomputed, since both
+    /// may depend on the author.
+    pub async fn change_author(
         &self,
         id: Uuid,
-        mut input: UpdateItem,
+        author_id: Uuid,
         user: &UserContext,
     ) -> Result<Option<Item>> {
-        // Load existing item
+        if !can_administer_content(user) {
+            anyhow::bail!("access denied");
+        }
         let Some(existing) = self.load(id).await? else {
             return Ok(None);
         };
-
-        // Check access
         if !self.check_access(&existing, "edit", user).await? {
             anyhow::bail!("access denied");
         }
+        self.inner
+            .read_only
+            .check_item_type(&existing.item_type)
+            .await?;
 
-        // Invoke tap_item_presave — plugins can modify fields before save.
-        let presave_json = serde_json::json!({
-            "item_type": existing.item_type,
-            "title": input.title.as_deref().unwrap_or(&existing.title),
-            "fields": input.fields.as_ref().unwrap_or(&existing.fields),
-            "status": input.status.unwrap_or(existing.status),
-        });
-        let presave_input = serde_json::to_string(&presave_json).context("serialize presave")?;
-        let presave_state = self.tap_state(user);
-
-        let presave_results = self
-            .inner
-            .dispatcher
-            .dispatch("tap_item_presave", &presave_input, presave_state)
-            .await;
-
-        // Apply presave modifications — merge plugin-returned fields into input.
-        for result in presave_results {
-            if let Ok(modified) = serde_json::from_str::<serde_json::Value>(&result.output)
-                && let Some(fields) = modified.get("fields")
-                && let Some(obj) = fields.as_object()
-            {
-                let input_fields = input.fields.get_or_insert_with(|| existing.fields.clone());
-                if let Some(input_obj) = input_fields.as_object_mut() {
-                    for (k, v) in obj {
-                        input_obj.insert(k.clone(), v.clone());
-                    }
-                }
-            }
+        if !Item::set_author(&self.inner.pool, id, author_id).await? {
+            return Ok(None);
         }
+        info!(item_id = %id, author_id = %author_id, "item author changed");
+        self.after_placement_change(&existing, user).await
+    }
 
-        // Update the item
-        let item = Item::update(&self.inner.pool, id, user.id, input).await?;
-
-        if let Some(ref i) = item {
-            // Invoke tap_item_update
-            let item_json = serde_json::to_string(i).context("serialize item")?;
-            let state = self.tap_state(user);
-
-            let _results = self
-                .inner
-                .dispatcher
-                .dispatch("tap_item_update", &item_json, state)
-                .await;
+    /// Move an item, with its URL aliases, to another stage.
+    ///
+    /// Requires [`ADMINISTER_CONTENT_PERMISSION`] as well as edit access.
+    /// Items reach live by publishing their stage, never by a move, and
+    /// other users' personal workspaces are off limits. Full personal
+    /// workspaces are refused as for new items.
+    pub async fn move_to_stage(
+        &self,
+        id: Uuid,
+        stage_id: Uuid,
+        user: &UserContext,
+    ) -> Result<Option<Item>> {
+        if !can_administer_content(user) {
+            anyhow::bail!("access denied");
+        }
+        if stage_id == LIVE_STAGE_ID {
+            anyhow::bail!("items cannot be moved to the live stage; publish their stage instead");
+        }
+        if let Some(workspace) =
+            crate::services::workspace::find_by_stage(&self.inner.pool, stage_id).await?
+            && workspace.user_id != user.id
+        {
+            anyhow::bail!("access denied: stage is another user's personal workspace");
+        }
+        let Some(existing) = self.load(id).await? else {
+            return Ok(None);
+        };
+        if !self.check_access(&existing, "edit", user).await? {
+            anyhow::bail!("access denied");
+        }
+        self.inner
+            .read_only
+            .check_item_type(&existing.item_type)
+            .await?;
+        crate::services::workspace::check_quota(&self.inner.pool, stage_id).await?;
 
-            // Tap errors are logged by the dispatcher
+        if !Item::set_stage(&self.inner.pool, id, stage_id).await? {
+            return Ok(None);
+        }
+        info!(item_id = %id, stage_id = %stage_id, "item moved to stage");
+        self.after_placement_change(&existing, user).await
+    }
 
-            // Invalidate cache
-            self.invalidate(id);
+    /// Reload an item whose author or stage changed, then notify plugins and
+    /// drop cached copies.
+    async fn after_placement_change(
+        &self,
+        existing: &Item,
+        user: &UserContext,
+    ) -> Result<Option<Item>> {
+        self.invalidate(existing.id);
+        let Some(item) = self.load(existing.id).await? else {
+            return Ok(None);
+        };
 
-            info!(item_id = %id, "item updated");
-        }
+        let item_json = serde_json::to_string(&item).context("serialize item")?;
+        let _results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_update", &item_json, self.tap_state(user))
+            .await;
+        self.write_grants(item.id, &item_json, user).await;
 
-        Ok(item)
+        self.invalidate_pages(item.id, &item.item_type).await;
+        self.invalidate_listings().await;
+        Ok(Some(item))
     }
 
     /// Delete an item with tap_item_delete invocation.
+    ///
+    /// Fails with [`DeleteRestricted`] while reference fields with the
+    /// `restrict` rule point at the item.
     pub async fn delete(&self, id: Uuid, user: &UserContext) -> Result<bool> {
         // Load item
         let Some(item) = self.load(id).await? else {
@@ -389,6 +1581,26 @@ impl ItemService {
         if !self.check_access(&item, "delete", user).await? {
             anyhow::bail!("access denied");
         }
+        self.inner
+            .read_only
+            .check_item_type(&item.item_type)
+            .await?;
+
+        let types = self.inner.content_types.list_all().await;
+        let references =
+            cascade::restricting_references(&self.inner.pool, &types, id, &item.item_type).await?;
+        if !references.is_empty() {
+            info!(
+                item_id = %id,
+                references = references.len(),
+                "item deletion refused by restricting references"
+            );
+            return Err(DeleteRestricted {
+                item_id: id,
+                references,
+            }
+            .into());
+        }
 
         // Invoke tap_item_delete (can abort deletion)
         let item_json = serde_json::to_string(&item).context("serialize item")?;
@@ -408,6 +1620,8 @@ impl ItemService {
         if deleted {
             // Invalidate cache
             self.invalidate(id);
+            self.invalidate_pages(id, &item.item_type).await;
+            self.invalidate_listings().await;
             info!(item_id = %id, "item deleted");
         }
 
@@ -423,6 +1637,10 @@ impl ItemService {
     /// 4. Plugin `tap_item_access` — Deny wins, then Grant
     /// 5. Role-based fallback — generic and type-specific permission patterns
     ///
+    /// Items with grants from `tap_item_grants` skip steps 3 and 5: after
+    /// the plugin taps, their grants decide, matching what listings and
+    /// search show.
+    ///
     /// **Design note:** The published-view fast-path (step 3) runs before plugin
     /// dispatch. This means plugins cannot Deny published items on public stages
     /// via `tap_item_access` for "view" operations. This is intentional — it
@@ -436,6 +1654,14 @@ impl ItemService {
         operation: &str,
         user: &UserContext,
     ) -> Result<bool> {
+        // 0. On a multisite deployment, items of other sites do not exist
+        //    for this one — not even for admins.
+        if crate::services::site::in_scope()
+            && item.tenant_id != crate::services::site::current_tenant_id()
+        {
+            return Ok(false);
+        }
+
         // 1. Admin always has access
         if user.is_admin() {
             return Ok(true);
@@ -485,10 +1711,15 @@ impl ItemService {
             return Ok(false);
         }
 
+        // Grants, when the item has any, replace the fast-path and the
+        // role-based fallback.
+        let granted = ItemAccess::check(&self.inner.pool, item.id, user.id, operation).await?;
+
         // 3. Published content on public/live stages is viewable by anyone
         //    with "access content". Skip this fast-path for internal stages
         //    so plugins can enforce stage-specific permissions.
         if operation == "view"
+            && granted.is_none()
             && !is_internal
             && item.is_published()
             && user.has_permission("access content")
@@ -499,7 +1730,13 @@ impl ItemService {
         // 4. Build access check input with full context for plugins.
         //    stage_id and stage_machine_name are Option in the SDK for
         //    forward-compatibility, but the kernel always populates them
-        //    here since every item has a stage_id.
+        //    here since every item has a stage_id. Roles are only loaded
+        //    when a plugin implements the tap.
+        let user_roles = if self.inner.dispatcher.registry().has_tap("tap_item_access") {
+            Role::get_user_role_names(&self.inner.pool, user.id, user.authenticated).await?
+        } else {
+            Vec::new()
+        };
         let input = ItemAccessInput {
             item_id: item.id,
             item_type: item.item_type.clone(),
@@ -508,6 +1745,7 @@ impl ItemService {
             user_id: user.id,
             user_authenticated: user.authenticated,
             user_permissions: user.permissions.clone(),
+            user_roles,
             stage_id: Some(item.stage_id),
             stage_machine_name,
         };
@@ -540,6 +1778,10 @@ impl ItemService {
             return Ok(true);
         }
 
+        if let Some(granted) = granted {
+            return Ok(granted);
+        }
+
         // 5. Fall back to role-based permissions. Check both type-specific and
         // generic patterns, plus own-vs-any variants:
         //   "{op} any content"             — generic, any author
@@ -547,6 +1789,7 @@ impl ItemService {
         //   "{op} any {type}"              — type-specific, any author
         //   "{op} own {type}"              — type-specific, own items only
         //   "{op} {type} content"          — legacy pattern
+        //   "view own unpublished {type} content" — own drafts, also listed
         let is_own = user.id == item.author_id;
         let checks: &[String] = &[
             format!("{operation} any content"),
@@ -569,6 +1812,14 @@ impl ItemService {
                     return Ok(true);
                 }
             }
+            if operation == "view"
+                && !item.is_published()
+                && user.has_permission(&crate::permissions::view_own_unpublished_permission(
+                    &item.item_type,
+                ))
+            {
+                return Ok(true);
+            }
         }
         Ok(false)
     }
@@ -639,14 +1890,23 @@ impl ItemService {
         accessible
     }
 
-    /// List items by type.
+    /// List the current site's items of a type.
     pub async fn list_by_type(&self, item_type: &str) -> Result<Vec<Item>> {
         Item::list_by_type(&self.inner.pool, item_type).await
     }
 
-    /// List published items.
-    pub async fn list_published(&self, limit: i64, offset: i64) -> Result<Vec<Item>> {
-        Item::list_published(&self.inner.pool, limit, offset).await
+    /// List the current site's published items `user` may see.
+    ///
+    /// Admins bypass item grants; everyone else only sees restricted items
+    /// they hold a view grant for.
+    pub async fn list_published(
+        &self,
+        user: &UserContext,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Item>> {
+        let viewer = ItemQueryAccess::for_user(user, false).grants_user(user);
+        Item::list_published(&self.inner.pool, viewer, limit, offset).await
     }
 
     /// List items with filtering and return total count for pagination.
@@ -671,11 +1931,156 @@ impl ItemService {
         Ok((items, total))
     }
 
+    /// List items by the reason code of their latest status change, with total count.
+    pub async fn list_by_status_reason(
+        &self,
+        item_type: Option<&str>,
+        status: Option<i16>,
+        reason_code: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Item>, i64)> {
+        let items = Item::list_by_status_reason(
+            &self.inner.pool,
+            item_type,
+            status,
+            reason_code,
+            limit,
+            offset,
+        )
+        .await?;
+        let total =
+            Item::count_by_status_reason(&self.inner.pool, item_type, status, reason_code).await?;
+        Ok((items, total))
+    }
+
+    /// Latest status change for each of the given items, keyed by item ID.
+    pub async fn latest_status_changes(
+        &self,
+        item_ids: &[Uuid],
+    ) -> Result<std::collections::HashMap<Uuid, ItemStatusChange>> {
+        ItemStatusChange::latest_for_items(&self.inner.pool, item_ids).await
+    }
+
     /// Get revisions for an item.
     pub async fn get_revisions(&self, item_id: Uuid) -> Result<Vec<ItemRevision>> {
         Item::get_revisions(&self.inner.pool, item_id).await
     }
 
+    /// Get a single revision by ID.
+    pub async fn get_revision(&self, revision_id: Uuid) -> Result<Option<ItemRevision>> {
+        Item::get_revision(&self.inner.pool, revision_id).await
+    }
+
+    /// List items in a stage with pagination, returning the page and total.
+    pub async fn list_in_stage(
+        &self,
+        stage_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Item>, i64)> {
+        let items = Item::list_in_stage(&self.inner.pool, stage_id, limit, offset).await?;
+        let total = Item::count_in_stage(&self.inner.pool, stage_id).await?;
+        Ok((items, total))
+    }
+
+    /// Find the live copy of a staged item, if it has been published before.
+    pub async fn find_live_counterpart(&self, item: &Item) -> Result<Option<Item>> {
+        Item::find_in_group(&self.inner.pool, item.item_group_id, LIVE_STAGE_ID).await
+    }
+
+    /// Create an unpublished copy of an item.
+    ///
+    /// The copy gets a deep copy of the source fields and a new item
+    /// group, optionally in another stage or language. When copying into
+    /// another language, an existing translation of the source in that
+    /// language is used as the starting point. `tap_item_clone` runs before
+    /// the copy is saved so plugins can adjust its fields (e.g., clear
+    /// schedules or duplicate referenced files); the copy is then created
+    /// through [`Self::create`], so presave, validation and insert taps run
+    /// as usual.
+    ///
+    /// Returns `None` if the source doesn't exist. The user needs view
+    /// access to the source; callers check create permission.
+    pub async fn clone_item(
+        &self,
+        id: Uuid,
+        options: CloneOptions,
+        user: &UserContext,
+    ) -> Result<Option<Item>> {
+        let Some(source) = self.load(id).await? else {
+            return Ok(None);
+        };
+        if !self.check_access(&source, "view", user).await? {
+            anyhow::bail!("access denied");
+        }
+
+        let language = options.language.unwrap_or_else(|| source.language.clone());
+        let mut title = source.title.clone();
+        let mut fields = source.fields.clone();
+        if language != source.language {
+            match self.load_translation(id, &language).await {
+                Ok(Some(translation)) => {
+                    if !translation.title.is_empty() {
+                        title = translation.title;
+                    }
+                    if let Some(changes) = translation.fields.as_object() {
+                        apply_clone_changes(&mut fields, changes);
+                    }
+                }
+                Ok(None) => {}
+                // The translation table only exists with the
+                // content translation plugin; copy the source as-is.
+                Err(e) => debug!(item_id = %id, error = %e, "no translation to clone from"),
+            }
+        }
+
+        let mut input = ItemCloneInput {
+            source_id: id,
+            item_type: source.item_type.clone(),
+            title: options.title.unwrap_or(title),
+            fields,
+            stage_id: options.stage_id.unwrap_or(source.stage_id),
+            language,
+            user_id: user.id,
+        };
+
+        let clone_json = serde_json::to_string(&input).context("serialize clone input")?;
+        let results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_clone", &clone_json, self.tap_state(user))
+            .await;
+        for result in results {
+            if let Ok(modified) = serde_json::from_str::<serde_json::Value>(&result.output)
+                && let Some(changes) = modified.get("fields").and_then(|f| f.as_object())
+            {
+                apply_clone_changes(&mut input.fields, changes);
+            }
+        }
+
+        let item = self
+            .create(
+                CreateItem {
+                    item_type: input.item_type,
+                    title: input.title,
+                    author_id: user.id,
+                    status: Some(0),
+                    promote: Some(source.promote),
+                    sticky: Some(source.sticky),
+                    fields: Some(input.fields),
+                    stage_id: Some(input.stage_id),
+                    language: Some(input.language),
+                    log: Some(format!("Cloned from {id}")),
+                },
+                user,
+            )
+            .await?;
+
+        info!(item_id = %item.id, source_id = %id, "item cloned");
+        Ok(Some(item))
+    }
+
     /// Revert an item to a previous revision.
     pub async fn revert_to_revision(
         &self,
@@ -698,6 +2103,8 @@ impl ItemService {
 
         // Invalidate cache
         self.invalidate(item_id);
+        self.invalidate_pages(item_id, &item.item_type).await;
+        self.invalidate_listings().await;
 
         // Invoke tap_item_update for the revert
         let item_json = serde_json::to_string(&updated).context("serialize item")?;
@@ -708,16 +2115,101 @@ impl ItemService {
             .dispatcher
             .dispatch("tap_item_update", &item_json, state)
             .await;
+        self.write_grants(item_id, &item_json, user).await;
 
         info!(item_id = %item_id, revision_id = %revision_id, "item reverted");
         Ok(updated)
     }
 
+    /// Recompute an item's access grants from `tap_item_grants`.
+    ///
+    /// With no plugin implementing the tap, stale grants are cleared. If a
+    /// plugin fails, the stored grants are kept rather than dropping
+    /// restrictions it may have set.
+    async fn write_grants(&self, item_id: Uuid, item_json: &str, user: &UserContext) {
+        let expected = self
+            .inner
+            .dispatcher
+            .registry()
+            .handler_count("tap_item_grants");
+        let results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_item_grants", item_json, self.tap_state(user))
+            .await;
+
+        let mut grants = Vec::new();
+        for result in &results {
+            match serde_json::from_str::<Vec<ItemGrant>>(&result.output) {
+                Ok(list) => grants.extend(list),
+                Err(e) => {
+                    warn!(
+                        plugin = %result.plugin_name,
+                        item_id = %item_id,
+                        error = %e,
+                        "invalid tap_item_grants output, keeping stored grants"
+                    );
+                    return;
+                }
+            }
+        }
+        if results.len() < expected {
+            warn!(item_id = %item_id, "tap_item_grants failed, keeping stored grants");
+            return;
+        }
+
+        let grants = item_access::merge_grants(grants);
+        if let Err(e) = ItemAccess::replace(&self.inner.pool, item_id, &grants).await {
+            warn!(item_id = %item_id, error = %e, "failed to store item grants");
+        }
+    }
+
     /// Invalidate cached item.
     pub fn invalidate(&self, id: Uuid) {
         self.inner.cache.invalidate(&id);
     }
 
+    /// Invalidate cached pages that displayed an item, and those of items
+    /// referencing it (up to the configured reference depth).
+    pub async fn invalidate_pages(&self, id: Uuid, item_type: &str) {
+        let Some(cache) = &self.inner.tap_services.cache else {
+            return;
+        };
+        cache.invalidate_tag(&page::item_tag(id)).await;
+
+        if self.inner.reference_depth == 0 {
+            return;
+        }
+        let types = self.inner.content_types.list();
+        match references::referencing_items(
+            &self.inner.pool,
+            &types,
+            id,
+            item_type,
+            self.inner.reference_depth,
+        )
+        .await
+        {
+            Ok(referrers) => {
+                for (referrer, _) in &referrers {
+                    cache.invalidate_tag(&page::item_tag(*referrer)).await;
+                }
+                if !referrers.is_empty() {
+                    debug!(item_id = %id, referrers = referrers.len(), "invalidated referencing items");
+                }
+            }
+            Err(e) => warn!(item_id = %id, error = %e, "failed to invalidate referencing items"),
+        }
+    }
+
+    /// Invalidate shared cached output derived from item listings
+    /// (e.g. the sitemap and gather pages).
+    async fn invalidate_listings(&self) {
+        if let Some(cache) = &self.inner.tap_services.cache {
+            cache.invalidate_tag(ITEM_LISTING_TAG).await;
+        }
+    }
+
     /// Clear all cached items and stages.
     pub fn clear_cache(&self) {
         self.inner.cache.invalidate_all();
@@ -736,6 +2228,26 @@ impl ItemService {
     }
 }
 
+/// Merge field changes into a copy's fields; `null` removes a field.
+fn apply_clone_changes(
+    fields: &mut serde_json::Value,
+    changes: &serde_json::Map<String, serde_json::Value>,
+) {
+    if !fields.is_object() {
+        *fields = serde_json::Value::Object(serde_json::Map::new());
+    }
+    let Some(obj) = fields.as_object_mut() else {
+        return;
+    };
+    for (name, value) in changes {
+        if value.is_null() {
+            obj.remove(name);
+        } else {
+            obj.insert(name.clone(), value.clone());
+        }
+    }
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -752,6 +2264,7 @@ mod tests {
             user_id: Uuid::nil(),
             user_authenticated: false,
             user_permissions: vec![],
+            user_roles: vec![],
             stage_id: None,
             stage_machine_name: None,
         };
@@ -769,6 +2282,7 @@ mod tests {
         assert_eq!(input.operation, "edit");
         assert!(!input.user_authenticated);
         assert!(input.user_permissions.is_empty());
+        assert!(input.user_roles.is_empty());
         assert!(input.stage_id.is_none());
         assert!(input.stage_machine_name.is_none());
     }
@@ -788,6 +2302,7 @@ mod tests {
             user_id: id3,
             user_authenticated: true,
             user_permissions: vec!["edit any content".to_string()],
+            user_roles: vec!["authenticated user".to_string(), "editor".to_string()],
             stage_id: Some(stage),
             stage_machine_name: Some("curated".to_string()),
         };
@@ -801,7 +2316,66 @@ mod tests {
         assert_eq!(parsed.operation, "delete");
         assert!(parsed.user_authenticated);
         assert_eq!(parsed.user_permissions, vec!["edit any content"]);
+        assert_eq!(parsed.user_roles, vec!["authenticated user", "editor"]);
         assert_eq!(parsed.stage_id, Some(stage));
         assert_eq!(parsed.stage_machine_name.as_deref(), Some("curated"));
     }
+
+    #[test]
+    fn clone_changes_replace_and_remove_fields() {
+        let mut fields = serde_json::json!({
+            "field_body": "Text",
+            "field_publish_on": 1_800_000_000,
+            "field_image": "file-1",
+        });
+        let changes = serde_json::json!({
+            "field_publish_on": null,
+            "field_image": "file-2",
+        });
+        apply_clone_changes(&mut fields, changes.as_object().unwrap());
+        assert_eq!(
+            fields,
+            serde_json::json!({"field_body": "Text", "field_image": "file-2"})
+        );
+
+        let mut empty = serde_json::Value::Null;
+        apply_clone_changes(&mut empty, changes.as_object().unwrap());
+        assert_eq!(empty, serde_json::json!({"field_image": "file-2"}));
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc_7386() {
+        let mut fields = serde_json::json!({
+            "field_body": {"value": "Old", "format": "plain_text"},
+            "field_tags": ["a", "b"],
+            "field_subtitle": "Sub",
+        });
+        let patch = serde_json::json!({
+            "field_body": {"value": "New"},
+            "field_tags": ["c"],
+            "field_subtitle": null,
+            "field_rating": 4,
+        });
+        merge_patch(&mut fields, &patch);
+        assert_eq!(
+            fields,
+            serde_json::json!({
+                "field_body": {"value": "New", "format": "plain_text"},
+                "field_tags": ["c"],
+                "field_rating": 4,
+            })
+        );
+    }
+
+    #[test]
+    fn field_changes_cover_added_changed_and_removed_fields() {
+        let old = serde_json::json!({"field_a": 1, "field_b": 2, "field_c": 3});
+        let new = serde_json::json!({"field_a": 1, "field_b": 5, "field_d": 4});
+        let changes = field_changes(&old, &new);
+        let names: Vec<&str> = changes.iter().map(|c| c.field_name.as_str()).collect();
+        assert_eq!(names, ["field_b", "field_c", "field_d"]);
+        assert_eq!(changes[1].new_value, None);
+        assert_eq!(changes[2].old_value, None);
+        assert!(field_changes(&old, &old).is_empty());
+    }
 }
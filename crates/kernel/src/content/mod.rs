@@ -2,25 +2,38 @@
 //!
 //! This module provides:
 //! - ContentTypeRegistry: Manages content type definitions from plugins
+//! - diff: Sanitized HTML diffs between item versions
+//! - display: Field display settings for item pages and teasers
+//! - field_constraints: Declared field defaults and value constraints
 //! - ItemService: CRUD operations with tap invocations
+//! - item_query: Structured, access-checked item queries for plugins
+//! - references: Reverse lookups over record reference fields
 //! - FilterPipeline: Text format filtering for security
-//! - FormBuilder: Auto-generated admin forms
+//! - FormBuilder: Auto-generated admin forms, laid out by field widgets and groups
 //! - BlockTypeRegistry: Block type definitions and validation for block editor
 //! - BlockRenderer: Server-side block rendering for Editor.js content
 
 pub mod block_render;
 pub mod block_types;
 pub mod compound;
+pub mod diff;
+pub mod display;
+pub mod field_constraints;
 mod filter;
 mod form;
+pub mod item_query;
 mod item_service;
 pub mod page_builder;
 pub mod page_builder_components;
+pub mod references;
 mod type_registry;
 
 pub use block_render::render_blocks;
 pub use block_types::{BlockTypeDefinition, BlockTypeRegistry};
 pub use filter::{FilterPipeline, TextFilter};
-pub use form::FormBuilder;
-pub use item_service::ItemService;
-pub use type_registry::ContentTypeRegistry;
+pub use form::{FieldSection, FormBuilder, FormField, field_sections, item_form};
+pub use item_service::{
+    ADMINISTER_CONTENT_PERMISSION, CloneOptions, ItemPatch, ItemService, ItemTranslation,
+    ItemValidationFailed, ItemViolation, can_administer_content,
+};
+pub use type_registry::{ContentTypeRegistry, ItemTypeUpdateReport, OrphanedFieldData};
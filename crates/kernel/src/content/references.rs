@@ -0,0 +1,175 @@
+//! Reverse lookups over record reference fields.
+//!
+//! Pages rendering an item often embed items it references (an article
+//! showing its topic's title), so changing the referenced item must also
+//! invalidate cached output of the items pointing at it. [`referencing_items`]
+//! walks references backwards from a changed item, up to a configurable
+//! depth (`CACHE_REFERENCE_DEPTH`, default 1).
+
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use trovato_sdk::types::{ContentTypeDefinition, FieldType};
+use uuid::Uuid;
+
+/// Default number of reference hops followed when invalidating.
+pub const DEFAULT_REFERENCE_DEPTH: u32 = 1;
+
+/// Maximum number of referencing items returned for one change.
+///
+/// Bounds the work done on save for heavily referenced items; pages past
+/// the limit expire with their TTL.
+pub const MAX_REFERENCING_ITEMS: usize = 500;
+
+/// A record reference field that can point at items of some type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceField {
+    /// Content type holding the reference field.
+    pub item_type: String,
+    /// Reference field machine name.
+    pub field_name: String,
+}
+
+/// Record reference fields that can point at `target_type`.
+///
+/// Reference fields without a target type accept any type and are included.
+pub fn reference_fields(types: &[ContentTypeDefinition], target_type: &str) -> Vec<ReferenceField> {
+    types
+        .iter()
+        .flat_map(|ct| {
+            ct.fields.iter().filter_map(move |f| {
+                let FieldType::RecordReference(target) = &f.field_type else {
+                    return None;
+                };
+                (target.is_empty() || target == target_type).then(|| ReferenceField {
+                    item_type: ct.machine_name.clone(),
+                    field_name: f.field_name.clone(),
+                })
+            })
+        })
+        .collect()
+}
+
+/// Items referencing `target` (an item of `target_type`), following
+/// references up to `depth` hops, as `(id, item type)` pairs.
+///
+/// The target itself is never included; each item is listed once, nearest
+/// first. Stops at [`MAX_REFERENCING_ITEMS`].
+pub async fn referencing_items(
+    pool: &PgPool,
+    types: &[ContentTypeDefinition],
+    target: Uuid,
+    target_type: &str,
+    depth: u32,
+) -> Result<Vec<(Uuid, String)>> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::from([target]);
+    let mut queue = VecDeque::from([(target, target_type.to_string(), 0)]);
+
+    while let Some((id, item_type, hops)) = queue.pop_front() {
+        if hops >= depth {
+            continue;
+        }
+        for field in reference_fields(types, &item_type) {
+            let remaining = MAX_REFERENCING_ITEMS - found.len();
+            for referrer in find_referrers(pool, &field, id, remaining).await? {
+                if !seen.insert(referrer) {
+                    continue;
+                }
+                found.push((referrer, field.item_type.clone()));
+                if found.len() >= MAX_REFERENCING_ITEMS {
+                    return Ok(found);
+                }
+                queue.push_back((referrer, field.item_type.clone(), hops + 1));
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Items of `field.item_type` whose reference field holds `target`.
+///
+/// Reference values are stored as a UUID string or an array of them.
+async fn find_referrers(
+    pool: &PgPool,
+    field: &ReferenceField,
+    target: Uuid,
+    limit: usize,
+) -> Result<Vec<Uuid>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT id FROM item
+        WHERE type = $1
+          AND (fields -> $2 = to_jsonb($3::text)
+               OR fields -> $2 @> jsonb_build_array($3::text))
+        LIMIT $4
+        "#,
+    )
+    .bind(&field.item_type)
+    .bind(&field.field_name)
+    .bind(target.to_string())
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await
+    .context("failed to find referencing items")
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use trovato_sdk::types::FieldDefinition;
+
+    fn content_type(name: &str, fields: Vec<FieldDefinition>) -> ContentTypeDefinition {
+        ContentTypeDefinition {
+            machine_name: name.to_string(),
+            label: name.to_string(),
+            description: String::new(),
+            title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
+            fields,
+            extends: None,
+            template: false,
+        }
+    }
+
+    #[test]
+    fn reference_fields_match_target_type() {
+        let reference = |name: &str, target: &str| {
+            FieldDefinition::new(name, FieldType::RecordReference(target.to_string()))
+        };
+        let types = vec![
+            content_type(
+                "argus_article",
+                vec![
+                    reference("field_topic", "argus_topic"),
+                    reference("field_related", ""),
+                    reference("field_feed", "argus_feed"),
+                    FieldDefinition::new("field_body", FieldType::TextLong),
+                ],
+            ),
+            content_type(
+                "argus_story",
+                vec![reference("field_topics", "argus_topic")],
+            ),
+        ];
+
+        let fields: Vec<(String, String)> = reference_fields(&types, "argus_topic")
+            .into_iter()
+            .map(|f| (f.item_type, f.field_name))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("argus_article".to_string(), "field_topic".to_string()),
+                ("argus_article".to_string(), "field_related".to_string()),
+                ("argus_story".to_string(), "field_topics".to_string()),
+            ]
+        );
+        assert_eq!(reference_fields(&types, "page").len(), 1);
+    }
+}
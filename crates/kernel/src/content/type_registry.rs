@@ -5,6 +5,7 @@
 //! reload task so that external database changes (CLI config import, second
 //! server instance) become visible within a bounded window.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -14,8 +15,11 @@ use sqlx::PgPool;
 use tracing::{info, warn};
 
 use crate::models::{CreateItemType, ItemType};
-use crate::tap::TapDispatcher;
-use trovato_sdk::types::{ContentTypeDefinition, FieldDefinition};
+use crate::search::SearchService;
+use crate::tap::{RequestState, TapDispatcher};
+use trovato_sdk::types::{
+    CacheMetadata, ContentTypeDefinition, FieldDefinition, FieldGroup, ItemTypeUpdate,
+};
 
 /// Maximum entries in the content type cache.
 const MAX_CAPACITY: u64 = 500;
@@ -35,6 +39,28 @@ struct ContentTypeRegistryInner {
     types: Cache<String, ContentTypeDefinition>,
 }
 
+/// Items that still hold data for a field removed from their content type,
+/// or stored under the field's previous type.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OrphanedFieldData {
+    /// Removed or retyped field.
+    pub field_name: String,
+    /// Items (across all stages) with a stored value for the field.
+    pub item_count: i64,
+}
+
+/// Outcome of the kernel tasks run after a content type change.
+#[derive(Debug, Default)]
+pub struct ItemTypeUpdateReport {
+    /// Removed fields that items still hold data for.
+    pub orphaned: Vec<OrphanedFieldData>,
+    /// Fields whose type changed that items hold data for. Stored values
+    /// are not converted.
+    pub retyped: Vec<OrphanedFieldData>,
+    /// Items queued for reindex because search configuration changed.
+    pub reindexed: Option<u64>,
+}
+
 /// Resolve the title label, normalizing empty strings to None and
 /// falling back to "Title" if no value is provided.
 fn resolve_title_label(primary: Option<&str>, fallback: Option<&str>) -> Option<String> {
@@ -53,6 +79,111 @@ fn resolve_title_label(primary: Option<&str>, fallback: Option<&str>) -> Option<
         .or_else(|| Some("Title".to_string()))
 }
 
+/// Parse cache metadata from ItemType settings JSON.
+///
+/// Malformed metadata is ignored so a bad value can't take a type offline.
+fn parse_cache_from_settings(settings: &serde_json::Value) -> Option<CacheMetadata> {
+    settings
+        .get("cache")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
+/// Parse field groups from ItemType settings JSON.
+///
+/// Malformed groups are ignored; fields then render ungrouped.
+fn parse_field_groups_from_settings(settings: &serde_json::Value) -> Vec<FieldGroup> {
+    settings
+        .get("field_groups")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Parse the base type a plugin type extends from ItemType settings JSON.
+///
+/// Recorded for reference only; stored fields are already resolved.
+fn parse_extends_from_settings(settings: &serde_json::Value) -> Option<String> {
+    settings
+        .get("extends")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+/// Settings stored for a plugin-declared type: its fields (including their
+/// widget, display and group metadata), field groups, cache metadata and the
+/// base type it extends.
+fn plugin_type_settings(def: &ContentTypeDefinition) -> Result<serde_json::Value> {
+    let mut settings = serde_json::json!({
+        "fields": serde_json::to_value(&def.fields).context("serialize fields")?,
+    });
+    if !def.field_groups.is_empty() {
+        settings["field_groups"] =
+            serde_json::to_value(&def.field_groups).context("serialize field groups")?;
+    }
+    if let Some(cache) = &def.cache {
+        settings["cache"] = serde_json::to_value(cache).context("serialize cache metadata")?;
+    }
+    if let Some(base) = &def.extends {
+        settings["extends"] = serde_json::Value::String(base.clone());
+    }
+    Ok(settings)
+}
+
+/// Resolve `extends` across the definitions returned by all plugins.
+///
+/// Returns each content type (templates are dropped) with the plugin that
+/// declared it and its fully inherited definition, or the reason it could
+/// not be resolved: an unknown base or an inheritance cycle. Bases resolve
+/// before the types extending them, so chains of any depth work.
+fn resolve_inheritance(
+    declared: Vec<(String, ContentTypeDefinition)>,
+) -> Vec<(String, Result<ContentTypeDefinition>)> {
+    let by_name: HashMap<String, ContentTypeDefinition> = declared
+        .iter()
+        .map(|(_, def)| (def.machine_name.clone(), def.clone()))
+        .collect();
+    let mut resolved: HashMap<String, ContentTypeDefinition> = HashMap::new();
+
+    fn resolve(
+        name: &str,
+        by_name: &HashMap<String, ContentTypeDefinition>,
+        resolved: &mut HashMap<String, ContentTypeDefinition>,
+        chain: &mut Vec<String>,
+    ) -> Result<ContentTypeDefinition> {
+        if let Some(def) = resolved.get(name) {
+            return Ok(def.clone());
+        }
+        if chain.iter().any(|n| n == name) {
+            chain.push(name.to_string());
+            anyhow::bail!("inheritance cycle: {}", chain.join(" -> "));
+        }
+        let def = by_name
+            .get(name)
+            .cloned()
+            .with_context(|| format!("unknown base type '{name}'"))?;
+        let def = match def.extends.clone() {
+            Some(base) => {
+                chain.push(name.to_string());
+                let base = resolve(&base, by_name, resolved, chain)?;
+                chain.pop();
+                def.inherit(&base)
+            }
+            None => def,
+        };
+        resolved.insert(name.to_string(), def.clone());
+        Ok(def)
+    }
+
+    declared
+        .into_iter()
+        .filter(|(_, def)| !def.template)
+        .map(|(plugin, def)| {
+            let result = resolve(&def.machine_name, &by_name, &mut resolved, &mut Vec::new())
+                .with_context(|| format!("content type '{}'", def.machine_name));
+            (plugin, result)
+        })
+        .collect()
+}
+
 impl ContentTypeRegistry {
     /// Create a new content type registry.
     pub fn new(pool: PgPool, ttl: Duration) -> Self {
@@ -70,9 +201,13 @@ impl ContentTypeRegistry {
     /// Sync content types from plugins via tap_item_info.
     ///
     /// This calls tap_item_info on all plugins, collects the returned
-    /// ContentTypeDefinitions, and upserts them into the database.
+    /// ContentTypeDefinitions, resolves `extends` across all of them, and
+    /// upserts the resulting types into the database (field templates are
+    /// not registered). When a type's stored fields change, for example
+    /// because a base type gained or dropped a field, the same post-change
+    /// tasks as an admin edit run (see [`Self::after_update`]).
     pub async fn sync_from_plugins(&self, dispatcher: &TapDispatcher) -> Result<()> {
-        use crate::tap::{RequestState, UserContext};
+        use crate::tap::UserContext;
 
         info!("syncing content types from plugins");
 
@@ -82,25 +217,17 @@ impl ContentTypeRegistry {
         // Invoke tap_item_info on all plugins
         let results = dispatcher.dispatch("tap_item_info", "{}", state).await;
 
-        let mut synced_count = 0;
-
+        // Collect every declaration first: a type may extend one declared by
+        // another plugin.
+        let mut declared = Vec::new();
         for result in results {
             // Parse the JSON as Vec<ContentTypeDefinition>
             match serde_json::from_str::<Vec<ContentTypeDefinition>>(&result.output) {
-                Ok(definitions) => {
-                    for def in definitions {
-                        if let Err(e) = self.register_type(&def, &result.plugin_name).await {
-                            warn!(
-                                plugin = %result.plugin_name,
-                                type_name = %def.machine_name,
-                                error = %e,
-                                "failed to register content type"
-                            );
-                        } else {
-                            synced_count += 1;
-                        }
-                    }
-                }
+                Ok(definitions) => declared.extend(
+                    definitions
+                        .into_iter()
+                        .map(|def| (result.plugin_name.clone(), def)),
+                ),
                 Err(e) => {
                     warn!(
                         plugin = %result.plugin_name,
@@ -111,6 +238,49 @@ impl ContentTypeRegistry {
             }
         }
 
+        let mut synced_count = 0;
+
+        for (plugin_name, resolved) in resolve_inheritance(declared) {
+            let def = match resolved {
+                Ok(def) => def,
+                Err(e) => {
+                    warn!(
+                        plugin = %plugin_name,
+                        error = %format!("{e:#}"),
+                        "failed to resolve content type"
+                    );
+                    continue;
+                }
+            };
+
+            let previous = match self.get_or_load(&def.machine_name).await {
+                Ok(previous) => previous,
+                Err(e) => {
+                    warn!(
+                        type_name = %def.machine_name,
+                        error = %e,
+                        "failed to load stored content type"
+                    );
+                    None
+                }
+            };
+
+            if let Err(e) = self.register_type(&def, &plugin_name).await {
+                warn!(
+                    plugin = %plugin_name,
+                    type_name = %def.machine_name,
+                    error = %e,
+                    "failed to register content type"
+                );
+                continue;
+            }
+            synced_count += 1;
+
+            if let Some(old) = previous {
+                self.migrate_synced_type(old, &def, dispatcher).await;
+            }
+        }
+
         // Also load types from database (including 'core' types)
         let db_types = ItemType::list(&self.inner.pool).await?;
         for db_type in db_types {
@@ -121,6 +291,10 @@ impl ContentTypeRegistry {
                 description: db_type.description.clone().unwrap_or_default(),
                 title_label: db_type.title_label.clone(),
                 fields: self.parse_fields_from_settings(&db_type.settings),
+                cache: parse_cache_from_settings(&db_type.settings),
+                field_groups: parse_field_groups_from_settings(&db_type.settings),
+                extends: parse_extends_from_settings(&db_type.settings),
+                template: false,
             };
             self.inner.types.insert(db_type.type_name, def);
         }
@@ -129,6 +303,52 @@ impl ContentTypeRegistry {
         Ok(())
     }
 
+    /// Run post-change tasks when a plugin sync changed a type's fields.
+    ///
+    /// Logs the migration plan (fields added, removed and retyped) and runs
+    /// [`Self::after_update`], which drops search configuration for removed
+    /// fields, counts items still holding their data and dispatches
+    /// `tap_item_type_update` so plugins can migrate it. Stored item values
+    /// are never rewritten here.
+    async fn migrate_synced_type(
+        &self,
+        old: ContentTypeDefinition,
+        new: &ContentTypeDefinition,
+        dispatcher: &TapDispatcher,
+    ) {
+        use crate::tap::UserContext;
+
+        let plan = ItemTypeUpdate {
+            old,
+            new: new.clone(),
+        };
+        let (added, removed, retyped) = (
+            plan.added_fields(),
+            plan.removed_fields(),
+            plan.retyped_fields(),
+        );
+        if added.is_empty() && removed.is_empty() && retyped.is_empty() {
+            return;
+        }
+        info!(
+            type_name = %new.machine_name,
+            extends = ?new.extends,
+            added = ?added,
+            removed = ?removed,
+            retyped = ?retyped,
+            "plugin content type fields changed"
+        );
+
+        let state = RequestState::without_services(UserContext::anonymous());
+        if let Err(e) = self.after_update(plan.old, dispatcher, state).await {
+            warn!(
+                type_name = %new.machine_name,
+                error = %e,
+                "post-change tasks failed for synced content type"
+            );
+        }
+    }
+
     /// Register a content type definition from a plugin.
     async fn register_type(&self, def: &ContentTypeDefinition, plugin_name: &str) -> Result<()> {
         // Upsert to database
@@ -139,11 +359,31 @@ impl ContentTypeRegistry {
             has_title: Some(true),
             title_label: resolve_title_label(def.title_label.as_deref(), None),
             plugin: plugin_name.to_string(),
-            settings: Some(serde_json::to_value(&def.fields).context("serialize fields")?),
+            settings: Some(plugin_type_settings(def)?),
         };
 
         ItemType::upsert(&self.inner.pool, input).await?;
 
+        // Sync declared search weights; queue a reindex so the tsvector
+        // trigger picks up the new configuration for existing items.
+        let weights: Vec<(String, char)> = def
+            .fields
+            .iter()
+            .filter_map(|f| f.search_weight.map(|w| (f.field_name.clone(), w.as_char())))
+            .collect();
+        let search = SearchService::new(self.inner.pool.clone());
+        if search
+            .sync_declared_weights(&def.machine_name, &weights)
+            .await?
+        {
+            let count = search.queue_reindex_bundle(&def.machine_name).await?;
+            info!(
+                type_name = %def.machine_name,
+                queued = count,
+                "search weights changed, bundle queued for reindex"
+            );
+        }
+
         // Update cache
         self.inner
             .types
@@ -174,6 +414,10 @@ impl ContentTypeRegistry {
                 description: db_type.description.clone().unwrap_or_default(),
                 title_label: db_type.title_label.clone(),
                 fields: self.parse_fields_from_settings(&db_type.settings),
+                cache: parse_cache_from_settings(&db_type.settings),
+                field_groups: parse_field_groups_from_settings(&db_type.settings),
+                extends: parse_extends_from_settings(&db_type.settings),
+                template: false,
             };
             self.inner.types.insert(db_type.type_name, def);
         }
@@ -200,6 +444,10 @@ impl ContentTypeRegistry {
                 description: db_type.description.clone().unwrap_or_default(),
                 title_label: db_type.title_label.clone(),
                 fields: self.parse_fields_from_settings(&db_type.settings),
+                cache: parse_cache_from_settings(&db_type.settings),
+                field_groups: parse_field_groups_from_settings(&db_type.settings),
+                extends: parse_extends_from_settings(&db_type.settings),
+                template: false,
             };
             self.inner.types.insert(type_name.to_string(), def.clone());
             Ok(Some(def))
@@ -254,6 +502,10 @@ impl ContentTypeRegistry {
             description: description.unwrap_or("").to_string(),
             title_label,
             fields,
+            cache: parse_cache_from_settings(&settings),
+            field_groups: parse_field_groups_from_settings(&settings),
+            extends: None,
+            template: false,
         };
         self.inner.types.insert(machine_name.to_string(), def);
 
@@ -295,7 +547,14 @@ impl ContentTypeRegistry {
             label: label.to_string(),
             description: description.unwrap_or("").to_string(),
             title_label,
+            cache: parse_cache_from_settings(&settings),
+            field_groups: existing
+                .as_ref()
+                .map(|e| e.field_groups.clone())
+                .unwrap_or_default(),
+            extends: existing.as_ref().and_then(|e| e.extends.clone()),
             fields: existing.map(|e| e.fields).unwrap_or_default(),
+            template: false,
         };
         self.inner.types.insert(machine_name.to_string(), def);
 
@@ -346,6 +605,13 @@ impl ContentTypeRegistry {
             cardinality: 1,
             settings: serde_json::Value::Object(serde_json::Map::new()),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: Default::default(),
         };
 
         // Add to existing fields
@@ -402,6 +668,9 @@ impl ContentTypeRegistry {
     }
 
     /// Update properties of an existing field (label, required, cardinality).
+    ///
+    /// Keys in `settings` are merged into the field's settings; other keys
+    /// are kept.
     pub async fn update_field(
         &self,
         type_name: &str,
@@ -409,6 +678,7 @@ impl ContentTypeRegistry {
         label: &str,
         required: bool,
         cardinality: i32,
+        settings: serde_json::Map<String, serde_json::Value>,
     ) -> Result<()> {
         let mut def = self
             .get_or_load(type_name)
@@ -424,6 +694,14 @@ impl ContentTypeRegistry {
         field.label = label.to_string();
         field.required = required;
         field.cardinality = cardinality;
+        if !settings.is_empty() {
+            if !field.settings.is_object() {
+                field.settings = serde_json::json!({});
+            }
+            if let Some(obj) = field.settings.as_object_mut() {
+                obj.extend(settings);
+            }
+        }
 
         self.persist_fields(type_name, &def).await?;
         info!(type_name = %type_name, field = %field_name, "field updated");
@@ -448,6 +726,105 @@ impl ContentTypeRegistry {
         Ok(())
     }
 
+    /// Run post-change tasks after an administrator edits a content type,
+    /// or a plugin sync changes its fields.
+    ///
+    /// `old` is the definition before the change; the new one is read from
+    /// the registry. Search configuration for removed fields is dropped (and
+    /// the bundle queued for reindex), items still holding data for removed
+    /// or retyped fields are counted, and `tap_item_type_update` is
+    /// dispatched so plugins can migrate or reindex their own data.
+    pub async fn after_update(
+        &self,
+        old: ContentTypeDefinition,
+        dispatcher: &TapDispatcher,
+        state: RequestState,
+    ) -> Result<ItemTypeUpdateReport> {
+        let new = self
+            .get_or_load(&old.machine_name)
+            .await?
+            .context("content type not found")?;
+        let update = ItemTypeUpdate { old, new };
+        let type_name = update.new.machine_name.as_str();
+        let removed = update.removed_fields();
+        let retyped = update.retyped_fields();
+        let mut report = ItemTypeUpdateReport::default();
+
+        if !retyped.is_empty() {
+            report.retyped = self.count_orphaned(type_name, &retyped).await?;
+            for field in &report.retyped {
+                warn!(
+                    type_name = %type_name,
+                    field = %field.field_name,
+                    items = field.item_count,
+                    "items contain data stored under the field's previous type"
+                );
+            }
+        }
+
+        if !removed.is_empty() {
+            let search = SearchService::new(self.inner.pool.clone());
+            if search.remove_field_configs(type_name, &removed).await? {
+                let count = search.queue_reindex_bundle(type_name).await?;
+                info!(
+                    type_name = %type_name,
+                    queued = count,
+                    "search config for removed fields dropped, bundle queued for reindex"
+                );
+                report.reindexed = Some(count);
+            }
+
+            report.orphaned = self.count_orphaned(type_name, &removed).await?;
+            for orphan in &report.orphaned {
+                warn!(
+                    type_name = %type_name,
+                    field = %orphan.field_name,
+                    items = orphan.item_count,
+                    "items still contain data for removed field"
+                );
+            }
+        }
+
+        let json = serde_json::to_string(&update).context("serialize type update")?;
+        dispatcher
+            .dispatch("tap_item_type_update", &json, state)
+            .await;
+
+        Ok(report)
+    }
+
+    /// Count items of a type that store a value for each of `fields`.
+    ///
+    /// Only fields with at least one such item are returned.
+    async fn count_orphaned(
+        &self,
+        type_name: &str,
+        fields: &[&str],
+    ) -> Result<Vec<OrphanedFieldData>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT f.name, COUNT(i.id)
+            FROM unnest($2::text[]) AS f(name)
+            JOIN item i ON i.type = $1 AND i.fields ? f.name
+            GROUP BY f.name
+            ORDER BY f.name
+            "#,
+        )
+        .bind(type_name)
+        .bind(fields)
+        .fetch_all(&self.inner.pool)
+        .await
+        .context("failed to count items with removed field data")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(field_name, item_count)| OrphanedFieldData {
+                field_name,
+                item_count,
+            })
+            .collect())
+    }
+
     /// List content type names.
     pub fn type_names(&self) -> Vec<String> {
         self.inner
@@ -487,9 +864,161 @@ impl ContentTypeRegistry {
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
+    use super::*;
+    use trovato_sdk::types::{
+        CacheContext, FieldDisplay, FieldFormatter, FieldType, FormatterType, WidgetType,
+    };
+
     #[test]
     fn content_type_registry_placeholder() {
         // Full tests require database connection.
         // See tests/item_test.rs for ContentTypeDefinition tests.
     }
+
+    #[test]
+    fn plugin_settings_round_trip_fields_and_cache() {
+        let def = ContentTypeDefinition {
+            machine_name: "event".to_string(),
+            label: "Event".to_string(),
+            description: String::new(),
+            title_label: None,
+            fields: vec![FieldDefinition::new("field_date", FieldType::Date)],
+            cache: Some(CacheMetadata {
+                max_age: Some(60),
+                shared_max_age: None,
+                contexts: vec![CacheContext::Language],
+            }),
+            field_groups: Vec::new(),
+            extends: None,
+            template: false,
+        };
+
+        let settings = plugin_type_settings(&def).unwrap();
+        assert_eq!(settings["fields"][0]["field_name"], "field_date");
+        assert_eq!(parse_cache_from_settings(&settings), def.cache);
+
+        let malformed = serde_json::json!({"cache": {"max_age": "soon"}});
+        assert_eq!(parse_cache_from_settings(&malformed), None);
+        assert_eq!(parse_cache_from_settings(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn plugin_settings_round_trip_field_ui_metadata() {
+        let def = ContentTypeDefinition {
+            machine_name: "event".to_string(),
+            label: "Event".to_string(),
+            description: String::new(),
+            title_label: None,
+            fields: vec![
+                FieldDefinition::new("field_notes", FieldType::TextLong)
+                    .widget(WidgetType::Textarea, serde_json::json!({"rows": 3}))
+                    .display(FieldDisplay {
+                        full: FieldFormatter::hidden(),
+                        teaser: FieldFormatter::new(FormatterType::Trimmed),
+                    })
+                    .group("details"),
+            ],
+            cache: None,
+            field_groups: vec![FieldGroup {
+                name: "details".to_string(),
+                label: "Details".to_string(),
+                weight: 0,
+                collapsed: true,
+            }],
+            extends: None,
+            template: false,
+        };
+
+        let settings = plugin_type_settings(&def).unwrap();
+        let parsed: Vec<FieldDefinition> =
+            serde_json::from_value(settings["fields"].clone()).unwrap();
+        assert_eq!(parsed[0].widget, def.fields[0].widget);
+        assert_eq!(parsed[0].display, def.fields[0].display);
+        assert_eq!(parsed[0].field_group.as_deref(), Some("details"));
+        assert_eq!(
+            parse_field_groups_from_settings(&settings),
+            def.field_groups
+        );
+        assert!(parse_field_groups_from_settings(&serde_json::json!({})).is_empty());
+    }
+
+    fn declared(name: &str, fields: &[&str]) -> ContentTypeDefinition {
+        ContentTypeDefinition {
+            machine_name: name.to_string(),
+            label: name.to_string(),
+            description: String::new(),
+            title_label: None,
+            fields: fields
+                .iter()
+                .map(|f| FieldDefinition::new(f, FieldType::TextLong))
+                .collect(),
+            cache: None,
+            field_groups: Vec::new(),
+            extends: None,
+            template: false,
+        }
+    }
+
+    fn field_names(def: &ContentTypeDefinition) -> Vec<&str> {
+        def.fields.iter().map(|f| f.field_name.as_str()).collect()
+    }
+
+    #[test]
+    fn inheritance_resolves_across_plugins_and_drops_templates() {
+        let resolved = resolve_inheritance(vec![
+            (
+                "b".to_string(),
+                declared("report", &["field_body"]).extends("timed"),
+            ),
+            (
+                "a".to_string(),
+                declared("timed", &["field_end"]).extends("base"),
+            ),
+            (
+                "a".to_string(),
+                declared("base", &["field_start"]).as_template(),
+            ),
+        ]);
+
+        assert_eq!(resolved.len(), 2);
+        let (plugin, report) = &resolved[0];
+        assert_eq!(plugin, "b");
+        let report = report.as_ref().unwrap();
+        assert_eq!(
+            field_names(report),
+            vec!["field_start", "field_end", "field_body"]
+        );
+        assert_eq!(report.extends.as_deref(), Some("timed"));
+        assert_eq!(
+            field_names(resolved[1].1.as_ref().unwrap()),
+            vec!["field_start", "field_end"]
+        );
+    }
+
+    #[test]
+    fn inheritance_reports_unknown_bases_and_cycles() {
+        let resolved = resolve_inheritance(vec![
+            ("a".to_string(), declared("orphan", &[]).extends("missing")),
+            ("a".to_string(), declared("x", &[]).extends("y")),
+            ("a".to_string(), declared("y", &[]).extends("x")),
+            ("a".to_string(), declared("plain", &["field_a"])),
+        ]);
+
+        let err = |i: usize| format!("{:#}", resolved[i].1.as_ref().unwrap_err());
+        assert!(err(0).contains("content type 'orphan': unknown base type 'missing'"));
+        assert!(err(1).contains("x -> y -> x"));
+        assert!(err(2).contains("inheritance cycle"));
+        assert!(resolved[3].1.is_ok());
+    }
+
+    #[test]
+    fn plugin_settings_record_base_type() {
+        let def = declared("report", &[]).extends("timed");
+        let settings = plugin_type_settings(&def).unwrap();
+        assert_eq!(
+            parse_extends_from_settings(&settings).as_deref(),
+            Some("timed")
+        );
+        assert_eq!(parse_extends_from_settings(&serde_json::json!({})), None);
+    }
 }
@@ -0,0 +1,427 @@
+//! Cron run history and failure alerting.
+//!
+//! Every task executed by a cron cycle is recorded in `cron_run` with its
+//! duration, the number of entries it processed, and any error. Alerts
+//! configured in `site_config` under `cron_alerts` fire when a task fails
+//! several runs in a row or runs longer than a threshold.
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// `site_config` key for alert settings.
+pub(super) const ALERT_SETTINGS_KEY: &str = "cron_alerts";
+
+/// Days of history kept; older runs are pruned when a run is recorded.
+const HISTORY_RETENTION_DAYS: i64 = 30;
+
+/// Maximum entries returned by a history query.
+const MAX_HISTORY_ENTRIES: i64 = 500;
+
+/// Status of a successful task run.
+pub const STATUS_OK: &str = "ok";
+
+/// Status of a failed task run.
+pub const STATUS_FAILED: &str = "failed";
+
+/// One task execution within a cron cycle.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CronTaskRun {
+    pub id: i64,
+    /// Cron cycle the task ran in; shared by all tasks of the cycle.
+    pub run_id: Uuid,
+    pub task: String,
+    /// Instance that ran the cycle.
+    pub hostname: String,
+    /// Unix timestamp when the task started.
+    pub started: i64,
+    pub duration_ms: i64,
+    /// [`STATUS_OK`] or [`STATUS_FAILED`].
+    pub status: String,
+    /// Entries the task processed (deleted, queued, dispatched, ...).
+    pub processed: i64,
+    pub error: Option<String>,
+}
+
+/// Filter for history reports.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CronHistoryFilter {
+    /// Restrict to one task.
+    pub task: Option<String>,
+    /// Restrict to `ok` or `failed` runs.
+    pub status: Option<String>,
+    /// Only runs started at or after this Unix timestamp.
+    pub since: Option<i64>,
+    /// Maximum entries (capped at [`MAX_HISTORY_ENTRIES`]).
+    pub limit: Option<i64>,
+}
+
+/// Alert settings, stored as JSON in `site_config` under `cron_alerts`.
+///
+/// Alerts are off until a mail recipient or webhook is configured.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CronAlertSettings {
+    /// Addresses mailed when an alert fires.
+    #[serde(default)]
+    pub mail: Vec<String>,
+    /// URL receiving a JSON POST of each [`CronAlert`].
+    #[serde(default)]
+    pub webhook: Option<String>,
+    /// Consecutive failed runs of a task that raise an alert.
+    #[serde(default = "default_consecutive_failures")]
+    pub consecutive_failures: u32,
+    /// Alert when a task runs longer than this many seconds.
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+}
+
+impl Default for CronAlertSettings {
+    fn default() -> Self {
+        Self {
+            mail: Vec::new(),
+            webhook: None,
+            consecutive_failures: default_consecutive_failures(),
+            max_duration_secs: None,
+        }
+    }
+}
+
+fn default_consecutive_failures() -> u32 {
+    3
+}
+
+impl CronAlertSettings {
+    /// Whether alerts have anywhere to go.
+    pub fn is_enabled(&self) -> bool {
+        !self.mail.is_empty() || self.webhook.is_some()
+    }
+}
+
+/// Why an alert fired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CronAlertKind {
+    /// The task failed this many runs in a row.
+    ConsecutiveFailures { count: i64, error: String },
+    /// The task took longer than the configured threshold.
+    SlowRun { duration_ms: i64, threshold_ms: i64 },
+}
+
+/// An alert about a cron task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CronAlert {
+    pub task: String,
+    pub hostname: String,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub kind: CronAlertKind,
+}
+
+impl CronAlert {
+    /// One-line summary used as the mail subject.
+    pub fn subject(&self) -> String {
+        match &self.kind {
+            CronAlertKind::ConsecutiveFailures { count, .. } => {
+                format!("Cron task {} failed {count} runs in a row", self.task)
+            }
+            CronAlertKind::SlowRun { duration_ms, .. } => {
+                format!("Cron task {} took {duration_ms} ms", self.task)
+            }
+        }
+    }
+
+    /// Plain text mail body.
+    pub fn body(&self) -> String {
+        let detail = match &self.kind {
+            CronAlertKind::ConsecutiveFailures { error, .. } => format!("Last error: {error}"),
+            CronAlertKind::SlowRun { threshold_ms, .. } => {
+                format!("The alert threshold is {threshold_ms} ms.")
+            }
+        };
+        format!(
+            "{}.\n\n{detail}\n\nHost: {}\nSee /admin/reports/cron/history?task={} for details.\n",
+            self.subject(),
+            self.hostname,
+            self.task
+        )
+    }
+}
+
+/// Task results collected during one cron cycle.
+#[derive(Debug)]
+pub struct RunLog {
+    pub run_id: Uuid,
+    pub hostname: String,
+    pub entries: Vec<CronTaskRun>,
+}
+
+impl RunLog {
+    /// Start the log of a cron cycle.
+    pub fn new(hostname: String) -> Self {
+        Self {
+            run_id: Uuid::now_v7(),
+            hostname,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record a successful task run that began at `started`.
+    pub fn ok(&mut self, task: &str, started: Instant, processed: u64) {
+        self.push(task, started, STATUS_OK, processed, None);
+    }
+
+    /// Record a failed task run that began at `started`.
+    pub fn failed(&mut self, task: &str, started: Instant, error: impl std::fmt::Display) {
+        self.push(task, started, STATUS_FAILED, 0, Some(error.to_string()));
+    }
+
+    /// Record a task run from its result and the entries it processed.
+    pub fn finish(
+        &mut self,
+        task: &str,
+        started: Instant,
+        result: Result<u64, impl std::fmt::Display>,
+    ) {
+        match result {
+            Ok(processed) => self.ok(task, started, processed),
+            Err(e) => self.failed(task, started, e),
+        }
+    }
+
+    /// Names of the tasks that ran and succeeded this cycle.
+    pub fn succeeded(&self) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == STATUS_OK)
+            .map(|e| e.task.as_str())
+            .collect()
+    }
+
+    fn push(
+        &mut self,
+        task: &str,
+        started: Instant,
+        status: &str,
+        processed: u64,
+        error: Option<String>,
+    ) {
+        let elapsed = started.elapsed();
+        self.entries.push(CronTaskRun {
+            id: 0,
+            run_id: self.run_id,
+            task: task.to_string(),
+            hostname: self.hostname.clone(),
+            started: chrono::Utc::now().timestamp() - elapsed.as_secs() as i64,
+            duration_ms: elapsed.as_millis() as i64,
+            status: status.to_string(),
+            processed: processed as i64,
+            error,
+        });
+    }
+}
+
+/// Persist the task runs of a cycle and prune expired history.
+pub async fn record(pool: &PgPool, log: &RunLog) -> Result<()> {
+    for entry in &log.entries {
+        sqlx::query(
+            r#"
+            INSERT INTO cron_run
+                (run_id, task, hostname, started, duration_ms, status, processed, error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(entry.run_id)
+        .bind(&entry.task)
+        .bind(&entry.hostname)
+        .bind(entry.started)
+        .bind(entry.duration_ms)
+        .bind(&entry.status)
+        .bind(entry.processed)
+        .bind(&entry.error)
+        .execute(pool)
+        .await
+        .context("failed to record cron task run")?;
+    }
+
+    let cutoff = chrono::Utc::now().timestamp() - HISTORY_RETENTION_DAYS * 86400;
+    sqlx::query("DELETE FROM cron_run WHERE started < $1")
+        .bind(cutoff)
+        .execute(pool)
+        .await
+        .context("failed to prune cron history")?;
+    Ok(())
+}
+
+/// Task runs matching `filter`, newest first.
+pub async fn query(pool: &PgPool, filter: &CronHistoryFilter) -> Result<Vec<CronTaskRun>> {
+    let limit = filter
+        .limit
+        .unwrap_or(MAX_HISTORY_ENTRIES)
+        .clamp(1, MAX_HISTORY_ENTRIES);
+
+    sqlx::query_as::<_, CronTaskRun>(
+        r#"
+        SELECT id, run_id, task, hostname, started, duration_ms, status, processed, error
+        FROM cron_run
+        WHERE ($1::text IS NULL OR task = $1)
+          AND ($2::text IS NULL OR status = $2)
+          AND ($3::bigint IS NULL OR started >= $3)
+        ORDER BY id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(filter.task.as_deref())
+    .bind(filter.status.as_deref())
+    .bind(filter.since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to query cron history")
+}
+
+/// Number of failed runs of a task since its last successful run.
+pub async fn consecutive_failures(pool: &PgPool, task: &str) -> Result<i64> {
+    sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM cron_run
+        WHERE task = $1
+          AND status = 'failed'
+          AND id > COALESCE(
+              (SELECT MAX(id) FROM cron_run WHERE task = $1 AND status = 'ok'),
+              0
+          )
+        "#,
+    )
+    .bind(task)
+    .fetch_one(pool)
+    .await
+    .context("failed to count consecutive cron failures")
+}
+
+/// Decide whether a recorded task run raises an alert.
+///
+/// `failures_in_row` counts the run itself when it failed. A failure streak
+/// alerts once, when it reaches the threshold, so a broken task does not
+/// send mail every cycle.
+pub fn alert_for(
+    settings: &CronAlertSettings,
+    entry: &CronTaskRun,
+    failures_in_row: i64,
+) -> Option<CronAlert> {
+    let kind = if entry.status == STATUS_FAILED
+        && settings.consecutive_failures > 0
+        && failures_in_row == i64::from(settings.consecutive_failures)
+    {
+        CronAlertKind::ConsecutiveFailures {
+            count: failures_in_row,
+            error: entry.error.clone().unwrap_or_default(),
+        }
+    } else {
+        let threshold_ms = settings.max_duration_secs? as i64 * 1000;
+        if entry.duration_ms <= threshold_ms {
+            return None;
+        }
+        CronAlertKind::SlowRun {
+            duration_ms: entry.duration_ms,
+            threshold_ms,
+        }
+    };
+
+    Some(CronAlert {
+        task: entry.task.clone(),
+        hostname: entry.hostname.clone(),
+        timestamp: entry.started,
+        kind,
+    })
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn entry(status: &str, duration_ms: i64) -> CronTaskRun {
+        CronTaskRun {
+            id: 1,
+            run_id: Uuid::nil(),
+            task: "process_queues".to_string(),
+            hostname: "web-1".to_string(),
+            started: 1_800_000_000,
+            duration_ms,
+            status: status.to_string(),
+            processed: 0,
+            error: (status == STATUS_FAILED).then(|| "redis down".to_string()),
+        }
+    }
+
+    #[test]
+    fn alert_settings_default_to_disabled() {
+        let settings: CronAlertSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, CronAlertSettings::default());
+        assert!(!settings.is_enabled());
+        assert_eq!(settings.consecutive_failures, 3);
+    }
+
+    #[test]
+    fn failure_streak_alerts_once_at_threshold() {
+        let settings = CronAlertSettings {
+            webhook: Some("https://hooks.example.com/cron".to_string()),
+            ..Default::default()
+        };
+        let failed = entry(STATUS_FAILED, 10);
+
+        assert_eq!(alert_for(&settings, &failed, 2), None);
+        let alert = alert_for(&settings, &failed, 3).unwrap();
+        assert_eq!(
+            alert.kind,
+            CronAlertKind::ConsecutiveFailures {
+                count: 3,
+                error: "redis down".to_string()
+            }
+        );
+        assert!(alert.subject().contains("failed 3 runs in a row"));
+        assert_eq!(alert_for(&settings, &failed, 4), None);
+    }
+
+    #[test]
+    fn slow_runs_alert_above_threshold() {
+        let settings = CronAlertSettings {
+            max_duration_secs: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(alert_for(&settings, &entry(STATUS_OK, 2000), 0), None);
+        let alert = alert_for(&settings, &entry(STATUS_OK, 2500), 0).unwrap();
+        assert_eq!(
+            alert.kind,
+            CronAlertKind::SlowRun {
+                duration_ms: 2500,
+                threshold_ms: 2000
+            }
+        );
+
+        let json = serde_json::to_value(&alert).unwrap();
+        assert_eq!(json["kind"], "slow_run");
+        assert_eq!(json["task"], "process_queues");
+    }
+
+    #[test]
+    fn run_log_records_outcomes() {
+        let mut log = RunLog::new("web-1".to_string());
+        let started = Instant::now();
+        log.ok("cleanup_temp_files", started, 4);
+        log.failed("process_queues", started, "boom");
+        log.finish("batch_continue", started, Err::<u64, _>("timed out"));
+
+        assert_eq!(log.entries.len(), 3);
+        assert_eq!(log.entries[2].status, STATUS_FAILED);
+        assert_eq!(log.entries[0].status, STATUS_OK);
+        assert_eq!(log.entries[0].processed, 4);
+        assert_eq!(log.entries[1].error.as_deref(), Some("boom"));
+        assert!(log.entries.iter().all(|e| e.run_id == log.run_id));
+        assert_eq!(log.succeeded(), vec!["cleanup_temp_files"]);
+    }
+}
@@ -2,27 +2,51 @@
 //!
 //! Provides distributed cron with Redis-based locking to ensure
 //! exactly-once execution across multiple server instances.
+//!
+//! Each task can carry its own cron expression and be enabled or disabled
+//! individually. Task settings live in `site_config` under
+//! `cron_task.{name}`; per-task last-run timestamps live in Redis.
+//! Every task run is recorded in the `cron_run` history table, which also
+//! drives failure and duration alerts (see [`history`]).
 
+mod history;
 mod pagefind;
+mod plugin_state;
 mod queue;
+mod schedule;
 mod tasks;
 
-pub use queue::{Queue, RedisQueue};
+pub use history::{CronAlertSettings, CronHistoryFilter, CronTaskRun};
+pub use pagefind::build_index as build_pagefind_index;
+pub use plugin_state::PluginCronState;
+pub use queue::{DeadLetter, MAX_ATTEMPTS, Queue, RedisQueue};
+pub use schedule::CronSchedule;
 pub use tasks::CronTasks;
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use redis::{AsyncCommands, Client as RedisClient};
+use redis::AsyncCommands;
 use sqlx::PgPool;
 use tokio::sync::watch;
 use tracing::{debug, info, warn};
 
+use crate::batch::BatchService;
+use crate::cache::CacheLayer;
+use crate::content::ContentTypeRegistry;
 use crate::file::FileService;
+use crate::models::SiteConfig;
+use crate::redis_manager::RedisManager;
 use crate::services::ai_provider::AiProviderService;
 use crate::services::ai_token_budget::AiTokenBudgetService;
-use crate::tap::{RequestState, TapDispatcher};
+use crate::services::mail::{MailMessage, MailService};
+use crate::services::saved_search::SavedSearchService;
+use crate::services::scheduled_update::ScheduledUpdateService;
+use crate::stage::StageService;
+use crate::tap::{RequestState, TapDispatcher, TapResult};
+use history::{CronAlert, RunLog};
 
 /// Lock TTL in seconds (5 minutes).
 const LOCK_TTL_SECS: u64 = 300;
@@ -33,6 +57,102 @@ const HEARTBEAT_INTERVAL_SECS: u64 = 60;
 /// Cron lock key in Redis.
 const CRON_LOCK_KEY: &str = "cron:lock";
 
+/// Redis hash mapping task name to last-run Unix timestamp.
+const TASK_LAST_RUN_KEY: &str = "cron:task_last_run";
+
+/// `site_config` key prefix for per-task settings.
+const TASK_SETTINGS_PREFIX: &str = "cron_task.";
+
+/// Names of all cron tasks, in execution order.
+///
+/// `tap_cron` covers every plugin implementing the tap; plugins that need
+/// finer-grained scheduling should track their own state.
+pub const CRON_TASKS: &[&str] = &[
+    "cleanup_temp_files",
+    "cleanup_expired_sessions",
+    "cleanup_form_state_cache",
+    "search_reindex",
+    "process_queues",
+    "cleanup_verification_tokens",
+    "cleanup_password_reset_tokens",
+    "cleanup_expired_locks",
+    "cleanup_audit_log",
+    "cleanup_personal_stages",
+    "publish_scheduled_stages",
+    "check_dangling_references",
+    "refresh_mac_vendors",
+    "tap_cron",
+    "tap_queue_worker",
+    "batch_continue",
+    "pagefind_rebuild",
+];
+
+/// Redis queues drained by the `process_queues` task.
+pub const KERNEL_QUEUES: &[&str] = &[
+    crate::services::mail::MAIL_QUEUE,
+    crate::search::REINDEX_QUEUE,
+];
+
+/// Tasks that keep running while the site is read-only.
+///
+/// They only expire ephemeral data (sessions, form state, tokens, content
+/// locks) or rebuild the search index from existing content. Everything
+/// else — queues, plugin cron, batches, retention purges — waits until
+/// writes are allowed again.
+pub const READ_ONLY_SAFE_TASKS: &[&str] = &[
+    "cleanup_expired_sessions",
+    "cleanup_form_state_cache",
+    "cleanup_verification_tokens",
+    "cleanup_password_reset_tokens",
+    "cleanup_expired_locks",
+    "pagefind_rebuild",
+];
+
+/// Per-task scheduling settings.
+///
+/// Stored as JSON in `site_config` under `cron_task.{name}`. A task without
+/// stored settings is enabled and runs on every cron cycle.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CronTaskSettings {
+    /// Whether the task runs at all.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Cron expression (five fields, UTC). `None` runs every cycle.
+    #[serde(default)]
+    pub schedule: Option<String>,
+}
+
+impl Default for CronTaskSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            schedule: None,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Status of a single cron task for the admin report.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CronTaskStatus {
+    /// Task name.
+    pub name: String,
+    /// Whether the task is enabled.
+    pub enabled: bool,
+    /// Cron expression, if the task is scheduled.
+    pub schedule: Option<String>,
+    /// Unix timestamp of the last run, if any.
+    pub last_run: Option<i64>,
+    /// Unix timestamp of the next scheduled run.
+    ///
+    /// `None` for disabled tasks and for impossible schedules. Unscheduled
+    /// tasks report `None` as well since they run on every cycle.
+    pub next_run: Option<i64>,
+}
+
 /// Result of a cron run.
 #[derive(Debug, Clone)]
 pub enum CronResult {
@@ -51,20 +171,27 @@ pub enum CronResult {
 
 /// Cron service for scheduled operations.
 pub struct CronService {
-    redis: RedisClient,
+    redis: RedisManager,
     pool: PgPool,
     tasks: CronTasks,
     queue: Arc<RedisQueue>,
     tap_dispatcher: Option<Arc<TapDispatcher>>,
+    batch: Option<Arc<BatchService>>,
     ai_providers: Option<Arc<AiProviderService>>,
     ai_budgets: Option<Arc<AiTokenBudgetService>>,
+    cache: Option<CacheLayer>,
     http: reqwest::Client,
     pagefind_enabled: bool,
+    jitter_secs: u64,
+    read_only: Option<Arc<crate::services::read_only::ReadOnlyService>>,
+    mail: Option<Arc<MailService>>,
+    saved_searches: Option<Arc<SavedSearchService>>,
+    scheduled_updates: Option<Arc<ScheduledUpdateService>>,
 }
 
 impl CronService {
     /// Create a new cron service.
-    pub fn new(redis: RedisClient, pool: PgPool) -> Self {
+    pub fn new(redis: RedisManager, pool: PgPool) -> Self {
         let queue = Arc::new(RedisQueue::new(redis.clone()));
         let tasks = CronTasks::new(pool.clone(), queue.clone());
         Self {
@@ -73,15 +200,22 @@ impl CronService {
             tasks,
             queue,
             tap_dispatcher: None,
+            batch: None,
             ai_providers: None,
             ai_budgets: None,
+            cache: None,
             http: build_http_client(),
             pagefind_enabled: false,
+            jitter_secs: 0,
+            read_only: None,
+            mail: None,
+            saved_searches: None,
+            scheduled_updates: None,
         }
     }
 
     /// Create a new cron service with file service for proper cleanup.
-    pub fn with_file_service(redis: RedisClient, pool: PgPool, files: Arc<FileService>) -> Self {
+    pub fn with_file_service(redis: RedisManager, pool: PgPool, files: Arc<FileService>) -> Self {
         let queue = Arc::new(RedisQueue::new(redis.clone()));
         let tasks = CronTasks::with_file_service(pool.clone(), queue.clone(), files);
         Self {
@@ -90,10 +224,17 @@ impl CronService {
             tasks,
             queue,
             tap_dispatcher: None,
+            batch: None,
             ai_providers: None,
             ai_budgets: None,
+            cache: None,
             http: build_http_client(),
             pagefind_enabled: false,
+            jitter_secs: 0,
+            read_only: None,
+            mail: None,
+            saved_searches: None,
+            scheduled_updates: None,
         }
     }
 
@@ -102,6 +243,11 @@ impl CronService {
         self.tap_dispatcher = Some(dispatcher);
     }
 
+    /// Set the batch service so unfinished plugin batches continue on cron.
+    pub fn set_batch_service(&mut self, batch: Arc<BatchService>) {
+        self.batch = Some(batch);
+    }
+
     /// Set the AI provider service for cron plugin access.
     pub fn set_ai_providers(&mut self, ai_providers: Arc<AiProviderService>) {
         self.ai_providers = Some(ai_providers);
@@ -112,11 +258,24 @@ impl CronService {
         self.ai_budgets = Some(ai_budgets);
     }
 
+    /// Set the cache layer for cron plugin access.
+    pub fn set_cache(&mut self, cache: CacheLayer) {
+        self.cache = Some(cache);
+    }
+
     /// Enable pagefind index rebuilding (requires `trovato_search` plugin).
     pub fn set_pagefind_enabled(&mut self, enabled: bool) {
         self.pagefind_enabled = enabled;
     }
 
+    /// Set the maximum random delay applied before acquiring the cron lock.
+    ///
+    /// When several instances are triggered at the same moment, jitter
+    /// spreads their lock attempts so they don't all hit Redis at once.
+    pub fn set_jitter_secs(&mut self, secs: u64) {
+        self.jitter_secs = secs;
+    }
+
     /// Set optional plugin services for cron tasks.
     pub fn set_plugin_services(
         &mut self,
@@ -126,20 +285,59 @@ impl CronService {
         self.tasks.set_plugin_services(content_lock, audit);
     }
 
-    /// Set the email service for sending queued emails.
-    pub fn set_email_service(
+    /// Set the stage service for scheduled stage publishes.
+    pub fn set_stage_service(&mut self, stage: Arc<StageService>) {
+        self.tasks.set_stage_service(stage);
+    }
+
+    /// Set the content type registry for the dangling reference check.
+    pub fn set_content_types(&mut self, content_types: Arc<ContentTypeRegistry>) {
+        self.tasks.set_content_types(content_types);
+    }
+
+    /// Set the read-only service; write tasks are skipped while read-only.
+    pub fn set_read_only_service(
         &mut self,
-        email: Option<std::sync::Arc<crate::services::email::EmailService>>,
+        read_only: Arc<crate::services::read_only::ReadOnlyService>,
     ) {
-        self.tasks.set_email_service(email);
+        self.read_only = Some(read_only);
+    }
+
+    /// Set the mail service for delivering queued mail and cron alerts.
+    pub fn set_mail_service(&mut self, mail: Arc<MailService>) {
+        self.tasks.set_mail_service(mail.clone());
+        self.mail = Some(mail);
+    }
+
+    /// Set the saved search service `tap_cron` handlers check alerts with.
+    pub fn set_saved_search_service(&mut self, saved_searches: Arc<SavedSearchService>) {
+        self.saved_searches = Some(saved_searches);
     }
 
-    /// Run all cron tasks.
+    /// Set the scheduled update service `tap_cron` handlers apply due
+    /// updates with.
+    pub fn set_scheduled_update_service(&mut self, scheduled_updates: Arc<ScheduledUpdateService>) {
+        self.scheduled_updates = Some(scheduled_updates);
+    }
+
+    /// Run all due cron tasks.
     ///
-    /// Acquires a distributed lock before running to ensure only one
-    /// instance executes cron at a time.
+    /// Applies the configured jitter, then acquires a distributed lock
+    /// before running to ensure only one instance executes cron at a time.
+    /// Tasks that are disabled or not yet due per their schedule are skipped,
+    /// as are tasks outside [`READ_ONLY_SAFE_TASKS`] while the site is
+    /// read-only.
     pub async fn run(&self) -> CronResult {
-        let start = std::time::Instant::now();
+        if self.jitter_secs > 0 {
+            let delay = {
+                use rand::Rng;
+                rand::thread_rng().gen_range(0..=self.jitter_secs * 1000)
+            };
+            debug!(delay_ms = delay, "applying cron jitter");
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+
+        let start = Instant::now();
 
         // Try to acquire lock
         let lock_value = match self.acquire_lock().await {
@@ -164,107 +362,264 @@ impl CronService {
             run_heartbeat(heartbeat_redis, &heartbeat_lock, stop_rx).await;
         });
 
+        // Work out which tasks are due this cycle
+        let mut due = self.due_tasks(chrono::Utc::now()).await;
+        if self.is_read_only().await {
+            due.retain(|task| READ_ONLY_SAFE_TASKS.contains(task));
+            info!("site is read-only; running read-only safe tasks only");
+        }
+
         // Run tasks
         let mut tasks_run = Vec::new();
+        let mut log = RunLog::new(hostname());
 
         // Cleanup temporary files
-        match self.tasks.cleanup_temp_files().await {
-            Ok(count) => {
-                info!(deleted = count, "cleaned up temporary files");
-                tasks_run.push(format!("cleanup_temp_files: {count}"));
+        if due.contains("cleanup_temp_files") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_temp_files().await;
+            log.finish("cleanup_temp_files", started, result.as_ref().copied());
+            match result {
+                Ok(count) => {
+                    info!(deleted = count, "cleaned up temporary files");
+                    tasks_run.push(format!("cleanup_temp_files: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup temp files"),
             }
-            Err(e) => warn!(error = %e, "failed to cleanup temp files"),
         }
 
         // Cleanup expired sessions
-        match self.tasks.cleanup_expired_sessions().await {
-            Ok(count) => {
-                info!(deleted = count, "cleaned up expired sessions");
-                tasks_run.push(format!("cleanup_expired_sessions: {count}"));
+        if due.contains("cleanup_expired_sessions") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_expired_sessions().await;
+            log.finish(
+                "cleanup_expired_sessions",
+                started,
+                result.as_ref().copied(),
+            );
+            match result {
+                Ok(count) => {
+                    info!(deleted = count, "cleaned up expired sessions");
+                    tasks_run.push(format!("cleanup_expired_sessions: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup sessions"),
             }
-            Err(e) => warn!(error = %e, "failed to cleanup sessions"),
         }
 
         // Cleanup form state cache
-        match self.tasks.cleanup_form_state_cache().await {
-            Ok(count) => {
-                info!(deleted = count, "cleaned up form state cache");
-                tasks_run.push(format!("cleanup_form_state_cache: {count}"));
+        if due.contains("cleanup_form_state_cache") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_form_state_cache().await;
+            log.finish(
+                "cleanup_form_state_cache",
+                started,
+                result.as_ref().copied(),
+            );
+            match result {
+                Ok(count) => {
+                    info!(deleted = count, "cleaned up form state cache");
+                    tasks_run.push(format!("cleanup_form_state_cache: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup form state"),
+            }
+        }
+
+        // Feed items pending a search reindex to the reindex queue
+        if due.contains("search_reindex") {
+            let started = Instant::now();
+            let result = self.tasks.queue_search_reindex().await;
+            log.finish(
+                "search_reindex",
+                started,
+                result.as_ref().map(|(pushed, _)| *pushed),
+            );
+            match result {
+                Ok((pushed, pending)) if pending > 0 => {
+                    info!(
+                        pushed = pushed,
+                        pending = pending,
+                        "queued search reindex batch"
+                    );
+                    tasks_run.push(format!(
+                        "search_reindex: {pushed} queued, {pending} pending"
+                    ));
+                }
+                Err(e) => warn!(error = %e, "failed to queue search reindex"),
+                _ => {}
             }
-            Err(e) => warn!(error = %e, "failed to cleanup form state"),
         }
 
         // Process queues
-        match self.tasks.process_queues().await {
-            Ok(count) => {
-                info!(processed = count, "processed queue items");
-                tasks_run.push(format!("process_queues: {count}"));
+        if due.contains("process_queues") {
+            let started = Instant::now();
+            let result = self.tasks.process_queues().await;
+            log.finish("process_queues", started, result.as_ref().copied());
+            match result {
+                Ok(count) => {
+                    info!(processed = count, "processed queue items");
+                    tasks_run.push(format!("process_queues: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to process queues"),
             }
-            Err(e) => warn!(error = %e, "failed to process queues"),
         }
 
         // Cleanup expired verification tokens
-        match self.tasks.cleanup_verification_tokens().await {
-            Ok(count) if count > 0 => {
-                info!(count = count, "cleaned up expired verification tokens");
-                tasks_run.push(format!("cleanup_verification_tokens: {count}"));
+        if due.contains("cleanup_verification_tokens") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_verification_tokens().await;
+            log.finish(
+                "cleanup_verification_tokens",
+                started,
+                result.as_ref().copied(),
+            );
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "cleaned up expired verification tokens");
+                    tasks_run.push(format!("cleanup_verification_tokens: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup verification tokens"),
+                _ => {}
             }
-            Err(e) => warn!(error = %e, "failed to cleanup verification tokens"),
-            _ => {}
         }
 
         // Cleanup expired password reset tokens
-        match self.tasks.cleanup_password_reset_tokens().await {
-            Ok(count) if count > 0 => {
-                info!(count = count, "cleaned up expired password reset tokens");
-                tasks_run.push(format!("cleanup_password_reset_tokens: {count}"));
+        if due.contains("cleanup_password_reset_tokens") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_password_reset_tokens().await;
+            log.finish(
+                "cleanup_password_reset_tokens",
+                started,
+                result.as_ref().copied(),
+            );
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "cleaned up expired password reset tokens");
+                    tasks_run.push(format!("cleanup_password_reset_tokens: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup password reset tokens"),
+                _ => {}
             }
-            Err(e) => warn!(error = %e, "failed to cleanup password reset tokens"),
-            _ => {}
         }
 
         // Cleanup expired content locks
-        match self.tasks.cleanup_expired_locks().await {
-            Ok(count) if count > 0 => {
-                info!(count = count, "cleaned up expired locks");
-                tasks_run.push(format!("cleanup_expired_locks: {count}"));
+        if due.contains("cleanup_expired_locks") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_expired_locks().await;
+            log.finish("cleanup_expired_locks", started, result.as_ref().copied());
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "cleaned up expired locks");
+                    tasks_run.push(format!("cleanup_expired_locks: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup locks"),
+                _ => {}
             }
-            Err(e) => warn!(error = %e, "failed to cleanup locks"),
-            _ => {}
         }
 
         // Cleanup audit log (periodic)
-        match self.tasks.cleanup_audit_log().await {
-            Ok(count) if count > 0 => {
-                info!(count = count, "cleaned up old audit log entries");
-                tasks_run.push(format!("cleanup_audit_log: {count}"));
+        if due.contains("cleanup_audit_log") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_audit_log().await;
+            log.finish("cleanup_audit_log", started, result.as_ref().copied());
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "cleaned up old audit log entries");
+                    tasks_run.push(format!("cleanup_audit_log: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup audit log"),
+                _ => {}
+            }
+        }
+
+        // Remove personal workspaces past their expiry
+        if due.contains("cleanup_personal_stages") {
+            let started = Instant::now();
+            let result = self.tasks.cleanup_personal_stages().await;
+            log.finish("cleanup_personal_stages", started, result.as_ref().copied());
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "removed expired personal workspaces");
+                    tasks_run.push(format!("cleanup_personal_stages: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to cleanup personal workspaces"),
+                _ => {}
+            }
+        }
+
+        // Publish stages whose scheduled time has passed
+        if due.contains("publish_scheduled_stages") {
+            let started = Instant::now();
+            let result = self.tasks.publish_scheduled_stages().await;
+            log.finish(
+                "publish_scheduled_stages",
+                started,
+                result.as_ref().copied(),
+            );
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "published scheduled stages");
+                    tasks_run.push(format!("publish_scheduled_stages: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to publish scheduled stages"),
+                _ => {}
+            }
+        }
+
+        // Record references to deleted items
+        if due.contains("check_dangling_references") {
+            let started = Instant::now();
+            let result = self.tasks.check_dangling_references().await;
+            log.finish(
+                "check_dangling_references",
+                started,
+                result.as_ref().copied(),
+            );
+            match result {
+                Ok(count) if count > 0 => {
+                    warn!(count = count, "found dangling item references");
+                    tasks_run.push(format!("check_dangling_references: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to check dangling references"),
+                _ => {}
+            }
+        }
+
+        if due.contains("refresh_mac_vendors") {
+            let started = Instant::now();
+            let result = crate::services::mac_vendor::refresh_if_due(&self.pool, &self.http).await;
+            log.finish(
+                "refresh_mac_vendors",
+                started,
+                result.as_ref().map(|count| count.unwrap_or(0) as u64),
+            );
+            match result {
+                Ok(Some(count)) => tasks_run.push(format!("refresh_mac_vendors: {count}")),
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "failed to refresh MAC vendors"),
             }
-            Err(e) => warn!(error = %e, "failed to cleanup audit log"),
-            _ => {}
         }
 
         // Dispatch tap_cron to all plugins that implement it
-        if let Some(ref dispatcher) = self.tap_dispatcher {
-            let expected = dispatcher.registry().handler_count("tap_cron");
+        if let Some(ref dispatcher) = self.tap_dispatcher
+            && due.contains("tap_cron")
+        {
+            let plugins = dispatcher.plugin_names("tap_cron");
+            let expected = plugins.len();
             if expected > 0 {
-                let cron_input = trovato_sdk::types::CronInput {
-                    timestamp: chrono::Utc::now().timestamp(),
-                };
-                // Infallible: CronInput is a flat struct with a single i64 field.
-                let input_json = serde_json::to_string(&cron_input)
-                    .unwrap_or_else(|_| r#"{"timestamp":0}"#.to_string());
-                let state = RequestState::new(
-                    crate::tap::UserContext::anonymous(),
-                    crate::tap::RequestServices::for_background(
-                        self.pool.clone(),
-                        self.ai_providers.clone(),
-                        self.ai_budgets.clone(),
-                        self.http.clone(),
-                    ),
+                let started = Instant::now();
+                let timestamp = chrono::Utc::now().timestamp();
+                let mut services = crate::tap::RequestServices::for_background(
+                    self.pool.clone(),
+                    self.cache.clone(),
+                    self.ai_providers.clone(),
+                    self.ai_budgets.clone(),
+                    self.http.clone(),
                 );
+                services.saved_searches = self.saved_searches.clone();
+                services.scheduled_updates = self.scheduled_updates.clone();
+                let state = RequestState::new(crate::tap::UserContext::anonymous(), services);
                 match tokio::time::timeout(
                     Duration::from_secs(LOCK_TTL_SECS / 2),
-                    dispatcher.dispatch("tap_cron", &input_json, state),
+                    self.dispatch_tap_cron(dispatcher, &plugins, timestamp, state),
                 )
                 .await
                 {
@@ -281,6 +636,13 @@ impl CronService {
                                 failed = failed,
                                 "some tap_cron handlers failed (see dispatcher errors above)"
                             );
+                            log.failed(
+                                "tap_cron",
+                                started,
+                                format!("{failed} of {expected} handlers failed"),
+                            );
+                        } else {
+                            log.ok("tap_cron", started, results.len() as u64);
                         }
                     }
                     Err(_) => {
@@ -290,6 +652,7 @@ impl CronService {
                             "tap_cron dispatch timed out"
                         );
                         tasks_run.push("tap_cron:TIMEOUT".to_string());
+                        log.failed("tap_cron", started, "dispatch timed out");
                     }
                 }
             }
@@ -301,23 +664,68 @@ impl CronService {
         // We drain up to MAX_QUEUE_ITEMS_PER_CYCLE items per plugin and call
         // tap_queue_worker on each. Items are deleted after successful dispatch.
         if let Some(ref dispatcher) = self.tap_dispatcher
+            && due.contains("tap_queue_worker")
             && dispatcher.registry().has_tap("tap_queue_worker")
         {
-            match self.dispatch_plugin_queues(dispatcher).await {
+            let started = Instant::now();
+            let result = self.dispatch_plugin_queues(dispatcher).await;
+            log.finish("tap_queue_worker", started, result.as_ref().map(|()| 0));
+            match result {
                 Ok(()) => tasks_run.push("tap_queue_worker".to_string()),
                 Err(e) => warn!(error = %e, "plugin queue dispatch failed"),
             }
         }
 
+        // Continue plugin batches whose starting request ran out of time
+        if let Some(ref batch) = self.batch
+            && due.contains("batch_continue")
+        {
+            let started = Instant::now();
+            let result = batch
+                .continue_active(Duration::from_secs(LOCK_TTL_SECS / 2))
+                .await;
+            log.finish(
+                "batch_continue",
+                started,
+                result.as_ref().map(|count| *count as u64),
+            );
+            match result {
+                Ok(count) if count > 0 => {
+                    info!(count = count, "continued plugin batches");
+                    tasks_run.push(format!("batch_continue: {count}"));
+                }
+                Err(e) => warn!(error = %e, "failed to continue plugin batches"),
+                _ => {}
+            }
+        }
+
         // Rebuild Pagefind index if the trovato_search plugin is enabled and requested it
-        if self.pagefind_enabled {
-            match pagefind::maybe_rebuild_index(&self.pool).await {
+        if self.pagefind_enabled && due.contains("pagefind_rebuild") {
+            let started = Instant::now();
+            let result = pagefind::maybe_rebuild_index(&self.pool).await;
+            log.finish(
+                "pagefind_rebuild",
+                started,
+                result.as_ref().map(|rebuilt| u64::from(*rebuilt)),
+            );
+            match result {
                 Ok(true) => tasks_run.push("pagefind_rebuild".to_string()),
                 Ok(false) => {}
                 Err(e) => warn!(error = %e, "pagefind index rebuild failed"),
             }
         }
 
+        // Record last-run timestamps of the tasks that ran successfully;
+        // skipped and failed tasks stay due for the next cycle
+        if let Err(e) = self.record_task_runs(&log.succeeded()).await {
+            warn!(error = %e, "failed to record cron task runs");
+        }
+
+        // Persist the run history and alert on failing or slow tasks
+        if let Err(e) = self.record_history(&log).await {
+            warn!(error = %e, "failed to record cron history");
+        }
+
         // Stop heartbeat
         let _ = stop_tx.send(true);
         let _ = heartbeat_handle.await;
@@ -350,7 +758,7 @@ impl CronService {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -374,7 +782,7 @@ impl CronService {
     async fn release_lock(&self, lock_value: &str) -> Result<()> {
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -391,11 +799,310 @@ impl CronService {
         Ok(())
     }
 
+    /// Determine which tasks should run at `now`.
+    ///
+    /// Disabled tasks are skipped. Scheduled tasks run when their next
+    /// occurrence after the last recorded run has passed. Settings or
+    /// Redis failures fall back to running the task, matching the
+    /// pre-scheduling behavior of running everything every cycle.
+    async fn due_tasks(&self, now: chrono::DateTime<chrono::Utc>) -> HashSet<&'static str> {
+        let last_runs = self.task_last_runs().await.unwrap_or_else(|e| {
+            warn!(error = %e, "failed to load cron task last runs");
+            HashMap::new()
+        });
+
+        let mut due = HashSet::new();
+        for &name in CRON_TASKS {
+            let settings = self.task_settings(name).await.unwrap_or_else(|e| {
+                warn!(task = name, error = %e, "failed to load cron task settings");
+                CronTaskSettings::default()
+            });
+            let last_run = last_runs
+                .get(name)
+                .and_then(|ts| chrono::DateTime::from_timestamp(*ts, 0));
+            if is_task_due(&settings, last_run, now) {
+                due.insert(name);
+            } else {
+                debug!(task = name, "cron task not due");
+            }
+        }
+        due
+    }
+
+    /// Whether the site is read-only. Settings failures count as writable,
+    /// as the tasks would then fail on the database themselves.
+    async fn is_read_only(&self) -> bool {
+        let Some(read_only) = &self.read_only else {
+            return false;
+        };
+        read_only.is_read_only().await.unwrap_or_else(|e| {
+            warn!(error = %e, "failed to load read-only settings");
+            false
+        })
+    }
+
+    /// Load settings for a task, falling back to defaults if none are stored.
+    pub async fn task_settings(&self, name: &str) -> Result<CronTaskSettings> {
+        let key = format!("{TASK_SETTINGS_PREFIX}{name}");
+        match SiteConfig::get(&self.pool, &key).await? {
+            Some(value) => {
+                serde_json::from_value(value).context("failed to parse cron task settings")
+            }
+            None => Ok(CronTaskSettings::default()),
+        }
+    }
+
+    /// Persist settings for a task.
+    ///
+    /// Returns an error if the task is unknown or the schedule does not parse.
+    pub async fn set_task_settings(&self, name: &str, settings: &CronTaskSettings) -> Result<()> {
+        if !CRON_TASKS.contains(&name) {
+            anyhow::bail!("unknown cron task: {name}");
+        }
+        if let Some(ref expr) = settings.schedule {
+            CronSchedule::parse(expr).map_err(|e| anyhow::anyhow!(e))?;
+        }
+        let key = format!("{TASK_SETTINGS_PREFIX}{name}");
+        let value = serde_json::to_value(settings).context("failed to serialize settings")?;
+        SiteConfig::set(&self.pool, &key, value).await
+    }
+
+    /// Load the cron alert settings, falling back to defaults (no alerts).
+    pub async fn alert_settings(&self) -> Result<CronAlertSettings> {
+        match SiteConfig::get(&self.pool, history::ALERT_SETTINGS_KEY).await? {
+            Some(value) => {
+                serde_json::from_value(value).context("failed to parse cron alert settings")
+            }
+            None => Ok(CronAlertSettings::default()),
+        }
+    }
+
+    /// Persist the cron alert settings.
+    pub async fn set_alert_settings(&self, settings: &CronAlertSettings) -> Result<()> {
+        let value = serde_json::to_value(settings).context("failed to serialize settings")?;
+        SiteConfig::set(&self.pool, history::ALERT_SETTINGS_KEY, value).await
+    }
+
+    /// Recorded task runs matching `filter`, newest first.
+    pub async fn history(&self, filter: &CronHistoryFilter) -> Result<Vec<CronTaskRun>> {
+        history::query(&self.pool, filter).await
+    }
+
+    /// Persist the task runs of a cycle and send any alerts they raise.
+    async fn record_history(&self, log: &RunLog) -> Result<()> {
+        history::record(&self.pool, log).await?;
+
+        let settings = self.alert_settings().await?;
+        if !settings.is_enabled() {
+            return Ok(());
+        }
+        for entry in &log.entries {
+            let failures_in_row = if entry.status == history::STATUS_FAILED {
+                history::consecutive_failures(&self.pool, &entry.task).await?
+            } else {
+                0
+            };
+            if let Some(alert) = history::alert_for(&settings, entry, failures_in_row) {
+                self.send_alert(&settings, &alert).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deliver an alert by mail and webhook. Delivery failures are logged.
+    async fn send_alert(&self, settings: &CronAlertSettings, alert: &CronAlert) {
+        warn!(task = %alert.task, alert = %alert.subject(), "cron alert");
+
+        match &self.mail {
+            Some(mail) => {
+                for to in &settings.mail {
+                    let message = MailMessage::new(to, &alert.subject(), &alert.body());
+                    if let Err(e) = mail.send_now(message).await {
+                        warn!(error = %e, to = %to, "failed to mail cron alert");
+                    }
+                }
+            }
+            None if !settings.mail.is_empty() => {
+                warn!("cron alert mail configured but no mail service is available");
+            }
+            None => {}
+        }
+
+        if let Some(ref url) = settings.webhook {
+            let result = self
+                .http
+                .post(url)
+                .json(alert)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status());
+            if let Err(e) = result {
+                warn!(error = %e, "failed to post cron alert webhook");
+            }
+        }
+    }
+
+    /// Load last-run timestamps for all tasks.
+    async fn task_last_runs(&self) -> Result<HashMap<String, i64>> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        conn.hgetall(TASK_LAST_RUN_KEY)
+            .await
+            .context("failed to get task last runs")
+    }
+
+    /// Record the current time as the last run for each given task.
+    async fn record_task_runs(&self, tasks: &[&str]) -> Result<()> {
+        if tasks.is_empty() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let fields: Vec<(&str, i64)> = tasks.iter().map(|&name| (name, now)).collect();
+
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        conn.hset_multiple::<_, _, _, ()>(TASK_LAST_RUN_KEY, &fields)
+            .await
+            .context("failed to record task runs")
+    }
+
+    /// Build the status report for all tasks.
+    pub async fn task_statuses(&self) -> Result<Vec<CronTaskStatus>> {
+        let last_runs = self.task_last_runs().await?;
+        let now = chrono::Utc::now();
+
+        let mut statuses = Vec::with_capacity(CRON_TASKS.len());
+        for &name in CRON_TASKS {
+            let settings = self.task_settings(name).await?;
+            let last_run = last_runs.get(name).copied();
+            let next_run = if settings.enabled {
+                settings
+                    .schedule
+                    .as_deref()
+                    .and_then(|expr| CronSchedule::parse(expr).ok())
+                    .and_then(|s| {
+                        let from = last_run
+                            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                            .unwrap_or(now);
+                        s.next_after(from)
+                    })
+                    .map(|t| t.timestamp())
+            } else {
+                None
+            };
+            statuses.push(CronTaskStatus {
+                name: name.to_string(),
+                enabled: settings.enabled,
+                schedule: settings.schedule,
+                last_run,
+                next_run,
+            });
+        }
+
+        Ok(statuses)
+    }
+
+    /// Get the configured jitter ceiling in seconds.
+    pub fn jitter_secs(&self) -> u64 {
+        self.jitter_secs
+    }
+
     /// Get the queue for pushing items.
     pub fn queue(&self) -> &Arc<RedisQueue> {
         &self.queue
     }
 
+    /// Pending plugin queue items per plugin and queue name.
+    pub async fn plugin_queue_depths(&self) -> Result<Vec<PluginQueueDepth>> {
+        sqlx::query_as::<_, PluginQueueDepth>(
+            r#"
+            SELECT plugin_name, queue_name, COUNT(*) AS depth
+            FROM plugin_queue
+            GROUP BY plugin_name, queue_name
+            ORDER BY plugin_name, queue_name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to count plugin queue items")
+    }
+
+    /// Dispatch `tap_cron` to each plugin with its own last run and state.
+    ///
+    /// Returns the results of the handlers that succeeded; their run and
+    /// returned state are recorded for the next cycle.
+    async fn dispatch_tap_cron(
+        &self,
+        dispatcher: &TapDispatcher,
+        plugins: &[String],
+        timestamp: i64,
+        state: RequestState,
+    ) -> Vec<TapResult> {
+        let mut stored: HashMap<String, plugin_state::PluginCronState> =
+            match plugin_state::load_all(&self.pool).await {
+                Ok(rows) => rows
+                    .into_iter()
+                    .map(|row| (row.plugin_name.clone(), row))
+                    .collect(),
+                Err(e) => {
+                    warn!(error = %e, "failed to load plugin cron state");
+                    HashMap::new()
+                }
+            };
+
+        let mut results = Vec::with_capacity(plugins.len());
+        for plugin in plugins {
+            let previous = stored.remove(plugin);
+            let cron_input = trovato_sdk::types::CronInput {
+                timestamp,
+                last_run: previous.as_ref().map(|p| p.last_run),
+                state: previous.and_then(|p| p.state),
+            };
+            let input_json = match serde_json::to_string(&cron_input) {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!(plugin = %plugin, error = %e, "failed to serialize cron input");
+                    continue;
+                }
+            };
+            let Some(result) = dispatcher
+                .dispatch_to_plugin("tap_cron", &input_json, plugin, state.clone())
+                .await
+            else {
+                continue;
+            };
+
+            let update = plugin_state::state_update(&result.output);
+            if let plugin_state::StateUpdate::TooLarge(bytes) = update {
+                warn!(
+                    plugin = %plugin,
+                    bytes = bytes,
+                    max_bytes = trovato_sdk::types::CRON_STATE_MAX_BYTES,
+                    "tap_cron state too large, keeping previous state"
+                );
+            }
+            if let Err(e) = plugin_state::record(&self.pool, plugin, timestamp, &update).await {
+                warn!(plugin = %plugin, error = %e, "failed to record plugin cron state");
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    /// Stored `tap_cron` run and state of every plugin that has run.
+    pub async fn plugin_states(&self) -> Result<Vec<PluginCronState>> {
+        plugin_state::load_all(&self.pool).await
+    }
+
     /// Drain pending plugin queue items and dispatch `tap_queue_worker`.
     ///
     /// For each distinct plugin with items in `plugin_queue`, we pop up to
@@ -464,6 +1171,7 @@ impl CronService {
                     crate::tap::UserContext::anonymous(),
                     crate::tap::RequestServices::for_background(
                         self.pool.clone(),
+                        self.cache.clone(),
                         self.ai_providers.clone(),
                         self.ai_budgets.clone(),
                         self.http.clone(),
@@ -506,7 +1214,7 @@ impl CronService {
     pub async fn last_run(&self) -> Result<Option<LastCronRun>> {
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -535,7 +1243,7 @@ impl CronService {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -556,14 +1264,46 @@ pub struct LastCronRun {
     pub result: String,
 }
 
+/// Pending items of one plugin queue.
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+pub struct PluginQueueDepth {
+    pub plugin_name: String,
+    pub queue_name: String,
+    pub depth: i64,
+}
+
+/// Decide whether a task is due given its settings and last run.
+///
+/// Unparseable schedules are treated as unscheduled so that a bad config
+/// value can never silently stop a maintenance task.
+pub fn is_task_due(
+    settings: &CronTaskSettings,
+    last_run: Option<chrono::DateTime<chrono::Utc>>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if !settings.enabled {
+        return false;
+    }
+    let Some(ref expr) = settings.schedule else {
+        return true;
+    };
+    match CronSchedule::parse(expr) {
+        Ok(schedule) => schedule.is_due(last_run, now),
+        Err(e) => {
+            warn!(schedule = %expr, error = %e, "invalid cron schedule; running every cycle");
+            true
+        }
+    }
+}
+
 /// Run the heartbeat task to extend lock TTL.
-async fn run_heartbeat(redis: RedisClient, lock_value: &str, mut stop_rx: watch::Receiver<bool>) {
+async fn run_heartbeat(redis: RedisManager, lock_value: &str, mut stop_rx: watch::Receiver<bool>) {
     let mut interval = tokio::time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
 
     loop {
         tokio::select! {
             _ = interval.tick() => {
-                if let Ok(mut conn) = redis.get_multiplexed_async_connection().await {
+                if let Ok(mut conn) = redis.get().await {
                     // Extend lock TTL if we still own it
                     let script = redis::Script::new(EXTEND_LOCK_SCRIPT);
                     if let Err(e) = script
@@ -655,4 +1395,51 @@ mod tests {
         let parsed: LastCronRun = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.hostname, "test-host");
     }
+
+    #[test]
+    fn test_task_settings_defaults_from_empty_json() {
+        let settings: CronTaskSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, CronTaskSettings::default());
+        assert!(settings.enabled);
+    }
+
+    #[test]
+    fn test_read_only_safe_tasks_are_known() {
+        for task in READ_ONLY_SAFE_TASKS {
+            assert!(CRON_TASKS.contains(task), "unknown task {task}");
+        }
+        assert!(!READ_ONLY_SAFE_TASKS.contains(&"process_queues"));
+        assert!(!READ_ONLY_SAFE_TASKS.contains(&"tap_cron"));
+    }
+
+    #[test]
+    fn test_is_task_due() {
+        let now = chrono::DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let recent = Some(now - chrono::Duration::minutes(5));
+
+        // Unscheduled tasks run every cycle.
+        assert!(is_task_due(&CronTaskSettings::default(), recent, now));
+
+        // Disabled tasks never run.
+        let disabled = CronTaskSettings {
+            enabled: false,
+            schedule: None,
+        };
+        assert!(!is_task_due(&disabled, None, now));
+
+        // Daily task that ran five minutes ago is not due.
+        let daily = CronTaskSettings {
+            enabled: true,
+            schedule: Some("0 3 * * *".to_string()),
+        };
+        assert!(!is_task_due(&daily, recent, now));
+        assert!(is_task_due(&daily, None, now));
+
+        // Invalid schedules fall back to running.
+        let invalid = CronTaskSettings {
+            enabled: true,
+            schedule: Some("not a schedule".to_string()),
+        };
+        assert!(is_task_due(&invalid, recent, now));
+    }
 }
@@ -106,7 +106,7 @@ pub async fn maybe_rebuild_index(pool: &PgPool) -> Result<bool> {
 }
 
 /// Build the Pagefind index from published live-stage items.
-async fn build_index(pool: &PgPool) -> Result<usize> {
+pub async fn build_index(pool: &PgPool) -> Result<usize> {
     let static_dir = std::env::var("STATIC_DIR")
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from("./static"));
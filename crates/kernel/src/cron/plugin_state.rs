@@ -0,0 +1,161 @@
+//! Per-plugin `tap_cron` state.
+//!
+//! Each plugin's last successful `tap_cron` run and the `state` value it
+//! returned are kept in `plugin_cron_state` and passed back in the next
+//! run's `CronInput`, so plugins need no tables of their own to remember
+//! where they left off.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::PgPool;
+use trovato_sdk::types::CRON_STATE_MAX_BYTES;
+
+/// Stored `tap_cron` state of one plugin.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PluginCronState {
+    pub plugin_name: String,
+    /// Cycle timestamp of the last successful run.
+    pub last_run: i64,
+    pub state: Option<serde_json::Value>,
+    /// Serialized size of `state` in bytes.
+    pub state_bytes: i32,
+    /// Unix timestamp the row was last written.
+    pub updated: i64,
+}
+
+/// What a `tap_cron` result asks for its stored state.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum StateUpdate {
+    /// No `state` key (or output that is not an object): keep the old state.
+    Keep,
+    /// Replace the state; carries the serialized size.
+    Set(serde_json::Value, usize),
+    /// `"state": null`: drop the state.
+    Clear,
+    /// The state exceeded [`CRON_STATE_MAX_BYTES`]; the old state is kept.
+    TooLarge(usize),
+}
+
+/// Read the `state` key from a `tap_cron` handler's JSON output.
+pub(super) fn state_update(output: &str) -> StateUpdate {
+    let Ok(serde_json::Value::Object(mut map)) = serde_json::from_str(output) else {
+        return StateUpdate::Keep;
+    };
+    match map.remove("state") {
+        None => StateUpdate::Keep,
+        Some(serde_json::Value::Null) => StateUpdate::Clear,
+        Some(state) => {
+            let bytes = state.to_string().len();
+            if bytes > CRON_STATE_MAX_BYTES {
+                StateUpdate::TooLarge(bytes)
+            } else {
+                StateUpdate::Set(state, bytes)
+            }
+        }
+    }
+}
+
+/// All stored plugin states, ordered by plugin name.
+pub async fn load_all(pool: &PgPool) -> Result<Vec<PluginCronState>> {
+    sqlx::query_as::<_, PluginCronState>(
+        r#"
+        SELECT plugin_name, last_run, state, state_bytes, updated
+        FROM plugin_cron_state
+        ORDER BY plugin_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to load plugin cron state")
+}
+
+/// Record a successful `tap_cron` run of `plugin` in the cycle started at
+/// `last_run`, applying the state update from its output.
+pub(super) async fn record(
+    pool: &PgPool,
+    plugin: &str,
+    last_run: i64,
+    update: &StateUpdate,
+) -> Result<()> {
+    let now = chrono::Utc::now().timestamp();
+    let (state, bytes) = match update {
+        StateUpdate::Keep | StateUpdate::TooLarge(_) => {
+            sqlx::query(
+                r#"
+                INSERT INTO plugin_cron_state (plugin_name, last_run, updated)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (plugin_name) DO UPDATE
+                SET last_run = EXCLUDED.last_run, updated = EXCLUDED.updated
+                "#,
+            )
+            .bind(plugin)
+            .bind(last_run)
+            .bind(now)
+            .execute(pool)
+            .await
+            .context("failed to record plugin cron run")?;
+            return Ok(());
+        }
+        StateUpdate::Set(state, bytes) => (Some(state), *bytes),
+        StateUpdate::Clear => (None, 0),
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO plugin_cron_state (plugin_name, last_run, state, state_bytes, updated)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (plugin_name) DO UPDATE
+        SET last_run = EXCLUDED.last_run,
+            state = EXCLUDED.state,
+            state_bytes = EXCLUDED.state_bytes,
+            updated = EXCLUDED.updated
+        "#,
+    )
+    .bind(plugin)
+    .bind(last_run)
+    .bind(state)
+    .bind(bytes as i32)
+    .bind(now)
+    .execute(pool)
+    .await
+    .context("failed to save plugin cron state")?;
+    Ok(())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_key_sets_state() {
+        let update = state_update(r#"{"done": 3, "state": {"cursor": 7}}"#);
+        assert_eq!(
+            update,
+            StateUpdate::Set(serde_json::json!({"cursor": 7}), 12)
+        );
+    }
+
+    #[test]
+    fn missing_state_key_keeps_state() {
+        assert_eq!(state_update(r#"{"done": 3}"#), StateUpdate::Keep);
+        assert_eq!(state_update("[1, 2]"), StateUpdate::Keep);
+        assert_eq!(state_update("not json"), StateUpdate::Keep);
+    }
+
+    #[test]
+    fn null_state_clears_state() {
+        assert_eq!(state_update(r#"{"state": null}"#), StateUpdate::Clear);
+    }
+
+    #[test]
+    fn oversized_state_is_refused() {
+        let big = "x".repeat(CRON_STATE_MAX_BYTES);
+        let output = serde_json::json!({"state": big}).to_string();
+        assert_eq!(
+            state_update(&output),
+            StateUpdate::TooLarge(CRON_STATE_MAX_BYTES + 2)
+        );
+    }
+}
@@ -1,9 +1,47 @@
 //! Redis-backed queue for background task processing.
+//!
+//! Consumers [`ack`](Queue::ack) items they processed and
+//! [`fail`](Queue::fail) items they could not. A failed item waits in the
+//! queue's retry list until [`requeue_failed`](Queue::requeue_failed) puts
+//! it back at the start of the next run; after [`MAX_ATTEMPTS`] failures it
+//! moves to the dead-letter list with its payload and the last error, where
+//! administrators can retry or discard it.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use redis::{AsyncCommands, Client as RedisClient};
-use tracing::debug;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::redis_manager::RedisManager;
+
+/// Failures after which an item moves to the dead-letter list.
+pub const MAX_ATTEMPTS: u64 = 3;
+
+/// Dead letters kept per queue; the oldest are dropped beyond this.
+pub const MAX_DEAD_LETTERS: usize = 1000;
+
+/// An item that failed [`MAX_ATTEMPTS`] times.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub queue: String,
+    /// The item as it was pushed.
+    pub payload: String,
+    /// Error of the last attempt.
+    pub error: String,
+    pub attempts: u64,
+    /// Unix timestamp of the last attempt.
+    pub failed_at: i64,
+}
+
+impl DeadLetter {
+    /// Whether the letter is among `ids` (all letters when `None`).
+    fn is_selected(&self, ids: Option<&[Uuid]>) -> bool {
+        ids.is_none_or(|ids| ids.contains(&self.id))
+    }
+}
 
 /// Queue trait for background task processing.
 #[async_trait]
@@ -21,16 +59,48 @@ pub trait Queue: Send + Sync {
     async fn is_empty(&self, queue: &str) -> Result<bool> {
         Ok(self.len(queue).await? == 0)
     }
+
+    /// Record that `item` was processed, forgetting earlier failures.
+    async fn ack(&self, queue: &str, item: &str) -> Result<()>;
+
+    /// Record that processing `item` failed with `error`.
+    ///
+    /// The item is retried on the next run, or moved to the dead-letter
+    /// list once it has failed [`MAX_ATTEMPTS`] times. Returns `true` if
+    /// it was dead-lettered.
+    async fn fail(&self, queue: &str, item: &str, error: &str) -> Result<bool>;
+
+    /// Move failed items awaiting a retry back onto the queue.
+    ///
+    /// Returns the number of items requeued.
+    async fn requeue_failed(&self, queue: &str) -> Result<u64>;
+
+    /// Number of failed items awaiting a retry.
+    async fn retry_len(&self, queue: &str) -> Result<u64>;
+
+    /// Dead letters of the queue, newest first.
+    async fn dead_letters(&self, queue: &str) -> Result<Vec<DeadLetter>>;
+
+    /// Push dead letters back onto the queue with a fresh attempt count.
+    ///
+    /// Retries the letters in `ids`, or all when `None`. Returns the number
+    /// retried.
+    async fn retry_dead_letters(&self, queue: &str, ids: Option<&[Uuid]>) -> Result<u64>;
+
+    /// Delete dead letters in `ids`, or all when `None`.
+    ///
+    /// Returns the number deleted.
+    async fn discard_dead_letters(&self, queue: &str, ids: Option<&[Uuid]>) -> Result<u64>;
 }
 
 /// Redis-backed queue implementation.
 pub struct RedisQueue {
-    redis: RedisClient,
+    redis: RedisManager,
 }
 
 impl RedisQueue {
     /// Create a new Redis queue.
-    pub fn new(redis: RedisClient) -> Self {
+    pub fn new(redis: RedisManager) -> Self {
         Self { redis }
     }
 
@@ -38,6 +108,58 @@ impl RedisQueue {
     fn queue_key(&self, queue: &str) -> String {
         format!("queue:{queue}")
     }
+
+    /// Key of the hash counting failed attempts per item.
+    fn attempts_key(&self, queue: &str) -> String {
+        format!("queue:{queue}:attempts")
+    }
+
+    /// Key of the list of failed items awaiting a retry.
+    fn retry_key(&self, queue: &str) -> String {
+        format!("queue:{queue}:retry")
+    }
+
+    /// Key of the dead-letter list.
+    fn dead_key(&self, queue: &str) -> String {
+        format!("queue:{queue}:dead")
+    }
+
+    /// Remove the selected dead letters and return them.
+    async fn take_dead_letters(
+        &self,
+        queue: &str,
+        ids: Option<&[Uuid]>,
+    ) -> Result<Vec<DeadLetter>> {
+        let key = self.dead_key(queue);
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        let raw: Vec<String> = conn
+            .lrange(&key, 0, -1)
+            .await
+            .context("failed to read dead letters")?;
+
+        let mut taken = Vec::new();
+        for entry in raw {
+            let Ok(letter) = serde_json::from_str::<DeadLetter>(&entry) else {
+                continue;
+            };
+            if !letter.is_selected(ids) {
+                continue;
+            }
+            let removed: u64 = conn
+                .lrem(&key, 1, &entry)
+                .await
+                .context("failed to remove dead letter")?;
+            if removed > 0 {
+                taken.push(letter);
+            }
+        }
+        Ok(taken)
+    }
 }
 
 #[async_trait]
@@ -47,7 +169,7 @@ impl Queue for RedisQueue {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -64,7 +186,7 @@ impl Queue for RedisQueue {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -95,7 +217,7 @@ impl Queue for RedisQueue {
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -106,6 +228,134 @@ impl Queue for RedisQueue {
 
         Ok(len)
     }
+
+    async fn ack(&self, queue: &str, item: &str) -> Result<()> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        conn.hdel::<_, _, ()>(self.attempts_key(queue), item)
+            .await
+            .context("failed to clear queue item attempts")?;
+
+        Ok(())
+    }
+
+    async fn fail(&self, queue: &str, item: &str, error: &str) -> Result<bool> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        let attempts_key = self.attempts_key(queue);
+        let attempts: u64 = conn
+            .hincr(&attempts_key, item, 1)
+            .await
+            .context("failed to count queue item attempts")?;
+
+        if attempts < MAX_ATTEMPTS {
+            conn.rpush::<_, _, ()>(self.retry_key(queue), item)
+                .await
+                .context("failed to schedule queue item retry")?;
+            debug!(queue = %queue, attempts, "queue item will be retried");
+            return Ok(false);
+        }
+
+        let letter = DeadLetter {
+            id: Uuid::now_v7(),
+            queue: queue.to_string(),
+            payload: item.to_string(),
+            error: error.to_string(),
+            attempts,
+            failed_at: chrono::Utc::now().timestamp(),
+        };
+        let json = serde_json::to_string(&letter).context("failed to serialize dead letter")?;
+        let dead_key = self.dead_key(queue);
+        conn.lpush::<_, _, ()>(&dead_key, json)
+            .await
+            .context("failed to push dead letter")?;
+        conn.ltrim::<_, ()>(&dead_key, 0, MAX_DEAD_LETTERS as isize - 1)
+            .await
+            .context("failed to trim dead letters")?;
+        conn.hdel::<_, _, ()>(&attempts_key, item)
+            .await
+            .context("failed to clear queue item attempts")?;
+
+        warn!(queue = %queue, attempts, error = %error, "queue item moved to dead-letter list");
+        Ok(true)
+    }
+
+    async fn requeue_failed(&self, queue: &str) -> Result<u64> {
+        let retry_key = self.retry_key(queue);
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        // Copy, then trim: a crash in between repeats items rather than
+        // losing them.
+        let items: Vec<String> = conn
+            .lrange(&retry_key, 0, -1)
+            .await
+            .context("failed to read queue retries")?;
+        if items.is_empty() {
+            return Ok(0);
+        }
+        conn.rpush::<_, _, ()>(self.queue_key(queue), &items)
+            .await
+            .context("failed to requeue retries")?;
+        conn.ltrim::<_, ()>(&retry_key, items.len() as isize, -1)
+            .await
+            .context("failed to trim queue retries")?;
+
+        Ok(items.len() as u64)
+    }
+
+    async fn retry_len(&self, queue: &str) -> Result<u64> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        conn.llen(self.retry_key(queue))
+            .await
+            .context("failed to get queue retry length")
+    }
+
+    async fn dead_letters(&self, queue: &str) -> Result<Vec<DeadLetter>> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        let raw: Vec<String> = conn
+            .lrange(self.dead_key(queue), 0, -1)
+            .await
+            .context("failed to read dead letters")?;
+
+        Ok(raw
+            .iter()
+            .filter_map(|entry| serde_json::from_str(entry).ok())
+            .collect())
+    }
+
+    async fn retry_dead_letters(&self, queue: &str, ids: Option<&[Uuid]>) -> Result<u64> {
+        let letters = self.take_dead_letters(queue, ids).await?;
+        for letter in &letters {
+            self.push(queue, &letter.payload).await?;
+        }
+        Ok(letters.len() as u64)
+    }
+
+    async fn discard_dead_letters(&self, queue: &str, ids: Option<&[Uuid]>) -> Result<u64> {
+        Ok(self.take_dead_letters(queue, ids).await?.len() as u64)
+    }
 }
 
 impl std::fmt::Debug for RedisQueue {
@@ -119,12 +369,34 @@ impl std::fmt::Debug for RedisQueue {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::redis_manager::RedisSettings;
 
     #[test]
     fn test_queue_key() {
-        let client = RedisClient::open("redis://127.0.0.1:6379").unwrap();
+        let client =
+            RedisManager::new(RedisSettings::standalone("redis://127.0.0.1:6379")).unwrap();
         let queue = RedisQueue::new(client);
         assert_eq!(queue.queue_key("test"), "queue:test");
         assert_eq!(queue.queue_key("email:send"), "queue:email:send");
+        assert_eq!(queue.retry_key("email:send"), "queue:email:send:retry");
+        assert_eq!(queue.dead_key("email:send"), "queue:email:send:dead");
+    }
+
+    #[test]
+    fn dead_letter_selection() {
+        let letter = DeadLetter {
+            id: Uuid::now_v7(),
+            queue: "email:send".to_string(),
+            payload: "{}".to_string(),
+            error: "smtp down".to_string(),
+            attempts: MAX_ATTEMPTS,
+            failed_at: 0,
+        };
+        assert!(letter.is_selected(None));
+        assert!(letter.is_selected(Some(&[letter.id])));
+        assert!(!letter.is_selected(Some(&[Uuid::now_v7()])));
+
+        let json = serde_json::to_string(&letter).unwrap();
+        assert_eq!(serde_json::from_str::<DeadLetter>(&json).unwrap(), letter);
     }
 }
@@ -0,0 +1,295 @@
+//! Cron expression parsing and next-run computation.
+//!
+//! Supports the standard five-field syntax (`minute hour day-of-month
+//! month day-of-week`) with `*`, single values, ranges (`1-5`), lists
+//! (`1,15,30`), and steps (`*/15`, `0-30/10`). Day-of-week accepts both
+//! `0` and `7` for Sunday. All evaluation is in UTC.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+/// Upper bound on how far ahead [`CronSchedule::next_after`] searches.
+///
+/// Expressions such as `0 0 31 2 *` never match; the search gives up
+/// after this many days instead of looping forever.
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+/// A parsed five-field cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    /// Whether the day-of-month field was `*` (affects DOM/DOW combination).
+    dom_wildcard: bool,
+    /// Whether the day-of-week field was `*`.
+    dow_wildcard: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression.
+    ///
+    /// Returns a human-readable error describing the first invalid field.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "cron expression must have 5 fields, found {}",
+                fields.len()
+            ));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, "minute")?;
+        let hours = parse_field(fields[1], 0, 23, "hour")?;
+        let days_of_month = parse_field(fields[2], 1, 31, "day-of-month")?;
+        let months = parse_field(fields[3], 1, 12, "month")?;
+        let mut days_of_week = parse_field(fields[4], 0, 7, "day-of-week")?;
+
+        // Fold Sunday-as-7 onto Sunday-as-0.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+            days_of_week &= !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            dom_wildcard: fields[2] == "*",
+            dow_wildcard: fields[4] == "*",
+        })
+    }
+
+    /// The normalized expression string.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Whether the schedule fires during the minute containing `at`.
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        self.minutes & (1 << at.minute()) != 0
+            && self.hours & (1 << at.hour()) != 0
+            && self.months & (1 << at.month()) != 0
+            && self.day_matches(at)
+    }
+
+    /// Compute the first matching minute strictly after `after`.
+    ///
+    /// Returns `None` if no match exists within the search horizon
+    /// (e.g. an impossible date such as February 31st).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Start from the next whole minute.
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let horizon = start + Duration::days(MAX_SEARCH_DAYS);
+        let mut t = start;
+
+        while t < horizon {
+            if self.months & (1 << t.month()) == 0 {
+                // Jump to the first minute of next month.
+                let (y, m) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(y, m, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(t) {
+                t = (t + Duration::days(1)).with_hour(0)?.with_minute(0)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = (t + Duration::hours(1)).with_minute(0)?;
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+
+        None
+    }
+
+    /// Whether the schedule is due given the previous run time.
+    ///
+    /// A task that has never run is always due. Otherwise it is due when
+    /// the next scheduled time after the last run is at or before `now`.
+    pub fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        match last_run {
+            None => true,
+            Some(last) => self.next_after(last).is_some_and(|next| next <= now),
+        }
+    }
+
+    /// Standard cron day matching: when both day fields are restricted,
+    /// a match on either one is sufficient.
+    fn day_matches(&self, at: DateTime<Utc>) -> bool {
+        let dom = self.days_of_month & (1 << at.day()) != 0;
+        let dow = self.days_of_week & (1 << at.weekday().num_days_from_sunday()) != 0;
+        match (self.dom_wildcard, self.dow_wildcard) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+impl std::fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Parse a single cron field into a bitmask of allowed values.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => {
+                let step: u32 = s
+                    .parse()
+                    .map_err(|_| format!("invalid step '{s}' in {name} field"))?;
+                if step == 0 {
+                    return Err(format!("step must be positive in {name} field"));
+                }
+                (r, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                parse_value(a, min, max, name)?,
+                parse_value(b, min, max, name)?,
+            )
+        } else {
+            let v = parse_value(range, min, max, name)?;
+            // "5/10" means "starting at 5, every 10".
+            if step > 1 { (v, max) } else { (v, v) }
+        };
+
+        if start > end {
+            return Err(format!("invalid range '{range}' in {name} field"));
+        }
+
+        let mut v = start;
+        while v <= end {
+            mask |= 1 << v;
+            v += step;
+        }
+    }
+
+    Ok(mask)
+}
+
+/// Parse a single numeric value and check its bounds.
+fn parse_value(s: &str, min: u32, max: u32, name: &str) -> Result<u32, String> {
+    let v: u32 = s
+        .parse()
+        .map_err(|_| format!("invalid value '{s}' in {name} field"))?;
+    if v < min || v > max {
+        return Err(format!(
+            "value {v} out of range {min}-{max} in {name} field"
+        ));
+    }
+    Ok(v)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_rejects_wrong_field_count() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("* * * * * *").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_out_of_range() {
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("* 24 * * *").is_err());
+        assert!(CronSchedule::parse("* * 0 * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn every_minute_is_next_minute() {
+        let s = CronSchedule::parse("* * * * *").unwrap();
+        let next = s.next_after(at(2026, 1, 1, 10, 30)).unwrap();
+        assert_eq!(next, at(2026, 1, 1, 10, 31));
+    }
+
+    #[test]
+    fn step_minutes() {
+        let s = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            s.next_after(at(2026, 1, 1, 10, 31)).unwrap(),
+            at(2026, 1, 1, 10, 45)
+        );
+        assert_eq!(
+            s.next_after(at(2026, 1, 1, 10, 45)).unwrap(),
+            at(2026, 1, 1, 11, 0)
+        );
+    }
+
+    #[test]
+    fn daily_rolls_over_month_and_year() {
+        let s = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(
+            s.next_after(at(2026, 12, 31, 3, 0)).unwrap(),
+            at(2027, 1, 1, 2, 30)
+        );
+    }
+
+    #[test]
+    fn day_of_week_sunday_as_seven() {
+        let s = CronSchedule::parse("0 0 * * 7").unwrap();
+        // 2026-01-04 is a Sunday.
+        assert_eq!(
+            s.next_after(at(2026, 1, 1, 0, 0)).unwrap(),
+            at(2026, 1, 4, 0, 0)
+        );
+    }
+
+    #[test]
+    fn impossible_date_returns_none() {
+        let s = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert!(s.next_after(at(2026, 1, 1, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn is_due_respects_last_run() {
+        let s = CronSchedule::parse("0 * * * *").unwrap();
+        assert!(s.is_due(None, at(2026, 1, 1, 10, 5)));
+        assert!(!s.is_due(Some(at(2026, 1, 1, 10, 0)), at(2026, 1, 1, 10, 59)));
+        assert!(s.is_due(Some(at(2026, 1, 1, 10, 0)), at(2026, 1, 1, 11, 0)));
+    }
+
+    #[test]
+    fn matches_checks_all_fields() {
+        let s = CronSchedule::parse("0-10 9-17 * 1-6 1-5").unwrap();
+        // 2026-01-05 is a Monday.
+        assert!(s.matches(at(2026, 1, 5, 9, 5)));
+        assert!(!s.matches(at(2026, 1, 5, 9, 11)));
+        assert!(!s.matches(at(2026, 1, 4, 9, 5)));
+        assert!(!s.matches(at(2026, 7, 6, 9, 5)));
+    }
+}
@@ -4,15 +4,23 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use sqlx::PgPool;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
 
 use super::queue::RedisQueue;
+use crate::content::ContentTypeRegistry;
 use crate::file::FileService;
+use crate::models::User;
+use crate::search::{REINDEX_QUEUE, SearchService};
 use crate::services;
+use crate::stage::{StagePublishSchedule, StageService};
 
 /// Temporary file max age in seconds (6 hours).
 const TEMP_FILE_MAX_AGE_SECS: i64 = 6 * 60 * 60;
 
+/// Search reindex queue items processed per run.
+const SEARCH_REINDEX_BATCH: i64 = 100;
+
 /// Collection of cron tasks.
 pub struct CronTasks {
     pool: PgPool,
@@ -20,7 +28,9 @@ pub struct CronTasks {
     files: Option<Arc<FileService>>,
     content_lock: Option<Arc<services::content_lock::ContentLockService>>,
     audit: Option<Arc<services::audit::AuditService>>,
-    email: Option<Arc<services::email::EmailService>>,
+    mail: Option<Arc<services::mail::MailService>>,
+    stage: Option<Arc<StageService>>,
+    content_types: Option<Arc<ContentTypeRegistry>>,
 }
 
 impl CronTasks {
@@ -32,7 +42,9 @@ impl CronTasks {
             files: None,
             content_lock: None,
             audit: None,
-            email: None,
+            mail: None,
+            stage: None,
+            content_types: None,
         }
     }
 
@@ -48,7 +60,9 @@ impl CronTasks {
             files: Some(files),
             content_lock: None,
             audit: None,
-            email: None,
+            mail: None,
+            stage: None,
+            content_types: None,
         }
     }
 
@@ -62,9 +76,19 @@ impl CronTasks {
         self.audit = audit;
     }
 
-    /// Set the email service for sending queued emails.
-    pub fn set_email_service(&mut self, email: Option<Arc<services::email::EmailService>>) {
-        self.email = email;
+    /// Set the mail service for delivering queued mail.
+    pub fn set_mail_service(&mut self, mail: Arc<services::mail::MailService>) {
+        self.mail = Some(mail);
+    }
+
+    /// Set the stage service for scheduled stage publishes.
+    pub fn set_stage_service(&mut self, stage: Arc<StageService>) {
+        self.stage = Some(stage);
+    }
+
+    /// Set the content type registry for the dangling reference check.
+    pub fn set_content_types(&mut self, content_types: Arc<ContentTypeRegistry>) {
+        self.content_types = Some(content_types);
     }
 
     /// Cleanup temporary files older than 6 hours.
@@ -107,35 +131,18 @@ impl CronTasks {
 
     /// Cleanup expired sessions.
     ///
-    /// Sessions are stored in Redis with TTL, but we also clean up
-    /// any stale session data in the database if present.
+    /// Redis sessions expire via TTL; this removes expired rows left by the
+    /// PostgreSQL session store (`SESSION_STORE=postgres`).
     pub async fn cleanup_expired_sessions(&self) -> Result<u64> {
-        // Calculate cutoff time (sessions older than 24 hours)
-        let cutoff = chrono::Utc::now().timestamp() - (24 * 60 * 60);
+        let now = chrono::Utc::now().timestamp();
 
-        // Check if sessions table exists and clean up if so
-        let result = sqlx::query(
-            r#"
-            DELETE FROM sessions
-            WHERE updated < $1
-            "#,
-        )
-        .bind(cutoff)
-        .execute(&self.pool)
-        .await;
+        let result = sqlx::query("DELETE FROM sessions WHERE expires <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("failed to cleanup sessions")?;
 
-        match result {
-            Ok(res) => Ok(res.rows_affected()),
-            Err(e) => {
-                // Table might not exist
-                if e.to_string().contains("sessions") {
-                    debug!("sessions table not found, skipping cleanup");
-                    Ok(0)
-                } else {
-                    Err(e).context("failed to cleanup sessions")
-                }
-            }
-        }
+        Ok(result.rows_affected())
     }
 
     /// Cleanup form state cache entries older than 6 hours.
@@ -161,32 +168,38 @@ impl CronTasks {
     ///
     /// Currently processes:
     /// - email:send - Send queued emails
-    /// - search:reindex - Reindex items for search
+    /// - search:reindex - Rebuild search vectors of queued items
+    ///
+    /// Items that failed on an earlier run are requeued first. Failing
+    /// items are retried on the next run until they reach the dead-letter
+    /// list.
     pub async fn process_queues(&self) -> Result<u64> {
         use super::Queue;
 
         let mut total_processed = 0u64;
 
         // Process email queue (up to 50 items per run)
+        self.queue
+            .requeue_failed(services::mail::MAIL_QUEUE)
+            .await?;
         for _ in 0..50 {
-            match self.queue.pop("email:send", 0).await? {
+            match self.queue.pop(services::mail::MAIL_QUEUE, 0).await? {
                 Some(item) => {
-                    if let Err(e) = self.process_email_item(&item).await {
-                        info!(error = %e, "failed to process email queue item");
-                    }
+                    let result = self.process_email_item(&item).await;
+                    self.settle(services::mail::MAIL_QUEUE, &item, result).await;
                     total_processed += 1;
                 }
                 None => break,
             }
         }
 
-        // Process search reindex queue (up to 100 items per run)
-        for _ in 0..100 {
-            match self.queue.pop("search:reindex", 0).await? {
+        // Process search reindex queue
+        self.queue.requeue_failed(REINDEX_QUEUE).await?;
+        for _ in 0..SEARCH_REINDEX_BATCH {
+            match self.queue.pop(REINDEX_QUEUE, 0).await? {
                 Some(item) => {
-                    if let Err(e) = self.process_reindex_item(&item).await {
-                        info!(error = %e, "failed to process reindex queue item");
-                    }
+                    let result = self.process_reindex_item(&item).await;
+                    self.settle(REINDEX_QUEUE, &item, result).await;
                     total_processed += 1;
                 }
                 None => break,
@@ -196,6 +209,54 @@ impl CronTasks {
         Ok(total_processed)
     }
 
+    /// Acknowledge a processed queue item, or record its failure.
+    async fn settle(&self, queue: &str, item: &str, result: Result<()>) {
+        use super::Queue;
+
+        let settled = match result {
+            Ok(()) => self.queue.ack(queue, item).await,
+            Err(e) => {
+                info!(queue = %queue, error = %e, "failed to process queue item");
+                self.queue
+                    .fail(queue, item, &format!("{e:#}"))
+                    .await
+                    .map(|_| ())
+            }
+        };
+        if let Err(e) = settled {
+            warn!(queue = %queue, error = %e, "failed to record queue item outcome");
+        }
+    }
+
+    /// Feed items pending a search reindex to the reindex queue.
+    ///
+    /// Pushes the next batch of pending items from `search_index_status`
+    /// once the queue has drained, so [`process_queues`](Self::process_queues)
+    /// rebuilds a bundle a batch per cron run. Items stay pending until
+    /// their vector is rebuilt, so nothing is lost if Redis is flushed.
+    /// Returns the number of items pushed and the number still pending.
+    pub async fn queue_search_reindex(&self) -> Result<(u64, u64)> {
+        use super::Queue;
+
+        let search = SearchService::new(self.pool.clone());
+        let pending: u64 = search
+            .index_status()
+            .await?
+            .iter()
+            .map(|status| u64::try_from(status.pending).unwrap_or(0))
+            .sum();
+        if pending == 0 || !self.queue.is_empty(REINDEX_QUEUE).await? {
+            return Ok((0, pending));
+        }
+
+        let mut pushed = 0u64;
+        for item_id in search.pending_reindex(SEARCH_REINDEX_BATCH).await? {
+            self.queue.push(REINDEX_QUEUE, &item_id.to_string()).await?;
+            pushed += 1;
+        }
+        Ok((pushed, pending))
+    }
+
     /// Cleanup expired content locks.
     pub async fn cleanup_expired_locks(&self) -> Result<u64> {
         if let Some(ref service) = self.content_lock {
@@ -224,35 +285,133 @@ impl CronTasks {
         }
     }
 
+    /// Remove personal workspaces unused for longer than the configured expiry.
+    pub async fn cleanup_personal_stages(&self) -> Result<u64> {
+        services::workspace::cleanup_expired(&self.pool).await
+    }
+
+    /// Record references to items that no longer exist.
+    ///
+    /// Replaces the `dangling_reference` table with the current findings.
+    /// Returns the number of dangling references found.
+    pub async fn check_dangling_references(&self) -> Result<u64> {
+        let Some(ref content_types) = self.content_types else {
+            return Ok(0);
+        };
+        let types = content_types.list_all().await;
+        let found = services::cascade::find_dangling(&self.pool, &types).await?;
+        services::cascade::record_dangling(&self.pool, &found).await?;
+        Ok(found.len() as u64)
+    }
+
+    /// Publish stages whose scheduled time has passed.
+    ///
+    /// First mails the conflict report for publishes due within the hour,
+    /// then publishes due stages to live. A schedule is removed before its
+    /// publish runs, so a failing publish is reported once rather than
+    /// retried every cycle. Returns the number of stages published.
+    pub async fn publish_scheduled_stages(&self) -> Result<u64> {
+        let Some(ref stages) = self.stage else {
+            return Ok(0);
+        };
+        let now = chrono::Utc::now().timestamp();
+
+        for schedule in stages.scheduled_publishes_awaiting_report(now).await? {
+            if let Err(e) = self.send_stage_conflict_report(stages, &schedule).await {
+                warn!(stage_id = %schedule.stage_id, error = %e, "failed to send stage conflict report");
+            }
+            stages.mark_publish_reported(schedule.stage_id, now).await?;
+        }
+
+        let mut published = 0;
+        for schedule in stages.due_scheduled_publishes(now).await? {
+            let stage_id = schedule.stage_id;
+            if !stages.cancel_scheduled_publish(stage_id).await? {
+                // Cancelled since it was loaded.
+                continue;
+            }
+            let error = match stages.publish(stage_id).await {
+                Ok(result) if result.success => {
+                    info!(
+                        stage_id = %stage_id,
+                        items = result.items_published,
+                        "published scheduled stage"
+                    );
+                    published += 1;
+                    continue;
+                }
+                Ok(result) => result
+                    .error_message
+                    .unwrap_or_else(|| "publish failed".to_string()),
+                Err(e) => e.to_string(),
+            };
+            warn!(stage_id = %stage_id, error = %error, "scheduled stage publish failed");
+            let subject = "Scheduled stage publish failed";
+            let body = format!("The scheduled publish of stage {stage_id} failed: {error}");
+            self.mail_scheduler(schedule.scheduled_by, subject, &body)
+                .await;
+        }
+
+        Ok(published)
+    }
+
+    /// Mail the conflict report of a scheduled publish to its scheduler.
+    async fn send_stage_conflict_report(
+        &self,
+        stages: &StageService,
+        schedule: &StagePublishSchedule,
+    ) -> Result<()> {
+        let Some(stage) = stages.get_stage(schedule.stage_id).await? else {
+            return Ok(());
+        };
+        let conflicts = stages.detect_conflicts(stage.id).await?;
+        let unpublishable = stages.unpublishable_items(stage.id).await?;
+        let (subject, body) =
+            crate::stage::conflict_report(&stage, schedule, &conflicts, &unpublishable);
+        self.mail_scheduler(schedule.scheduled_by, &subject, &body)
+            .await;
+        Ok(())
+    }
+
+    /// Queue a message to the user who scheduled a stage publish.
+    ///
+    /// Does nothing without a mail service or a known recipient.
+    async fn mail_scheduler(&self, user_id: Option<Uuid>, subject: &str, body: &str) {
+        let (Some(mail), Some(user_id)) = (&self.mail, user_id) else {
+            return;
+        };
+        let user = match User::find_by_id(&self.pool, user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(user_id = %user_id, error = %e, "failed to load stage publish scheduler");
+                return;
+            }
+        };
+        let message = services::mail::MailMessage {
+            key: "stage_publish_schedule".to_string(),
+            ..services::mail::MailMessage::new(&user.mail, subject, body)
+        };
+        if let Err(e) = mail.queue(message).await {
+            warn!(to = %user.mail, error = %e, "failed to queue stage publish mail");
+        }
+    }
+
     /// Process a single email queue item.
     ///
-    /// Expects JSON with `to`, `subject`, and `body` fields.
-    /// Drops the email with a debug log if no email service is configured.
+    /// Expects a serialized `MailMessage` (legacy `{to, subject, body}`
+    /// items also parse). Messages were altered when queued, so they are
+    /// delivered as-is.
     async fn process_email_item(&self, item: &str) -> Result<()> {
-        let email_data: serde_json::Value =
+        let message: services::mail::MailMessage =
             serde_json::from_str(item).context("failed to parse email item")?;
 
-        let to = email_data
-            .get("to")
-            .and_then(|v| v.as_str())
-            .context("email item missing 'to' field")?;
-        let subject = email_data
-            .get("subject")
-            .and_then(|v| v.as_str())
-            .context("email item missing 'subject' field")?;
-        let body = email_data
-            .get("body")
-            .and_then(|v| v.as_str())
-            .context("email item missing 'body' field")?;
-
-        let Some(ref email_service) = self.email else {
-            debug!(to = %to, subject = %subject, "email service not configured, dropping queued email");
+        let Some(ref mail) = self.mail else {
+            debug!(to = %message.to, subject = %message.subject, "mail service not available, dropping queued email");
             return Ok(());
         };
 
-        email_service.send(to, subject, body).await?;
-        info!(to = %to, subject = %subject, "sent queued email");
-        Ok(())
+        mail.deliver(&message).await
     }
 
     /// Process a single reindex queue item.
@@ -260,19 +419,9 @@ impl CronTasks {
         // Item is just the UUID of the item to reindex
         let item_id: uuid::Uuid = item.parse().context("invalid item ID")?;
 
-        // Touch the item to trigger search_vector update
-        sqlx::query(
-            r#"
-            UPDATE item
-            SET changed = $2
-            WHERE id = $1
-            "#,
-        )
-        .bind(item_id)
-        .bind(chrono::Utc::now().timestamp())
-        .execute(&self.pool)
-        .await
-        .context("failed to reindex item")?;
+        SearchService::new(self.pool.clone())
+            .reindex_item(item_id)
+            .await?;
 
         debug!(item_id = %item_id, "reindexed item");
         Ok(())
@@ -1,10 +1,22 @@
 //! Database connection pool management.
+//!
+//! All writes go to the primary pool. Read replicas are optional: when
+//! `DATABASE_REPLICA_URLS` is set, read-only paths (search, gather, item
+//! view, config listing) go through [`DbPools`], which spreads them over
+//! healthy replicas and falls back to the primary.
 
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use serde::Serialize;
 use sqlx::migrate::Migrator;
+use sqlx::pool::PoolConnection;
 use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::{Postgres, Transaction};
+use tracing::{info, warn};
 
 use crate::config::Config;
 
@@ -29,6 +41,28 @@ pub async fn create_pool(config: &Config) -> Result<PgPool> {
     Ok(pool)
 }
 
+/// Create lazily connected pools for the configured read replicas.
+///
+/// Replicas do not block startup: each pool connects on first use, and
+/// [`DbPools::check_replicas`] decides whether it receives traffic.
+pub fn create_replica_pools(config: &Config) -> Result<Vec<PgPool>> {
+    config
+        .database_replica_urls
+        .iter()
+        .enumerate()
+        .map(|(i, url)| {
+            PgPoolOptions::new()
+                .max_connections(config.database_max_connections)
+                .idle_timeout(Duration::from_secs(10 * 60))
+                .max_lifetime(Duration::from_secs(30 * 60))
+                .acquire_timeout(Duration::from_secs(2))
+                .test_before_acquire(true)
+                .connect_lazy(url)
+                .with_context(|| format!("invalid URL for read replica {i}"))
+        })
+        .collect()
+}
+
 /// Run database migrations.
 pub async fn run_migrations(pool: &PgPool) -> Result<()> {
     MIGRATOR
@@ -39,7 +73,278 @@ pub async fn run_migrations(pool: &PgPool) -> Result<()> {
     Ok(())
 }
 
+/// Kernel migrations that have not been applied, as "{version} {description}".
+pub async fn pending_migrations(pool: &PgPool) -> Result<Vec<String>> {
+    let applied: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await
+            .context("failed to load applied migrations")?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+        .map(|m| format!("{} {}", m.version, m.description))
+        .collect())
+}
+
 /// Check if the database connection is healthy.
 pub async fn check_health(pool: &PgPool) -> bool {
     sqlx::query("SELECT 1").execute(pool).await.is_ok()
 }
+
+/// Health and utilization of one pool, for `/health` and metrics.
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStatus {
+    /// `primary`, or `replica_{n}` in configuration order.
+    pub name: String,
+    /// Whether the pool currently receives traffic.
+    pub healthy: bool,
+    /// Current number of connections.
+    pub size: u32,
+    /// Idle connections.
+    pub idle: u32,
+}
+
+/// A read replica and its last known health.
+struct Replica {
+    name: String,
+    pool: PgPool,
+    healthy: AtomicBool,
+}
+
+/// The primary pool plus any read replicas.
+///
+/// Cheap to clone. Without replicas every read goes to the primary, so
+/// callers don't need to care whether replicas are configured.
+#[derive(Clone)]
+pub struct DbPools {
+    inner: Arc<DbPoolsInner>,
+}
+
+struct DbPoolsInner {
+    primary: PgPool,
+    replicas: Vec<Replica>,
+    /// Round-robin cursor over replicas.
+    next: AtomicUsize,
+}
+
+impl DbPools {
+    /// Wrap a primary pool and its replicas.
+    ///
+    /// Replicas start out unhealthy until the first
+    /// [`check_replicas`](Self::check_replicas).
+    pub fn new(primary: PgPool, replicas: Vec<PgPool>) -> Self {
+        let replicas = replicas
+            .into_iter()
+            .enumerate()
+            .map(|(i, pool)| Replica {
+                name: format!("replica_{i}"),
+                pool,
+                healthy: AtomicBool::new(false),
+            })
+            .collect();
+        Self {
+            inner: Arc::new(DbPoolsInner {
+                primary,
+                replicas,
+                next: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// The primary pool. Use for writes and read-your-writes paths.
+    pub fn primary(&self) -> &PgPool {
+        &self.inner.primary
+    }
+
+    /// Whether any replicas are configured (healthy or not).
+    pub fn has_replicas(&self) -> bool {
+        !self.inner.replicas.is_empty()
+    }
+
+    /// Pick the next healthy replica, round-robin.
+    fn pick_replica(&self) -> Option<&Replica> {
+        let replicas = &self.inner.replicas;
+        if replicas.is_empty() {
+            return None;
+        }
+        let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+        (0..replicas.len())
+            .map(|offset| &replicas[(start + offset) % replicas.len()])
+            .find(|r| r.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Stop sending reads to a replica until it passes a health check.
+    fn mark_unhealthy(&self, replica: &Replica, error: &dyn std::fmt::Display) {
+        if replica.healthy.swap(false, Ordering::Relaxed) {
+            warn!(replica = %replica.name, error = %error, "read replica failed; falling back to primary");
+        }
+    }
+
+    /// Pool for a read-only query: a healthy replica, or the primary.
+    ///
+    /// Prefer [`acquire_read`](Self::acquire_read) or
+    /// [`read_with_fallback`](Self::read_with_fallback), which also fall
+    /// back when the chosen replica fails.
+    pub fn read(&self) -> &PgPool {
+        self.pick_replica().map_or(&self.inner.primary, |r| &r.pool)
+    }
+
+    /// Acquire a connection for read-only queries, falling back to the
+    /// primary if the replica cannot hand one out.
+    pub async fn acquire_read(&self) -> Result<PoolConnection<Postgres>> {
+        if let Some(replica) = self.pick_replica() {
+            match replica.pool.acquire().await {
+                Ok(conn) => return Ok(conn),
+                Err(e) => self.mark_unhealthy(replica, &e),
+            }
+        }
+        self.inner
+            .primary
+            .acquire()
+            .await
+            .context("failed to acquire database connection")
+    }
+
+    /// Begin a transaction for read-only queries, falling back to the
+    /// primary if the replica cannot start one.
+    pub async fn begin_read(&self) -> Result<Transaction<'static, Postgres>> {
+        if let Some(replica) = self.pick_replica() {
+            match replica.pool.begin().await {
+                Ok(tx) => return Ok(tx),
+                Err(e) => self.mark_unhealthy(replica, &e),
+            }
+        }
+        self.inner
+            .primary
+            .begin()
+            .await
+            .context("failed to begin transaction")
+    }
+
+    /// Run a read-only operation on a replica, retrying it on the primary
+    /// if the replica fails with a connection error.
+    ///
+    /// Query errors (bad SQL, constraint violations) are returned as is;
+    /// the primary would fail the same way.
+    pub async fn read_with_fallback<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: Fn(PgPool) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if let Some(replica) = self.pick_replica() {
+            match f(replica.pool.clone()).await {
+                Err(e) if is_connection_error(&e) => self.mark_unhealthy(replica, &e),
+                result => return result,
+            }
+        }
+        f(self.inner.primary.clone()).await
+    }
+
+    /// Ping every replica and update which ones receive reads.
+    pub async fn check_replicas(&self) {
+        for replica in &self.inner.replicas {
+            let healthy = check_health(&replica.pool).await;
+            let was_healthy = replica.healthy.swap(healthy, Ordering::Relaxed);
+            if healthy && !was_healthy {
+                info!(replica = %replica.name, "read replica is healthy");
+            } else if !healthy && was_healthy {
+                warn!(replica = %replica.name, "read replica is not responding");
+            }
+        }
+    }
+
+    /// Health and utilization of the primary and each replica.
+    ///
+    /// Replica health is the result of the last check; the caller pings
+    /// the primary itself.
+    pub fn status(&self, primary_healthy: bool) -> Vec<PoolStatus> {
+        let describe = |name: &str, pool: &PgPool, healthy: bool| PoolStatus {
+            name: name.to_string(),
+            healthy,
+            size: pool.size(),
+            idle: pool.num_idle() as u32,
+        };
+        std::iter::once(describe("primary", &self.inner.primary, primary_healthy))
+            .chain(
+                self.inner
+                    .replicas
+                    .iter()
+                    .map(|r| describe(&r.name, &r.pool, r.healthy.load(Ordering::Relaxed))),
+            )
+            .collect()
+    }
+}
+
+/// Whether an error means the database could not be reached, as opposed to
+/// the query itself failing.
+fn is_connection_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<sqlx::Error>())
+        .any(|e| {
+            matches!(
+                e,
+                sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::Protocol(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+                    | sqlx::Error::WorkerCrashed
+            )
+        })
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn lazy_pool() -> PgPool {
+        PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/trovato_test")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reads_use_primary_without_healthy_replicas() {
+        let pools = DbPools::new(lazy_pool(), vec![lazy_pool()]);
+        assert!(pools.has_replicas());
+        // Replicas start unhealthy until checked.
+        assert!(pools.pick_replica().is_none());
+
+        pools.inner.replicas[0]
+            .healthy
+            .store(true, Ordering::Relaxed);
+        assert_eq!(pools.pick_replica().unwrap().name, "replica_0");
+
+        let status = pools.status(true);
+        assert_eq!(status.len(), 2);
+        assert_eq!(status[0].name, "primary");
+        assert!(status[1].healthy);
+    }
+
+    #[tokio::test]
+    async fn replicas_rotate_and_skip_unhealthy() {
+        let pools = DbPools::new(lazy_pool(), vec![lazy_pool(), lazy_pool(), lazy_pool()]);
+        for (i, replica) in pools.inner.replicas.iter().enumerate() {
+            replica.healthy.store(i != 1, Ordering::Relaxed);
+        }
+        let picked: Vec<String> = (0..4)
+            .map(|_| pools.pick_replica().unwrap().name.clone())
+            .collect();
+        assert!(!picked.contains(&"replica_1".to_string()));
+        assert!(picked.contains(&"replica_0".to_string()));
+        assert!(picked.contains(&"replica_2".to_string()));
+    }
+
+    #[test]
+    fn connection_errors_are_distinguished_from_query_errors() {
+        let err = anyhow::Error::from(sqlx::Error::PoolTimedOut).context("failed to search");
+        assert!(is_connection_error(&err));
+        let err = anyhow::Error::from(sqlx::Error::RowNotFound).context("failed to search");
+        assert!(!is_connection_error(&err));
+        assert!(!is_connection_error(&anyhow::anyhow!("bad input")));
+    }
+}
@@ -5,11 +5,20 @@
 //! validation details. API requests receive JSON; HTML error pages
 //! are rendered by separate helpers in `routes::helpers`.
 
+use std::borrow::Cow;
+
 use axum::Json;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 
+use crate::config_storage::ConfigValidationFailed;
+use crate::content::ItemValidationFailed;
+use crate::models::item::ItemModified;
+use crate::models::password_policy::PasswordPolicyViolated;
+use crate::services::cascade::DeleteRestricted;
+use crate::services::read_only::WriteLocked;
+
 /// Structured error response returned to API clients.
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
@@ -30,7 +39,7 @@ pub struct FieldError {
     /// Field name that failed validation.
     pub field: String,
     /// Machine-readable error code (e.g., "required", "too_long", "invalid_format").
-    pub code: &'static str,
+    pub code: Cow<'static, str>,
     /// Human-readable description of what's wrong.
     pub message: String,
 }
@@ -66,6 +75,11 @@ pub enum AppError {
     #[error("conflict: {message}")]
     Conflict { message: String },
 
+    /// A conditional request's precondition (e.g. `If-Unmodified-Since`)
+    /// no longer holds.
+    #[error("precondition failed: {message}")]
+    PreconditionFailed { message: String },
+
     /// Rate limit exceeded.
     #[error("rate limit exceeded")]
     RateLimited {
@@ -93,6 +107,10 @@ pub enum AppError {
         reason: String,
     },
 
+    /// Writes are frozen by read-only mode or an item type write lock.
+    #[error("read-only: {message}")]
+    ReadOnly { message: String },
+
     /// Internal error — catch-all for unexpected failures.
     #[error("internal error")]
     Internal {
@@ -152,6 +170,13 @@ impl AppError {
         }
     }
 
+    /// Precondition of a conditional request failed.
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::PreconditionFailed {
+            message: message.into(),
+        }
+    }
+
     /// Database error with operation context.
     pub fn db(source: sqlx::Error, operation: &'static str) -> Self {
         Self::Database { source, operation }
@@ -165,20 +190,69 @@ impl AppError {
         }
     }
 
+    /// Writes refused by read-only mode, with the explanation for clients.
+    pub fn read_only(message: impl Into<String>) -> Self {
+        Self::ReadOnly {
+            message: message.into(),
+        }
+    }
+
     /// Internal error from any source.
     pub fn internal(source: impl Into<anyhow::Error>) -> Self {
-        Self::Internal {
-            source: source.into(),
-            context: None,
-        }
+        Self::from_source(source.into(), None)
     }
 
     /// Internal error with additional context.
     pub fn internal_ctx(source: impl Into<anyhow::Error>, context: impl Into<String>) -> Self {
-        Self::Internal {
-            source: source.into(),
-            context: Some(context.into()),
+        Self::from_source(source.into(), Some(context.into()))
+    }
+
+    /// Internal error, unless the source is a refused write, rejected,
+    /// concurrently modified or still referenced item, or rejected password,
+    /// which keep their own status.
+    fn from_source(source: anyhow::Error, context: Option<String>) -> Self {
+        if let Some(locked) = source.downcast_ref::<WriteLocked>() {
+            return Self::read_only(locked.message.clone());
+        }
+        if let Some(failed) = source.downcast_ref::<ItemValidationFailed>() {
+            return Self::validation(
+                failed
+                    .violations
+                    .iter()
+                    .map(|v| Self::field_error(v.field.clone(), v.code.clone(), v.message.clone()))
+                    .collect(),
+            );
+        }
+        if let Some(restricted) = source.downcast_ref::<DeleteRestricted>() {
+            return Self::conflict(format!(
+                "The item is referenced by {} item(s) that prevent its deletion.",
+                restricted.references.len()
+            ));
+        }
+        if source.downcast_ref::<ItemModified>().is_some() {
+            return Self::precondition_failed(
+                "The item was modified after it was read. Reload it and try again.",
+            );
+        }
+        if let Some(failed) = source.downcast_ref::<PasswordPolicyViolated>() {
+            return Self::validation(
+                failed
+                    .violations
+                    .iter()
+                    .map(|v| Self::field_error("password", v.code.clone(), v.message.clone()))
+                    .collect(),
+            );
         }
+        if let Some(failed) = source.downcast_ref::<ConfigValidationFailed>() {
+            return Self::validation(
+                failed
+                    .violations
+                    .iter()
+                    .map(|v| Self::field_error(v.field.clone(), v.code.clone(), v.message.clone()))
+                    .collect(),
+            );
+        }
+        Self::Internal { source, context }
     }
 
     /// Validation error with per-field details.
@@ -189,12 +263,12 @@ impl AppError {
     /// Create a single field error.
     pub fn field_error(
         field: impl Into<String>,
-        code: &'static str,
+        code: impl Into<Cow<'static, str>>,
         message: impl Into<String>,
     ) -> FieldError {
         FieldError {
             field: field.into(),
-            code,
+            code: code.into(),
             message: message.into(),
         }
     }
@@ -206,10 +280,7 @@ impl AppError {
 
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
-        Self::Internal {
-            source: err,
-            context: None,
-        }
+        Self::from_source(err, None)
     }
 }
 
@@ -271,6 +342,12 @@ impl IntoResponse for AppError {
             AppError::Conflict { message } => {
                 (StatusCode::CONFLICT, "conflict", message.clone(), None)
             }
+            AppError::PreconditionFailed { message } => (
+                StatusCode::PRECONDITION_FAILED,
+                "precondition_failed",
+                message.clone(),
+                None,
+            ),
             AppError::RateLimited {
                 retry_after_secs,
                 category,
@@ -311,6 +388,12 @@ impl IntoResponse for AppError {
                     None,
                 )
             }
+            AppError::ReadOnly { message } => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "read_only",
+                message.clone(),
+                None,
+            ),
             AppError::Internal { source, context } => {
                 tracing::error!(
                     error = %source,
@@ -411,6 +494,8 @@ pub type AppResult<T> = Result<T, AppError>;
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::content::ItemViolation;
+    use crate::models::password_policy::PasswordViolation;
 
     #[test]
     fn not_found_without_id() {
@@ -539,6 +624,80 @@ mod tests {
         assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
 
+    #[test]
+    fn write_locked_becomes_read_only() {
+        let err = AppError::internal_ctx(
+            WriteLocked {
+                message: "Migration in progress".to_string(),
+            },
+            "update item",
+        );
+        assert!(
+            matches!(&err, AppError::ReadOnly { message } if message == "Migration in progress")
+        );
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[test]
+    fn rejected_item_becomes_validation_error() {
+        let err = AppError::internal_ctx(
+            ItemValidationFailed {
+                violations: vec![ItemViolation {
+                    field: "isbn".to_string(),
+                    message: "Not a valid ISBN".to_string(),
+                    code: "invalid_isbn".to_string(),
+                }],
+            },
+            "create item",
+        );
+        let AppError::Validation { errors } = &err else {
+            panic!("expected validation error, got {err:?}");
+        };
+        assert_eq!(errors[0].field, "isbn");
+        assert_eq!(errors[0].code, "invalid_isbn");
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[test]
+    fn modified_item_becomes_precondition_failed() {
+        let err = AppError::internal_ctx(
+            ItemModified {
+                id: uuid::Uuid::nil(),
+            },
+            "patch item",
+        );
+        assert!(matches!(err, AppError::PreconditionFailed { .. }));
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::PRECONDITION_FAILED
+        );
+    }
+
+    #[test]
+    fn rejected_password_becomes_validation_error() {
+        let err = AppError::internal_ctx(
+            PasswordPolicyViolated {
+                violations: vec![
+                    PasswordViolation::new("too_short", "Too short"),
+                    PasswordViolation::new("breached", "Breached"),
+                ],
+            },
+            "change password",
+        );
+        let AppError::Validation { errors } = &err else {
+            panic!("expected validation error, got {err:?}");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.field == "password"));
+        assert_eq!(errors[1].code, "breached");
+    }
+
     #[test]
     fn payload_too_large_status() {
         let err = AppError::PayloadTooLarge {
@@ -0,0 +1,346 @@
+//! Media library search and usage.
+//!
+//! Backs the media browser API. Permanent files are searched by filename
+//! and by the alt text of the `media` items wrapping them. Each result
+//! carries the number of items that use the file: by file ID, by stored
+//! path, or through one of its `media` items.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::PgPool;
+use sqlx::postgres::{PgArguments, Postgres};
+use sqlx::query::QueryAs;
+use uuid::Uuid;
+
+use super::service::{FileInfo, FileStatus};
+
+/// Field of a `media` item holding its alt text.
+pub const ALT_TEXT_FIELD: &str = "field_alt_text";
+
+/// SQL condition: item `m` is a `media` item wrapping file `fm`.
+const WRAPS_FILE: &str = "m.type = 'media' AND m.fields->>'field_file' = fm.id::text";
+
+/// SQL expression: the alt text of item `m`, stored plain or as
+/// `{"value": ...}`.
+const ALT_TEXT: &str = "COALESCE(m.fields->'field_alt_text'->>'value', \
+     CASE WHEN jsonb_typeof(m.fields->'field_alt_text') = 'string' \
+     THEN m.fields->>'field_alt_text' END)";
+
+/// SQL `WITH` clause: `refs(file_id, needle)` holds the strings that refer
+/// to each file in `$1` — its ID, its stored path and the IDs of the
+/// `media` items wrapping it.
+const FILE_REFS: &str = "WITH files AS (SELECT fm.id, \
+     NULLIF(split_part(fm.uri, '://', 2), '') AS path \
+     FROM file_managed fm WHERE fm.id = ANY($1)), \
+     refs AS (SELECT id AS file_id, id::text AS needle FROM files \
+     UNION ALL SELECT id, path FROM files WHERE path IS NOT NULL \
+     UNION ALL SELECT files.id, m.id::text FROM files \
+     JOIN item m ON m.type = 'media' AND m.fields->>'field_file' = files.id::text)";
+
+/// SQL condition: item `u` contains reference `r`, not counting the `media`
+/// items that wrap the file.
+const USES_REF: &str = "strpos(u.fields::text, r.needle) > 0 \
+     AND NOT (u.type = 'media' AND u.fields->>'field_file' = r.file_id::text)";
+
+/// Filters for a media library search.
+#[derive(Debug, Clone, Default)]
+pub struct MediaQuery {
+    /// Case-insensitive search of filenames and alt text.
+    pub search: Option<String>,
+    /// MIME type: exact (`image/png`) or a prefix ending in `/` (`image/`).
+    pub mime: Option<String>,
+    /// Only files uploaded at or after this Unix timestamp.
+    pub created_from: Option<i64>,
+    /// Only files uploaded before this Unix timestamp.
+    pub created_to: Option<i64>,
+    /// `newest` (default), `oldest`, `name` or `size`.
+    pub sort: String,
+}
+
+/// A file in the media library.
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaEntry {
+    #[serde(flatten)]
+    pub file: FileInfo,
+    /// Alt text of the most recently changed `media` item wrapping the file.
+    pub alt_text: Option<String>,
+    /// Number of items using the file.
+    pub usage_count: i64,
+}
+
+/// An item using a file.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MediaUsage {
+    pub item_id: Uuid,
+    pub title: String,
+    pub item_type: String,
+    pub status: i16,
+    pub author_id: Uuid,
+}
+
+/// Database row for a library search result.
+#[derive(sqlx::FromRow)]
+struct MediaRow {
+    id: Uuid,
+    owner_id: Uuid,
+    filename: String,
+    uri: String,
+    filemime: String,
+    filesize: i64,
+    status: i16,
+    created: i64,
+    changed: i64,
+    alt_text: Option<String>,
+}
+
+impl MediaEntry {
+    fn new(row: MediaRow, usage_count: i64) -> Self {
+        Self {
+            file: FileInfo {
+                id: row.id,
+                owner_id: row.owner_id,
+                filename: row.filename,
+                uri: row.uri,
+                filemime: row.filemime,
+                filesize: row.filesize,
+                status: FileStatus::from(row.status),
+                created: row.created,
+                changed: row.changed,
+            },
+            alt_text: row.alt_text,
+            usage_count,
+        }
+    }
+}
+
+impl MediaQuery {
+    /// `WHERE` conditions on `file_managed fm`, numbering parameters from
+    /// `$1` in the order [`Self::bind`] binds them. Returns the next free
+    /// parameter number.
+    fn conditions(&self) -> (String, usize) {
+        let mut sql = format!("fm.status = {}", FileStatus::Permanent as i16);
+        let mut idx = 1;
+
+        if self.search.is_some() {
+            sql.push_str(&format!(
+                " AND (fm.filename ILIKE ${idx} OR EXISTS \
+                 (SELECT 1 FROM item m WHERE {WRAPS_FILE} AND {ALT_TEXT} ILIKE ${idx}))"
+            ));
+            idx += 1;
+        }
+        if let Some(ref mime) = self.mime {
+            let op = if mime.ends_with('/') { "LIKE" } else { "=" };
+            sql.push_str(&format!(" AND fm.filemime {op} ${idx}"));
+            idx += 1;
+        }
+        if self.created_from.is_some() {
+            sql.push_str(&format!(" AND fm.created >= ${idx}"));
+            idx += 1;
+        }
+        if self.created_to.is_some() {
+            sql.push_str(&format!(" AND fm.created < ${idx}"));
+            idx += 1;
+        }
+
+        (sql, idx)
+    }
+
+    /// Bind the parameters of [`Self::conditions`].
+    fn bind<'q, O>(
+        &self,
+        mut query: QueryAs<'q, Postgres, O, PgArguments>,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        if let Some(ref q) = self.search {
+            query = query.bind(format!("%{}%", escape_like(q)));
+        }
+        if let Some(ref mime) = self.mime {
+            if mime.ends_with('/') {
+                query = query.bind(format!("{}%", escape_like(mime)));
+            } else {
+                query = query.bind(mime.clone());
+            }
+        }
+        if let Some(from) = self.created_from {
+            query = query.bind(from);
+        }
+        if let Some(to) = self.created_to {
+            query = query.bind(to);
+        }
+        query
+    }
+
+    fn order_by(&self) -> &'static str {
+        match self.sort.as_str() {
+            "oldest" => "ORDER BY fm.created ASC, fm.id",
+            "name" => "ORDER BY fm.filename ASC, fm.id",
+            "size" => "ORDER BY fm.filesize DESC, fm.id",
+            _ => "ORDER BY fm.created DESC, fm.id", // "newest" (default)
+        }
+    }
+}
+
+/// Search the media library, returning one page of files and the total
+/// number of matches.
+pub async fn search(
+    pool: &PgPool,
+    query: &MediaQuery,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<MediaEntry>, i64)> {
+    let (conditions, idx) = query.conditions();
+
+    let count_sql = format!("SELECT COUNT(*) FROM file_managed fm WHERE {conditions}");
+    let (total,) = query
+        .bind(sqlx::query_as::<_, (i64,)>(&count_sql))
+        .fetch_one(pool)
+        .await
+        .context("failed to count media library files")?;
+
+    let list_sql = format!(
+        "SELECT fm.id, fm.owner_id, fm.filename, fm.uri, fm.filemime, fm.filesize, \
+         fm.status, fm.created, fm.changed, \
+         (SELECT {ALT_TEXT} FROM item m WHERE {WRAPS_FILE} \
+          ORDER BY m.changed DESC LIMIT 1) AS alt_text \
+         FROM file_managed fm WHERE {conditions} {} LIMIT ${idx} OFFSET ${}",
+        query.order_by(),
+        idx + 1
+    );
+    let rows = query
+        .bind(sqlx::query_as::<_, MediaRow>(&list_sql))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to search media library")?;
+
+    let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+    let counts = usage_counts(pool, &ids).await?;
+    let entries = rows
+        .into_iter()
+        .map(|row| {
+            let count = counts.get(&row.id).copied().unwrap_or(0);
+            MediaEntry::new(row, count)
+        })
+        .collect();
+    Ok((entries, total))
+}
+
+/// Number of items using each of `file_ids`, in one query for the whole
+/// page. Files no item uses are absent.
+async fn usage_counts(pool: &PgPool, file_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>> {
+    if file_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let sql = format!(
+        "{FILE_REFS} SELECT r.file_id, COUNT(DISTINCT u.id) \
+         FROM refs r JOIN item u ON {USES_REF} GROUP BY r.file_id"
+    );
+    let rows = sqlx::query_as::<_, (Uuid, i64)>(&sql)
+        .bind(file_ids)
+        .fetch_all(pool)
+        .await
+        .context("failed to count media usage")?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Items using a file, most recently changed first.
+pub async fn usage(pool: &PgPool, file_id: Uuid) -> Result<Vec<MediaUsage>> {
+    let sql = format!(
+        "{FILE_REFS} SELECT u.id AS item_id, u.title, u.type AS item_type, u.status, u.author_id \
+         FROM item u WHERE EXISTS (SELECT 1 FROM refs r WHERE {USES_REF}) \
+         ORDER BY u.changed DESC, u.id"
+    );
+    sqlx::query_as::<_, MediaUsage>(&sql)
+        .bind(vec![file_id])
+        .fetch_all(pool)
+        .await
+        .context("failed to load media usage")
+}
+
+/// IDs of the `media` items wrapping a file.
+pub async fn media_items(pool: &PgPool, file_id: Uuid) -> Result<Vec<Uuid>> {
+    let sql = format!(
+        "SELECT m.id FROM item m JOIN file_managed fm ON {WRAPS_FILE} \
+         WHERE fm.id = $1 ORDER BY m.id"
+    );
+    sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(file_id)
+        .fetch_all(pool)
+        .await
+        .context("failed to load media items for file")
+}
+
+/// Parse a date filter: a Unix timestamp or a `YYYY-MM-DD` date (UTC).
+///
+/// With `end` set, a date means the end of that day, so `to=2026-10-01`
+/// includes files uploaded on October 1st.
+pub fn parse_date_bound(value: &str, end: bool) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(ts) = value.parse::<i64>() {
+        return Some(ts);
+    }
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let date = if end { date.succ_opt()? } else { date };
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+}
+
+/// Escape `LIKE` wildcards so user input matches literally.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_number_parameters_in_bind_order() {
+        let (sql, next) = MediaQuery::default().conditions();
+        assert_eq!(sql, "fm.status = 1");
+        assert_eq!(next, 1);
+
+        let query = MediaQuery {
+            search: Some("logo".to_string()),
+            mime: Some("image/".to_string()),
+            created_from: Some(10),
+            created_to: Some(20),
+            sort: String::new(),
+        };
+        let (sql, next) = query.conditions();
+        assert_eq!(next, 5);
+        assert!(sql.contains("fm.filename ILIKE $1 OR EXISTS"));
+        assert!(sql.contains(" ILIKE $1))"));
+        assert!(sql.contains("fm.filemime LIKE $2"));
+        assert!(sql.contains("fm.created >= $3"));
+        assert!(sql.contains("fm.created < $4"));
+
+        let exact = MediaQuery {
+            mime: Some("image/png".to_string()),
+            ..Default::default()
+        };
+        assert!(exact.conditions().0.ends_with("fm.filemime = $1"));
+    }
+
+    #[test]
+    fn date_bounds_accept_timestamps_and_days() {
+        assert_eq!(parse_date_bound("1791849600", false), Some(1_791_849_600));
+        assert_eq!(parse_date_bound("2026-10-13", false), Some(1_791_849_600));
+        assert_eq!(
+            parse_date_bound("2026-10-13", true),
+            Some(1_791_849_600 + 86_400)
+        );
+        assert_eq!(parse_date_bound("13/10/2026", false), None);
+    }
+
+    #[test]
+    fn like_wildcards_are_escaped() {
+        assert_eq!(escape_like("50%_off\\"), "50\\%\\_off\\\\");
+    }
+}
@@ -0,0 +1,103 @@
+//! Moving managed files between storage backends.
+//!
+//! Used by `trovato files migrate`: every `file_managed` row stored on the
+//! source backend is copied to the target backend under the same path, and
+//! its URI is rewritten. Files are migrated one at a time so a failure
+//! leaves the row pointing at the intact source copy.
+
+use anyhow::{Context, Result, bail};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::storage::{FileStorage, rewrite_uri};
+
+/// Result of a storage migration.
+#[derive(Debug, Default)]
+pub struct MigrationSummary {
+    /// Files copied (or, for a dry run, that would be copied).
+    pub migrated: usize,
+    /// Total bytes copied.
+    pub bytes: u64,
+    /// Files that failed, with the reason.
+    pub failed: Vec<(Uuid, String)>,
+}
+
+/// Copy every file stored on `from` to `to` and point its record there.
+///
+/// With `delete_source`, the source copy is removed once the record has
+/// been updated. A dry run only counts the files that would move.
+pub async fn migrate_storage(
+    pool: &PgPool,
+    from: &dyn FileStorage,
+    to: &dyn FileStorage,
+    delete_source: bool,
+    dry_run: bool,
+) -> Result<MigrationSummary> {
+    if from.scheme() == to.scheme() {
+        bail!("source and target storage are both {}", from.scheme());
+    }
+
+    let pattern = format!("{}://%", from.scheme());
+    let files: Vec<(Uuid, String, i64)> = sqlx::query_as(
+        "SELECT id, uri, filesize FROM file_managed WHERE uri LIKE $1 ORDER BY created",
+    )
+    .bind(&pattern)
+    .fetch_all(pool)
+    .await
+    .context("failed to list files to migrate")?;
+
+    let mut summary = MigrationSummary::default();
+    for (id, uri, filesize) in files {
+        if dry_run {
+            summary.migrated += 1;
+            summary.bytes += u64::try_from(filesize).unwrap_or(0);
+            continue;
+        }
+        match migrate_file(pool, from, to, id, &uri, delete_source).await {
+            Ok(bytes) => {
+                summary.migrated += 1;
+                summary.bytes += bytes;
+            }
+            Err(e) => {
+                warn!(id = %id, uri = %uri, error = %e, "failed to migrate file");
+                summary.failed.push((id, format!("{e:#}")));
+            }
+        }
+    }
+
+    info!(
+        from = from.scheme(),
+        to = to.scheme(),
+        migrated = summary.migrated,
+        failed = summary.failed.len(),
+        "file storage migration finished"
+    );
+    Ok(summary)
+}
+
+async fn migrate_file(
+    pool: &PgPool,
+    from: &dyn FileStorage,
+    to: &dyn FileStorage,
+    id: Uuid,
+    uri: &str,
+    delete_source: bool,
+) -> Result<u64> {
+    let target = rewrite_uri(uri, to.scheme()).context("malformed storage URI")?;
+    let data = from.read(uri).await?;
+    to.write(&target, &data).await?;
+
+    sqlx::query("UPDATE file_managed SET uri = $1, changed = $2 WHERE id = $3")
+        .bind(&target)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(id)
+        .execute(pool)
+        .await
+        .context("failed to update file record")?;
+
+    if delete_source && let Err(e) = from.delete(uri).await {
+        warn!(id = %id, uri = %uri, error = %e, "failed to delete migrated source file");
+    }
+    Ok(data.len() as u64)
+}
@@ -1,14 +1,20 @@
 //! File and media management.
 //!
-//! Provides file upload, storage, and cleanup functionality.
+//! Provides file upload, storage, cleanup, migration between storage
+//! backends, and media library search.
 
+pub mod library;
+pub mod migrate;
 pub mod service;
 pub mod storage;
 
+pub use library::{MediaEntry, MediaQuery, MediaUsage};
+pub use migrate::{MigrationSummary, migrate_storage};
 pub use service::{
-    ALLOWED_MIME_TYPES, FileInfo, FileService, FileStatus, MAX_FILE_SIZE, UploadResult,
+    ALLOWED_MIME_TYPES, DirectUpload, FileInfo, FileService, FileStatus, MAX_DIRECT_UPLOAD_SIZE,
+    MAX_FILE_SIZE, UploadResult,
 };
-pub use storage::{FileStorage, LocalFileStorage};
+pub use storage::{FileStorage, LocalFileStorage, storage_for_backend};
 
 #[cfg(feature = "s3")]
 pub use storage::S3FileStorage;
@@ -3,6 +3,7 @@
 //! Handles file uploads, metadata storage, and cleanup.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
@@ -15,6 +16,15 @@ use super::storage::FileStorage;
 /// Maximum file size (10 MB).
 pub const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
 
+/// Maximum size of a direct upload to the storage backend (1 GiB).
+pub const MAX_DIRECT_UPLOAD_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// How long a presigned direct upload URL stays valid.
+pub const DIRECT_UPLOAD_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Bytes read from the start of a direct upload to check its type.
+const MAGIC_BYTES_LEN: usize = 8192;
+
 /// Allowed MIME types for upload.
 pub const ALLOWED_MIME_TYPES: &[&str] = &[
     // Images (SVG excluded: XML-based format enables stored XSS)
@@ -109,6 +119,21 @@ pub struct UploadResult {
     pub mime_type: String,
 }
 
+/// A presigned upload awaiting completion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectUpload {
+    pub id: Uuid,
+    pub filename: String,
+    pub mime_type: String,
+    /// Declared size; the stored object must match it.
+    pub size: u64,
+    pub uri: String,
+    /// URL the client `PUT`s the file to.
+    pub upload_url: String,
+    /// Unix timestamp when `upload_url` expires.
+    pub expires_at: i64,
+}
+
 /// File service for managing uploads.
 pub struct FileService {
     pool: PgPool,
@@ -121,10 +146,11 @@ impl FileService {
         Self { pool, storage }
     }
 
-    /// Upload a file (default tenant — no tenant prefix in URI).
+    /// Upload a file for the current site.
     ///
     /// Validates size and MIME type, stores the file, and creates a database record.
     /// File is created with temporary status until attached to content.
+    /// Files of the default tenant get no tenant prefix in their URI.
     pub async fn upload(
         &self,
         owner_id: Uuid,
@@ -132,7 +158,8 @@ impl FileService {
         mime_type: &str,
         data: &[u8],
     ) -> Result<UploadResult> {
-        self.upload_for_tenant(owner_id, filename, mime_type, data, None)
+        let tenant_id = crate::services::site::scoped_tenant_id();
+        self.upload_for_tenant(owner_id, filename, mime_type, data, tenant_id)
             .await
     }
 
@@ -168,6 +195,108 @@ impl FileService {
         // This prevents uploading executables disguised as images, etc.
         validate_magic_bytes(data, mime_type)?;
 
+        let uri = self.new_uri(filename, tenant_id).await?;
+
+        // Write to storage
+        self.storage
+            .write(&uri, data)
+            .await
+            .context("failed to write file to storage")?;
+
+        let tenant_id = tenant_id.unwrap_or(crate::models::tenant::DEFAULT_TENANT_ID);
+        self.insert_record(
+            owner_id,
+            filename,
+            &uri,
+            mime_type,
+            data.len() as u64,
+            tenant_id,
+        )
+        .await
+    }
+
+    /// Start a direct upload to the storage backend.
+    ///
+    /// Validates the declared type and size, picks a storage URI and
+    /// presigns a `PUT` to it. Returns `None` when the backend does not
+    /// support direct uploads. The client uploads the file itself, then
+    /// calls [`Self::complete_direct_upload`] to create the file record.
+    pub async fn presign_upload(
+        &self,
+        filename: &str,
+        mime_type: &str,
+        size: u64,
+        tenant_id: Option<Uuid>,
+    ) -> Result<Option<DirectUpload>> {
+        if size == 0 || size > MAX_DIRECT_UPLOAD_SIZE {
+            bail!("file size must be between 1 and {MAX_DIRECT_UPLOAD_SIZE} bytes");
+        }
+        if !ALLOWED_MIME_TYPES.contains(&mime_type) {
+            bail!("file type not allowed: {mime_type}");
+        }
+
+        let uri = self.new_uri(filename, tenant_id).await?;
+        let Some(upload_url) = self
+            .storage
+            .presigned_upload_url(&uri, mime_type, size, DIRECT_UPLOAD_TTL)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(DirectUpload {
+            id: Uuid::now_v7(),
+            filename: filename.to_string(),
+            mime_type: mime_type.to_string(),
+            size,
+            uri,
+            upload_url,
+            expires_at: chrono::Utc::now().timestamp() + DIRECT_UPLOAD_TTL.as_secs() as i64,
+        }))
+    }
+
+    /// Finish a direct upload once the client has stored the file.
+    ///
+    /// Checks that the stored object has the declared size and content
+    /// type, then creates a temporary file record. Rejected objects are
+    /// deleted.
+    pub async fn complete_direct_upload(
+        &self,
+        owner_id: Uuid,
+        upload: &DirectUpload,
+    ) -> Result<UploadResult> {
+        let stored = self.storage.size(&upload.uri).await?;
+        if stored != Some(upload.size) {
+            bail!(
+                "uploaded file not found or size mismatch: expected {} bytes",
+                upload.size
+            );
+        }
+
+        let head = self.storage.read_head(&upload.uri, MAGIC_BYTES_LEN).await?;
+        if let Err(e) = validate_magic_bytes(&head, &upload.mime_type) {
+            if let Err(del) = self.storage.delete(&upload.uri).await {
+                warn!(error = %del, uri = %upload.uri, "failed to delete rejected upload");
+            }
+            return Err(e);
+        }
+
+        self.insert_record(
+            owner_id,
+            &upload.filename,
+            &upload.uri,
+            &upload.mime_type,
+            upload.size,
+            crate::services::site::current_tenant_id(),
+        )
+        .await
+    }
+
+    /// Generate a storage URI for a new file.
+    ///
+    /// When `tenant_id` is `Some` and not the default tenant, the URI
+    /// includes a tenant prefix: `local://{tenant}/{YYYY}/{MM}/{uuid}_{name}`.
+    async fn new_uri(&self, filename: &str, tenant_id: Option<Uuid>) -> Result<String> {
         // Determine tenant prefix for URI. Default tenant uses no prefix
         // (backward compatible with existing files).
         let tenant_prefix = match tenant_id {
@@ -217,51 +346,59 @@ impl FileService {
             scheme => bail!("unsupported storage scheme: {scheme}"),
         };
 
-        // Write to storage
-        self.storage
-            .write(&uri, data)
-            .await
-            .context("failed to write file to storage")?;
+        Ok(uri)
+    }
 
-        // Create database record
+    /// Create a temporary file record for a stored file.
+    async fn insert_record(
+        &self,
+        owner_id: Uuid,
+        filename: &str,
+        uri: &str,
+        mime_type: &str,
+        size: u64,
+        tenant_id: Uuid,
+    ) -> Result<UploadResult> {
         let id = Uuid::now_v7();
         let now = chrono::Utc::now().timestamp();
+        let size = i64::try_from(size).context("file too large")?;
 
         sqlx::query(
             r#"
-            INSERT INTO file_managed (id, owner_id, filename, uri, filemime, filesize, status, created, changed)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO file_managed (id, owner_id, filename, uri, filemime, filesize, status, created, changed, tenant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
         )
         .bind(id)
         .bind(owner_id)
         .bind(filename)
-        .bind(&uri)
+        .bind(uri)
         .bind(mime_type)
-        .bind(data.len() as i64)
+        .bind(size)
         .bind(FileStatus::Temporary as i16)
         .bind(now)
         .bind(now)
+        .bind(tenant_id)
         .execute(&self.pool)
         .await
         .context("failed to create file record")?;
 
-        let url = self.storage.public_url(&uri);
+        let url = self.storage.public_url(uri);
 
         debug!(
             id = %id,
             filename = %filename,
             uri = %uri,
-            size = data.len(),
+            size = size,
             "file uploaded"
         );
 
         Ok(UploadResult {
             id,
             filename: filename.to_string(),
-            uri,
+            uri: uri.to_string(),
             url,
-            size: data.len() as i64,
+            size,
             mime_type: mime_type.to_string(),
         })
     }
@@ -1,16 +1,28 @@
 //! File storage backends.
 //!
 //! Provides trait and implementations for storing files locally or in S3.
+//! The backend is chosen with `FILE_STORAGE` (see [`storage_for_backend`]).
 
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
+use crate::config::Config;
 use crate::file::service::sanitize_filename;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use tracing::{debug, warn};
 
+/// Objects larger than this are uploaded to S3 in parts.
+#[cfg(feature = "s3")]
+const MULTIPART_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Part size for S3 multipart uploads (S3 requires at least 5 MiB).
+#[cfg(feature = "s3")]
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
 /// File storage backend trait.
 #[async_trait]
 pub trait FileStorage: Send + Sync {
@@ -26,6 +38,40 @@ pub trait FileStorage: Send + Sync {
     /// Check if a file exists.
     async fn exists(&self, uri: &str) -> Result<bool>;
 
+    /// Size of a stored file in bytes, or `None` if it does not exist.
+    async fn size(&self, uri: &str) -> Result<Option<u64>>;
+
+    /// Read up to `len` bytes from the start of a file.
+    async fn read_head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        let mut data = self.read(uri).await?;
+        data.truncate(len);
+        Ok(data)
+    }
+
+    /// Presigned URL for downloading a file straight from the backend.
+    ///
+    /// `None` for backends that serve files through the kernel.
+    async fn presigned_download_url(
+        &self,
+        _uri: &str,
+        _expires_in: Duration,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Presigned URL a client can `PUT` a file of exactly `size` bytes to.
+    ///
+    /// `None` for backends that only accept uploads through the kernel.
+    async fn presigned_upload_url(
+        &self,
+        _uri: &str,
+        _content_type: &str,
+        _size: u64,
+        _expires_in: Duration,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
     /// Get the public URL for a file.
     fn public_url(&self, uri: &str) -> String;
 
@@ -136,6 +182,15 @@ impl FileStorage for LocalFileStorage {
         Ok(path.exists())
     }
 
+    async fn size(&self, uri: &str) -> Result<Option<u64>> {
+        let path = self.parse_uri(uri)?;
+        match fs::metadata(&path).await {
+            Ok(meta) => Ok(Some(meta.len())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).context("failed to read file metadata"),
+        }
+    }
+
     fn public_url(&self, uri: &str) -> String {
         let path = uri.strip_prefix("local://").unwrap_or(uri);
         format!("{}/{}", self.base_url.trim_end_matches('/'), path)
@@ -164,6 +219,9 @@ pub struct S3FileStorage {
     prefix: Option<String>,
     /// Base URL for public access (e.g., CloudFront distribution).
     base_url: String,
+    /// Whether `base_url` is the kernel's files route, which redirects to
+    /// presigned download URLs, rather than the bucket itself.
+    presign_downloads: bool,
     /// Circuit breaker for S3 operations.
     circuit_breaker: crate::circuit_breaker::CircuitBreaker,
 }
@@ -187,6 +245,7 @@ impl S3FileStorage {
             bucket: bucket.into(),
             prefix,
             base_url: base_url.into(),
+            presign_downloads: false,
             circuit_breaker: crate::circuit_breaker::CircuitBreaker::new(
                 "s3_storage",
                 crate::circuit_breaker::BreakerConfig {
@@ -199,6 +258,8 @@ impl S3FileStorage {
     }
 
     /// Create with a custom endpoint (for S3-compatible services like MinIO).
+    ///
+    /// Uses path-style addressing, which such services expect.
     pub async fn with_endpoint(
         endpoint_url: &str,
         bucket: impl Into<String>,
@@ -210,13 +271,17 @@ impl S3FileStorage {
             .endpoint_url(endpoint_url)
             .load()
             .await;
-        let client = aws_sdk_s3::Client::new(&config);
+        let s3_config = aws_sdk_s3::config::Builder::from(&config)
+            .force_path_style(true)
+            .build();
+        let client = aws_sdk_s3::Client::from_conf(s3_config);
 
         Ok(Self {
             client,
             bucket: bucket.into(),
             prefix,
             base_url: base_url.into(),
+            presign_downloads: false,
             circuit_breaker: crate::circuit_breaker::CircuitBreaker::new(
                 "s3_storage",
                 crate::circuit_breaker::BreakerConfig {
@@ -228,11 +293,94 @@ impl S3FileStorage {
         })
     }
 
+    /// Link files through the kernel's files route instead of the bucket.
+    ///
+    /// For private buckets: `files_url` redirects each request to a
+    /// short-lived presigned download URL.
+    pub fn with_presigned_downloads(mut self, files_url: impl Into<String>) -> Self {
+        self.base_url = files_url.into();
+        self.presign_downloads = true;
+        self
+    }
+
     /// Get the circuit breaker for monitoring.
     pub fn circuit_breaker(&self) -> &crate::circuit_breaker::CircuitBreaker {
         &self.circuit_breaker
     }
 
+    /// Upload an object in parts, aborting the upload if any part fails so
+    /// the bucket does not keep the orphaned parts.
+    async fn write_multipart(&self, key: &str, data: &[u8]) -> Result<()> {
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .context("failed to start S3 multipart upload")?;
+        let upload_id = upload
+            .upload_id()
+            .context("S3 multipart upload has no ID")?
+            .to_string();
+
+        let result = self.upload_parts(key, &upload_id, data).await;
+        if result.is_err()
+            && let Err(e) = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(&upload_id)
+                .send()
+                .await
+        {
+            warn!(error = %e, key = %key, "failed to abort S3 multipart upload");
+        }
+        result
+    }
+
+    async fn upload_parts(&self, key: &str, upload_id: &str, data: &[u8]) -> Result<()> {
+        use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+
+        let mut parts = Vec::new();
+        for (i, chunk) in data.chunks(MULTIPART_PART_SIZE).enumerate() {
+            let part_number = i32::try_from(i + 1).context("too many multipart parts")?;
+            let part = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(aws_sdk_s3::primitives::ByteStream::from(chunk.to_vec()))
+                .send()
+                .await
+                .with_context(|| format!("failed to upload S3 part {part_number}"))?;
+            parts.push(
+                CompletedPart::builder()
+                    .set_e_tag(part.e_tag().map(str::to_string))
+                    .part_number(part_number)
+                    .build(),
+            );
+        }
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .context("failed to complete S3 multipart upload")?;
+        Ok(())
+    }
+
     /// Parse an s3:// URI to get the S3 key.
     fn parse_uri(&self, uri: &str) -> Result<String> {
         let path = uri
@@ -272,6 +420,9 @@ impl FileStorage for S3FileStorage {
 
         self.circuit_breaker
             .call(|| async {
+                if data_vec.len() > MULTIPART_THRESHOLD {
+                    return self.write_multipart(&key, &data_vec).await;
+                }
                 self.client
                     .put_object()
                     .bucket(&self.bucket)
@@ -365,8 +516,108 @@ impl FileStorage for S3FileStorage {
             .map_err(|e| e.into_anyhow("S3"))
     }
 
+    async fn size(&self, uri: &str) -> Result<Option<u64>> {
+        let key = self.parse_uri(uri)?;
+
+        self.circuit_breaker
+            .call(|| async {
+                match self
+                    .client
+                    .head_object()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .send()
+                    .await
+                {
+                    Ok(head) => Ok(head.content_length().and_then(|n| u64::try_from(n).ok())),
+                    Err(err) => {
+                        if err.as_service_error().is_some_and(|e| e.is_not_found()) {
+                            return Ok(None);
+                        }
+                        Err(err).context("failed to get S3 object size")
+                    }
+                }
+            })
+            .await
+            .map_err(|e| e.into_anyhow("S3"))
+    }
+
+    async fn read_head(&self, uri: &str, len: usize) -> Result<Vec<u8>> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let key = self.parse_uri(uri)?;
+
+        self.circuit_breaker
+            .call(|| async {
+                let response = self
+                    .client
+                    .get_object()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .range(format!("bytes=0-{}", len - 1))
+                    .send()
+                    .await
+                    .context("failed to get object range from S3")?;
+
+                response
+                    .body
+                    .collect()
+                    .await
+                    .context("failed to read S3 response body")
+                    .map(|b| b.into_bytes().to_vec())
+            })
+            .await
+            .map_err(|e| e.into_anyhow("S3"))
+    }
+
+    async fn presigned_download_url(
+        &self,
+        uri: &str,
+        expires_in: Duration,
+    ) -> Result<Option<String>> {
+        let key = self.parse_uri(uri)?;
+        let config = aws_sdk_s3::presigning::PresigningConfig::expires_in(expires_in)
+            .context("invalid presigned URL lifetime")?;
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .presigned(config)
+            .await
+            .context("failed to presign S3 download")?;
+        Ok(Some(request.uri().to_string()))
+    }
+
+    async fn presigned_upload_url(
+        &self,
+        uri: &str,
+        content_type: &str,
+        size: u64,
+        expires_in: Duration,
+    ) -> Result<Option<String>> {
+        let key = self.parse_uri(uri)?;
+        let config = aws_sdk_s3::presigning::PresigningConfig::expires_in(expires_in)
+            .context("invalid presigned URL lifetime")?;
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .content_type(content_type)
+            .content_length(i64::try_from(size).context("upload too large")?)
+            .presigned(config)
+            .await
+            .context("failed to presign S3 upload")?;
+        Ok(Some(request.uri().to_string()))
+    }
+
     fn public_url(&self, uri: &str) -> String {
         let path = uri.strip_prefix("s3://").unwrap_or(uri);
+        if self.presign_downloads {
+            return format!("{}/{}", self.base_url.trim_end_matches('/'), path);
+        }
         match &self.prefix {
             Some(prefix) => format!(
                 "{}/{}/{}",
@@ -390,10 +641,59 @@ impl std::fmt::Debug for S3FileStorage {
             .field("bucket", &self.bucket)
             .field("prefix", &self.prefix)
             .field("base_url", &self.base_url)
+            .field("presign_downloads", &self.presign_downloads)
             .finish()
     }
 }
 
+/// Create the storage backend named `backend` ("local" or "s3").
+///
+/// Most callers want the configured backend, `config.file_storage`; the
+/// file migration command also opens the other one.
+pub async fn storage_for_backend(config: &Config, backend: &str) -> Result<Arc<dyn FileStorage>> {
+    match backend {
+        "local" => Ok(Arc::new(LocalFileStorage::new(
+            &config.uploads_dir,
+            &config.files_url,
+        ))),
+        "s3" => s3_storage(config).await,
+        other => bail!("unknown file storage backend '{other}' (expected local or s3)"),
+    }
+}
+
+#[cfg(feature = "s3")]
+async fn s3_storage(config: &Config) -> Result<Arc<dyn FileStorage>> {
+    let bucket = config
+        .s3_bucket
+        .clone()
+        .context("S3_BUCKET is required for S3 file storage")?;
+    let prefix = config.s3_prefix.clone();
+    let base_url = config.s3_public_url.clone().unwrap_or_default();
+    let storage = match &config.s3_endpoint_url {
+        Some(endpoint) => S3FileStorage::with_endpoint(endpoint, bucket, prefix, base_url).await?,
+        None => S3FileStorage::new(bucket, prefix, base_url).await?,
+    };
+    let storage = if config.s3_public_url.is_some() {
+        storage
+    } else {
+        storage.with_presigned_downloads(&config.files_url)
+    };
+    Ok(Arc::new(storage))
+}
+
+#[cfg(not(feature = "s3"))]
+async fn s3_storage(_config: &Config) -> Result<Arc<dyn FileStorage>> {
+    bail!("S3 file storage requires building with the `s3` feature")
+}
+
+/// Rewrite a storage URI for another backend, keeping its path.
+///
+/// `local://2026/02/abc_photo.jpg` becomes `s3://2026/02/abc_photo.jpg`.
+pub fn rewrite_uri(uri: &str, scheme: &str) -> Option<String> {
+    let (_, path) = uri.split_once("://")?;
+    Some(format!("{scheme}://{path}"))
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -445,4 +745,34 @@ mod tests {
 
         assert_eq!(url, "https://example.com/files/2026/02/abc123_test.jpg");
     }
+
+    #[test]
+    fn rewrite_uri_keeps_path() {
+        assert_eq!(
+            rewrite_uri("local://acme/2026/02/abc_photo.jpg", "s3").as_deref(),
+            Some("s3://acme/2026/02/abc_photo.jpg")
+        );
+        assert_eq!(rewrite_uri("no-scheme.jpg", "s3"), None);
+    }
+
+    #[tokio::test]
+    async fn local_size_and_head() {
+        let dir = std::env::temp_dir().join(format!("trovato-storage-{}", uuid::Uuid::now_v7()));
+        let storage = LocalFileStorage::new(&dir, "/files");
+        let uri = "local://2026/02/abc_test.txt";
+
+        assert_eq!(storage.size(uri).await.unwrap(), None);
+        storage.write(uri, b"hello world").await.unwrap();
+        assert_eq!(storage.size(uri).await.unwrap(), Some(11));
+        assert_eq!(storage.read_head(uri, 5).await.unwrap(), b"hello");
+        assert_eq!(
+            storage
+                .presigned_download_url(uri, Duration::from_secs(60))
+                .await
+                .unwrap(),
+            None
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
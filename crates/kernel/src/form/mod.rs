@@ -15,5 +15,5 @@ mod types;
 
 pub use ajax::{AjaxCommand, AjaxRequest, AjaxResponse};
 pub use csrf::{generate_csrf_token, verify_csrf_token};
-pub use service::{FormResult, FormService, FormState, ValidationError};
+pub use service::{Decoration, FormAdditions, FormResult, FormService, FormState, ValidationError};
 pub use types::{ElementType, Form, FormElement};
@@ -1,6 +1,6 @@
 //! Form service for building, processing, and AJAX handling.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
@@ -51,63 +51,69 @@ impl FormService {
         let mut form = Form::new(form_id);
         form.token = token;
 
-        // Invoke tap_form_alter for plugins to modify the form
-        let form_json = serde_json::to_string(&form)?;
-        let results = self
+        self.alter(form, state).await
+    }
+
+    /// Run `tap_form_alter` on a form built by the kernel.
+    ///
+    /// Handlers run in weight order, each receiving the form as altered by
+    /// the previous one; unparseable responses are logged and ignored.
+    pub async fn alter(&self, form: Form, state: &RequestState) -> Result<Form> {
+        let form_id = form.form_id.clone();
+        let form = self
             .dispatcher
-            .dispatch("tap_form_alter", &form_json, state.clone())
+            .dispatch_alter("tap_form_alter", form, state.clone())
             .await;
-
-        // Apply alterations from plugins
-        for result in results {
-            if result.output.is_empty() || result.output == "{}" {
-                continue;
-            }
-
-            match serde_json::from_str::<Form>(&result.output) {
-                Ok(altered_form) => {
-                    debug!(
-                        plugin = %result.plugin_name,
-                        form_id = %form_id,
-                        "form altered by plugin"
-                    );
-                    form = altered_form;
-                }
-                Err(e) => {
-                    warn!(
-                        plugin = %result.plugin_name,
-                        error = %e,
-                        "failed to parse form alteration"
-                    );
-                }
-            }
-        }
-
+        debug!(form_id = %form_id, "form altered");
         Ok(form)
     }
 
-    /// Process a form submission.
-    pub async fn process(
+    /// Run `tap_form_alter` on a form laid out by its own template.
+    ///
+    /// `form` describes the template's fields. Plugins may set a `prefix` or
+    /// `suffix` on them and add elements of their own; the added elements
+    /// are rendered here, for the template to place after its fields. Other
+    /// changes to the template's fields are ignored.
+    ///
+    /// Added elements named in `values` (the stored or submitted values)
+    /// show that value rather than their default.
+    pub async fn additions(
         &self,
-        form_id: &str,
+        form: Form,
         values: &HashMap<String, Value>,
-        session: &Session,
         state: &RequestState,
-    ) -> Result<FormResult> {
-        // Verify CSRF token
-        let csrf_token = values.get("_token").and_then(|v| v.as_str()).unwrap_or("");
-
-        if !verify_csrf_token(session, csrf_token).await? {
-            return Ok(FormResult::ValidationFailed(vec![ValidationError {
-                field: None,
-                message: "Invalid or expired form token. Please try again.".to_string(),
-            }]));
+    ) -> Result<FormAdditions> {
+        let own: Vec<String> = form.elements.keys().cloned().collect();
+        let altered = self.alter(form, state).await?;
+        let (decorations, mut added) = split_additions(&own, altered);
+        if added.elements.is_empty() {
+            return Ok(FormAdditions {
+                decorations,
+                elements_html: String::new(),
+            });
         }
 
-        // Run built-in validation
-        let mut errors = self.validate_form(form_id, values, state).await?;
+        fill_values(&mut added.elements, values);
+        let elements_html = self
+            .theme
+            .render_form_elements_html(&added)
+            .context("failed to render plugin form elements")?;
+        Ok(FormAdditions {
+            decorations,
+            elements_html,
+        })
+    }
 
-        // Run tap_form_validate for plugin validation
+    /// Run `tap_form_validate` on submitted values.
+    ///
+    /// Returns the errors plugins reported, which may name an element or
+    /// apply to the whole form.
+    pub async fn validate(
+        &self,
+        form_id: &str,
+        values: &HashMap<String, Value>,
+        state: &RequestState,
+    ) -> Result<Vec<ValidationError>> {
         let validate_input = serde_json::json!({
             "form_id": form_id,
             "values": values,
@@ -122,30 +128,38 @@ impl FormService {
             )
             .await;
 
+        let mut errors = Vec::new();
         for result in results {
             if result.output.is_empty() || result.output == "{}" {
                 continue;
             }
 
-            if let Ok(tap_errors) = serde_json::from_str::<TapValidationResult>(&result.output) {
-                for error in tap_errors.errors {
-                    errors.push(ValidationError {
-                        field: None,
-                        message: error,
-                    });
-                }
+            match serde_json::from_str::<TapValidationResult>(&result.output) {
+                Ok(tap_errors) => errors.extend(tap_errors.errors.into_iter().map(Into::into)),
+                Err(e) => warn!(
+                    plugin = %result.plugin_name,
+                    error = %e,
+                    "failed to parse form validation result"
+                ),
             }
         }
+        Ok(errors)
+    }
 
-        // If there are errors, return them
-        if !errors.is_empty() {
-            return Ok(FormResult::ValidationFailed(errors));
-        }
-
-        // Call tap_form_submit for side effects
+    /// Run `tap_form_submit` for an accepted submission.
+    ///
+    /// `item_id` is the saved item on item forms.
+    pub async fn submit(
+        &self,
+        form_id: &str,
+        values: &HashMap<String, Value>,
+        item_id: Option<uuid::Uuid>,
+        state: &RequestState,
+    ) -> Result<()> {
         let submit_input = serde_json::json!({
             "form_id": form_id,
             "values": values,
+            "item_id": item_id,
         });
 
         self.dispatcher
@@ -155,6 +169,40 @@ impl FormService {
                 state.clone(),
             )
             .await;
+        Ok(())
+    }
+
+    /// Process a form submission.
+    pub async fn process(
+        &self,
+        form_id: &str,
+        values: &HashMap<String, Value>,
+        session: &Session,
+        state: &RequestState,
+    ) -> Result<FormResult> {
+        // Verify CSRF token
+        let csrf_token = values.get("_token").and_then(|v| v.as_str()).unwrap_or("");
+
+        if !verify_csrf_token(session, csrf_token).await? {
+            return Ok(FormResult::ValidationFailed(vec![ValidationError {
+                field: None,
+                message: "Invalid or expired form token. Please try again.".to_string(),
+            }]));
+        }
+
+        // Run built-in validation
+        let mut errors = self.validate_form(form_id, values, state).await?;
+
+        // Run tap_form_validate for plugin validation
+        errors.extend(self.validate(form_id, values, state).await?);
+
+        // If there are errors, return them
+        if !errors.is_empty() {
+            return Ok(FormResult::ValidationFailed(errors));
+        }
+
+        // Call tap_form_submit for side effects
+        self.submit(form_id, values, None, state).await?;
 
         // Default to success redirect
         Ok(FormResult::Success)
@@ -551,11 +599,83 @@ impl ValidationError {
     }
 }
 
+/// What plugins added to a form laid out by its own template.
+#[derive(Debug, Default, Serialize)]
+pub struct FormAdditions {
+    /// `prefix` and `suffix` markup set on the template's fields, by name.
+    pub decorations: BTreeMap<String, Decoration>,
+    /// Rendered elements added by plugins.
+    pub elements_html: String,
+}
+
+/// Markup a plugin placed around a field.
+#[derive(Debug, Default, Serialize)]
+pub struct Decoration {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+}
+
+/// Split an altered form into the decorations of the fields named in
+/// `own` and a form holding the elements plugins added.
+fn split_additions(own: &[String], mut altered: Form) -> (BTreeMap<String, Decoration>, Form) {
+    let mut decorations = BTreeMap::new();
+    for name in own {
+        if let Some(element) = altered.elements.remove(name)
+            && (element.prefix.is_some() || element.suffix.is_some())
+        {
+            decorations.insert(
+                name.clone(),
+                Decoration {
+                    prefix: element.prefix,
+                    suffix: element.suffix,
+                },
+            );
+        }
+    }
+    (decorations, altered)
+}
+
+/// Set the default value of elements, and their children, named in
+/// `values`.
+fn fill_values(elements: &mut BTreeMap<String, FormElement>, values: &HashMap<String, Value>) {
+    for (name, element) in elements {
+        if let Some(value) = values.get(name) {
+            element.default_value = Some(value.clone());
+        }
+        fill_values(&mut element.children, values);
+    }
+}
+
 /// Response from tap_form_validate.
 #[derive(Debug, Deserialize)]
 struct TapValidationResult {
     #[serde(default)]
-    errors: Vec<String>,
+    errors: Vec<TapValidationError>,
+}
+
+/// An error from tap_form_validate: a plain message, or a message naming
+/// the element it is about.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TapValidationError {
+    Message(String),
+    Error {
+        #[serde(default)]
+        field: Option<String>,
+        message: String,
+    },
+}
+
+impl From<TapValidationError> for ValidationError {
+    fn from(error: TapValidationError) -> Self {
+        match error {
+            TapValidationError::Message(message) => Self {
+                field: None,
+                message,
+            },
+            TapValidationError::Error { field, message } => Self { field, message },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -581,6 +701,61 @@ mod tests {
         assert!(form_error.field.is_none());
     }
 
+    #[test]
+    fn split_additions_separates_plugin_elements() {
+        let own = vec!["title".to_string(), "body".to_string()];
+        let mut title = FormElement::textfield();
+        title.suffix = Some("<button>AI</button>".to_string());
+        let altered = Form::new("item_add_page")
+            .element("title", title)
+            .element("body", FormElement::textarea(5))
+            .element("field_publish_on", FormElement::textfield());
+
+        let (decorations, added) = split_additions(&own, altered);
+        assert_eq!(decorations.len(), 1);
+        assert_eq!(
+            decorations["title"].suffix.as_deref(),
+            Some("<button>AI</button>")
+        );
+        assert!(decorations["title"].prefix.is_none());
+        assert_eq!(added.form_id, "item_add_page");
+        assert_eq!(
+            added.elements.keys().collect::<Vec<_>>(),
+            ["field_publish_on"]
+        );
+    }
+
+    #[test]
+    fn fill_values_reaches_children() {
+        let mut elements = BTreeMap::from([(
+            "_schedule".to_string(),
+            FormElement::fieldset().child(
+                "field_publish_on",
+                FormElement::textfield().default_value(""),
+            ),
+        )]);
+        let values = HashMap::from([("field_publish_on".to_string(), Value::from(100))]);
+        fill_values(&mut elements, &values);
+        assert!(elements["_schedule"].default_value.is_none());
+        assert_eq!(
+            elements["_schedule"].children["field_publish_on"].default_value,
+            Some(Value::from(100))
+        );
+    }
+
+    #[test]
+    fn tap_validation_errors_may_name_a_field() {
+        let result: TapValidationResult = serde_json::from_str(
+            r#"{"errors": ["Spam detected.", {"field": "title", "message": "Too short."}]}"#,
+        )
+        .unwrap();
+        let errors: Vec<ValidationError> = result.errors.into_iter().map(Into::into).collect();
+        assert!(errors[0].field.is_none());
+        assert_eq!(errors[0].message, "Spam detected.");
+        assert_eq!(errors[1].field.as_deref(), Some("title"));
+        assert_eq!(errors[1].message, "Too short.");
+    }
+
     #[test]
     fn test_form_state_serialization() {
         let mut state = FormState::new("test", "build-1");
@@ -0,0 +1,293 @@
+//! Date-based archive support for gather listings.
+//!
+//! A gather query whose display config carries an [`ArchiveConfig`] gets
+//! `/{base}/{year}` and `/{base}/{year}/{month}` routes that restrict the
+//! listing to items created in that period, plus year/month facet counts
+//! for rendering an archive navigation block. Archive periods are also
+//! emitted in `sitemap.xml` unless disabled per query.
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Earliest year accepted in archive URLs.
+///
+/// Guards against absurd values (e.g. `/blog/0001`) producing empty pages
+/// that crawlers would otherwise index.
+pub const MIN_ARCHIVE_YEAR: i32 = 1970;
+
+/// Latest year accepted in archive URLs.
+pub const MAX_ARCHIVE_YEAR: i32 = 9999;
+
+/// Archive configuration attached to a gather query display.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArchiveConfig {
+    /// Path prefix for archive routes, e.g. `/blog` → `/blog/2024/05`.
+    pub base_path: String,
+
+    /// Whether archive period URLs are listed in `sitemap.xml`.
+    #[serde(default = "default_true")]
+    pub sitemap: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A year or year/month archive period.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArchivePeriod {
+    /// Four-digit year.
+    pub year: i32,
+    /// Month (1–12), or `None` for a whole-year archive.
+    pub month: Option<u32>,
+}
+
+impl ArchivePeriod {
+    /// Parse path segments into a period, rejecting out-of-range values.
+    ///
+    /// The month must be two digits (`05`, not `5`) so each period has a
+    /// single canonical URL.
+    pub fn parse(year: &str, month: Option<&str>) -> Option<Self> {
+        if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let year: i32 = year.parse().ok()?;
+        if !(MIN_ARCHIVE_YEAR..=MAX_ARCHIVE_YEAR).contains(&year) {
+            return None;
+        }
+        let month = match month {
+            Some(m) => {
+                if m.len() != 2 || !m.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let m: u32 = m.parse().ok()?;
+                if !(1..=12).contains(&m) {
+                    return None;
+                }
+                Some(m)
+            }
+            None => None,
+        };
+        Some(Self { year, month })
+    }
+
+    /// Unix timestamp range `[start, end)` covered by this period (UTC).
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        let (start, end) = match self.month {
+            Some(m) => {
+                let start = NaiveDate::from_ymd_opt(self.year, m, 1)?;
+                let end = if m == 12 {
+                    NaiveDate::from_ymd_opt(self.year + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(self.year, m + 1, 1)?
+                };
+                (start, end)
+            }
+            None => (
+                NaiveDate::from_ymd_opt(self.year, 1, 1)?,
+                NaiveDate::from_ymd_opt(self.year + 1, 1, 1)?,
+            ),
+        };
+        let start = Utc.from_utc_datetime(&start.and_hms_opt(0, 0, 0)?);
+        let end = Utc.from_utc_datetime(&end.and_hms_opt(0, 0, 0)?);
+        Some((start.timestamp(), end.timestamp()))
+    }
+
+    /// Canonical archive URL for this period under `base_path`.
+    pub fn path(&self, base_path: &str) -> String {
+        let base = base_path.trim_end_matches('/');
+        match self.month {
+            Some(m) => format!("{base}/{:04}/{m:02}", self.year),
+            None => format!("{base}/{:04}", self.year),
+        }
+    }
+
+    /// Human-readable label (`2024` or `May 2024`).
+    pub fn label(&self) -> String {
+        match self
+            .month
+            .and_then(|m| NaiveDate::from_ymd_opt(self.year, m, 1))
+        {
+            Some(d) => d.format("%B %Y").to_string(),
+            None => self.year.to_string(),
+        }
+    }
+}
+
+/// Published item count for one month of an archive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, sqlx::FromRow)]
+pub struct ArchiveBucket {
+    /// Year of the bucket.
+    pub year: i32,
+    /// Month of the bucket (1–12).
+    pub month: i32,
+    /// Number of published items created in this month.
+    pub count: i64,
+    /// Most recent `changed` timestamp among those items.
+    pub last_changed: i64,
+}
+
+/// Year-level rollup of archive buckets, newest first.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ArchiveYear {
+    /// Year.
+    pub year: i32,
+    /// Total items in the year.
+    pub count: i64,
+    /// Archive URL for the year.
+    pub path: String,
+    /// Per-month entries, newest first.
+    pub months: Vec<ArchiveMonth>,
+}
+
+/// A month entry within an [`ArchiveYear`].
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ArchiveMonth {
+    /// Month (1–12).
+    pub month: i32,
+    /// Human-readable label (`May 2024`).
+    pub label: String,
+    /// Items in the month.
+    pub count: i64,
+    /// Archive URL for the month.
+    pub path: String,
+}
+
+/// Group monthly buckets into years for template rendering.
+///
+/// Expects `buckets` ordered newest first (as returned by
+/// `GatherService::archive_counts`).
+pub fn group_by_year(buckets: &[ArchiveBucket], base_path: &str) -> Vec<ArchiveYear> {
+    let mut years: Vec<ArchiveYear> = Vec::new();
+    for bucket in buckets {
+        let month_period = ArchivePeriod {
+            year: bucket.year,
+            month: u32::try_from(bucket.month).ok(),
+        };
+        let entry = ArchiveMonth {
+            month: bucket.month,
+            label: month_period.label(),
+            count: bucket.count,
+            path: month_period.path(base_path),
+        };
+        match years.last_mut() {
+            Some(y) if y.year == bucket.year => {
+                y.count += bucket.count;
+                y.months.push(entry);
+            }
+            _ => years.push(ArchiveYear {
+                year: bucket.year,
+                count: bucket.count,
+                path: ArchivePeriod {
+                    year: bucket.year,
+                    month: None,
+                }
+                .path(base_path),
+                months: vec![entry],
+            }),
+        }
+    }
+    years
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_canonical_segments() {
+        assert_eq!(
+            ArchivePeriod::parse("2024", Some("05")),
+            Some(ArchivePeriod {
+                year: 2024,
+                month: Some(5)
+            })
+        );
+        assert_eq!(
+            ArchivePeriod::parse("2024", None),
+            Some(ArchivePeriod {
+                year: 2024,
+                month: None
+            })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_segments() {
+        assert!(ArchivePeriod::parse("24", None).is_none());
+        assert!(ArchivePeriod::parse("1900", None).is_none());
+        assert!(ArchivePeriod::parse("2024", Some("5")).is_none());
+        assert!(ArchivePeriod::parse("2024", Some("13")).is_none());
+        assert!(ArchivePeriod::parse("2024", Some("00")).is_none());
+        assert!(ArchivePeriod::parse("20x4", None).is_none());
+    }
+
+    #[test]
+    fn bounds_cover_month_and_year() {
+        let may = ArchivePeriod::parse("2024", Some("05")).unwrap();
+        let (start, end) = may.bounds().unwrap();
+        assert_eq!(start, 1_714_521_600); // 2024-05-01T00:00:00Z
+        assert_eq!(end, 1_717_200_000); // 2024-06-01T00:00:00Z
+
+        let dec = ArchivePeriod::parse("2024", Some("12")).unwrap();
+        let (_, end) = dec.bounds().unwrap();
+        assert_eq!(end, 1_735_689_600); // 2025-01-01T00:00:00Z
+
+        let year = ArchivePeriod::parse("2024", None).unwrap();
+        let (start, end) = year.bounds().unwrap();
+        assert_eq!(start, 1_704_067_200); // 2024-01-01T00:00:00Z
+        assert_eq!(end, 1_735_689_600);
+    }
+
+    #[test]
+    fn path_and_label() {
+        let may = ArchivePeriod::parse("2024", Some("05")).unwrap();
+        assert_eq!(may.path("/blog"), "/blog/2024/05");
+        assert_eq!(may.path("/blog/"), "/blog/2024/05");
+        assert_eq!(may.label(), "May 2024");
+        let year = ArchivePeriod::parse("2024", None).unwrap();
+        assert_eq!(year.path("/blog"), "/blog/2024");
+        assert_eq!(year.label(), "2024");
+    }
+
+    #[test]
+    fn group_by_year_rolls_up_counts() {
+        let buckets = vec![
+            ArchiveBucket {
+                year: 2025,
+                month: 2,
+                count: 3,
+                last_changed: 0,
+            },
+            ArchiveBucket {
+                year: 2025,
+                month: 1,
+                count: 2,
+                last_changed: 0,
+            },
+            ArchiveBucket {
+                year: 2024,
+                month: 12,
+                count: 1,
+                last_changed: 0,
+            },
+        ];
+        let years = group_by_year(&buckets, "/blog");
+        assert_eq!(years.len(), 2);
+        assert_eq!(years[0].year, 2025);
+        assert_eq!(years[0].count, 5);
+        assert_eq!(years[0].path, "/blog/2025");
+        assert_eq!(years[0].months.len(), 2);
+        assert_eq!(years[0].months[0].path, "/blog/2025/02");
+        assert_eq!(years[1].count, 1);
+    }
+
+    #[test]
+    fn config_sitemap_defaults_to_true() {
+        let config: ArchiveConfig =
+            serde_json::from_value(serde_json::json!({"base_path": "/blog"})).unwrap();
+        assert!(config.sitemap);
+    }
+}
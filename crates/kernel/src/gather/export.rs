@@ -0,0 +1,678 @@
+//! Export renderers for gather results.
+//!
+//! Besides HTML and JSON, a gather query whose display config carries an
+//! [`ExportConfig`] can be fetched as CSV (`/gather/{id}.csv`) or as an
+//! RSS 2.0 or Atom feed (`.rss`, `.atom`). Columns and feed elements are
+//! mapped from item field paths (`title`, `fields.summary`, ...) per query,
+//! so listings become downloads and feeds without custom routes.
+//!
+//! Each format is a [`ResultRenderer`] that emits a document in chunks, so
+//! CSV exports can be streamed page by page.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::gather_service::extract_field_value;
+use super::types::GatherQuery;
+use crate::routes::helpers::html_escape;
+use crate::services::contact::csv_field;
+
+/// Export formats for gather results, selected by URL extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Rss,
+    Atom,
+}
+
+impl ExportFormat {
+    /// Every format, in URL extension order.
+    pub const ALL: [Self; 3] = [Self::Csv, Self::Rss, Self::Atom];
+
+    /// Parse a URL extension (`csv`, `rss`, `atom`).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "csv" => Some(Self::Csv),
+            "rss" => Some(Self::Rss),
+            "atom" => Some(Self::Atom),
+            _ => None,
+        }
+    }
+
+    /// URL extension of this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Rss => "rss",
+            Self::Atom => "atom",
+        }
+    }
+}
+
+/// Export configuration attached to a gather query display.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportConfig {
+    /// CSV download of all results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv: Option<CsvExport>,
+
+    /// RSS and Atom feeds of the first page of results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed: Option<FeedExport>,
+}
+
+impl ExportConfig {
+    /// Formats this config enables.
+    pub fn formats(&self) -> Vec<ExportFormat> {
+        ExportFormat::ALL
+            .into_iter()
+            .filter(|format| match format {
+                ExportFormat::Csv => self.csv.is_some(),
+                ExportFormat::Rss | ExportFormat::Atom => self.feed.is_some(),
+            })
+            .collect()
+    }
+}
+
+/// CSV export settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsvExport {
+    /// Columns, in order.
+    pub columns: Vec<CsvColumn>,
+}
+
+/// A CSV column.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CsvColumn {
+    /// Field path of the value, e.g. `title` or `fields.venue`.
+    pub path: String,
+
+    /// Header label; defaults to the path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Feed export settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeedExport {
+    /// Feed title; defaults to the query label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Feed description; defaults to the query description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Mapping of feed entry elements to item fields.
+    #[serde(default)]
+    pub mapping: FeedMapping,
+}
+
+/// Item field paths of feed entry elements.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeedMapping {
+    /// Entry title.
+    #[serde(default = "default_title")]
+    pub title: String,
+
+    /// Entry link, a template where `{path}` is replaced by a field value,
+    /// e.g. `/item/{id}` or `{fields.url}`. Relative links get the site URL.
+    #[serde(default = "default_link")]
+    pub link: String,
+
+    /// Entry summary (plain text).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Entry author name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Publication date (Unix timestamp or RFC 3339).
+    #[serde(default = "default_published")]
+    pub published: String,
+
+    /// Last update date (Unix timestamp or RFC 3339).
+    #[serde(default = "default_updated")]
+    pub updated: String,
+}
+
+fn default_title() -> String {
+    "title".to_string()
+}
+
+fn default_link() -> String {
+    "/item/{id}".to_string()
+}
+
+fn default_published() -> String {
+    "created".to_string()
+}
+
+fn default_updated() -> String {
+    "changed".to_string()
+}
+
+impl Default for FeedMapping {
+    fn default() -> Self {
+        Self {
+            title: default_title(),
+            link: default_link(),
+            summary: None,
+            author: None,
+            published: default_published(),
+            updated: default_updated(),
+        }
+    }
+}
+
+/// Renders gather result items into an export document.
+///
+/// A document is [`begin`](Self::begin), one [`item`](Self::item) per
+/// result row, then [`end`](Self::end).
+pub trait ResultRenderer: Send + Sync {
+    /// `Content-Type` of the document.
+    fn content_type(&self) -> &'static str;
+
+    /// Whether the document covers every page of results rather than only
+    /// the requested one.
+    fn all_pages(&self) -> bool {
+        false
+    }
+
+    /// Document start.
+    fn begin(&self) -> String;
+
+    /// One result item.
+    fn item(&self, item: &Value) -> String;
+
+    /// Document end.
+    fn end(&self) -> String;
+}
+
+/// Where an export is served, for absolute links in feeds.
+#[derive(Debug, Clone)]
+pub struct ExportTarget<'a> {
+    /// Public site URL without trailing slash.
+    pub site_url: &'a str,
+    /// Path of the HTML listing, e.g. `/stories`.
+    pub base_path: &'a str,
+    /// Path of the export itself, e.g. `/stories.rss`.
+    pub export_path: &'a str,
+    /// Unix timestamp used as the feed's update time.
+    pub now: i64,
+}
+
+/// The renderer for a query's export in `format`, or `None` if the query
+/// does not enable that format.
+pub fn renderer_for(
+    query: &GatherQuery,
+    format: ExportFormat,
+    target: &ExportTarget<'_>,
+) -> Option<Box<dyn ResultRenderer>> {
+    let exports = query.display.exports.as_ref()?;
+    match format {
+        ExportFormat::Csv => Some(Box::new(CsvRenderer {
+            columns: exports.csv.as_ref()?.columns.clone(),
+        })),
+        ExportFormat::Rss | ExportFormat::Atom => {
+            let feed = exports.feed.as_ref()?;
+            let channel = FeedChannel {
+                title: feed.title.clone().unwrap_or_else(|| query.label.clone()),
+                description: feed
+                    .description
+                    .clone()
+                    .or_else(|| query.description.clone())
+                    .unwrap_or_default(),
+                link: absolute_url(target.site_url, target.base_path),
+                self_link: absolute_url(target.site_url, target.export_path),
+                site_url: target.site_url.to_string(),
+                updated: target.now,
+                mapping: feed.mapping.clone(),
+            };
+            Some(if format == ExportFormat::Rss {
+                Box::new(RssRenderer(channel))
+            } else {
+                Box::new(AtomRenderer(channel))
+            })
+        }
+    }
+}
+
+/// CSV (RFC 4180) with a header row.
+struct CsvRenderer {
+    columns: Vec<CsvColumn>,
+}
+
+impl ResultRenderer for CsvRenderer {
+    fn content_type(&self) -> &'static str {
+        "text/csv; charset=utf-8"
+    }
+
+    fn all_pages(&self) -> bool {
+        true
+    }
+
+    fn begin(&self) -> String {
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| csv_field(c.label.as_deref().unwrap_or(&c.path)))
+            .collect();
+        format!("{}\r\n", header.join(","))
+    }
+
+    fn item(&self, item: &Value) -> String {
+        let row: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| csv_field(&field_text(item, &c.path).unwrap_or_default()))
+            .collect();
+        format!("{}\r\n", row.join(","))
+    }
+
+    fn end(&self) -> String {
+        String::new()
+    }
+}
+
+/// Channel-level data shared by the feed renderers.
+struct FeedChannel {
+    title: String,
+    description: String,
+    link: String,
+    self_link: String,
+    site_url: String,
+    updated: i64,
+    mapping: FeedMapping,
+}
+
+/// Entry data extracted from a result item.
+struct FeedEntry {
+    title: String,
+    link: String,
+    summary: Option<String>,
+    author: Option<String>,
+    published: Option<i64>,
+    updated: Option<i64>,
+}
+
+impl FeedChannel {
+    fn entry(&self, item: &Value) -> FeedEntry {
+        let mapping = &self.mapping;
+        FeedEntry {
+            title: field_text(item, &mapping.title).unwrap_or_default(),
+            link: absolute_url(&self.site_url, &expand_template(&mapping.link, item)),
+            summary: mapping.summary.as_ref().and_then(|p| field_text(item, p)),
+            author: mapping.author.as_ref().and_then(|p| field_text(item, p)),
+            published: timestamp(item, &mapping.published),
+            updated: timestamp(item, &mapping.updated),
+        }
+    }
+}
+
+/// RSS 2.0.
+struct RssRenderer(FeedChannel);
+
+impl ResultRenderer for RssRenderer {
+    fn content_type(&self) -> &'static str {
+        "application/rss+xml; charset=utf-8"
+    }
+
+    fn begin(&self) -> String {
+        let channel = &self.0;
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n  <channel>\n");
+        xml.push_str(&format!(
+            "    <title>{}</title>\n    <link>{}</link>\n    <description>{}</description>\n",
+            html_escape(&channel.title),
+            html_escape(&channel.link),
+            html_escape(&channel.description),
+        ));
+        xml.push_str(&format!(
+            "    <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+            html_escape(&channel.self_link)
+        ));
+        xml.push_str(&format!(
+            "    <lastBuildDate>{}</lastBuildDate>\n",
+            rfc2822(channel.updated)
+        ));
+        xml
+    }
+
+    fn item(&self, item: &Value) -> String {
+        let entry = self.0.entry(item);
+        let mut xml = String::from("    <item>\n");
+        xml.push_str(&format!(
+            "      <title>{}</title>\n      <link>{}</link>\n      <guid isPermaLink=\"true\">{}</guid>\n",
+            html_escape(&entry.title),
+            html_escape(&entry.link),
+            html_escape(&entry.link),
+        ));
+        if let Some(published) = entry.published {
+            xml.push_str(&format!(
+                "      <pubDate>{}</pubDate>\n",
+                rfc2822(published)
+            ));
+        }
+        if let Some(author) = &entry.author {
+            xml.push_str(&format!(
+                "      <dc:creator>{}</dc:creator>\n",
+                html_escape(author)
+            ));
+        }
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!(
+                "      <description>{}</description>\n",
+                html_escape(summary)
+            ));
+        }
+        xml.push_str("    </item>\n");
+        xml
+    }
+
+    fn end(&self) -> String {
+        "  </channel>\n</rss>\n".to_string()
+    }
+}
+
+/// Atom (RFC 4287).
+struct AtomRenderer(FeedChannel);
+
+impl ResultRenderer for AtomRenderer {
+    fn content_type(&self) -> &'static str {
+        "application/atom+xml; charset=utf-8"
+    }
+
+    fn begin(&self) -> String {
+        let channel = &self.0;
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!(
+            "  <title>{}</title>\n  <id>{}</id>\n  <updated>{}</updated>\n",
+            html_escape(&channel.title),
+            html_escape(&channel.self_link),
+            rfc3339(channel.updated),
+        ));
+        xml.push_str(&format!(
+            "  <link href=\"{}\"/>\n  <link href=\"{}\" rel=\"self\" type=\"application/atom+xml\"/>\n",
+            html_escape(&channel.link),
+            html_escape(&channel.self_link),
+        ));
+        if !channel.description.is_empty() {
+            xml.push_str(&format!(
+                "  <subtitle>{}</subtitle>\n",
+                html_escape(&channel.description)
+            ));
+        }
+        xml
+    }
+
+    fn item(&self, item: &Value) -> String {
+        let entry = self.0.entry(item);
+        // Atom requires an update time; fall back to publication, then the feed's.
+        let updated = entry.updated.or(entry.published).unwrap_or(self.0.updated);
+        let mut xml = String::from("  <entry>\n");
+        xml.push_str(&format!(
+            "    <title>{}</title>\n    <link href=\"{}\"/>\n    <id>{}</id>\n    <updated>{}</updated>\n",
+            html_escape(&entry.title),
+            html_escape(&entry.link),
+            html_escape(&entry.link),
+            rfc3339(updated),
+        ));
+        if let Some(published) = entry.published {
+            xml.push_str(&format!(
+                "    <published>{}</published>\n",
+                rfc3339(published)
+            ));
+        }
+        if let Some(author) = &entry.author {
+            xml.push_str(&format!(
+                "    <author><name>{}</name></author>\n",
+                html_escape(author)
+            ));
+        }
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!(
+                "    <summary>{}</summary>\n",
+                html_escape(summary)
+            ));
+        }
+        xml.push_str("  </entry>\n");
+        xml
+    }
+
+    fn end(&self) -> String {
+        "</feed>\n".to_string()
+    }
+}
+
+/// Text of a field path; text fields stored as `{"value": ...}` give
+/// their value.
+fn field_text(item: &Value, path: &str) -> Option<String> {
+    if path.starts_with("fields.")
+        && let Some(value) = extract_field_value(item, &format!("{path}.value"))
+    {
+        return Some(value);
+    }
+    extract_field_value(item, path)
+}
+
+/// Replace `{path}` placeholders with field values (empty when missing).
+fn expand_template(template: &str, item: &Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let path = &rest[start + 1..start + len];
+        out.push_str(&field_text(item, path).unwrap_or_default());
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Prefix site-relative links with the site URL.
+fn absolute_url(site_url: &str, link: &str) -> String {
+    if link.starts_with('/') {
+        format!("{site_url}{link}")
+    } else {
+        link.to_string()
+    }
+}
+
+/// A date field as a Unix timestamp; accepts timestamps and RFC 3339.
+fn timestamp(item: &Value, path: &str) -> Option<i64> {
+    let text = field_text(item, path)?;
+    text.parse::<i64>().ok().or_else(|| {
+        chrono::DateTime::parse_from_rfc3339(&text)
+            .ok()
+            .map(|dt| dt.timestamp())
+    })
+}
+
+/// Format a Unix timestamp as an RFC 2822 date for RSS.
+fn rfc2822(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.to_rfc2822())
+        .unwrap_or_default()
+}
+
+/// Format a Unix timestamp as an RFC 3339 date for Atom.
+fn rfc3339(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(exports: ExportConfig) -> GatherQuery {
+        let mut query = GatherQuery {
+            query_id: "stories".to_string(),
+            label: "Stories".to_string(),
+            description: Some("Latest stories".to_string()),
+            ..GatherQuery::default()
+        };
+        query.display.exports = Some(exports);
+        query
+    }
+
+    fn target() -> ExportTarget<'static> {
+        ExportTarget {
+            site_url: "https://example.com",
+            base_path: "/stories",
+            export_path: "/stories.rss",
+            now: 1_700_000_000,
+        }
+    }
+
+    fn render(renderer: &dyn ResultRenderer, items: &[Value]) -> String {
+        let mut doc = renderer.begin();
+        for item in items {
+            doc.push_str(&renderer.item(item));
+        }
+        doc.push_str(&renderer.end());
+        doc
+    }
+
+    fn story() -> Value {
+        json!({
+            "id": "0190a8f0-0000-7000-8000-000000000001",
+            "title": "Rust & <friends>",
+            "created": 1_700_000_000,
+            "changed": 1_700_000_600,
+            "fields": {
+                "summary": {"value": "Short, \"quoted\"", "format": "plain_text"},
+                "byline": "Ada",
+            },
+        })
+    }
+
+    #[test]
+    fn formats_only_when_configured() {
+        let feed_only = query(ExportConfig {
+            csv: None,
+            feed: Some(FeedExport::default()),
+        });
+        assert!(renderer_for(&feed_only, ExportFormat::Csv, &target()).is_none());
+        assert!(renderer_for(&feed_only, ExportFormat::Atom, &target()).is_some());
+        assert_eq!(
+            feed_only.display.exports.as_ref().unwrap().formats(),
+            [ExportFormat::Rss, ExportFormat::Atom]
+        );
+
+        let mut plain = feed_only.clone();
+        plain.display.exports = None;
+        assert!(renderer_for(&plain, ExportFormat::Rss, &target()).is_none());
+
+        assert_eq!(ExportFormat::from_extension("rss"), Some(ExportFormat::Rss));
+        assert_eq!(ExportFormat::from_extension("html"), None);
+        for format in ExportFormat::ALL {
+            assert_eq!(
+                ExportFormat::from_extension(format.extension()),
+                Some(format)
+            );
+        }
+    }
+
+    #[test]
+    fn csv_maps_columns_and_quotes() {
+        let query = query(ExportConfig {
+            csv: Some(CsvExport {
+                columns: vec![
+                    CsvColumn {
+                        path: "title".to_string(),
+                        label: Some("Title".to_string()),
+                    },
+                    CsvColumn {
+                        path: "fields.summary".to_string(),
+                        label: None,
+                    },
+                    CsvColumn {
+                        path: "fields.missing".to_string(),
+                        label: None,
+                    },
+                ],
+            }),
+            feed: None,
+        });
+        let renderer = renderer_for(&query, ExportFormat::Csv, &target()).unwrap();
+        assert!(renderer.all_pages());
+        assert_eq!(
+            render(renderer.as_ref(), &[story()]),
+            "Title,fields.summary,fields.missing\r\nRust & <friends>,\"Short, \"\"quoted\"\"\",\r\n"
+        );
+    }
+
+    #[test]
+    fn rss_escapes_and_links_items() {
+        let query = query(ExportConfig {
+            csv: None,
+            feed: Some(FeedExport {
+                mapping: FeedMapping {
+                    summary: Some("fields.summary".to_string()),
+                    author: Some("fields.byline".to_string()),
+                    ..FeedMapping::default()
+                },
+                ..FeedExport::default()
+            }),
+        });
+        let renderer = renderer_for(&query, ExportFormat::Rss, &target()).unwrap();
+        assert!(!renderer.all_pages());
+        let xml = render(renderer.as_ref(), &[story()]);
+
+        assert!(xml.contains("<title>Stories</title>"));
+        assert!(xml.contains("<description>Latest stories</description>"));
+        assert!(xml.contains("<link>https://example.com/stories</link>"));
+        assert!(xml.contains("<title>Rust &amp; &lt;friends&gt;</title>"));
+        assert!(xml.contains(
+            "<link>https://example.com/item/0190a8f0-0000-7000-8000-000000000001</link>"
+        ));
+        assert!(xml.contains("<dc:creator>Ada</dc:creator>"));
+        assert!(xml.contains("<description>Short, &quot;quoted&quot;</description>"));
+        assert!(xml.contains("<pubDate>Tue, 14 Nov 2023 22:13:20 +0000</pubDate>"));
+        assert!(xml.ends_with("</rss>\n"));
+    }
+
+    #[test]
+    fn atom_uses_rfc3339_and_link_templates() {
+        let query = query(ExportConfig {
+            csv: None,
+            feed: Some(FeedExport {
+                title: Some("Argus".to_string()),
+                mapping: FeedMapping {
+                    link: "{fields.byline}/{missing}".to_string(),
+                    updated: "fields.none".to_string(),
+                    ..FeedMapping::default()
+                },
+                ..FeedExport::default()
+            }),
+        });
+        let renderer = renderer_for(&query, ExportFormat::Atom, &target()).unwrap();
+        let xml = render(renderer.as_ref(), &[story()]);
+
+        assert!(xml.contains("<title>Argus</title>"));
+        assert!(xml.contains("<link href=\"Ada/\"/>"));
+        // No update field: falls back to the publication date.
+        assert!(xml.contains("<updated>2023-11-14T22:13:20+00:00</updated>"));
+        assert!(xml.contains("<published>2023-11-14T22:13:20+00:00</published>"));
+        assert!(xml.ends_with("</feed>\n"));
+    }
+
+    #[test]
+    fn timestamps_accept_rfc3339() {
+        let item = json!({"fields": {"date": "2024-05-01T00:00:00Z"}});
+        assert_eq!(timestamp(&item, "fields.date"), Some(1_714_521_600));
+        assert_eq!(timestamp(&json!({"created": 42}), "created"), Some(42));
+        assert_eq!(timestamp(&item, "fields.nope"), None);
+    }
+}
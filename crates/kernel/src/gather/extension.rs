@@ -133,7 +133,8 @@ pub struct GatherExtensionDeclaration {
 pub struct FilterExtension {
     /// Extension name used in `FilterOperator::Custom(name)`.
     pub name: String,
-    /// Built-in handler name (e.g. "hierarchical_in", "jsonb_array_contains").
+    /// Built-in handler name (e.g. "hierarchical_in", "jsonb_array_contains",
+    /// "vector_similar").
     pub handler: String,
     /// Handler-specific configuration.
     #[serde(default)]
@@ -241,6 +242,10 @@ impl GatherExtensionRegistry {
             "jsonb_array_contains",
             Box::new(super::handlers::JsonbArrayContainsFilterHandler),
         );
+        registry.register_filter_handler(
+            "vector_similar",
+            Box::new(super::handlers::VectorSimilarFilterHandler),
+        );
 
         registry
     }
@@ -456,6 +461,7 @@ mod tests {
                 .filter_handlers
                 .contains_key("jsonb_array_contains")
         );
+        assert!(registry.filter_handlers.contains_key("vector_similar"));
     }
 
     #[test]
@@ -6,6 +6,7 @@
 //! - Exposed filter handling
 //! - Result caching
 
+use super::archive::ArchiveBucket;
 use super::category_service::CategoryService;
 use super::extension::GatherExtensionRegistry;
 use super::query_builder::GatherQueryBuilder;
@@ -13,6 +14,10 @@ use super::types::{
     ContextualValue, FilterOperator, FilterValue, GatherQuery, GatherResult, QueryContext,
     QueryDefinition, QueryDisplay, QueryFilter,
 };
+use crate::cache::{ITEM_LISTING_TAG, page};
+use crate::db::DbPools;
+use crate::metrics::{CacheRegion, CacheStage, CacheTier, Metrics};
+use crate::profiling::{self, Phase};
 use anyhow::{Context, Result};
 use moka::sync::Cache;
 use sqlx::PgPool;
@@ -41,30 +46,47 @@ const DISTINCT_VALUES_TTL: Duration = Duration::from_secs(300);
 /// Maximum entries in the distinct-values cache.
 const DISTINCT_VALUES_CAPACITY: u64 = 500;
 
+/// TTL for the archive facet-count cache (5 minutes).
+const ARCHIVE_COUNTS_TTL: Duration = Duration::from_secs(300);
+
+/// Maximum entries in the archive facet-count cache.
+const ARCHIVE_COUNTS_CAPACITY: u64 = 200;
+
 /// Service for executing Gather queries.
 pub struct GatherService {
     pool: PgPool,
+    /// Pools for query execution; definitions and caches use `pool`.
+    read_pools: DbPools,
     categories: Arc<CategoryService>,
     extensions: Arc<GatherExtensionRegistry>,
     /// Registered queries by query_id (TTL-bounded).
     queries: Cache<String, GatherQuery>,
     /// Cache of distinct field values per `"item_type::source_field"`.
     distinct_values_cache: Cache<String, Vec<String>>,
+    /// Cache of archive month buckets per `"item_type::stage_ids"`.
+    archive_counts_cache: Cache<String, Vec<ArchiveBucket>>,
     /// Maximum per_page for query execution (from `GATHER_MAX_PAGE_SIZE`).
     max_page_size: u32,
+    /// Records distinct-value and archive count cache hits and misses.
+    metrics: Arc<Metrics>,
 }
 
 impl GatherService {
     /// Create a new GatherService.
+    ///
+    /// Query execution runs on `read_pools`, which may spread it over read
+    /// replicas.
     pub fn new(
-        pool: PgPool,
+        read_pools: DbPools,
         categories: Arc<CategoryService>,
         extensions: Arc<GatherExtensionRegistry>,
         ttl: Duration,
         max_page_size: u32,
+        metrics: Arc<Metrics>,
     ) -> Arc<Self> {
         Arc::new(Self {
-            pool,
+            pool: read_pools.primary().clone(),
+            read_pools,
             categories,
             extensions,
             queries: Cache::builder()
@@ -75,10 +97,25 @@ impl GatherService {
                 .max_capacity(DISTINCT_VALUES_CAPACITY)
                 .time_to_live(DISTINCT_VALUES_TTL)
                 .build(),
+            archive_counts_cache: Cache::builder()
+                .max_capacity(ARCHIVE_COUNTS_CAPACITY)
+                .time_to_live(ARCHIVE_COUNTS_TTL)
+                .build(),
             max_page_size,
+            metrics,
         })
     }
 
+    /// Record a distinct-value or archive count cache lookup.
+    fn record_lookup(&self, stage: CacheStage, hit: bool) {
+        if hit {
+            self.metrics
+                .record_cache_hit(CacheRegion::Gather, stage, CacheTier::L1);
+        } else {
+            self.metrics.record_cache_miss(CacheRegion::Gather, stage);
+        }
+    }
+
     /// Register a query definition.
     pub async fn register_query(&self, query: GatherQuery) -> Result<()> {
         let query_id = query.query_id.clone();
@@ -131,13 +168,14 @@ impl GatherService {
     /// Maximum number of distinct values returned by [`Self::fetch_distinct_values`].
     const DISTINCT_VALUES_LIMIT: i64 = 200;
 
-    /// Fetch distinct non-empty values for a field within an item type.
+    /// Fetch distinct non-empty values for a field within the current
+    /// site's items of a type.
     ///
     /// Only JSONB fields (path prefix `"fields."`) are supported. Returns an
     /// empty list for unrecognised or unsafe field names.
     ///
     /// Results are capped at `DISTINCT_VALUES_LIMIT` and cached for
-    /// `DISTINCT_VALUES_TTL` (5 min) per `(item_type, source_field)` pair.
+    /// `DISTINCT_VALUES_TTL` (5 min) per tenant, item type and field.
     ///
     /// **Stage note:** This query filters by `status = 1` but does not filter
     /// by `stage_id`. Widget options therefore reflect published-status items
@@ -157,11 +195,15 @@ impl GatherService {
             return Ok(Vec::new());
         }
 
-        let cache_key = format!("{item_type}::{source_field}");
+        let tenant_id = crate::services::site::current_tenant_id();
+        let cache_key = format!("{tenant_id}::{item_type}::{source_field}");
 
+        // Distinct values span all stages (see above); count them as live.
         if let Some(cached) = self.distinct_values_cache.get(&cache_key) {
+            self.record_lookup(CacheStage::Live, true);
             return Ok(cached);
         }
+        self.record_lookup(CacheStage::Live, false);
 
         // Only JSONB paths (e.g. "fields.field_country") are supported.
         let Some(jsonb_key) = source_field.strip_prefix("fields.") else {
@@ -178,6 +220,7 @@ impl GatherService {
              FROM item \
              WHERE type = $2 \
                AND status = 1 \
+               AND tenant_id = $4 \
                AND fields->>$1 IS NOT NULL \
                AND fields->>$1 <> '' \
              ORDER BY 1 \
@@ -186,6 +229,7 @@ impl GatherService {
         .bind(jsonb_key)
         .bind(item_type)
         .bind(Self::DISTINCT_VALUES_LIMIT)
+        .bind(tenant_id)
         .fetch_all(&self.pool)
         .await
         .context("failed to fetch distinct field values")?;
@@ -195,6 +239,56 @@ impl GatherService {
         Ok(values)
     }
 
+    /// Count published items per creation month for an archive listing.
+    ///
+    /// Returns buckets newest first. Only the current site's items in
+    /// `stage_ids` are counted, matching the stage overlay and tenant scope
+    /// used when the listing itself executes.
+    /// Results are cached for `ARCHIVE_COUNTS_TTL` (5 min), so counts may
+    /// briefly lag behind newly published items.
+    pub async fn archive_counts(
+        &self,
+        item_type: &str,
+        stage_ids: &[Uuid],
+    ) -> Result<Vec<ArchiveBucket>> {
+        let mut sorted_stages = stage_ids.to_vec();
+        sorted_stages.sort();
+        let stage_key: Vec<String> = sorted_stages.iter().map(Uuid::to_string).collect();
+        let tenant_id = crate::services::site::current_tenant_id();
+        let cache_key = format!("{tenant_id}::{item_type}::{}", stage_key.join(","));
+
+        let stage = CacheStage::of_all(stage_ids);
+        if let Some(cached) = self.archive_counts_cache.get(&cache_key) {
+            self.record_lookup(stage, true);
+            return Ok(cached);
+        }
+        self.record_lookup(stage, false);
+
+        let buckets: Vec<ArchiveBucket> = sqlx::query_as(
+            "SELECT EXTRACT(YEAR FROM to_timestamp(created) AT TIME ZONE 'UTC')::int AS year, \
+                    EXTRACT(MONTH FROM to_timestamp(created) AT TIME ZONE 'UTC')::int AS month, \
+                    COUNT(*) AS count, \
+                    MAX(changed) AS last_changed \
+             FROM item \
+             WHERE type = $1 \
+               AND status = 1 \
+               AND stage_id = ANY($2) \
+               AND tenant_id = $3 \
+             GROUP BY 1, 2 \
+             ORDER BY 1 DESC, 2 DESC",
+        )
+        .bind(item_type)
+        .bind(&sorted_stages)
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to count archive buckets")?;
+
+        self.archive_counts_cache.insert(cache_key, buckets.clone());
+
+        Ok(buckets)
+    }
+
     /// Build scope conditions for faceted-option queries.
     ///
     /// Walks `all_exposed` and, for each filter whose field differs from
@@ -527,10 +621,60 @@ impl GatherService {
         stage_ids: &[Uuid],
         context: &QueryContext,
     ) -> Result<GatherResult> {
-        let query = self
+        self.execute_registered(query_id, page, None, exposed_filters, stage_ids, context)
+            .await
+    }
+
+    /// Execute a registered query with a caller-chosen page size.
+    ///
+    /// `per_page` replaces the query's `items_per_page`; callers resolve it
+    /// through the pagination policy first. The service maximum still applies.
+    pub async fn execute_paged(
+        &self,
+        query_id: &str,
+        page: u32,
+        per_page: u32,
+        exposed_filters: HashMap<String, FilterValue>,
+        stage_id: Uuid,
+        context: &QueryContext,
+    ) -> Result<GatherResult> {
+        self.execute_registered(
+            query_id,
+            page,
+            Some(per_page),
+            exposed_filters,
+            &[stage_id],
+            context,
+        )
+        .await
+    }
+
+    async fn execute_registered(
+        &self,
+        query_id: &str,
+        page: u32,
+        per_page: Option<u32>,
+        exposed_filters: HashMap<String, FilterValue>,
+        stage_ids: &[Uuid],
+        context: &QueryContext,
+    ) -> Result<GatherResult> {
+        let mut query = self
             .queries
             .get(query_id)
             .ok_or_else(|| anyhow::anyhow!("query not found: {query_id}"))?;
+        page::add_tag(page::gather_tag(query_id));
+
+        // Archive periods restrict the top-level listing only; includes run
+        // through execute_definition_with_stages and are unaffected.
+        if let Some(period) = context.archive {
+            let (start, end) = period
+                .bounds()
+                .ok_or_else(|| anyhow::anyhow!("invalid archive period"))?;
+            query.definition.filters.extend(archive_filters(start, end));
+        }
+        if let Some(per_page) = per_page {
+            query.display.items_per_page = per_page;
+        }
 
         self.execute_definition_with_stages(
             &query.definition,
@@ -580,6 +724,9 @@ impl GatherService {
             anyhow::bail!("Query validation failed: {}", validation_errors.join("; "));
         }
 
+        // Results change whenever any item is saved.
+        page::add_tag(ITEM_LISTING_TAG);
+
         // Cap items_per_page to the configured maximum (GATHER_MAX_PAGE_SIZE).
         let max_page = self.max_page_size;
         let resolved_display = if display.items_per_page > max_page {
@@ -623,17 +770,23 @@ impl GatherService {
 
         // Build and execute queries (per_page already clamped in resolved_display above)
         let per_page = display.items_per_page;
-        let builder = GatherQueryBuilder::new_with_stages(builder_def, stage_ids.to_vec())
+        let mut builder = GatherQueryBuilder::new_with_stages(builder_def, stage_ids.to_vec())
             .with_extensions(self.extensions.clone())
-            .with_language(context.language.clone());
+            .with_language(context.language.clone())
+            .with_grants_user(context.grants_user)
+            .with_own_unpublished(
+                context.current_user_id,
+                context.own_unpublished_types.clone(),
+            );
+        // Multisite: only list the current site's items.
+        if crate::services::site::in_scope() {
+            builder = builder.with_tenant(crate::services::site::current_tenant_id());
+        }
 
         // Execute count and main queries with a statement timeout for safety.
         // Use a transaction so SET LOCAL applies correctly and resets on commit/rollback.
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .context("failed to begin transaction")?;
+        let db_timer = profiling::Timer::start(Phase::Db);
+        let mut tx = self.read_pools.begin_read().await?;
 
         // Set statement timeout (10 seconds) within this transaction
         sqlx::query("SET LOCAL statement_timeout = '10s'")
@@ -658,6 +811,7 @@ impl GatherService {
         tx.commit()
             .await
             .context("failed to commit query transaction")?;
+        drop(db_timer);
 
         // Execute includes (batched sub-queries)
         if !includes.is_empty() {
@@ -1459,6 +1613,28 @@ fn json_value_to_string(v: &serde_json::Value) -> Option<String> {
     }
 }
 
+/// Filters restricting `item.created` to the half-open range `[start, end)`.
+fn archive_filters(start: i64, end: i64) -> [QueryFilter; 2] {
+    [
+        QueryFilter {
+            field: "created".to_string(),
+            operator: FilterOperator::GreaterOrEqual,
+            value: FilterValue::Integer(start),
+            exposed: false,
+            exposed_label: None,
+            widget: Default::default(),
+        },
+        QueryFilter {
+            field: "created".to_string(),
+            operator: FilterOperator::LessThan,
+            value: FilterValue::Integer(end),
+            exposed: false,
+            exposed_label: None,
+            widget: Default::default(),
+        },
+    ]
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -1609,6 +1785,9 @@ mod tests {
             current_user_id: Some(user_id),
             url_args: HashMap::new(),
             language: None,
+            archive: None,
+            grants_user: None,
+            own_unpublished_types: Vec::new(),
         };
 
         let def = QueryDefinition {
@@ -1689,6 +1868,9 @@ mod tests {
             current_user_id: None,
             url_args,
             language: None,
+            archive: None,
+            grants_user: None,
+            own_unpublished_types: Vec::new(),
         };
 
         let def = QueryDefinition {
@@ -1814,6 +1996,17 @@ mod tests {
         assert_eq!(MAX_ITEMS_PER_PAGE, 100);
     }
 
+    #[test]
+    fn archive_filters_bound_created_half_open() {
+        let [lower, upper] = archive_filters(100, 200);
+        assert_eq!(lower.field, "created");
+        assert_eq!(lower.operator, FilterOperator::GreaterOrEqual);
+        assert_eq!(lower.value.as_i64(), Some(100));
+        assert_eq!(upper.operator, FilterOperator::LessThan);
+        assert_eq!(upper.value.as_i64(), Some(200));
+        assert!(!lower.exposed && !upper.exposed);
+    }
+
     #[test]
     fn is_valid_field_name_basic() {
         assert!(super::is_valid_field_name("status"));
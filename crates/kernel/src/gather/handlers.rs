@@ -236,6 +236,93 @@ impl FilterHandler for JsonbArrayContainsFilterHandler {
     }
 }
 
+// ---------------------------------------------------------------------------
+// VectorSimilarFilterHandler
+// ---------------------------------------------------------------------------
+
+/// Default number of similar items when `limit` is not configured.
+const DEFAULT_SIMILAR_LIMIT: u32 = 10;
+
+/// Filter handler that restricts results to the items whose embeddings are
+/// nearest to a source item's embedding (see [`crate::search::vector`]).
+///
+/// The filter value is the source item ID, typically a URL argument. The
+/// resolve phase looks up the nearest items; `build_condition` then matches
+/// `base_table.id` against them. If the source has no embedding or pgvector
+/// is unavailable, nothing matches.
+///
+/// Config keys:
+/// - `namespace`: Embedding namespace, i.e. the plugin that stored the vectors
+/// - `limit`: Maximum number of similar items (default 10)
+pub struct VectorSimilarFilterHandler;
+
+impl FilterHandler for VectorSimilarFilterHandler {
+    fn build_condition(
+        &self,
+        filter: &QueryFilter,
+        _config: &serde_json::Value,
+        ctx: &FilterContext,
+    ) -> Result<Option<SimpleExpr>> {
+        // resolve() always produces a list; anything else means it did not run.
+        let FilterValue::List(_) = &filter.value else {
+            return Ok(Some(Expr::cust("FALSE")));
+        };
+        let uuids = filter.value.as_uuid_list();
+        if uuids.is_empty() {
+            return Ok(Some(Expr::cust("FALSE")));
+        }
+
+        // Defense-in-depth: validate base_table before interpolation
+        if !is_safe_identifier(&ctx.base_table) {
+            bail!(
+                "unsafe base_table name: '{}'",
+                &ctx.base_table[..ctx.base_table.len().min(64)]
+            );
+        }
+
+        let uuid_list: Vec<String> = uuids.iter().map(|u| format!("'{u}'")).collect();
+        let expr = format!("{}.id IN ({})", ctx.base_table, uuid_list.join(", "));
+
+        Ok(Some(Expr::cust(expr)))
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        filter: QueryFilter,
+        config: &'a serde_json::Value,
+        pool: &'a PgPool,
+    ) -> Pin<Box<dyn Future<Output = Result<QueryFilter>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(namespace) = config.get("namespace").and_then(|v| v.as_str()) else {
+                bail!("vector_similar filter requires a 'namespace' config key");
+            };
+            let limit = config
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map_or(DEFAULT_SIMILAR_LIMIT, |l| {
+                    u32::try_from(l).unwrap_or(DEFAULT_SIMILAR_LIMIT)
+                });
+
+            let similar = match filter.value.as_uuid() {
+                Some(source) => {
+                    match crate::search::vector::similar_items(pool, namespace, source, limit).await
+                    {
+                        Ok(ids) => ids,
+                        Err(crate::search::vector::EmbeddingError::Unavailable) => Vec::new(),
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                None => Vec::new(),
+            };
+
+            Ok(QueryFilter {
+                value: FilterValue::List(similar.into_iter().map(FilterValue::Uuid).collect()),
+                ..filter
+            })
+        })
+    }
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -359,6 +446,45 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn vector_similar_unresolved_matches_nothing() {
+        let handler = VectorSimilarFilterHandler;
+        let filter = make_filter("id", FilterValue::String(Uuid::nil().to_string()));
+        let config = serde_json::json!({"namespace": "argus"});
+
+        let expr = handler
+            .build_condition(&filter, &config, &make_context())
+            .unwrap();
+        assert!(format!("{:?}", expr.unwrap()).contains("FALSE"));
+    }
+
+    #[test]
+    fn vector_similar_empty_list_matches_nothing() {
+        let handler = VectorSimilarFilterHandler;
+        let filter = make_filter("id", FilterValue::List(vec![]));
+        let config = serde_json::json!({"namespace": "argus"});
+
+        let expr = handler
+            .build_condition(&filter, &config, &make_context())
+            .unwrap();
+        assert!(format!("{:?}", expr.unwrap()).contains("FALSE"));
+    }
+
+    #[test]
+    fn vector_similar_builds_id_in() {
+        let handler = VectorSimilarFilterHandler;
+        let id = Uuid::from_u128(7);
+        let filter = make_filter("id", FilterValue::List(vec![FilterValue::Uuid(id)]));
+        let config = serde_json::json!({"namespace": "argus"});
+
+        let expr = handler
+            .build_condition(&filter, &config, &make_context())
+            .unwrap();
+        let expr = format!("{:?}", expr.unwrap());
+        assert!(expr.contains("item.id IN"));
+        assert!(expr.contains(&id.to_string()));
+    }
+
     #[test]
     fn jsonb_array_contains_build_condition() {
         let handler = JsonbArrayContainsFilterHandler;
@@ -5,18 +5,29 @@
 //! - GatherService: Executes declarative gather queries
 //! - GatherQueryBuilder: SeaQuery-based SQL generation
 //! - GatherExtensionRegistry: Plugin-provided filter/relationship/sort extensions
+//! - Archives: date-based year/month listings and facet counts
+//! - Exports: CSV, RSS and Atom renderers for query results
 //! - Types: QueryDefinition, QueryDisplay, FilterOperator, etc.
 
+pub mod archive;
 mod category_service;
+pub mod export;
 pub mod extension;
 mod gather_service;
 mod handlers;
 mod query_builder;
 pub mod types;
 
+#[allow(unused_imports)]
+pub use archive::{ArchiveBucket, ArchiveConfig, ArchivePeriod};
 #[allow(unused_imports)]
 pub use category_service::CategoryService;
 #[allow(unused_imports)]
+pub use export::{
+    CsvColumn, CsvExport, ExportConfig, ExportFormat, ExportTarget, FeedExport, FeedMapping,
+    ResultRenderer, renderer_for,
+};
+#[allow(unused_imports)]
 pub use extension::{
     FilterContext, FilterExtension, FilterHandler, GatherExtensionDeclaration,
     GatherExtensionRegistry, JoinSpec, RelationshipContext, RelationshipExtension,
@@ -25,7 +36,9 @@ pub use extension::{
 #[allow(unused_imports)]
 pub use gather_service::{GatherService, MAX_ITEMS_PER_PAGE};
 #[allow(unused_imports)]
-pub use handlers::{HierarchicalInFilterHandler, JsonbArrayContainsFilterHandler};
+pub use handlers::{
+    HierarchicalInFilterHandler, JsonbArrayContainsFilterHandler, VectorSimilarFilterHandler,
+};
 #[allow(unused_imports)]
 pub use query_builder::{CategoryHierarchyQuery, GatherQueryBuilder};
 #[allow(unused_imports)]
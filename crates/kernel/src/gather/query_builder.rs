@@ -39,6 +39,11 @@ pub struct GatherQueryBuilder {
     /// When set, adds `WHERE tenant_id = $id` to all queries.
     /// `None` means no tenant filtering (backward compatible).
     tenant_id: Option<Uuid>,
+    /// User whose item grants restrict the results, if any.
+    grants_user: Option<Uuid>,
+    /// Author and item types whose unpublished items pass a published-only
+    /// `status` filter.
+    own_unpublished: Option<(Uuid, Vec<String>)>,
 }
 
 impl GatherQueryBuilder {
@@ -50,6 +55,8 @@ impl GatherQueryBuilder {
             extensions: None,
             language: None,
             tenant_id: None,
+            grants_user: None,
+            own_unpublished: None,
         }
     }
 
@@ -64,6 +71,8 @@ impl GatherQueryBuilder {
             extensions: None,
             language: None,
             tenant_id: None,
+            grants_user: None,
+            own_unpublished: None,
         }
     }
 
@@ -73,6 +82,30 @@ impl GatherQueryBuilder {
         self
     }
 
+    /// Restrict item results to those the user's grants allow viewing.
+    ///
+    /// `None` leaves results unrestricted.
+    pub fn with_grants_user(mut self, user_id: Option<Uuid>) -> Self {
+        self.grants_user = user_id;
+        self
+    }
+
+    /// Also list `author_id`'s unpublished items of `item_types`.
+    ///
+    /// A non-exposed `status = 1` filter on the item table becomes
+    /// `status = 1 OR (author_id = $author AND type IN (...))`. Without an
+    /// author or item types, results are unchanged.
+    pub fn with_own_unpublished(
+        mut self,
+        author_id: Option<Uuid>,
+        item_types: Vec<String>,
+    ) -> Self {
+        self.own_unpublished = author_id
+            .filter(|_| !item_types.is_empty())
+            .map(|id| (id, item_types));
+        self
+    }
+
     /// Set the extension registry for custom filter/sort/relationship handling.
     pub fn with_extensions(mut self, extensions: Arc<GatherExtensionRegistry>) -> Self {
         self.extensions = Some(extensions);
@@ -111,6 +144,9 @@ impl GatherQueryBuilder {
         // Filter by stage (only for stage-aware tables like `item`)
         self.add_stage_filter(&mut query);
 
+        // Filter by item grants
+        self.add_grant_filter(&mut query);
+
         // Filter by tenant (multi-tenancy — injected automatically)
         if let Some(tid) = self.tenant_id {
             query.and_where(
@@ -172,6 +208,9 @@ impl GatherQueryBuilder {
         // Stage filter (only for stage-aware tables)
         self.add_stage_filter(&mut query);
 
+        // Item grant filter
+        self.add_grant_filter(&mut query);
+
         // Tenant filter (multi-tenancy)
         if let Some(tid) = self.tenant_id {
             query.and_where(
@@ -213,6 +252,23 @@ impl GatherQueryBuilder {
         }
     }
 
+    /// Restrict items to those the grants user may view.
+    ///
+    /// `item_access_granted` passes items without grants, so only items
+    /// restricted by `tap_item_grants` are affected.
+    fn add_grant_filter(&self, query: &mut SelectStatement) {
+        let Some(user_id) = self.grants_user else {
+            return;
+        };
+        if !self.is_item_table() {
+            return;
+        }
+        query.and_where(Expr::cust_with_values(
+            r#"item_access_granted("item"."id", $1, 'view')"#,
+            [user_id],
+        ));
+    }
+
     /// Returns `true` when the base table is `"item"` — the only table
     /// that has a corresponding `item_translation` table.
     fn is_item_table(&self) -> bool {
@@ -343,11 +399,37 @@ impl GatherQueryBuilder {
     fn add_filters(&self, query: &mut SelectStatement) {
         for filter in &self.definition.filters {
             if let Some(condition) = self.build_filter_condition(filter) {
+                let condition = match self.own_unpublished_condition(filter) {
+                    Some(own) => condition.or(own),
+                    None => condition,
+                };
                 query.and_where(condition);
             }
         }
     }
 
+    /// The own-unpublished alternative to a published-only status filter.
+    ///
+    /// Exposed filters are left alone: a visitor asking for published
+    /// items gets only published items.
+    fn own_unpublished_condition(&self, filter: &QueryFilter) -> Option<SimpleExpr> {
+        let (author_id, item_types) = self.own_unpublished.as_ref()?;
+        if !self.is_item_table()
+            || filter.exposed
+            || filter.field != "status"
+            || filter.operator != FilterOperator::Equals
+            || filter.value.as_i64() != Some(1)
+        {
+            return None;
+        }
+        let table = Alias::new(&self.definition.base_table);
+        Some(
+            Expr::col((table.clone(), Alias::new("author_id")))
+                .eq(*author_id)
+                .and(Expr::col((table, Alias::new("type"))).is_in(item_types.clone())),
+        )
+    }
+
     /// Build a single filter condition.
     fn build_filter_condition(&self, filter: &QueryFilter) -> Option<SimpleExpr> {
         let field_expr = self.field_expr(&filter.field);
@@ -729,9 +811,15 @@ impl GatherQueryBuilder {
                 NullsOrder::Last => sea_query::NullOrdering::Last,
             });
 
-            if sort.field.starts_with("fields.") {
-                let jsonb_path = &sort.field[7..];
-                let expr = self.jsonb_extract_expr(&self.definition.base_table, jsonb_path);
+            let expr = if let Some(jsonb_path) = sort.field.strip_prefix("fields.") {
+                Some(self.jsonb_extract_expr(&self.definition.base_table, jsonb_path))
+            } else {
+                sort.field
+                    .strip_prefix("flag_count.")
+                    .map(|flag| self.flag_count_expr(flag))
+            };
+
+            if let Some(expr) = expr {
                 if let Some(nulls) = null_order {
                     query.order_by_expr_with_nulls(expr, order, nulls);
                 } else {
@@ -758,6 +846,29 @@ impl GatherQueryBuilder {
         }
     }
 
+    /// Sort expression for `flag_count.{flag}`: how many times each item
+    /// was flagged. Kernel flags sort by their item column.
+    fn flag_count_expr(&self, flag: &str) -> SimpleExpr {
+        let table = &self.definition.base_table;
+        if !is_safe_identifier(table) || !crate::services::flag::is_valid_flag_name(flag) {
+            tracing::error!(
+                flag = &flag[..flag.len().min(64)],
+                "unsafe flag count sort; returning NULL"
+            );
+            return Expr::cust("NULL");
+        }
+        if crate::services::flag::is_kernel_flag(flag) {
+            return Expr::cust(format!("{table}.{flag}"));
+        }
+        Expr::cust_with_values(
+            format!(
+                "(SELECT COUNT(*) FROM flagging \
+                 WHERE flagging.item_id = {table}.id AND flagging.flag_name = $1)"
+            ),
+            [flag],
+        )
+    }
+
     /// Extract a list of strings from a FilterValue.
     fn extract_string_list(&self, value: &FilterValue) -> Vec<String> {
         match value {
@@ -1428,6 +1539,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sort_by_flag_count() {
+        let sorted_by = |field: &str| {
+            let def = QueryDefinition {
+                base_table: "item".to_string(),
+                sorts: vec![QuerySort {
+                    field: field.to_string(),
+                    direction: SortDirection::Desc,
+                    nulls: None,
+                }],
+                ..Default::default()
+            };
+            GatherQueryBuilder::new(def, LIVE_STAGE_ID).build(1, 10)
+        };
+
+        let sql = sorted_by("flag_count.bookmark");
+        assert!(
+            sql.contains("flagging.item_id = item.id AND flagging.flag_name = 'bookmark')"),
+            "{sql}"
+        );
+        assert!(sorted_by("flag_count.sticky").contains("ORDER BY item.sticky DESC"));
+
+        let sql = sorted_by("flag_count.x'; DROP TABLE item;--");
+        assert!(!sql.contains("flagging"), "{sql}");
+        assert!(sql.contains("ORDER BY NULL"), "{sql}");
+    }
+
     /// Helper: render a SimpleExpr to SQL string via a dummy SELECT.
     fn expr_to_sql(expr: SimpleExpr) -> String {
         let mut q = Query::select();
@@ -1461,6 +1599,82 @@ mod tests {
         );
     }
 
+    // ── Item grant tests ─────────────────────────────────────────────
+
+    #[test]
+    fn grant_filter_only_with_grants_user() {
+        let def = || QueryDefinition {
+            base_table: "item".to_string(),
+            ..Default::default()
+        };
+        let sql = GatherQueryBuilder::new(def(), LIVE_STAGE_ID).build(1, 10);
+        assert!(!sql.contains("item_access_granted"), "{sql}");
+
+        let user = Uuid::now_v7();
+        let builder = GatherQueryBuilder::new(def(), LIVE_STAGE_ID).with_grants_user(Some(user));
+        for sql in [builder.build(1, 10), builder.build_count()] {
+            assert!(
+                sql.contains(&format!(
+                    r#"item_access_granted("item"."id", '{user}', 'view')"#
+                )),
+                "grant filter missing: {sql}"
+            );
+        }
+
+        let categories = QueryDefinition {
+            base_table: "category_tag".to_string(),
+            stage_aware: false,
+            ..Default::default()
+        };
+        let sql = GatherQueryBuilder::new(categories, LIVE_STAGE_ID)
+            .with_grants_user(Some(user))
+            .build(1, 10);
+        assert!(!sql.contains("item_access_granted"), "{sql}");
+    }
+
+    // ── Own unpublished tests ────────────────────────────────────────
+
+    #[test]
+    fn own_unpublished_widens_published_filter() {
+        let def = |exposed| QueryDefinition {
+            base_table: "item".to_string(),
+            filters: vec![QueryFilter {
+                field: "status".to_string(),
+                operator: FilterOperator::Equals,
+                value: FilterValue::Integer(1),
+                exposed,
+                exposed_label: None,
+                widget: Default::default(),
+            }],
+            ..Default::default()
+        };
+        let author = Uuid::now_v7();
+        let types = vec!["blog".to_string()];
+
+        let builder = GatherQueryBuilder::new(def(false), LIVE_STAGE_ID)
+            .with_own_unpublished(Some(author), types.clone());
+        for sql in [builder.build(1, 10), builder.build_count()] {
+            assert!(
+                sql.contains(&format!(
+                    r#"("item"."status" = '1' OR ("item"."author_id" = '{author}' AND "item"."type" IN ('blog')))"#
+                )),
+                "own unpublished condition missing: {sql}"
+            );
+        }
+
+        // Exposed filters, anonymous users and empty type lists are unchanged.
+        for builder in [
+            GatherQueryBuilder::new(def(true), LIVE_STAGE_ID)
+                .with_own_unpublished(Some(author), types.clone()),
+            GatherQueryBuilder::new(def(false), LIVE_STAGE_ID).with_own_unpublished(None, types),
+            GatherQueryBuilder::new(def(false), LIVE_STAGE_ID)
+                .with_own_unpublished(Some(author), Vec::new()),
+        ] {
+            let sql = builder.build(1, 10);
+            assert!(!sql.contains("author_id"), "{sql}");
+        }
+    }
+
     // ── Translation overlay tests ────────────────────────────────────
 
     #[test]
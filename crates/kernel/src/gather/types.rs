@@ -9,6 +9,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use super::archive::{ArchiveConfig, ArchivePeriod};
+use super::export::ExportConfig;
+
 /// Complete query definition for Gather queries.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryDefinition {
@@ -332,6 +335,18 @@ pub struct QueryContext {
     /// that translated content is returned when available, falling back to
     /// the original values.
     pub language: Option<String>,
+
+    /// Restrict results to items created within this archive period.
+    pub archive: Option<ArchivePeriod>,
+
+    /// Restrict item results to those this user's grants allow viewing
+    /// (`Uuid::nil()` for anonymous). `None` skips the grant check, for
+    /// administrators and internal callers.
+    pub grants_user: Option<Uuid>,
+
+    /// Item types whose unpublished items by `current_user_id` are listed
+    /// alongside published ones ("view own unpublished {type} content").
+    pub own_unpublished_types: Vec<String>,
 }
 
 /// Sort specification.
@@ -434,6 +449,18 @@ pub struct QueryDisplay {
     /// redirect to the gather query with that UUID as a filter value.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub routes: Vec<GatherRoute>,
+
+    /// Date-based archive routes and facet counts for this listing.
+    ///
+    /// When set, `{base_path}/{yyyy}` and `{base_path}/{yyyy}/{mm}` render
+    /// this query restricted to items created in that period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<ArchiveConfig>,
+
+    /// CSV and feed exports of this listing (`/gather/{id}.csv`, `.rss`,
+    /// `.atom`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exports: Option<ExportConfig>,
 }
 
 fn default_items_per_page() -> u32 {
@@ -451,6 +478,8 @@ impl Default for QueryDisplay {
             footer: None,
             canonical_url: None,
             routes: Vec::new(),
+            archive: None,
+            exports: None,
         }
     }
 }
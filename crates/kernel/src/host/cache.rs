@@ -2,15 +2,28 @@
 //!
 //! Provides a two-tier cache (Moka L1 + Redis L2) accessible from WASM
 //! plugins. Cache keys are namespaced by plugin name and bin to prevent
-//! collisions: `plugin:{plugin_name}:{bin}:{key}`.
+//! collisions: `plugin:{plugin_name}:{bin}:{key}`. Tags are namespaced
+//! the same way, so a plugin can only invalidate its own entries.
 //!
-//! When no cache service is available (background/test contexts), `get`
-//! returns a cache miss and `set`/`invalidate-tag` are silent no-ops.
+//! Each plugin has a quota: entries are limited in size (the `CACHE_MAX_*`
+//! constants in `trovato_sdk::types`) and a plugin may hold at most
+//! `CACHE_MAX_ENTRIES` live entries. Every entry is also registered with
+//! the plugin-wide tag `plugin:{plugin_name}`, which is used to count them.
+//!
+//! When no cache service is available (test contexts), `get` returns a
+//! cache miss and `set`/`invalidate-tag` are silent no-ops.
 
 use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use trovato_sdk::types::{
+    CACHE_MAX_ENTRIES, CACHE_MAX_KEY_BYTES, CACHE_MAX_TAGS, CACHE_MAX_TTL_SECS,
+    CACHE_MAX_VALUE_BYTES,
+};
 use wasmtime::Linker;
 
 use super::{read_string_from_memory, write_string_to_memory};
+use crate::cache::CacheLayer;
 use crate::plugin::{PluginState, WasmtimeExt};
 
 /// Default TTL for plugin cache entries (5 minutes).
@@ -55,81 +68,112 @@ pub fn register_cache_functions(linker: &mut Linker<PluginState>) -> Result<()>
                         return -1; // Cache miss — no cache layer
                     };
 
-                    let plugin_name = &caller.data().plugin_name;
-                    let cache_key = format!("plugin:{plugin_name}:{bin}:{key}");
+                    let cache_key = plugin_key(&caller.data().plugin_name, &bin, &key);
 
                     match cache.get(&cache_key).await {
-                        Some(value) => write_string_to_memory(
-                            &memory,
-                            &mut caller,
-                            out_ptr,
-                            out_max_len,
-                            &value,
-                        )
-                        .unwrap_or(-1),
-                        None => -1,
+                        // A value larger than the output buffer would be
+                        // truncated; report a miss instead.
+                        Some(value) if value.len() <= out_max_len.max(0) as usize => {
+                            write_string_to_memory(
+                                &memory,
+                                &mut caller,
+                                out_ptr,
+                                out_max_len,
+                                &value,
+                            )
+                            .unwrap_or(-1)
+                        }
+                        _ => -1,
                     }
                 })
             },
         )
         .into_anyhow()?;
 
-    // set(bin, key, value, tags_json) -> void
+    // set(bin, key, value, tags_json, ttl_secs) -> i32 (0 = success, negative = error)
     linker
         .func_wrap_async(
             "trovato:kernel/cache-api",
             "set",
             |mut caller: wasmtime::Caller<'_, PluginState>,
-             (bin_ptr, bin_len, key_ptr, key_len, value_ptr, value_len, tags_ptr, tags_len): (
-                i32,
-                i32,
-                i32,
-                i32,
-                i32,
-                i32,
-                i32,
-                i32,
-            )| {
+             (
+                bin_ptr,
+                bin_len,
+                key_ptr,
+                key_len,
+                value_ptr,
+                value_len,
+                tags_ptr,
+                tags_len,
+                ttl_secs,
+            ): (i32, i32, i32, i32, i32, i32, i32, i32, i32)| {
                 Box::new(async move {
                     let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
-                        return;
+                        return host_errors::ERR_MEMORY_MISSING;
                     };
 
                     let Ok(bin) = read_string_from_memory(&memory, &caller, bin_ptr, bin_len)
                     else {
-                        return;
+                        return host_errors::ERR_PARAM1_READ;
                     };
                     let Ok(key) = read_string_from_memory(&memory, &caller, key_ptr, key_len)
                     else {
-                        return;
+                        return host_errors::ERR_PARAM1_READ;
                     };
                     let Ok(value) = read_string_from_memory(&memory, &caller, value_ptr, value_len)
                     else {
-                        return;
+                        return host_errors::ERR_PARAM2_OR_OUTPUT;
+                    };
+                    let Ok(tags_json) =
+                        read_string_from_memory(&memory, &caller, tags_ptr, tags_len)
+                    else {
+                        return host_errors::ERR_PARAM3_READ;
                     };
-                    let tags_json = read_string_from_memory(&memory, &caller, tags_ptr, tags_len)
-                        .unwrap_or_default();
+
+                    let tags: Vec<String> = if tags_json.is_empty() {
+                        Vec::new()
+                    } else {
+                        match serde_json::from_str(&tags_json) {
+                            Ok(tags) => tags,
+                            Err(_) => return host_errors::ERR_PARAM_DESERIALIZE,
+                        }
+                    };
+
+                    if let Err(code) = check_entry_limits(&bin, &key, &value, &tags) {
+                        return code;
+                    }
 
                     let Some(services) = caller.data().request.services() else {
-                        return;
+                        return 0;
                     };
                     let Some(ref cache) = services.cache else {
-                        return;
+                        return 0;
                     };
 
                     let plugin_name = &caller.data().plugin_name;
-                    let cache_key = format!("plugin:{plugin_name}:{bin}:{key}");
+                    let cache_key = plugin_key(plugin_name, &bin, &key);
+                    let plugin_tag = format!("plugin:{plugin_name}");
+
+                    if !within_quota(cache, &plugin_tag, &cache_key).await {
+                        warn!(
+                            plugin = %plugin_name,
+                            limit = CACHE_MAX_ENTRIES,
+                            "plugin cache quota reached"
+                        );
+                        return host_errors::ERR_CACHE_QUOTA_EXCEEDED;
+                    }
 
-                    let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
                     let prefixed_tags: Vec<String> = tags
                         .iter()
                         .map(|t| format!("plugin:{plugin_name}:{t}"))
+                        .chain(std::iter::once(plugin_tag))
                         .collect();
                     let tag_refs: Vec<&str> = prefixed_tags.iter().map(|s| s.as_str()).collect();
 
                     cache
-                        .set(&cache_key, &value, DEFAULT_TTL_SECS, &tag_refs)
+                        .set(&cache_key, &value, effective_ttl(ttl_secs), &tag_refs)
                         .await;
+                    0
                 })
             },
         )
@@ -170,6 +214,53 @@ pub fn register_cache_functions(linker: &mut Linker<PluginState>) -> Result<()>
     Ok(())
 }
 
+/// Build the namespaced cache key for a plugin entry.
+fn plugin_key(plugin_name: &str, bin: &str, key: &str) -> String {
+    format!("plugin:{plugin_name}:{bin}:{key}")
+}
+
+/// Check an entry against the per-entry size limits.
+fn check_entry_limits(bin: &str, key: &str, value: &str, tags: &[String]) -> Result<(), i32> {
+    if key.is_empty()
+        || bin.len() + key.len() > CACHE_MAX_KEY_BYTES
+        || value.len() > CACHE_MAX_VALUE_BYTES
+        || tags.len() > CACHE_MAX_TAGS
+        || tags.iter().any(|t| t.len() > CACHE_MAX_KEY_BYTES)
+    {
+        return Err(host_errors::ERR_CACHE_ENTRY_TOO_LARGE);
+    }
+    Ok(())
+}
+
+/// Resolve the TTL requested by a plugin: 0 (or negative) means the
+/// default, anything longer is capped.
+fn effective_ttl(ttl_secs: i32) -> u64 {
+    if ttl_secs <= 0 {
+        DEFAULT_TTL_SECS
+    } else {
+        (ttl_secs as u64).min(u64::from(CACHE_MAX_TTL_SECS))
+    }
+}
+
+/// Check whether a plugin may store `cache_key`.
+///
+/// Overwriting an existing entry is always allowed. When the plugin is at
+/// its limit, expired entries are pruned from the count before refusing.
+/// If Redis can't be reached the quota can't be counted, and the entry is
+/// allowed (it will only reach L1).
+async fn within_quota(cache: &CacheLayer, plugin_tag: &str, cache_key: &str) -> bool {
+    let Some(count) = cache.tag_len(plugin_tag).await else {
+        return true;
+    };
+    if count < CACHE_MAX_ENTRIES || cache.tag_contains(plugin_tag, cache_key).await {
+        return true;
+    }
+    cache
+        .prune_tag(plugin_tag)
+        .await
+        .is_none_or(|remaining| remaining < CACHE_MAX_ENTRIES)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -185,4 +276,39 @@ mod tests {
         let result = register_cache_functions(&mut linker);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn keys_are_namespaced_by_plugin_and_bin() {
+        assert_eq!(
+            plugin_key("argus", "feeds", "etag:1"),
+            "plugin:argus:feeds:etag:1"
+        );
+        assert_ne!(
+            plugin_key("argus", "feeds", "x"),
+            plugin_key("trovato_search", "feeds", "x")
+        );
+    }
+
+    #[test]
+    fn entry_limits_are_enforced() {
+        assert!(check_entry_limits("feeds", "etag", "\"abc\"", &[]).is_ok());
+        assert_eq!(
+            check_entry_limits("feeds", "", "v", &[]),
+            Err(host_errors::ERR_CACHE_ENTRY_TOO_LARGE)
+        );
+        let long_key = "k".repeat(CACHE_MAX_KEY_BYTES);
+        assert!(check_entry_limits("feeds", &long_key, "v", &[]).is_err());
+        let big_value = "v".repeat(CACHE_MAX_VALUE_BYTES + 1);
+        assert!(check_entry_limits("feeds", "etag", &big_value, &[]).is_err());
+        let tags = vec!["t".to_string(); CACHE_MAX_TAGS + 1];
+        assert!(check_entry_limits("feeds", "etag", "v", &tags).is_err());
+    }
+
+    #[test]
+    fn ttl_defaults_and_caps() {
+        assert_eq!(effective_ttl(0), DEFAULT_TTL_SECS);
+        assert_eq!(effective_ttl(-5), DEFAULT_TTL_SECS);
+        assert_eq!(effective_ttl(3600), 3600);
+        assert_eq!(effective_ttl(i32::MAX), u64::from(CACHE_MAX_TTL_SECS));
+    }
 }
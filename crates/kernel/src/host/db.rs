@@ -3,11 +3,17 @@
 //! Provides both raw and structured database access with DDL guards
 //! to prevent schema modification from plugins. All queries use
 //! JSON-encoded parameters and return JSON results.
+//!
+//! Each statement normally runs in its own short transaction. A tap can
+//! instead group statements with `begin`/`commit`/`rollback`; the
+//! transaction belongs to that tap invocation and is rolled back if the
+//! tap ends without committing it (see [`rollback_open_transaction`]).
 
 use anyhow::Result;
 use regex::Regex;
+use sqlx::pool::PoolConnection;
 use sqlx::postgres::PgArguments;
-use sqlx::{Column, Executor, PgPool, Row, TypeInfo};
+use sqlx::{Column, Executor, PgConnection, PgPool, Postgres, Row, TypeInfo};
 use std::sync::LazyLock;
 use tracing::warn;
 use trovato_sdk::host_errors;
@@ -21,6 +27,18 @@ const PLUGIN_QUERY_TIMEOUT_MS: u32 = 5000;
 use super::{read_string_from_memory, write_string_to_memory};
 use crate::plugin::PluginState;
 
+/// Map a failed statement to a host error code.
+///
+/// Database errors carry their SQLSTATE across the WASM boundary (see
+/// [`host_errors::encode_sql_state`]) so plugins can tell a unique
+/// violation from a timeout; anything else is `ERR_SQL_FAILED`.
+pub(super) fn sql_error_code(e: &sqlx::Error) -> i32 {
+    e.as_database_error()
+        .and_then(|db| db.code())
+        .and_then(|code| host_errors::encode_sql_state(&code))
+        .unwrap_or(host_errors::ERR_SQL_FAILED)
+}
+
 /// Regex for valid SQL identifiers (table/column names).
 ///
 /// # Panics
@@ -33,6 +51,26 @@ static VALID_IDENTIFIER: LazyLock<Regex> =
 /// DDL keywords that `execute-raw` must reject.
 const DDL_KEYWORDS: &[&str] = &["CREATE", "DROP", "ALTER", "TRUNCATE", "GRANT", "REVOKE"];
 
+/// Transaction control keywords that `execute-raw` must reject.
+///
+/// Plugins manage transactions through the `begin`/`commit`/`rollback`
+/// host functions; raw control statements would end the kernel's
+/// per-statement transaction or savepoint early.
+const TRANSACTION_KEYWORDS: &[&str] = &[
+    "BEGIN",
+    "START",
+    "COMMIT",
+    "END",
+    "ROLLBACK",
+    "ABORT",
+    "SAVEPOINT",
+    "RELEASE",
+    "PREPARE",
+];
+
+/// Savepoint wrapping each statement run inside a plugin transaction.
+const STATEMENT_SAVEPOINT: &str = "plugin_statement";
+
 /// Extract the first SQL keyword, skipping leading comments and whitespace.
 ///
 /// Handles `-- line comments` and `/* block comments */` that could be
@@ -61,6 +99,14 @@ fn is_ddl(sql: &str) -> bool {
         .any(|kw| first_word.eq_ignore_ascii_case(kw))
 }
 
+/// Check if SQL starts with a transaction control keyword (after stripping comments).
+fn is_transaction_control(sql: &str) -> bool {
+    let first_word = first_sql_keyword(sql);
+    TRANSACTION_KEYWORDS
+        .iter()
+        .any(|kw| first_word.eq_ignore_ascii_case(kw))
+}
+
 /// Check if SQL is a read-only statement (SELECT or WITH), after stripping comments.
 fn is_read_only(sql: &str) -> bool {
     let first_word = first_sql_keyword(sql);
@@ -160,9 +206,141 @@ fn row_to_json(row: &sqlx::postgres::PgRow) -> serde_json::Value {
     serde_json::Value::Object(map)
 }
 
+/// Where a plugin statement runs.
+enum DbTarget<'a> {
+    /// Its own transaction on a pooled connection.
+    Pool(&'a PgPool),
+    /// The tap's open transaction, behind a savepoint.
+    Transaction(&'a mut PgConnection),
+}
+
+impl<'a> DbTarget<'a> {
+    /// Target the open transaction if there is one, otherwise `pool`.
+    fn new(pool: &'a PgPool, transaction: Option<&'a mut PgConnection>) -> Self {
+        match transaction {
+            Some(conn) => Self::Transaction(conn),
+            None => Self::Pool(pool),
+        }
+    }
+}
+
+/// A statement in progress: the connection it runs on and how to end it.
+enum StatementScope<'a> {
+    /// A pooled connection inside `BEGIN`, ended with `COMMIT`/`ROLLBACK`.
+    Pooled(PoolConnection<Postgres>),
+    /// A savepoint in the tap's transaction, released or rolled back to,
+    /// so a failed statement leaves the transaction usable.
+    Savepoint(&'a mut PgConnection),
+}
+
+impl<'a> StatementScope<'a> {
+    /// Open a scope for one statement on `target`.
+    async fn open(target: DbTarget<'a>) -> std::result::Result<Self, i32> {
+        match target {
+            DbTarget::Pool(pool) => {
+                let mut conn = pool.acquire().await.map_err(|e| {
+                    warn!(error = %e, "failed to acquire DB connection for plugin query");
+                    host_errors::ERR_SQL_FAILED
+                })?;
+                begin_with_timeout(&mut conn).await?;
+                Ok(Self::Pooled(conn))
+            }
+            DbTarget::Transaction(conn) => {
+                conn.execute(format!("SAVEPOINT {STATEMENT_SAVEPOINT}").as_str())
+                    .await
+                    .map_err(|e| {
+                        warn!(error = %e, "failed to set savepoint for plugin query");
+                        sql_error_code(&e)
+                    })?;
+                Ok(Self::Savepoint(conn))
+            }
+        }
+    }
+
+    /// The connection to run the statement on.
+    fn conn(&mut self) -> &mut PgConnection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Savepoint(conn) => conn,
+        }
+    }
+
+    /// Keep the statement's effects if `succeeded`, discard them otherwise.
+    async fn finish(mut self, succeeded: bool) {
+        let sql = match (&self, succeeded) {
+            (Self::Pooled(_), true) => "COMMIT".to_string(),
+            (Self::Pooled(_), false) => "ROLLBACK".to_string(),
+            (Self::Savepoint(_), true) => format!("RELEASE SAVEPOINT {STATEMENT_SAVEPOINT}"),
+            (Self::Savepoint(_), false) => format!("ROLLBACK TO SAVEPOINT {STATEMENT_SAVEPOINT}"),
+        };
+        let _ = self.conn().execute(sql.as_str()).await;
+    }
+}
+
+/// Start a transaction with the plugin statement timeout.
+///
+/// `SET LOCAL statement_timeout` has no effect outside a transaction, and
+/// is scoped to the transaction so the pooled connection is unaffected.
+async fn begin_with_timeout(conn: &mut PgConnection) -> std::result::Result<(), i32> {
+    conn.execute("BEGIN").await.map_err(|e| {
+        warn!(error = %e, "failed to begin transaction for plugin query");
+        host_errors::ERR_SQL_FAILED
+    })?;
+
+    let timeout_result = conn
+        .execute(format!("SET LOCAL statement_timeout = '{PLUGIN_QUERY_TIMEOUT_MS}'").as_str())
+        .await;
+    if let Err(e) = timeout_result {
+        warn!(error = %e, "failed to set statement_timeout");
+        let _ = conn.execute("ROLLBACK").await;
+        return Err(host_errors::ERR_SQL_FAILED);
+    }
+    Ok(())
+}
+
+/// Open a plugin transaction with the plugin statement timeout.
+///
+/// Dropping the returned transaction rolls it back, so a tap that is
+/// torn down mid-call cannot leave it open on a pooled connection.
+async fn begin_transaction(
+    pool: &PgPool,
+) -> std::result::Result<sqlx::Transaction<'static, Postgres>, i32> {
+    let mut transaction = pool.begin().await.map_err(|e| {
+        warn!(error = %e, "failed to begin plugin transaction");
+        host_errors::ERR_SQL_FAILED
+    })?;
+    transaction
+        .execute(format!("SET LOCAL statement_timeout = '{PLUGIN_QUERY_TIMEOUT_MS}'").as_str())
+        .await
+        .map_err(|e| {
+            warn!(error = %e, "failed to set statement_timeout");
+            host_errors::ERR_SQL_FAILED
+        })?;
+    Ok(transaction)
+}
+
+/// Roll back a transaction the tap left open.
+///
+/// Called after every tap invocation. A plugin transaction is never
+/// committed implicitly: a tap that trapped, returned an error, or simply
+/// forgot to commit has its statements discarded.
+pub async fn rollback_open_transaction(state: &mut PluginState, tap_name: &str) {
+    let Some(transaction) = state.db_transaction.take() else {
+        return;
+    };
+    warn!(
+        plugin = %state.plugin_name,
+        tap = tap_name,
+        "tap ended with an open transaction; rolling back"
+    );
+    if let Err(e) = transaction.rollback().await {
+        warn!(error = %e, plugin = %state.plugin_name, "failed to roll back plugin transaction");
+    }
+}
+
 /// Execute a SELECT query and return JSON results, writing to the WASM output buffer.
 async fn do_query_raw(
-    pool: &PgPool,
+    target: DbTarget<'_>,
     sql: &str,
     params: &[serde_json::Value],
 ) -> std::result::Result<String, i32> {
@@ -173,53 +351,32 @@ async fn do_query_raw(
         return Err(host_errors::ERR_DDL_REJECTED);
     }
 
-    fetch_rows_as_json(pool, sql, params).await
+    fetch_rows_as_json(target, sql, params).await
 }
 
 /// Execute a SQL statement that returns rows and serialize them as JSON.
 ///
 /// Shared implementation for `do_query_raw` (after guard) and `do_insert` (RETURNING *).
-/// Wraps the query in an explicit transaction so `SET LOCAL statement_timeout`
-/// is scoped correctly (it has no effect outside a transaction).
 async fn fetch_rows_as_json(
-    pool: &PgPool,
+    target: DbTarget<'_>,
     sql: &str,
     params: &[serde_json::Value],
 ) -> std::result::Result<String, i32> {
-    let mut conn = pool.acquire().await.map_err(|e| {
-        warn!(error = %e, "failed to acquire DB connection for plugin query");
-        host_errors::ERR_SQL_FAILED
-    })?;
-
-    // Wrap in explicit transaction so SET LOCAL is properly scoped.
-    conn.execute("BEGIN").await.map_err(|e| {
-        warn!(error = %e, "failed to begin transaction for plugin query");
-        host_errors::ERR_SQL_FAILED
-    })?;
-
-    // Set statement timeout scoped to this transaction.
-    let timeout_result = conn
-        .execute(format!("SET LOCAL statement_timeout = '{PLUGIN_QUERY_TIMEOUT_MS}'").as_str())
-        .await;
-    if let Err(e) = timeout_result {
-        warn!(error = %e, "failed to set statement_timeout");
-        let _ = conn.execute("ROLLBACK").await;
-        return Err(host_errors::ERR_SQL_FAILED);
-    }
+    let mut scope = StatementScope::open(target).await?;
 
     let query = sqlx::query(sql);
     let query = bind_json_params(params, query);
 
-    let rows = match query.fetch_all(&mut *conn).await {
+    let rows = match query.fetch_all(scope.conn()).await {
         Ok(rows) => rows,
         Err(e) => {
             warn!(error = %e, sql = sql, "plugin query failed");
-            let _ = conn.execute("ROLLBACK").await;
-            return Err(host_errors::ERR_SQL_FAILED);
+            scope.finish(false).await;
+            return Err(sql_error_code(&e));
         }
     };
 
-    let _ = conn.execute("COMMIT").await;
+    scope.finish(true).await;
 
     let json_rows: Vec<serde_json::Value> = rows.iter().map(row_to_json).collect();
     serde_json::to_string(&json_rows).map_err(|_| host_errors::ERR_SERIALIZE_FAILED)
@@ -227,59 +384,41 @@ async fn fetch_rows_as_json(
 
 /// Execute a DML statement and return rows affected.
 ///
-/// Wraps the statement in an explicit transaction so `SET LOCAL statement_timeout`
-/// is scoped correctly. Rejects DDL keywords and semicolons (multi-statement).
+/// Rejects DDL and transaction control keywords and semicolons
+/// (multi-statement).
 async fn do_execute_raw(
-    pool: &PgPool,
+    target: DbTarget<'_>,
     sql: &str,
     params: &[serde_json::Value],
 ) -> std::result::Result<u64, i32> {
-    if is_ddl(sql) {
+    if is_ddl(sql) || is_transaction_control(sql) {
         return Err(host_errors::ERR_DDL_REJECTED);
     }
     if has_semicolons(sql) {
         return Err(host_errors::ERR_DDL_REJECTED);
     }
 
-    let mut conn = pool.acquire().await.map_err(|e| {
-        warn!(error = %e, "failed to acquire DB connection for plugin execute");
-        host_errors::ERR_SQL_FAILED
-    })?;
-
-    // Wrap in explicit transaction so SET LOCAL is properly scoped.
-    conn.execute("BEGIN").await.map_err(|e| {
-        warn!(error = %e, "failed to begin transaction for plugin execute");
-        host_errors::ERR_SQL_FAILED
-    })?;
-
-    let timeout_result = conn
-        .execute(format!("SET LOCAL statement_timeout = '{PLUGIN_QUERY_TIMEOUT_MS}'").as_str())
-        .await;
-    if let Err(e) = timeout_result {
-        warn!(error = %e, "failed to set statement_timeout");
-        let _ = conn.execute("ROLLBACK").await;
-        return Err(host_errors::ERR_SQL_FAILED);
-    }
+    let mut scope = StatementScope::open(target).await?;
 
     let query = sqlx::query(sql);
     let query = bind_json_params(params, query);
 
-    let result = match query.execute(&mut *conn).await {
+    let result = match query.execute(scope.conn()).await {
         Ok(result) => result,
         Err(e) => {
             warn!(error = %e, sql = sql, "plugin execute-raw failed");
-            let _ = conn.execute("ROLLBACK").await;
-            return Err(host_errors::ERR_SQL_FAILED);
+            scope.finish(false).await;
+            return Err(sql_error_code(&e));
         }
     };
 
-    let _ = conn.execute("COMMIT").await;
+    scope.finish(true).await;
 
     Ok(result.rows_affected())
 }
 
 /// Build and execute a structured SELECT query.
-async fn do_select(pool: &PgPool, query_json: &str) -> std::result::Result<String, i32> {
+async fn do_select(target: DbTarget<'_>, query_json: &str) -> std::result::Result<String, i32> {
     let query: SelectQuery =
         serde_json::from_str(query_json).map_err(|_| host_errors::ERR_PARAM_DESERIALIZE)?;
 
@@ -346,12 +485,12 @@ async fn do_select(pool: &PgPool, query_json: &str) -> std::result::Result<Strin
         params.push(serde_json::json!(limit));
     }
 
-    do_query_raw(pool, &sql, &params).await
+    do_query_raw(target, &sql, &params).await
 }
 
 /// Build and execute a structured INSERT.
 async fn do_insert(
-    pool: &PgPool,
+    target: DbTarget<'_>,
     table: &str,
     data_json: &str,
 ) -> std::result::Result<String, i32> {
@@ -389,12 +528,12 @@ async fn do_insert(
     );
 
     // Bypass read-only guard since INSERT RETURNING needs row results.
-    fetch_rows_as_json(pool, &sql, &params).await
+    fetch_rows_as_json(target, &sql, &params).await
 }
 
 /// Build and execute a structured UPDATE.
 async fn do_update(
-    pool: &PgPool,
+    target: DbTarget<'_>,
     table: &str,
     data_json: &str,
     where_json: &str,
@@ -442,11 +581,15 @@ async fn do_update(
         where_parts.join(" AND ")
     );
 
-    do_execute_raw(pool, &sql, &params).await
+    do_execute_raw(target, &sql, &params).await
 }
 
 /// Build and execute a structured DELETE.
-async fn do_delete(pool: &PgPool, table: &str, where_json: &str) -> std::result::Result<u64, i32> {
+async fn do_delete(
+    target: DbTarget<'_>,
+    table: &str,
+    where_json: &str,
+) -> std::result::Result<u64, i32> {
     if !VALID_IDENTIFIER.is_match(table) {
         return Err(host_errors::ERR_INVALID_IDENTIFIER);
     }
@@ -473,7 +616,7 @@ async fn do_delete(pool: &PgPool, table: &str, where_json: &str) -> std::result:
 
     let sql = format!("DELETE FROM {} WHERE {}", table, where_parts.join(" AND "));
 
-    do_execute_raw(pool, &sql, &params).await
+    do_execute_raw(target, &sql, &params).await
 }
 
 /// Structured SELECT query format.
@@ -528,7 +671,15 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                     };
                     let pool = services.db.clone();
 
-                    match do_select(&pool, &query_json).await {
+                    let mut transaction = caller.data_mut().db_transaction.take();
+                    let result = do_select(
+                        DbTarget::new(&pool, transaction.as_deref_mut()),
+                        &query_json,
+                    )
+                    .await;
+                    caller.data_mut().db_transaction = transaction;
+
+                    match result {
                         Ok(result) => write_string_to_memory(
                             &memory,
                             &mut caller,
@@ -579,7 +730,16 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                     };
                     let pool = services.db.clone();
 
-                    match do_insert(&pool, &table, &data_json).await {
+                    let mut transaction = caller.data_mut().db_transaction.take();
+                    let result = do_insert(
+                        DbTarget::new(&pool, transaction.as_deref_mut()),
+                        &table,
+                        &data_json,
+                    )
+                    .await;
+                    caller.data_mut().db_transaction = transaction;
+
+                    match result {
                         Ok(result) => write_string_to_memory(
                             &memory,
                             &mut caller,
@@ -636,7 +796,17 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                     };
                     let pool = services.db.clone();
 
-                    match do_update(&pool, &table, &data_json, &where_json).await {
+                    let mut transaction = caller.data_mut().db_transaction.take();
+                    let result = do_update(
+                        DbTarget::new(&pool, transaction.as_deref_mut()),
+                        &table,
+                        &data_json,
+                        &where_json,
+                    )
+                    .await;
+                    caller.data_mut().db_transaction = transaction;
+
+                    match result {
                         Ok(rows) => rows as i64,
                         Err(code) => i64::from(code),
                     }
@@ -673,7 +843,16 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                     };
                     let pool = services.db.clone();
 
-                    match do_delete(&pool, &table, &where_json).await {
+                    let mut transaction = caller.data_mut().db_transaction.take();
+                    let result = do_delete(
+                        DbTarget::new(&pool, transaction.as_deref_mut()),
+                        &table,
+                        &where_json,
+                    )
+                    .await;
+                    caller.data_mut().db_transaction = transaction;
+
+                    match result {
                         Ok(rows) => rows as i64,
                         Err(code) => i64::from(code),
                     }
@@ -682,6 +861,84 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
         )
         .into_anyhow()?;
 
+    // begin() -> i32 (0 or error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/db",
+            "begin",
+            |mut caller: wasmtime::Caller<'_, PluginState>, (): ()| {
+                Box::new(async move {
+                    if caller.data().db_transaction.is_some() {
+                        return host_errors::ERR_TRANSACTION_OPEN;
+                    }
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let pool = services.db.clone();
+
+                    match begin_transaction(&pool).await {
+                        Ok(transaction) => {
+                            caller.data_mut().db_transaction = Some(transaction);
+                            0
+                        }
+                        Err(code) => code,
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    // commit() -> i32 (0 or error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/db",
+            "commit",
+            |mut caller: wasmtime::Caller<'_, PluginState>, (): ()| {
+                Box::new(async move {
+                    let Some(transaction) = caller.data_mut().db_transaction.take() else {
+                        return host_errors::ERR_NO_TRANSACTION;
+                    };
+                    match transaction.commit().await {
+                        Ok(()) => 0,
+                        Err(e) => {
+                            warn!(error = %e, "plugin transaction commit failed");
+                            sql_error_code(&e)
+                        }
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    // rollback() -> i32 (0 or error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/db",
+            "rollback",
+            |mut caller: wasmtime::Caller<'_, PluginState>, (): ()| {
+                Box::new(async move {
+                    let Some(transaction) = caller.data_mut().db_transaction.take() else {
+                        return host_errors::ERR_NO_TRANSACTION;
+                    };
+                    match transaction.rollback().await {
+                        Ok(()) => 0,
+                        Err(e) => {
+                            warn!(error = %e, "plugin transaction rollback failed");
+                            sql_error_code(&e)
+                        }
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+/// Register the raw SQL host functions (`query-raw`, `execute-raw`).
+///
+/// Only linked for plugins granted the `raw_sql` capability.
+pub fn register_raw_sql_functions(linker: &mut Linker<PluginState>) -> Result<()> {
     // query-raw(sql, params_json, out) -> i32 (bytes written or error)
     linker
         .func_wrap_async(
@@ -722,7 +979,16 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                         Err(_) => return host_errors::ERR_PARAM_DESERIALIZE,
                     };
 
-                    match do_query_raw(&pool, &sql, &params).await {
+                    let mut transaction = caller.data_mut().db_transaction.take();
+                    let result = do_query_raw(
+                        DbTarget::new(&pool, transaction.as_deref_mut()),
+                        &sql,
+                        &params,
+                    )
+                    .await;
+                    caller.data_mut().db_transaction = transaction;
+
+                    match result {
                         Ok(result) => write_string_to_memory(
                             &memory,
                             &mut caller,
@@ -771,7 +1037,16 @@ pub fn register_db_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                         Err(_) => return i64::from(host_errors::ERR_PARAM_DESERIALIZE),
                     };
 
-                    match do_execute_raw(&pool, &sql, &params).await {
+                    let mut transaction = caller.data_mut().db_transaction.take();
+                    let result = do_execute_raw(
+                        DbTarget::new(&pool, transaction.as_deref_mut()),
+                        &sql,
+                        &params,
+                    )
+                    .await;
+                    caller.data_mut().db_transaction = transaction;
+
+                    match result {
                         Ok(rows) => rows as i64,
                         Err(code) => i64::from(code),
                     }
@@ -798,6 +1073,8 @@ mod tests {
 
         let result = register_db_functions(&mut linker);
         assert!(result.is_ok());
+        let result = register_raw_sql_functions(&mut linker);
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -819,6 +1096,19 @@ mod tests {
         assert!(!is_ddl("WITH cte AS (SELECT 1) SELECT * FROM cte"));
     }
 
+    #[test]
+    fn transaction_guard_rejects_transaction_control() {
+        assert!(is_transaction_control("COMMIT"));
+        assert!(is_transaction_control("rollback"));
+        assert!(is_transaction_control("BEGIN ISOLATION LEVEL SERIALIZABLE"));
+        assert!(is_transaction_control("START TRANSACTION"));
+        assert!(is_transaction_control("SAVEPOINT plugin_statement"));
+        assert!(is_transaction_control("RELEASE SAVEPOINT plugin_statement"));
+        assert!(is_transaction_control("/* sneaky */ END"));
+        assert!(!is_transaction_control("UPDATE foo SET committed = true"));
+        assert!(!is_transaction_control("INSERT INTO foo VALUES (1)"));
+    }
+
     #[test]
     fn read_only_guard() {
         assert!(is_read_only("SELECT * FROM foo"));
@@ -0,0 +1,124 @@
+//! Device fingerprinting host functions for WASM plugins.
+//!
+//! `mac-vendor` looks up the vendor of a MAC address in the kernel's OUI
+//! table (see [`crate::services::mac_vendor`]). `classify` sends a device
+//! through `tap_ng_classify` so other plugins can refine the caller's
+//! device type guess; a handler calling `classify` again is refused.
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use trovato_sdk::types::DeviceFingerprint;
+use wasmtime::Linker;
+
+use super::{read_string_from_memory, write_string_to_memory};
+use crate::plugin::{PluginState, WasmtimeExt};
+use crate::services::mac_vendor;
+
+/// Tap refining device type guesses.
+const CLASSIFY_TAP: &str = "tap_ng_classify";
+
+/// Register device host functions.
+pub fn register_device_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // mac-vendor(mac) -> string (bytes written, 0 if unknown, or negative error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/device",
+            "mac-vendor",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (mac_ptr, mac_len, out_ptr, out_max_len): (i32, i32, i32, i32)| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(mac) = read_string_from_memory(&memory, &caller, mac_ptr, mac_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let pool = services.db.clone();
+
+                    let vendor = mac_vendor::lookup(&pool, &mac).await.unwrap_or_default();
+
+                    write_string_to_memory(&memory, &mut caller, out_ptr, out_max_len, &vendor)
+                        .unwrap_or(host_errors::ERR_PARAM2_OR_OUTPUT)
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    // classify(device_json) -> DeviceFingerprint JSON (bytes written or negative error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/device",
+            "classify",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (device_ptr, device_len, out_ptr, out_max_len): (i32, i32, i32, i32)| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(device_json) =
+                        read_string_from_memory(&memory, &caller, device_ptr, device_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let device: DeviceFingerprint = match serde_json::from_str(&device_json) {
+                        Ok(device) => device,
+                        Err(e) => {
+                            warn!(error = %e, "classify: invalid device JSON");
+                            return host_errors::ERR_PARAM_DESERIALIZE;
+                        }
+                    };
+
+                    let mut state = caller.data().request.clone();
+                    if state.events.iter().any(|e| e == CLASSIFY_TAP) {
+                        warn!(
+                            plugin = %caller.data().plugin_name,
+                            "classify: refusing call from a tap_ng_classify handler"
+                        );
+                        return host_errors::ERR_EVENT_LOOP;
+                    }
+                    let Some(dispatcher) = caller.data().dispatcher.clone() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    state.events.push(CLASSIFY_TAP.to_string());
+
+                    let device = dispatcher.dispatch_alter(CLASSIFY_TAP, device, state).await;
+
+                    let Ok(json) = serde_json::to_string(&device) else {
+                        return host_errors::ERR_PARAM2_OR_OUTPUT;
+                    };
+                    write_string_to_memory(&memory, &mut caller, out_ptr, out_max_len, &json)
+                        .unwrap_or(host_errors::ERR_PARAM2_OR_OUTPUT)
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_device_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).unwrap();
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_device_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+}
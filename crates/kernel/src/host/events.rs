@@ -0,0 +1,101 @@
+//! Event host functions for WASM plugins.
+//!
+//! `dispatch` lets a plugin publish a named event with a JSON payload; the
+//! kernel delivers it to every other plugin implementing `tap_event`
+//! before the call returns. Loop protection lives in
+//! [`TapDispatcher::dispatch_event`](crate::tap::TapDispatcher::dispatch_event).
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use trovato_sdk::types::{EVENT_MAX_PAYLOAD_BYTES, is_valid_event_name};
+use wasmtime::Linker;
+
+use super::read_string_from_memory;
+use crate::plugin::{PluginState, WasmtimeExt};
+
+/// Register event host functions.
+pub fn register_event_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // dispatch(name_ptr, name_len, payload_ptr, payload_len) -> i32
+    //
+    // Returns the number of plugins the event was delivered to, or a
+    // negative error code. The source plugin is taken from PluginState so
+    // plugins cannot impersonate each other.
+    linker
+        .func_wrap_async(
+            "trovato:kernel/events",
+            "dispatch",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (name_ptr, name_len, payload_ptr, payload_len): (i32, i32, i32, i32)| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(name) = read_string_from_memory(&memory, &caller, name_ptr, name_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let Ok(payload_json) =
+                        read_string_from_memory(&memory, &caller, payload_ptr, payload_len)
+                    else {
+                        return host_errors::ERR_PARAM2_OR_OUTPUT;
+                    };
+
+                    let plugin_name = caller.data().plugin_name.clone();
+                    if !is_valid_event_name(&name) || payload_json.len() > EVENT_MAX_PAYLOAD_BYTES {
+                        warn!(
+                            plugin = %plugin_name,
+                            event = %name,
+                            payload_bytes = payload_json.len(),
+                            "dispatch_event: invalid event name or payload too large"
+                        );
+                        return host_errors::ERR_EVENT_INVALID;
+                    }
+
+                    let payload: serde_json::Value = match serde_json::from_str(&payload_json) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            warn!(error = %e, "dispatch_event: invalid payload JSON");
+                            return host_errors::ERR_PARAM_DESERIALIZE;
+                        }
+                    };
+
+                    let Some(dispatcher) = caller.data().dispatcher.clone() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let state = caller.data().request.clone();
+
+                    match dispatcher
+                        .dispatch_event(&plugin_name, &name, payload, state)
+                        .await
+                    {
+                        Some(delivered) => i32::try_from(delivered).unwrap_or(i32::MAX),
+                        None => host_errors::ERR_EVENT_LOOP,
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_events_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).unwrap();
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_event_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+}
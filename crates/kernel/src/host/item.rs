@@ -4,14 +4,20 @@
 //! `Item` model queries. Uses the model directly (not `ItemService`)
 //! to avoid re-entrant tap dispatch when a plugin calls save_item
 //! from within a tap handler.
+//!
+//! `item-query` is the preferred way for plugins to read items: unlike
+//! `query-items` and `query-raw` it resolves the stage overlay and applies
+//! access checks for the calling user (see [`crate::content::item_query`]).
 
 use anyhow::Result;
 use tracing::warn;
 use trovato_sdk::host_errors;
+use trovato_sdk::types::ItemQuery;
 use uuid::Uuid;
 use wasmtime::Linker;
 
 use super::{read_string_from_memory, write_string_to_memory};
+use crate::content::item_query::{self, ItemQueryAccess, ItemQueryError};
 use crate::models::{CreateItem, Item, UpdateItem};
 use crate::plugin::{PluginState, WasmtimeExt};
 
@@ -128,6 +134,7 @@ pub fn register_item_functions(linker: &mut Linker<PluginState>) -> Result<()> {
                             sticky: None,
                             fields: parsed.get("fields").cloned(),
                             log: parsed.get("log").and_then(|v| v.as_str()).map(String::from),
+                            status_meta: None,
                         };
                         Item::update(&pool, id, user_id, update).await
                     } else {
@@ -297,6 +304,87 @@ pub fn register_item_functions(linker: &mut Linker<PluginState>) -> Result<()> {
         )
         .into_anyhow()?;
 
+    // item-query(query_json, out) -> i32 (bytes written or error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/item-api",
+            "item-query",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (query_ptr, query_len, out_ptr, out_max_len): (i32, i32, i32, i32)| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(query_json) =
+                        read_string_from_memory(&memory, &caller, query_ptr, query_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let pool = services.db.clone();
+                    let background = services.background;
+                    let user = caller.data().request.user.clone();
+
+                    let Ok(query) = serde_json::from_str::<ItemQuery>(&query_json) else {
+                        return host_errors::ERR_PARAM_DESERIALIZE;
+                    };
+
+                    let access = ItemQueryAccess::for_user(&user, background);
+                    let stage_ids = match item_query::resolve_stage_ids(
+                        &pool,
+                        query.stage.as_deref(),
+                        &user,
+                        access,
+                    )
+                    .await
+                    {
+                        Ok(Some(ids)) => ids,
+                        Ok(None) => return host_errors::ERR_INVALID_IDENTIFIER,
+                        Err(e) => {
+                            warn!(error = %e, "item-query stage resolution failed");
+                            return host_errors::ERR_SQL_FAILED;
+                        }
+                    };
+
+                    let grants_user = access.grants_user(&user);
+                    let built = match item_query::build(&query, stage_ids, access, grants_user) {
+                        Ok(built) => built,
+                        Err(ItemQueryError::InvalidField(field)) => {
+                            warn!(field = %field, "item-query rejected invalid field name");
+                            return host_errors::ERR_INVALID_IDENTIFIER;
+                        }
+                        Err(e) => {
+                            warn!(error = %e, "item-query rejected query");
+                            return host_errors::ERR_PARAM_DESERIALIZE;
+                        }
+                    };
+
+                    match built.fetch(&pool).await {
+                        Ok(items) => match serde_json::to_string(&items) {
+                            Ok(json) => write_string_to_memory(
+                                &memory,
+                                &mut caller,
+                                out_ptr,
+                                out_max_len,
+                                &json,
+                            )
+                            .unwrap_or(host_errors::ERR_PARAM2_OR_OUTPUT),
+                            Err(_) => host_errors::ERR_SERIALIZE_FAILED,
+                        },
+                        Err(e) => {
+                            warn!(error = %e, "item-query host function failed");
+                            host_errors::ERR_SQL_FAILED
+                        }
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
     Ok(())
 }
 
@@ -7,44 +7,78 @@ mod ai;
 mod cache;
 mod crypto;
 mod db;
+mod device;
+mod events;
 mod http;
 mod item;
 mod logging;
 mod queue;
+mod render;
 mod request_context;
+mod saved_search;
+mod scheduled_update;
+mod slug;
 mod user;
 mod variables;
+mod vector;
 
 use anyhow::Result;
 use wasmtime::Linker;
 
-use crate::plugin::PluginState;
+use crate::plugin::{PluginCapabilities, PluginState};
 
 pub use ai::register_ai_functions;
 pub use cache::register_cache_functions;
 pub use crypto::register_crypto_functions;
-pub use db::register_db_functions;
+pub use db::{register_db_functions, register_raw_sql_functions, rollback_open_transaction};
+pub use device::register_device_functions;
+pub use events::register_event_functions;
 pub use http::register_http_functions;
 pub use item::register_item_functions;
 pub use logging::register_logging_functions;
 pub use queue::register_queue_functions;
+pub use render::{RenderBuffers, register_render_functions};
 pub use request_context::register_request_context_functions;
+pub use saved_search::register_saved_search_functions;
+pub use scheduled_update::register_scheduled_update_functions;
+pub use slug::register_slug_functions;
 pub use user::register_user_functions;
 pub use variables::register_variables_functions;
+pub use vector::register_vector_functions;
 
-/// Register all host functions with the linker.
-pub fn register_all(linker: &mut Linker<PluginState>) -> Result<()> {
+/// Register the host functions a plugin's capabilities grant.
+///
+/// Functions outside the capability groups are always registered; see
+/// [`crate::plugin::Capability`].
+pub fn register_all(
+    linker: &mut Linker<PluginState>,
+    capabilities: &PluginCapabilities,
+) -> Result<()> {
     register_logging_functions(linker)?;
     register_variables_functions(linker)?;
     register_request_context_functions(linker)?;
     register_user_functions(linker)?;
-    register_cache_functions(linker)?;
+    if capabilities.cache {
+        register_cache_functions(linker)?;
+    }
     register_item_functions(linker)?;
     register_db_functions(linker)?;
+    if capabilities.raw_sql {
+        register_raw_sql_functions(linker)?;
+    }
     register_ai_functions(linker)?;
     register_queue_functions(linker)?;
-    register_http_functions(linker)?;
+    register_event_functions(linker)?;
+    register_device_functions(linker)?;
+    register_saved_search_functions(linker)?;
+    register_scheduled_update_functions(linker)?;
+    if capabilities.http {
+        register_http_functions(linker)?;
+    }
     register_crypto_functions(linker)?;
+    register_slug_functions(linker)?;
+    register_vector_functions(linker)?;
+    register_render_functions(linker)?;
     Ok(())
 }
 
@@ -0,0 +1,225 @@
+//! Render host functions for WASM plugins.
+//!
+//! Lets a tap build its output on the host one element at a time instead
+//! of assembling and serializing the whole render tree in plugin memory.
+//! `begin` opens a handle and `append` adds an element to it; the tap then
+//! returns the handle (serialized as `{"#render_handle": id}`), and the
+//! dispatcher replaces that marker with a `container` holding the appended
+//! elements in order.
+//!
+//! Handles belong to a single tap call: the buffers live in the call's
+//! [`PluginState`] and are dropped with its store.
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use trovato_sdk::render::{RenderElement, container};
+use trovato_sdk::types::{RENDER_MAX_BYTES, RENDER_MAX_ELEMENTS, RENDER_MAX_HANDLES};
+use wasmtime::Linker;
+
+use super::read_string_from_memory;
+use crate::plugin::{PluginState, WasmtimeExt};
+
+/// Key of the marker a tap returns in place of a built render.
+const RENDER_HANDLE_KEY: &str = "#render_handle";
+
+/// Render elements appended during one tap call, by handle.
+#[derive(Debug, Default)]
+pub struct RenderBuffers {
+    handles: Vec<Vec<RenderElement>>,
+    bytes: usize,
+}
+
+impl RenderBuffers {
+    /// Open a handle, or return an error code once the limit is reached.
+    fn begin(&mut self) -> i32 {
+        if self.handles.len() >= RENDER_MAX_HANDLES {
+            return host_errors::ERR_RENDER_LIMIT_EXCEEDED;
+        }
+        self.handles.push(Vec::new());
+        self.handles.len() as i32 - 1
+    }
+
+    /// Append an element to a handle; returns 0 or an error code.
+    fn append(&mut self, handle: i32, element_json: &str) -> i32 {
+        let Some(elements) = usize::try_from(handle)
+            .ok()
+            .and_then(|index| self.handles.get_mut(index))
+        else {
+            return host_errors::ERR_RENDER_INVALID_HANDLE;
+        };
+        if elements.len() >= RENDER_MAX_ELEMENTS
+            || self.bytes + element_json.len() > RENDER_MAX_BYTES
+        {
+            return host_errors::ERR_RENDER_LIMIT_EXCEEDED;
+        }
+        let Ok(element) = serde_json::from_str::<RenderElement>(element_json) else {
+            return host_errors::ERR_PARAM_DESERIALIZE;
+        };
+        elements.push(element);
+        self.bytes += element_json.len();
+        0
+    }
+
+    /// Replace a returned render handle marker with the built render.
+    ///
+    /// Any other output is returned unchanged.
+    pub fn resolve(&mut self, output: String) -> String {
+        if self.handles.is_empty() {
+            return output;
+        }
+        let Some(handle) = parse_marker(&output) else {
+            return output;
+        };
+        let Some(elements) = usize::try_from(handle)
+            .ok()
+            .and_then(|index| self.handles.get_mut(index))
+        else {
+            warn!(handle, "tap returned an unknown render handle");
+            return output;
+        };
+
+        // Zero-padded keys keep append order among equal weights.
+        let mut root = container().build();
+        for (index, element) in std::mem::take(elements).into_iter().enumerate() {
+            root.set_child(&format!("{index:05}"), element);
+        }
+        match serde_json::to_string(&root) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!(error = %e, "failed to serialize render output");
+                output
+            }
+        }
+    }
+}
+
+/// The handle ID if `output` is exactly a render handle marker.
+fn parse_marker(output: &str) -> Option<i64> {
+    let value: serde_json::Value = serde_json::from_str(output).ok()?;
+    let object = value.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    object.get(RENDER_HANDLE_KEY)?.as_i64()
+}
+
+/// Register render host functions.
+pub fn register_render_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // begin() -> i32 (handle or negative error)
+    linker
+        .func_wrap(
+            "trovato:kernel/render",
+            "begin",
+            |mut caller: wasmtime::Caller<'_, PluginState>| -> i32 {
+                caller.data_mut().render.begin()
+            },
+        )
+        .into_anyhow()?;
+
+    // append(handle, element) -> i32 (0 or negative error)
+    linker
+        .func_wrap(
+            "trovato:kernel/render",
+            "append",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             handle: i32,
+             element_ptr: i32,
+             element_len: i32|
+             -> i32 {
+                let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                    return host_errors::ERR_MEMORY_MISSING;
+                };
+
+                let Ok(element) =
+                    read_string_from_memory(&memory, &caller, element_ptr, element_len)
+                else {
+                    return host_errors::ERR_PARAM1_READ;
+                };
+
+                caller.data_mut().render.append(handle, &element)
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_render_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).expect("valid engine config");
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_render_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn returned_handle_is_replaced_with_container() {
+        let mut buffers = RenderBuffers::default();
+        let handle = buffers.begin();
+        for text in ["First", "Second"] {
+            let element = format!(r##"{{"#type": "markup", "#tag": "p", "#value": "{text}"}}"##);
+            assert_eq!(buffers.append(handle, &element), 0);
+        }
+
+        let output = buffers.resolve(format!(r##"{{"#render_handle": {handle}}}"##));
+        let root: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(root["#type"], "container");
+        assert_eq!(root["00000"]["#value"], "First");
+        assert_eq!(root["00001"]["#value"], "Second");
+    }
+
+    #[test]
+    fn other_output_is_unchanged() {
+        let mut buffers = RenderBuffers::default();
+        let marker = r##"{"#render_handle": 0}"##.to_string();
+        // No handles opened: the marker is just data.
+        assert_eq!(buffers.resolve(marker.clone()), marker);
+
+        buffers.begin();
+        let output = r##"{"#render_handle": 0, "title": "x"}"##.to_string();
+        assert_eq!(buffers.resolve(output.clone()), output);
+    }
+
+    #[test]
+    fn append_rejects_bad_handles_and_elements() {
+        let mut buffers = RenderBuffers::default();
+        let element = r##"{"#type": "markup"}"##;
+        assert_eq!(
+            buffers.append(0, element),
+            host_errors::ERR_RENDER_INVALID_HANDLE
+        );
+
+        let handle = buffers.begin();
+        assert_eq!(
+            buffers.append(handle, r#"{"value": 1}"#),
+            host_errors::ERR_PARAM_DESERIALIZE
+        );
+
+        let too_large = format!(
+            r##"{{"#type": "markup", "#value": "{}"}}"##,
+            "x".repeat(RENDER_MAX_BYTES)
+        );
+        assert_eq!(
+            buffers.append(handle, &too_large),
+            host_errors::ERR_RENDER_LIMIT_EXCEEDED
+        );
+    }
+
+    #[test]
+    fn begin_is_limited() {
+        let mut buffers = RenderBuffers::default();
+        for _ in 0..RENDER_MAX_HANDLES {
+            assert!(buffers.begin() >= 0);
+        }
+        assert_eq!(buffers.begin(), host_errors::ERR_RENDER_LIMIT_EXCEEDED);
+    }
+}
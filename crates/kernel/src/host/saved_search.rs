@@ -0,0 +1,63 @@
+//! Saved search host functions for WASM plugins.
+//!
+//! `check-alerts` re-runs alert-enabled saved searches and notifies their
+//! owners of new matches (see [`crate::services::saved_search`]). The
+//! `trovato_saved_searches` plugin calls it from `tap_cron`; outside cron
+//! the saved search service is not available.
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use wasmtime::Linker;
+
+use crate::plugin::{PluginState, WasmtimeExt};
+
+/// Register saved search host functions.
+pub fn register_saved_search_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // check-alerts() -> i32 (alerts sent or negative error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/saved-search",
+            "check-alerts",
+            |caller: wasmtime::Caller<'_, PluginState>, (): ()| {
+                Box::new(async move {
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let Some(saved_searches) = services.saved_searches.clone() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let http = services.http.clone();
+
+                    match saved_searches.check_alerts(&http).await {
+                        Ok(sent) => i32::try_from(sent).unwrap_or(i32::MAX),
+                        Err(e) => {
+                            warn!(error = %e, "check-alerts: saved search check failed");
+                            host_errors::ERR_SQL_FAILED
+                        }
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_saved_search_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).unwrap();
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_saved_search_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+}
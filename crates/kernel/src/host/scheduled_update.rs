@@ -0,0 +1,65 @@
+//! Scheduled update host functions for WASM plugins.
+//!
+//! `apply-due` applies the scheduled field changes whose time has passed
+//! (see [`crate::services::scheduled_update`]). The
+//! `trovato_scheduled_updates` plugin calls it from `tap_cron`; outside
+//! cron the scheduled update service is not available.
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use wasmtime::Linker;
+
+use crate::plugin::{PluginState, WasmtimeExt};
+
+/// Register scheduled update host functions.
+pub fn register_scheduled_update_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // apply-due() -> i32 (updates applied or negative error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/scheduled-update",
+            "apply-due",
+            |caller: wasmtime::Caller<'_, PluginState>, (): ()| {
+                Box::new(async move {
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let Some(scheduled_updates) = services.scheduled_updates.clone() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+
+                    match scheduled_updates
+                        .apply_due(chrono::Utc::now().timestamp())
+                        .await
+                    {
+                        Ok(applied) => i32::try_from(applied).unwrap_or(i32::MAX),
+                        Err(e) => {
+                            warn!(error = %e, "apply-due: scheduled updates failed");
+                            host_errors::ERR_SQL_FAILED
+                        }
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_scheduled_update_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).unwrap();
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_scheduled_update_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+}
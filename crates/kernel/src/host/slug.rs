@@ -0,0 +1,87 @@
+//! Slug host functions for WASM plugins.
+//!
+//! Exposes the kernel's language-aware slug generator so plugins that
+//! build their own paths produce the same slugs as pathauto, using the
+//! site-wide separator, length and stopword settings.
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use wasmtime::Linker;
+
+use super::{read_string_from_memory, write_string_to_memory};
+use crate::plugin::{PluginState, WasmtimeExt};
+use crate::services::slug::{SlugOptions, slugify};
+
+/// Register slug host functions.
+pub fn register_slug_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // slugify(text, lang) -> string (bytes written or negative error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/slug",
+            "slugify",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (text_ptr, text_len, lang_ptr, lang_len, out_ptr, out_max_len): (
+                i32,
+                i32,
+                i32,
+                i32,
+                i32,
+                i32,
+            )| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(text) = read_string_from_memory(&memory, &caller, text_ptr, text_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let Ok(lang) = read_string_from_memory(&memory, &caller, lang_ptr, lang_len)
+                    else {
+                        return host_errors::ERR_PARAM2_OR_OUTPUT;
+                    };
+
+                    // Site settings apply when services are available; otherwise
+                    // (e.g. during plugin install) fall back to defaults.
+                    let options = match caller.data().request.services() {
+                        Some(services) => {
+                            let pool = services.db.clone();
+                            SlugOptions::load(&pool).await.unwrap_or_else(|e| {
+                                warn!(error = %e, "failed to load slug settings");
+                                SlugOptions::default()
+                            })
+                        }
+                        None => SlugOptions::default(),
+                    };
+
+                    let slug = slugify(&text, &lang, &options);
+
+                    write_string_to_memory(&memory, &mut caller, out_ptr, out_max_len, &slug)
+                        .unwrap_or(host_errors::ERR_PARAM2_OR_OUTPUT)
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_slug_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).expect("valid engine config");
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_slug_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,178 @@
+//! Embedding host functions for WASM plugins.
+//!
+//! Store and search item embeddings via pgvector (see
+//! [`crate::search::vector`]). Each plugin has its own namespace, so a
+//! plugin only ever searches the vectors it stored itself.
+
+use anyhow::Result;
+use tracing::warn;
+use trovato_sdk::host_errors;
+use uuid::Uuid;
+use wasmtime::Linker;
+
+use super::db::sql_error_code;
+use super::{read_string_from_memory, write_string_to_memory};
+use crate::content::item_query::ItemQueryAccess;
+use crate::plugin::{PluginState, WasmtimeExt};
+use crate::search::vector::{self, EmbeddingError};
+
+/// Map an embedding error to a host error code.
+fn error_code(e: &EmbeddingError) -> i32 {
+    match e {
+        EmbeddingError::Unavailable => host_errors::ERR_VECTOR_UNAVAILABLE,
+        EmbeddingError::Invalid(_) => host_errors::ERR_VECTOR_INVALID,
+        // The item_embeddings foreign key: the item doesn't exist.
+        EmbeddingError::Database(e)
+            if e.as_database_error().and_then(|db| db.code()).as_deref() == Some("23503") =>
+        {
+            host_errors::ERR_NOT_FOUND
+        }
+        EmbeddingError::Database(e) => sql_error_code(e),
+    }
+}
+
+/// Register embedding host functions.
+pub fn register_vector_functions(linker: &mut Linker<PluginState>) -> Result<()> {
+    // embedding-store(item_id, vector_json) -> i32 (0 or error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/vector",
+            "embedding-store",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (id_ptr, id_len, vec_ptr, vec_len): (i32, i32, i32, i32)| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(id_str) = read_string_from_memory(&memory, &caller, id_ptr, id_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let Ok(id) = id_str.parse::<Uuid>() else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+
+                    let Ok(vector_json) =
+                        read_string_from_memory(&memory, &caller, vec_ptr, vec_len)
+                    else {
+                        return host_errors::ERR_PARAM2_OR_OUTPUT;
+                    };
+                    let Ok(vector) = serde_json::from_str::<Vec<f32>>(&vector_json) else {
+                        return host_errors::ERR_PARAM_DESERIALIZE;
+                    };
+
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let pool = services.db.clone();
+                    let namespace = caller.data().plugin_name.clone();
+
+                    match vector::store(&pool, &namespace, id, &vector).await {
+                        Ok(()) => 0,
+                        Err(e) => {
+                            warn!(
+                                plugin = %namespace,
+                                item_id = %id,
+                                error = %e,
+                                "embedding-store failed"
+                            );
+                            error_code(&e)
+                        }
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    // embedding-search(vector_json, k, out) -> i32 (bytes written or error)
+    linker
+        .func_wrap_async(
+            "trovato:kernel/vector",
+            "embedding-search",
+            |mut caller: wasmtime::Caller<'_, PluginState>,
+             (vec_ptr, vec_len, k, out_ptr, out_max_len): (i32, i32, i32, i32, i32)| {
+                Box::new(async move {
+                    let Some(wasmtime::Extern::Memory(memory)) = caller.get_export("memory") else {
+                        return host_errors::ERR_MEMORY_MISSING;
+                    };
+
+                    let Ok(vector_json) =
+                        read_string_from_memory(&memory, &caller, vec_ptr, vec_len)
+                    else {
+                        return host_errors::ERR_PARAM1_READ;
+                    };
+                    let Ok(vector) = serde_json::from_str::<Vec<f32>>(&vector_json) else {
+                        return host_errors::ERR_PARAM_DESERIALIZE;
+                    };
+
+                    let Some(services) = caller.data().request.services() else {
+                        return host_errors::ERR_NO_SERVICES;
+                    };
+                    let pool = services.db.clone();
+                    let access =
+                        ItemQueryAccess::for_user(&caller.data().request.user, services.background);
+                    let namespace = caller.data().plugin_name.clone();
+                    let k = u32::try_from(k).unwrap_or(0);
+
+                    let matches = match vector::search(&pool, &namespace, &vector, k, access).await
+                    {
+                        Ok(matches) => matches,
+                        Err(e) => {
+                            warn!(plugin = %namespace, error = %e, "embedding-search failed");
+                            return error_code(&e);
+                        }
+                    };
+
+                    match serde_json::to_string(&matches) {
+                        Ok(json) => write_string_to_memory(
+                            &memory,
+                            &mut caller,
+                            out_ptr,
+                            out_max_len,
+                            &json,
+                        )
+                        .unwrap_or(host_errors::ERR_PARAM2_OR_OUTPUT),
+                        Err(_) => host_errors::ERR_SERIALIZE_FAILED,
+                    }
+                })
+            },
+        )
+        .into_anyhow()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use wasmtime::Engine;
+
+    #[test]
+    fn register_vector_succeeds() {
+        let config = wasmtime::Config::new();
+        let engine = Engine::new(&config).expect("valid engine config");
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+
+        let result = register_vector_functions(&mut linker);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn errors_map_to_host_codes() {
+        assert_eq!(
+            error_code(&EmbeddingError::Unavailable),
+            host_errors::ERR_VECTOR_UNAVAILABLE
+        );
+        assert_eq!(
+            error_code(&EmbeddingError::Invalid("vector is empty")),
+            host_errors::ERR_VECTOR_INVALID
+        );
+        assert_eq!(
+            error_code(&EmbeddingError::Database(sqlx::Error::PoolTimedOut)),
+            host_errors::ERR_SQL_FAILED
+        );
+    }
+}
@@ -6,6 +6,7 @@
 // Items exposed for integration tests and plugin use; not all are consumed by the binary.
 #![allow(dead_code)]
 
+pub mod backup;
 pub mod batch;
 pub mod cache;
 pub mod circuit_breaker;
@@ -26,12 +27,15 @@ pub mod middleware;
 pub mod models;
 pub mod permissions;
 pub mod plugin;
+pub mod profiling;
+pub mod redis_manager;
 pub mod routes;
 pub mod search;
 pub mod services;
 pub mod session;
 pub mod stage;
 pub mod state;
+pub mod static_export;
 pub mod tap;
 pub mod theme;
 
@@ -44,6 +48,6 @@ pub use config_storage::{
 pub use models::stage::{LIVE_STAGE_ID, StageVisibility};
 pub use stage::{
     ConflictInfo, ConflictResolution, ConflictType, PublishPhase, PublishResult, Resolution,
-    StageService,
+    StagePublishSchedule, StageService,
 };
 pub use state::AppState;
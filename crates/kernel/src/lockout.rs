@@ -1,40 +1,456 @@
 //! Account lockout service using Redis.
 //!
-//! Tracks failed login attempts and temporarily locks accounts
-//! after too many failures.
+//! Tracks failed login attempts and temporarily locks accounts after too
+//! many failures. Failures are also counted per client IP with separate,
+//! usually higher, limits, so one address guessing passwords across many
+//! accounts is stopped too. Addresses in the allowlist (trusted networks
+//! such as an office range or a monitoring host) are never IP-locked;
+//! their accounts are still locked as usual.
+//!
+//! The thresholds live in `site_config` under `lockout_settings`:
+//!
+//! ```json
+//! {
+//!   "max_attempts": 5, "lockout_secs": 900, "attempt_window_secs": 900,
+//!   "ip_max_attempts": 20, "ip_lockout_secs": 900, "ip_attempt_window_secs": 900,
+//!   "ip_allowlist": ["10.0.0.0/8", "192.0.2.10"]
+//! }
+//! ```
+//!
+//! Missing fields keep their defaults.
+
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
+use moka::future::Cache;
 use redis::AsyncCommands;
-use redis::Client as RedisClient;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::models::SiteConfig;
+use crate::redis_manager::RedisManager;
+
+/// `site_config` key holding [`LockoutSettings`].
+pub const LOCKOUT_SETTINGS_KEY: &str = "lockout_settings";
+
+/// Longest lockout or attempt window that may be configured (one week).
+pub const MAX_DURATION_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// How long loaded settings are cached before re-reading `site_config`.
+const SETTINGS_TTL: Duration = Duration::from_secs(30);
+
+/// Lockout thresholds and the IP allowlist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockoutSettings {
+    /// Failed attempts on one account before it is locked.
+    pub max_attempts: u32,
+    /// How long a locked account stays locked.
+    pub lockout_secs: u64,
+    /// Window in which failed attempts on one account are counted.
+    pub attempt_window_secs: u64,
+    /// Failed attempts from one IP address before it is locked.
+    pub ip_max_attempts: u32,
+    /// How long a locked IP address stays locked.
+    pub ip_lockout_secs: u64,
+    /// Window in which failed attempts from one IP address are counted.
+    pub ip_attempt_window_secs: u64,
+    /// Addresses and CIDR ranges that are never IP-locked.
+    pub ip_allowlist: Vec<String>,
+}
+
+impl Default for LockoutSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            lockout_secs: 15 * 60,
+            attempt_window_secs: 15 * 60,
+            ip_max_attempts: 20,
+            ip_lockout_secs: 15 * 60,
+            ip_attempt_window_secs: 15 * 60,
+            ip_allowlist: Vec::new(),
+        }
+    }
+}
+
+impl LockoutSettings {
+    /// Check ranges and allowlist entries.
+    ///
+    /// Returns the offending field and a message.
+    pub fn validate(&self) -> Result<(), (&'static str, String)> {
+        for (field, value) in [
+            ("max_attempts", self.max_attempts),
+            ("ip_max_attempts", self.ip_max_attempts),
+        ] {
+            if value == 0 {
+                return Err((field, "must be at least 1".to_string()));
+            }
+        }
+        for (field, value) in [
+            ("lockout_secs", self.lockout_secs),
+            ("attempt_window_secs", self.attempt_window_secs),
+            ("ip_lockout_secs", self.ip_lockout_secs),
+            ("ip_attempt_window_secs", self.ip_attempt_window_secs),
+        ] {
+            if value == 0 || value > MAX_DURATION_SECS {
+                return Err((field, format!("must be between 1 and {MAX_DURATION_SECS}")));
+            }
+        }
+        for entry in &self.ip_allowlist {
+            if entry.parse::<IpNetwork>().is_err() {
+                return Err((
+                    "ip_allowlist",
+                    format!("'{entry}' is not an IP address or CIDR range"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `ip` falls in the allowlist.
+    ///
+    /// Entries that do not parse are ignored.
+    pub fn is_allowlisted(&self, ip: IpAddr) -> bool {
+        self.ip_allowlist
+            .iter()
+            .filter_map(|entry| entry.parse::<IpNetwork>().ok())
+            .any(|network| network.contains(ip))
+    }
+
+    /// Attempt limit, lockout duration and window for a scope.
+    fn limits(&self, scope: LockoutScope) -> (u32, u64, u64) {
+        match scope {
+            LockoutScope::Account => (
+                self.max_attempts,
+                self.lockout_secs,
+                self.attempt_window_secs,
+            ),
+            LockoutScope::Ip => (
+                self.ip_max_attempts,
+                self.ip_lockout_secs,
+                self.ip_attempt_window_secs,
+            ),
+        }
+    }
+}
+
+/// An IP address or CIDR range such as `10.0.0.0/8` or `2001:db8::/32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// Whether `ip` is inside this network.
+    ///
+    /// IPv4-mapped IPv6 addresses match IPv4 networks.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().context("invalid IP address")?;
+        let addr = addr.to_canonical();
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse::<u8>().context("invalid prefix length")?,
+            None => max_prefix,
+        };
+        anyhow::ensure!(prefix <= max_prefix, "prefix length out of range");
+        Ok(Self { addr, prefix })
+    }
+}
+
+/// What a lockout applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockoutScope {
+    /// A username.
+    Account,
+    /// A client IP address.
+    Ip,
+}
+
+impl LockoutScope {
+    fn attempts_key(self, subject: &str) -> String {
+        match self {
+            Self::Account => format!("lockout:attempts:{subject}"),
+            Self::Ip => format!("lockout:ip_attempts:{subject}"),
+        }
+    }
+
+    fn locked_prefix(self) -> &'static str {
+        match self {
+            Self::Account => "lockout:locked:",
+            Self::Ip => "lockout:ip_locked:",
+        }
+    }
+
+    fn locked_key(self, subject: &str) -> String {
+        format!("{}{subject}", self.locked_prefix())
+    }
+}
 
-/// Maximum failed attempts before lockout.
-const MAX_FAILED_ATTEMPTS: u32 = 5;
+impl FromStr for LockoutScope {
+    type Err = anyhow::Error;
 
-/// Lockout duration in seconds (15 minutes).
-const LOCKOUT_DURATION_SECS: u64 = 15 * 60;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "account" => Ok(Self::Account),
+            "ip" => Ok(Self::Ip),
+            other => anyhow::bail!("unknown lockout scope '{other}'"),
+        }
+    }
+}
 
-/// Failed attempt tracking window in seconds (15 minutes).
-const ATTEMPT_WINDOW_SECS: u64 = 15 * 60;
+/// A lock currently in force.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveLockout {
+    pub scope: LockoutScope,
+    /// Username or IP address.
+    pub subject: String,
+    /// Seconds until the lock expires.
+    pub remaining_secs: u64,
+}
 
 /// Account lockout service.
 #[derive(Clone)]
 pub struct LockoutService {
-    redis: RedisClient,
+    redis: RedisManager,
+    pool: PgPool,
+    settings: Cache<(), Arc<LockoutSettings>>,
 }
 
 impl LockoutService {
     /// Create a new lockout service.
-    pub fn new(redis: RedisClient) -> Self {
-        Self { redis }
+    pub fn new(redis: RedisManager, pool: PgPool) -> Self {
+        Self {
+            redis,
+            pool,
+            settings: Cache::builder()
+                .max_capacity(1)
+                .time_to_live(SETTINGS_TTL)
+                .build(),
+        }
+    }
+
+    /// Current settings (cached).
+    ///
+    /// An unreadable or invalid setting falls back to the defaults so that
+    /// login keeps working; a warning is logged.
+    pub async fn settings(&self) -> Arc<LockoutSettings> {
+        if let Some(settings) = self.settings.get(&()).await {
+            return settings;
+        }
+        let settings = match SiteConfig::get(&self.pool, LOCKOUT_SETTINGS_KEY).await {
+            Ok(Some(value)) => serde_json::from_value(value).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "invalid lockout_settings; using defaults");
+                LockoutSettings::default()
+            }),
+            Ok(None) => LockoutSettings::default(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load lockout_settings; using defaults");
+                LockoutSettings::default()
+            }
+        };
+        let settings = Arc::new(settings);
+        self.settings.insert((), settings.clone()).await;
+        settings
+    }
+
+    /// Validate and persist settings.
+    pub async fn save_settings(&self, settings: &LockoutSettings) -> Result<()> {
+        settings
+            .validate()
+            .map_err(|(field, message)| anyhow::anyhow!("{field} {message}"))?;
+        SiteConfig::set(
+            &self.pool,
+            LOCKOUT_SETTINGS_KEY,
+            serde_json::to_value(settings)?,
+        )
+        .await?;
+        self.settings.invalidate(&()).await;
+        Ok(())
     }
 
     /// Check if an account is currently locked.
     pub async fn is_locked(&self, username: &str) -> Result<bool> {
-        let key = lockout_key(username);
+        self.is_subject_locked(LockoutScope::Account, username)
+            .await
+    }
+
+    /// Check if a client IP address is currently locked.
+    ///
+    /// Client identifiers that are not IP addresses (no proxy header and no
+    /// connection address) and allowlisted addresses are never locked.
+    pub async fn is_ip_locked(&self, client_ip: &str) -> Result<bool> {
+        if !self.tracks_ip(client_ip).await {
+            return Ok(false);
+        }
+        self.is_subject_locked(LockoutScope::Ip, client_ip).await
+    }
+
+    /// Record a failed login attempt.
+    ///
+    /// Returns (is_now_locked, attempts_remaining).
+    pub async fn record_failed_attempt(&self, username: &str) -> Result<(bool, u32)> {
+        self.record(LockoutScope::Account, username).await
+    }
+
+    /// Record a failed login attempt from a client IP address.
+    ///
+    /// Untracked addresses (see [`Self::is_ip_locked`]) are not counted.
+    /// Returns (is_now_locked, attempts_remaining).
+    pub async fn record_failed_ip_attempt(&self, client_ip: &str) -> Result<(bool, u32)> {
+        if !self.tracks_ip(client_ip).await {
+            let settings = self.settings().await;
+            return Ok((false, settings.ip_max_attempts));
+        }
+        self.record(LockoutScope::Ip, client_ip).await
+    }
+
+    /// Clear failed attempts after successful login.
+    pub async fn clear_attempts(&self, username: &str) -> Result<()> {
+        let attempts_key = LockoutScope::Account.attempts_key(username);
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        conn.del::<_, ()>(&attempts_key)
+            .await
+            .context("failed to clear attempt counter")?;
+
+        Ok(())
+    }
+
+    /// Clear all lockout state (both attempts and lock) for a user.
+    pub async fn clear_all(&self, username: &str) -> Result<()> {
+        self.clear(LockoutScope::Account, username).await
+    }
+
+    /// Clear all lockout state (both attempts and lock) for a subject.
+    pub async fn clear(&self, scope: LockoutScope, subject: &str) -> Result<()> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        conn.del::<_, ()>(&scope.attempts_key(subject))
+            .await
+            .context("failed to clear attempt counter")?;
+        conn.del::<_, ()>(&scope.locked_key(subject))
+            .await
+            .context("failed to clear lockout flag")?;
+
+        Ok(())
+    }
+
+    /// Get remaining lockout time in seconds.
+    pub async fn get_lockout_remaining(&self, username: &str) -> Result<Option<u64>> {
+        self.remaining(LockoutScope::Account, username).await
+    }
+
+    /// Get remaining lockout time in seconds for a client IP address.
+    pub async fn get_ip_lockout_remaining(&self, client_ip: &str) -> Result<Option<u64>> {
+        self.remaining(LockoutScope::Ip, client_ip).await
+    }
+
+    /// All accounts and IP addresses currently locked, accounts first.
+    pub async fn active_lockouts(&self) -> Result<Vec<ActiveLockout>> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .context("failed to get Redis connection")?;
+
+        let mut lockouts = Vec::new();
+        for scope in [LockoutScope::Account, LockoutScope::Ip] {
+            let prefix = scope.locked_prefix();
+            let mut keys = Vec::new();
+            let mut cursor = 0u64;
+            loop {
+                let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(format!("{prefix}*"))
+                    .arg("COUNT")
+                    .arg(100)
+                    .query_async(&mut conn)
+                    .await
+                    .context("failed to scan lockout keys")?;
+                keys.extend(batch);
+                cursor = next_cursor;
+                if cursor == 0 {
+                    break;
+                }
+            }
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let ttl: i64 = conn.ttl(&key).await.context("failed to get lockout TTL")?;
+                // Expired between SCAN and TTL.
+                if ttl <= 0 {
+                    continue;
+                }
+                if let Some(subject) = key.strip_prefix(prefix) {
+                    lockouts.push(ActiveLockout {
+                        scope,
+                        subject: subject.to_string(),
+                        remaining_secs: ttl as u64,
+                    });
+                }
+            }
+        }
+
+        Ok(lockouts)
+    }
+
+    /// Whether failures from `client_ip` are tracked.
+    async fn tracks_ip(&self, client_ip: &str) -> bool {
+        match client_ip.parse::<IpAddr>() {
+            Ok(ip) => !self.settings().await.is_allowlisted(ip),
+            Err(_) => false,
+        }
+    }
+
+    async fn is_subject_locked(&self, scope: LockoutScope, subject: &str) -> Result<bool> {
+        let key = scope.locked_key(subject);
+
+        let mut conn = self
+            .redis
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -46,16 +462,14 @@ impl LockoutService {
         Ok(locked)
     }
 
-    /// Record a failed login attempt.
-    ///
-    /// Returns (is_now_locked, attempts_remaining).
-    pub async fn record_failed_attempt(&self, username: &str) -> Result<(bool, u32)> {
-        let attempts_key = attempts_key(username);
-        let lockout_key = lockout_key(username);
+    async fn record(&self, scope: LockoutScope, subject: &str) -> Result<(bool, u32)> {
+        let (max_attempts, lockout_secs, window_secs) = self.settings().await.limits(scope);
+        let attempts_key = scope.attempts_key(subject);
+        let lockout_key = scope.locked_key(subject);
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -67,15 +481,15 @@ impl LockoutService {
 
         // Set TTL on first attempt
         if attempts == 1 {
-            conn.expire::<_, ()>(&attempts_key, ATTEMPT_WINDOW_SECS as i64)
+            conn.expire::<_, ()>(&attempts_key, window_secs as i64)
                 .await
                 .context("failed to set attempt expiry")?;
         }
 
         // Check if we should lock
-        if attempts >= MAX_FAILED_ATTEMPTS {
+        if attempts >= max_attempts {
             // Set lockout flag
-            conn.set_ex::<_, _, ()>(&lockout_key, "locked", LOCKOUT_DURATION_SECS)
+            conn.set_ex::<_, _, ()>(&lockout_key, "locked", lockout_secs)
                 .await
                 .context("failed to set lockout")?;
 
@@ -84,74 +498,121 @@ impl LockoutService {
                 .await
                 .context("failed to clear attempt counter")?;
 
-            tracing::warn!(username = %username, "account locked due to failed attempts");
+            match scope {
+                LockoutScope::Account => {
+                    tracing::warn!(username = %subject, "account locked due to failed attempts");
+                }
+                LockoutScope::Ip => {
+                    tracing::warn!(client_ip = %subject, "IP address locked due to failed attempts");
+                }
+            }
 
             return Ok((true, 0));
         }
 
-        let remaining = MAX_FAILED_ATTEMPTS - attempts;
+        let remaining = max_attempts - attempts;
         Ok((false, remaining))
     }
 
-    /// Clear failed attempts after successful login.
-    pub async fn clear_attempts(&self, username: &str) -> Result<()> {
-        let attempts_key = attempts_key(username);
+    async fn remaining(&self, scope: LockoutScope, subject: &str) -> Result<Option<u64>> {
+        let key = scope.locked_key(subject);
 
         let mut conn = self
             .redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
-        conn.del::<_, ()>(&attempts_key)
-            .await
-            .context("failed to clear attempt counter")?;
+        let ttl: i64 = conn.ttl(&key).await.context("failed to get lockout TTL")?;
 
-        Ok(())
+        if ttl > 0 {
+            Ok(Some(ttl as u64))
+        } else {
+            Ok(None)
+        }
     }
+}
 
-    /// Clear all lockout state (both attempts and lock) for a user.
-    pub async fn clear_all(&self, username: &str) -> Result<()> {
-        let mut conn = self
-            .redis
-            .get_multiplexed_async_connection()
-            .await
-            .context("failed to get Redis connection")?;
-
-        conn.del::<_, ()>(&attempts_key(username))
-            .await
-            .context("failed to clear attempt counter")?;
-        conn.del::<_, ()>(&lockout_key(username))
-            .await
-            .context("failed to clear lockout flag")?;
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_when_fields_missing() {
+        let settings: LockoutSettings = serde_json::from_str(r#"{"ip_max_attempts": 50}"#).unwrap();
+        assert_eq!(settings.max_attempts, 5);
+        assert_eq!(settings.lockout_secs, 900);
+        assert_eq!(settings.ip_max_attempts, 50);
+        assert!(settings.validate().is_ok());
+    }
 
-        Ok(())
+    #[test]
+    fn validate_rejects_bad_values() {
+        let settings = LockoutSettings {
+            max_attempts: 0,
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().unwrap_err().0, "max_attempts");
+
+        let settings = LockoutSettings {
+            ip_lockout_secs: MAX_DURATION_SECS + 1,
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().unwrap_err().0, "ip_lockout_secs");
+
+        let settings = LockoutSettings {
+            ip_allowlist: vec!["10.0.0.0/33".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().unwrap_err().0, "ip_allowlist");
     }
 
-    /// Get remaining lockout time in seconds.
-    pub async fn get_lockout_remaining(&self, username: &str) -> Result<Option<u64>> {
-        let key = lockout_key(username);
+    #[test]
+    fn network_matching() {
+        let net: IpNetwork = "10.1.0.0/16".parse().unwrap();
+        assert!(net.contains("10.1.200.3".parse().unwrap()));
+        assert!(!net.contains("10.2.0.1".parse().unwrap()));
+        assert!(net.contains("::ffff:10.1.0.9".parse().unwrap()));
+        assert!(!net.contains("2001:db8::1".parse().unwrap()));
 
-        let mut conn = self
-            .redis
-            .get_multiplexed_async_connection()
-            .await
-            .context("failed to get Redis connection")?;
+        let host: IpNetwork = "192.0.2.10".parse().unwrap();
+        assert!(host.contains("192.0.2.10".parse().unwrap()));
+        assert!(!host.contains("192.0.2.11".parse().unwrap()));
 
-        let ttl: i64 = conn.ttl(&key).await.context("failed to get lockout TTL")?;
+        let any: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("203.0.113.7".parse().unwrap()));
 
-        if ttl > 0 {
-            Ok(Some(ttl as u64))
-        } else {
-            Ok(None)
-        }
+        let v6: IpNetwork = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains("2001:db8:ffff::1".parse().unwrap()));
+        assert!(!v6.contains("2001:db9::1".parse().unwrap()));
+
+        assert!("not-an-ip".parse::<IpNetwork>().is_err());
+        assert!("::1/129".parse::<IpNetwork>().is_err());
     }
-}
 
-fn attempts_key(username: &str) -> String {
-    format!("lockout:attempts:{username}")
-}
+    #[test]
+    fn allowlist_ignores_unparsable_entries() {
+        let settings = LockoutSettings {
+            ip_allowlist: vec!["bogus".to_string(), "192.0.2.0/24".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.is_allowlisted("192.0.2.99".parse().unwrap()));
+        assert!(!settings.is_allowlisted("198.51.100.1".parse().unwrap()));
+    }
 
-fn lockout_key(username: &str) -> String {
-    format!("lockout:locked:{username}")
+    #[test]
+    fn scope_keys() {
+        assert_eq!(
+            LockoutScope::Account.locked_key("alice"),
+            "lockout:locked:alice"
+        );
+        assert_eq!(
+            LockoutScope::Ip.attempts_key("192.0.2.1"),
+            "lockout:ip_attempts:192.0.2.1"
+        );
+        assert_eq!("ip".parse::<LockoutScope>().unwrap(), LockoutScope::Ip);
+        assert!("user".parse::<LockoutScope>().is_err());
+    }
 }
@@ -7,6 +7,7 @@
 // for the binary target; the lib target (lib.rs) maintains stricter checking.
 #![allow(dead_code, unused_imports)]
 
+mod backup;
 mod batch;
 mod cache;
 mod circuit_breaker;
@@ -27,12 +28,15 @@ mod middleware;
 mod models;
 mod permissions;
 mod plugin;
+mod profiling;
+mod redis_manager;
 mod routes;
 mod search;
 mod services;
 mod session;
 mod stage;
 mod state;
+mod static_export;
 mod tap;
 mod theme;
 
@@ -81,6 +85,36 @@ enum Commands {
         #[command(subcommand)]
         action: UserAction,
     },
+    /// Read-only mode and per-type write lock commands.
+    ReadOnly {
+        #[command(subcommand)]
+        action: ReadOnlyAction,
+    },
+    /// Static site export commands.
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+    /// Managed file commands.
+    Files {
+        #[command(subcommand)]
+        action: FilesAction,
+    },
+    /// URL alias commands.
+    Alias {
+        #[command(subcommand)]
+        action: AliasAction,
+    },
+    /// Multisite hostname and per-site plugin commands.
+    Site {
+        #[command(subcommand)]
+        action: SiteAction,
+    },
+    /// Backup and restore commands.
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
 }
 
 #[derive(Subcommand)]
@@ -102,6 +136,24 @@ enum ConfigAction {
         /// Validate files without writing to the database.
         #[arg(long)]
         dry_run: bool,
+        /// Import only these entity types or entities (repeatable).
+        #[arg(long, value_name = "TYPE[:ID]")]
+        only: Vec<String>,
+        /// Leave out these entity types or entities (repeatable).
+        #[arg(long, value_name = "TYPE[:ID]")]
+        skip: Vec<String>,
+    },
+    /// Show how YAML files differ from the config in the database.
+    Diff {
+        /// Config directory.
+        #[arg(default_value = "config")]
+        dir: String,
+        /// Compare only these entity types or entities (repeatable).
+        #[arg(long, value_name = "TYPE[:ID]")]
+        only: Vec<String>,
+        /// Leave out these entity types or entities (repeatable).
+        #[arg(long, value_name = "TYPE[:ID]")]
+        skip: Vec<String>,
     },
 }
 
@@ -116,6 +168,122 @@ enum UserAction {
     },
 }
 
+#[derive(Subcommand)]
+enum ReadOnlyAction {
+    /// Reject mutating requests on every instance.
+    On {
+        /// Explanation returned to clients.
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// Allow writes again.
+    Off,
+    /// Show read-only state and locked item types.
+    Status,
+    /// Refuse writes to an item type.
+    Lock {
+        /// Item type machine name.
+        item_type: String,
+    },
+    /// Allow writes to a locked item type.
+    Unlock {
+        /// Item type machine name.
+        item_type: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportAction {
+    /// Render the published live stage to static HTML files.
+    Static {
+        /// Output directory.
+        dir: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum FilesAction {
+    /// Copy managed files to another storage backend and repoint them.
+    Migrate {
+        /// Backend the files are on: local or s3.
+        #[arg(long, default_value = "local")]
+        from: String,
+        /// Backend to move them to: local or s3.
+        #[arg(long, default_value = "s3")]
+        to: String,
+        /// Delete each source file once it has been copied.
+        #[arg(long)]
+        delete_source: bool,
+        /// Count the files that would move without copying anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AliasAction {
+    /// Regenerate pathauto aliases of existing items from their patterns.
+    Regenerate {
+        /// Content type to regenerate (default: every type with a pattern).
+        item_type: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SiteAction {
+    /// List hostnames and the tenants serving them.
+    List,
+    /// Serve a hostname from a tenant, creating the tenant if needed.
+    Add {
+        /// Hostname, e.g. blog.example.com.
+        hostname: String,
+        /// Tenant machine name.
+        tenant: String,
+        /// Human-readable name for a new tenant (default: the hostname).
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Stop serving a hostname. The tenant and its content are kept.
+    Remove {
+        /// Hostname.
+        hostname: String,
+    },
+    /// Turn a plugin back on for a tenant's sites.
+    EnablePlugin {
+        /// Tenant machine name.
+        tenant: String,
+        /// Plugin machine name.
+        plugin: String,
+    },
+    /// Turn a plugin off for a tenant's sites.
+    DisablePlugin {
+        /// Tenant machine name.
+        tenant: String,
+        /// Plugin machine name.
+        plugin: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupAction {
+    /// Write a backup archive of content, config and the file manifest.
+    Create {
+        /// Archive path (default: a timestamped archive in BACKUP_DIR).
+        path: Option<String>,
+        /// Back up only `content` or `config`.
+        #[arg(long, value_name = "PART")]
+        only: Option<String>,
+    },
+    /// Restore a backup archive.
+    Restore {
+        /// Archive path.
+        archive: String,
+        /// Restore only `content` or `config`.
+        #[arg(long, value_name = "PART")]
+        only: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum PluginAction {
     /// Scaffold a new plugin in the plugins/ directory.
@@ -162,6 +330,12 @@ async fn main() -> Result<()> {
         Some(Commands::Plugin { action }) => run_plugin_command(action).await,
         Some(Commands::Config { action }) => run_config_command(action).await,
         Some(Commands::User { action }) => run_user_command(action).await,
+        Some(Commands::ReadOnly { action }) => run_read_only_command(action).await,
+        Some(Commands::Export { action }) => run_export_command(action).await,
+        Some(Commands::Files { action }) => run_files_command(action).await,
+        Some(Commands::Alias { action }) => run_alias_command(action).await,
+        Some(Commands::Site { action }) => run_site_command(action).await,
+        Some(Commands::Backup { action }) => run_backup_command(action).await,
     }
 }
 
@@ -216,15 +390,44 @@ async fn run_server() -> Result<()> {
         });
     }
 
+    // Spawn background task to detect lost Redis connections while idle
+    {
+        let redis = state.redis().clone();
+        let token = shutdown_token.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if !redis.ping().await {
+                            warn!("Redis health check failed");
+                        }
+                    }
+                    _ = token.cancelled() => {
+                        info!("Redis health check shutting down");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
     // Create session layer
     let same_site = match config.cookie_same_site.as_str() {
         "lax" => SameSite::Lax,
         "none" => SameSite::None,
         _ => SameSite::Strict,
     };
-    let session_layer = session::create_session_layer(&config.redis_url, same_site)
-        .await
-        .context("failed to create session layer")?;
+    let session_settings =
+        session::SessionSettings::from_config(&config).context("invalid session settings")?;
+    let session_layer = session::create_session_layer(
+        &session_settings,
+        state.redis().settings(),
+        state.db(),
+        same_site,
+    )
+    .await
+    .context("failed to create session layer")?;
 
     // Log plugin and content type info
     info!(
@@ -236,64 +439,19 @@ async fn run_server() -> Result<()> {
     // Build CORS layer from config
     let cors = build_cors_layer(&config);
 
-    // Build the inner router with all routes (no path alias — that's handled
-    // by the fallback below so it runs BEFORE Axum route matching).
-    let inner_router: Router<AppState> = Router::new()
-        .merge(routes::front::router())
-        .merge(routes::install::router())
-        .merge(routes::auth::router())
-        .merge(routes::admin::router())
-        .merge(routes::password_reset::router())
-        .merge(routes::health::router())
-        .merge(routes::item::router())
-        .merge(routes::gather::router())
-        .merge(routes::gather_admin::router())
-        .merge(routes::plugin_admin::router())
-        .merge(routes::search::router())
-        .merge(routes::cron::router())
-        .merge(routes::file::router())
-        .merge(routes::metrics::router())
-        .merge(routes::batch::router())
-        .merge(routes::api_token::router())
-        .merge(routes::api_ai_assist::router())
-        .merge(routes::api_chat::router())
-        .merge(routes::api_search::router())
-        .merge(routes::api_v1::router())
-        .merge(routes::tile_admin::router())
-        .merge(routes::static_files::router())
-        .merge(routes::sitemap::router())
-        // Plugin-gated routes — runtime middleware returns 404 when disabled.
-        .merge(routes::gated_plugin_routes(&state))
-        // Dynamic gather route aliases from query display configs.
-        .merge(routes::gather_routes::build_gather_route_router(
-            &state.gather().list_queries(),
-        ));
-
-    // Wrap the inner router with state so we can clone it for the fallback.
-    let inner_with_state: Router = inner_router.clone().with_state(state.clone());
-    let shared_router = Arc::new(inner_with_state);
-
-    // Build the outer app with a fallback that handles path alias resolution.
-    // In Axum 0.8, Router::layer() middleware runs AFTER route matching, so
-    // URI rewriting in middleware cannot affect which route is matched.
-    // The fallback receives all unmatched requests and forwards them to the
-    // inner router after resolving any URL alias.
-    let app = inner_router
-        .fallback({
-            let router = shared_router.clone();
-            let app_state = state.clone();
-            move |session: Session, request: axum::extract::Request| {
-                let router = router.clone();
-                let app_state = app_state.clone();
-                async move {
-                    crate::middleware::path_alias_fallback(app_state, session, router, request).await
-                }
-            }
-        })
+    // Build the router with all routes; path aliases are resolved by its
+    // fallback so they run BEFORE Axum route matching.
+    let app = with_path_alias_fallback(app_routes(&state), &state)
         // Middleware layers (last added = first executed in request flow):
-        // TraceLayer → security_headers → CORS → session → rate_limit(per-IP) →
-        // bearer_auth → api_token → rate_limit(per-user) → install_check →
-        // negotiate_language → redirect → routes
+        // TraceLayer → security_headers → CORS → tenant → session →
+        // session_expiry → request_timing → tap_trace → tap_memo →
+        // rate_limit(per-IP) → bearer_auth → api_token → rate_limit(per-user) →
+        // install_check → read_only → deprecations → negotiate_language →
+        // redirect → page_cache → routes
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::serve_page_cache,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             crate::middleware::check_redirect,
@@ -302,6 +460,14 @@ async fn run_server() -> Result<()> {
             state.clone(),
             crate::middleware::negotiate_language,
         ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::track_deprecations,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::enforce_read_only,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             crate::middleware::check_installation,
@@ -322,7 +488,24 @@ async fn run_server() -> Result<()> {
             state.clone(),
             crate::middleware::check_rate_limit,
         ))
+        .layer(axum::middleware::from_fn(crate::middleware::memoize_taps))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::trace_taps,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::track_request_timing,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            session_settings,
+            crate::middleware::apply_session_expiry,
+        ))
         .layer(session_layer)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::resolve_tenant,
+        ))
         .layer(cors)
         .layer(axum::middleware::from_fn(
             crate::middleware::inject_security_headers,
@@ -382,6 +565,75 @@ async fn run_server() -> Result<()> {
     Ok(())
 }
 
+/// All kernel and plugin routes, without middleware.
+fn app_routes(state: &AppState) -> Router<AppState> {
+    let router = Router::new()
+        .merge(routes::front::router())
+        .merge(routes::install::router())
+        .merge(routes::auth::router())
+        .merge(routes::author::router())
+        .merge(routes::admin::router())
+        .merge(routes::password_reset::router())
+        .merge(routes::health::router())
+        .merge(routes::item::router())
+        .merge(routes::jsonapi::router())
+        .merge(routes::menu::router())
+        .merge(routes::gather::router())
+        .merge(routes::gather_admin::router())
+        .merge(routes::plugin_admin::router())
+        .merge(routes::search::router())
+        .merge(routes::mfa::router())
+        .merge(routes::cron::router())
+        .merge(routes::deprecation::router())
+        .merge(routes::status_report::router())
+        .merge(routes::read_only::router())
+        .merge(routes::file::router())
+        .merge(routes::metrics::router())
+        .merge(routes::backup::router())
+        .merge(routes::batch::router())
+        .merge(routes::api_token::router())
+        .merge(routes::api_people::router())
+        .merge(routes::api_admin_content::router())
+        .merge(routes::api_ai_assist::router())
+        .merge(routes::api_chat::router())
+        .merge(routes::api_search::router())
+        .merge(routes::api_v1::router())
+        .merge(routes::webhook::router())
+        .merge(routes::tile_admin::router())
+        .merge(routes::static_files::router())
+        .merge(routes::sitemap::router())
+        // Plugin-gated routes — runtime middleware returns 404 when disabled.
+        .merge(routes::gated_plugin_routes(state))
+        // Dynamic gather route aliases from query display configs.
+        .merge(routes::gather_routes::build_gather_route_router(
+            &state.gather().list_queries(),
+        ));
+    #[cfg(feature = "profiling")]
+    let router = router.merge(routes::profiling::router());
+    router
+}
+
+/// Add the path alias fallback to the inner router.
+///
+/// In Axum 0.8, Router::layer() middleware runs AFTER route matching, so
+/// URI rewriting in middleware cannot affect which route is matched.
+/// The fallback receives all unmatched requests and forwards them to the
+/// inner router after resolving any URL alias.
+fn with_path_alias_fallback(inner_router: Router<AppState>, state: &AppState) -> Router<AppState> {
+    // Wrap the inner router with state so we can clone it for the fallback.
+    let shared_router = Arc::new(inner_router.clone().with_state(state.clone()));
+    let app_state = state.clone();
+    inner_router.fallback(
+        move |session: Session, request: axum::extract::Request| {
+            let router = shared_router.clone();
+            let app_state = app_state.clone();
+            async move {
+                crate::middleware::path_alias_fallback(app_state, session, router, request).await
+            }
+        },
+    )
+}
+
 /// Wait for a shutdown signal (SIGINT or SIGTERM).
 async fn shutdown_signal() {
     let ctrl_c = tokio::signal::ctrl_c();
@@ -471,18 +723,58 @@ async fn run_config_command(action: ConfigAction) -> Result<()> {
             let result = config_storage::yaml::export_config(&storage, &pool, &dir, clean).await?;
             print_config_summary("Exported", &dir, &result.counts, &result.warnings);
         }
-        ConfigAction::Import { dir, dry_run } => {
+        ConfigAction::Import {
+            dir,
+            dry_run,
+            only,
+            skip,
+        } => {
+            let filter = config_storage::yaml::ConfigFilter::new(&only, &skip)?;
+            // Imported entities are checked by enabled plugins, as in the admin UI.
+            let storage = storage.with_validation(load_tap_dispatcher(&config, &pool).await?);
             let dir = std::path::PathBuf::from(dir);
             let result =
-                config_storage::yaml::import_config(&storage, &pool, &dir, dry_run).await?;
+                config_storage::yaml::import_config(&storage, &pool, &dir, dry_run, &filter)
+                    .await?;
             let verb = if dry_run { "Would import" } else { "Imported" };
             print_config_summary(verb, &dir, &result.counts, &result.warnings);
         }
+        ConfigAction::Diff { dir, only, skip } => {
+            let filter = config_storage::yaml::ConfigFilter::new(&only, &skip)?;
+            let dir = std::path::PathBuf::from(dir);
+            let diff = config_storage::yaml::diff_config(&storage, &pool, &dir, &filter).await?;
+            print_config_diff(&dir, &diff);
+        }
     }
 
     Ok(())
 }
 
+/// Load enabled plugins and build a tap dispatcher for a CLI command.
+async fn load_tap_dispatcher(
+    config: &Config,
+    pool: &sqlx::PgPool,
+) -> Result<Arc<tap::TapDispatcher>> {
+    let enabled: std::collections::HashSet<String> = plugin::status::get_enabled_names(pool)
+        .await
+        .context("failed to get enabled plugins")?
+        .into_iter()
+        .collect();
+    let plugin_config = plugin::PluginConfig {
+        cache_dir: config.plugin_cache_dir.clone(),
+        ..Default::default()
+    };
+    let mut runtime =
+        plugin::PluginRuntime::new(&plugin_config).context("failed to create plugin runtime")?;
+    runtime
+        .load_enabled(&config.plugins_dir, &enabled)
+        .await
+        .context("failed to load plugins")?;
+    let runtime = Arc::new(runtime);
+    let registry = Arc::new(tap::TapRegistry::from_plugins(&runtime));
+    Ok(Arc::new(tap::TapDispatcher::new(runtime, registry)))
+}
+
 /// Run a user CLI command with a minimal context (pool only).
 async fn run_user_command(action: UserAction) -> Result<()> {
     let config = Config::from_env().context("failed to load configuration")?;
@@ -529,6 +821,381 @@ async fn run_user_command(action: UserAction) -> Result<()> {
     Ok(())
 }
 
+async fn run_read_only_command(action: ReadOnlyAction) -> Result<()> {
+    use crate::services::read_only::{load_settings, save_settings};
+
+    let config = Config::from_env().context("failed to load configuration")?;
+
+    let pool = db::create_pool(&config)
+        .await
+        .context("failed to create database pool")?;
+
+    db::run_migrations(&pool)
+        .await
+        .context("failed to run migrations")?;
+
+    let mut settings = load_settings(&pool).await?;
+    match action {
+        ReadOnlyAction::On { message } => {
+            settings.enabled = true;
+            if message.is_some() {
+                settings.message = message;
+            }
+            save_settings(&pool, &settings).await?;
+            println!("Read-only mode enabled: {}", settings.message());
+        }
+        ReadOnlyAction::Off => {
+            settings.enabled = false;
+            save_settings(&pool, &settings).await?;
+            println!("Read-only mode disabled.");
+            if config.read_only {
+                println!("Note: READ_ONLY is set in this environment and still applies.");
+            }
+        }
+        ReadOnlyAction::Status => {
+            let state = if settings.enabled || config.read_only {
+                "on"
+            } else {
+                "off"
+            };
+            println!("Read-only: {state}");
+            if config.read_only {
+                println!("  forced by READ_ONLY");
+            }
+            println!("  message: {}", settings.message());
+            if settings.locked_item_types.is_empty() {
+                println!("  locked item types: none");
+            } else {
+                println!(
+                    "  locked item types: {}",
+                    settings.locked_item_types.join(", ")
+                );
+            }
+        }
+        ReadOnlyAction::Lock { item_type } => {
+            if !settings.is_type_locked(&item_type) {
+                settings.locked_item_types.push(item_type.clone());
+                save_settings(&pool, &settings).await?;
+            }
+            println!("Writes to '{item_type}' items are locked.");
+        }
+        ReadOnlyAction::Unlock { item_type } => {
+            settings.locked_item_types.retain(|t| *t != item_type);
+            save_settings(&pool, &settings).await?;
+            println!("Writes to '{item_type}' items are allowed.");
+        }
+    }
+    println!("Running instances pick up the change within a few seconds.");
+
+    Ok(())
+}
+
+/// Run an export CLI command with the full application state.
+///
+/// Pages are rendered through the site router, so plugins, themes and
+/// path aliases behave exactly as they do when serving.
+async fn run_export_command(action: ExportAction) -> Result<()> {
+    let config = Config::from_env().context("failed to load configuration")?;
+
+    let state = AppState::new(&config)
+        .await
+        .context("failed to initialize application state")?;
+
+    // Anonymous visitors only: an in-memory session store is enough, and
+    // language negotiation keeps prefixed aliases working.
+    let app = with_path_alias_fallback(app_routes(&state), &state)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::negotiate_language,
+        ))
+        .layer(tower_sessions::SessionManagerLayer::new(
+            tower_sessions::MemoryStore::default(),
+        ))
+        .with_state(state.clone());
+
+    match action {
+        ExportAction::Static { dir } => {
+            let dir = std::path::PathBuf::from(dir);
+            let summary = static_export::export_static(&state, app, &dir).await?;
+            println!(
+                "Exported {} pages and {} files ({})",
+                summary.pages,
+                summary.files,
+                dir.display()
+            );
+            if !summary.skipped.is_empty() {
+                println!("{} path(s) skipped:", summary.skipped.len());
+                for (path, status) in &summary.skipped {
+                    println!("  {status} {path}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_files_command(action: FilesAction) -> Result<()> {
+    let config = Config::from_env().context("failed to load configuration")?;
+
+    let pool = db::create_pool(&config)
+        .await
+        .context("failed to create database pool")?;
+
+    db::run_migrations(&pool)
+        .await
+        .context("failed to run migrations")?;
+
+    match action {
+        FilesAction::Migrate {
+            from,
+            to,
+            delete_source,
+            dry_run,
+        } => {
+            let source = file::storage_for_backend(&config, &from.to_lowercase()).await?;
+            let target = file::storage_for_backend(&config, &to.to_lowercase()).await?;
+            let summary =
+                file::migrate_storage(&pool, &*source, &*target, delete_source, dry_run).await?;
+
+            let verb = if dry_run { "Would migrate" } else { "Migrated" };
+            println!(
+                "{verb} {} files ({} bytes) from {} to {}",
+                summary.migrated,
+                summary.bytes,
+                source.scheme(),
+                target.scheme()
+            );
+            if !summary.failed.is_empty() {
+                println!("{} file(s) failed:", summary.failed.len());
+                for (id, error) in &summary.failed {
+                    println!("  {id}: {error}");
+                }
+                anyhow::bail!("some files were not migrated; re-run to retry them");
+            }
+            if !dry_run && config.file_storage != target.scheme() {
+                println!(
+                    "Set FILE_STORAGE={} and restart to serve the migrated files.",
+                    target.scheme()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_backup_command(action: BackupAction) -> Result<()> {
+    let config = Config::from_env().context("failed to load configuration")?;
+
+    let pool = db::create_pool(&config)
+        .await
+        .context("failed to create database pool")?;
+
+    db::run_migrations(&pool)
+        .await
+        .context("failed to run migrations")?;
+
+    let storage = config_storage::DirectConfigStorage::new(pool.clone());
+    let files = file::storage_for_backend(&config, &config.file_storage).await?;
+
+    match action {
+        BackupAction::Create { path, only } => {
+            let parts = backup::BackupParts::only(only.as_deref())?;
+            let archive = path.map(std::path::PathBuf::from).unwrap_or_else(|| {
+                config
+                    .backup_dir
+                    .join(backup::backup_name(chrono::Utc::now().timestamp()))
+            });
+            let sources = backup::BackupSources {
+                pool: &pool,
+                database_url: &config.database_url,
+                config: &storage,
+                files: &*files,
+            };
+            let summary = backup::create_backup(&sources, parts, &archive).await?;
+
+            println!(
+                "Backed up {} to {} ({} bytes)",
+                parts.describe(),
+                archive.display(),
+                summary.size
+            );
+            if parts.content {
+                println!(
+                    "  {} content tables, {} managed files",
+                    summary.tables, summary.files
+                );
+            }
+            if parts.config {
+                println!("  {} config entities", summary.config_entities);
+            }
+            for warning in &summary.warnings {
+                println!("  warning: {warning}");
+            }
+        }
+        BackupAction::Restore { archive, only } => {
+            let parts = backup::BackupParts::only(only.as_deref())?;
+            // Restored config is checked by enabled plugins, as on import.
+            let storage = storage.with_validation(load_tap_dispatcher(&config, &pool).await?);
+            let sources = backup::BackupSources {
+                pool: &pool,
+                database_url: &config.database_url,
+                config: &storage,
+                files: &*files,
+            };
+            let archive = std::path::PathBuf::from(archive);
+            let summary = backup::restore_backup(&sources, &archive, parts).await?;
+
+            println!("Restored {} from {}", parts.describe(), archive.display());
+            if let Some(result) = &summary.config {
+                print_config_summary("Imported", &archive, &result.counts, &result.warnings);
+            }
+            if parts.content {
+                println!("  {} content tables reloaded", summary.tables);
+            }
+            if !summary.missing_files.is_empty() || !summary.changed_files.is_empty() {
+                println!(
+                    "{} managed file(s) missing and {} changed in {} storage:",
+                    summary.missing_files.len(),
+                    summary.changed_files.len(),
+                    files.scheme()
+                );
+                for uri in &summary.missing_files {
+                    println!("  missing: {uri}");
+                }
+                for uri in &summary.changed_files {
+                    println!("  changed: {uri}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_alias_command(action: AliasAction) -> Result<()> {
+    let config = Config::from_env().context("failed to load configuration")?;
+
+    let pool = db::create_pool(&config)
+        .await
+        .context("failed to create database pool")?;
+
+    db::run_migrations(&pool)
+        .await
+        .context("failed to run migrations")?;
+
+    match action {
+        AliasAction::Regenerate { item_type } => {
+            let item_types = match item_type {
+                Some(item_type) => vec![item_type],
+                None => {
+                    let mut types: Vec<String> = models::AliasPattern::list_all(&pool)
+                        .await?
+                        .into_iter()
+                        .map(|p| p.item_type)
+                        .collect();
+                    types.sort();
+                    types.dedup();
+                    types
+                }
+            };
+            for item_type in &item_types {
+                let count = services::pathauto::regenerate_aliases(&pool, item_type).await?;
+                println!("{item_type}: {count} aliases created or updated");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_site_command(action: SiteAction) -> Result<()> {
+    use crate::models::{Site, Tenant};
+
+    let config = Config::from_env().context("failed to load configuration")?;
+
+    let pool = db::create_pool(&config)
+        .await
+        .context("failed to create database pool")?;
+
+    db::run_migrations(&pool)
+        .await
+        .context("failed to run migrations")?;
+
+    match action {
+        SiteAction::List => {
+            let tenants: std::collections::HashMap<_, _> = Tenant::list_all(&pool)
+                .await?
+                .into_iter()
+                .map(|t| (t.id, t.machine_name))
+                .collect();
+            let sites = Site::list_all(&pool).await?;
+            if sites.is_empty() {
+                println!("No sites; every hostname is served by the default tenant.");
+            }
+            for site in sites {
+                let tenant = tenants
+                    .get(&site.tenant_id)
+                    .map(String::as_str)
+                    .unwrap_or("?");
+                let disabled = Site::disabled_plugins(&pool, site.tenant_id).await?;
+                if disabled.is_empty() {
+                    println!("{} -> {tenant}", site.hostname);
+                } else {
+                    println!(
+                        "{} -> {tenant} (disabled: {})",
+                        site.hostname,
+                        disabled.join(", ")
+                    );
+                }
+            }
+        }
+        SiteAction::Add {
+            hostname,
+            tenant,
+            name,
+        } => {
+            let tenant = match Tenant::find_by_machine_name(&pool, &tenant).await? {
+                Some(tenant) => tenant,
+                None => {
+                    let name = name.as_deref().unwrap_or(&hostname);
+                    let created = Tenant::create(&pool, name, &tenant).await?;
+                    println!("Created tenant '{}'", created.machine_name);
+                    created
+                }
+            };
+            let site = Site::upsert(&pool, &hostname, tenant.id).await?;
+            println!("{} -> {}", site.hostname, tenant.machine_name);
+        }
+        SiteAction::Remove { hostname } => {
+            if !Site::delete(&pool, &hostname).await? {
+                anyhow::bail!("site '{hostname}' not found");
+            }
+            println!("Removed site '{hostname}'");
+        }
+        SiteAction::EnablePlugin { tenant, plugin } => {
+            let tenant = find_tenant(&pool, &tenant).await?;
+            Site::set_plugin_enabled(&pool, tenant.id, &plugin, true).await?;
+            println!("Enabled '{plugin}' for '{}'", tenant.machine_name);
+        }
+        SiteAction::DisablePlugin { tenant, plugin } => {
+            let tenant = find_tenant(&pool, &tenant).await?;
+            Site::set_plugin_enabled(&pool, tenant.id, &plugin, false).await?;
+            println!("Disabled '{plugin}' for '{}'", tenant.machine_name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Find a tenant by machine name for the site commands.
+async fn find_tenant(pool: &sqlx::PgPool, machine_name: &str) -> Result<models::Tenant> {
+    models::Tenant::find_by_machine_name(pool, machine_name)
+        .await?
+        .with_context(|| format!("tenant '{machine_name}' not found"))
+}
+
 fn print_config_summary(
     verb: &str,
     dir: &std::path::Path,
@@ -548,6 +1215,65 @@ fn print_config_summary(
     }
 }
 
+fn print_config_diff(dir: &std::path::Path, diff: &config_storage::yaml::ConfigDiff) {
+    use config_storage::yaml::DiffKind;
+
+    let marker = |kind: DiffKind| match kind {
+        DiffKind::Added => '+',
+        DiffKind::Changed => '~',
+        DiffKind::Removed => '-',
+    };
+    let value = |v: &Option<serde_json::Value>| {
+        let text = v.as_ref().map(|v| v.to_string()).unwrap_or_default();
+        match text.char_indices().nth(60) {
+            Some((end, _)) => format!("{}...", &text[..end]),
+            None => text,
+        }
+    };
+
+    println!("Config diff ({} -> database)", dir.display());
+    for entity in &diff.entities {
+        let note = match entity.kind {
+            DiffKind::Added => " (new)",
+            DiffKind::Changed => "",
+            DiffKind::Removed => " (only in database; import keeps it)",
+        };
+        println!(
+            "{} {}.{}{note}",
+            marker(entity.kind),
+            entity.entity_type,
+            entity.id
+        );
+        for property in &entity.properties {
+            let change = match property.kind() {
+                DiffKind::Added => value(&property.file),
+                DiffKind::Changed => {
+                    format!("{} -> {}", value(&property.database), value(&property.file))
+                }
+                DiffKind::Removed => value(&property.database),
+            };
+            println!(
+                "    {} {}: {change}",
+                marker(property.kind()),
+                property.path
+            );
+        }
+    }
+    println!(
+        "{} new, {} changed, {} only in database, {} unchanged",
+        diff.count(DiffKind::Added),
+        diff.count(DiffKind::Changed),
+        diff.count(DiffKind::Removed),
+        diff.unchanged
+    );
+    if !diff.warnings.is_empty() {
+        println!("{} warning(s):", diff.warnings.len());
+        for warning in &diff.warnings {
+            println!("  warning: {warning}");
+        }
+    }
+}
+
 fn build_cors_layer(config: &Config) -> CorsLayer {
     let methods = [
         Method::GET,
@@ -562,6 +1288,7 @@ fn build_cors_layer(config: &Config) -> CorsLayer {
             .allow_origin(tower_http::cors::Any)
             .allow_methods(methods)
             .allow_headers(tower_http::cors::Any)
+            .expose_headers([axum::http::header::ETAG])
     } else if config.cors_allowed_origins.is_empty() {
         // No CORS origins configured — block cross-origin requests.
         // Use a restrictive layer that allows nothing from other origins.
@@ -590,7 +1317,10 @@ fn build_cors_layer(config: &Config) -> CorsLayer {
             .allow_headers([
                 axum::http::header::CONTENT_TYPE,
                 axum::http::header::AUTHORIZATION,
+                axum::http::header::IF_NONE_MATCH,
+                axum::http::header::IF_MODIFIED_SINCE,
             ])
+            .expose_headers([axum::http::header::ETAG])
             .allow_credentials(true)
     }
 }
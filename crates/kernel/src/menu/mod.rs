@@ -4,7 +4,10 @@
 //! - Route definitions for the HTTP router
 //! - Navigation structure for admin/frontend
 //! - Permission requirements per route
+//! - Nested, permission-filtered menu trees with an active trail
 
 mod registry;
+mod tree;
 
 pub use registry::{MenuDefinition, MenuRegistry, RouteMatch};
+pub use tree::{MAIN_MENU, MenuTree, MenuTreeNode, active_trail, tree};
@@ -0,0 +1,273 @@
+//! Menu trees built from the flat plugin menu registry.
+//!
+//! Plugins declare menus as flat [`MenuDefinition`]s linked by `parent`
+//! paths. [`tree`] nests them into a navigation tree, drops links the
+//! current user may not access, and marks the active trail: the link for
+//! the current request path and all of its ancestors.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::registry::{MenuDefinition, MenuRegistry};
+
+/// Name of the menu holding every top-level link.
+pub const MAIN_MENU: &str = "main";
+
+/// Maximum nesting depth, guarding against parent cycles in plugin data.
+const MAX_DEPTH: usize = 10;
+
+/// A link in a menu tree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MenuTreeNode {
+    /// Link destination path.
+    pub path: String,
+    /// Display title.
+    pub title: String,
+    /// Sort weight (lower = higher priority).
+    pub weight: i32,
+    /// Whether this link is the current page.
+    pub active: bool,
+    /// Whether this link is the current page or one of its ancestors.
+    pub in_active_trail: bool,
+    /// Child links, sorted by weight then title.
+    pub children: Vec<MenuTreeNode>,
+}
+
+/// A named menu tree for one user and request path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MenuTree {
+    /// Menu name (`main`, or the first segment of the root link's path).
+    pub name: String,
+    /// Paths of the active link and its ancestors, outermost first.
+    pub active_trail: Vec<String>,
+    /// Top-level links.
+    pub items: Vec<MenuTreeNode>,
+}
+
+/// Build the menu tree `name` for `current_path`.
+///
+/// The `main` menu contains every top-level link; any other name selects
+/// the subtree below `/{name}` (e.g. `admin` for `/admin`). Returns `None`
+/// if no such root link exists or the user may not access it.
+///
+/// Only visible `GET` links without path parameters are included, and
+/// local tasks are left to the tab bar. A link whose permission the user
+/// lacks (checked with `can_access`) is dropped along with its children.
+pub fn tree(
+    registry: &MenuRegistry,
+    name: &str,
+    current_path: &str,
+    can_access: impl Fn(&str) -> bool,
+) -> Option<MenuTree> {
+    let is_linkable =
+        |menu: &MenuDefinition| is_navigation_link(menu) && accessible(menu, &can_access);
+
+    let roots: Vec<&MenuDefinition> = if name == MAIN_MENU {
+        registry
+            .all()
+            .filter(|m| m.parent.is_none() && is_linkable(m))
+            .collect()
+    } else {
+        let root = registry.get(&format!("/{name}"))?;
+        if !is_linkable(root) {
+            return None;
+        }
+        registry.children_of(&root.path)
+    };
+
+    let trail = active_trail(registry, current_path);
+    let items = build_nodes(registry, roots, &trail, &is_linkable, 0);
+
+    Some(MenuTree {
+        name: name.to_string(),
+        active_trail: trail,
+        items,
+    })
+}
+
+/// Paths of the registered link for `current_path` and its ancestors.
+///
+/// The link is found by route matching (so `/blog/my-post` matches
+/// `/blog/:slug`), falling back to the longest registered path that is a
+/// prefix of `current_path`. The result is ordered outermost first.
+pub fn active_trail(registry: &MenuRegistry, current_path: &str) -> Vec<String> {
+    let current = registry
+        .match_path(current_path)
+        .map(|m| m.menu)
+        .or_else(|| longest_prefix_match(registry, current_path).cloned());
+
+    let mut trail = Vec::new();
+    let mut next = current;
+    while let Some(menu) = next {
+        if trail.len() >= MAX_DEPTH || trail.contains(&menu.path) {
+            break;
+        }
+        next = menu
+            .parent
+            .as_deref()
+            .and_then(|p| registry.get(p))
+            .cloned();
+        trail.push(menu.path);
+    }
+    trail.reverse();
+    trail
+}
+
+/// The registered link with the longest path that prefixes `path` at a
+/// segment boundary.
+fn longest_prefix_match<'a>(registry: &'a MenuRegistry, path: &str) -> Option<&'a MenuDefinition> {
+    registry
+        .all()
+        .filter(|m| m.path != "/" && is_path_prefix(&m.path, path))
+        .max_by_key(|m| m.path.len())
+}
+
+/// Whether `prefix` is `path` or one of its ancestor paths.
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether a menu definition belongs in a navigation tree.
+fn is_navigation_link(menu: &MenuDefinition) -> bool {
+    menu.visible
+        && !menu.local_task
+        && menu.method.eq_ignore_ascii_case("GET")
+        && !menu.path.contains([':', '{'])
+}
+
+/// Whether the user may follow a link (links without a permission are public).
+fn accessible(menu: &MenuDefinition, can_access: &impl Fn(&str) -> bool) -> bool {
+    menu.permission.is_empty() || can_access(&menu.permission)
+}
+
+/// Convert menu definitions and their descendants into sorted tree nodes.
+fn build_nodes(
+    registry: &MenuRegistry,
+    menus: Vec<&MenuDefinition>,
+    trail: &[String],
+    is_linkable: &impl Fn(&MenuDefinition) -> bool,
+    depth: usize,
+) -> Vec<MenuTreeNode> {
+    if depth >= MAX_DEPTH {
+        return Vec::new();
+    }
+
+    let mut seen = HashSet::new();
+    let mut nodes: Vec<MenuTreeNode> = menus
+        .into_iter()
+        .filter(|m| is_linkable(m) && seen.insert(m.path.as_str()))
+        .map(|menu| MenuTreeNode {
+            path: menu.path.clone(),
+            title: menu.title.clone(),
+            weight: menu.weight,
+            active: trail.last() == Some(&menu.path),
+            in_active_trail: trail.contains(&menu.path),
+            children: build_nodes(
+                registry,
+                registry.children_of(&menu.path),
+                trail,
+                is_linkable,
+                depth + 1,
+            ),
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.weight.cmp(&b.weight).then_with(|| a.title.cmp(&b.title)));
+    nodes
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn registry() -> MenuRegistry {
+        let json = r#"[
+            {"path": "/blog", "title": "Blog", "weight": 5},
+            {"path": "/blog/archive", "title": "Archive", "parent": "/blog"},
+            {"path": "/blog/:slug", "title": "Post", "parent": "/blog"},
+            {"path": "/about", "title": "About", "weight": 1},
+            {"path": "/admin", "title": "Admin", "permission": "access admin"},
+            {"path": "/admin/content", "title": "Content", "parent": "/admin", "weight": 2},
+            {"path": "/admin/users", "title": "Users", "parent": "/admin", "permission": "administer users"},
+            {"path": "/admin/content/:id/edit", "title": "Edit", "parent": "/admin/content", "local_task": true},
+            {"path": "/hidden", "title": "Hidden", "visible": false},
+            {"path": "/api/thing", "title": "Thing", "method": "POST"}
+        ]"#;
+        MenuRegistry::from_tap_results(vec![("test".to_string(), json.to_string())])
+    }
+
+    fn paths(nodes: &[MenuTreeNode]) -> Vec<&str> {
+        nodes.iter().map(|n| n.path.as_str()).collect()
+    }
+
+    #[test]
+    fn main_menu_nests_and_sorts_links() {
+        let tree = tree(&registry(), MAIN_MENU, "/", |_| true).unwrap();
+        assert_eq!(paths(&tree.items), vec!["/admin", "/about", "/blog"]);
+
+        let blog = &tree.items[2];
+        assert_eq!(paths(&blog.children), vec!["/blog/archive"]);
+        let admin = &tree.items[0];
+        assert_eq!(
+            paths(&admin.children),
+            vec!["/admin/users", "/admin/content"]
+        );
+        assert!(admin.children[1].children.is_empty());
+    }
+
+    #[test]
+    fn permissions_prune_links_and_subtrees() {
+        let tree = tree(&registry(), MAIN_MENU, "/", |_| false).unwrap();
+        assert_eq!(paths(&tree.items), vec!["/about", "/blog"]);
+
+        let admin = super::tree(&registry(), "admin", "/", |p| p == "access admin").unwrap();
+        assert_eq!(paths(&admin.items), vec!["/admin/content"]);
+
+        assert!(super::tree(&registry(), "admin", "/", |_| false).is_none());
+        assert!(super::tree(&registry(), "missing", "/", |_| true).is_none());
+    }
+
+    #[test]
+    fn active_trail_follows_route_match_and_parents() {
+        let tree = tree(&registry(), MAIN_MENU, "/blog/hello-world", |_| true).unwrap();
+        assert_eq!(tree.active_trail, vec!["/blog", "/blog/:slug"]);
+
+        let blog = tree.items.iter().find(|n| n.path == "/blog").unwrap();
+        assert!(blog.in_active_trail);
+        assert!(!blog.active);
+        assert!(
+            !tree
+                .items
+                .iter()
+                .any(|n| n.path == "/about" && n.in_active_trail)
+        );
+    }
+
+    #[test]
+    fn active_trail_falls_back_to_path_prefix() {
+        let registry = registry();
+        assert_eq!(
+            active_trail(&registry, "/admin/content/123/revisions"),
+            vec!["/admin", "/admin/content"]
+        );
+        assert_eq!(active_trail(&registry, "/administer"), Vec::<String>::new());
+
+        let tree = tree(&registry, "admin", "/admin/content", |_| true).unwrap();
+        assert_eq!(paths(&tree.items), vec!["/admin/users", "/admin/content"]);
+        assert!(!tree.items[0].active);
+        assert!(tree.items[1].active);
+    }
+
+    #[test]
+    fn active_trail_stops_on_parent_cycles() {
+        let json = r#"[
+            {"path": "/a", "title": "A", "parent": "/b"},
+            {"path": "/b", "title": "B", "parent": "/a"}
+        ]"#;
+        let registry = MenuRegistry::from_tap_results(vec![("test".to_string(), json.to_string())]);
+        assert_eq!(active_trail(&registry, "/a"), vec!["/b", "/a"]);
+    }
+}
@@ -24,6 +24,190 @@ pub struct TapLabels {
     pub tap: String,
 }
 
+/// Database pool labels (`primary`, `replica_0`, ...).
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct DbPoolLabels {
+    pub pool: String,
+}
+
+/// Database pool connection labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct DbPoolConnectionLabels {
+    pub pool: String,
+    /// `idle` or `active`.
+    pub state: String,
+}
+
+/// Cache hit labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct CacheHitLabels {
+    /// See [`CacheRegion`].
+    pub region: String,
+    /// `live` or `preview`.
+    pub stage: String,
+    /// `l1` (in-process) or `l2` (Redis).
+    pub tier: String,
+}
+
+/// Cache miss labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct CacheMissLabels {
+    /// See [`CacheRegion`].
+    pub region: String,
+    /// `live` or `preview`.
+    pub stage: String,
+}
+
+/// Cache invalidation labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct CacheTagLabels {
+    /// Tag family: the tag up to its first `:` (`item` for `item:{id}`).
+    pub tag: String,
+}
+
+/// What a cache holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheRegion {
+    /// Rendered pages.
+    Page,
+    /// Loaded items.
+    Item,
+    /// Gather filter values and archive counts.
+    Gather,
+    /// Config entities.
+    Config,
+    /// Anything else in the shared cache layer.
+    Other,
+}
+
+impl CacheRegion {
+    /// Region of a shared cache layer key, from its prefix.
+    pub fn of_key(key: &str) -> Self {
+        match strip_scope(key).split(':').next() {
+            Some("page") => Self::Page,
+            Some("item") => Self::Item,
+            Some("gather") => Self::Gather,
+            Some("config") => Self::Config,
+            _ => Self::Other,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Page => "page",
+            Self::Item => "item",
+            Self::Gather => "gather",
+            Self::Config => "config",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Whether cached data belongs to the live stage or a preview stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheStage {
+    /// The live stage.
+    Live,
+    /// Any other stage, seen by editors previewing changes.
+    Preview,
+}
+
+impl CacheStage {
+    /// Stage of an item or query in `stage_id`.
+    pub fn of(stage_id: uuid::Uuid) -> Self {
+        if stage_id == crate::models::stage::LIVE_STAGE_ID {
+            Self::Live
+        } else {
+            Self::Preview
+        }
+    }
+
+    /// Stage of a query over `stage_ids`: preview if any is not live.
+    pub fn of_all(stage_ids: &[uuid::Uuid]) -> Self {
+        if stage_ids.iter().all(|id| Self::of(*id) == Self::Live) {
+            Self::Live
+        } else {
+            Self::Preview
+        }
+    }
+
+    /// Stage of a shared cache layer key (see [`crate::cache::CacheLayer::stage_key`]).
+    pub fn of_key(key: &str) -> Self {
+        let key = key
+            .strip_prefix("t:")
+            .and_then(|rest| rest.split_once(':'))
+            .map_or(key, |(_, rest)| rest);
+        if key.starts_with("st:") {
+            Self::Preview
+        } else {
+            Self::Live
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Preview => "preview",
+        }
+    }
+}
+
+/// Cache tier that served a hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheTier {
+    /// In-process Moka cache.
+    L1,
+    /// Shared Redis cache.
+    L2,
+}
+
+impl CacheTier {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::L1 => "l1",
+            Self::L2 => "l2",
+        }
+    }
+}
+
+/// Strip the tenant (`t:{id}:`) and stage (`st:{id}:`) prefixes of a key.
+fn strip_scope(key: &str) -> &str {
+    let mut key = key;
+    for prefix in ["t:", "st:"] {
+        if let Some((_, rest)) = key
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.split_once(':'))
+        {
+            key = rest;
+        }
+    }
+    key
+}
+
+/// Deprecated API usage labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct DeprecationLabels {
+    /// Deprecation ID from [`crate::services::deprecation::DEPRECATIONS`].
+    pub deprecation: String,
+}
+
+/// Plugin event labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct PluginEventLabels {
+    /// Event name passed to `host::dispatch_event`.
+    pub event: String,
+    /// `dispatched`, `refused` (would loop), or per handler `delivered`
+    /// or `failed`.
+    pub outcome: String,
+}
+
+/// Plugin module load labels.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ModuleLoadLabels {
+    /// `compiled` or `cached`.
+    pub source: String,
+}
+
 /// Application metrics.
 pub struct Metrics {
     registry: Registry,
@@ -40,11 +224,17 @@ pub struct Metrics {
     /// Database query duration.
     pub db_query_duration_seconds: Histogram,
 
-    /// Cache hit counter.
-    pub cache_hits: Counter,
+    /// Cache hits by region, stage and tier.
+    pub cache_hits: Family<CacheHitLabels, Counter>,
+
+    /// Cache misses by region and stage.
+    pub cache_misses: Family<CacheMissLabels, Counter>,
 
-    /// Cache miss counter.
-    pub cache_misses: Counter,
+    /// Tag invalidations by tag family.
+    pub cache_invalidations: Family<CacheTagLabels, Counter>,
+
+    /// Keys removed by tag invalidations, by tag family.
+    pub cache_invalidated_keys: Family<CacheTagLabels, Counter>,
 
     /// Active HTTP connections gauge.
     pub active_connections: Gauge,
@@ -61,6 +251,12 @@ pub struct Metrics {
     /// Maximum database connection pool size.
     pub db_pool_max: Gauge,
 
+    /// Whether each pool (primary and read replicas) receives traffic.
+    pub db_pool_up: Family<DbPoolLabels, Gauge>,
+
+    /// Connections per pool by state.
+    pub db_pool_connections: Family<DbPoolConnectionLabels, Gauge>,
+
     /// File uploads counter.
     pub file_uploads: Counter,
 
@@ -70,6 +266,16 @@ pub struct Metrics {
     /// Rate limit rejections counter.
     pub rate_limit_rejections: Counter,
 
+    /// Requests using deprecated endpoints, fields or parameters.
+    pub deprecated_requests: Family<DeprecationLabels, Counter>,
+
+    /// Plugin events by name and outcome.
+    pub plugin_events: Family<PluginEventLabels, Counter>,
+
+    /// Startup time spent compiling plugin modules or loading them from
+    /// the module cache.
+    pub plugin_module_load_seconds: Family<ModuleLoadLabels, Histogram>,
+
     /// AI provider circuit breaker state (0=closed, 1=open, 2=half_open).
     pub ai_circuit_breaker_state: Gauge,
 
@@ -114,16 +320,34 @@ impl Metrics {
             db_query_duration_seconds.clone(),
         );
 
-        let cache_hits = Counter::default();
-        registry.register("cache_hits_total", "Cache hit count", cache_hits.clone());
+        let cache_hits = Family::<CacheHitLabels, Counter>::default();
+        registry.register(
+            "cache_hits",
+            "Cache hits by region, stage and tier",
+            cache_hits.clone(),
+        );
 
-        let cache_misses = Counter::default();
+        let cache_misses = Family::<CacheMissLabels, Counter>::default();
         registry.register(
-            "cache_misses_total",
-            "Cache miss count",
+            "cache_misses",
+            "Cache misses by region and stage",
             cache_misses.clone(),
         );
 
+        let cache_invalidations = Family::<CacheTagLabels, Counter>::default();
+        registry.register(
+            "cache_invalidations",
+            "Cache tag invalidations by tag family",
+            cache_invalidations.clone(),
+        );
+
+        let cache_invalidated_keys = Family::<CacheTagLabels, Counter>::default();
+        registry.register(
+            "cache_invalidated_keys",
+            "Cache keys removed by tag invalidations, by tag family",
+            cache_invalidated_keys.clone(),
+        );
+
         let active_connections = Gauge::default();
         registry.register(
             "http_active_connections",
@@ -159,6 +383,20 @@ impl Metrics {
             db_pool_max.clone(),
         );
 
+        let db_pool_up = Family::<DbPoolLabels, Gauge>::default();
+        registry.register(
+            "trovato_db_pool_up",
+            "Whether a database pool (primary or read replica) is healthy",
+            db_pool_up.clone(),
+        );
+
+        let db_pool_connections = Family::<DbPoolConnectionLabels, Gauge>::default();
+        registry.register(
+            "trovato_db_pool_connections",
+            "Database connections per pool by state",
+            db_pool_connections.clone(),
+        );
+
         let file_uploads = Counter::default();
         registry.register(
             "file_uploads_total",
@@ -180,6 +418,30 @@ impl Metrics {
             rate_limit_rejections.clone(),
         );
 
+        let deprecated_requests = Family::<DeprecationLabels, Counter>::default();
+        registry.register(
+            "trovato_api_deprecated_requests",
+            "Requests using deprecated API endpoints, fields or parameters",
+            deprecated_requests.clone(),
+        );
+
+        let plugin_events = Family::<PluginEventLabels, Counter>::default();
+        registry.register(
+            "trovato_plugin_events",
+            "Plugin events by name and outcome",
+            plugin_events.clone(),
+        );
+
+        let plugin_module_load_seconds =
+            Family::<ModuleLoadLabels, Histogram>::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.001, 2.0, 14))
+            });
+        registry.register(
+            "trovato_plugin_module_load_seconds",
+            "Plugin module load time at startup by source (compiled or cached)",
+            plugin_module_load_seconds.clone(),
+        );
+
         let ai_circuit_breaker_state = Gauge::default();
         registry.register(
             "trovato_circuit_breaker_ai",
@@ -202,14 +464,21 @@ impl Metrics {
             db_query_duration_seconds,
             cache_hits,
             cache_misses,
+            cache_invalidations,
+            cache_invalidated_keys,
             active_connections,
             db_pool_size,
             db_pool_idle,
             db_pool_active,
             db_pool_max,
+            db_pool_up,
+            db_pool_connections,
             file_uploads,
             file_upload_bytes,
             rate_limit_rejections,
+            deprecated_requests,
+            plugin_events,
+            plugin_module_load_seconds,
             ai_circuit_breaker_state,
             email_circuit_breaker_state,
         }
@@ -246,14 +515,56 @@ impl Metrics {
         self.db_query_duration_seconds.observe(duration_secs);
     }
 
+    /// Record health and connection counts for each database pool.
+    pub fn record_db_pools(&self, pools: &[crate::db::PoolStatus]) {
+        for status in pools {
+            self.db_pool_up
+                .get_or_create(&DbPoolLabels {
+                    pool: status.name.clone(),
+                })
+                .set(i64::from(status.healthy));
+            let active = status.size.saturating_sub(status.idle);
+            for (state, count) in [("idle", status.idle), ("active", active)] {
+                self.db_pool_connections
+                    .get_or_create(&DbPoolConnectionLabels {
+                        pool: status.name.clone(),
+                        state: state.to_string(),
+                    })
+                    .set(i64::from(count));
+            }
+        }
+    }
+
     /// Record a cache hit.
-    pub fn record_cache_hit(&self) {
-        self.cache_hits.inc();
+    pub fn record_cache_hit(&self, region: CacheRegion, stage: CacheStage, tier: CacheTier) {
+        self.cache_hits
+            .get_or_create(&CacheHitLabels {
+                region: region.as_str().to_string(),
+                stage: stage.as_str().to_string(),
+                tier: tier.as_str().to_string(),
+            })
+            .inc();
     }
 
     /// Record a cache miss.
-    pub fn record_cache_miss(&self) {
-        self.cache_misses.inc();
+    pub fn record_cache_miss(&self, region: CacheRegion, stage: CacheStage) {
+        self.cache_misses
+            .get_or_create(&CacheMissLabels {
+                region: region.as_str().to_string(),
+                stage: stage.as_str().to_string(),
+            })
+            .inc();
+    }
+
+    /// Record a tag invalidation and the number of keys it removed.
+    pub fn record_cache_invalidation(&self, tag: &str, keys: usize) {
+        let labels = CacheTagLabels {
+            tag: tag_family(tag).to_string(),
+        };
+        self.cache_invalidations.get_or_create(&labels).inc();
+        self.cache_invalidated_keys
+            .get_or_create(&labels)
+            .inc_by(keys as u64);
     }
 
     /// Record a file upload.
@@ -267,6 +578,36 @@ impl Metrics {
         self.rate_limit_rejections.inc();
     }
 
+    /// Record a request using a deprecated API shape.
+    pub fn record_deprecated(&self, deprecation: &str) {
+        self.deprecated_requests
+            .get_or_create(&DeprecationLabels {
+                deprecation: deprecation.to_string(),
+            })
+            .inc();
+    }
+
+    /// Record a plugin event outcome.
+    pub fn record_plugin_event(&self, event: &str, outcome: &str) {
+        self.plugin_events
+            .get_or_create(&PluginEventLabels {
+                event: event.to_string(),
+                outcome: outcome.to_string(),
+            })
+            .inc();
+    }
+
+    /// Record the startup load time of each loaded plugin module.
+    pub fn record_plugin_module_loads(&self, runtime: &crate::plugin::PluginRuntime) {
+        for plugin in runtime.plugins().values() {
+            self.plugin_module_load_seconds
+                .get_or_create(&ModuleLoadLabels {
+                    source: plugin.source.as_str().to_string(),
+                })
+                .observe(plugin.load_time.as_secs_f64());
+        }
+    }
+
     /// Increment active connections.
     pub fn connection_start(&self) {
         self.active_connections.inc();
@@ -306,6 +647,11 @@ impl std::fmt::Debug for Metrics {
     }
 }
 
+/// The family of a cache tag, to limit label cardinality.
+fn tag_family(tag: &str) -> &str {
+    tag.split(':').next().unwrap_or(tag)
+}
+
 /// Normalize a path for metrics labels.
 ///
 /// Replaces dynamic segments (UUIDs, IDs) with placeholders to limit cardinality.
@@ -352,7 +698,57 @@ mod tests {
         let metrics = Metrics::new();
         let output = metrics.encode();
         assert!(output.contains("http_requests_total"));
-        assert!(output.contains("cache_hits_total"));
+        assert!(output.contains("cache_hits"));
+    }
+
+    #[test]
+    fn test_record_db_pools() {
+        let metrics = Metrics::new();
+        metrics.record_db_pools(&[crate::db::PoolStatus {
+            name: "replica_0".to_string(),
+            healthy: false,
+            size: 4,
+            idle: 1,
+        }]);
+
+        let output = metrics.encode();
+        assert!(output.contains(r#"trovato_db_pool_up{pool="replica_0"} 0"#));
+        assert!(
+            output.contains(r#"trovato_db_pool_connections{pool="replica_0",state="active"} 3"#)
+        );
+    }
+
+    #[test]
+    fn test_cache_key_labels() {
+        let preview = uuid::Uuid::now_v7();
+        let tenant = uuid::Uuid::now_v7();
+        assert_eq!(CacheRegion::of_key("page:en:/about"), CacheRegion::Page);
+        assert_eq!(CacheStage::of_key("page:en:/about"), CacheStage::Live);
+
+        let key = format!("t:{tenant}:st:{preview}:page:en:/about");
+        assert_eq!(CacheRegion::of_key(&key), CacheRegion::Page);
+        assert_eq!(CacheStage::of_key(&key), CacheStage::Preview);
+        assert_eq!(CacheRegion::of_key("sitemap.xml"), CacheRegion::Other);
+
+        assert_eq!(CacheStage::of_all(&[]), CacheStage::Live);
+        assert_eq!(
+            CacheStage::of_all(&[preview, crate::models::stage::LIVE_STAGE_ID]),
+            CacheStage::Preview
+        );
+    }
+
+    #[test]
+    fn test_record_cache_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_cache_hit(CacheRegion::Page, CacheStage::Live, CacheTier::L2);
+        metrics.record_cache_miss(CacheRegion::Item, CacheStage::Preview);
+        metrics.record_cache_invalidation("item:550e8400-e29b-41d4-a716-446655440000", 3);
+
+        let output = metrics.encode();
+        assert!(output.contains(r#"cache_hits_total{region="page",stage="live",tier="l2"} 1"#));
+        assert!(output.contains(r#"cache_misses_total{region="item",stage="preview"} 1"#));
+        assert!(output.contains(r#"cache_invalidations_total{tag="item"} 1"#));
+        assert!(output.contains(r#"cache_invalidated_keys_total{tag="item"} 3"#));
     }
 
     #[test]
@@ -2,7 +2,8 @@
 //!
 //! Checks for `Authorization: Bearer <token>` headers and, if valid,
 //! injects the token's user_id into the session so existing handlers
-//! work unchanged.
+//! work unchanged. The token itself is recorded as an [`ApiTokenAuth`]
+//! request extension.
 
 use axum::{
     body::Body,
@@ -20,10 +21,18 @@ use crate::models::api_token::ApiToken;
 use crate::routes::auth::SESSION_USER_ID;
 use crate::state::AppState;
 
+/// API token that authenticated the current request.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiTokenAuth {
+    pub token_id: Uuid,
+    pub user_id: Uuid,
+}
+
 /// Middleware that authenticates via Bearer token.
 ///
 /// If an `Authorization: Bearer <token>` header is present:
-/// - Valid token -> injects user_id into session, fires touch_last_used in background
+/// - Valid token -> injects user_id into session, adds [`ApiTokenAuth`], fires
+///   touch_last_used in background
 /// - Invalid/expired -> returns 401 JSON error
 /// - No header -> passes through (session auth may still work)
 ///
@@ -38,7 +47,7 @@ use crate::state::AppState;
 pub async fn authenticate_api_token(
     State(state): State<AppState>,
     session: Session,
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Response {
     let auth_header = request
@@ -100,5 +109,9 @@ pub async fn authenticate_api_token(
         }
     });
 
+    request.extensions_mut().insert(ApiTokenAuth {
+        token_id: token.id,
+        user_id: token.user_id,
+    });
     next.run(request).await
 }
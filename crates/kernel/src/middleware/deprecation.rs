@@ -0,0 +1,108 @@
+//! Deprecated API usage tracking middleware.
+//!
+//! Counts requests that use a shape listed in
+//! [`crate::services::deprecation::DEPRECATIONS`] and, when enabled,
+//! announces the deprecation in response headers.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Request, header::AUTHORIZATION},
+    middleware::Next,
+    response::Response,
+};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::middleware::api_token::ApiTokenAuth;
+use crate::middleware::bearer_auth::BearerAuth;
+use crate::models::api_token::ApiToken;
+use crate::routes::auth::SESSION_USER_ID;
+use crate::services::deprecation::{self, ApiClient};
+use crate::state::AppState;
+
+/// Middleware to track deprecated API usage.
+///
+/// Requests matching no deprecation pass straight through. Matching
+/// requests are counted per client and, with `DEPRECATION_HEADERS` set,
+/// the response gets `Deprecation` and `Sunset` headers.
+pub async fn track_deprecations(
+    State(state): State<AppState>,
+    session: Session,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let found = deprecation::matching(
+        request.method().as_str(),
+        request.uri().path(),
+        request.uri().query(),
+    );
+    if found.is_empty() {
+        return next.run(request).await;
+    }
+
+    let client = match authenticated_client(&request) {
+        Some(client) => client,
+        None => {
+            let raw_token = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .map(str::to_string);
+            lookup_client(&state, &session, raw_token.as_deref()).await
+        }
+    };
+    for d in &found {
+        state.deprecations().record(d, &client);
+        state.metrics().record_deprecated(d.id);
+    }
+
+    let mut response = next.run(request).await;
+    if state.deprecations().headers_enabled() {
+        let headers = response.headers_mut();
+        // With several matches, announce the earliest dates.
+        let since = found
+            .iter()
+            .min_by_key(|d| d.deprecated_since)
+            .and_then(|d| d.deprecation_header());
+        let sunset = found
+            .iter()
+            .filter(|d| d.sunset.is_some())
+            .min_by_key(|d| d.sunset)
+            .and_then(|d| d.sunset_header());
+        for (name, value) in [("deprecation", since), ("sunset", sunset)] {
+            if let Some(value) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+    }
+    response
+}
+
+/// Client identified by the authentication middleware, if any.
+fn authenticated_client(request: &Request<Body>) -> Option<ApiClient> {
+    if let Some(auth) = request.extensions().get::<ApiTokenAuth>() {
+        return Some(ApiClient::api_token(auth.token_id, auth.user_id));
+    }
+    let auth = request.extensions().get::<BearerAuth>()?;
+    let user_id = (!auth.is_client_credentials).then_some(auth.user_id);
+    Some(ApiClient::oauth(&auth.client_id, user_id))
+}
+
+/// Identify a client the authentication middleware did not record.
+///
+/// An API token sent alongside the session cookie its first use created is
+/// skipped by `authenticate_api_token`; look it up so usage stays
+/// attributed to the token rather than its user.
+async fn lookup_client(state: &AppState, session: &Session, raw_token: Option<&str>) -> ApiClient {
+    if let Some(raw_token) = raw_token
+        && let Ok(Some(token)) = ApiToken::find_by_token(state.db(), raw_token).await
+    {
+        return ApiClient::api_token(token.id, token.user_id);
+    }
+    match session.get::<Uuid>(SESSION_USER_ID).await {
+        Ok(Some(user_id)) => ApiClient::user(user_id),
+        _ => ApiClient::anonymous(),
+    }
+}
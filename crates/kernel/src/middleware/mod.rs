@@ -5,25 +5,37 @@
 
 pub mod api_token;
 pub mod bearer_auth;
+pub mod deprecation;
 pub mod install_check;
 pub mod language;
+pub mod page_cache;
 pub mod path_alias;
 pub mod query_profiler;
 pub mod rate_limit;
+pub mod read_only;
 pub mod redirect;
 pub mod security_headers;
+pub mod session_expiry;
+pub mod tap_memo;
+pub mod tap_trace;
 pub mod tenant;
 
 pub use api_token::authenticate_api_token;
 pub use bearer_auth::authenticate_bearer_token;
+pub use deprecation::track_deprecations;
 pub use install_check::check_installation;
 pub use language::negotiate_language;
+pub use page_cache::serve_page_cache;
 pub use path_alias::{path_alias_fallback, resolve_path_alias};
 pub use query_profiler::track_request_timing;
 pub use rate_limit::{
-    RateLimitConfig, RateLimiter, categorize_path, check_authenticated_rate_limit,
-    check_rate_limit, get_client_id, rate_limit_response,
+    ClientIp, RateLimitConfig, RateLimiter, categorize_path, check_authenticated_rate_limit,
+    check_rate_limit, rate_limit_response, resolve_client_ip,
 };
+pub use read_only::enforce_read_only;
 pub use redirect::check_redirect;
 pub use security_headers::inject_security_headers;
+pub use session_expiry::apply_session_expiry;
+pub use tap_memo::memoize_taps;
+pub use tap_trace::trace_taps;
 pub use tenant::resolve_tenant;
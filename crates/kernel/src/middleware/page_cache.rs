@@ -0,0 +1,501 @@
+//! Page cache middleware for anonymous users.
+//!
+//! Caches full HTML responses for anonymous GET requests in the
+//! [`CacheLayer`], keyed by path, query string, negotiated language, and
+//! active stage. Tags emitted while rendering (see [`crate::cache::page`])
+//! are stored with the entry, so item and gather saves invalidate only the
+//! pages that displayed them.
+//!
+//! Responses are cached only when they are `200 OK` HTML, do not set
+//! cookies, and did not modify the session (CSRF tokens, flash messages).
+//!
+//! Cached pages carry an `ETag` (a hash of the body) and a `Last-Modified`
+//! time of when they were rendered. Conditional requests matching a cached
+//! page get `304 Not Modified` straight from the cache.
+//!
+//! Content types may declare cache metadata (see
+//! [`trovato_sdk::types::CacheMetadata`]). A page displaying such content is
+//! cached for the declared max-age instead of the site default and gets
+//! `Cache-Control`, `Surrogate-Control` and `Vary` headers so a CDN can
+//! cache it too. A max-age of `0` or the `user` context keeps the page out
+//! of the page cache and shared caches.
+//!
+//! Configuration:
+//! - `CACHE_TTL_PAGES` (default: 300) — page lifetime in seconds; `0` disables
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::State,
+    http::{HeaderMap, HeaderValue, Method, Request, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower_sessions::Session;
+use trovato_sdk::types::CacheContext;
+use uuid::Uuid;
+
+use crate::cache::CacheLayer;
+use crate::cache::conditional::Validators;
+use crate::cache::page::{CachedPage, PAGE_TAG, RenderMetadata, collect_render_metadata};
+use crate::middleware::language::ResolvedLanguage;
+use crate::routes::auth::{SESSION_ACTIVE_STAGE, SESSION_USER_ID};
+use crate::state::AppState;
+
+/// Response header reporting whether the page cache served the response.
+pub const PAGE_CACHE_HEADER: &str = "x-page-cache";
+
+/// Response header carrying the CDN lifetime.
+pub const SURROGATE_CONTROL_HEADER: &str = "surrogate-control";
+
+/// Largest response body stored in the page cache (1 MiB).
+const MAX_PAGE_BYTES: u64 = 1024 * 1024;
+
+/// Path prefixes never served from the page cache.
+///
+/// Covers authenticated areas, APIs, and routes with their own caching
+/// or per-request output.
+const UNCACHEABLE_PREFIXES: &[&str] = &[
+    "/admin", "/api", "/auth", "/user", "/install", "/oauth", "/cron", "/batch", "/health",
+    "/metrics", "/search", "/static", "/files", "/file", "/system",
+];
+
+/// Whether requests for `path` may be served from the page cache.
+pub fn is_cacheable_path(path: &str) -> bool {
+    !UNCACHEABLE_PREFIXES.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+/// Build the cache key for a page request.
+pub fn page_cache_key(path_and_query: &str, language: &str, stage_id: Option<Uuid>) -> String {
+    CacheLayer::stage_key(&format!("page:{language}:{path_and_query}"), stage_id)
+}
+
+/// Serve anonymous page views from the page cache, populating it on miss.
+pub async fn serve_page_cache(
+    State(state): State<AppState>,
+    session: Session,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(ttl) = state.page_cache_ttl() else {
+        return next.run(request).await;
+    };
+
+    if request.method() != Method::GET || !is_cacheable_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    if let Ok(Some(_)) = session.get::<Uuid>(SESSION_USER_ID).await {
+        return next.run(request).await;
+    }
+
+    let language = request
+        .extensions()
+        .get::<ResolvedLanguage>()
+        .map(|l| l.0.clone())
+        .unwrap_or_else(|| state.default_language().to_string());
+    let stage_id = session
+        .get::<String>(SESSION_ACTIVE_STAGE)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|s| s.parse::<Uuid>().ok());
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    let key = page_cache_key(path_and_query, &language, stage_id);
+    let request_headers = request.headers().clone();
+
+    if let Some(page) = state
+        .cache()
+        .get(&key)
+        .await
+        .and_then(|raw| serde_json::from_str::<CachedPage>(&raw).ok())
+    {
+        return cached_response(page, "HIT", &request_headers);
+    }
+
+    let (mut response, render) = collect_render_metadata(next.run(request)).await;
+
+    if !is_storable(&response) || session.is_modified() {
+        return response;
+    }
+
+    let mut caching = PageCaching::from_render(&render, ttl);
+    if response.headers().contains_key(header::CACHE_CONTROL) {
+        // The handler chose its own caching headers; leave them alone.
+        caching = PageCaching {
+            ttl: caching.ttl,
+            ..PageCaching::default()
+        };
+    }
+    let Some(ttl) = caching.ttl else {
+        caching.apply(response.headers_mut());
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    caching.apply(&mut parts.headers);
+    let bytes = match axum::body::to_bytes(body, MAX_PAGE_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to buffer page for caching");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let Ok(body) = String::from_utf8(bytes.to_vec()) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let validators = Validators::from_body(body.as_bytes(), Some(chrono::Utc::now().timestamp()));
+    let page = CachedPage {
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("text/html; charset=utf-8")
+            .to_string(),
+        body,
+        cache_control: caching.cache_control,
+        surrogate_control: caching.surrogate_control,
+        vary: caching.vary,
+        etag: Some(validators.etag.clone()),
+        last_modified: validators.last_modified,
+    };
+    let tag_refs: Vec<&str> = render
+        .tags
+        .iter()
+        .map(String::as_str)
+        .chain([PAGE_TAG])
+        .collect();
+    match serde_json::to_string(&page) {
+        Ok(raw) => state.cache().set(&key, &raw, ttl, &tag_refs).await,
+        Err(e) => tracing::warn!(error = %e, "failed to serialize cached page"),
+    }
+
+    let mut response = if validators.is_fresh(&request_headers) {
+        let mut response = validators.not_modified();
+        insert_caching_headers(
+            response.headers_mut(),
+            [&page.cache_control, &page.surrogate_control, &page.vary],
+        );
+        response
+    } else {
+        let mut response = Response::from_parts(parts, Body::from(page.body));
+        validators.apply(response.headers_mut());
+        response
+    };
+    response
+        .headers_mut()
+        .insert(PAGE_CACHE_HEADER, HeaderValue::from_static("MISS"));
+    response
+}
+
+/// How a rendered page is cached, derived from the cache metadata of the
+/// content it displayed.
+#[derive(Debug, Default, PartialEq)]
+struct PageCaching {
+    /// Page cache lifetime in seconds; `None` keeps the page out of the
+    /// page cache.
+    ttl: Option<u64>,
+    cache_control: Option<String>,
+    surrogate_control: Option<String>,
+    vary: Option<String>,
+}
+
+impl PageCaching {
+    /// Caching for a page, given the site's default page lifetime.
+    ///
+    /// Pages whose content declared no metadata are cached for the site
+    /// default and get no caching headers, as before.
+    fn from_render(render: &RenderMetadata, default_ttl: u64) -> Self {
+        if !render.has_cache_metadata() {
+            return Self {
+                ttl: Some(default_ttl),
+                ..Self::default()
+            };
+        }
+
+        let vary: Vec<&str> = render
+            .contexts
+            .iter()
+            .filter_map(|context| match context {
+                CacheContext::Language => Some("Accept-Language"),
+                CacheContext::Role => Some("Cookie"),
+                CacheContext::User => None,
+            })
+            .collect();
+        let vary = (!vary.is_empty()).then(|| vary.join(", "));
+
+        let max_age = render.max_age.unwrap_or(default_ttl);
+        if render.contexts.contains(&CacheContext::User) {
+            let cache_control = if max_age == 0 {
+                "private, no-cache".to_string()
+            } else {
+                format!("private, max-age={max_age}")
+            };
+            return Self {
+                ttl: None,
+                cache_control: Some(cache_control),
+                surrogate_control: Some("no-store".to_string()),
+                vary,
+            };
+        }
+        if max_age == 0 {
+            return Self {
+                ttl: None,
+                cache_control: Some("no-cache".to_string()),
+                surrogate_control: Some("no-store".to_string()),
+                vary,
+            };
+        }
+
+        let surrogate_control = match render.shared_max_age.unwrap_or(max_age) {
+            0 => "no-store".to_string(),
+            shared => format!("max-age={shared}"),
+        };
+        Self {
+            ttl: Some(max_age),
+            cache_control: Some(format!("public, max-age={max_age}")),
+            surrogate_control: Some(surrogate_control),
+            vary,
+        }
+    }
+
+    /// Add the caching headers to a response.
+    fn apply(&self, headers: &mut HeaderMap) {
+        insert_caching_headers(
+            headers,
+            [&self.cache_control, &self.surrogate_control, &self.vary],
+        );
+    }
+}
+
+/// Insert `Cache-Control`, `Surrogate-Control` and `Vary` values, leaving
+/// responses whose handler set its own `Cache-Control` untouched.
+fn insert_caching_headers(headers: &mut HeaderMap, values: [&Option<String>; 3]) {
+    if headers.contains_key(header::CACHE_CONTROL) {
+        return;
+    }
+    let names = [
+        header::CACHE_CONTROL.as_str(),
+        SURROGATE_CONTROL_HEADER,
+        header::VARY.as_str(),
+    ];
+    for (name, value) in names.into_iter().zip(values) {
+        if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(name, value);
+        }
+    }
+}
+
+/// Whether a rendered response may be stored in the page cache.
+///
+/// The body size must be known up front so oversized or streaming bodies
+/// pass through untouched.
+fn is_storable(response: &Response) -> bool {
+    let headers = response.headers();
+    let is_html = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/html"));
+    let is_private = headers
+        .get(header::CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|cc| cc.contains("private") || cc.contains("no-store"));
+
+    response.status() == StatusCode::OK
+        && is_html
+        && !is_private
+        && !headers.contains_key(header::SET_COOKIE)
+        && response
+            .body()
+            .size_hint()
+            .exact()
+            .is_some_and(|len| len <= MAX_PAGE_BYTES)
+}
+
+/// Build a response from a cached page, or `304 Not Modified` when the
+/// request's validators match it.
+fn cached_response(page: CachedPage, status: &'static str, request: &HeaderMap) -> Response {
+    let validators = page.validators();
+    let mut response = match &validators {
+        Some(validators) if validators.is_fresh(request) => validators.not_modified(),
+        _ => {
+            let mut response = Response::new(Body::from(page.body));
+            if let Ok(content_type) = HeaderValue::from_str(&page.content_type) {
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, content_type);
+            }
+            if let Some(validators) = &validators {
+                validators.apply(response.headers_mut());
+            }
+            response
+        }
+    };
+    insert_caching_headers(
+        response.headers_mut(),
+        [&page.cache_control, &page.surrogate_control, &page.vary],
+    );
+    response
+        .headers_mut()
+        .insert(PAGE_CACHE_HEADER, HeaderValue::from_static(status));
+    response
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes_admin_and_api_paths() {
+        assert!(!is_cacheable_path("/admin"));
+        assert!(!is_cacheable_path("/admin/content"));
+        assert!(!is_cacheable_path("/api/v1/items"));
+        assert!(!is_cacheable_path("/user/login"));
+        assert!(!is_cacheable_path("/auth/google/callback"));
+    }
+
+    #[test]
+    fn allows_public_paths() {
+        assert!(is_cacheable_path("/"));
+        assert!(is_cacheable_path(
+            "/item/0190a0a0-0000-7000-8000-000000000000"
+        ));
+        assert!(is_cacheable_path("/about-us"));
+        // Prefix match is per path segment
+        assert!(is_cacheable_path("/administration-guide"));
+        assert!(is_cacheable_path("/users-of-trovato"));
+    }
+
+    #[test]
+    fn key_varies_by_language_and_stage() {
+        let live = page_cache_key("/about?page=2", "en", None);
+        assert_eq!(live, "page:en:/about?page=2");
+        assert_ne!(live, page_cache_key("/about?page=2", "fr", None));
+
+        let preview = Uuid::now_v7();
+        assert_eq!(
+            page_cache_key("/about", "en", Some(preview)),
+            format!("st:{preview}:page:en:/about")
+        );
+    }
+
+    #[test]
+    fn only_plain_html_ok_responses_are_storable() {
+        let html = || {
+            Response::builder()
+                .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+                .body(Body::from("<p>hi</p>"))
+                .unwrap()
+        };
+        assert!(is_storable(&html()));
+
+        let mut with_cookie = html();
+        with_cookie
+            .headers_mut()
+            .insert(header::SET_COOKIE, HeaderValue::from_static("id=1"));
+        assert!(!is_storable(&with_cookie));
+
+        let mut not_found = html();
+        *not_found.status_mut() = StatusCode::NOT_FOUND;
+        assert!(!is_storable(&not_found));
+
+        let json = Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        assert!(!is_storable(&json));
+    }
+
+    #[test]
+    fn cached_pages_answer_conditional_requests() {
+        let page = CachedPage {
+            content_type: "text/html; charset=utf-8".to_string(),
+            body: "<p>hi</p>".to_string(),
+            cache_control: Some("public, max-age=60".to_string()),
+            surrogate_control: None,
+            vary: None,
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some(0),
+        };
+
+        let response = cached_response(page.clone(), "HIT", &HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], "\"abc\"");
+
+        let mut request = HeaderMap::new();
+        request.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"abc\""));
+        let response = cached_response(page, "HIT", &request);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=60"
+        );
+        assert_eq!(response.headers()[PAGE_CACHE_HEADER], "HIT");
+    }
+
+    #[test]
+    fn pages_without_metadata_use_site_default() {
+        let caching = PageCaching::from_render(&RenderMetadata::default(), 300);
+        assert_eq!(caching.ttl, Some(300));
+        assert_eq!(caching.cache_control, None);
+        assert_eq!(caching.surrogate_control, None);
+    }
+
+    #[test]
+    fn declared_metadata_sets_ttl_and_headers() {
+        let render = RenderMetadata {
+            max_age: Some(60),
+            shared_max_age: Some(3600),
+            contexts: [CacheContext::Language, CacheContext::Role].into(),
+            ..Default::default()
+        };
+        let caching = PageCaching::from_render(&render, 300);
+        assert_eq!(caching.ttl, Some(60));
+        assert_eq!(caching.cache_control.as_deref(), Some("public, max-age=60"));
+        assert_eq!(caching.surrogate_control.as_deref(), Some("max-age=3600"));
+        assert_eq!(caching.vary.as_deref(), Some("Accept-Language, Cookie"));
+
+        let mut headers = HeaderMap::new();
+        caching.apply(&mut headers);
+        assert_eq!(headers[SURROGATE_CONTROL_HEADER], "max-age=3600");
+
+        // A handler's own Cache-Control wins.
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        caching.apply(&mut headers);
+        assert_eq!(headers[header::CACHE_CONTROL], "no-store");
+        assert!(!headers.contains_key(SURROGATE_CONTROL_HEADER));
+    }
+
+    #[test]
+    fn zero_max_age_and_user_context_are_not_stored() {
+        let zero = RenderMetadata {
+            max_age: Some(0),
+            ..Default::default()
+        };
+        let caching = PageCaching::from_render(&zero, 300);
+        assert_eq!(caching.ttl, None);
+        assert_eq!(caching.cache_control.as_deref(), Some("no-cache"));
+
+        let per_user = RenderMetadata {
+            contexts: [CacheContext::User].into(),
+            ..Default::default()
+        };
+        let caching = PageCaching::from_render(&per_user, 300);
+        assert_eq!(caching.ttl, None);
+        assert_eq!(
+            caching.cache_control.as_deref(),
+            Some("private, max-age=300")
+        );
+        assert_eq!(caching.surrogate_control.as_deref(), Some("no-store"));
+    }
+}
@@ -1,29 +1,57 @@
 //! Query profiler middleware.
 //!
-//! When enabled (via `--features query-profiler` or always-on in dev),
-//! logs slow database queries and adds `Server-Timing` response headers.
+//! Logs slow requests and adds a `Server-Timing` response header with the
+//! total request time. When a user with the "view profiling data"
+//! permission sends the `X-Trovato-Debug-Timing` header, the header also
+//! breaks the time down into db/cache/tap/render (see [`crate::profiling`]).
 //!
 //! Configuration:
-//! - `QUERY_SLOW_THRESHOLD_MS` (default: 100) — queries exceeding this are logged
-//! - Queries exceeding 5x threshold are logged at ERROR level
+//! - `QUERY_SLOW_THRESHOLD_MS` (default: 100) — requests exceeding this are logged
+//! - Requests exceeding 5x threshold are logged at ERROR level
 
 use std::time::Instant;
 
-use axum::{body::Body, http::Request, middleware::Next, response::Response};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tower_sessions::Session;
 
-/// Middleware that tracks total request DB time via `Server-Timing` header.
-///
-/// Actual per-query tracking requires wrapping the PgPool, which is
-/// deferred to a future enhancement. This middleware measures total
-/// request processing time as a proxy.
-pub async fn track_request_timing(request: Request<Body>, next: Next) -> Response {
+use crate::profiling::{self, DEBUG_TIMING_HEADER, PROFILING_PERMISSION};
+use crate::routes::helpers::require_permission;
+use crate::state::AppState;
+
+/// Middleware that reports request timing via the `Server-Timing` header.
+pub async fn track_request_timing(
+    State(state): State<AppState>,
+    session: Session,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
     let start = Instant::now();
-    let mut response = next.run(request).await;
+
+    // Only authorized users get the breakdown: it reveals backend internals.
+    let wants_breakdown = request.headers().contains_key(DEBUG_TIMING_HEADER)
+        && require_permission(&state, &session, PROFILING_PERMISSION)
+            .await
+            .is_ok();
+
+    let (mut response, timing_value) = if wants_breakdown {
+        let (response, timings) = profiling::collect_timings(next.run(request)).await;
+        let value = timings.server_timing(start.elapsed());
+        (response, value)
+    } else {
+        let response = next.run(request).await;
+        let elapsed_ms = start.elapsed().as_millis();
+        (response, format!("total;dur={elapsed_ms}"))
+    };
     let elapsed_ms = start.elapsed().as_millis();
 
     // Add Server-Timing header for browser DevTools
-    let timing_value = format!("total;dur={elapsed_ms}");
-    if let Ok(val) = timing_value.parse() {
+    if let Ok(val) = HeaderValue::from_str(&timing_value) {
         response.headers_mut().insert("server-timing", val);
     }
 
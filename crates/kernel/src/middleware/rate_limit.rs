@@ -2,16 +2,19 @@
 //!
 //! Uses a sliding window counter pattern with Redis INCR + EXPIRE.
 
-use std::net::SocketAddr;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 
-use axum::extract::{ConnectInfo, State};
-use axum::http::StatusCode;
+use axum::extract::{ConnectInfo, FromRequestParts, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
 use redis::AsyncCommands;
-use redis::Client as RedisClient;
 use tracing::{debug, warn};
 
+use crate::lockout::IpNetwork;
+use crate::redis_manager::RedisManager;
 use crate::state::AppState;
 
 /// Rate limit configuration for different endpoint categories.
@@ -35,6 +38,10 @@ pub struct RateLimitConfig {
     pub profile: (u32, Duration),
     /// Password change submissions
     pub password: (u32, Duration),
+    /// Contact form submissions
+    pub contact: (u32, Duration),
+    /// Inbound webhook calls
+    pub webhooks: (u32, Duration),
 }
 
 impl Default for RateLimitConfig {
@@ -49,6 +56,8 @@ impl Default for RateLimitConfig {
             verify_email: (10, Duration::from_secs(60)), // 10 per minute
             profile: (10, Duration::from_secs(60)),      // 10 per minute
             password: (5, Duration::from_secs(60)),      // 5 per minute
+            contact: (5, Duration::from_secs(3600)),     // 5 per hour
+            webhooks: (60, Duration::from_secs(60)),     // 60 per minute
         }
     }
 }
@@ -56,13 +65,13 @@ impl Default for RateLimitConfig {
 /// Rate limiter using Redis for distributed counting.
 #[derive(Clone)]
 pub struct RateLimiter {
-    redis: RedisClient,
+    redis: RedisManager,
     config: RateLimitConfig,
 }
 
 impl RateLimiter {
     /// Create a new rate limiter.
-    pub fn new(redis: RedisClient, config: RateLimitConfig) -> Self {
+    pub fn new(redis: RedisManager, config: RateLimitConfig) -> Self {
         Self { redis, config }
     }
 
@@ -71,6 +80,20 @@ impl RateLimiter {
     /// Returns Ok(()) if allowed, Err with retry-after seconds if limited.
     pub async fn check(&self, category: &str, identifier: &str) -> Result<(), u64> {
         let (limit, window) = self.get_limit(category);
+        self.check_with(category, identifier, limit, window).await
+    }
+
+    /// Check a request against an explicit limit instead of the category's
+    /// configured one (for limits set in site configuration).
+    ///
+    /// Returns Ok(()) if allowed, Err with retry-after seconds if limited.
+    pub async fn check_with(
+        &self,
+        category: &str,
+        identifier: &str,
+        limit: u32,
+        window: Duration,
+    ) -> Result<(), u64> {
         let key = format!("rate:{category}:{identifier}");
         let window_secs = window.as_secs();
 
@@ -109,6 +132,8 @@ impl RateLimiter {
             "verify_email" => self.config.verify_email,
             "profile" => self.config.profile,
             "password" => self.config.password,
+            "contact" => self.config.contact,
+            "webhooks" => self.config.webhooks,
             _ => self.config.api, // Default to API limits
         }
     }
@@ -118,7 +143,7 @@ impl RateLimiter {
     /// Uses a Lua script to atomically INCR + EXPIRE, preventing a race
     /// where a crash between the two commands creates an immortal counter.
     async fn increment(&self, key: &str, ttl_secs: u64) -> Result<i64, redis::RedisError> {
-        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.get().await?;
 
         let script = redis::Script::new(
             r"local count = redis.call('INCR', KEYS[1])
@@ -144,7 +169,7 @@ impl RateLimiter {
         identifier: &str,
     ) -> Result<i64, redis::RedisError> {
         let key = format!("rate:{category}:{identifier}");
-        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.get().await?;
         let count: Option<i64> = conn.get(&key).await?;
         Ok(count.unwrap_or(0))
     }
@@ -152,7 +177,7 @@ impl RateLimiter {
     /// Reset the counter for a key (for testing).
     pub async fn reset(&self, category: &str, identifier: &str) -> Result<(), redis::RedisError> {
         let key = format!("rate:{category}:{identifier}");
-        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.get().await?;
         let _: () = conn.del(&key).await?;
         Ok(())
     }
@@ -168,6 +193,8 @@ pub fn categorize_path(path: &str, method: &str) -> &'static str {
         "uploads"
     } else if path.starts_with("/search") || path.starts_with("/api/search") {
         "search"
+    } else if path.starts_with("/api/hooks/") {
+        "webhooks"
     } else if path.starts_with("/api/") {
         "api"
     } else if method == "POST" {
@@ -177,31 +204,76 @@ pub fn categorize_path(path: &str, method: &str) -> &'static str {
     }
 }
 
-/// Get the client identifier (IP address) for rate limiting.
-pub fn get_client_id(
-    addr: Option<std::net::SocketAddr>,
-    headers: &axum::http::HeaderMap,
-) -> String {
-    // Check X-Forwarded-For header first (for proxied requests)
-    if let Some(forwarded) = headers.get("x-forwarded-for")
-        && let Ok(value) = forwarded.to_str()
-    {
-        // Take the first IP in the chain
-        if let Some(ip) = value.split(',').next() {
-            return ip.trim().to_string();
+/// Resolve the client IP address of a connection from `peer`.
+///
+/// Forwarding headers are only honored when `peer` is one of the
+/// `trusted_proxies`. `X-Forwarded-For` is then read from the right,
+/// skipping trusted proxies, so addresses a client prepends itself are
+/// never used; `X-Real-IP` is the fallback. Connections from anywhere
+/// else are identified by `peer` alone, whatever headers they send.
+pub fn resolve_client_ip(
+    peer: IpAddr,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpNetwork],
+) -> IpAddr {
+    let peer = peer.to_canonical();
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(peer) {
+        return peer;
+    }
+
+    if let Some(value) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
+        // Each proxy appends the address it received the request from.
+        let mut hop = peer;
+        for entry in value.rsplit(',') {
+            let Ok(ip) = entry.trim().parse::<IpAddr>() else {
+                // Unparseable entry: stop at the last hop a proxy vouched for.
+                return hop;
+            };
+            if !is_trusted(ip) {
+                return ip;
+            }
+            hop = ip;
         }
+        return hop;
     }
 
-    // Check X-Real-IP header
-    if let Some(real_ip) = headers.get("x-real-ip")
-        && let Ok(value) = real_ip.to_str()
-    {
-        return value.to_string();
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(peer)
+}
+
+/// Client IP address of a request, resolved by [`resolve_client_ip`]
+/// against the configured trusted proxies.
+///
+/// Holds `None` when the server runs without connection info.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    /// The address as a rate limit or lockout identifier (`"unknown"` if absent).
+    pub fn key(&self) -> String {
+        self.0
+            .map_or_else(|| "unknown".to_string(), |ip| ip.to_string())
     }
+}
 
-    // Fall back to connection address
-    addr.map(|a| a.ip().to_string())
-        .unwrap_or_else(|| "unknown".to_string())
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = ConnectInfo::<SocketAddr>::from_request_parts(parts, state)
+            .await
+            .ok();
+        Ok(Self(peer.map(|ConnectInfo(addr)| {
+            resolve_client_ip(addr.ip(), &parts.headers, state.trusted_proxies())
+        })))
+    }
 }
 
 /// Rate limit exceeded response.
@@ -228,7 +300,8 @@ pub async fn check_rate_limit(
     next: axum::middleware::Next,
 ) -> axum::response::Response {
     let category = categorize_path(request.uri().path(), request.method().as_str());
-    let client_id = get_client_id(Some(addr), request.headers());
+    let client_id =
+        resolve_client_ip(addr.ip(), request.headers(), state.trusted_proxies()).to_string();
 
     match state.rate_limiter().check(category, &client_id).await {
         Ok(()) => next.run(request).await,
@@ -318,6 +391,15 @@ mod tests {
         assert_eq!(categorize_path("/api/v1/chat", "POST"), "api");
     }
 
+    #[test]
+    fn categorize_webhook_paths() {
+        assert_eq!(
+            categorize_path("/api/hooks/argus/websub", "POST"),
+            "webhooks"
+        );
+        assert_eq!(categorize_path("/api/hooks/goose/ci", "GET"), "webhooks");
+    }
+
     #[test]
     fn categorize_form_submission() {
         assert_eq!(categorize_path("/item/123", "POST"), "forms");
@@ -351,76 +433,112 @@ mod tests {
         );
     }
 
-    // --- get_client_id tests ---
+    // --- resolve_client_ip tests ---
 
-    #[test]
-    fn client_id_from_x_forwarded_for() {
-        let mut headers = axum::http::HeaderMap::new();
-        headers.insert("x-forwarded-for", "1.2.3.4, 5.6.7.8".parse().unwrap());
-        assert_eq!(get_client_id(None, &headers), "1.2.3.4");
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
     }
 
-    #[test]
-    fn client_id_from_x_real_ip() {
-        let mut headers = axum::http::HeaderMap::new();
-        headers.insert("x-real-ip", "10.0.0.1".parse().unwrap());
-        assert_eq!(get_client_id(None, &headers), "10.0.0.1");
+    fn proxies(list: &[&str]) -> Vec<IpNetwork> {
+        list.iter().map(|p| p.parse().unwrap()).collect()
     }
 
     #[test]
-    fn client_id_from_socket_addr() {
-        let addr = "192.168.1.1:8080".parse().ok();
+    fn untrusted_peer_ignores_x_forwarded_for() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.2.3.4".parse().unwrap());
+        headers.insert("x-real-ip", "10.0.0.1".parse().unwrap());
+        assert_eq!(
+            resolve_client_ip(ip("203.0.113.7"), &headers, &[]),
+            ip("203.0.113.7")
+        );
         assert_eq!(
-            get_client_id(addr, &axum::http::HeaderMap::new()),
-            "192.168.1.1"
+            resolve_client_ip(ip("203.0.113.7"), &headers, &proxies(&["10.0.0.0/8"])),
+            ip("203.0.113.7")
         );
     }
 
     #[test]
-    fn client_id_unknown_fallback() {
+    fn trusted_peer_uses_x_forwarded_for() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "9.9.9.9".parse().unwrap());
         assert_eq!(
-            get_client_id(None, &axum::http::HeaderMap::new()),
-            "unknown"
+            resolve_client_ip(ip("127.0.0.1"), &headers, &proxies(&["127.0.0.1"])),
+            ip("9.9.9.9")
         );
     }
 
     #[test]
-    fn x_forwarded_for_takes_first_ip() {
-        let mut headers = axum::http::HeaderMap::new();
+    fn x_forwarded_for_skips_trusted_hops_from_the_right() {
+        let mut headers = HeaderMap::new();
+        // Client spoofed 1.1.1.1; the edge proxy saw 2.2.2.2 and an inner
+        // proxy (10.0.0.2) forwarded to us.
         headers.insert(
             "x-forwarded-for",
-            "1.1.1.1, 2.2.2.2, 3.3.3.3".parse().unwrap(),
+            "1.1.1.1, 2.2.2.2, 10.0.0.2".parse().unwrap(),
+        );
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.1"), &headers, &proxies(&["10.0.0.0/8"])),
+            ip("2.2.2.2")
+        );
+    }
+
+    #[test]
+    fn x_forwarded_for_stops_at_unparseable_entry() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "garbage, 10.0.0.2".parse().unwrap());
+        assert_eq!(
+            resolve_client_ip(ip("10.0.0.1"), &headers, &proxies(&["10.0.0.0/8"])),
+            ip("10.0.0.2")
         );
-        assert_eq!(get_client_id(None, &headers), "1.1.1.1");
     }
 
     #[test]
     fn x_forwarded_for_takes_priority_over_x_real_ip() {
-        let mut headers = axum::http::HeaderMap::new();
-        headers.insert("x-forwarded-for", "1.2.3.4".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", " 1.2.3.4 ".parse().unwrap());
         headers.insert("x-real-ip", "10.0.0.1".parse().unwrap());
-        assert_eq!(get_client_id(None, &headers), "1.2.3.4");
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &headers, &proxies(&["127.0.0.1"])),
+            ip("1.2.3.4")
+        );
     }
 
     #[test]
-    fn x_real_ip_takes_priority_over_socket_addr() {
-        let mut headers = axum::http::HeaderMap::new();
+    fn trusted_peer_falls_back_to_x_real_ip_then_peer() {
+        let trusted = proxies(&["127.0.0.1"]);
+        let mut headers = HeaderMap::new();
         headers.insert("x-real-ip", "10.0.0.1".parse().unwrap());
-        let addr = "192.168.1.1:8080".parse().ok();
-        assert_eq!(get_client_id(addr, &headers), "10.0.0.1");
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &headers, &trusted),
+            ip("10.0.0.1")
+        );
+        assert_eq!(
+            resolve_client_ip(ip("127.0.0.1"), &HeaderMap::new(), &trusted),
+            ip("127.0.0.1")
+        );
     }
 
     #[test]
-    fn x_forwarded_for_single_ip() {
-        let mut headers = axum::http::HeaderMap::new();
-        headers.insert("x-forwarded-for", "9.9.9.9".parse().unwrap());
-        assert_eq!(get_client_id(None, &headers), "9.9.9.9");
+    fn spoofed_header_does_not_match_lockout_allowlist() {
+        let settings = crate::lockout::LockoutSettings {
+            ip_allowlist: vec!["10.0.0.0/8".to_string()],
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "10.1.2.3".parse().unwrap());
+        headers.insert("x-real-ip", "10.1.2.3".parse().unwrap());
+
+        // Direct connection, no trusted proxies: the peer is what gets
+        // tracked and it is outside the allowlist.
+        let resolved = resolve_client_ip(ip("203.0.113.7"), &headers, &[]);
+        assert_eq!(resolved, ip("203.0.113.7"));
+        assert!(!settings.is_allowlisted(resolved));
     }
 
     #[test]
-    fn x_forwarded_for_trims_whitespace() {
-        let mut headers = axum::http::HeaderMap::new();
-        headers.insert("x-forwarded-for", " 1.2.3.4 , 5.6.7.8".parse().unwrap());
-        assert_eq!(get_client_id(None, &headers), "1.2.3.4");
+    fn client_ip_key_unknown_without_connection_info() {
+        assert_eq!(ClientIp(None).key(), "unknown");
+        assert_eq!(ClientIp(Some(ip("192.168.1.1"))).key(), "192.168.1.1");
     }
 }
@@ -0,0 +1,40 @@
+//! Read-only mode middleware.
+//!
+//! Rejects mutating requests with 503 while the site is read-only.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+use crate::services::read_only::is_exempt;
+use crate::state::AppState;
+
+/// Middleware to enforce read-only mode.
+///
+/// Safe methods and the endpoints listed in
+/// [`crate::services::read_only`] pass through; other requests get a 503
+/// carrying the configured explanation. If the settings cannot be loaded
+/// the request proceeds, since the write would hit the same database.
+pub async fn enforce_read_only(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if is_exempt(request.method().as_str(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match state.read_only().read_only_message().await {
+        Ok(Some(message)) => AppError::read_only(message).into_response(),
+        Ok(None) => next.run(request).await,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load read-only settings");
+            next.run(request).await
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Per-session expiry middleware.
+//!
+//! tower-sessions only persists a session's expiry date, not its
+//! [`Expiry`](tower_sessions::Expiry) policy, so every loaded session starts
+//! with the layer default. This middleware re-applies the lifetime for
+//! authenticated sessions ("remember me", fixed expiry from login) after the
+//! handler runs, before the session layer saves the record.
+
+use axum::{body::Body, extract::State, http::Request, middleware::Next, response::Response};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::routes::auth::{SESSION_AUTHENTICATED_AT, SESSION_REMEMBER_ME, SESSION_USER_ID};
+use crate::session::SessionSettings;
+
+/// Apply the configured lifetime to authenticated sessions.
+///
+/// Anonymous sessions keep the layer default.
+pub async fn apply_session_expiry(
+    State(settings): State<SessionSettings>,
+    session: Session,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+
+    // Runs after the handler so logins and logouts in this request count.
+    if let Ok(Some(_)) = session.get::<Uuid>(SESSION_USER_ID).await {
+        let remember_me = session
+            .get::<bool>(SESSION_REMEMBER_ME)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        let authenticated_at = session
+            .get::<i64>(SESSION_AUTHENTICATED_AT)
+            .await
+            .ok()
+            .flatten();
+
+        let expiry = settings.expiry_for(remember_me, authenticated_at);
+        if session.expiry() != Some(expiry) {
+            session.set_expiry(Some(expiry));
+        }
+    }
+
+    response
+}
@@ -0,0 +1,14 @@
+//! Tap memo middleware.
+//!
+//! Runs every request inside a tap memo scope, so identical invocations of
+//! side-effect-free taps are answered once per request. See
+//! [`crate::tap::memo`].
+
+use axum::{body::Body, http::Request, middleware::Next, response::Response};
+
+use crate::tap::memo;
+
+/// Memoize tap results for the duration of the request.
+pub async fn memoize_taps(request: Request<Body>, next: Next) -> Response {
+    memo::scope(next.run(request)).await
+}
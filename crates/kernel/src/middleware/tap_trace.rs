@@ -0,0 +1,164 @@
+//! Tap trace middleware.
+//!
+//! Admins can ask for a trace of every tap invoked while handling a request
+//! by sending the `X-Trovato-Debug-Taps` header or a `_debug=taps` query
+//! parameter. The trace is logged with a request id and, for JSON object
+//! responses, returned in a `_debug` section:
+//!
+//! ```json
+//! { "...": "...", "_debug": { "request_id": "...", "taps": [ { "tap": "tap_item_view", ... } ] } }
+//! ```
+//!
+//! Other responses carry the request id in `X-Request-Id`; the trace itself
+//! is only in the log.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Request, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::routes::helpers::require_admin;
+use crate::state::AppState;
+use crate::tap::trace::{TapTraceEntry, collect_trace};
+
+/// Request header that asks for a tap trace (any value).
+pub const DEBUG_TAPS_HEADER: &str = "x-trovato-debug-taps";
+
+/// Largest JSON response that is rewritten to include the trace.
+const MAX_TRACED_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// Whether the request asks for a tap trace.
+fn wants_trace<B>(request: &Request<B>) -> bool {
+    request.headers().contains_key(DEBUG_TAPS_HEADER)
+        || request
+            .uri()
+            .query()
+            .is_some_and(|q| q.split('&').any(|pair| pair == "_debug=taps"))
+}
+
+/// Record the taps invoked for admin requests that ask for a trace.
+pub async fn trace_taps(
+    State(state): State<AppState>,
+    session: Session,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !wants_trace(&request) || require_admin(&state, &session).await.is_err() {
+        return next.run(request).await;
+    }
+
+    let request_id = Uuid::now_v7().to_string();
+    let path = request.uri().path().to_string();
+    let (response, trace) = collect_trace(next.run(request)).await;
+
+    for entry in &trace {
+        tracing::info!(
+            request_id = %request_id,
+            path = %path,
+            tap = %entry.tap,
+            plugin = %entry.plugin,
+            input_bytes = entry.input_bytes,
+            duration_ms = entry.duration_ms,
+            ok = entry.ok,
+            output_bytes = entry.output_bytes,
+            summary = %entry.summary,
+            "tap trace"
+        );
+    }
+
+    let mut response = attach_trace(response, &request_id, trace).await;
+    if let Ok(val) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert("x-request-id", val);
+    }
+    response
+}
+
+/// Add a `_debug` section to JSON object responses; pass others through.
+async fn attach_trace(response: Response, request_id: &str, trace: Vec<TapTraceEntry>) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_TRACED_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to buffer response for tap trace");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let Ok(serde_json::Value::Object(mut object)) = serde_json::from_slice(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    object.insert(
+        "_debug".to_string(),
+        serde_json::json!({ "request_id": request_id, "taps": trace }),
+    );
+    let Ok(body) = serde_json::to_vec(&object) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str) -> Request<()> {
+        Request::get(uri).body(()).unwrap()
+    }
+
+    #[test]
+    fn trace_requested_by_query_or_header() {
+        assert!(wants_trace(&request("/api/item/1?_debug=taps")));
+        assert!(wants_trace(&request("/api/item/1?page=2&_debug=taps")));
+        assert!(!wants_trace(&request("/api/item/1?_debug=tapsx")));
+        assert!(!wants_trace(&request("/api/item/1")));
+
+        let mut with_header = request("/item/1");
+        with_header
+            .headers_mut()
+            .insert(DEBUG_TAPS_HEADER, HeaderValue::from_static("1"));
+        assert!(wants_trace(&with_header));
+    }
+
+    #[tokio::test]
+    async fn attaches_debug_section_to_json_objects() {
+        let response = axum::Json(serde_json::json!({"title": "Hello"})).into_response();
+        let response = attach_trace(response, "req-1", Vec::new()).await;
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["title"], "Hello");
+        assert_eq!(body["_debug"]["request_id"], "req-1");
+        assert!(body["_debug"]["taps"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn leaves_non_object_json_untouched() {
+        let response = axum::Json(serde_json::json!([1, 2])).into_response();
+        let response = attach_trace(response, "req-1", Vec::new()).await;
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"[1,2]");
+    }
+}
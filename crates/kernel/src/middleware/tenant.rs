@@ -1,36 +1,82 @@
 //! Tenant resolution middleware.
 //!
 //! Resolves the active tenant for each request and stores it in
-//! request extensions as `TenantContext`. Runs after auth middleware,
-//! before route handlers.
+//! request extensions as `TenantContext`. Runs right after CORS, before
+//! sessions, so everything downstream sees the resolved tenant.
 //!
-//! Resolution strategies:
+//! Resolution strategies (`TENANT_RESOLUTION_METHOD`):
 //! - `default`: always resolves to `DEFAULT_TENANT_ID` (zero overhead for single-tenant)
-//! - `subdomain`: `tenant-a.example.com` → look up by machine_name
-//! - `path_prefix`: `/t/tenant-a/...` → strip prefix and resolve
-//! - `header`: `X-Tenant-ID: {uuid}` → direct UUID resolution
+//! - `host`: `Host: blog.example.com` → look up in the `site` table (multisite);
+//!   the rest of the request runs in the site's scope (see [`crate::services::site`])
+//! - `header`: `X-Tenant-ID: {uuid}` → direct UUID resolution (not scoped)
 
-use axum::{body::Body, http::Request, middleware::Next, response::Response};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, header},
+    middleware::Next,
+    response::Response,
+};
 
 use crate::models::tenant::{DEFAULT_TENANT_ID, TenantContext};
+use crate::services::site::{self, SiteScope};
+use crate::state::AppState;
 
 /// Resolve the tenant for the current request.
 ///
-/// The resolution method is controlled by `TENANT_RESOLUTION_METHOD` env var.
-/// Default is `"default"` — always returns `DEFAULT_TENANT_ID` with zero
-/// database overhead (static `TenantContext` construction).
-pub async fn resolve_tenant(mut request: Request<Body>, next: Next) -> Response {
-    let method = std::env::var("TENANT_RESOLUTION_METHOD").unwrap_or_default();
-
-    let tenant_context = match method.as_str() {
-        "header" => resolve_from_header(&request),
-        // "subdomain" and "path_prefix" require database lookups —
-        // deferred until a multi-tenant deployment needs them.
-        _ => TenantContext::default_tenant(),
+/// The default method returns `DEFAULT_TENANT_ID` with zero database
+/// overhead (static `TenantContext` construction) and no site scope.
+pub async fn resolve_tenant(
+    State(state): State<AppState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    let (tenant_context, scope) = match state.sites().method() {
+        "host" => {
+            let host = request_host(&request);
+            let scope = resolve_from_host(&state, &host).await;
+            let tenant_context = TenantContext {
+                id: scope.tenant_id,
+                name: String::new(),
+                machine_name: String::new(),
+            };
+            (tenant_context, Some(scope))
+        }
+        // The header is client-controlled, so it only labels the request;
+        // content and config are not scoped by it.
+        "header" => (resolve_from_header(&request), None),
+        // "subdomain" and "path_prefix" are covered by "host" with one
+        // `site` row per hostname.
+        _ => (TenantContext::default_tenant(), None),
     };
 
     request.extensions_mut().insert(tenant_context);
-    next.run(request).await
+    match scope {
+        Some(scope) => site::scope(scope, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+/// The `Host` header, or the URI authority for HTTP/2 requests.
+fn request_host(request: &Request<Body>) -> String {
+    request
+        .headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| request.uri().host())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Resolve the site for a hostname; unknown hosts get the default.
+async fn resolve_from_host(state: &AppState, host: &str) -> SiteScope {
+    match state.sites().resolve(host).await {
+        Ok(site) => SiteScope::clone(&site),
+        Err(e) => {
+            tracing::warn!(error = %e, host = %host, "failed to resolve site; using default");
+            SiteScope::default_site()
+        }
+    }
 }
 
 /// Resolve tenant from `X-Tenant-ID` header (UUID).
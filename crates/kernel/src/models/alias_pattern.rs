@@ -0,0 +1,215 @@
+//! URL alias patterns.
+//!
+//! An alias pattern is a config entity telling pathauto how to build the
+//! URL alias of items of one content type, e.g. `blog/[title-slug]`. A
+//! pattern may be limited to one language, overriding the type's general
+//! pattern for items in that language. Patterns are expanded by
+//! [`crate::services::pathauto`].
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::services::pathauto;
+
+/// Maximum length of an item type machine name.
+const MAX_TYPE_LENGTH: usize = 64;
+
+/// Maximum length of a language code.
+const MAX_LANGUAGE_LENGTH: usize = 12;
+
+/// Separator between item type and language in a language pattern's id.
+const LANGUAGE_SEPARATOR: &str = "__";
+
+/// A URL alias pattern for a content type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AliasPattern {
+    /// Machine name: the item type, or `{item_type}__{language}` for a
+    /// language pattern (see [`AliasPattern::id_for`]).
+    pub id: String,
+    /// Content type the pattern applies to.
+    pub item_type: String,
+    /// Language the pattern is limited to; `None` applies to all languages.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// The pattern, e.g. `stories/[field_topic:name]/[title-slug]`.
+    pub pattern: String,
+    /// Unix timestamp when created.
+    #[serde(default)]
+    pub created: i64,
+    /// Unix timestamp when last changed.
+    #[serde(default)]
+    pub changed: i64,
+}
+
+impl AliasPattern {
+    /// Create a pattern for an item type and optional language.
+    pub fn new(item_type: &str, language: Option<&str>, pattern: &str) -> Self {
+        Self {
+            id: Self::id_for(item_type, language),
+            item_type: item_type.to_string(),
+            language: language.map(String::from),
+            pattern: pattern.to_string(),
+            created: 0,
+            changed: 0,
+        }
+    }
+
+    /// The id of the pattern for an item type and optional language.
+    pub fn id_for(item_type: &str, language: Option<&str>) -> String {
+        match language {
+            Some(language) => format!("{item_type}{LANGUAGE_SEPARATOR}{language}"),
+            None => item_type.to_string(),
+        }
+    }
+
+    /// Check the id matches the item type and language, and the pattern
+    /// only uses known tokens.
+    pub fn validate(&self) -> Result<()> {
+        let valid_type = !self.item_type.is_empty()
+            && self.item_type.len() <= MAX_TYPE_LENGTH
+            && self
+                .item_type
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_type {
+            bail!("invalid item type machine name: '{}'", self.item_type);
+        }
+        if let Some(language) = &self.language {
+            let valid_language = !language.is_empty()
+                && language.len() <= MAX_LANGUAGE_LENGTH
+                && language
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid_language {
+                bail!("invalid language code: '{language}'");
+            }
+        }
+        let expected = Self::id_for(&self.item_type, self.language.as_deref());
+        if self.id != expected {
+            bail!("alias pattern id '{}' should be '{expected}'", self.id);
+        }
+        pathauto::validate_pattern(&self.pattern)
+            .with_context(|| format!("invalid pattern for '{}'", self.id))
+    }
+
+    /// Find a pattern by id.
+    pub async fn find_by_id(pool: &PgPool, id: &str) -> Result<Option<Self>> {
+        sqlx::query_as::<_, Self>("SELECT * FROM alias_pattern WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("failed to fetch alias pattern")
+    }
+
+    /// Find the pattern for items of `item_type` in `language`.
+    ///
+    /// A pattern for the language wins over the type's general pattern.
+    pub async fn find_for(pool: &PgPool, item_type: &str, language: &str) -> Result<Option<Self>> {
+        sqlx::query_as::<_, Self>(
+            "SELECT * FROM alias_pattern \
+             WHERE item_type = $1 AND (language = $2 OR language IS NULL) \
+             ORDER BY language NULLS LAST LIMIT 1",
+        )
+        .bind(item_type)
+        .bind(language)
+        .fetch_optional(pool)
+        .await
+        .context("failed to fetch alias pattern for item type")
+    }
+
+    /// List all patterns ordered by id.
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Self>("SELECT * FROM alias_pattern ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .context("failed to list alias patterns")
+    }
+
+    /// Validate and insert or update a pattern.
+    pub async fn upsert(pool: &PgPool, pattern: &Self) -> Result<Self> {
+        pattern.validate()?;
+        let now = chrono::Utc::now().timestamp();
+        let created = if pattern.created > 0 {
+            pattern.created
+        } else {
+            now
+        };
+
+        sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO alias_pattern (id, item_type, language, pattern, created, changed)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (id) DO UPDATE SET
+                pattern = EXCLUDED.pattern,
+                changed = EXCLUDED.changed
+            RETURNING *
+            "#,
+        )
+        .bind(&pattern.id)
+        .bind(&pattern.item_type)
+        .bind(&pattern.language)
+        .bind(pattern.pattern.trim())
+        .bind(created)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .context("failed to save alias pattern")
+    }
+
+    /// Delete a pattern. Aliases generated from it are kept.
+    pub async fn delete(pool: &PgPool, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM alias_pattern WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to delete alias pattern")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_follow_type_and_language() {
+        let general = AliasPattern::new("blog", None, "blog/[title-slug]");
+        assert_eq!(general.id, "blog");
+        assert!(general.validate().is_ok());
+
+        let italian = AliasPattern::new("blog", Some("it"), "it/blog/[title-slug]");
+        assert_eq!(italian.id, "blog__it");
+        assert!(italian.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_entities() {
+        let mut pattern = AliasPattern::new("blog", None, "blog/[title-slug]");
+        pattern.id = "news".to_string();
+        assert!(pattern.validate().is_err());
+
+        assert!(
+            AliasPattern::new("Blog Post", None, "[title]")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            AliasPattern::new("blog", Some("it/x"), "[title]")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            AliasPattern::new("blog", None, "blog/[author]")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            AliasPattern::new("blog", None, "admin/[title]")
+                .validate()
+                .is_err()
+        );
+    }
+}
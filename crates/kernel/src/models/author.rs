@@ -0,0 +1,202 @@
+//! Public author profile derived from a user account.
+//!
+//! Author metadata lives in the user's `data` JSONB under the `author`
+//! key so it can be edited from the profile form without a schema change:
+//!
+//! ```json
+//! { "author": { "display_name": "Ada", "bio": "…", "website": "https://…", "hidden": false } }
+//! ```
+//!
+//! Users who set `hidden` opt out of the public `/author/{name}` page and
+//! feed entirely; the routes respond with 404 as if the author did not exist.
+
+use serde::{Deserialize, Serialize};
+
+use super::User;
+
+/// Key in `users.data` holding the author profile object.
+pub const AUTHOR_DATA_KEY: &str = "author";
+
+/// Maximum length of the author bio in characters.
+pub const MAX_BIO_LENGTH: usize = 2000;
+
+/// Author metadata stored in `users.data.author`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthorSettings {
+    /// Name shown on author pages instead of the username.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Short biography (plain text).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bio: Option<String>,
+    /// Personal website URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    /// Opt out of the public author page and feed.
+    pub hidden: bool,
+}
+
+impl AuthorSettings {
+    /// Read author settings from a user's `data`, tolerating missing or
+    /// malformed values.
+    pub fn from_user(user: &User) -> Self {
+        user.data
+            .get(AUTHOR_DATA_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write these settings into a copy of `data`, preserving other keys.
+    pub fn merge_into(&self, data: &serde_json::Value) -> serde_json::Value {
+        let mut data = data.clone();
+        if !data.is_object() {
+            data = serde_json::json!({});
+        }
+        if let Some(obj) = data.as_object_mut() {
+            obj.insert(
+                AUTHOR_DATA_KEY.to_string(),
+                serde_json::to_value(self).unwrap_or_default(),
+            );
+        }
+        data
+    }
+}
+
+/// Public view of an author for templates and feeds.
+///
+/// Contains only fields safe to expose to anonymous visitors — never the
+/// email address or account flags.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorProfile {
+    /// Username (used in the URL).
+    pub name: String,
+    /// Display name, falling back to the username.
+    pub display_name: String,
+    /// Biography, if set.
+    pub bio: Option<String>,
+    /// Website URL, if set and using http(s).
+    pub website: Option<String>,
+    /// Canonical author page path.
+    pub path: String,
+    /// Author feed path.
+    pub feed_path: String,
+}
+
+impl AuthorProfile {
+    /// Build the public profile for a user.
+    ///
+    /// Returns `None` if the account is inactive or the user opted out.
+    pub fn for_user(user: &User) -> Option<Self> {
+        if !user.is_active() {
+            return None;
+        }
+        let settings = AuthorSettings::from_user(user);
+        if settings.hidden {
+            return None;
+        }
+
+        let display_name = settings
+            .display_name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| user.name.clone());
+        let website = settings
+            .website
+            .filter(|w| w.starts_with("https://") || w.starts_with("http://"));
+        let encoded = urlencoding::encode(&user.name);
+
+        Some(Self {
+            name: user.name.clone(),
+            display_name,
+            bio: settings.bio.filter(|b| !b.trim().is_empty()),
+            website,
+            path: format!("/author/{encoded}"),
+            feed_path: format!("/author/{encoded}/feed"),
+        })
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user_with_data(data: serde_json::Value) -> User {
+        User {
+            id: uuid::Uuid::now_v7(),
+            name: "ada".to_string(),
+            pass: String::new(),
+            mail: "ada@example.com".to_string(),
+            is_admin: false,
+            created: chrono::Utc::now(),
+            access: None,
+            login: None,
+            status: 1,
+            timezone: None,
+            language: None,
+            data,
+            consent_given: None,
+            consent_date: None,
+            consent_version: None,
+            data_retention_days: None,
+            password_changed: chrono::Utc::now(),
+            fields: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn profile_falls_back_to_username() {
+        let user = user_with_data(json!({}));
+        let profile = AuthorProfile::for_user(&user).unwrap();
+        assert_eq!(profile.display_name, "ada");
+        assert_eq!(profile.path, "/author/ada");
+        assert_eq!(profile.feed_path, "/author/ada/feed");
+        assert!(profile.bio.is_none());
+    }
+
+    #[test]
+    fn profile_uses_author_settings() {
+        let user = user_with_data(json!({
+            "author": {
+                "display_name": "Ada Lovelace",
+                "bio": "Analyst",
+                "website": "https://example.com"
+            }
+        }));
+        let profile = AuthorProfile::for_user(&user).unwrap();
+        assert_eq!(profile.display_name, "Ada Lovelace");
+        assert_eq!(profile.bio.as_deref(), Some("Analyst"));
+        assert_eq!(profile.website.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn profile_rejects_non_http_website() {
+        let user = user_with_data(json!({"author": {"website": "javascript:alert(1)"}}));
+        let profile = AuthorProfile::for_user(&user).unwrap();
+        assert!(profile.website.is_none());
+    }
+
+    #[test]
+    fn hidden_or_inactive_users_have_no_profile() {
+        let hidden = user_with_data(json!({"author": {"hidden": true}}));
+        assert!(AuthorProfile::for_user(&hidden).is_none());
+
+        let mut blocked = user_with_data(json!({}));
+        blocked.status = 0;
+        assert!(AuthorProfile::for_user(&blocked).is_none());
+    }
+
+    #[test]
+    fn merge_preserves_other_data_keys() {
+        let data = json!({"pending_email": "new@example.com"});
+        let settings = AuthorSettings {
+            hidden: true,
+            ..Default::default()
+        };
+        let merged = settings.merge_into(&data);
+        assert_eq!(merged["pending_email"], "new@example.com");
+        assert_eq!(merged["author"]["hidden"], true);
+    }
+}
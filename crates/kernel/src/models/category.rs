@@ -138,6 +138,19 @@ impl Category {
         Ok(category)
     }
 
+    /// Find several categories by ID in one query.
+    pub async fn find_many(pool: &PgPool, ids: &[String]) -> Result<Vec<Self>> {
+        let categories = sqlx::query_as::<_, Self>(
+            "SELECT id, label, description, hierarchy, weight FROM category WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+        .context("failed to fetch categories")?;
+
+        Ok(categories)
+    }
+
     /// List all categories ordered by weight.
     pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
         let categories = sqlx::query_as::<_, Self>(
@@ -240,6 +253,19 @@ impl Tag {
         Ok(tag)
     }
 
+    /// Find several tags by ID in one query.
+    pub async fn find_many(pool: &PgPool, ids: &[Uuid]) -> Result<Vec<Self>> {
+        let tags = sqlx::query_as::<_, Self>(
+            "SELECT id, category_id, label, description, slug, weight, created, changed FROM category_tag WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+        .context("failed to fetch tags")?;
+
+        Ok(tags)
+    }
+
     /// Find a tag by slug within a category.
     ///
     /// Uses the partial unique index `idx_category_tag_category_slug` for lookups.
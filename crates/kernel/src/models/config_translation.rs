@@ -0,0 +1,120 @@
+//! Config translation overlays (`config_translation` table).
+//!
+//! The table belongs to the `trovato_config_translation` plugin; callers
+//! check the plugin is enabled before using it. Each row holds the
+//! translation of one property of one config entity in one language.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// A translated property of a config entity.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ConfigTranslation {
+    /// Config entity type (e.g., "item_type").
+    pub entity_type: String,
+
+    /// Config entity ID.
+    pub entity_id: String,
+
+    /// Language code.
+    pub language: String,
+
+    /// Translated property (e.g., "label").
+    pub property: String,
+
+    /// Translated value.
+    pub value: String,
+
+    /// Unix timestamp when last changed.
+    pub changed: i64,
+}
+
+impl ConfigTranslation {
+    /// Load the translations of one entity in a language.
+    pub async fn for_entity(
+        pool: &PgPool,
+        entity_type: &str,
+        entity_id: &str,
+        language: &str,
+    ) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Self>(
+            "SELECT entity_type, entity_id, language, property, value, changed \
+             FROM config_translation \
+             WHERE entity_type = $1 AND entity_id = $2 AND language = $3",
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(language)
+        .fetch_all(pool)
+        .await
+        .context("failed to load config translations")
+    }
+
+    /// Load the translations of all entities of a type in a language.
+    pub async fn for_type(pool: &PgPool, entity_type: &str, language: &str) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Self>(
+            "SELECT entity_type, entity_id, language, property, value, changed \
+             FROM config_translation \
+             WHERE entity_type = $1 AND language = $2",
+        )
+        .bind(entity_type)
+        .bind(language)
+        .fetch_all(pool)
+        .await
+        .context("failed to load config translations")
+    }
+
+    /// Create or update the translation of a property.
+    pub async fn upsert(
+        pool: &PgPool,
+        entity_type: &str,
+        entity_id: &str,
+        language: &str,
+        property: &str,
+        value: &str,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO config_translation \
+             (entity_type, entity_id, language, property, value, changed) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (entity_type, entity_id, language, property) \
+             DO UPDATE SET value = EXCLUDED.value, changed = EXCLUDED.changed",
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(language)
+        .bind(property)
+        .bind(value)
+        .bind(now)
+        .execute(pool)
+        .await
+        .context("failed to save config translation")?;
+        Ok(())
+    }
+
+    /// Delete the translation of a property.
+    ///
+    /// Returns `true` if a translation was deleted.
+    pub async fn delete(
+        pool: &PgPool,
+        entity_type: &str,
+        entity_id: &str,
+        language: &str,
+        property: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM config_translation \
+             WHERE entity_type = $1 AND entity_id = $2 AND language = $3 AND property = $4",
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(language)
+        .bind(property)
+        .execute(pool)
+        .await
+        .context("failed to delete config translation")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,237 @@
+//! Contact form categories and submissions.
+//!
+//! A category names a topic on the site contact form ("General",
+//! "Press", ...) and the addresses its messages are delivered to.
+//! Every accepted submission is stored as a lightweight record for the
+//! admin listing and CSV export.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Contact form category.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ContactCategory {
+    /// Unique identifier (UUIDv7).
+    pub id: Uuid,
+    /// Label shown on the contact form.
+    pub name: String,
+    /// Addresses that receive messages for this category.
+    pub recipients: Vec<String>,
+    /// Sort order on the form (lower first).
+    pub weight: i32,
+    /// Unix timestamp when the category was created.
+    pub created: i64,
+}
+
+/// Input for creating or updating a contact category.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateContactCategory {
+    pub name: String,
+    pub recipients: Vec<String>,
+    pub weight: i32,
+}
+
+impl ContactCategory {
+    /// List all categories in form order.
+    pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
+        let categories = sqlx::query_as::<_, Self>(
+            "SELECT id, name, recipients, weight, created FROM contact_category ORDER BY weight, name",
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to list contact categories")?;
+
+        Ok(categories)
+    }
+
+    /// Find a category by ID.
+    pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let category = sqlx::query_as::<_, Self>(
+            "SELECT id, name, recipients, weight, created FROM contact_category WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to fetch contact category")?;
+
+        Ok(category)
+    }
+
+    /// Create a category.
+    pub async fn create(pool: &PgPool, input: CreateContactCategory) -> Result<Self> {
+        let category = sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO contact_category (id, name, recipients, weight, created)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, recipients, weight, created
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(&input.name)
+        .bind(&input.recipients)
+        .bind(input.weight)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(pool)
+        .await
+        .context("failed to create contact category")?;
+
+        Ok(category)
+    }
+
+    /// Update a category.
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        input: CreateContactCategory,
+    ) -> Result<Option<Self>> {
+        let category = sqlx::query_as::<_, Self>(
+            r#"
+            UPDATE contact_category
+            SET name = $2, recipients = $3, weight = $4
+            WHERE id = $1
+            RETURNING id, name, recipients, weight, created
+            "#,
+        )
+        .bind(id)
+        .bind(&input.name)
+        .bind(&input.recipients)
+        .bind(input.weight)
+        .fetch_optional(pool)
+        .await
+        .context("failed to update contact category")?;
+
+        Ok(category)
+    }
+
+    /// Delete a category. Stored submissions keep the category name.
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM contact_category WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to delete contact category")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// A stored contact form submission.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ContactSubmission {
+    /// Unique identifier (UUIDv7).
+    pub id: Uuid,
+    /// Category the message was sent to (`None` once deleted).
+    pub category_id: Option<Uuid>,
+    /// Category name at the time of submission.
+    pub category_name: String,
+    /// Sender name.
+    pub name: String,
+    /// Sender email address.
+    pub mail: String,
+    /// Subject line.
+    pub subject: String,
+    /// Message body (plain text).
+    pub message: String,
+    /// Sender's account, if they were logged in.
+    pub user_id: Option<Uuid>,
+    /// Unix timestamp of the submission.
+    pub created: i64,
+}
+
+/// Input for storing a contact form submission.
+#[derive(Debug, Clone)]
+pub struct CreateContactSubmission {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub name: String,
+    pub mail: String,
+    pub subject: String,
+    pub message: String,
+    pub user_id: Option<Uuid>,
+}
+
+impl ContactSubmission {
+    /// Store a submission.
+    pub async fn create(pool: &PgPool, input: CreateContactSubmission) -> Result<Self> {
+        let submission = sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO contact_submission
+                (id, category_id, category_name, name, mail, subject, message, user_id, created)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, category_id, category_name, name, mail, subject, message, user_id, created
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(input.category_id)
+        .bind(&input.category_name)
+        .bind(&input.name)
+        .bind(&input.mail)
+        .bind(&input.subject)
+        .bind(&input.message)
+        .bind(input.user_id)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(pool)
+        .await
+        .context("failed to store contact submission")?;
+
+        Ok(submission)
+    }
+
+    /// List submissions, newest first.
+    pub async fn list(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<Self>> {
+        let submissions = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT id, category_id, category_name, name, mail, subject, message, user_id, created
+            FROM contact_submission
+            ORDER BY created DESC, id DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list contact submissions")?;
+
+        Ok(submissions)
+    }
+
+    /// List every submission, newest first (for export).
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<Self>> {
+        let submissions = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT id, category_id, category_name, name, mail, subject, message, user_id, created
+            FROM contact_submission
+            ORDER BY created DESC, id DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to export contact submissions")?;
+
+        Ok(submissions)
+    }
+
+    /// Count all submissions.
+    pub async fn count(pool: &PgPool) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM contact_submission")
+            .fetch_one(pool)
+            .await
+            .context("failed to count contact submissions")?;
+
+        Ok(count)
+    }
+
+    /// Delete a submission.
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM contact_submission WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to delete contact submission")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -134,13 +134,13 @@ impl EmailVerificationToken {
 }
 
 /// Generate a secure random token.
-fn generate_token() -> String {
+pub(crate) fn generate_token() -> String {
     let bytes: [u8; 32] = rand::random();
     hex::encode(bytes)
 }
 
 /// Hash a token for storage using SHA-256.
-fn hash_token(token: &str) -> String {
+pub(crate) fn hash_token(token: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(token.as_bytes());
     hex::encode(hasher.finalize())
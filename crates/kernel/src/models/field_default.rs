@@ -0,0 +1,384 @@
+//! Role-conditional field defaults for newly created items.
+//!
+//! Rules are stored per content type. When an item is created without a
+//! value for a field, the first rule (by weight) whose role the creating
+//! user holds fills it in — for example `ng_device.owner_id` from the
+//! person linked to the creator's account, or `field_tags` per team role.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Where a default value comes from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum FieldDefaultValue {
+    /// A fixed value, stored as given.
+    Value { value: Value },
+    /// The creating user's ID.
+    Creator,
+    /// A profile field on the creating user's account (see `tap_user_info`).
+    CreatorField { field: String },
+}
+
+/// A stored default rule for one field of a content type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldDefaultRule {
+    /// Unique identifier (UUIDv7).
+    pub id: Uuid,
+    /// Content type machine name.
+    pub item_type: String,
+    /// Field the rule fills in.
+    pub field_name: String,
+    /// Role the creator must hold; `None` matches every creator.
+    pub role_id: Option<Uuid>,
+    /// Source of the default value.
+    pub default_value: FieldDefaultValue,
+    /// Evaluation order; lower weights are tried first.
+    pub weight: i32,
+    /// Unix timestamp when the rule was created.
+    pub created: i64,
+}
+
+/// Input for creating a default rule.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateFieldDefaultRule {
+    pub field_name: String,
+    #[serde(default)]
+    pub role_id: Option<Uuid>,
+    pub default_value: FieldDefaultValue,
+    #[serde(default)]
+    pub weight: i32,
+}
+
+/// The user creating an item, as seen by default rules.
+#[derive(Debug, Clone)]
+pub struct Creator<'a> {
+    /// User ID (`Uuid::nil()` for anonymous).
+    pub id: Uuid,
+    /// Whether the user is authenticated.
+    pub authenticated: bool,
+    /// Roles the user holds, including the implicit authenticated or
+    /// anonymous role.
+    pub role_ids: &'a [Uuid],
+    /// The user's profile fields (`users.fields`).
+    pub fields: &'a Value,
+}
+
+#[derive(sqlx::FromRow)]
+struct FieldDefaultRuleRow {
+    id: Uuid,
+    item_type: String,
+    field_name: String,
+    role_id: Option<Uuid>,
+    default_value: Value,
+    weight: i32,
+    created: i64,
+}
+
+impl TryFrom<FieldDefaultRuleRow> for FieldDefaultRule {
+    type Error = serde_json::Error;
+
+    fn try_from(row: FieldDefaultRuleRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            item_type: row.item_type,
+            field_name: row.field_name,
+            role_id: row.role_id,
+            default_value: serde_json::from_value(row.default_value)?,
+            weight: row.weight,
+            created: row.created,
+        })
+    }
+}
+
+impl FieldDefaultRule {
+    /// List the rules for a content type in evaluation order.
+    ///
+    /// Rows whose `default_value` no longer parses are logged and skipped.
+    pub async fn list_for_type(pool: &PgPool, item_type: &str) -> Result<Vec<Self>> {
+        let rows = sqlx::query_as::<_, FieldDefaultRuleRow>(
+            r#"
+            SELECT id, item_type, field_name, role_id, default_value, weight, created
+            FROM item_type_field_default
+            WHERE item_type = $1
+            ORDER BY weight, created
+            "#,
+        )
+        .bind(item_type)
+        .fetch_all(pool)
+        .await
+        .context("failed to list field default rules")?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let id = row.id;
+                match Self::try_from(row) {
+                    Ok(rule) => Some(rule),
+                    Err(e) => {
+                        tracing::warn!(rule_id = %id, error = %e, "invalid field default rule");
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    /// Create a rule for a content type.
+    pub async fn create(
+        pool: &PgPool,
+        item_type: &str,
+        input: CreateFieldDefaultRule,
+    ) -> Result<Self> {
+        let rule = Self {
+            id: Uuid::now_v7(),
+            item_type: item_type.to_string(),
+            field_name: input.field_name,
+            role_id: input.role_id,
+            default_value: input.default_value,
+            weight: input.weight,
+            created: chrono::Utc::now().timestamp(),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO item_type_field_default
+                (id, item_type, field_name, role_id, default_value, weight, created)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(rule.id)
+        .bind(&rule.item_type)
+        .bind(&rule.field_name)
+        .bind(rule.role_id)
+        .bind(serde_json::to_value(&rule.default_value)?)
+        .bind(rule.weight)
+        .bind(rule.created)
+        .execute(pool)
+        .await
+        .context("failed to create field default rule")?;
+
+        Ok(rule)
+    }
+
+    /// Delete a rule of a content type.
+    pub async fn delete(pool: &PgPool, item_type: &str, id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM item_type_field_default WHERE id = $1 AND item_type = $2")
+                .bind(id)
+                .bind(item_type)
+                .execute(pool)
+                .await
+                .context("failed to delete field default rule")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether the creator holds the rule's role.
+    pub fn matches(&self, creator: &Creator<'_>) -> bool {
+        self.role_id
+            .is_none_or(|role_id| creator.role_ids.contains(&role_id))
+    }
+
+    /// Resolve the default value for a creator.
+    ///
+    /// Returns `None` when the source has nothing to offer (anonymous
+    /// creator, or an unset profile field).
+    pub fn resolve(&self, creator: &Creator<'_>) -> Option<Value> {
+        match &self.default_value {
+            FieldDefaultValue::Value { value } => Some(value.clone()),
+            FieldDefaultValue::Creator => creator
+                .authenticated
+                .then(|| Value::String(creator.id.to_string())),
+            FieldDefaultValue::CreatorField { field } => creator
+                .fields
+                .get(field)
+                .filter(|v| !is_empty_value(v))
+                .cloned(),
+        }
+    }
+}
+
+/// Fill in missing fields from default rules.
+///
+/// `rules` must be in evaluation order. A field counts as missing when it
+/// is absent, `null`, an empty string, or an empty array; values supplied
+/// by the caller are never overwritten. Returns the names of the fields
+/// that were filled in.
+pub fn apply_field_defaults(
+    rules: &[FieldDefaultRule],
+    creator: &Creator<'_>,
+    fields: &mut Map<String, Value>,
+) -> Vec<String> {
+    let mut applied = Vec::new();
+
+    for rule in rules {
+        if fields
+            .get(&rule.field_name)
+            .is_some_and(|v| !is_empty_value(v))
+            || !rule.matches(creator)
+        {
+            continue;
+        }
+        if let Some(value) = rule.resolve(creator) {
+            fields.insert(rule.field_name.clone(), value);
+            applied.push(rule.field_name.clone());
+        }
+    }
+
+    applied
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const EDITORS: Uuid = Uuid::from_u128(10);
+    const TEAM_A: Uuid = Uuid::from_u128(11);
+
+    fn rule(
+        field: &str,
+        role_id: Option<Uuid>,
+        default_value: FieldDefaultValue,
+    ) -> FieldDefaultRule {
+        FieldDefaultRule {
+            id: Uuid::now_v7(),
+            item_type: "ng_device".to_string(),
+            field_name: field.to_string(),
+            role_id,
+            default_value,
+            weight: 0,
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn default_value_serde_uses_source_tag() {
+        let parsed: FieldDefaultValue =
+            serde_json::from_value(json!({"source": "creator_field", "field": "ng_person"}))
+                .unwrap();
+        assert_eq!(
+            parsed,
+            FieldDefaultValue::CreatorField {
+                field: "ng_person".to_string()
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(FieldDefaultValue::Creator).unwrap(),
+            json!({"source": "creator"})
+        );
+    }
+
+    #[test]
+    fn first_matching_rule_fills_missing_fields() {
+        let person = Uuid::now_v7().to_string();
+        let profile = json!({"ng_person": person});
+        let user_id = Uuid::now_v7();
+        let roles = [EDITORS, TEAM_A];
+        let creator = Creator {
+            id: user_id,
+            authenticated: true,
+            role_ids: &roles,
+            fields: &profile,
+        };
+        let rules = vec![
+            rule(
+                "owner_id",
+                Some(EDITORS),
+                FieldDefaultValue::CreatorField {
+                    field: "ng_person".to_string(),
+                },
+            ),
+            rule("owner_id", None, FieldDefaultValue::Creator),
+            rule(
+                "field_tags",
+                Some(TEAM_A),
+                FieldDefaultValue::Value {
+                    value: json!(["team-a"]),
+                },
+            ),
+            rule("created_by", None, FieldDefaultValue::Creator),
+        ];
+
+        let mut fields = json!({"field_tags": [], "name": "router"})
+            .as_object()
+            .cloned()
+            .unwrap();
+        let applied = apply_field_defaults(&rules, &creator, &mut fields);
+
+        assert_eq!(applied, vec!["owner_id", "field_tags", "created_by"]);
+        assert_eq!(fields["owner_id"], json!(person));
+        assert_eq!(fields["field_tags"], json!(["team-a"]));
+        assert_eq!(fields["created_by"], json!(user_id.to_string()));
+    }
+
+    #[test]
+    fn supplied_values_and_unmatched_roles_are_left_alone() {
+        let profile = json!({});
+        let roles = [TEAM_A];
+        let creator = Creator {
+            id: Uuid::now_v7(),
+            authenticated: true,
+            role_ids: &roles,
+            fields: &profile,
+        };
+        let rules = vec![
+            rule(
+                "field_tags",
+                Some(EDITORS),
+                FieldDefaultValue::Value {
+                    value: json!(["editors"]),
+                },
+            ),
+            rule("owner_id", None, FieldDefaultValue::Creator),
+            rule(
+                "ng_person",
+                None,
+                FieldDefaultValue::CreatorField {
+                    field: "ng_person".to_string(),
+                },
+            ),
+        ];
+
+        let mut fields = json!({"owner_id": "explicit"})
+            .as_object()
+            .cloned()
+            .unwrap();
+        let applied = apply_field_defaults(&rules, &creator, &mut fields);
+
+        assert!(applied.is_empty());
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields["owner_id"], "explicit");
+    }
+
+    #[test]
+    fn anonymous_creator_has_no_creator_default() {
+        let profile = json!({});
+        let creator = Creator {
+            id: Uuid::nil(),
+            authenticated: false,
+            role_ids: &[],
+            fields: &profile,
+        };
+        assert!(
+            rule("owner_id", None, FieldDefaultValue::Creator)
+                .resolve(&creator)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Per-field change history for fields declared with `track_history`.
+//!
+//! Each row records a single field's old and new value along with the
+//! user and revision responsible for the change.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum number of history entries returned by a single query.
+pub const MAX_HISTORY_ENTRIES: i64 = 500;
+
+/// A single recorded field change.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FieldHistoryEntry {
+    /// Unique identifier (UUIDv7).
+    pub id: Uuid,
+    /// Item the field belongs to.
+    pub item_id: Uuid,
+    /// Field machine name.
+    pub field_name: String,
+    /// Value before the change (`None` if the field was absent).
+    pub old_value: Option<serde_json::Value>,
+    /// Value after the change (`None` if the field was removed).
+    pub new_value: Option<serde_json::Value>,
+    /// User who made the change.
+    pub changed_by: Uuid,
+    /// Revision that introduced the change, if any.
+    pub revision_id: Option<Uuid>,
+    /// Unix timestamp of the change.
+    pub changed: i64,
+}
+
+/// A detected change to a tracked field, prior to persistence.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Field machine name.
+    pub field_name: String,
+    /// Previous value (`None` if absent).
+    pub old_value: Option<serde_json::Value>,
+    /// New value (`None` if removed).
+    pub new_value: Option<serde_json::Value>,
+}
+
+impl FieldHistoryEntry {
+    /// Persist a batch of field changes for an item.
+    ///
+    /// All rows share the same timestamp, user, and revision.
+    pub async fn record(
+        pool: &PgPool,
+        item_id: Uuid,
+        changed_by: Uuid,
+        revision_id: Option<Uuid>,
+        changes: &[FieldChange],
+    ) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let mut tx = pool.begin().await.context("failed to start transaction")?;
+
+        for change in changes {
+            sqlx::query(
+                r#"
+                INSERT INTO field_history
+                    (id, item_id, field_name, old_value, new_value, changed_by, revision_id, changed)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+            )
+            .bind(Uuid::now_v7())
+            .bind(item_id)
+            .bind(&change.field_name)
+            .bind(&change.old_value)
+            .bind(&change.new_value)
+            .bind(changed_by)
+            .bind(revision_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("failed to insert field history")?;
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit field history")?;
+        Ok(())
+    }
+
+    /// List the change timeline for one field on an item, newest first.
+    pub async fn list_for_field(
+        pool: &PgPool,
+        item_id: Uuid,
+        field_name: &str,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let entries = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT id, item_id, field_name, old_value, new_value, changed_by, revision_id, changed
+            FROM field_history
+            WHERE item_id = $1 AND field_name = $2
+            ORDER BY changed DESC, id DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(item_id)
+        .bind(field_name)
+        .bind(limit.clamp(1, MAX_HISTORY_ENTRIES))
+        .fetch_all(pool)
+        .await
+        .context("failed to list field history")?;
+
+        Ok(entries)
+    }
+}
+
+/// Compare old and new field maps and return changes to tracked fields.
+///
+/// Fields not listed in `tracked` are ignored. A field present on one side
+/// and absent on the other counts as a change; `null` and absent are
+/// treated as distinct.
+pub fn diff_tracked_fields(
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    tracked: &[&str],
+) -> Vec<FieldChange> {
+    tracked
+        .iter()
+        .filter_map(|&name| {
+            let before = old.get(name);
+            let after = new.get(name);
+            if before == after {
+                return None;
+            }
+            Some(FieldChange {
+                field_name: name.to_string(),
+                old_value: before.cloned(),
+                new_value: after.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_ignores_untracked_fields() {
+        let old = json!({"owner_id": "a", "body": "x"});
+        let new = json!({"owner_id": "a", "body": "y"});
+        assert!(diff_tracked_fields(&old, &new, &["owner_id"]).is_empty());
+    }
+
+    #[test]
+    fn diff_detects_changed_added_and_removed() {
+        let old = json!({"owner_id": "a", "threshold": 5});
+        let new = json!({"owner_id": "b", "team": "ops"});
+        let changes = diff_tracked_fields(&old, &new, &["owner_id", "threshold", "team"]);
+        assert_eq!(changes.len(), 3);
+
+        assert_eq!(changes[0].field_name, "owner_id");
+        assert_eq!(changes[0].old_value, Some(json!("a")));
+        assert_eq!(changes[0].new_value, Some(json!("b")));
+
+        assert_eq!(changes[1].field_name, "threshold");
+        assert_eq!(changes[1].new_value, None);
+
+        assert_eq!(changes[2].field_name, "team");
+        assert_eq!(changes[2].old_value, None);
+    }
+}
@@ -8,14 +8,17 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use super::item_status::StatusChangeMeta;
 use super::stage::LIVE_STAGE_ID;
+use super::tenant::DEFAULT_TENANT_ID;
+use crate::profiling::{self, Phase};
 
 /// Item record (content record).
 ///
 /// SYNC: field names and types must match `crates/plugin-sdk/src/types.rs` Item.
 /// The kernel serializes this struct via `serde_json::to_string()` for tap dispatch.
 /// The SDK deserializes it. Extra kernel-only fields (promote, sticky, language,
-/// item_group_id) are safely ignored by the SDK's serde.
+/// item_group_id, tenant_id) are safely ignored by the SDK's serde.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Item {
     /// Unique identifier (UUIDv7).
@@ -65,6 +68,19 @@ pub struct Item {
     /// Data retention period in days (NULL = keep indefinitely).
     #[serde(default)]
     pub retention_days: Option<i32>,
+
+    /// Current state in the item type's moderation workflow (NULL = unmoderated).
+    #[serde(default)]
+    #[sqlx(default)]
+    pub moderation_state: Option<String>,
+
+    /// Tenant (site) owning the item.
+    #[serde(default = "default_tenant_id")]
+    pub tenant_id: Uuid,
+}
+
+fn default_tenant_id() -> Uuid {
+    DEFAULT_TENANT_ID
 }
 
 /// Item revision record.
@@ -121,6 +137,16 @@ pub struct CreateItem {
     pub log: Option<String>,
 }
 
+/// Error returned when an item was saved by someone else after the caller
+/// read it.
+///
+/// Converted to a 412 response by [`crate::error::AppError`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("item {id} was modified by another save")]
+pub struct ItemModified {
+    pub id: Uuid,
+}
+
 /// Input for updating an item.
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpdateItem {
@@ -130,6 +156,9 @@ pub struct UpdateItem {
     pub sticky: Option<i16>,
     pub fields: Option<serde_json::Value>,
     pub log: Option<String>,
+    /// Reason, note, and embargo context for a status change.
+    #[serde(default)]
+    pub status_meta: Option<StatusChangeMeta>,
 }
 
 impl Item {
@@ -150,8 +179,9 @@ impl Item {
 
     /// Find an item by ID.
     pub async fn find_by_id(pool: &PgPool, id: Uuid) -> Result<Option<Self>> {
+        let _db_timer = profiling::Timer::start(Phase::Db);
         let item = sqlx::query_as::<_, Item>(
-            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days FROM item WHERE id = $1"
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE id = $1"
         )
         .bind(id)
         .fetch_optional(pool)
@@ -161,12 +191,34 @@ impl Item {
         Ok(item)
     }
 
-    /// List items by content type.
+    /// List the current site's items of a content type.
     pub async fn list_by_type(pool: &PgPool, item_type: &str) -> Result<Vec<Self>> {
         let items = sqlx::query_as::<_, Item>(
-            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days FROM item WHERE type = $1 ORDER BY created DESC"
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE type = $1 AND tenant_id = $2 ORDER BY created DESC"
+        )
+        .bind(item_type)
+        .bind(crate::services::site::current_tenant_id())
+        .fetch_all(pool)
+        .await
+        .context("failed to list items by type")?;
+
+        Ok(items)
+    }
+
+    /// List up to `limit` items of a content type with IDs after `after`,
+    /// ordered by ID. Used to walk all items of a type in pages.
+    pub async fn list_by_type_after(
+        pool: &PgPool,
+        item_type: &str,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        let items = sqlx::query_as::<_, Item>(
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE type = $1 AND ($2::uuid IS NULL OR id > $2) ORDER BY id LIMIT $3"
         )
         .bind(item_type)
+        .bind(after)
+        .bind(limit)
         .fetch_all(pool)
         .await
         .context("failed to list items by type")?;
@@ -174,12 +226,13 @@ impl Item {
         Ok(items)
     }
 
-    /// List items by author.
+    /// List the current site's items by an author.
     pub async fn list_by_author(pool: &PgPool, author_id: Uuid) -> Result<Vec<Self>> {
         let items = sqlx::query_as::<_, Item>(
-            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days FROM item WHERE author_id = $1 ORDER BY created DESC"
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE author_id = $1 AND tenant_id = $2 ORDER BY created DESC"
         )
         .bind(author_id)
+        .bind(crate::services::site::current_tenant_id())
         .fetch_all(pool)
         .await
         .context("failed to list items by author")?;
@@ -187,14 +240,73 @@ impl Item {
         Ok(items)
     }
 
-    /// List published items (live stage only).
-    pub async fn list_published(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<Self>> {
+    /// List the current site's published live-stage items by an author,
+    /// newest first.
+    ///
+    /// With a `viewer`, items restricted by `tap_item_grants` are only listed
+    /// when that user holds a view grant; `None` skips the grant check.
+    pub async fn list_published_by_author(
+        pool: &PgPool,
+        author_id: Uuid,
+        viewer: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>> {
+        let items = sqlx::query_as::<_, Item>(
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE author_id = $1 AND status = 1 AND stage_id = $2 AND tenant_id = $5 AND ($6::uuid IS NULL OR item_access_granted(id, $6, 'view')) ORDER BY created DESC LIMIT $3 OFFSET $4"
+        )
+        .bind(author_id)
+        .bind(LIVE_STAGE_ID)
+        .bind(limit)
+        .bind(offset)
+        .bind(crate::services::site::current_tenant_id())
+        .bind(viewer)
+        .fetch_all(pool)
+        .await
+        .context("failed to list published items by author")?;
+
+        Ok(items)
+    }
+
+    /// Count the current site's published live-stage items by an author
+    /// that `viewer` may see (see [`Self::list_published_by_author`]).
+    pub async fn count_published_by_author(
+        pool: &PgPool,
+        author_id: Uuid,
+        viewer: Option<Uuid>,
+    ) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM item WHERE author_id = $1 AND status = 1 AND stage_id = $2 AND tenant_id = $3 AND ($4::uuid IS NULL OR item_access_granted(id, $4, 'view'))",
+        )
+        .bind(author_id)
+        .bind(LIVE_STAGE_ID)
+        .bind(crate::services::site::current_tenant_id())
+        .bind(viewer)
+        .fetch_one(pool)
+        .await
+        .context("failed to count published items by author")?;
+
+        Ok(count)
+    }
+
+    /// List the current site's published items (live stage only).
+    ///
+    /// With a `viewer`, items restricted by `tap_item_grants` are only listed
+    /// when that user holds a view grant; `None` skips the grant check.
+    pub async fn list_published(
+        pool: &PgPool,
+        viewer: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>> {
         let items = sqlx::query_as::<_, Item>(
-            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days FROM item WHERE status = 1 AND stage_id = $1 ORDER BY sticky DESC, created DESC LIMIT $2 OFFSET $3"
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE status = 1 AND stage_id = $1 AND tenant_id = $4 AND ($5::uuid IS NULL OR item_access_granted(id, $5, 'view')) ORDER BY sticky DESC, created DESC LIMIT $2 OFFSET $3"
         )
         .bind(LIVE_STAGE_ID)
         .bind(limit)
         .bind(offset)
+        .bind(crate::services::site::current_tenant_id())
+        .bind(viewer)
         .fetch_all(pool)
         .await
         .context("failed to list published items")?;
@@ -202,6 +314,55 @@ impl Item {
         Ok(items)
     }
 
+    /// List items in a stage, most recently changed first.
+    pub async fn list_in_stage(
+        pool: &PgPool,
+        stage_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>> {
+        let items = sqlx::query_as::<_, Item>(
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE stage_id = $1 ORDER BY changed DESC, id LIMIT $2 OFFSET $3"
+        )
+        .bind(stage_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list items in stage")?;
+
+        Ok(items)
+    }
+
+    /// Count items in a stage.
+    pub async fn count_in_stage(pool: &PgPool, stage_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item WHERE stage_id = $1")
+            .bind(stage_id)
+            .fetch_one(pool)
+            .await
+            .context("failed to count items in stage")?;
+
+        Ok(count)
+    }
+
+    /// Find the copy of a logical item (by `item_group_id`) in a stage.
+    pub async fn find_in_group(
+        pool: &PgPool,
+        item_group_id: Uuid,
+        stage_id: Uuid,
+    ) -> Result<Option<Self>> {
+        let item = sqlx::query_as::<_, Item>(
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE item_group_id = $1 AND stage_id = $2 ORDER BY changed DESC LIMIT 1"
+        )
+        .bind(item_group_id)
+        .bind(stage_id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to fetch item in group")?;
+
+        Ok(item)
+    }
+
     /// Create a new item with initial revision.
     pub async fn create(pool: &PgPool, input: CreateItem) -> Result<Self> {
         let now = chrono::Utc::now().timestamp();
@@ -211,11 +372,14 @@ impl Item {
         // Start a transaction
         let mut tx = pool.begin().await.context("failed to start transaction")?;
 
+        // Items belong to the site they were created on (see `services::site`).
+        let tenant_id = crate::services::site::current_tenant_id();
+
         // Insert item (without current_revision_id first)
         sqlx::query(
             r#"
-            INSERT INTO item (id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id)
-            VALUES ($1, NULL, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            INSERT INTO item (id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, tenant_id)
+            VALUES ($1, NULL, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             "#,
         )
         .bind(item_id)
@@ -233,6 +397,7 @@ impl Item {
         .bind(input.language.as_deref().unwrap_or("en"))
         // New items are their own group (item_group_id = item_id)
         .bind(item_id)
+        .bind(tenant_id)
         .execute(&mut *tx)
         .await
         .context("failed to insert item")?;
@@ -240,8 +405,8 @@ impl Item {
         // Insert initial revision
         sqlx::query(
             r#"
-            INSERT INTO item_revision (id, item_id, author_id, title, status, fields, created, log)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO item_revision (id, item_id, author_id, title, status, fields, created, log, tenant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
         )
         .bind(revision_id)
@@ -252,6 +417,7 @@ impl Item {
         .bind(input.fields.clone().unwrap_or(serde_json::json!({})))
         .bind(now)
         .bind(input.log.as_deref().unwrap_or("Initial revision"))
+        .bind(tenant_id)
         .execute(&mut *tx)
         .await
         .context("failed to insert initial revision")?;
@@ -278,6 +444,31 @@ impl Item {
         id: Uuid,
         author_id: Uuid,
         input: UpdateItem,
+    ) -> Result<Option<Self>> {
+        Self::write_update(pool, id, author_id, input, None).await
+    }
+
+    /// Update an item only if its current revision is still
+    /// `expected_revision`, the revision the caller read.
+    ///
+    /// Fails with [`ItemModified`] when another save got in first.
+    pub async fn update_if_current(
+        pool: &PgPool,
+        id: Uuid,
+        author_id: Uuid,
+        input: UpdateItem,
+        expected_revision: Option<Uuid>,
+    ) -> Result<Option<Self>> {
+        Self::write_update(pool, id, author_id, input, Some(expected_revision)).await
+    }
+
+    /// Shared body of [`Self::update`] and [`Self::update_if_current`].
+    async fn write_update(
+        pool: &PgPool,
+        id: Uuid,
+        author_id: Uuid,
+        input: UpdateItem,
+        expected_revision: Option<Option<Uuid>>,
     ) -> Result<Option<Self>> {
         let now = chrono::Utc::now().timestamp();
         let revision_id = Uuid::now_v7();
@@ -301,8 +492,8 @@ impl Item {
         // Create new revision FIRST (before updating item to point to it)
         sqlx::query(
             r#"
-            INSERT INTO item_revision (id, item_id, author_id, title, status, fields, created, log)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO item_revision (id, item_id, author_id, title, status, fields, created, log, tenant_id)
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, tenant_id FROM item WHERE id = $2
             "#,
         )
         .bind(revision_id)
@@ -318,7 +509,7 @@ impl Item {
         .context("failed to insert revision")?;
 
         // Update item with reference to new revision
-        sqlx::query(
+        let updated = sqlx::query(
             r#"
             UPDATE item SET
                 title = $1,
@@ -329,6 +520,7 @@ impl Item {
                 fields = $6,
                 current_revision_id = $7
             WHERE id = $8
+              AND ($9 OR current_revision_id IS NOT DISTINCT FROM $10)
             "#,
         )
         .bind(&title)
@@ -339,16 +531,80 @@ impl Item {
         .bind(&fields)
         .bind(revision_id)
         .bind(id)
+        .bind(expected_revision.is_none())
+        .bind(expected_revision.flatten())
         .execute(&mut *tx)
         .await
         .context("failed to update item")?;
 
+        // The revision row is rolled back with the transaction.
+        if updated.rows_affected() == 0 && expected_revision.is_some() {
+            return Err(ItemModified { id }.into());
+        }
+
         tx.commit().await.context("failed to commit transaction")?;
 
         // Return updated item
         Self::find_by_id(pool, id).await
     }
 
+    /// Set an item's moderation state without creating a revision.
+    pub async fn set_moderation_state(pool: &PgPool, id: Uuid, state: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE item SET moderation_state = $1 WHERE id = $2")
+            .bind(state)
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to set moderation state")?;
+        Ok(())
+    }
+
+    /// Reassign an item to another author without creating a revision.
+    pub async fn set_author(pool: &PgPool, id: Uuid, author_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("UPDATE item SET author_id = $1, changed = $2 WHERE id = $3")
+            .bind(author_id)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to set item author")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move an item and its URL aliases to another stage.
+    pub async fn set_stage(pool: &PgPool, id: Uuid, stage_id: Uuid) -> Result<bool> {
+        let mut tx = pool.begin().await.context("failed to start transaction")?;
+
+        let previous: Option<Uuid> =
+            sqlx::query_scalar("SELECT stage_id FROM item WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .context("failed to fetch item stage")?;
+        let Some(previous) = previous else {
+            return Ok(false);
+        };
+
+        sqlx::query("UPDATE item SET stage_id = $1, changed = $2 WHERE id = $3")
+            .bind(stage_id)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .context("failed to move item to stage")?;
+
+        sqlx::query("UPDATE url_alias SET stage_id = $1 WHERE source = $2 AND stage_id = $3")
+            .bind(stage_id)
+            .bind(format!("/item/{id}"))
+            .bind(previous)
+            .execute(&mut *tx)
+            .await
+            .context("failed to move item aliases to stage")?;
+
+        tx.commit().await.context("failed to commit transaction")?;
+        Ok(true)
+    }
+
     /// Delete an item and all its revisions.
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool> {
         // Revisions are deleted via CASCADE
@@ -412,6 +668,7 @@ impl Item {
             sticky: None,
             fields: Some(revision.fields),
             log: Some(format!("Reverted to revision {revision_id}")),
+            status_meta: None,
         };
 
         // Update creates a new revision with the old content
@@ -434,7 +691,7 @@ impl Item {
     /// List all items with pagination.
     pub async fn list_all(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<Self>> {
         let items = sqlx::query_as::<_, Item>(
-            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days FROM item ORDER BY changed DESC LIMIT $1 OFFSET $2"
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item ORDER BY changed DESC LIMIT $1 OFFSET $2"
         )
         .bind(limit)
         .bind(offset)
@@ -456,7 +713,7 @@ impl Item {
     ) -> Result<Vec<Self>> {
         // Build dynamic query
         let mut query = String::from(
-            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days FROM item WHERE 1=1",
+            "SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id FROM item WHERE 1=1",
         );
         let mut param_idx = 1;
         let mut conditions = Vec::new();
@@ -553,6 +810,70 @@ impl Item {
         Ok(count)
     }
 
+    /// List items whose latest status change carries the given reason code.
+    ///
+    /// Type and status filters are optional, as in [`Item::list_filtered`].
+    pub async fn list_by_status_reason(
+        pool: &PgPool,
+        item_type: Option<&str>,
+        status: Option<i16>,
+        reason_code: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>> {
+        let items = sqlx::query_as::<_, Item>(
+            r#"
+            SELECT id, current_revision_id, type, title, author_id, status, created, changed, promote, sticky, fields, stage_id, language, item_group_id, retention_days, moderation_state, tenant_id
+            FROM item
+            WHERE ($1::text IS NULL OR type = $1)
+              AND ($2::smallint IS NULL OR status = $2)
+              AND (SELECT c.reason_code FROM item_status_change c
+                   WHERE c.item_id = item.id
+                   ORDER BY c.changed DESC, c.id DESC LIMIT 1) = $3
+            ORDER BY changed DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(item_type)
+        .bind(status)
+        .bind(reason_code)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list items by status reason")?;
+
+        Ok(items)
+    }
+
+    /// Count items whose latest status change carries the given reason code.
+    pub async fn count_by_status_reason(
+        pool: &PgPool,
+        item_type: Option<&str>,
+        status: Option<i16>,
+        reason_code: &str,
+    ) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM item
+            WHERE ($1::text IS NULL OR type = $1)
+              AND ($2::smallint IS NULL OR status = $2)
+              AND (SELECT c.reason_code FROM item_status_change c
+                   WHERE c.item_id = item.id
+                   ORDER BY c.changed DESC, c.id DESC LIMIT 1) = $3
+            "#,
+        )
+        .bind(item_type)
+        .bind(status)
+        .bind(reason_code)
+        .fetch_one(pool)
+        .await
+        .context("failed to count items by status reason")?;
+
+        Ok(count)
+    }
+
     /// Count all items.
     pub async fn count_all(pool: &PgPool) -> Result<i64> {
         let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item")
@@ -600,6 +921,8 @@ mod tests {
             language: "en".to_string(),
             item_group_id: Uuid::now_v7(),
             retention_days: None,
+            moderation_state: None,
+            tenant_id: DEFAULT_TENANT_ID,
         };
 
         assert!(item.is_published());
@@ -0,0 +1,173 @@
+//! Item access grants (`item_access` table).
+//!
+//! Grants come from `tap_item_grants` when an item is saved. The
+//! `item_access_granted(item_id, user_id, op)` SQL function evaluates them,
+//! so gather and search queries can filter whole result sets and
+//! [`ItemAccess::check`] agrees with them for single items.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use trovato_sdk::types::{GRANT_REALM_ALL, GRANT_REALM_ROLE, GRANT_REALM_USER, ItemGrant};
+use uuid::Uuid;
+
+/// Item access grant storage.
+pub struct ItemAccess;
+
+impl ItemAccess {
+    /// Replace the grants of an item.
+    pub async fn replace(pool: &PgPool, item_id: Uuid, grants: &[ItemGrant]) -> Result<()> {
+        let mut tx = pool.begin().await.context("failed to start transaction")?;
+
+        sqlx::query("DELETE FROM item_access WHERE item_id = $1")
+            .bind(item_id)
+            .execute(&mut *tx)
+            .await
+            .context("failed to clear item grants")?;
+
+        for grant in grants {
+            sqlx::query(
+                "INSERT INTO item_access \
+                 (item_id, realm, gid, grant_view, grant_update, grant_delete) \
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(item_id)
+            .bind(&grant.realm)
+            .bind(&grant.gid)
+            .bind(grant.view)
+            .bind(grant.update)
+            .bind(grant.delete)
+            .execute(&mut *tx)
+            .await
+            .context("failed to insert item grant")?;
+        }
+
+        tx.commit().await.context("failed to commit item grants")?;
+        Ok(())
+    }
+
+    /// Check an operation against an item's grants.
+    ///
+    /// Returns `None` when the item has no grants, so the caller applies
+    /// its regular access rules instead.
+    pub async fn check(
+        pool: &PgPool,
+        item_id: Uuid,
+        user_id: Uuid,
+        operation: &str,
+    ) -> Result<Option<bool>> {
+        let Some(op) = grant_operation(operation) else {
+            return Ok(None);
+        };
+        let granted: Option<bool> = sqlx::query_scalar(
+            "SELECT item_access_granted($1, $2, $3) \
+             WHERE EXISTS (SELECT 1 FROM item_access WHERE item_id = $1)",
+        )
+        .bind(item_id)
+        .bind(user_id)
+        .bind(op)
+        .fetch_optional(pool)
+        .await
+        .context("failed to check item grants")?;
+        Ok(granted)
+    }
+}
+
+/// Map an access operation to the grant column it uses.
+///
+/// Returns `None` for operations grants don't cover.
+pub fn grant_operation(operation: &str) -> Option<&'static str> {
+    match operation {
+        "view" => Some("view"),
+        "edit" | "update" => Some("update"),
+        "delete" => Some("delete"),
+        _ => None,
+    }
+}
+
+/// Combine the grants returned by several plugins.
+///
+/// Grants for the same realm and ID are merged, operations OR-ed together.
+/// Grants with an unknown realm, a malformed ID or no operations are
+/// dropped.
+pub fn merge_grants(grants: impl IntoIterator<Item = ItemGrant>) -> Vec<ItemGrant> {
+    let mut merged: BTreeMap<(String, String), ItemGrant> = BTreeMap::new();
+    for grant in grants.into_iter().filter_map(normalize_grant) {
+        merged
+            .entry((grant.realm.clone(), grant.gid.clone()))
+            .and_modify(|g| {
+                g.view |= grant.view;
+                g.update |= grant.update;
+                g.delete |= grant.delete;
+            })
+            .or_insert(grant);
+    }
+    merged.into_values().collect()
+}
+
+/// Validate a grant, writing IDs in the form the SQL function compares.
+fn normalize_grant(mut grant: ItemGrant) -> Option<ItemGrant> {
+    if !(grant.view || grant.update || grant.delete) {
+        return None;
+    }
+    match grant.realm.as_str() {
+        GRANT_REALM_ALL if grant.gid == "0" => {}
+        GRANT_REALM_ROLE | GRANT_REALM_USER => {
+            grant.gid = grant.gid.parse::<Uuid>().ok()?.to_string();
+        }
+        _ => return None,
+    }
+    Some(grant)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_combines_operations_and_drops_invalid_grants() {
+        let role = Uuid::now_v7();
+        let grants = merge_grants([
+            ItemGrant::role(role).allow_view(),
+            ItemGrant::role(role).allow_update(),
+            ItemGrant::all().allow_view(),
+            // No operations.
+            ItemGrant::user(Uuid::now_v7()),
+            ItemGrant {
+                realm: "group".to_string(),
+                gid: "7".to_string(),
+                view: true,
+                update: false,
+                delete: false,
+            },
+            ItemGrant {
+                realm: GRANT_REALM_USER.to_string(),
+                gid: "not-a-uuid".to_string(),
+                view: true,
+                update: false,
+                delete: false,
+            },
+        ]);
+
+        assert_eq!(grants.len(), 2);
+        assert_eq!(grants[0], ItemGrant::all().allow_view());
+        assert_eq!(grants[1], ItemGrant::role(role).allow_view().allow_update());
+
+        let shouting = ItemGrant {
+            gid: role.to_string().to_uppercase(),
+            ..ItemGrant::role(role).allow_delete()
+        };
+        assert_eq!(merge_grants([shouting])[0].gid, role.to_string());
+    }
+
+    #[test]
+    fn operations_map_to_grant_columns() {
+        assert_eq!(grant_operation("view"), Some("view"));
+        assert_eq!(grant_operation("edit"), Some("update"));
+        assert_eq!(grant_operation("delete"), Some("delete"));
+        assert_eq!(grant_operation("translate"), None);
+    }
+}
@@ -0,0 +1,225 @@
+//! Structured context for item status changes.
+//!
+//! Each publish/unpublish transition can carry a reason code, a free-text
+//! note, and an embargo end date. Rows are append-only; the newest row for
+//! an item explains its current status.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum number of status changes returned by a single query.
+pub const MAX_STATUS_CHANGES: i64 = 200;
+
+/// Maximum length of a status change note, in characters.
+pub const MAX_STATUS_NOTE_LENGTH: usize = 2000;
+
+/// Recognised reason codes and their admin labels.
+pub const STATUS_REASON_CODES: &[(&str, &str)] = &[
+    ("editorial", "Editorial decision"),
+    ("correction", "Correction pending"),
+    ("embargo", "Embargo"),
+    ("legal_hold", "Legal hold"),
+    ("expired", "Expired"),
+    ("other", "Other"),
+];
+
+/// Human-readable label for a reason code, if recognised.
+pub fn status_reason_label(code: &str) -> Option<&'static str> {
+    STATUS_REASON_CODES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, label)| *label)
+}
+
+/// Metadata supplied alongside a status change.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusChangeMeta {
+    /// One of [`STATUS_REASON_CODES`].
+    #[serde(default)]
+    pub reason_code: Option<String>,
+    /// Free-text explanation.
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Unix timestamp until which the item is embargoed.
+    #[serde(default)]
+    pub embargo_until: Option<i64>,
+}
+
+impl StatusChangeMeta {
+    /// Check the reason code is recognised and the note is within limits.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(code) = &self.reason_code
+            && status_reason_label(code).is_none()
+        {
+            anyhow::bail!("unknown status reason code: {code}");
+        }
+        if let Some(note) = &self.note
+            && note.chars().count() > MAX_STATUS_NOTE_LENGTH
+        {
+            anyhow::bail!("status note exceeds {MAX_STATUS_NOTE_LENGTH} characters");
+        }
+        if self.embargo_until.is_some() && self.reason_code.as_deref() != Some("embargo") {
+            anyhow::bail!("embargo date requires the 'embargo' reason code");
+        }
+        Ok(())
+    }
+}
+
+/// A single recorded status change.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ItemStatusChange {
+    /// Unique identifier (UUIDv7).
+    pub id: Uuid,
+    /// Item whose status changed.
+    pub item_id: Uuid,
+    /// Status before the change.
+    pub old_status: i16,
+    /// Status after the change.
+    pub new_status: i16,
+    /// Reason code, if supplied.
+    pub reason_code: Option<String>,
+    /// Free-text note, if supplied.
+    pub note: Option<String>,
+    /// Unix timestamp until which the item is embargoed.
+    pub embargo_until: Option<i64>,
+    /// User who made the change.
+    pub changed_by: Uuid,
+    /// Revision created by the change, if any.
+    pub revision_id: Option<Uuid>,
+    /// Unix timestamp of the change.
+    pub changed: i64,
+}
+
+impl ItemStatusChange {
+    /// Persist a status change for an item.
+    pub async fn record(
+        pool: &PgPool,
+        item_id: Uuid,
+        old_status: i16,
+        new_status: i16,
+        meta: &StatusChangeMeta,
+        changed_by: Uuid,
+        revision_id: Option<Uuid>,
+    ) -> Result<Self> {
+        let entry = sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO item_status_change
+                (id, item_id, old_status, new_status, reason_code, note, embargo_until,
+                 changed_by, revision_id, changed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, item_id, old_status, new_status, reason_code, note, embargo_until,
+                      changed_by, revision_id, changed
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(item_id)
+        .bind(old_status)
+        .bind(new_status)
+        .bind(&meta.reason_code)
+        .bind(&meta.note)
+        .bind(meta.embargo_until)
+        .bind(changed_by)
+        .bind(revision_id)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(pool)
+        .await
+        .context("failed to insert item status change")?;
+
+        Ok(entry)
+    }
+
+    /// List status changes for an item, newest first.
+    pub async fn list_for_item(pool: &PgPool, item_id: Uuid, limit: i64) -> Result<Vec<Self>> {
+        let entries = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT id, item_id, old_status, new_status, reason_code, note, embargo_until,
+                   changed_by, revision_id, changed
+            FROM item_status_change
+            WHERE item_id = $1
+            ORDER BY changed DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(item_id)
+        .bind(limit.clamp(1, MAX_STATUS_CHANGES))
+        .fetch_all(pool)
+        .await
+        .context("failed to list item status changes")?;
+
+        Ok(entries)
+    }
+
+    /// Latest status change for each of the given items, keyed by item ID.
+    ///
+    /// Items with no recorded change are absent from the map.
+    pub async fn latest_for_items(pool: &PgPool, item_ids: &[Uuid]) -> Result<HashMap<Uuid, Self>> {
+        if item_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let entries = sqlx::query_as::<_, Self>(
+            r#"
+            SELECT DISTINCT ON (item_id)
+                   id, item_id, old_status, new_status, reason_code, note, embargo_until,
+                   changed_by, revision_id, changed
+            FROM item_status_change
+            WHERE item_id = ANY($1)
+            ORDER BY item_id, changed DESC, id DESC
+            "#,
+        )
+        .bind(item_ids)
+        .fetch_all(pool)
+        .await
+        .context("failed to load latest item status changes")?;
+
+        Ok(entries.into_iter().map(|e| (e.item_id, e)).collect())
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_meta_is_valid() {
+        assert!(StatusChangeMeta::default().validate().is_ok());
+    }
+
+    #[test]
+    fn unknown_reason_code_rejected() {
+        let meta = StatusChangeMeta {
+            reason_code: Some("whim".to_string()),
+            ..Default::default()
+        };
+        assert!(meta.validate().is_err());
+    }
+
+    #[test]
+    fn embargo_date_requires_embargo_reason() {
+        let mut meta = StatusChangeMeta {
+            reason_code: Some("legal_hold".to_string()),
+            note: Some("Pending review".to_string()),
+            embargo_until: Some(1_800_000_000),
+        };
+        assert!(meta.validate().is_err());
+
+        meta.reason_code = Some("embargo".to_string());
+        assert!(meta.validate().is_ok());
+    }
+
+    #[test]
+    fn overlong_note_rejected() {
+        let meta = StatusChangeMeta {
+            reason_code: Some("other".to_string()),
+            note: Some("x".repeat(MAX_STATUS_NOTE_LENGTH + 1)),
+            embargo_until: None,
+        };
+        assert!(meta.validate().is_err());
+    }
+}
@@ -46,6 +46,16 @@ pub struct CreateItemType {
 }
 
 impl ItemType {
+    /// Whether status changes on items of this type must carry a reason code.
+    ///
+    /// Read from the `require_status_reason` key in type settings.
+    pub fn requires_status_reason(&self) -> bool {
+        self.settings
+            .get("require_status_reason")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
     /// Find a content type by machine name.
     pub async fn find_by_type(pool: &PgPool, type_name: &str) -> Result<Option<Self>> {
         let item_type = sqlx::query_as::<_, ItemType>(
@@ -59,6 +69,19 @@ impl ItemType {
         Ok(item_type)
     }
 
+    /// Find several content types by machine name in one query.
+    pub async fn find_many(pool: &PgPool, type_names: &[String]) -> Result<Vec<Self>> {
+        let types = sqlx::query_as::<_, ItemType>(
+            "SELECT type as type_name, label, description, has_title, title_label, plugin, settings FROM item_type WHERE type = ANY($1)"
+        )
+        .bind(type_names)
+        .fetch_all(pool)
+        .await
+        .context("failed to fetch item types")?;
+
+        Ok(types)
+    }
+
     /// List all content types.
     pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
         let types = sqlx::query_as::<_, ItemType>(
@@ -181,4 +204,21 @@ mod tests {
         assert_eq!(input.type_name, "blog");
         assert_eq!(input.label, "Blog Post");
     }
+
+    #[test]
+    fn requires_status_reason_reads_settings() {
+        let mut item_type = ItemType {
+            type_name: "notice".to_string(),
+            label: "Notice".to_string(),
+            description: None,
+            has_title: true,
+            title_label: None,
+            plugin: "core".to_string(),
+            settings: serde_json::json!({}),
+        };
+        assert!(!item_type.requires_status_reason());
+
+        item_type.settings = serde_json::json!({"require_status_reason": true});
+        assert!(item_type.requires_status_reason());
+    }
 }
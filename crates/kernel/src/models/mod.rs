@@ -1,15 +1,25 @@
 //! Database models.
 
+pub mod alias_pattern;
 pub mod api_token;
+pub mod author;
 pub mod category;
 pub mod comment;
+pub mod config_translation;
+pub mod contact;
 pub mod email_verification;
+pub mod field_default;
+pub mod field_history;
 pub mod item;
+pub mod item_access;
+pub mod item_status;
 pub mod item_type;
 pub mod language;
 pub mod menu_link;
+pub mod password_policy;
 pub mod password_reset;
 pub mod role;
+pub mod site;
 pub mod site_config;
 pub mod stage;
 pub mod subscription;
@@ -17,22 +27,38 @@ pub mod tenant;
 pub mod tile;
 pub mod url_alias;
 pub mod user;
+pub mod user_invitation;
+pub mod workflow;
 
+pub use alias_pattern::AliasPattern;
+pub use author::{AuthorProfile, AuthorSettings};
 pub use category::{
     Category, CreateCategory, CreateTag, Tag, TagHierarchy, TagTreeNode, TagWithDepth,
     UpdateCategory, UpdateTag,
 };
 pub use comment::{Comment, CreateComment, UpdateComment};
+pub use config_translation::ConfigTranslation;
+pub use contact::{
+    ContactCategory, ContactSubmission, CreateContactCategory, CreateContactSubmission,
+};
 pub use email_verification::EmailVerificationToken;
+pub use field_default::{CreateFieldDefaultRule, FieldDefaultRule, FieldDefaultValue};
+pub use field_history::FieldHistoryEntry;
 pub use item::{CreateItem, Item, ItemRevision, UpdateItem};
+pub use item_access::ItemAccess;
+pub use item_status::{ItemStatusChange, StatusChangeMeta};
 pub use item_type::{CreateItemType, ItemType};
 pub use language::{CreateLanguage, Language};
 pub use menu_link::{CreateMenuLink, MenuLink, UpdateMenuLink};
+pub use password_policy::PasswordPolicy;
 pub use password_reset::PasswordResetToken;
 pub use role::Role;
+pub use site::{Site, SitePlugin};
 pub use site_config::SiteConfig;
 pub use stage::{CreateStage, Stage};
 pub use subscription::Subscription;
 pub use tenant::{DEFAULT_TENANT_ID, Tenant, TenantContext};
 pub use url_alias::{CreateUrlAlias, UpdateUrlAlias, UrlAlias};
 pub use user::{CreateUser, UpdateUser, User};
+pub use user_invitation::UserInvitation;
+pub use workflow::{Workflow, WorkflowState, WorkflowTransition};
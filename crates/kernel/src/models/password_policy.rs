@@ -0,0 +1,461 @@
+//! Password policies.
+//!
+//! A password policy is a config entity with rules new passwords must meet:
+//! minimum length, required character classes, a breach-list check, no reuse
+//! of recent passwords, and a maximum age. A policy applies to accounts
+//! holding one of its roles, or to every account when it lists no roles.
+//! When several apply, [`PasswordRules::combine`] keeps the strictest
+//! setting of each rule. Policies are enforced by
+//! [`crate::services::password_policy`].
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum length of a policy machine name.
+const MAX_ID_LENGTH: usize = 64;
+
+/// Lowest allowed minimum length; shorter passwords are always rejected.
+pub const MIN_PASSWORD_LENGTH: u32 = 12;
+
+/// Highest allowed minimum length; longer passwords are always rejected.
+pub const MAX_PASSWORD_LENGTH: u32 = 128;
+
+/// Most previous passwords a policy may remember.
+pub const MAX_HISTORY_COUNT: u32 = 24;
+
+/// Longest allowed maximum age, in days.
+const MAX_AGE_DAYS: u32 = 3650;
+
+/// A password policy config entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PasswordPolicy {
+    /// Machine name.
+    pub id: String,
+    /// Human-readable name.
+    pub label: String,
+    /// Roles the policy applies to; empty applies to every account.
+    #[serde(default)]
+    pub roles: Vec<Uuid>,
+    /// Minimum number of characters.
+    #[serde(default = "default_min_length")]
+    pub min_length: i32,
+    /// Require an uppercase letter.
+    #[serde(default)]
+    pub require_uppercase: bool,
+    /// Require a lowercase letter.
+    #[serde(default)]
+    pub require_lowercase: bool,
+    /// Require a digit.
+    #[serde(default)]
+    pub require_digit: bool,
+    /// Require a character that is neither a letter nor a digit.
+    #[serde(default)]
+    pub require_symbol: bool,
+    /// Reject passwords found in known data breaches.
+    #[serde(default)]
+    pub breach_check: bool,
+    /// Number of previous passwords that may not be reused (0 = reuse allowed).
+    #[serde(default)]
+    pub history_count: i32,
+    /// Days after which a password expires (0 = never).
+    #[serde(default)]
+    pub max_age_days: i32,
+    /// Unix timestamp when created.
+    #[serde(default)]
+    pub created: i64,
+    /// Unix timestamp when last changed.
+    #[serde(default)]
+    pub changed: i64,
+}
+
+fn default_min_length() -> i32 {
+    MIN_PASSWORD_LENGTH as i32
+}
+
+impl PasswordPolicy {
+    /// Create a policy with the baseline rules only.
+    pub fn new(id: &str, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            roles: Vec::new(),
+            min_length: default_min_length(),
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            breach_check: false,
+            history_count: 0,
+            max_age_days: 0,
+            created: 0,
+            changed: 0,
+        }
+    }
+
+    /// Whether the policy applies to an account holding `role_ids`.
+    pub fn applies_to(&self, role_ids: &[Uuid]) -> bool {
+        self.roles.is_empty() || self.roles.iter().any(|r| role_ids.contains(r))
+    }
+
+    /// Check the id and that every rule is in range.
+    pub fn validate(&self) -> Result<()> {
+        let valid_id = !self.id.is_empty()
+            && self.id.len() <= MAX_ID_LENGTH
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_id {
+            bail!("invalid password policy machine name: '{}'", self.id);
+        }
+        if self.label.trim().is_empty() {
+            bail!("password policy '{}' needs a label", self.id);
+        }
+        let length_range = MIN_PASSWORD_LENGTH as i32..=MAX_PASSWORD_LENGTH as i32;
+        if !length_range.contains(&self.min_length) {
+            bail!(
+                "min_length must be between {MIN_PASSWORD_LENGTH} and {MAX_PASSWORD_LENGTH}, got {}",
+                self.min_length
+            );
+        }
+        if !(0..=MAX_HISTORY_COUNT as i32).contains(&self.history_count) {
+            bail!(
+                "history_count must be between 0 and {MAX_HISTORY_COUNT}, got {}",
+                self.history_count
+            );
+        }
+        if !(0..=MAX_AGE_DAYS as i32).contains(&self.max_age_days) {
+            bail!(
+                "max_age_days must be between 0 and {MAX_AGE_DAYS}, got {}",
+                self.max_age_days
+            );
+        }
+        Ok(())
+    }
+
+    /// Find a policy by id.
+    pub async fn find_by_id(pool: &PgPool, id: &str) -> Result<Option<Self>> {
+        sqlx::query_as::<_, Self>("SELECT * FROM password_policy WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("failed to fetch password policy")
+    }
+
+    /// List all policies ordered by id.
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Self>("SELECT * FROM password_policy ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .context("failed to list password policies")
+    }
+
+    /// Validate and insert or update a policy.
+    pub async fn upsert(pool: &PgPool, policy: &Self) -> Result<Self> {
+        policy.validate()?;
+        let now = chrono::Utc::now().timestamp();
+        let created = if policy.created > 0 {
+            policy.created
+        } else {
+            now
+        };
+
+        sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO password_policy (
+                id, label, roles, min_length, require_uppercase, require_lowercase,
+                require_digit, require_symbol, breach_check, history_count, max_age_days,
+                created, changed
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (id) DO UPDATE SET
+                label = EXCLUDED.label,
+                roles = EXCLUDED.roles,
+                min_length = EXCLUDED.min_length,
+                require_uppercase = EXCLUDED.require_uppercase,
+                require_lowercase = EXCLUDED.require_lowercase,
+                require_digit = EXCLUDED.require_digit,
+                require_symbol = EXCLUDED.require_symbol,
+                breach_check = EXCLUDED.breach_check,
+                history_count = EXCLUDED.history_count,
+                max_age_days = EXCLUDED.max_age_days,
+                changed = EXCLUDED.changed
+            RETURNING *
+            "#,
+        )
+        .bind(&policy.id)
+        .bind(policy.label.trim())
+        .bind(&policy.roles)
+        .bind(policy.min_length)
+        .bind(policy.require_uppercase)
+        .bind(policy.require_lowercase)
+        .bind(policy.require_digit)
+        .bind(policy.require_symbol)
+        .bind(policy.breach_check)
+        .bind(policy.history_count)
+        .bind(policy.max_age_days)
+        .bind(created)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .context("failed to save password policy")
+    }
+
+    /// Delete a policy.
+    pub async fn delete(pool: &PgPool, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM password_policy WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to delete password policy")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// The rules in force for one account: the strictest of its policies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordRules {
+    /// Minimum number of characters.
+    pub min_length: u32,
+    /// Require an uppercase letter.
+    pub require_uppercase: bool,
+    /// Require a lowercase letter.
+    pub require_lowercase: bool,
+    /// Require a digit.
+    pub require_digit: bool,
+    /// Require a character that is neither a letter nor a digit.
+    pub require_symbol: bool,
+    /// Reject passwords found in known data breaches.
+    pub breach_check: bool,
+    /// Number of previous passwords that may not be reused.
+    pub history_count: u32,
+    /// Days after which a password expires.
+    pub max_age_days: Option<u32>,
+}
+
+impl Default for PasswordRules {
+    fn default() -> Self {
+        Self {
+            min_length: MIN_PASSWORD_LENGTH,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            breach_check: false,
+            history_count: 0,
+            max_age_days: None,
+        }
+    }
+}
+
+impl PasswordRules {
+    /// Combine policies, keeping the strictest setting of each rule.
+    pub fn combine<'a>(policies: impl IntoIterator<Item = &'a PasswordPolicy>) -> Self {
+        let mut rules = Self::default();
+        for policy in policies {
+            rules.min_length = rules.min_length.max(policy.min_length.max(0) as u32);
+            rules.require_uppercase |= policy.require_uppercase;
+            rules.require_lowercase |= policy.require_lowercase;
+            rules.require_digit |= policy.require_digit;
+            rules.require_symbol |= policy.require_symbol;
+            rules.breach_check |= policy.breach_check;
+            rules.history_count = rules.history_count.max(policy.history_count.max(0) as u32);
+            if policy.max_age_days > 0 {
+                let days = policy.max_age_days as u32;
+                rules.max_age_days = Some(rules.max_age_days.map_or(days, |d| d.min(days)));
+            }
+        }
+        rules
+    }
+
+    /// Check length and character classes.
+    ///
+    /// The breach list and history need I/O and are checked by the service.
+    pub fn check_composition(&self, password: &str) -> Vec<PasswordViolation> {
+        let mut violations = Vec::new();
+        if password.chars().count() < self.min_length as usize {
+            violations.push(PasswordViolation::new(
+                "too_short",
+                format!("Password must be at least {} characters.", self.min_length),
+            ));
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push(PasswordViolation::new(
+                "missing_uppercase",
+                "Password must contain an uppercase letter.",
+            ));
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push(PasswordViolation::new(
+                "missing_lowercase",
+                "Password must contain a lowercase letter.",
+            ));
+        }
+        if self.require_digit && !password.chars().any(char::is_numeric) {
+            violations.push(PasswordViolation::new(
+                "missing_digit",
+                "Password must contain a digit.",
+            ));
+        }
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            violations.push(PasswordViolation::new(
+                "missing_symbol",
+                "Password must contain a symbol.",
+            ));
+        }
+        violations
+    }
+
+    /// Whether a password set at `changed` has expired at `now`.
+    pub fn is_expired(&self, changed: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.max_age_days
+            .is_some_and(|days| now - changed >= Duration::days(i64::from(days)))
+    }
+}
+
+/// A password policy rule a new password does not meet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasswordViolation {
+    /// Machine-readable code (e.g., "too_short", "breached", "reused").
+    pub code: String,
+    /// Human-readable explanation shown to the user.
+    pub message: String,
+}
+
+impl PasswordViolation {
+    /// Create a violation.
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Error returned when a new password breaks the policies in force.
+///
+/// Converted to a 422 response on the `password` field by
+/// [`crate::error::AppError`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("password rejected by policy ({} violation(s))", .violations.len())]
+pub struct PasswordPolicyViolated {
+    /// Every rule the password breaks.
+    pub violations: Vec<PasswordViolation>,
+}
+
+impl PasswordPolicyViolated {
+    /// Violation messages, for forms that list errors.
+    pub fn messages(&self) -> Vec<String> {
+        self.violations.iter().map(|v| v.message.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn codes(violations: &[PasswordViolation]) -> Vec<&str> {
+        violations.iter().map(|v| v.code.as_str()).collect()
+    }
+
+    #[test]
+    fn validate_checks_ranges() {
+        let policy = PasswordPolicy::new("default", "Default");
+        assert!(policy.validate().is_ok());
+
+        let mut bad = policy.clone();
+        bad.id = "Default Policy".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = policy.clone();
+        bad.min_length = 8;
+        assert!(bad.validate().is_err());
+
+        let mut bad = policy.clone();
+        bad.history_count = MAX_HISTORY_COUNT as i32 + 1;
+        assert!(bad.validate().is_err());
+
+        let mut bad = policy;
+        bad.max_age_days = -1;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn policies_apply_by_role() {
+        let editor = Uuid::from_u128(10);
+        let mut policy = PasswordPolicy::new("editors", "Editors");
+        assert!(policy.applies_to(&[]));
+
+        policy.roles = vec![editor];
+        assert!(policy.applies_to(&[Uuid::from_u128(2), editor]));
+        assert!(!policy.applies_to(&[Uuid::from_u128(2)]));
+    }
+
+    #[test]
+    fn combine_keeps_strictest_rules() {
+        let mut a = PasswordPolicy::new("a", "A");
+        a.min_length = 16;
+        a.require_digit = true;
+        a.history_count = 3;
+        a.max_age_days = 90;
+        let mut b = PasswordPolicy::new("b", "B");
+        b.require_symbol = true;
+        b.breach_check = true;
+        b.history_count = 5;
+        b.max_age_days = 30;
+
+        let rules = PasswordRules::combine([&a, &b]);
+        assert_eq!(rules.min_length, 16);
+        assert!(rules.require_digit && rules.require_symbol && rules.breach_check);
+        assert!(!rules.require_uppercase);
+        assert_eq!(rules.history_count, 5);
+        assert_eq!(rules.max_age_days, Some(30));
+
+        assert_eq!(PasswordRules::combine([]), PasswordRules::default());
+    }
+
+    #[test]
+    fn composition_reports_every_broken_rule() {
+        let rules = PasswordRules {
+            min_length: 14,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            ..PasswordRules::default()
+        };
+
+        assert_eq!(
+            codes(&rules.check_composition("lowercaseonly")),
+            [
+                "too_short",
+                "missing_uppercase",
+                "missing_digit",
+                "missing_symbol"
+            ]
+        );
+        assert!(rules.check_composition("Correct-Horse-42").is_empty());
+        // Length counts characters, not bytes.
+        assert_eq!(
+            codes(&rules.check_composition("Ünïcödé-1ab")),
+            ["too_short"]
+        );
+    }
+
+    #[test]
+    fn expiry_follows_max_age() {
+        let now = Utc::now();
+        let rules = PasswordRules {
+            max_age_days: Some(30),
+            ..PasswordRules::default()
+        };
+        assert!(!rules.is_expired(now - Duration::days(29), now));
+        assert!(rules.is_expired(now - Duration::days(30), now));
+        assert!(!PasswordRules::default().is_expired(now - Duration::days(3000), now));
+    }
+}
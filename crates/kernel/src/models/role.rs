@@ -159,6 +159,35 @@ impl Role {
         Ok(roles)
     }
 
+    /// Get the names of a user's roles, ordered by name.
+    ///
+    /// Includes the built-in "authenticated user" role, or "anonymous user"
+    /// when `authenticated` is false.
+    pub async fn get_user_role_names(
+        pool: &PgPool,
+        user_id: Uuid,
+        authenticated: bool,
+    ) -> Result<Vec<String>> {
+        let builtin = if authenticated {
+            well_known::AUTHENTICATED_ROLE_ID
+        } else {
+            well_known::ANONYMOUS_ROLE_ID
+        };
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT name FROM roles
+            WHERE id = $2
+               OR id IN (SELECT role_id FROM user_roles WHERE user_id = $1)
+            ORDER BY name
+            "#,
+        )
+        .bind(user_id)
+        .bind(builtin)
+        .fetch_all(pool)
+        .await
+        .context("failed to get user role names")
+    }
+
     /// Assign a role to a user.
     pub async fn assign_to_user(pool: &PgPool, user_id: Uuid, role_id: Uuid) -> Result<()> {
         sqlx::query(
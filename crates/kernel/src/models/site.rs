@@ -0,0 +1,194 @@
+//! Sites served by a multisite deployment.
+//!
+//! A site is a tenant reached through a hostname. One tenant may answer on
+//! several hostnames (`example.com`, `www.example.com`). Per-site plugin
+//! switches live in `site_plugin`; see [`crate::services::site`] for how
+//! requests are matched to sites.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Maximum length of a hostname.
+const MAX_HOSTNAME_LENGTH: usize = 255;
+
+/// A hostname mapped to a tenant.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Site {
+    /// Normalized hostname (see [`normalize_host`]).
+    pub hostname: String,
+
+    /// Tenant serving this hostname.
+    pub tenant_id: Uuid,
+
+    /// Unix timestamp when created.
+    pub created: i64,
+}
+
+/// A per-site plugin switch.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SitePlugin {
+    /// Tenant the switch applies to.
+    pub tenant_id: Uuid,
+
+    /// Plugin machine name.
+    pub plugin_name: String,
+
+    /// Whether the plugin runs for this site.
+    pub enabled: bool,
+
+    /// Unix timestamp when last changed.
+    pub changed: i64,
+}
+
+/// Normalize a `Host` header value: lowercase, without port or trailing dot.
+///
+/// Returns `None` for values that cannot be a hostname.
+pub fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim();
+    // Bracketed IPv6 literals keep their colons.
+    let host = match host.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map(|(addr, _)| addr)?,
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    let host = host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase();
+
+    let valid = !host.is_empty()
+        && host.len() <= MAX_HOSTNAME_LENGTH
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | ':'));
+    valid.then_some(host)
+}
+
+impl Site {
+    /// Find the site for a hostname.
+    pub async fn find_by_hostname(pool: &PgPool, hostname: &str) -> Result<Option<Self>> {
+        sqlx::query_as::<_, Self>("SELECT * FROM site WHERE hostname = $1")
+            .bind(hostname)
+            .fetch_optional(pool)
+            .await
+            .context("failed to fetch site")
+    }
+
+    /// List all sites ordered by hostname.
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Self>("SELECT * FROM site ORDER BY hostname")
+            .fetch_all(pool)
+            .await
+            .context("failed to list sites")
+    }
+
+    /// Map a hostname to a tenant, replacing any existing mapping.
+    pub async fn upsert(pool: &PgPool, hostname: &str, tenant_id: Uuid) -> Result<Self> {
+        let Some(hostname) = normalize_host(hostname) else {
+            bail!("invalid hostname: '{hostname}'");
+        };
+
+        sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO site (hostname, tenant_id, created)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (hostname) DO UPDATE SET tenant_id = EXCLUDED.tenant_id
+            RETURNING *
+            "#,
+        )
+        .bind(&hostname)
+        .bind(tenant_id)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(pool)
+        .await
+        .context("failed to save site")
+    }
+
+    /// Remove a hostname. The tenant and its content are kept.
+    pub async fn delete(pool: &PgPool, hostname: &str) -> Result<bool> {
+        let hostname = normalize_host(hostname).unwrap_or_default();
+        let result = sqlx::query("DELETE FROM site WHERE hostname = $1")
+            .bind(hostname)
+            .execute(pool)
+            .await
+            .context("failed to delete site")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Plugin switches for a tenant, ordered by plugin name.
+    pub async fn plugins(pool: &PgPool, tenant_id: Uuid) -> Result<Vec<SitePlugin>> {
+        sqlx::query_as::<_, SitePlugin>(
+            "SELECT * FROM site_plugin WHERE tenant_id = $1 ORDER BY plugin_name",
+        )
+        .bind(tenant_id)
+        .fetch_all(pool)
+        .await
+        .context("failed to list site plugins")
+    }
+
+    /// Names of the plugins switched off for a tenant.
+    pub async fn disabled_plugins(pool: &PgPool, tenant_id: Uuid) -> Result<Vec<String>> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT plugin_name FROM site_plugin WHERE tenant_id = $1 AND enabled = FALSE",
+        )
+        .bind(tenant_id)
+        .fetch_all(pool)
+        .await
+        .context("failed to list disabled site plugins")
+    }
+
+    /// Switch a plugin on or off for a tenant.
+    ///
+    /// Only plugins enabled for the deployment can run; switching one on
+    /// for a site just clears an earlier switch-off.
+    pub async fn set_plugin_enabled(
+        pool: &PgPool,
+        tenant_id: Uuid,
+        plugin_name: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO site_plugin (tenant_id, plugin_name, enabled, changed)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tenant_id, plugin_name) DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                changed = EXCLUDED.changed
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(plugin_name)
+        .bind(enabled)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(pool)
+        .await
+        .context("failed to save site plugin")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_host_strips_port_and_case() {
+        assert_eq!(normalize_host("Example.COM").unwrap(), "example.com");
+        assert_eq!(normalize_host("example.com:8080").unwrap(), "example.com");
+        assert_eq!(
+            normalize_host("www.example.com.").unwrap(),
+            "www.example.com"
+        );
+        assert_eq!(normalize_host("[::1]:3000").unwrap(), "::1");
+        assert_eq!(normalize_host("localhost").unwrap(), "localhost");
+    }
+
+    #[test]
+    fn normalize_host_rejects_garbage() {
+        assert!(normalize_host("").is_none());
+        assert!(normalize_host(":8080").is_none());
+        assert!(normalize_host("exa mple.com").is_none());
+        assert!(normalize_host("example.com/path").is_none());
+        assert!(normalize_host("[::1").is_none());
+    }
+}
@@ -18,9 +18,11 @@ pub struct SiteConfig {
 }
 
 impl SiteConfig {
-    /// Get a configuration value by key (default tenant).
+    /// Get a configuration value by key for the current site.
+    ///
+    /// Outside a multisite request this is the default tenant.
     pub async fn get(pool: &PgPool, key: &str) -> Result<Option<serde_json::Value>> {
-        Self::get_for_tenant(pool, key, crate::models::tenant::DEFAULT_TENANT_ID).await
+        Self::get_for_tenant(pool, key, crate::services::site::current_tenant_id()).await
     }
 
     /// Get a configuration value by key for a specific tenant.
@@ -63,15 +65,28 @@ impl SiteConfig {
         Ok(None)
     }
 
-    /// Set a configuration value (default tenant).
+    /// Set a configuration value for the current site.
+    ///
+    /// Outside a multisite request this is the default tenant.
     pub async fn set(pool: &PgPool, key: &str, value: serde_json::Value) -> Result<()> {
+        Self::set_for_tenant(pool, key, value, crate::services::site::current_tenant_id()).await
+    }
+
+    /// Set a configuration value for a specific tenant.
+    pub async fn set_for_tenant(
+        pool: &PgPool,
+        key: &str,
+        value: serde_json::Value,
+        tenant_id: uuid::Uuid,
+    ) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO site_config (key, value, updated)
-            VALUES ($1, $2, NOW())
-            ON CONFLICT (key) DO UPDATE SET value = $2, updated = NOW()
+            INSERT INTO site_config (tenant_id, key, value, updated)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (tenant_id, key) DO UPDATE SET value = $3, updated = NOW()
             "#,
         )
+        .bind(tenant_id)
         .bind(key)
         .bind(value)
         .execute(pool)
@@ -198,15 +213,24 @@ impl SiteConfig {
         Self::set(pool, "site_front_page", serde_json::json!(path)).await
     }
 
-    /// Get all configuration as a map.
+    /// Get all configuration of the current site as a map.
+    ///
+    /// Keys the site does not override come from the default tenant.
     pub async fn all(
         pool: &PgPool,
     ) -> Result<std::collections::HashMap<String, serde_json::Value>> {
-        let configs =
-            sqlx::query_as::<_, SiteConfig>("SELECT key, value, updated FROM site_config")
-                .fetch_all(pool)
-                .await
-                .context("failed to get all site configs")?;
+        let configs = sqlx::query_as::<_, SiteConfig>(
+            r#"
+            SELECT DISTINCT ON (key) key, value, updated FROM site_config
+            WHERE tenant_id = $1 OR tenant_id = $2
+            ORDER BY key, (tenant_id = $1) DESC
+            "#,
+        )
+        .bind(crate::services::site::current_tenant_id())
+        .bind(crate::models::tenant::DEFAULT_TENANT_ID)
+        .fetch_all(pool)
+        .await
+        .context("failed to get all site configs")?;
 
         Ok(configs.into_iter().map(|c| (c.key, c.value)).collect())
     }
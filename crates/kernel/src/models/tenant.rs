@@ -61,6 +61,32 @@ impl Tenant {
             .await
             .context("failed to fetch tenant by machine name")
     }
+
+    /// List all tenants ordered by machine name.
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, Tenant>("SELECT * FROM tenant ORDER BY machine_name")
+            .fetch_all(pool)
+            .await
+            .context("failed to list tenants")
+    }
+
+    /// Create a tenant.
+    pub async fn create(pool: &PgPool, name: &str, machine_name: &str) -> Result<Self> {
+        sqlx::query_as::<_, Tenant>(
+            r#"
+            INSERT INTO tenant (id, name, machine_name, status, created)
+            VALUES ($1, $2, $3, TRUE, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(name)
+        .bind(machine_name)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(pool)
+        .await
+        .context("failed to create tenant")
+    }
 }
 
 /// Tenant context resolved per request by the tenant middleware.
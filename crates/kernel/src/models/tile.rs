@@ -201,14 +201,16 @@ impl Tile {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Check if this tile is visible for a given request path and user roles.
+    /// Check if this tile is visible for a given request path, user roles,
+    /// and the content type of the item the page displays (if any).
     ///
     /// Visibility rules in JSON:
     /// - `{ "paths": ["/admin/*", "/user/*"] }` — show only on matching paths
     /// - `{ "paths_exclude": ["/admin/*"] }` — hide on matching paths
     /// - `{ "roles": ["authenticated user", "administrator"] }` — show only to users with one of these roles
+    /// - `{ "content_types": ["blog"] }` — show only on pages displaying an item of these types
     /// - `{}` — always visible
-    pub fn is_visible(&self, path: &str, user_roles: &[String]) -> bool {
+    pub fn is_visible(&self, path: &str, user_roles: &[String], item_type: Option<&str>) -> bool {
         // Role check
         if let Some(roles) = self.visibility.get("roles").and_then(|v| v.as_array()) {
             let role_match = roles.iter().any(|r| {
@@ -220,6 +222,18 @@ impl Tile {
             }
         }
 
+        // Content type check
+        if let Some(types) = self
+            .visibility
+            .get("content_types")
+            .and_then(|v| v.as_array())
+        {
+            let type_match = item_type.is_some_and(|t| types.iter().any(|v| v.as_str() == Some(t)));
+            if !type_match {
+                return false;
+            }
+        }
+
         // Path check
         if let Some(paths) = self.visibility.get("paths").and_then(|v| v.as_array()) {
             return paths.iter().any(|p| {
@@ -279,29 +293,29 @@ mod tests {
     #[test]
     fn empty_visibility_always_visible() {
         let tile = make_tile(serde_json::json!({}));
-        assert!(tile.is_visible("/", &[]));
-        assert!(tile.is_visible("/admin", &[]));
+        assert!(tile.is_visible("/", &[], None));
+        assert!(tile.is_visible("/admin", &[], None));
     }
 
     #[test]
     fn paths_include_filter() {
         let tile = make_tile(serde_json::json!({ "paths": ["/admin/*"] }));
-        assert!(tile.is_visible("/admin/people", &[]));
-        assert!(!tile.is_visible("/user/login", &[]));
+        assert!(tile.is_visible("/admin/people", &[], None));
+        assert!(!tile.is_visible("/user/login", &[], None));
     }
 
     #[test]
     fn paths_exclude_filter() {
         let tile = make_tile(serde_json::json!({ "paths_exclude": ["/admin/*"] }));
-        assert!(!tile.is_visible("/admin/people", &[]));
-        assert!(tile.is_visible("/user/login", &[]));
+        assert!(!tile.is_visible("/admin/people", &[], None));
+        assert!(tile.is_visible("/user/login", &[], None));
     }
 
     #[test]
     fn exact_path_match() {
         let tile = make_tile(serde_json::json!({ "paths": ["/about"] }));
-        assert!(tile.is_visible("/about", &[]));
-        assert!(!tile.is_visible("/about/team", &[]));
+        assert!(tile.is_visible("/about", &[], None));
+        assert!(!tile.is_visible("/about/team", &[], None));
     }
 
     #[test]
@@ -312,9 +326,9 @@ mod tests {
             "administrator".to_string(),
         ];
         let user_roles = vec!["authenticated user".to_string()];
-        assert!(tile.is_visible("/", &admin_roles));
-        assert!(!tile.is_visible("/", &user_roles));
-        assert!(!tile.is_visible("/", &[]));
+        assert!(tile.is_visible("/", &admin_roles, None));
+        assert!(!tile.is_visible("/", &user_roles, None));
+        assert!(!tile.is_visible("/", &[], None));
     }
 
     #[test]
@@ -324,9 +338,17 @@ mod tests {
             "paths": ["/dashboard*"]
         }));
         let roles = vec!["authenticated user".to_string()];
-        assert!(tile.is_visible("/dashboard", &roles));
-        assert!(!tile.is_visible("/about", &roles));
-        assert!(!tile.is_visible("/dashboard", &[]));
+        assert!(tile.is_visible("/dashboard", &roles, None));
+        assert!(!tile.is_visible("/about", &roles, None));
+        assert!(!tile.is_visible("/dashboard", &[], None));
+    }
+
+    #[test]
+    fn content_type_visibility_filter() {
+        let tile = make_tile(serde_json::json!({ "content_types": ["blog", "page"] }));
+        assert!(tile.is_visible("/item/x", &[], Some("blog")));
+        assert!(!tile.is_visible("/item/x", &[], Some("conference")));
+        assert!(!tile.is_visible("/", &[], None));
     }
 
     #[test]
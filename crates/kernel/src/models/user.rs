@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use super::password_policy::MAX_HISTORY_COUNT;
+
 /// Anonymous user UUID (nil UUID).
 pub const ANONYMOUS_USER_ID: Uuid = Uuid::nil();
 
@@ -44,6 +46,14 @@ pub struct User {
     /// User-requested data retention period in days.
     #[serde(default)]
     pub data_retention_days: Option<i32>,
+
+    /// When the password was last set (see password policy `max_age_days`).
+    #[serde(default = "Utc::now")]
+    pub password_changed: DateTime<Utc>,
+
+    /// Plugin-defined profile field values (see `tap_user_info`).
+    #[serde(default)]
+    pub fields: serde_json::Value,
 }
 
 /// Input for creating a new user.
@@ -65,6 +75,7 @@ pub struct UpdateUser {
     pub timezone: Option<String>,
     pub language: Option<String>,
     pub data: Option<serde_json::Value>,
+    pub fields: Option<serde_json::Value>,
 }
 
 impl User {
@@ -197,6 +208,10 @@ impl User {
             params.push(format!("data = ${param_idx}"));
             param_idx += 1;
         }
+        if input.fields.is_some() {
+            params.push(format!("fields = ${param_idx}"));
+            param_idx += 1;
+        }
 
         if params.is_empty() {
             // Nothing to update, just return the user
@@ -231,6 +246,9 @@ impl User {
         if let Some(ref data) = input.data {
             query_builder = query_builder.bind(data);
         }
+        if let Some(ref fields) = input.fields {
+            query_builder = query_builder.bind(fields);
+        }
         query_builder = query_builder.bind(id);
 
         let user = query_builder
@@ -242,19 +260,60 @@ impl User {
     }
 
     /// Update the user's password.
+    ///
+    /// The replaced hash is added to the password history, which keeps the
+    /// newest [`MAX_HISTORY_COUNT`] entries per user.
     pub async fn update_password(pool: &PgPool, id: Uuid, new_password: &str) -> Result<bool> {
         let pass = hash_password(new_password)?;
+        let mut tx = pool.begin().await.context("failed to begin transaction")?;
 
-        let result = sqlx::query("UPDATE users SET pass = $1 WHERE id = $2")
-            .bind(&pass)
-            .bind(id)
-            .execute(pool)
-            .await
-            .context("failed to update password")?;
+        sqlx::query(
+            "INSERT INTO password_history (id, user_id, pass) \
+             SELECT $1, id, pass FROM users WHERE id = $2 AND pass <> ''",
+        )
+        .bind(Uuid::now_v7())
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .context("failed to record password history")?;
+
+        sqlx::query(
+            "DELETE FROM password_history WHERE user_id = $1 AND id NOT IN \
+             (SELECT id FROM password_history WHERE user_id = $1 ORDER BY created DESC LIMIT $2)",
+        )
+        .bind(id)
+        .bind(i64::from(MAX_HISTORY_COUNT))
+        .execute(&mut *tx)
+        .await
+        .context("failed to prune password history")?;
+
+        let result =
+            sqlx::query("UPDATE users SET pass = $1, password_changed = NOW() WHERE id = $2")
+                .bind(&pass)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .context("failed to update password")?;
 
+        tx.commit()
+            .await
+            .context("failed to commit password update")?;
         Ok(result.rows_affected() > 0)
     }
 
+    /// Hashes of the user's previous passwords, newest first.
+    pub async fn password_history(pool: &PgPool, id: Uuid, limit: u32) -> Result<Vec<String>> {
+        sqlx::query_scalar(
+            "SELECT pass FROM password_history WHERE user_id = $1 \
+             ORDER BY created DESC LIMIT $2",
+        )
+        .bind(id)
+        .bind(i64::from(limit))
+        .fetch_all(pool)
+        .await
+        .context("failed to fetch password history")
+    }
+
     /// Update the user's last access time.
     pub async fn touch_access(pool: &PgPool, id: Uuid) -> Result<()> {
         sqlx::query("UPDATE users SET access = NOW() WHERE id = $1")
@@ -300,6 +359,19 @@ impl User {
         Ok(users)
     }
 
+    /// List blocked users whose email is verified and who await approval
+    /// (see [`crate::services::registration`]), oldest first.
+    pub async fn list_pending_approval(pool: &PgPool) -> Result<Vec<Self>> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE status = 0 AND data->>'pending_approval' = 'true' ORDER BY created",
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to list users awaiting approval")?;
+
+        Ok(users)
+    }
+
     /// Count all users.
     pub async fn count(pool: &PgPool) -> Result<i64> {
         let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
@@ -328,18 +400,23 @@ impl User {
 
     /// Verify a password against this user's hash.
     pub fn verify_password(&self, password: &str) -> bool {
-        if self.pass.is_empty() {
-            return false;
-        }
-
-        let Ok(parsed_hash) = PasswordHash::new(&self.pass) else {
-            return false;
-        };
+        verify_hash(&self.pass, password)
+    }
+}
 
-        argon2_instance()
-            .verify_password(password.as_bytes(), &parsed_hash)
-            .is_ok()
+/// Verify a password against an Argon2 hash.
+pub fn verify_hash(hash: &str, password: &str) -> bool {
+    if hash.is_empty() {
+        return false;
     }
+
+    let Ok(parsed_hash) = PasswordHash::new(hash) else {
+        return false;
+    };
+
+    argon2_instance()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok()
 }
 
 /// Create an Argon2id instance with RFC 9106 recommended parameters.
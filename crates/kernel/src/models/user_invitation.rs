@@ -0,0 +1,186 @@
+//! Invitation model for invite-only registration.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::email_verification::{generate_token, hash_token};
+
+/// Invitation validity period (7 days).
+pub const INVITATION_VALIDITY_DAYS: i64 = 7;
+
+/// Invitation record.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UserInvitation {
+    /// Invitation ID.
+    pub id: Uuid,
+    /// Address the invitation was sent to; registration must use it.
+    pub mail: String,
+    /// SHA-256 hash of the plain token.
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    /// Roles assigned to the account when the invitation is accepted.
+    pub role_ids: Vec<Uuid>,
+    /// Administrator who sent the invitation.
+    pub invited_by: Option<Uuid>,
+    /// When this invitation expires.
+    pub expires_at: DateTime<Utc>,
+    /// When this invitation was accepted (None if pending).
+    pub used_at: Option<DateTime<Utc>>,
+    /// Account created from this invitation.
+    pub user_id: Option<Uuid>,
+    /// When this invitation was created.
+    pub created: DateTime<Utc>,
+}
+
+impl UserInvitation {
+    /// Create an invitation for `mail`.
+    ///
+    /// Returns `(invitation, plain_token)` where `plain_token` should be
+    /// sent to the invitee via email.
+    pub async fn create(
+        pool: &PgPool,
+        mail: &str,
+        role_ids: &[Uuid],
+        invited_by: Option<Uuid>,
+    ) -> Result<(Self, String)> {
+        let plain_token = generate_token();
+        let token_hash = hash_token(&plain_token);
+        let expires_at = Utc::now() + Duration::days(INVITATION_VALIDITY_DAYS);
+
+        let invitation = sqlx::query_as::<_, UserInvitation>(
+            r#"
+            INSERT INTO user_invitation (id, mail, token_hash, role_ids, invited_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(mail)
+        .bind(&token_hash)
+        .bind(role_ids)
+        .bind(invited_by)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await
+        .context("failed to create invitation")?;
+
+        Ok((invitation, plain_token))
+    }
+
+    /// Find a pending invitation by its plain token.
+    ///
+    /// Returns `None` if the token doesn't exist, is expired or was used.
+    pub async fn find_valid(pool: &PgPool, plain_token: &str) -> Result<Option<Self>> {
+        let invitation = sqlx::query_as::<_, UserInvitation>(
+            r#"
+            SELECT * FROM user_invitation
+            WHERE token_hash = $1
+              AND expires_at > NOW()
+              AND used_at IS NULL
+            "#,
+        )
+        .bind(hash_token(plain_token))
+        .fetch_optional(pool)
+        .await
+        .context("failed to find invitation")?;
+
+        Ok(invitation)
+    }
+
+    /// Find the newest pending invitation sent to `mail` (case-insensitive).
+    ///
+    /// Used by logins that carry a verified address but no token, such as
+    /// external identity providers.
+    pub async fn find_valid_for_mail(pool: &PgPool, mail: &str) -> Result<Option<Self>> {
+        let invitation = sqlx::query_as::<_, UserInvitation>(
+            r#"
+            SELECT * FROM user_invitation
+            WHERE LOWER(TRIM(mail)) = LOWER(TRIM($1))
+              AND expires_at > NOW()
+              AND used_at IS NULL
+            ORDER BY created DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(mail)
+        .fetch_optional(pool)
+        .await
+        .context("failed to find invitation by mail")?;
+
+        Ok(invitation)
+    }
+
+    /// Whether `mail` is the invited address (case-insensitive).
+    pub fn matches_mail(&self, mail: &str) -> bool {
+        self.mail.trim().eq_ignore_ascii_case(mail.trim())
+    }
+
+    /// Claim a pending invitation before creating its account.
+    ///
+    /// Returns `false` if it was already used, so an invitation can only be
+    /// redeemed once even under concurrent registrations.
+    pub async fn claim(pool: &PgPool, id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE user_invitation SET used_at = NOW() WHERE id = $1 AND used_at IS NULL AND expires_at > NOW()",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .context("failed to claim invitation")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Release a claimed invitation whose account could not be created.
+    pub async fn release(pool: &PgPool, id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE user_invitation SET used_at = NULL WHERE id = $1 AND user_id IS NULL")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to release invitation")?;
+
+        Ok(())
+    }
+
+    /// Record the account created from a claimed invitation.
+    pub async fn record_user(pool: &PgPool, id: Uuid, user_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE user_invitation SET user_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .context("failed to record invited user")?;
+
+        Ok(())
+    }
+
+    /// List invitations that are neither used nor expired, newest first.
+    pub async fn list_pending(pool: &PgPool) -> Result<Vec<Self>> {
+        let invitations = sqlx::query_as::<_, UserInvitation>(
+            r#"
+            SELECT * FROM user_invitation
+            WHERE used_at IS NULL AND expires_at > NOW()
+            ORDER BY created DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to list invitations")?;
+
+        Ok(invitations)
+    }
+
+    /// Revoke a pending invitation.
+    pub async fn revoke(pool: &PgPool, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM user_invitation WHERE id = $1 AND used_at IS NULL")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to revoke invitation")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
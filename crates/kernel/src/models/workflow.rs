@@ -0,0 +1,371 @@
+//! Content moderation workflows.
+//!
+//! A workflow is a config entity describing the editorial states an item
+//! moves through (e.g. draft → review → published → archived) and the
+//! transitions allowed between them. Each workflow applies to a set of item
+//! types; items of those types carry a `moderation_state`.
+//!
+//! Every transition is gated by its own permission, built by
+//! [`transition_permission`]: `transition editorial from draft to review`.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Maximum length of workflow and state machine names.
+const MAX_NAME_LENGTH: usize = 64;
+
+/// A state in a workflow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowState {
+    /// Machine name (e.g. "review").
+    pub id: String,
+    /// Human-readable label.
+    pub label: String,
+    /// Whether items in this state are published (`status = 1`).
+    #[serde(default)]
+    pub published: bool,
+    /// Whether items in this state may be published from a stage.
+    #[serde(default)]
+    pub publishable: bool,
+}
+
+/// An allowed move between two states.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowTransition {
+    /// Source state machine name.
+    pub from: String,
+    /// Target state machine name.
+    pub to: String,
+}
+
+/// A moderation workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workflow {
+    /// Machine name (e.g. "editorial").
+    pub id: String,
+    /// Human-readable label.
+    pub label: String,
+    /// State new items start in.
+    pub initial_state: String,
+    /// States, in display order.
+    pub states: Vec<WorkflowState>,
+    /// Allowed transitions.
+    pub transitions: Vec<WorkflowTransition>,
+    /// Item types moderated by this workflow.
+    #[serde(default)]
+    pub item_types: Vec<String>,
+    /// Refuse to publish a stage holding items of these types in a state
+    /// that is not `publishable`.
+    #[serde(default)]
+    pub require_publishable: bool,
+    /// Unix timestamp when created.
+    #[serde(default)]
+    pub created: i64,
+    /// Unix timestamp when last changed.
+    #[serde(default)]
+    pub changed: i64,
+}
+
+/// Row type for workflow queries.
+#[derive(sqlx::FromRow)]
+struct WorkflowRow {
+    id: String,
+    label: String,
+    initial_state: String,
+    states: serde_json::Value,
+    transitions: serde_json::Value,
+    item_types: Vec<String>,
+    require_publishable: bool,
+    created: i64,
+    changed: i64,
+}
+
+impl TryFrom<WorkflowRow> for Workflow {
+    type Error = anyhow::Error;
+
+    fn try_from(row: WorkflowRow) -> Result<Self> {
+        Ok(Self {
+            states: serde_json::from_value(row.states)
+                .with_context(|| format!("failed to parse states for workflow '{}'", row.id))?,
+            transitions: serde_json::from_value(row.transitions).with_context(|| {
+                format!("failed to parse transitions for workflow '{}'", row.id)
+            })?,
+            id: row.id,
+            label: row.label,
+            initial_state: row.initial_state,
+            item_types: row.item_types,
+            require_publishable: row.require_publishable,
+            created: row.created,
+            changed: row.changed,
+        })
+    }
+}
+
+/// Permission required to move items from `from` to `to` in `workflow`.
+pub fn transition_permission(workflow: &str, from: &str, to: &str) -> String {
+    format!("transition {workflow} from {from} to {to}")
+}
+
+impl Workflow {
+    /// Look up a state by machine name.
+    pub fn state(&self, id: &str) -> Option<&WorkflowState> {
+        self.states.iter().find(|s| s.id == id)
+    }
+
+    /// Whether the workflow defines a transition from `from` to `to`.
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.transitions
+            .iter()
+            .any(|t| t.from == from && t.to == to)
+    }
+
+    /// Transitions leaving `from`.
+    pub fn transitions_from<'a>(
+        &'a self,
+        from: &'a str,
+    ) -> impl Iterator<Item = &'a WorkflowTransition> + 'a {
+        self.transitions.iter().filter(move |t| t.from == from)
+    }
+
+    /// Whether an item in `state` may be published from a stage.
+    ///
+    /// Items without a state have not entered the workflow and are treated
+    /// as being in the initial state.
+    pub fn is_publishable(&self, state: Option<&str>) -> bool {
+        self.state(state.unwrap_or(&self.initial_state))
+            .is_some_and(|s| s.publishable)
+    }
+
+    /// Permissions for every transition, one per transition.
+    pub fn permissions(&self) -> Vec<String> {
+        self.transitions
+            .iter()
+            .map(|t| transition_permission(&self.id, &t.from, &t.to))
+            .collect()
+    }
+
+    /// Check names are valid and every referenced state exists.
+    pub fn validate(&self) -> Result<()> {
+        let valid_name = |name: &str| {
+            !name.is_empty()
+                && name.len() <= MAX_NAME_LENGTH
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        };
+
+        if !valid_name(&self.id) {
+            bail!("invalid workflow machine name: '{}'", self.id);
+        }
+        if self.label.trim().is_empty() {
+            bail!("workflow '{}' needs a label", self.id);
+        }
+        if self.states.is_empty() {
+            bail!("workflow '{}' has no states", self.id);
+        }
+        for (i, state) in self.states.iter().enumerate() {
+            if !valid_name(&state.id) {
+                bail!("invalid state machine name: '{}'", state.id);
+            }
+            if self.states[..i].iter().any(|s| s.id == state.id) {
+                bail!("duplicate state '{}' in workflow '{}'", state.id, self.id);
+            }
+        }
+        if self.state(&self.initial_state).is_none() {
+            bail!(
+                "initial state '{}' is not a state of workflow '{}'",
+                self.initial_state,
+                self.id
+            );
+        }
+        for t in &self.transitions {
+            if self.state(&t.from).is_none() || self.state(&t.to).is_none() {
+                bail!(
+                    "transition {} -> {} references an unknown state",
+                    t.from,
+                    t.to
+                );
+            }
+            if t.from == t.to {
+                bail!("transition {} -> {} does not change state", t.from, t.to);
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a workflow by machine name.
+    pub async fn find_by_id(pool: &PgPool, id: &str) -> Result<Option<Self>> {
+        let row = sqlx::query_as::<_, WorkflowRow>("SELECT * FROM workflow WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .context("failed to fetch workflow")?;
+        row.map(Self::try_from).transpose()
+    }
+
+    /// Find the workflow moderating an item type.
+    ///
+    /// If several workflows claim the type, the first by machine name wins.
+    pub async fn find_for_type(pool: &PgPool, item_type: &str) -> Result<Option<Self>> {
+        let row = sqlx::query_as::<_, WorkflowRow>(
+            "SELECT * FROM workflow WHERE $1 = ANY(item_types) ORDER BY id LIMIT 1",
+        )
+        .bind(item_type)
+        .fetch_optional(pool)
+        .await
+        .context("failed to fetch workflow for item type")?;
+        row.map(Self::try_from).transpose()
+    }
+
+    /// List all workflows ordered by machine name.
+    pub async fn list_all(pool: &PgPool) -> Result<Vec<Self>> {
+        let rows = sqlx::query_as::<_, WorkflowRow>("SELECT * FROM workflow ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .context("failed to list workflows")?;
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Validate and insert or update a workflow.
+    pub async fn upsert(pool: &PgPool, workflow: &Self) -> Result<Self> {
+        workflow.validate()?;
+        let now = chrono::Utc::now().timestamp();
+        let created = if workflow.created > 0 {
+            workflow.created
+        } else {
+            now
+        };
+
+        let row = sqlx::query_as::<_, WorkflowRow>(
+            r#"
+            INSERT INTO workflow (id, label, initial_state, states, transitions, item_types,
+                                  require_publishable, created, changed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (id) DO UPDATE SET
+                label = EXCLUDED.label,
+                initial_state = EXCLUDED.initial_state,
+                states = EXCLUDED.states,
+                transitions = EXCLUDED.transitions,
+                item_types = EXCLUDED.item_types,
+                require_publishable = EXCLUDED.require_publishable,
+                changed = EXCLUDED.changed
+            RETURNING *
+            "#,
+        )
+        .bind(&workflow.id)
+        .bind(&workflow.label)
+        .bind(&workflow.initial_state)
+        .bind(serde_json::to_value(&workflow.states).context("failed to serialize states")?)
+        .bind(
+            serde_json::to_value(&workflow.transitions)
+                .context("failed to serialize transitions")?,
+        )
+        .bind(&workflow.item_types)
+        .bind(workflow.require_publishable)
+        .bind(created)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .context("failed to save workflow")?;
+
+        Self::try_from(row)
+    }
+
+    /// Delete a workflow. Items keep their last moderation state.
+    pub async fn delete(pool: &PgPool, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM workflow WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await
+            .context("failed to delete workflow")?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn editorial() -> Workflow {
+        serde_json::from_value(serde_json::json!({
+            "id": "editorial",
+            "label": "Editorial",
+            "initial_state": "draft",
+            "states": [
+                {"id": "draft", "label": "Draft"},
+                {"id": "review", "label": "In review"},
+                {"id": "published", "label": "Published", "published": true, "publishable": true},
+                {"id": "archived", "label": "Archived"}
+            ],
+            "transitions": [
+                {"from": "draft", "to": "review"},
+                {"from": "review", "to": "draft"},
+                {"from": "review", "to": "published"},
+                {"from": "published", "to": "archived"}
+            ],
+            "item_types": ["page"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn transitions_follow_definition() {
+        let wf = editorial();
+        assert!(wf.validate().is_ok());
+        assert!(wf.allows("draft", "review"));
+        assert!(!wf.allows("draft", "published"));
+        let targets: Vec<&str> = wf
+            .transitions_from("review")
+            .map(|t| t.to.as_str())
+            .collect();
+        assert_eq!(targets, vec!["draft", "published"]);
+    }
+
+    #[test]
+    fn permissions_name_each_transition() {
+        let wf = editorial();
+        assert_eq!(
+            transition_permission("editorial", "draft", "review"),
+            "transition editorial from draft to review"
+        );
+        assert_eq!(wf.permissions().len(), 4);
+        assert!(
+            wf.permissions()
+                .contains(&"transition editorial from published to archived".to_string())
+        );
+    }
+
+    #[test]
+    fn publishable_defaults_to_initial_state() {
+        let wf = editorial();
+        assert!(wf.is_publishable(Some("published")));
+        assert!(!wf.is_publishable(Some("review")));
+        assert!(!wf.is_publishable(None));
+        assert!(!wf.is_publishable(Some("unknown")));
+    }
+
+    #[test]
+    fn validate_rejects_dangling_states() {
+        let mut wf = editorial();
+        wf.transitions.push(WorkflowTransition {
+            from: "archived".to_string(),
+            to: "deleted".to_string(),
+        });
+        assert!(wf.validate().is_err());
+
+        let mut wf = editorial();
+        wf.initial_state = "missing".to_string();
+        assert!(wf.validate().is_err());
+
+        let mut wf = editorial();
+        wf.states.push(wf.states[0].clone());
+        assert!(wf.validate().is_err());
+
+        let mut wf = editorial();
+        wf.id = "Editorial Flow".to_string();
+        assert!(wf.validate().is_err());
+    }
+}
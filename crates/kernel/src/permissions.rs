@@ -15,6 +15,12 @@ use crate::models::{Role, User};
 /// Maximum entries in the permission cache.
 const MAX_CAPACITY: u64 = 10_000;
 
+/// Permission to see one's own unpublished items of `item_type`, both
+/// directly and in gather listings.
+pub fn view_own_unpublished_permission(item_type: &str) -> String {
+    format!("view own unpublished {item_type} content")
+}
+
 /// Permission cache entry.
 #[derive(Debug, Clone)]
 struct CachedPermissions {
@@ -0,0 +1,205 @@
+//! Per-plugin host function capabilities.
+//!
+//! Plugins declare the sensitive host APIs they need under `[capabilities]`
+//! in their `.info.toml`. Only granted host functions are linked into the
+//! plugin's instances, and a module importing a function it was not granted
+//! fails to load, so the grant list is reviewed with the plugin at install
+//! time rather than discovered at runtime.
+//!
+//! Raw SQL is denied unless declared; HTTP and cache access are granted
+//! unless switched off. Host functions outside these groups (logging,
+//! variables, items, ...) are always linked. Plugins have no file system
+//! access: the WASI file calls are stubs.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use wasmtime::Module;
+
+/// A group of host functions granted as a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// `trovato:kernel/cache-api`.
+    Cache,
+    /// `trovato:kernel/http`.
+    Http,
+    /// `query-raw` and `execute-raw` of `trovato:kernel/db`.
+    RawSql,
+}
+
+impl Capability {
+    /// Every capability, in report order.
+    pub const ALL: [Self; 3] = [Self::Cache, Self::Http, Self::RawSql];
+
+    /// Name used in `.info.toml` and reports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cache => "cache",
+            Self::Http => "http",
+            Self::RawSql => "raw_sql",
+        }
+    }
+
+    /// The capability a host function import requires, if any.
+    pub fn of_import(module: &str, name: &str) -> Option<Self> {
+        match (module, name) {
+            ("trovato:kernel/cache-api", _) => Some(Self::Cache),
+            ("trovato:kernel/http", _) => Some(Self::Http),
+            ("trovato:kernel/db", "query-raw" | "execute-raw") => Some(Self::RawSql),
+            _ => None,
+        }
+    }
+}
+
+/// Capabilities declared under `[capabilities]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginCapabilities {
+    /// Read and write the cache (default: granted).
+    #[serde(default = "default_true")]
+    pub cache: bool,
+
+    /// Make outbound HTTP requests (default: granted).
+    #[serde(default = "default_true")]
+    pub http: bool,
+
+    /// Run arbitrary SQL (default: denied).
+    #[serde(default)]
+    pub raw_sql: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PluginCapabilities {
+    fn default() -> Self {
+        Self {
+            cache: true,
+            http: true,
+            raw_sql: false,
+        }
+    }
+}
+
+impl PluginCapabilities {
+    /// Every capability granted.
+    pub fn all() -> Self {
+        Self {
+            cache: true,
+            http: true,
+            raw_sql: true,
+        }
+    }
+
+    /// Whether `capability` is granted.
+    pub fn grants(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Cache => self.cache,
+            Capability::Http => self.http,
+            Capability::RawSql => self.raw_sql,
+        }
+    }
+
+    /// Granted capabilities, in report order.
+    pub fn granted(&self) -> Vec<Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(|c| self.grants(*c))
+            .collect()
+    }
+}
+
+/// Reject a module importing host functions its plugin was not granted.
+pub fn check_imports(
+    plugin: &str,
+    module: &Module,
+    capabilities: &PluginCapabilities,
+) -> Result<()> {
+    let denied = denied_imports(
+        module
+            .imports()
+            .map(|import| (import.module(), import.name())),
+        capabilities,
+    );
+    if denied.is_empty() {
+        return Ok(());
+    }
+
+    let imports: Vec<String> = denied
+        .iter()
+        .map(|(module, name, _)| format!("{module}.{name}"))
+        .collect();
+    let mut missing: Vec<Capability> = denied.iter().map(|(_, _, c)| *c).collect();
+    missing.sort();
+    missing.dedup();
+    let missing: Vec<&str> = missing.into_iter().map(Capability::as_str).collect();
+    bail!(
+        "plugin '{plugin}' imports {} without the {} capability; declare it under [capabilities] in the plugin's .info.toml",
+        imports.join(", "),
+        missing.join(", ")
+    )
+}
+
+/// Imports requiring a capability that is not granted, in import order.
+fn denied_imports<'a>(
+    imports: impl Iterator<Item = (&'a str, &'a str)>,
+    capabilities: &PluginCapabilities,
+) -> Vec<(&'a str, &'a str, Capability)> {
+    imports
+        .filter_map(|(module, name)| {
+            let capability = Capability::of_import(module, name)?;
+            (!capabilities.grants(capability)).then_some((module, name, capability))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_sql_is_denied_by_default() {
+        let caps = PluginCapabilities::default();
+        assert_eq!(caps.granted(), [Capability::Cache, Capability::Http]);
+
+        let caps: PluginCapabilities = toml::from_str("raw_sql = true\nhttp = false").unwrap();
+        assert_eq!(caps.granted(), [Capability::Cache, Capability::RawSql]);
+
+        assert!(toml::from_str::<PluginCapabilities>("files = true").is_err());
+    }
+
+    #[test]
+    fn imports_map_to_capabilities() {
+        assert_eq!(
+            Capability::of_import("trovato:kernel/db", "query-raw"),
+            Some(Capability::RawSql)
+        );
+        assert_eq!(Capability::of_import("trovato:kernel/db", "select"), None);
+        assert_eq!(
+            Capability::of_import("trovato:kernel/http", "request"),
+            Some(Capability::Http)
+        );
+        assert_eq!(Capability::of_import("trovato:kernel/logging", "log"), None);
+    }
+
+    #[test]
+    fn denied_imports_lists_ungranted_functions() {
+        let imports = [
+            ("trovato:kernel/db", "select"),
+            ("trovato:kernel/db", "execute-raw"),
+            ("trovato:kernel/cache-api", "get"),
+            ("wasi_snapshot_preview1", "fd_write"),
+        ];
+
+        let denied = denied_imports(imports.into_iter(), &PluginCapabilities::default());
+        assert_eq!(
+            denied,
+            [("trovato:kernel/db", "execute-raw", Capability::RawSql)]
+        );
+
+        assert!(denied_imports(imports.into_iter(), &PluginCapabilities::all()).is_empty());
+    }
+}
@@ -8,6 +8,7 @@ use std::path::Path;
 use anyhow::{Context, Result, bail};
 use sqlx::PgPool;
 
+use super::Capability;
 use super::migration;
 use super::runtime::PluginRuntime;
 use super::status;
@@ -147,6 +148,10 @@ weight = 0
 
 [migrations]
 files = []
+
+# Host functions beyond the defaults must be granted here.
+[capabilities]
+raw_sql = false
 "#
     );
     write_file(&plugin_dir.join(format!("{name}.info.toml")), &info_toml)?;
@@ -345,6 +350,18 @@ pub async fn cmd_plugin_install(pool: &PgPool, plugins_dir: &Path, name: &str) -
     // Check API version compatibility before any filesystem or DB changes.
     info.check_api_compatibility()?;
 
+    // Show what the plugin may do so the install can be reviewed.
+    let granted: Vec<&str> = info
+        .capabilities
+        .granted()
+        .into_iter()
+        .map(Capability::as_str)
+        .collect();
+    println!("Capabilities: {}", granted.join(", "));
+    if info.capabilities.raw_sql {
+        println!("Warning: '{name}' is granted raw SQL access to the database.");
+    }
+
     let already_installed = status::is_installed(pool, name).await?;
 
     // Check dependencies before touching the filesystem — bail here so the
@@ -137,6 +137,7 @@ pub fn check_dependencies(plugin: &PluginInfo, available: &HashSet<String>) -> R
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::plugin::PluginCapabilities;
     use crate::plugin::info_parser::{MigrationConfig, TapConfig};
 
     fn make_plugin(name: &str, deps: Vec<&str>) -> PluginInfo {
@@ -149,6 +150,10 @@ mod tests {
             dependencies: deps.into_iter().map(String::from).collect(),
             taps: TapConfig::default(),
             migrations: MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
+            flags: HashMap::new(),
         }
     }
 
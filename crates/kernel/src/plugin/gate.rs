@@ -36,6 +36,14 @@ pub const GATED_ROUTE_PLUGINS: &[GatedPlugin] = &[
         name: "trovato_comments",
         description: "Comment moderation admin UI + API routes",
     },
+    GatedPlugin {
+        name: "trovato_config_translation",
+        description: "Config translation admin UI routes",
+    },
+    GatedPlugin {
+        name: "trovato_contact",
+        description: "Contact form and contact admin UI routes",
+    },
     GatedPlugin {
         name: "trovato_content_locking",
         description: "Content lock API routes",
@@ -44,6 +52,10 @@ pub const GATED_ROUTE_PLUGINS: &[GatedPlugin] = &[
         name: "trovato_content_translation",
         description: "Content translation admin UI routes",
     },
+    GatedPlugin {
+        name: "trovato_flags",
+        description: "Item flag API routes",
+    },
     GatedPlugin {
         name: "trovato_image_styles",
         description: "Image style derivative routes",
@@ -52,10 +64,34 @@ pub const GATED_ROUTE_PLUGINS: &[GatedPlugin] = &[
         name: "trovato_oauth2",
         description: "OAuth2 authorization routes",
     },
+    GatedPlugin {
+        name: "trovato_oidc",
+        description: "External identity provider login and provider admin routes",
+    },
+    GatedPlugin {
+        name: "trovato_read_log",
+        description: "Read access log report and settings routes",
+    },
+    GatedPlugin {
+        name: "trovato_saved_searches",
+        description: "Saved search API routes",
+    },
+    GatedPlugin {
+        name: "trovato_scheduled_publishing",
+        description: "Scheduled content admin UI + schedule API routes",
+    },
+    GatedPlugin {
+        name: "trovato_scheduled_updates",
+        description: "Timed update admin UI + scheduled update API routes",
+    },
     GatedPlugin {
         name: "trovato_block_editor",
         description: "Block editor upload and preview API routes",
     },
+    GatedPlugin {
+        name: "goose",
+        description: "Load test run comparison API routes",
+    },
 ];
 
 /// A plugin whose kernel routes are runtime-gated.
@@ -4,6 +4,10 @@
 //! - name, version, description
 //! - dependencies (other plugins that must load first)
 //! - taps (which tap functions the plugin implements)
+//! - webhooks (inbound hooks received through `tap_webhook_receive`)
+//! - bulk operations (item bulk actions run through `tap_item_bulk_operation`)
+//! - flags (global or per-user item markers, see [`crate::services::flag`])
+//! - capabilities (host functions the plugin may call)
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -11,6 +15,8 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
+use super::capability::PluginCapabilities;
+
 /// Plugin metadata parsed from `.info.toml`.
 #[derive(Debug, Clone, Deserialize)]
 pub struct PluginInfo {
@@ -42,6 +48,97 @@ pub struct PluginInfo {
     /// Migration configuration.
     #[serde(default)]
     pub migrations: MigrationConfig,
+
+    /// Inbound webhooks, keyed by hook name.
+    #[serde(default)]
+    pub webhooks: HashMap<String, WebhookConfig>,
+
+    /// Host function capabilities (raw SQL, HTTP, cache).
+    #[serde(default)]
+    pub capabilities: PluginCapabilities,
+
+    /// Item bulk operations, keyed by operation name.
+    #[serde(default)]
+    pub bulk_operations: HashMap<String, BulkOperationConfig>,
+
+    /// Item flags, keyed by flag name.
+    #[serde(default)]
+    pub flags: HashMap<String, FlagConfig>,
+}
+
+/// An item flag declared under `[flags.{name}]`.
+///
+/// Flags are set and cleared through `POST|DELETE /item/{id}/flag/{name}`;
+/// every change is reported to `tap_flag`. Kernel flags (`promote`,
+/// `sticky`) take precedence over plugin ones of the same name.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlagConfig {
+    /// Label shown next to the flag link.
+    pub label: String,
+
+    /// `user` (the default): each user flags items for themselves, like a
+    /// bookmark. `global`: one shared flag per item, like "featured".
+    #[serde(default)]
+    pub scope: crate::services::flag::FlagScope,
+}
+
+/// An item bulk operation declared under `[bulk_operations.{name}]`.
+///
+/// Requests to `POST /api/items/bulk` naming the operation pass each item
+/// the user may access to the plugin's `tap_item_bulk_operation`. Kernel
+/// operations (`publish`, `delete`, ...) take precedence over plugin ones
+/// of the same name.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkOperationConfig {
+    /// Label shown in bulk action menus.
+    pub label: String,
+
+    /// Item access operation checked for each item: `edit` (the default)
+    /// or `delete`.
+    #[serde(default = "default_bulk_access")]
+    pub access: String,
+}
+
+fn default_bulk_access() -> String {
+    "edit".to_string()
+}
+
+/// An inbound webhook declared under `[webhooks.{hook}]`.
+///
+/// Calls to `/api/hooks/{plugin}/{hook}` are verified against the hook's
+/// secret (see [`crate::services::webhook`]) and passed to the plugin's
+/// `tap_webhook_receive`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    /// How calls are verified.
+    #[serde(default)]
+    pub verify: WebhookVerify,
+
+    /// Header carrying the signature or token. Defaults to
+    /// `X-Hub-Signature-256` for signatures and `X-Webhook-Token` for
+    /// tokens.
+    #[serde(default)]
+    pub header: Option<String>,
+
+    /// Accepted HTTP methods: `POST` (the default) and/or `GET`.
+    #[serde(default = "default_webhook_methods")]
+    pub methods: Vec<String>,
+}
+
+/// Verification scheme of an inbound webhook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookVerify {
+    /// HMAC of the raw body keyed with the secret (`sha256=<hex>` or
+    /// `sha1=<hex>`), as sent by WebSub hubs and most CI systems.
+    #[default]
+    Signature,
+    /// The secret itself, in the header or as `Authorization: Bearer`.
+    Token,
+}
+
+fn default_webhook_methods() -> Vec<String> {
+    vec!["POST".to_string()]
 }
 
 /// Configuration for plugin-declared SQL migrations.
@@ -68,15 +165,32 @@ pub struct TapConfig {
     #[serde(default)]
     pub weight: i32,
 
-    /// Per-tap options (reserved for future use).
+    /// Per-tap options, keyed by tap name.
     #[serde(default)]
     pub options: HashMap<String, TapOptions>,
 }
 
+impl TapConfig {
+    /// Whether results of `tap` may be reused within a request.
+    pub fn memoize(&self, tap: &str) -> bool {
+        self.options.get(tap).is_none_or(|o| o.memoize)
+    }
+}
+
 /// Per-tap configuration options.
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TapOptions {
-    // Reserved for future use (e.g., field filtering for filtered serialization)
+    /// Reuse results of identical calls within a request (see
+    /// [`crate::tap::memo`]). Set to `false` for taps whose output varies
+    /// between identical calls.
+    #[serde(default = "default_true")]
+    pub memoize: bool,
+}
+
+impl Default for TapOptions {
+    fn default() -> Self {
+        Self { memoize: true }
+    }
 }
 
 /// Known tap names for validation.
@@ -88,6 +202,9 @@ pub const KNOWN_TAPS: &[&str] = &[
     "tap_uninstall",
     // Content types
     "tap_item_info",
+    "tap_item_type_update",
+    // Config
+    "tap_config_validate",
     // Item CRUD
     "tap_item_view",
     "tap_item_view_alter",
@@ -95,8 +212,13 @@ pub const KNOWN_TAPS: &[&str] = &[
     "tap_item_update",
     "tap_item_delete",
     "tap_item_presave",
+    "tap_item_validate",
     "tap_item_access",
+    "tap_item_grants",
+    "tap_item_clone",
     "tap_field_access",
+    // Moderation
+    "tap_transition",
     // Categories
     "tap_categories_term_insert",
     "tap_categories_term_update",
@@ -111,19 +233,32 @@ pub const KNOWN_TAPS: &[&str] = &[
     // Theme
     "tap_theme",
     "tap_preprocess_item",
+    "tap_block_info",
+    "tap_block_render",
     // Search
     "tap_item_update_index",
     // Cron & queues
     "tap_cron",
     "tap_queue_info",
     "tap_queue_worker",
+    // Batch
+    "tap_batch_define",
+    "tap_batch_process",
+    // Events
+    "tap_event",
+    // Flags
+    "tap_flag",
+    // Device fingerprinting
+    "tap_ng_classify",
     // User
+    "tap_user_insert",
     "tap_user_login",
     "tap_user_logout",
     "tap_user_register",
     "tap_user_update",
     "tap_user_delete",
     "tap_user_export",
+    "tap_user_info",
     // AI governance
     "tap_ai_request",
     "tap_chat_actions",
@@ -134,8 +269,17 @@ pub const KNOWN_TAPS: &[&str] = &[
     "tap_comment_update",
     "tap_comment_delete",
     "tap_comment_access",
+    "tap_comment_spam_check",
     // Gather extensions
     "tap_gather_extend",
+    // Mail
+    "tap_mail_alter",
+    // Webhooks
+    "tap_webhook_receive",
+    // Bulk operations
+    "tap_item_bulk_operation",
+    // Status report
+    "tap_requirements",
 ];
 
 fn default_true() -> bool {
@@ -203,6 +347,86 @@ impl PluginInfo {
             }
         }
 
+        // Validate bulk operations: machine names, known access, and a handler
+        if !self.bulk_operations.is_empty()
+            && !self
+                .taps
+                .implements
+                .iter()
+                .any(|t| t == "tap_item_bulk_operation")
+        {
+            anyhow::bail!(
+                "plugin '{}' declares bulk operations but does not implement tap_item_bulk_operation",
+                self.name
+            );
+        }
+        for (operation, config) in &self.bulk_operations {
+            let valid_name = !operation.is_empty()
+                && operation
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid_name {
+                anyhow::bail!(
+                    "plugin '{}': bulk operation name '{}' must be lowercase letters, digits or '_'",
+                    self.name,
+                    operation
+                );
+            }
+            if config.access != "edit" && config.access != "delete" {
+                anyhow::bail!(
+                    "plugin '{}': bulk operation '{}' access must be edit or delete",
+                    self.name,
+                    operation
+                );
+            }
+        }
+
+        // Validate flags: machine names
+        for flag in self.flags.keys() {
+            if !crate::services::flag::is_valid_flag_name(flag) {
+                anyhow::bail!(
+                    "plugin '{}': flag name '{}' must be lowercase letters, digits or '_'",
+                    self.name,
+                    flag
+                );
+            }
+        }
+
+        // Validate webhooks: URL-safe names, known methods, and a receiver
+        if !self.webhooks.is_empty()
+            && !self
+                .taps
+                .implements
+                .iter()
+                .any(|t| t == "tap_webhook_receive")
+        {
+            anyhow::bail!(
+                "plugin '{}' declares webhooks but does not implement tap_webhook_receive",
+                self.name
+            );
+        }
+        for (hook, config) in &self.webhooks {
+            let valid_name = !hook.is_empty()
+                && hook
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+            if !valid_name {
+                anyhow::bail!(
+                    "plugin '{}': webhook name '{}' must be lowercase letters, digits, '_' or '-'",
+                    self.name,
+                    hook
+                );
+            }
+            if config.methods.is_empty() || config.methods.iter().any(|m| m != "GET" && m != "POST")
+            {
+                anyhow::bail!(
+                    "plugin '{}': webhook '{}' methods must be GET and/or POST",
+                    self.name,
+                    hook
+                );
+            }
+        }
+
         // Validate migration file paths: must be relative, no traversal, .sql only
         for file in &self.migrations.files {
             let p = Path::new(file);
@@ -455,6 +679,152 @@ files = ["migrations/001_create.txt"]
         assert!(result.unwrap_err().to_string().contains(".sql"));
     }
 
+    #[test]
+    fn parse_webhooks() {
+        let toml = r#"
+name = "argus"
+description = "News intelligence"
+version = "1.0.0"
+
+[taps]
+implements = ["tap_webhook_receive"]
+
+[webhooks.websub]
+header = "X-Hub-Signature"
+methods = ["GET", "POST"]
+
+[webhooks.ci]
+verify = "token"
+"#;
+
+        let info = PluginInfo::parse_str(toml, Path::new("test.toml")).unwrap();
+        let websub = &info.webhooks["websub"];
+        assert_eq!(websub.verify, WebhookVerify::Signature);
+        assert_eq!(websub.header.as_deref(), Some("X-Hub-Signature"));
+        assert_eq!(websub.methods, vec!["GET", "POST"]);
+        let ci = &info.webhooks["ci"];
+        assert_eq!(ci.verify, WebhookVerify::Token);
+        assert_eq!(ci.methods, vec!["POST"]);
+    }
+
+    #[test]
+    fn parse_capabilities() {
+        let minimal = r#"
+name = "blog"
+description = "Blog"
+version = "1.0.0"
+"#;
+        let info = PluginInfo::parse_str(minimal, Path::new("test.toml")).unwrap();
+        assert_eq!(info.capabilities, PluginCapabilities::default());
+        assert!(!info.capabilities.raw_sql);
+
+        let toml = format!("{minimal}\n[capabilities]\nraw_sql = true\nhttp = false\n");
+        let info = PluginInfo::parse_str(&toml, Path::new("test.toml")).unwrap();
+        assert!(info.capabilities.raw_sql);
+        assert!(!info.capabilities.http);
+        assert!(info.capabilities.cache);
+    }
+
+    #[test]
+    fn reject_webhooks_without_receiver() {
+        let toml = r#"
+name = "bad"
+description = "No receiver"
+version = "1.0.0"
+
+[webhooks.ci]
+"#;
+
+        let result = PluginInfo::parse_str(toml, Path::new("test.toml"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("tap_webhook_receive")
+        );
+    }
+
+    #[test]
+    fn parse_bulk_operations() {
+        let toml = r#"
+name = "archive"
+description = "Archiving"
+version = "1.0.0"
+
+[taps]
+implements = ["tap_item_bulk_operation"]
+
+[bulk_operations.archive]
+label = "Archive"
+
+[bulk_operations.purge]
+label = "Purge"
+access = "delete"
+"#;
+
+        let info = PluginInfo::parse_str(toml, Path::new("test.toml")).unwrap();
+        assert_eq!(info.bulk_operations["archive"].label, "Archive");
+        assert_eq!(info.bulk_operations["archive"].access, "edit");
+        assert_eq!(info.bulk_operations["purge"].access, "delete");
+
+        let without_tap = toml.replace("tap_item_bulk_operation", "tap_item_update");
+        let result = PluginInfo::parse_str(&without_tap, Path::new("test.toml"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("tap_item_bulk_operation")
+        );
+
+        let bad_access = toml.replace("\"delete\"", "\"view\"");
+        let result = PluginInfo::parse_str(&bad_access, Path::new("test.toml"));
+        assert!(result.unwrap_err().to_string().contains("edit or delete"));
+    }
+
+    #[test]
+    fn parse_flags() {
+        use crate::services::flag::FlagScope;
+
+        let toml = r#"
+name = "reading"
+description = "Reading lists"
+version = "1.0.0"
+
+[flags.read_later]
+label = "Read later"
+
+[flags.editors_pick]
+label = "Editors' pick"
+scope = "global"
+"#;
+
+        let info = PluginInfo::parse_str(toml, Path::new("test.toml")).unwrap();
+        assert_eq!(info.flags["read_later"].scope, FlagScope::User);
+        assert_eq!(info.flags["editors_pick"].scope, FlagScope::Global);
+
+        let bad_name = toml.replace("read_later", "Read-Later");
+        let result = PluginInfo::parse_str(&bad_name, Path::new("test.toml"));
+        assert!(result.unwrap_err().to_string().contains("flag name"));
+    }
+
+    #[test]
+    fn reject_invalid_webhook_method() {
+        let toml = r#"
+name = "bad"
+description = "Bad method"
+version = "1.0.0"
+
+[taps]
+implements = ["tap_webhook_receive"]
+
+[webhooks.ci]
+methods = ["DELETE"]
+"#;
+
+        let result = PluginInfo::parse_str(toml, Path::new("test.toml"));
+        assert!(result.unwrap_err().to_string().contains("GET and/or POST"));
+    }
+
     #[test]
     fn parse_default_enabled_false() {
         let toml = r#"
@@ -468,6 +838,25 @@ default_enabled = false
         assert!(!info.default_enabled);
     }
 
+    #[test]
+    fn parse_tap_memoize_option() {
+        let toml = r#"
+name = "random_blocks"
+description = "Random blocks"
+version = "1.0.0"
+
+[taps]
+implements = ["tap_block_render", "tap_menu"]
+
+[taps.options.tap_block_render]
+memoize = false
+"#;
+
+        let info = PluginInfo::parse_str(toml, Path::new("test.toml")).unwrap();
+        assert!(!info.taps.memoize("tap_block_render"));
+        assert!(info.taps.memoize("tap_menu"));
+    }
+
     #[test]
     fn default_enabled_is_true_when_omitted() {
         let toml = r#"
@@ -571,6 +960,10 @@ api_version = "1.2.3"
             dependencies: vec![],
             taps: super::TapConfig::default(),
             migrations: super::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
+            flags: HashMap::new(),
         }
     }
 }
@@ -277,6 +277,7 @@ pub async fn run_all_pending_migrations(
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::plugin::PluginCapabilities;
     use crate::plugin::info_parser::{MigrationConfig, TapConfig};
 
     fn make_plugin(name: &str, deps: Vec<&str>, migration_deps: Vec<&str>) -> PluginInfo {
@@ -292,6 +293,10 @@ mod tests {
                 files: Vec::new(),
                 depends_on: migration_deps.into_iter().map(String::from).collect(),
             },
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
+            flags: HashMap::new(),
         }
     }
 
@@ -2,25 +2,33 @@
 //!
 //! This module handles:
 //! - Parsing plugin metadata from `.info.toml` files
-//! - Loading and compiling WASM plugins
+//! - Loading and compiling WASM plugins, with an on-disk module cache
 //! - Managing plugin dependencies
+//! - Per-plugin host function capabilities
 //! - Providing the runtime environment for plugin execution
 //! - Plugin status tracking (enable/disable)
 //! - Plugin-declared SQL migrations
 //! - CLI commands for plugin management
 
+mod capability;
 pub mod cli;
 mod dependency;
 mod error;
 pub mod gate;
 mod info_parser;
 pub mod migration;
+mod module_cache;
 pub mod runtime;
 pub mod status;
 
+pub use capability::{Capability, PluginCapabilities, check_imports};
 pub use dependency::{check_dependencies, resolve_load_order};
 pub use error::PluginError;
-pub use info_parser::{KNOWN_TAPS, MigrationConfig, PluginInfo, TapConfig, TapOptions};
+pub use info_parser::{
+    BulkOperationConfig, KNOWN_TAPS, MigrationConfig, PluginInfo, TapConfig, TapOptions,
+    WebhookConfig, WebhookVerify,
+};
+pub use module_cache::ModuleSource;
 pub(crate) use runtime::WasmtimeExt;
 pub use runtime::{CompiledPlugin, PluginConfig, PluginLoadError, PluginRuntime, PluginState};
 
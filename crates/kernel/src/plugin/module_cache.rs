@@ -0,0 +1,298 @@
+//! On-disk cache of compiled plugin modules.
+//!
+//! Compiling plugin WASM with Cranelift dominates cold starts. The cache
+//! keeps each compiled module ([`Module::serialize`]) under a key derived
+//! from the WASM bytes and the engine's compilation settings, so a restart
+//! with unchanged plugins loads native code instead of recompiling. A
+//! changed plugin, wasmtime upgrade or engine config change yields a new
+//! key; the plugin's older entries are removed when the new one is written.
+//!
+//! Entries start with the SHA-256 of the serialized module, checked before
+//! deserializing so truncated or corrupted files are recompiled.
+
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+use wasmtime::{Engine, Module};
+
+use super::WasmtimeExt;
+
+/// File extension of cache entries.
+const EXTENSION: &str = "cwasm";
+
+/// Length of the checksum heading each entry.
+const CHECKSUM_LEN: usize = 32;
+
+/// How a plugin's module was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleSource {
+    /// Compiled from WASM.
+    Compiled,
+    /// Loaded from the module cache.
+    Cached,
+}
+
+impl ModuleSource {
+    /// Label used in logs and metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Compiled => "compiled",
+            Self::Cached => "cached",
+        }
+    }
+}
+
+/// A loaded module with how it was obtained and how long that took.
+pub struct LoadedModule {
+    /// The compiled module.
+    pub module: Module,
+    /// Whether it was compiled from WASM or loaded from the cache.
+    pub source: ModuleSource,
+    /// Time spent compiling or deserializing it.
+    pub elapsed: Duration,
+}
+
+/// Compiled module cache in a directory.
+#[derive(Debug, Clone)]
+pub struct ModuleCache {
+    dir: PathBuf,
+    /// Hash of the engine settings affecting compiled code.
+    engine_hash: [u8; 32],
+}
+
+impl ModuleCache {
+    /// Open (creating if needed) the cache directory for `engine`.
+    pub fn new(dir: &Path, engine: &Engine) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| {
+            format!("failed to create plugin cache directory {}", dir.display())
+        })?;
+
+        let mut hasher = Sha256Hasher(Sha256::new());
+        engine.precompile_compatibility_hash().hash(&mut hasher);
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            engine_hash: hasher.0.finalize().into(),
+        })
+    }
+
+    /// Cache key for a plugin's WASM bytes.
+    fn key(&self, wasm: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.engine_hash);
+        hasher.update(wasm);
+        hex::encode(hasher.finalize())
+    }
+
+    fn entry_path(&self, plugin: &str, key: &str) -> PathBuf {
+        self.dir.join(format!("{plugin}-{key}.{EXTENSION}"))
+    }
+
+    /// Load a plugin's module from the cache, or compile it and store it.
+    ///
+    /// Cache read and write failures are logged and fall back to compiling;
+    /// only a compile error fails.
+    pub fn load_or_compile(
+        &self,
+        engine: &Engine,
+        plugin: &str,
+        wasm: &[u8],
+    ) -> Result<LoadedModule> {
+        let start = Instant::now();
+        let key = self.key(wasm);
+        let path = self.entry_path(plugin, &key);
+
+        if let Some(module) = self.read(engine, plugin, &path) {
+            return Ok(LoadedModule {
+                module,
+                source: ModuleSource::Cached,
+                elapsed: start.elapsed(),
+            });
+        }
+
+        let loaded = compile(engine, plugin, wasm)?;
+        match self.write(&path, &loaded.module) {
+            Ok(()) => self.prune(plugin, &key),
+            Err(e) => warn!(
+                plugin = %plugin,
+                error = %e,
+                "failed to cache compiled plugin module"
+            ),
+        }
+        Ok(loaded)
+    }
+
+    /// Read a cache entry, removing it if it is corrupt or was built for
+    /// another engine.
+    fn read(&self, engine: &Engine, plugin: &str, path: &Path) -> Option<Module> {
+        let bytes = std::fs::read(path).ok()?;
+        let Some(serialized) = unframe(&bytes) else {
+            warn!(
+                plugin = %plugin,
+                path = %path.display(),
+                "corrupt plugin cache entry, recompiling"
+            );
+            let _ = std::fs::remove_file(path);
+            return None;
+        };
+
+        // SAFETY: `Module::deserialize` trusts its input to be a module
+        // serialized by this wasmtime version. Entries are only written by
+        // `write` below into the kernel's own cache directory, and the
+        // checksum verified above rejects partial or altered files.
+        // Wasmtime itself rejects modules built for other engine settings.
+        match unsafe { Module::deserialize(engine, serialized) }.into_anyhow() {
+            Ok(module) => {
+                debug!(plugin = %plugin, "loaded plugin module from cache");
+                Some(module)
+            }
+            Err(e) => {
+                warn!(plugin = %plugin, error = %e, "unusable plugin cache entry, recompiling");
+                let _ = std::fs::remove_file(path);
+                None
+            }
+        }
+    }
+
+    /// Write a cache entry through a temporary file, so readers never see
+    /// a partial entry.
+    fn write(&self, path: &Path, module: &Module) -> Result<()> {
+        let serialized = module
+            .serialize()
+            .into_anyhow()
+            .context("failed to serialize module")?;
+        let tmp = path.with_extension(format!("{EXTENSION}.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, frame(&serialized))
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Remove a plugin's entries other than `keep_key`.
+    fn prune(&self, plugin: &str, keep_key: &str) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name();
+            let Some((name, key)) = file_name.to_str().and_then(parse_entry_name) else {
+                continue;
+            };
+            if name == plugin && key != keep_key {
+                debug!(plugin = %plugin, key = %key, "removing stale plugin cache entry");
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// Compile a plugin's module without the cache.
+pub fn compile(engine: &Engine, plugin: &str, wasm: &[u8]) -> Result<LoadedModule> {
+    let start = Instant::now();
+    let module = Module::new(engine, wasm)
+        .into_anyhow()
+        .with_context(|| format!("failed to compile WASM module for plugin '{plugin}'"))?;
+    Ok(LoadedModule {
+        module,
+        source: ModuleSource::Compiled,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Prefix serialized module bytes with their checksum.
+fn frame(serialized: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(CHECKSUM_LEN + serialized.len());
+    bytes.extend_from_slice(&Sha256::digest(serialized));
+    bytes.extend_from_slice(serialized);
+    bytes
+}
+
+/// The serialized module of an entry, if its checksum matches.
+fn unframe(bytes: &[u8]) -> Option<&[u8]> {
+    if bytes.len() <= CHECKSUM_LEN {
+        return None;
+    }
+    let (checksum, serialized) = bytes.split_at(CHECKSUM_LEN);
+    (Sha256::digest(serialized).as_slice() == checksum).then_some(serialized)
+}
+
+/// Split an entry file name into plugin name and key.
+fn parse_entry_name(file_name: &str) -> Option<(&str, &str)> {
+    file_name
+        .strip_suffix(&format!(".{EXTENSION}"))?
+        .rsplit_once('-')
+}
+
+/// Feeds `Hash` output into SHA-256, giving a key that is stable across
+/// builds (unlike `DefaultHasher`).
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        // Only the SHA-256 state is used.
+        0
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    const WAT: &str = r#"(module (func (export "answer") (result i32) i32.const 42))"#;
+
+    #[test]
+    fn entries_are_checksummed() {
+        let bytes = frame(b"module");
+        assert_eq!(unframe(&bytes), Some(&b"module"[..]));
+
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        assert_eq!(unframe(&corrupt), None);
+        assert_eq!(unframe(&bytes[..CHECKSUM_LEN]), None);
+    }
+
+    #[test]
+    fn entry_names_split_at_the_last_dash() {
+        assert_eq!(
+            parse_entry_name("my-plugin-abc123.cwasm"),
+            Some(("my-plugin", "abc123"))
+        );
+        assert_eq!(parse_entry_name("my-plugin-abc123.cwasm.42.tmp"), None);
+    }
+
+    #[test]
+    fn second_load_comes_from_the_cache() {
+        let dir =
+            std::env::temp_dir().join(format!("trovato-module-cache-{}", uuid::Uuid::now_v7()));
+        let engine = Engine::default();
+        let cache = ModuleCache::new(&dir, &engine).unwrap();
+
+        let first = cache
+            .load_or_compile(&engine, "answer", WAT.as_bytes())
+            .unwrap();
+        assert_eq!(first.source, ModuleSource::Compiled);
+        let second = cache
+            .load_or_compile(&engine, "answer", WAT.as_bytes())
+            .unwrap();
+        assert_eq!(second.source, ModuleSource::Cached);
+        assert!(second.module.get_export("answer").is_some());
+
+        // A changed plugin gets a new entry and the old one is removed.
+        let changed = WAT.replace("42", "43");
+        let third = cache
+            .load_or_compile(&engine, "answer", changed.as_bytes())
+            .unwrap();
+        assert_eq!(third.source, ModuleSource::Compiled);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
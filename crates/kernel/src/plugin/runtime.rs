@@ -2,17 +2,26 @@
 //!
 //! Manages the Wasmtime engine, linker, and compiled plugin modules.
 //! Uses a pooling allocator for efficient per-request instantiation (~5µs).
+//!
+//! Compiled modules are kept in an on-disk [`ModuleCache`] so restarts
+//! skip recompiling unchanged plugins. Each plugin is pre-linked
+//! ([`InstancePre`]) on its first tap call and reused for later calls.
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
+use super::capability::{self, PluginCapabilities};
 use super::info_parser::PluginInfo;
-use crate::tap::RequestState;
+use super::module_cache::{self, LoadedModule, ModuleCache, ModuleSource};
+use crate::host::RenderBuffers;
+use crate::tap::{RequestState, TapDispatcher};
 use anyhow::{Context, Result};
 use tracing::{debug, info, warn};
 use wasmtime::{
-    Config, Engine, InstanceAllocationStrategy, Linker, Module, PoolingAllocationConfig,
+    Config, Engine, InstanceAllocationStrategy, InstancePre, Linker, Module,
+    PoolingAllocationConfig,
 };
 
 /// Extension trait to convert `Result<T, wasmtime::Error>` to `anyhow::Result<T>`.
@@ -37,6 +46,13 @@ pub struct PluginState {
     pub request: RequestState,
     /// Plugin name (used to namespace per-plugin context keys).
     pub plugin_name: String,
+    /// Render output built through the render host functions.
+    pub render: RenderBuffers,
+    /// Dispatcher for events the plugin dispatches (None outside tap calls).
+    pub dispatcher: Option<TapDispatcher>,
+    /// Database transaction opened by the plugin through the `begin` host
+    /// function, rolled back if the tap ends without committing it.
+    pub db_transaction: Option<sqlx::Transaction<'static, sqlx::Postgres>>,
 }
 
 impl PluginState {
@@ -45,8 +61,17 @@ impl PluginState {
         Self {
             request,
             plugin_name,
+            render: RenderBuffers::default(),
+            dispatcher: None,
+            db_transaction: None,
         }
     }
+
+    /// Let the plugin dispatch events through `dispatcher`.
+    pub fn with_dispatcher(mut self, dispatcher: TapDispatcher) -> Self {
+        self.dispatcher = Some(dispatcher);
+        self
+    }
 }
 
 /// Configuration for the plugin runtime.
@@ -56,6 +81,12 @@ pub struct PluginConfig {
     pub max_instances: u32,
     /// Maximum memory pages per instance (64KB per page).
     pub max_memory_pages: u64,
+    /// Idle instance slots the pooling allocator keeps warm for reuse by
+    /// the module that last used them.
+    pub warm_slots: u32,
+    /// Directory for compiled modules; `None` compiles every plugin at
+    /// each start.
+    pub cache_dir: Option<PathBuf>,
 }
 
 impl Default for PluginConfig {
@@ -63,6 +94,8 @@ impl Default for PluginConfig {
         Self {
             max_instances: 1000,
             max_memory_pages: 1024, // 64MB max per instance
+            warm_slots: 100,
+            cache_dir: None,
         }
     }
 }
@@ -76,6 +109,10 @@ pub struct CompiledPlugin {
     pub module: Module,
     /// Modification time of the `.wasm` file at load time.
     pub mtime: Option<std::time::SystemTime>,
+    /// Whether the module was compiled or loaded from the module cache.
+    pub source: ModuleSource,
+    /// Time spent compiling or loading the module.
+    pub load_time: Duration,
 }
 
 /// A plugin that failed to load.
@@ -91,10 +128,15 @@ pub struct PluginLoadError {
 pub struct PluginRuntime {
     /// Wasmtime engine with pooling allocator.
     engine: Engine,
-    /// Linker with host function bindings and WASI support.
-    linker: Linker<PluginState>,
+    /// Linkers with WASI support and the host functions of each capability
+    /// set in use, so plugins only get the functions they were granted.
+    linkers: HashMap<PluginCapabilities, Linker<PluginState>>,
     /// Compiled plugins indexed by name.
     plugins: HashMap<String, Arc<CompiledPlugin>>,
+    /// Pre-linked plugins, created on each plugin's first tap call.
+    instance_pres: parking_lot::RwLock<HashMap<String, InstancePre<PluginState>>>,
+    /// Compiled module cache (None when disabled or unusable).
+    module_cache: Option<ModuleCache>,
     /// Plugins that failed to load (for admin UI visibility).
     load_errors: Vec<PluginLoadError>,
 }
@@ -103,7 +145,14 @@ impl PluginRuntime {
     /// Create a new plugin runtime with the given configuration.
     pub fn new(config: &PluginConfig) -> Result<Self> {
         let engine = create_engine(config)?;
-        let linker = create_linker(&engine)?;
+        let default_capabilities = PluginCapabilities::default();
+        let linker = create_linker(&engine, &default_capabilities)?;
+
+        let module_cache = config.cache_dir.as_deref().and_then(|dir| {
+            ModuleCache::new(dir, &engine)
+                .inspect_err(|e| warn!(error = %e, "plugin module cache disabled"))
+                .ok()
+        });
 
         // Spawn background thread to increment the engine epoch once per second.
         // This drives epoch-based interruption: plugins with a deadline of N
@@ -121,8 +170,10 @@ impl PluginRuntime {
 
         Ok(Self {
             engine,
-            linker,
+            linkers: HashMap::from([(default_capabilities, linker)]),
             plugins: HashMap::new(),
+            instance_pres: parking_lot::RwLock::new(HashMap::new()),
+            module_cache,
             load_errors: Vec::new(),
         })
     }
@@ -132,9 +183,50 @@ impl PluginRuntime {
         &self.engine
     }
 
-    /// Get the linker with host functions and WASI support.
-    pub fn linker(&self) -> &Linker<PluginState> {
-        &self.linker
+    /// Get the linker for a loaded plugin, holding WASI support and the
+    /// host functions its capabilities grant.
+    pub fn linker_for(&self, plugin: &CompiledPlugin) -> Option<&Linker<PluginState>> {
+        self.linkers.get(&plugin.info.capabilities)
+    }
+
+    /// Get a loaded plugin linked against its host functions, ready to
+    /// instantiate into a store.
+    ///
+    /// Import resolution is done once, on the plugin's first call, and
+    /// reused afterwards.
+    pub fn instance_pre(&self, plugin: &CompiledPlugin) -> Result<InstancePre<PluginState>> {
+        if let Some(pre) = self.instance_pres.read().get(&plugin.info.name) {
+            return Ok(pre.clone());
+        }
+
+        let linker = self
+            .linker_for(plugin)
+            .with_context(|| format!("no linker for plugin '{}'", plugin.info.name))?;
+        let pre = linker
+            .instantiate_pre(&plugin.module)
+            .into_anyhow()
+            .with_context(|| format!("failed to link plugin '{}'", plugin.info.name))?;
+        debug!(plugin = %plugin.info.name, "linked plugin");
+
+        Ok(self
+            .instance_pres
+            .write()
+            .entry(plugin.info.name.clone())
+            .or_insert(pre)
+            .clone())
+    }
+
+    /// Store a compiled plugin, creating the linker for its capabilities
+    /// if no loaded plugin shares them yet.
+    fn insert_plugin(&mut self, name: String, compiled: Arc<CompiledPlugin>) -> Result<()> {
+        let capabilities = compiled.info.capabilities;
+        if !self.linkers.contains_key(&capabilities) {
+            let linker = create_linker(&self.engine, &capabilities)?;
+            self.linkers.insert(capabilities, linker);
+        }
+        self.instance_pres.get_mut().remove(&name);
+        self.plugins.insert(name, compiled);
+        Ok(())
     }
 
     /// Load all plugins from a directory.
@@ -226,26 +318,32 @@ impl PluginRuntime {
             .ok()
             .and_then(|m| m.modified().ok());
 
-        let module = Module::new(&self.engine, &wasm_bytes)
-            .into_anyhow()
-            .with_context(|| format!("failed to compile WASM module for plugin '{plugin_name}'"))?;
+        let loaded = load_module(
+            &self.engine,
+            self.module_cache.as_ref(),
+            &plugin_name,
+            &wasm_bytes,
+        )?;
+        capability::check_imports(&plugin_name, &loaded.module, &info.capabilities)?;
 
         debug!(
             plugin = %plugin_name,
             taps = ?info.taps.implements,
-            "compiled plugin"
+            capabilities = ?info.capabilities.granted(),
+            source = loaded.source.as_str(),
+            "loaded plugin module"
         );
 
-        self.plugins.insert(
-            plugin_name.clone(),
+        self.insert_plugin(
+            plugin_name,
             Arc::new(CompiledPlugin {
                 info,
-                module,
+                module: loaded.module,
                 mtime,
+                source: loaded.source,
+                load_time: loaded.elapsed,
             }),
-        );
-
-        Ok(())
+        )
     }
 
     /// Get a compiled plugin by name.
@@ -421,15 +519,18 @@ impl PluginRuntime {
         }
 
         // Phase 2: Compile WASM modules concurrently using blocking tasks.
-        // Module::new() is CPU-bound so we use spawn_blocking for each plugin.
+        // Module::new() is CPU-bound so we use spawn_blocking for each plugin;
+        // cached modules skip compilation but still read and map a file.
         let engine = self.engine.clone();
         let mut compile_handles = Vec::new();
 
         for plugin_dir in dirs_to_load {
             let engine = engine.clone();
+            let cache = self.module_cache.clone();
             let dir = plugin_dir.clone();
-            let handle =
-                tokio::task::spawn_blocking(move || compile_plugin_from_dir(&engine, &dir));
+            let handle = tokio::task::spawn_blocking(move || {
+                compile_plugin_from_dir(&engine, cache.as_ref(), &dir)
+            });
             compile_handles.push((plugin_dir, handle));
         }
 
@@ -437,7 +538,14 @@ impl PluginRuntime {
         for (plugin_dir, handle) in compile_handles {
             match handle.await {
                 Ok(Ok((name, compiled))) => {
-                    self.plugins.insert(name, compiled);
+                    if let Err(e) = self.insert_plugin(name, compiled) {
+                        let dir_str = plugin_dir.display().to_string();
+                        warn!(plugin_dir = %dir_str, error = %e, "failed to link plugin, skipping");
+                        self.load_errors.push(PluginLoadError {
+                            plugin: dir_str,
+                            error: format!("{e:#}"),
+                        });
+                    }
                 }
                 Ok(Err(e)) => {
                     let dir_str = plugin_dir.display().to_string();
@@ -458,21 +566,50 @@ impl PluginRuntime {
             }
         }
 
+        let (compiled, cached): (Vec<_>, Vec<_>) = self
+            .plugins
+            .values()
+            .partition(|p| p.source == ModuleSource::Compiled);
+        let total_ms = |plugins: &[&Arc<CompiledPlugin>]| {
+            plugins
+                .iter()
+                .map(|p| p.load_time)
+                .sum::<Duration>()
+                .as_millis()
+        };
         info!(
             loaded = self.plugins.len(),
             failed = self.load_errors.len(),
+            compiled = compiled.len(),
+            compile_ms = total_ms(&compiled),
+            cached = cached.len(),
+            cache_load_ms = total_ms(&cached),
             "loaded enabled plugins"
         );
         Ok(())
     }
 }
 
+/// Load a plugin's module through the cache when one is configured.
+fn load_module(
+    engine: &Engine,
+    cache: Option<&ModuleCache>,
+    plugin_name: &str,
+    wasm_bytes: &[u8],
+) -> Result<LoadedModule> {
+    match cache {
+        Some(cache) => cache.load_or_compile(engine, plugin_name, wasm_bytes),
+        None => module_cache::compile(engine, plugin_name, wasm_bytes),
+    }
+}
+
 /// Compile a single plugin from its directory (runs on a blocking thread).
 ///
-/// Reads the `.info.toml` and `.wasm` files, compiles the module, and
-/// returns the plugin name and compiled module.
+/// Reads the `.info.toml` and `.wasm` files, compiles the module (or loads
+/// it from `cache`), and returns the plugin name and compiled module.
 fn compile_plugin_from_dir(
     engine: &Engine,
+    cache: Option<&ModuleCache>,
     plugin_dir: &Path,
 ) -> Result<(String, Arc<CompiledPlugin>)> {
     // Find the .info.toml file
@@ -515,22 +652,26 @@ fn compile_plugin_from_dir(
         .ok()
         .and_then(|m| m.modified().ok());
 
-    let module = Module::new(engine, &wasm_bytes)
-        .into_anyhow()
-        .with_context(|| format!("failed to compile WASM module for plugin '{plugin_name}'"))?;
+    let loaded = load_module(engine, cache, &plugin_name, &wasm_bytes)?;
+    capability::check_imports(&plugin_name, &loaded.module, &info.capabilities)?;
 
     debug!(
         plugin = %plugin_name,
         taps = ?info.taps.implements,
-        "compiled plugin"
+        capabilities = ?info.capabilities.granted(),
+        source = loaded.source.as_str(),
+        elapsed_ms = loaded.elapsed.as_millis(),
+        "loaded plugin module"
     );
 
     Ok((
         plugin_name,
         Arc::new(CompiledPlugin {
             info,
-            module,
+            module: loaded.module,
             mtime,
+            source: loaded.source,
+            load_time: loaded.elapsed,
         }),
     ))
 }
@@ -548,6 +689,9 @@ fn create_engine(config: &PluginConfig) -> Result<Engine> {
     pooling_config.total_memories(config.max_instances);
     pooling_config.total_tables(config.max_instances);
     pooling_config.max_memory_size(config.max_memory_pages as usize * 65536);
+    // Keep recently used slots warm: an instance of the same plugin reuses
+    // its previous slot without re-initializing its memory from scratch.
+    pooling_config.max_unused_warm_slots(config.warm_slots);
 
     wasmtime_config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling_config));
 
@@ -567,15 +711,19 @@ fn create_engine(config: &PluginConfig) -> Result<Engine> {
         .context("failed to create wasmtime engine with pooling allocator")
 }
 
-/// Creates a Linker with host function bindings and WASI support.
-fn create_linker(engine: &Engine) -> Result<Linker<PluginState>> {
+/// Creates a Linker with WASI support and the host functions granted by
+/// `capabilities`.
+fn create_linker(
+    engine: &Engine,
+    capabilities: &PluginCapabilities,
+) -> Result<Linker<PluginState>> {
     let mut linker = Linker::new(engine);
 
     // Add minimal WASI stubs for wasi_snapshot_preview1
     add_wasi_stubs(&mut linker)?;
 
     // Add custom host functions
-    crate::host::register_all(&mut linker)?;
+    crate::host::register_all(&mut linker, capabilities)?;
 
     Ok(linker)
 }
@@ -685,6 +833,7 @@ mod tests {
         let config = PluginConfig {
             max_instances: 500,
             max_memory_pages: 512,
+            ..Default::default()
         };
         let runtime = PluginRuntime::new(&config);
         assert!(runtime.is_ok());
@@ -0,0 +1,90 @@
+//! Sampling CPU profiler and runtime snapshots (`profiling` feature).
+//!
+//! CPU profiles are captured with `pprof` and rendered as flamegraph SVGs.
+//! Only one profile runs at a time: the sampler is process-wide and
+//! overlapping captures would skew each other.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// Longest capture accepted, in seconds.
+pub const MAX_PROFILE_SECONDS: u64 = 60;
+
+/// Default sampling frequency in Hz.
+pub const DEFAULT_FREQUENCY: i32 = 99;
+
+/// Set while a capture is running.
+static PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// Exclusive right to run a CPU capture; released on drop.
+pub struct Capture {
+    _private: (),
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        PROFILING.store(false, Ordering::Release);
+    }
+}
+
+/// Reserve the profiler, or `None` if a capture is already running.
+pub fn begin_capture() -> Option<Capture> {
+    PROFILING
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .ok()
+        .map(|_| Capture { _private: () })
+}
+
+impl Capture {
+    /// Sample all threads for `duration` and return a flamegraph SVG.
+    pub async fn flamegraph(self, duration: Duration, frequency: i32) -> Result<Vec<u8>> {
+        // The profiler guard is not Send; keep it on one blocking thread.
+        tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+            let _capture = self;
+            let profiler = pprof::ProfilerGuardBuilder::default()
+                .frequency(frequency)
+                .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+                .build()
+                .context("failed to start CPU profiler")?;
+            std::thread::sleep(duration);
+            let report = profiler
+                .report()
+                .build()
+                .context("failed to build CPU profile report")?;
+            let mut svg = Vec::new();
+            report
+                .flamegraph(&mut svg)
+                .context("failed to render flamegraph")?;
+            Ok(svg)
+        })
+        .await
+        .context("CPU profiler task panicked")?
+    }
+}
+
+/// Point-in-time view of the tokio runtime.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeSnapshot {
+    /// Worker threads driving async tasks.
+    pub workers: usize,
+    /// Tasks currently alive (spawned and not yet completed).
+    pub alive_tasks: usize,
+    /// Tasks waiting in the global injection queue.
+    pub global_queue_depth: usize,
+}
+
+/// Snapshot the current tokio runtime.
+///
+/// Per-task dumps need a `tokio_unstable` build; use `tokio-console` for
+/// those.
+pub fn runtime_snapshot() -> RuntimeSnapshot {
+    let metrics = tokio::runtime::Handle::current().metrics();
+    RuntimeSnapshot {
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+    }
+}
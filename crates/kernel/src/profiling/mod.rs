@@ -0,0 +1,207 @@
+//! Request profiling.
+//!
+//! Per-request timing breakdown: when an admin sends the
+//! [`DEBUG_TIMING_HEADER`], the timing middleware opens a scope and the
+//! kernel's database, cache, tap, and render paths record their elapsed
+//! time into it with [`Timer`]. The totals are returned in a
+//! `Server-Timing` header. Recording outside a scope is a no-op, so the
+//! instrumented paths cost nothing for ordinary requests.
+//!
+//! CPU profiling and runtime snapshots live in [`cpu`], compiled only with
+//! the `profiling` feature.
+
+#[cfg(feature = "profiling")]
+pub mod cpu;
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Request header that asks for a timing breakdown (any value).
+pub const DEBUG_TIMING_HEADER: &str = "x-trovato-debug-timing";
+
+/// Permission required for timing breakdowns and profiling endpoints.
+pub const PROFILING_PERMISSION: &str = "view profiling data";
+
+/// A category of request work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Kernel database queries.
+    Db,
+    /// Cache lookups (L1 and Redis).
+    Cache,
+    /// Plugin tap invocations, including host calls they make.
+    Tap,
+    /// Template rendering.
+    Render,
+}
+
+impl Phase {
+    const ALL: [Phase; 4] = [Phase::Db, Phase::Cache, Phase::Tap, Phase::Render];
+
+    /// Metric name used in the `Server-Timing` header.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Db => "db",
+            Phase::Cache => "cache",
+            Phase::Tap => "tap",
+            Phase::Render => "render",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Accumulated time per phase for one request.
+#[derive(Debug, Default)]
+struct PhaseTimings {
+    elapsed: [Duration; 4],
+    /// Open timers per phase; only the outermost one records, so nested
+    /// instrumented calls (e.g. `render_item` → `render_element`) are not
+    /// counted twice.
+    depth: [u32; 4],
+}
+
+tokio::task_local! {
+    static TIMINGS: RefCell<PhaseTimings>;
+}
+
+/// Measures one phase until dropped.
+///
+/// ```ignore
+/// let _timer = profiling::Timer::start(Phase::Db);
+/// sqlx::query(...).fetch_one(pool).await?;
+/// ```
+#[must_use = "the timer records when dropped"]
+pub struct Timer {
+    phase: Phase,
+    start: Instant,
+    active: bool,
+}
+
+impl Timer {
+    /// Start timing `phase` in the current request scope, if any.
+    pub fn start(phase: Phase) -> Self {
+        let active = TIMINGS
+            .try_with(|t| t.borrow_mut().depth[phase.index()] += 1)
+            .is_ok();
+        Self {
+            phase,
+            start: Instant::now(),
+            active,
+        }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+        let elapsed = self.start.elapsed();
+        let i = self.phase.index();
+        // Err means the scope ended before the timer (e.g. moved to another task).
+        let _ = TIMINGS.try_with(|t| {
+            let mut t = t.borrow_mut();
+            t.depth[i] = t.depth[i].saturating_sub(1);
+            if t.depth[i] == 0 {
+                t.elapsed[i] += elapsed;
+            }
+        });
+    }
+}
+
+/// Phase totals collected for a request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimingBreakdown {
+    elapsed: [Duration; 4],
+}
+
+impl TimingBreakdown {
+    /// Time spent in `phase`.
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.elapsed[phase.index()]
+    }
+
+    /// Format as a `Server-Timing` header value, with `total` last.
+    pub fn server_timing(&self, total: Duration) -> String {
+        let mut parts: Vec<String> = Phase::ALL
+            .iter()
+            .map(|p| format!("{};dur={:.1}", p.as_str(), ms(self.get(*p))))
+            .collect();
+        parts.push(format!("total;dur={:.1}", ms(total)));
+        parts.join(", ")
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Run a future inside a timing scope, returning its output and the phase
+/// totals recorded while it ran.
+pub async fn collect_timings<F: Future>(fut: F) -> (F::Output, TimingBreakdown) {
+    TIMINGS
+        .scope(RefCell::new(PhaseTimings::default()), async {
+            let output = fut.await;
+            let elapsed = TIMINGS.with(|t| t.borrow().elapsed);
+            (output, TimingBreakdown { elapsed })
+        })
+        .await
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_phases_in_scope() {
+        let ((), timings) = collect_timings(async {
+            let _db = Timer::start(Phase::Db);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        })
+        .await;
+
+        assert!(timings.get(Phase::Db) >= Duration::from_millis(5));
+        assert_eq!(timings.get(Phase::Tap), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn nested_timers_count_once() {
+        let start = Instant::now();
+        let ((), timings) = collect_timings(async {
+            let _outer = Timer::start(Phase::Render);
+            {
+                let _inner = Timer::start(Phase::Render);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+
+        // Double counting would exceed the wall-clock time.
+        let render = timings.get(Phase::Render);
+        assert!(render >= Duration::from_millis(5));
+        assert!(render <= start.elapsed());
+    }
+
+    #[tokio::test]
+    async fn timer_outside_scope_is_noop() {
+        drop(Timer::start(Phase::Cache));
+        let ((), timings) = collect_timings(async {}).await;
+        assert_eq!(timings, TimingBreakdown::default());
+    }
+
+    #[test]
+    fn formats_server_timing_header() {
+        let mut timings = TimingBreakdown::default();
+        timings.elapsed[Phase::Db.index()] = Duration::from_micros(1500);
+        assert_eq!(
+            timings.server_timing(Duration::from_millis(3)),
+            "db;dur=1.5, cache;dur=0.0, tap;dur=0.0, render;dur=0.0, total;dur=3.0"
+        );
+    }
+}
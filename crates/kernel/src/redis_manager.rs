@@ -0,0 +1,536 @@
+//! Shared Redis connection manager.
+//!
+//! Cache, queue, cron lock, batch, lockout, rate limiting and OAuth token
+//! revocation all talk to Redis through one [`RedisManager`], so topology
+//! (standalone, Sentinel, Cluster), health checks, and reconnect backoff
+//! behave the same in every subsystem. Sessions use a separate `fred` pool
+//! built from the same [`RedisSettings`] (see [`RedisSettings::fred_config`]).
+//!
+//! The manager keeps one multiplexed connection. A command that fails with
+//! a connection-level error (I/O, refused, dropped, timeout, or `READONLY`
+//! after a Sentinel failover) discards it; the next caller reconnects, with
+//! exponential backoff while Redis stays unreachable. With Sentinel, each
+//! reconnect asks the sentinels for the current master.
+//!
+//! In Cluster mode, multi-key commands and scripts must only touch keys in
+//! one hash slot, and `SCAN` only covers the node it is routed to.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use redis::aio::{ConnectionLike, MultiplexedConnection};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::{SentinelClient, SentinelServerType};
+use redis::{
+    Cmd, ConnectionAddr, ErrorKind, IntoConnectionInfo, Pipeline, RedisError, RedisFuture,
+    RedisResult, Value,
+};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+/// Time allowed for establishing a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First reconnect delay after a failure.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Upper bound on the reconnect delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Redis deployment topology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisTopology {
+    /// A single Redis server.
+    Standalone,
+    /// Sentinel-managed primary with automatic failover.
+    Sentinel,
+    /// Redis Cluster.
+    Cluster,
+}
+
+impl FromStr for RedisTopology {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "standalone" => Ok(Self::Standalone),
+            "sentinel" => Ok(Self::Sentinel),
+            "cluster" => Ok(Self::Cluster),
+            other => bail!(
+                "unknown REDIS_TOPOLOGY '{other}' (expected standalone, sentinel, or cluster)"
+            ),
+        }
+    }
+}
+
+/// Redis connection settings.
+#[derive(Debug, Clone)]
+pub struct RedisSettings {
+    /// Deployment topology.
+    pub topology: RedisTopology,
+    /// Node URLs: the server (standalone), the sentinels, or cluster seed nodes.
+    pub nodes: Vec<String>,
+    /// Master name monitored by the sentinels (Sentinel only).
+    pub sentinel_master: Option<String>,
+}
+
+impl RedisSettings {
+    /// Settings for a single server.
+    pub fn standalone(url: &str) -> Self {
+        Self {
+            topology: RedisTopology::Standalone,
+            nodes: vec![url.to_string()],
+            sentinel_master: None,
+        }
+    }
+
+    /// Build settings from `REDIS_URL`, `REDIS_TOPOLOGY`, `REDIS_NODES` and
+    /// `REDIS_SENTINEL_MASTER`.
+    ///
+    /// `REDIS_NODES` defaults to `REDIS_URL` when empty.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Self::parse(
+            &config.redis_topology,
+            &config.redis_url,
+            &config.redis_nodes,
+            config.redis_sentinel_master.as_deref(),
+        )
+    }
+
+    fn parse(
+        topology: &str,
+        url: &str,
+        nodes: &[String],
+        sentinel_master: Option<&str>,
+    ) -> Result<Self> {
+        let topology: RedisTopology = topology.parse()?;
+        let nodes = if nodes.is_empty() {
+            vec![url.to_string()]
+        } else {
+            nodes.to_vec()
+        };
+        let sentinel_master = sentinel_master
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
+        match topology {
+            RedisTopology::Standalone if nodes.len() > 1 => {
+                bail!("REDIS_NODES lists several nodes but REDIS_TOPOLOGY is standalone")
+            }
+            RedisTopology::Sentinel if sentinel_master.is_none() => {
+                bail!("REDIS_SENTINEL_MASTER is required when REDIS_TOPOLOGY=sentinel")
+            }
+            _ => {}
+        }
+        for node in &nodes {
+            node.as_str()
+                .into_connection_info()
+                .with_context(|| format!("invalid Redis node URL '{node}'"))?;
+        }
+
+        Ok(Self {
+            topology,
+            nodes,
+            sentinel_master,
+        })
+    }
+
+    /// Host/port pairs of all nodes.
+    fn host_ports(&self) -> Result<Vec<(String, u16)>> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                let info = node
+                    .as_str()
+                    .into_connection_info()
+                    .with_context(|| format!("invalid Redis node URL '{node}'"))?;
+                match info.addr {
+                    ConnectionAddr::Tcp(host, port) | ConnectionAddr::TcpTls { host, port, .. } => {
+                        Ok((host, port))
+                    }
+                    _ => bail!("Redis node '{node}' must be a TCP address"),
+                }
+            })
+            .collect()
+    }
+
+    /// `fred` configuration for the session store, matching this topology.
+    pub fn fred_config(&self) -> Result<fred::prelude::Config> {
+        use fred::types::config::ServerConfig;
+
+        let mut config =
+            fred::prelude::Config::from_url(&self.nodes[0]).context("failed to parse Redis URL")?;
+        match self.topology {
+            RedisTopology::Standalone => {}
+            RedisTopology::Cluster => {
+                config.server = ServerConfig::new_clustered(self.host_ports()?);
+            }
+            RedisTopology::Sentinel => {
+                let master = self.sentinel_master.clone().unwrap_or_default();
+                config.server = ServerConfig::new_sentinel(self.host_ports()?, master);
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Client used to open new connections.
+enum Connector {
+    Standalone(redis::Client),
+    Sentinel(Box<SentinelClient>),
+    Cluster(ClusterClient),
+}
+
+impl Connector {
+    fn new(settings: &RedisSettings) -> RedisResult<Self> {
+        Ok(match settings.topology {
+            RedisTopology::Standalone => {
+                Self::Standalone(redis::Client::open(settings.nodes[0].as_str())?)
+            }
+            RedisTopology::Sentinel => Self::Sentinel(Box::new(SentinelClient::build(
+                settings.nodes.clone(),
+                settings.sentinel_master.clone().unwrap_or_default(),
+                None,
+                SentinelServerType::Master,
+            )?)),
+            RedisTopology::Cluster => Self::Cluster(
+                ClusterClient::builder(settings.nodes.clone())
+                    .connection_timeout(CONNECT_TIMEOUT)
+                    .build()?,
+            ),
+        })
+    }
+
+    async fn connect(&mut self) -> RedisResult<RedisConnection> {
+        Ok(match self {
+            Self::Standalone(client) => {
+                RedisConnection::Single(client.get_multiplexed_async_connection().await?)
+            }
+            Self::Sentinel(client) => RedisConnection::Single(client.get_async_connection().await?),
+            Self::Cluster(client) => RedisConnection::Cluster(client.get_async_connection().await?),
+        })
+    }
+}
+
+/// A raw connection for either topology.
+#[derive(Clone)]
+enum RedisConnection {
+    Single(MultiplexedConnection),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Self::Single(conn) => conn.req_packed_command(cmd),
+            Self::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Self::Single(conn) => conn.req_packed_commands(cmd, offset, count),
+            Self::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Self::Single(conn) => conn.get_db(),
+            Self::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+/// Mutable connection state, guarded by one lock so that concurrent
+/// callers wait for a single reconnect instead of stampeding.
+struct ConnState {
+    connector: Connector,
+    current: Option<(u64, RedisConnection)>,
+    generation: u64,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+struct RedisManagerInner {
+    settings: RedisSettings,
+    state: Mutex<ConnState>,
+}
+
+/// Shared, self-healing Redis connection manager.
+///
+/// Cheap to clone. Connections are opened lazily on first use.
+#[derive(Clone)]
+pub struct RedisManager {
+    inner: Arc<RedisManagerInner>,
+}
+
+impl RedisManager {
+    /// Create a manager. Does not connect.
+    pub fn new(settings: RedisSettings) -> RedisResult<Self> {
+        let connector = Connector::new(&settings)?;
+        Ok(Self {
+            inner: Arc::new(RedisManagerInner {
+                settings,
+                state: Mutex::new(ConnState {
+                    connector,
+                    current: None,
+                    generation: 0,
+                    failures: 0,
+                    retry_at: None,
+                }),
+            }),
+        })
+    }
+
+    /// Connection settings.
+    pub fn settings(&self) -> &RedisSettings {
+        &self.inner.settings
+    }
+
+    /// Get a connection, reconnecting if the previous one failed.
+    ///
+    /// While Redis is unreachable, calls within the backoff window fail
+    /// immediately rather than waiting on another connection attempt.
+    pub async fn get(&self) -> RedisResult<ManagedConnection> {
+        let mut state = self.inner.state.lock().await;
+        if let Some((generation, conn)) = &state.current {
+            return Ok(ManagedConnection {
+                conn: conn.clone(),
+                generation: *generation,
+                manager: self.clone(),
+            });
+        }
+
+        if let Some(retry_at) = state.retry_at
+            && Instant::now() < retry_at
+        {
+            return Err(RedisError::from((
+                ErrorKind::IoError,
+                "Redis unavailable",
+                "reconnect backoff in effect".to_string(),
+            )));
+        }
+
+        let result = tokio::time::timeout(CONNECT_TIMEOUT, state.connector.connect()).await;
+        let result = result.unwrap_or_else(|_| {
+            Err(RedisError::from((
+                ErrorKind::IoError,
+                "Redis connect timed out",
+            )))
+        });
+        match result {
+            Ok(conn) => {
+                if state.failures > 0 {
+                    info!(failures = state.failures, "Redis connection re-established");
+                }
+                state.generation += 1;
+                state.failures = 0;
+                state.retry_at = None;
+                let generation = state.generation;
+                state.current = Some((generation, conn.clone()));
+                Ok(ManagedConnection {
+                    conn,
+                    generation,
+                    manager: self.clone(),
+                })
+            }
+            Err(e) => {
+                state.failures = state.failures.saturating_add(1);
+                let delay = backoff_delay(state.failures);
+                state.retry_at = Some(Instant::now() + delay);
+                warn!(
+                    error = %e,
+                    failures = state.failures,
+                    retry_in_ms = delay.as_millis() as u64,
+                    "failed to connect to Redis"
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Check connectivity with `PING`.
+    ///
+    /// A failed ping discards the current connection, so this doubles as
+    /// the periodic health check that triggers reconnects when idle.
+    pub async fn ping(&self) -> bool {
+        let Ok(mut conn) = self.get().await else {
+            return false;
+        };
+        redis::cmd("PING")
+            .query_async::<String>(&mut conn)
+            .await
+            .is_ok()
+    }
+
+    /// Discard the connection from `generation` if it is still current.
+    async fn invalidate(&self, generation: u64, error: &RedisError) {
+        let mut state = self.inner.state.lock().await;
+        if matches!(&state.current, Some((current, _)) if *current == generation) {
+            warn!(error = %error, "Redis connection lost; reconnecting on next use");
+            state.current = None;
+        }
+    }
+}
+
+/// A connection handed out by [`RedisManager::get`].
+///
+/// Implements [`ConnectionLike`], so it works with `AsyncCommands`, scripts
+/// and pipelines. Connection-level errors are reported back to the manager.
+pub struct ManagedConnection {
+    conn: RedisConnection,
+    generation: u64,
+    manager: RedisManager,
+}
+
+impl ManagedConnection {
+    async fn observe<T>(&self, result: RedisResult<T>) -> RedisResult<T> {
+        if let Err(e) = &result
+            && needs_reconnect(e)
+        {
+            self.manager.invalidate(self.generation, e).await;
+        }
+        result
+    }
+}
+
+impl ConnectionLike for ManagedConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let result = self.conn.req_packed_command(cmd).await;
+            self.observe(result).await
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let result = self.conn.req_packed_commands(cmd, offset, count).await;
+            self.observe(result).await
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.conn.get_db()
+    }
+}
+
+/// Whether an error means the connection itself is unusable.
+///
+/// `READONLY` comes from a demoted master after a Sentinel failover.
+fn needs_reconnect(error: &RedisError) -> bool {
+    error.is_io_error()
+        || error.is_connection_dropped()
+        || error.is_connection_refusal()
+        || error.is_timeout()
+        || error.kind() == ErrorKind::ReadOnly
+}
+
+/// Exponential backoff for the given number of consecutive failures.
+fn backoff_delay(failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    INITIAL_BACKOFF
+        .saturating_mul(1u32 << exponent)
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topology_parses_case_insensitively() {
+        assert_eq!(
+            "".parse::<RedisTopology>().unwrap(),
+            RedisTopology::Standalone
+        );
+        assert_eq!(
+            "Sentinel".parse::<RedisTopology>().unwrap(),
+            RedisTopology::Sentinel
+        );
+        assert_eq!(
+            "cluster".parse::<RedisTopology>().unwrap(),
+            RedisTopology::Cluster
+        );
+        assert!("ring".parse::<RedisTopology>().is_err());
+    }
+
+    #[test]
+    fn nodes_default_to_redis_url() {
+        let settings =
+            RedisSettings::parse("standalone", "redis://127.0.0.1:6379", &[], None).unwrap();
+        assert_eq!(settings.nodes, vec!["redis://127.0.0.1:6379"]);
+    }
+
+    #[test]
+    fn sentinel_requires_master_name() {
+        let nodes = vec!["redis://10.0.0.1:26379".to_string()];
+        assert!(RedisSettings::parse("sentinel", "", &nodes, None).is_err());
+        assert!(RedisSettings::parse("sentinel", "", &nodes, Some(" ")).is_err());
+
+        let settings = RedisSettings::parse("sentinel", "", &nodes, Some("mymaster")).unwrap();
+        assert_eq!(settings.sentinel_master.as_deref(), Some("mymaster"));
+        assert_eq!(
+            settings.host_ports().unwrap(),
+            vec![("10.0.0.1".to_string(), 26379)]
+        );
+    }
+
+    #[test]
+    fn invalid_node_urls_are_rejected() {
+        let nodes = vec!["redis://a:7000".to_string(), "not a url".to_string()];
+        assert!(RedisSettings::parse("cluster", "", &nodes, None).is_err());
+        assert!(
+            RedisSettings::parse(
+                "standalone",
+                "",
+                &["redis://a:1".to_string(), "redis://b:1".to_string()],
+                None
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn backoff_grows_and_caps() {
+        assert_eq!(backoff_delay(1), INITIAL_BACKOFF);
+        assert_eq!(backoff_delay(2), INITIAL_BACKOFF * 2);
+        assert_eq!(backoff_delay(3), INITIAL_BACKOFF * 4);
+        assert_eq!(backoff_delay(50), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn connection_errors_trigger_reconnect() {
+        let io = RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert!(needs_reconnect(&io));
+        let readonly = RedisError::from((ErrorKind::ReadOnly, "READONLY"));
+        assert!(needs_reconnect(&readonly));
+        let type_error = RedisError::from((ErrorKind::TypeError, "WRONGTYPE"));
+        assert!(!needs_reconnect(&type_error));
+    }
+
+    #[tokio::test]
+    async fn manager_is_lazy() {
+        // Creating a manager must not require a reachable server.
+        let manager = RedisManager::new(RedisSettings::standalone("redis://127.0.0.1:1")).unwrap();
+        assert_eq!(manager.settings().topology, RedisTopology::Standalone);
+    }
+}
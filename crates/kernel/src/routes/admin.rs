@@ -1,18 +1,24 @@
 //! Core admin routes: dashboard, stage management, file management,
 //! comment moderation, and AJAX callbacks.
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
 use axum::{Form, Json, Router};
 use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
+use uuid::Uuid;
 
 use crate::file::service::FileStatus;
 use crate::form::AjaxRequest;
 use crate::models::UpdateComment;
+use crate::models::stage::LIVE_STAGE_ID;
 use crate::routes::auth::SESSION_ACTIVE_STAGE;
+use crate::services::comment_spam::{self, CommentSpamSettings};
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::services::workspace::{self, WorkspaceSettings};
+use crate::stage::{StageManifest, StagePublishSchedule};
 use crate::state::AppState;
 
 use crate::form::csrf::generate_csrf_token;
@@ -86,6 +92,224 @@ async fn get_current_stage(
     }))
 }
 
+/// Get the personal workspace policy.
+///
+/// GET /admin/stage/workspaces/settings
+async fn get_workspace_settings(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<WorkspaceSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let settings = workspace::settings(state.db())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load workspace settings"))?;
+
+    Ok(Json(settings))
+}
+
+/// Replace the personal workspace policy.
+///
+/// POST /admin/stage/workspaces/settings
+async fn update_workspace_settings(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(settings): Json<WorkspaceSettings>,
+) -> Result<Json<WorkspaceSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+    super::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    settings.validate().map_err(|e| {
+        AppError::validation(vec![AppError::field_error("settings", "out_of_range", e)])
+    })?;
+
+    workspace::save_settings(state.db(), &settings)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save workspace settings"))?;
+
+    Ok(Json(settings))
+}
+
+/// Stage change manifest query parameters.
+#[derive(Debug, Deserialize)]
+struct StageChangesQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+/// Stage change manifest response.
+#[derive(Debug, Serialize)]
+struct StageChangesResponse {
+    page: i64,
+    per_page: i64,
+    #[serde(flatten)]
+    manifest: StageManifest,
+}
+
+/// Itemize what publishing a stage would change.
+///
+/// Each list (items, config, aliases, menu links) holds one page; `counts`
+/// has the totals.
+///
+/// GET /admin/stage/{stage_id}/changes?page=1&per_page=25
+async fn stage_changes(
+    State(state): State<AppState>,
+    session: Session,
+    Path(stage_id): Path<Uuid>,
+    Query(query): Query<StageChangesQuery>,
+) -> Result<Json<StageChangesResponse>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    if stage_id == LIVE_STAGE_ID {
+        return Err(AppError::bad_request(
+            "The live stage has nothing to publish",
+        ));
+    }
+    state
+        .stage()
+        .get_stage(stage_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load stage"))?
+        .ok_or_else(|| AppError::not_found_id("stage", stage_id))?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, query.per_page)
+        .limit;
+    let manifest = state
+        .stage()
+        .change_manifest(stage_id, per_page, (page - 1) * per_page)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load stage changes"))?;
+
+    Ok(Json(StageChangesResponse {
+        page,
+        per_page,
+        manifest,
+    }))
+}
+
+/// Stage publish schedule request.
+#[derive(Debug, Deserialize)]
+struct SchedulePublishRequest {
+    /// Unix timestamp to publish at; must be in the future.
+    publish_at: i64,
+}
+
+/// List all scheduled stage publishes, soonest first.
+///
+/// GET /admin/stage/schedules
+async fn list_stage_schedules(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<StagePublishSchedule>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let schedules = state
+        .stage()
+        .list_scheduled_publishes()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list stage schedules"))?;
+
+    Ok(Json(schedules))
+}
+
+/// Get the scheduled publish of a stage.
+///
+/// GET /admin/stage/{stage_id}/schedule
+async fn get_stage_schedule(
+    State(state): State<AppState>,
+    session: Session,
+    Path(stage_id): Path<Uuid>,
+) -> Result<Json<StagePublishSchedule>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let schedule = state
+        .stage()
+        .scheduled_publish(stage_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load stage schedule"))?
+        .ok_or_else(|| AppError::not_found_id("stage schedule", stage_id))?;
+
+    Ok(Json(schedule))
+}
+
+/// Schedule a stage to publish to live, replacing any existing schedule.
+///
+/// POST /admin/stage/{stage_id}/schedule
+async fn schedule_stage_publish(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(stage_id): Path<Uuid>,
+    Json(request): Json<SchedulePublishRequest>,
+) -> Result<Json<StagePublishSchedule>, AppError> {
+    let user = require_admin(&state, &session)
+        .await
+        .map_err(|_| AppError::forbidden("Admin access required"))?;
+    super::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    if stage_id == LIVE_STAGE_ID {
+        return Err(AppError::bad_request(
+            "The live stage cannot be scheduled to publish",
+        ));
+    }
+    state
+        .stage()
+        .get_stage(stage_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load stage"))?
+        .ok_or_else(|| AppError::not_found_id("stage", stage_id))?;
+
+    if request.publish_at <= chrono::Utc::now().timestamp() {
+        return Err(AppError::validation(vec![AppError::field_error(
+            "publish_at",
+            "in_past",
+            "The publish time must be in the future.",
+        )]));
+    }
+
+    let schedule = state
+        .stage()
+        .schedule_publish(stage_id, request.publish_at, Some(user.id))
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "schedule stage publish"))?;
+
+    Ok(Json(schedule))
+}
+
+/// Cancel the scheduled publish of a stage.
+///
+/// DELETE /admin/stage/{stage_id}/schedule
+async fn cancel_stage_schedule(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(stage_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    require_admin_json(&state, &session).await?;
+    super::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let cancelled = state
+        .stage()
+        .cancel_scheduled_publish(stage_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "cancel stage schedule"))?;
+    if !cancelled {
+        return Err(AppError::not_found_id("stage schedule", stage_id));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // =============================================================================
 // Admin Dashboard
 // =============================================================================
@@ -338,7 +562,7 @@ async fn ajax_callback(
 
     // Handle admin-specific AJAX triggers
     if request.trigger == "add_field" {
-        return super::admin_content_type::handle_ajax_add_field(&state, &request).await;
+        return super::admin_content_type::handle_ajax_add_field(&state, &user, &request).await;
     }
 
     // Build user context with permissions
@@ -376,6 +600,7 @@ async fn ajax_callback(
 struct CommentListQuery {
     status: Option<i16>,
     page: Option<i64>,
+    per_page: Option<i64>,
 }
 
 /// Form data for editing a comment.
@@ -400,7 +625,10 @@ async fn list_comments(
     }
 
     let page = query.page.unwrap_or(1).max(1);
-    let per_page: i64 = 25;
+    let per_page = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, query.per_page)
+        .limit;
     let offset = (page - 1) * per_page;
 
     let comments = if let Some(status) = query.status {
@@ -440,10 +668,17 @@ async fn list_comments(
         }
     }
 
+    // Spam detections, keyed by comment ID
+    let comment_ids: Vec<uuid::Uuid> = comments.iter().map(|c| c.id).collect();
+    let spam = comment_spam::for_comments(state.db(), &comment_ids)
+        .await
+        .unwrap_or_default();
+
     let csrf_token = generate_csrf_token(&session).await;
 
     let mut context = tera::Context::new();
     context.insert("comments", &comments);
+    context.insert("spam", &spam);
     context.insert("authors", &authors);
     context.insert("items", &items);
     context.insert("total", &total);
@@ -459,6 +694,47 @@ async fn list_comments(
     render_admin_template(&state, "admin/comments.html", context).await
 }
 
+/// Get the comment spam policy.
+///
+/// GET /admin/config/comment-spam
+async fn get_comment_spam_settings(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<CommentSpamSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let settings = comment_spam::settings(state.db())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load comment spam settings"))?;
+
+    Ok(Json(settings))
+}
+
+/// Replace the comment spam policy.
+///
+/// POST /admin/config/comment-spam
+async fn update_comment_spam_settings(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(settings): Json<CommentSpamSettings>,
+) -> Result<Json<CommentSpamSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+    super::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    settings.validate().map_err(|e| {
+        AppError::validation(vec![AppError::field_error("settings", "out_of_range", e)])
+    })?;
+
+    comment_spam::save_settings(state.db(), &settings)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save comment spam settings"))?;
+
+    Ok(Json(settings))
+}
+
 /// Edit a comment form.
 ///
 /// GET /admin/content/comments/{id}/edit
@@ -640,8 +916,26 @@ pub fn router() -> Router<AppState> {
         // Stage management
         .route("/admin/stage/switch", post(switch_stage))
         .route("/admin/stage/current", get(get_current_stage))
+        .route(
+            "/admin/stage/workspaces/settings",
+            get(get_workspace_settings).post(update_workspace_settings),
+        )
+        .route("/admin/stage/schedules", get(list_stage_schedules))
+        .route("/admin/stage/{stage_id}/changes", get(stage_changes))
+        // Stage diff against live
+        .merge(super::admin_stage::router())
+        .route(
+            "/admin/stage/{stage_id}/schedule",
+            get(get_stage_schedule)
+                .post(schedule_stage_publish)
+                .delete(cancel_stage_schedule),
+        )
         // User, role, and permission management
         .merge(super::admin_user::router())
+        // Two-factor authentication enforcement
+        .merge(super::admin_mfa::router())
+        // Login lockout thresholds and active locks
+        .merge(super::admin_lockout::router())
         // Content management
         .merge(super::admin_content::router())
         // File management
@@ -652,6 +946,8 @@ pub fn router() -> Router<AppState> {
         .route("/admin/media", get(media_library))
         // Content type and search configuration management
         .merge(super::admin_content_type::router())
+        // Search index status
+        .merge(super::admin_search::router())
         // URL Alias management
         .merge(super::admin_alias::router())
         // Pathauto configuration
@@ -677,6 +973,10 @@ pub fn category_admin_router() -> Router<AppState> {
 pub fn comment_admin_router() -> Router<AppState> {
     Router::new()
         .route("/admin/content/comments", get(list_comments))
+        .route(
+            "/admin/config/comment-spam",
+            get(get_comment_spam_settings).post(update_comment_spam_settings),
+        )
         .route("/admin/content/comments/{id}/edit", get(edit_comment_form))
         .route(
             "/admin/content/comments/{id}/edit",
@@ -9,6 +9,7 @@ use tower_sessions::Session;
 
 use crate::form::csrf::generate_csrf_token;
 use crate::models::{CreateUrlAlias, UpdateUrlAlias, UrlAlias};
+use crate::services::pagination::{PageClass, PaginationPolicy};
 use crate::state::AppState;
 
 use super::helpers::{
@@ -60,7 +61,11 @@ async fn list_aliases(
         .and_then(|p| p.parse().ok())
         .unwrap_or(1)
         .max(1);
-    let per_page: i64 = 50;
+    let requested = params.get("per_page").and_then(|p| p.parse().ok());
+    let per_page = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, requested)
+        .limit;
     let offset = (page - 1) * per_page;
 
     let aliases = match UrlAlias::list_all(state.db(), per_page, offset).await {
@@ -4,6 +4,8 @@
 //! email, language, registration mode, front page configuration,
 //! SMTP delivery, and notification preferences.
 
+use std::collections::HashMap;
+
 use axum::extract::State;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
@@ -11,11 +13,16 @@ use axum::{Form, Router};
 use serde::Deserialize;
 use tower_sessions::Session;
 
+use crate::config_storage::ConfigEntity;
 use crate::form::csrf::generate_csrf_token;
 use crate::models::SiteConfig;
 use crate::state::AppState;
+use crate::tap::RequestState;
 
-use super::helpers::{render_admin_template, render_server_error, require_admin, require_csrf};
+use super::helpers::{
+    admin_user_context, config_violation_messages, render_admin_template, render_server_error,
+    require_admin, require_csrf,
+};
 
 /// Session key for flash messages on the site config page.
 const FLASH_KEY: &str = "site_config_flash";
@@ -23,6 +30,29 @@ const FLASH_KEY: &str = "site_config_flash";
 /// Default items per page when not configured.
 const DEFAULT_ITEMS_PER_PAGE: &str = "10";
 
+/// Form ID of the site settings form, as passed to form taps.
+const SITE_FORM_ID: &str = "config_site";
+
+/// Settings described to `tap_form_alter`, as `(name, label)`. The SMTP
+/// password is left out.
+const SITE_FORM_FIELDS: &[(&str, &str)] = &[
+    ("site_name", "Site name"),
+    ("site_slogan", "Site slogan"),
+    ("site_mail", "Site email"),
+    ("front_page", "Front page"),
+    ("items_per_page", "Items per page"),
+    ("registration_mode", "Registration"),
+    ("smtp_host", "SMTP host"),
+    ("smtp_port", "SMTP port"),
+    ("smtp_username", "SMTP username"),
+    ("smtp_encryption", "SMTP encryption"),
+    ("smtp_from", "SMTP from address"),
+    (
+        "notify_admin_on_register",
+        "Notify admin when new users register",
+    ),
+];
+
 // =============================================================================
 // Form data
 // =============================================================================
@@ -52,6 +82,41 @@ struct SiteConfigFormData {
     smtp_from: String,
     #[serde(default)]
     notify_admin_on_register: Option<String>,
+    /// Elements added by `tap_form_alter`.
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
+}
+
+impl SiteConfigFormData {
+    /// Submitted values as passed to form taps, without the SMTP password.
+    fn tap_values(&self) -> HashMap<String, serde_json::Value> {
+        let mut values: HashMap<String, serde_json::Value> = self
+            .extra
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::json!(v)))
+            .collect();
+        values.extend(
+            [
+                ("site_name", self.site_name.as_str()),
+                ("site_slogan", self.site_slogan.as_str()),
+                ("site_mail", self.site_mail.as_str()),
+                ("front_page", self.front_page.as_str()),
+                ("items_per_page", self.items_per_page.as_str()),
+                ("registration_mode", self.registration_mode.as_str()),
+                ("smtp_host", self.smtp_host.as_str()),
+                ("smtp_port", self.smtp_port.as_str()),
+                ("smtp_username", self.smtp_username.as_str()),
+                ("smtp_encryption", self.smtp_encryption.as_str()),
+                ("smtp_from", self.smtp_from.as_str()),
+            ]
+            .map(|(k, v)| (k.to_string(), serde_json::json!(v.trim()))),
+        );
+        values.insert(
+            "notify_admin_on_register".to_string(),
+            serde_json::json!(self.notify_admin_on_register.is_some()),
+        );
+        values
+    }
 }
 
 /// Test email form data (CSRF token only).
@@ -75,6 +140,53 @@ fn is_valid_email(email: &str) -> bool {
         && !parts[1].ends_with('.')
 }
 
+/// Describe the site settings form to `tap_form_alter`.
+///
+/// The template lays the settings out itself; plugins see them as elements
+/// holding `values` and may add elements of their own.
+fn site_form(values: &HashMap<String, serde_json::Value>) -> crate::form::Form {
+    use crate::form::{Form, FormElement};
+
+    let mut form = Form::new(SITE_FORM_ID).action("/admin/config/site");
+    for (weight, (name, label)) in (0..).zip(SITE_FORM_FIELDS) {
+        let mut element = match *name {
+            "registration_mode" => FormElement::radio(
+                ["open", "admin_only", "closed"]
+                    .map(|mode| (mode.to_string(), mode.to_string()))
+                    .to_vec(),
+            ),
+            "notify_admin_on_register" => FormElement::checkbox(),
+            _ => FormElement::textfield(),
+        }
+        .title(*label)
+        .weight(weight);
+        if let Some(value) = values.get(*name) {
+            element = element.default_value(value.clone());
+        }
+        form = form.element(*name, element);
+    }
+    form
+}
+
+/// Run `tap_form_alter` on the site settings form and insert what plugins
+/// added as `form_additions`.
+async fn insert_form_additions(
+    state: &AppState,
+    context: &mut tera::Context,
+    request_state: &RequestState,
+    values: &HashMap<String, serde_json::Value>,
+) {
+    let additions = state
+        .forms()
+        .additions(site_form(values), values, request_state)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to alter site settings form");
+            crate::form::FormAdditions::default()
+        });
+    context.insert("form_additions", &additions);
+}
+
 /// Load a string site config value, returning empty string on missing/error.
 async fn load_config_string(pool: &sqlx::PgPool, key: &str) -> String {
     SiteConfig::get(pool, key)
@@ -93,9 +205,10 @@ async fn load_config_string(pool: &sqlx::PgPool, key: &str) -> String {
 ///
 /// GET /admin/config/site
 async fn site_config_form(State(state): State<AppState>, session: Session) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     let pool = state.db();
 
@@ -182,6 +295,28 @@ async fn site_config_form(State(state): State<AppState>, session: Session) -> Re
     context.insert("flash", &flash);
     context.insert("path", "/admin/config/site");
 
+    let values: HashMap<String, serde_json::Value> = [
+        ("site_name", serde_json::json!(site_name)),
+        ("site_slogan", serde_json::json!(site_slogan)),
+        ("site_mail", serde_json::json!(site_mail)),
+        ("front_page", serde_json::json!(front_page)),
+        ("items_per_page", serde_json::json!(items_per_page)),
+        ("registration_mode", serde_json::json!(registration_mode)),
+        ("smtp_host", serde_json::json!(smtp_host)),
+        ("smtp_port", serde_json::json!(smtp_port)),
+        ("smtp_username", serde_json::json!(smtp_username)),
+        ("smtp_encryption", serde_json::json!(smtp_encryption)),
+        ("smtp_from", serde_json::json!(smtp_from)),
+        (
+            "notify_admin_on_register",
+            serde_json::json!(notify_admin_on_register),
+        ),
+    ]
+    .map(|(k, v)| (k.to_string(), v))
+    .into();
+    let request_state = RequestState::new(admin_user_context(&user), state.tap_services().clone());
+    insert_form_additions(&state, &mut context, &request_state, &values).await;
+
     render_admin_template(&state, "admin/config/site.html", context).await
 }
 
@@ -193,14 +328,17 @@ async fn site_config_submit(
     session: Session,
     Form(form): Form<SiteConfigFormData>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
     let pool = state.db();
+    let request_state = RequestState::new(admin_user_context(&user), state.tap_services().clone());
+    let values = form.tap_values();
 
     // Validate
     let mut errors: Vec<String> = Vec::new();
@@ -261,6 +399,52 @@ async fn site_config_submit(
         errors.push("Invalid SMTP encryption mode.".to_string());
     }
 
+    let ipp = if items_per_page_str.is_empty() {
+        DEFAULT_ITEMS_PER_PAGE
+    } else {
+        items_per_page_str
+    };
+    let smtp_port_val = if smtp_port_str.is_empty() {
+        "587"
+    } else {
+        smtp_port_str
+    };
+
+    // Let plugins validate the variables about to be saved
+    if errors.is_empty() {
+        let variables = [
+            ("site_name", serde_json::json!(site_name)),
+            ("site_slogan", serde_json::json!(form.site_slogan.trim())),
+            ("site_mail", serde_json::json!(site_mail)),
+            ("front_page", serde_json::json!(form.front_page.trim())),
+            ("items_per_page", serde_json::json!(ipp)),
+            ("user_registration", serde_json::json!(registration_mode)),
+            ("smtp_host", serde_json::json!(form.smtp_host.trim())),
+            ("smtp_port", serde_json::json!(smtp_port_val)),
+            ("smtp_encryption", serde_json::json!(smtp_encryption)),
+            ("smtp_from", serde_json::json!(smtp_from)),
+            (
+                "notify_admin_on_register",
+                serde_json::json!(form.notify_admin_on_register.is_some()),
+            ),
+        ]
+        .map(|(key, value)| ConfigEntity::Variable {
+            key: key.to_string(),
+            value,
+        });
+        errors.extend(config_violation_messages(&state, &variables).await);
+    }
+
+    // Let plugins validate the submission, including elements they added
+    match state
+        .forms()
+        .validate(SITE_FORM_ID, &values, &request_state)
+        .await
+    {
+        Ok(plugin_errors) => errors.extend(plugin_errors.into_iter().map(|e| e.message)),
+        Err(e) => tracing::warn!(error = %e, "site settings validation taps failed"),
+    }
+
     if !errors.is_empty() {
         // Re-render form with errors
         let csrf_token = generate_csrf_token(&session).await;
@@ -288,6 +472,7 @@ async fn site_config_submit(
         context.insert("notify_admin_on_register", &notify_admin_on_register);
         context.insert("errors", &errors);
         context.insert("path", "/admin/config/site");
+        insert_form_additions(&state, &mut context, &request_state, &values).await;
 
         return render_admin_template(&state, "admin/config/site.html", context).await;
     }
@@ -313,11 +498,6 @@ async fn site_config_submit(
         return render_server_error("Failed to save site settings.");
     }
 
-    let ipp = if items_per_page_str.is_empty() {
-        DEFAULT_ITEMS_PER_PAGE
-    } else {
-        items_per_page_str
-    };
     if let Err(e) = SiteConfig::set(pool, "items_per_page", serde_json::json!(ipp)).await {
         tracing::error!(error = %e, "failed to save items_per_page");
         return render_server_error("Failed to save site settings.");
@@ -340,11 +520,6 @@ async fn site_config_submit(
         tracing::error!(error = %e, "failed to save smtp_host");
     }
 
-    let smtp_port_val = if smtp_port_str.is_empty() {
-        "587"
-    } else {
-        smtp_port_str
-    };
     if let Err(e) = SiteConfig::set(pool, "smtp_port", serde_json::json!(smtp_port_val)).await {
         tracing::error!(error = %e, "failed to save smtp_port");
     }
@@ -389,6 +564,14 @@ async fn site_config_submit(
         tracing::error!(error = %e, "failed to save notify_admin_on_register");
     }
 
+    if let Err(e) = state
+        .forms()
+        .submit(SITE_FORM_ID, &values, None, &request_state)
+        .await
+    {
+        tracing::warn!(error = %e, "site settings submit taps failed");
+    }
+
     let _ = session
         .insert(FLASH_KEY, "Settings saved successfully.")
         .await;
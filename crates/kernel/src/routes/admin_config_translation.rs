@@ -0,0 +1,246 @@
+//! Admin routes for config translation.
+//!
+//! Lets translators provide per-language overlays for item type labels,
+//! category names and string variables. Overlays are stored in the
+//! `trovato_config_translation` plugin's `config_translation` table and
+//! applied on read by [`TranslatedConfigStorage`](crate::config_storage::TranslatedConfigStorage).
+
+use std::collections::HashMap;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Form, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+
+use crate::config_storage::{ConfigEntity, entity_types, source_value, translatable_properties};
+use crate::form::csrf::generate_csrf_token;
+use crate::models::ConfigTranslation;
+use crate::state::AppState;
+
+use super::helpers::{
+    render_admin_template, render_error, render_not_found, render_server_error, require_csrf,
+    require_permission,
+};
+
+/// Session key for flash messages on the config translation page.
+const FLASH_KEY: &str = "config_translation_flash";
+
+/// Permission required to translate config (declared by the plugin).
+const PERMISSION: &str = "translate configuration";
+
+/// Config entity types offered for translation, with their display labels.
+const TRANSLATABLE_TYPES: &[(&str, &str)] = &[
+    (entity_types::ITEM_TYPE, "Content types"),
+    (entity_types::CATEGORY, "Categories"),
+    (entity_types::VARIABLE, "Variables"),
+];
+
+/// Query parameters selecting the entity type and language to translate.
+#[derive(Debug, Deserialize)]
+struct TranslateQuery {
+    #[serde(rename = "type")]
+    entity_type: Option<String>,
+    lang: Option<String>,
+}
+
+/// A config entity row for the template.
+#[derive(Debug, Serialize)]
+struct EntityDisplay {
+    id: String,
+    properties: Vec<PropertyDisplay>,
+}
+
+/// A translatable property with its source and translated values.
+#[derive(Debug, Serialize)]
+struct PropertyDisplay {
+    name: &'static str,
+    source: String,
+    translation: String,
+}
+
+/// Create the config translation admin router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/config/translate", get(translate_overview))
+        .route(
+            "/admin/config/translate/{entity_type}/{id}/{lang}",
+            post(translate_save),
+        )
+}
+
+/// Languages config can be translated into (all but the default).
+fn target_languages(state: &AppState) -> Vec<String> {
+    state
+        .known_languages()
+        .iter()
+        .filter(|l| l.as_str() != state.default_language())
+        .cloned()
+        .collect()
+}
+
+/// List translatable config of one type with its translations.
+///
+/// GET /admin/config/translate?type={entity_type}&lang={lang}
+async fn translate_overview(
+    State(state): State<AppState>,
+    session: Session,
+    Query(query): Query<TranslateQuery>,
+) -> Response {
+    if let Err(resp) = require_permission(&state, &session, PERMISSION).await {
+        return resp;
+    }
+
+    let entity_type = query
+        .entity_type
+        .unwrap_or_else(|| entity_types::ITEM_TYPE.to_string());
+    if translatable_properties(&entity_type).is_empty() {
+        return render_not_found();
+    }
+
+    let languages = target_languages(&state);
+    let language = match query.lang {
+        Some(lang) if languages.contains(&lang) => Some(lang),
+        Some(_) => return render_not_found(),
+        None => languages.first().cloned(),
+    };
+
+    let mut entities = Vec::new();
+    if let Some(language) = &language {
+        let source = match state.config_storage().list(&entity_type, None).await {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::error!(error = %e, entity_type = %entity_type, "failed to list config");
+                return render_server_error("Failed to load configuration.");
+            }
+        };
+        let translations =
+            match ConfigTranslation::for_type(state.db(), &entity_type, language).await {
+                Ok(rows) => rows,
+                Err(e) => {
+                    tracing::error!(error = %e, "failed to load config translations");
+                    return render_server_error("Failed to load translations.");
+                }
+            };
+        let mut translated: HashMap<(String, String), String> = translations
+            .into_iter()
+            .map(|t| ((t.entity_id, t.property), t.value))
+            .collect();
+
+        for entity in &source {
+            let id = entity.id();
+            let properties: Vec<PropertyDisplay> = translatable_properties(&entity_type)
+                .iter()
+                .filter_map(|&name| {
+                    let source = source_value(entity, name)?;
+                    let translation = translated
+                        .remove(&(id.clone(), name.to_string()))
+                        .unwrap_or_default();
+                    Some(PropertyDisplay {
+                        name,
+                        source,
+                        translation,
+                    })
+                })
+                .collect();
+            if !properties.is_empty() {
+                entities.push(EntityDisplay { id, properties });
+            }
+        }
+    }
+
+    let csrf_token = generate_csrf_token(&session).await;
+    let flash: Option<String> = session.remove(FLASH_KEY).await.ok().flatten();
+
+    let types: Vec<serde_json::Value> = TRANSLATABLE_TYPES
+        .iter()
+        .map(|(id, label)| serde_json::json!({ "id": id, "label": label }))
+        .collect();
+
+    let mut context = tera::Context::new();
+    context.insert("entity_types", &types);
+    context.insert("entity_type", &entity_type);
+    context.insert("languages", &languages);
+    context.insert("language", &language);
+    context.insert("entities", &entities);
+    context.insert("csrf_token", &csrf_token);
+    context.insert("path", "/admin/config/translate");
+    if let Some(msg) = flash {
+        context.insert("flash", &msg);
+    }
+
+    render_admin_template(&state, "admin/config-translate.html", context).await
+}
+
+/// Save the translations of one config entity.
+///
+/// Accepts one form field per translatable property. An empty value
+/// removes the translation, so the source value shows again.
+///
+/// POST /admin/config/translate/{entity_type}/{id}/{lang}
+async fn translate_save(
+    State(state): State<AppState>,
+    session: Session,
+    Path((entity_type, id, lang)): Path<(String, String, String)>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Response {
+    if let Err(resp) = require_permission(&state, &session, PERMISSION).await {
+        return resp;
+    }
+
+    let token = form.get("_token").map(String::as_str).unwrap_or("");
+    if let Err(resp) = require_csrf(&session, token).await {
+        return resp;
+    }
+
+    if translatable_properties(&entity_type).is_empty() {
+        return render_not_found();
+    }
+    if !target_languages(&state).contains(&lang) {
+        return render_error("Unknown or default language.");
+    }
+
+    let entity: ConfigEntity = match state.config_storage().load(&entity_type, &id).await {
+        Ok(Some(entity)) => entity,
+        Ok(None) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, entity_type = %entity_type, id = %id, "failed to load config");
+            return render_server_error("Failed to load configuration.");
+        }
+    };
+
+    for &property in translatable_properties(&entity_type) {
+        // Properties without a source value (e.g. non-string variables)
+        // have nothing to translate.
+        if source_value(&entity, property).is_none() {
+            continue;
+        }
+        let Some(value) = form.get(property) else {
+            continue;
+        };
+
+        let value = value.trim();
+        let result = if value.is_empty() {
+            ConfigTranslation::delete(state.db(), &entity_type, &id, &lang, property)
+                .await
+                .map(|_| ())
+        } else {
+            ConfigTranslation::upsert(state.db(), &entity_type, &id, &lang, property, value).await
+        };
+        if let Err(e) = result {
+            tracing::error!(error = %e, entity_type = %entity_type, id = %id, "failed to save config translation");
+            return render_server_error("Failed to save translation.");
+        }
+    }
+
+    let _ = session
+        .insert(FLASH_KEY, format!("Translation of {entity} saved."))
+        .await;
+    Redirect::to(&format!(
+        "/admin/config/translate?type={}&lang={}",
+        urlencoding::encode(&entity_type),
+        urlencoding::encode(&lang)
+    ))
+    .into_response()
+}
@@ -0,0 +1,433 @@
+//! Admin routes for the contact form: categories and received messages.
+//!
+//! Gated on the `trovato_contact` plugin, like the public form.
+
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Form, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::form::csrf::generate_csrf_token;
+use crate::models::{ContactCategory, ContactSubmission, CreateContactCategory};
+use crate::services::contact::{export_csv, parse_recipients};
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::state::AppState;
+
+use super::helpers::{
+    CsrfOnlyForm, render_admin_template, render_not_found, render_server_error, require_admin,
+    require_csrf,
+};
+
+// =============================================================================
+// Form data
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct CategoryFormData {
+    #[serde(rename = "_token")]
+    token: String,
+    name: String,
+    recipients: String,
+    weight: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmissionListQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+/// Submission display struct for templates.
+#[derive(Debug, Serialize)]
+struct SubmissionDisplay {
+    id: Uuid,
+    category_name: String,
+    name: String,
+    mail: String,
+    subject: String,
+    message: String,
+    created_display: String,
+}
+
+/// Validate the category form, returning the input or error messages.
+fn parse_category_form(form: &CategoryFormData) -> Result<CreateContactCategory, Vec<String>> {
+    let mut errors = Vec::new();
+
+    let name = form.name.trim();
+    if name.is_empty() {
+        errors.push("Name is required.".to_string());
+    }
+    let recipients = parse_recipients(&form.recipients).unwrap_or_else(|e| {
+        errors.push(e);
+        Vec::new()
+    });
+    let weight = match form.weight.as_deref().map(str::trim) {
+        None | Some("") => 0,
+        Some(w) => w.parse().unwrap_or_else(|_| {
+            errors.push("Weight must be a whole number.".to_string());
+            0
+        }),
+    };
+
+    if errors.is_empty() {
+        Ok(CreateContactCategory {
+            name: name.to_string(),
+            recipients,
+            weight,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+// =============================================================================
+// Categories
+// =============================================================================
+
+/// List contact categories.
+///
+/// GET /admin/structure/contact
+async fn list_categories(State(state): State<AppState>, session: Session) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let categories = match ContactCategory::list(state.db()).await {
+        Ok(categories) => categories,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list contact categories");
+            return render_server_error("Failed to load contact categories.");
+        }
+    };
+
+    let csrf_token = generate_csrf_token(&session).await;
+
+    let mut context = tera::Context::new();
+    context.insert("categories", &categories);
+    context.insert("csrf_token", &csrf_token);
+    context.insert("path", "/admin/structure/contact");
+
+    render_admin_template(&state, "admin/contact-categories.html", context).await
+}
+
+/// Render the add/edit category form.
+async fn render_category_form(
+    state: &AppState,
+    session: &Session,
+    category_id: Option<Uuid>,
+    values: serde_json::Value,
+    errors: &[String],
+) -> Response {
+    let action = match category_id {
+        Some(id) => format!("/admin/structure/contact/{id}/edit"),
+        None => "/admin/structure/contact/add".to_string(),
+    };
+    let csrf_token = generate_csrf_token(session).await;
+
+    let mut context = tera::Context::new();
+    context.insert("csrf_token", &csrf_token);
+    context.insert("action", &action);
+    context.insert("editing", &category_id.is_some());
+    context.insert("values", &values);
+    context.insert("errors", errors);
+    context.insert("path", &action);
+
+    render_admin_template(state, "admin/contact-category-form.html", context).await
+}
+
+/// Add category form.
+///
+/// GET /admin/structure/contact/add
+async fn add_category_form(State(state): State<AppState>, session: Session) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    render_category_form(&state, &session, None, serde_json::json!({}), &[]).await
+}
+
+/// Add category submit.
+///
+/// POST /admin/structure/contact/add
+async fn add_category_submit(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<CategoryFormData>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let input = match parse_category_form(&form) {
+        Ok(input) => input,
+        Err(errors) => {
+            let values = serde_json::json!({
+                "name": form.name,
+                "recipients": form.recipients,
+                "weight": form.weight,
+            });
+            return render_category_form(&state, &session, None, values, &errors).await;
+        }
+    };
+
+    match ContactCategory::create(state.db(), input).await {
+        Ok(category) => {
+            tracing::info!(category_id = %category.id, "contact category created");
+            Redirect::to("/admin/structure/contact").into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "failed to create contact category");
+            render_server_error("Failed to create category. The name may already be in use.")
+        }
+    }
+}
+
+/// Edit category form.
+///
+/// GET /admin/structure/contact/{id}/edit
+async fn edit_category_form(
+    State(state): State<AppState>,
+    session: Session,
+    Path(category_id): Path<Uuid>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let category = match ContactCategory::find_by_id(state.db(), category_id).await {
+        Ok(Some(category)) => category,
+        Ok(None) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load contact category");
+            return render_server_error("Failed to load contact category.");
+        }
+    };
+
+    let values = serde_json::json!({
+        "name": category.name,
+        "recipients": category.recipients.join("\n"),
+        "weight": category.weight,
+    });
+    render_category_form(&state, &session, Some(category_id), values, &[]).await
+}
+
+/// Edit category submit.
+///
+/// POST /admin/structure/contact/{id}/edit
+async fn edit_category_submit(
+    State(state): State<AppState>,
+    session: Session,
+    Path(category_id): Path<Uuid>,
+    Form(form): Form<CategoryFormData>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let input = match parse_category_form(&form) {
+        Ok(input) => input,
+        Err(errors) => {
+            let values = serde_json::json!({
+                "name": form.name,
+                "recipients": form.recipients,
+                "weight": form.weight,
+            });
+            return render_category_form(&state, &session, Some(category_id), values, &errors)
+                .await;
+        }
+    };
+
+    match ContactCategory::update(state.db(), category_id, input).await {
+        Ok(Some(_)) => Redirect::to("/admin/structure/contact").into_response(),
+        Ok(None) => render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to update contact category");
+            render_server_error("Failed to update category. The name may already be in use.")
+        }
+    }
+}
+
+/// Delete a category.
+///
+/// POST /admin/structure/contact/{id}/delete
+async fn delete_category(
+    State(state): State<AppState>,
+    session: Session,
+    Path(category_id): Path<Uuid>,
+    Form(form): Form<CsrfOnlyForm>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    match ContactCategory::delete(state.db(), category_id).await {
+        Ok(true) => {
+            tracing::info!(category_id = %category_id, "contact category deleted");
+            Redirect::to("/admin/structure/contact").into_response()
+        }
+        Ok(false) => render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to delete contact category");
+            render_server_error("Failed to delete contact category.")
+        }
+    }
+}
+
+// =============================================================================
+// Submissions
+// =============================================================================
+
+/// List received contact messages.
+///
+/// GET /admin/content/contact
+async fn list_submissions(
+    State(state): State<AppState>,
+    session: Session,
+    Query(query): Query<SubmissionListQuery>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, query.per_page)
+        .limit;
+    let offset = (page - 1) * per_page;
+
+    let submissions = match ContactSubmission::list(state.db(), per_page, offset).await {
+        Ok(submissions) => submissions,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list contact submissions");
+            return render_server_error("Failed to load contact messages.");
+        }
+    };
+
+    let total = ContactSubmission::count(state.db()).await.unwrap_or(0);
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let submissions: Vec<SubmissionDisplay> = submissions
+        .into_iter()
+        .map(|s| SubmissionDisplay {
+            id: s.id,
+            category_name: s.category_name,
+            name: s.name,
+            mail: s.mail,
+            subject: s.subject,
+            message: s.message,
+            created_display: chrono::DateTime::from_timestamp(s.created, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+        })
+        .collect();
+
+    let csrf_token = generate_csrf_token(&session).await;
+
+    let mut context = tera::Context::new();
+    context.insert("submissions", &submissions);
+    context.insert("total", &total);
+    context.insert("page", &page);
+    context.insert("total_pages", &total_pages);
+    context.insert("csrf_token", &csrf_token);
+    context.insert("path", "/admin/content/contact");
+
+    render_admin_template(&state, "admin/contact-submissions.html", context).await
+}
+
+/// Download every contact message as CSV.
+///
+/// GET /admin/content/contact/export
+async fn export_submissions(State(state): State<AppState>, session: Session) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    match ContactSubmission::list_all(state.db()).await {
+        Ok(submissions) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"contact-messages.csv\"",
+                ),
+            ],
+            export_csv(&submissions),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to export contact submissions");
+            render_server_error("Failed to export contact messages.")
+        }
+    }
+}
+
+/// Delete a contact message.
+///
+/// POST /admin/content/contact/{id}/delete
+async fn delete_submission(
+    State(state): State<AppState>,
+    session: Session,
+    Path(submission_id): Path<Uuid>,
+    Form(form): Form<CsrfOnlyForm>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    match ContactSubmission::delete(state.db(), submission_id).await {
+        Ok(true) => Redirect::to("/admin/content/contact").into_response(),
+        Ok(false) => render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to delete contact submission");
+            render_server_error("Failed to delete contact message.")
+        }
+    }
+}
+
+// =============================================================================
+// Router
+// =============================================================================
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/structure/contact", get(list_categories))
+        .route(
+            "/admin/structure/contact/add",
+            get(add_category_form).post(add_category_submit),
+        )
+        .route(
+            "/admin/structure/contact/{id}/edit",
+            get(edit_category_form).post(edit_category_submit),
+        )
+        .route(
+            "/admin/structure/contact/{id}/delete",
+            post(delete_category),
+        )
+        .route("/admin/content/contact", get(list_submissions))
+        .route("/admin/content/contact/export", get(export_submissions))
+        .route(
+            "/admin/content/contact/{id}/delete",
+            post(delete_submission),
+        )
+}
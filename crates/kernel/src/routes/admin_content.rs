@@ -1,19 +1,25 @@
 //! Admin routes for content item management.
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
 use axum::{Extension, Form, Router};
 use serde::Deserialize;
 use tower_sessions::Session;
+use trovato_sdk::types::ContentTypeDefinition;
 
+use crate::content::{ItemValidationFailed, field_sections, item_form};
 use crate::form::csrf::generate_csrf_token;
-use crate::models::CreateItem;
+use crate::models::item_status::{STATUS_REASON_CODES, StatusChangeMeta, status_reason_label};
+use crate::models::{CreateItem, ItemType};
+use crate::services::cascade::{self, CascadeAction, DeleteRestricted};
 use crate::state::AppState;
+use crate::tap::RequestState;
 
 use super::helpers::{
     CsrfOnlyForm, admin_user_context, build_local_tasks, html_escape, render_admin_template,
-    render_not_found, render_server_error, require_admin, require_csrf,
+    render_not_found, render_server_error, require_admin, require_csrf, start_cascade,
 };
 
 /// Session key for flash messages on the content list page.
@@ -32,6 +38,85 @@ fn extract_content_fields(
     result
 }
 
+/// Form ID of a content type's add or edit form, as passed to form taps.
+fn item_form_id(editing: bool, type_name: &str) -> String {
+    let op = if editing { "edit" } else { "add" };
+    format!("item_{op}_{type_name}")
+}
+
+/// Tap request state for an administrator.
+fn admin_request_state(state: &AppState, user: &crate::models::User) -> RequestState {
+    RequestState::new(admin_user_context(user), state.tap_services().clone())
+}
+
+/// Run `tap_form_alter` on a content form and insert what plugins added as
+/// `form_additions`.
+///
+/// `values` are the item's stored or submitted fields; a form that plugins
+/// fail to alter is shown without additions.
+async fn insert_form_additions(
+    state: &AppState,
+    context: &mut tera::Context,
+    user: &crate::models::User,
+    form: crate::form::Form,
+    values: &std::collections::HashMap<String, serde_json::Value>,
+) {
+    let form_id = form.form_id.clone();
+    let additions = state
+        .forms()
+        .additions(form, values, &admin_request_state(state, user))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, form_id = %form_id, "failed to alter content form");
+            crate::form::FormAdditions::default()
+        });
+    context.insert("form_additions", &additions);
+}
+
+/// Values of a content form submission as passed to `tap_form_validate`
+/// and `tap_form_submit`: everything submitted, with fields as processed
+/// for saving.
+fn tap_values(
+    form: &ContentFormData,
+    fields_json: &serde_json::Map<String, serde_json::Value>,
+) -> std::collections::HashMap<String, serde_json::Value> {
+    let mut values = form.fields.clone();
+    values.extend(fields_json.iter().map(|(k, v)| (k.clone(), v.clone())));
+    values.insert("title".to_string(), serde_json::json!(form.title));
+    values.insert(
+        "status".to_string(),
+        serde_json::json!(form.status.is_some()),
+    );
+    values
+}
+
+/// Messages of the errors `tap_form_validate` handlers report for a content
+/// form submission.
+async fn plugin_form_errors(
+    state: &AppState,
+    form_id: &str,
+    values: &std::collections::HashMap<String, serde_json::Value>,
+    request_state: &RequestState,
+) -> Vec<String> {
+    match state.forms().validate(form_id, values, request_state).await {
+        Ok(errors) => errors.into_iter().map(|e| e.message).collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, form_id = %form_id, "form validation taps failed");
+            Vec::new()
+        }
+    }
+}
+
+/// Item fields as a value map.
+fn field_values(
+    fields: &serde_json::Value,
+) -> std::collections::HashMap<String, serde_json::Value> {
+    fields
+        .as_object()
+        .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default()
+}
+
 /// Extract file UUIDs from item fields.
 ///
 /// File fields store a UUID string referencing `file_managed.id`. Call this
@@ -87,10 +172,54 @@ struct ContentFormData {
     form_build_id: String,
     title: String,
     status: Option<String>,
+    #[serde(default)]
+    status_reason: Option<String>,
+    #[serde(default)]
+    status_note: Option<String>,
+    #[serde(default)]
+    embargo_until: Option<String>,
     #[serde(flatten)]
     fields: std::collections::HashMap<String, serde_json::Value>,
 }
 
+/// Reason code options for status change select lists.
+fn status_reason_options() -> Vec<serde_json::Value> {
+    STATUS_REASON_CODES
+        .iter()
+        .map(|(code, label)| serde_json::json!({"code": code, "label": label}))
+        .collect()
+}
+
+/// Build status change metadata from submitted form values.
+///
+/// Empty inputs are treated as absent. The embargo date is a `YYYY-MM-DD`
+/// string interpreted as midnight UTC.
+fn parse_status_meta(
+    reason: Option<&str>,
+    note: Option<&str>,
+    embargo_until: Option<&str>,
+) -> Result<StatusChangeMeta, String> {
+    let non_empty = |s: Option<&str>| s.map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+
+    let embargo_until = match non_empty(embargo_until) {
+        Some(date) => {
+            let date = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .map_err(|_| "Embargo date must be in YYYY-MM-DD format.".to_string())?;
+            Some(date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp())
+        }
+        None => None,
+    };
+
+    let meta = StatusChangeMeta {
+        reason_code: non_empty(reason),
+        note: non_empty(note),
+        embargo_until,
+    };
+    meta.validate()
+        .map_err(|e| format!("Invalid status reason: {e}."))?;
+    Ok(meta)
+}
+
 /// List all content.
 ///
 /// GET /admin/content
@@ -105,12 +234,26 @@ async fn list_content(
 
     let type_filter = params.get("type").map(|s| s.as_str());
     let status_filter = params.get("status").and_then(|s| s.parse::<i16>().ok());
-
-    let items = match state
-        .items()
-        .list_filtered(type_filter, status_filter, None, 100, 0)
-        .await
-    {
+    let reason_filter = params
+        .get("reason")
+        .map(|s| s.as_str())
+        .filter(|s| status_reason_label(s).is_some());
+
+    let listed = match reason_filter {
+        Some(reason) => {
+            state
+                .items()
+                .list_by_status_reason(type_filter, status_filter, reason, 100, 0)
+                .await
+        }
+        None => {
+            state
+                .items()
+                .list_filtered(type_filter, status_filter, None, 100, 0)
+                .await
+        }
+    };
+    let items = match listed {
         Ok((items, _total)) => items,
         Err(e) => {
             tracing::error!(error = %e, "failed to list content");
@@ -118,6 +261,30 @@ async fn list_content(
         }
     };
 
+    // Latest status reason per item, shown beneath the status badge
+    let item_ids: Vec<uuid::Uuid> = items.iter().map(|i| i.id).collect();
+    let status_reasons: std::collections::HashMap<String, serde_json::Value> =
+        match state.items().latest_status_changes(&item_ids).await {
+            Ok(changes) => changes
+                .into_values()
+                .filter_map(|c| {
+                    let label = status_reason_label(c.reason_code.as_deref()?)?;
+                    Some((
+                        c.item_id.to_string(),
+                        serde_json::json!({
+                            "label": label,
+                            "note": c.note,
+                            "embargo_until": c.embargo_until,
+                        }),
+                    ))
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load status reasons");
+                std::collections::HashMap::new()
+            }
+        };
+
     // Get authors for display
     let mut authors: std::collections::HashMap<String, String> = std::collections::HashMap::new();
     for item in &items {
@@ -143,12 +310,15 @@ async fn list_content(
     let mut context = tera::Context::new();
     context.insert("items", &items);
     context.insert("authors", &authors);
+    context.insert("status_reasons", &status_reasons);
+    context.insert("reason_options", &status_reason_options());
     context.insert("content_types", &content_types);
     context.insert("type_filter", &type_filter.unwrap_or(""));
     context.insert(
         "status_filter",
         &status_filter.map(|s| s.to_string()).unwrap_or_default(),
     );
+    context.insert("reason_filter", &reason_filter.unwrap_or(""));
     context.insert("csrf_token", &csrf_token);
     context.insert("flash", &flash);
     context.insert("path", "/admin/content");
@@ -181,9 +351,10 @@ async fn add_content_form(
     session: Session,
     Path(type_name): Path<String>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     let Some(content_type) = state.content_types().get(&type_name) else {
         return render_not_found();
@@ -198,9 +369,24 @@ async fn add_content_form(
     context.insert("form_build_id", &form_build_id);
     context.insert("editing", &false);
     context.insert("content_type", &content_type);
+    context.insert("field_sections", &field_sections(&content_type));
     context.insert("values", &serde_json::json!({}));
     context.insert("path", &format!("/admin/content/add/{type_name}"));
-    context.insert("ai_assist_enabled", &state.is_plugin_enabled("trovato_ai"));
+    let form = item_form(
+        &content_type,
+        item_form_id(false, &type_name),
+        "",
+        &serde_json::Map::new(),
+    )
+    .action(format!("/admin/content/add/{type_name}"));
+    insert_form_additions(
+        &state,
+        &mut context,
+        &user,
+        form,
+        &std::collections::HashMap::new(),
+    )
+    .await;
 
     render_admin_template(&state, "admin/content-form.html", context).await
 }
@@ -230,6 +416,10 @@ async fn add_content_submit(
     };
 
     let mut fields_json = extract_content_fields(&form.fields);
+    crate::content::field_constraints::fill_unchecked_booleans(
+        &mut fields_json,
+        &content_type.fields,
+    );
 
     // Validate all fields before checking errors
     let mut errors = Vec::new();
@@ -256,29 +446,23 @@ async fn add_content_submit(
         &content_type.fields,
     ));
 
+    // Let plugins validate the submission, including elements they added
+    let form_id = item_form_id(false, &type_name);
+    let values = tap_values(&form, &fields_json);
+    let request_state = admin_request_state(&state, &user);
+    errors.extend(plugin_form_errors(&state, &form_id, &values, &request_state).await);
+
     if !errors.is_empty() {
-        let csrf_token = generate_csrf_token(&session).await;
-        let form_build_id = uuid::Uuid::new_v4().to_string();
-
-        let mut context = tera::Context::new();
-        context.insert("action", &format!("/admin/content/add/{type_name}"));
-        context.insert("csrf_token", &csrf_token);
-        context.insert("form_build_id", &form_build_id);
-        context.insert("editing", &false);
-        context.insert("content_type", &content_type);
-        context.insert("errors", &errors);
-        context.insert(
-            "values",
-            &serde_json::json!({
-                "title": form.title,
-                "status": form.status.is_some(),
-                "fields": fields_json,
-            }),
-        );
-        context.insert("path", &format!("/admin/content/add/{type_name}"));
-        context.insert("ai_assist_enabled", &state.is_plugin_enabled("trovato_ai"));
-
-        return render_admin_template(&state, "admin/content-form.html", context).await;
+        return render_add_form_errors(
+            &state,
+            &session,
+            &user,
+            &content_type,
+            &form,
+            &fields_json,
+            &errors,
+        )
+        .await;
     }
 
     let file_ids = extract_file_ids(&state, &fields_json, &type_name);
@@ -289,7 +473,7 @@ async fn add_content_submit(
         status: Some(if form.status.is_some() { 1 } else { 0 }),
         promote: None,
         sticky: None,
-        fields: Some(serde_json::Value::Object(fields_json)),
+        fields: Some(serde_json::Value::Object(fields_json.clone())),
         stage_id: None,
         language: Some(resolved_lang.0),
         log: Some("Created via admin UI".to_string()),
@@ -301,16 +485,16 @@ async fn add_content_submit(
             // Promote temporary file uploads to permanent
             promote_file_ids(&state, &file_ids).await;
 
-            // Auto-generate URL alias if pattern configured for this type
-            if let Err(e) = crate::services::pathauto::auto_alias_item(
-                state.db(),
-                item.id,
-                &item.title,
-                &item.item_type,
-                item.created,
-            )
-            .await
+            if let Err(e) = state
+                .forms()
+                .submit(&form_id, &values, Some(item.id), &request_state)
+                .await
             {
+                tracing::warn!(error = %e, item_id = %item.id, "form submit taps failed");
+            }
+
+            // Auto-generate URL alias if pattern configured for this type
+            if let Err(e) = crate::services::pathauto::auto_alias_item(state.db(), &item).await {
                 tracing::warn!(error = %e, item_id = %item.id, "pathauto alias generation failed");
             }
 
@@ -335,12 +519,134 @@ async fn add_content_submit(
             Redirect::to("/admin/content").into_response()
         }
         Err(e) => {
+            if let Some(failed) = e.downcast_ref::<ItemValidationFailed>() {
+                let mut response = render_add_form_errors(
+                    &state,
+                    &session,
+                    &user,
+                    &content_type,
+                    &form,
+                    &fields_json,
+                    &failed.messages(),
+                )
+                .await;
+                *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+                return response;
+            }
             tracing::error!(error = %e, "failed to create content");
             render_server_error("Failed to create content.")
         }
     }
 }
 
+/// Re-render the add content form with validation errors.
+async fn render_add_form_errors(
+    state: &AppState,
+    session: &Session,
+    user: &crate::models::User,
+    content_type: &ContentTypeDefinition,
+    form: &ContentFormData,
+    fields_json: &serde_json::Map<String, serde_json::Value>,
+    errors: &[String],
+) -> Response {
+    let type_name = &content_type.machine_name;
+    let csrf_token = generate_csrf_token(session).await;
+    let form_build_id = uuid::Uuid::new_v4().to_string();
+
+    let mut context = tera::Context::new();
+    context.insert("action", &format!("/admin/content/add/{type_name}"));
+    context.insert("csrf_token", &csrf_token);
+    context.insert("form_build_id", &form_build_id);
+    context.insert("editing", &false);
+    context.insert("content_type", content_type);
+    context.insert("field_sections", &field_sections(content_type));
+    context.insert("errors", errors);
+    context.insert(
+        "values",
+        &serde_json::json!({
+            "title": form.title,
+            "status": form.status.is_some(),
+            "fields": fields_json,
+        }),
+    );
+    context.insert("path", &format!("/admin/content/add/{type_name}"));
+    let alterable = item_form(
+        content_type,
+        item_form_id(false, type_name),
+        &form.title,
+        fields_json,
+    )
+    .action(format!("/admin/content/add/{type_name}"));
+    insert_form_additions(state, &mut context, user, alterable, &form.fields).await;
+
+    render_admin_template(state, "admin/content-form.html", context).await
+}
+
+/// Re-render the edit content form with validation errors.
+async fn render_edit_form_errors(
+    state: &AppState,
+    session: &Session,
+    user: &crate::models::User,
+    item: &crate::models::Item,
+    content_type: &ContentTypeDefinition,
+    form: &ContentFormData,
+    fields_json: &serde_json::Map<String, serde_json::Value>,
+    errors: &[String],
+) -> Response {
+    let item_id = item.id;
+    let csrf_token = generate_csrf_token(session).await;
+    let form_build_id = uuid::Uuid::new_v4().to_string();
+
+    let mut context = tera::Context::new();
+    context.insert("action", &format!("/admin/content/{item_id}/edit"));
+    context.insert("csrf_token", &csrf_token);
+    context.insert("form_build_id", &form_build_id);
+    context.insert("editing", &true);
+    context.insert("item_id", &item_id.to_string());
+    context.insert("content_type", content_type);
+    context.insert("field_sections", &field_sections(content_type));
+    context.insert("item", item);
+    insert_status_context(state, &mut context, item).await;
+    context.insert("errors", errors);
+    context.insert(
+        "values",
+        &serde_json::json!({
+            "title": form.title,
+            "status": form.status.is_some(),
+            "status_reason": form.status_reason.as_deref().unwrap_or(""),
+            "status_note": form.status_note.as_deref().unwrap_or(""),
+            "embargo_until": form.embargo_until.as_deref().unwrap_or(""),
+            "fields": fields_json,
+        }),
+    );
+    let current_path = format!("/admin/content/{item_id}/edit");
+    context.insert("path", &current_path);
+    context.insert(
+        "local_tasks",
+        &build_local_tasks(
+            state,
+            "/admin/content/:id",
+            &current_path,
+            Some(&item_id.to_string()),
+            vec![
+                serde_json::json!({"title": "View", "path": format!("/item/{item_id}"), "active": false}),
+                serde_json::json!({"title": "Edit", "path": &current_path, "active": true}),
+                serde_json::json!({"title": "Revisions", "path": format!("/item/{item_id}/revisions"), "active": false}),
+            ],
+        ),
+    );
+    let alterable = item_form(
+        content_type,
+        item_form_id(true, &item.item_type),
+        &form.title,
+        fields_json,
+    )
+    .action(current_path);
+    insert_form_additions(state, &mut context, user, alterable, &form.fields).await;
+
+    render_admin_template(state, "admin/content-form.html", context).await
+}
+
 /// Show edit content form.
 ///
 /// GET /admin/content/{id}/edit
@@ -349,9 +655,10 @@ async fn edit_content_form(
     session: Session,
     Path(item_id): Path<uuid::Uuid>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     let Some(item) = state.items().load(item_id).await.ok().flatten() else {
         return render_not_found();
@@ -371,16 +678,26 @@ async fn edit_content_form(
     context.insert("editing", &true);
     context.insert("item_id", &item_id.to_string());
     context.insert("content_type", &content_type);
+    context.insert("field_sections", &field_sections(&content_type));
     context.insert("item", &item);
+    insert_status_context(&state, &mut context, &item).await;
     context.insert(
         "values",
         &serde_json::json!({
             "title": item.title,
             "status": item.status == 1,
+            "status_reason": "",
+            "status_note": "",
+            "embargo_until": "",
             "fields": item.fields,
         }),
     );
     context.insert("path", &format!("/admin/content/{item_id}/edit"));
+    let types = state.content_types().list_all().await;
+    context.insert(
+        "has_cascade_rules",
+        &!cascade::dependent_fields(&types, &item.item_type).is_empty(),
+    );
 
     // Local task tabs for item edit pages (hardcoded + plugin-registered)
     let current_path = format!("/admin/content/{item_id}/edit");
@@ -393,16 +710,58 @@ async fn edit_content_form(
             Some(&item_id.to_string()),
             vec![
                 serde_json::json!({"title": "View", "path": format!("/item/{item_id}"), "active": false}),
-                serde_json::json!({"title": "Edit", "path": current_path, "active": true}),
+                serde_json::json!({"title": "Edit", "path": &current_path, "active": true}),
                 serde_json::json!({"title": "Revisions", "path": format!("/item/{item_id}/revisions"), "active": false}),
             ],
         ),
     );
-    context.insert("ai_assist_enabled", &state.is_plugin_enabled("trovato_ai"));
+    let stored = item.fields.as_object().cloned().unwrap_or_default();
+    let alterable = item_form(
+        &content_type,
+        item_form_id(true, &item.item_type),
+        &item.title,
+        &stored,
+    )
+    .action(current_path);
+    let values = field_values(&item.fields);
+    insert_form_additions(&state, &mut context, &user, alterable, &values).await;
 
     render_admin_template(&state, "admin/content-form.html", context).await
 }
 
+/// Add status reason options, the type's reason requirement, and the latest
+/// recorded status change to an edit form context.
+async fn insert_status_context(
+    state: &AppState,
+    context: &mut tera::Context,
+    item: &crate::models::Item,
+) {
+    let require_status_reason = ItemType::find_by_type(state.db(), &item.item_type)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|it| it.requires_status_reason());
+
+    let last_status_change = state
+        .items()
+        .status_changes(item.id, 1)
+        .await
+        .ok()
+        .and_then(|changes| changes.into_iter().next())
+        .map(|c| {
+            serde_json::json!({
+                "reason": c.reason_code.as_deref().and_then(status_reason_label),
+                "note": c.note,
+                "embargo_until": c.embargo_until,
+                "changed": c.changed,
+            })
+        });
+
+    context.insert("reason_options", &status_reason_options());
+    context.insert("require_status_reason", &require_status_reason);
+    context.insert("last_status_change", &last_status_change);
+}
+
 /// Handle edit content form submission.
 ///
 /// POST /admin/content/{id}/edit
@@ -457,56 +816,62 @@ async fn edit_content_submit(
         &content_type.fields,
     ));
 
+    // Status change context: reason, note, and embargo date
+    let new_status: i16 = if form.status.is_some() { 1 } else { 0 };
+    let mut status_meta = None;
+    if new_status != item.status {
+        match parse_status_meta(
+            form.status_reason.as_deref(),
+            form.status_note.as_deref(),
+            form.embargo_until.as_deref(),
+        ) {
+            Ok(meta) => {
+                let required = ItemType::find_by_type(state.db(), &item.item_type)
+                    .await
+                    .ok()
+                    .flatten()
+                    .is_some_and(|it| it.requires_status_reason());
+                if required && meta.reason_code.is_none() {
+                    errors.push(
+                        "A reason is required when publishing or unpublishing this content type."
+                            .to_string(),
+                    );
+                }
+                status_meta = Some(meta);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    // Let plugins validate the submission, including elements they added
+    let form_id = item_form_id(true, &item.item_type);
+    let values = tap_values(&form, &fields_json);
+    let request_state = admin_request_state(&state, &user);
+    errors.extend(plugin_form_errors(&state, &form_id, &values, &request_state).await);
+
     if !errors.is_empty() {
-        let csrf_token = generate_csrf_token(&session).await;
-        let form_build_id = uuid::Uuid::new_v4().to_string();
-
-        let mut context = tera::Context::new();
-        context.insert("action", &format!("/admin/content/{item_id}/edit"));
-        context.insert("csrf_token", &csrf_token);
-        context.insert("form_build_id", &form_build_id);
-        context.insert("editing", &true);
-        context.insert("item_id", &item_id.to_string());
-        context.insert("content_type", &content_type);
-        context.insert("item", &item);
-        context.insert("errors", &errors);
-        context.insert(
-            "values",
-            &serde_json::json!({
-                "title": form.title,
-                "status": form.status.is_some(),
-                "fields": fields_json,
-            }),
-        );
-        let current_path = format!("/admin/content/{item_id}/edit");
-        context.insert("path", &current_path);
-        context.insert(
-            "local_tasks",
-            &build_local_tasks(
-                &state,
-                "/admin/content/:id",
-                &current_path,
-                Some(&item_id.to_string()),
-                vec![
-                    serde_json::json!({"title": "View", "path": format!("/item/{item_id}"), "active": false}),
-                    serde_json::json!({"title": "Edit", "path": &current_path, "active": true}),
-                    serde_json::json!({"title": "Revisions", "path": format!("/item/{item_id}/revisions"), "active": false}),
-                ],
-            ),
-        );
-        context.insert("ai_assist_enabled", &state.is_plugin_enabled("trovato_ai"));
-
-        return render_admin_template(&state, "admin/content-form.html", context).await;
+        return render_edit_form_errors(
+            &state,
+            &session,
+            &user,
+            &item,
+            &content_type,
+            &form,
+            &fields_json,
+            &errors,
+        )
+        .await;
     }
 
     let file_ids = extract_file_ids(&state, &fields_json, &item.item_type);
     let input = crate::models::UpdateItem {
         title: Some(form.title.clone()),
-        status: Some(if form.status.is_some() { 1 } else { 0 }),
+        status: Some(new_status),
         promote: None,
         sticky: None,
-        fields: Some(serde_json::Value::Object(fields_json)),
+        fields: Some(serde_json::Value::Object(fields_json.clone())),
         log: Some("Updated via admin UI".to_string()),
+        status_meta,
     };
 
     let user_ctx = admin_user_context(&user);
@@ -515,16 +880,18 @@ async fn edit_content_submit(
             // Promote temporary file uploads to permanent
             promote_file_ids(&state, &file_ids).await;
 
+            if let Err(e) = state
+                .forms()
+                .submit(&form_id, &values, Some(item_id), &request_state)
+                .await
+            {
+                tracing::warn!(error = %e, item_id = %item_id, "form submit taps failed");
+            }
+
             // Auto-update URL alias from pathauto pattern
             if let Some(ref updated_item) = updated
-                && let Err(e) = crate::services::pathauto::update_alias_item(
-                    state.db(),
-                    updated_item.id,
-                    &updated_item.title,
-                    &updated_item.item_type,
-                    updated_item.created,
-                )
-                .await
+                && let Err(e) =
+                    crate::services::pathauto::update_alias_item(state.db(), updated_item).await
             {
                 tracing::warn!(error = %e, item_id = %item_id, "pathauto alias update failed");
             }
@@ -537,12 +904,24 @@ async fn edit_content_submit(
                     .get(&updated_item.item_type)
                     .map(|ct| ct.label)
                     .unwrap_or_else(|| updated_item.item_type.clone());
-                let msg = format!(
+                let mut msg = format!(
                     "{} <a href=\"/admin/content/{}/edit\">{}</a> has been updated.",
                     html_escape(&type_label),
                     item_id,
                     html_escape(&updated_item.title),
                 );
+                if item.is_published()
+                    && !updated_item.is_published()
+                    && let Some(started) = start_cascade(
+                        &state,
+                        &[(item_id, item.item_type.clone())],
+                        CascadeAction::Unpublish,
+                        &user_ctx,
+                    )
+                    .await
+                {
+                    msg.push_str(&cascade_notice(started));
+                }
                 if let Err(e) = session.insert(CONTENT_FLASH_KEY, &msg).await {
                     tracing::warn!(error = %e, "failed to set flash message");
                 }
@@ -551,6 +930,21 @@ async fn edit_content_submit(
             Redirect::to("/admin/content").into_response()
         }
         Err(e) => {
+            if let Some(failed) = e.downcast_ref::<ItemValidationFailed>() {
+                let mut response = render_edit_form_errors(
+                    &state,
+                    &session,
+                    &user,
+                    &item,
+                    &content_type,
+                    &form,
+                    &fields_json,
+                    &failed.messages(),
+                )
+                .await;
+                *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
+                return response;
+            }
             tracing::error!(error = %e, "failed to update content");
             render_server_error("Failed to update content.")
         }
@@ -575,19 +969,35 @@ async fn delete_content(
         return resp;
     }
 
+    let Some(item) = state.items().load(item_id).await.ok().flatten() else {
+        return render_not_found();
+    };
+
     let user_ctx = admin_user_context(&user);
     match state.items().delete(item_id, &user_ctx).await {
         Ok(true) => {
             tracing::info!(item_id = %item_id, "content deleted");
-            if let Err(e) = session
-                .insert(CONTENT_FLASH_KEY, "Content has been deleted.")
-                .await
+            let mut msg = "Content has been deleted.".to_string();
+            if let Some(started) = start_cascade(
+                &state,
+                &[(item_id, item.item_type)],
+                CascadeAction::Delete,
+                &user_ctx,
+            )
+            .await
             {
+                msg.push_str(&cascade_notice(started));
+            }
+            if let Err(e) = session.insert(CONTENT_FLASH_KEY, msg).await {
                 tracing::warn!(error = %e, "failed to set flash message");
             }
             Redirect::to("/admin/content").into_response()
         }
         Ok(false) => render_not_found(),
+        // The delete preview lists the references that block it.
+        Err(e) if e.downcast_ref::<DeleteRestricted>().is_some() => {
+            Redirect::to(&format!("/admin/content/{item_id}/cascade?action=delete")).into_response()
+        }
         Err(e) => {
             tracing::error!(error = %e, "failed to delete content");
             render_server_error("Failed to delete content.")
@@ -603,6 +1013,10 @@ struct BulkActionForm {
     action: String,
     #[serde(rename = "ids[]", default)]
     ids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
 }
 
 /// Bulk operations on content items.
@@ -649,10 +1063,29 @@ async fn bulk_content_action(
 
     let mut success_count = 0u32;
     let mut fail_count = 0u32;
+    // Items whose unpublish or delete may cascade to referencing content
+    let mut cascade_sources: Vec<(uuid::Uuid, String)> = Vec::new();
     match form.action.as_str() {
         "publish" | "unpublish" => {
             let new_status: i16 = if form.action == "publish" { 1 } else { 0 };
+            let status_meta =
+                match parse_status_meta(form.reason.as_deref(), form.note.as_deref(), None) {
+                    Ok(meta) => meta,
+                    Err(e) => {
+                        if let Err(e) = session.insert(CONTENT_FLASH_KEY, html_escape(&e)).await {
+                            tracing::warn!(error = %e, "failed to set flash message");
+                        }
+                        return Redirect::to("/admin/content").into_response();
+                    }
+                };
             for id in &form.ids {
+                let was_published = state
+                    .items()
+                    .load(*id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .filter(|item| item.is_published());
                 let update = crate::models::UpdateItem {
                     title: None,
                     status: Some(new_status),
@@ -660,9 +1093,17 @@ async fn bulk_content_action(
                     promote: None,
                     sticky: None,
                     log: Some(format!("Bulk {}", form.action)),
+                    status_meta: Some(status_meta.clone()),
                 };
                 match state.items().update(*id, update, &user_ctx).await {
-                    Ok(_) => success_count += 1,
+                    Ok(_) => {
+                        success_count += 1;
+                        if new_status == 0
+                            && let Some(item) = was_published
+                        {
+                            cascade_sources.push((item.id, item.item_type));
+                        }
+                    }
                     Err(e) => {
                         tracing::warn!(item_id = %id, error = %e, "bulk action failed");
                         fail_count += 1;
@@ -672,8 +1113,20 @@ async fn bulk_content_action(
         }
         "delete" => {
             for id in &form.ids {
+                let item_type = state
+                    .items()
+                    .load(*id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|item| item.item_type);
                 match state.items().delete(*id, &user_ctx).await {
-                    Ok(true) => success_count += 1,
+                    Ok(true) => {
+                        success_count += 1;
+                        if let Some(item_type) = item_type {
+                            cascade_sources.push((*id, item_type));
+                        }
+                    }
                     Ok(false) => fail_count += 1,
                     Err(e) => {
                         tracing::warn!(item_id = %id, error = %e, "bulk delete failed");
@@ -691,11 +1144,21 @@ async fn bulk_content_action(
         "delete" => "deleted",
         _ => unreachable!(),
     };
-    let msg = if fail_count > 0 {
+    let mut msg = if fail_count > 0 {
         format!("{success_count} item(s) {action_label}. {fail_count} item(s) failed.")
     } else {
         format!("{success_count} item(s) {action_label}.")
     };
+    let trigger = if form.action == "delete" {
+        CascadeAction::Delete
+    } else {
+        CascadeAction::Unpublish
+    };
+    if !cascade_sources.is_empty()
+        && let Some(started) = start_cascade(&state, &cascade_sources, trigger, &user_ctx).await
+    {
+        msg.push_str(&cascade_notice(started));
+    }
     if let Err(e) = session.insert(CONTENT_FLASH_KEY, msg).await {
         tracing::warn!(error = %e, "failed to set flash message");
     }
@@ -703,6 +1166,136 @@ async fn bulk_content_action(
     Redirect::to("/admin/content").into_response()
 }
 
+/// Flash message suffix linking to a started cascade's progress page.
+fn cascade_notice((batch_id, count): (uuid::Uuid, usize)) -> String {
+    format!(
+        " {count} referencing item(s) are being updated. \
+         <a href=\"/admin/content/cascade/{batch_id}\">View progress</a>."
+    )
+}
+
+/// Query parameters for the cascade preview.
+#[derive(Debug, Deserialize)]
+struct CascadePreviewQuery {
+    #[serde(default)]
+    action: Option<String>,
+}
+
+/// Preview the referencing content an unpublish or delete would change.
+///
+/// Dry run: the cascade is planned from the reference fields' rules but
+/// nothing is modified. The page offers the action itself as a button,
+/// unless `restrict` references block the deletion; those are listed.
+///
+/// GET /admin/content/{id}/cascade?action=unpublish|delete
+async fn cascade_preview(
+    State(state): State<AppState>,
+    session: Session,
+    Path(item_id): Path<uuid::Uuid>,
+    Query(query): Query<CascadePreviewQuery>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let Some(item) = state.items().load(item_id).await.ok().flatten() else {
+        return render_not_found();
+    };
+
+    let action = query
+        .action
+        .as_deref()
+        .and_then(CascadeAction::parse)
+        .unwrap_or(CascadeAction::Unpublish);
+
+    let types = state.content_types().list_all().await;
+    let plan = match cascade::plan(
+        state.db(),
+        &types,
+        &[(item.id, item.item_type.clone())],
+        action,
+    )
+    .await
+    {
+        Ok(plan) => plan,
+        Err(e) => {
+            tracing::error!(error = %e, item_id = %item_id, "failed to plan reference cascade");
+            return render_server_error("Failed to preview dependent content.");
+        }
+    };
+
+    let csrf_token = generate_csrf_token(&session).await;
+
+    let mut context = tera::Context::new();
+    context.insert("item", &item);
+    context.insert("action", action.as_str());
+    context.insert("steps", &plan.steps);
+    context.insert("truncated", &plan.truncated);
+    context.insert("unpublish_count", &plan.unpublish_count());
+    context.insert("delete_count", &plan.delete_count());
+    context.insert("clear_count", &plan.clear_count());
+    context.insert("restricted", &plan.restricted);
+    context.insert("reason_options", &status_reason_options());
+    context.insert("csrf_token", &csrf_token);
+    context.insert("path", &format!("/admin/content/{item_id}/cascade"));
+
+    render_admin_template(&state, "admin/content-cascade.html", context).await
+}
+
+/// Show the progress and outcome of a reference cascade.
+///
+/// GET /admin/content/cascade/{id}
+async fn cascade_progress(
+    State(state): State<AppState>,
+    session: Session,
+    Path(batch_id): Path<uuid::Uuid>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let operation = match state.batch().get(batch_id).await {
+        Ok(Some(op)) if op.operation_type == cascade::CASCADE_OPERATION => op,
+        Ok(_) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, batch_id = %batch_id, "failed to load cascade batch");
+            return render_server_error("Failed to load cascade progress.");
+        }
+    };
+
+    let mut context = tera::Context::new();
+    context.insert("active", &operation.status.is_active());
+    context.insert("operation", &operation);
+    context.insert("path", &format!("/admin/content/cascade/{batch_id}"));
+
+    render_admin_template(&state, "admin/content-cascade-progress.html", context).await
+}
+
+/// List references to content that no longer exists.
+///
+/// Shows what the last `check_dangling_references` cron run found.
+///
+/// GET /admin/content/references
+async fn dangling_references(State(state): State<AppState>, session: Session) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let references = match cascade::list_dangling(state.db()).await {
+        Ok(references) => references,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list dangling references");
+            return render_server_error("Failed to load dangling references.");
+        }
+    };
+
+    let mut context = tera::Context::new();
+    context.insert("references", &references);
+    context.insert("path", "/admin/content/references");
+
+    render_admin_template(&state, "admin/content-dangling-references.html", context).await
+}
+
 /// Build admin content routes.
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -717,5 +1310,8 @@ pub fn router() -> Router<AppState> {
             get(edit_content_form).post(edit_content_submit),
         )
         .route("/admin/content/{id}/delete", post(delete_content))
+        .route("/admin/content/{id}/cascade", get(cascade_preview))
+        .route("/admin/content/cascade/{id}", get(cascade_progress))
+        .route("/admin/content/references", get(dangling_references))
         .route("/admin/content/bulk", post(bulk_content_action))
 }
@@ -1,20 +1,31 @@
 //! Admin routes for content type management.
 
 use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Redirect, Response};
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::{Form, Json, Router};
 use serde::Deserialize;
 use tower_sessions::Session;
+use uuid::Uuid;
 
+use crate::config_storage::ConfigEntity;
+use crate::error::AppError;
 use crate::form::csrf::generate_csrf_token;
+use crate::models::{CreateFieldDefaultRule, FieldDefaultRule, FieldDefaultValue, ItemType, User};
+use crate::services::cascade::{CASCADE_SETTING, CascadeRule};
 use crate::state::AppState;
+use trovato_sdk::types::{ContentTypeDefinition, FieldDefinition, FieldType};
 
 use super::helpers::{
-    CsrfOnlyForm, MACHINE_NAME_ERROR, html_escape, is_valid_machine_name, render_admin_template,
-    render_error, render_not_found, render_server_error, require_admin, require_csrf,
+    CsrfOnlyForm, MACHINE_NAME_ERROR, admin_user_context, config_violation_messages, html_escape,
+    is_valid_machine_name, render_admin_template, render_error, render_not_found,
+    render_server_error, require_admin, require_admin_json, require_csrf, require_csrf_header,
 };
 
+/// Session key for flash messages on the manage fields page.
+const FIELDS_FLASH_KEY: &str = "content_type_fields_flash";
+
 // =============================================================================
 // Form data
 // =============================================================================
@@ -32,6 +43,7 @@ struct ContentTypeFormData {
     title_label: Option<String>,
     published_default: Option<String>,
     revision_default: Option<String>,
+    require_status_reason: Option<String>,
 }
 
 /// Field form data.
@@ -61,6 +73,34 @@ struct SearchConfigFormData {
 // Content Type Management
 // =============================================================================
 
+/// Settings saved for a submitted content type form.
+fn type_settings(form: &ContentTypeFormData) -> serde_json::Value {
+    serde_json::json!({
+        "title_label": form.title_label.clone().unwrap_or_else(|| "Title".to_string()),
+        "published_default": form.published_default.is_some(),
+        "revision_default": form.revision_default.is_some(),
+        "require_status_reason": form.require_status_reason.is_some(),
+    })
+}
+
+/// The `item_type` config entity a submitted form would save, for
+/// `tap_config_validate`.
+fn type_entity(
+    type_name: &str,
+    form: &ContentTypeFormData,
+    settings: &serde_json::Value,
+) -> ConfigEntity {
+    ConfigEntity::ItemType(ItemType {
+        type_name: type_name.to_string(),
+        label: form.label.clone(),
+        description: form.description.clone(),
+        has_title: true,
+        title_label: settings["title_label"].as_str().map(str::to_string),
+        plugin: "core".to_string(),
+        settings: settings.clone(),
+    })
+}
+
 /// List all content types.
 ///
 /// GET /admin/structure/types
@@ -138,6 +178,12 @@ async fn add_content_type_submit(
         ));
     }
 
+    let settings = type_settings(&form);
+    if errors.is_empty() {
+        let entity = type_entity(&form.machine_name, &form, &settings);
+        errors.extend(config_violation_messages(&state, &[entity]).await);
+    }
+
     if !errors.is_empty() {
         let csrf_token = generate_csrf_token(&session).await;
         let form_build_id = uuid::Uuid::new_v4().to_string();
@@ -157,6 +203,7 @@ async fn add_content_type_submit(
                 "title_label": form.title_label,
                 "published_default": form.published_default.is_some(),
                 "revision_default": form.revision_default.is_some(),
+                "require_status_reason": form.require_status_reason.is_some(),
             }),
         );
         context.insert("path", "/admin/structure/types/add");
@@ -165,12 +212,6 @@ async fn add_content_type_submit(
     }
 
     // Create the content type
-    let settings = serde_json::json!({
-        "title_label": form.title_label.unwrap_or_else(|| "Title".to_string()),
-        "published_default": form.published_default.is_some(),
-        "revision_default": form.revision_default.is_some(),
-    });
-
     match state
         .content_types()
         .create(
@@ -224,7 +265,7 @@ async fn edit_content_type_form(
         .await
         .ok()
         .flatten();
-    let (title_label, published_default, revision_default) = match &db_type {
+    let (title_label, published_default, revision_default, require_status_reason) = match &db_type {
         Some(it) => (
             it.title_label.as_deref().unwrap_or("Title"),
             it.settings
@@ -235,8 +276,9 @@ async fn edit_content_type_form(
                 .get("revision_default")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false),
+            it.requires_status_reason(),
         ),
-        None => ("Title", false, false),
+        None => ("Title", false, false, false),
     };
 
     context.insert(
@@ -248,6 +290,7 @@ async fn edit_content_type_form(
             "title_label": title_label,
             "published_default": published_default,
             "revision_default": revision_default,
+            "require_status_reason": require_status_reason,
         }),
     );
     context.insert("path", &format!("/admin/structure/types/{type_name}/edit"));
@@ -264,16 +307,17 @@ async fn edit_content_type_submit(
     Path(type_name): Path<String>,
     Form(form): Form<ContentTypeFormData>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     // Verify CSRF token
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
-    let Some(_content_type) = state.content_types().get(&type_name) else {
+    let Some(old) = state.content_types().get(&type_name) else {
         return render_not_found();
     };
 
@@ -284,6 +328,12 @@ async fn edit_content_type_submit(
         errors.push("Name is required.".to_string());
     }
 
+    let settings = type_settings(&form);
+    if errors.is_empty() {
+        let entity = type_entity(&type_name, &form, &settings);
+        errors.extend(config_violation_messages(&state, &[entity]).await);
+    }
+
     if !errors.is_empty() {
         let csrf_token = generate_csrf_token(&session).await;
         let form_build_id = uuid::Uuid::new_v4().to_string();
@@ -306,6 +356,7 @@ async fn edit_content_type_submit(
                 "title_label": form.title_label,
                 "published_default": form.published_default.is_some(),
                 "revision_default": form.revision_default.is_some(),
+                "require_status_reason": form.require_status_reason.is_some(),
             }),
         );
         context.insert("path", &format!("/admin/structure/types/{type_name}/edit"));
@@ -314,12 +365,6 @@ async fn edit_content_type_submit(
     }
 
     // Update the content type
-    let settings = serde_json::json!({
-        "title_label": form.title_label.unwrap_or_else(|| "Title".to_string()),
-        "published_default": form.published_default.is_some(),
-        "revision_default": form.revision_default.is_some(),
-    });
-
     match state
         .content_types()
         .update(
@@ -332,6 +377,7 @@ async fn edit_content_type_submit(
     {
         Ok(_) => {
             tracing::info!(machine_name = %type_name, "content type updated");
+            after_type_update(&state, &user, old).await;
             Redirect::to("/admin/structure/types").into_response()
         }
         Err(e) => {
@@ -359,6 +405,14 @@ async fn manage_fields(
         return render_not_found();
     };
 
+    // Read and clear flash message
+    let flash: Option<String> = session.get(FIELDS_FLASH_KEY).await.ok().flatten();
+    if flash.is_some()
+        && let Err(e) = session.remove::<String>(FIELDS_FLASH_KEY).await
+    {
+        tracing::warn!(error = %e, "failed to clear flash message");
+    }
+
     let csrf_token = generate_csrf_token(&session).await;
     let form_build_id = uuid::Uuid::new_v4().to_string();
 
@@ -374,6 +428,7 @@ async fn manage_fields(
     context.insert("fields", &content_type.fields);
     context.insert("csrf_token", &csrf_token);
     context.insert("form_build_id", &form_build_id);
+    context.insert("flash", &flash);
     context.insert(
         "path",
         &format!("/admin/structure/types/{type_name}/fields"),
@@ -391,16 +446,17 @@ async fn add_field(
     Path(type_name): Path<String>,
     Form(form): Form<FieldFormData>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     // Verify CSRF token
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
-    let Some(_content_type) = state.content_types().get(&type_name) else {
+    let Some(old) = state.content_types().get(&type_name) else {
         return render_not_found();
     };
 
@@ -471,6 +527,7 @@ async fn add_field(
                 field = %form.name,
                 "field added"
             );
+            after_type_update(&state, &user, old).await;
             Redirect::to(&format!("/admin/structure/types/{type_name}/fields")).into_response()
         }
         Err(e) => {
@@ -494,6 +551,21 @@ struct FieldEditFormData {
     label: String,
     required: Option<String>,
     cardinality: Option<i32>,
+    /// Cascade rule; only submitted for record reference fields.
+    cascade: Option<String>,
+}
+
+/// Add the cascade rule selector for record reference fields.
+fn insert_cascade_context(context: &mut tera::Context, field: &FieldDefinition) {
+    if !matches!(field.field_type, FieldType::RecordReference(_)) {
+        return;
+    }
+    let options: Vec<serde_json::Value> = CascadeRule::ALL
+        .iter()
+        .map(|r| serde_json::json!({"value": r.as_str(), "label": r.label()}))
+        .collect();
+    context.insert("cascade_options", &options);
+    context.insert("cascade", CascadeRule::for_field(field).as_str());
 }
 
 /// Show field edit form.
@@ -528,6 +600,7 @@ async fn edit_field_form(
     context.insert("field", field);
     context.insert("csrf_token", &csrf_token);
     context.insert("form_build_id", &form_build_id);
+    insert_cascade_context(&mut context, field);
     context.insert(
         "path",
         &format!("/admin/structure/types/{type_name}/fields/{field_name}/edit"),
@@ -545,9 +618,10 @@ async fn edit_field_submit(
     Path((type_name, field_name)): Path<(String, String)>,
     Form(form): Form<FieldEditFormData>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
@@ -577,6 +651,18 @@ async fn edit_field_submit(
         errors.push("Number of values must be 1 or more, or -1 for unlimited.".to_string());
     }
 
+    let mut settings = serde_json::Map::new();
+    if matches!(field.field_type, FieldType::RecordReference(_))
+        && let Some(value) = form.cascade.as_deref()
+    {
+        match CascadeRule::parse(value) {
+            Some(rule) => {
+                settings.insert(CASCADE_SETTING.to_string(), rule.as_str().into());
+            }
+            None => errors.push("Unknown cascade rule.".to_string()),
+        }
+    }
+
     if !errors.is_empty() {
         let csrf_token = generate_csrf_token(&session).await;
         let form_build_id = uuid::Uuid::new_v4().to_string();
@@ -586,6 +672,9 @@ async fn edit_field_submit(
         edited_field.label = form.label;
         edited_field.required = form.required.is_some();
         edited_field.cardinality = cardinality;
+        if !settings.is_empty() {
+            edited_field.settings = settings.into();
+        }
 
         let mut context = tera::Context::new();
         context.insert("type_name", &type_name);
@@ -593,6 +682,7 @@ async fn edit_field_submit(
         context.insert("csrf_token", &csrf_token);
         context.insert("form_build_id", &form_build_id);
         context.insert("errors", &errors);
+        insert_cascade_context(&mut context, &edited_field);
         context.insert(
             "path",
             &format!("/admin/structure/types/{type_name}/fields/{field_name}/edit"),
@@ -605,7 +695,14 @@ async fn edit_field_submit(
 
     match state
         .content_types()
-        .update_field(&type_name, &field_name, &form.label, required, cardinality)
+        .update_field(
+            &type_name,
+            &field_name,
+            &form.label,
+            required,
+            cardinality,
+            settings,
+        )
         .await
     {
         Ok(_) => {
@@ -615,6 +712,7 @@ async fn edit_field_submit(
                 required = %required,
                 "field updated"
             );
+            after_type_update(&state, &user, content_type).await;
             Redirect::to(&format!("/admin/structure/types/{type_name}/fields")).into_response()
         }
         Err(e) => {
@@ -633,15 +731,16 @@ async fn delete_field_submit(
     Path((type_name, field_name)): Path<(String, String)>,
     Form(form): Form<CsrfOnlyForm>,
 ) -> Response {
-    if let Err(redirect) = require_admin(&state, &session).await {
-        return redirect;
-    }
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
 
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
-    let Some(_content_type) = state.content_types().get(&type_name) else {
+    let Some(old) = state.content_types().get(&type_name) else {
         return render_not_found();
     };
 
@@ -656,6 +755,11 @@ async fn delete_field_submit(
                 field = %field_name,
                 "field deleted"
             );
+            if let Some(msg) = after_type_update(&state, &user, old).await
+                && let Err(e) = session.insert(FIELDS_FLASH_KEY, &msg).await
+            {
+                tracing::warn!(error = %e, "failed to set flash message");
+            }
             Redirect::to(&format!("/admin/structure/types/{type_name}/fields")).into_response()
         }
         Err(e) => {
@@ -665,6 +769,53 @@ async fn delete_field_submit(
     }
 }
 
+/// Run the kernel's post-change tasks and `tap_item_type_update` after an
+/// admin edit of a content type.
+///
+/// The edit is already saved, so failures are only logged. Returns a flash
+/// message if items still hold data for removed fields.
+async fn after_type_update(
+    state: &AppState,
+    user: &User,
+    old: ContentTypeDefinition,
+) -> Option<String> {
+    let type_name = old.machine_name.clone();
+    let tap_state =
+        crate::tap::RequestState::new(admin_user_context(user), state.tap_services().clone());
+
+    let report = match state
+        .content_types()
+        .after_update(old, state.tap_dispatcher(), tap_state)
+        .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!(content_type = %type_name, error = %e, "content type update tasks failed");
+            return None;
+        }
+    };
+
+    if report.orphaned.is_empty() {
+        return None;
+    }
+    let counts: Vec<String> = report
+        .orphaned
+        .iter()
+        .map(|o| {
+            let noun = if o.item_count == 1 { "item" } else { "items" };
+            format!(
+                "<code>{}</code> ({} {noun})",
+                html_escape(&o.field_name),
+                o.item_count
+            )
+        })
+        .collect();
+    Some(format!(
+        "Existing content still contains data for removed fields: {}. The data is kept but no longer shown or indexed.",
+        counts.join(", ")
+    ))
+}
+
 // =============================================================================
 // Search Configuration
 // =============================================================================
@@ -808,7 +959,10 @@ async fn remove_search_config(
     }
 }
 
-/// Reindex all content of a specific type.
+/// Queue all content of a specific type for background reindexing.
+///
+/// Cron rebuilds the queued items in batches; progress is reported at
+/// `/admin/config/search/status`.
 ///
 /// POST /admin/structure/types/{type}/search/reindex
 async fn reindex_content_type(
@@ -829,18 +983,18 @@ async fn reindex_content_type(
         return render_not_found();
     };
 
-    match state.search().reindex_bundle(&type_name).await {
+    match state.search().queue_reindex_bundle(&type_name).await {
         Ok(count) => {
             tracing::info!(
                 content_type = %type_name,
                 count = %count,
-                "content type reindexed"
+                "content type queued for reindex"
             );
             Redirect::to(&format!("/admin/structure/types/{type_name}/search")).into_response()
         }
         Err(e) => {
-            tracing::error!(error = %e, "failed to reindex content type");
-            render_server_error("Failed to reindex content.")
+            tracing::error!(error = %e, "failed to queue content type reindex");
+            render_server_error("Failed to queue content for reindexing.")
         }
     }
 }
@@ -852,6 +1006,7 @@ async fn reindex_content_type(
 /// Handle AJAX add_field trigger for manage_fields forms.
 pub(crate) async fn handle_ajax_add_field(
     state: &AppState,
+    user: &User,
     request: &crate::form::AjaxRequest,
 ) -> Response {
     use crate::form::{AjaxCommand, AjaxResponse};
@@ -912,6 +1067,10 @@ pub(crate) async fn handle_ajax_add_field(
         return Json(AjaxResponse::new().alert("Field type is required.")).into_response();
     }
 
+    let Some(old) = state.content_types().get(type_name) else {
+        return Json(AjaxResponse::new().alert("Content type not found.")).into_response();
+    };
+
     // Add the field
     if let Err(e) = state
         .content_types()
@@ -923,6 +1082,7 @@ pub(crate) async fn handle_ajax_add_field(
     }
 
     tracing::info!(content_type = %type_name, field = %name, "field added via AJAX");
+    after_type_update(state, user, old).await;
 
     // Build the new row HTML
     let row_html = format!(
@@ -953,6 +1113,105 @@ pub(crate) async fn handle_ajax_add_field(
     .into_response()
 }
 
+// =============================================================================
+// Field default rules (JSON)
+// =============================================================================
+
+/// List the role-conditional field defaults of a content type.
+///
+/// GET /admin/structure/types/{type}/field-defaults
+async fn list_field_defaults(
+    State(state): State<AppState>,
+    session: Session,
+    Path(type_name): Path<String>,
+) -> Result<Json<Vec<FieldDefaultRule>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    if !state.content_types().exists(&type_name) {
+        return Err(AppError::not_found_id("content type", &type_name));
+    }
+    let rules = FieldDefaultRule::list_for_type(state.db(), &type_name)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list field defaults"))?;
+
+    Ok(Json(rules))
+}
+
+/// Add a role-conditional field default to a content type.
+///
+/// POST /admin/structure/types/{type}/field-defaults
+async fn add_field_default(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(type_name): Path<String>,
+    Json(input): Json<CreateFieldDefaultRule>,
+) -> Result<(StatusCode, Json<FieldDefaultRule>), AppError> {
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    require_admin_json(&state, &session).await?;
+
+    let Some(content_type) = state.content_types().get(&type_name) else {
+        return Err(AppError::not_found_id("content type", &type_name));
+    };
+    if !content_type
+        .fields
+        .iter()
+        .any(|f| f.field_name == input.field_name)
+    {
+        return Err(AppError::bad_request(format!(
+            "{type_name} has no field named {}",
+            input.field_name
+        )));
+    }
+    if let FieldDefaultValue::CreatorField { field } = &input.default_value
+        && field.trim().is_empty()
+    {
+        return Err(AppError::bad_request("creator_field needs a field name"));
+    }
+    if let Some(role_id) = input.role_id {
+        state
+            .roles()
+            .find_by_id(role_id)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load role"))?
+            .ok_or_else(|| AppError::not_found_id("role", role_id))?;
+    }
+
+    let rule = FieldDefaultRule::create(state.db(), &type_name, input)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "create field default"))?;
+
+    tracing::info!(content_type = %type_name, field = %rule.field_name, "field default added");
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Remove a field default from a content type.
+///
+/// DELETE /admin/structure/types/{type}/field-defaults/{id}
+async fn delete_field_default(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((type_name, id)): Path<(String, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    require_admin_json(&state, &session).await?;
+
+    let deleted = FieldDefaultRule::delete(state.db(), &type_name, id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete field default"))?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::not_found_id("field default", id))
+    }
+}
+
 // =============================================================================
 // Router
 // =============================================================================
@@ -995,4 +1254,12 @@ pub fn router() -> Router<AppState> {
             "/admin/structure/types/{type}/search/reindex",
             post(reindex_content_type),
         )
+        .route(
+            "/admin/structure/types/{type}/field-defaults",
+            get(list_field_defaults).post(add_field_default),
+        )
+        .route(
+            "/admin/structure/types/{type}/field-defaults/{id}",
+            delete(delete_field_default),
+        )
 }
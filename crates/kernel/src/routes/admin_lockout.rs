@@ -0,0 +1,121 @@
+//! Login lockout administration (admin only).
+//!
+//! - `GET /admin/people/lockouts` — settings and active account and IP locks
+//! - `POST /admin/people/lockouts/settings` — replace thresholds and allowlist
+//! - `DELETE /admin/people/lockouts/{scope}/{subject}` — lift a lock
+//!   (`scope` is `account` or `ip`)
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::lockout::{ActiveLockout, LockoutScope, LockoutSettings};
+use crate::state::AppState;
+
+use super::helpers::{require_admin_json, require_csrf_header};
+
+/// Create the lockout admin router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/people/lockouts", get(list_lockouts))
+        .route("/admin/people/lockouts/settings", post(update_settings))
+        .route(
+            "/admin/people/lockouts/{scope}/{subject}",
+            delete(clear_lockout),
+        )
+}
+
+/// Lockout settings with the locks currently in force.
+#[derive(Debug, Serialize)]
+struct LockoutStatus {
+    settings: LockoutSettings,
+    lockouts: Vec<ActiveLockout>,
+}
+
+/// List settings and active lockouts.
+///
+/// GET /admin/people/lockouts
+async fn list_lockouts(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<LockoutStatus>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let lockouts = state
+        .lockout()
+        .active_lockouts()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list lockouts"))?;
+
+    Ok(Json(LockoutStatus {
+        settings: state.lockout().settings().await.as_ref().clone(),
+        lockouts,
+    }))
+}
+
+/// Replace lockout settings.
+///
+/// POST /admin/people/lockouts/settings
+async fn update_settings(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(settings): Json<LockoutSettings>,
+) -> Result<Json<LockoutSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    settings.validate().map_err(|(field, message)| {
+        AppError::validation(vec![AppError::field_error(field, "invalid", message)])
+    })?;
+
+    state
+        .lockout()
+        .save_settings(&settings)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save lockout settings"))?;
+
+    tracing::warn!(
+        max_attempts = settings.max_attempts,
+        ip_max_attempts = settings.ip_max_attempts,
+        ip_allowlist = ?settings.ip_allowlist,
+        "lockout settings changed"
+    );
+
+    Ok(Json(settings))
+}
+
+/// Lift an account or IP lock and reset its failed attempts.
+///
+/// DELETE /admin/people/lockouts/{scope}/{subject}
+async fn clear_lockout(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((scope, subject)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let scope: LockoutScope = scope
+        .parse()
+        .map_err(|_| AppError::bad_request("scope must be 'account' or 'ip'"))?;
+
+    state
+        .lockout()
+        .clear(scope, &subject)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "clear lockout"))?;
+
+    tracing::info!(scope = ?scope, subject = %subject, "lockout cleared by admin");
+
+    Ok(StatusCode::NO_CONTENT)
+}
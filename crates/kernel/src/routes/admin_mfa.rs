@@ -0,0 +1,171 @@
+//! Admin routes for two-factor authentication: role enforcement and resets.
+
+use std::collections::HashMap;
+
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Form, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::form::csrf::generate_csrf_token;
+use crate::models::Role;
+use crate::models::role::well_known::ANONYMOUS_ROLE_ID;
+use crate::services::mfa::{self, ADMINISTER_MFA_PERMISSION, UserMfa};
+use crate::state::AppState;
+
+use super::helpers::{
+    CsrfOnlyForm, render_admin_template, render_not_found, render_server_error, require_csrf,
+    require_permission,
+};
+
+/// Enforcement form: one `role_<id>` checkbox per role.
+#[derive(Debug, Deserialize)]
+struct EnforcementFormData {
+    #[serde(rename = "_token")]
+    token: String,
+    #[serde(flatten)]
+    roles: HashMap<String, String>,
+}
+
+/// Enrolled user display struct for templates.
+#[derive(Debug, Serialize)]
+struct EnrollmentDisplay {
+    user_id: Uuid,
+    name: String,
+    enabled_display: String,
+}
+
+/// Two-factor authentication overview.
+///
+/// GET /admin/people/mfa
+async fn mfa_settings(State(state): State<AppState>, session: Session) -> Response {
+    if let Err(redirect) = require_permission(&state, &session, ADMINISTER_MFA_PERMISSION).await {
+        return redirect;
+    }
+
+    let roles = match Role::list(state.db()).await {
+        Ok(roles) => roles
+            .into_iter()
+            .filter(|r| r.id != ANONYMOUS_ROLE_ID)
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list roles");
+            return render_server_error("Failed to load roles.");
+        }
+    };
+    let required = match mfa::required_roles(state.db()).await {
+        Ok(required) => required,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load 2FA required roles");
+            return render_server_error("Failed to load two-factor settings.");
+        }
+    };
+    let enrollments = match UserMfa::list_enabled(state.db()).await {
+        Ok(enrollments) => enrollments,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list 2FA enrollments");
+            return render_server_error("Failed to load two-factor settings.");
+        }
+    };
+
+    let enrollments: Vec<EnrollmentDisplay> = enrollments
+        .into_iter()
+        .map(|e| EnrollmentDisplay {
+            user_id: e.user_id,
+            name: e.name,
+            enabled_display: chrono::DateTime::from_timestamp(e.changed, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+        })
+        .collect();
+
+    let csrf_token = generate_csrf_token(&session).await;
+
+    let mut context = tera::Context::new();
+    context.insert("roles", &roles);
+    context.insert("required_roles", &required);
+    context.insert("enrollments", &enrollments);
+    context.insert("csrf_token", &csrf_token);
+    context.insert("path", "/admin/people/mfa");
+
+    render_admin_template(&state, "admin/mfa-settings.html", context).await
+}
+
+/// Save the roles that must use two-factor authentication.
+///
+/// POST /admin/people/mfa
+async fn save_mfa_settings(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<EnforcementFormData>,
+) -> Response {
+    if let Err(redirect) = require_permission(&state, &session, ADMINISTER_MFA_PERMISSION).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let roles: Vec<Uuid> = form
+        .roles
+        .keys()
+        .filter_map(|key| key.strip_prefix("role_"))
+        .filter_map(|id| id.parse().ok())
+        .filter(|id| *id != ANONYMOUS_ROLE_ID)
+        .collect();
+
+    match mfa::set_required_roles(state.db(), &roles).await {
+        Ok(()) => {
+            tracing::info!(roles = roles.len(), "2FA required roles updated");
+            Redirect::to("/admin/people/mfa").into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "failed to save 2FA required roles");
+            render_server_error("Failed to save two-factor settings.")
+        }
+    }
+}
+
+/// Remove a user's authenticator and recovery codes (lost device).
+///
+/// POST /admin/people/{id}/mfa/reset
+async fn reset_user_mfa(
+    State(state): State<AppState>,
+    session: Session,
+    Path(user_id): Path<Uuid>,
+    Form(form): Form<CsrfOnlyForm>,
+) -> Response {
+    let admin = match require_permission(&state, &session, ADMINISTER_MFA_PERMISSION).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    match UserMfa::disable(state.db(), user_id).await {
+        Ok(true) => {
+            tracing::info!(user_id = %user_id, admin_id = %admin.id, "2FA reset by administrator");
+            Redirect::to("/admin/people/mfa").into_response()
+        }
+        Ok(false) => render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to reset 2FA");
+            render_server_error("Failed to reset two-factor authentication.")
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/admin/people/mfa",
+            get(mfa_settings).post(save_mfa_settings),
+        )
+        .route("/admin/people/{id}/mfa/reset", post(reset_user_mfa))
+}
@@ -1,8 +1,10 @@
 //! Admin routes for pathauto (automatic path alias) configuration.
 //!
 //! Provides settings and bulk-regeneration endpoints for the pathauto
-//! pattern system. Patterns are stored as a JSON object in `site_config`
-//! under the key `pathauto_patterns`.
+//! pattern system. Patterns are `alias_pattern` config entities; this page
+//! edits each content type's general pattern; language patterns are
+//! listed and managed through config import. The page also edits the
+//! site-wide [`SlugOptions`] used when tokens are slugified.
 
 use std::collections::HashMap;
 
@@ -13,9 +15,11 @@ use axum::{Form, Router};
 use serde::Deserialize;
 use tower_sessions::Session;
 
+use crate::config_storage::{ConfigEntity, ConfigValidationFailed, entity_types};
 use crate::form::csrf::generate_csrf_token;
-use crate::models::{Item, SiteConfig};
+use crate::models::{AliasPattern, Item};
 use crate::services::pathauto::update_alias_item;
+use crate::services::slug::SlugOptions;
 use crate::state::AppState;
 
 use super::helpers::{
@@ -31,18 +35,9 @@ const FLASH_KEY: &str = "pathauto_flash";
 /// Regeneration is synchronous and runs one DB round-trip per item, so an
 /// unbounded operation would block an Axum worker thread for an arbitrary
 /// amount of time and exhaust memory on large datasets. Administrators with
-/// sites that exceed this limit should run regeneration via the CLI.
+/// sites that exceed this limit should run `trovato alias regenerate`.
 const MAX_REGENERATE_ITEMS: usize = 10_000;
 
-/// Path prefixes that the alias middleware explicitly skips.
-///
-/// Patterns that expand to one of these prefixes generate aliases that are
-/// never resolved by the middleware, and may shadow system routes. They are
-/// rejected at save time to prevent administrator confusion.
-const RESERVED_PATTERN_PREFIXES: &[&str] = &[
-    "admin/", "api/", "user/", "item/", "static/", "install/", "system/", "health",
-];
-
 // =============================================================================
 // Form data
 // =============================================================================
@@ -57,6 +52,19 @@ struct RegenerateForm {
     item_type: String,
 }
 
+/// Slug settings form.
+///
+/// Posted to `POST /admin/config/pathauto/slug`.
+#[derive(Debug, Deserialize)]
+struct SlugSettingsForm {
+    #[serde(rename = "_token")]
+    token: String,
+    separator: char,
+    max_length: usize,
+    /// Checkbox: present only when checked.
+    remove_stopwords: Option<String>,
+}
+
 // =============================================================================
 // Handlers
 // =============================================================================
@@ -74,34 +82,57 @@ async fn pathauto_config_page(State(state): State<AppState>, session: Session) -
 
     let content_types = state.content_types().list_all().await;
 
-    // Load existing patterns map from site_config.
-    let patterns_json = match SiteConfig::get(state.db(), "pathauto_patterns").await {
-        Ok(v) => v.unwrap_or(serde_json::json!({})),
+    let all_patterns: Vec<AliasPattern> = match state
+        .config_storage()
+        .list(entity_types::ALIAS_PATTERN, None)
+        .await
+    {
+        Ok(entities) => entities
+            .into_iter()
+            .filter_map(|e| match e {
+                ConfigEntity::AliasPattern(p) => Some(p),
+                _ => None,
+            })
+            .collect(),
         Err(e) => {
             tracing::error!(error = %e, "failed to load pathauto patterns");
             return render_server_error("Failed to load pathauto configuration.");
         }
     };
 
-    // Build a flat map of machine_name -> pattern string for the template.
+    // Build a flat map of machine_name -> general pattern for the form.
     let patterns: HashMap<String, String> = content_types
         .iter()
         .map(|ct| {
-            let pat = patterns_json
-                .get(&ct.machine_name)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
+            let pat = all_patterns
+                .iter()
+                .find(|p| p.item_type == ct.machine_name && p.language.is_none())
+                .map(|p| p.pattern.clone())
+                .unwrap_or_default();
             (ct.machine_name.clone(), pat)
         })
         .collect();
+    let language_patterns: Vec<&AliasPattern> = all_patterns
+        .iter()
+        .filter(|p| p.language.is_some())
+        .collect();
+
+    let slug_options = match SlugOptions::load(state.db()).await {
+        Ok(options) => options,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load slug settings");
+            return render_server_error("Failed to load pathauto configuration.");
+        }
+    };
 
     let csrf_token = generate_csrf_token(&session).await;
     let flash: Option<String> = session.remove(FLASH_KEY).await.ok().flatten();
 
     let mut context = tera::Context::new();
     context.insert("content_types", &content_types);
+    context.insert("slug_options", &slug_options);
     context.insert("patterns", &patterns);
+    context.insert("language_patterns", &language_patterns);
     context.insert("csrf_token", &csrf_token);
     context.insert("path", "/admin/config/pathauto");
     if let Some(msg) = flash {
@@ -113,15 +144,16 @@ async fn pathauto_config_page(State(state): State<AppState>, session: Session) -
 
 /// Save pathauto patterns.
 ///
-/// Accepts form fields named `pattern.{machine_name}` (one per content type).
-/// Empty patterns are dropped (removing a pattern disables alias generation
-/// for that type going forward; existing aliases are not deleted).
+/// Accepts form fields named `pattern.{machine_name}` (one per content type)
+/// and saves each as the type's general `alias_pattern`. An empty pattern
+/// deletes it (removing a pattern disables alias generation for that type
+/// going forward; existing aliases are not deleted).
 ///
 /// Keys that are not valid machine names are silently ignored — they cannot
-/// originate from the admin UI and indicate a crafted request. Patterns whose
-/// leading path segment collides with a reserved system prefix (e.g. `admin/`,
-/// `api/`) are rejected with a 400 error, since the alias middleware skips
-/// those paths and such patterns would generate unreachable aliases.
+/// originate from the admin UI and indicate a crafted request. Patterns with
+/// unknown tokens or whose leading path segment collides with a reserved
+/// system prefix (e.g. `admin/`, `api/`) are rejected with a 400 error
+/// before anything is saved.
 ///
 /// POST /admin/config/pathauto
 async fn save_pathauto_config(
@@ -139,74 +171,105 @@ async fn save_pathauto_config(
         return resp;
     }
 
-    // Collect non-empty patterns from fields prefixed with "pattern.".
-    let mut patterns = serde_json::Map::new();
+    // Collect patterns from fields prefixed with "pattern."; `None` removes one.
+    let mut changes: Vec<(String, Option<AliasPattern>)> = Vec::new();
     for (key, value) in &form {
         let Some(type_name) = key.strip_prefix("pattern.") else {
             continue;
         };
 
-        // Guard: a field literally named "pattern." yields an empty suffix.
-        if type_name.is_empty() {
-            continue;
-        }
-
         // Only accept keys that are valid content type machine names. This
-        // prevents crafted requests from polluting site_config with arbitrary
-        // JSON keys. Our own form only ever generates valid machine names.
+        // also rejects a field literally named "pattern.", and prevents
+        // crafted requests from creating patterns for arbitrary ids. Our own
+        // form only ever generates valid machine names.
         if !is_valid_machine_name(type_name) {
             continue;
         }
 
         let trimmed = value.trim();
         if trimmed.is_empty() {
-            // Empty pattern = remove alias generation for this type.
+            changes.push((type_name.to_string(), None));
             continue;
         }
 
-        // Reject patterns whose leading segment collides with a system prefix.
-        // The alias middleware never resolves paths starting with these prefixes,
-        // so such patterns would create unreachable aliases and could shadow
-        // system routes.
-        if RESERVED_PATTERN_PREFIXES
-            .iter()
-            .any(|prefix| trimmed.starts_with(prefix))
-        {
-            return render_error(&format!(
-                "Pattern for '{type_name}' begins with a reserved path prefix (e.g. admin/, api/, user/). \
-                 The alias middleware skips these paths, so the alias would never resolve. \
-                 Choose a different prefix."
-            ));
+        let pattern = AliasPattern::new(type_name, None, trimmed);
+        if let Err(e) = pattern.validate() {
+            return render_error(&format!("{e:#}. Choose a different pattern."));
         }
-
-        patterns.insert(
-            type_name.to_string(),
-            serde_json::Value::String(trimmed.to_string()),
-        );
+        changes.push((type_name.to_string(), Some(pattern)));
     }
 
-    if let Err(e) = SiteConfig::set(
-        state.db(),
-        "pathauto_patterns",
-        serde_json::Value::Object(patterns),
-    )
-    .await
-    {
-        tracing::error!(error = %e, "failed to save pathauto patterns");
-        return render_server_error("Failed to save pathauto configuration.");
+    for (type_name, pattern) in changes {
+        let result = match pattern {
+            Some(pattern) => {
+                state
+                    .config_storage()
+                    .save(&ConfigEntity::AliasPattern(pattern))
+                    .await
+            }
+            None => state
+                .config_storage()
+                .delete(entity_types::ALIAS_PATTERN, &type_name)
+                .await
+                .map(|_| ()),
+        };
+        if let Err(e) = result {
+            if let Some(failed) = e.downcast_ref::<ConfigValidationFailed>() {
+                return render_error(&failed.to_string());
+            }
+            tracing::error!(error = %e, item_type = %type_name, "failed to save pathauto pattern");
+            return render_server_error("Failed to save pathauto configuration.");
+        }
     }
 
     let _ = session.insert(FLASH_KEY, "Pathauto patterns saved.").await;
     Redirect::to("/admin/config/pathauto").into_response()
 }
 
+/// Save site-wide slug settings.
+///
+/// Out-of-range values are rejected with a 400 error. The settings apply
+/// to aliases generated from now on; existing aliases change only when
+/// they are regenerated.
+///
+/// POST /admin/config/pathauto/slug
+async fn save_slug_settings(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<SlugSettingsForm>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let options = SlugOptions {
+        separator: form.separator,
+        max_length: form.max_length,
+        remove_stopwords: form.remove_stopwords.is_some(),
+    };
+    if let Err(e) = options.validate() {
+        return render_error(&format!("Invalid slug settings: {e}."));
+    }
+    if let Err(e) = options.save(state.db()).await {
+        tracing::error!(error = %e, "failed to save slug settings");
+        return render_server_error("Failed to save slug settings.");
+    }
+
+    let _ = session.insert(FLASH_KEY, "Slug settings saved.").await;
+    Redirect::to("/admin/config/pathauto").into_response()
+}
+
 /// Regenerate path aliases for all items of a given content type.
 ///
 /// Validates that the requested content type is registered, then iterates
 /// every item of that type (up to [`MAX_REGENERATE_ITEMS`]), calls
-/// [`update_alias_item`] for each, and counts how many aliases were created
-/// or updated. Items whose alias already matches the current pattern are
-/// skipped automatically.
+/// [`update_alias_item`] for each (in the item's own stage), and counts how
+/// many aliases were created or updated. Items whose alias already matches
+/// the current pattern are skipped automatically.
 ///
 /// Returns a 400 error if the content type is unrecognized or the item
 /// count exceeds the per-request limit.
@@ -249,23 +312,16 @@ async fn regenerate_aliases(
     if items.len() > MAX_REGENERATE_ITEMS {
         return render_error(&format!(
             "Too many items to regenerate in one request ({} found; limit is {}). \
-             Run regeneration from the command line for large datasets.",
+             Run `trovato alias regenerate {}` for large datasets.",
             items.len(),
-            MAX_REGENERATE_ITEMS
+            MAX_REGENERATE_ITEMS,
+            form.item_type
         ));
     }
 
     let mut count: u64 = 0;
     for item in &items {
-        match update_alias_item(
-            state.db(),
-            item.id,
-            &item.title,
-            &item.item_type,
-            item.created,
-        )
-        .await
-        {
+        match update_alias_item(state.db(), item).await {
             Ok(Some(_)) => count += 1,
             Ok(None) => {} // alias already matches or no pattern configured
             Err(e) => {
@@ -303,6 +359,7 @@ pub fn router() -> Router<AppState> {
             "/admin/config/pathauto",
             get(pathauto_config_page).post(save_pathauto_config),
         )
+        .route("/admin/config/pathauto/slug", post(save_slug_settings))
         .route(
             "/admin/config/pathauto/regenerate",
             post(regenerate_aliases),
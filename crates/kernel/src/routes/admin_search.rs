@@ -0,0 +1,64 @@
+//! Search index admin routes.
+//!
+//! Reports index freshness per content type while the background
+//! reindexer works through queued items.
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use tower_sessions::Session;
+
+use crate::cron::Queue;
+use crate::error::AppError;
+use crate::search::{BundleIndexStatus, REINDEX_QUEUE};
+use crate::state::AppState;
+
+use super::helpers::require_admin_json;
+
+/// Search index status response.
+#[derive(Debug, Serialize)]
+pub struct SearchStatusResponse {
+    /// Items waiting for a reindex, across all bundles.
+    pub pending: i64,
+    /// Items currently in the reindex queue, processed on the next cron run.
+    pub queue_length: u64,
+    /// Per-bundle index freshness.
+    pub bundles: Vec<BundleIndexStatus>,
+}
+
+/// Create the search admin router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/admin/config/search/status", get(search_status))
+}
+
+/// Search index status (admin only).
+///
+/// GET /admin/config/search/status
+async fn search_status(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<SearchStatusResponse>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let bundles = state
+        .search()
+        .index_status()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load search index status"))?;
+    let queue_length = state
+        .cron()
+        .queue()
+        .len(REINDEX_QUEUE)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to get search reindex queue length");
+            0
+        });
+
+    Ok(Json(SearchStatusResponse {
+        pending: bundles.iter().map(|b| b.pending).sum(),
+        queue_length,
+        bundles,
+    }))
+}
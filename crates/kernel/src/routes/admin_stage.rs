@@ -0,0 +1,128 @@
+//! Stage diff (admin only).
+//!
+//! - `GET /admin/stage/{stage_id}/diff?page=1` — every item in a stage
+//!   diffed against its live counterpart
+
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::content::diff::{DiffInput, DiffSummary, diff_items};
+use crate::error::AppError;
+use crate::models::stage::{LIVE_STAGE_ID, Stage};
+use crate::state::AppState;
+
+use super::helpers::require_admin_json;
+
+/// Create the stage diff router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/admin/stage/{stage_id}/diff", get(stage_diff))
+}
+
+/// Items per page in the stage diff.
+const STAGE_DIFF_PAGE_SIZE: i64 = 50;
+
+/// Stage diff query parameters.
+#[derive(Debug, Deserialize)]
+struct StageDiffQuery {
+    page: Option<i64>,
+}
+
+/// One staged item diffed against its live counterpart.
+#[derive(Debug, Serialize)]
+struct StageDiffEntry {
+    item_id: Uuid,
+    item_type: String,
+    title: String,
+    /// Live copy of the item; `None` if it has never been published.
+    live_item_id: Option<Uuid>,
+    html: String,
+    summary: DiffSummary,
+}
+
+/// Stage diff response.
+#[derive(Debug, Serialize)]
+struct StageDiffResponse {
+    stage_id: Uuid,
+    page: i64,
+    total: i64,
+    items: Vec<StageDiffEntry>,
+}
+
+/// Diff every item in a stage against its live counterpart.
+///
+/// GET /admin/stage/{stage_id}/diff?page=1
+async fn stage_diff(
+    State(state): State<AppState>,
+    session: Session,
+    Path(stage_id): Path<Uuid>,
+    Query(query): Query<StageDiffQuery>,
+) -> Result<Json<StageDiffResponse>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    if stage_id == LIVE_STAGE_ID {
+        return Err(AppError::bad_request("The live stage has nothing to diff"));
+    }
+    Stage::find_by_id(state.db(), stage_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load stage"))?
+        .ok_or_else(|| AppError::not_found_id("stage", stage_id))?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let (items, total) = state
+        .items()
+        .list_in_stage(
+            stage_id,
+            STAGE_DIFF_PAGE_SIZE,
+            (page - 1) * STAGE_DIFF_PAGE_SIZE,
+        )
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list stage items"))?;
+
+    let mut entries = Vec::with_capacity(items.len());
+    for item in items {
+        let live = state
+            .items()
+            .find_live_counterpart(&item)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load live item"))?;
+        let field_defs = state
+            .content_types()
+            .get_or_load(&item.item_type)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load content type"))?
+            .map(|def| def.fields)
+            .unwrap_or_default();
+
+        let before = live.as_ref().map(|live| DiffInput {
+            title: &live.title,
+            status: live.status,
+            fields: &live.fields,
+        });
+        let after = DiffInput {
+            title: &item.title,
+            status: item.status,
+            fields: &item.fields,
+        };
+        let diff = diff_items(before.as_ref(), &after, &field_defs);
+
+        entries.push(StageDiffEntry {
+            item_id: item.id,
+            item_type: item.item_type.clone(),
+            title: item.title.clone(),
+            live_item_id: live.map(|l| l.id),
+            html: diff.html,
+            summary: diff.summary,
+        });
+    }
+
+    Ok(Json(StageDiffResponse {
+        stage_id,
+        page,
+        total,
+        items: entries,
+    }))
+}
@@ -1,22 +1,36 @@
 //! Admin routes for category and tag management.
+//!
+//! With a stage active in the session, edits are staged through
+//! [`StageAwareConfigStorage`](crate::config_storage::StageAwareConfigStorage)
+//! and reach live when the stage is published.
 
+use std::sync::Arc;
+
+use anyhow::Result;
 use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
 use axum::{Form, Router};
 use serde::Deserialize;
 use tower_sessions::Session;
+use uuid::Uuid;
 
+use crate::config_storage::{ConfigEntity, ConfigFilter, ConfigStorage, entity_types};
 use crate::form::csrf::generate_csrf_token;
-use crate::models::{CreateCategory, CreateTag, UpdateCategory, UpdateTag};
+use crate::models::stage::LIVE_STAGE_ID;
+use crate::models::{Category, CreateCategory, CreateTag, Tag, UpdateCategory, UpdateTag};
 use crate::state::AppState;
 
 use super::helpers::{
-    CsrfOnlyForm, MACHINE_NAME_ERROR, SLUG_FORMAT_ERROR, is_valid_machine_name, is_valid_slug,
-    render_admin_template, render_error, render_not_found, render_server_error, require_admin,
-    require_csrf,
+    CsrfOnlyForm, MACHINE_NAME_ERROR, SLUG_FORMAT_ERROR, active_stage, is_valid_machine_name,
+    is_valid_slug, render_admin_template, render_error, render_not_found, render_server_error,
+    require_admin, require_csrf,
 };
 
+/// Shown when a tag's parents are changed while a stage is active.
+const STAGED_PARENTS_ERROR: &str =
+    "Tag parents cannot be changed in a stage. Switch to live to change them.";
+
 // =============================================================================
 // Form data
 // =============================================================================
@@ -48,6 +62,217 @@ struct TagFormData {
     parent_id: Option<String>,
 }
 
+// =============================================================================
+// Stage-aware access
+// =============================================================================
+
+/// Category and tag access for the session's active stage.
+///
+/// On live this goes through the category service and its caches. In a
+/// stage it goes through stage-aware config storage, which overlays the
+/// stage's changes on live. Tag parents are not staged.
+struct Taxonomy {
+    state: AppState,
+    staged: Option<Arc<dyn ConfigStorage>>,
+}
+
+impl Taxonomy {
+    async fn for_session(state: &AppState, session: &Session) -> Self {
+        let stage_id = active_stage(session).await;
+        let staged = (stage_id != LIVE_STAGE_ID).then(|| state.config_storage_for_stage(stage_id));
+        Self {
+            state: state.clone(),
+            staged,
+        }
+    }
+
+    fn is_staged(&self) -> bool {
+        self.staged.is_some()
+    }
+
+    async fn list_categories(&self) -> Result<Vec<Category>> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().list_categories().await;
+        };
+        let mut categories: Vec<Category> = storage
+            .list(entity_types::CATEGORY, None)
+            .await?
+            .into_iter()
+            .filter_map(|entity| match entity {
+                ConfigEntity::Category(category) => Some(category),
+                _ => None,
+            })
+            .collect();
+        categories.sort_by(|a, b| (a.weight, &a.label).cmp(&(b.weight, &b.label)));
+        Ok(categories)
+    }
+
+    async fn get_category(&self, id: &str) -> Result<Option<Category>> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().get_category(id).await;
+        };
+        Ok(match storage.load(entity_types::CATEGORY, id).await? {
+            Some(ConfigEntity::Category(category)) => Some(category),
+            _ => None,
+        })
+    }
+
+    async fn category_exists(&self, id: &str) -> Result<bool> {
+        match self.staged {
+            Some(ref storage) => storage.exists(entity_types::CATEGORY, id).await,
+            None => self.state.categories().category_exists(id).await,
+        }
+    }
+
+    async fn create_category(&self, input: CreateCategory) -> Result<()> {
+        let Some(ref storage) = self.staged else {
+            return self
+                .state
+                .categories()
+                .create_category(input)
+                .await
+                .map(drop);
+        };
+        let category = Category {
+            id: input.id,
+            label: input.label,
+            description: input.description,
+            hierarchy: input.hierarchy.unwrap_or(0),
+            weight: input.weight.unwrap_or(0),
+        };
+        storage.save(&ConfigEntity::Category(category)).await
+    }
+
+    /// Returns `false` if the category does not exist.
+    async fn update_category(&self, id: &str, input: UpdateCategory) -> Result<bool> {
+        let Some(ref storage) = self.staged else {
+            let updated = self.state.categories().update_category(id, input).await?;
+            return Ok(updated.is_some());
+        };
+        let Some(current) = self.get_category(id).await? else {
+            return Ok(false);
+        };
+        let category = Category {
+            label: input.label.unwrap_or(current.label),
+            description: input.description.or(current.description),
+            hierarchy: input.hierarchy.unwrap_or(current.hierarchy),
+            weight: input.weight.unwrap_or(current.weight),
+            ..current
+        };
+        storage.save(&ConfigEntity::Category(category)).await?;
+        Ok(true)
+    }
+
+    async fn delete_category(&self, id: &str) -> Result<bool> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().delete_category(id).await;
+        };
+        if !storage.exists(entity_types::CATEGORY, id).await? {
+            return Ok(false);
+        }
+        storage.delete(entity_types::CATEGORY, id).await
+    }
+
+    async fn count_tags(&self, category_id: &str) -> Result<i64> {
+        if self.is_staged() {
+            return Ok(self.list_tags(category_id).await?.len() as i64);
+        }
+        self.state.categories().count_tags(category_id).await
+    }
+
+    async fn list_tags(&self, category_id: &str) -> Result<Vec<Tag>> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().list_tags(category_id).await;
+        };
+        let filter = ConfigFilter::new().with_field("category_id", category_id);
+        let mut tags: Vec<Tag> = storage
+            .list(entity_types::TAG, Some(&filter))
+            .await?
+            .into_iter()
+            .filter_map(|entity| match entity {
+                ConfigEntity::Tag(tag) if tag.category_id == category_id => Some(tag),
+                _ => None,
+            })
+            .collect();
+        tags.sort_by(|a, b| (a.weight, &a.label).cmp(&(b.weight, &b.label)));
+        Ok(tags)
+    }
+
+    async fn get_tag(&self, id: Uuid) -> Result<Option<Tag>> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().get_tag(id).await;
+        };
+        Ok(
+            match storage.load(entity_types::TAG, &id.to_string()).await? {
+                Some(ConfigEntity::Tag(tag)) => Some(tag),
+                _ => None,
+            },
+        )
+    }
+
+    async fn find_tag_by_slug(&self, category_id: &str, slug: &str) -> Result<Option<Tag>> {
+        if self.is_staged() {
+            let tags = self.list_tags(category_id).await?;
+            return Ok(tags.into_iter().find(|t| t.slug.as_deref() == Some(slug)));
+        }
+        Tag::find_by_slug(self.state.db(), category_id, slug).await
+    }
+
+    async fn create_tag(&self, input: CreateTag) -> Result<()> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().create_tag(input).await.map(drop);
+        };
+        let now = chrono::Utc::now().timestamp();
+        let tag = Tag {
+            id: Uuid::now_v7(),
+            category_id: input.category_id,
+            label: input.label,
+            description: input.description,
+            slug: input.slug,
+            weight: input.weight.unwrap_or(0),
+            created: now,
+            changed: now,
+        };
+        storage.save(&ConfigEntity::Tag(tag)).await
+    }
+
+    async fn update_tag(&self, current: Tag, input: UpdateTag) -> Result<()> {
+        let Some(ref storage) = self.staged else {
+            return self
+                .state
+                .categories()
+                .update_tag(current.id, input)
+                .await
+                .map(drop);
+        };
+        // Same merge as `Tag::update`: an empty slug clears it.
+        let slug = match input.slug {
+            Some(s) if s.is_empty() => None,
+            Some(s) => Some(s),
+            None => current.slug,
+        };
+        let tag = Tag {
+            label: input.label.unwrap_or(current.label),
+            description: input.description.or(current.description),
+            slug,
+            weight: input.weight.unwrap_or(current.weight),
+            changed: chrono::Utc::now().timestamp(),
+            ..current
+        };
+        storage.save(&ConfigEntity::Tag(tag)).await
+    }
+
+    async fn delete_tag(&self, id: Uuid) -> Result<bool> {
+        let Some(ref storage) = self.staged else {
+            return self.state.categories().delete_tag(id).await;
+        };
+        if self.get_tag(id).await?.is_none() {
+            return Ok(false);
+        }
+        storage.delete(entity_types::TAG, &id.to_string()).await
+    }
+}
+
 // =============================================================================
 // Category handlers
 // =============================================================================
@@ -60,7 +285,9 @@ async fn list_categories(State(state): State<AppState>, session: Session) -> Res
         return redirect;
     }
 
-    let categories = match state.categories().list_categories().await {
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
+    let categories = match taxonomy.list_categories().await {
         Ok(categories) => categories,
         Err(e) => {
             tracing::error!(error = %e, "failed to list categories");
@@ -71,7 +298,7 @@ async fn list_categories(State(state): State<AppState>, session: Session) -> Res
     // Get tag counts for each category
     let mut tag_counts: std::collections::HashMap<String, i64> = std::collections::HashMap::new();
     for cat in &categories {
-        let count = state.categories().count_tags(&cat.id).await.unwrap_or(0);
+        let count = taxonomy.count_tags(&cat.id).await.unwrap_or(0);
         tag_counts.insert(cat.id.clone(), count);
     }
 
@@ -120,6 +347,8 @@ async fn add_category_submit(
         return redirect;
     }
 
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
     // Verify CSRF token
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
@@ -139,12 +368,7 @@ async fn add_category_submit(
     }
 
     // Check if category already exists
-    if state
-        .categories()
-        .category_exists(&form.id)
-        .await
-        .unwrap_or(false)
-    {
+    if taxonomy.category_exists(&form.id).await.unwrap_or(false) {
         errors.push(format!("A category with ID '{}' already exists.", form.id));
     }
 
@@ -180,7 +404,7 @@ async fn add_category_submit(
         weight: None,
     };
 
-    match state.categories().create_category(input).await {
+    match taxonomy.create_category(input).await {
         Ok(_) => {
             tracing::info!(id = %form.id, "category created");
             Redirect::to("/admin/structure/categories").into_response()
@@ -204,13 +428,9 @@ async fn edit_category_form(
         return redirect;
     }
 
-    let Some(category) = state
-        .categories()
-        .get_category(&category_id)
-        .await
-        .ok()
-        .flatten()
-    else {
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
+    let Some(category) = taxonomy.get_category(&category_id).await.ok().flatten() else {
         return render_not_found();
     };
 
@@ -256,6 +476,8 @@ async fn edit_category_submit(
         return redirect;
     }
 
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
     // Verify CSRF token
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
@@ -306,16 +528,12 @@ async fn edit_category_submit(
         weight: None,
     };
 
-    match state
-        .categories()
-        .update_category(&category_id, input)
-        .await
-    {
-        Ok(Some(_)) => {
+    match taxonomy.update_category(&category_id, input).await {
+        Ok(true) => {
             tracing::info!(id = %category_id, "category updated");
             Redirect::to("/admin/structure/categories").into_response()
         }
-        Ok(None) => render_not_found(),
+        Ok(false) => render_not_found(),
         Err(e) => {
             tracing::error!(error = %e, "failed to update category");
             render_server_error("Failed to update category.")
@@ -336,11 +554,13 @@ async fn delete_category(
         return redirect;
     }
 
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
-    match state.categories().delete_category(&category_id).await {
+    match taxonomy.delete_category(&category_id).await {
         Ok(true) => {
             tracing::info!(id = %category_id, "category deleted");
             Redirect::to("/admin/structure/categories").into_response()
@@ -369,17 +589,13 @@ async fn list_tags(
         return redirect;
     }
 
-    let Some(category) = state
-        .categories()
-        .get_category(&category_id)
-        .await
-        .ok()
-        .flatten()
-    else {
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
+    let Some(category) = taxonomy.get_category(&category_id).await.ok().flatten() else {
         return render_not_found();
     };
 
-    let tags = match state.categories().list_tags(&category_id).await {
+    let tags = match taxonomy.list_tags(&category_id).await {
         Ok(tags) => tags,
         Err(e) => {
             tracing::error!(error = %e, "failed to list tags");
@@ -413,22 +629,14 @@ async fn add_tag_form(
         return redirect;
     }
 
-    let Some(category) = state
-        .categories()
-        .get_category(&category_id)
-        .await
-        .ok()
-        .flatten()
-    else {
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
+    let Some(category) = taxonomy.get_category(&category_id).await.ok().flatten() else {
         return render_not_found();
     };
 
     // Get existing tags for parent selector
-    let tags = state
-        .categories()
-        .list_tags(&category_id)
-        .await
-        .unwrap_or_default();
+    let tags = taxonomy.list_tags(&category_id).await.unwrap_or_default();
 
     let csrf_token = generate_csrf_token(&session).await;
     let form_build_id = uuid::Uuid::new_v4().to_string();
@@ -465,18 +673,14 @@ async fn add_tag_submit(
         return redirect;
     }
 
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
     // Verify CSRF token
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
-    let Some(category) = state
-        .categories()
-        .get_category(&category_id)
-        .await
-        .ok()
-        .flatten()
-    else {
+    let Some(category) = taxonomy.get_category(&category_id).await.ok().flatten() else {
         return render_not_found();
     };
 
@@ -503,17 +707,17 @@ async fn add_tag_submit(
     // Check for duplicate slug within this category.
     if errors.is_empty()
         && let Some(ref s) = slug
-        && let Ok(Some(_)) = crate::models::Tag::find_by_slug(state.db(), &category_id, s).await
+        && let Ok(Some(_)) = taxonomy.find_tag_by_slug(&category_id, s).await
     {
         errors.push("A tag with this slug already exists in this category.".to_string());
     }
 
+    if taxonomy.is_staged() && form.parent_id.as_deref().is_some_and(|id| !id.is_empty()) {
+        errors.push(STAGED_PARENTS_ERROR.to_string());
+    }
+
     if !errors.is_empty() {
-        let tags = state
-            .categories()
-            .list_tags(&category_id)
-            .await
-            .unwrap_or_default();
+        let tags = taxonomy.list_tags(&category_id).await.unwrap_or_default();
         let csrf_token = generate_csrf_token(&session).await;
         let form_build_id = uuid::Uuid::new_v4().to_string();
 
@@ -563,7 +767,7 @@ async fn add_tag_submit(
         parent_ids,
     };
 
-    match state.categories().create_tag(input).await {
+    match taxonomy.create_tag(input).await {
         Ok(_) => {
             tracing::info!(category = %category_id, label = %form.label, "tag created");
             Redirect::to(&format!("/admin/structure/categories/{category_id}/tags")).into_response()
@@ -587,23 +791,18 @@ async fn edit_tag_form(
         return redirect;
     }
 
-    let Some(tag) = state.categories().get_tag(tag_id).await.ok().flatten() else {
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
+    let Some(tag) = taxonomy.get_tag(tag_id).await.ok().flatten() else {
         return render_not_found();
     };
 
-    let Some(category) = state
-        .categories()
-        .get_category(&tag.category_id)
-        .await
-        .ok()
-        .flatten()
-    else {
+    let Some(category) = taxonomy.get_category(&tag.category_id).await.ok().flatten() else {
         return render_error("Category not found.");
     };
 
     // Get existing tags for parent selector (excluding self)
-    let tags: Vec<_> = state
-        .categories()
+    let tags: Vec<_> = taxonomy
         .list_tags(&tag.category_id)
         .await
         .unwrap_or_default()
@@ -658,22 +857,18 @@ async fn edit_tag_submit(
         return redirect;
     }
 
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
     // Verify CSRF token
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
-    let Some(tag) = state.categories().get_tag(tag_id).await.ok().flatten() else {
+    let Some(tag) = taxonomy.get_tag(tag_id).await.ok().flatten() else {
         return render_not_found();
     };
 
-    let Some(category) = state
-        .categories()
-        .get_category(&tag.category_id)
-        .await
-        .ok()
-        .flatten()
-    else {
+    let Some(category) = taxonomy.get_category(&tag.category_id).await.ok().flatten() else {
         return render_error("Category not found.");
     };
 
@@ -695,16 +890,29 @@ async fn edit_tag_submit(
     // Check for duplicate slug within the same category (excluding this tag).
     if errors.is_empty()
         && !slug_value.is_empty()
-        && let Ok(Some(existing)) =
-            crate::models::Tag::find_by_slug(state.db(), &tag.category_id, slug_value).await
+        && let Ok(Some(existing)) = taxonomy
+            .find_tag_by_slug(&tag.category_id, slug_value)
+            .await
         && existing.id != tag_id
     {
         errors.push("A tag with this slug already exists in this category.".to_string());
     }
 
-    if !errors.is_empty() {
-        let tags: Vec<_> = state
+    // Parents are stored outside config storage, so a stage can't carry them.
+    if taxonomy.is_staged() && category.hierarchy > 0 {
+        let parents = state
             .categories()
+            .get_parents(tag_id)
+            .await
+            .unwrap_or_default();
+        let current_parent_id = parents.first().map(|p| p.id.to_string());
+        if form.parent_id.as_deref().filter(|id| !id.is_empty()) != current_parent_id.as_deref() {
+            errors.push(STAGED_PARENTS_ERROR.to_string());
+        }
+    }
+
+    if !errors.is_empty() {
+        let tags: Vec<_> = taxonomy
             .list_tags(&tag.category_id)
             .await
             .unwrap_or_default()
@@ -747,13 +955,13 @@ async fn edit_tag_submit(
         weight: form.weight.as_ref().and_then(|s| s.parse().ok()),
     };
 
-    if let Err(e) = state.categories().update_tag(tag_id, input).await {
+    if let Err(e) = taxonomy.update_tag(tag.clone(), input).await {
         tracing::error!(error = %e, "failed to update tag");
         return render_server_error("Failed to update tag.");
     }
 
     // Update parent if hierarchy is enabled
-    if category.hierarchy > 0 {
+    if category.hierarchy > 0 && !taxonomy.is_staged() {
         let parent_ids: Vec<uuid::Uuid> = match &form.parent_id {
             Some(id) if !id.is_empty() => match uuid::Uuid::parse_str(id) {
                 Ok(uuid) => vec![uuid],
@@ -788,20 +996,21 @@ async fn delete_tag(
         return redirect;
     }
 
+    let taxonomy = Taxonomy::for_session(&state, &session).await;
+
     if let Err(resp) = require_csrf(&session, &form.token).await {
         return resp;
     }
 
     // Get category ID for redirect
-    let category_id = state
-        .categories()
+    let category_id = taxonomy
         .get_tag(tag_id)
         .await
         .ok()
         .flatten()
         .map(|t| t.category_id);
 
-    match state.categories().delete_tag(tag_id).await {
+    match taxonomy.delete_tag(tag_id).await {
         Ok(true) => {
             tracing::info!(tag_id = %tag_id, "tag deleted");
             let redirect_url = category_id
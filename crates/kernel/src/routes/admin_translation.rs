@@ -1,22 +1,43 @@
-//! Admin routes for content translation management.
+//! Routes for content translation management.
 //!
 //! Provides a side-by-side translation UI for translating content items
-//! into different languages.
+//! into different languages, and a JSON API for the same translations:
+//!
+//! - `GET /api/item/{id}/translations` — the item's language and its
+//!   translations
+//! - `GET|PUT|DELETE /api/item/{id}/translations/{lang}` — read, create or
+//!   replace, or delete one translation
+//!
+//! A translation overlays the item's title and fields in one language; the
+//! item itself is the translation set. All routes require the
+//! `translate content` permission; changing a translation also requires
+//! edit access to the item.
 
-use axum::Router;
 use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::Response;
 use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
 use uuid::Uuid;
 
+use crate::content::ItemTranslation;
+use crate::error::AppError;
+use crate::models::Item;
 use crate::state::AppState;
+use crate::tap::UserContext;
 
 use super::helpers::{
-    render_admin_template, render_not_found, render_server_error, require_permission,
+    render_admin_template, render_not_found, render_server_error, require_csrf_header,
+    require_permission,
 };
+use super::item::get_user_context;
+
+/// Permission granted by the content translation plugin.
+const TRANSLATE_PERMISSION: &str = "translate content";
 
-/// Create the content translation admin router.
+/// Create the content translation router.
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/admin/content/{id}/translate", get(translation_list))
@@ -24,6 +45,13 @@ pub fn router() -> Router<AppState> {
             "/admin/content/{id}/translate/{lang}",
             get(translation_edit),
         )
+        .route("/api/item/{id}/translations", get(api_list_translations))
+        .route(
+            "/api/item/{id}/translations/{lang}",
+            get(api_get_translation)
+                .put(api_put_translation)
+                .delete(api_delete_translation),
+        )
 }
 
 /// List available translations for an item.
@@ -102,3 +130,189 @@ async fn translation_edit(
 
     render_admin_template(&state, "admin/content-translate-edit.html", context).await
 }
+
+/// An item's source language and translations.
+#[derive(Debug, Serialize)]
+struct TranslationList {
+    item_id: Uuid,
+    language: String,
+    translations: Vec<TranslationSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct TranslationSummary {
+    language: String,
+    title: String,
+}
+
+/// Body of a translation save.
+#[derive(Debug, Deserialize)]
+struct TranslationInput {
+    #[serde(default)]
+    title: String,
+    #[serde(default = "empty_object")]
+    fields: serde_json::Value,
+}
+
+fn empty_object() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+/// The session user, if they may translate content.
+async fn translator(state: &AppState, session: &Session) -> Result<UserContext, AppError> {
+    let user = get_user_context(session, state).await;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Authentication required"));
+    }
+    if !user.is_admin() && !user.has_permission(TRANSLATE_PERMISSION) {
+        return Err(AppError::forbidden(
+            "Permission required: translate content",
+        ));
+    }
+    Ok(user)
+}
+
+/// Load an item the user may access with `op`: `"view"` to read its
+/// translations, `"edit"` to change them.
+async fn translatable_item(
+    state: &AppState,
+    user: &UserContext,
+    id: Uuid,
+    op: &str,
+) -> Result<Item, AppError> {
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+    let allowed = state
+        .items()
+        .check_access(&item, op, user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "check item access"))?;
+    if !allowed {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    Ok(item)
+}
+
+/// Check that `lang` is a site language other than the item's own.
+fn check_language(state: &AppState, item: &Item, lang: &str) -> Result<(), AppError> {
+    if !state.known_languages().iter().any(|l| l == lang) {
+        return Err(AppError::bad_request(format!("unknown language: {lang}")));
+    }
+    if item.language == lang {
+        return Err(AppError::bad_request(format!(
+            "{lang} is the item's own language"
+        )));
+    }
+    Ok(())
+}
+
+/// List an item's translations.
+///
+/// GET /api/item/{id}/translations
+async fn api_list_translations(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TranslationList>, AppError> {
+    let user = translator(&state, &session).await?;
+    let item = translatable_item(&state, &user, id, "view").await?;
+
+    let translations = state
+        .items()
+        .list_translations(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list translations"))?
+        .into_iter()
+        .map(|(language, title)| TranslationSummary { language, title })
+        .collect();
+
+    Ok(Json(TranslationList {
+        item_id: id,
+        language: item.language,
+        translations,
+    }))
+}
+
+/// Read one translation.
+///
+/// GET /api/item/{id}/translations/{lang}
+async fn api_get_translation(
+    State(state): State<AppState>,
+    session: Session,
+    Path((id, lang)): Path<(Uuid, String)>,
+) -> Result<Json<ItemTranslation>, AppError> {
+    let user = translator(&state, &session).await?;
+    translatable_item(&state, &user, id, "view").await?;
+
+    let translation = state
+        .items()
+        .load_translation(id, &lang)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load translation"))?
+        .ok_or_else(|| AppError::not_found_id("translation", &lang))?;
+    Ok(Json(translation))
+}
+
+/// Create or replace a translation.
+///
+/// The body is `{ "title", "fields" }`; `fields` may only name fields of
+/// the item's type, and an empty title falls back to the item's own.
+/// Requires edit access; the translated values are validated like an item
+/// patch (see [`ItemService::save_translation`](crate::content::ItemService::save_translation)).
+///
+/// PUT /api/item/{id}/translations/{lang}
+async fn api_put_translation(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((id, lang)): Path<(Uuid, String)>,
+    Json(input): Json<TranslationInput>,
+) -> Result<Json<ItemTranslation>, AppError> {
+    let user = translator(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    let item = translatable_item(&state, &user, id, "edit").await?;
+    check_language(&state, &item, &lang)?;
+    if !input.fields.is_object() {
+        return Err(AppError::bad_request("fields must be an object"));
+    }
+
+    let translation = state
+        .items()
+        .save_translation(&item, &lang, &input.title, input.fields, &user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save translation"))?;
+    Ok(Json(translation))
+}
+
+/// Delete a translation.
+///
+/// DELETE /api/item/{id}/translations/{lang}
+async fn api_delete_translation(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((id, lang)): Path<(Uuid, String)>,
+) -> Result<StatusCode, AppError> {
+    let user = translator(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    let item = translatable_item(&state, &user, id, "edit").await?;
+
+    let deleted = state
+        .items()
+        .delete_translation(&item, &lang, &user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete translation"))?;
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::not_found_id("translation", &lang))
+    }
+}
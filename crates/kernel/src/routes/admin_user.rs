@@ -3,6 +3,8 @@
 use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
+use std::collections::HashMap;
+
 use axum::{Form, Router};
 use serde::Deserialize;
 use tower_sessions::Session;
@@ -11,6 +13,7 @@ use crate::form::csrf::generate_csrf_token;
 use crate::models::role::well_known::{ANONYMOUS_ROLE_ID, AUTHENTICATED_ROLE_ID};
 use crate::models::user::ANONYMOUS_USER_ID;
 use crate::models::{CreateUser, UpdateUser};
+use crate::services::user_fields;
 use crate::state::AppState;
 
 use super::helpers::{
@@ -29,21 +32,46 @@ const AVAILABLE_PERMISSIONS: &[&str] = &[
     "edit any content",
     "delete own content",
     "delete any content",
+    "administer content",
     "access user profiles",
     "administer users",
+    "administer 2fa",
     "administer categories",
+    "use personal workspace",
     "access files",
     "administer files",
     "use filtered_html",
     "use full_html",
+    "use saved search webhooks",
     "use ai",
     "use ai chat",
     "use ai embeddings",
     "use ai image generation",
     "configure ai",
     "view ai usage",
+    "view profiling data",
 ];
 
+/// Static permissions plus per-type and per-workflow-transition ones.
+async fn available_permissions(state: &AppState) -> Vec<String> {
+    let mut permissions: Vec<String> = AVAILABLE_PERMISSIONS
+        .iter()
+        .map(|p| (*p).to_string())
+        .collect();
+    let mut item_types = state.content_types().type_names();
+    item_types.sort();
+    permissions.extend(
+        item_types
+            .iter()
+            .map(|t| crate::permissions::view_own_unpublished_permission(t)),
+    );
+    match crate::models::Workflow::list_all(state.db()).await {
+        Ok(workflows) => permissions.extend(workflows.iter().flat_map(|w| w.permissions())),
+        Err(e) => tracing::warn!(error = %e, "failed to list workflow permissions"),
+    }
+    permissions
+}
+
 /// User form data.
 #[derive(Debug, Deserialize)]
 struct UserFormData {
@@ -56,6 +84,9 @@ struct UserFormData {
     password: Option<String>,
     is_admin: Option<String>,
     status: Option<String>,
+    /// Profile field inputs (`profile[<field_name>]`).
+    #[serde(flatten)]
+    extra: HashMap<String, String>,
 }
 
 /// Role form data.
@@ -252,8 +283,10 @@ async fn edit_user_form(
             "mail": target_user.mail,
             "is_admin": target_user.is_admin,
             "status": target_user.status == 1,
+            "fields": target_user.fields,
         }),
     );
+    context.insert("profile_fields", &state.users().profile_fields());
     context.insert("path", &format!("/admin/people/{user_id}/edit"));
 
     // Local task tabs for user edit pages (hardcoded + plugin-registered)
@@ -328,6 +361,20 @@ async fn edit_user_submit(
         errors.push(msg.to_string());
     }
 
+    let profile_fields = state.users().profile_fields();
+    let submitted_fields = user_fields::form_values(&form.extra);
+    let fields = match user_fields::apply_form_values(
+        &profile_fields,
+        &submitted_fields,
+        &existing_user.fields,
+    ) {
+        Ok(fields) => Some(fields),
+        Err(field_errors) => {
+            errors.extend(field_errors);
+            None
+        }
+    };
+
     if !errors.is_empty() {
         let csrf_token = generate_csrf_token(&session).await;
         let form_build_id = uuid::Uuid::new_v4().to_string();
@@ -346,8 +393,10 @@ async fn edit_user_submit(
                 "mail": form.mail,
                 "is_admin": form.is_admin.is_some(),
                 "status": form.status.is_some(),
+                "fields": submitted_fields,
             }),
         );
+        context.insert("profile_fields", &profile_fields);
         let current_path = format!("/admin/people/{user_id}/edit");
         context.insert("path", &current_path);
         context.insert(
@@ -373,6 +422,7 @@ async fn edit_user_submit(
         timezone: None,
         language: None,
         data: None,
+        fields,
     };
 
     let user_ctx = admin_user_context(&current_user);
@@ -646,6 +696,12 @@ async fn edit_role_submit(
         errors.push("Role name is required.".to_string());
     }
 
+    if (role_id == ANONYMOUS_ROLE_ID || role_id == AUTHENTICATED_ROLE_ID)
+        && form.name != existing_role.name
+    {
+        errors.push("Built-in roles cannot be renamed.".to_string());
+    }
+
     // Check if new name is taken by someone else
     if form.name != existing_role.name
         && let Ok(Some(_)) = state.roles().find_by_name(&form.name).await
@@ -762,7 +818,10 @@ async fn permissions_matrix(State(state): State<AppState>, session: Session) ->
     let mut context = tera::Context::new();
     context.insert("roles", &roles);
     context.insert("role_permissions", &role_permissions);
-    context.insert("available_permissions", &AVAILABLE_PERMISSIONS);
+    context.insert(
+        "available_permissions",
+        &available_permissions(&state).await,
+    );
     context.insert("csrf_token", &csrf_token);
     context.insert("form_build_id", &form_build_id);
     context.insert("path", "/admin/people/permissions");
@@ -795,15 +854,17 @@ async fn save_permissions(
         }
     };
 
+    let available = available_permissions(&state).await;
+
     // Process form data - permissions are submitted as "perm_{role_id}_{permission}"
     for role in &roles {
-        let desired: Vec<String> = AVAILABLE_PERMISSIONS
+        let desired: Vec<String> = available
             .iter()
             .filter(|permission| {
                 let key = format!("perm_{}_{}", role.id, permission.replace(' ', "_"));
                 form.permissions.contains_key(&key)
             })
-            .map(|p| (*p).to_string())
+            .cloned()
             .collect();
 
         if let Err(e) = state.roles().save_permissions(role.id, &desired).await {
@@ -0,0 +1,219 @@
+//! Admin content listing API (admin only).
+//!
+//! - `GET /api/admin/content` — filtered, sorted, paginated item list
+//! - `GET /api/admin/content/views` — the admin's saved views
+//! - `PUT|DELETE /api/admin/content/views/{name}` — save or delete a view
+//!
+//! Mutating requests require the `X-CSRF-Token` header.
+
+use std::collections::BTreeMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use trovato_sdk::types::SortDirection;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::User;
+use crate::services::content_listing::{
+    self, ContentFilter, ContentRow, ContentSort, MAX_SAVED_VIEWS,
+};
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::state::AppState;
+
+use super::helpers::{require_admin, require_csrf_header};
+
+/// Create the admin content listing API router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/admin/content", get(list_content))
+        .route("/api/admin/content/views", get(list_views))
+        .route(
+            "/api/admin/content/views/{name}",
+            put(save_view).delete(delete_view),
+        )
+}
+
+/// Require an admin session, and the CSRF header for mutating requests.
+async fn require_admin_user(
+    state: &AppState,
+    session: &Session,
+    headers: Option<&HeaderMap>,
+) -> Result<User, AppError> {
+    let user = require_admin(state, session)
+        .await
+        .map_err(|_| AppError::forbidden("Admin access required"))?;
+    if let Some(headers) = headers {
+        require_csrf_header(session, headers)
+            .await
+            .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    }
+    Ok(user)
+}
+
+/// Query parameters of the content listing.
+///
+/// Filters are listed explicitly rather than flattening [`ContentFilter`]:
+/// query strings only deserialize numbers into named fields.
+#[derive(Debug, Deserialize)]
+struct ListParams {
+    /// Saved view to start from; other parameters override its values.
+    view: Option<String>,
+    #[serde(rename = "type")]
+    item_type: Option<String>,
+    status: Option<i16>,
+    stage: Option<Uuid>,
+    author: Option<Uuid>,
+    language: Option<String>,
+    changed_after: Option<i64>,
+    changed_before: Option<i64>,
+    sort: Option<ContentSort>,
+    direction: Option<SortDirection>,
+    #[serde(default = "default_page")]
+    page: i64,
+    per_page: Option<i64>,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+impl ListParams {
+    /// The filter set by the request itself; empty strings count as unset.
+    fn filter(&self) -> ContentFilter {
+        let non_empty = |s: &Option<String>| s.clone().filter(|s| !s.is_empty());
+        ContentFilter {
+            item_type: non_empty(&self.item_type),
+            status: self.status,
+            stage: self.stage,
+            author: self.author,
+            language: non_empty(&self.language),
+            changed_after: self.changed_after,
+            changed_before: self.changed_before,
+            sort: self.sort,
+            direction: self.direction,
+        }
+    }
+}
+
+/// Content listing response.
+#[derive(Debug, Serialize)]
+struct ContentList {
+    data: Vec<ContentRow>,
+    total: i64,
+    page: i64,
+    per_page: i64,
+    /// The filter applied, including the saved view's values.
+    filter: ContentFilter,
+    /// Set when the requested `per_page` was clamped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+/// List items for the admin content screen.
+///
+/// GET /api/admin/content?type=blog&status=0&sort=title&direction=asc
+async fn list_content(
+    State(state): State<AppState>,
+    session: Session,
+    Query(params): Query<ListParams>,
+) -> Result<Json<ContentList>, AppError> {
+    let user = require_admin_user(&state, &session, None).await?;
+
+    let mut filter = match params.view.as_deref().filter(|v| !v.is_empty()) {
+        Some(name) => content_listing::saved_views(state.db(), user.id)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load saved content views"))?
+            .remove(name)
+            .ok_or_else(|| AppError::not_found("content view"))?,
+        None => ContentFilter::default(),
+    };
+    filter.merge(params.filter());
+
+    let page_size = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, params.per_page);
+    let per_page = page_size.limit;
+    let page = params.page.max(1);
+
+    let (data, total) = content_listing::list(state.db(), &filter, per_page, (page - 1) * per_page)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list content"))?;
+
+    Ok(Json(ContentList {
+        data,
+        total,
+        page,
+        per_page,
+        filter,
+        warning: page_size.warning,
+    }))
+}
+
+/// List the admin's saved views, by name.
+///
+/// GET /api/admin/content/views
+async fn list_views(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<BTreeMap<String, ContentFilter>>, AppError> {
+    let user = require_admin_user(&state, &session, None).await?;
+    let views = content_listing::saved_views(state.db(), user.id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load saved content views"))?;
+    Ok(Json(views))
+}
+
+/// Save a filter under a name, replacing any view of that name.
+///
+/// PUT /api/admin/content/views/{name}
+async fn save_view(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(filter): Json<ContentFilter>,
+) -> Result<Json<ContentFilter>, AppError> {
+    let user = require_admin_user(&state, &session, Some(&headers)).await?;
+    let name = content_listing::normalize_view_name(&name).map_err(AppError::bad_request)?;
+    if let Some(ref item_type) = filter.item_type
+        && !state.content_types().exists(item_type)
+    {
+        return Err(AppError::bad_request(format!(
+            "unknown item type '{item_type}'"
+        )));
+    }
+
+    let saved = content_listing::save_view(state.db(), user.id, &name, &filter)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save content view"))?;
+    if !saved {
+        return Err(AppError::bad_request(format!(
+            "at most {MAX_SAVED_VIEWS} views can be saved"
+        )));
+    }
+    Ok(Json(filter))
+}
+
+/// Delete a saved view.
+///
+/// DELETE /api/admin/content/views/{name}
+async fn delete_view(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let user = require_admin_user(&state, &session, Some(&headers)).await?;
+    let removed = content_listing::delete_view(state.db(), user.id, name.trim())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete content view"))?;
+    if !removed {
+        return Err(AppError::not_found("content view"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
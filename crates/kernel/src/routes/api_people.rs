@@ -0,0 +1,560 @@
+//! People administration API (admin only): registration mode, account
+//! approval, invitations, roles and role assignment.
+//!
+//! - `GET|PUT /api/registration` — registration mode
+//! - `GET /api/users/pending` — verified accounts awaiting approval
+//! - `POST /api/users/{id}/approve` — activate an account awaiting approval
+//! - `GET|POST /api/invitations`, `DELETE /api/invitations/{id}`
+//! - `GET|POST /api/roles`, `GET|PATCH|DELETE /api/roles/{id}`
+//! - `GET /api/users/{id}/roles`, `PUT|DELETE /api/users/{id}/roles/{role_id}`
+//!
+//! Mutating requests require the `X-CSRF-Token` header.
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::role::well_known::{ANONYMOUS_ROLE_ID, AUTHENTICATED_ROLE_ID};
+use crate::models::{Role, SiteConfig, UpdateUser, User, UserInvitation};
+use crate::services::registration::{self, RegistrationMode};
+use crate::state::AppState;
+
+use super::helpers::{admin_user_context, is_valid_email, require_admin, require_csrf_header};
+
+/// Create the people administration API router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/registration",
+            get(get_registration).put(update_registration),
+        )
+        .route("/api/users/pending", get(list_pending))
+        .route("/api/users/{id}/approve", post(approve_user))
+        .route(
+            "/api/invitations",
+            get(list_invitations).post(create_invitation),
+        )
+        .route("/api/invitations/{id}", delete(revoke_invitation))
+        .route("/api/roles", get(list_roles).post(create_role))
+        .route(
+            "/api/roles/{id}",
+            get(get_role).patch(update_role).delete(delete_role),
+        )
+        .route("/api/users/{id}/roles", get(list_user_roles))
+        .route(
+            "/api/users/{id}/roles/{role_id}",
+            put(assign_role).delete(unassign_role),
+        )
+}
+
+/// Require an admin session, and the CSRF header for mutating requests.
+async fn require_admin_user(
+    state: &AppState,
+    session: &Session,
+    headers: Option<&HeaderMap>,
+) -> Result<User, AppError> {
+    let user = require_admin(state, session)
+        .await
+        .map_err(|_| AppError::forbidden("Admin access required"))?;
+    if let Some(headers) = headers {
+        require_csrf_header(session, headers)
+            .await
+            .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    }
+    Ok(user)
+}
+
+// ─── Registration mode ───────────────────────────────────────────────────────
+
+/// Registration mode request and response body.
+#[derive(Debug, Serialize, Deserialize)]
+struct RegistrationSettings {
+    mode: RegistrationMode,
+}
+
+/// GET /api/registration
+async fn get_registration(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<RegistrationSettings>, AppError> {
+    require_admin_user(&state, &session, None).await?;
+    Ok(Json(RegistrationSettings {
+        mode: RegistrationMode::load(state.db()).await,
+    }))
+}
+
+/// PUT /api/registration
+async fn update_registration(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(body): Json<RegistrationSettings>,
+) -> Result<Json<RegistrationSettings>, AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+
+    body.mode
+        .save(state.db())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save registration mode"))?;
+
+    tracing::info!(mode = ?body.mode, "registration mode changed");
+    Ok(Json(body))
+}
+
+// ─── Approval ────────────────────────────────────────────────────────────────
+
+/// Account awaiting approval.
+#[derive(Debug, Serialize)]
+struct PendingUser {
+    id: Uuid,
+    name: String,
+    mail: String,
+    created: i64,
+}
+
+/// GET /api/users/pending
+async fn list_pending(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<PendingUser>>, AppError> {
+    require_admin_user(&state, &session, None).await?;
+
+    let users = User::list_pending_approval(state.db())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list users awaiting approval"))?;
+
+    Ok(Json(
+        users
+            .into_iter()
+            .map(|u| PendingUser {
+                id: u.id,
+                name: u.name,
+                mail: u.mail,
+                created: u.created.timestamp(),
+            })
+            .collect(),
+    ))
+}
+
+/// Activate an account awaiting approval.
+///
+/// POST /api/users/{id}/approve
+async fn approve_user(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let admin = require_admin_user(&state, &session, Some(&headers)).await?;
+
+    let user = state
+        .users()
+        .find_by_id(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load user"))?
+        .ok_or_else(|| AppError::not_found_id("user", id))?;
+    if !registration::is_pending_approval(&user) {
+        return Err(AppError::conflict("User is not awaiting approval"));
+    }
+
+    state
+        .users()
+        .update(
+            id,
+            UpdateUser {
+                status: Some(1),
+                data: Some(registration::with_pending_approval(&user.data, false)),
+                ..Default::default()
+            },
+            &admin_user_context(&admin),
+        )
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "approve user"))?;
+
+    tracing::info!(user_id = %id, approved_by = %admin.id, "user approved");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ─── Invitations ─────────────────────────────────────────────────────────────
+
+/// Invitation request body.
+#[derive(Debug, Deserialize)]
+struct CreateInvitationRequest {
+    mail: String,
+    /// Roles granted when the invitation is accepted.
+    #[serde(default)]
+    role_ids: Vec<Uuid>,
+}
+
+/// Created invitation.
+#[derive(Debug, Serialize)]
+struct CreateInvitationResponse {
+    #[serde(flatten)]
+    invitation: UserInvitation,
+    /// Whether the invitation email was sent.
+    email_sent: bool,
+}
+
+/// GET /api/invitations — pending invitations.
+async fn list_invitations(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<UserInvitation>>, AppError> {
+    require_admin_user(&state, &session, None).await?;
+
+    let invitations = UserInvitation::list_pending(state.db())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list invitations"))?;
+    Ok(Json(invitations))
+}
+
+/// Invite an address to register and email the invitation link.
+///
+/// POST /api/invitations
+async fn create_invitation(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(body): Json<CreateInvitationRequest>,
+) -> Result<(StatusCode, Json<CreateInvitationResponse>), AppError> {
+    let admin = require_admin_user(&state, &session, Some(&headers)).await?;
+
+    let mail = body.mail.trim();
+    if !is_valid_email(mail) {
+        return Err(AppError::validation(vec![AppError::field_error(
+            "mail",
+            "invalid",
+            "Please enter a valid email address.",
+        )]));
+    }
+    if let Ok(Some(_)) = state.users().find_by_mail(mail).await {
+        return Err(AppError::conflict(
+            "An account with this email already exists",
+        ));
+    }
+    for role_id in &body.role_ids {
+        check_assignable_role(&state, *role_id).await?;
+    }
+
+    let (invitation, token) =
+        UserInvitation::create(state.db(), mail, &body.role_ids, Some(admin.id))
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "create invitation"))?;
+
+    let mut email_sent = false;
+    if let Some(email_service) = state.email() {
+        let site_name = SiteConfig::site_name(state.db()).await.unwrap_or_default();
+        match email_service
+            .send_invitation_email(mail, &token, &site_name)
+            .await
+        {
+            Ok(()) => email_sent = true,
+            Err(e) => tracing::error!(error = %e, "failed to send invitation email"),
+        }
+    } else {
+        tracing::warn!(invitation_id = %invitation.id, "SMTP not configured; invitation not sent");
+    }
+
+    tracing::info!(invitation_id = %invitation.id, invited_by = %admin.id, "invitation created");
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateInvitationResponse {
+            invitation,
+            email_sent,
+        }),
+    ))
+}
+
+/// DELETE /api/invitations/{id}
+async fn revoke_invitation(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+
+    let revoked = UserInvitation::revoke(state.db(), id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "revoke invitation"))?;
+    if !revoked {
+        return Err(AppError::not_found_id("invitation", id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ─── Roles ───────────────────────────────────────────────────────────────────
+
+/// Role with its permissions.
+#[derive(Debug, Serialize)]
+struct RoleResponse {
+    #[serde(flatten)]
+    role: Role,
+    permissions: Vec<String>,
+}
+
+/// Role create request body.
+#[derive(Debug, Deserialize)]
+struct CreateRoleRequest {
+    name: String,
+    #[serde(default)]
+    permissions: Vec<String>,
+}
+
+/// Role update request body; omitted fields are left unchanged.
+#[derive(Debug, Deserialize)]
+struct UpdateRoleRequest {
+    name: Option<String>,
+    permissions: Option<Vec<String>>,
+}
+
+async fn role_response(state: &AppState, role: Role) -> Result<RoleResponse, AppError> {
+    let permissions = state
+        .roles()
+        .get_permissions(role.id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load role permissions"))?;
+    Ok(RoleResponse { role, permissions })
+}
+
+/// Check a role name, rejecting duplicates of another role.
+async fn check_role_name(
+    state: &AppState,
+    name: &str,
+    current: Option<Uuid>,
+) -> Result<(), AppError> {
+    if name.is_empty() {
+        return Err(AppError::validation(vec![AppError::field_error(
+            "name",
+            "required",
+            "Role name is required.",
+        )]));
+    }
+    if let Ok(Some(existing)) = state.roles().find_by_name(name).await
+        && Some(existing.id) != current
+    {
+        return Err(AppError::conflict(format!(
+            "A role named '{name}' already exists"
+        )));
+    }
+    Ok(())
+}
+
+/// Check that a role exists and may be assigned to users.
+///
+/// The anonymous and authenticated roles are implied, never assigned.
+async fn check_assignable_role(state: &AppState, role_id: Uuid) -> Result<(), AppError> {
+    if role_id == ANONYMOUS_ROLE_ID || role_id == AUTHENTICATED_ROLE_ID {
+        return Err(AppError::bad_request(
+            "The anonymous and authenticated roles cannot be assigned",
+        ));
+    }
+    state
+        .roles()
+        .find_by_id(role_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load role"))?
+        .ok_or_else(|| AppError::not_found_id("role", role_id))?;
+    Ok(())
+}
+
+/// GET /api/roles
+async fn list_roles(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<RoleResponse>>, AppError> {
+    require_admin_user(&state, &session, None).await?;
+
+    let roles = state
+        .roles()
+        .list()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list roles"))?;
+    let mut response = Vec::with_capacity(roles.len());
+    for role in roles {
+        response.push(role_response(&state, role).await?);
+    }
+    Ok(Json(response))
+}
+
+/// POST /api/roles
+async fn create_role(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(body): Json<CreateRoleRequest>,
+) -> Result<(StatusCode, Json<RoleResponse>), AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+
+    let name = body.name.trim();
+    check_role_name(&state, name, None).await?;
+
+    let role = state
+        .roles()
+        .create(name)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "create role"))?;
+    if !body.permissions.is_empty() {
+        state
+            .roles()
+            .save_permissions(role.id, &body.permissions)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "save role permissions"))?;
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(role_response(&state, role).await?),
+    ))
+}
+
+/// GET /api/roles/{id}
+async fn get_role(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RoleResponse>, AppError> {
+    require_admin_user(&state, &session, None).await?;
+
+    let role = state
+        .roles()
+        .find_by_id(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load role"))?
+        .ok_or_else(|| AppError::not_found_id("role", id))?;
+    Ok(Json(role_response(&state, role).await?))
+}
+
+/// PATCH /api/roles/{id}
+async fn update_role(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateRoleRequest>,
+) -> Result<Json<RoleResponse>, AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+
+    let mut role = state
+        .roles()
+        .find_by_id(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load role"))?
+        .ok_or_else(|| AppError::not_found_id("role", id))?;
+
+    if let Some(name) = body.name.as_deref().map(str::trim) {
+        if (id == ANONYMOUS_ROLE_ID || id == AUTHENTICATED_ROLE_ID) && name != role.name {
+            return Err(AppError::bad_request("Built-in roles cannot be renamed"));
+        }
+        check_role_name(&state, name, Some(id)).await?;
+        role = state
+            .roles()
+            .update(id, name)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "update role"))?
+            .ok_or_else(|| AppError::not_found_id("role", id))?;
+    }
+    if let Some(permissions) = &body.permissions {
+        state
+            .roles()
+            .save_permissions(id, permissions)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "save role permissions"))?;
+    }
+
+    Ok(Json(role_response(&state, role).await?))
+}
+
+/// DELETE /api/roles/{id}
+async fn delete_role(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+
+    if id == ANONYMOUS_ROLE_ID || id == AUTHENTICATED_ROLE_ID {
+        return Err(AppError::bad_request("Built-in roles cannot be deleted"));
+    }
+    let deleted = state
+        .roles()
+        .delete(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete role"))?;
+    if !deleted {
+        return Err(AppError::not_found_id("role", id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ─── Role assignment ─────────────────────────────────────────────────────────
+
+/// Load a user or fail with 404.
+async fn find_user(state: &AppState, id: Uuid) -> Result<User, AppError> {
+    state
+        .users()
+        .find_by_id(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load user"))?
+        .ok_or_else(|| AppError::not_found_id("user", id))
+}
+
+/// GET /api/users/{id}/roles
+async fn list_user_roles(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<Role>>, AppError> {
+    require_admin_user(&state, &session, None).await?;
+    find_user(&state, id).await?;
+
+    let roles = state
+        .roles()
+        .get_user_roles(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load user roles"))?;
+    Ok(Json(roles))
+}
+
+/// PUT /api/users/{id}/roles/{role_id}
+async fn assign_role(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((id, role_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+    find_user(&state, id).await?;
+    check_assignable_role(&state, role_id).await?;
+
+    state
+        .roles()
+        .assign_to_user(id, role_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "assign role"))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/users/{id}/roles/{role_id}
+async fn unassign_role(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((id, role_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, AppError> {
+    require_admin_user(&state, &session, Some(&headers)).await?;
+    find_user(&state, id).await?;
+
+    state
+        .roles()
+        .remove_from_user(id, role_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "remove role"))?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,26 +1,30 @@
 //! Versioned REST API (v1) for content management.
 //!
 //! Provides a stable, paginated JSON API with envelope responses.
-//! All list endpoints return `{ data, total, page, per_page }`.
+//! All list endpoints return `{ data, total, page, per_page }`, plus a
+//! `warning` when the requested `per_page` was clamped.
 //! Single-resource endpoints return `{ data }`.
 //! Error endpoints return `{ error, status }`.
 
 use axum::{
     Json, Router,
     body::Body,
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
 };
 use serde::Serialize;
 use std::collections::HashMap;
 use tower_sessions::Session;
 use uuid::Uuid;
 
-use crate::file::service::FileStatus;
+use crate::content::{ItemPatch, ItemValidationFailed};
+use crate::file::library::ALT_TEXT_FIELD;
+use crate::file::{MediaQuery, MediaUsage};
 use crate::models::stage::LIVE_STAGE_ID;
 use crate::routes::auth::SESSION_USER_ID;
+use crate::services::pagination::{PageClass, PaginationPolicy};
 use crate::state::AppState;
 
 // -------------------------------------------------------------------------
@@ -34,6 +38,9 @@ struct ListEnvelope<T: Serialize> {
     total: u64,
     page: i64,
     per_page: i64,
+    /// Set when the requested `per_page` was clamped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
 }
 
 /// Error envelope.
@@ -52,8 +59,7 @@ struct ErrorEnvelope {
 struct ListParams {
     #[serde(default = "default_page")]
     page: i64,
-    #[serde(default = "default_per_page")]
-    per_page: i64,
+    per_page: Option<i64>,
     q: Option<String>,
 }
 
@@ -61,10 +67,6 @@ fn default_page() -> i64 {
     1
 }
 
-fn default_per_page() -> i64 {
-    25
-}
-
 /// Create the v1 API router.
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -72,6 +74,8 @@ pub fn router() -> Router<AppState> {
         .route("/api/v1/user/export", get(export_user_data))
         .route("/api/v1/items/autocomplete", get(autocomplete_items))
         .route("/api/v1/media/browse", get(browse_media))
+        .route("/api/v1/media/alt-text", post(edit_alt_text))
+        .route("/api/v1/media/{id}/usage", get(media_usage))
         .route("/api/openapi.json", get(openapi_spec))
         .route(
             "/api/v1/page-builder/components",
@@ -113,11 +117,6 @@ fn error_response(status: StatusCode, message: &str) -> (StatusCode, Json<ErrorE
     )
 }
 
-/// Clamp per_page to a sane range.
-fn clamp_per_page(per_page: i64) -> i64 {
-    per_page.clamp(1, 100)
-}
-
 // -------------------------------------------------------------------------
 // Search endpoint
 // -------------------------------------------------------------------------
@@ -130,7 +129,10 @@ async fn search(
     session: Session,
     Query(params): Query<ListParams>,
 ) -> impl IntoResponse {
-    let per_page = clamp_per_page(params.per_page);
+    let page_size = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Search, params.per_page);
+    let per_page = page_size.limit;
     let page = params.page.max(1);
 
     let query = params.q.as_deref().unwrap_or("");
@@ -140,6 +142,7 @@ async fn search(
             total: 0,
             page,
             per_page,
+            warning: page_size.warning,
         })
         .into_response();
     }
@@ -161,6 +164,7 @@ async fn search(
                         "title": item.title,
                         "type": item.item_type,
                         "snippet": item.snippet,
+                        "fuzzy": item.fuzzy,
                     })
                 })
                 .collect();
@@ -170,6 +174,7 @@ async fn search(
                 total: results.total as u64,
                 page,
                 per_page,
+                warning: page_size.warning,
             })
             .into_response()
         }
@@ -388,9 +393,12 @@ async fn autocomplete_items(
 }
 
 // -------------------------------------------------------------------------
-// Media browse endpoint
+// Media library endpoints
 // -------------------------------------------------------------------------
 
+/// Most alt text edits accepted in one request.
+const MAX_ALT_TEXT_EDITS: usize = 100;
+
 /// Response envelope for the media browse API.
 #[derive(Debug, Serialize)]
 struct MediaBrowseResponse {
@@ -421,6 +429,10 @@ struct MediaItem {
     thumbnail_url: Option<String>,
     /// Upload timestamp (Unix epoch).
     created: i64,
+    /// Alt text from the media item wrapping the file, if any.
+    alt_text: Option<String>,
+    /// Number of items using the file.
+    usage_count: i64,
 }
 
 /// Browse the media library with filtering, search, and pagination.
@@ -431,7 +443,9 @@ struct MediaItem {
 /// - `page` — page number (default 1)
 /// - `page_size` — items per page (default 24, max 100)
 /// - `type` — MIME type prefix filter: `image`, `document`, or `all` (default)
-/// - `q` — case-insensitive filename search
+/// - `mime` — exact MIME type (`image/png`) or prefix (`image/`); overrides `type`
+/// - `from`, `to` — upload date range, as `YYYY-MM-DD` (inclusive) or Unix timestamps
+/// - `q` — case-insensitive filename and alt text search
 /// - `sort` — `newest` (default), `oldest`, `name`, `size`
 async fn browse_media(
     State(state): State<AppState>,
@@ -454,55 +468,57 @@ async fn browse_media(
         .and_then(|p| p.parse().ok())
         .unwrap_or(24)
         .clamp(1, 100);
-    let sort = params.get("sort").map(String::as_str).unwrap_or("newest");
-    let search = params.get("q").map(String::as_str);
 
     // Map the `type` parameter to a MIME prefix
-    let mime_prefix: Option<&str> = match params.get("type").map(String::as_str) {
-        Some("image") => Some("image/"),
-        Some("document") => Some("application/"),
+    let type_prefix = match params.get("type").map(String::as_str) {
+        Some("image") => Some("image/".to_string()),
+        Some("document") => Some("application/".to_string()),
         _ => None,
     };
 
-    let offset = (page - 1) * page_size;
-
-    let total = match state
-        .files()
-        .count_filtered_media(Some(FileStatus::Permanent), mime_prefix, search)
-        .await
-    {
-        Ok(t) => t,
-        Err(e) => {
-            tracing::error!(error = %e, "failed to count media");
-            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to browse media")
+    let mut date_bounds = [None, None];
+    for (bound, (name, end)) in date_bounds.iter_mut().zip([("from", false), ("to", true)]) {
+        if let Some(value) = params.get(name).filter(|v| !v.is_empty()) {
+            let Some(ts) = crate::file::library::parse_date_bound(value, end) else {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    &format!("`{name}` must be a YYYY-MM-DD date or a Unix timestamp"),
+                )
                 .into_response();
+            };
+            *bound = Some(ts);
         }
+    }
+    let [created_from, created_to] = date_bounds;
+
+    let query = MediaQuery {
+        search: params.get("q").filter(|q| !q.is_empty()).cloned(),
+        mime: params
+            .get("mime")
+            .filter(|m| !m.is_empty())
+            .map(|m| m.trim_end_matches('*').to_string())
+            .or(type_prefix),
+        created_from,
+        created_to,
+        sort: params.get("sort").cloned().unwrap_or_default(),
     };
 
-    let files = match state
-        .files()
-        .list_filtered_media(
-            Some(FileStatus::Permanent),
-            mime_prefix,
-            search,
-            sort,
-            page_size,
-            offset,
-        )
-        .await
-    {
-        Ok(f) => f,
-        Err(e) => {
-            tracing::error!(error = %e, "failed to list media");
-            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to browse media")
-                .into_response();
-        }
-    };
+    let offset = (page - 1) * page_size;
+    let (entries, total) =
+        match crate::file::library::search(state.db(), &query, page_size, offset).await {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to browse media");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to browse media")
+                    .into_response();
+            }
+        };
 
     let storage = state.files().storage();
-    let items: Vec<MediaItem> = files
-        .iter()
-        .map(|f| {
+    let items: Vec<MediaItem> = entries
+        .into_iter()
+        .map(|entry| {
+            let f = entry.file;
             let url = storage.public_url(&f.uri);
             let thumbnail_url = if f.filemime.starts_with("image/") {
                 Some(format!(
@@ -514,12 +530,14 @@ async fn browse_media(
             };
             MediaItem {
                 id: f.id.to_string(),
-                filename: f.filename.clone(),
-                mime_type: f.filemime.clone(),
+                filename: f.filename,
+                mime_type: f.filemime,
                 size: f.filesize,
                 url,
                 thumbnail_url,
                 created: f.created,
+                alt_text: entry.alt_text,
+                usage_count: entry.usage_count,
             }
         })
         .collect();
@@ -533,6 +551,160 @@ async fn browse_media(
     .into_response()
 }
 
+/// List the items using a media file.
+///
+/// `GET /api/v1/media/{id}/usage`
+///
+/// Unpublished items are listed only to their authors and administrators.
+async fn media_usage(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let user = crate::routes::item::get_user_context(&session, &state).await;
+    if !user.authenticated {
+        return error_response(StatusCode::UNAUTHORIZED, "Authentication required").into_response();
+    }
+
+    match state.files().get(id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return error_response(StatusCode::NOT_FOUND, "File not found").into_response();
+        }
+        Err(e) => {
+            tracing::error!(error = %e, file_id = %id, "failed to load file");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load usage")
+                .into_response();
+        }
+    }
+
+    let usage = match crate::file::library::usage(state.db(), id).await {
+        Ok(usage) => usage,
+        Err(e) => {
+            tracing::error!(error = %e, file_id = %id, "failed to load media usage");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load usage")
+                .into_response();
+        }
+    };
+
+    let is_admin = user.has_permission("administer site");
+    let data: Vec<MediaUsage> = usage
+        .into_iter()
+        .filter(|u| u.status == 1 || u.author_id == user.id || is_admin)
+        .collect();
+    let total = data.len() as u64;
+
+    Json(ListEnvelope {
+        data,
+        total,
+        page: 1,
+        per_page: i64::try_from(total).unwrap_or(i64::MAX),
+        warning: None,
+    })
+    .into_response()
+}
+
+/// Request body for bulk alt text editing.
+#[derive(Debug, serde::Deserialize)]
+struct AltTextRequest {
+    items: Vec<AltTextEdit>,
+}
+
+/// New alt text for the media items wrapping one file.
+#[derive(Debug, serde::Deserialize)]
+struct AltTextEdit {
+    file_id: Uuid,
+    alt_text: String,
+}
+
+/// Outcome of one alt text edit.
+#[derive(Debug, Serialize)]
+struct AltTextResult {
+    file_id: Uuid,
+    /// Media items updated.
+    updated: usize,
+    /// Why some or all media items were not updated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Set the alt text of many media files at once.
+///
+/// `POST /api/v1/media/alt-text` with
+/// `{"items": [{"file_id": "...", "alt_text": "..."}]}`
+///
+/// Each file's `media` items are patched like `PATCH /item/{id}`, so edit
+/// access, validation and revisions apply per item. Edits are independent:
+/// the response reports the outcome of each.
+async fn edit_alt_text(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(request): Json<AltTextRequest>,
+) -> impl IntoResponse {
+    let user = crate::routes::item::get_user_context(&session, &state).await;
+    if !user.authenticated {
+        return error_response(StatusCode::UNAUTHORIZED, "Authentication required").into_response();
+    }
+    if let Err(resp) = crate::routes::helpers::require_csrf_header(&session, &headers).await {
+        return resp.into_response();
+    }
+    if request.items.len() > MAX_ALT_TEXT_EDITS {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("At most {MAX_ALT_TEXT_EDITS} edits per request"),
+        )
+        .into_response();
+    }
+
+    let mut results = Vec::with_capacity(request.items.len());
+    for edit in request.items {
+        let mut result = AltTextResult {
+            file_id: edit.file_id,
+            updated: 0,
+            error: None,
+        };
+        let item_ids = match crate::file::library::media_items(state.db(), edit.file_id).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::error!(error = %e, file_id = %edit.file_id, "failed to load media items");
+                result.error = Some("Failed to load media items".to_string());
+                results.push(result);
+                continue;
+            }
+        };
+        if item_ids.is_empty() {
+            result.error = Some("No media item wraps this file".to_string());
+        }
+
+        for item_id in item_ids {
+            let patch = ItemPatch {
+                fields: serde_json::json!({ (ALT_TEXT_FIELD): edit.alt_text.trim() }),
+                unmodified_since: None,
+                log: Some("Bulk alt text edit".to_string()),
+            };
+            match state.items().patch(item_id, patch, &user).await {
+                Ok(Some(_)) => result.updated += 1,
+                Ok(None) => {}
+                Err(e) => {
+                    let message = if let Some(failed) = e.downcast_ref::<ItemValidationFailed>() {
+                        failed.messages().join(" ")
+                    } else if e.to_string().contains("access denied") {
+                        "Access denied".to_string()
+                    } else {
+                        tracing::error!(error = %e, item_id = %item_id, "alt text edit failed");
+                        "Failed to update media item".to_string()
+                    };
+                    result.error = Some(format!("{item_id}: {message}"));
+                }
+            }
+        }
+        results.push(result);
+    }
+
+    Json(serde_json::json!({ "data": results })).into_response()
+}
+
 /// Get field names marked `personal_data: true` for a content type.
 ///
 /// Returns an empty vec if the type is not found or has no PII fields.
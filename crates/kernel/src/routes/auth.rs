@@ -1,6 +1,6 @@
 //! Authentication routes (login, logout, registration, email verification).
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
@@ -11,15 +11,21 @@ use tracing::info;
 
 use crate::error::AppError;
 use crate::form::csrf::generate_csrf_token;
+use crate::middleware::ClientIp;
 use crate::middleware::language::SESSION_ACTIVE_LANGUAGE;
+use crate::models::author::{AuthorSettings, MAX_BIO_LENGTH};
 use crate::models::email_verification::{
     EmailVerificationToken, PURPOSE_EMAIL_CHANGE, PURPOSE_REGISTRATION,
 };
-use crate::models::{CreateUser, SiteConfig, User};
+use crate::models::{CreateUser, SiteConfig, User, UserInvitation};
 use crate::routes::helpers::{
-    CsrfOnlyForm, JsonSuccess, html_escape, is_valid_email, is_valid_timezone, require_csrf,
-    validate_password, validate_username,
+    CsrfOnlyForm, JsonSuccess, check_password_policy, html_escape, is_valid_email,
+    is_valid_timezone, require_csrf, validate_password, validate_username,
 };
+use crate::services::mfa::{self, UserMfa};
+use crate::services::registration::{self, RegistrationMode};
+use crate::services::user_fields;
+use crate::services::workspace;
 use crate::state::AppState;
 
 /// Check if an anyhow error wraps a sqlx unique constraint violation.
@@ -42,6 +48,10 @@ pub const SESSION_ACTIVE_STAGE: &str = "active_stage";
 /// Session key for remember_me flag.
 pub const SESSION_REMEMBER_ME: &str = "remember_me";
 
+/// Session key for the login time (Unix timestamp), which anchors the
+/// session lifetime when sliding expiration is disabled.
+pub const SESSION_AUTHENTICATED_AT: &str = "authenticated_at";
+
 /// Login request body.
 #[derive(Deserialize)]
 pub struct LoginRequest {
@@ -49,6 +59,19 @@ pub struct LoginRequest {
     pub password: String,
     #[serde(default)]
     pub remember_me: bool,
+    /// TOTP or recovery code, for accounts with two-factor authentication.
+    #[serde(default)]
+    pub mfa_code: Option<String>,
+}
+
+/// Outcome of a correct username and password.
+pub(crate) enum LoginOutcome {
+    /// The session is established.
+    LoggedIn,
+    /// The account has two-factor authentication; a code is still needed.
+    MfaRequired(Box<User>),
+    /// The user's role requires two-factor authentication but none is set up.
+    MfaEnrollmentRequired(Box<User>),
 }
 
 /// Typed login error for explicit status code mapping.
@@ -56,11 +79,15 @@ pub struct LoginRequest {
 /// Avoids brittle substring matching on error strings by encoding
 /// the error category in the enum variant.
 #[derive(Debug)]
-enum LoginError {
+pub(crate) enum LoginError {
     /// Account temporarily locked due to too many failed attempts (429).
     Locked(String),
     /// Invalid credentials — wrong username or password (401).
     InvalidCredentials,
+    /// Wrong or already used two-factor code (401).
+    InvalidMfaCode,
+    /// Correct password, but older than the password policy allows (403).
+    PasswordExpired,
     /// Internal server error — database failure, etc. (500).
     Internal(String),
 }
@@ -69,15 +96,20 @@ impl LoginError {
     fn status_code(&self) -> StatusCode {
         match self {
             LoginError::Locked(_) => StatusCode::TOO_MANY_REQUESTS,
-            LoginError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            LoginError::InvalidCredentials | LoginError::InvalidMfaCode => StatusCode::UNAUTHORIZED,
+            LoginError::PasswordExpired => StatusCode::FORBIDDEN,
             LoginError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
-    fn message(&self) -> &str {
+    pub(crate) fn message(&self) -> &str {
         match self {
             LoginError::Locked(msg) => msg,
             LoginError::InvalidCredentials => "Invalid username or password",
+            LoginError::InvalidMfaCode => "Invalid authentication code",
+            LoginError::PasswordExpired => {
+                "Your password has expired. Use password reset to choose a new one."
+            }
             LoginError::Internal(msg) => msg,
         }
     }
@@ -89,6 +121,25 @@ impl std::fmt::Display for LoginError {
     }
 }
 
+impl From<LoginError> for AppError {
+    fn from(e: LoginError) -> Self {
+        match e {
+            LoginError::Locked(_) => AppError::RateLimited {
+                retry_after_secs: 60,
+                category: "login".into(),
+            },
+            LoginError::InvalidCredentials => {
+                AppError::unauthorized("Invalid username or password")
+            }
+            LoginError::InvalidMfaCode => AppError::unauthorized("Invalid authentication code"),
+            LoginError::PasswordExpired => AppError::forbidden(
+                "Your password has expired. Use password reset to choose a new one.",
+            ),
+            LoginError::Internal(msg) => AppError::internal_ctx(anyhow::anyhow!(msg), "login"),
+        }
+    }
+}
+
 /// Login form handler.
 ///
 /// GET /user/login
@@ -100,6 +151,10 @@ async fn login_form(State(state): State<AppState>, session: Session) -> Response
     // Render login form
     let mut context = tera::Context::new();
     context.insert("csrf_token", &csrf_token);
+    context.insert(
+        "oidc_providers",
+        &super::oidc::login_providers(&state).await,
+    );
     super::helpers::inject_site_context(&state, &session, &mut context, "/user/login").await;
 
     match state.theme().tera().render("user/login.html", &context) {
@@ -141,12 +196,12 @@ pub struct LoginFormRequest {
 /// POST /user/login (form data)
 async fn login_form_submit(
     State(state): State<AppState>,
+    client_ip: ClientIp,
     session: Session,
-    headers: axum::http::HeaderMap,
     Form(form): Form<LoginFormRequest>,
 ) -> Response {
     // Rate limit login attempts by IP
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     if let Err(retry_after) = state.rate_limiter().check("login", &client_id).await {
         return crate::middleware::rate_limit_response(retry_after);
     }
@@ -161,22 +216,50 @@ async fn login_form_submit(
         username: form.username,
         password: form.password,
         remember_me: form.remember_me.is_some(),
+        mfa_code: None,
     };
 
     // Perform login
-    match do_login(&state, &session, &request).await {
-        Ok(_) => Redirect::to("/").into_response(),
+    match do_login(&state, &session, &request, &client_id).await {
+        Ok(LoginOutcome::LoggedIn) => Redirect::to("/").into_response(),
+        Ok(LoginOutcome::MfaRequired(user)) => {
+            super::mfa::start_challenge(
+                &state,
+                &session,
+                &request.username,
+                request.remember_me,
+                user.id,
+                false,
+            )
+            .await
+        }
+        Ok(LoginOutcome::MfaEnrollmentRequired(user)) => {
+            super::mfa::start_challenge(
+                &state,
+                &session,
+                &request.username,
+                request.remember_me,
+                user.id,
+                true,
+            )
+            .await
+        }
         Err(e) => render_login_error(&state, &session, e.message()).await,
     }
 }
 
 /// Render login form with error message.
-async fn render_login_error(state: &AppState, session: &Session, error: &str) -> Response {
+pub(crate) async fn render_login_error(
+    state: &AppState,
+    session: &Session,
+    error: &str,
+) -> Response {
     let csrf_token = generate_csrf_token(session).await;
 
     let mut context = tera::Context::new();
     context.insert("csrf_token", &csrf_token);
     context.insert("error", error);
+    context.insert("oidc_providers", &super::oidc::login_providers(state).await);
     super::helpers::inject_site_context(state, session, &mut context, "/user/login").await;
 
     match state.theme().tera().render("user/login.html", &context) {
@@ -245,21 +328,24 @@ async fn setup_session(
             LoginError::Internal("Internal server error".to_string())
         })?;
 
+    session
+        .insert(SESSION_AUTHENTICATED_AT, chrono::Utc::now().timestamp())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "failed to insert authenticated_at into session");
+            LoginError::Internal("Internal server error".to_string())
+        })?;
+
     Ok(())
 }
 
-/// Perform login and return typed error on failure.
-async fn do_login(
-    state: &AppState,
-    session: &Session,
-    request: &LoginRequest,
-) -> Result<(), LoginError> {
-    // Check if account is locked
-    match state.lockout().is_locked(&request.username).await {
+/// Fail if the account is temporarily locked.
+async fn check_lockout(state: &AppState, username: &str) -> Result<(), LoginError> {
+    match state.lockout().is_locked(username).await {
         Ok(true) => {
             let remaining = state
                 .lockout()
-                .get_lockout_remaining(&request.username)
+                .get_lockout_remaining(username)
                 .await
                 .unwrap_or(None);
 
@@ -278,6 +364,81 @@ async fn do_login(
             tracing::error!(error = %e, "failed to check lockout status");
         }
     }
+    Ok(())
+}
+
+/// Fail if the client IP address is temporarily locked.
+async fn check_ip_lockout(state: &AppState, client_ip: &str) -> Result<(), LoginError> {
+    match state.lockout().is_ip_locked(client_ip).await {
+        Ok(true) => {
+            let remaining = state
+                .lockout()
+                .get_ip_lockout_remaining(client_ip)
+                .await
+                .unwrap_or(None);
+
+            let message = if let Some(secs) = remaining {
+                format!(
+                    "Too many failed login attempts from your network. Try again in {} minutes.",
+                    (secs / 60) + 1
+                )
+            } else {
+                "Too many failed login attempts from your network. Try again later.".to_string()
+            };
+            return Err(LoginError::Locked(message));
+        }
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(error = %e, "failed to check IP lockout status");
+        }
+    }
+    Ok(())
+}
+
+/// Count a failed attempt against the client IP address, returning `Locked`
+/// if it locks the address.
+async fn record_ip_failure(state: &AppState, client_ip: &str, error: LoginError) -> LoginError {
+    match state.lockout().record_failed_ip_attempt(client_ip).await {
+        Ok((true, _)) => LoginError::Locked(
+            "Too many failed login attempts from your network. Try again later.".to_string(),
+        ),
+        Ok((false, _)) => error,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to record failed attempt for IP address");
+            error
+        }
+    }
+}
+
+/// Count a failed attempt, returning `Locked` if it locks the account.
+async fn record_failure(state: &AppState, username: &str, error: LoginError) -> LoginError {
+    match state.lockout().record_failed_attempt(username).await {
+        Ok((true, _)) => LoginError::Locked(
+            "Account temporarily locked due to too many failed attempts.".to_string(),
+        ),
+        Ok((false, _)) => error,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to record failed attempt");
+            error
+        }
+    }
+}
+
+/// Perform login and return typed error on failure.
+///
+/// With two-factor authentication the session is not established yet: the
+/// caller must verify a code with [`verify_mfa_code`] (or enroll the user)
+/// and then call [`complete_login`].
+///
+/// Failures count against both the account and `client_ip`.
+async fn do_login(
+    state: &AppState,
+    session: &Session,
+    request: &LoginRequest,
+    client_ip: &str,
+) -> Result<LoginOutcome, LoginError> {
+    check_ip_lockout(state, client_ip).await?;
+    check_lockout(state, &request.username).await?;
 
     // Find user by username
     let user = match state.users().find_by_name(&request.username).await {
@@ -290,7 +451,7 @@ async fn do_login(
             {
                 tracing::warn!(error = %e, "failed to record failed login attempt");
             }
-            return Err(LoginError::InvalidCredentials);
+            return Err(record_ip_failure(state, client_ip, LoginError::InvalidCredentials).await);
         }
         Err(e) => {
             tracing::error!(error = %e, "database error during login");
@@ -307,60 +468,157 @@ async fn do_login(
         {
             tracing::warn!(error = %e, user_id = %user.id, "failed to record failed login attempt");
         }
-        return Err(LoginError::InvalidCredentials);
+        return Err(record_ip_failure(state, client_ip, LoginError::InvalidCredentials).await);
     }
 
     // Verify password
     if !user.verify_password(&request.password) {
-        match state
-            .lockout()
-            .record_failed_attempt(&request.username)
-            .await
-        {
-            Ok((locked, _)) => {
-                if locked {
-                    return Err(LoginError::Locked(
-                        "Account temporarily locked due to too many failed attempts.".to_string(),
-                    ));
-                }
-            }
-            Err(e) => {
-                tracing::error!(error = %e, user_id = %user.id, "failed to record failed attempt");
-            }
+        let error = record_failure(state, &request.username, LoginError::InvalidCredentials).await;
+        return Err(record_ip_failure(state, client_ip, error).await);
+    }
+
+    // Expired passwords are replaced through password reset.
+    match state.password_policy().is_expired(&user).await {
+        Ok(true) => return Err(LoginError::PasswordExpired),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to check password expiry");
+            return Err(LoginError::Internal("Internal server error".to_string()));
+        }
+    }
+
+    // Second factor. Failed attempts are only cleared once it passes, so
+    // re-entering the password does not reset the counter for code guesses.
+    match UserMfa::is_enabled(state.db(), user.id).await {
+        Ok(true) => return Ok(LoginOutcome::MfaRequired(Box::new(user))),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to check 2FA status");
+            return Err(LoginError::Internal("Internal server error".to_string()));
+        }
+    }
+    match mfa::is_required_for(state.db(), &user).await {
+        Ok(true) => return Ok(LoginOutcome::MfaEnrollmentRequired(Box::new(user))),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to check 2FA requirement");
+            return Err(LoginError::Internal("Internal server error".to_string()));
+        }
+    }
+
+    complete_login(
+        state,
+        session,
+        &user,
+        &request.username,
+        request.remember_me,
+    )
+    .await?;
+    Ok(LoginOutcome::LoggedIn)
+}
+
+/// Check a two-factor code for a user whose password was accepted.
+///
+/// Wrong codes count against the account lockout like wrong passwords.
+pub(crate) async fn verify_mfa_code(
+    state: &AppState,
+    username: &str,
+    user_id: uuid::Uuid,
+    code: &str,
+) -> Result<(), LoginError> {
+    check_lockout(state, username).await?;
+
+    match UserMfa::verify(state.db(), user_id, code).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(record_failure(state, username, LoginError::InvalidMfaCode).await),
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user_id, "failed to verify 2FA code");
+            Err(LoginError::Internal("Internal server error".to_string()))
         }
-        return Err(LoginError::InvalidCredentials);
     }
+}
 
-    // Successful login - clear any failed attempts
-    if let Err(e) = state.lockout().clear_attempts(&request.username).await {
+/// Finish a successful login: clear failed attempts, record the login and
+/// establish the session.
+pub(crate) async fn complete_login(
+    state: &AppState,
+    session: &Session,
+    user: &User,
+    username: &str,
+    remember_me: bool,
+) -> Result<(), LoginError> {
+    if let Err(e) = state.lockout().clear_attempts(username).await {
         tracing::warn!(error = %e, user_id = %user.id, "failed to clear login attempts");
     }
 
     // Record login (updates timestamp + dispatches tap_user_login)
-    if let Err(e) = state.users().record_login(&user).await {
+    if let Err(e) = state.users().record_login(user).await {
         tracing::warn!(error = %e, user_id = %user.id, "failed to record login");
     }
 
     // Create session
-    setup_session(session, user.id, request.remember_me).await?;
+    setup_session(session, user.id, remember_me).await?;
+
+    open_personal_workspace(state, session, user).await;
 
     info!(user_id = %user.id, "user logged in");
     Ok(())
 }
 
+/// Provision the user's personal workspace if the policy covers them, and
+/// make it the active stage when configured to.
+///
+/// Failures are logged; they never block the login.
+async fn open_personal_workspace(state: &AppState, session: &Session, user: &User) {
+    let settings = match workspace::settings(state.db()).await {
+        Ok(settings) if settings.enabled => settings,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load workspace settings");
+            return;
+        }
+    };
+
+    let entitled = user.is_admin
+        || state
+            .permissions()
+            .user_has_permission(user, workspace::USE_WORKSPACE_PERMISSION)
+            .await
+            .unwrap_or(false);
+    if !entitled {
+        return;
+    }
+
+    match workspace::provision(state.db(), user).await {
+        Ok(ws) if settings.activate_on_login => {
+            if let Err(e) = session
+                .insert(SESSION_ACTIVE_STAGE, Some(ws.stage_id.to_string()))
+                .await
+            {
+                tracing::warn!(error = %e, user_id = %user.id, "failed to activate workspace");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!(error = %e, user_id = %user.id, "failed to provision workspace");
+        }
+    }
+}
+
 /// JSON login handler.
 ///
 /// POST /user/login/json
 /// - Delegates to `do_login` for all auth logic
+/// - Accounts with two-factor authentication must send `mfa_code`
 /// - Maps typed `LoginError` variants to appropriate HTTP status codes
 async fn login(
     State(state): State<AppState>,
+    client_ip: ClientIp,
     session: Session,
-    headers: axum::http::HeaderMap,
     Json(request): Json<LoginRequest>,
 ) -> Result<Json<JsonSuccess>, AppError> {
     // Rate limit login attempts by IP
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     if let Err(retry_after) = state.rate_limiter().check("login", &client_id).await {
         return Err(AppError::RateLimited {
             retry_after_secs: retry_after,
@@ -368,22 +626,36 @@ async fn login(
         });
     }
 
-    match do_login(&state, &session, &request).await {
-        Ok(()) => Ok(Json(JsonSuccess {
-            success: true,
-            message: "Login successful".to_string(),
-        })),
-        Err(e) => Err(match e {
-            LoginError::Locked(_) => AppError::RateLimited {
-                retry_after_secs: 60,
-                category: "login".into(),
-            },
-            LoginError::InvalidCredentials => {
-                AppError::unauthorized("Invalid username or password")
-            }
-            LoginError::Internal(msg) => AppError::internal_ctx(anyhow::anyhow!(msg), "login"),
-        }),
+    match do_login(&state, &session, &request, &client_id).await? {
+        LoginOutcome::LoggedIn => {}
+        LoginOutcome::MfaRequired(user) => {
+            let Some(code) = request.mfa_code.as_deref().filter(|c| !c.trim().is_empty()) else {
+                return Err(AppError::unauthorized(
+                    "Two-factor authentication code required",
+                ));
+            };
+            verify_mfa_code(&state, &request.username, user.id, code).await?;
+            complete_login(
+                &state,
+                &session,
+                &user,
+                &request.username,
+                request.remember_me,
+            )
+            .await?;
+        }
+        LoginOutcome::MfaEnrollmentRequired(_) => {
+            return Err(AppError::forbidden(
+                "Two-factor authentication must be set up before logging in. \
+                 Log in through the website to set it up.",
+            ));
+        }
     }
+
+    Ok(Json(JsonSuccess {
+        success: true,
+        message: "Login successful".to_string(),
+    }))
 }
 
 /// Logout handler.
@@ -438,6 +710,9 @@ struct RegisterFormRequest {
     confirm_password: String,
     #[serde(rename = "_token")]
     csrf_token: String,
+    /// Invitation token carried over from the form's query string.
+    #[serde(default)]
+    invite: Option<String>,
 }
 
 /// JSON registration request body.
@@ -448,24 +723,93 @@ struct RegisterJsonRequest {
     password: String,
     #[serde(default)]
     confirm_password: Option<String>,
+    /// Invitation token, required when registration is invite-only.
+    #[serde(default)]
+    invite: Option<String>,
 }
 
-/// Check whether user registration is enabled via site config.
-async fn is_registration_enabled(state: &AppState) -> bool {
-    SiteConfig::get(state.db(), "allow_user_registration")
-        .await
-        .ok()
-        .flatten()
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false)
+/// Registration form query string.
+#[derive(Deserialize)]
+struct RegisterQuery {
+    #[serde(default)]
+    invite: Option<String>,
+}
+
+/// Why a registration attempt is refused before its input is checked.
+enum RegistrationRefused {
+    /// Registration is closed; answered with 404.
+    Closed,
+    /// Invitation missing, expired or already used; answered with 403.
+    Forbidden(&'static str),
+}
+
+impl RegistrationRefused {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Closed => (StatusCode::NOT_FOUND, "Registration is not enabled.").into_response(),
+            Self::Forbidden(message) => (StatusCode::FORBIDDEN, message).into_response(),
+        }
+    }
+
+    fn into_app_error(self) -> AppError {
+        match self {
+            Self::Closed => AppError::not_found("registration"),
+            Self::Forbidden(message) => AppError::forbidden(message),
+        }
+    }
+}
+
+/// Check the registration mode and the invitation, if any.
+///
+/// Returns the invitation to redeem, or `None` for an uninvited
+/// registration.
+async fn check_registration_access(
+    state: &AppState,
+    invite: Option<&str>,
+) -> Result<Option<UserInvitation>, RegistrationRefused> {
+    let mode = RegistrationMode::load(state.db()).await;
+    if !mode.allows_invitations() {
+        return Err(RegistrationRefused::Closed);
+    }
+
+    match invite.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(token) => match UserInvitation::find_valid(state.db(), token).await {
+            Ok(Some(invitation)) => Ok(Some(invitation)),
+            Ok(None) => Err(RegistrationRefused::Forbidden(
+                "This invitation is invalid or has expired.",
+            )),
+            Err(e) => {
+                tracing::error!(error = %e, "failed to look up invitation");
+                Err(RegistrationRefused::Forbidden(
+                    "This invitation could not be checked. Please try again later.",
+                ))
+            }
+        },
+        None if mode.allows_uninvited() => Ok(None),
+        None => Err(RegistrationRefused::Forbidden(
+            "Registration is by invitation only.",
+        )),
+    }
+}
+
+/// Require the registration to use the invited address.
+fn check_invited_mail(invitation: Option<&UserInvitation>, mail: &str, errors: &mut Vec<String>) {
+    if invitation.is_some_and(|invitation| !invitation.matches_mail(mail)) {
+        errors.push("Register with the email address the invitation was sent to.".to_string());
+    }
 }
 
 /// Registration form handler.
 ///
-/// GET /user/register
+/// GET /user/register[?invite=<token>]
 /// - Renders registration form with CSRF token
-/// - Returns 404 if registration is disabled
-async fn register_form(State(state): State<AppState>, session: Session) -> Response {
+/// - Returns 404 if registration is disabled, 403 without a valid
+///   invitation when registration is invite-only
+async fn register_form(
+    State(state): State<AppState>,
+    session: Session,
+    Query(query): Query<RegisterQuery>,
+) -> Response {
     // Check if already logged in
     if session
         .get::<uuid::Uuid>(SESSION_USER_ID)
@@ -477,13 +821,20 @@ async fn register_form(State(state): State<AppState>, session: Session) -> Respo
         return Redirect::to("/").into_response();
     }
 
-    if !is_registration_enabled(&state).await {
-        return (StatusCode::NOT_FOUND, "Registration is not enabled.").into_response();
-    }
+    let invitation = match check_registration_access(&state, query.invite.as_deref()).await {
+        Ok(invitation) => invitation,
+        Err(refused) => return refused.into_response(),
+    };
 
     let csrf_token = generate_csrf_token(&session).await;
 
-    render_register_form(&state, &session, &csrf_token, None, None, None).await
+    let values = invitation.map(|invitation| {
+        serde_json::json!({
+            "mail": invitation.mail,
+            "invite": query.invite,
+        })
+    });
+    render_register_form(&state, &session, &csrf_token, None, None, values.as_ref()).await
 }
 
 /// Render the registration form with optional context.
@@ -525,16 +876,17 @@ async fn render_register_form(
 /// - Creates inactive user, sends verification email
 async fn register_form_submit(
     State(state): State<AppState>,
+    client_ip: ClientIp,
     session: Session,
-    headers: axum::http::HeaderMap,
     Form(form): Form<RegisterFormRequest>,
 ) -> Response {
-    if !is_registration_enabled(&state).await {
-        return (StatusCode::NOT_FOUND, "Registration is not enabled.").into_response();
-    }
+    let invitation = match check_registration_access(&state, form.invite.as_deref()).await {
+        Ok(invitation) => invitation,
+        Err(refused) => return refused.into_response(),
+    };
 
     // Rate limit registration attempts (separate bucket from login)
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     if let Err(retry_after) = state.rate_limiter().check("register", &client_id).await {
         return crate::middleware::rate_limit_response(retry_after);
     }
@@ -550,11 +902,16 @@ async fn register_form_submit(
     let values = serde_json::json!({
         "username": username,
         "mail": mail,
+        "invite": form.invite,
     });
 
     // Validate input
     let mut errors = Vec::new();
     validate_registration_input(&state, &username, &mail, &form.password, &mut errors).await;
+    check_invited_mail(invitation.as_ref(), &mail, &mut errors);
+    if validate_password(&form.password).is_ok() {
+        check_password_policy(&state, &form.password, None, &mut errors).await;
+    }
 
     if form.password != form.confirm_password {
         errors.push("Passwords do not match.".to_string());
@@ -573,19 +930,33 @@ async fn register_form_submit(
         .await;
     }
 
-    // Create inactive user
-    match do_register(&state, &username, &mail, &form.password).await {
+    // Create the account (inactive unless invited)
+    match do_register(
+        &state,
+        &username,
+        &mail,
+        &form.password,
+        invitation.as_ref(),
+    )
+    .await
+    {
         Ok(result) => {
             let csrf_token = generate_csrf_token(&session).await;
-            let message = if result.email_sent {
-                "Registration successful! Check your email for a verification link \
-                 to activate your account."
+            let message = if result.active {
+                "Registration successful! You can now log in.".to_string()
+            } else if result.email_sent {
+                format!(
+                    "Registration successful! Check your email for a verification link \
+                     to activate your account.{}",
+                    approval_notice(result.needs_approval)
+                )
             } else {
                 "Registration successful! However, we were unable to send the \
                  verification email. Please contact the site administrator to \
                  activate your account."
+                    .to_string()
             };
-            render_register_form(&state, &session, &csrf_token, None, Some(message), None).await
+            render_register_form(&state, &session, &csrf_token, None, Some(&message), None).await
         }
         Err(e) => {
             tracing::error!(error = %e, "registration failed");
@@ -660,14 +1031,31 @@ async fn validate_registration_input(
 struct RegistrationResult {
     /// Whether the verification email was actually sent.
     email_sent: bool,
+    /// Whether the account is active already (invited registrations).
+    active: bool,
+    /// Whether an administrator must approve the account after verification.
+    needs_approval: bool,
+}
+
+/// Extra sentence for registration messages in admin-approval mode.
+fn approval_notice(needs_approval: bool) -> &'static str {
+    if needs_approval {
+        " An administrator must then approve your account before you can log in."
+    } else {
+        ""
+    }
 }
 
 /// Core registration logic shared by form and JSON handlers.
+///
+/// With an invitation the account is active at once and gets the invited
+/// roles; otherwise it stays inactive until the email is verified.
 async fn do_register(
     state: &AppState,
     username: &str,
     mail: &str,
     password: &str,
+    invitation: Option<&UserInvitation>,
 ) -> anyhow::Result<RegistrationResult> {
     let input = CreateUser {
         name: username.trim().to_string(),
@@ -676,13 +1064,15 @@ async fn do_register(
         is_admin: false,
     };
 
-    // Create user with status=0 (inactive, pending email verification)
     // Registration is an anonymous action — no authenticated user context.
     let user_ctx = crate::tap::UserContext::anonymous();
-    let user = state
-        .users()
-        .create_with_status(input, 0, &user_ctx)
-        .await?;
+
+    if let Some(invitation) = invitation {
+        return redeem_invitation(state, input, invitation, &user_ctx).await;
+    }
+
+    // Create user with status=0 (inactive, pending email verification)
+    let user = state.users().register(input, 0, &user_ctx).await?;
 
     // Create verification token
     let (_, plain_token) =
@@ -712,22 +1102,19 @@ async fn do_register(
     }
 
     // Notify admin of new registration (if configured)
-    if let Some(email_svc) = state.email()
+    if state.mail().is_configured()
         && let Ok(Some(notify_val)) = SiteConfig::get(state.db(), "notify_admin_on_register").await
         && notify_val.as_bool().unwrap_or(false)
     {
         let admin_mail = SiteConfig::site_mail(state.db()).await.unwrap_or_default();
         if !admin_mail.is_empty() {
-            let email = email_svc.clone();
-            let site_url = email_svc.site_url().to_string();
+            let notify_state = state.clone();
             let user_name = username.trim().to_string();
             let user_mail = mail.trim().to_string();
             let site = site_name.clone();
-            let tera = state.theme().tera().clone();
-            let dest = admin_mail.clone();
 
             tokio::spawn(async move {
-                let action_url = format!("{site_url}/admin/users");
+                let action_url = format!("{}/admin/users", notify_state.site_url());
                 let subject = format!("New user registration at {site}: {user_name}");
 
                 let mut ctx = tera::Context::new();
@@ -735,17 +1122,21 @@ async fn do_register(
                 ctx.insert("username", &user_name);
                 ctx.insert("user_email", &user_mail);
                 ctx.insert("action_url", &action_url);
-                ctx.insert("subject", &subject);
-
-                match crate::services::email_templates::render(&tera, "admin_new_user", &ctx) {
-                    Ok((html, text)) => {
-                        if let Err(e) = email
-                            .send_templated(&dest, &subject, &text, html.as_deref())
-                            .await
-                        {
+
+                let mail_service = notify_state.mail();
+                match mail_service.compose(
+                    notify_state.theme(),
+                    "admin_new_user",
+                    &admin_mail,
+                    &subject,
+                    None,
+                    &ctx,
+                ) {
+                    Ok(message) => {
+                        if let Err(e) = mail_service.queue(message).await {
                             tracing::warn!(
                                 error = %e,
-                                "admin registration notification: failed to send"
+                                "admin registration notification: failed to queue"
                             );
                         }
                     }
@@ -761,7 +1152,63 @@ async fn do_register(
     }
 
     info!(user_id = %user.id, "user registered (pending verification)");
-    Ok(RegistrationResult { email_sent })
+    Ok(RegistrationResult {
+        email_sent,
+        active: false,
+        needs_approval: RegistrationMode::load(state.db()).await == RegistrationMode::AdminApproval,
+    })
+}
+
+/// Create an active account from an invitation.
+async fn redeem_invitation(
+    state: &AppState,
+    input: CreateUser,
+    invitation: &UserInvitation,
+    user_ctx: &crate::tap::UserContext,
+) -> anyhow::Result<RegistrationResult> {
+    create_invited_account(state, input, invitation, user_ctx).await?;
+    Ok(RegistrationResult {
+        email_sent: false,
+        active: true,
+        needs_approval: false,
+    })
+}
+
+/// Create an active account from an invitation and assign its roles.
+///
+/// The invitation is claimed before the account is created so it cannot be
+/// redeemed twice; it is released again if creating the account fails.
+pub(crate) async fn create_invited_account(
+    state: &AppState,
+    input: CreateUser,
+    invitation: &UserInvitation,
+    user_ctx: &crate::tap::UserContext,
+) -> anyhow::Result<User> {
+    if !UserInvitation::claim(state.db(), invitation.id).await? {
+        anyhow::bail!("invitation {} was already used", invitation.id);
+    }
+
+    let user = match state.users().register(input, 1, user_ctx).await {
+        Ok(user) => user,
+        Err(e) => {
+            if let Err(release_err) = UserInvitation::release(state.db(), invitation.id).await {
+                tracing::warn!(error = %release_err, "failed to release invitation");
+            }
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = UserInvitation::record_user(state.db(), invitation.id, user.id).await {
+        tracing::warn!(error = %e, user_id = %user.id, "failed to link invitation to user");
+    }
+    for role_id in &invitation.role_ids {
+        if let Err(e) = state.roles().assign_to_user(user.id, *role_id).await {
+            tracing::warn!(error = %e, user_id = %user.id, role_id = %role_id, "failed to assign invited role");
+        }
+    }
+
+    info!(user_id = %user.id, invitation_id = %invitation.id, "user registered from invitation");
+    Ok(user)
 }
 
 /// JSON registration handler.
@@ -779,11 +1226,11 @@ async fn do_register(
 /// additional abuse protection.
 async fn register_json(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
+    client_ip: ClientIp,
     Json(request): Json<RegisterJsonRequest>,
 ) -> Result<Json<JsonSuccess>, AppError> {
     // Rate limit registration attempts (separate bucket from login)
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     if let Err(retry_after) = state.rate_limiter().check("register", &client_id).await {
         return Err(AppError::RateLimited {
             retry_after_secs: retry_after,
@@ -791,9 +1238,9 @@ async fn register_json(
         });
     }
 
-    if !is_registration_enabled(&state).await {
-        return Err(AppError::not_found("registration"));
-    }
+    let invitation = check_registration_access(&state, request.invite.as_deref())
+        .await
+        .map_err(RegistrationRefused::into_app_error)?;
 
     // Validate
     let mut errors = Vec::new();
@@ -805,6 +1252,7 @@ async fn register_json(
         &mut errors,
     )
     .await;
+    check_invited_mail(invitation.as_ref(), &request.mail, &mut errors);
 
     // Validate password confirmation if provided
     if let Some(ref confirm) = request.confirm_password
@@ -817,17 +1265,38 @@ async fn register_json(
         return Err(AppError::bad_request(errors.join(" ")));
     }
 
-    match do_register(&state, &request.username, &request.mail, &request.password).await {
+    // Policy violations are returned as field errors on `password`.
+    state
+        .password_policy()
+        .check(&request.password, None)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "password policy"))?;
+
+    match do_register(
+        &state,
+        &request.username,
+        &request.mail,
+        &request.password,
+        invitation.as_ref(),
+    )
+    .await
+    {
         Ok(result) => {
-            let message = if result.email_sent {
-                "Registration successful. Check your email for a verification link."
+            let message = if result.active {
+                "Registration successful. You can now log in.".to_string()
+            } else if result.email_sent {
+                format!(
+                    "Registration successful. Check your email for a verification link.{}",
+                    approval_notice(result.needs_approval)
+                )
             } else {
                 "Registration successful. However, the verification email could not be sent. \
                  Please contact the site administrator."
+                    .to_string()
             };
             Ok(Json(JsonSuccess {
                 success: true,
-                message: message.to_string(),
+                message,
             }))
         }
         Err(e) => {
@@ -846,11 +1315,11 @@ async fn register_json(
 /// - Validates the token, activates the user, redirects to login
 async fn verify_email(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
+    client_ip: ClientIp,
     Path(token): Path<String>,
 ) -> Response {
     // Rate limit verification attempts to prevent token brute-force
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     if let Err(retry_after) = state.rate_limiter().check("verify_email", &client_id).await {
         return crate::middleware::rate_limit_response(retry_after);
     }
@@ -886,20 +1355,40 @@ async fn verify_email(
         tracing::warn!(error = %e, "failed to invalidate remaining verification tokens");
     }
 
-    // Activate the user (status=1)
+    // Activate the user (status=1), or flag the account for approval when
+    // an administrator must approve new accounts.
+    let needs_approval =
+        RegistrationMode::load(state.db()).await == RegistrationMode::AdminApproval;
+    let update = if needs_approval {
+        let data = match state.users().find_by_id(verification.user_id).await {
+            Ok(Some(user)) => user.data,
+            Ok(None) => serde_json::Value::Null,
+            Err(e) => {
+                tracing::error!(error = %e, user_id = %verification.user_id, "failed to load user");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to activate account",
+                )
+                    .into_response();
+            }
+        };
+        crate::models::UpdateUser {
+            data: Some(registration::with_pending_approval(&data, true)),
+            ..Default::default()
+        }
+    } else {
+        crate::models::UpdateUser {
+            status: Some(1),
+            ..Default::default()
+        }
+    };
+
     // Email verification is a system action — use anonymous context since
     // the user doesn't have a session yet.
     let user_ctx = crate::tap::UserContext::anonymous();
     if let Err(e) = state
         .users()
-        .update(
-            verification.user_id,
-            crate::models::UpdateUser {
-                status: Some(1),
-                ..Default::default()
-            },
-            &user_ctx,
-        )
+        .update(verification.user_id, update, &user_ctx)
         .await
     {
         tracing::error!(error = %e, user_id = %verification.user_id, "failed to activate user");
@@ -910,6 +1399,17 @@ async fn verify_email(
             .into_response();
     }
 
+    if needs_approval {
+        info!(user_id = %verification.user_id, "email verified, account awaiting approval");
+        return Html(
+            "<h1>Email Verified</h1>\
+             <p>Your email address is verified. An administrator must approve your \
+             account before you can log in.</p>"
+                .to_string(),
+        )
+        .into_response();
+    }
+
     info!(user_id = %verification.user_id, "email verified, account activated");
 
     Redirect::to("/user/login").into_response()
@@ -921,11 +1421,11 @@ async fn verify_email(
 /// - Validates the token, updates the user's email to the pending address
 async fn verify_email_change(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
+    client_ip: ClientIp,
     Path(token): Path<String>,
 ) -> Response {
     // Rate limit verification attempts to prevent token brute-force
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     if let Err(retry_after) = state.rate_limiter().check("verify_email", &client_id).await {
         return crate::middleware::rate_limit_response(retry_after);
     }
@@ -1083,8 +1583,20 @@ struct ProfileFormRequest {
     timezone: String,
     #[serde(default)]
     current_password: String,
+    #[serde(default)]
+    display_name: String,
+    #[serde(default)]
+    bio: String,
+    #[serde(default)]
+    website: String,
+    /// Checkbox: present when the user opts out of the public author page.
+    #[serde(default)]
+    author_hidden: Option<String>,
     #[serde(rename = "_token")]
     csrf_token: String,
+    /// Profile field inputs (`profile[<field_name>]`).
+    #[serde(flatten)]
+    extra: std::collections::HashMap<String, String>,
 }
 
 /// Password change form request.
@@ -1101,7 +1613,10 @@ struct PasswordChangeRequest {
 ///
 /// Returns the user only if they exist AND have an active account (status=1).
 /// Blocked or deactivated users are redirected to login.
-async fn get_current_user(state: &AppState, session: &Session) -> Result<User, Response> {
+pub(crate) async fn get_current_user(
+    state: &AppState,
+    session: &Session,
+) -> Result<User, Response> {
     let user_id: uuid::Uuid = session
         .get(SESSION_USER_ID)
         .await
@@ -1156,8 +1671,11 @@ async fn render_profile(
             "name": user.name,
             "mail": user.mail,
             "timezone": user.timezone,
+            "author": AuthorSettings::from_user(user),
+            "fields": user.fields,
         })),
     );
+    context.insert("profile_fields", &editable_profile_fields(state));
     if let Some(errors) = errors {
         context.insert("errors", errors);
     }
@@ -1178,6 +1696,16 @@ async fn render_profile(
     }
 }
 
+/// Profile fields users may edit on their own profile page.
+fn editable_profile_fields(state: &AppState) -> Vec<trovato_sdk::types::FieldDefinition> {
+    state
+        .users()
+        .profile_fields()
+        .into_iter()
+        .filter(user_fields::is_user_editable)
+        .collect()
+}
+
 /// Generate a pair of CSRF tokens for the profile page (one per form).
 async fn profile_csrf_pair(session: &Session) -> (String, String) {
     let profile = generate_csrf_token(session).await;
@@ -1242,15 +1770,42 @@ async fn profile_update(
     let email_changing = !mail.eq_ignore_ascii_case(&user.mail);
     let name_changing = !name.eq_ignore_ascii_case(&user.name);
 
+    let non_empty = |s: &str| {
+        let s = s.trim();
+        (!s.is_empty()).then(|| s.to_string())
+    };
+    let author = AuthorSettings {
+        display_name: non_empty(&form.display_name),
+        bio: non_empty(&form.bio),
+        website: non_empty(&form.website),
+        hidden: form.author_hidden.is_some(),
+    };
+
+    let submitted_fields = user_fields::form_values(&form.extra);
+
     // Build form values for re-rendering on validation errors
     let form_values = serde_json::json!({
         "name": name,
         "mail": mail,
         "timezone": timezone,
+        "author": author,
+        "fields": submitted_fields,
     });
 
     let mut errors = Vec::new();
 
+    let fields = match user_fields::apply_form_values(
+        &editable_profile_fields(&state),
+        &submitted_fields,
+        &user.fields,
+    ) {
+        Ok(fields) => (fields != user.fields).then_some(fields),
+        Err(field_errors) => {
+            errors.extend(field_errors);
+            None
+        }
+    };
+
     if let Err(msg) = validate_username(name) {
         errors.push(msg.to_string());
     }
@@ -1265,6 +1820,30 @@ async fn profile_update(
         errors.push("Please enter a valid timezone (e.g., America/New_York).".to_string());
     }
 
+    if author
+        .display_name
+        .as_ref()
+        .is_some_and(|n| n.chars().count() > 255)
+    {
+        errors.push("Display name must be 255 characters or fewer.".to_string());
+    }
+
+    if author
+        .bio
+        .as_ref()
+        .is_some_and(|b| b.chars().count() > MAX_BIO_LENGTH)
+    {
+        errors.push(format!("Bio must be {MAX_BIO_LENGTH} characters or fewer."));
+    }
+
+    if author
+        .website
+        .as_ref()
+        .is_some_and(|w| !w.starts_with("https://") && !w.starts_with("http://"))
+    {
+        errors.push("Website must start with http:// or https://.".to_string());
+    }
+
     // Require current password when changing username or email (login credentials)
     if email_changing || name_changing {
         // Check lockout before attempting password verification
@@ -1335,25 +1914,31 @@ async fn profile_update(
         } else {
             None
         },
+        fields,
         ..Default::default()
     };
 
+    // Author settings live in user data; only write when they changed.
+    let mut data_update = if author != AuthorSettings::from_user(&user) {
+        Some(author.merge_into(&user.data))
+    } else {
+        None
+    };
+
     // If email is changing, store pending_email and send verification
-    let pending_email_data = if email_changing {
-        let mut data = user.data.clone();
+    if email_changing {
+        let mut data = data_update.unwrap_or_else(|| user.data.clone());
         if let Some(obj) = data.as_object_mut() {
             obj.insert(
                 "pending_email".to_string(),
                 serde_json::Value::String(mail.to_string()),
             );
         }
-        Some(data)
-    } else {
-        None
-    };
+        data_update = Some(data);
+    }
 
     // Merge data update into the main update if needed
-    let update = if let Some(data) = pending_email_data {
+    let update = if let Some(data) = data_update {
         crate::models::UpdateUser {
             data: Some(data),
             ..update
@@ -1514,6 +2099,8 @@ async fn password_change(
 
     if let Err(msg) = validate_password(&form.new_password) {
         errors.push(msg.to_string());
+    } else {
+        check_password_policy(&state, &form.new_password, Some(&user), &mut errors).await;
     }
 
     if form.new_password != form.confirm_password {
@@ -0,0 +1,365 @@
+//! Author pages and per-author feeds.
+//!
+//! - `GET /author/{name}` — paginated listing of a user's published content
+//! - `GET /author/{name}/feed` — RSS 2.0 feed of the same content
+//!
+//! Only active users who have not opted out (see [`AuthorProfile`]) and
+//! have published content are listed; everyone else gets a 404 so the routes cannot be used to probe
+//! for account names. Items restricted by `tap_item_grants` are left out
+//! unless the visitor holds a view grant.
+
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::content::FilterPipeline;
+use crate::content::item_query::ItemQueryAccess;
+use crate::models::author::AuthorProfile;
+use crate::models::{Item, SiteConfig, User};
+use crate::state::AppState;
+
+use super::helpers::{html_escape, inject_site_context, render_not_found, render_server_error};
+
+/// Items per author page.
+const AUTHOR_PAGE_SIZE: i64 = 10;
+
+/// Items in an author feed.
+const AUTHOR_FEED_SIZE: i64 = 20;
+
+/// Maximum teaser length in characters.
+const SUMMARY_LENGTH: usize = 200;
+
+/// Create the author router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/author/{name}", get(author_page))
+        .route("/author/{name}/feed", get(author_feed))
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorPageQuery {
+    #[serde(default = "default_page")]
+    page: i64,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+/// Teaser for one item on an author page.
+#[derive(Debug, Serialize)]
+struct AuthorItemTeaser {
+    id: uuid::Uuid,
+    title: String,
+    item_type: String,
+    path: String,
+    created: i64,
+    /// Plain-text summary, already HTML-escaped.
+    summary: Option<String>,
+}
+
+/// Look up a listable author by username, with the number of their
+/// published items `viewer` may see.
+///
+/// Returns `Ok(None)` for unknown, inactive, or opted-out users, and for
+/// users without published content visible to `viewer`.
+async fn find_author(
+    state: &AppState,
+    name: &str,
+    viewer: Option<Uuid>,
+) -> anyhow::Result<Option<(User, AuthorProfile, i64)>> {
+    let Some(user) = state.users().find_by_name(name).await? else {
+        return Ok(None);
+    };
+    let Some(profile) = AuthorProfile::for_user(&user) else {
+        return Ok(None);
+    };
+    let total = Item::count_published_by_author(state.db(), user.id, viewer).await?;
+    if total == 0 {
+        return Ok(None);
+    }
+    Ok(Some((user, profile, total)))
+}
+
+/// Author page handler.
+///
+/// GET /author/{name}
+async fn author_page(
+    State(state): State<AppState>,
+    session: Session,
+    Path(name): Path<String>,
+    Query(query): Query<AuthorPageQuery>,
+) -> Response {
+    let viewer = grants_viewer(&state, &session).await;
+    let (user, profile, total) = match find_author(&state, &name, viewer).await {
+        Ok(Some(found)) => found,
+        Ok(None) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load author");
+            return render_server_error("Failed to load author.");
+        }
+    };
+    let total_pages = ((total + AUTHOR_PAGE_SIZE - 1) / AUTHOR_PAGE_SIZE).max(1);
+    let page = query.page.clamp(1, total_pages);
+    let offset = (page - 1) * AUTHOR_PAGE_SIZE;
+
+    let items =
+        match Item::list_published_by_author(state.db(), user.id, viewer, AUTHOR_PAGE_SIZE, offset)
+            .await
+        {
+            Ok(items) => items,
+            Err(e) => {
+                tracing::error!(error = %e, author = %user.id, "failed to list author items");
+                return render_server_error("Failed to load author content.");
+            }
+        };
+    let teasers: Vec<AuthorItemTeaser> = items.iter().map(teaser).collect();
+
+    let mut context = tera::Context::new();
+    context.insert("author", &profile);
+    context.insert("items", &teasers);
+    context.insert("total", &total);
+    context.insert("page", &page);
+    context.insert("total_pages", &total_pages);
+    context.insert("has_prev", &(page > 1));
+    context.insert("has_next", &(page < total_pages));
+    inject_site_context(&state, &session, &mut context, &profile.path).await;
+
+    match state.theme().tera().render("author.html", &context) {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            tracing::error!(error = ?e, "failed to render author template");
+            render_server_error("Failed to render author page.")
+        }
+    }
+}
+
+/// Author RSS feed handler.
+///
+/// GET /author/{name}/feed
+async fn author_feed(
+    State(state): State<AppState>,
+    session: Session,
+    Path(name): Path<String>,
+) -> Response {
+    let viewer = grants_viewer(&state, &session).await;
+    let (user, profile, _) = match find_author(&state, &name, viewer).await {
+        Ok(Some(found)) => found,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load author for feed");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let items = match Item::list_published_by_author(
+        state.db(),
+        user.id,
+        viewer,
+        AUTHOR_FEED_SIZE,
+        0,
+    )
+    .await
+    {
+        Ok(items) => items,
+        Err(e) => {
+            tracing::error!(error = %e, author = %user.id, "failed to list author feed items");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let site_name = SiteConfig::site_name(state.db()).await.unwrap_or_default();
+    let teasers: Vec<AuthorItemTeaser> = items.iter().map(teaser).collect();
+    let xml = render_feed(state.site_url(), &site_name, &profile, &teasers);
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        xml,
+    )
+        .into_response()
+}
+
+/// User whose item grants restrict the listing (`None` for admins).
+async fn grants_viewer(state: &AppState, session: &Session) -> Option<Uuid> {
+    let user = super::item::get_user_context(session, state).await;
+    ItemQueryAccess::for_user(&user, false).grants_user(&user)
+}
+
+/// Build a teaser from an item.
+fn teaser(item: &Item) -> AuthorItemTeaser {
+    AuthorItemTeaser {
+        id: item.id,
+        title: item.title.clone(),
+        item_type: item.item_type.clone(),
+        path: format!("/item/{}", item.id),
+        created: item.created,
+        summary: summary(item),
+    }
+}
+
+/// Plain-text summary of the item's body field, HTML-escaped and truncated.
+fn summary(item: &Item) -> Option<String> {
+    let body = item.fields.get("body")?;
+    let value = body.get("value")?.as_str()?;
+    let format = body
+        .get("format")
+        .and_then(|v| v.as_str())
+        .unwrap_or("plain_text");
+    let filtered = FilterPipeline::for_format_safe(format).process(value);
+
+    // Strip all tags; ammonia leaves text entity-escaped.
+    let text = ammonia::Builder::default()
+        .tags(std::collections::HashSet::new())
+        .clean(&filtered)
+        .to_string();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(truncate_escaped(&text, SUMMARY_LENGTH))
+}
+
+/// Truncate entity-escaped text to `max_chars`, never splitting an entity.
+fn truncate_escaped(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars).collect();
+    // Drop a trailing partial entity such as "&am".
+    if let Some(amp) = truncated.rfind('&')
+        && !truncated[amp..].contains(';')
+    {
+        truncated.truncate(amp);
+    }
+    format!("{}...", truncated.trim_end())
+}
+
+/// Format a Unix timestamp as an RFC 2822 date for RSS.
+fn rfc2822(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.to_rfc2822())
+        .unwrap_or_default()
+}
+
+/// Render an RSS 2.0 document for an author.
+fn render_feed(
+    site_url: &str,
+    site_name: &str,
+    profile: &AuthorProfile,
+    items: &[AuthorItemTeaser],
+) -> String {
+    let channel_title = if site_name.is_empty() {
+        profile.display_name.clone()
+    } else {
+        format!("{} — {site_name}", profile.display_name)
+    };
+    let channel_link = format!("{site_url}{}", profile.path);
+    let feed_link = format!("{site_url}{}", profile.feed_path);
+    let description = profile
+        .bio
+        .clone()
+        .unwrap_or_else(|| format!("Content by {}", profile.display_name));
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n  <channel>\n");
+    xml.push_str(&format!(
+        "    <title>{}</title>\n    <link>{}</link>\n    <description>{}</description>\n",
+        html_escape(&channel_title),
+        html_escape(&channel_link),
+        html_escape(&description),
+    ));
+    xml.push_str(&format!(
+        "    <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        html_escape(&feed_link)
+    ));
+    if let Some(first) = items.first() {
+        xml.push_str(&format!(
+            "    <lastBuildDate>{}</lastBuildDate>\n",
+            rfc2822(first.created)
+        ));
+    }
+
+    for item in items {
+        let link = format!("{site_url}{}", item.path);
+        xml.push_str("    <item>\n");
+        xml.push_str(&format!(
+            "      <title>{}</title>\n      <link>{}</link>\n      <guid isPermaLink=\"true\">{}</guid>\n      <pubDate>{}</pubDate>\n",
+            html_escape(&item.title),
+            html_escape(&link),
+            html_escape(&link),
+            rfc2822(item.created),
+        ));
+        if let Some(summary) = &item.summary {
+            // Summary is entity-escaped text; escape again for XML.
+            xml.push_str(&format!(
+                "      <description>{}</description>\n",
+                html_escape(summary)
+            ));
+        }
+        xml.push_str("    </item>\n");
+    }
+
+    xml.push_str("  </channel>\n</rss>\n");
+    xml
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn profile() -> AuthorProfile {
+        AuthorProfile {
+            name: "ada".to_string(),
+            display_name: "Ada <Lovelace>".to_string(),
+            bio: None,
+            website: None,
+            path: "/author/ada".to_string(),
+            feed_path: "/author/ada/feed".to_string(),
+        }
+    }
+
+    #[test]
+    fn truncate_escaped_keeps_entities_whole() {
+        assert_eq!(truncate_escaped("short", 10), "short");
+        assert_eq!(truncate_escaped("abc &amp; def", 6), "abc...");
+        assert_eq!(truncate_escaped("abc &amp; def", 9), "abc &amp;...");
+    }
+
+    #[test]
+    fn feed_escapes_and_uses_absolute_links() {
+        let items = vec![AuthorItemTeaser {
+            id: uuid::Uuid::nil(),
+            title: "Fish & Chips".to_string(),
+            item_type: "blog".to_string(),
+            path: "/item/00000000-0000-0000-0000-000000000000".to_string(),
+            created: 0,
+            summary: Some("a &amp; b".to_string()),
+        }];
+        let xml = render_feed("https://example.com", "Site", &profile(), &items);
+        assert!(xml.contains("<title>Ada &lt;Lovelace&gt; — Site</title>"));
+        assert!(xml.contains("<link>https://example.com/author/ada</link>"));
+        assert!(xml.contains("<title>Fish &amp; Chips</title>"));
+        assert!(xml.contains(
+            "<link>https://example.com/item/00000000-0000-0000-0000-000000000000</link>"
+        ));
+        assert!(xml.contains("<description>a &amp;amp; b</description>"));
+        assert!(xml.contains("Jan 1970 00:00:00 +0000</pubDate>"));
+    }
+
+    #[test]
+    fn feed_without_items_is_valid_channel() {
+        let xml = render_feed("https://example.com", "", &profile(), &[]);
+        assert!(xml.contains("<description>Content by Ada &lt;Lovelace&gt;</description>"));
+        assert!(!xml.contains("<item>"));
+        assert!(xml.ends_with("</rss>\n"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Backup routes (admin only).
+//!
+//! - `GET /admin/backups` — archives in `BACKUP_DIR`, newest first
+//! - `POST /admin/backups` — start a backup as a batch operation
+//! - `GET /admin/backups/{name}` — download an archive
+//!
+//! Restoring replaces the database content, so it is only offered by
+//! `trovato backup restore`.
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncReadExt;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::backup::{self, BackupInfo, BackupParts, BackupSources};
+use crate::batch::CreateBatch;
+use crate::error::AppError;
+use crate::state::AppState;
+
+use super::helpers::{require_admin_json, require_csrf_header};
+
+/// Batch operation type of backup jobs.
+const BACKUP_OPERATION: &str = "backup";
+
+/// Create the backup router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/backups", get(list_backups).post(create_backup))
+        .route("/admin/backups/{name}", get(download_backup))
+}
+
+/// Request to start a backup.
+#[derive(Debug, Default, Deserialize)]
+struct CreateBackupRequest {
+    /// `content` or `config`; both when omitted.
+    #[serde(default)]
+    only: Option<String>,
+}
+
+/// A started backup.
+#[derive(Debug, Serialize)]
+struct CreateBackupResponse {
+    batch_id: Uuid,
+    name: String,
+}
+
+/// List backup archives.
+///
+/// GET /admin/backups
+async fn list_backups(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<BackupInfo>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let backups = backup::list_backups(state.backup_dir())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list backups"))?;
+    Ok(Json(backups))
+}
+
+/// Start a backup. Progress is reported through the batch API.
+///
+/// POST /admin/backups
+async fn create_backup(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    request: Option<Json<CreateBackupRequest>>,
+) -> Result<(StatusCode, Json<CreateBackupResponse>), AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let parts = BackupParts::only(request.only.as_deref())
+        .map_err(|e| AppError::bad_request(e.to_string()))?;
+
+    let name = backup::backup_name(chrono::Utc::now().timestamp());
+    let archive = state.backup_dir().join(&name);
+    if archive.exists() {
+        return Err(AppError::conflict("a backup is already being written"));
+    }
+
+    let batch = state
+        .batch()
+        .create(CreateBatch {
+            operation_type: BACKUP_OPERATION.to_string(),
+            params: serde_json::json!({
+                "name": name,
+                "parts": parts,
+            }),
+        })
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "create backup batch"))?;
+
+    let batch_id = batch.id;
+    let job_state = state.clone();
+    tokio::spawn(async move {
+        let state = job_state;
+        let sources = BackupSources {
+            pool: state.db(),
+            database_url: state.database_url(),
+            config: state.config_storage().as_ref(),
+            files: state.files().storage().as_ref(),
+        };
+        let result = match backup::create_backup(&sources, parts, &archive).await {
+            Ok(summary) => match serde_json::to_value(&summary) {
+                Ok(json) => state.batch().complete(batch_id, Some(json)).await,
+                Err(e) => state.batch().fail(batch_id, &e.to_string()).await,
+            },
+            Err(e) => {
+                tracing::error!(error = %e, batch_id = %batch_id, "backup failed");
+                state.batch().fail(batch_id, &format!("{e:#}")).await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(error = %e, batch_id = %batch_id, "failed to record backup result");
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(CreateBackupResponse { batch_id, name }),
+    ))
+}
+
+/// Download a backup archive.
+///
+/// GET /admin/backups/{name}
+async fn download_backup(
+    State(state): State<AppState>,
+    session: Session,
+    Path(name): Path<String>,
+) -> Result<Response, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    if !backup::is_valid_backup_name(&name) {
+        return Err(AppError::not_found("backup"));
+    }
+    let path = state.backup_dir().join(&name);
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::not_found("backup"));
+        }
+        Err(e) => return Err(AppError::internal_ctx(e, "open backup")),
+    };
+    let size = file
+        .metadata()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "read backup metadata"))?
+        .len();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/gzip"),
+    );
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+    if let Ok(disposition) = HeaderValue::from_str(&format!("attachment; filename=\"{name}\"")) {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+
+    let stream = async_stream::stream! {
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            match file.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(n) => yield Ok(buf[..n].to_vec()),
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+        }
+    };
+
+    Ok((headers, Body::from_stream(stream)).into_response())
+}
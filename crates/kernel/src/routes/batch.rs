@@ -1,19 +1,31 @@
 //! Batch operations API.
 //!
 //! Provides REST endpoints for managing long-running batch operations
-//! with progress polling support.
+//! with progress polling support. Plugin-declared batches are started via
+//! `POST /api/batch/plugin/{name}` and polled at `/batch/{id}/progress`.
+
+use std::time::Duration;
 
 use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::routing::{delete, get, post};
 use axum::{Json, Router};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
 use uuid::Uuid;
 
-use crate::batch::{BatchOperation, BatchStatus, CreateBatch};
+use crate::batch::{BatchOperation, BatchStatus, CreateBatch, PluginBatchDefinition};
 use crate::error::AppError;
 use crate::state::AppState;
 
+use super::helpers::{require_admin_json, require_csrf_header};
+
+/// Time spent running plugin batch steps in the starting request.
+///
+/// Whatever remains afterwards is continued by cron, so the request returns
+/// well before typical proxy timeouts.
+const START_STEP_BUDGET: Duration = Duration::from_secs(10);
+
 /// Response for batch operation creation.
 #[derive(Serialize)]
 struct CreateBatchResponse {
@@ -134,6 +146,96 @@ async fn delete_batch(
     }
 }
 
+/// Request body for starting a plugin batch.
+#[derive(Debug, Default, Deserialize)]
+struct StartPluginBatchRequest {
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+/// List plugin-declared batches (admin only).
+///
+/// GET /api/batch/plugin
+async fn list_plugin_batches(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<PluginBatchDefinition>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let definitions = state
+        .batch()
+        .definitions()
+        .map(|d| d.list().into_iter().cloned().collect())
+        .unwrap_or_default();
+    Ok(Json(definitions))
+}
+
+/// Start a plugin-declared batch (admin only).
+///
+/// Runs steps for up to [`START_STEP_BUDGET`]; unfinished batches are
+/// continued by cron and can be polled via `/batch/{id}/progress`.
+///
+/// POST /api/batch/plugin/{name}
+async fn start_plugin_batch(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    body: Option<Json<StartPluginBatchRequest>>,
+) -> Result<(StatusCode, Json<BatchStatusResponse>), AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    if state
+        .batch()
+        .definitions()
+        .and_then(|d| d.get(&name))
+        .is_none()
+    {
+        return Err(AppError::not_found_id("batch definition", &name));
+    }
+
+    let params = body.map(|Json(b)| b.params).unwrap_or_default();
+    let operation = state
+        .batch()
+        .start_plugin_batch(&name, params)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "start plugin batch"))?;
+
+    let operation = state
+        .batch()
+        .run_steps(operation.id, START_STEP_BUDGET)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "run plugin batch steps"))?
+        .unwrap_or(operation);
+
+    let status = if operation.status.is_active() {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(operation_to_response(operation))))
+}
+
+/// Poll batch progress.
+///
+/// GET /batch/{id}/progress
+async fn batch_progress(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BatchStatusResponse>, AppError> {
+    let operation = state
+        .batch()
+        .get(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "get batch progress"))?
+        .ok_or_else(|| AppError::not_found_id("batch operation", id))?;
+
+    Ok(Json(operation_to_response(operation)))
+}
+
 /// Convert BatchOperation to response format.
 fn operation_to_response(op: BatchOperation) -> BatchStatusResponse {
     BatchStatusResponse {
@@ -160,6 +262,9 @@ pub fn router() -> Router<AppState> {
         .route("/api/batch/{id}", get(get_batch))
         .route("/api/batch/{id}/cancel", post(cancel_batch))
         .route("/api/batch/{id}", delete(delete_batch))
+        .route("/api/batch/plugin", get(list_plugin_batches))
+        .route("/api/batch/plugin/{name}", post(start_plugin_batch))
+        .route("/batch/{id}/progress", get(batch_progress))
 }
 
 #[cfg(test)]
@@ -178,6 +283,8 @@ mod tests {
             params: serde_json::Value::Null,
             result: None,
             error: None,
+            plugin: None,
+            sandbox: serde_json::Value::Null,
             created: 1000,
             updated: 1000,
         };
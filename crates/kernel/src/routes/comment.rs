@@ -13,9 +13,14 @@ use tower_sessions::Session;
 use uuid::Uuid;
 
 use crate::content::FilterPipeline;
+use crate::middleware::ClientIp;
 use crate::models::{Comment, CreateComment, UpdateComment};
 use crate::routes::auth::SESSION_USER_ID;
 use crate::routes::helpers::{JsonError, require_csrf_header};
+use crate::services::comment_spam::{
+    self, CommentSpamCheckInput, RATE_LIMIT_CATEGORY, SESSION_RENDERED_AT, SpamAction,
+    SpamDetection,
+};
 use crate::state::AppState;
 use crate::tap::UserContext;
 
@@ -64,6 +69,9 @@ pub struct CommentListResponse {
 pub struct CreateCommentRequest {
     pub body: String,
     pub parent_id: Option<Uuid>,
+    /// Honeypot ([`comment_spam::HONEYPOT_FIELD`]); must stay empty.
+    #[serde(default)]
+    pub homepage: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,9 +93,12 @@ pub struct ListCommentsQuery {
 
 /// List comments for an item.
 ///
+/// Also starts the comment spam time trap for the session.
+///
 /// GET /api/item/{id}/comments
 async fn list_item_comments(
     State(state): State<AppState>,
+    session: Session,
     Path(item_id): Path<Uuid>,
     Query(query): Query<ListCommentsQuery>,
 ) -> Result<Json<CommentListResponse>, (StatusCode, Json<JsonError>)> {
@@ -130,6 +141,13 @@ async fn list_item_comments(
 
     let total = comments.len() as i64;
 
+    if let Err(e) = session
+        .insert(SESSION_RENDERED_AT, chrono::Utc::now().timestamp())
+        .await
+    {
+        tracing::warn!(error = %e, "failed to store comment form timestamp");
+    }
+
     // Build response with optional author info
     let mut comment_responses = Vec::with_capacity(comments.len());
     let mut author_cache: std::collections::HashMap<Uuid, AuthorInfo> =
@@ -181,6 +199,7 @@ async fn list_item_comments(
 /// POST /api/item/{id}/comments
 async fn create_comment(
     State(state): State<AppState>,
+    client_ip: ClientIp,
     session: Session,
     headers: HeaderMap,
     Path(item_id): Path<Uuid>,
@@ -209,6 +228,54 @@ async fn create_comment(
             )
         })?;
 
+    let spam_settings = comment_spam::settings(state.db())
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to load comment spam settings; using defaults");
+            comment_spam::CommentSpamSettings::default()
+        });
+
+    // Per-IP posting limit
+    let client_ip = client_ip.key();
+    if spam_settings.rate_limit > 0
+        && let Err(retry_after) = state
+            .rate_limiter()
+            .check_with(
+                RATE_LIMIT_CATEGORY,
+                &client_ip,
+                spam_settings.rate_limit,
+                std::time::Duration::from_secs(spam_settings.rate_window_secs),
+            )
+            .await
+    {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(JsonError {
+                error: format!("Too many comments; try again in {retry_after} seconds"),
+            }),
+        ));
+    }
+
+    // Honeypot and time traps
+    let rendered_at: Option<i64> = session.get(SESSION_RENDERED_AT).await.ok().flatten();
+    let trapped = spam_settings
+        .trap(
+            request.homepage.as_deref(),
+            rendered_at,
+            chrono::Utc::now().timestamp(),
+        )
+        .map(|(source, reason)| SpamDetection::trap(source, reason));
+    if let Some(ref detection) = trapped {
+        tracing::info!(
+            client = %client_ip,
+            source = %detection.source,
+            "comment caught by spam trap"
+        );
+        if spam_settings.trap_action == SpamAction::Reject {
+            return Err(spam_rejected());
+        }
+    }
+
     // Verify item exists (used for notification below)
     let item = state
         .items()
@@ -317,14 +384,52 @@ async fn create_comment(
         ));
     }
 
-    // Create comment
+    // Plugin spam checks (skipped when a trap already caught the comment)
+    let spam = match trapped {
+        Some(detection) => Some((detection, spam_settings.trap_action)),
+        None => {
+            let check = CommentSpamCheckInput {
+                item_id,
+                parent_id: request.parent_id,
+                author_id: user_id,
+                author_name: user.name.clone(),
+                author_mail: user.mail.clone(),
+                body: request.body.clone(),
+                client_ip: client_ip.clone(),
+                user_agent: headers
+                    .get(axum::http::header::USER_AGENT)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string),
+            };
+            match state.comments().check_spam(&check, &user_ctx).await {
+                Ok(detection) => detection.map(|d| (d, spam_settings.spam_action)),
+                Err(e) => {
+                    tracing::warn!(error = %e, "comment spam check failed");
+                    None
+                }
+            }
+        }
+    };
+    if let Some((ref detection, action)) = spam {
+        tracing::info!(
+            client = %client_ip,
+            source = %detection.source,
+            action = action.as_str(),
+            "comment judged spam"
+        );
+        if action == SpamAction::Reject {
+            return Err(spam_rejected());
+        }
+    }
+
+    // Create comment (published unless held for moderation)
     let input = CreateComment {
         item_id,
         parent_id: request.parent_id,
         author_id: user_id,
         body: request.body.clone(),
         body_format: Some("filtered_html".to_string()),
-        status: Some(1), // Published by default
+        status: Some(spam.as_ref().map_or(1, |(_, action)| action.status())),
     };
     let comment = state
         .comments()
@@ -340,6 +445,13 @@ async fn create_comment(
             )
         })?;
 
+    if let Some((detection, action)) = spam
+        && let Err(e) =
+            comment_spam::record(state.db(), comment.id, &detection, action, &client_ip).await
+    {
+        tracing::warn!(error = %e, comment_id = %comment.id, "failed to record comment spam");
+    }
+
     // Get commenter info
     let commenter = state
         .users()
@@ -352,12 +464,12 @@ async fn create_comment(
             name: u.name,
         });
 
-    // Send comment notification to content author (non-blocking)
-    if let Some(email_service) = state.email() {
+    // Queue comment notification to content author (non-blocking); comments
+    // held for moderation are not announced.
+    if state.mail().is_configured() && comment.status == 1 {
         // Only notify when commenter is not the content author
         if comment.author_id != item.author_id {
             let notification_state = state.clone();
-            let email = email_service.clone();
             let comment_body = comment.body.clone();
             let item_title = item.title.clone();
             let item_author_id = item.author_id;
@@ -369,7 +481,6 @@ async fn create_comment(
             tokio::spawn(async move {
                 send_comment_notification(
                     &notification_state,
-                    &email,
                     item_author_id,
                     &commenter_name,
                     &item_title,
@@ -398,6 +509,16 @@ async fn create_comment(
     }))
 }
 
+/// Error for a comment refused by the spam check.
+fn spam_rejected() -> (StatusCode, Json<JsonError>) {
+    (
+        StatusCode::FORBIDDEN,
+        Json(JsonError {
+            error: "Comment rejected as spam".to_string(),
+        }),
+    )
+}
+
 /// Get a single comment.
 ///
 /// GET /api/comment/{id}
@@ -765,13 +886,12 @@ async fn delete_comment(
 // Notification helpers
 // =============================================================================
 
-/// Send a comment notification email to the content author.
+/// Queue a comment notification email to the content author.
 ///
 /// This is called in a background task and must not panic. All errors
 /// are logged but silently swallowed.
 async fn send_comment_notification(
     state: &AppState,
-    email_service: &std::sync::Arc<crate::services::email::EmailService>,
     item_author_id: uuid::Uuid,
     commenter_name: &str,
     item_title: &str,
@@ -798,8 +918,7 @@ async fn send_comment_notification(
     let site_name = crate::models::SiteConfig::site_name(state.db())
         .await
         .unwrap_or_else(|_| "Trovato".to_string());
-    let site_url = email_service.site_url();
-    let action_url = format!("{site_url}/item/{item_id}");
+    let action_url = format!("{}/item/{item_id}", state.site_url());
     let subject = format!("New comment on \"{item_title}\" at {site_name}");
 
     // Truncate comment preview for email
@@ -815,21 +934,24 @@ async fn send_comment_notification(
     context.insert("content_title", item_title);
     context.insert("comment_text", preview);
     context.insert("action_url", &action_url);
-    context.insert("subject", &subject);
-
-    let tera = state.theme().tera();
-    match crate::services::email_templates::render(tera, "comment_notification", &context) {
-        Ok((html, text)) => {
-            if let Err(e) = email_service
-                .send_templated(&author.mail, &subject, &text, html.as_deref())
-                .await
-            {
-                tracing::warn!(error = %e, "comment notification: failed to send email");
-            }
-        }
+
+    let message = match state.mail().compose(
+        state.theme(),
+        "comment_notification",
+        &author.mail,
+        &subject,
+        author.language.as_deref(),
+        &context,
+    ) {
+        Ok(message) => message,
         Err(e) => {
             tracing::warn!(error = %e, "comment notification: failed to render template");
+            return;
         }
+    };
+
+    if let Err(e) = state.mail().queue(message).await {
+        tracing::warn!(error = %e, "comment notification: failed to queue email");
     }
 }
 
@@ -0,0 +1,279 @@
+//! Site contact form.
+//!
+//! `GET /contact` renders the form built by [`crate::services::contact`];
+//! `POST /contact` validates it, stores the submission and mails it to the
+//! recipients of the chosen category.
+//!
+//! Gated on the `trovato_contact` plugin.
+
+use std::collections::HashMap;
+
+use axum::{
+    Router,
+    extract::{Form, State},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+};
+use serde_json::Value;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::form::csrf::generate_csrf_token;
+use crate::form::{FormResult, ValidationError};
+use crate::middleware::ClientIp;
+use crate::models::{ContactCategory, ContactSubmission, CreateContactSubmission};
+use crate::routes::auth::SESSION_USER_ID;
+use crate::routes::helpers::{render_not_found, render_server_error};
+use crate::services::contact::{self, CONTACT_FORM_ID, ContactInput, SESSION_RENDERED_AT};
+use crate::state::AppState;
+use crate::tap::{RequestState, UserContext};
+
+/// Create the contact form router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/contact", get(contact_form).post(contact_submit))
+}
+
+/// Show the contact form.
+///
+/// GET /contact — 404 until an administrator creates a category.
+async fn contact_form(State(state): State<AppState>, session: Session) -> Response {
+    let categories = match ContactCategory::list(state.db()).await {
+        Ok(categories) => categories,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load contact categories");
+            return render_server_error("Failed to load the contact form.");
+        }
+    };
+    if categories.is_empty() {
+        return render_not_found();
+    }
+
+    render_contact_page(&state, &session, &categories, &HashMap::new(), &[], None).await
+}
+
+/// Handle a contact form submission.
+///
+/// POST /contact
+async fn contact_submit(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    session: Session,
+    Form(values): Form<HashMap<String, String>>,
+) -> Response {
+    let client_id = client_ip.key();
+    if let Err(retry_after) = state.rate_limiter().check("contact", &client_id).await {
+        return crate::middleware::rate_limit_response(retry_after);
+    }
+
+    let categories = match ContactCategory::list(state.db()).await {
+        Ok(categories) if !categories.is_empty() => categories,
+        Ok(_) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load contact categories");
+            return render_server_error("Failed to load the contact form.");
+        }
+    };
+
+    let rendered_at: Option<i64> = session.get(SESSION_RENDERED_AT).await.ok().flatten();
+    let now = chrono::Utc::now().timestamp();
+    if let Some(reason) = contact::spam_reason(&values, rendered_at, now) {
+        // Pretend it worked so bots get no signal to adapt to.
+        tracing::info!(client = %client_id, reason, "contact submission dropped as spam");
+        return render_sent(&state, &session).await;
+    }
+
+    let input = match contact::validate(&values, &categories) {
+        Ok(input) => input,
+        Err(errors) => {
+            return render_contact_page(&state, &session, &categories, &values, &errors, None)
+                .await;
+        }
+    };
+
+    let (user_id, request_state) = request_state(&state, &session).await;
+
+    // CSRF check plus tap_form_validate (e.g. spam-checking plugins) and
+    // tap_form_submit.
+    let tap_values: HashMap<String, Value> = values
+        .iter()
+        .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+        .collect();
+    let result = state
+        .forms()
+        .process(CONTACT_FORM_ID, &tap_values, &session, &request_state)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::debug!(error = %e, "contact form token rejected");
+            FormResult::ValidationFailed(vec![ValidationError::form(
+                "Invalid or expired form token. Please try again.",
+            )])
+        });
+    if let FormResult::ValidationFailed(errors) = result {
+        return render_contact_page(&state, &session, &categories, &values, &errors, None).await;
+    }
+
+    let submission = match ContactSubmission::create(
+        state.db(),
+        CreateContactSubmission {
+            category_id: input.category.id,
+            category_name: input.category.name.clone(),
+            name: input.name.clone(),
+            mail: input.mail.clone(),
+            subject: input.subject.clone(),
+            message: input.message.clone(),
+            user_id,
+        },
+    )
+    .await
+    {
+        Ok(submission) => submission,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to store contact submission");
+            let errors = [ValidationError::form(
+                "Your message could not be sent. Please try again later.",
+            )];
+            return render_contact_page(&state, &session, &categories, &values, &errors, None)
+                .await;
+        }
+    };
+
+    send_contact_message(&state, &input).await;
+    tracing::info!(
+        submission_id = %submission.id,
+        category = %input.category.name,
+        "contact message received"
+    );
+
+    render_sent(&state, &session).await
+}
+
+/// Tap request state for the session user.
+async fn request_state(state: &AppState, session: &Session) -> (Option<Uuid>, RequestState) {
+    let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
+    let user_ctx = match user_id {
+        Some(id) => UserContext::authenticated(id, vec![]),
+        None => UserContext::anonymous(),
+    };
+    (
+        user_id,
+        RequestState::new(user_ctx, state.tap_services().clone()),
+    )
+}
+
+/// Mail a submission to its category's recipients.
+///
+/// The first recipient is the `To` address, the rest are copied; replies go
+/// to the sender. Failures are logged: the submission is already stored.
+async fn send_contact_message(state: &AppState, input: &ContactInput) {
+    let Some((to, cc)) = input.category.recipients.split_first() else {
+        tracing::warn!(category = %input.category.name, "contact category has no recipients");
+        return;
+    };
+
+    let site_name = crate::models::SiteConfig::site_name(state.db())
+        .await
+        .unwrap_or_else(|_| "Trovato".to_string());
+    let subject = format!("[{site_name}] {}", input.subject);
+
+    let mut context = tera::Context::new();
+    context.insert("site_name", &site_name);
+    context.insert("category", &input.category.name);
+    context.insert("sender_name", &input.name);
+    context.insert("sender_mail", &input.mail);
+    context.insert("message", &input.message);
+    context.insert(
+        "admin_url",
+        &format!("{}/admin/content/contact", state.site_url()),
+    );
+
+    let mut message = match state.mail().compose(
+        state.theme(),
+        "contact_message",
+        to,
+        &subject,
+        None,
+        &context,
+    ) {
+        Ok(message) => message,
+        Err(e) => {
+            tracing::warn!(error = %e, "contact message: failed to render template");
+            return;
+        }
+    };
+    message.cc = cc.to_vec();
+    message.reply_to = Some(input.mail.clone());
+
+    if let Err(e) = state.mail().queue(message).await {
+        tracing::warn!(error = %e, "contact message: failed to queue email");
+    }
+}
+
+/// Render the confirmation shown after sending.
+async fn render_sent(state: &AppState, session: &Session) -> Response {
+    if let Err(e) = session.remove::<i64>(SESSION_RENDERED_AT).await {
+        tracing::warn!(error = %e, "failed to clear contact form timestamp");
+    }
+    render_contact_page(
+        state,
+        session,
+        &[],
+        &HashMap::new(),
+        &[],
+        Some("Your message has been sent. Thank you for getting in touch."),
+    )
+    .await
+}
+
+/// Render the contact page, with the form unless `success` is set.
+async fn render_contact_page(
+    state: &AppState,
+    session: &Session,
+    categories: &[ContactCategory],
+    values: &HashMap<String, String>,
+    errors: &[ValidationError],
+    success: Option<&str>,
+) -> Response {
+    let mut context = tera::Context::new();
+
+    if let Some(success) = success {
+        context.insert("success", success);
+    } else {
+        let mut form = contact::build_form(categories, values);
+        form.token = generate_csrf_token(session).await;
+
+        let (_, request_state) = request_state(state, session).await;
+        let form = match state.forms().alter(form, &request_state).await {
+            Ok(form) => form,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to alter contact form");
+                return render_server_error("Failed to render the contact form.");
+            }
+        };
+
+        let form_html = match state.theme().render_form_with_errors(&form, errors) {
+            Ok(html) => html,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to render contact form");
+                return render_server_error("Failed to render the contact form.");
+            }
+        };
+        context.insert("form_html", &form_html);
+
+        if let Err(e) = session
+            .insert(SESSION_RENDERED_AT, chrono::Utc::now().timestamp())
+            .await
+        {
+            tracing::warn!(error = %e, "failed to store contact form timestamp");
+        }
+    }
+
+    super::helpers::inject_site_context(state, session, &mut context, "/contact").await;
+
+    match state.theme().tera().render("contact.html", &context) {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to render contact page");
+            render_server_error("Failed to render the contact page.")
+        }
+    }
+}
@@ -2,25 +2,43 @@
 
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
 use tracing::info;
+use uuid::Uuid;
 
-use crate::cron::CronResult;
+use crate::cron::{
+    CRON_TASKS, CronHistoryFilter, CronResult, CronSchedule, CronTaskRun, CronTaskStatus,
+    DeadLetter, KERNEL_QUEUES, MAX_ATTEMPTS, PluginCronState, PluginQueueDepth, Queue,
+};
+use crate::error::AppError;
 use crate::state::AppState;
 
-use super::helpers::require_admin;
+use super::helpers::{require_admin, require_admin_json, require_csrf_header};
 
 /// Create the cron router.
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/cron/{key}", post(run_cron))
         .route("/cron/status", get(cron_status))
+        .route("/admin/reports/cron", get(cron_report))
+        .route("/admin/reports/cron/history", get(cron_history))
+        .route("/admin/reports/cron/plugins", get(cron_plugin_states))
+        .route("/admin/cron/tasks/{name}", post(update_cron_task))
+        .route("/admin/reports/queues", get(queue_report))
+        .route(
+            "/admin/reports/queues/{queue}/retry",
+            post(retry_dead_letters),
+        )
+        .route(
+            "/admin/reports/queues/{queue}/discard",
+            post(discard_dead_letters),
+        )
 }
 
 /// Cron run response.
@@ -151,10 +169,9 @@ async fn cron_status(State(state): State<AppState>, session: Session) -> Respons
     };
 
     // Get queue lengths
-    use crate::cron::Queue;
     let queue = state.cron().queue();
     let email_send = queue.len("email:send").await.unwrap_or(0);
-    let search_reindex = queue.len("search:reindex").await.unwrap_or(0);
+    let search_reindex = queue.len(crate::search::REINDEX_QUEUE).await.unwrap_or(0);
 
     Json(CronStatusResponse {
         last_run,
@@ -165,3 +182,288 @@ async fn cron_status(State(state): State<AppState>, session: Session) -> Respons
     })
     .into_response()
 }
+
+/// Cron report response.
+#[derive(Debug, Serialize)]
+pub struct CronReportResponse {
+    /// Most recent cron run, if any.
+    pub last_run: Option<crate::cron::LastCronRun>,
+    /// Maximum pre-lock jitter in seconds.
+    pub jitter_secs: u64,
+    /// Per-task schedule and run status.
+    pub tasks: Vec<CronTaskStatus>,
+}
+
+/// Per-task cron report (admin only).
+///
+/// GET /admin/reports/cron
+async fn cron_report(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<CronReportResponse>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let last_run = state
+        .cron()
+        .last_run()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load cron last run"))?;
+    let tasks = state
+        .cron()
+        .task_statuses()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load cron task statuses"))?;
+
+    Ok(Json(CronReportResponse {
+        last_run,
+        jitter_secs: state.cron().jitter_secs(),
+        tasks,
+    }))
+}
+
+/// Cron run history (admin only).
+///
+/// GET /admin/reports/cron/history?task=&status=&since=&limit=
+///
+/// Returns recorded task runs, newest first.
+async fn cron_history(
+    State(state): State<AppState>,
+    session: Session,
+    Query(filter): Query<CronHistoryFilter>,
+) -> Result<Json<Vec<CronTaskRun>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let runs = state
+        .cron()
+        .history(&filter)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load cron history"))?;
+
+    Ok(Json(runs))
+}
+
+/// Stored `tap_cron` run and state per plugin (admin only).
+///
+/// GET /admin/reports/cron/plugins
+async fn cron_plugin_states(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<PluginCronState>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let states = state
+        .cron()
+        .plugin_states()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load plugin cron state"))?;
+
+    Ok(Json(states))
+}
+
+/// Request body for updating a cron task.
+///
+/// Omitted fields keep their current value. An empty `schedule` string
+/// clears the schedule so the task runs every cycle.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCronTaskRequest {
+    /// Enable or disable the task.
+    pub enabled: Option<bool>,
+    /// Cron expression for when the task runs; empty to clear it.
+    pub schedule: Option<String>,
+}
+
+/// Enable, disable, or reschedule a cron task (admin only).
+///
+/// POST /admin/cron/tasks/{name}
+async fn update_cron_task(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(body): Json<UpdateCronTaskRequest>,
+) -> Result<Json<CronTaskStatus>, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    if !CRON_TASKS.contains(&name.as_str()) {
+        return Err(AppError::not_found_id("cron task", &name));
+    }
+
+    let mut settings = state
+        .cron()
+        .task_settings(&name)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load cron task settings"))?;
+
+    if let Some(enabled) = body.enabled {
+        settings.enabled = enabled;
+    }
+    if let Some(schedule) = body.schedule {
+        let schedule = schedule.trim();
+        if schedule.is_empty() {
+            settings.schedule = None;
+        } else {
+            let parsed = CronSchedule::parse(schedule).map_err(|e| {
+                AppError::validation(vec![AppError::field_error("schedule", "invalid_format", e)])
+            })?;
+            settings.schedule = Some(parsed.expression().to_string());
+        }
+    }
+
+    state
+        .cron()
+        .set_task_settings(&name, &settings)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save cron task settings"))?;
+
+    info!(task = %name, enabled = settings.enabled, schedule = ?settings.schedule, "cron task updated");
+
+    let status = state
+        .cron()
+        .task_statuses()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load cron task statuses"))?
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| AppError::not_found_id("cron task", &name))?;
+
+    Ok(Json(status))
+}
+
+/// Depth, pending retries and dead letters of a kernel queue.
+#[derive(Debug, Serialize)]
+pub struct QueueReport {
+    pub name: &'static str,
+    /// Items waiting to be processed.
+    pub depth: u64,
+    /// Failed items waiting for the next run.
+    pub retrying: u64,
+    /// Items that failed too often, newest first.
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+/// Queue report response.
+#[derive(Debug, Serialize)]
+pub struct QueuesReportResponse {
+    /// Failures after which an item becomes a dead letter.
+    pub max_attempts: u64,
+    pub queues: Vec<QueueReport>,
+    /// Pending `tap_queue_worker` items per plugin queue.
+    pub plugin_queues: Vec<PluginQueueDepth>,
+}
+
+/// Queue depths and dead letters (admin only).
+///
+/// GET /admin/reports/queues
+async fn queue_report(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<QueuesReportResponse>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let queue = state.cron().queue();
+    let mut queues = Vec::with_capacity(KERNEL_QUEUES.len());
+    for &name in KERNEL_QUEUES {
+        queues.push(QueueReport {
+            name,
+            depth: queue
+                .len(name)
+                .await
+                .map_err(|e| AppError::internal_ctx(e, "load queue length"))?,
+            retrying: queue
+                .retry_len(name)
+                .await
+                .map_err(|e| AppError::internal_ctx(e, "load queue retries"))?,
+            dead_letters: queue
+                .dead_letters(name)
+                .await
+                .map_err(|e| AppError::internal_ctx(e, "load dead letters"))?,
+        });
+    }
+    let plugin_queues = state
+        .cron()
+        .plugin_queue_depths()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load plugin queue depths"))?;
+
+    Ok(Json(QueuesReportResponse {
+        max_attempts: MAX_ATTEMPTS,
+        queues,
+        plugin_queues,
+    }))
+}
+
+/// Request body selecting dead letters.
+///
+/// Without `ids`, every dead letter of the queue is selected.
+#[derive(Debug, Default, Deserialize)]
+pub struct DeadLetterSelection {
+    pub ids: Option<Vec<Uuid>>,
+}
+
+/// Check admin access and CSRF for a dead-letter action and resolve the queue.
+async fn dead_letter_queue(
+    state: &AppState,
+    session: &Session,
+    headers: &HeaderMap,
+    queue: &str,
+) -> Result<&'static str, AppError> {
+    require_admin_json(state, session).await?;
+    require_csrf_header(session, headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    KERNEL_QUEUES
+        .iter()
+        .copied()
+        .find(|name| *name == queue)
+        .ok_or_else(|| AppError::not_found_id("queue", queue))
+}
+
+/// Push dead letters back onto their queue (admin only).
+///
+/// POST /admin/reports/queues/{queue}/retry
+async fn retry_dead_letters(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(queue): Path<String>,
+    Json(selection): Json<DeadLetterSelection>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let queue = dead_letter_queue(&state, &session, &headers, &queue).await?;
+
+    let retried = state
+        .cron()
+        .queue()
+        .retry_dead_letters(queue, selection.ids.as_deref())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "retry dead letters"))?;
+
+    info!(queue = %queue, retried, "dead letters retried");
+    Ok(Json(serde_json::json!({ "retried": retried })))
+}
+
+/// Delete dead letters (admin only).
+///
+/// POST /admin/reports/queues/{queue}/discard
+async fn discard_dead_letters(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(queue): Path<String>,
+    Json(selection): Json<DeadLetterSelection>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let queue = dead_letter_queue(&state, &session, &headers, &queue).await?;
+
+    let discarded = state
+        .cron()
+        .queue()
+        .discard_dead_letters(queue, selection.ids.as_deref())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "discard dead letters"))?;
+
+    info!(queue = %queue, discarded, "dead letters discarded");
+    Ok(Json(serde_json::json!({ "discarded": discarded })))
+}
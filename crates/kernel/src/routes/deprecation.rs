@@ -0,0 +1,36 @@
+//! Deprecated API usage report (admin only).
+//!
+//! - `GET /admin/reports/deprecations` — every registered deprecation with
+//!   its removal date, replacement and per-client usage
+
+use axum::{Json, Router, extract::State, routing::get};
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::services::deprecation::DeprecationReport;
+use crate::state::AppState;
+
+use super::helpers::require_admin_json;
+
+/// Create the deprecation report router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/admin/reports/deprecations", get(deprecation_report))
+}
+
+/// List deprecations and who still uses them.
+///
+/// GET /admin/reports/deprecations
+async fn deprecation_report(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<DeprecationReport>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let report = state
+        .deprecations()
+        .report()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load deprecation report"))?;
+
+    Ok(Json(report))
+}
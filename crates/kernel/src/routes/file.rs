@@ -1,28 +1,40 @@
 //! File upload route handlers.
 
+use std::collections::HashMap;
+use std::time::Duration;
+
 use axum::{
     Json, Router,
     extract::{Multipart, Path, State},
     http::{HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::file::{ALLOWED_MIME_TYPES, MAX_FILE_SIZE, UploadResult};
+use crate::error::AppError;
+use crate::file::{ALLOWED_MIME_TYPES, DirectUpload, MAX_FILE_SIZE, UploadResult};
 use crate::routes::auth::SESSION_USER_ID;
 use crate::state::AppState;
 
 /// Allowed image MIME types for block editor uploads.
 const BLOCK_EDITOR_IMAGE_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
 
+/// Session key holding pending direct uploads by ID.
+const SESSION_DIRECT_UPLOADS: &str = "direct_uploads";
+
+/// Lifetime of presigned download URLs handed out by `/files/{*path}`.
+const DOWNLOAD_URL_TTL: Duration = Duration::from_secs(5 * 60);
+
 /// Create the file router.
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/file/upload", post(upload_file))
+        .route("/file/presign", post(presign_upload))
+        .route("/file/presign/{id}/complete", post(complete_direct_upload))
         .route("/file/{id}", get(get_file_info))
         .route("/files/{*path}", get(serve_uploaded_file))
 }
@@ -200,6 +212,103 @@ async fn upload_file(
     }
 }
 
+/// Direct upload request.
+#[derive(Debug, Deserialize)]
+struct PresignRequest {
+    filename: String,
+    mime_type: String,
+    /// Exact size of the file in bytes.
+    size: u64,
+}
+
+/// Start a direct upload to the storage backend.
+///
+/// POST /file/presign
+///
+/// Returns a presigned `upload_url`. The client `PUT`s the file there with
+/// the same `Content-Type`, then calls `POST /file/presign/{id}/complete`.
+/// Only available with S3 storage.
+async fn presign_upload(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(request): Json<PresignRequest>,
+) -> Result<Json<DirectUpload>, AppError> {
+    require_upload_user(&session, &headers).await?;
+
+    let upload = state
+        .files()
+        .presign_upload(
+            &request.filename,
+            &request.mime_type,
+            request.size,
+            crate::services::site::scoped_tenant_id(),
+        )
+        .await
+        .map_err(|e| AppError::bad_request(e.to_string()))?
+        .ok_or_else(|| {
+            AppError::bad_request("direct uploads are not supported by this storage backend")
+        })?;
+
+    let mut pending = pending_uploads(&session).await;
+    let now = chrono::Utc::now().timestamp();
+    pending.retain(|_, u| u.expires_at > now);
+    pending.insert(upload.id, upload.clone());
+    session
+        .insert(SESSION_DIRECT_UPLOADS, &pending)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "store pending upload"))?;
+
+    Ok(Json(upload))
+}
+
+/// Create the file record for a finished direct upload.
+///
+/// POST /file/presign/{id}/complete
+async fn complete_direct_upload(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UploadResult>, AppError> {
+    let user_id = require_upload_user(&session, &headers).await?;
+
+    let mut pending = pending_uploads(&session).await;
+    let upload = pending
+        .remove(&id)
+        .ok_or_else(|| AppError::not_found_id("upload", id))?;
+    session
+        .insert(SESSION_DIRECT_UPLOADS, &pending)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "clear pending upload"))?;
+
+    let result = state
+        .files()
+        .complete_direct_upload(user_id, &upload)
+        .await
+        .map_err(|e| AppError::bad_request(e.to_string()))?;
+    Ok(Json(result))
+}
+
+/// Require an authenticated user and a CSRF header for upload endpoints.
+async fn require_upload_user(session: &Session, headers: &HeaderMap) -> Result<Uuid, AppError> {
+    let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
+    let user_id = user_id.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    crate::routes::helpers::require_csrf_header(session, headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    Ok(user_id)
+}
+
+async fn pending_uploads(session: &Session) -> HashMap<Uuid, DirectUpload> {
+    session
+        .get(SESSION_DIRECT_UPLOADS)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
 /// File info response.
 #[derive(Debug, Serialize)]
 pub struct FileInfoResponse {
@@ -240,7 +349,9 @@ async fn get_file_info(State(state): State<AppState>, Path(id): Path<Uuid>) -> R
 ///
 /// GET /files/{*path}
 ///
-/// Path segments map to the storage URI after the `local://` prefix.
+/// Path segments map to the storage URI after the scheme prefix
+/// (`local://`, `s3://`). Backends that presign downloads (private S3
+/// buckets) get a redirect to a short-lived URL instead of the file.
 /// Rejects directory traversal attempts.
 async fn serve_uploaded_file(State(state): State<AppState>, Path(path): Path<String>) -> Response {
     let path = path.trim_start_matches('/');
@@ -256,7 +367,17 @@ async fn serve_uploaded_file(State(state): State<AppState>, Path(path): Path<Str
         return StatusCode::NOT_FOUND.into_response();
     }
 
-    let uri = format!("local://{path}");
+    let storage = state.files().storage();
+    let uri = format!("{}://{path}", storage.scheme());
+
+    match storage.presigned_download_url(&uri, DOWNLOAD_URL_TTL).await {
+        Ok(Some(url)) => return Redirect::temporary(&url).into_response(),
+        Ok(None) => {}
+        Err(e) => {
+            warn!(error = %e, path = %path, "failed to presign file download");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
 
     match state.files().load_file_data(&uri).await {
         Ok(Some(data)) => {
@@ -0,0 +1,245 @@
+//! Item flag routes.
+//!
+//! - `GET /api/flags` — available flags
+//! - `POST /api/flags`, `DELETE /api/flags/{name}` — add or remove an
+//!   administrator-defined flag (admin only)
+//! - `GET /item/{id}/flags` — each flag's count and state for the user
+//! - `POST|DELETE /item/{id}/flag/{name}` — set or clear a flag
+//!
+//! Mutating requests require the `X-CSRF-Token` header. Gated on the
+//! `trovato_flags` plugin.
+
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::Item;
+use crate::services::flag::{
+    ADMIN_PROVIDER, FlagDefinition, FlagScope, FlagService, FlagState, is_valid_flag_name,
+};
+use crate::state::AppState;
+
+use super::helpers::{require_admin_json, require_csrf_header};
+use super::item::{enabled_plugin_infos, get_user_context};
+
+/// Create the flag router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/flags", get(list_flags).post(create_flag))
+        .route("/api/flags/{name}", delete(delete_flag))
+        .route("/item/{id}/flags", get(item_flags))
+        .route("/item/{id}/flag/{name}", post(set_flag).delete(clear_flag))
+}
+
+/// Request to add a flag.
+#[derive(Debug, Deserialize)]
+struct CreateFlagRequest {
+    name: String,
+    label: String,
+    #[serde(default)]
+    scope: FlagScope,
+}
+
+/// The flag service, or 503 if it was not started with the server.
+fn flag_service(state: &AppState) -> Result<&Arc<FlagService>, AppError> {
+    state
+        .flags()
+        .ok_or_else(|| AppError::service_unavailable("flags", "Item flags not enabled"))
+}
+
+/// All flags, kernel, plugin and administrator-defined.
+async fn definitions(state: &AppState) -> Result<Vec<FlagDefinition>, AppError> {
+    flag_service(state)?
+        .definitions(enabled_plugin_infos(state))
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load flags"))
+}
+
+/// List the available flags.
+///
+/// GET /api/flags
+async fn list_flags(State(state): State<AppState>) -> Result<Json<Vec<FlagDefinition>>, AppError> {
+    Ok(Json(definitions(&state).await?))
+}
+
+/// Add an administrator-defined flag.
+///
+/// POST /api/flags
+async fn create_flag(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(request): Json<CreateFlagRequest>,
+) -> Result<(StatusCode, Json<FlagDefinition>), AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    if !is_valid_flag_name(&request.name) {
+        return Err(AppError::bad_request(
+            "flag name must be lowercase letters, digits or '_', starting with a letter",
+        ));
+    }
+    let label = request.label.trim();
+    if label.is_empty() {
+        return Err(AppError::bad_request("flag label is required"));
+    }
+    if definitions(&state)
+        .await?
+        .iter()
+        .any(|f| f.name == request.name)
+    {
+        return Err(AppError::conflict(format!(
+            "flag '{}' already exists",
+            request.name
+        )));
+    }
+
+    let flag = flag_service(&state)?
+        .create(&request.name, label, request.scope)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "create flag"))?;
+    Ok((StatusCode::CREATED, Json(flag)))
+}
+
+/// Remove an administrator-defined flag and all its marks.
+///
+/// DELETE /api/flags/{name}
+async fn delete_flag(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    if let Some(flag) = definitions(&state)
+        .await?
+        .into_iter()
+        .find(|f| f.name == name)
+        && flag.provider != ADMIN_PROVIDER
+    {
+        return Err(AppError::bad_request(format!(
+            "flag '{name}' is provided by {} and cannot be deleted",
+            flag.provider
+        )));
+    }
+
+    let deleted = flag_service(&state)?
+        .delete(&name)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete flag"))?;
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::not_found("flag"))
+    }
+}
+
+/// Load an item the user may view.
+async fn viewable_item(
+    state: &AppState,
+    user: &crate::tap::UserContext,
+    id: Uuid,
+) -> Result<Item, AppError> {
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+    let allowed = state
+        .items()
+        .check_access(&item, "view", user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "check item access"))?;
+    if !allowed {
+        return Err(AppError::not_found_id("item", id));
+    }
+    Ok(item)
+}
+
+/// Each flag's count and state on an item for the current user.
+///
+/// GET /item/{id}/flags
+async fn item_flags(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<FlagState>>, AppError> {
+    let user = get_user_context(&session, &state).await;
+    let item = viewable_item(&state, &user, id).await?;
+
+    let flags = definitions(&state).await?;
+    let states = flag_service(&state)?
+        .states(&flags, &item, user.id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item flags"))?;
+    Ok(Json(states))
+}
+
+/// POST /item/{id}/flag/{name}
+async fn set_flag(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((id, name)): Path<(Uuid, String)>,
+) -> Result<Json<FlagState>, AppError> {
+    change_flag(&state, &session, &headers, id, &name, true).await
+}
+
+/// DELETE /item/{id}/flag/{name}
+async fn clear_flag(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path((id, name)): Path<(Uuid, String)>,
+) -> Result<Json<FlagState>, AppError> {
+    change_flag(&state, &session, &headers, id, &name, false).await
+}
+
+/// Set or clear a flag on an item for the current user.
+async fn change_flag(
+    state: &AppState,
+    session: &Session,
+    headers: &HeaderMap,
+    id: Uuid,
+    name: &str,
+    flagged: bool,
+) -> Result<Json<FlagState>, AppError> {
+    let user = get_user_context(session, state).await;
+    require_csrf_header(session, headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Login required"));
+    }
+
+    let flag = definitions(state)
+        .await?
+        .into_iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| AppError::not_found("flag"))?;
+    let item = viewable_item(state, &user, id).await?;
+
+    match flag_service(state)?.set(&flag, &item, &user, flagged).await {
+        Ok(flag_state) => Ok(Json(flag_state)),
+        Err(e) if e.to_string().contains("access denied") => {
+            Err(AppError::forbidden("Access denied"))
+        }
+        Err(e) => Err(AppError::internal_ctx(e, "change item flag")),
+    }
+}
@@ -2,15 +2,16 @@
 
 use axum::{Router, extract::State, response::Html, routing::get};
 use tower_sessions::Session;
+use trovato_sdk::types::ViewMode;
 use uuid::Uuid;
 
-use crate::content::FilterPipeline;
+use crate::content::{FilterPipeline, display};
 use crate::models::{Item, SiteConfig};
 use crate::state::AppState;
 use crate::tap::UserContext;
 
 use super::auth::SESSION_USER_ID;
-use super::helpers::{html_escape, inject_site_context};
+use super::helpers::{format_display_value, html_escape, inject_site_context};
 
 /// Create the front page router.
 pub fn router() -> Router<AppState> {
@@ -30,7 +31,7 @@ async fn front_page(State(state): State<AppState>, session: Session) -> Html<Str
     }
 
     // Fall back to promoted items listing
-    let content = render_promoted_listing(&state).await;
+    let content = render_promoted_listing(&state, &session).await;
 
     let mut context = tera::Context::new();
     inject_site_context(&state, &session, &mut context, "/").await;
@@ -96,10 +97,13 @@ async fn render_configured_front_page(
 }
 
 /// Render promoted items listing HTML.
-async fn render_promoted_listing(state: &AppState) -> String {
+///
+/// Lists only items the visitor may see under item grants.
+async fn render_promoted_listing(state: &AppState, session: &Session) -> String {
+    let user = super::item::get_user_context(session, state).await;
     let items = state
         .items()
-        .list_published(10, 0)
+        .list_published(&user, 10, 0)
         .await
         .unwrap_or_default();
 
@@ -128,8 +132,36 @@ async fn render_promoted_listing(state: &AppState) -> String {
             ));
         }
 
-        // Render body field summary if available
-        if let Some(body) = item
+        // Render the fields the content type shows in teasers; without
+        // teaser display settings, fall back to a body summary.
+        let content_type = state.content_types().get(&item.item_type);
+        let teaser_fields = item
+            .fields
+            .as_object()
+            .map(|fields| {
+                display::fields_to_display(content_type.as_ref(), fields, ViewMode::Teaser)
+            })
+            .unwrap_or_default();
+        if !teaser_fields.is_empty() {
+            let mut rendered: Vec<(&str, String)> = Vec::new();
+            for field in &teaser_fields {
+                let (Some(definition), Some(formatter)) =
+                    (field.definition, field.formatter.as_ref())
+                else {
+                    continue;
+                };
+                if let Some(html) = format_display_value(state, field.value, formatter).await {
+                    rendered.push((
+                        field.name,
+                        display::render_field(definition, formatter, &html),
+                    ));
+                }
+            }
+            html.push_str(&format!(
+                "<div class=\"blog-teaser__fields\">{}</div>",
+                display::group_fields(content_type.as_ref(), &rendered)
+            ));
+        } else if let Some(body) = item
             .fields
             .get("body")
             .and_then(|f| f.get("value"))
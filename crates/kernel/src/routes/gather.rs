@@ -2,19 +2,26 @@
 //!
 //! REST endpoints for executing gather queries.
 
+use crate::cache::conditional;
+use crate::gather::archive::{self, ArchiveYear};
 use crate::gather::{
-    ExposedWidget, FilterValue, GatherQuery, QueryContext, QueryDefinition, QueryDisplay,
+    ArchivePeriod, ExportFormat, ExportTarget, ExposedWidget, FilterValue, GatherQuery,
+    MAX_ITEMS_PER_PAGE, QueryContext, QueryDefinition, QueryDisplay, renderer_for,
 };
 use crate::middleware::language::ResolvedLanguage;
 use crate::models::TagWithDepth;
 use crate::models::stage::LIVE_STAGE_ID;
+use crate::permissions::view_own_unpublished_permission;
 use crate::routes::auth::SESSION_USER_ID;
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::services::site;
 use crate::state::AppState;
 use axum::{
     Extension, Router,
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::{Html, Json},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{Html, IntoResponse, Json, Response},
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
@@ -37,12 +44,38 @@ fn language_for_context(lang: &ResolvedLanguage, default: &str) -> Option<String
     }
 }
 
+/// Item types whose unpublished items the user may list as their author.
+///
+/// Empty for anonymous users; admins get every type.
+async fn own_unpublished_types(state: &AppState, user_id: Option<Uuid>) -> Vec<String> {
+    let Some(id) = user_id else {
+        return Vec::new();
+    };
+    let Ok(Some(user)) = state.users().find_by_id(id).await else {
+        return Vec::new();
+    };
+    let mut types = Vec::new();
+    for item_type in state.content_types().type_names() {
+        let permission = view_own_unpublished_permission(&item_type);
+        if state
+            .permissions()
+            .user_has_permission(&user, &permission)
+            .await
+            .unwrap_or(false)
+        {
+            types.push(item_type);
+        }
+    }
+    types
+}
+
 /// Create the gather router.
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/api/queries", get(list_queries))
         .route("/api/query/{query_id}", get(get_query))
         .route("/api/query/{query_id}/execute", get(execute_query))
+        .route("/api/query/{query_id}/archive", get(get_query_archive))
         .route("/api/gather/query", post(execute_adhoc_query))
         .route("/gather/{query_id}", get(render_query_html))
 }
@@ -80,6 +113,16 @@ struct GatherResultResponse {
     total_pages: u32,
     has_next: bool,
     has_prev: bool,
+    /// Set when the requested page size was clamped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ArchiveResponse {
+    query_id: String,
+    base_path: String,
+    years: Vec<ArchiveYear>,
 }
 
 // -------------------------------------------------------------------------
@@ -92,9 +135,14 @@ pub struct ExecuteParams {
     page: u32,
     #[serde(default = "default_stage")]
     stage: String,
+    /// Page size override; defaults to the query's `items_per_page`.
+    per_page: Option<i64>,
     /// Exposed filter values as JSON-encoded strings
     #[serde(flatten)]
     filters: HashMap<String, String>,
+    /// Archive period set by archive route handlers (never from the query string).
+    #[serde(skip)]
+    archive: Option<ArchivePeriod>,
 }
 
 impl ExecuteParams {
@@ -106,9 +154,17 @@ impl ExecuteParams {
         Self {
             page: page.max(1),
             stage,
+            per_page: None,
             filters,
+            archive: None,
         }
     }
+
+    /// Restrict execution to items created within an archive period.
+    pub fn with_archive(mut self, period: ArchivePeriod) -> Self {
+        self.archive = Some(period);
+        self
+    }
 }
 
 fn default_page() -> u32 {
@@ -172,32 +228,92 @@ async fn get_query(
     }))
 }
 
+/// Year/month facet counts for a query with archive routes.
+///
+/// Counts cover published Live-stage items only, matching what the public
+/// archive routes display.
+///
+/// GET /api/query/{query_id}/archive
+async fn get_query_archive(
+    State(state): State<AppState>,
+    Path(query_id): Path<String>,
+) -> Result<Json<ArchiveResponse>, AppError> {
+    let query = state
+        .gather()
+        .get_query(&query_id)
+        .ok_or_else(|| AppError::not_found_id("query", &query_id))?;
+
+    let (Some(config), Some(item_type)) = (
+        query.display.archive.as_ref(),
+        query.definition.item_type.as_deref(),
+    ) else {
+        return Err(AppError::not_found_id("archive", &query_id));
+    };
+
+    let buckets = state
+        .gather()
+        .archive_counts(item_type, &[LIVE_STAGE_ID])
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "archive counts"))?;
+
+    Ok(Json(ArchiveResponse {
+        years: archive::group_by_year(&buckets, &config.base_path),
+        base_path: config.base_path.clone(),
+        query_id: query.query_id,
+    }))
+}
+
 async fn execute_query(
     State(state): State<AppState>,
     session: Session,
     Extension(resolved_lang): Extension<ResolvedLanguage>,
     Path(query_id): Path<String>,
     Query(params): Query<ExecuteParams>,
-) -> Result<Json<GatherResultResponse>, AppError> {
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
     let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
     let language = language_for_context(&resolved_lang, state.default_language());
     let context = QueryContext {
         current_user_id: user_id,
         url_args: params.filters.clone(),
         language,
+        archive: None,
+        grants_user: Some(user_id.unwrap_or(Uuid::nil())),
+        own_unpublished_types: own_unpublished_types(&state, user_id).await,
     };
 
     // Parse exposed filter values
     let exposed_filters = parse_filter_params(&params.filters);
     let stage_id = params.stage.parse::<Uuid>().unwrap_or(LIVE_STAGE_ID);
 
-    let result = state
-        .gather()
-        .execute(&query_id, params.page, exposed_filters, stage_id, &context)
-        .await
-        .map_err(|e| AppError::internal_ctx(e, "execute gather query"))?;
+    let gather = state.gather();
+    let (result, warning) = match params.per_page {
+        Some(requested) => {
+            let page_size = PaginationPolicy::load(state.db())
+                .await
+                .resolve(PageClass::Gather, Some(requested));
+            let result = gather
+                .execute_paged(
+                    &query_id,
+                    params.page,
+                    page_size.limit as u32,
+                    exposed_filters,
+                    stage_id,
+                    &context,
+                )
+                .await;
+            (result, page_size.warning)
+        }
+        None => {
+            let result = gather
+                .execute(&query_id, params.page, exposed_filters, stage_id, &context)
+                .await;
+            (result, None)
+        }
+    };
+    let result = result.map_err(|e| AppError::internal_ctx(e, "execute gather query"))?;
 
-    Ok(Json(GatherResultResponse {
+    let response = GatherResultResponse {
         items: result.items,
         total: result.total,
         page: result.page,
@@ -205,7 +321,9 @@ async fn execute_query(
         total_pages: result.total_pages,
         has_next: result.has_next,
         has_prev: result.has_prev,
-    }))
+        warning,
+    };
+    Ok(conditional::json_response(&headers, &response))
 }
 
 async fn execute_adhoc_query(
@@ -220,6 +338,9 @@ async fn execute_adhoc_query(
         current_user_id: user_id,
         url_args: HashMap::new(),
         language,
+        archive: None,
+        grants_user: Some(user_id.unwrap_or(Uuid::nil())),
+        own_unpublished_types: own_unpublished_types(&state, user_id).await,
     };
 
     // Convert JSON filter values to FilterValue
@@ -231,11 +352,19 @@ async fn execute_adhoc_query(
 
     let stage_id = request.stage.parse::<Uuid>().unwrap_or(LIVE_STAGE_ID);
 
+    // Ad-hoc displays come from the client, so their page size is a request.
+    let page_size = PaginationPolicy::load(state.db()).await.resolve(
+        PageClass::Gather,
+        Some(i64::from(request.display.items_per_page)),
+    );
+    let mut display = request.display;
+    display.items_per_page = page_size.limit as u32;
+
     let result = state
         .gather()
         .execute_definition(
             &request.definition,
-            &request.display,
+            &display,
             request.page,
             exposed_filters,
             stage_id,
@@ -252,6 +381,7 @@ async fn execute_adhoc_query(
         total_pages: result.total_pages,
         has_next: result.has_next,
         has_prev: result.has_prev,
+        warning: page_size.warning,
     }))
 }
 
@@ -261,7 +391,23 @@ async fn render_query_html(
     Extension(resolved_lang): Extension<ResolvedLanguage>,
     Path(query_id): Path<String>,
     Query(params): Query<ExecuteParams>,
-) -> Result<Html<String>, (StatusCode, Json<JsonError>)> {
+) -> Response {
+    let language = language_for_context(&resolved_lang, state.default_language());
+
+    // `/gather/{id}.csv`, `.rss` and `.atom` are exports, unless a query
+    // has that literal ID.
+    if state.gather().get_query(&query_id).is_none()
+        && let Some((id, ext)) = query_id.rsplit_once('.')
+        && let Some(format) = ExportFormat::from_extension(ext)
+    {
+        let base_path = state
+            .gather()
+            .get_query(id)
+            .and_then(|q| q.display.canonical_url)
+            .unwrap_or_else(|| format!("/gather/{id}"));
+        return render_export(&state, &session, id, format, params, &base_path, language).await;
+    }
+
     // Use canonical_url from the query definition when available so that pager
     // links and filter form actions stay on the friendly URL (e.g. /conferences)
     // rather than /gather/{query_id}.
@@ -270,8 +416,159 @@ async fn render_query_html(
         .get_query(&query_id)
         .and_then(|q| q.display.canonical_url);
     let base_path = canonical.unwrap_or_else(|| format!("/gather/{query_id}"));
-    let language = language_for_context(&resolved_lang, state.default_language());
-    execute_and_render(&state, &session, &query_id, params, &base_path, language).await
+    execute_and_render(&state, &session, &query_id, params, &base_path, language)
+        .await
+        .into_response()
+}
+
+/// Render a gather query as a CSV or feed export.
+///
+/// Exports always show Live content. Feeds cover the requested page; CSV
+/// streams every page at the maximum page size. Queries that do not enable
+/// `format` in `display.exports` answer 404. `base_path` is the HTML
+/// listing's path; the export lives at `{base_path}.{ext}`.
+pub async fn render_export(
+    state: &AppState,
+    session: &Session,
+    query_id: &str,
+    format: ExportFormat,
+    params: ExecuteParams,
+    base_path: &str,
+    language: Option<String>,
+) -> Response {
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(JsonError {
+                error: "export not found".to_string(),
+            }),
+        )
+            .into_response()
+    };
+    let Some(query) = state.gather().get_query(query_id) else {
+        return not_found();
+    };
+    let export_path = format!("{base_path}.{}", format.extension());
+    let target = ExportTarget {
+        site_url: state.site_url(),
+        base_path,
+        export_path: &export_path,
+        now: chrono::Utc::now().timestamp(),
+    };
+    let Some(renderer) = renderer_for(&query, format, &target) else {
+        return not_found();
+    };
+
+    let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
+    let context = QueryContext {
+        current_user_id: user_id,
+        url_args: params.filters.clone(),
+        language,
+        archive: params.archive,
+        grants_user: Some(user_id.unwrap_or(Uuid::nil())),
+        // Exports are shared documents (feeds, downloads): published only.
+        own_unpublished_types: Vec::new(),
+    };
+    let exposed_filters = parse_filter_params(&params.filters);
+    let (page, per_page) = if renderer.all_pages() {
+        (1, MAX_ITEMS_PER_PAGE)
+    } else {
+        (params.page, query.display.items_per_page)
+    };
+
+    // The first page runs before the response starts so failures still
+    // get an error status.
+    let first = match state
+        .gather()
+        .execute_paged(
+            query_id,
+            page,
+            per_page,
+            exposed_filters.clone(),
+            LIVE_STAGE_ID,
+            &context,
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!(error = %e, query_id = %query_id, "gather export failed");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(JsonError {
+                    error: "export failed".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(renderer.content_type()),
+    );
+    if renderer.all_pages()
+        && let Ok(disposition) = HeaderValue::from_str(&format!(
+            "attachment; filename=\"{query_id}.{}\"",
+            format.extension()
+        ))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+
+    let mut head = renderer.begin();
+    for item in &first.items {
+        head.push_str(&renderer.item(item));
+    }
+    if !renderer.all_pages() || !first.has_next {
+        head.push_str(&renderer.end());
+        return (headers, head).into_response();
+    }
+
+    // The body is polled outside the request task; later pages re-enter
+    // the site scope so they stay tenant-filtered.
+    let site_scope = site::current();
+    let state = state.clone();
+    let query_id = query_id.to_string();
+    let stream = async_stream::stream! {
+        yield Ok::<_, std::io::Error>(head);
+        let mut page = first.page;
+        loop {
+            page += 1;
+            let fetch = state.gather().execute_paged(
+                &query_id,
+                page,
+                per_page,
+                exposed_filters.clone(),
+                LIVE_STAGE_ID,
+                &context,
+            );
+            let result = match &site_scope {
+                Some(scope) => site::scope(scope.clone(), fetch).await,
+                None => fetch.await,
+            };
+            match result {
+                Ok(result) => {
+                    let chunk: String = result.items.iter().map(|i| renderer.item(i)).collect();
+                    yield Ok(chunk);
+                    if !result.has_next {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    // Headers are sent; aborting the body marks the download
+                    // as incomplete.
+                    tracing::error!(error = %e, query_id = %query_id, page, "gather export failed");
+                    yield Err(std::io::Error::other(e.to_string()));
+                    return;
+                }
+            }
+        }
+        yield Ok(renderer.end());
+    };
+
+    (headers, Body::from_stream(stream)).into_response()
 }
 
 /// Execute a gather query and render it as an HTML page.
@@ -292,6 +589,9 @@ pub async fn execute_query_only(
         current_user_id: None,
         url_args: params.filters.clone(),
         language,
+        archive: params.archive,
+        grants_user: Some(Uuid::nil()),
+        own_unpublished_types: Vec::new(),
     };
 
     let exposed_filters = parse_filter_params(&params.filters);
@@ -322,6 +622,9 @@ pub async fn execute_and_render(
         current_user_id: user_id,
         url_args: params.filters.clone(),
         language,
+        archive: params.archive,
+        grants_user: Some(user_id.unwrap_or(Uuid::nil())),
+        own_unpublished_types: own_unpublished_types(state, user_id).await,
     };
 
     let gather_query = state.gather().get_query(query_id).ok_or_else(|| {
@@ -367,6 +670,34 @@ pub async fn execute_and_render(
         .map(|(k, v)| (k.clone(), v.clone()))
         .collect();
 
+    // Archive navigation (year/month facet counts) for archive-enabled listings
+    let archive_nav = match (
+        gather_query.display.archive.as_ref(),
+        gather_query.definition.item_type.as_deref(),
+    ) {
+        (Some(config), Some(item_type)) => {
+            let buckets = state
+                .gather()
+                .archive_counts(item_type, &[stage_id])
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, query_id, "failed to load archive counts");
+                    Vec::new()
+                });
+            Some(serde_json::json!({
+                "base_path": config.base_path,
+                "years": archive::group_by_year(&buckets, &config.base_path),
+                "current": query_context.archive.map(|p| serde_json::json!({
+                    "year": p.year,
+                    "month": p.month,
+                    "label": p.label(),
+                    "path": p.path(&config.base_path),
+                })),
+            }))
+        }
+        _ => None,
+    };
+
     // Render gather content (either via theme template or fallback HTML)
     let content_html = render_gather_with_theme(
         state,
@@ -375,6 +706,7 @@ pub async fn execute_and_render(
         &filter_values,
         base_path,
         &preload,
+        archive_nav.as_ref(),
     )
     .unwrap_or_else(|| {
         render_gather_content_html(&gather_query, &result, &filter_values, base_path, &preload)
@@ -393,16 +725,31 @@ pub async fn execute_and_render(
         );
     }
 
-    // Build breadcrumbs: Home > Query Label
-    let breadcrumbs = vec![
-        serde_json::json!({"path": "/", "title": "Home"}),
-        serde_json::json!({"path": null, "title": gather_query.label}),
-    ];
+    // Build breadcrumbs: Home > Query Label (> Archive Period)
+    let archive_period = query_context
+        .archive
+        .zip(gather_query.display.archive.as_ref());
+    let breadcrumbs = match archive_period {
+        Some((period, config)) => vec![
+            serde_json::json!({"path": "/", "title": "Home"}),
+            serde_json::json!({"path": config.base_path, "title": gather_query.label}),
+            serde_json::json!({"path": null, "title": period.label()}),
+        ],
+        None => vec![
+            serde_json::json!({"path": "/", "title": "Home"}),
+            serde_json::json!({"path": null, "title": gather_query.label}),
+        ],
+    };
     context.insert("breadcrumbs", &breadcrumbs);
 
+    let page_title = match archive_period {
+        Some((period, _)) => format!("{} — {}", gather_query.label, period.label()),
+        None => gather_query.label.clone(),
+    };
+
     let page_html = state
         .theme()
-        .render_page(base_path, &gather_query.label, &content_html, &mut context)
+        .render_page(base_path, &page_title, &content_html, &mut context)
         .unwrap_or_else(|_| render_gather_html(&gather_query, &result));
 
     Ok(Html(page_html))
@@ -415,6 +762,7 @@ fn render_gather_with_theme(
     filter_values: &HashMap<String, String>,
     base_path: &str,
     preload: &WidgetPreloadData,
+    archive_nav: Option<&serde_json::Value>,
 ) -> Option<String> {
     // Try to find a template for this query
     let suggestions = [
@@ -443,6 +791,11 @@ fn render_gather_with_theme(
     context.insert("exposed_filters", &exposed_filters);
     context.insert("filter_values", filter_values);
 
+    // Archive navigation (only for queries with `display.archive`)
+    if let Some(nav) = archive_nav {
+        context.insert("archive", nav);
+    }
+
     // Pager info
     if query.display.pager.enabled && result.total_pages > 1 {
         let page_url_prefix = build_page_url_prefix(base_path, filter_values);
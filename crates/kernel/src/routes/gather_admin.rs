@@ -370,6 +370,10 @@ async fn save_submit(
 
     match state.gather().register_query(query).await {
         Ok(()) => {
+            state
+                .cache()
+                .invalidate_tag(&crate::cache::page::gather_tag(&id))
+                .await;
             tracing::info!(query_id = %id, "gather query updated");
             Redirect::to("/admin/gather").into_response()
         }
@@ -437,6 +441,10 @@ async fn delete_query(
 
     match state.gather().delete_query(&id).await {
         Ok(true) => {
+            state
+                .cache()
+                .invalidate_tag(&crate::cache::page::gather_tag(&id))
+                .await;
             tracing::info!(query_id = %id, "gather query deleted");
             Redirect::to("/admin/gather").into_response()
         }
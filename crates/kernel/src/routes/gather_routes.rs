@@ -9,6 +9,11 @@
 //! - **Tag slug lookup:** path segment is resolved to a tag UUID via the
 //!   `category_tag.slug` column, then the UUID is passed as the query param.
 //!
+//! Queries with `display.archive` additionally get date-based archive routes
+//! (`{base}/{yyyy}` and `{base}/{yyyy}/{mm}`) that restrict the listing to
+//! items created in that period. Queries with `display.exports` and a
+//! canonical URL get `{canonical}.csv`, `.rss` and `.atom` export routes.
+//!
 //! This replaces the former `ritrovo_topics.rs` module, which hard-coded
 //! routes for `/topics/{slug}` and `/location/{country}[/{city}]`.
 
@@ -24,6 +29,7 @@ use axum::{
 };
 use tower_sessions::Session;
 
+use crate::gather::ArchivePeriod;
 use crate::gather::types::{GatherQuery, GatherRouteParam};
 use crate::middleware::language::ResolvedLanguage;
 use crate::models::Tag;
@@ -67,7 +73,7 @@ pub fn build_gather_route_router(queries: &[GatherQuery]) -> Router<AppState> {
             }
 
             // Guard against duplicate paths — axum panics on route conflicts.
-            if !registered_paths.insert(route.path.clone()) {
+            if !registered_paths.insert(route_shape(&route.path)) {
                 tracing::warn!(
                     query_id = %query.query_id,
                     path = %route.path,
@@ -109,11 +115,181 @@ pub fn build_gather_route_router(queries: &[GatherQuery]) -> Router<AppState> {
                 ),
             );
         }
+
+        router = register_archive_routes(router, query, &mut registered_paths);
+        router = register_export_routes(router, query, &mut registered_paths);
+    }
+
+    router
+}
+
+/// Register `{base}/{year}` and `{base}/{year}/{month}` routes for a query
+/// with `display.archive` configured.
+///
+/// Archives require `definition.item_type` so facet counts can be scoped;
+/// queries without one are skipped with a warning.
+fn register_archive_routes(
+    mut router: Router<AppState>,
+    query: &GatherQuery,
+    registered_paths: &mut HashSet<String>,
+) -> Router<AppState> {
+    let Some(archive) = &query.display.archive else {
+        return router;
+    };
+
+    let base = archive.base_path.trim_end_matches('/');
+    if !archive.base_path.starts_with('/') || base.is_empty() || base.contains('{') {
+        tracing::warn!(
+            query_id = %query.query_id,
+            base_path = %archive.base_path,
+            "skipping archive routes with invalid base path"
+        );
+        return router;
+    }
+    if query.definition.item_type.is_none() {
+        tracing::warn!(
+            query_id = %query.query_id,
+            "skipping archive routes: query has no item_type"
+        );
+        return router;
+    }
+
+    for path in [
+        format!("{base}/{{year}}"),
+        format!("{base}/{{year}}/{{month}}"),
+    ] {
+        // Any other single-segment capture under the same base would conflict.
+        if !registered_paths.insert(route_shape(&path)) {
+            tracing::warn!(
+                query_id = %query.query_id,
+                path = %path,
+                "skipping archive route that conflicts with an existing gather route"
+            );
+            continue;
+        }
+
+        tracing::info!(
+            query_id = %query.query_id,
+            path = %path,
+            "registering gather archive route"
+        );
+
+        let query_id: Arc<str> = Arc::from(query.query_id.as_str());
+        router = router.route(
+            &path,
+            get(
+                move |state: State<AppState>,
+                      session: Session,
+                      Extension(resolved_lang): Extension<ResolvedLanguage>,
+                      uri: OriginalUri,
+                      path: Path<HashMap<String, String>>,
+                      query_params: Query<HashMap<String, String>>| {
+                    let query_id = query_id.clone();
+                    async move {
+                        handle_archive_route(
+                            state,
+                            session,
+                            resolved_lang,
+                            uri,
+                            path,
+                            query_params,
+                            &query_id,
+                        )
+                        .await
+                    }
+                },
+            ),
+        );
+    }
+
+    router
+}
+
+/// Register `{canonical_url}.csv`, `.rss` and `.atom` routes for the
+/// exports a query enables (see [`crate::gather::export`]).
+///
+/// Queries without a canonical URL are only exported under
+/// `/gather/{query_id}.{ext}`.
+fn register_export_routes(
+    mut router: Router<AppState>,
+    query: &GatherQuery,
+    registered_paths: &mut HashSet<String>,
+) -> Router<AppState> {
+    let (Some(exports), Some(canonical)) = (&query.display.exports, &query.display.canonical_url)
+    else {
+        return router;
+    };
+    let base = canonical.trim_end_matches('/');
+    if !canonical.starts_with('/') || base.is_empty() || base.contains('{') {
+        tracing::warn!(
+            query_id = %query.query_id,
+            canonical_url = %canonical,
+            "skipping export routes with invalid canonical URL"
+        );
+        return router;
+    }
+
+    for format in exports.formats() {
+        let path = format!("{base}.{}", format.extension());
+        if !registered_paths.insert(route_shape(&path)) {
+            tracing::warn!(
+                query_id = %query.query_id,
+                path = %path,
+                "skipping export route that conflicts with an existing gather route"
+            );
+            continue;
+        }
+
+        tracing::info!(
+            query_id = %query.query_id,
+            path = %path,
+            "registering gather export route"
+        );
+
+        let query_id: Arc<str> = Arc::from(query.query_id.as_str());
+        let base_path: Arc<str> = Arc::from(base);
+        router = router.route(
+            &path,
+            get(
+                move |State(state): State<AppState>,
+                      session: Session,
+                      Extension(resolved_lang): Extension<ResolvedLanguage>,
+                      Query(params): Query<ExecuteParams>| {
+                    let query_id = query_id.clone();
+                    let base_path = base_path.clone();
+                    async move {
+                        let language = (resolved_lang.0 != state.default_language())
+                            .then_some(resolved_lang.0);
+                        super::gather::render_export(
+                            &state, &session, &query_id, format, params, &base_path, language,
+                        )
+                        .await
+                    }
+                },
+            ),
+        );
     }
 
     router
 }
 
+/// Normalize a route path so that captures with different names compare
+/// equal (`/blog/{year}` and `/blog/{slug}` both become `/blog/{}`).
+///
+/// axum rejects such overlapping routes at registration time by panicking.
+fn route_shape(path: &str) -> String {
+    path.split('/')
+        .map(|seg| {
+            if seg.starts_with('{') && seg.ends_with('}') {
+                "{}"
+            } else {
+                seg
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 /// Handle a gather route alias request.
 ///
 /// Extracts path segments, resolves tag slugs if needed, and renders the
@@ -159,6 +335,67 @@ async fn handle_gather_route(
         }
     }
 
+    render_gather_route(
+        &state,
+        &session,
+        resolved_lang,
+        uri.path(),
+        &config.query_id,
+        extra_params,
+        resolved_params,
+        None,
+    )
+    .await
+}
+
+/// Handle a date-based archive request (`{base}/{yyyy}` or `{base}/{yyyy}/{mm}`).
+///
+/// Returns 404 for malformed or out-of-range periods so each period has a
+/// single canonical URL.
+async fn handle_archive_route(
+    State(state): State<AppState>,
+    session: Session,
+    resolved_lang: ResolvedLanguage,
+    OriginalUri(uri): OriginalUri,
+    Path(segments): Path<HashMap<String, String>>,
+    Query(extra_params): Query<HashMap<String, String>>,
+    query_id: &str,
+) -> Response {
+    let Some(year) = segments.get("year") else {
+        return render_not_found();
+    };
+    let Some(period) = ArchivePeriod::parse(year, segments.get("month").map(String::as_str)) else {
+        return render_not_found();
+    };
+
+    render_gather_route(
+        &state,
+        &session,
+        resolved_lang,
+        uri.path(),
+        query_id,
+        extra_params,
+        HashMap::new(),
+        Some(period),
+    )
+    .await
+}
+
+/// Execute a gather query for a route alias and render it as HTML or JSON.
+// Both route handlers pass their request extractors straight through and
+// differ only in the params and archive period they resolve; a struct would
+// just rename these arguments at every call site.
+#[allow(clippy::too_many_arguments)]
+async fn render_gather_route(
+    state: &AppState,
+    session: &Session,
+    resolved_lang: ResolvedLanguage,
+    path: &str,
+    query_id: &str,
+    extra_params: HashMap<String, String>,
+    resolved_params: HashMap<String, String>,
+    archive: Option<ArchivePeriod>,
+) -> Response {
     // Content negotiation: check format param before consuming extra_params
     let wants_json = extra_params.get("format").is_some_and(|f| f == "json");
 
@@ -180,11 +417,14 @@ async fn handle_gather_route(
     all_filters.remove("stage");
     let stage = LIVE_STAGE_ID.to_string();
 
-    let params = ExecuteParams::new(page, stage, all_filters);
+    let mut params = ExecuteParams::new(page, stage, all_filters);
+    if let Some(period) = archive {
+        params = params.with_archive(period);
+    }
 
     // Use the request path (without query string) as the base path so that
     // pager links and form actions stay on the pretty URL.
-    let base_path = uri.path().to_string();
+    let base_path = path.to_string();
 
     // Resolve language: skip the translation JOIN for the default language.
     let language = if resolved_lang.0 != state.default_language() {
@@ -195,7 +435,7 @@ async fn handle_gather_route(
 
     if wants_json {
         // JSON response: execute query and return structured data
-        match super::gather::execute_query_only(&state, &config.query_id, params, language).await {
+        match super::gather::execute_query_only(state, query_id, params, language).await {
             Ok(result) => Json(serde_json::json!({
                 "items": result.items,
                 "pager": {
@@ -205,8 +445,9 @@ async fn handle_gather_route(
                     "per_page": result.per_page,
                 },
                 "query": {
-                    "name": config.query_id,
+                    "name": query_id,
                 },
+                "archive": archive,
             }))
             .into_response(),
             Err(e) => {
@@ -216,12 +457,7 @@ async fn handle_gather_route(
         }
     } else {
         match super::gather::execute_and_render(
-            &state,
-            &session,
-            &config.query_id,
-            params,
-            &base_path,
-            language,
+            state, session, query_id, params, &base_path, language,
         )
         .await
         {
@@ -236,3 +472,20 @@ async fn handle_gather_route(
         }
     } // end else (HTML branch)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_shape_ignores_capture_names() {
+        assert_eq!(route_shape("/blog/{year}"), "/blog/{}");
+        assert_eq!(route_shape("/blog/{slug}"), route_shape("/blog/{year}"));
+        assert_ne!(
+            route_shape("/blog/{year}"),
+            route_shape("/blog/{year}/{month}")
+        );
+        assert_eq!(route_shape("/topics"), "/topics");
+    }
+}
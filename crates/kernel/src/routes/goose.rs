@@ -0,0 +1,191 @@
+//! Goose load test comparison API (gated on the `goose` plugin).
+//!
+//! - `GET /api/goose/compare?runs={baseline},{candidate}` — compute a report
+//! - `POST /api/goose/compare?comparison={id}` — compute a report and store
+//!   it on a `goose_comparison` item; `runs` defaults to the item's
+//!   `field_run_ids`
+//!
+//! Both accept `threshold` (percent) to override the configured
+//! regression threshold.
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::HeaderMap,
+    routing::get,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::UpdateItem;
+use crate::services::goose_compare::{
+    self, ComparisonReport, REPORT_FIELD, RUN_IDS_FIELD, parse_run_ids,
+};
+use crate::state::AppState;
+use crate::tap::UserContext;
+
+use super::helpers::require_csrf_header;
+use super::item::get_user_context;
+
+/// Content type holding test runs.
+const TEST_RUN_TYPE: &str = "goose_test_run";
+
+/// Content type holding stored comparisons.
+const COMPARISON_TYPE: &str = "goose_comparison";
+
+/// Query parameters for the compare endpoint.
+#[derive(Debug, Deserialize)]
+struct CompareQuery {
+    /// Baseline and candidate run IDs, comma-separated.
+    runs: Option<String>,
+    /// Regression threshold in percent.
+    threshold: Option<f64>,
+    /// Comparison item to store the report on (POST only).
+    comparison: Option<Uuid>,
+}
+
+/// Create the Goose router.
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/api/goose/compare",
+        get(compare_runs).post(store_comparison),
+    )
+}
+
+/// Compare two test runs.
+///
+/// GET /api/goose/compare?runs={baseline},{candidate}&threshold={pct}
+async fn compare_runs(
+    State(state): State<AppState>,
+    session: Session,
+    Query(query): Query<CompareQuery>,
+) -> Result<Json<ComparisonReport>, AppError> {
+    let user = get_user_context(&session, &state).await;
+    let runs = query
+        .runs
+        .as_deref()
+        .ok_or_else(|| AppError::bad_request("runs is required"))?;
+
+    let report = build_report(&state, &user, runs, query.threshold).await?;
+    Ok(Json(report))
+}
+
+/// Compare two test runs and store the report on a comparison item.
+///
+/// POST /api/goose/compare?comparison={id}&runs={baseline},{candidate}
+async fn store_comparison(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Query(query): Query<CompareQuery>,
+) -> Result<Json<ComparisonReport>, AppError> {
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    let user = get_user_context(&session, &state).await;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Authentication required"));
+    }
+
+    let id = query
+        .comparison
+        .ok_or_else(|| AppError::bad_request("comparison is required"))?;
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load comparison"))?
+        .filter(|item| item.item_type == COMPARISON_TYPE)
+        .ok_or_else(|| AppError::not_found_id("comparison", id))?;
+
+    let runs = match query.runs {
+        Some(runs) => runs,
+        None => item
+            .fields
+            .get(RUN_IDS_FIELD)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+    };
+    let report = build_report(&state, &user, &runs, query.threshold).await?;
+
+    let mut fields = item.fields.clone();
+    if let Some(obj) = fields.as_object_mut() {
+        let stored = serde_json::to_string(&report)
+            .map_err(|e| AppError::internal_ctx(e, "serialize comparison report"))?;
+        obj.insert(REPORT_FIELD.to_string(), serde_json::Value::String(stored));
+    }
+    let input = UpdateItem {
+        title: None,
+        status: None,
+        promote: None,
+        sticky: None,
+        fields: Some(fields),
+        log: Some("Computed run comparison".to_string()),
+        status_meta: None,
+    };
+    match state.items().update(id, input, &user).await {
+        Ok(Some(_)) => Ok(Json(report)),
+        Ok(None) => Err(AppError::not_found_id("comparison", id)),
+        Err(e) if e.to_string().contains("access denied") => {
+            Err(AppError::forbidden("Access denied"))
+        }
+        Err(e) => Err(AppError::internal_ctx(e, "store comparison report")),
+    }
+}
+
+/// Load both runs' endpoint results and compare them.
+///
+/// The caller must be able to view both test runs.
+async fn build_report(
+    state: &AppState,
+    user: &UserContext,
+    runs: &str,
+    threshold: Option<f64>,
+) -> Result<ComparisonReport, AppError> {
+    let ids = parse_run_ids(runs).map_err(|e| AppError::bad_request(e.to_string()))?;
+    let [baseline, candidate] = ids[..] else {
+        return Err(AppError::bad_request(
+            "runs must list exactly two test runs: baseline,candidate",
+        ));
+    };
+
+    for run_id in [baseline, candidate] {
+        let run = state
+            .items()
+            .load(run_id)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load test run"))?
+            .filter(|item| item.item_type == TEST_RUN_TYPE)
+            .ok_or_else(|| AppError::not_found_id("test run", run_id))?;
+        let can_view = state
+            .items()
+            .check_access(&run, "view", user)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "check test run access"))?;
+        if !can_view {
+            return Err(AppError::not_found_id("test run", run_id));
+        }
+    }
+
+    let threshold = match threshold {
+        Some(t) if t.is_finite() && t >= 0.0 => t,
+        Some(_) => return Err(AppError::bad_request("threshold must be a positive number")),
+        None => goose_compare::configured_threshold(state.db())
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load regression threshold"))?,
+    };
+
+    let before = goose_compare::endpoint_results(state.db(), baseline)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load baseline results"))?;
+    let after = goose_compare::endpoint_results(state.db(), candidate)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load candidate results"))?;
+
+    Ok(goose_compare::compare(
+        baseline, &before, candidate, &after, threshold,
+    ))
+}
@@ -1,7 +1,8 @@
 //! Health check endpoint.
 //!
 //! Returns 200 OK if both PostgreSQL and Redis are reachable,
-//! 503 Service Unavailable otherwise.
+//! 503 Service Unavailable otherwise. Read replicas are reported per pool
+//! but don't fail the check, since reads fall back to the primary.
 
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -19,6 +20,8 @@ struct HealthResponse {
     redis: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pool: Option<PoolHealth>,
+    /// Primary and read replica health.
+    pools: Vec<crate::db::PoolStatus>,
     #[serde(skip_serializing_if = "Option::is_none")]
     services: Option<crate::state::HealthReport>,
 }
@@ -67,6 +70,8 @@ async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Health
         utilization_pct,
     });
 
+    let pools = state.db_pools().status(postgres);
+
     // Build the full service health report (includes circuit breaker states, etc.)
     let services = Some(state.health_report().await);
 
@@ -77,6 +82,7 @@ async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Health
             postgres,
             redis,
             pool,
+            pools,
             services,
         }),
     )
@@ -1,15 +1,25 @@
 //! Shared route helpers for page rendering.
 
+use std::collections::HashSet;
+
 use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Redirect, Response};
 use tower_sessions::Session;
 use uuid::Uuid;
 
 use serde::{Deserialize, Serialize};
+use trovato_sdk::types::{FieldFormatter, FormatterType};
 
+use crate::batch::CreateBatch;
+use crate::config_storage::ConfigEntity;
+use crate::menu::{self, MAIN_MENU, MenuTree};
+use crate::models::password_policy::PasswordPolicyViolated;
 use crate::models::stage::LIVE_STAGE_ID;
+use crate::models::user::ANONYMOUS_USER_ID;
 use crate::models::{SiteConfig, User};
-use crate::routes::auth::SESSION_USER_ID;
+use crate::routes::auth::{SESSION_ACTIVE_STAGE, SESSION_USER_ID};
+use crate::services::cascade::{self, CascadeAction};
+use crate::services::tile::{REGIONS, RegionContext};
 use crate::state::AppState;
 use crate::tap::UserContext;
 
@@ -151,7 +161,7 @@ pub async fn require_permission_json(
 
 /// Inject site-wide context variables into a Tera context.
 ///
-/// Adds: `site_name`, `site_slogan`, `menus`, `user_authenticated`, `current_path`,
+/// Adds: `site_name`, `site_slogan`, `menus`, `menu_tree`, `user_authenticated`, `current_path`,
 /// `header_tiles`, `navigation_tiles`, `sidebar_tiles`, `footer_tiles`
 ///
 /// The `path` parameter is the current request path, used for sidebar tile
@@ -178,13 +188,19 @@ pub async fn inject_site_context(
     context.insert("site_name", &site_name);
     context.insert("site_slogan", &site_slogan);
 
-    // Language context variables (defaults — route handlers may override active_language)
+    // Language context variables (defaults — route handlers may set active_language
+    // before or after this call)
     context.insert("available_languages", state.known_languages());
     context.insert("default_language", state.default_language());
-    context.insert("active_language", state.default_language());
+    let active_language = context
+        .get("active_language")
+        .and_then(|v| v.as_str())
+        .unwrap_or(state.default_language())
+        .to_string();
+    context.insert("active_language", &active_language);
     context.insert(
         "text_direction",
-        crate::middleware::language::text_direction_for_language(state.default_language()),
+        crate::middleware::language::text_direction_for_language(&active_language),
     );
 
     // Load main navigation menu links from database (not plugin registry)
@@ -224,22 +240,45 @@ pub async fn inject_site_context(
 
     let mut user_roles = vec!["anonymous user".to_string()];
     let mut is_admin = false;
+    let mut user = None;
     if let Some(id) = user_id {
         user_roles.push("authenticated user".to_string());
-        if let Ok(Some(user)) = state.users().find_by_id(id).await
-            && user.is_admin
-        {
+        user = state.users().find_by_id(id).await.ok().flatten();
+        if user.as_ref().is_some_and(|u| u.is_admin) {
             user_roles.push("administrator".to_string());
             is_admin = true;
         }
     }
     context.insert("user_is_admin", &is_admin);
 
-    // Load tiles for all regions filtered by request path and user roles
-    for region in &["header", "navigation", "sidebar", "footer"] {
+    // Plugin menu tree with active trail, filtered by the user's permissions
+    if let Some(tree) = user_menu_tree(state, user.as_ref(), MAIN_MENU, path).await {
+        context.insert("menu_tree", &tree);
+    }
+
+    // Load tiles for all regions filtered by request path, user roles and the
+    // content type of the item on an item page
+    let item_type = match path.strip_prefix("/item/").and_then(|id| id.parse().ok()) {
+        Some(id) => state
+            .items()
+            .load(id)
+            .await
+            .ok()
+            .flatten()
+            .map(|item| item.item_type),
+        None => None,
+    };
+    let page = RegionContext {
+        path: path.to_string(),
+        language: active_language,
+        item_type,
+        user_roles,
+        user: super::item::get_user_context(session, state).await,
+    };
+    for (region, _) in REGIONS {
         let region_html = state
             .tiles()
-            .render_region(region, LIVE_STAGE_ID, path, &user_roles)
+            .render_region(region, LIVE_STAGE_ID, &page)
             .await
             .unwrap_or_default();
         context.insert(format!("{region}_tiles"), &region_html);
@@ -248,6 +287,55 @@ pub async fn inject_site_context(
     context.insert("current_path", &path);
 }
 
+/// Build the plugin menu tree `name` for a user (`None` for anonymous).
+///
+/// Each distinct permission required by a registered menu is checked once
+/// through the cached permission service. Returns `None` if the menu does
+/// not exist or the user may not access its root.
+pub async fn user_menu_tree(
+    state: &AppState,
+    user: Option<&User>,
+    name: &str,
+    current_path: &str,
+) -> Option<MenuTree> {
+    let registry = state.menu_registry();
+
+    let anonymous;
+    let user = match user {
+        Some(user) => Some(user),
+        None => {
+            anonymous = state
+                .users()
+                .find_by_id(ANONYMOUS_USER_ID)
+                .await
+                .ok()
+                .flatten();
+            anonymous.as_ref()
+        }
+    };
+
+    let mut allowed = HashSet::new();
+    if let Some(user) = user {
+        let required: HashSet<&str> = registry
+            .all()
+            .map(|m| m.permission.as_str())
+            .filter(|p| !p.is_empty())
+            .collect();
+        for permission in required {
+            if state
+                .permissions()
+                .user_has_permission(user, permission)
+                .await
+                .unwrap_or(false)
+            {
+                allowed.insert(permission);
+            }
+        }
+    }
+
+    menu::tree(registry, name, current_path, |p| allowed.contains(p))
+}
+
 /// Render an admin template with common context (enabled_plugins).
 ///
 /// This is the shared implementation used by all admin route modules
@@ -258,7 +346,11 @@ pub async fn render_admin_template(
     template: &str,
     mut context: tera::Context,
 ) -> Response {
-    let mut enabled: Vec<String> = state.enabled_plugins().into_iter().collect();
+    let mut enabled: Vec<String> = state
+        .enabled_plugins()
+        .into_iter()
+        .filter(|p| crate::services::site::plugin_enabled(p))
+        .collect();
     enabled.sort();
     context.insert("enabled_plugins", &enabled);
     // Ensure "errors" is always available for form templates that use form::errors().
@@ -485,6 +577,29 @@ pub fn validate_password(password: &str) -> Result<(), &'static str> {
     Ok(())
 }
 
+/// Check a new password against the password policies for `user` (`None`
+/// when registering), appending a message per violated rule to `errors`.
+///
+/// For HTML forms; JSON handlers propagate the error so the violations are
+/// returned as structured field errors.
+pub async fn check_password_policy(
+    state: &AppState,
+    password: &str,
+    user: Option<&User>,
+    errors: &mut Vec<String>,
+) {
+    let Err(e) = state.password_policy().check(password, user).await else {
+        return;
+    };
+    match e.downcast_ref::<PasswordPolicyViolated>() {
+        Some(violated) => errors.extend(violated.messages()),
+        None => {
+            tracing::error!(error = %e, "failed to check password policy");
+            errors.push("Unable to check the password right now. Please try again.".to_string());
+        }
+    }
+}
+
 /// Validate a username meets format requirements.
 ///
 /// Checks: non-empty, 2–60 characters, alphanumeric + underscores + hyphens + periods only.
@@ -534,6 +649,107 @@ pub fn admin_user_context(user: &User) -> UserContext {
     UserContext::authenticated(user.id, vec!["administer site".to_string()])
 }
 
+/// The session's active stage, or [`LIVE_STAGE_ID`] when none is set.
+///
+/// An unparseable stage ID is treated as live.
+pub async fn active_stage(session: &Session) -> Uuid {
+    session
+        .get::<Option<String>>(SESSION_ACTIVE_STAGE)
+        .await
+        .ok()
+        .flatten()
+        .flatten()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(LIVE_STAGE_ID)
+}
+
+/// Messages from plugins refusing any of `entities` (`tap_config_validate`).
+///
+/// Admin forms that save config outside [`crate::config_storage::ConfigStorage`]
+/// call this first, so plugin violations are listed with the form's own
+/// errors instead of being saved.
+pub async fn config_violation_messages(state: &AppState, entities: &[ConfigEntity]) -> Vec<String> {
+    let mut messages = Vec::new();
+    for entity in entities {
+        match state.config_storage().validate(entity).await {
+            Ok(violations) => messages.extend(violations.into_iter().map(|v| v.message)),
+            Err(e) => tracing::warn!(entity = %entity, error = %e, "failed to validate config"),
+        }
+    }
+    messages
+}
+
+/// Start the reference cascade for items that were unpublished or deleted.
+///
+/// `sources` are `(item id, content type)` pairs. Plans the cascade and, if
+/// any item is affected, runs it in the background as a batch operation
+/// acting as `user`. Returns the batch ID and the number of planned changes.
+/// Failures are logged; the editor's own change has already been saved.
+pub async fn start_cascade(
+    state: &AppState,
+    sources: &[(Uuid, String)],
+    trigger: CascadeAction,
+    user: &UserContext,
+) -> Option<(Uuid, usize)> {
+    let types = state.content_types().list_all().await;
+    let plan = match cascade::plan(state.db(), &types, sources, trigger).await {
+        Ok(plan) => plan,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to plan reference cascade");
+            return None;
+        }
+    };
+    if plan.steps.is_empty() {
+        return None;
+    }
+
+    let source_ids: Vec<Uuid> = sources.iter().map(|(id, _)| *id).collect();
+    let operation = match state
+        .batch()
+        .create(CreateBatch {
+            operation_type: cascade::CASCADE_OPERATION.to_string(),
+            params: serde_json::json!({
+                "trigger": trigger,
+                "sources": source_ids,
+                "unpublish": plan.unpublish_count(),
+                "delete": plan.delete_count(),
+                "truncated": plan.truncated,
+            }),
+        })
+        .await
+    {
+        Ok(operation) => operation,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to create cascade batch");
+            return None;
+        }
+    };
+
+    let batch_id = operation.id;
+    let count = plan.steps.len();
+    let items = state.items().clone();
+    let batch = state.batch().clone();
+    let audit = state.audit().cloned();
+    let user = user.clone();
+    tokio::spawn(async move {
+        if let Err(e) = cascade::execute(
+            &items,
+            &batch,
+            audit.as_deref(),
+            batch_id,
+            &plan.steps,
+            &user,
+        )
+        .await
+            && let Err(e) = batch.fail(batch_id, &e.to_string()).await
+        {
+            tracing::error!(error = %e, batch_id = %batch_id, "failed to record cascade failure");
+        }
+    });
+
+    Some((batch_id, count))
+}
+
 pub fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -615,6 +831,49 @@ pub fn build_hreflang_links(
     links
 }
 
+/// Build hreflang links for an item's language versions.
+///
+/// Lists the item's own language and each site language it has a
+/// translation in, at the item's canonical alias (or `/item/{id}`). Empty
+/// when the item has no translations.
+pub async fn item_hreflang_links(
+    state: &AppState,
+    item: &crate::models::Item,
+) -> Vec<serde_json::Value> {
+    let translations = match state.items().list_translations(item.id).await {
+        Ok(translations) => translations,
+        Err(e) => {
+            // The translation table only exists with the content
+            // translation plugin.
+            tracing::debug!(item_id = %item.id, error = %e, "no translations for hreflang");
+            return Vec::new();
+        }
+    };
+    let known = state.known_languages();
+    let mut languages = vec![item.language.clone()];
+    for (language, _) in translations {
+        if known.contains(&language) && !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    if languages.len() < 2 {
+        return Vec::new();
+    }
+
+    let source = format!("/item/{}", item.id);
+    let path = crate::models::UrlAlias::get_canonical_alias_with_context(
+        state.db(),
+        &source,
+        item.stage_id,
+        state.default_language(),
+    )
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or(source);
+    build_hreflang_links(&path, &languages, state.default_language())
+}
+
 /// Apply a translation overlay to an item's title and fields.
 ///
 /// If a translation exists for `language`, the item's title is replaced
@@ -651,6 +910,83 @@ pub async fn apply_translation_overlay(
     }
 }
 
+/// Format a field value for display, including formatters that need
+/// services.
+///
+/// `responsive_image` renders through [`render_responsive_image`]; other
+/// formatters use [`display::format_value`](crate::content::display::format_value).
+pub async fn format_display_value(
+    state: &AppState,
+    value: &serde_json::Value,
+    formatter: &FieldFormatter,
+) -> Option<String> {
+    match formatter.formatter {
+        FormatterType::ResponsiveImage => render_responsive_image(state, value, formatter).await,
+        _ => crate::content::display::format_value(value, formatter),
+    }
+}
+
+/// Render an image file field as a responsive `<picture>`.
+///
+/// The field value is the file's UUID; the formatter's `responsive_style`
+/// setting names the responsive image style and `alt` the alternative
+/// text. Returns `None` when image styles are disabled, the style or file
+/// is missing, or the file is not an image.
+pub async fn render_responsive_image(
+    state: &AppState,
+    value: &serde_json::Value,
+    formatter: &FieldFormatter,
+) -> Option<String> {
+    let image_styles = state.image_styles()?;
+    let style_name = formatter.settings.get("responsive_style")?.as_str()?;
+    let file_id = value.as_str()?.parse::<Uuid>().ok()?;
+
+    let file = match state.files().get(file_id).await {
+        Ok(file) => file?,
+        Err(e) => {
+            tracing::warn!(file_id = %file_id, error = %e, "failed to load image file");
+            return None;
+        }
+    };
+    if !file.filemime.starts_with("image/") {
+        return None;
+    }
+
+    let style = match image_styles.load_responsive_style(style_name).await {
+        Ok(Some(style)) => style,
+        Ok(None) => {
+            tracing::warn!(style = %style_name, "responsive image style not found");
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!(style = %style_name, error = %e, "failed to load responsive image style");
+            return None;
+        }
+    };
+
+    // Derivative URLs address the file by its path within storage.
+    let path = file
+        .uri
+        .split_once("://")
+        .map_or(file.uri.as_str(), |(_, p)| p);
+    let alt = formatter
+        .settings
+        .get("alt")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let rendered = match image_styles.responsive_picture(&style, path, alt).await {
+        Ok(picture) => state.theme().render_responsive_image(&picture),
+        Err(e) => Err(e),
+    };
+    match rendered {
+        Ok(html) => Some(html),
+        Err(e) => {
+            tracing::warn!(style = %style_name, error = %e, "failed to render responsive image");
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -872,6 +1208,8 @@ mod tests {
             consent_date: None,
             consent_version: None,
             data_retention_days: None,
+            password_changed: chrono::Utc::now(),
+            fields: serde_json::json!({}),
         };
 
         let ctx = admin_user_context(&user);
@@ -1,16 +1,17 @@
 //! Image style routes.
 //!
-//! On-demand image derivative generation route.
+//! On-demand image derivative generation route, with WebP/AVIF variants.
 
 use axum::{
     Router,
     body::Body,
     extract::{Path, State},
-    http::{StatusCode, header},
-    response::IntoResponse,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
     routing::get,
 };
 
+use crate::services::image_style::{VariantFormat, split_variant_suffix};
 use crate::state::AppState;
 
 /// Create the image style routes.
@@ -47,9 +48,14 @@ fn validate_image_path(path: &str) -> bool {
 }
 
 /// GET /files/styles/{style_name}/{path} — serve or generate image derivative.
+///
+/// A `.webp` or `.avif` suffix on the original's name (`photo.jpg.webp`)
+/// requests that variant. Otherwise the variant is negotiated from the
+/// `Accept` header, unless the style fixes its output format.
 async fn serve_derivative(
     State(state): State<AppState>,
     Path((style_name, file_path)): Path<(String, String)>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     if !validate_image_path(&file_path) || !validate_image_path(&style_name) {
         return (StatusCode::BAD_REQUEST, "Invalid path").into_response();
@@ -59,23 +65,42 @@ async fn serve_derivative(
         return (StatusCode::SERVICE_UNAVAILABLE, "Image styles not enabled").into_response();
     };
 
+    // Load style from DB — or synthesize a width-based auto-style (w400, w800, etc.)
+    let style = match image_service.resolve_style(&style_name).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, "Image style not found").into_response();
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load image style");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load style").into_response();
+        }
+    };
+
+    let (original_path, variant, negotiated) = match split_variant_suffix(&file_path) {
+        Some((original, format)) => (original, Some(format), false),
+        None if style.has_output_format() => (file_path.as_str(), None, false),
+        None => {
+            let accepted = headers
+                .get(header::ACCEPT)
+                .and_then(|v| v.to_str().ok())
+                .and_then(VariantFormat::negotiate);
+            (file_path.as_str(), accepted, true)
+        }
+    };
+    let (cache_key, content_type) = match variant {
+        Some(format) => (
+            format!("{original_path}.{}", format.extension()),
+            format.content_type(),
+        ),
+        None => (original_path.to_string(), style.content_type()),
+    };
+
     // Try reading from disk cache directly (avoids TOCTOU race with separate exists + read)
-    let cache_path = image_service.cache_path(&style_name, &file_path);
+    let cache_path = image_service.cache_path(&style_name, &cache_key);
     match tokio::fs::read(&cache_path).await {
         Ok(data) => {
-            let content_type = guess_content_type(&file_path);
-            return (
-                StatusCode::OK,
-                [
-                    (header::CONTENT_TYPE, content_type),
-                    (
-                        header::CACHE_CONTROL,
-                        "public, max-age=31536000".to_string(),
-                    ),
-                ],
-                Body::from(data),
-            )
-                .into_response();
+            return derivative_response(data, content_type, negotiated);
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             // Not cached yet, generate below
@@ -86,32 +111,16 @@ async fn serve_derivative(
         }
     }
 
-    // Load style from DB — or synthesize a width-based auto-style (w400, w800, etc.)
-    let style = match image_service.load_style(&style_name).await {
-        Ok(Some(s)) => s,
-        Ok(None) => {
-            // Auto-generate width styles: w{N} scales to width N preserving aspect ratio
-            if let Some(auto) = synthesize_width_style(&style_name) {
-                auto
-            } else {
-                return (StatusCode::NOT_FOUND, "Image style not found").into_response();
-            }
-        }
-        Err(e) => {
-            tracing::warn!(error = %e, "failed to load image style");
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load style").into_response();
-        }
-    };
-
     // Load original file from FileStorage
-    let original = state.files().load_file_data(&file_path).await;
+    let uri = format!("{}://{original_path}", state.files().storage().scheme());
+    let original = state.files().load_file_data(&uri).await;
     let original = match original {
         Ok(Some(data)) => data,
         Ok(None) => {
             return (StatusCode::NOT_FOUND, "Original file not found").into_response();
         }
         Err(e) => {
-            tracing::warn!(error = %e, path = %file_path, "failed to load original file");
+            tracing::warn!(error = %e, path = %original_path, "failed to load original file");
             return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load file").into_response();
         }
     };
@@ -129,11 +138,10 @@ async fn serve_derivative(
     // the Tokio runtime with CPU-intensive image decoding/encoding.
     let svc = image_service.clone();
     let sn = style_name.clone();
-    let fp = file_path.clone();
     let result = tokio::task::spawn_blocking(move || {
-        let derivative = svc.process_image(&original, &style)?;
+        let derivative = svc.process_image(&original, &style, variant)?;
         // Save to disk cache while still on the blocking thread
-        if let Err(e) = svc.save_derivative(&sn, &fp, &derivative) {
+        if let Err(e) = svc.save_derivative(&sn, &cache_key, &derivative) {
             tracing::warn!(error = %e, "failed to cache derivative");
         }
         Ok::<Vec<u8>, anyhow::Error>(derivative)
@@ -152,53 +160,29 @@ async fn serve_derivative(
         }
     };
 
-    let content_type = guess_content_type(&file_path);
-    (
+    derivative_response(derivative, content_type, negotiated)
+}
+
+/// Build a long-lived derivative response.
+///
+/// Negotiated responses vary by `Accept` so shared caches keep one copy
+/// per variant.
+fn derivative_response(data: Vec<u8>, content_type: &'static str, negotiated: bool) -> Response {
+    let mut response = (
         StatusCode::OK,
         [
             (header::CONTENT_TYPE, content_type),
-            (
-                header::CACHE_CONTROL,
-                "public, max-age=31536000".to_string(),
-            ),
+            (header::CACHE_CONTROL, "public, max-age=31536000"),
         ],
-        Body::from(derivative),
+        Body::from(data),
     )
-        .into_response()
-}
-
-/// Synthesize a width-based image style from a `w{N}` style name.
-///
-/// Returns `None` if the name doesn't match the `w{N}` pattern or the width
-/// is out of range (1..=4096).
-fn synthesize_width_style(style_name: &str) -> Option<crate::services::image_style::ImageStyle> {
-    let width_str = style_name.strip_prefix('w')?;
-    let width: u32 = width_str.parse().ok()?;
-    if width == 0 || width > 4096 {
-        return None;
-    }
-
-    Some(crate::services::image_style::ImageStyle {
-        id: uuid::Uuid::nil(),
-        name: style_name.to_string(),
-        label: format!("Width {width}"),
-        effects: serde_json::json!([{"type": "scale", "width": width}]),
-        created: 0,
-        changed: 0,
-    })
-}
-
-/// Guess content type from file extension.
-fn guess_content_type(path: &str) -> String {
-    match path.rsplit('.').next() {
-        Some("jpg" | "jpeg") => "image/jpeg",
-        Some("png") => "image/png",
-        Some("gif") => "image/gif",
-        Some("webp") => "image/webp",
-        Some("avif") => "image/avif",
-        _ => "application/octet-stream",
+        .into_response();
+    if negotiated {
+        response
+            .headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("Accept"));
     }
-    .to_string()
+    response
 }
 
 #[cfg(test)]
@@ -207,14 +191,6 @@ fn guess_content_type(path: &str) -> String {
 mod tests {
     use super::*;
 
-    #[test]
-    fn content_type_guessing() {
-        assert_eq!(guess_content_type("photo.jpg"), "image/jpeg");
-        assert_eq!(guess_content_type("photo.png"), "image/png");
-        assert_eq!(guess_content_type("photo.webp"), "image/webp");
-        assert_eq!(guess_content_type("file.txt"), "application/octet-stream");
-    }
-
     #[test]
     fn path_validation_rejects_traversal() {
         assert!(!validate_image_path(""));
@@ -239,30 +215,12 @@ mod tests {
     }
 
     #[test]
-    fn synthesize_width_style_valid() {
-        let style = synthesize_width_style("w800").unwrap();
-        assert_eq!(style.name, "w800");
-        let effects: Vec<serde_json::Value> = serde_json::from_value(style.effects).unwrap();
-        assert_eq!(effects.len(), 1);
-        assert_eq!(effects[0]["type"], "scale");
-        assert_eq!(effects[0]["width"], 800);
-    }
+    fn negotiated_derivatives_vary_by_accept() {
+        let response = derivative_response(vec![1], "image/avif", true);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/avif");
+        assert_eq!(response.headers()[header::VARY], "Accept");
 
-    #[test]
-    fn synthesize_width_style_rejects_invalid() {
-        assert!(synthesize_width_style("thumbnail").is_none());
-        assert!(synthesize_width_style("w0").is_none());
-        assert!(synthesize_width_style("w9999").is_none());
-        assert!(synthesize_width_style("wabc").is_none());
-    }
-
-    #[test]
-    fn content_type_avif() {
-        assert_eq!(guess_content_type("photo.avif"), "image/avif");
-    }
-
-    #[test]
-    fn content_type_webp() {
-        assert_eq!(guess_content_type("photo.webp"), "image/webp");
+        let response = derivative_response(vec![1], "image/jpeg", false);
+        assert!(response.headers().get(header::VARY).is_none());
     }
 }
@@ -5,24 +5,34 @@
 use axum::{
     Extension, Form, Json, Router,
     extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, StatusCode, header},
     response::{Html, IntoResponse, Redirect, Response},
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
+use trovato_sdk::types::ViewMode;
 use uuid::Uuid;
 
-use crate::content::{FilterPipeline, FormBuilder};
+use crate::batch::CreateBatch;
+use crate::cache::conditional::Validators;
+use crate::content::diff::{DiffInput, DiffSummary, diff_items};
+use crate::content::display;
+use crate::content::{CloneOptions, FilterPipeline, FormBuilder, ItemPatch};
 use crate::error::AppError;
 use crate::form::csrf::generate_csrf_token;
+use crate::middleware::ClientIp;
 use crate::middleware::language::ResolvedLanguage;
-use crate::models::{CreateItem, UpdateItem, UrlAlias};
+use crate::models::{CreateItem, Stage, StatusChangeMeta, UpdateItem, UrlAlias};
+use crate::services::cascade::CascadeAction;
+use crate::services::item_bulk::{self, BulkOperation, BulkRequest, BulkServices};
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::services::site;
 use crate::state::AppState;
 use crate::tap::UserContext;
 
 use super::auth::SESSION_USER_ID;
-use super::helpers::{CsrfOnlyForm, JsonError, html_escape};
+use super::helpers::{CsrfOnlyForm, JsonError, html_escape, start_cascade};
 
 /// Response for successful item operations.
 #[derive(Debug, Serialize)]
@@ -31,6 +41,9 @@ pub struct ItemResponse {
     pub title: String,
     pub item_type: String,
     pub status: i16,
+    /// Batch operation unpublishing referencing items, if the change cascaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cascade_batch: Option<Uuid>,
 }
 
 /// Full item response for JSON API.
@@ -54,6 +67,27 @@ pub struct ItemApiResponse {
     pub item_group_id: Uuid,
 }
 
+impl ItemApiResponse {
+    /// Build the response for an item, with optional embedded author.
+    fn new(item: crate::models::Item, author: Option<AuthorResponse>) -> Self {
+        Self {
+            id: item.id,
+            item_type: item.item_type,
+            title: item.title,
+            status: item.status,
+            author_id: item.author_id,
+            author,
+            created: item.created,
+            changed: item.changed,
+            promote: item.promote,
+            sticky: item.sticky,
+            fields: item.fields,
+            stage_id: item.stage_id,
+            item_group_id: item.item_group_id,
+        }
+    }
+}
+
 /// Author information for embedding.
 #[derive(Debug, Clone, Serialize)]
 pub struct AuthorResponse {
@@ -75,6 +109,9 @@ pub struct PaginationMeta {
     pub page: i64,
     pub per_page: i64,
     pub total_pages: i64,
+    /// Set when the requested `per_page` was clamped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 /// Query parameters for listing items.
@@ -89,6 +126,44 @@ pub struct ListItemsQuery {
     pub include: Option<String>,
 }
 
+/// Query parameters for the field history endpoint.
+#[derive(Debug, Deserialize)]
+pub struct FieldHistoryQuery {
+    pub limit: Option<i64>,
+}
+
+/// Field change timeline response.
+#[derive(Debug, Serialize)]
+pub struct FieldHistoryResponse {
+    pub item_id: Uuid,
+    pub field_name: String,
+    pub entries: Vec<crate::models::FieldHistoryEntry>,
+}
+
+/// Query parameters for the revision compare endpoint.
+#[derive(Debug, Deserialize)]
+pub struct RevisionCompareQuery {
+    /// Older revision.
+    pub from: Uuid,
+    /// Newer revision; defaults to the item's current state.
+    pub to: Option<Uuid>,
+}
+
+/// Revision compare response.
+#[derive(Debug, Serialize)]
+pub struct RevisionCompareResponse {
+    /// Item whose revisions are compared.
+    pub item_id: Uuid,
+    /// Older revision.
+    pub from: Uuid,
+    /// `None` when compared against the current item.
+    pub to: Option<Uuid>,
+    /// Escaped HTML fragment showing the changes.
+    pub html: String,
+    /// What changed between the two versions.
+    pub summary: DiffSummary,
+}
+
 /// Query parameters for getting a single item.
 #[derive(Debug, Deserialize)]
 pub struct GetItemQuery {
@@ -112,13 +187,26 @@ pub struct UpdateItemRequest {
     pub fields: Option<serde_json::Value>,
     pub log: Option<String>,
     pub url_alias: Option<String>,
+    #[serde(default)]
+    pub status_meta: Option<StatusChangeMeta>,
+}
+
+/// Request for patching an item's fields.
+#[derive(Debug, Deserialize)]
+pub struct PatchItemRequest {
+    /// JSON merge patch applied to the item's fields.
+    pub fields: serde_json::Value,
+    /// `changed` timestamp of the version being edited; the patch is
+    /// refused if the item changed since.
+    pub changed: Option<i64>,
+    pub log: Option<String>,
 }
 
 /// Create the item router.
 pub fn router() -> Router<AppState> {
     Router::new()
         // View item
-        .route("/item/{id}", get(view_item))
+        .route("/item/{id}", get(view_item).patch(patch_item))
         // Add item form and submission
         .route("/item/add/{type}", get(add_item_form))
         .route("/item/add/{type}", post(create_item))
@@ -127,6 +215,8 @@ pub fn router() -> Router<AppState> {
         .route("/item/{id}/edit", post(update_item))
         // Delete item
         .route("/item/{id}/delete", post(delete_item))
+        // Clone item
+        .route("/item/{id}/clone", post(clone_item))
         // Revision history
         .route("/item/{id}/revisions", get(list_revisions))
         .route("/item/{id}/revert/{rev_id}", post(revert_revision))
@@ -135,11 +225,25 @@ pub fn router() -> Router<AppState> {
         .route("/api/items/{type}", get(list_items_by_type))
         // JSON API endpoints
         .route("/api/item/{id}", get(get_item_api))
+        .route(
+            "/api/item/{id}/fields/{field}/history",
+            get(field_history_api),
+        )
+        .route(
+            "/api/item/{id}/revisions/compare",
+            get(compare_revisions_api),
+        )
+        .route("/api/item/{id}/transitions", get(list_transitions_api))
+        .route("/api/item/{id}/transition", post(transition_item_api))
         .route("/api/items", get(list_items_api))
+        .route(
+            "/api/items/bulk",
+            get(list_bulk_operations_api).post(bulk_items_api),
+        )
 }
 
 /// Get current user from session with permissions loaded from the database.
-async fn get_user_context(session: &Session, state: &AppState) -> UserContext {
+pub(crate) async fn get_user_context(session: &Session, state: &AppState) -> UserContext {
     let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
 
     match user_id {
@@ -212,6 +316,7 @@ fn permitted_text_formats(user: &UserContext) -> Vec<String> {
 /// View an item.
 async fn view_item(
     State(state): State<AppState>,
+    client_ip: ClientIp,
     Extension(lang): Extension<ResolvedLanguage>,
     session: Session,
     Path(id): Path<Uuid>,
@@ -240,210 +345,44 @@ async fn view_item(
         }
     };
 
+    log_read(
+        &state,
+        &item,
+        user.authenticated.then_some(user.id),
+        client_ip,
+    )
+    .await;
+
     // Overlay translation if the active language differs from the default
     let active_language = lang.0;
     if active_language != state.default_language() {
         super::helpers::apply_translation_overlay(state.items(), &mut item, &active_language).await;
     }
 
-    // Look up content type field definitions for Blocks detection
-    let content_type_fields = state
-        .content_types()
-        .get(&item.item_type)
-        .map(|ct| ct.fields.clone())
+    // Render fields in display order through the filter pipeline
+    let content_type = state.content_types().get(&item.item_type);
+    let content_type_fields = content_type
+        .as_ref()
+        .map(|ct| ct.fields.as_slice())
         .unwrap_or_default();
-
-    // Render fields through filter pipeline
-    let mut children_html = String::new();
+    let mut rendered: Vec<(&str, String)> = Vec::new();
     if let Some(fields) = item.fields.as_object() {
-        for (name, value) in fields {
-            // Blocks field: flat JSON array of {type, weight, data}
-            let is_blocks_field = content_type_fields.iter().any(|f| {
-                f.field_name == *name
-                    && matches!(f.field_type, trovato_sdk::types::FieldType::Blocks)
-            });
-            if is_blocks_field {
-                if let Some(blocks) = value.as_array() {
-                    let rendered = crate::content::render_blocks(blocks);
-                    children_html.push_str(&format!(
-                        "<div class=\"field field--blocks field-{}\">{}</div>",
-                        html_escape(name),
-                        rendered
-                    ));
-                }
-                continue;
-            }
-
-            // PageBuilder field: Puck JSON component tree.
-            // Detect via field type definition OR structural check (root+content keys).
-            let is_page_builder_field = content_type_fields.iter().any(|f| {
-                f.field_name == *name
-                    && matches!(f.field_type, trovato_sdk::types::FieldType::PageBuilder)
-            }) || (value.get("root").is_some()
-                && value.get("content").is_some());
-            if is_page_builder_field {
-                match state.theme().render_page_builder_content(value) {
-                    Ok(rendered) => {
-                        children_html.push_str(&format!(
-                            "<div class=\"field field--page-builder field-{}\">{}</div>",
-                            html_escape(name),
-                            rendered
-                        ));
-                    }
-                    Err(e) => {
-                        tracing::warn!(field = %name, error = %e, "failed to render page builder field");
-                    }
-                }
-                continue;
-            }
-
-            // Compound field: has "sections" array
-            if let Some(sections_raw) = value.get("sections").and_then(|s| s.as_array()) {
-                // Sort sections by weight for correct display order
-                let mut sorted_sections = sections_raw.clone();
-                sorted_sections
-                    .sort_by_key(|s| s.get("weight").and_then(|w| w.as_i64()).unwrap_or(0));
-
-                for section in &sorted_sections {
-                    let section_type = section
-                        .get("type")
-                        .and_then(|t| t.as_str())
-                        .unwrap_or("unknown");
-
-                    // Sanitize section_type for template suggestion: only allow
-                    // alphanumeric, hyphens, and underscores to prevent path traversal
-                    let safe_type: String = section_type
-                        .chars()
-                        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-                        .collect();
-
-                    // Process section data fields through FilterPipeline
-                    let mut section_fields_html = String::new();
-                    if let Some(data) = section.get("data").and_then(|d| d.as_object()) {
-                        for (_key, val) in data {
-                            if let (Some(text), Some(fmt)) = (
-                                val.get("value").and_then(|v| v.as_str()),
-                                val.get("format").and_then(|v| v.as_str()),
-                            ) {
-                                let filtered = FilterPipeline::for_format_safe(fmt).process(text);
-                                section_fields_html.push_str(&filtered);
-                            } else if let Some(text) = val.as_str() {
-                                let filtered =
-                                    FilterPipeline::for_format("plain_text").process(text);
-                                section_fields_html.push_str(&filtered);
-                            } else {
-                                // Render non-string values (Integer, Float, Boolean) as
-                                // escaped text so they're not silently dropped
-                                if !val.is_object() && !val.is_array() && !val.is_null() {
-                                    let text = val.to_string();
-                                    let filtered =
-                                        FilterPipeline::for_format("plain_text").process(&text);
-                                    section_fields_html.push_str(&filtered);
-                                }
-                            }
-                        }
-                    }
-
-                    // Try to resolve section template using sanitized type
-                    let suggestions = [
-                        format!("elements/compound-section--{safe_type}"),
-                        "elements/compound-section".to_string(),
-                    ];
-                    let suggestion_refs: Vec<&str> =
-                        suggestions.iter().map(|s| s.as_str()).collect();
-                    let template = state
-                        .theme()
-                        .resolve_template(&suggestion_refs)
-                        .unwrap_or_else(|| "elements/compound-section.html".to_string());
-
-                    // Build sanitized section data: HTML-escape all string values
-                    // so custom templates can safely use {{ section_data.field }}
-                    let sanitized_data = if let Some(data) =
-                        section.get("data").and_then(|d| d.as_object())
-                    {
-                        let mut clean = serde_json::Map::new();
-                        for (k, v) in data {
-                            if let Some(s) = v.as_str() {
-                                clean.insert(k.clone(), serde_json::json!(html_escape(s)));
-                            } else if let Some(obj) = v.as_object() {
-                                // Escape string values inside nested objects like {value, format}
-                                let mut inner = serde_json::Map::new();
-                                for (ik, iv) in obj {
-                                    if let Some(s) = iv.as_str() {
-                                        inner.insert(ik.clone(), serde_json::json!(html_escape(s)));
-                                    } else {
-                                        inner.insert(ik.clone(), iv.clone());
-                                    }
-                                }
-                                clean.insert(k.clone(), serde_json::Value::Object(inner));
-                            } else {
-                                clean.insert(k.clone(), v.clone());
-                            }
-                        }
-                        serde_json::Value::Object(clean)
-                    } else {
-                        serde_json::json!({})
-                    };
-
-                    let mut section_ctx = tera::Context::new();
-                    section_ctx.insert("section_data", &sanitized_data);
-                    section_ctx.insert("section_type", &safe_type);
-                    section_ctx.insert("section_body", &section_fields_html);
-
-                    let section_html = state
-                        .theme()
-                        .tera()
-                        .render(&template, &section_ctx)
-                        .unwrap_or_else(|_| {
-                            format!(
-                                "<div class=\"compound-section compound-section--{}\">{}</div>",
-                                html_escape(&safe_type),
-                                section_fields_html
-                            )
-                        });
-                    children_html.push_str(&section_html);
+        for field in display::fields_to_display(content_type.as_ref(), fields, ViewMode::Full) {
+            let formatted = match field.definition.zip(field.formatter.as_ref()) {
+                Some((definition, formatter)) => {
+                    super::helpers::format_display_value(&state, field.value, formatter)
+                        .await
+                        .map(|html| display::render_field(definition, formatter, &html))
                 }
-            } else if let Some(text_val) = value.get("value").and_then(|v| v.as_str()) {
-                let raw_fmt = value
-                    .get("format")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("plain_text");
-                let filtered = FilterPipeline::for_format_safe(raw_fmt).process(text_val);
-                children_html.push_str(&format!(
-                    "<div class=\"field field-{}\">{}</div>",
-                    html_escape(name),
-                    filtered
-                ));
-            } else if let Some(s) = value.as_str() {
-                // Plain string field (e.g., "field_city": "Portland")
-                let label = name
-                    .strip_prefix("field_")
-                    .unwrap_or(name)
-                    .replace('_', " ");
-                let filtered = FilterPipeline::for_format("plain_text").process(s);
-                children_html.push_str(&format!(
-                    "<div class=\"field field-{}\"><strong class=\"field__label\">{}</strong>: {}</div>",
-                    html_escape(name),
-                    html_escape(&label),
-                    filtered
-                ));
-            } else if !value.is_object() && !value.is_array() && !value.is_null() {
-                // Numeric or boolean scalar fields
-                let label = name
-                    .strip_prefix("field_")
-                    .unwrap_or(name)
-                    .replace('_', " ");
-                let text = value.to_string();
-                let filtered = FilterPipeline::for_format("plain_text").process(&text);
-                children_html.push_str(&format!(
-                    "<div class=\"field field-{}\"><strong class=\"field__label\">{}</strong>: {}</div>",
-                    html_escape(name),
-                    html_escape(&label),
-                    filtered
-                ));
-            }
+                None => None,
+            };
+            let html = formatted.unwrap_or_else(|| {
+                render_field_value(&state, field.name, field.value, content_type_fields)
+            });
+            rendered.push((field.name, html));
         }
     }
+    let mut children_html = display::group_fields(content_type.as_ref(), &rendered);
 
     // Include plugin render outputs
     for output in render_outputs {
@@ -609,6 +548,11 @@ async fn view_item(
     ];
     context.insert("breadcrumbs", &breadcrumbs);
 
+    let hreflang_links = super::helpers::item_hreflang_links(&state, &item).await;
+    if !hreflang_links.is_empty() {
+        context.insert("hreflang_links", &hreflang_links);
+    }
+
     let page_html = state
         .theme()
         .render_page(&item_path, &item.title, &item_html, &mut context)
@@ -617,6 +561,199 @@ async fn view_item(
     Ok(Html(page_html))
 }
 
+/// Render a field value with the built-in rendering for its type.
+///
+/// Used for fields without display settings and for structured values
+/// that display formatters do not handle.
+fn render_field_value(
+    state: &AppState,
+    name: &str,
+    value: &serde_json::Value,
+    content_type_fields: &[trovato_sdk::types::FieldDefinition],
+) -> String {
+    let mut html = String::new();
+    // Blocks field: flat JSON array of {type, weight, data}
+    let is_blocks_field = content_type_fields.iter().any(|f| {
+        f.field_name == *name && matches!(f.field_type, trovato_sdk::types::FieldType::Blocks)
+    });
+    if is_blocks_field {
+        if let Some(blocks) = value.as_array() {
+            let rendered = crate::content::render_blocks(blocks);
+            html.push_str(&format!(
+                "<div class=\"field field--blocks field-{}\">{}</div>",
+                html_escape(name),
+                rendered
+            ));
+        }
+        return html;
+    }
+
+    // PageBuilder field: Puck JSON component tree.
+    // Detect via field type definition OR structural check (root+content keys).
+    let is_page_builder_field = content_type_fields.iter().any(|f| {
+        f.field_name == *name && matches!(f.field_type, trovato_sdk::types::FieldType::PageBuilder)
+    }) || (value.get("root").is_some()
+        && value.get("content").is_some());
+    if is_page_builder_field {
+        match state.theme().render_page_builder_content(value) {
+            Ok(rendered) => {
+                html.push_str(&format!(
+                    "<div class=\"field field--page-builder field-{}\">{}</div>",
+                    html_escape(name),
+                    rendered
+                ));
+            }
+            Err(e) => {
+                tracing::warn!(field = %name, error = %e, "failed to render page builder field");
+            }
+        }
+        return html;
+    }
+
+    // Compound field: has "sections" array
+    if let Some(sections_raw) = value.get("sections").and_then(|s| s.as_array()) {
+        // Sort sections by weight for correct display order
+        let mut sorted_sections = sections_raw.clone();
+        sorted_sections.sort_by_key(|s| s.get("weight").and_then(|w| w.as_i64()).unwrap_or(0));
+
+        for section in &sorted_sections {
+            let section_type = section
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("unknown");
+
+            // Sanitize section_type for template suggestion: only allow
+            // alphanumeric, hyphens, and underscores to prevent path traversal
+            let safe_type: String = section_type
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                .collect();
+
+            // Process section data fields through FilterPipeline
+            let mut section_fields_html = String::new();
+            if let Some(data) = section.get("data").and_then(|d| d.as_object()) {
+                for (_key, val) in data {
+                    if let (Some(text), Some(fmt)) = (
+                        val.get("value").and_then(|v| v.as_str()),
+                        val.get("format").and_then(|v| v.as_str()),
+                    ) {
+                        let filtered = FilterPipeline::for_format_safe(fmt).process(text);
+                        section_fields_html.push_str(&filtered);
+                    } else if let Some(text) = val.as_str() {
+                        let filtered = FilterPipeline::for_format("plain_text").process(text);
+                        section_fields_html.push_str(&filtered);
+                    } else {
+                        // Render non-string values (Integer, Float, Boolean) as
+                        // escaped text so they're not silently dropped
+                        if !val.is_object() && !val.is_array() && !val.is_null() {
+                            let text = val.to_string();
+                            let filtered = FilterPipeline::for_format("plain_text").process(&text);
+                            section_fields_html.push_str(&filtered);
+                        }
+                    }
+                }
+            }
+
+            // Try to resolve section template using sanitized type
+            let suggestions = [
+                format!("elements/compound-section--{safe_type}"),
+                "elements/compound-section".to_string(),
+            ];
+            let suggestion_refs: Vec<&str> = suggestions.iter().map(|s| s.as_str()).collect();
+            let template = state
+                .theme()
+                .resolve_template(&suggestion_refs)
+                .unwrap_or_else(|| "elements/compound-section.html".to_string());
+
+            // Build sanitized section data: HTML-escape all string values
+            // so custom templates can safely use {{ section_data.field }}
+            let sanitized_data = if let Some(data) = section.get("data").and_then(|d| d.as_object())
+            {
+                let mut clean = serde_json::Map::new();
+                for (k, v) in data {
+                    if let Some(s) = v.as_str() {
+                        clean.insert(k.clone(), serde_json::json!(html_escape(s)));
+                    } else if let Some(obj) = v.as_object() {
+                        // Escape string values inside nested objects like {value, format}
+                        let mut inner = serde_json::Map::new();
+                        for (ik, iv) in obj {
+                            if let Some(s) = iv.as_str() {
+                                inner.insert(ik.clone(), serde_json::json!(html_escape(s)));
+                            } else {
+                                inner.insert(ik.clone(), iv.clone());
+                            }
+                        }
+                        clean.insert(k.clone(), serde_json::Value::Object(inner));
+                    } else {
+                        clean.insert(k.clone(), v.clone());
+                    }
+                }
+                serde_json::Value::Object(clean)
+            } else {
+                serde_json::json!({})
+            };
+
+            let mut section_ctx = tera::Context::new();
+            section_ctx.insert("section_data", &sanitized_data);
+            section_ctx.insert("section_type", &safe_type);
+            section_ctx.insert("section_body", &section_fields_html);
+
+            let section_html = state
+                .theme()
+                .tera()
+                .render(&template, &section_ctx)
+                .unwrap_or_else(|_| {
+                    format!(
+                        "<div class=\"compound-section compound-section--{}\">{}</div>",
+                        html_escape(&safe_type),
+                        section_fields_html
+                    )
+                });
+            html.push_str(&section_html);
+        }
+    } else if let Some(text_val) = value.get("value").and_then(|v| v.as_str()) {
+        let raw_fmt = value
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("plain_text");
+        let filtered = FilterPipeline::for_format_safe(raw_fmt).process(text_val);
+        html.push_str(&format!(
+            "<div class=\"field field-{}\">{}</div>",
+            html_escape(name),
+            filtered
+        ));
+    } else if let Some(s) = value.as_str() {
+        // Plain string field (e.g., "field_city": "Portland")
+        let label = name
+            .strip_prefix("field_")
+            .unwrap_or(name)
+            .replace('_', " ");
+        let filtered = FilterPipeline::for_format("plain_text").process(s);
+        html.push_str(&format!(
+            "<div class=\"field field-{}\"><strong class=\"field__label\">{}</strong>: {}</div>",
+            html_escape(name),
+            html_escape(&label),
+            filtered
+        ));
+    } else if !value.is_object() && !value.is_array() && !value.is_null() {
+        // Numeric or boolean scalar fields
+        let label = name
+            .strip_prefix("field_")
+            .unwrap_or(name)
+            .replace('_', " ");
+        let text = value.to_string();
+        let filtered = FilterPipeline::for_format("plain_text").process(&text);
+        html.push_str(&format!(
+            "<div class=\"field field-{}\"><strong class=\"field__label\">{}</strong>: {}</div>",
+            html_escape(name),
+            html_escape(&label),
+            filtered
+        ));
+    }
+
+    html
+}
+
 /// Display add item form.
 async fn add_item_form(
     State(state): State<AppState>,
@@ -726,15 +863,7 @@ async fn create_item(
         .map_err(|e| AppError::internal_ctx(e, "create item"))?;
 
     // Auto-generate URL alias if pattern configured for this type
-    if let Err(e) = crate::services::pathauto::auto_alias_item(
-        state.db(),
-        item.id,
-        &item.title,
-        &item.item_type,
-        item.created,
-    )
-    .await
-    {
+    if let Err(e) = crate::services::pathauto::auto_alias_item(state.db(), &item).await {
         tracing::warn!(error = %e, item_id = %item.id, "pathauto alias generation failed");
     }
 
@@ -743,6 +872,7 @@ async fn create_item(
         title: item.title,
         item_type: item.item_type,
         status: item.status,
+        cascade_batch: None,
     }))
 }
 
@@ -867,6 +997,14 @@ async fn update_item(
         .await
         .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
 
+    let was_published = state
+        .items()
+        .load(id)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|item| item.is_published());
+
     let input = UpdateItem {
         title: request.title,
         status: request.status,
@@ -874,6 +1012,7 @@ async fn update_item(
         sticky: None,
         fields: request.fields,
         log: request.log,
+        status_meta: request.status_meta,
     };
 
     match state.items().update(id, input, &user).await {
@@ -911,24 +1050,32 @@ async fn update_item(
                 }
             } else {
                 // No explicit alias — auto-update from pathauto pattern
-                if let Err(e) = crate::services::pathauto::update_alias_item(
-                    state.db(),
-                    item.id,
-                    &item.title,
-                    &item.item_type,
-                    item.created,
-                )
-                .await
+                if let Err(e) =
+                    crate::services::pathauto::update_alias_item(state.db(), &item).await
                 {
                     tracing::warn!(error = %e, item_id = %item.id, "pathauto alias update failed");
                 }
             }
 
+            let cascade_batch = if was_published && !item.is_published() {
+                start_cascade(
+                    &state,
+                    &[(item.id, item.item_type.clone())],
+                    CascadeAction::Unpublish,
+                    &user,
+                )
+                .await
+                .map(|(batch_id, _)| batch_id)
+            } else {
+                None
+            };
+
             Ok(Json(ItemResponse {
                 id: item.id,
                 title: item.title,
                 item_type: item.item_type,
                 status: item.status,
+                cascade_batch,
             }))
         }
         Ok(None) => Err(AppError::not_found_id("item", id)),
@@ -936,6 +1083,8 @@ async fn update_item(
             let msg = e.to_string();
             if msg.contains("access denied") {
                 Err(AppError::forbidden("Access denied"))
+            } else if msg.contains("are moderated") {
+                Err(AppError::bad_request(msg))
             } else {
                 Err(AppError::internal_ctx(e, "update item"))
             }
@@ -943,6 +1092,396 @@ async fn update_item(
     }
 }
 
+/// Apply a JSON merge patch to an item's fields.
+///
+/// Only the patched fields are validated. The patch is refused with 412 if
+/// the item changed after the `If-Unmodified-Since` header or the body's
+/// `changed` timestamp, or if another save lands while it is applied.
+///
+/// PATCH /item/{id}
+async fn patch_item(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<PatchItemRequest>,
+) -> Result<Response, AppError> {
+    let user = get_user_context(&session, &state).await;
+
+    crate::routes::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    if !request.fields.is_object() {
+        return Err(AppError::bad_request("fields must be a JSON object"));
+    }
+
+    let patch = ItemPatch {
+        fields: request.fields,
+        unmodified_since: unmodified_since(&headers, request.changed),
+        log: request.log,
+    };
+    let item = match state.items().patch(id, patch, &user).await {
+        Ok(Some(item)) => item,
+        Ok(None) => return Err(AppError::not_found_id("item", id)),
+        Err(e) if e.to_string().contains("access denied") => {
+            return Err(AppError::forbidden("Access denied"));
+        }
+        Err(e) => return Err(AppError::internal_ctx(e, "patch item")),
+    };
+
+    if let Err(e) = crate::services::pathauto::update_alias_item(state.db(), &item).await {
+        tracing::warn!(error = %e, item_id = %item.id, "pathauto alias update failed");
+    }
+
+    let last_modified = http_date(item.changed);
+    let mut response = Json(ItemApiResponse::new(item, None)).into_response();
+    if let Some(value) = last_modified.and_then(|d| d.parse().ok()) {
+        response.headers_mut().insert(header::LAST_MODIFIED, value);
+    }
+    Ok(response)
+}
+
+/// The stricter of the `If-Unmodified-Since` header and a body `changed`
+/// timestamp. An unparseable header is ignored, as RFC 9110 requires.
+fn unmodified_since(headers: &HeaderMap, changed: Option<i64>) -> Option<i64> {
+    let from_header = headers
+        .get(header::IF_UNMODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok())
+        .map(|dt| dt.timestamp());
+    match (from_header, changed) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Format a Unix timestamp as an HTTP date.
+fn http_date(ts: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+}
+
+/// Moderation state of an item and the transitions open to the user.
+#[derive(Debug, Serialize)]
+struct TransitionsResponse {
+    item_id: Uuid,
+    workflow: String,
+    state: String,
+    state_label: String,
+    transitions: Vec<TransitionOption>,
+}
+
+/// A transition the user may take.
+#[derive(Debug, Serialize)]
+struct TransitionOption {
+    to: String,
+    label: String,
+}
+
+/// Request for moving an item to another moderation state.
+#[derive(Debug, Deserialize)]
+struct TransitionRequest {
+    to: String,
+    #[serde(default)]
+    status_meta: Option<StatusChangeMeta>,
+}
+
+/// List the moderation transitions the current user may apply to an item.
+///
+/// Returns 404 if the item's type is not moderated.
+///
+/// GET /api/item/{id}/transitions
+async fn list_transitions_api(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TransitionsResponse>, AppError> {
+    let user = get_user_context(&session, &state).await;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Authentication required"));
+    }
+
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+
+    let can_edit = state
+        .items()
+        .check_access(&item, "edit", &user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "check item access"))?;
+    if !can_edit {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let (workflow, transitions) = state
+        .items()
+        .available_transitions(&item, &user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load transitions"))?
+        .ok_or_else(|| AppError::not_found_id("workflow for item type", &item.item_type))?;
+
+    let current = item
+        .moderation_state
+        .unwrap_or_else(|| workflow.initial_state.clone());
+    let label_of = |id: &str| {
+        workflow
+            .state(id)
+            .map_or_else(|| id.to_string(), |s| s.label.clone())
+    };
+
+    Ok(Json(TransitionsResponse {
+        item_id: id,
+        state_label: label_of(&current),
+        transitions: transitions
+            .into_iter()
+            .map(|t| TransitionOption {
+                label: label_of(&t.to),
+                to: t.to,
+            })
+            .collect(),
+        workflow: workflow.id.clone(),
+        state: current,
+    }))
+}
+
+/// Move an item to another moderation state.
+///
+/// POST /api/item/{id}/transition
+async fn transition_item_api(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<TransitionRequest>,
+) -> Result<Json<ItemResponse>, AppError> {
+    let user = get_user_context(&session, &state).await;
+
+    crate::routes::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let was_published = state
+        .items()
+        .load(id)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|item| item.is_published());
+
+    let meta = request.status_meta.unwrap_or_default();
+    match state.items().transition(id, &request.to, meta, &user).await {
+        Ok(Some(item)) => {
+            let cascade_batch = if was_published && !item.is_published() {
+                start_cascade(
+                    &state,
+                    &[(item.id, item.item_type.clone())],
+                    CascadeAction::Unpublish,
+                    &user,
+                )
+                .await
+                .map(|(batch_id, _)| batch_id)
+            } else {
+                None
+            };
+
+            Ok(Json(ItemResponse {
+                id: item.id,
+                title: item.title,
+                item_type: item.item_type,
+                status: item.status,
+                cascade_batch,
+            }))
+        }
+        Ok(None) => Err(AppError::not_found_id("item", id)),
+        Err(e) => {
+            let msg = e.to_string();
+            if msg.contains("access denied") {
+                Err(AppError::forbidden("Access denied"))
+            } else if msg.starts_with("invalid transition") || msg.contains("status reason") {
+                Err(AppError::bad_request(msg))
+            } else {
+                Err(AppError::internal_ctx(e, "transition item"))
+            }
+        }
+    }
+}
+
+/// A bulk operation offered to editors.
+#[derive(Debug, Serialize)]
+struct BulkOperationOption {
+    name: String,
+    label: String,
+}
+
+/// Response for an accepted bulk request.
+#[derive(Debug, Serialize)]
+struct BulkResponse {
+    /// Batch operation to poll at `/api/batch/{id}`.
+    batch_id: Uuid,
+    operation: String,
+    items: usize,
+}
+
+/// Enabled plugins with their manifests.
+pub(crate) fn enabled_plugin_infos(state: &AppState) -> Vec<(&str, &crate::plugin::PluginInfo)> {
+    state
+        .plugin_runtime()
+        .plugins()
+        .iter()
+        .filter(|(name, _)| state.is_plugin_enabled(name) && site::plugin_enabled(name))
+        .map(|(name, plugin)| (name.as_str(), &plugin.info))
+        .collect()
+}
+
+/// List the bulk operations available to content lists.
+///
+/// GET /api/items/bulk
+async fn list_bulk_operations_api(State(state): State<AppState>) -> Json<Vec<BulkOperationOption>> {
+    let mut plugin_operations: Vec<(String, String)> =
+        item_bulk::plugin_operations(enabled_plugin_infos(&state))
+            .into_iter()
+            .collect();
+    plugin_operations.sort();
+
+    let operations = item_bulk::KERNEL_OPERATIONS
+        .iter()
+        .map(|(name, label)| (name.to_string(), label.to_string()))
+        .chain(plugin_operations)
+        .map(|(name, label)| BulkOperationOption { name, label })
+        .collect();
+    Json(operations)
+}
+
+/// Apply an operation to many items in a background batch.
+///
+/// POST /api/items/bulk
+///
+/// Answers `202 Accepted` with the batch operation ID; the batch result
+/// holds the counts of applied, skipped, denied and failed items. Items the
+/// user may not change are left alone and counted as denied.
+async fn bulk_items_api(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(request): Json<BulkRequest>,
+) -> Result<(StatusCode, Json<BulkResponse>), AppError> {
+    let user = get_user_context(&session, &state).await;
+
+    crate::routes::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Login required"));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let item_ids: Vec<Uuid> = request
+        .item_ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+    if item_ids.is_empty() {
+        return Err(AppError::bad_request("item_ids must not be empty"));
+    }
+    if item_ids.len() > item_bulk::MAX_BULK_ITEMS {
+        return Err(AppError::bad_request(format!(
+            "at most {} items per bulk request",
+            item_bulk::MAX_BULK_ITEMS
+        )));
+    }
+
+    let operation = match BulkOperation::kernel(&request.operation, &request.params) {
+        Ok(Some(operation)) => operation,
+        Ok(None) => BulkOperation::plugin(&request.operation, enabled_plugin_infos(&state))
+            .ok_or_else(|| {
+                AppError::bad_request(format!("unknown bulk operation: {}", request.operation))
+            })?,
+        Err(e) => return Err(AppError::bad_request(e.to_string())),
+    };
+    if !operation.permitted(&user) {
+        return Err(AppError::forbidden(format!(
+            "{} requires the \"{}\" permission",
+            operation.name(),
+            crate::content::ADMINISTER_CONTENT_PERMISSION
+        )));
+    }
+    if let Err(e) = operation.check_target(state.db(), &user).await {
+        let msg = e.to_string();
+        return Err(if msg.starts_with("invalid bulk operation") {
+            AppError::bad_request(msg)
+        } else {
+            AppError::internal_ctx(e, "check bulk operation")
+        });
+    }
+
+    let batch = state
+        .batch()
+        .create(CreateBatch {
+            operation_type: item_bulk::BULK_OPERATION.to_string(),
+            params: serde_json::json!({
+                "operation": operation.name(),
+                "items": item_ids.len(),
+                "params": request.params,
+                "user_id": user.id,
+            }),
+        })
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "create bulk batch"))?;
+
+    let response = BulkResponse {
+        batch_id: batch.id,
+        operation: operation.name().to_string(),
+        items: item_ids.len(),
+    };
+    let services = BulkServices {
+        items: state.items().clone(),
+        batch: state.batch().clone(),
+        content_types: state.content_types().clone(),
+        dispatcher: state.tap_dispatcher().clone(),
+        tap_services: state.tap_services().clone(),
+        audit: state.audit().cloned(),
+    };
+    let batch_id = batch.id;
+    let params = request.params;
+    let run = async move {
+        let outcome = match item_bulk::execute(
+            &services, batch_id, &operation, &item_ids, &params, &user,
+        )
+        .await
+        {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                if let Err(e) = services.batch.fail(batch_id, &e.to_string()).await {
+                    tracing::error!(error = %e, batch_id = %batch_id, "failed to record bulk failure");
+                }
+                return;
+            }
+        };
+
+        // Bulk unpublishing and deleting cascade like single-item changes.
+        let trigger = match operation {
+            BulkOperation::Unpublish => CascadeAction::Unpublish,
+            BulkOperation::Delete => CascadeAction::Delete,
+            _ => return,
+        };
+        if !outcome.changed.is_empty() {
+            start_cascade(&state, &outcome.changed, trigger, &user).await;
+        }
+    };
+    // The run outlives the request, so it re-enters the site scope itself.
+    match site::current() {
+        Some(scope) => tokio::spawn(site::scope(scope, run)),
+        None => tokio::spawn(run),
+    };
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
 /// Delete an item.
 async fn delete_item(
     State(state): State<AppState>,
@@ -957,8 +1496,25 @@ async fn delete_item(
         .await
         .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
 
+    let item_type = state
+        .items()
+        .load(id)
+        .await
+        .ok()
+        .flatten()
+        .map(|item| item.item_type);
+
     match state.items().delete(id, &user).await {
-        Ok(true) => Ok(Json(serde_json::json!({"deleted": true}))),
+        Ok(true) => {
+            let mut body = serde_json::json!({"deleted": true});
+            if let Some(item_type) = item_type
+                && let Some((batch_id, _)) =
+                    start_cascade(&state, &[(id, item_type)], CascadeAction::Delete, &user).await
+            {
+                body["cascade_batch"] = serde_json::json!(batch_id);
+            }
+            Ok(Json(body))
+        }
         Ok(false) => Err(AppError::not_found_id("item", id)),
         Err(e) => {
             let msg = e.to_string();
@@ -971,6 +1527,80 @@ async fn delete_item(
     }
 }
 
+/// Create an unpublished copy of an item.
+///
+/// The body may set the copy's `title`, `stage_id` and `language`; all
+/// default to the source's. Requires view access to the source and the
+/// type's create permission.
+///
+/// POST /item/{id}/clone
+async fn clone_item(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(options): Json<CloneOptions>,
+) -> Result<Json<ItemResponse>, AppError> {
+    let user = get_user_context(&session, &state).await;
+
+    crate::routes::helpers::require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let source = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+
+    let permission = format!("create {} content", source.item_type);
+    if !user.has_permission(&permission) && !user.is_admin() {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    if let Some(language) = &options.language
+        && !state.known_languages().contains(language)
+    {
+        return Err(AppError::bad_request(format!(
+            "unknown language: {language}"
+        )));
+    }
+    if let Some(stage_id) = options.stage_id {
+        let stage = Stage::find_by_id(state.db(), stage_id)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load stage"))?;
+        if stage.is_none() {
+            return Err(AppError::bad_request(format!("unknown stage: {stage_id}")));
+        }
+    }
+
+    let item = match state.items().clone_item(id, options, &user).await {
+        Ok(Some(item)) => item,
+        Ok(None) => return Err(AppError::not_found_id("item", id)),
+        Err(e) => {
+            let msg = e.to_string();
+            return if msg.contains("access denied") {
+                Err(AppError::forbidden("Access denied"))
+            } else {
+                Err(AppError::internal_ctx(e, "clone item"))
+            };
+        }
+    };
+
+    if let Err(e) = crate::services::pathauto::auto_alias_item(state.db(), &item).await {
+        tracing::warn!(error = %e, item_id = %item.id, "pathauto alias generation failed");
+    }
+
+    Ok(Json(ItemResponse {
+        id: item.id,
+        title: item.title,
+        item_type: item.item_type,
+        status: item.status,
+        cascade_batch: None,
+    }))
+}
+
 /// List revision history for an item.
 ///
 /// Requires authentication — revision history may contain draft titles and
@@ -1081,6 +1711,7 @@ async fn list_items_by_type(
                 title: i.title,
                 item_type: i.item_type,
                 status: i.status,
+                cascade_batch: None,
             })
             .collect(),
     ))
@@ -1095,9 +1726,12 @@ async fn list_items_by_type(
 /// GET /api/item/{id}?include=author
 async fn get_item_api(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    session: Session,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
     Query(query): Query<GetItemQuery>,
-) -> Result<Json<ItemApiResponse>, AppError> {
+) -> Result<Response, AppError> {
     // Load item
     let item = state
         .items()
@@ -1106,6 +1740,9 @@ async fn get_item_api(
         .map_err(|e| AppError::internal_ctx(e, "load item"))?
         .ok_or_else(|| AppError::not_found_id("item", id))?;
 
+    let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
+    log_read(&state, &item, user_id, client_ip).await;
+
     // Check if we should include author
     let include_author = query
         .include
@@ -1125,23 +1762,197 @@ async fn get_item_api(
         None
     };
 
-    Ok(Json(ItemApiResponse {
-        id: item.id,
-        item_type: item.item_type,
-        title: item.title,
-        status: item.status,
-        author_id: item.author_id,
-        author,
-        created: item.created,
-        changed: item.changed,
-        promote: item.promote,
-        sticky: item.sticky,
-        fields: item.fields,
-        stage_id: item.stage_id,
-        item_group_id: item.item_group_id,
+    // The cached item identifies the representation, so revalidation
+    // needs no database round trip.
+    let revision = item.current_revision_id.map(|r| r.to_string());
+    let validators = Validators::from_parts(
+        &[
+            "item",
+            &item.id.to_string(),
+            revision.as_deref().unwrap_or(""),
+            &item.changed.to_string(),
+            author.as_ref().map_or("", |a| a.name.as_str()),
+        ],
+        Some(item.changed),
+    );
+
+    Ok(validators.respond(&headers, Json(ItemApiResponse::new(item, author))))
+}
+
+/// Record a sampled read of an item.
+///
+/// No-op unless the read_log plugin is enabled and read logging is enabled
+/// for the item's type. Sampled reads are queued for a background writer,
+/// and failures are only logged, so logging never fails the response.
+async fn log_read(
+    state: &AppState,
+    item: &crate::models::Item,
+    user_id: Option<Uuid>,
+    client_ip: ClientIp,
+) {
+    let Some(read_log) = state.read_log() else {
+        return;
+    };
+    let ip = client_ip.key();
+    if let Err(e) = read_log
+        .record(item.id, &item.item_type, user_id, &ip)
+        .await
+    {
+        tracing::warn!(error = %e, item_id = %item.id, "failed to record item read");
+    }
+}
+
+/// Get the change timeline for a history-tracked field (JSON API).
+///
+/// Requires edit access to the item, since tracked fields are typically
+/// sensitive. Returns 404 if the field does not exist on the item's type
+/// or is not declared with `track_history`.
+///
+/// GET /api/item/{id}/fields/{field}/history?limit=50
+async fn field_history_api(
+    State(state): State<AppState>,
+    session: Session,
+    Path((id, field)): Path<(Uuid, String)>,
+    Query(query): Query<FieldHistoryQuery>,
+) -> Result<Json<FieldHistoryResponse>, AppError> {
+    let user_ctx = get_user_context(&session, &state).await;
+    if !user_ctx.authenticated {
+        return Err(AppError::unauthorized("Authentication required"));
+    }
+
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+
+    let can_edit = state
+        .items()
+        .check_access(&item, "edit", &user_ctx)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "check item access"))?;
+    if !can_edit {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let tracked = state
+        .content_types()
+        .get_or_load(&item.item_type)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load content type"))?
+        .is_some_and(|def| {
+            def.fields
+                .iter()
+                .any(|f| f.field_name == field && f.track_history)
+        });
+    if !tracked {
+        return Err(AppError::not_found_id("tracked field", &field));
+    }
+
+    let entries = state
+        .items()
+        .field_history(id, &field, query.limit.unwrap_or(100))
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load field history"))?;
+
+    Ok(Json(FieldHistoryResponse {
+        item_id: id,
+        field_name: field,
+        entries,
+    }))
+}
+
+/// Compare two revisions of an item as a rendered HTML diff (JSON API).
+///
+/// Requires edit access to the item. `to` defaults to the item's current
+/// state. Both revisions must belong to the item.
+///
+/// GET /api/item/{id}/revisions/compare?from={rev_id}&to={rev_id}
+async fn compare_revisions_api(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RevisionCompareQuery>,
+) -> Result<Json<RevisionCompareResponse>, AppError> {
+    let user_ctx = get_user_context(&session, &state).await;
+    if !user_ctx.authenticated {
+        return Err(AppError::unauthorized("Authentication required"));
+    }
+
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+
+    let can_edit = state
+        .items()
+        .check_access(&item, "edit", &user_ctx)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "check item access"))?;
+    if !can_edit {
+        return Err(AppError::forbidden("Access denied"));
+    }
+
+    let from = load_item_revision(&state, id, query.from).await?;
+    let to = match query.to {
+        Some(rev_id) => Some(load_item_revision(&state, id, rev_id).await?),
+        None => None,
+    };
+
+    let field_defs = state
+        .content_types()
+        .get_or_load(&item.item_type)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load content type"))?
+        .map(|def| def.fields)
+        .unwrap_or_default();
+
+    let before = DiffInput {
+        title: &from.title,
+        status: from.status,
+        fields: &from.fields,
+    };
+    let after = match &to {
+        Some(rev) => DiffInput {
+            title: &rev.title,
+            status: rev.status,
+            fields: &rev.fields,
+        },
+        None => DiffInput {
+            title: &item.title,
+            status: item.status,
+            fields: &item.fields,
+        },
+    };
+    let diff = diff_items(Some(&before), &after, &field_defs);
+
+    Ok(Json(RevisionCompareResponse {
+        item_id: id,
+        from: query.from,
+        to: query.to,
+        html: diff.html,
+        summary: diff.summary,
     }))
 }
 
+/// Load a revision, returning 404 unless it belongs to `item_id`.
+async fn load_item_revision(
+    state: &AppState,
+    item_id: Uuid,
+    rev_id: Uuid,
+) -> Result<crate::models::ItemRevision, AppError> {
+    state
+        .items()
+        .get_revision(rev_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load revision"))?
+        .filter(|rev| rev.item_id == item_id)
+        .ok_or_else(|| AppError::not_found_id("revision", rev_id))
+}
+
 /// List items with filtering and pagination (JSON API).
 ///
 /// GET /api/items?type=article&status=1&page=1&per_page=20&include=author
@@ -1150,7 +1961,10 @@ async fn list_items_api(
     Query(query): Query<ListItemsQuery>,
 ) -> Result<Json<PaginatedResponse<ItemApiResponse>>, AppError> {
     let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let page_size = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Api, query.per_page);
+    let per_page = page_size.limit;
     let offset = (page - 1) * per_page;
 
     // Check if we should include author
@@ -1228,6 +2042,7 @@ async fn list_items_api(
             page,
             per_page,
             total_pages,
+            warning: page_size.warning,
         },
     }))
 }
@@ -0,0 +1,1039 @@
+//! JSON:API endpoints for items and content types.
+//!
+//! Read-only resources in the JSON:API 1.1 format, shaped like Drupal's
+//! JSON:API module so existing clients need few changes:
+//!
+//! - `GET /jsonapi` — entry point linking every collection
+//! - `GET /jsonapi/content_type` and `/jsonapi/content_type/{name}`
+//! - `GET /jsonapi/item/{type}` and `/jsonapi/item/{type}/{id}`
+//! - `GET /jsonapi/item/{type}/{id}/{field}` — items a RecordReference
+//!   field points to
+//! - `GET /jsonapi/item/{type}/{id}/relationships/{field}` — its linkage
+//!
+//! Items are resources of type `item--{type}`. RecordReference fields are
+//! relationships to `item--{target}`; all other fields are attributes.
+//! Collections support `filter`, `sort`, sparse fieldsets (`fields`),
+//! `include` and cursor pagination (`page[size]`, `page[cursor]`), and
+//! apply the same access rules as the plugin `item-query` host function:
+//! admins see every item, users with "access content" published ones.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::{Map, Value, json};
+use tower_sessions::Session;
+use trovato_sdk::types::{
+    ContentTypeDefinition, FieldType, ITEM_QUERY_MAX_LIMIT, ItemFieldPredicate, ItemQuery,
+    ItemQueryOp, ItemQuerySort, SortDirection,
+};
+use uuid::Uuid;
+
+use crate::content::item_query::{self, ItemQueryAccess, SORT_COLUMNS};
+use crate::models::Item;
+use crate::models::stage::LIVE_STAGE_ID;
+use crate::routes::item::get_user_context;
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::state::AppState;
+use crate::tap::UserContext;
+
+/// JSON:API media type.
+const MEDIA_TYPE: &str = "application/vnd.api+json";
+
+/// Resource type of content types.
+const CONTENT_TYPE_RESOURCE: &str = "content_type";
+
+/// Create the JSON:API router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/jsonapi", get(entry_point))
+        .route("/jsonapi/content_type", get(list_content_types))
+        .route("/jsonapi/content_type/{name}", get(get_content_type))
+        .route("/jsonapi/item/{type}", get(list_items))
+        .route("/jsonapi/item/{type}/{id}", get(get_item))
+        .route("/jsonapi/item/{type}/{id}/{field}", get(get_related))
+        .route(
+            "/jsonapi/item/{type}/{id}/relationships/{field}",
+            get(get_relationship),
+        )
+}
+
+// -------------------------------------------------------------------------
+// Errors and documents
+// -------------------------------------------------------------------------
+
+/// An error returned as a JSON:API error document.
+#[derive(Debug)]
+struct JsonApiError {
+    status: StatusCode,
+    detail: String,
+}
+
+impl JsonApiError {
+    fn bad_request(detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            detail: detail.into(),
+        }
+    }
+
+    fn not_found(detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            detail: detail.into(),
+        }
+    }
+
+    fn forbidden() -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            detail: "access denied".to_string(),
+        }
+    }
+
+    /// Log an internal failure and hide its details from the client.
+    fn internal(error: anyhow::Error, action: &str) -> Self {
+        tracing::error!(error = %error, "jsonapi: failed to {action}");
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            detail: format!("failed to {action}"),
+        }
+    }
+}
+
+impl IntoResponse for JsonApiError {
+    fn into_response(self) -> Response {
+        let body = json!({
+            "jsonapi": { "version": "1.1" },
+            "errors": [{
+                "status": self.status.as_u16().to_string(),
+                "title": self.status.canonical_reason().unwrap_or("Error"),
+                "detail": self.detail,
+            }],
+        });
+        (
+            self.status,
+            [(header::CONTENT_TYPE, MEDIA_TYPE)],
+            Json(body),
+        )
+            .into_response()
+    }
+}
+
+/// Wrap a top-level document in a response with the JSON:API media type.
+fn document(mut body: Value) -> Response {
+    body["jsonapi"] = json!({ "version": "1.1" });
+    ([(header::CONTENT_TYPE, MEDIA_TYPE)], Json(body)).into_response()
+}
+
+// -------------------------------------------------------------------------
+// Query parameters
+// -------------------------------------------------------------------------
+
+/// Parsed JSON:API query parameters.
+#[derive(Debug, Default)]
+struct JsonApiParams {
+    status: Option<i16>,
+    filters: Vec<ItemFieldPredicate>,
+    sort: Vec<ItemQuerySort>,
+    /// Relationship fields to include.
+    include: Vec<String>,
+    /// Sparse fieldsets by resource type.
+    fields: HashMap<String, Vec<String>>,
+    page_size: Option<i64>,
+    offset: i64,
+}
+
+impl JsonApiParams {
+    /// Parse query parameters for items of `def`.
+    ///
+    /// Filter, sort and include names must be fields of the type (or
+    /// sortable item columns); unknown names are rejected rather than
+    /// silently ignored.
+    fn parse(
+        pairs: &[(String, String)],
+        def: &ContentTypeDefinition,
+    ) -> Result<Self, JsonApiError> {
+        let mut params = Self::default();
+        for (key, value) in pairs {
+            if key == "sort" {
+                params.sort = parse_sort(value, def)?;
+            } else if key == "include" {
+                params.include = parse_include(value, def)?;
+            } else if let Some(path) = bracketed(key, "filter") {
+                params.parse_filter(&path, value, def)?;
+            } else if let Some(path) = bracketed(key, "fields") {
+                let [resource_type] = path.as_slice() else {
+                    return Err(JsonApiError::bad_request(format!(
+                        "invalid parameter '{key}'"
+                    )));
+                };
+                params
+                    .fields
+                    .insert(resource_type.to_string(), split_list(value));
+            } else if let Some(path) = bracketed(key, "page") {
+                match path.as_slice() {
+                    ["size"] => {
+                        let size =
+                            value
+                                .parse::<i64>()
+                                .ok()
+                                .filter(|s| *s > 0)
+                                .ok_or_else(|| {
+                                    JsonApiError::bad_request(
+                                        "page[size] must be a positive integer",
+                                    )
+                                })?;
+                        params.page_size = Some(size);
+                    }
+                    ["cursor"] => {
+                        params.offset = decode_cursor(value)
+                            .ok_or_else(|| JsonApiError::bad_request("invalid page[cursor]"))?;
+                    }
+                    _ => {
+                        return Err(JsonApiError::bad_request(format!(
+                            "invalid parameter '{key}'"
+                        )));
+                    }
+                }
+            }
+            // Other parameters are implementation-specific; ignore them.
+        }
+        Ok(params)
+    }
+
+    /// Add `filter[field]=value` or `filter[field][op]=value`.
+    fn parse_filter(
+        &mut self,
+        path: &[&str],
+        value: &str,
+        def: &ContentTypeDefinition,
+    ) -> Result<(), JsonApiError> {
+        let (field, op) = match path {
+            [field] => (*field, ItemQueryOp::Eq),
+            [field, op] => (*field, parse_op(op)?),
+            _ => return Err(JsonApiError::bad_request("invalid filter parameter")),
+        };
+
+        if field == "status" {
+            let status = value
+                .parse::<i16>()
+                .ok()
+                .filter(|s| (0..=1).contains(s) && op == ItemQueryOp::Eq)
+                .ok_or_else(|| JsonApiError::bad_request("filter[status] must be 0 or 1"))?;
+            self.status = Some(status);
+            return Ok(());
+        }
+        if !def.fields.iter().any(|f| f.field_name == field) {
+            return Err(JsonApiError::bad_request(format!(
+                "unknown filter field '{field}'"
+            )));
+        }
+
+        // Range comparisons are numeric when the value is a number; stored
+        // values are compared as text otherwise.
+        let value = match op {
+            ItemQueryOp::Lt | ItemQueryOp::Lte | ItemQueryOp::Gt | ItemQueryOp::Gte => {
+                serde_json::from_str::<serde_json::Number>(value)
+                    .map(Value::Number)
+                    .unwrap_or_else(|_| Value::String(value.to_string()))
+            }
+            _ => Value::String(value.to_string()),
+        };
+        self.filters.push(ItemFieldPredicate {
+            field: field.to_string(),
+            op,
+            value,
+        });
+        Ok(())
+    }
+
+    /// The sparse fieldset requested for a resource type, if any.
+    fn fieldset(&self, resource_type: &str) -> Option<&[String]> {
+        self.fields.get(resource_type).map(Vec::as_slice)
+    }
+}
+
+/// The bracketed path of a `family[a][b]` parameter.
+fn bracketed<'a>(key: &'a str, family: &str) -> Option<Vec<&'a str>> {
+    let mut rest = key.strip_prefix(family)?;
+    if rest.is_empty() {
+        return None;
+    }
+    let mut path = Vec::new();
+    while !rest.is_empty() {
+        let (segment, tail) = rest.strip_prefix('[')?.split_once(']')?;
+        path.push(segment);
+        rest = tail;
+    }
+    Some(path)
+}
+
+/// Split a comma-separated parameter value.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_op(op: &str) -> Result<ItemQueryOp, JsonApiError> {
+    serde_json::from_value(Value::String(op.to_string()))
+        .map_err(|_| JsonApiError::bad_request(format!("unknown filter operator '{op}'")))
+}
+
+/// Parse `sort=-changed,title`.
+fn parse_sort(
+    value: &str,
+    def: &ContentTypeDefinition,
+) -> Result<Vec<ItemQuerySort>, JsonApiError> {
+    split_list(value)
+        .into_iter()
+        .map(|key| {
+            let (field, direction) = match key.strip_prefix('-') {
+                Some(field) => (field.to_string(), SortDirection::Desc),
+                None => (key, SortDirection::Asc),
+            };
+            if SORT_COLUMNS.contains(&field.as_str())
+                || def.fields.iter().any(|f| f.field_name == field)
+            {
+                Ok(ItemQuerySort { field, direction })
+            } else {
+                Err(JsonApiError::bad_request(format!(
+                    "unknown sort field '{field}'"
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Parse `include=field_a,field_b`; only direct relationships are supported.
+fn parse_include(value: &str, def: &ContentTypeDefinition) -> Result<Vec<String>, JsonApiError> {
+    let relationships = reference_fields(def);
+    split_list(value)
+        .into_iter()
+        .map(|path| {
+            if relationships.contains_key(path.as_str()) {
+                Ok(path)
+            } else {
+                Err(JsonApiError::bad_request(format!(
+                    "'{path}' is not a relationship of item--{}",
+                    def.machine_name
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Encode a collection offset as an opaque cursor.
+fn encode_cursor(offset: i64) -> String {
+    URL_SAFE_NO_PAD.encode(offset.to_string())
+}
+
+fn decode_cursor(cursor: &str) -> Option<i64> {
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    String::from_utf8(bytes)
+        .ok()?
+        .parse::<i64>()
+        .ok()
+        .filter(|offset| *offset >= 0)
+}
+
+/// Link to a collection page: the request's parameters with `cursor`
+/// replacing `page[cursor]`.
+fn collection_link(path: &str, pairs: &[(String, String)], cursor: Option<&str>) -> String {
+    let mut query = url::form_urlencoded::Serializer::new(String::new());
+    for (key, value) in pairs.iter().filter(|(key, _)| key != "page[cursor]") {
+        query.append_pair(key, value);
+    }
+    if let Some(cursor) = cursor {
+        query.append_pair("page[cursor]", cursor);
+    }
+    let query = query.finish();
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{query}")
+    }
+}
+
+// -------------------------------------------------------------------------
+// Resource objects
+// -------------------------------------------------------------------------
+
+/// RecordReference fields of a type, mapped to their target type.
+fn reference_fields(def: &ContentTypeDefinition) -> HashMap<&str, &str> {
+    def.fields
+        .iter()
+        .filter_map(|f| match &f.field_type {
+            FieldType::RecordReference(target) => Some((f.field_name.as_str(), target.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn item_resource_type(item_type: &str) -> String {
+    format!("item--{item_type}")
+}
+
+fn item_link(item: &Item) -> String {
+    format!("/jsonapi/item/{}/{}", item.item_type, item.id)
+}
+
+/// Referenced item IDs stored in a field value (a UUID or list of UUIDs).
+fn referenced_ids(value: Option<&Value>) -> Vec<Uuid> {
+    match value {
+        Some(Value::String(s)) => s.parse().into_iter().collect(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str()?.parse().ok())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Resource linkage for a relationship field.
+fn linkage(item: &Item, field: &str, target: &str, to_many: bool) -> Value {
+    let identifiers: Vec<Value> = referenced_ids(item.fields.get(field))
+        .into_iter()
+        .map(|id| json!({ "type": item_resource_type(target), "id": id }))
+        .collect();
+    if to_many {
+        Value::Array(identifiers)
+    } else {
+        identifiers.into_iter().next().unwrap_or(Value::Null)
+    }
+}
+
+/// Build the resource object for an item.
+///
+/// `visible` lists the fields the user may view; `fieldset` restricts the
+/// attributes and relationships to a sparse fieldset.
+fn item_resource(
+    item: &Item,
+    def: Option<&ContentTypeDefinition>,
+    visible: &HashSet<String>,
+    fieldset: Option<&[String]>,
+) -> Value {
+    let wanted = |name: &str| fieldset.is_none_or(|set| set.iter().any(|f| f == name));
+    let references = def.map(reference_fields).unwrap_or_default();
+
+    let mut attributes = Map::new();
+    let base = [
+        ("title", json!(item.title)),
+        ("status", json!(item.status)),
+        ("created", json!(item.created)),
+        ("changed", json!(item.changed)),
+        ("promote", json!(item.promote)),
+        ("sticky", json!(item.sticky)),
+        ("language", json!(item.language)),
+    ];
+    for (name, value) in base {
+        if wanted(name) {
+            attributes.insert(name.to_string(), value);
+        }
+    }
+    if let Some(fields) = item.fields.as_object() {
+        for (name, value) in fields {
+            if visible.contains(name) && !references.contains_key(name.as_str()) && wanted(name) {
+                attributes.insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    let self_link = item_link(item);
+    let mut relationships = Map::new();
+    if let Some(def) = def {
+        for field in &def.fields {
+            let Some(target) = references.get(field.field_name.as_str()) else {
+                continue;
+            };
+            let name = &field.field_name;
+            if !visible.contains(name) || !wanted(name) {
+                continue;
+            }
+            relationships.insert(
+                name.clone(),
+                json!({
+                    "data": linkage(item, name, target, field.cardinality != 1),
+                    "links": {
+                        "self": format!("{self_link}/relationships/{name}"),
+                        "related": format!("{self_link}/{name}"),
+                    },
+                }),
+            );
+        }
+    }
+
+    let mut resource = json!({
+        "type": item_resource_type(&item.item_type),
+        "id": item.id,
+        "attributes": attributes,
+        "links": { "self": self_link },
+    });
+    if !relationships.is_empty() {
+        resource["relationships"] = Value::Object(relationships);
+    }
+    resource
+}
+
+fn content_type_resource(def: &ContentTypeDefinition) -> Value {
+    json!({
+        "type": CONTENT_TYPE_RESOURCE,
+        "id": def.machine_name,
+        "attributes": {
+            "label": def.label,
+            "description": def.description,
+            "title_label": def.title_label,
+            "fields": def.fields,
+        },
+        "links": {
+            "self": format!("/jsonapi/content_type/{}", def.machine_name),
+            "items": format!("/jsonapi/item/{}", def.machine_name),
+        },
+    })
+}
+
+/// Builds item resources for one request, caching per-type field access.
+struct ResourceBuilder<'a> {
+    state: &'a AppState,
+    user: &'a UserContext,
+    visible: HashMap<String, HashSet<String>>,
+}
+
+impl<'a> ResourceBuilder<'a> {
+    fn new(state: &'a AppState, user: &'a UserContext) -> Self {
+        Self {
+            state,
+            user,
+            visible: HashMap::new(),
+        }
+    }
+
+    async fn resource(&mut self, item: &Item, params: &JsonApiParams) -> Value {
+        let def = self.state.content_types().get(&item.item_type);
+        if !self.visible.contains_key(&item.item_type) {
+            let names: Vec<String> = match (&def, item.fields.as_object()) {
+                (Some(def), _) => def.fields.iter().map(|f| f.field_name.clone()).collect(),
+                (None, Some(fields)) => fields.keys().cloned().collect(),
+                (None, None) => Vec::new(),
+            };
+            let visible = self
+                .state
+                .items()
+                .accessible_fields(self.user, &item.item_type, &names, "view")
+                .await;
+            self.visible
+                .insert(item.item_type.clone(), visible.into_iter().collect());
+        }
+        let visible = self
+            .visible
+            .get(&item.item_type)
+            .cloned()
+            .unwrap_or_default();
+        item_resource(
+            item,
+            def.as_ref(),
+            &visible,
+            params.fieldset(&item_resource_type(&item.item_type)),
+        )
+    }
+
+    /// Load the items referenced by `field` that the user may view.
+    async fn related_items(&self, items: &[Item], field: &str) -> Result<Vec<Item>, JsonApiError> {
+        let mut seen = HashSet::new();
+        let mut related = Vec::new();
+        for item in items {
+            for id in referenced_ids(item.fields.get(field)) {
+                if !seen.insert(id) {
+                    continue;
+                }
+                let Some(target) = self
+                    .state
+                    .items()
+                    .load(id)
+                    .await
+                    .map_err(|e| JsonApiError::internal(e, "load related item"))?
+                else {
+                    continue;
+                };
+                if self.can_view(&target).await? {
+                    related.push(target);
+                }
+            }
+        }
+        Ok(related)
+    }
+
+    async fn can_view(&self, item: &Item) -> Result<bool, JsonApiError> {
+        self.state
+            .items()
+            .check_access(item, "view", self.user)
+            .await
+            .map_err(|e| JsonApiError::internal(e, "check item access"))
+    }
+
+    /// Resources for the `included` member.
+    async fn included(
+        &mut self,
+        items: &[Item],
+        params: &JsonApiParams,
+    ) -> Result<Vec<Value>, JsonApiError> {
+        let primary: HashSet<Uuid> = items.iter().map(|i| i.id).collect();
+        let mut seen = HashSet::new();
+        let mut included = Vec::new();
+        for field in &params.include {
+            for target in self.related_items(items, field).await? {
+                if primary.contains(&target.id) || !seen.insert(target.id) {
+                    continue;
+                }
+                included.push(self.resource(&target, params).await);
+            }
+        }
+        Ok(included)
+    }
+}
+
+// -------------------------------------------------------------------------
+// Handlers
+// -------------------------------------------------------------------------
+
+/// `GET /jsonapi`
+async fn entry_point(State(state): State<AppState>) -> Response {
+    let mut links = Map::new();
+    links.insert("self".to_string(), json!("/jsonapi"));
+    links.insert(
+        CONTENT_TYPE_RESOURCE.to_string(),
+        json!("/jsonapi/content_type"),
+    );
+    let mut names = state.content_types().type_names();
+    names.sort();
+    for name in names {
+        links.insert(
+            item_resource_type(&name),
+            json!(format!("/jsonapi/item/{name}")),
+        );
+    }
+    document(json!({ "data": [], "links": links }))
+}
+
+/// `GET /jsonapi/content_type`
+async fn list_content_types(State(state): State<AppState>) -> Response {
+    let mut defs = state.content_types().list();
+    defs.sort_by(|a, b| a.machine_name.cmp(&b.machine_name));
+    let data: Vec<Value> = defs.iter().map(content_type_resource).collect();
+    document(json!({
+        "data": data,
+        "links": { "self": "/jsonapi/content_type" },
+    }))
+}
+
+/// `GET /jsonapi/content_type/{name}`
+async fn get_content_type(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, JsonApiError> {
+    let def = content_type(&state, &name)?;
+    Ok(document(json!({ "data": content_type_resource(&def) })))
+}
+
+fn content_type(state: &AppState, name: &str) -> Result<ContentTypeDefinition, JsonApiError> {
+    state
+        .content_types()
+        .get(name)
+        .ok_or_else(|| JsonApiError::not_found(format!("unknown content type '{name}'")))
+}
+
+/// `GET /jsonapi/item/{type}`
+async fn list_items(
+    State(state): State<AppState>,
+    session: Session,
+    Path(item_type): Path<String>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Result<Response, JsonApiError> {
+    let def = content_type(&state, &item_type)?;
+    let params = JsonApiParams::parse(&pairs, &def)?;
+    let user = get_user_context(&session, &state).await;
+
+    let size = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Api, params.page_size)
+        .limit
+        .min(ITEM_QUERY_MAX_LIMIT);
+    let query = ItemQuery {
+        status: params.status,
+        fields: params.filters.clone(),
+        sort: params.sort.clone(),
+        ..ItemQuery::new()
+            .item_type(&item_type)
+            .limit(size)
+            .offset(params.offset)
+    };
+    let access = ItemQueryAccess::for_user(&user, false);
+    let items = item_query::build(
+        &query,
+        vec![LIVE_STAGE_ID],
+        access,
+        access.grants_user(&user),
+    )
+    .map_err(|e| JsonApiError::bad_request(e.to_string()))?
+    .fetch(state.db())
+    .await
+    .map_err(|e| JsonApiError::internal(e, "list items"))?;
+
+    let mut builder = ResourceBuilder::new(&state, &user);
+    let mut data = Vec::with_capacity(items.len());
+    for item in &items {
+        data.push(builder.resource(item, &params).await);
+    }
+    let included = builder.included(&items, &params).await?;
+
+    let path = format!("/jsonapi/item/{item_type}");
+    let mut links = json!({ "self": collection_link(&path, &pairs, None) });
+    if params.offset > 0 {
+        links["self"] = json!(collection_link(
+            &path,
+            &pairs,
+            Some(&encode_cursor(params.offset))
+        ));
+    }
+    // A full page may have more after it.
+    if items.len() as i64 == size {
+        links["next"] = json!(collection_link(
+            &path,
+            &pairs,
+            Some(&encode_cursor(params.offset + size))
+        ));
+    }
+
+    let mut body = json!({ "data": data, "links": links });
+    if !params.include.is_empty() {
+        body["included"] = Value::Array(included);
+    }
+    Ok(document(body))
+}
+
+/// Load an item of `item_type` the user may view.
+async fn viewable_item(
+    state: &AppState,
+    user: &UserContext,
+    item_type: &str,
+    id: Uuid,
+) -> Result<Item, JsonApiError> {
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| JsonApiError::internal(e, "load item"))?
+        .filter(|item| item.item_type == item_type)
+        .ok_or_else(|| JsonApiError::not_found(format!("item--{item_type} '{id}' not found")))?;
+    let allowed = state
+        .items()
+        .check_access(&item, "view", user)
+        .await
+        .map_err(|e| JsonApiError::internal(e, "check item access"))?;
+    if !allowed {
+        return Err(JsonApiError::forbidden());
+    }
+    Ok(item)
+}
+
+/// `GET /jsonapi/item/{type}/{id}`
+async fn get_item(
+    State(state): State<AppState>,
+    session: Session,
+    Path((item_type, id)): Path<(String, Uuid)>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Result<Response, JsonApiError> {
+    let def = content_type(&state, &item_type)?;
+    let params = JsonApiParams::parse(&pairs, &def)?;
+    let user = get_user_context(&session, &state).await;
+    let item = viewable_item(&state, &user, &item_type, id).await?;
+
+    let mut builder = ResourceBuilder::new(&state, &user);
+    let data = builder.resource(&item, &params).await;
+    let included = builder
+        .included(std::slice::from_ref(&item), &params)
+        .await?;
+
+    let mut body = json!({ "data": data, "links": { "self": item_link(&item) } });
+    if !params.include.is_empty() {
+        body["included"] = Value::Array(included);
+    }
+    Ok(document(body))
+}
+
+/// Resolve a relationship field of `def`: its target type and whether it
+/// is to-many.
+fn relationship<'a>(
+    def: &'a ContentTypeDefinition,
+    field: &str,
+) -> Result<(&'a str, bool), JsonApiError> {
+    def.fields
+        .iter()
+        .find_map(|f| match &f.field_type {
+            FieldType::RecordReference(target) if f.field_name == field => {
+                Some((target.as_str(), f.cardinality != 1))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            JsonApiError::not_found(format!(
+                "'{field}' is not a relationship of item--{}",
+                def.machine_name
+            ))
+        })
+}
+
+/// `GET /jsonapi/item/{type}/{id}/{field}`
+async fn get_related(
+    State(state): State<AppState>,
+    session: Session,
+    Path((item_type, id, field)): Path<(String, Uuid, String)>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Result<Response, JsonApiError> {
+    let def = content_type(&state, &item_type)?;
+    let (_, to_many) = relationship(&def, &field)?;
+    let user = get_user_context(&session, &state).await;
+    let item = viewable_item(&state, &user, &item_type, id).await?;
+
+    let mut builder = ResourceBuilder::new(&state, &user);
+    let related = builder
+        .related_items(std::slice::from_ref(&item), &field)
+        .await?;
+    // Sparse fieldsets apply; other parameters refer to the related type.
+    let params = JsonApiParams {
+        fields: JsonApiParams::parse(&pairs, &def)?.fields,
+        ..JsonApiParams::default()
+    };
+    let mut data = Vec::with_capacity(related.len());
+    for target in &related {
+        data.push(builder.resource(target, &params).await);
+    }
+    let data = if to_many {
+        Value::Array(data)
+    } else {
+        data.into_iter().next().unwrap_or(Value::Null)
+    };
+
+    Ok(document(json!({
+        "data": data,
+        "links": { "self": format!("{}/{field}", item_link(&item)) },
+    })))
+}
+
+/// `GET /jsonapi/item/{type}/{id}/relationships/{field}`
+async fn get_relationship(
+    State(state): State<AppState>,
+    session: Session,
+    Path((item_type, id, field)): Path<(String, Uuid, String)>,
+) -> Result<Response, JsonApiError> {
+    let def = content_type(&state, &item_type)?;
+    let (target, to_many) = relationship(&def, &field)?;
+    let user = get_user_context(&session, &state).await;
+    let item = viewable_item(&state, &user, &item_type, id).await?;
+
+    let self_link = item_link(&item);
+    Ok(document(json!({
+        "data": linkage(&item, &field, target, to_many),
+        "links": {
+            "self": format!("{self_link}/relationships/{field}"),
+            "related": format!("{self_link}/{field}"),
+        },
+    })))
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::models::DEFAULT_TENANT_ID;
+    use trovato_sdk::types::FieldDefinition;
+
+    fn article() -> ContentTypeDefinition {
+        ContentTypeDefinition {
+            machine_name: "article".to_string(),
+            label: "Article".to_string(),
+            description: String::new(),
+            title_label: None,
+            fields: vec![
+                FieldDefinition::new("field_body", FieldType::TextLong),
+                FieldDefinition::new("field_rank", FieldType::Integer),
+                FieldDefinition::new("field_author", FieldType::RecordReference("person".into())),
+                FieldDefinition::new("field_tags", FieldType::RecordReference("tag".into()))
+                    .cardinality(-1),
+            ],
+            cache: None,
+            field_groups: Vec::new(),
+            extends: None,
+            template: false,
+        }
+    }
+
+    fn pairs(query: &str) -> Vec<(String, String)> {
+        url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect()
+    }
+
+    fn item(fields: Value) -> Item {
+        Item {
+            id: Uuid::nil(),
+            current_revision_id: None,
+            item_type: "article".to_string(),
+            title: "Hello".to_string(),
+            author_id: Uuid::nil(),
+            status: 1,
+            created: 0,
+            changed: 0,
+            promote: 0,
+            sticky: 0,
+            fields,
+            stage_id: LIVE_STAGE_ID,
+            language: "en".to_string(),
+            item_group_id: Uuid::nil(),
+            retention_days: None,
+            moderation_state: None,
+            tenant_id: DEFAULT_TENANT_ID,
+        }
+    }
+
+    #[test]
+    fn bracketed_parameters_split_into_paths() {
+        assert_eq!(bracketed("filter[a]", "filter"), Some(vec!["a"]));
+        assert_eq!(
+            bracketed("filter[a][gte]", "filter"),
+            Some(vec!["a", "gte"])
+        );
+        assert_eq!(bracketed("filter", "filter"), None);
+        assert_eq!(bracketed("filter[a", "filter"), None);
+        assert_eq!(bracketed("filters[a]", "filter"), None);
+    }
+
+    #[test]
+    fn parses_filters_sort_include_and_pages() {
+        let query = "filter[status]=1&filter[field_body]=rust&filter[field_rank][gte]=3\
+                     &sort=-changed,field_rank&include=field_tags\
+                     &fields[item--article]=title,field_tags&page[size]=10";
+        let mut query = query.replace(char::is_whitespace, "");
+        query.push_str(&format!("&page[cursor]={}", encode_cursor(20)));
+        let params = JsonApiParams::parse(&pairs(&query), &article()).unwrap();
+
+        assert_eq!(params.status, Some(1));
+        assert_eq!(params.filters.len(), 2);
+        assert_eq!(params.filters[0].op, ItemQueryOp::Eq);
+        assert_eq!(params.filters[0].value, json!("rust"));
+        assert_eq!(params.filters[1].op, ItemQueryOp::Gte);
+        assert_eq!(params.filters[1].value, json!(3));
+        assert_eq!(params.sort[0].field, "changed");
+        assert_eq!(params.sort[0].direction, SortDirection::Desc);
+        assert_eq!(params.sort[1].direction, SortDirection::Asc);
+        assert_eq!(params.include, vec!["field_tags"]);
+        assert_eq!(
+            params.fieldset("item--article").unwrap(),
+            ["title", "field_tags"]
+        );
+        assert_eq!(params.page_size, Some(10));
+        assert_eq!(params.offset, 20);
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let def = article();
+        for query in [
+            "filter[field_missing]=1",
+            "filter[field_rank][like]=1",
+            "filter[status]=2",
+            "sort=field_missing",
+            "include=field_body",
+            "include=field_tags.field_owner",
+            "page[size]=0",
+            "page[cursor]=not-a-cursor",
+        ] {
+            let err = JsonApiParams::parse(&pairs(query), &def).unwrap_err();
+            assert_eq!(err.status, StatusCode::BAD_REQUEST, "{query}");
+        }
+    }
+
+    #[test]
+    fn cursors_round_trip() {
+        assert_eq!(decode_cursor(&encode_cursor(0)), Some(0));
+        assert_eq!(decode_cursor(&encode_cursor(150)), Some(150));
+        assert_eq!(decode_cursor(&URL_SAFE_NO_PAD.encode("-5")), None);
+    }
+
+    #[test]
+    fn collection_links_replace_the_cursor() {
+        let query = pairs("filter[status]=1&page[cursor]=abc");
+        assert_eq!(
+            collection_link("/jsonapi/item/article", &query, Some("def")),
+            "/jsonapi/item/article?filter%5Bstatus%5D=1&page%5Bcursor%5D=def"
+        );
+        assert_eq!(
+            collection_link("/jsonapi/item/article", &[], None),
+            "/jsonapi/item/article"
+        );
+    }
+
+    #[test]
+    fn item_resources_split_attributes_and_relationships() {
+        let author = Uuid::now_v7();
+        let tag = Uuid::now_v7();
+        let item = item(json!({
+            "field_body": "Text",
+            "field_rank": 3,
+            "field_author": author.to_string(),
+            "field_tags": [tag.to_string()],
+            "field_secret": "hidden",
+        }));
+        let def = article();
+        let visible: HashSet<String> = ["field_body", "field_rank", "field_author", "field_tags"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let resource = item_resource(&item, Some(&def), &visible, None);
+        assert_eq!(resource["type"], "item--article");
+        assert_eq!(resource["attributes"]["title"], "Hello");
+        assert_eq!(resource["attributes"]["field_body"], "Text");
+        assert!(resource["attributes"].get("field_author").is_none());
+        assert!(resource["attributes"].get("field_secret").is_none());
+        let relationships = &resource["relationships"];
+        assert_eq!(
+            relationships["field_author"]["data"]["type"],
+            "item--person"
+        );
+        assert_eq!(
+            relationships["field_author"]["data"]["id"],
+            author.to_string()
+        );
+        assert_eq!(relationships["field_tags"]["data"][0]["type"], "item--tag");
+        assert_eq!(
+            relationships["field_tags"]["links"]["related"],
+            format!("/jsonapi/item/article/{}/field_tags", Uuid::nil())
+        );
+
+        let fieldset = ["title".to_string(), "field_tags".to_string()];
+        let sparse = item_resource(&item, Some(&def), &visible, Some(&fieldset));
+        assert_eq!(sparse["attributes"].as_object().unwrap().len(), 1);
+        assert!(sparse["relationships"].get("field_author").is_none());
+        assert!(sparse["relationships"].get("field_tags").is_some());
+    }
+
+    #[test]
+    fn empty_relationships_use_null_or_empty_list() {
+        let item = item(json!({}));
+        assert_eq!(linkage(&item, "field_author", "person", false), Value::Null);
+        assert_eq!(linkage(&item, "field_tags", "tag", true), json!([]));
+    }
+}
@@ -0,0 +1,61 @@
+//! Menu tree API.
+//!
+//! `GET /api/menu/{name}` returns the nested plugin menu `name` for the
+//! session user, with the active trail for the `path` query parameter.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::menu::MenuTree;
+use crate::routes::auth::SESSION_USER_ID;
+use crate::state::AppState;
+
+use super::helpers::user_menu_tree;
+
+/// Create the menu API router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/menu/{name}", get(menu_tree))
+}
+
+/// Query parameters for the menu tree endpoint.
+#[derive(Debug, Deserialize)]
+struct MenuTreeQuery {
+    /// Request path used to compute the active trail.
+    #[serde(default = "default_path")]
+    path: String,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Get a menu tree.
+///
+/// GET /api/menu/{name}?path=/current/page
+async fn menu_tree(
+    State(state): State<AppState>,
+    session: Session,
+    Path(name): Path<String>,
+    Query(query): Query<MenuTreeQuery>,
+) -> Result<Json<MenuTree>, AppError> {
+    let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
+    let user = match user_id {
+        Some(id) => state
+            .users()
+            .find_by_id(id)
+            .await
+            .map_err(|e| AppError::internal_ctx(e, "load menu user"))?
+            .filter(|u| u.is_active()),
+        None => None,
+    };
+
+    user_menu_tree(&state, user.as_ref(), &name, &query.path)
+        .await
+        .map(Json)
+        .ok_or_else(|| AppError::not_found_id("menu", name))
+}
@@ -28,6 +28,7 @@ async fn metrics(State(state): State<AppState>) -> Response {
     m.db_pool_active.set(i64::from(pool_active));
     m.db_pool_max
         .set(i64::from(state.db_pool_max_connections()));
+    m.record_db_pools(&state.db_pools().status(state.postgres_healthy().await));
 
     // Update circuit breaker state gauges (0=closed, 1=open, 2=half_open)
     m.ai_circuit_breaker_state.set(breaker_state_value(
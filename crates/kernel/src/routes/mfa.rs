@@ -0,0 +1,532 @@
+//! Two-factor authentication routes.
+//!
+//! - `/user/login/mfa` — the code challenge after a correct password, or
+//!   forced enrollment when the user's role requires 2FA.
+//! - `/user/mfa` — self-service setup, recovery codes and removal.
+//!
+//! See [`crate::services::mfa`] for the TOTP and storage details.
+
+use axum::{
+    Form, Router,
+    extract::State,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::form::csrf::generate_csrf_token;
+use crate::middleware::ClientIp;
+use crate::models::{SiteConfig, User};
+use crate::routes::auth::{
+    LoginError, complete_login, get_current_user, render_login_error, verify_mfa_code,
+};
+use crate::routes::helpers::{render_server_error, require_csrf};
+use crate::services::mfa::{self, UserMfa};
+use crate::state::AppState;
+
+/// Session key for a login waiting on its second factor.
+const SESSION_MFA_PENDING: &str = "mfa_pending";
+
+/// How long a password-verified login may wait for its code.
+const PENDING_TTL_SECS: i64 = 300;
+
+/// A login whose password was accepted but which still needs a code.
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingLogin {
+    user_id: Uuid,
+    /// Username as typed, so failures share the password lockout counter.
+    username: String,
+    remember_me: bool,
+    /// The user must set up an authenticator before logging in.
+    enroll: bool,
+    /// Unix timestamp of the password check.
+    started: i64,
+}
+
+/// Form carrying a single code.
+#[derive(Debug, Deserialize)]
+struct CodeForm {
+    #[serde(rename = "_token")]
+    token: String,
+    #[serde(default)]
+    code: String,
+}
+
+/// Create the two-factor authentication router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/user/login/mfa",
+            get(challenge_form).post(challenge_submit),
+        )
+        .route("/user/mfa", get(settings))
+        .route("/user/mfa/setup", post(setup))
+        .route("/user/mfa/confirm", post(confirm))
+        .route("/user/mfa/recovery-codes", post(regenerate_recovery_codes))
+        .route("/user/mfa/disable", post(disable))
+}
+
+// =============================================================================
+// Login challenge
+// =============================================================================
+
+/// Park a login whose first factor passed and send the user to the code
+/// challenge.
+///
+/// `username` is the name the login was made with, so failed codes share
+/// its lockout counter.
+pub(crate) async fn start_challenge(
+    state: &AppState,
+    session: &Session,
+    username: &str,
+    remember_me: bool,
+    user_id: Uuid,
+    enroll: bool,
+) -> Response {
+    let pending = PendingLogin {
+        user_id,
+        username: username.to_string(),
+        remember_me,
+        enroll,
+        started: chrono::Utc::now().timestamp(),
+    };
+    if let Err(e) = session.insert(SESSION_MFA_PENDING, &pending).await {
+        tracing::error!(error = %e, "failed to store pending 2FA login");
+        return render_login_error(state, session, "Internal server error").await;
+    }
+    Redirect::to("/user/login/mfa").into_response()
+}
+
+/// Load the pending login, dropping it once expired.
+async fn load_pending(session: &Session) -> Option<PendingLogin> {
+    let pending: PendingLogin = session.get(SESSION_MFA_PENDING).await.ok().flatten()?;
+    if chrono::Utc::now().timestamp() - pending.started > PENDING_TTL_SECS {
+        clear_pending(session).await;
+        return None;
+    }
+    Some(pending)
+}
+
+async fn clear_pending(session: &Session) {
+    if let Err(e) = session.remove::<PendingLogin>(SESSION_MFA_PENDING).await {
+        tracing::warn!(error = %e, "failed to clear pending 2FA login");
+    }
+}
+
+/// Code challenge (or forced enrollment) after a correct password.
+///
+/// GET /user/login/mfa
+async fn challenge_form(State(state): State<AppState>, session: Session) -> Response {
+    let Some(pending) = load_pending(&session).await else {
+        return Redirect::to("/user/login").into_response();
+    };
+    render_challenge(&state, &session, &pending, None).await
+}
+
+/// Check the code and finish logging in.
+///
+/// POST /user/login/mfa
+async fn challenge_submit(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    session: Session,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    let client_id = client_ip.key();
+    if let Err(retry_after) = state.rate_limiter().check("login", &client_id).await {
+        return crate::middleware::rate_limit_response(retry_after);
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let Some(pending) = load_pending(&session).await else {
+        return render_login_error(&state, &session, "Your login expired. Please log in again.")
+            .await;
+    };
+
+    let user = match state.users().find_by_id(pending.user_id).await {
+        Ok(Some(user)) if user.is_active() => user,
+        Ok(_) => {
+            clear_pending(&session).await;
+            return render_login_error(&state, &session, LoginError::InvalidCredentials.message())
+                .await;
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load user for 2FA");
+            return render_server_error("Failed to complete login.");
+        }
+    };
+
+    let mut recovery_codes = None;
+    if pending.enroll {
+        match UserMfa::confirm_enrollment(state.db(), user.id, &form.code).await {
+            Ok(Some(codes)) => recovery_codes = Some(codes),
+            Ok(None) => {
+                return render_challenge(
+                    &state,
+                    &session,
+                    &pending,
+                    Some("That code is not valid. Check the time on your device and try again."),
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, user_id = %user.id, "failed to confirm 2FA enrollment");
+                return render_server_error("Failed to set up two-factor authentication.");
+            }
+        }
+        tracing::info!(user_id = %user.id, "2FA enrolled at login");
+    } else {
+        match verify_mfa_code(&state, &pending.username, user.id, &form.code).await {
+            Ok(()) => {}
+            Err(LoginError::InvalidMfaCode) => {
+                return render_challenge(
+                    &state,
+                    &session,
+                    &pending,
+                    Some(LoginError::InvalidMfaCode.message()),
+                )
+                .await;
+            }
+            Err(e) => {
+                clear_pending(&session).await;
+                return render_login_error(&state, &session, e.message()).await;
+            }
+        }
+    }
+
+    clear_pending(&session).await;
+    if let Err(e) = complete_login(
+        &state,
+        &session,
+        &user,
+        &pending.username,
+        pending.remember_me,
+    )
+    .await
+    {
+        return render_login_error(&state, &session, e.message()).await;
+    }
+
+    match recovery_codes {
+        Some(codes) => {
+            let page = SettingsPage {
+                recovery_codes: Some(codes),
+                success: Some("Two-factor authentication is now enabled."),
+                ..Default::default()
+            };
+            render_settings(&state, &session, &user, page).await
+        }
+        None => Redirect::to("/").into_response(),
+    }
+}
+
+/// Render the challenge page, with the enrollment secret when enrolling.
+async fn render_challenge(
+    state: &AppState,
+    session: &Session,
+    pending: &PendingLogin,
+    error: Option<&str>,
+) -> Response {
+    let mut context = tera::Context::new();
+    context.insert("csrf_token", &generate_csrf_token(session).await);
+    context.insert("enroll", &pending.enroll);
+    if let Some(error) = error {
+        context.insert("error", error);
+    }
+
+    if pending.enroll {
+        // Reuse a pending secret so reloading the page does not invalidate
+        // an authenticator the user has already added.
+        let enrollment = match UserMfa::find(state.db(), pending.user_id).await {
+            Ok(Some(mfa)) if !mfa.enabled => Ok(mfa),
+            _ => UserMfa::begin_enrollment(state.db(), pending.user_id).await,
+        };
+        let enrollment = match enrollment {
+            Ok(mfa) => mfa,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to start 2FA enrollment");
+                return render_server_error("Failed to set up two-factor authentication.");
+            }
+        };
+        let uri = provisioning_uri(state, &pending.username, &enrollment.secret).await;
+        context.insert("secret", &enrollment.secret);
+        context.insert("provisioning_uri", &uri);
+    }
+
+    super::helpers::inject_site_context(state, session, &mut context, "/user/login/mfa").await;
+
+    match state.theme().tera().render("user/login-mfa.html", &context) {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to render 2FA challenge");
+            render_server_error("Failed to render the login page.")
+        }
+    }
+}
+
+/// Provisioning URI labelled with the site name.
+async fn provisioning_uri(state: &AppState, account: &str, secret: &str) -> String {
+    let issuer = SiteConfig::site_name(state.db())
+        .await
+        .unwrap_or_else(|_| "Trovato".to_string());
+    mfa::provisioning_uri(&issuer, account, secret)
+}
+
+// =============================================================================
+// Self-service settings
+// =============================================================================
+
+/// Transient parts of the settings page.
+#[derive(Default)]
+struct SettingsPage<'a> {
+    /// Pending enrollment to show (secret, provisioning URI).
+    setup: Option<(String, String)>,
+    /// Freshly issued recovery codes, shown once.
+    recovery_codes: Option<Vec<String>>,
+    error: Option<&'a str>,
+    success: Option<&'a str>,
+}
+
+/// Two-factor authentication settings.
+///
+/// GET /user/mfa
+async fn settings(State(state): State<AppState>, session: Session) -> Response {
+    let user = match get_current_user(&state, &session).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    render_settings(&state, &session, &user, SettingsPage::default()).await
+}
+
+/// Start enrollment with a new secret.
+///
+/// POST /user/mfa/setup
+async fn setup(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    let user = match get_current_user(&state, &session).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let page = match UserMfa::begin_enrollment(state.db(), user.id).await {
+        Ok(mfa) => SettingsPage {
+            setup: Some((
+                provisioning_uri(&state, &user.name, &mfa.secret).await,
+                mfa.secret,
+            )),
+            ..Default::default()
+        },
+        Err(e) => {
+            tracing::warn!(error = %e, user_id = %user.id, "2FA enrollment not started");
+            SettingsPage {
+                error: Some("Two-factor authentication is already enabled."),
+                ..Default::default()
+            }
+        }
+    };
+    render_settings(&state, &session, &user, page).await
+}
+
+/// Confirm enrollment with a first code.
+///
+/// POST /user/mfa/confirm
+async fn confirm(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    let user = match get_current_user(&state, &session).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let page = match UserMfa::confirm_enrollment(state.db(), user.id, &form.code).await {
+        Ok(Some(codes)) => {
+            tracing::info!(user_id = %user.id, "2FA enabled");
+            SettingsPage {
+                recovery_codes: Some(codes),
+                success: Some("Two-factor authentication is now enabled."),
+                ..Default::default()
+            }
+        }
+        Ok(None) => {
+            let setup = match UserMfa::find(state.db(), user.id).await {
+                Ok(Some(mfa)) if !mfa.enabled => Some((
+                    provisioning_uri(&state, &user.name, &mfa.secret).await,
+                    mfa.secret,
+                )),
+                _ => None,
+            };
+            SettingsPage {
+                setup,
+                error: Some("That code is not valid. Check the time on your device and try again."),
+                ..Default::default()
+            }
+        }
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to confirm 2FA enrollment");
+            return render_server_error("Failed to set up two-factor authentication.");
+        }
+    };
+    render_settings(&state, &session, &user, page).await
+}
+
+/// Replace the recovery codes. Requires a current code.
+///
+/// POST /user/mfa/recovery-codes
+async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    let user = match get_current_user(&state, &session).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    if let Err(e) = verify_mfa_code(&state, &user.name, user.id, &form.code).await {
+        let page = SettingsPage {
+            error: Some(e.message()),
+            ..Default::default()
+        };
+        return render_settings(&state, &session, &user, page).await;
+    }
+
+    match UserMfa::replace_recovery_codes(state.db(), user.id).await {
+        Ok(codes) => {
+            tracing::info!(user_id = %user.id, "2FA recovery codes regenerated");
+            let page = SettingsPage {
+                recovery_codes: Some(codes),
+                success: Some("New recovery codes generated. The old codes no longer work."),
+                ..Default::default()
+            };
+            render_settings(&state, &session, &user, page).await
+        }
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to regenerate recovery codes");
+            render_server_error("Failed to generate recovery codes.")
+        }
+    }
+}
+
+/// Turn two-factor authentication off. Requires a current code and is
+/// refused when one of the user's roles requires 2FA.
+///
+/// POST /user/mfa/disable
+async fn disable(
+    State(state): State<AppState>,
+    session: Session,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    let user = match get_current_user(&state, &session).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    if mfa::is_required_for(state.db(), &user)
+        .await
+        .unwrap_or(true)
+    {
+        let page = SettingsPage {
+            error: Some("Your role requires two-factor authentication."),
+            ..Default::default()
+        };
+        return render_settings(&state, &session, &user, page).await;
+    }
+
+    if let Err(e) = verify_mfa_code(&state, &user.name, user.id, &form.code).await {
+        let page = SettingsPage {
+            error: Some(e.message()),
+            ..Default::default()
+        };
+        return render_settings(&state, &session, &user, page).await;
+    }
+
+    match UserMfa::disable(state.db(), user.id).await {
+        Ok(_) => {
+            tracing::info!(user_id = %user.id, "2FA disabled");
+            let page = SettingsPage {
+                success: Some("Two-factor authentication has been turned off."),
+                ..Default::default()
+            };
+            render_settings(&state, &session, &user, page).await
+        }
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to disable 2FA");
+            render_server_error("Failed to turn off two-factor authentication.")
+        }
+    }
+}
+
+/// Render the settings page.
+async fn render_settings(
+    state: &AppState,
+    session: &Session,
+    user: &User,
+    page: SettingsPage<'_>,
+) -> Response {
+    let (enabled, remaining) = match UserMfa::find(state.db(), user.id).await {
+        Ok(Some(mfa)) if mfa.enabled => (
+            true,
+            UserMfa::remaining_recovery_codes(state.db(), user.id)
+                .await
+                .unwrap_or(0),
+        ),
+        Ok(_) => (false, 0),
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to load 2FA status");
+            return render_server_error("Failed to load two-factor authentication settings.");
+        }
+    };
+    let required = mfa::is_required_for(state.db(), user)
+        .await
+        .unwrap_or(false);
+
+    let mut context = tera::Context::new();
+    context.insert("csrf_token", &generate_csrf_token(session).await);
+    context.insert("enabled", &enabled);
+    context.insert("required", &required);
+    context.insert("remaining_recovery_codes", &remaining);
+    if let Some((uri, secret)) = &page.setup {
+        context.insert("provisioning_uri", uri);
+        context.insert("secret", secret);
+    }
+    if let Some(codes) = &page.recovery_codes {
+        context.insert("recovery_codes", codes);
+    }
+    if let Some(error) = page.error {
+        context.insert("error", error);
+    }
+    if let Some(success) = page.success {
+        context.insert("success", success);
+    }
+    super::helpers::inject_site_context(state, session, &mut context, "/user/mfa").await;
+
+    match state.theme().tera().render("user/mfa.html", &context) {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to render 2FA settings");
+            render_server_error("Failed to render two-factor authentication settings.")
+        }
+    }
+}
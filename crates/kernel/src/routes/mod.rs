@@ -7,42 +7,69 @@ pub mod admin_ai_features;
 pub mod admin_ai_provider;
 pub mod admin_alias;
 pub mod admin_config;
+pub mod admin_config_translation;
+pub mod admin_contact;
 pub mod admin_content;
 pub mod admin_content_type;
+pub mod admin_lockout;
+pub mod admin_mfa;
 pub mod admin_pathauto;
+pub mod admin_search;
+pub mod admin_stage;
 pub mod admin_taxonomy;
 pub mod admin_translation;
 pub mod admin_user;
+pub mod api_admin_content;
 pub mod api_ai_assist;
 pub mod api_chat;
+pub mod api_people;
 pub mod api_search;
 pub mod api_token;
 pub mod api_v1;
 pub mod auth;
+pub mod author;
+pub mod backup;
 pub mod batch;
 pub mod category;
 pub mod comment;
+pub mod contact;
 pub mod cron;
+pub mod deprecation;
 pub mod file;
+pub mod flag;
 pub mod front;
 pub mod gather;
 pub mod gather_admin;
 pub mod gather_routes;
+pub mod goose;
 pub mod health;
 pub mod helpers;
 pub mod image_style;
 pub mod install;
 pub mod item;
+pub mod jsonapi;
 pub mod lock;
+pub mod menu;
 pub mod metrics;
+pub mod mfa;
 pub mod oauth;
+pub mod oidc;
 pub mod password_reset;
 pub mod plugin_admin;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+pub mod read_log;
+pub mod read_only;
 pub mod route_metadata;
+pub mod saved_search;
+pub mod scheduled_publishing;
+pub mod scheduled_update;
 pub mod search;
 pub mod sitemap;
 pub mod static_files;
+pub mod status_report;
 pub mod tile_admin;
+pub mod webhook;
 
 use axum::Router;
 
@@ -72,11 +99,20 @@ macro_rules! plugin_gate {
 
 plugin_gate!(gate_categories, "trovato_categories");
 plugin_gate!(gate_comments, "trovato_comments");
+plugin_gate!(gate_config_translation, "trovato_config_translation");
+plugin_gate!(gate_contact, "trovato_contact");
 plugin_gate!(gate_content_locking, "trovato_content_locking");
 plugin_gate!(gate_content_translation, "trovato_content_translation");
+plugin_gate!(gate_flags, "trovato_flags");
 plugin_gate!(gate_image_styles, "trovato_image_styles");
 plugin_gate!(gate_oauth2, "trovato_oauth2");
+plugin_gate!(gate_oidc, "trovato_oidc");
+plugin_gate!(gate_read_log, "trovato_read_log");
+plugin_gate!(gate_saved_searches, "trovato_saved_searches");
+plugin_gate!(gate_scheduled_publishing, "trovato_scheduled_publishing");
+plugin_gate!(gate_scheduled_updates, "trovato_scheduled_updates");
 plugin_gate!(gate_block_editor, "trovato_block_editor");
+plugin_gate!(gate_goose, "goose");
 
 /// Plugin names that are runtime-gated in [`gated_plugin_routes`].
 ///
@@ -85,11 +121,20 @@ plugin_gate!(gate_block_editor, "trovato_block_editor");
 pub(crate) const RUNTIME_GATED_NAMES: &[&str] = &[
     "trovato_categories",
     "trovato_comments",
+    "trovato_config_translation",
+    "trovato_contact",
     "trovato_content_locking",
     "trovato_content_translation",
+    "trovato_flags",
     "trovato_image_styles",
     "trovato_oauth2",
+    "trovato_oidc",
+    "trovato_read_log",
+    "trovato_saved_searches",
+    "trovato_scheduled_publishing",
+    "trovato_scheduled_updates",
     "trovato_block_editor",
+    "goose",
 ];
 
 /// Build the router fragment for plugin-gated routes.
@@ -120,6 +165,17 @@ pub fn gated_plugin_routes(state: &AppState) -> Router<AppState> {
                     gate_comments,
                 )),
         )
+        .merge(admin_config_translation::router().route_layer(
+            axum::middleware::from_fn_with_state(state.clone(), gate_config_translation),
+        ))
+        .merge(
+            contact::router()
+                .merge(admin_contact::router())
+                .route_layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    gate_contact,
+                )),
+        )
         .merge(
             lock::router().route_layer(axum::middleware::from_fn_with_state(
                 state.clone(),
@@ -132,6 +188,12 @@ pub fn gated_plugin_routes(state: &AppState) -> Router<AppState> {
                 gate_content_translation,
             )),
         )
+        .merge(
+            flag::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_flags,
+            )),
+        )
         .merge(
             image_style::router().route_layer(axum::middleware::from_fn_with_state(
                 state.clone(),
@@ -144,10 +206,46 @@ pub fn gated_plugin_routes(state: &AppState) -> Router<AppState> {
                 gate_oauth2,
             )),
         )
+        .merge(
+            oidc::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_oidc,
+            )),
+        )
+        .merge(
+            read_log::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_read_log,
+            )),
+        )
+        .merge(
+            saved_search::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_saved_searches,
+            )),
+        )
+        .merge(
+            scheduled_publishing::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_scheduled_publishing,
+            )),
+        )
+        .merge(
+            scheduled_update::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_scheduled_updates,
+            )),
+        )
         .merge(
             file::block_editor_router().route_layer(axum::middleware::from_fn_with_state(
                 state.clone(),
                 gate_block_editor,
             )),
         )
+        .merge(
+            goose::router().route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                gate_goose,
+            )),
+        )
 }
@@ -0,0 +1,534 @@
+//! External identity provider (OpenID Connect) login routes.
+//!
+//! - `GET /auth/{provider}/login` — redirect to the provider
+//! - `GET /auth/{provider}/callback` — finish the login
+//! - `GET /admin/config/oidc-providers` — list provider configurations
+//! - `POST /admin/config/oidc-providers` — create or update a provider
+//! - `DELETE /admin/config/oidc-providers/{id}` — delete a provider
+//!
+//! The callback links the provider's subject to a local account: an already
+//! linked identity, else an account with the same verified email (when the
+//! provider allows linking; never an administrator), else a new account
+//! (when the provider allows registration and the site's registration mode
+//! admits it). Mapped roles are synced from the provider's claims on every
+//! login. Accounts with two-factor authentication enabled or required still
+//! go through the code challenge.
+//!
+//! Gated on the `trovato_oidc` plugin.
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::{delete, get},
+};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::middleware::ClientIp;
+use crate::models::{CreateUser, UpdateUser, User, UserInvitation};
+use crate::services::mfa::{self, UserMfa};
+use crate::services::oidc::{IdTokenClaims, OidcProviderConfig, PendingLogin, SESSION_OIDC_LOGIN};
+use crate::services::registration::{self, RegistrationMode};
+use crate::state::AppState;
+use crate::tap::UserContext;
+
+use super::auth::{complete_login, create_invited_account, render_login_error};
+use super::helpers::{
+    render_not_found, render_server_error, require_admin_json, require_csrf_header,
+};
+use super::mfa::start_challenge;
+
+/// Most attempts at a unique username for a new account.
+const MAX_USERNAME_ATTEMPTS: u32 = 20;
+
+/// Create the OIDC login router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/auth/{provider}/login", get(login))
+        .route("/auth/{provider}/callback", get(callback))
+        .route(
+            "/admin/config/oidc-providers",
+            get(list_providers).post(save_provider),
+        )
+        .route("/admin/config/oidc-providers/{id}", delete(delete_provider))
+}
+
+/// A provider offered on the login form.
+#[derive(Debug, Serialize)]
+pub(crate) struct LoginProvider {
+    id: String,
+    label: String,
+}
+
+/// Enabled providers for the login form; none while the plugin is
+/// disabled. Failures are logged and hide the buttons rather than the form.
+pub(crate) async fn login_providers(state: &AppState) -> Vec<LoginProvider> {
+    if !state.is_plugin_enabled("trovato_oidc") {
+        return Vec::new();
+    }
+    match state.oidc().enabled_providers().await {
+        Ok(providers) => providers
+            .into_iter()
+            .map(|p| LoginProvider {
+                id: p.id,
+                label: p.label,
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load OIDC providers");
+            Vec::new()
+        }
+    }
+}
+
+/// The callback URL registered with providers.
+fn redirect_uri(state: &AppState, provider: &str) -> String {
+    format!("{}/auth/{provider}/callback", state.site_url())
+}
+
+/// Start a login with an external provider.
+///
+/// GET /auth/{provider}/login
+async fn login(
+    State(state): State<AppState>,
+    session: Session,
+    Path(provider_id): Path<String>,
+) -> Response {
+    let provider = match state.oidc().get_enabled(&provider_id).await {
+        Ok(Some(provider)) => provider,
+        Ok(None) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load OIDC provider");
+            return render_server_error("Failed to start the login.");
+        }
+    };
+
+    let (url, pending) = match state
+        .oidc()
+        .begin_login(&provider, &redirect_uri(&state, &provider.id))
+        .await
+    {
+        Ok(started) => started,
+        Err(e) => {
+            tracing::warn!(error = %e, provider = %provider.id, "failed to start OIDC login");
+            let message = format!(
+                "{} is not reachable. Please try again later.",
+                provider.label
+            );
+            return render_login_error(&state, &session, &message).await;
+        }
+    };
+
+    if let Err(e) = session.insert(SESSION_OIDC_LOGIN, &pending).await {
+        tracing::error!(error = %e, "failed to store OIDC login state");
+        return render_server_error("Failed to start the login.");
+    }
+    Redirect::to(&url).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// Finish a login with an external provider.
+///
+/// GET /auth/{provider}/callback
+async fn callback(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    session: Session,
+    Path(provider_id): Path<String>,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    let client_id = client_ip.key();
+    if let Err(retry_after) = state.rate_limiter().check("login", &client_id).await {
+        return crate::middleware::rate_limit_response(retry_after);
+    }
+
+    // The login state is single-use.
+    let pending = session
+        .remove::<PendingLogin>(SESSION_OIDC_LOGIN)
+        .await
+        .ok()
+        .flatten();
+
+    let provider = match state.oidc().get_enabled(&provider_id).await {
+        Ok(Some(provider)) => provider,
+        Ok(None) => return render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to load OIDC provider");
+            return render_server_error("Failed to finish the login.");
+        }
+    };
+
+    if let Some(error) = query.error {
+        tracing::info!(provider = %provider.id, error = %error, "OIDC login not completed");
+        let message = format!("Login with {} was cancelled or refused.", provider.label);
+        return render_login_error(&state, &session, &message).await;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let (Some(pending), Some(code), Some(returned_state)) = (pending, query.code, query.state)
+    else {
+        return render_login_error(&state, &session, "The login has expired. Please try again.")
+            .await;
+    };
+    let state_matches: bool = pending
+        .state
+        .as_bytes()
+        .ct_eq(returned_state.as_bytes())
+        .into();
+    if pending.provider != provider.id || !state_matches || pending.is_expired(now) {
+        return render_login_error(&state, &session, "The login has expired. Please try again.")
+            .await;
+    }
+
+    let claims = match state
+        .oidc()
+        .finish_login(
+            &provider,
+            &pending,
+            &code,
+            &redirect_uri(&state, &provider.id),
+        )
+        .await
+    {
+        Ok(claims) => claims,
+        Err(e) => {
+            tracing::warn!(error = %e, provider = %provider.id, "OIDC login failed");
+            let message = format!("The login with {} could not be verified.", provider.label);
+            return render_login_error(&state, &session, &message).await;
+        }
+    };
+
+    let user = match resolve_account(&state, &provider, &claims).await {
+        Ok(user) => user,
+        Err(message) => return render_login_error(&state, &session, &message).await,
+    };
+    if !user.is_active() {
+        let message = if registration::is_pending_approval(&user) {
+            "An administrator must approve your account before you can log in."
+        } else {
+            "This account is not active."
+        };
+        return render_login_error(&state, &session, message).await;
+    }
+
+    sync_roles(&state, &provider, &claims, &user).await;
+
+    login_verified_account(&state, &session, &user, &provider.id).await
+}
+
+/// Log in the local account of a verified provider login.
+///
+/// The provider stands in for the password only: an account with 2FA
+/// enabled, or whose roles require it, continues to the code challenge as
+/// after a password login.
+pub async fn login_verified_account(
+    state: &AppState,
+    session: &Session,
+    user: &User,
+    provider_id: &str,
+) -> Response {
+    match UserMfa::is_enabled(state.db(), user.id).await {
+        Ok(true) => {
+            return start_challenge(state, session, &user.name, false, user.id, false).await;
+        }
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to check 2FA status");
+            return render_login_error(state, session, "Internal server error").await;
+        }
+    }
+    match mfa::is_required_for(state.db(), user).await {
+        Ok(true) => {
+            return start_challenge(state, session, &user.name, false, user.id, true).await;
+        }
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!(error = %e, user_id = %user.id, "failed to check 2FA requirement");
+            return render_login_error(state, session, "Internal server error").await;
+        }
+    }
+
+    match complete_login(state, session, user, &user.name, false).await {
+        Ok(()) => {
+            tracing::info!(user_id = %user.id, provider = %provider_id, "user logged in through OIDC");
+            Redirect::to("/").into_response()
+        }
+        Err(e) => render_login_error(state, session, e.message()).await,
+    }
+}
+
+/// Find or create the local account of a verified login. The error is the
+/// message shown on the login form.
+async fn resolve_account(
+    state: &AppState,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+) -> Result<User, String> {
+    let internal = |e: anyhow::Error| {
+        tracing::error!(error = %e, provider = %provider.id, "failed to resolve OIDC account");
+        "Internal server error".to_string()
+    };
+
+    if let Some(identity) = state
+        .oidc()
+        .find_identity(&provider.id, &claims.sub)
+        .await
+        .map_err(internal)?
+    {
+        if let Err(e) = state
+            .oidc()
+            .touch_identity(identity.id, claims.verified_email())
+            .await
+        {
+            tracing::warn!(error = %e, "failed to record identity login");
+        }
+        return state
+            .users()
+            .find_by_id(identity.user_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| "No account is linked to this login.".to_string());
+    }
+
+    let email = claims.verified_email();
+    let existing = match email {
+        Some(mail) if provider.link_by_email => {
+            state.users().find_by_mail(mail).await.map_err(internal)?
+        }
+        _ => None,
+    };
+
+    let not_linked = || format!("No account is linked to this {} login.", provider.label);
+    // A matching address is not enough to take over an administrator.
+    if let Some(user) = existing.as_ref().filter(|user| user.is_admin) {
+        tracing::warn!(user_id = %user.id, provider = %provider.id, "refused to link an administrator by email");
+        return Err(not_linked());
+    }
+
+    let user = match (existing, email) {
+        (Some(user), _) => user,
+        (None, Some(mail)) if provider.allow_registration => {
+            register_account(state, provider, claims, mail).await?
+        }
+        _ => return Err(not_linked()),
+    };
+
+    state
+        .oidc()
+        .link_identity(user.id, &provider.id, claims)
+        .await
+        .map_err(internal)?;
+    tracing::info!(user_id = %user.id, provider = %provider.id, "linked external identity");
+    Ok(user)
+}
+
+/// Create an account for a login as the site's registration mode allows:
+/// closed refuses, invite-only needs a pending invitation for the verified
+/// address, and admin approval creates the account blocked until approved.
+/// The error is the message shown on the login form.
+async fn register_account(
+    state: &AppState,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+    mail: &str,
+) -> Result<User, String> {
+    let internal = |e: anyhow::Error| {
+        tracing::error!(error = %e, provider = %provider.id, "failed to register OIDC account");
+        "Internal server error".to_string()
+    };
+    let anonymous = UserContext::anonymous();
+
+    match RegistrationMode::load(state.db()).await {
+        RegistrationMode::Closed => Err("Registration is closed.".to_string()),
+        RegistrationMode::Open => {
+            let input = new_account(state, claims, mail).await.map_err(internal)?;
+            state
+                .users()
+                .register(input, 1, &anonymous)
+                .await
+                .map_err(internal)
+        }
+        RegistrationMode::AdminApproval => {
+            let input = new_account(state, claims, mail).await.map_err(internal)?;
+            let user = state
+                .users()
+                .register(input, 0, &anonymous)
+                .await
+                .map_err(internal)?;
+            let update = UpdateUser {
+                data: Some(registration::with_pending_approval(&user.data, true)),
+                ..Default::default()
+            };
+            let user = state
+                .users()
+                .update(user.id, update, &anonymous)
+                .await
+                .map_err(internal)?
+                .ok_or_else(|| "Internal server error".to_string())?;
+            tracing::info!(user_id = %user.id, provider = %provider.id, "OIDC account awaiting approval");
+            Ok(user)
+        }
+        RegistrationMode::InviteOnly => {
+            let Some(invitation) = UserInvitation::find_valid_for_mail(state.db(), mail)
+                .await
+                .map_err(internal)?
+            else {
+                return Err("Registration is by invitation only.".to_string());
+            };
+            let input = new_account(state, claims, mail).await.map_err(internal)?;
+            create_invited_account(state, input, &invitation, &anonymous)
+                .await
+                .map_err(internal)
+        }
+    }
+}
+
+/// A new account for a login, named after its claims. The account gets a
+/// random password; the user can set one through password reset.
+async fn new_account(
+    state: &AppState,
+    claims: &IdTokenClaims,
+    mail: &str,
+) -> anyhow::Result<CreateUser> {
+    let base = claims.username_base();
+    let mut name = base.clone();
+    let mut attempt = 1;
+    while state.users().find_by_name(&name).await?.is_some() {
+        attempt += 1;
+        if attempt > MAX_USERNAME_ATTEMPTS {
+            anyhow::bail!("no free username for {base}");
+        }
+        name = format!("{base}-{attempt}");
+    }
+
+    Ok(CreateUser {
+        name,
+        password: crate::models::email_verification::generate_token(),
+        mail: mail.to_string(),
+        is_admin: false,
+    })
+}
+
+/// Grant the mapped roles the claims carry and remove the mapped roles they
+/// no longer carry. Roles outside the mapping are left alone. Failures are
+/// logged; they never block the login.
+async fn sync_roles(
+    state: &AppState,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+    user: &User,
+) {
+    let managed = provider.managed_roles();
+    if managed.is_empty() {
+        return;
+    }
+    let granted = provider.granted_roles(&claims.raw);
+    let current = match state.roles().get_user_roles(user.id).await {
+        Ok(roles) => roles,
+        Err(e) => {
+            tracing::warn!(error = %e, user_id = %user.id, "failed to load roles for OIDC mapping");
+            return;
+        }
+    };
+
+    for name in managed {
+        let role = match state.roles().find_by_name(name).await {
+            Ok(Some(role)) => role,
+            Ok(None) => {
+                tracing::warn!(role = %name, provider = %provider.id, "mapped role does not exist");
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, role = %name, "failed to load mapped role");
+                continue;
+            }
+        };
+        let has = current.iter().any(|r| r.id == role.id);
+        let result = match (granted.contains(&name), has) {
+            (true, false) => state.roles().assign_to_user(user.id, role.id).await,
+            (false, true) => state.roles().remove_from_user(user.id, role.id).await,
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::warn!(error = %e, user_id = %user.id, role = %name, "failed to sync mapped role");
+        }
+    }
+}
+
+/// List provider configurations.
+///
+/// GET /admin/config/oidc-providers
+async fn list_providers(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<OidcProviderConfig>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let providers = state
+        .oidc()
+        .list_providers()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list OIDC providers"))?;
+    Ok(Json(providers))
+}
+
+/// Create or update a provider.
+///
+/// POST /admin/config/oidc-providers
+async fn save_provider(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(provider): Json<OidcProviderConfig>,
+) -> Result<Json<OidcProviderConfig>, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    provider
+        .validate()
+        .map_err(|e| AppError::validation(vec![AppError::field_error("provider", "invalid", e)]))?;
+
+    state
+        .oidc()
+        .save_provider(provider.clone())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save OIDC provider"))?;
+    Ok(Json(provider))
+}
+
+/// Delete a provider.
+///
+/// DELETE /admin/config/oidc-providers/{id}
+async fn delete_provider(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let removed = state
+        .oidc()
+        .delete_provider(&id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete OIDC provider"))?;
+    if !removed {
+        return Err(AppError::not_found("OIDC provider"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -7,6 +7,7 @@ use serde::Deserialize;
 use tracing::info;
 
 use crate::error::AppError;
+use crate::middleware::ClientIp;
 use crate::models::password_reset::PasswordResetToken;
 use crate::routes::helpers::{JsonSuccess, validate_password};
 use crate::state::AppState;
@@ -30,10 +31,10 @@ pub struct SetPasswordInput {
 /// Always returns success (security: don't reveal if email exists).
 async fn request_reset(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
+    client_ip: ClientIp,
     Json(input): Json<RequestResetInput>,
 ) -> Json<JsonSuccess> {
-    let client_id = crate::middleware::get_client_id(None, &headers);
+    let client_id = client_ip.key();
     // Try to find user by email
     match state.users().find_by_mail(&input.email).await {
         Ok(Some(user)) => {
@@ -139,6 +140,19 @@ async fn set_password(
         .map_err(|e| AppError::internal_ctx(e, "validate reset token"))?
         .ok_or_else(|| AppError::bad_request("Invalid or expired reset token"))?;
 
+    // Policy violations are returned as field errors on `password`.
+    let user = state
+        .users()
+        .find_by_id(reset_token.user_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load user"))?
+        .ok_or_else(|| AppError::bad_request("Invalid or expired reset token"))?;
+    state
+        .password_policy()
+        .check(&input.password, Some(&user))
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "password policy"))?;
+
     // Update the password (anonymous context — user is not logged in)
     let anon = UserContext::anonymous();
     state
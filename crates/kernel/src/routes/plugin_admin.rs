@@ -1,18 +1,21 @@
-//! Admin routes for plugin management (enable/disable).
+//! Admin routes for plugin management (enable/disable) and the plugin
+//! capability report.
 
 use axum::extract::State;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::routing::{get, post};
-use axum::{Form, Router};
+use axum::{Form, Json, Router};
 use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
 
+use crate::error::AppError;
 use crate::form::csrf::generate_csrf_token;
+use crate::plugin::Capability;
 use crate::plugin::runtime::PluginRuntime;
 use crate::plugin::status::{self, STATUS_DISABLED, STATUS_ENABLED};
 use crate::state::AppState;
 
-use super::helpers::{render_admin_template, require_admin, require_csrf};
+use super::helpers::{render_admin_template, require_admin, require_admin_json, require_csrf};
 
 const FLASH_KEY: &str = "plugin_admin_flash";
 
@@ -31,6 +34,17 @@ struct PluginRow {
     default_enabled: bool,
 }
 
+/// A plugin's host function capabilities.
+#[derive(Debug, Serialize)]
+struct CapabilityRow {
+    name: String,
+    status: &'static str,
+    granted: Vec<Capability>,
+    denied: Vec<Capability>,
+    /// Why the plugin failed to load, e.g. an ungranted host function import.
+    load_error: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ToggleForm {
     #[serde(rename = "_token")]
@@ -251,6 +265,52 @@ async fn render_plugin_error(state: &AppState, message: &str) -> Response {
     render_admin_template(state, "admin/plugin-error.html", context).await
 }
 
+/// Report the capabilities each plugin on disk is granted.
+///
+/// GET /admin/reports/plugin-capabilities
+async fn capability_report(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<CapabilityRow>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let discovered = PluginRuntime::discover_plugins(state.plugins_dir()).await;
+    let statuses = status::get_all_statuses(state.db())
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load plugin statuses"))?;
+    let load_errors = state.plugin_runtime().load_errors();
+
+    let mut names: Vec<&String> = discovered.keys().collect();
+    names.sort();
+    let rows = names
+        .into_iter()
+        .map(|name| {
+            let (info, dir) = &discovered[name];
+            let status = match statuses.iter().find(|s| &s.name == name) {
+                Some(s) if s.status == STATUS_ENABLED => "enabled",
+                Some(_) => "disabled",
+                None => "not installed",
+            };
+            let dir = dir.display().to_string();
+            let (granted, denied): (Vec<Capability>, Vec<Capability>) = Capability::ALL
+                .into_iter()
+                .partition(|c| info.capabilities.grants(*c));
+            CapabilityRow {
+                name: name.clone(),
+                status,
+                granted,
+                denied,
+                load_error: load_errors
+                    .iter()
+                    .find(|e| e.plugin == dir)
+                    .map(|e| e.error.clone()),
+            }
+        })
+        .collect();
+
+    Ok(Json(rows))
+}
+
 // =============================================================================
 // Router
 // =============================================================================
@@ -259,4 +319,5 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/admin/plugins", get(list_plugins))
         .route("/admin/plugins/toggle", post(toggle_plugin))
+        .route("/admin/reports/plugin-capabilities", get(capability_report))
 }
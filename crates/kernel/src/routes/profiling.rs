@@ -0,0 +1,98 @@
+//! Profiling endpoints (`profiling` feature, "view profiling data" permission).
+//!
+//! - `GET /admin/profiling/cpu?seconds=10&frequency=99` — CPU flamegraph SVG
+//! - `GET /admin/profiling/runtime` — tokio runtime snapshot
+
+use std::time::Duration;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::profiling::PROFILING_PERMISSION;
+use crate::profiling::cpu::{self, DEFAULT_FREQUENCY, MAX_PROFILE_SECONDS, RuntimeSnapshot};
+use crate::state::AppState;
+
+use super::helpers::require_permission;
+
+/// Create the profiling router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/profiling/cpu", get(cpu_profile))
+        .route("/admin/profiling/runtime", get(runtime))
+}
+
+/// CPU profile parameters.
+#[derive(Debug, Deserialize)]
+struct CpuProfileParams {
+    /// Capture length (default 10, max [`MAX_PROFILE_SECONDS`]).
+    #[serde(default = "default_seconds")]
+    seconds: u64,
+    /// Sampling frequency in Hz.
+    #[serde(default = "default_frequency")]
+    frequency: i32,
+}
+
+fn default_seconds() -> u64 {
+    10
+}
+
+fn default_frequency() -> i32 {
+    DEFAULT_FREQUENCY
+}
+
+async fn require_profiling(state: &AppState, session: &Session) -> Result<(), AppError> {
+    require_permission(state, session, PROFILING_PERMISSION)
+        .await
+        .map(|_| ())
+        .map_err(|_| AppError::forbidden(format!("Permission required: {PROFILING_PERMISSION}")))
+}
+
+/// Capture a CPU profile and return it as a flamegraph.
+///
+/// GET /admin/profiling/cpu
+async fn cpu_profile(
+    State(state): State<AppState>,
+    session: Session,
+    Query(params): Query<CpuProfileParams>,
+) -> Result<Response, AppError> {
+    require_profiling(&state, &session).await?;
+
+    if params.seconds == 0 || params.seconds > MAX_PROFILE_SECONDS {
+        return Err(AppError::bad_request(format!(
+            "seconds must be between 1 and {MAX_PROFILE_SECONDS}"
+        )));
+    }
+    if !(1..=1000).contains(&params.frequency) {
+        return Err(AppError::bad_request(
+            "frequency must be between 1 and 1000",
+        ));
+    }
+
+    let capture = cpu::begin_capture()
+        .ok_or_else(|| AppError::conflict("A CPU profile is already being captured"))?;
+    let svg = capture
+        .flamegraph(Duration::from_secs(params.seconds), params.frequency)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "capture CPU profile"))?;
+
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg).into_response())
+}
+
+/// Snapshot the async runtime.
+///
+/// GET /admin/profiling/runtime
+async fn runtime(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<RuntimeSnapshot>, AppError> {
+    require_profiling(&state, &session).await?;
+    Ok(Json(cpu::runtime_snapshot()))
+}
@@ -0,0 +1,102 @@
+//! Read access log report and settings (admin only).
+//!
+//! - `GET /admin/reports/read-log` — entries filtered by `item_id`,
+//!   `user_id`, and/or `since`
+//! - `GET /admin/reports/read-log/settings` — current sampling settings
+//! - `POST /admin/reports/read-log/settings` — replace sampling settings
+//!
+//! Gated on the `trovato_read_log` plugin.
+
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::HeaderMap,
+    routing::get,
+};
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::services::read_log::{ReadLogEntry, ReadLogFilter, ReadLogService, ReadLogSettings};
+use crate::state::AppState;
+
+use super::helpers::{require_admin_json, require_csrf_header};
+
+/// Create the read log router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/reports/read-log", get(read_log_report))
+        .route(
+            "/admin/reports/read-log/settings",
+            get(get_settings).post(update_settings),
+        )
+}
+
+/// The read log service, or 503 if it was not started with the server.
+fn read_log_service(state: &AppState) -> Result<&Arc<ReadLogService>, AppError> {
+    state
+        .read_log()
+        .ok_or_else(|| AppError::service_unavailable("read log", "Read logging not enabled"))
+}
+
+/// List read log entries, newest first.
+///
+/// GET /admin/reports/read-log
+async fn read_log_report(
+    State(state): State<AppState>,
+    session: Session,
+    Query(filter): Query<ReadLogFilter>,
+) -> Result<Json<Vec<ReadLogEntry>>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let entries = read_log_service(&state)?
+        .query(&filter)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "query read log"))?;
+
+    Ok(Json(entries))
+}
+
+/// Get read log settings.
+///
+/// GET /admin/reports/read-log/settings
+async fn get_settings(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<ReadLogSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let settings = read_log_service(&state)?
+        .settings()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load read log settings"))?;
+
+    Ok(Json(settings.as_ref().clone()))
+}
+
+/// Replace read log settings.
+///
+/// POST /admin/reports/read-log/settings
+async fn update_settings(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(settings): Json<ReadLogSettings>,
+) -> Result<Json<ReadLogSettings>, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    settings.validate().map_err(|e| {
+        AppError::validation(vec![AppError::field_error("settings", "out_of_range", e)])
+    })?;
+
+    read_log_service(&state)?
+        .save_settings(&settings)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save read log settings"))?;
+
+    Ok(Json(settings))
+}
@@ -0,0 +1,87 @@
+//! Read-only mode settings (admin only).
+//!
+//! - `GET /admin/read-only` — current settings and whether `READ_ONLY`
+//!   forces read-only mode
+//! - `POST /admin/read-only` — replace settings (allowed while read-only so
+//!   the mode can be lifted)
+
+use axum::{Json, Router, extract::State, http::HeaderMap, routing::get};
+use serde::Serialize;
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::services::read_only::ReadOnlySettings;
+use crate::state::AppState;
+
+use super::helpers::{require_admin_json, require_csrf_header};
+
+/// Create the read-only settings router.
+pub fn router() -> Router<AppState> {
+    Router::new().route("/admin/read-only", get(get_settings).post(update_settings))
+}
+
+/// Read-only settings with the configuration override.
+#[derive(Debug, Serialize)]
+struct ReadOnlyStatus {
+    /// Read-only regardless of settings (`READ_ONLY` environment variable).
+    forced: bool,
+    #[serde(flatten)]
+    settings: ReadOnlySettings,
+}
+
+/// Get read-only settings.
+///
+/// GET /admin/read-only
+async fn get_settings(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<ReadOnlyStatus>, AppError> {
+    require_admin_json(&state, &session).await?;
+
+    let settings = state
+        .read_only()
+        .settings()
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load read-only settings"))?;
+
+    Ok(Json(ReadOnlyStatus {
+        forced: state.read_only().is_forced(),
+        settings: settings.as_ref().clone(),
+    }))
+}
+
+/// Replace read-only settings.
+///
+/// POST /admin/read-only
+async fn update_settings(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(settings): Json<ReadOnlySettings>,
+) -> Result<Json<ReadOnlyStatus>, AppError> {
+    require_admin_json(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    settings
+        .validate()
+        .map_err(|e| AppError::validation(vec![AppError::field_error("message", "too_long", e)]))?;
+
+    state
+        .read_only()
+        .save_settings(&settings)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save read-only settings"))?;
+
+    tracing::warn!(
+        enabled = settings.enabled,
+        locked_item_types = ?settings.locked_item_types,
+        "read-only settings changed"
+    );
+
+    Ok(Json(ReadOnlyStatus {
+        forced: state.read_only().is_forced(),
+        settings,
+    }))
+}
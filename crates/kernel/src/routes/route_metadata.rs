@@ -178,6 +178,59 @@ impl RouteRegistry {
             deprecated: false,
         });
 
+        let query_param = |name: &str, description: &str| ParamMeta {
+            name: name.to_string(),
+            location: "query".to_string(),
+            required: false,
+            description: description.to_string(),
+        };
+        self.routes.push(RouteMetadata {
+            method: Method::GET.to_string(),
+            path: "/api/v1/media/browse".to_string(),
+            summary: "Search the media library by filename, alt text, type and date".to_string(),
+            parameters: vec![
+                query_param("q", "Filename or alt text search"),
+                query_param("type", "image, document or all (default)"),
+                query_param("mime", "Exact MIME type or prefix ending in /"),
+                query_param(
+                    "from",
+                    "Uploaded on or after (YYYY-MM-DD or Unix timestamp)",
+                ),
+                query_param("to", "Uploaded on or before (YYYY-MM-DD or Unix timestamp)"),
+                query_param("sort", "newest (default), oldest, name or size"),
+                query_param("page", "Page number (default 1)"),
+                query_param("page_size", "Files per page (default 24, max 100)"),
+            ],
+            response_type: "application/json".to_string(),
+            tags: vec!["media".to_string()],
+            deprecated: false,
+        });
+
+        self.routes.push(RouteMetadata {
+            method: Method::GET.to_string(),
+            path: "/api/v1/media/{id}/usage".to_string(),
+            summary: "List the items using a media file".to_string(),
+            parameters: vec![ParamMeta {
+                name: "id".to_string(),
+                location: "path".to_string(),
+                required: true,
+                description: "File UUID".to_string(),
+            }],
+            response_type: "application/json".to_string(),
+            tags: vec!["media".to_string()],
+            deprecated: false,
+        });
+
+        self.routes.push(RouteMetadata {
+            method: Method::POST.to_string(),
+            path: "/api/v1/media/alt-text".to_string(),
+            summary: "Set the alt text of many media files at once".to_string(),
+            parameters: vec![],
+            response_type: "application/json".to_string(),
+            tags: vec!["media".to_string()],
+            deprecated: false,
+        });
+
         self.routes.push(RouteMetadata {
             method: Method::POST.to_string(),
             path: "/api/v1/ai/assist".to_string(),
@@ -0,0 +1,251 @@
+//! Saved search routes.
+//!
+//! - `GET /api/saved-searches` — the user's saved searches
+//! - `POST /api/saved-searches` — save a search
+//! - `GET|PUT|DELETE /api/saved-searches/{id}` — read, replace or delete one
+//! - `GET /api/saved-searches/{id}/results` — its newest matches
+//!
+//! All routes require a login and only reach the user's own searches.
+//! Mutating requests require the `X-CSRF-Token` header. Gated on the
+//! `trovato_saved_searches` plugin.
+
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::search::SearchResult;
+use crate::services::saved_search::{
+    MAX_SAVED_SEARCHES, SavedSearch, SavedSearchInput, SavedSearchService, is_valid_webhook_url,
+};
+use crate::state::AppState;
+use crate::tap::UserContext;
+
+use super::helpers::require_csrf_header;
+use super::item::get_user_context;
+
+/// Permission needed to post alerts to a webhook.
+pub const WEBHOOK_PERMISSION: &str = "use saved search webhooks";
+
+/// Longest saved search name.
+const MAX_NAME_LEN: usize = 255;
+
+/// Create the saved search router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/saved-searches",
+            get(list_saved_searches).post(create_saved_search),
+        )
+        .route(
+            "/api/saved-searches/{id}",
+            get(get_saved_search)
+                .put(update_saved_search)
+                .delete(delete_saved_search),
+        )
+        .route(
+            "/api/saved-searches/{id}/results",
+            get(saved_search_results),
+        )
+}
+
+/// The logged-in user.
+async fn require_login(state: &AppState, session: &Session) -> Result<UserContext, AppError> {
+    let user = get_user_context(session, state).await;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Login required"));
+    }
+    Ok(user)
+}
+
+/// The saved search service, or 503 if it was not started with the server.
+fn saved_search_service(state: &AppState) -> Result<&Arc<SavedSearchService>, AppError> {
+    state.saved_searches().ok_or_else(|| {
+        AppError::service_unavailable("saved_searches", "Saved searches not enabled")
+    })
+}
+
+/// Load one of the user's saved searches.
+///
+/// Other users' searches are reported as missing.
+async fn own_saved_search(
+    state: &AppState,
+    user: &UserContext,
+    id: Uuid,
+) -> Result<SavedSearch, AppError> {
+    saved_search_service(state)?
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load saved search"))?
+        .filter(|s| s.user_id == user.id)
+        .ok_or_else(|| AppError::not_found_id("saved search", id))
+}
+
+/// Trim and check a saved search's fields.
+fn validate_input(
+    state: &AppState,
+    user: &UserContext,
+    mut input: SavedSearchInput,
+) -> Result<SavedSearchInput, AppError> {
+    input.name = input.name.trim().to_string();
+    input.query = input.query.trim().to_string();
+    if input.name.is_empty() || input.name.len() > MAX_NAME_LEN {
+        return Err(AppError::bad_request(format!(
+            "name is required and at most {MAX_NAME_LEN} characters"
+        )));
+    }
+    if input.query.is_empty() {
+        return Err(AppError::bad_request("query is required"));
+    }
+    input.item_types.sort();
+    input.item_types.dedup();
+    if let Some(unknown) = input
+        .item_types
+        .iter()
+        .find(|t| !state.content_types().exists(t))
+    {
+        return Err(AppError::bad_request(format!(
+            "unknown item type '{unknown}'"
+        )));
+    }
+
+    input.webhook = input
+        .webhook
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    if let Some(ref url) = input.webhook {
+        if !user.is_admin() && !user.has_permission(WEBHOOK_PERMISSION) {
+            return Err(AppError::forbidden("Webhook alerts are not allowed"));
+        }
+        if !is_valid_webhook_url(url) {
+            return Err(AppError::bad_request("webhook must be an http(s) URL"));
+        }
+    }
+    Ok(input)
+}
+
+/// List the user's saved searches, newest first.
+///
+/// GET /api/saved-searches
+async fn list_saved_searches(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Json<Vec<SavedSearch>>, AppError> {
+    let user = require_login(&state, &session).await?;
+    let searches = saved_search_service(&state)?
+        .list(user.id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list saved searches"))?;
+    Ok(Json(searches))
+}
+
+/// Save a search.
+///
+/// POST /api/saved-searches
+async fn create_saved_search(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Json(input): Json<SavedSearchInput>,
+) -> Result<(StatusCode, Json<SavedSearch>), AppError> {
+    let user = require_login(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    let input = validate_input(&state, &user, input)?;
+
+    let count = saved_search_service(&state)?
+        .count(user.id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "count saved searches"))?;
+    if count >= MAX_SAVED_SEARCHES {
+        return Err(AppError::bad_request(format!(
+            "at most {MAX_SAVED_SEARCHES} searches can be saved"
+        )));
+    }
+
+    let saved = saved_search_service(&state)?
+        .create(user.id, &input)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "save search"))?;
+    Ok((StatusCode::CREATED, Json(saved)))
+}
+
+/// GET /api/saved-searches/{id}
+async fn get_saved_search(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let user = require_login(&state, &session).await?;
+    Ok(Json(own_saved_search(&state, &user, id).await?))
+}
+
+/// Replace a saved search's name, query, filters and alert settings.
+///
+/// PUT /api/saved-searches/{id}
+async fn update_saved_search(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<SavedSearchInput>,
+) -> Result<Json<SavedSearch>, AppError> {
+    let user = require_login(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    own_saved_search(&state, &user, id).await?;
+    let input = validate_input(&state, &user, input)?;
+
+    saved_search_service(&state)?
+        .update(id, &input)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "update saved search"))?
+        .map(Json)
+        .ok_or_else(|| AppError::not_found_id("saved search", id))
+}
+
+/// DELETE /api/saved-searches/{id}
+async fn delete_saved_search(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user = require_login(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    own_saved_search(&state, &user, id).await?;
+
+    saved_search_service(&state)?
+        .delete(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "delete saved search"))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The newest published items matching a saved search.
+///
+/// GET /api/saved-searches/{id}/results
+async fn saved_search_results(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<SearchResult>>, AppError> {
+    let user = require_login(&state, &session).await?;
+    let saved = own_saved_search(&state, &user, id).await?;
+    let results = saved_search_service(&state)?
+        .matches(&saved)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "run saved search"))?;
+    Ok(Json(results))
+}
@@ -0,0 +1,479 @@
+//! Scheduled publishing routes (gated on `trovato_scheduled_publishing`).
+//!
+//! - `GET /admin/content/scheduled` — items with a pending publish or unpublish
+//! - `POST /admin/content/scheduled/{id}/reschedule` — set both times
+//! - `POST /admin/content/scheduled/{id}/cancel` — clear both times
+//! - `GET|PUT|DELETE /api/item/{id}/schedule` — read, set or clear an
+//!   item's schedule as JSON
+//!
+//! All routes require the `schedule publishing` permission. Schedule
+//! changes are item edits: they need edit access to the item and create a
+//! revision.
+
+use std::collections::HashMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::content::ItemPatch;
+use crate::error::AppError;
+use crate::form::csrf::generate_csrf_token;
+use crate::models::Item;
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::services::scheduled_publishing::{self, Schedule, ScheduleFilter, ScheduleSort};
+use crate::state::AppState;
+use crate::tap::UserContext;
+
+use super::helpers::{
+    CsrfOnlyForm, render_admin_template, render_server_error, require_csrf, require_csrf_header,
+    require_permission,
+};
+use super::item::get_user_context;
+
+/// Permission granted by the scheduled publishing plugin.
+const SCHEDULE_PERMISSION: &str = "schedule publishing";
+
+/// Session key for flash messages on the scheduled items page.
+const SCHEDULED_FLASH_KEY: &str = "scheduled_admin_flash";
+
+/// Create the scheduled publishing router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/content/scheduled", get(list_scheduled))
+        .route(
+            "/admin/content/scheduled/{id}/reschedule",
+            post(reschedule_submit),
+        )
+        .route("/admin/content/scheduled/{id}/cancel", post(cancel_submit))
+        .route(
+            "/api/item/{id}/schedule",
+            get(get_schedule).put(put_schedule).delete(delete_schedule),
+        )
+}
+
+/// Query parameters for the scheduled items listing.
+#[derive(Debug, Deserialize)]
+struct ScheduledListQuery {
+    #[serde(rename = "type")]
+    item_type: Option<String>,
+    stage: Option<String>,
+    #[serde(default)]
+    sort: ScheduleSort,
+    #[serde(default)]
+    desc: bool,
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+impl ScheduledListQuery {
+    /// Listing filter; empty select values mean "any".
+    fn filter(&self) -> ScheduleFilter {
+        ScheduleFilter {
+            item_type: self.item_type.clone().filter(|t| !t.is_empty()),
+            stage: self.stage.as_deref().and_then(|s| s.parse().ok()),
+            sort: self.sort,
+            desc: self.desc,
+        }
+    }
+}
+
+/// Listing URL for a filter, sorted by `sort`.
+fn listing_url(filter: &ScheduleFilter, sort: ScheduleSort, desc: bool) -> String {
+    let sort = serde_json::to_value(sort)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+    let mut url = format!("/admin/content/scheduled?sort={sort}&desc={desc}");
+    if let Some(item_type) = &filter.item_type {
+        url.push_str(&format!("&type={}", urlencoding::encode(item_type)));
+    }
+    if let Some(stage) = filter.stage {
+        url.push_str(&format!("&stage={stage}"));
+    }
+    url
+}
+
+/// List items with a pending publish or unpublish.
+///
+/// GET /admin/content/scheduled
+async fn list_scheduled(
+    State(state): State<AppState>,
+    session: Session,
+    Query(query): Query<ScheduledListQuery>,
+) -> Response {
+    if let Err(redirect) = require_permission(&state, &session, SCHEDULE_PERMISSION).await {
+        return redirect;
+    }
+
+    let filter = query.filter();
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, query.per_page)
+        .limit;
+    let offset = (page - 1) * per_page;
+
+    let (items, total) =
+        match scheduled_publishing::list(state.db(), &filter, per_page, offset).await {
+            Ok(listed) => listed,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to list scheduled items");
+                return render_server_error("Failed to load scheduled content.");
+            }
+        };
+
+    let stages = match state.stage().list_stages().await {
+        Ok(stages) => stages,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to list stages");
+            Vec::new()
+        }
+    };
+    let stage_labels: HashMap<String, String> = stages
+        .iter()
+        .map(|s| (s.id.to_string(), s.label.clone()))
+        .collect();
+
+    // Clicking the current sort column flips its direction.
+    let sort_links: HashMap<String, String> = [
+        ("next", ScheduleSort::Next),
+        ("publish_on", ScheduleSort::PublishOn),
+        ("unpublish_on", ScheduleSort::UnpublishOn),
+        ("title", ScheduleSort::Title),
+        ("type", ScheduleSort::Type),
+        ("changed", ScheduleSort::Changed),
+    ]
+    .into_iter()
+    .map(|(key, sort)| {
+        let desc = sort == filter.sort && !filter.desc;
+        (key.to_string(), listing_url(&filter, sort, desc))
+    })
+    .collect();
+    let page_url = listing_url(&filter, filter.sort, filter.desc);
+
+    let content_types = state.content_types().list_all().await;
+    let csrf_token = generate_csrf_token(&session).await;
+
+    // Read and clear flash message
+    let flash: Option<String> = session.get(SCHEDULED_FLASH_KEY).await.ok().flatten();
+    if flash.is_some()
+        && let Err(e) = session.remove::<String>(SCHEDULED_FLASH_KEY).await
+    {
+        tracing::warn!(error = %e, "failed to clear flash message");
+    }
+
+    let mut context = tera::Context::new();
+    context.insert("items", &items);
+    context.insert("total", &total);
+    context.insert("page", &page);
+    context.insert("per_page", &per_page);
+    context.insert("page_url", &page_url);
+    context.insert("sort_links", &sort_links);
+    context.insert("sort", &filter.sort);
+    context.insert("desc", &filter.desc);
+    context.insert("content_types", &content_types);
+    context.insert("stages", &stages);
+    context.insert("stage_labels", &stage_labels);
+    context.insert("type_filter", &filter.item_type.clone().unwrap_or_default());
+    context.insert(
+        "stage_filter",
+        &filter.stage.map(|s| s.to_string()).unwrap_or_default(),
+    );
+    context.insert("now", &chrono::Utc::now().timestamp());
+    context.insert("csrf_token", &csrf_token);
+    context.insert("flash", &flash);
+    context.insert("path", "/admin/content/scheduled");
+
+    render_admin_template(&state, "admin/scheduled.html", context).await
+}
+
+/// Reschedule form submission.
+#[derive(Debug, Deserialize)]
+struct RescheduleForm {
+    #[serde(rename = "_token")]
+    token: String,
+    #[serde(default)]
+    publish_on: String,
+    #[serde(default)]
+    unpublish_on: String,
+}
+
+/// Parse a `datetime-local` input value (interpreted as UTC).
+///
+/// Empty inputs clear the time.
+fn parse_datetime_input(value: &str) -> Result<Option<i64>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .map(|dt| Some(dt.and_utc().timestamp()))
+        .map_err(|_| "Dates must be in YYYY-MM-DDTHH:MM format.".to_string())
+}
+
+/// Reschedule an item.
+///
+/// POST /admin/content/scheduled/{id}/reschedule
+async fn reschedule_submit(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+    Form(form): Form<RescheduleForm>,
+) -> Response {
+    if let Err(redirect) = require_permission(&state, &session, SCHEDULE_PERMISSION).await {
+        return redirect;
+    }
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let schedule = parse_datetime_input(&form.publish_on).and_then(|publish_on| {
+        Ok(Schedule {
+            publish_on,
+            unpublish_on: parse_datetime_input(&form.unpublish_on)?,
+        })
+    });
+    let message = match schedule {
+        Ok(schedule) => {
+            let user = get_user_context(&session, &state).await;
+            schedule_flash(
+                save_schedule(&state, id, schedule, &user, "Rescheduled publishing").await,
+                "Schedule updated.",
+            )
+        }
+        Err(message) => message,
+    };
+
+    set_flash(&session, message).await;
+    Redirect::to("/admin/content/scheduled").into_response()
+}
+
+/// Cancel an item's scheduled publish and unpublish.
+///
+/// POST /admin/content/scheduled/{id}/cancel
+async fn cancel_submit(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+    Form(form): Form<CsrfOnlyForm>,
+) -> Response {
+    if let Err(redirect) = require_permission(&state, &session, SCHEDULE_PERMISSION).await {
+        return redirect;
+    }
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let user = get_user_context(&session, &state).await;
+    let result = save_schedule(
+        &state,
+        id,
+        Schedule::default(),
+        &user,
+        "Cancelled scheduled publishing",
+    )
+    .await;
+
+    set_flash(&session, schedule_flash(result, "Schedule cancelled.")).await;
+    Redirect::to("/admin/content/scheduled").into_response()
+}
+
+/// Flash message for the outcome of a schedule change.
+fn schedule_flash(result: anyhow::Result<Option<Item>>, success: &str) -> String {
+    match result {
+        Ok(Some(_)) => success.to_string(),
+        Ok(None) => "Content not found.".to_string(),
+        Err(e) => {
+            if let Some(failed) = e.downcast_ref::<crate::content::ItemValidationFailed>() {
+                return failed.messages().join(" ");
+            }
+            if e.to_string().contains("access denied") {
+                return "You do not have permission to edit this content.".to_string();
+            }
+            tracing::error!(error = %e, "failed to update schedule");
+            "Failed to update the schedule.".to_string()
+        }
+    }
+}
+
+/// Show `message` on the next scheduled items page view.
+async fn set_flash(session: &Session, message: String) {
+    if let Err(e) = session.insert(SCHEDULED_FLASH_KEY, message).await {
+        tracing::warn!(error = %e, "failed to set flash message");
+    }
+}
+
+/// Validate a schedule and save it onto the item.
+async fn save_schedule(
+    state: &AppState,
+    id: Uuid,
+    schedule: Schedule,
+    user: &UserContext,
+    log: &str,
+) -> anyhow::Result<Option<Item>> {
+    schedule.validate()?;
+    let patch = ItemPatch {
+        fields: schedule.to_patch(),
+        unmodified_since: None,
+        log: Some(log.to_string()),
+    };
+    state.items().patch(id, patch, user).await
+}
+
+/// An item's schedule.
+#[derive(Debug, Serialize)]
+struct ScheduleResponse {
+    item_id: Uuid,
+    status: i16,
+    changed: i64,
+    #[serde(flatten)]
+    schedule: Schedule,
+}
+
+impl From<&Item> for ScheduleResponse {
+    fn from(item: &Item) -> Self {
+        Self {
+            item_id: item.id,
+            status: item.status,
+            changed: item.changed,
+            schedule: Schedule::from_fields(&item.fields),
+        }
+    }
+}
+
+/// The session user, if they may schedule publishing.
+async fn schedule_user(state: &AppState, session: &Session) -> Result<UserContext, AppError> {
+    let user = get_user_context(session, state).await;
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Authentication required"));
+    }
+    if !user.is_admin() && !user.has_permission(SCHEDULE_PERMISSION) {
+        return Err(AppError::forbidden(
+            "Permission required: schedule publishing",
+        ));
+    }
+    Ok(user)
+}
+
+/// Read an item's schedule.
+///
+/// GET /api/item/{id}/schedule
+async fn get_schedule(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ScheduleResponse>, AppError> {
+    schedule_user(&state, &session).await?;
+
+    let item = state
+        .items()
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", id))?;
+
+    Ok(Json(ScheduleResponse::from(&item)))
+}
+
+/// Set an item's publish and unpublish times.
+///
+/// Both times are replaced; an omitted or null time is cleared. Answers
+/// 422 when the unpublish time is not after the publish time.
+///
+/// PUT /api/item/{id}/schedule
+async fn put_schedule(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(schedule): Json<Schedule>,
+) -> Result<Json<ScheduleResponse>, AppError> {
+    let user = schedule_user(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let item = api_save(&state, id, schedule, &user, "Rescheduled publishing").await?;
+    Ok(Json(ScheduleResponse::from(&item)))
+}
+
+/// Cancel an item's scheduled publish and unpublish.
+///
+/// DELETE /api/item/{id}/schedule
+async fn delete_schedule(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user = schedule_user(&state, &session).await?;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    api_save(
+        &state,
+        id,
+        Schedule::default(),
+        &user,
+        "Cancelled scheduled publishing",
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// [`save_schedule`] with errors mapped to API responses.
+async fn api_save(
+    state: &AppState,
+    id: Uuid,
+    schedule: Schedule,
+    user: &UserContext,
+    log: &str,
+) -> Result<Item, AppError> {
+    match save_schedule(state, id, schedule, user, log).await {
+        Ok(Some(item)) => Ok(item),
+        Ok(None) => Err(AppError::not_found_id("item", id)),
+        Err(e) if e.to_string().contains("access denied") => {
+            Err(AppError::forbidden("Access denied"))
+        }
+        Err(e) => Err(AppError::internal_ctx(e, "update item schedule")),
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn datetime_input_is_utc() {
+        assert_eq!(
+            parse_datetime_input("2027-01-02T03:04").unwrap(),
+            Some(1_798_859_040)
+        );
+        assert_eq!(parse_datetime_input(" ").unwrap(), None);
+        assert!(parse_datetime_input("tomorrow").is_err());
+    }
+
+    #[test]
+    fn listing_url_keeps_filters() {
+        let filter = ScheduleFilter {
+            item_type: Some("blog post".into()),
+            stage: None,
+            sort: ScheduleSort::Title,
+            desc: false,
+        };
+        assert_eq!(
+            listing_url(&filter, ScheduleSort::UnpublishOn, true),
+            "/admin/content/scheduled?sort=unpublish_on&desc=true&type=blog%20post"
+        );
+    }
+}
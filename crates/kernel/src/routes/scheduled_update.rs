@@ -0,0 +1,328 @@
+//! Scheduled update ("timed update") routes.
+//!
+//! - `GET /api/item/{id}/scheduled-updates` — an item's scheduled updates
+//! - `POST /api/item/{id}/scheduled-updates` — schedule a field change
+//! - `DELETE /api/scheduled-updates/{id}` — cancel a pending update
+//! - `GET /admin/content/timed-updates` — upcoming updates of all items
+//! - `POST /admin/content/timed-updates/{id}/cancel` — cancel from the listing
+//!
+//! The API routes require edit access to the item. Mutating API requests
+//! require the `X-CSRF-Token` header. Gated on the
+//! `trovato_scheduled_updates` plugin.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    Form, Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::{delete, get, post},
+};
+use serde::{Deserialize, Serialize};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::form::csrf::generate_csrf_token;
+use crate::models::{Item, User};
+use crate::services::pagination::{PageClass, PaginationPolicy};
+use crate::services::scheduled_update::{
+    MAX_PENDING_PER_ITEM, ScheduledUpdate, ScheduledUpdateInput, ScheduledUpdateService,
+};
+use crate::state::AppState;
+use crate::tap::UserContext;
+
+use super::helpers::{
+    CsrfOnlyForm, render_admin_template, render_not_found, render_server_error, require_admin,
+    require_csrf, require_csrf_header,
+};
+use super::item::get_user_context;
+
+/// Create the scheduled update router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/item/{id}/scheduled-updates",
+            get(list_item_updates).post(create_update),
+        )
+        .route("/api/scheduled-updates/{id}", delete(cancel_update))
+        .route("/admin/content/timed-updates", get(list_upcoming))
+        .route(
+            "/admin/content/timed-updates/{id}/cancel",
+            post(cancel_upcoming),
+        )
+}
+
+/// The scheduled update service, or 503 if it was not started with the
+/// server.
+fn scheduled_update_service(state: &AppState) -> Result<&Arc<ScheduledUpdateService>, AppError> {
+    state.scheduled_updates().ok_or_else(|| {
+        AppError::service_unavailable("scheduled_updates", "Scheduled updates not enabled")
+    })
+}
+
+/// Load an item the user may edit.
+async fn editable_item(
+    state: &AppState,
+    user: &UserContext,
+    item_id: Uuid,
+) -> Result<Item, AppError> {
+    if !user.authenticated {
+        return Err(AppError::unauthorized("Login required"));
+    }
+    let item = state
+        .items()
+        .load(item_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load item"))?
+        .ok_or_else(|| AppError::not_found_id("item", item_id))?;
+    let allowed = state
+        .items()
+        .check_access(&item, "edit", user)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "check item access"))?;
+    if !allowed {
+        return Err(AppError::forbidden("Access denied"));
+    }
+    Ok(item)
+}
+
+/// An item's scheduled updates, latest first.
+///
+/// GET /api/item/{id}/scheduled-updates
+async fn list_item_updates(
+    State(state): State<AppState>,
+    session: Session,
+    Path(item_id): Path<Uuid>,
+) -> Result<Json<Vec<ScheduledUpdate>>, AppError> {
+    let user = get_user_context(&session, &state).await;
+    editable_item(&state, &user, item_id).await?;
+
+    let updates = scheduled_update_service(&state)?
+        .list_for_item(item_id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "list scheduled updates"))?;
+    Ok(Json(updates))
+}
+
+/// Schedule a change of an item's fields.
+///
+/// POST /api/item/{id}/scheduled-updates
+async fn create_update(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(item_id): Path<Uuid>,
+    Json(mut input): Json<ScheduledUpdateInput>,
+) -> Result<(StatusCode, Json<ScheduledUpdate>), AppError> {
+    let user = get_user_context(&session, &state).await;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+    let item = editable_item(&state, &user, item_id).await?;
+
+    input.label = input
+        .label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    let definition = state
+        .content_types()
+        .get_or_load(&item.item_type)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load content type"))?;
+    let field_names: Vec<&str> = definition
+        .as_ref()
+        .map(|d| d.fields.iter().map(|f| f.field_name.as_str()).collect())
+        .unwrap_or_default();
+    input
+        .validate(&field_names, chrono::Utc::now().timestamp())
+        .map_err(AppError::bad_request)?;
+
+    let pending = scheduled_update_service(&state)?
+        .count_pending(Some(item_id))
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "count scheduled updates"))?;
+    if pending >= MAX_PENDING_PER_ITEM {
+        return Err(AppError::bad_request(format!(
+            "an item can have at most {MAX_PENDING_PER_ITEM} pending updates"
+        )));
+    }
+
+    let update = scheduled_update_service(&state)?
+        .create(item_id, &input, user.id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "schedule update"))?;
+    Ok((StatusCode::CREATED, Json(update)))
+}
+
+/// Cancel a pending update.
+///
+/// DELETE /api/scheduled-updates/{id}
+async fn cancel_update(
+    State(state): State<AppState>,
+    session: Session,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user = get_user_context(&session, &state).await;
+    require_csrf_header(&session, &headers)
+        .await
+        .map_err(|_| AppError::forbidden("Invalid or missing CSRF token"))?;
+
+    let update = scheduled_update_service(&state)?
+        .load(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load scheduled update"))?
+        .ok_or_else(|| AppError::not_found_id("scheduled update", id))?;
+    editable_item(&state, &user, update.item_id).await?;
+
+    let cancelled = scheduled_update_service(&state)?
+        .cancel(id)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "cancel scheduled update"))?;
+    if !cancelled {
+        return Err(AppError::bad_request("the update has already run"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct UpcomingQuery {
+    page: Option<i64>,
+    per_page: Option<i64>,
+}
+
+/// Upcoming update display struct for templates.
+#[derive(Debug, Serialize)]
+struct UpcomingDisplay {
+    id: Uuid,
+    item_id: Uuid,
+    item_title: String,
+    label: Option<String>,
+    /// Field names the update changes.
+    fields: Vec<String>,
+    update_at_display: String,
+    scheduled_by: String,
+}
+
+/// Upcoming updates of all items, soonest first.
+///
+/// GET /admin/content/timed-updates
+async fn list_upcoming(
+    State(state): State<AppState>,
+    session: Session,
+    Query(query): Query<UpcomingQuery>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    let scheduled_updates = match scheduled_update_service(&state) {
+        Ok(service) => service,
+        Err(e) => return e.into_response(),
+    };
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Admin, query.per_page)
+        .limit;
+    let offset = (page - 1) * per_page;
+
+    let updates = match scheduled_updates.list_upcoming(per_page, offset).await {
+        Ok(updates) => updates,
+        Err(e) => {
+            tracing::error!(error = %e, "failed to list scheduled updates");
+            return render_server_error("Failed to load scheduled updates.");
+        }
+    };
+    let total = scheduled_updates.count_pending(None).await.unwrap_or(0);
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+
+    let mut titles: HashMap<Uuid, String> = HashMap::new();
+    let mut names: HashMap<Uuid, String> = HashMap::new();
+    let mut display = Vec::with_capacity(updates.len());
+    for update in updates {
+        if !titles.contains_key(&update.item_id)
+            && let Ok(Some(item)) = state.items().load(update.item_id).await
+        {
+            titles.insert(update.item_id, item.title);
+        }
+        if let Some(user_id) = update.scheduled_by
+            && !names.contains_key(&user_id)
+            && let Ok(Some(user)) = User::find_by_id(state.db(), user_id).await
+        {
+            names.insert(user_id, user.name);
+        }
+        display.push(UpcomingDisplay {
+            id: update.id,
+            item_id: update.item_id,
+            item_title: titles
+                .get(&update.item_id)
+                .cloned()
+                .unwrap_or_else(|| "Unknown".to_string()),
+            label: update.label,
+            fields: update
+                .fields
+                .as_object()
+                .map(|f| f.keys().cloned().collect())
+                .unwrap_or_default(),
+            update_at_display: chrono::DateTime::from_timestamp(update.update_at, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            scheduled_by: update
+                .scheduled_by
+                .and_then(|id| names.get(&id).cloned())
+                .unwrap_or_else(|| "Unknown".to_string()),
+        });
+    }
+
+    let csrf_token = generate_csrf_token(&session).await;
+
+    let mut context = tera::Context::new();
+    context.insert("updates", &display);
+    context.insert("total", &total);
+    context.insert("page", &page);
+    context.insert("total_pages", &total_pages);
+    context.insert("csrf_token", &csrf_token);
+    context.insert("path", "/admin/content/timed-updates");
+
+    render_admin_template(&state, "admin/timed-updates.html", context).await
+}
+
+/// Cancel a pending update from the listing.
+///
+/// POST /admin/content/timed-updates/{id}/cancel
+async fn cancel_upcoming(
+    State(state): State<AppState>,
+    session: Session,
+    Path(id): Path<Uuid>,
+    Form(form): Form<CsrfOnlyForm>,
+) -> Response {
+    if let Err(redirect) = require_admin(&state, &session).await {
+        return redirect;
+    }
+
+    if let Err(resp) = require_csrf(&session, &form.token).await {
+        return resp;
+    }
+
+    let scheduled_updates = match scheduled_update_service(&state) {
+        Ok(service) => service,
+        Err(e) => return e.into_response(),
+    };
+
+    match scheduled_updates.cancel(id).await {
+        Ok(true) => {
+            tracing::info!(update_id = %id, "scheduled update cancelled");
+            Redirect::to("/admin/content/timed-updates").into_response()
+        }
+        Ok(false) => render_not_found(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to cancel scheduled update");
+            render_server_error("Failed to cancel the scheduled update.")
+        }
+    }
+}
@@ -1,9 +1,9 @@
 //! Search route handlers.
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{Html, IntoResponse, Response},
     routing::get,
 };
@@ -13,9 +13,13 @@ use uuid::Uuid;
 
 use tower_sessions::Session;
 
+use crate::cache::conditional;
+use crate::middleware::language::ResolvedLanguage;
 use crate::models::stage::LIVE_STAGE_ID;
 use crate::routes::auth::{SESSION_ACTIVE_STAGE, SESSION_USER_ID};
 use crate::routes::helpers::html_escape;
+use crate::search::{SearchResult, SearchResults};
+use crate::services::pagination::{PageClass, PaginationPolicy};
 use crate::state::AppState;
 
 /// Resolve the active stage IDs from the user session.
@@ -66,6 +70,7 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/search", get(search_html))
         .route("/api/search", get(search_json))
+        .route("/api/search/suggest", get(suggest_json))
 }
 
 /// Search query parameters.
@@ -76,17 +81,21 @@ pub struct SearchQuery {
     /// Page number (1-indexed).
     #[serde(default = "default_page")]
     pub page: i64,
-    /// Results per page.
-    #[serde(default = "default_limit")]
-    pub limit: i64,
+    /// Results per page (see [`PageClass::Search`] for the default and maximum).
+    pub per_page: Option<i64>,
+    /// Deprecated alias for `per_page`.
+    pub limit: Option<i64>,
 }
 
-fn default_page() -> i64 {
-    1
+impl SearchQuery {
+    /// Requested page size, from `per_page` or the legacy `limit`.
+    fn requested_per_page(&self) -> Option<i64> {
+        self.per_page.or(self.limit)
+    }
 }
 
-fn default_limit() -> i64 {
-    10
+fn default_page() -> i64 {
+    1
 }
 
 /// JSON search response.
@@ -98,6 +107,12 @@ pub struct SearchJsonResponse {
     pub page: i64,
     pub limit: i64,
     pub total_pages: i64,
+    /// "Did you mean" query, set when the full-text search matched nothing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// Set when the requested `limit` was clamped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 /// Single search result in JSON format.
@@ -110,17 +125,83 @@ pub struct SearchResultJson {
     pub rank: f32,
     pub snippet: Option<String>,
     pub url: String,
+    /// Matched by fuzzy title similarity rather than full-text search.
+    pub fuzzy: bool,
+}
+
+/// Suggestion query parameters.
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    /// Search query string.
+    pub q: Option<String>,
+}
+
+/// JSON "did you mean" response.
+#[derive(Debug, Serialize)]
+pub struct SuggestJsonResponse {
+    pub query: String,
+    pub suggestion: Option<String>,
+}
+
+/// Compute a "did you mean" suggestion for a search that found no
+/// full-text matches (no results, or only fuzzy ones).
+///
+/// Suggestion failures are logged and treated as "no suggestion".
+async fn suggestion_for(
+    state: &AppState,
+    query: &str,
+    results: &SearchResults,
+    stage_ids: &[Uuid],
+    user_id: Option<Uuid>,
+) -> Option<String> {
+    if query.trim().is_empty() || results.results.iter().any(|r| !r.fuzzy) {
+        return None;
+    }
+    state
+        .search()
+        .suggest(query, stage_ids, user_id)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "search suggestion failed");
+            None
+        })
+}
+
+/// Show result titles in the negotiated language.
+///
+/// Results without a translation in that language keep their own title.
+async fn localize_titles(state: &AppState, results: &mut [SearchResult], language: &str) {
+    if language == state.default_language() || results.is_empty() {
+        return;
+    }
+    let ids: Vec<Uuid> = results.iter().map(|r| r.id).collect();
+    match state.items().translated_titles(&ids, language).await {
+        Ok(titles) => {
+            for result in results {
+                if let Some(title) = titles.get(&result.id) {
+                    result.title = title.clone();
+                }
+            }
+        }
+        // The translation table only exists with the content translation
+        // plugin.
+        Err(e) => tracing::debug!(error = %e, "no translated search result titles"),
+    }
 }
 
 /// HTML search page.
 async fn search_html(
     State(state): State<AppState>,
+    Extension(lang): Extension<ResolvedLanguage>,
     session: Session,
     Query(params): Query<SearchQuery>,
 ) -> Response {
     let query = params.q.clone().unwrap_or_default();
     let page = params.page.max(1);
-    let limit = params.limit.clamp(1, 50);
+    let page_size = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Search, params.requested_per_page());
+    let limit = page_size.limit;
     let offset = (page - 1) * limit;
 
     // Get user ID if logged in
@@ -128,7 +209,7 @@ async fn search_html(
     let stage_ids = resolve_stage_ids(&session).await;
 
     // Execute search
-    let results = match state
+    let mut results = match state
         .search()
         .search(&query, &stage_ids, user_id, limit, offset)
         .await
@@ -144,6 +225,9 @@ async fn search_html(
         }
     };
 
+    let suggestion = suggestion_for(&state, &query, &results, &stage_ids, user_id).await;
+    localize_titles(&state, &mut results.results, &lang.0).await;
+
     // Calculate pagination
     let total_pages = (results.total + limit - 1) / limit;
 
@@ -170,6 +254,7 @@ async fn search_html(
     context.insert("has_next", &(page < total_pages));
     context.insert("prev_page", &(page - 1));
     context.insert("next_page", &(page + 1));
+    context.insert("suggestion", &suggestion);
 
     // Inject site context for page layout (header, nav, footer)
     super::helpers::inject_site_context(&state, &session, &mut context, "/search").await;
@@ -189,12 +274,17 @@ async fn search_html(
 /// JSON search endpoint.
 async fn search_json(
     State(state): State<AppState>,
+    Extension(lang): Extension<ResolvedLanguage>,
     session: Session,
     Query(params): Query<SearchQuery>,
+    headers: HeaderMap,
 ) -> Response {
     let query = params.q.clone().unwrap_or_default();
     let page = params.page.max(1);
-    let limit = params.limit.clamp(1, 50);
+    let page_size = PaginationPolicy::load(state.db())
+        .await
+        .resolve(PageClass::Search, params.requested_per_page());
+    let limit = page_size.limit;
     let offset = (page - 1) * limit;
 
     // Get user ID if logged in
@@ -202,7 +292,7 @@ async fn search_json(
     let stage_ids = resolve_stage_ids(&session).await;
 
     // Execute search
-    let results = match state
+    let mut results = match state
         .search()
         .search(&query, &stage_ids, user_id, limit, offset)
         .await
@@ -220,6 +310,9 @@ async fn search_json(
         }
     };
 
+    let suggestion = suggestion_for(&state, &query, &results, &stage_ids, user_id).await;
+    localize_titles(&state, &mut results.results, &lang.0).await;
+
     // Calculate pagination
     let total_pages = (results.total + limit - 1) / limit;
 
@@ -236,15 +329,47 @@ async fn search_json(
                 title: r.title,
                 rank: r.rank,
                 snippet: r.snippet.map(|s| sanitize_snippet(&s)),
+                fuzzy: r.fuzzy,
             })
             .collect(),
         total: results.total,
         page,
         limit,
         total_pages,
+        suggestion,
+        warning: page_size.warning,
     };
 
-    Json(response).into_response()
+    conditional::json_response(&headers, &response)
+}
+
+/// JSON "did you mean" endpoint.
+///
+/// `GET /api/search/suggest?q=helo+wrld` returns the query with each word
+/// replaced by the closest word from visible item titles, or `null` when
+/// nothing would change.
+async fn suggest_json(
+    State(state): State<AppState>,
+    session: Session,
+    Query(params): Query<SuggestQuery>,
+) -> Response {
+    let query = params.q.unwrap_or_default();
+    let user_id: Option<Uuid> = session.get(SESSION_USER_ID).await.ok().flatten();
+    let stage_ids = resolve_stage_ids(&session).await;
+
+    match state.search().suggest(&query, &stage_ids, user_id).await {
+        Ok(suggestion) => Json(SuggestJsonResponse { query, suggestion }).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "search suggestion failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Suggestion failed"
+                })),
+            )
+                .into_response()
+        }
+    }
 }
 
 /// Render fallback search HTML when template is unavailable.
@@ -1,21 +1,164 @@
 //! Sitemap.xml and robots.txt routes.
 //!
-//! Generates an XML sitemap of all published items (with URL alias
-//! resolution) and a robots.txt pointing to the sitemap.
+//! Generates an XML sitemap of published live-stage items (with URL alias
+//! resolution) and date-based archive pages, plus a robots.txt pointing
+//! to the sitemap.
+//!
+//! Per-type inclusion, priority and changefreq live in `site_config` under
+//! `sitemap`:
+//!
+//! ```json
+//! { "types": { "blog": { "priority": 0.8, "changefreq": "weekly" },
+//!              "page": { "include": false } } }
+//! ```
+//!
+//! Types without settings are included with no priority or changefreq.
+//! Above [`MAX_URLS_PER_SITEMAP`] URLs, `/sitemap.xml` becomes a sitemap
+//! index over `/sitemap/{n}.xml` pages and `/sitemap/archives.xml`.
+//! Output is cached under [`ITEM_LISTING_TAG`], which item saves invalidate.
+
+use std::collections::HashMap;
 
+use anyhow::{Context, Result};
 use axum::Router;
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Response};
 use axum::routing::get;
+use serde::{Deserialize, Serialize};
 
+use crate::cache::ITEM_LISTING_TAG;
+use crate::gather::{ArchiveBucket, ArchivePeriod};
 use crate::models::SiteConfig;
 use crate::models::stage::LIVE_STAGE_ID;
+use crate::services::site::current_tenant_id;
 use crate::state::AppState;
 
+use super::helpers::html_escape;
+
+/// `site_config` key holding [`SitemapSettings`].
+pub const SITEMAP_SETTINGS_KEY: &str = "sitemap";
+
+/// Maximum URLs in one sitemap file (sitemaps.org protocol limit).
+pub const MAX_URLS_PER_SITEMAP: i64 = 50_000;
+
+/// TTL for cached sitemap output; item saves invalidate it sooner.
+const SITEMAP_CACHE_TTL_SECS: u64 = 3600;
+
+/// How frequently a page is likely to change (sitemap `<changefreq>`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeFreq {
+    /// Changes on every access.
+    Always,
+    /// Changes about once an hour.
+    Hourly,
+    /// Changes about once a day.
+    Daily,
+    /// Changes about once a week.
+    Weekly,
+    /// Changes about once a month.
+    Monthly,
+    /// Changes about once a year.
+    Yearly,
+    /// Archived; never changes.
+    Never,
+}
+
+impl ChangeFreq {
+    /// Protocol value for `<changefreq>`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// Sitemap settings for one item type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SitemapTypeSettings {
+    /// Whether items of this type are listed.
+    pub include: bool,
+    /// Priority (0.0–1.0); omitted from output when `None`.
+    pub priority: Option<f32>,
+    /// Expected change frequency; omitted from output when `None`.
+    pub changefreq: Option<ChangeFreq>,
+}
+
+impl Default for SitemapTypeSettings {
+    fn default() -> Self {
+        Self {
+            include: true,
+            priority: None,
+            changefreq: None,
+        }
+    }
+}
+
+/// Sitemap settings, keyed by item type.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SitemapSettings {
+    /// Per-type settings; types not listed use [`SitemapTypeSettings::default`].
+    pub types: HashMap<String, SitemapTypeSettings>,
+}
+
+impl SitemapSettings {
+    /// Load settings from `site_config`, falling back to defaults.
+    pub async fn load(pool: &sqlx::PgPool) -> Self {
+        match SiteConfig::get(pool, SITEMAP_SETTINGS_KEY).await {
+            Ok(Some(value)) => serde_json::from_value(value).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "invalid sitemap settings, using defaults");
+                Self::default()
+            }),
+            Ok(None) => Self::default(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load sitemap settings");
+                Self::default()
+            }
+        }
+    }
+
+    /// Item types explicitly excluded from the sitemap.
+    pub fn excluded_types(&self) -> Vec<String> {
+        let mut excluded: Vec<String> = self
+            .types
+            .iter()
+            .filter(|(_, s)| !s.include)
+            .map(|(t, _)| t.clone())
+            .collect();
+        excluded.sort();
+        excluded
+    }
+
+    /// Settings for an item type (defaults if not configured).
+    pub fn for_type(&self, item_type: &str) -> SitemapTypeSettings {
+        self.types.get(item_type).cloned().unwrap_or_default()
+    }
+}
+
+/// One `<url>` entry.
+#[derive(Debug, Clone, PartialEq)]
+struct SitemapEntry {
+    /// Site-relative path.
+    path: String,
+    /// Last modification date (`YYYY-MM-DD`).
+    lastmod: String,
+    changefreq: Option<ChangeFreq>,
+    priority: Option<f32>,
+}
+
 /// Row type for sitemap item queries.
 #[derive(sqlx::FromRow)]
 struct SitemapRow {
     id: uuid::Uuid,
+    item_type: String,
     changed: i64,
 }
 
@@ -26,54 +169,194 @@ struct AliasRow {
     alias: String,
 }
 
-/// Generate sitemap.xml listing all published live-stage items.
-async fn sitemap_xml(State(state): State<AppState>) -> Response {
-    let items = match sqlx::query_as::<_, SitemapRow>(
-        "SELECT id, changed FROM item WHERE status = 1 AND stage_id = $1 ORDER BY changed DESC",
+/// Format a Unix timestamp as a sitemap `<lastmod>` date.
+fn format_lastmod(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Count the current site's published live-stage items in included types.
+async fn count_items(state: &AppState, settings: &SitemapSettings) -> Result<i64> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM item WHERE status = 1 AND stage_id = $1 AND NOT (type = ANY($2)) AND tenant_id = $3",
+    )
+    .bind(LIVE_STAGE_ID)
+    .bind(settings.excluded_types())
+    .bind(current_tenant_id())
+    .fetch_one(state.db())
+    .await
+    .context("failed to count sitemap items")
+}
+
+/// Load one page (zero-based) of item entries, newest change first.
+async fn item_entries(
+    state: &AppState,
+    settings: &SitemapSettings,
+    page: i64,
+) -> Result<Vec<SitemapEntry>> {
+    let rows = sqlx::query_as::<_, SitemapRow>(
+        r#"
+        SELECT id, type AS item_type, changed FROM item
+        WHERE status = 1 AND stage_id = $1 AND NOT (type = ANY($2)) AND tenant_id = $3
+        ORDER BY changed DESC, id
+        LIMIT $4 OFFSET $5
+        "#,
     )
     .bind(LIVE_STAGE_ID)
+    .bind(settings.excluded_types())
+    .bind(current_tenant_id())
+    .bind(MAX_URLS_PER_SITEMAP)
+    .bind(page * MAX_URLS_PER_SITEMAP)
     .fetch_all(state.db())
     .await
-    {
-        Ok(rows) => rows,
-        Err(e) => {
-            tracing::error!(error = %e, "failed to query items for sitemap");
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                "Sitemap generation failed",
-            )
-                .into_response();
-        }
-    };
+    .context("failed to query items for sitemap")?;
 
-    // Bulk-load URL aliases for item sources so we can emit friendly URLs.
-    let aliases: std::collections::HashMap<String, String> = match sqlx::query_as::<_, AliasRow>(
-        "SELECT source, alias FROM url_alias WHERE source LIKE '/item/%' AND stage_id = $1",
+    // Load URL aliases for this page's items so we can emit friendly URLs.
+    let sources: Vec<String> = rows.iter().map(|r| format!("/item/{}", r.id)).collect();
+    let aliases: HashMap<String, String> = match sqlx::query_as::<_, AliasRow>(
+        "SELECT source, alias FROM url_alias WHERE source = ANY($1) AND stage_id = $2",
     )
+    .bind(&sources)
     .bind(LIVE_STAGE_ID)
     .fetch_all(state.db())
     .await
     {
         Ok(rows) => rows.into_iter().map(|r| (r.source, r.alias)).collect(),
-        Err(_) => std::collections::HashMap::new(),
+        Err(_) => HashMap::new(),
     };
 
+    Ok(rows
+        .into_iter()
+        .zip(sources)
+        .map(|(row, source)| {
+            let type_settings = settings.for_type(&row.item_type);
+            SitemapEntry {
+                path: aliases.get(&source).cloned().unwrap_or(source),
+                lastmod: format_lastmod(row.changed),
+                changefreq: type_settings.changefreq,
+                priority: type_settings.priority,
+            }
+        })
+        .collect())
+}
+
+/// Archive period entries for gather queries with `display.archive`.
+async fn archive_entries(state: &AppState) -> Vec<SitemapEntry> {
+    let mut entries = Vec::new();
+    for query in state.gather().list_queries() {
+        let (Some(config), Some(item_type)) = (
+            query.display.archive.as_ref(),
+            query.definition.item_type.as_deref(),
+        ) else {
+            continue;
+        };
+        if !config.sitemap {
+            continue;
+        }
+        let buckets = match state
+            .gather()
+            .archive_counts(item_type, &[LIVE_STAGE_ID])
+            .await
+        {
+            Ok(b) => b,
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    query_id = %query.query_id,
+                    "failed to load archive counts for sitemap"
+                );
+                continue;
+            }
+        };
+        entries.extend(
+            archive_sitemap_entries(&buckets, &config.base_path)
+                .into_iter()
+                .map(|(path, lastmod)| SitemapEntry {
+                    path,
+                    lastmod,
+                    changefreq: None,
+                    priority: None,
+                }),
+        );
+    }
+    entries
+}
+
+/// Render a `<urlset>` document.
+fn render_urlset(site_url: &str, entries: &[SitemapEntry]) -> String {
     let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
 
-    for row in &items {
-        let source = format!("/item/{}", row.id);
-        let url = aliases.get(&source).cloned().unwrap_or(source);
-        let lastmod = chrono::DateTime::from_timestamp(row.changed, 0)
-            .map(|dt| dt.format("%Y-%m-%d").to_string())
-            .unwrap_or_default();
-
+    for entry in entries {
         xml.push_str(&format!(
-            "  <url>\n    <loc>{url}</loc>\n    <lastmod>{lastmod}</lastmod>\n  </url>\n"
+            "  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n",
+            html_escape(&format!("{site_url}{}", entry.path)),
+            entry.lastmod
         ));
+        if let Some(freq) = entry.changefreq {
+            xml.push_str(&format!("    <changefreq>{}</changefreq>\n", freq.as_str()));
+        }
+        if let Some(priority) = entry.priority {
+            xml.push_str(&format!(
+                "    <priority>{:.1}</priority>\n",
+                priority.clamp(0.0, 1.0)
+            ));
+        }
+        xml.push_str("  </url>\n");
     }
 
     xml.push_str("</urlset>");
+    xml
+}
+
+/// Render a `<sitemapindex>` document over site-relative sitemap paths.
+fn render_index(site_url: &str, paths: &[String]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for path in paths {
+        xml.push_str(&format!(
+            "  <sitemap>\n    <loc>{}</loc>\n  </sitemap>\n",
+            html_escape(&format!("{site_url}{path}"))
+        ));
+    }
+    xml.push_str("</sitemapindex>");
+    xml
+}
+
+/// Number of item pages needed for `count` items.
+fn page_count(count: i64) -> i64 {
+    (count + MAX_URLS_PER_SITEMAP - 1) / MAX_URLS_PER_SITEMAP
+}
+
+/// Serve cached XML, building and caching it on a miss.
+///
+/// `Ok(None)` from the builder means "not found".
+async fn cached_xml<F>(state: &AppState, key: &str, build: F) -> Response
+where
+    F: std::future::Future<Output = Result<Option<String>>>,
+{
+    let xml = match state.cache().get(key).await {
+        Some(xml) => xml,
+        None => match build.await {
+            Ok(Some(xml)) => {
+                state
+                    .cache()
+                    .set(key, &xml, SITEMAP_CACHE_TTL_SECS, &[ITEM_LISTING_TAG])
+                    .await;
+                xml
+            }
+            Ok(None) => return axum::http::StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                tracing::error!(error = %e, "sitemap generation failed");
+                return (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    "Sitemap generation failed",
+                )
+                    .into_response();
+            }
+        },
+    };
 
     (
         axum::http::StatusCode::OK,
@@ -83,6 +366,88 @@ async fn sitemap_xml(State(state): State<AppState>) -> Response {
         .into_response()
 }
 
+/// Generate sitemap.xml: a single urlset, or a sitemap index when the site
+/// has more than [`MAX_URLS_PER_SITEMAP`] URLs.
+async fn sitemap_xml(State(state): State<AppState>) -> Response {
+    cached_xml(&state, "sitemap:root", async {
+        let settings = SitemapSettings::load(state.db()).await;
+        let count = count_items(&state, &settings).await?;
+        let archives = archive_entries(&state).await;
+        let site_url = state.site_url();
+
+        let total = count.saturating_add(i64::try_from(archives.len()).unwrap_or(i64::MAX));
+        if total <= MAX_URLS_PER_SITEMAP {
+            let mut entries = item_entries(&state, &settings, 0).await?;
+            entries.extend(archives);
+            return Ok(Some(render_urlset(site_url, &entries)));
+        }
+
+        let mut paths: Vec<String> = (1..=page_count(count))
+            .map(|n| format!("/sitemap/{n}.xml"))
+            .collect();
+        if !archives.is_empty() {
+            paths.push("/sitemap/archives.xml".to_string());
+        }
+        Ok(Some(render_index(site_url, &paths)))
+    })
+    .await
+}
+
+/// Generate one sitemap index page: `/sitemap/{n}.xml` (1-based) or
+/// `/sitemap/archives.xml`.
+async fn sitemap_page(State(state): State<AppState>, Path(file): Path<String>) -> Response {
+    let Some(name) = file.strip_suffix(".xml") else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+
+    if name == "archives" {
+        return cached_xml(&state, "sitemap:archives", async {
+            let archives = archive_entries(&state).await;
+            Ok(Some(render_urlset(state.site_url(), &archives)))
+        })
+        .await;
+    }
+
+    let Some(page) = name.parse::<i64>().ok().filter(|n| *n >= 1) else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+
+    cached_xml(&state, &format!("sitemap:page:{page}"), async {
+        let settings = SitemapSettings::load(state.db()).await;
+        let count = count_items(&state, &settings).await?;
+        if page > page_count(count) {
+            return Ok(None);
+        }
+        let entries = item_entries(&state, &settings, page - 1).await?;
+        Ok(Some(render_urlset(state.site_url(), &entries)))
+    })
+    .await
+}
+
+/// Build `(url, lastmod)` pairs for archive years and months.
+///
+/// Each year's lastmod is the latest change among its months.
+fn archive_sitemap_entries(buckets: &[ArchiveBucket], base_path: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut year_latest: Vec<(i32, i64)> = Vec::new();
+    for bucket in buckets {
+        match year_latest.last_mut() {
+            Some((y, latest)) if *y == bucket.year => *latest = (*latest).max(bucket.last_changed),
+            _ => year_latest.push((bucket.year, bucket.last_changed)),
+        }
+        let period = ArchivePeriod {
+            year: bucket.year,
+            month: u32::try_from(bucket.month).ok(),
+        };
+        entries.push((period.path(base_path), format_lastmod(bucket.last_changed)));
+    }
+    for (year, latest) in year_latest {
+        let period = ArchivePeriod { year, month: None };
+        entries.push((period.path(base_path), format_lastmod(latest)));
+    }
+    entries
+}
+
 /// Known AI search engine crawlers and their `site_config` toggle keys.
 const AI_CRAWLERS: [(&str, &str); 8] = [
     ("GPTBot", "gptbot_blocked"),
@@ -138,7 +503,7 @@ async fn robots_txt(State(state): State<AppState>) -> Response {
     }
 
     // Sitemap reference
-    sections.push("Sitemap: /sitemap.xml".to_string());
+    sections.push(format!("Sitemap: {}/sitemap.xml", state.site_url()));
 
     let body = sections.join("\n");
     (
@@ -153,6 +518,7 @@ async fn robots_txt(State(state): State<AppState>) -> Response {
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/sitemap.xml", get(sitemap_xml))
+        .route("/sitemap/{file}", get(sitemap_page))
         .route("/robots.txt", get(robots_txt))
 }
 
@@ -176,4 +542,92 @@ mod tests {
         assert!(names.contains(&"PerplexityBot"));
         assert!(names.contains(&"Amazonbot"));
     }
+
+    #[test]
+    fn archive_sitemap_entries_include_months_and_years() {
+        let buckets = vec![
+            ArchiveBucket {
+                year: 2024,
+                month: 5,
+                count: 2,
+                last_changed: 1_716_000_000, // 2024-05-18
+            },
+            ArchiveBucket {
+                year: 2024,
+                month: 3,
+                count: 1,
+                last_changed: 1_710_000_000, // 2024-03-09
+            },
+        ];
+        let entries = archive_sitemap_entries(&buckets, "/blog");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0, "/blog/2024/05");
+        assert_eq!(entries[1].0, "/blog/2024/03");
+        assert_eq!(
+            entries[2],
+            ("/blog/2024".to_string(), "2024-05-18".to_string())
+        );
+    }
+
+    #[test]
+    fn settings_parse_and_exclude() {
+        let settings: SitemapSettings = serde_json::from_value(serde_json::json!({
+            "types": {
+                "blog": { "priority": 0.8, "changefreq": "weekly" },
+                "page": { "include": false }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(settings.excluded_types(), vec!["page".to_string()]);
+        let blog = settings.for_type("blog");
+        assert!(blog.include);
+        assert_eq!(blog.priority, Some(0.8));
+        assert_eq!(blog.changefreq, Some(ChangeFreq::Weekly));
+        // Unconfigured types are included with no hints.
+        assert_eq!(settings.for_type("event"), SitemapTypeSettings::default());
+    }
+
+    #[test]
+    fn urlset_renders_optional_hints_and_escapes() {
+        let entries = vec![
+            SitemapEntry {
+                path: "/a?x=1&y=2".to_string(),
+                lastmod: "2024-05-18".to_string(),
+                changefreq: Some(ChangeFreq::Daily),
+                priority: Some(1.5),
+            },
+            SitemapEntry {
+                path: "/b".to_string(),
+                lastmod: "2024-05-17".to_string(),
+                changefreq: None,
+                priority: None,
+            },
+        ];
+        let xml = render_urlset("https://example.com", &entries);
+        assert!(xml.contains("<loc>https://example.com/a?x=1&amp;y=2</loc>"));
+        assert!(xml.contains("<changefreq>daily</changefreq>"));
+        assert!(xml.contains("<priority>1.0</priority>"));
+        assert_eq!(xml.matches("<changefreq>").count(), 1);
+        assert_eq!(xml.matches("<url>").count(), 2);
+    }
+
+    #[test]
+    fn index_lists_pages() {
+        let xml = render_index(
+            "https://example.com",
+            &["/sitemap/1.xml".to_string(), "/sitemap/2.xml".to_string()],
+        );
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<sitemapindex"));
+        assert!(xml.contains("<loc>https://example.com/sitemap/2.xml</loc>"));
+    }
+
+    #[test]
+    fn page_count_rounds_up() {
+        assert_eq!(page_count(0), 0);
+        assert_eq!(page_count(1), 1);
+        assert_eq!(page_count(MAX_URLS_PER_SITEMAP), 1);
+        assert_eq!(page_count(MAX_URLS_PER_SITEMAP + 1), 2);
+    }
 }
@@ -0,0 +1,66 @@
+//! Status report (admin only).
+//!
+//! - `GET /admin/reports/status` — kernel and plugin requirement checks
+//! - `GET /admin/reports/status.json` — the same report for uptime
+//!   monitors: `503 Service Unavailable` when any check has an error
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+use super::helpers::{admin_user_context, render_admin_template, require_admin};
+
+/// Create the status report router.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/reports/status", get(status_report))
+        .route("/admin/reports/status.json", get(status_report_json))
+}
+
+/// Show the status report.
+///
+/// GET /admin/reports/status
+async fn status_report(State(state): State<AppState>, session: Session) -> Response {
+    let user = match require_admin(&state, &session).await {
+        Ok(user) => user,
+        Err(redirect) => return redirect,
+    };
+
+    let report = state.status_report(admin_user_context(&user)).await;
+
+    let mut context = tera::Context::new();
+    context.insert("report", &report);
+    context.insert("path", "/admin/reports/status");
+
+    render_admin_template(&state, "admin/status-report.html", context).await
+}
+
+/// Status report as JSON.
+///
+/// GET /admin/reports/status.json
+///
+/// Answers 200 while no check has an error, so monitors can alert on the
+/// status code alone. Authenticate with an API token of an admin user.
+async fn status_report_json(
+    State(state): State<AppState>,
+    session: Session,
+) -> Result<Response, AppError> {
+    let user = require_admin(&state, &session)
+        .await
+        .map_err(|_| AppError::forbidden("Admin access required"))?;
+
+    let report = state.status_report(admin_user_context(&user)).await;
+    let status = if report.has_errors() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((status, Json(report)).into_response())
+}
@@ -8,8 +8,10 @@ use serde::Deserialize;
 use tower_sessions::Session;
 use uuid::Uuid;
 
+use crate::cache::page::PAGE_TAG;
 use crate::form::csrf::generate_csrf_token;
 use crate::models::tile::{CreateTile, Tile, UpdateTile};
+use crate::services::tile::{PLUGIN_TILE_PREFIX, REGIONS, tile_tag};
 use crate::state::AppState;
 
 use super::helpers::{
@@ -33,6 +35,16 @@ pub struct TileFormData {
     pub format: Option<String>,
     pub menu_name: Option<String>,
     pub query_id: Option<String>,
+    /// JSON object passed to plugin blocks as their configuration.
+    pub block_config: Option<String>,
+    /// Paths to show the tile on, one per line.
+    pub visibility_paths: Option<String>,
+    /// Paths to hide the tile on, one per line.
+    pub visibility_paths_exclude: Option<String>,
+    /// Comma-separated role names.
+    pub visibility_roles: Option<String>,
+    /// Comma-separated content type machine names.
+    pub visibility_content_types: Option<String>,
     #[serde(default)]
     pub weight: i32,
     pub status: Option<String>,
@@ -53,9 +65,84 @@ impl TileFormData {
                 "query_id": self.query_id.as_deref().unwrap_or(""),
             }),
             "chat" => serde_json::json!({}),
+            t if t.starts_with(PLUGIN_TILE_PREFIX) => self
+                .block_config
+                .as_deref()
+                .filter(|c| !c.trim().is_empty())
+                .and_then(|c| serde_json::from_str(c).ok())
+                .filter(serde_json::Value::is_object)
+                .unwrap_or_else(|| serde_json::json!({})),
             _ => serde_json::json!({}),
         }
     }
+
+    /// Build the visibility rules JSON from form data. Empty fields add no
+    /// rule.
+    fn build_visibility(&self) -> serde_json::Value {
+        let mut rules = serde_json::Map::new();
+        let lists = [
+            ("paths", &self.visibility_paths, '\n'),
+            ("paths_exclude", &self.visibility_paths_exclude, '\n'),
+            ("roles", &self.visibility_roles, ','),
+            ("content_types", &self.visibility_content_types, ','),
+        ];
+        for (key, value, separator) in lists {
+            let values: Vec<&str> = value
+                .as_deref()
+                .unwrap_or("")
+                .split(separator)
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .collect();
+            if !values.is_empty() {
+                rules.insert(key.to_string(), serde_json::json!(values));
+            }
+        }
+        serde_json::Value::Object(rules)
+    }
+}
+
+/// The plugin owning a tile type: `core` for kernel types, the declaring
+/// plugin for `plugin:{block}`, `None` for an unknown block.
+fn tile_type_owner(state: &AppState, tile_type: &str) -> Option<String> {
+    match tile_type.strip_prefix(PLUGIN_TILE_PREFIX) {
+        Some(block) => state
+            .tiles()
+            .blocks()
+            .and_then(|blocks| blocks.get(block))
+            .map(|block| block.plugin.clone()),
+        None => Some("core".to_string()),
+    }
+}
+
+/// Insert the region, plugin block and content type choices for the tile form.
+fn insert_form_options(state: &AppState, context: &mut tera::Context) {
+    let regions: Vec<serde_json::Value> = REGIONS
+        .iter()
+        .map(|(name, label)| serde_json::json!({ "name": name, "label": label }))
+        .collect();
+    context.insert("regions", &regions);
+    let blocks = state
+        .tiles()
+        .blocks()
+        .map(|blocks| blocks.list())
+        .unwrap_or_default();
+    context.insert("plugin_blocks", &blocks);
+    let mut content_types: Vec<String> = state
+        .content_types()
+        .list()
+        .into_iter()
+        .map(|t| t.machine_name)
+        .collect();
+    content_types.sort();
+    context.insert("content_types", &content_types);
+}
+
+/// Drop cached pages and block output after a tile changed.
+async fn invalidate_tile(state: &AppState, tile_id: Uuid) {
+    state.cache().invalidate_tag(&tile_tag(tile_id)).await;
+    // A placed, moved or deleted tile can change any page.
+    state.cache().invalidate_tag(PAGE_TAG).await;
 }
 
 // -------------------------------------------------------------------------
@@ -105,6 +192,7 @@ async fn add_tile_form(State(state): State<AppState>, session: Session) -> Respo
     context.insert("editing", &false);
     context.insert("values", &serde_json::json!({}));
     context.insert("path", "/admin/structure/tiles/add");
+    insert_form_options(&state, &mut context);
 
     render_admin_template(&state, "admin/tile-form.html", context).await
 }
@@ -131,6 +219,10 @@ async fn add_tile_submit(
         );
     }
 
+    let Some(plugin) = tile_type_owner(&state, &form.tile_type) else {
+        return render_error("Unknown block type.");
+    };
+
     let config = form.build_config();
     let input = CreateTile {
         machine_name: form.machine_name.clone(),
@@ -138,15 +230,18 @@ async fn add_tile_submit(
         region: Some(form.region.clone()),
         tile_type: Some(form.tile_type.clone()),
         config: Some(config),
-        visibility: None,
+        visibility: Some(form.build_visibility()),
         weight: Some(form.weight),
         status: Some(if form.status.is_some() { 1 } else { 0 }),
-        plugin: None,
+        plugin: Some(plugin),
         stage_id: None,
     };
 
     match Tile::create(state.db(), input).await {
-        Ok(_) => Redirect::to("/admin/structure/tiles").into_response(),
+        Ok(tile) => {
+            invalidate_tile(&state, tile.id).await;
+            Redirect::to("/admin/structure/tiles").into_response()
+        }
         Err(e) => {
             tracing::error!(error = %e, "failed to create tile");
             render_server_error("Failed to create tile.")
@@ -171,6 +266,19 @@ async fn edit_tile_form(
     };
 
     let csrf_token = generate_csrf_token(&session).await;
+    let visibility_list = |key: &str, separator: &str| {
+        tile.visibility
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect::<Vec<_>>()
+                    .join(separator)
+            })
+            .unwrap_or_default()
+    };
 
     let mut context = tera::Context::new();
     context.insert("action", &format!("/admin/structure/tiles/{tile_id}/edit"));
@@ -188,11 +296,17 @@ async fn edit_tile_form(
             "format": tile.config.get("format").and_then(|v| v.as_str()).unwrap_or("filtered_html"),
             "menu_name": tile.config.get("menu_name").and_then(|v| v.as_str()).unwrap_or("main"),
             "query_id": tile.config.get("query_id").and_then(|v| v.as_str()).unwrap_or(""),
+            "block_config": if tile.tile_type.starts_with(PLUGIN_TILE_PREFIX) { tile.config.to_string() } else { String::new() },
+            "visibility_paths": visibility_list("paths", "\n"),
+            "visibility_paths_exclude": visibility_list("paths_exclude", "\n"),
+            "visibility_roles": visibility_list("roles", ", "),
+            "visibility_content_types": visibility_list("content_types", ", "),
             "weight": tile.weight,
             "status": tile.status == 1,
         }),
     );
     context.insert("path", &format!("/admin/structure/tiles/{tile_id}/edit"));
+    insert_form_options(&state, &mut context);
 
     render_admin_template(&state, "admin/tile-form.html", context).await
 }
@@ -214,21 +328,28 @@ async fn edit_tile_submit(
         return resp;
     }
 
+    let Some(plugin) = tile_type_owner(&state, &form.tile_type) else {
+        return render_error("Unknown block type.");
+    };
+
     let config = form.build_config();
     let input = UpdateTile {
         label: Some(form.label.clone()),
         region: Some(form.region.clone()),
         tile_type: Some(form.tile_type.clone()),
         config: Some(config),
-        visibility: None,
+        visibility: Some(form.build_visibility()),
         weight: Some(form.weight),
         status: Some(if form.status.is_some() { 1 } else { 0 }),
-        plugin: None,
+        plugin: Some(plugin),
         stage_id: None,
     };
 
     match Tile::update(state.db(), tile_id, input).await {
-        Ok(_) => Redirect::to("/admin/structure/tiles").into_response(),
+        Ok(_) => {
+            invalidate_tile(&state, tile_id).await;
+            Redirect::to("/admin/structure/tiles").into_response()
+        }
         Err(e) => {
             tracing::error!(error = %e, "failed to update tile");
             render_server_error("Failed to update tile.")
@@ -261,7 +382,10 @@ async fn delete_tile(
     }
 
     match Tile::delete(state.db(), tile_id).await {
-        Ok(true) => Redirect::to("/admin/structure/tiles").into_response(),
+        Ok(true) => {
+            invalidate_tile(&state, tile_id).await;
+            Redirect::to("/admin/structure/tiles").into_response()
+        }
         Ok(false) => render_not_found(),
         Err(e) => {
             tracing::error!(error = %e, "failed to delete tile");
@@ -0,0 +1,183 @@
+//! Inbound webhook receiver.
+//!
+//! - `GET|POST /api/hooks/{plugin}/{hook}` — verify a call against the
+//!   hook's secret and pass it to the plugin's `tap_webhook_receive`
+//!
+//! Hooks are declared in the plugin's `.info.toml`; see
+//! [`crate::services::webhook`] for secrets and verification. Calls are
+//! rate limited per client by the `webhooks` rate limit category.
+
+use std::collections::HashMap;
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use tracing::{debug, warn};
+use trovato_sdk::types::{WebhookRequest, WebhookResponse};
+
+use crate::error::AppError;
+use crate::plugin::WebhookVerify;
+use crate::services::webhook;
+use crate::state::AppState;
+use crate::tap::{RequestState, UserContext};
+
+/// Tap receiving webhook calls.
+const WEBHOOK_TAP: &str = "tap_webhook_receive";
+
+/// Create the webhook router.
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/api/hooks/{plugin}/{hook}",
+        get(receive_webhook).post(receive_webhook),
+    )
+}
+
+/// Receive a webhook call for a plugin.
+///
+/// GET|POST /api/hooks/{plugin}/{hook}
+///
+/// Unknown hooks, hooks of disabled plugins, and hooks without a secret
+/// answer 404; calls failing verification answer 401. The plugin's
+/// [`WebhookResponse`] becomes the response.
+async fn receive_webhook(
+    State(state): State<AppState>,
+    Path((plugin, hook)): Path<(String, String)>,
+    method: Method,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    body: Bytes,
+) -> Result<Response, AppError> {
+    let not_found = || AppError::not_found_id("webhook", format!("{plugin}/{hook}"));
+
+    if !state.is_plugin_enabled(&plugin) {
+        return Err(not_found());
+    }
+    let compiled = state
+        .plugin_runtime()
+        .get_plugin(&plugin)
+        .ok_or_else(not_found)?;
+    let config = compiled.info.webhooks.get(&hook).ok_or_else(not_found)?;
+
+    if !config.methods.iter().any(|m| m == method.as_str()) {
+        return Ok(StatusCode::METHOD_NOT_ALLOWED.into_response());
+    }
+
+    let Some(secret) = webhook::load_secret(state.db(), &plugin, &hook)
+        .await
+        .map_err(|e| AppError::internal_ctx(e, "load webhook secret"))?
+    else {
+        warn!(plugin = %plugin, hook = %hook, "webhook called but no secret is configured");
+        return Err(not_found());
+    };
+
+    // Signed hooks cannot sign GET requests (e.g. WebSub intent
+    // verification); those pass through unverified for the plugin to judge.
+    let unsigned_get = method == Method::GET && config.verify == WebhookVerify::Signature;
+    let verified = webhook::verify(config, &secret, &headers, &body);
+    if !verified && !unsigned_get {
+        warn!(plugin = %plugin, hook = %hook, "webhook verification failed");
+        return Err(AppError::unauthorized("invalid webhook signature"));
+    }
+
+    let body = String::from_utf8(body.to_vec())
+        .map_err(|_| AppError::bad_request("webhook body must be UTF-8"))?;
+    let input = WebhookRequest {
+        hook: hook.clone(),
+        method: method.to_string(),
+        headers: collect_headers(&headers),
+        query,
+        body,
+        verified,
+    };
+    let input_json =
+        serde_json::to_string(&input).map_err(|e| AppError::internal_ctx(e, "encode webhook"))?;
+
+    let request_state = RequestState::new(UserContext::anonymous(), state.tap_services().clone());
+    let result = state
+        .tap_dispatcher()
+        .dispatch_to_plugin(WEBHOOK_TAP, &input_json, &plugin, request_state)
+        .await
+        .ok_or_else(|| AppError::Plugin {
+            plugin: plugin.clone(),
+            message: format!("{WEBHOOK_TAP} failed for hook '{hook}'"),
+        })?;
+
+    let reply = if result.output.is_empty() {
+        WebhookResponse::default()
+    } else {
+        serde_json::from_str(&result.output).map_err(|e| AppError::Plugin {
+            plugin: plugin.clone(),
+            message: format!("invalid {WEBHOOK_TAP} output: {e}"),
+        })?
+    };
+    debug!(plugin = %plugin, hook = %hook, status = reply.status, "webhook received");
+
+    Ok(into_response(reply))
+}
+
+/// Flatten request headers into lowercase names and string values.
+fn collect_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut collected: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        collected
+            .entry(name.as_str().to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    collected
+}
+
+/// Turn a plugin's reply into an HTTP response.
+fn into_response(reply: WebhookResponse) -> Response {
+    let status = StatusCode::from_u16(reply.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let content_type = reply
+        .content_type
+        .unwrap_or_else(|| "text/plain; charset=utf-8".to_string());
+    (status, [(header::CONTENT_TYPE, content_type)], reply.body).into_response()
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_are_lowercased_and_joined() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-GitHub-Event", "push".parse().unwrap());
+        headers.append("accept", "text/html".parse().unwrap());
+        headers.append("accept", "application/json".parse().unwrap());
+
+        let collected = collect_headers(&headers);
+        assert_eq!(collected["x-github-event"], "push");
+        assert_eq!(collected["accept"], "text/html, application/json");
+    }
+
+    #[test]
+    fn reply_becomes_response() {
+        let response = into_response(WebhookResponse::text("challenge"));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+
+        let response = into_response(WebhookResponse::status(202));
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let response = into_response(WebhookResponse::status(42));
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
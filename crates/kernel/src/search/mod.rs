@@ -1,9 +1,13 @@
 //! Full-text search service.
 //!
 //! Uses PostgreSQL tsvector columns with GIN indexes for efficient
-//! full-text search across content items.
+//! full-text search across content items. When a query matches nothing
+//! (usually a typo), titles are matched by `pg_trgm` word similarity
+//! instead and the results are flagged `fuzzy`. Embedding similarity for
+//! plugins lives in [`vector`].
 
 pub mod prompts;
+pub mod vector;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -11,6 +15,13 @@ use sqlx::PgPool;
 use tracing::debug;
 use uuid::Uuid;
 
+use crate::db::DbPools;
+use crate::profiling::{self, Phase};
+use crate::services::site::current_tenant_id;
+
+/// Redis queue of item IDs whose search vectors need rebuilding.
+pub const REINDEX_QUEUE: &str = "search:reindex";
+
 /// Search result with ranking information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -24,6 +35,9 @@ pub struct SearchResult {
     pub rank: f32,
     /// Snippet with highlighted matches.
     pub snippet: Option<String>,
+    /// Matched by trigram title similarity rather than full-text search.
+    #[serde(default)]
+    pub fuzzy: bool,
 }
 
 /// Collection of search results with pagination.
@@ -45,12 +59,24 @@ pub struct SearchResults {
 #[derive(Clone)]
 pub struct SearchService {
     pool: PgPool,
+    /// Pools for search queries; index maintenance uses `pool`.
+    read_pools: DbPools,
 }
 
 impl SearchService {
     /// Create a new search service.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            read_pools: DbPools::new(pool.clone(), Vec::new()),
+            pool,
+        }
+    }
+
+    /// Run search queries (search, fuzzy search, suggestions) on read
+    /// replicas when available.
+    pub fn with_read_pools(mut self, read_pools: DbPools) -> Self {
+        self.read_pools = read_pools;
+        self
     }
 
     /// Search for items matching the query.
@@ -58,7 +84,12 @@ impl SearchService {
     /// Uses PostgreSQL full-text search with ts_rank for relevance scoring.
     /// Results are filtered to only include items whose `stage_id` is in
     /// `stage_ids`. If `user_id` is provided, also includes the user's
-    /// draft items (still stage-filtered).
+    /// draft items (still stage-filtered). Items with access grants are
+    /// only returned when the user's grants allow viewing them. Only items
+    /// of the current site's tenant are searched.
+    ///
+    /// When the full-text query matches nothing, falls back to
+    /// [`fuzzy_search`](Self::fuzzy_search) with the same filters.
     pub async fn search(
         &self,
         query: &str,
@@ -77,14 +108,11 @@ impl SearchService {
                 limit,
             });
         }
+        let _db_timer = profiling::Timer::start(Phase::Db);
+        let mut conn = self.read_pools.acquire_read().await?;
+        let tenant_id = current_tenant_id();
 
-        // Convert query to tsquery format
-        // Split on whitespace and join with & for AND search
-        let ts_query = query_clean
-            .split_whitespace()
-            .map(|w| format!("{w}:*")) // Prefix matching
-            .collect::<Vec<_>>()
-            .join(" & ");
+        let ts_query = prefix_ts_query(query_clean);
 
         debug!(query = %query_clean, ts_query = %ts_query, "executing search");
 
@@ -97,12 +125,15 @@ impl SearchService {
                 WHERE search_vector @@ to_tsquery('english', $1)
                   AND (status = 1 OR author_id = $2)
                   AND stage_id = ANY($3)
+                  AND tenant_id = $4
+                  AND item_access_granted(id, $2, 'view')
                 "#,
             )
             .bind(&ts_query)
             .bind(uid)
             .bind(stage_ids)
-            .fetch_one(&self.pool)
+            .bind(tenant_id)
+            .fetch_one(&mut *conn)
             .await
             .context("failed to count search results")?
         } else {
@@ -113,15 +144,40 @@ impl SearchService {
                 WHERE search_vector @@ to_tsquery('english', $1)
                   AND status = 1
                   AND stage_id = ANY($2)
+                  AND tenant_id = $3
+                  AND item_access_granted(id, '00000000-0000-0000-0000-000000000000', 'view')
                 "#,
             )
             .bind(&ts_query)
             .bind(stage_ids)
-            .fetch_one(&self.pool)
+            .bind(tenant_id)
+            .fetch_one(&mut *conn)
             .await
             .context("failed to count search results")?
         };
 
+        if total == 0 {
+            drop(conn);
+            // A failing fallback (e.g. pg_trgm unavailable) must not turn an
+            // empty result into an error.
+            return match self
+                .fuzzy_search(query, stage_ids, user_id, limit, offset)
+                .await
+            {
+                Ok(results) => Ok(results),
+                Err(e) => {
+                    tracing::warn!(error = %e, "fuzzy search fallback failed");
+                    Ok(SearchResults {
+                        query: query.to_string(),
+                        results: vec![],
+                        total: 0,
+                        offset,
+                        limit,
+                    })
+                }
+            };
+        }
+
         // Get ranked results
         // Headline source: title + body text for richer snippets
         let results = if let Some(uid) = user_id {
@@ -146,6 +202,8 @@ impl SearchService {
                 WHERE search_vector @@ to_tsquery('english', $1)
                   AND (status = 1 OR author_id = $2)
                   AND stage_id = ANY($3)
+                  AND tenant_id = $6
+                  AND item_access_granted(id, $2, 'view')
                 ORDER BY rank DESC, created DESC
                 LIMIT $4 OFFSET $5
                 "#,
@@ -155,7 +213,8 @@ impl SearchService {
             .bind(stage_ids)
             .bind(limit)
             .bind(offset)
-            .fetch_all(&self.pool)
+            .bind(tenant_id)
+            .fetch_all(&mut *conn)
             .await
             .context("failed to execute search query")?
         } else {
@@ -180,6 +239,8 @@ impl SearchService {
                 WHERE search_vector @@ to_tsquery('english', $1)
                   AND status = 1
                   AND stage_id = ANY($2)
+                  AND tenant_id = $5
+                  AND item_access_granted(id, '00000000-0000-0000-0000-000000000000', 'view')
                 ORDER BY rank DESC, created DESC
                 LIMIT $3 OFFSET $4
                 "#,
@@ -188,7 +249,8 @@ impl SearchService {
             .bind(stage_ids)
             .bind(limit)
             .bind(offset)
-            .fetch_all(&self.pool)
+            .bind(tenant_id)
+            .fetch_all(&mut *conn)
             .await
             .context("failed to execute search query")?
         };
@@ -209,9 +271,217 @@ impl SearchService {
         })
     }
 
+    /// Match item titles by trigram word similarity.
+    ///
+    /// Catches typos that prefix tsquery matching misses. Uses the `<%`
+    /// operator so the `idx_item_title_trgm` index applies; the cut-off is
+    /// PostgreSQL's `pg_trgm.word_similarity_threshold` (0.6 by default).
+    /// Results carry `fuzzy: true`, are ranked by similarity, and have no
+    /// snippet. Same stage, tenant, draft and grant visibility rules as
+    /// [`search`](Self::search).
+    pub async fn fuzzy_search(
+        &self,
+        query: &str,
+        stage_ids: &[Uuid],
+        user_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<SearchResults> {
+        let query_clean = query.trim();
+        if query_clean.is_empty() {
+            return Ok(SearchResults {
+                query: query.to_string(),
+                results: vec![],
+                total: 0,
+                offset,
+                limit,
+            });
+        }
+        let _db_timer = profiling::Timer::start(Phase::Db);
+        let mut conn = self.read_pools.acquire_read().await?;
+        let tenant_id = current_tenant_id();
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM item
+            WHERE $1 <% title
+              AND (status = 1 OR author_id = $2)
+              AND stage_id = ANY($3)
+              AND tenant_id = $4
+              AND item_access_granted(id, COALESCE($2, '00000000-0000-0000-0000-000000000000'::uuid), 'view')
+            "#,
+        )
+        .bind(query_clean)
+        .bind(user_id)
+        .bind(stage_ids)
+        .bind(tenant_id)
+        .fetch_one(&mut *conn)
+        .await
+        .context("failed to count fuzzy search results")?;
+
+        let rows = if total == 0 {
+            Vec::new()
+        } else {
+            sqlx::query_as::<_, SearchResultRow>(
+                r#"
+                SELECT
+                    id,
+                    type,
+                    title,
+                    word_similarity($1, title) as rank,
+                    NULL::text as snippet
+                FROM item
+                WHERE $1 <% title
+                  AND (status = 1 OR author_id = $2)
+                  AND stage_id = ANY($3)
+                  AND tenant_id = $6
+                  AND item_access_granted(id, COALESCE($2, '00000000-0000-0000-0000-000000000000'::uuid), 'view')
+                ORDER BY rank DESC, created DESC
+                LIMIT $4 OFFSET $5
+                "#,
+            )
+            .bind(query_clean)
+            .bind(user_id)
+            .bind(stage_ids)
+            .bind(limit)
+            .bind(offset)
+            .bind(tenant_id)
+            .fetch_all(&mut *conn)
+            .await
+            .context("failed to execute fuzzy search query")?
+        };
+
+        debug!(
+            query = %query_clean,
+            total = %total,
+            returned = %rows.len(),
+            "fuzzy search completed"
+        );
+
+        Ok(SearchResults {
+            query: query.to_string(),
+            results: rows
+                .into_iter()
+                .map(|r| SearchResult {
+                    fuzzy: true,
+                    ..r.into()
+                })
+                .collect(),
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    /// Suggest a corrected query ("did you mean").
+    ///
+    /// Each query word is replaced by the most similar word from the titles
+    /// of visible items. Returns `None` when no word changes, i.e. when the
+    /// query already uses words found in titles or nothing is close enough.
+    pub async fn suggest(
+        &self,
+        query: &str,
+        stage_ids: &[Uuid],
+        user_id: Option<Uuid>,
+    ) -> Result<Option<String>> {
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return Ok(None);
+        }
+        let _db_timer = profiling::Timer::start(Phase::Db);
+        let mut conn = self.read_pools.acquire_read().await?;
+        let tenant_id = current_tenant_id();
+
+        let mut corrections = Vec::with_capacity(words.len());
+        for word in &words {
+            // Narrow to candidate titles via the trigram index, then pick
+            // the closest individual word within them.
+            let best: Option<String> = sqlx::query_scalar(
+                r#"
+                SELECT w.word
+                FROM (
+                    SELECT DISTINCT lower(t.word) AS word
+                    FROM item,
+                         regexp_split_to_table(item.title, '[^[:alnum:]]+') AS t(word)
+                    WHERE $1 <% item.title
+                      AND (item.status = 1 OR item.author_id = $2)
+                      AND item.stage_id = ANY($3)
+                      AND item.tenant_id = $4
+                      AND item_access_granted(item.id, COALESCE($2, '00000000-0000-0000-0000-000000000000'::uuid), 'view')
+                ) w
+                WHERE w.word <> '' AND w.word % lower($1)
+                ORDER BY similarity(w.word, lower($1)) DESC, w.word
+                LIMIT 1
+                "#,
+            )
+            .bind(*word)
+            .bind(user_id)
+            .bind(stage_ids)
+            .bind(tenant_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .context("failed to compute search suggestion")?;
+            corrections.push(best);
+        }
+
+        Ok(build_suggestion(&words, &corrections))
+    }
+
+    /// The newest published items matching a query, for saved search
+    /// alerts.
+    ///
+    /// Only live items the user may view are matched, optionally restricted
+    /// to `item_types`. Results are ordered newest first and have no
+    /// snippet; there is no fuzzy fallback.
+    pub async fn newest_matches(
+        &self,
+        query: &str,
+        item_types: &[String],
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>> {
+        let query_clean = query.trim();
+        if query_clean.is_empty() {
+            return Ok(Vec::new());
+        }
+        let _db_timer = profiling::Timer::start(Phase::Db);
+        let mut conn = self.read_pools.acquire_read().await?;
+
+        let rows = sqlx::query_as::<_, SearchResultRow>(
+            r#"
+            SELECT
+                id,
+                type,
+                title,
+                ts_rank(search_vector, to_tsquery('english', $1)) as rank,
+                NULL::text as snippet
+            FROM item
+            WHERE search_vector @@ to_tsquery('english', $1)
+              AND status = 1
+              AND stage_id = $2
+              AND (cardinality($3::text[]) = 0 OR type = ANY($3))
+              AND item_access_granted(id, $4, 'view')
+            ORDER BY created DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(prefix_ts_query(query_clean))
+        .bind(crate::models::stage::LIVE_STAGE_ID)
+        .bind(item_types)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&mut *conn)
+        .await
+        .context("failed to find newest search matches")?;
+
+        Ok(rows.into_iter().map(SearchResult::from).collect())
+    }
+
     /// Configure search indexing for a field.
     ///
-    /// Sets the weight (A-D) for a specific field on a content type.
+    /// Sets the weight (A-D) for a specific field on a content type. The
+    /// row is marked `manual`, so later plugin weight syncs leave it alone.
     pub async fn configure_field(
         &self,
         bundle: &str,
@@ -227,7 +497,7 @@ impl SearchService {
             INSERT INTO search_field_config (id, bundle, field_name, weight)
             VALUES ($1, $2, $3, $4)
             ON CONFLICT (bundle, field_name)
-            DO UPDATE SET weight = $4
+            DO UPDATE SET weight = $4, source = 'manual'
             "#,
         )
         .bind(Uuid::now_v7())
@@ -242,6 +512,74 @@ impl SearchService {
         Ok(())
     }
 
+    /// Sync plugin-declared search weights for a bundle.
+    ///
+    /// `weights` is the full set of `(field_name, weight)` pairs declared via
+    /// `FieldDefinition::search_weight` in `tap_item_info`. Plugin-sourced
+    /// rows not in the set are removed; manually configured rows are never
+    /// touched, so administrator overrides win. Returns `true` if anything
+    /// changed, in which case the caller should reindex the bundle so the
+    /// `item_search_update` trigger rebuilds existing `search_vector`s.
+    pub async fn sync_declared_weights(
+        &self,
+        bundle: &str,
+        weights: &[(String, char)],
+    ) -> Result<bool> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to start transaction")?;
+        let mut changed = false;
+
+        let field_names: Vec<&str> = weights.iter().map(|(f, _)| f.as_str()).collect();
+        let removed = sqlx::query(
+            r#"
+            DELETE FROM search_field_config
+            WHERE bundle = $1 AND source = 'plugin' AND NOT (field_name = ANY($2))
+            "#,
+        )
+        .bind(bundle)
+        .bind(&field_names)
+        .execute(&mut *tx)
+        .await
+        .context("failed to remove stale plugin search config")?;
+        changed |= removed.rows_affected() > 0;
+
+        for (field_name, weight) in weights {
+            if !['A', 'B', 'C', 'D'].contains(weight) {
+                anyhow::bail!("weight must be A, B, C, or D");
+            }
+            let upserted = sqlx::query(
+                r#"
+                INSERT INTO search_field_config (id, bundle, field_name, weight, source)
+                VALUES ($1, $2, $3, $4, 'plugin')
+                ON CONFLICT (bundle, field_name)
+                DO UPDATE SET weight = EXCLUDED.weight
+                WHERE search_field_config.source = 'plugin'
+                  AND search_field_config.weight <> EXCLUDED.weight
+                "#,
+            )
+            .bind(Uuid::now_v7())
+            .bind(bundle)
+            .bind(field_name)
+            .bind(weight.to_string())
+            .execute(&mut *tx)
+            .await
+            .context("failed to sync plugin search config")?;
+            changed |= upserted.rows_affected() > 0;
+        }
+
+        tx.commit()
+            .await
+            .context("failed to commit search config")?;
+
+        if changed {
+            debug!(bundle = %bundle, fields = weights.len(), "plugin search weights synced");
+        }
+        Ok(changed)
+    }
+
     /// Remove search indexing configuration for a field.
     pub async fn remove_field_config(&self, bundle: &str, field_name: &str) -> Result<bool> {
         let result = sqlx::query(
@@ -259,6 +597,31 @@ impl SearchService {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Remove search indexing configuration for fields no longer on a bundle.
+    ///
+    /// Drops both plugin-declared and manually configured rows. Returns
+    /// `true` if anything was removed, in which case the caller should
+    /// reindex the bundle.
+    pub async fn remove_field_configs(&self, bundle: &str, field_names: &[&str]) -> Result<bool> {
+        if field_names.is_empty() {
+            return Ok(false);
+        }
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM search_field_config
+            WHERE bundle = $1 AND field_name = ANY($2)
+            "#,
+        )
+        .bind(bundle)
+        .bind(field_names)
+        .execute(&self.pool)
+        .await
+        .context("failed to remove search field configs")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// List all search field configurations for a bundle.
     pub async fn list_field_configs(&self, bundle: &str) -> Result<Vec<FieldConfig>> {
         let configs = sqlx::query_as::<_, FieldConfigRow>(
@@ -277,43 +640,94 @@ impl SearchService {
         Ok(configs.into_iter().map(|r| r.into()).collect())
     }
 
-    /// Reindex a single item (useful after bulk field config changes).
+    /// Rebuild a single item's search vector now.
+    ///
+    /// Assigning the title to itself fires the tsvector trigger, which
+    /// also marks the item fresh in `search_index_status`.
     pub async fn reindex_item(&self, item_id: Uuid) -> Result<()> {
-        // Touch the item to trigger the search_vector update trigger
-        sqlx::query(
-            r#"
-            UPDATE item
-            SET changed = $2
-            WHERE id = $1
-            "#,
-        )
-        .bind(item_id)
-        .bind(chrono::Utc::now().timestamp())
-        .execute(&self.pool)
-        .await
-        .context("failed to reindex item")?;
+        sqlx::query("UPDATE item SET title = title WHERE id = $1")
+            .bind(item_id)
+            .execute(&self.pool)
+            .await
+            .context("failed to reindex item")?;
 
         Ok(())
     }
 
-    /// Reindex all items of a specific type.
-    pub async fn reindex_bundle(&self, bundle: &str) -> Result<u64> {
+    /// Queue all items of a specific type for background reindexing.
+    ///
+    /// Marks the items pending in `search_index_status`; the
+    /// `search_reindex` cron task feeds them to [`REINDEX_QUEUE`] in
+    /// batches. Returns the number of items queued.
+    pub async fn queue_reindex_bundle(&self, bundle: &str) -> Result<u64> {
         let result = sqlx::query(
             r#"
-            UPDATE item
-            SET changed = $2
-            WHERE type = $1
+            INSERT INTO search_index_status (item_id, bundle, queued)
+            SELECT id, type, $2 FROM item WHERE type = $1
+            ON CONFLICT (item_id) DO UPDATE SET
+                bundle = EXCLUDED.bundle,
+                queued = EXCLUDED.queued
             "#,
         )
         .bind(bundle)
         .bind(chrono::Utc::now().timestamp())
         .execute(&self.pool)
         .await
-        .context("failed to reindex bundle")?;
+        .context("failed to queue bundle reindex")?;
 
-        debug!(bundle = %bundle, count = %result.rows_affected(), "bundle reindexed");
+        debug!(bundle = %bundle, count = %result.rows_affected(), "bundle queued for reindex");
         Ok(result.rows_affected())
     }
+
+    /// IDs of the items waiting longest for a reindex.
+    pub async fn pending_reindex(&self, limit: i64) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT item_id FROM search_index_status
+            WHERE queued IS NOT NULL
+            ORDER BY queued, item_id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list pending reindex items")
+    }
+
+    /// Index freshness per bundle, ordered by bundle.
+    pub async fn index_status(&self) -> Result<Vec<BundleIndexStatus>> {
+        sqlx::query_as::<_, BundleIndexStatus>(
+            r#"
+            SELECT bundle,
+                   COUNT(*) AS total,
+                   COUNT(*) FILTER (WHERE queued IS NOT NULL) AS pending,
+                   MIN(queued) AS oldest_queued,
+                   NULLIF(MAX(indexed), 0) AS last_indexed
+            FROM search_index_status
+            GROUP BY bundle
+            ORDER BY bundle
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load search index status")
+    }
+}
+
+/// Search index freshness of one bundle.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BundleIndexStatus {
+    /// Item type.
+    pub bundle: String,
+    /// Items of the type.
+    pub total: i64,
+    /// Items waiting for a reindex.
+    pub pending: i64,
+    /// Unix timestamp of the oldest pending reindex request.
+    pub oldest_queued: Option<i64>,
+    /// Unix timestamp of the most recent index update.
+    pub last_indexed: Option<i64>,
 }
 
 /// Internal row type for search results.
@@ -335,10 +749,40 @@ impl From<SearchResultRow> for SearchResult {
             title: row.title,
             rank: row.rank,
             snippet: row.snippet,
+            fuzzy: false,
         }
     }
 }
 
+/// Join per-word corrections into a suggested query.
+///
+/// Words without a correction are kept as typed. Returns `None` if no word
+/// differs (case-insensitively) from the original query.
+fn build_suggestion(words: &[&str], corrections: &[Option<String>]) -> Option<String> {
+    let mut changed = false;
+    let suggested: Vec<&str> = words
+        .iter()
+        .zip(corrections)
+        .map(|(word, correction)| match correction {
+            Some(c) if !c.eq_ignore_ascii_case(word) => {
+                changed = true;
+                c.as_str()
+            }
+            _ => *word,
+        })
+        .collect();
+    changed.then(|| suggested.join(" "))
+}
+
+/// Convert search terms to a tsquery matching all words by prefix.
+fn prefix_ts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|w| format!("{w}:*"))
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
 /// Search field configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldConfig {
@@ -383,6 +827,7 @@ mod tests {
                 title: "Test Page".to_string(),
                 rank: 0.5,
                 snippet: Some("<mark>Test</mark> content".to_string()),
+                fuzzy: false,
             }],
             total: 1,
             offset: 0,
@@ -393,6 +838,44 @@ mod tests {
         let parsed: SearchResults = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.query, "test");
         assert_eq!(parsed.total, 1);
+        assert!(!parsed.results[0].fuzzy);
+    }
+
+    #[test]
+    fn fuzzy_defaults_to_false_when_missing() {
+        let json = serde_json::json!({
+            "id": Uuid::now_v7(),
+            "item_type": "page",
+            "title": "Old cache entry",
+            "rank": 0.1,
+            "snippet": null
+        });
+        let parsed: SearchResult = serde_json::from_value(json).unwrap();
+        assert!(!parsed.fuzzy);
+    }
+
+    #[test]
+    fn suggestion_replaces_misspelled_words() {
+        let words = ["helo", "wrld", "2026"];
+        let corrections = [Some("hello".to_string()), Some("world".to_string()), None];
+        assert_eq!(
+            build_suggestion(&words, &corrections).as_deref(),
+            Some("hello world 2026")
+        );
+    }
+
+    #[test]
+    fn no_suggestion_when_nothing_changes() {
+        let words = ["Rust", "guide"];
+        let corrections = [Some("rust".to_string()), None];
+        assert_eq!(build_suggestion(&words, &corrections), None);
+        assert_eq!(build_suggestion(&[], &[]), None);
+    }
+
+    #[test]
+    fn ts_query_matches_all_words_by_prefix() {
+        assert_eq!(prefix_ts_query("  rust  web "), "rust:* & web:*");
+        assert_eq!(prefix_ts_query(""), "");
     }
 
     #[test]
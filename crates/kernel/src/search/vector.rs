@@ -0,0 +1,213 @@
+//! Plugin embedding store on pgvector.
+//!
+//! Backs the `embedding-store` and `embedding-search` host functions and the
+//! `vector_similar` Gather filter. Plugins store one vector per item in
+//! their own namespace (the plugin name); vectors from different
+//! namespaces or with different dimensions are never compared, so plugins
+//! can use whatever embedding model they like.
+//!
+//! Vectors live in the `item_embeddings` table shared with
+//! [`PgVectorStore`](crate::services::vector_store::PgVectorStore), under
+//! the model name `plugin:{namespace}`. When pgvector is not installed the
+//! table does not exist and every operation reports
+//! [`EmbeddingError::Unavailable`].
+
+use sqlx::PgPool;
+use trovato_sdk::types::{EMBEDDING_MAX_DIMENSIONS, EMBEDDING_SEARCH_MAX_K, EmbeddingMatch};
+use uuid::Uuid;
+
+use crate::content::item_query::ItemQueryAccess;
+use crate::models::stage::LIVE_STAGE_ID;
+use crate::services::vector_store::vector_literal;
+
+/// `field_name` recorded for plugin-stored embeddings.
+const EMBEDDING_FIELD: &str = "plugin";
+
+/// An embedding operation that could not be completed.
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddingError {
+    /// pgvector (and so the `item_embeddings` table) is not installed.
+    #[error("pgvector is not available")]
+    Unavailable,
+    /// The vector is empty, too large, or contains NaN/infinite values.
+    #[error("invalid embedding: {0}")]
+    Invalid(&'static str),
+    /// Any other database error.
+    #[error("embedding query failed: {0}")]
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for EmbeddingError {
+    fn from(e: sqlx::Error) -> Self {
+        // 42P01 undefined_table, 42704 undefined_object (the vector type).
+        let code = e.as_database_error().and_then(|d| d.code());
+        match code.as_deref() {
+            Some("42P01" | "42704") => Self::Unavailable,
+            _ => Self::Database(e),
+        }
+    }
+}
+
+/// Model name under which a namespace's vectors are stored.
+fn namespace_model(namespace: &str) -> String {
+    format!("plugin:{namespace}")
+}
+
+/// Check that a vector can be stored or searched with.
+pub fn validate(vector: &[f32]) -> Result<(), EmbeddingError> {
+    if vector.is_empty() {
+        return Err(EmbeddingError::Invalid("vector is empty"));
+    }
+    if vector.len() > EMBEDDING_MAX_DIMENSIONS {
+        return Err(EmbeddingError::Invalid("too many dimensions"));
+    }
+    if !vector.iter().all(|v| v.is_finite()) {
+        return Err(EmbeddingError::Invalid("vector contains NaN or infinity"));
+    }
+    Ok(())
+}
+
+/// Store (or replace) the embedding of an item in a namespace.
+pub async fn store(
+    pool: &PgPool,
+    namespace: &str,
+    item_id: Uuid,
+    vector: &[f32],
+) -> Result<(), EmbeddingError> {
+    validate(vector)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO item_embeddings (item_id, field_name, model, dimensions, embedding)
+        VALUES ($1, $2, $3, $4, $5::vector)
+        ON CONFLICT (item_id, field_name, model)
+        DO UPDATE SET
+            dimensions = EXCLUDED.dimensions,
+            embedding = EXCLUDED.embedding,
+            created_at = EXTRACT(EPOCH FROM now())::BIGINT
+        "#,
+    )
+    .bind(item_id)
+    .bind(EMBEDDING_FIELD)
+    .bind(namespace_model(namespace))
+    .bind(vector.len() as i32)
+    .bind(vector_literal(vector))
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Find the `k` live items in a namespace closest to `vector`.
+///
+/// Only vectors with the same dimensions are compared. Callers without
+/// unrestricted access only see published items.
+pub async fn search(
+    pool: &PgPool,
+    namespace: &str,
+    vector: &[f32],
+    k: u32,
+    access: ItemQueryAccess,
+) -> Result<Vec<EmbeddingMatch>, EmbeddingError> {
+    validate(vector)?;
+    if access == ItemQueryAccess::Denied || k == 0 {
+        return Ok(Vec::new());
+    }
+
+    let rows: Vec<(Uuid, f64)> = sqlx::query_as(
+        r#"
+        SELECT e.item_id, (e.embedding <=> $1::vector) AS distance
+        FROM item_embeddings e
+        JOIN item i ON i.id = e.item_id
+        WHERE e.field_name = $2 AND e.model = $3 AND e.dimensions = $4
+          AND i.stage_id = $5
+          AND ($6 OR i.status = 1)
+        ORDER BY e.embedding <=> $1::vector
+        LIMIT $7
+        "#,
+    )
+    .bind(vector_literal(vector))
+    .bind(EMBEDDING_FIELD)
+    .bind(namespace_model(namespace))
+    .bind(vector.len() as i32)
+    .bind(LIVE_STAGE_ID)
+    .bind(access == ItemQueryAccess::Unrestricted)
+    .bind(i64::from(k.min(EMBEDDING_SEARCH_MAX_K)))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(item_id, distance)| EmbeddingMatch { item_id, distance })
+        .collect())
+}
+
+/// Items whose embedding in a namespace is closest to that of `item_id`,
+/// nearest first, excluding the item itself.
+///
+/// Returns an empty list if the item has no embedding. Does not filter by
+/// stage or status; callers (Gather) apply their own visibility rules.
+pub async fn similar_items(
+    pool: &PgPool,
+    namespace: &str,
+    item_id: Uuid,
+    k: u32,
+) -> Result<Vec<Uuid>, EmbeddingError> {
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT e.item_id
+        FROM item_embeddings src
+        JOIN item_embeddings e
+          ON e.field_name = src.field_name
+         AND e.model = src.model
+         AND e.dimensions = src.dimensions
+         AND e.item_id <> src.item_id
+        WHERE src.item_id = $1 AND src.field_name = $2 AND src.model = $3
+        ORDER BY e.embedding <=> src.embedding
+        LIMIT $4
+        "#,
+    )
+    .bind(item_id)
+    .bind(EMBEDDING_FIELD)
+    .bind(namespace_model(namespace))
+    .bind(i64::from(k.min(EMBEDDING_SEARCH_MAX_K)))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ids)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_finite_vectors() {
+        assert!(validate(&[0.1, -0.2, 0.3]).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_vectors() {
+        assert!(matches!(validate(&[]), Err(EmbeddingError::Invalid(_))));
+        assert!(matches!(
+            validate(&[1.0, f32::NAN]),
+            Err(EmbeddingError::Invalid(_))
+        ));
+        assert!(matches!(
+            validate(&[f32::INFINITY]),
+            Err(EmbeddingError::Invalid(_))
+        ));
+        let too_big = vec![0.0; EMBEDDING_MAX_DIMENSIONS + 1];
+        assert!(matches!(
+            validate(&too_big),
+            Err(EmbeddingError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn namespaces_do_not_collide_with_models() {
+        assert_eq!(namespace_model("argus"), "plugin:argus");
+    }
+}
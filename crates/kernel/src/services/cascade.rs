@@ -0,0 +1,835 @@
+//! Reference cascades.
+//!
+//! A record reference field can declare what happens to the items holding
+//! the reference when the referenced item is unpublished or deleted. The
+//! rule is stored in the field's `settings` under [`CASCADE_SETTING`]:
+//!
+//! - `none` (default): nothing happens.
+//! - `unpublish`: referencing items are unpublished when the target is
+//!   unpublished or deleted.
+//! - `delete`: referencing items are deleted with the target, and
+//!   unpublished when the target is only unpublished.
+//! - `restrict`: the target cannot be deleted while items reference it.
+//! - `set_null`: the reference is removed from referencing items when the
+//!   target is deleted.
+//!
+//! Cascades follow chains of references, so an unpublished article can in
+//! turn unpublish items that reference it. [`plan`] lists the affected items
+//! without changing anything (the dry-run preview); [`execute`] applies a
+//! plan as a kernel batch operation, reporting progress after each item and
+//! writing an audit entry for every cascaded change.
+//!
+//! [`ItemService::delete`] refuses to delete an item with restricting
+//! references ([`DeleteRestricted`]). References left pointing at items that
+//! no longer exist are found by [`find_dangling`], which the
+//! `check_dangling_references` cron task runs to refresh the
+//! `dangling_reference` table.
+
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use trovato_sdk::types::{ContentTypeDefinition, FieldDefinition, FieldType};
+use uuid::Uuid;
+
+use crate::batch::{BatchService, BatchStatus};
+use crate::content::{ItemPatch, ItemService};
+use crate::models::{StatusChangeMeta, UpdateItem};
+use crate::services::audit::AuditService;
+use crate::tap::UserContext;
+
+/// Field settings key holding the cascade rule.
+pub const CASCADE_SETTING: &str = "cascade";
+
+/// Batch operation type for cascade runs.
+pub const CASCADE_OPERATION: &str = "reference_cascade";
+
+/// Maximum number of items a single cascade may change.
+///
+/// Larger plans are cut off and flagged as truncated; the remaining
+/// references are left for an administrator to review.
+pub const MAX_CASCADE_ITEMS: usize = 500;
+
+/// What happens to referencing items when their target goes away.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CascadeRule {
+    /// Leave referencing items alone.
+    #[default]
+    None,
+    /// Unpublish referencing items.
+    Unpublish,
+    /// Delete referencing items (unpublish them if the target is only unpublished).
+    Delete,
+    /// Refuse to delete the target while items reference it.
+    Restrict,
+    /// Remove the reference from referencing items when the target is deleted.
+    SetNull,
+}
+
+impl CascadeRule {
+    /// All rules, in the order they are offered to administrators.
+    pub const ALL: [Self; 5] = [
+        Self::None,
+        Self::Unpublish,
+        Self::Delete,
+        Self::Restrict,
+        Self::SetNull,
+    ];
+
+    /// Machine name as stored in field settings.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Unpublish => "unpublish",
+            Self::Delete => "delete",
+            Self::Restrict => "restrict",
+            Self::SetNull => "set_null",
+        }
+    }
+
+    /// Admin label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "Do nothing",
+            Self::Unpublish => "Unpublish referencing content",
+            Self::Delete => "Delete referencing content",
+            Self::Restrict => "Prevent deleting referenced content",
+            Self::SetNull => "Remove the reference",
+        }
+    }
+
+    /// Parse a machine name.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == value)
+    }
+
+    /// The rule configured on a field; `None` for non-reference fields.
+    pub fn for_field(field: &FieldDefinition) -> Self {
+        if !matches!(field.field_type, FieldType::RecordReference(_)) {
+            return Self::None;
+        }
+        field
+            .settings
+            .get(CASCADE_SETTING)
+            .and_then(|v| v.as_str())
+            .and_then(Self::parse)
+            .unwrap_or_default()
+    }
+
+    /// Action applied to a referencing item when its target undergoes `trigger`.
+    ///
+    /// `restrict` never changes referencing items; it is enforced when the
+    /// target is deleted.
+    pub fn action_for(self, trigger: CascadeAction) -> Option<CascadeAction> {
+        match (self, trigger) {
+            (Self::None | Self::Restrict, _)
+            | (_, CascadeAction::Clear)
+            | (Self::SetNull, CascadeAction::Unpublish) => None,
+            (Self::Unpublish, _) | (Self::Delete, CascadeAction::Unpublish) => {
+                Some(CascadeAction::Unpublish)
+            }
+            (Self::Delete, CascadeAction::Delete) => Some(CascadeAction::Delete),
+            (Self::SetNull, CascadeAction::Delete) => Some(CascadeAction::Clear),
+        }
+    }
+}
+
+/// A change made to an item, either by an editor or by a cascade.
+///
+/// `Clear` is only ever made by a cascade: it removes the reference to the
+/// deleted item from the referencing field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CascadeAction {
+    Unpublish,
+    Delete,
+    Clear,
+}
+
+impl CascadeAction {
+    /// Parse a machine name (`unpublish` or `delete`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "unpublish" => Some(Self::Unpublish),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+
+    /// Machine name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unpublish => "unpublish",
+            Self::Delete => "delete",
+            Self::Clear => "clear",
+        }
+    }
+
+    /// Audit log action for a cascaded change.
+    fn audit_action(self) -> &'static str {
+        match self {
+            Self::Unpublish => "item.cascade_unpublish",
+            Self::Delete => "item.cascade_delete",
+            Self::Clear => "item.cascade_clear",
+        }
+    }
+}
+
+/// A reference field whose cascade rule reacts to items of some type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependentField {
+    /// Content type holding the reference field.
+    pub item_type: String,
+    /// Reference field machine name.
+    pub field_name: String,
+    /// Configured rule (never [`CascadeRule::None`]).
+    pub rule: CascadeRule,
+}
+
+/// Reference fields with a cascade rule that can point at `target_type`.
+///
+/// Reference fields without a target type accept any type and are included.
+pub fn dependent_fields(types: &[ContentTypeDefinition], target_type: &str) -> Vec<DependentField> {
+    types
+        .iter()
+        .flat_map(|ct| {
+            ct.fields.iter().filter_map(move |f| {
+                let FieldType::RecordReference(target) = &f.field_type else {
+                    return None;
+                };
+                if !target.is_empty() && target != target_type {
+                    return None;
+                }
+                let rule = CascadeRule::for_field(f);
+                (rule != CascadeRule::None).then(|| DependentField {
+                    item_type: ct.machine_name.clone(),
+                    field_name: f.field_name.clone(),
+                    rule,
+                })
+            })
+        })
+        .collect()
+}
+
+/// One planned change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CascadeStep {
+    pub item_id: Uuid,
+    pub title: String,
+    pub item_type: String,
+    pub action: CascadeAction,
+    /// Reference field that pulled the item into the cascade.
+    pub field: String,
+    /// Item the reference points at.
+    pub source_id: Uuid,
+}
+
+/// An item whose `restrict` reference blocks deleting the item it points at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestrictingReference {
+    pub item_id: Uuid,
+    pub title: String,
+    pub item_type: String,
+    /// Reference field with the `restrict` rule.
+    pub field: String,
+    /// Item the reference points at.
+    pub target_id: Uuid,
+}
+
+/// Deleting an item was refused because `restrict` references point at it.
+///
+/// Converted to a 409 response by [`crate::error::AppError`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("item {item_id} is referenced by {} item(s) that restrict its deletion", .references.len())]
+pub struct DeleteRestricted {
+    pub item_id: Uuid,
+    pub references: Vec<RestrictingReference>,
+}
+
+/// Items affected by a cascade, in the order they will be changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CascadePlan {
+    pub steps: Vec<CascadeStep>,
+    /// Whether the plan hit [`MAX_CASCADE_ITEMS`].
+    pub truncated: bool,
+    /// References that refuse the deletion of a source or of an item the
+    /// plan deletes. Only filled for delete cascades.
+    pub restricted: Vec<RestrictingReference>,
+}
+
+impl CascadePlan {
+    /// Number of items the plan unpublishes.
+    pub fn unpublish_count(&self) -> usize {
+        self.count(CascadeAction::Unpublish)
+    }
+
+    /// Number of items the plan deletes.
+    pub fn delete_count(&self) -> usize {
+        self.count(CascadeAction::Delete)
+    }
+
+    /// Number of references the plan removes.
+    pub fn clear_count(&self) -> usize {
+        self.count(CascadeAction::Clear)
+    }
+
+    fn count(&self, action: CascadeAction) -> usize {
+        self.steps.iter().filter(|s| s.action == action).count()
+    }
+}
+
+/// Counts reported when a cascade finishes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CascadeOutcome {
+    pub unpublished: u64,
+    pub deleted: u64,
+    /// References removed by `set_null` rules.
+    #[serde(default)]
+    pub cleared: u64,
+    /// Items that no longer existed when their step ran.
+    pub skipped: u64,
+    pub failed: u64,
+    /// Whether the run stopped early because the batch was cancelled.
+    pub cancelled: bool,
+}
+
+/// A referencing item found by [`find_dependents`].
+#[derive(Debug, sqlx::FromRow)]
+struct Dependent {
+    id: Uuid,
+    title: String,
+    status: i16,
+}
+
+/// Items of `item_type` whose `field_name` references `target`.
+///
+/// Reference values are stored as a UUID string or an array of them.
+async fn find_dependents(
+    pool: &PgPool,
+    item_type: &str,
+    field_name: &str,
+    target: Uuid,
+) -> Result<Vec<Dependent>> {
+    sqlx::query_as::<_, Dependent>(
+        r#"
+        SELECT id, title, status FROM item
+        WHERE type = $1
+          AND (fields -> $2 = to_jsonb($3::text)
+               OR fields -> $2 @> jsonb_build_array($3::text))
+        ORDER BY created
+        LIMIT $4
+        "#,
+    )
+    .bind(item_type)
+    .bind(field_name)
+    .bind(target.to_string())
+    .bind(MAX_CASCADE_ITEMS as i64 + 1)
+    .fetch_all(pool)
+    .await
+    .context("failed to find referencing items")
+}
+
+/// Items whose `restrict` references point at `target`, an item of
+/// `target_type`. An item referencing itself does not count.
+pub async fn restricting_references(
+    pool: &PgPool,
+    types: &[ContentTypeDefinition],
+    target: Uuid,
+    target_type: &str,
+) -> Result<Vec<RestrictingReference>> {
+    let mut references = Vec::new();
+    for field in dependent_fields(types, target_type) {
+        if field.rule != CascadeRule::Restrict {
+            continue;
+        }
+        for dependent in find_dependents(pool, &field.item_type, &field.field_name, target).await? {
+            if dependent.id == target {
+                continue;
+            }
+            references.push(RestrictingReference {
+                item_id: dependent.id,
+                title: dependent.title,
+                item_type: field.item_type.clone(),
+                field: field.field_name.clone(),
+                target_id: target,
+            });
+        }
+    }
+    Ok(references)
+}
+
+/// Work out which items a change to `sources` would cascade to.
+///
+/// `sources` are `(item id, content type)` pairs all undergoing `trigger`.
+/// Nothing is modified. Items are listed once, breadth first; items
+/// already unpublished are left out of unpublish cascades. A `set_null`
+/// reference is listed as a `clear` step for every field holding it, and
+/// does not cascade further. Delete cascades also list the `restrict`
+/// references that would refuse a deletion.
+pub async fn plan(
+    pool: &PgPool,
+    types: &[ContentTypeDefinition],
+    sources: &[(Uuid, String)],
+    trigger: CascadeAction,
+) -> Result<CascadePlan> {
+    let mut plan = CascadePlan::default();
+    let mut seen: HashSet<Uuid> = sources.iter().map(|(id, _)| *id).collect();
+    let mut queue: VecDeque<(Uuid, String, CascadeAction)> = sources
+        .iter()
+        .map(|(id, item_type)| (*id, item_type.clone(), trigger))
+        .collect();
+
+    while let Some((source_id, source_type, action)) = queue.pop_front() {
+        if action == CascadeAction::Delete {
+            plan.restricted.extend(
+                restricting_references(pool, types, source_id, &source_type)
+                    .await?
+                    .into_iter()
+                    .filter(|r| !sources.iter().any(|(id, _)| *id == r.item_id)),
+            );
+        }
+        for field in dependent_fields(types, &source_type) {
+            let Some(dependent_action) = field.rule.action_for(action) else {
+                continue;
+            };
+            let dependents =
+                find_dependents(pool, &field.item_type, &field.field_name, source_id).await?;
+            for dependent in dependents {
+                if dependent_action == CascadeAction::Clear {
+                    if plan.steps.len() >= MAX_CASCADE_ITEMS {
+                        plan.truncated = true;
+                        return Ok(plan);
+                    }
+                    plan.steps.push(CascadeStep {
+                        item_id: dependent.id,
+                        title: dependent.title,
+                        item_type: field.item_type.clone(),
+                        action: dependent_action,
+                        field: field.field_name.clone(),
+                        source_id,
+                    });
+                    continue;
+                }
+                if !seen.insert(dependent.id) {
+                    continue;
+                }
+                if dependent_action == CascadeAction::Unpublish && dependent.status == 0 {
+                    continue;
+                }
+                if plan.steps.len() >= MAX_CASCADE_ITEMS {
+                    plan.truncated = true;
+                    return Ok(plan);
+                }
+                queue.push_back((dependent.id, field.item_type.clone(), dependent_action));
+                plan.steps.push(CascadeStep {
+                    item_id: dependent.id,
+                    title: dependent.title,
+                    item_type: field.item_type.clone(),
+                    action: dependent_action,
+                    field: field.field_name.clone(),
+                    source_id,
+                });
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Apply planned steps as batch operation `batch_id`.
+///
+/// Changes go through [`ItemService`] with the acting user's context, so
+/// access checks, taps and cache invalidation behave as for manual edits.
+/// Progress is saved after every step and the batch is completed with the
+/// [`CascadeOutcome`]; cancelling the batch stops the run between steps.
+pub async fn execute(
+    items: &ItemService,
+    batch: &BatchService,
+    audit: Option<&AuditService>,
+    batch_id: Uuid,
+    steps: &[CascadeStep],
+    user: &UserContext,
+) -> Result<CascadeOutcome> {
+    let total = steps.len() as u64;
+    let mut outcome = CascadeOutcome::default();
+
+    for (done, step) in steps.iter().enumerate() {
+        if batch
+            .get(batch_id)
+            .await?
+            .is_some_and(|op| op.status == BatchStatus::Cancelled)
+        {
+            outcome.cancelled = true;
+            break;
+        }
+        batch
+            .update_progress(
+                batch_id,
+                done as u64,
+                total,
+                Some(format!("{} \"{}\"", step.action.as_str(), step.title)),
+            )
+            .await?;
+
+        let applied = match step.action {
+            CascadeAction::Unpublish => {
+                let input = UpdateItem {
+                    title: None,
+                    status: Some(0),
+                    promote: None,
+                    sticky: None,
+                    fields: None,
+                    log: Some("Unpublished by reference cascade".to_string()),
+                    status_meta: Some(StatusChangeMeta {
+                        reason_code: Some("other".to_string()),
+                        note: Some(format!(
+                            "Referenced item {} (field {}) was unpublished or deleted.",
+                            step.source_id, step.field
+                        )),
+                        embargo_until: None,
+                    }),
+                };
+                items
+                    .update(step.item_id, input, user)
+                    .await
+                    .map(|i| i.is_some())
+            }
+            CascadeAction::Delete => items.delete(step.item_id, user).await,
+            CascadeAction::Clear => clear_reference(items, step, user).await,
+        };
+
+        match applied {
+            Ok(true) => {
+                match step.action {
+                    CascadeAction::Unpublish => outcome.unpublished += 1,
+                    CascadeAction::Delete => outcome.deleted += 1,
+                    CascadeAction::Clear => outcome.cleared += 1,
+                }
+                if let Some(audit) = audit
+                    && let Err(e) = audit
+                        .log(
+                            step.action.audit_action(),
+                            "item",
+                            &step.item_id.to_string(),
+                            Some(user.id),
+                            "",
+                            serde_json::json!({
+                                "item_type": step.item_type,
+                                "source_id": step.source_id,
+                                "field": step.field,
+                                "batch_id": batch_id,
+                            }),
+                        )
+                        .await
+                {
+                    warn!(item_id = %step.item_id, error = %e, "failed to audit cascade step");
+                }
+            }
+            Ok(false) => outcome.skipped += 1,
+            Err(e) => {
+                warn!(item_id = %step.item_id, error = %e, "cascade step failed");
+                outcome.failed += 1;
+            }
+        }
+    }
+
+    if !outcome.cancelled {
+        batch
+            .complete(
+                batch_id,
+                Some(serde_json::to_value(&outcome).context("serialize cascade outcome")?),
+            )
+            .await?;
+    }
+
+    info!(
+        batch_id = %batch_id,
+        unpublished = outcome.unpublished,
+        deleted = outcome.deleted,
+        cleared = outcome.cleared,
+        failed = outcome.failed,
+        "reference cascade finished"
+    );
+    Ok(outcome)
+}
+
+/// Remove the reference to `step.source_id` from the step's field.
+///
+/// Returns `false` if the item is gone or no longer holds the reference.
+async fn clear_reference(
+    items: &ItemService,
+    step: &CascadeStep,
+    user: &UserContext,
+) -> Result<bool> {
+    let Some(item) = items.load(step.item_id).await? else {
+        return Ok(false);
+    };
+    let Some(value) = item.fields.get(&step.field) else {
+        return Ok(false);
+    };
+    let Some(remaining) = without_reference(value, step.source_id) else {
+        return Ok(false);
+    };
+    let patch = ItemPatch {
+        fields: serde_json::json!({ &step.field: remaining }),
+        unmodified_since: None,
+        log: Some(format!(
+            "Reference to deleted item {} removed from {}",
+            step.source_id, step.field
+        )),
+    };
+    Ok(items.patch(step.item_id, patch, user).await?.is_some())
+}
+
+/// A reference field value with `target` taken out.
+///
+/// `None` if the value does not reference `target`. A single reference
+/// becomes `null`, which removes the field.
+fn without_reference(value: &serde_json::Value, target: Uuid) -> Option<serde_json::Value> {
+    let target = target.to_string();
+    match value {
+        serde_json::Value::String(s) if *s == target => Some(serde_json::Value::Null),
+        serde_json::Value::Array(values) => {
+            let remaining: Vec<serde_json::Value> = values
+                .iter()
+                .filter(|v| v.as_str() != Some(target.as_str()))
+                .cloned()
+                .collect();
+            (remaining.len() < values.len()).then_some(serde_json::Value::Array(remaining))
+        }
+        _ => None,
+    }
+}
+
+/// A reference whose target item no longer exists.
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct DanglingReference {
+    pub item_id: Uuid,
+    pub title: String,
+    pub item_type: String,
+    pub field_name: String,
+    /// The missing item's ID, as stored in the field.
+    pub target_id: String,
+}
+
+/// Maximum number of dangling references recorded per field.
+const MAX_DANGLING_PER_FIELD: i64 = 1000;
+
+/// Find references to items that no longer exist.
+///
+/// Checks every record reference field, whatever its rule; stored values
+/// that are not UUIDs count as dangling too.
+pub async fn find_dangling(
+    pool: &PgPool,
+    types: &[ContentTypeDefinition],
+) -> Result<Vec<DanglingReference>> {
+    let mut dangling = Vec::new();
+    for ct in types {
+        for field in &ct.fields {
+            if !matches!(field.field_type, FieldType::RecordReference(_)) {
+                continue;
+            }
+            let found = sqlx::query_as::<_, DanglingReference>(
+                r#"
+                SELECT i.id AS item_id, i.title, i.type AS item_type,
+                       $2::text AS field_name, r.value AS target_id
+                FROM item i
+                CROSS JOIN LATERAL jsonb_array_elements_text(
+                    CASE jsonb_typeof(i.fields -> $2)
+                        WHEN 'array' THEN i.fields -> $2
+                        WHEN 'string' THEN jsonb_build_array(i.fields -> $2)
+                        ELSE '[]'::jsonb
+                    END
+                ) AS r(value)
+                WHERE i.type = $1
+                  AND r.value <> ''
+                  AND NOT EXISTS (
+                      SELECT 1 FROM item t
+                      WHERE t.id = CASE
+                          WHEN r.value ~* '^[0-9a-f]{8}(-[0-9a-f]{4}){3}-[0-9a-f]{12}$'
+                          THEN r.value::uuid
+                      END
+                  )
+                ORDER BY i.created
+                LIMIT $3
+                "#,
+            )
+            .bind(&ct.machine_name)
+            .bind(&field.field_name)
+            .bind(MAX_DANGLING_PER_FIELD)
+            .fetch_all(pool)
+            .await
+            .context("failed to find dangling references")?;
+            dangling.extend(found);
+        }
+    }
+    Ok(dangling)
+}
+
+/// Replace the recorded dangling references with `found`.
+pub async fn record_dangling(pool: &PgPool, found: &[DanglingReference]) -> Result<()> {
+    let now = chrono::Utc::now().timestamp();
+    let mut tx = pool.begin().await.context("failed to begin transaction")?;
+    sqlx::query("DELETE FROM dangling_reference")
+        .execute(&mut *tx)
+        .await
+        .context("failed to clear dangling references")?;
+    for reference in found {
+        sqlx::query(
+            r#"
+            INSERT INTO dangling_reference (item_id, field_name, target_id, detected)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(reference.item_id)
+        .bind(&reference.field_name)
+        .bind(&reference.target_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .context("failed to record dangling reference")?;
+    }
+    tx.commit()
+        .await
+        .context("failed to commit dangling references")?;
+    Ok(())
+}
+
+/// Dangling references found by the last `check_dangling_references` run.
+pub async fn list_dangling(pool: &PgPool) -> Result<Vec<DanglingReference>> {
+    sqlx::query_as::<_, DanglingReference>(
+        r#"
+        SELECT d.item_id, i.title, i.type AS item_type, d.field_name, d.target_id
+        FROM dangling_reference d
+        JOIN item i ON i.id = d.item_id
+        ORDER BY i.type, i.title, d.field_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to list dangling references")
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn reference(name: &str, target: &str, cascade: Option<&str>) -> FieldDefinition {
+        let mut field = FieldDefinition::new(name, FieldType::RecordReference(target.to_string()));
+        if let Some(rule) = cascade {
+            field.settings = serde_json::json!({ CASCADE_SETTING: rule });
+        }
+        field
+    }
+
+    fn content_type(name: &str, fields: Vec<FieldDefinition>) -> ContentTypeDefinition {
+        ContentTypeDefinition {
+            machine_name: name.to_string(),
+            label: name.to_string(),
+            description: String::new(),
+            title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
+            fields,
+            extends: None,
+            template: false,
+        }
+    }
+
+    #[test]
+    fn rule_read_from_settings() {
+        assert_eq!(
+            CascadeRule::for_field(&reference("f", "feed", Some("delete"))),
+            CascadeRule::Delete
+        );
+        assert_eq!(
+            CascadeRule::for_field(&reference("f", "feed", None)),
+            CascadeRule::None
+        );
+        assert_eq!(
+            CascadeRule::for_field(&reference("f", "feed", Some("bogus"))),
+            CascadeRule::None
+        );
+
+        let mut text = FieldDefinition::new("body", FieldType::Text { max_length: None });
+        text.settings = serde_json::json!({ CASCADE_SETTING: "delete" });
+        assert_eq!(CascadeRule::for_field(&text), CascadeRule::None);
+    }
+
+    #[test]
+    fn delete_rule_only_deletes_on_delete() {
+        use CascadeAction::{Clear, Delete, Unpublish};
+        assert_eq!(CascadeRule::None.action_for(Delete), None);
+        assert_eq!(CascadeRule::Unpublish.action_for(Delete), Some(Unpublish));
+        assert_eq!(CascadeRule::Delete.action_for(Unpublish), Some(Unpublish));
+        assert_eq!(CascadeRule::Delete.action_for(Delete), Some(Delete));
+        assert_eq!(CascadeRule::SetNull.action_for(Delete), Some(Clear));
+        assert_eq!(CascadeRule::SetNull.action_for(Unpublish), None);
+        assert_eq!(CascadeRule::Restrict.action_for(Delete), None);
+    }
+
+    #[test]
+    fn references_removed_from_values() {
+        let target = Uuid::now_v7();
+        let other = Uuid::now_v7().to_string();
+        assert_eq!(
+            without_reference(&serde_json::json!(target.to_string()), target),
+            Some(serde_json::Value::Null)
+        );
+        assert_eq!(
+            without_reference(&serde_json::json!([other, target.to_string()]), target),
+            Some(serde_json::json!([other]))
+        );
+        assert_eq!(without_reference(&serde_json::json!(other), target), None);
+        assert_eq!(without_reference(&serde_json::json!([other]), target), None);
+    }
+
+    #[test]
+    fn dependent_fields_match_target_type() {
+        let types = vec![
+            content_type(
+                "argus_article",
+                vec![
+                    reference("field_feed", "argus_feed", Some("unpublish")),
+                    reference("field_related", "", Some("delete")),
+                    reference("field_topic", "argus_topic", Some("delete")),
+                    reference("field_source", "argus_feed", None),
+                ],
+            ),
+            content_type("argus_feed", vec![]),
+        ];
+
+        let fields = dependent_fields(&types, "argus_feed");
+        assert_eq!(
+            fields,
+            vec![
+                DependentField {
+                    item_type: "argus_article".to_string(),
+                    field_name: "field_feed".to_string(),
+                    rule: CascadeRule::Unpublish,
+                },
+                DependentField {
+                    item_type: "argus_article".to_string(),
+                    field_name: "field_related".to_string(),
+                    rule: CascadeRule::Delete,
+                },
+            ]
+        );
+        assert_eq!(dependent_fields(&types, "page").len(), 1);
+    }
+
+    #[test]
+    fn rules_round_trip() {
+        for rule in CascadeRule::ALL {
+            assert_eq!(CascadeRule::parse(rule.as_str()), Some(rule));
+        }
+        assert_eq!(CascadeAction::parse("delete"), Some(CascadeAction::Delete));
+        assert_eq!(CascadeAction::parse("publish"), None);
+    }
+}
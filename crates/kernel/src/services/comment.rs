@@ -1,7 +1,7 @@
 //! Comment service with tap integration.
 //!
 //! Provides CRUD operations for comments with automatic tap invocations
-//! for plugin taps (insert, update, delete, access).
+//! for plugin taps (insert, update, delete, access, spam check).
 
 use std::sync::Arc;
 
@@ -10,7 +10,9 @@ use sqlx::PgPool;
 use tracing::info;
 use uuid::Uuid;
 
+use crate::cache::page;
 use crate::models::{Comment, CreateComment, UpdateComment};
+use crate::services::comment_spam::{self, CommentSpamCheckInput, SpamDetection};
 use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
 use trovato_sdk::types::AccessResult;
 
@@ -67,10 +69,33 @@ impl CommentService {
             .dispatch("tap_comment_insert", &json, state)
             .await;
 
+        self.invalidate_item_pages(comment.item_id).await;
+
         info!(comment_id = %comment.id, item_id = %comment.item_id, "comment created");
         Ok(comment)
     }
 
+    /// Ask `tap_comment_spam_check` plugins about a comment before it is saved.
+    ///
+    /// Returns the first plugin's spam verdict, if any plugin gave one.
+    pub async fn check_spam(
+        &self,
+        input: &CommentSpamCheckInput,
+        user: &UserContext,
+    ) -> Result<Option<SpamDetection>> {
+        let json = serde_json::to_string(input).context("serialize spam check input")?;
+        let results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_comment_spam_check", &json, self.tap_state(user))
+            .await;
+        Ok(comment_spam::first_spam_verdict(
+            results
+                .iter()
+                .map(|r| (r.plugin_name.as_str(), r.output.as_str())),
+        ))
+    }
+
     /// Update a comment with `tap_comment_update` invocation.
     pub async fn update(
         &self,
@@ -89,6 +114,8 @@ impl CommentService {
                 .dispatch("tap_comment_update", &json, state)
                 .await;
 
+            self.invalidate_item_pages(c.item_id).await;
+
             info!(comment_id = %id, "comment updated");
         }
 
@@ -98,8 +125,9 @@ impl CommentService {
     /// Delete a comment with `tap_comment_delete` invocation (before delete).
     pub async fn delete(&self, id: Uuid, user: &UserContext) -> Result<bool> {
         // Load to dispatch tap before deletion
-        if let Some(comment) = self.load(id).await? {
-            let json = serde_json::to_string(&comment).context("serialize comment")?;
+        let comment = self.load(id).await?;
+        if let Some(ref comment) = comment {
+            let json = serde_json::to_string(comment).context("serialize comment")?;
             let state = self.tap_state(user);
             let _ = self
                 .inner
@@ -110,11 +138,21 @@ impl CommentService {
 
         let deleted = Comment::delete(&self.inner.pool, id).await?;
         if deleted {
+            if let Some(comment) = comment {
+                self.invalidate_item_pages(comment.item_id).await;
+            }
             info!(comment_id = %id, "comment deleted");
         }
         Ok(deleted)
     }
 
+    /// Invalidate cached pages showing an item's comments.
+    async fn invalidate_item_pages(&self, item_id: Uuid) {
+        if let Some(cache) = &self.inner.tap_services.cache {
+            cache.invalidate_tag(&page::item_tag(item_id)).await;
+        }
+    }
+
     /// List comments for an item (threaded order).
     pub async fn list_for_item(&self, item_id: Uuid) -> Result<Vec<Comment>> {
         Comment::list_for_item(&self.inner.pool, item_id).await
@@ -0,0 +1,406 @@
+//! Comment spam protection.
+//!
+//! Every comment submission passes three checks before it is saved:
+//!
+//! 1. a per-IP posting rate limit,
+//! 2. two cheap traps: a honeypot field that humans never see, and a
+//!    minimum time between loading the comment thread and posting,
+//! 3. `tap_comment_spam_check`, where plugins can ask an external service
+//!    (Akismet and the like) through the host HTTP functions.
+//!
+//! What happens to a caught comment is configurable separately for the
+//! traps and the plugins: it is rejected, saved unpublished for
+//! moderation, or published with a flag. Saved detections are kept in
+//! `comment_spam` and shown on the moderation screen. The policy lives in
+//! `site_config` under `comment_spam`:
+//!
+//! ```json
+//! { "honeypot": true, "min_seconds": 0, "trap_action": "reject",
+//!   "spam_action": "moderate", "rate_limit": 10, "rate_window_secs": 3600 }
+//! ```
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::SiteConfig;
+use trovato_sdk::types::{CommentSpamCheckResult, SpamVerdict};
+
+/// `site_config` key holding [`CommentSpamSettings`].
+pub const COMMENT_SPAM_SETTINGS_KEY: &str = "comment_spam";
+
+/// Honeypot input name. Hidden from people; bots tend to fill it in.
+pub const HONEYPOT_FIELD: &str = "homepage";
+
+/// Session key holding the Unix timestamp the comment thread was loaded at.
+pub const SESSION_RENDERED_AT: &str = "comment_form_rendered";
+
+/// Rate limiter category for comment posting.
+pub const RATE_LIMIT_CATEGORY: &str = "comments";
+
+/// Upper bound on `min_seconds`.
+pub const MAX_MIN_SECONDS: u32 = 600;
+
+/// Upper bound on `rate_window_secs` (one day).
+pub const MAX_RATE_WINDOW_SECS: u64 = 86_400;
+
+/// Detection source of the honeypot trap.
+pub const SOURCE_HONEYPOT: &str = "honeypot";
+
+/// Detection source of the time trap.
+pub const SOURCE_TIME_TRAP: &str = "time_trap";
+
+/// What to do with a comment caught as spam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamAction {
+    /// Refuse the comment; nothing is saved.
+    Reject,
+    /// Save the comment unpublished for a moderator to review.
+    Moderate,
+    /// Publish the comment and mark it on the moderation screen.
+    Flag,
+}
+
+impl SpamAction {
+    /// Publication status a saved comment gets under this action.
+    pub fn status(self) -> i16 {
+        match self {
+            Self::Moderate => 0,
+            Self::Reject | Self::Flag => 1,
+        }
+    }
+
+    /// Name stored with the detection.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Moderate => "moderate",
+            Self::Flag => "flag",
+        }
+    }
+}
+
+/// Comment spam policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommentSpamSettings {
+    /// Catch submissions that fill in the honeypot field.
+    pub honeypot: bool,
+    /// Minimum seconds between loading the thread and posting (0 = off).
+    pub min_seconds: u32,
+    /// Action for comments caught by the honeypot or time trap.
+    pub trap_action: SpamAction,
+    /// Action for comments a `tap_comment_spam_check` plugin calls spam.
+    pub spam_action: SpamAction,
+    /// Comments accepted per IP address per window (0 = unlimited).
+    pub rate_limit: u32,
+    /// Length of the rate limit window in seconds.
+    pub rate_window_secs: u64,
+}
+
+impl Default for CommentSpamSettings {
+    fn default() -> Self {
+        Self {
+            honeypot: true,
+            min_seconds: 0,
+            trap_action: SpamAction::Reject,
+            spam_action: SpamAction::Moderate,
+            rate_limit: 10,
+            rate_window_secs: 3600,
+        }
+    }
+}
+
+impl CommentSpamSettings {
+    /// Check that the time trap and rate window are in range.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_seconds > MAX_MIN_SECONDS {
+            return Err(format!(
+                "min_seconds must be between 0 and {MAX_MIN_SECONDS}"
+            ));
+        }
+        if self.rate_limit > 0 && !(1..=MAX_RATE_WINDOW_SECS).contains(&self.rate_window_secs) {
+            return Err(format!(
+                "rate_window_secs must be between 1 and {MAX_RATE_WINDOW_SECS}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the honeypot and time traps.
+    ///
+    /// `rendered_at` is the timestamp stored in the session when the
+    /// comment thread was loaded. Returns the detection source and reason
+    /// when the submission looks automated.
+    pub fn trap(
+        &self,
+        honeypot: Option<&str>,
+        rendered_at: Option<i64>,
+        now: i64,
+    ) -> Option<(&'static str, &'static str)> {
+        if self.honeypot && honeypot.is_some_and(|v| !v.trim().is_empty()) {
+            return Some((SOURCE_HONEYPOT, "honeypot filled"));
+        }
+        if self.min_seconds == 0 {
+            return None;
+        }
+        match rendered_at {
+            None => Some((SOURCE_TIME_TRAP, "comments not loaded in this session")),
+            Some(t) if now - t < i64::from(self.min_seconds) => {
+                Some((SOURCE_TIME_TRAP, "submitted too quickly"))
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Input for `tap_comment_spam_check`.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. The kernel serializes this; plugins deserialize
+/// it. If you change fields here, update the SDK copy to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentSpamCheckInput {
+    /// Item being commented on.
+    pub item_id: Uuid,
+    /// Comment being replied to, if any.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub author_mail: String,
+    pub body: String,
+    /// IP address the comment was posted from.
+    pub client_ip: String,
+    /// `User-Agent` header of the request, if sent.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// Why a comment was caught.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpamDetection {
+    /// [`SOURCE_HONEYPOT`], [`SOURCE_TIME_TRAP`] or the plugin name.
+    pub source: String,
+    pub reason: Option<String>,
+    pub score: Option<f64>,
+}
+
+impl SpamDetection {
+    /// A detection by one of the kernel traps.
+    pub fn trap(source: &str, reason: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            reason: Some(reason.to_string()),
+            score: None,
+        }
+    }
+}
+
+/// The first spam verdict among `tap_comment_spam_check` outputs, given
+/// as `(plugin, output)` pairs in dispatch order.
+///
+/// Outputs that are empty or not a [`CommentSpamCheckResult`] count as ham.
+pub fn first_spam_verdict<'a>(
+    outputs: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Option<SpamDetection> {
+    outputs.into_iter().find_map(|(plugin, output)| {
+        let result = serde_json::from_str::<CommentSpamCheckResult>(output).ok()?;
+        (result.verdict == SpamVerdict::Spam).then(|| SpamDetection {
+            source: plugin.to_string(),
+            reason: result.reason,
+            score: result.score,
+        })
+    })
+}
+
+/// A saved comment's spam detection.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CommentSpamRecord {
+    pub comment_id: Uuid,
+    pub source: String,
+    pub reason: Option<String>,
+    pub score: Option<f64>,
+    /// "moderate" or "flag".
+    pub action: String,
+    pub client_ip: String,
+    /// Unix timestamp of the detection.
+    pub created: i64,
+}
+
+/// Load the spam policy, falling back to defaults if unset or invalid.
+pub async fn settings(pool: &PgPool) -> Result<CommentSpamSettings> {
+    Ok(
+        match SiteConfig::get(pool, COMMENT_SPAM_SETTINGS_KEY).await? {
+            Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+                warn!(error = %e, "invalid comment_spam settings; using defaults");
+                CommentSpamSettings::default()
+            }),
+            None => CommentSpamSettings::default(),
+        },
+    )
+}
+
+/// Validate and persist the spam policy.
+pub async fn save_settings(pool: &PgPool, settings: &CommentSpamSettings) -> Result<()> {
+    settings.validate().map_err(anyhow::Error::msg)?;
+    SiteConfig::set(
+        pool,
+        COMMENT_SPAM_SETTINGS_KEY,
+        serde_json::to_value(settings).context("failed to serialize comment spam settings")?,
+    )
+    .await
+}
+
+/// Record the detection for a saved comment.
+pub async fn record(
+    pool: &PgPool,
+    comment_id: Uuid,
+    detection: &SpamDetection,
+    action: SpamAction,
+    client_ip: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO comment_spam (comment_id, source, reason, score, action, client_ip, created)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (comment_id) DO NOTHING
+        "#,
+    )
+    .bind(comment_id)
+    .bind(&detection.source)
+    .bind(&detection.reason)
+    .bind(detection.score)
+    .bind(action.as_str())
+    .bind(client_ip)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await
+    .context("failed to record comment spam detection")?;
+    Ok(())
+}
+
+/// Detections for the given comments, keyed by comment ID.
+pub async fn for_comments(
+    pool: &PgPool,
+    comment_ids: &[Uuid],
+) -> Result<HashMap<Uuid, CommentSpamRecord>> {
+    if comment_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let records = sqlx::query_as::<_, CommentSpamRecord>(
+        r#"
+        SELECT comment_id, source, reason, score, action, client_ip, created
+        FROM comment_spam
+        WHERE comment_id = ANY($1)
+        "#,
+    )
+    .bind(comment_ids)
+    .fetch_all(pool)
+    .await
+    .context("failed to load comment spam detections")?;
+    Ok(records.into_iter().map(|r| (r.comment_id, r)).collect())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn honeypot_trap() {
+        let settings = CommentSpamSettings::default();
+        assert_eq!(settings.trap(None, None, 100), None);
+        assert_eq!(settings.trap(Some("  "), None, 100), None);
+        assert_eq!(
+            settings.trap(Some("http://spam"), None, 100),
+            Some((SOURCE_HONEYPOT, "honeypot filled"))
+        );
+
+        let off = CommentSpamSettings {
+            honeypot: false,
+            ..Default::default()
+        };
+        assert_eq!(off.trap(Some("http://spam"), None, 100), None);
+    }
+
+    #[test]
+    fn time_trap_only_when_enabled() {
+        let settings = CommentSpamSettings {
+            min_seconds: 5,
+            ..Default::default()
+        };
+        assert_eq!(settings.trap(None, Some(100), 110), None);
+        assert_eq!(
+            settings.trap(None, Some(100), 102).map(|t| t.0),
+            Some(SOURCE_TIME_TRAP)
+        );
+        assert_eq!(
+            settings.trap(None, None, 110).map(|t| t.0),
+            Some(SOURCE_TIME_TRAP)
+        );
+
+        // The default policy has no time trap.
+        assert_eq!(CommentSpamSettings::default().trap(None, None, 110), None);
+    }
+
+    #[test]
+    fn settings_validation() {
+        assert!(CommentSpamSettings::default().validate().is_ok());
+        let slow = CommentSpamSettings {
+            min_seconds: MAX_MIN_SECONDS + 1,
+            ..Default::default()
+        };
+        assert!(slow.validate().is_err());
+        let no_window = CommentSpamSettings {
+            rate_window_secs: 0,
+            ..Default::default()
+        };
+        assert!(no_window.validate().is_err());
+        let unlimited = CommentSpamSettings {
+            rate_limit: 0,
+            rate_window_secs: 0,
+            ..Default::default()
+        };
+        assert!(unlimited.validate().is_ok());
+    }
+
+    #[test]
+    fn settings_fill_missing_keys() {
+        let settings: CommentSpamSettings =
+            serde_json::from_value(serde_json::json!({"spam_action": "flag"})).unwrap();
+        assert_eq!(settings.spam_action, SpamAction::Flag);
+        assert_eq!(settings.trap_action, SpamAction::Reject);
+        assert_eq!(SpamAction::Moderate.status(), 0);
+        assert_eq!(SpamAction::Flag.status(), 1);
+    }
+
+    #[test]
+    fn first_spam_verdict_wins() {
+        let outputs = [
+            ("akismet", r#"{"verdict":"ham"}"#),
+            ("", ""),
+            ("broken", "not json"),
+            (
+                "blocklist",
+                r#"{"verdict":"spam","reason":"listed","score":0.8}"#,
+            ),
+            ("other", r#"{"verdict":"spam"}"#),
+        ];
+        let detection = first_spam_verdict(outputs).unwrap();
+        assert_eq!(detection.source, "blocklist");
+        assert_eq!(detection.reason.as_deref(), Some("listed"));
+        assert_eq!(detection.score, Some(0.8));
+
+        assert_eq!(
+            first_spam_verdict([("akismet", r#"{"verdict":"ham"}"#)]),
+            None
+        );
+    }
+}
@@ -0,0 +1,405 @@
+//! Site contact form.
+//!
+//! The form is built with the Form API (`contact_form`), so plugins can
+//! alter it with `tap_form_alter` and reject submissions with
+//! `tap_form_validate` (e.g. an external spam checker). On top of that the
+//! kernel applies two cheap spam traps: a honeypot field that humans never
+//! see, and a minimum time between rendering the form and submitting it.
+//! Submissions caught by a trap are dropped without telling the sender.
+//!
+//! Accepted submissions are stored as [`ContactSubmission`]s and mailed to
+//! the recipients of the chosen [`ContactCategory`].
+
+use std::collections::HashMap;
+
+use crate::form::{Form, FormElement, ValidationError};
+use crate::models::{ContactCategory, ContactSubmission};
+use crate::routes::helpers::is_valid_email;
+
+/// Form ID passed to form taps.
+pub const CONTACT_FORM_ID: &str = "contact_form";
+
+/// Honeypot input name. Hidden from people; bots tend to fill it in.
+pub const HONEYPOT_FIELD: &str = "homepage";
+
+/// Session key holding the Unix timestamp the form was rendered at.
+pub const SESSION_RENDERED_AT: &str = "contact_form_rendered";
+
+/// Submissions sent faster than this after rendering are treated as spam.
+pub const MIN_FILL_SECONDS: i64 = 3;
+
+/// Maximum length of the name and subject inputs.
+const MAX_LINE_LENGTH: usize = 255;
+
+/// Maximum length of the message body.
+const MAX_MESSAGE_LENGTH: usize = 10_000;
+
+/// A validated contact form submission.
+#[derive(Debug, Clone)]
+pub struct ContactInput {
+    pub category: ContactCategory,
+    pub name: String,
+    pub mail: String,
+    pub subject: String,
+    pub message: String,
+}
+
+/// Build the contact form, pre-filled with `values`.
+///
+/// With a single category the selector is omitted and the category is
+/// submitted as a hidden value.
+pub fn build_form(categories: &[ContactCategory], values: &HashMap<String, String>) -> Form {
+    let value = |name: &str| values.get(name).cloned().unwrap_or_default();
+
+    let category = match categories {
+        [only] => FormElement::hidden().default_value(only.id.to_string()),
+        _ => FormElement::select(
+            categories
+                .iter()
+                .map(|c| (c.id.to_string(), c.name.clone()))
+                .collect(),
+        )
+        .title("Category")
+        .required()
+        .default_value(value("category")),
+    };
+
+    let mut trap = FormElement::textfield().title("Leave this field empty");
+    trap.attributes = Some(serde_json::json!({"tabindex": "-1", "autocomplete": "off"}));
+    let mut honeypot = FormElement::container().child(HONEYPOT_FIELD, trap);
+    honeypot.attributes = Some(serde_json::json!({"hidden": "hidden", "aria-hidden": "true"}));
+
+    Form::new(CONTACT_FORM_ID)
+        .action("/contact")
+        .element(
+            "name",
+            FormElement::textfield()
+                .title("Your name")
+                .required()
+                .max_length(MAX_LINE_LENGTH)
+                .default_value(value("name"))
+                .weight(0),
+        )
+        .element(
+            "mail",
+            FormElement::textfield()
+                .title("Your email address")
+                .required()
+                .max_length(254)
+                .default_value(value("mail"))
+                .weight(1),
+        )
+        .element("category", category.weight(2))
+        .element(
+            "subject",
+            FormElement::textfield()
+                .title("Subject")
+                .required()
+                .max_length(MAX_LINE_LENGTH)
+                .default_value(value("subject"))
+                .weight(3),
+        )
+        .element(
+            "message",
+            FormElement::textarea(8)
+                .title("Message")
+                .required()
+                .default_value(value("message"))
+                .weight(4),
+        )
+        .element("honeypot", honeypot.weight(5))
+        .element("submit", FormElement::submit("Send message").weight(10))
+}
+
+/// Validate submitted values against the available categories.
+///
+/// Returns every error at once, keyed by field.
+pub fn validate(
+    values: &HashMap<String, String>,
+    categories: &[ContactCategory],
+) -> Result<ContactInput, Vec<ValidationError>> {
+    let value = |name: &str| values.get(name).map(|v| v.trim()).unwrap_or("");
+    let mut errors = Vec::new();
+
+    let name = value("name");
+    if name.is_empty() {
+        errors.push(ValidationError::field("name", "Your name is required."));
+    } else if name.chars().count() > MAX_LINE_LENGTH {
+        errors.push(ValidationError::field("name", "Your name is too long."));
+    }
+
+    let mail = value("mail");
+    if mail.is_empty() {
+        errors.push(ValidationError::field(
+            "mail",
+            "Your email address is required.",
+        ));
+    } else if !is_valid_email(mail) {
+        errors.push(ValidationError::field(
+            "mail",
+            "Please enter a valid email address.",
+        ));
+    }
+
+    let category = categories
+        .iter()
+        .find(|c| c.id.to_string() == value("category"));
+    if category.is_none() {
+        errors.push(ValidationError::field(
+            "category",
+            "Please choose a category.",
+        ));
+    }
+
+    let subject = value("subject");
+    if subject.is_empty() {
+        errors.push(ValidationError::field("subject", "Subject is required."));
+    } else if subject.chars().count() > MAX_LINE_LENGTH {
+        errors.push(ValidationError::field("subject", "Subject is too long."));
+    }
+
+    let message = value("message");
+    if message.is_empty() {
+        errors.push(ValidationError::field("message", "Message is required."));
+    } else if message.chars().count() > MAX_MESSAGE_LENGTH {
+        errors.push(ValidationError::field(
+            "message",
+            format!("Message must be {MAX_MESSAGE_LENGTH} characters or fewer."),
+        ));
+    }
+
+    match category {
+        Some(category) if errors.is_empty() => Ok(ContactInput {
+            category: category.clone(),
+            name: name.to_string(),
+            mail: mail.to_string(),
+            subject: subject.to_string(),
+            message: message.to_string(),
+        }),
+        _ => Err(errors),
+    }
+}
+
+/// Parse a category's recipient list (comma- or newline-separated).
+///
+/// Duplicates are dropped; at least one valid address is required.
+pub fn parse_recipients(raw: &str) -> Result<Vec<String>, String> {
+    let mut recipients: Vec<String> = Vec::new();
+    for address in raw
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        if !is_valid_email(address) {
+            return Err(format!("\"{address}\" is not a valid email address."));
+        }
+        if !recipients.iter().any(|r| r.eq_ignore_ascii_case(address)) {
+            recipients.push(address.to_string());
+        }
+    }
+    if recipients.is_empty() {
+        return Err("At least one recipient is required.".to_string());
+    }
+    Ok(recipients)
+}
+
+/// Check the kernel spam traps.
+///
+/// `rendered_at` is the timestamp stored in the session when the form was
+/// shown; a missing timestamp means the form was never rendered for this
+/// session. Returns the reason when the submission looks automated.
+pub fn spam_reason(
+    values: &HashMap<String, String>,
+    rendered_at: Option<i64>,
+    now: i64,
+) -> Option<&'static str> {
+    if values
+        .get(HONEYPOT_FIELD)
+        .is_some_and(|v| !v.trim().is_empty())
+    {
+        return Some("honeypot filled");
+    }
+    match rendered_at {
+        None => Some("form not rendered in this session"),
+        Some(t) if now - t < MIN_FILL_SECONDS => Some("submitted too quickly"),
+        Some(_) => None,
+    }
+}
+
+/// Render submissions as CSV (RFC 4180), header row first.
+pub fn export_csv(submissions: &[ContactSubmission]) -> String {
+    let mut csv = String::from("id,created,category,name,mail,subject,message,user_id\r\n");
+    for s in submissions {
+        let created = chrono::DateTime::from_timestamp(s.created, 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+        let user_id = s.user_id.map(|id| id.to_string()).unwrap_or_default();
+        let row = [
+            s.id.to_string().as_str(),
+            &created,
+            &s.category_name,
+            &s.name,
+            &s.mail,
+            &s.subject,
+            &s.message,
+            &user_id,
+        ]
+        .map(csv_field)
+        .join(",");
+        csv.push_str(&row);
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Quote a CSV field when needed.
+///
+/// Fields starting with a formula trigger (`=`, `+`, `-`, `@`) are prefixed
+/// with `'` so spreadsheet applications do not evaluate user input.
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn category(name: &str) -> ContactCategory {
+        ContactCategory {
+            id: Uuid::now_v7(),
+            name: name.to_string(),
+            recipients: vec!["team@example.com".to_string()],
+            weight: 0,
+            created: 0,
+        }
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn single_category_is_hidden() {
+        let categories = vec![category("General")];
+        let form = build_form(&categories, &HashMap::new());
+        assert_eq!(form.elements["category"].element_type.type_name(), "hidden");
+        assert_eq!(
+            form.elements["category"].default_value,
+            Some(serde_json::json!(categories[0].id.to_string()))
+        );
+
+        let form = build_form(&[category("General"), category("Press")], &HashMap::new());
+        assert_eq!(form.elements["category"].element_type.type_name(), "select");
+        assert!(
+            form.elements["honeypot"]
+                .children
+                .contains_key(HONEYPOT_FIELD)
+        );
+    }
+
+    #[test]
+    fn valid_submission_is_trimmed() {
+        let categories = vec![category("General"), category("Press")];
+        let input = validate(
+            &values(&[
+                ("name", " Ada "),
+                ("mail", "ada@example.com"),
+                ("category", &categories[1].id.to_string()),
+                ("subject", "Hello"),
+                ("message", "A question.\n"),
+            ]),
+            &categories,
+        )
+        .unwrap();
+
+        assert_eq!(input.category.name, "Press");
+        assert_eq!(input.name, "Ada");
+        assert_eq!(input.message, "A question.");
+    }
+
+    #[test]
+    fn invalid_submission_reports_every_field() {
+        let errors = validate(
+            &values(&[("mail", "not-an-address"), ("category", "bogus")]),
+            &[category("General")],
+        )
+        .unwrap_err();
+
+        let fields: Vec<&str> = errors.iter().filter_map(|e| e.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            vec!["name", "mail", "category", "subject", "message"]
+        );
+    }
+
+    #[test]
+    fn recipients_are_split_and_deduplicated() {
+        assert_eq!(
+            parse_recipients("a@example.com, b@example.com\r\nA@example.com\n").unwrap(),
+            vec!["a@example.com", "b@example.com"]
+        );
+        assert!(parse_recipients(" , ").is_err());
+        assert!(
+            parse_recipients("a@example.com, nope")
+                .unwrap_err()
+                .contains("nope")
+        );
+    }
+
+    #[test]
+    fn spam_traps() {
+        let clean = values(&[("homepage", "")]);
+        assert_eq!(spam_reason(&clean, Some(100), 110), None);
+        assert_eq!(
+            spam_reason(&values(&[("homepage", "http://spam")]), Some(100), 110),
+            Some("honeypot filled")
+        );
+        assert_eq!(
+            spam_reason(&clean, Some(100), 101),
+            Some("submitted too quickly")
+        );
+        assert!(spam_reason(&clean, None, 110).is_some());
+    }
+
+    #[test]
+    fn csv_quotes_and_neutralizes_formulas() {
+        let submission = ContactSubmission {
+            id: Uuid::nil(),
+            category_id: None,
+            category_name: "General".to_string(),
+            name: "=HYPERLINK(\"x\")".to_string(),
+            mail: "ada@example.com".to_string(),
+            subject: "Hi, there".to_string(),
+            message: "line one\nline two".to_string(),
+            user_id: None,
+            created: 0,
+        };
+
+        let csv = export_csv(&[submission]);
+        let mut lines = csv.split("\r\n");
+        assert_eq!(
+            lines.next(),
+            Some("id,created,category,name,mail,subject,message,user_id")
+        );
+        let row = lines.next().unwrap();
+        assert!(row.contains(r#""'=HYPERLINK(""x"")""#));
+        assert!(row.contains(r#""Hi, there""#));
+        assert!(row.contains("\"line one\nline two\""));
+        assert!(row.ends_with(','));
+    }
+}
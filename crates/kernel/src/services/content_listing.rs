@@ -0,0 +1,364 @@
+//! Admin content listing and saved views.
+//!
+//! [`ContentFilter`] narrows the item table by type, status, stage, author,
+//! language and changed range, and sorts by any listed column. Admins can
+//! save filters under a name; each admin's views are kept in `site_config`
+//! under `content_views.{user_id}`, keyed by name.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use trovato_sdk::types::SortDirection;
+use uuid::Uuid;
+
+use crate::models::SiteConfig;
+use crate::services::site::current_tenant_id;
+
+/// `site_config` key prefix of each admin's saved views.
+const VIEWS_CONFIG_PREFIX: &str = "content_views.";
+
+/// Most saved views per user.
+pub const MAX_SAVED_VIEWS: usize = 50;
+
+/// Longest saved view name.
+pub const MAX_VIEW_NAME_LEN: usize = 64;
+
+/// Conditions shared by the list and count queries.
+///
+/// `$1`–`$7` are the optional filters of [`ContentFilter`]; an unset filter
+/// binds NULL and matches every item.
+const FILTER_SQL: &str = r#"
+    FROM item i
+    LEFT JOIN users u ON u.id = i.author_id
+    LEFT JOIN category_tag s ON s.id = i.stage_id
+    WHERE ($1::text IS NULL OR i.type = $1)
+      AND ($2::smallint IS NULL OR i.status = $2)
+      AND ($3::uuid IS NULL OR i.stage_id = $3)
+      AND ($4::uuid IS NULL OR i.author_id = $4)
+      AND ($5::text IS NULL OR i.language = $5)
+      AND ($6::bigint IS NULL OR i.changed >= $6)
+      AND ($7::bigint IS NULL OR i.changed < $7)
+      AND i.tenant_id = $8
+"#;
+
+/// Column the listing is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentSort {
+    /// Title, case-insensitive.
+    Title,
+    /// Content type machine name.
+    Type,
+    /// Publication status.
+    Status,
+    /// Author name.
+    Author,
+    /// Stage label.
+    Stage,
+    /// Language code.
+    Language,
+    /// Creation time.
+    Created,
+    /// Last change time.
+    #[default]
+    Changed,
+}
+
+impl ContentSort {
+    /// SQL expression to order by.
+    fn column(self) -> &'static str {
+        match self {
+            Self::Title => "LOWER(i.title)",
+            Self::Type => "i.type",
+            Self::Status => "i.status",
+            Self::Author => "LOWER(u.name)",
+            Self::Stage => "s.label",
+            Self::Language => "i.language",
+            Self::Created => "i.created",
+            Self::Changed => "i.changed",
+        }
+    }
+}
+
+/// Filters and sort order of the admin content listing.
+///
+/// Also the body of a saved view. Unset filters match every item.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentFilter {
+    /// Content type machine name.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+    /// Publication status (0 = unpublished, 1 = published).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<i16>,
+    /// Stage the item lives in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<Uuid>,
+    /// Author user ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<Uuid>,
+    /// Language code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Only items changed at or after this Unix timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed_after: Option<i64>,
+    /// Only items changed before this Unix timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changed_before: Option<i64>,
+    /// Sort column; defaults to [`ContentSort::Changed`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<ContentSort>,
+    /// Sort direction; defaults to descending.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<SortDirection>,
+}
+
+impl ContentFilter {
+    /// Replace this filter's values with those set in `other`.
+    ///
+    /// Lets request parameters narrow or re-sort a saved view.
+    pub fn merge(&mut self, other: ContentFilter) {
+        self.item_type = other.item_type.or(self.item_type.take());
+        self.status = other.status.or(self.status);
+        self.stage = other.stage.or(self.stage);
+        self.author = other.author.or(self.author);
+        self.language = other.language.or(self.language.take());
+        self.changed_after = other.changed_after.or(self.changed_after);
+        self.changed_before = other.changed_before.or(self.changed_before);
+        self.sort = other.sort.or(self.sort);
+        self.direction = other.direction.or(self.direction);
+    }
+
+    /// ORDER BY clause, with the item ID as tie-breaker for stable pages.
+    fn order_by(&self) -> String {
+        let direction = match self.direction.unwrap_or_default() {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        format!(
+            "ORDER BY {} {direction} NULLS LAST, i.id {direction}",
+            self.sort.unwrap_or_default().column()
+        )
+    }
+
+    /// Bind the filters as `$1`–`$7` of [`FILTER_SQL`], and the current
+    /// site's tenant as `$8`.
+    fn bind<'q, O>(
+        &'q self,
+        query: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
+    ) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
+        query
+            .bind(self.item_type.as_deref())
+            .bind(self.status)
+            .bind(self.stage)
+            .bind(self.author)
+            .bind(self.language.as_deref())
+            .bind(self.changed_after)
+            .bind(self.changed_before)
+            .bind(current_tenant_id())
+    }
+}
+
+/// One row of the admin content listing.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ContentRow {
+    /// Item ID.
+    pub id: Uuid,
+    /// Content type machine name (`type` in the database and JSON).
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub item_type: String,
+    /// Item title.
+    pub title: String,
+    /// Publication status (0 = unpublished, 1 = published).
+    pub status: i16,
+    /// Author's user ID.
+    pub author_id: Uuid,
+    /// Author's user name; `None` if the account no longer exists.
+    pub author_name: Option<String>,
+    /// Stage the item lives in.
+    pub stage_id: Uuid,
+    /// Stage label; `None` if the stage no longer exists.
+    pub stage_label: Option<String>,
+    /// Item language code.
+    pub language: String,
+    /// Creation time (Unix timestamp).
+    pub created: i64,
+    /// Last change time (Unix timestamp).
+    pub changed: i64,
+}
+
+/// List items matching `filter`, with the total number of matches.
+pub async fn list(
+    pool: &PgPool,
+    filter: &ContentFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<ContentRow>, i64)> {
+    let sql = format!(
+        "SELECT i.id, i.type, i.title, i.status, i.author_id, u.name AS author_name, \
+         i.stage_id, s.label AS stage_label, i.language, i.created, i.changed \
+         {FILTER_SQL} {} LIMIT $9 OFFSET $10",
+        filter.order_by()
+    );
+    let rows = filter
+        .bind(sqlx::query_as::<_, ContentRow>(&sql))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list content")?;
+
+    let count_sql = format!("SELECT COUNT(*) {FILTER_SQL}");
+    let (total,): (i64,) = filter
+        .bind(sqlx::query_as(&count_sql))
+        .fetch_one(pool)
+        .await
+        .context("failed to count content")?;
+
+    Ok((rows, total))
+}
+
+/// Trim a view name and check its length.
+pub fn normalize_view_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_VIEW_NAME_LEN {
+        return Err(format!(
+            "view name is required and at most {MAX_VIEW_NAME_LEN} characters"
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// `site_config` key of the user's saved views.
+fn views_key(user_id: Uuid) -> String {
+    format!("{VIEWS_CONFIG_PREFIX}{user_id}")
+}
+
+/// A user's saved views, by name.
+pub async fn saved_views(pool: &PgPool, user_id: Uuid) -> Result<BTreeMap<String, ContentFilter>> {
+    let views = SiteConfig::get(pool, &views_key(user_id))
+        .await
+        .context("failed to read saved content views")?;
+
+    Ok(views
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+/// Save `filter` as the user's view `name`, replacing any view of that name.
+///
+/// Returns `false` without saving if the user already has
+/// [`MAX_SAVED_VIEWS`] other views.
+pub async fn save_view(
+    pool: &PgPool,
+    user_id: Uuid,
+    name: &str,
+    filter: &ContentFilter,
+) -> Result<bool> {
+    let mut views = saved_views(pool, user_id).await?;
+    if !views.contains_key(name) && views.len() >= MAX_SAVED_VIEWS {
+        return Ok(false);
+    }
+
+    views.insert(name.to_string(), filter.clone());
+    SiteConfig::set(pool, &views_key(user_id), serde_json::to_value(&views)?)
+        .await
+        .context("failed to save content view")?;
+
+    Ok(true)
+}
+
+/// Delete the user's view `name`. Returns whether it existed.
+pub async fn delete_view(pool: &PgPool, user_id: Uuid, name: &str) -> Result<bool> {
+    let mut views = saved_views(pool, user_id).await?;
+    if views.remove(name).is_none() {
+        return Ok(false);
+    }
+
+    SiteConfig::set(pool, &views_key(user_id), serde_json::to_value(&views)?)
+        .await
+        .context("failed to delete content view")?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_filter_lists_newest_changes_first() {
+        let filter = ContentFilter::default();
+        assert_eq!(
+            filter.order_by(),
+            "ORDER BY i.changed DESC NULLS LAST, i.id DESC"
+        );
+    }
+
+    #[test]
+    fn sort_columns_are_fixed_expressions() {
+        let filter = ContentFilter {
+            sort: Some(ContentSort::Author),
+            direction: Some(SortDirection::Asc),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.order_by(),
+            "ORDER BY LOWER(u.name) ASC NULLS LAST, i.id ASC"
+        );
+        assert!(serde_json::from_str::<ContentSort>(r#""i.title; DROP TABLE item""#).is_err());
+    }
+
+    #[test]
+    fn filter_round_trips_without_unset_fields() {
+        let filter: ContentFilter =
+            serde_json::from_str(r#"{"type": "blog", "status": 0, "sort": "title"}"#).unwrap();
+        assert_eq!(filter.item_type.as_deref(), Some("blog"));
+        assert_eq!(filter.status, Some(0));
+        assert_eq!(filter.sort, Some(ContentSort::Title));
+        assert_eq!(filter.direction, None);
+
+        let json = serde_json::to_value(&filter).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "blog", "status": 0, "sort": "title"})
+        );
+    }
+
+    #[test]
+    fn request_values_override_saved_view() {
+        let mut view = ContentFilter {
+            item_type: Some("blog".to_string()),
+            status: Some(0),
+            sort: Some(ContentSort::Title),
+            ..Default::default()
+        };
+        let request = ContentFilter {
+            status: Some(1),
+            language: Some("de".to_string()),
+            direction: Some(SortDirection::Asc),
+            ..Default::default()
+        };
+        view.merge(request);
+
+        assert_eq!(view.item_type.as_deref(), Some("blog"));
+        assert_eq!(view.status, Some(1));
+        assert_eq!(view.language.as_deref(), Some("de"));
+        assert_eq!(view.sort, Some(ContentSort::Title));
+        assert_eq!(view.direction, Some(SortDirection::Asc));
+    }
+
+    #[test]
+    fn view_names_are_trimmed_and_bounded() {
+        assert_eq!(normalize_view_name("  Drafts ").unwrap(), "Drafts");
+        assert!(normalize_view_name("   ").is_err());
+        assert!(normalize_view_name(&"x".repeat(MAX_VIEW_NAME_LEN + 1)).is_err());
+    }
+}
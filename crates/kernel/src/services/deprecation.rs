@@ -0,0 +1,414 @@
+//! Usage tracking for deprecated API shapes.
+//!
+//! Legacy endpoints, request fields and query parameters are listed in
+//! [`DEPRECATIONS`]. The `track_deprecations` middleware matches each request
+//! against the registry and counts hits per client (API token, OAuth client,
+//! session user or anonymous), so operators can see who still needs to
+//! migrate before a shape is removed. Handlers record deprecated body fields
+//! themselves through [`DeprecationService::record`].
+//!
+//! Counts accumulate in memory and are flushed to `api_deprecation_usage`
+//! every [`FLUSH_INTERVAL`] and before a report is read. With
+//! `DEPRECATION_HEADERS` enabled, matching responses also carry
+//! `Deprecation` (RFC 9745) and, once a removal date is set, `Sunset`
+//! (RFC 8594) headers.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, TimeZone, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// How often buffered counts are written to the database.
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What part of the API a deprecation covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeprecationKind {
+    /// A whole route.
+    Endpoint,
+    /// A request body field.
+    Field,
+    /// A query parameter.
+    Parameter,
+}
+
+/// A deprecated endpoint, field or parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    /// Stable identifier used in counters and reports.
+    pub id: &'static str,
+    pub kind: DeprecationKind,
+    /// HTTP method of the affected route.
+    pub method: &'static str,
+    /// Route pattern; `{name}` segments match any value.
+    pub path: &'static str,
+    /// Field or parameter name (`None` for endpoints).
+    pub name: Option<&'static str>,
+    /// Date the deprecation was announced (`YYYY-MM-DD`).
+    pub deprecated_since: &'static str,
+    /// Planned removal date (`YYYY-MM-DD`), once scheduled.
+    pub sunset: Option<&'static str>,
+    /// What clients should use instead.
+    pub replacement: &'static str,
+}
+
+/// Registry of deprecated API shapes.
+pub const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        id: "items_by_type",
+        kind: DeprecationKind::Endpoint,
+        method: "GET",
+        path: "/api/items/{type}",
+        name: None,
+        deprecated_since: "2026-10-16",
+        sunset: None,
+        replacement: "GET /api/items?type={type}",
+    },
+    Deprecation {
+        id: "search_limit",
+        kind: DeprecationKind::Parameter,
+        method: "GET",
+        path: "/api/search",
+        name: Some("limit"),
+        deprecated_since: "2026-10-16",
+        sunset: None,
+        replacement: "per_page",
+    },
+];
+
+impl Deprecation {
+    /// Look up a deprecation by ID.
+    pub fn find(id: &str) -> Option<&'static Deprecation> {
+        DEPRECATIONS.iter().find(|d| d.id == id)
+    }
+
+    /// Whether this deprecation applies to a request.
+    ///
+    /// Field deprecations never match here; handlers record them.
+    pub fn matches(&self, method: &str, path: &str, query: Option<&str>) -> bool {
+        if !self.method.eq_ignore_ascii_case(method) || !path_matches(self.path, path) {
+            return false;
+        }
+        match (self.kind, self.name) {
+            (DeprecationKind::Endpoint, _) => true,
+            (DeprecationKind::Parameter, Some(name)) => has_query_param(query, name),
+            _ => false,
+        }
+    }
+
+    /// `Deprecation` header value: `@<unix timestamp>`.
+    pub fn deprecation_header(&self) -> Option<String> {
+        let ts = parse_date(self.deprecated_since)?.timestamp();
+        Some(format!("@{ts}"))
+    }
+
+    /// `Sunset` header value as an HTTP date, if removal is scheduled.
+    pub fn sunset_header(&self) -> Option<String> {
+        let date = parse_date(self.sunset?)?;
+        Some(date.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+    }
+}
+
+/// Deprecations matching a request.
+pub fn matching(method: &str, path: &str, query: Option<&str>) -> Vec<&'static Deprecation> {
+    DEPRECATIONS
+        .iter()
+        .filter(|d| d.matches(method, path, query))
+        .collect()
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern = pattern.trim_end_matches('/').split('/');
+    let mut path = path.trim_end_matches('/').split('/');
+    loop {
+        match (pattern.next(), path.next()) {
+            (None, None) => return true,
+            (Some(p), Some(s)) if p.starts_with('{') && p.ends_with('}') => {
+                if s.is_empty() {
+                    return false;
+                }
+            }
+            (Some(p), Some(s)) if p == s => {}
+            _ => return false,
+        }
+    }
+}
+
+fn has_query_param(query: Option<&str>, name: &str) -> bool {
+    query.is_some_and(|q| {
+        q.split('&')
+            .any(|pair| pair.split('=').next() == Some(name))
+    })
+}
+
+fn parse_date(date: &str) -> Option<chrono::DateTime<Utc>> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Utc.from_local_datetime(&day.and_hms_opt(0, 0, 0)?).single()
+}
+
+/// Who made a request, for usage attribution.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiClient {
+    /// Attribution key: `token:<id>`, `oauth:<client>`, `user:<id>` or
+    /// `anonymous`.
+    pub key: String,
+    pub token_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+}
+
+impl ApiClient {
+    /// A request authenticated with a personal API token.
+    pub fn api_token(token_id: Uuid, user_id: Uuid) -> Self {
+        Self {
+            key: format!("token:{token_id}"),
+            token_id: Some(token_id),
+            user_id: Some(user_id),
+        }
+    }
+
+    /// A request authenticated with an OAuth access token.
+    pub fn oauth(client_id: &str, user_id: Option<Uuid>) -> Self {
+        Self {
+            key: format!("oauth:{client_id}"),
+            token_id: None,
+            user_id,
+        }
+    }
+
+    /// A request from a session user.
+    pub fn user(user_id: Uuid) -> Self {
+        Self {
+            key: format!("user:{user_id}"),
+            token_id: None,
+            user_id: Some(user_id),
+        }
+    }
+
+    /// An unauthenticated request.
+    pub fn anonymous() -> Self {
+        Self {
+            key: "anonymous".to_string(),
+            token_id: None,
+            user_id: None,
+        }
+    }
+}
+
+/// Buffered usage for one deprecation and client.
+#[derive(Debug, Clone, Copy)]
+struct PendingUsage {
+    count: i64,
+    first_seen: i64,
+    last_seen: i64,
+}
+
+/// Usage of a deprecation by one client.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DeprecationUsage {
+    pub deprecation: String,
+    pub client: String,
+    pub token_id: Option<Uuid>,
+    /// Name of the API token, if it still exists.
+    pub token_name: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub request_count: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+}
+
+/// A deprecation with its recorded usage.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecationReport {
+    #[serde(flatten)]
+    pub deprecation: Deprecation,
+    /// Requests across all clients.
+    pub total_requests: i64,
+    /// Per-client usage, most recent first.
+    pub clients: Vec<DeprecationUsage>,
+}
+
+/// Deprecated API usage tracking service.
+pub struct DeprecationService {
+    pool: PgPool,
+    headers: bool,
+    pending: DashMap<(&'static str, ApiClient), PendingUsage>,
+}
+
+impl DeprecationService {
+    /// Create a new deprecation service.
+    ///
+    /// `headers` enables `Deprecation`/`Sunset` response headers.
+    pub fn new(pool: PgPool, headers: bool) -> Self {
+        Self {
+            pool,
+            headers,
+            pending: DashMap::new(),
+        }
+    }
+
+    /// Whether responses announce deprecations in headers.
+    pub fn headers_enabled(&self) -> bool {
+        self.headers
+    }
+
+    /// Count one use of a deprecation by a client.
+    pub fn record(&self, deprecation: &'static Deprecation, client: &ApiClient) {
+        let now = Utc::now().timestamp();
+        self.pending
+            .entry((deprecation.id, client.clone()))
+            .and_modify(|usage| {
+                usage.count += 1;
+                usage.last_seen = now;
+            })
+            .or_insert(PendingUsage {
+                count: 1,
+                first_seen: now,
+                last_seen: now,
+            });
+    }
+
+    /// Write buffered counts to the database.
+    ///
+    /// Counts that fail to save are put back for the next flush.
+    pub async fn flush(&self) -> Result<()> {
+        let keys: Vec<_> = self.pending.iter().map(|e| e.key().clone()).collect();
+        for key in keys {
+            let Some(((id, client), usage)) = self.pending.remove(&key) else {
+                continue;
+            };
+            let saved = sqlx::query(
+                r#"
+                INSERT INTO api_deprecation_usage
+                    (deprecation, client, token_id, user_id, request_count, first_seen, last_seen)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (deprecation, client) DO UPDATE SET
+                    request_count = api_deprecation_usage.request_count + EXCLUDED.request_count,
+                    last_seen = GREATEST(api_deprecation_usage.last_seen, EXCLUDED.last_seen)
+                "#,
+            )
+            .bind(id)
+            .bind(&client.key)
+            .bind(client.token_id)
+            .bind(client.user_id)
+            .bind(usage.count)
+            .bind(usage.first_seen)
+            .bind(usage.last_seen)
+            .execute(&self.pool)
+            .await;
+
+            if let Err(e) = saved {
+                self.pending
+                    .entry((id, client))
+                    .and_modify(|pending| {
+                        pending.count += usage.count;
+                        pending.first_seen = pending.first_seen.min(usage.first_seen);
+                    })
+                    .or_insert(usage);
+                return Err(e).context("failed to save deprecation usage");
+            }
+        }
+        Ok(())
+    }
+
+    /// Usage of every registered deprecation.
+    ///
+    /// Flushes buffered counts first so the report is current.
+    pub async fn report(&self) -> Result<Vec<DeprecationReport>> {
+        self.flush().await?;
+
+        let usage: Vec<DeprecationUsage> = sqlx::query_as(
+            r#"
+            SELECT u.deprecation, u.client, u.token_id, t.name AS token_name,
+                   u.user_id, u.request_count, u.first_seen, u.last_seen
+            FROM api_deprecation_usage u
+            LEFT JOIN api_tokens t ON t.id = u.token_id
+            ORDER BY u.last_seen DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load deprecation usage")?;
+
+        Ok(DEPRECATIONS
+            .iter()
+            .map(|deprecation| {
+                let clients: Vec<DeprecationUsage> = usage
+                    .iter()
+                    .filter(|u| u.deprecation == deprecation.id)
+                    .cloned()
+                    .collect();
+                DeprecationReport {
+                    deprecation: *deprecation,
+                    total_requests: clients.iter().map(|c| c.request_count).sum(),
+                    clients,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_ids_are_unique_and_dates_parse() {
+        for (i, d) in DEPRECATIONS.iter().enumerate() {
+            assert!(
+                DEPRECATIONS[i + 1..].iter().all(|other| other.id != d.id),
+                "duplicate deprecation {}",
+                d.id
+            );
+            assert!(d.deprecation_header().is_some(), "{}", d.id);
+            if d.sunset.is_some() {
+                assert!(d.sunset_header().is_some(), "{}", d.id);
+            }
+            assert_eq!(d.name.is_none(), d.kind == DeprecationKind::Endpoint);
+        }
+    }
+
+    #[test]
+    fn matches_endpoints_and_parameters() {
+        let ids = |method, path, query| {
+            matching(method, path, query)
+                .into_iter()
+                .map(|d| d.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("GET", "/api/items/article", None), ["items_by_type"]);
+        assert!(ids("POST", "/api/items/article", None).is_empty());
+        assert!(ids("GET", "/api/items", Some("type=article")).is_empty());
+        assert!(ids("GET", "/api/items/article/extra", None).is_empty());
+
+        assert_eq!(
+            ids("GET", "/api/search", Some("q=rust&limit=5")),
+            ["search_limit"]
+        );
+        assert!(ids("GET", "/api/search", Some("q=rust&per_page=5")).is_empty());
+        assert!(ids("GET", "/api/search", Some("q=limit")).is_empty());
+    }
+
+    #[test]
+    fn headers_use_http_formats() {
+        let d = Deprecation {
+            id: "test",
+            kind: DeprecationKind::Endpoint,
+            method: "GET",
+            path: "/api/test",
+            name: None,
+            deprecated_since: "2026-01-01",
+            sunset: Some("2026-07-01"),
+            replacement: "/api/v2/test",
+        };
+        assert_eq!(d.deprecation_header().unwrap(), "@1767225600");
+        assert_eq!(d.sunset_header().unwrap(), "Wed, 01 Jul 2026 00:00:00 GMT");
+    }
+}
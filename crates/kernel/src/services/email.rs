@@ -114,6 +114,28 @@ impl EmailService {
         self.send(to, &subject, &body).await
     }
 
+    /// Send an invitation to register an account.
+    pub async fn send_invitation_email(
+        &self,
+        to: &str,
+        token: &str,
+        site_name: &str,
+    ) -> Result<()> {
+        let register_url = format!("{}/user/register?invite={}", self.site_url, token);
+        let subject = format!("You are invited to join {site_name}");
+        let body = format!(
+            "You have been invited to create an account at {site_name}.\n\n\
+             To accept the invitation, visit the following link and register \
+             with this email address:\n\
+             {register_url}\n\n\
+             If you were not expecting this invitation, you can safely ignore this email.\n\n\
+             This link will expire in {} days.",
+            crate::models::user_invitation::INVITATION_VALIDITY_DAYS
+        );
+
+        self.send(to, &subject, &body).await
+    }
+
     /// Send a password reset email with a tokenized link.
     pub async fn send_password_reset(&self, to: &str, token: &str, site_name: &str) -> Result<()> {
         let reset_url = format!("{}/user/password-reset/{}", self.site_url, token);
@@ -184,6 +206,63 @@ impl EmailService {
             .map_err(|e| e.into_anyhow("Email"))
     }
 
+    /// Send a [`MailMessage`] with its cc/bcc recipients, Reply-To and
+    /// optional HTML alternative.
+    ///
+    /// [`MailMessage`]: crate::services::mail::MailMessage
+    pub async fn send_message(&self, message: &crate::services::mail::MailMessage) -> Result<()> {
+        let mut builder = Message::builder()
+            .from(self.from_email.parse().context("invalid from email")?)
+            .to(message
+                .to
+                .parse()
+                .context("invalid recipient email address")?)
+            .subject(&message.subject);
+        for cc in &message.cc {
+            builder = builder.cc(cc.parse().context("invalid cc email address")?);
+        }
+        for bcc in &message.bcc {
+            builder = builder.bcc(bcc.parse().context("invalid bcc email address")?);
+        }
+        if let Some(reply_to) = &message.reply_to {
+            builder = builder.reply_to(reply_to.parse().context("invalid reply-to address")?);
+        }
+
+        let email = if let Some(html) = &message.html_body {
+            builder
+                .multipart(
+                    lettre::message::MultiPart::alternative()
+                        .singlepart(
+                            lettre::message::SinglePart::builder()
+                                .header(ContentType::TEXT_PLAIN)
+                                .body(message.text_body.clone()),
+                        )
+                        .singlepart(
+                            lettre::message::SinglePart::builder()
+                                .header(ContentType::TEXT_HTML)
+                                .body(html.clone()),
+                        ),
+                )
+                .context("failed to build multipart email")?
+        } else {
+            builder
+                .header(ContentType::TEXT_PLAIN)
+                .body(message.text_body.clone())
+                .context("failed to build email")?
+        };
+
+        self.circuit_breaker
+            .call(|| async {
+                self.transport
+                    .send(email)
+                    .await
+                    .context("failed to send email")?;
+                Ok::<(), anyhow::Error>(())
+            })
+            .await
+            .map_err(|e| e.into_anyhow("Email"))
+    }
+
     /// Send an email verification link using the template system.
     pub async fn send_verification_email_templated(
         &self,
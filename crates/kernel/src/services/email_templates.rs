@@ -88,6 +88,24 @@ mod tests {
         assert!(html.is_some());
     }
 
+    #[test]
+    fn render_contact_message() {
+        let tera = test_tera();
+        let mut ctx = tera::Context::new();
+        ctx.insert("site_name", "Test Site");
+        ctx.insert("category", "Press");
+        ctx.insert("sender_name", "Ada");
+        ctx.insert("sender_mail", "ada@example.com");
+        ctx.insert("message", "Hello there");
+        ctx.insert("admin_url", "https://example.com/admin/content/contact");
+        ctx.insert("subject", "[Test Site] Interview request");
+
+        let (html, text) = render(&tera, "contact_message", &ctx).unwrap();
+        assert!(text.contains("Ada <ada@example.com>"));
+        assert!(text.contains("Hello there"));
+        assert!(html.unwrap().contains("Interview request"));
+    }
+
     #[test]
     fn render_missing_template_fails() {
         let tera = test_tera();
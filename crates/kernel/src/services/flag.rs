@@ -0,0 +1,518 @@
+//! Item flags.
+//!
+//! A flag marks items, either globally (`featured`: one shared mark per
+//! item) or per user (`bookmark`, `read_later`: each user keeps their own).
+//! The kernel flags `promote` and `sticky` are the item's own columns and
+//! change through [`ItemService::update`]. Plugins declare more flags under
+//! `[flags]` in their `.info.toml`, and administrators add them through
+//! `/api/flags`; their marks live in the `flagging` table.
+//!
+//! Gather queries sort by a flag's count with the sort field
+//! `flag_count.{name}`. Every change is reported to `tap_flag`. The service
+//! and its routes are available while the `trovato_flags` plugin is enabled.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::content::ItemService;
+use crate::models::{Item, UpdateItem};
+use crate::plugin::PluginInfo;
+use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
+
+/// Kernel flags, stored in the item columns of the same name.
+pub const KERNEL_FLAGS: [(&str, &str); 2] = [
+    ("promote", "Promoted to front page"),
+    ("sticky", "Sticky at top of lists"),
+];
+
+/// Provider of the kernel flags.
+pub const KERNEL_PROVIDER: &str = "kernel";
+
+/// Provider of flags added through `/api/flags`.
+pub const ADMIN_PROVIDER: &str = "admin";
+
+/// Longest flag name.
+pub const MAX_FLAG_NAME_LEN: usize = 64;
+
+/// Whether a flag is shared or kept per user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagScope {
+    /// Each user flags items for themselves.
+    #[default]
+    User,
+    /// One flag per item, shared by everyone.
+    Global,
+}
+
+impl FlagScope {
+    /// Name used in the database and in `tap_flag` input.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Global => "global",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "global" => Self::Global,
+            _ => Self::User,
+        }
+    }
+}
+
+/// A flag items can be marked with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FlagDefinition {
+    /// Machine name.
+    pub name: String,
+    /// Human-readable label.
+    pub label: String,
+    pub scope: FlagScope,
+    /// `kernel`, `admin`, or the name of the declaring plugin.
+    pub provider: String,
+}
+
+impl FlagDefinition {
+    /// Whether the flag is an item column rather than `flagging` rows.
+    pub fn is_column(&self) -> bool {
+        self.provider == KERNEL_PROVIDER
+    }
+}
+
+/// A flag's state on one item, for one user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FlagState {
+    pub name: String,
+    pub label: String,
+    pub scope: FlagScope,
+    /// 0 or 1 for global flags; the number of users for per-user flags.
+    pub count: i64,
+    /// Whether the item is flagged (per-user flags: by this user).
+    pub flagged: bool,
+}
+
+impl FlagState {
+    fn new(flag: &FlagDefinition, count: i64, flagged: bool) -> Self {
+        Self {
+            name: flag.name.clone(),
+            label: flag.label.clone(),
+            scope: flag.scope,
+            count,
+            flagged,
+        }
+    }
+}
+
+/// Input for `tap_flag`, sent after a flag is set or cleared.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. The kernel serializes this; plugins deserialize
+/// it. If you change fields here, update the SDK copy to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagInput {
+    pub flag: String,
+    pub scope: String,
+    pub item_id: Uuid,
+    pub item_type: String,
+    pub user_id: Uuid,
+    pub flagged: bool,
+    pub count: i64,
+}
+
+/// Database row for an administrator-defined flag.
+#[derive(sqlx::FromRow)]
+struct FlagRow {
+    name: String,
+    label: String,
+    scope: String,
+}
+
+impl From<FlagRow> for FlagDefinition {
+    fn from(row: FlagRow) -> Self {
+        Self {
+            name: row.name,
+            label: row.label,
+            scope: FlagScope::parse(&row.scope),
+            provider: ADMIN_PROVIDER.to_string(),
+        }
+    }
+}
+
+/// Whether `name` is a valid flag name: lowercase letters, digits and `_`,
+/// starting with a letter.
+pub fn is_valid_flag_name(name: &str) -> bool {
+    name.len() <= MAX_FLAG_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Whether `name` is one of the [`KERNEL_FLAGS`].
+pub fn is_kernel_flag(name: &str) -> bool {
+    KERNEL_FLAGS.iter().any(|(flag, _)| *flag == name)
+}
+
+/// Combine kernel, plugin and administrator flags, sorted by name.
+///
+/// Kernel flags win over plugin flags of the same name, and plugin flags
+/// over administrator ones; among plugins the first by plugin name wins.
+pub fn merge_definitions<'a>(
+    plugins: impl IntoIterator<Item = (&'a str, &'a PluginInfo)>,
+    admin: Vec<FlagDefinition>,
+) -> Vec<FlagDefinition> {
+    let mut flags: BTreeMap<String, FlagDefinition> = BTreeMap::new();
+    for flag in admin {
+        flags.insert(flag.name.clone(), flag);
+    }
+
+    let mut plugins: Vec<_> = plugins.into_iter().collect();
+    plugins.sort_by(|a, b| b.0.cmp(a.0));
+    for (plugin, info) in plugins {
+        for (name, config) in &info.flags {
+            flags.insert(
+                name.clone(),
+                FlagDefinition {
+                    name: name.clone(),
+                    label: config.label.clone(),
+                    scope: config.scope,
+                    provider: plugin.to_string(),
+                },
+            );
+        }
+    }
+
+    for (name, label) in KERNEL_FLAGS {
+        flags.insert(
+            name.to_string(),
+            FlagDefinition {
+                name: name.to_string(),
+                label: label.to_string(),
+                scope: FlagScope::Global,
+                provider: KERNEL_PROVIDER.to_string(),
+            },
+        );
+    }
+
+    flags.into_values().collect()
+}
+
+/// Item flag service.
+pub struct FlagService {
+    pool: PgPool,
+    items: Arc<ItemService>,
+    dispatcher: Arc<TapDispatcher>,
+    tap_services: RequestServices,
+}
+
+impl FlagService {
+    /// Create a new flag service.
+    pub fn new(
+        pool: PgPool,
+        items: Arc<ItemService>,
+        dispatcher: Arc<TapDispatcher>,
+        tap_services: RequestServices,
+    ) -> Self {
+        Self {
+            pool,
+            items,
+            dispatcher,
+            tap_services,
+        }
+    }
+
+    /// Flags added by administrators, sorted by name.
+    pub async fn admin_flags(&self) -> Result<Vec<FlagDefinition>> {
+        let rows =
+            sqlx::query_as::<_, FlagRow>("SELECT name, label, scope FROM flag ORDER BY name")
+                .fetch_all(&self.pool)
+                .await
+                .context("failed to list flags")?;
+        Ok(rows.into_iter().map(FlagDefinition::from).collect())
+    }
+
+    /// All flags: kernel, those of the given plugins, and admin-defined.
+    pub async fn definitions<'a>(
+        &self,
+        plugins: impl IntoIterator<Item = (&'a str, &'a PluginInfo)>,
+    ) -> Result<Vec<FlagDefinition>> {
+        Ok(merge_definitions(plugins, self.admin_flags().await?))
+    }
+
+    /// Add an administrator-defined flag.
+    pub async fn create(
+        &self,
+        name: &str,
+        label: &str,
+        scope: FlagScope,
+    ) -> Result<FlagDefinition> {
+        let row = sqlx::query_as::<_, FlagRow>(
+            "INSERT INTO flag (name, label, scope, created) VALUES ($1, $2, $3, $4) \
+             RETURNING name, label, scope",
+        )
+        .bind(name)
+        .bind(label)
+        .bind(scope.as_str())
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to create flag")?;
+
+        info!(flag = %name, scope = scope.as_str(), "flag created");
+        Ok(row.into())
+    }
+
+    /// Remove an administrator-defined flag and all its marks.
+    ///
+    /// Returns `false` if no such flag exists.
+    pub async fn delete(&self, name: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await.context("failed to begin")?;
+        sqlx::query("DELETE FROM flagging WHERE flag_name = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("failed to delete flaggings")?;
+        let result = sqlx::query("DELETE FROM flag WHERE name = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .context("failed to delete flag")?;
+        tx.commit().await.context("failed to commit")?;
+
+        if result.rows_affected() > 0 {
+            info!(flag = %name, "flag deleted");
+        }
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// State of each flag on an item for a user.
+    pub async fn states(
+        &self,
+        flags: &[FlagDefinition],
+        item: &Item,
+        user_id: Uuid,
+    ) -> Result<Vec<FlagState>> {
+        let rows = sqlx::query_as::<_, (String, i64, bool)>(
+            "SELECT flag_name, COUNT(*), BOOL_OR(user_id = $2) FROM flagging \
+             WHERE item_id = $1 GROUP BY flag_name",
+        )
+        .bind(item.id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load item flags")?;
+
+        Ok(flags
+            .iter()
+            .map(|flag| {
+                if flag.is_column() {
+                    let on = column_value(item, &flag.name) == 1;
+                    return FlagState::new(flag, i64::from(on), on);
+                }
+                let (count, mine) = rows
+                    .iter()
+                    .find(|(name, _, _)| *name == flag.name)
+                    .map(|(_, count, mine)| (*count, *mine))
+                    .unwrap_or((0, false));
+                let flagged = match flag.scope {
+                    FlagScope::Global => count > 0,
+                    FlagScope::User => mine,
+                };
+                FlagState::new(flag, count, flagged)
+            })
+            .collect())
+    }
+
+    /// Set or clear a flag on an item.
+    ///
+    /// Global flags need edit access to the item; per-user flags need a
+    /// logged-in user who may view it. `tap_flag` is told about changes;
+    /// setting a flag that is already set changes nothing.
+    pub async fn set(
+        &self,
+        flag: &FlagDefinition,
+        item: &Item,
+        user: &UserContext,
+        flagged: bool,
+    ) -> Result<FlagState> {
+        let operation = match flag.scope {
+            FlagScope::Global => "edit",
+            FlagScope::User => "view",
+        };
+        if !user.authenticated || !self.items.check_access(item, operation, user).await? {
+            bail!("access denied");
+        }
+
+        let (changed, count) = if flag.is_column() {
+            let value = i16::from(flagged);
+            let changed = column_value(item, &flag.name) != value;
+            if changed {
+                let (promote, sticky) = match flag.name.as_str() {
+                    "promote" => (Some(value), None),
+                    _ => (None, Some(value)),
+                };
+                let update = UpdateItem {
+                    title: None,
+                    status: None,
+                    promote,
+                    sticky,
+                    fields: None,
+                    log: None,
+                    status_meta: None,
+                };
+                self.items.update(item.id, update, user).await?;
+            }
+            (changed, i64::from(flagged))
+        } else {
+            let user_id = match flag.scope {
+                FlagScope::Global => Uuid::nil(),
+                FlagScope::User => user.id,
+            };
+            let result = if flagged {
+                sqlx::query(
+                    "INSERT INTO flagging (flag_name, item_id, user_id, created) \
+                     VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+                )
+                .bind(&flag.name)
+                .bind(item.id)
+                .bind(user_id)
+                .bind(chrono::Utc::now().timestamp())
+                .execute(&self.pool)
+                .await
+                .context("failed to flag item")?
+            } else {
+                sqlx::query(
+                    "DELETE FROM flagging WHERE flag_name = $1 AND item_id = $2 AND user_id = $3",
+                )
+                .bind(&flag.name)
+                .bind(item.id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await
+                .context("failed to unflag item")?
+            };
+            let count = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM flagging WHERE flag_name = $1 AND item_id = $2",
+            )
+            .bind(&flag.name)
+            .bind(item.id)
+            .fetch_one(&self.pool)
+            .await
+            .context("failed to count flaggings")?;
+            (result.rows_affected() > 0, count)
+        };
+
+        if changed {
+            let input = FlagInput {
+                flag: flag.name.clone(),
+                scope: flag.scope.as_str().to_string(),
+                item_id: item.id,
+                item_type: item.item_type.clone(),
+                user_id: user.id,
+                flagged,
+                count,
+            };
+            let json = serde_json::to_string(&input).context("serialize flag input")?;
+            let state = RequestState::new(user.clone(), self.tap_services.clone());
+            let _ = self.dispatcher.dispatch("tap_flag", &json, state).await;
+            info!(flag = %flag.name, item_id = %item.id, flagged, "item flag changed");
+        }
+
+        Ok(FlagState::new(flag, count, flagged))
+    }
+}
+
+/// Value of a kernel flag column.
+fn column_value(item: &Item, flag: &str) -> i16 {
+    match flag {
+        "promote" => item.promote,
+        _ => item.sticky,
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn plugin(toml: &str) -> PluginInfo {
+        PluginInfo::parse_str(toml, Path::new("test.toml")).unwrap()
+    }
+
+    #[test]
+    fn flag_names_are_validated() {
+        assert!(is_valid_flag_name("read_later"));
+        assert!(is_valid_flag_name("top10"));
+        assert!(!is_valid_flag_name(""));
+        assert!(!is_valid_flag_name("_hidden"));
+        assert!(!is_valid_flag_name("Read-Later"));
+        assert!(!is_valid_flag_name(&"a".repeat(MAX_FLAG_NAME_LEN + 1)));
+    }
+
+    #[test]
+    fn kernel_then_plugin_then_admin_flags_win() {
+        let reading = plugin(
+            "name = \"reading\"\ndescription = \"\"\nversion = \"1.0.0\"\n\
+             [flags.bookmark]\nlabel = \"Bookmark\"\n\
+             [flags.promote]\nlabel = \"Shadowed\"\n",
+        );
+        let social = plugin(
+            "name = \"social\"\ndescription = \"\"\nversion = \"1.0.0\"\n\
+             [flags.bookmark]\nlabel = \"Save\"\nscope = \"global\"\n",
+        );
+        let admin = vec![
+            FlagDefinition {
+                name: "bookmark".to_string(),
+                label: "Admin bookmark".to_string(),
+                scope: FlagScope::User,
+                provider: ADMIN_PROVIDER.to_string(),
+            },
+            FlagDefinition {
+                name: "featured".to_string(),
+                label: "Featured".to_string(),
+                scope: FlagScope::Global,
+                provider: ADMIN_PROVIDER.to_string(),
+            },
+        ];
+
+        let flags = merge_definitions([("social", &social), ("reading", &reading)], admin);
+        let names: Vec<&str> = flags.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["bookmark", "featured", "promote", "sticky"]);
+
+        assert_eq!(flags[0].provider, "reading");
+        assert_eq!(flags[0].label, "Bookmark");
+        assert_eq!(flags[1].provider, ADMIN_PROVIDER);
+        assert!(flags[2].is_column());
+        assert_eq!(flags[2].label, "Promoted to front page");
+        assert_eq!(flags[2].scope, FlagScope::Global);
+    }
+
+    #[test]
+    fn flag_input_matches_sdk() {
+        let input = FlagInput {
+            flag: "bookmark".to_string(),
+            scope: FlagScope::User.as_str().to_string(),
+            item_id: Uuid::nil(),
+            item_type: "blog".to_string(),
+            user_id: Uuid::nil(),
+            flagged: true,
+            count: 3,
+        };
+        let json = serde_json::to_string(&input).unwrap();
+        let sdk: trovato_sdk::types::FlagInput = serde_json::from_str(&json).unwrap();
+        assert_eq!(sdk.flag, "bookmark");
+        assert_eq!(sdk.scope, "user");
+        assert!(sdk.flagged);
+        assert_eq!(sdk.count, 3);
+    }
+}
@@ -0,0 +1,346 @@
+//! Goose load test comparisons.
+//!
+//! A `goose_comparison` item lists the test runs it compares in
+//! `field_run_ids`. [`compare`] lines up the per-endpoint results
+//! (`goose_endpoint_result` items) of a baseline and a candidate run and
+//! reports how average, p95 and p99 latency and throughput changed. A change
+//! worse than the regression threshold (a percentage) flags the endpoint.
+//!
+//! The threshold comes from the request, else the `goose_regression_threshold`
+//! site variable, else [`DEFAULT_REGRESSION_THRESHOLD`].
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::SiteConfig;
+
+/// Site variable holding the default regression threshold (percent).
+pub const THRESHOLD_KEY: &str = "goose_regression_threshold";
+
+/// Regression threshold used when none is configured (percent).
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 10.0;
+
+/// Field on `goose_comparison` items holding the stored report (JSON).
+pub const REPORT_FIELD: &str = "field_report";
+
+/// Field on `goose_comparison` items listing the compared runs.
+pub const RUN_IDS_FIELD: &str = "field_run_ids";
+
+/// Metrics recorded for one endpoint in one run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndpointMetrics {
+    pub method: String,
+    pub url_pattern: String,
+    pub request_count: Option<f64>,
+    pub error_count: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub p95: Option<f64>,
+    pub p99: Option<f64>,
+    pub rps: Option<f64>,
+}
+
+impl EndpointMetrics {
+    /// Read metrics from the fields of a `goose_endpoint_result` item.
+    ///
+    /// Numbers may be stored as JSON numbers or numeric strings; anything
+    /// else counts as missing.
+    pub fn from_fields(fields: &serde_json::Value) -> Self {
+        let text = |name: &str| {
+            fields
+                .get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let number = |name: &str| {
+            fields.get(name).and_then(|v| match v {
+                serde_json::Value::Number(n) => n.as_f64(),
+                serde_json::Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            })
+        };
+        Self {
+            method: text("field_method").to_uppercase(),
+            url_pattern: text("field_url_pattern"),
+            request_count: number("field_request_count"),
+            error_count: number("field_error_count"),
+            avg_ms: number("field_avg_ms"),
+            p95: number("field_p95"),
+            p99: number("field_p99"),
+            rps: number("field_rps"),
+        }
+    }
+}
+
+/// How one metric changed between the baseline and the candidate run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub candidate: f64,
+    /// Change relative to the baseline, in percent (`None` for a zero baseline).
+    pub change_pct: Option<f64>,
+    /// Whether the change is worse than the threshold.
+    pub regression: bool,
+}
+
+impl MetricDelta {
+    /// Compare a metric where lower is better (latency) or higher is
+    /// better (throughput).
+    fn new(baseline: f64, candidate: f64, lower_is_better: bool, threshold: f64) -> Self {
+        let change_pct = (baseline != 0.0).then(|| (candidate - baseline) / baseline * 100.0);
+        let regression = change_pct.is_some_and(|pct| {
+            if lower_is_better {
+                pct > threshold
+            } else {
+                -pct > threshold
+            }
+        });
+        Self {
+            baseline,
+            candidate,
+            change_pct,
+            regression,
+        }
+    }
+}
+
+/// Whether an endpoint appears in both runs or only one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointPresence {
+    Both,
+    /// Only in the candidate run.
+    Added,
+    /// Only in the baseline run.
+    Removed,
+}
+
+/// Comparison of one endpoint (method + URL pattern).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndpointComparison {
+    pub method: String,
+    pub url_pattern: String,
+    pub presence: EndpointPresence,
+    pub avg_ms: Option<MetricDelta>,
+    pub p95: Option<MetricDelta>,
+    pub p99: Option<MetricDelta>,
+    pub rps: Option<MetricDelta>,
+    /// Whether any metric regressed beyond the threshold.
+    pub regressed: bool,
+}
+
+/// Result of comparing two test runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub baseline_run: Uuid,
+    pub candidate_run: Uuid,
+    /// Regression threshold applied, in percent.
+    pub threshold_pct: f64,
+    /// Unix timestamp of the computation.
+    pub computed_at: i64,
+    pub endpoints: Vec<EndpointComparison>,
+    /// Number of regressed endpoints.
+    pub regressions: usize,
+}
+
+/// An endpoint's metrics in the baseline and candidate runs.
+type EndpointPair<'a> = (Option<&'a EndpointMetrics>, Option<&'a EndpointMetrics>);
+
+/// Compare the endpoint results of two runs.
+///
+/// Endpoints are matched by method and URL pattern and listed in that
+/// order. Endpoints missing from one run are reported without deltas.
+pub fn compare(
+    baseline_run: Uuid,
+    baseline: &[EndpointMetrics],
+    candidate_run: Uuid,
+    candidate: &[EndpointMetrics],
+    threshold_pct: f64,
+) -> ComparisonReport {
+    let key = |m: &EndpointMetrics| (m.url_pattern.clone(), m.method.clone());
+    let mut pairs: BTreeMap<(String, String), EndpointPair<'_>> = BTreeMap::new();
+    for m in baseline {
+        pairs.entry(key(m)).or_default().0 = Some(m);
+    }
+    for m in candidate {
+        pairs.entry(key(m)).or_default().1 = Some(m);
+    }
+
+    let endpoints: Vec<EndpointComparison> = pairs
+        .into_iter()
+        .map(|((url_pattern, method), (before, after))| {
+            let delta = |get: fn(&EndpointMetrics) -> Option<f64>, lower_is_better: bool| {
+                let (b, a) = (get(before?)?, get(after?)?);
+                Some(MetricDelta::new(b, a, lower_is_better, threshold_pct))
+            };
+            let avg_ms = delta(|m| m.avg_ms, true);
+            let p95 = delta(|m| m.p95, true);
+            let p99 = delta(|m| m.p99, true);
+            let rps = delta(|m| m.rps, false);
+            let regressed = [&avg_ms, &p95, &p99, &rps]
+                .into_iter()
+                .flatten()
+                .any(|d| d.regression);
+            let presence = match (before, after) {
+                (Some(_), None) => EndpointPresence::Removed,
+                (None, Some(_)) => EndpointPresence::Added,
+                _ => EndpointPresence::Both,
+            };
+            EndpointComparison {
+                method,
+                url_pattern,
+                presence,
+                avg_ms,
+                p95,
+                p99,
+                rps,
+                regressed,
+            }
+        })
+        .collect();
+
+    ComparisonReport {
+        baseline_run,
+        candidate_run,
+        threshold_pct,
+        computed_at: chrono::Utc::now().timestamp(),
+        regressions: endpoints.iter().filter(|e| e.regressed).count(),
+        endpoints,
+    }
+}
+
+/// Parse run IDs as stored in `field_run_ids` or passed in `?runs=`.
+///
+/// Accepts IDs separated by commas or whitespace, optionally written as a
+/// JSON array.
+pub fn parse_run_ids(raw: &str) -> Result<Vec<Uuid>> {
+    raw.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|part| part.trim_matches(|c| matches!(c, '[' | ']' | '"')))
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<Uuid>()
+                .with_context(|| format!("invalid run ID '{part}'"))
+        })
+        .collect()
+}
+
+/// Load the endpoint results recorded for a test run.
+pub async fn endpoint_results(pool: &PgPool, run_id: Uuid) -> Result<Vec<EndpointMetrics>> {
+    let rows: Vec<serde_json::Value> = sqlx::query_scalar(
+        r#"
+        SELECT fields FROM item
+        WHERE type = 'goose_endpoint_result'
+          AND fields ->> 'field_test_run_id' = $1
+        ORDER BY created
+        "#,
+    )
+    .bind(run_id.to_string())
+    .fetch_all(pool)
+    .await
+    .context("failed to load endpoint results")?;
+
+    Ok(rows.iter().map(EndpointMetrics::from_fields).collect())
+}
+
+/// The configured regression threshold, in percent.
+pub async fn configured_threshold(pool: &PgPool) -> Result<f64> {
+    let value = SiteConfig::get(pool, THRESHOLD_KEY).await?;
+    Ok(value
+        .and_then(|v| match v {
+            serde_json::Value::Number(n) => n.as_f64(),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        })
+        .filter(|t: &f64| t.is_finite() && *t >= 0.0)
+        .unwrap_or(DEFAULT_REGRESSION_THRESHOLD))
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn endpoint(url: &str, avg_ms: f64, p95: f64, rps: f64) -> EndpointMetrics {
+        EndpointMetrics {
+            method: "GET".to_string(),
+            url_pattern: url.to_string(),
+            avg_ms: Some(avg_ms),
+            p95: Some(p95),
+            rps: Some(rps),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_changes_beyond_threshold() {
+        let (a, b) = (Uuid::now_v7(), Uuid::now_v7());
+        let baseline = vec![
+            endpoint("/", 100.0, 200.0, 50.0),
+            endpoint("/search", 80.0, 120.0, 20.0),
+            endpoint("/old", 10.0, 10.0, 10.0),
+        ];
+        let candidate = vec![
+            endpoint("/", 105.0, 230.0, 49.0),
+            endpoint("/search", 60.0, 100.0, 15.0),
+            endpoint("/new", 10.0, 10.0, 10.0),
+        ];
+
+        let report = compare(a, &baseline, b, &candidate, 10.0);
+        let by_url = |url: &str| {
+            report
+                .endpoints
+                .iter()
+                .find(|e| e.url_pattern == url)
+                .unwrap()
+        };
+
+        let front = by_url("/");
+        assert_eq!(front.avg_ms.as_ref().unwrap().change_pct, Some(5.0));
+        assert!(!front.avg_ms.as_ref().unwrap().regression);
+        assert!(front.p95.as_ref().unwrap().regression);
+        assert!(front.p99.is_none());
+        assert!(front.regressed);
+
+        // Faster but serving fewer requests per second.
+        let search = by_url("/search");
+        assert!(!search.avg_ms.as_ref().unwrap().regression);
+        assert!(search.rps.as_ref().unwrap().regression);
+
+        assert_eq!(by_url("/old").presence, EndpointPresence::Removed);
+        assert_eq!(by_url("/new").presence, EndpointPresence::Added);
+        assert!(by_url("/new").avg_ms.is_none());
+        assert_eq!(report.regressions, 2);
+    }
+
+    #[test]
+    fn metrics_from_fields_accept_numeric_strings() {
+        let metrics = EndpointMetrics::from_fields(&serde_json::json!({
+            "field_method": "get",
+            "field_url_pattern": "/item/{id}",
+            "field_avg_ms": "12.5",
+            "field_rps": 40,
+            "field_p95": "n/a",
+        }));
+        assert_eq!(metrics.method, "GET");
+        assert_eq!(metrics.avg_ms, Some(12.5));
+        assert_eq!(metrics.rps, Some(40.0));
+        assert_eq!(metrics.p95, None);
+    }
+
+    #[test]
+    fn run_ids_parse_lists_and_json() {
+        let (a, b) = (Uuid::now_v7(), Uuid::now_v7());
+        assert_eq!(parse_run_ids(&format!("{a}, {b}")).unwrap(), vec![a, b]);
+        assert_eq!(
+            parse_run_ids(&format!("[\"{a}\",\"{b}\"]")).unwrap(),
+            vec![a, b]
+        );
+        assert_eq!(parse_run_ids(&format!("{a}\n{b}\n")).unwrap(), vec![a, b]);
+        assert!(parse_run_ids("not-a-uuid").is_err());
+    }
+}
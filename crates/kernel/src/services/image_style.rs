@@ -2,7 +2,11 @@
 //!
 //! Loads style configuration from DB, applies effect chains
 //! (scale, crop, resize, desaturate), writes derivatives to disk cache.
+//! Responsive image styles map breakpoints to image styles and build the
+//! `<picture>` markup the theme renders; WebP and AVIF variants of a
+//! derivative are generated on request.
 
+use std::collections::HashMap;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -49,6 +53,202 @@ pub struct ImageEffect {
     pub output_format: Option<String>,
 }
 
+impl ImageStyle {
+    /// Parse the style's effect chain.
+    pub fn effect_chain(&self) -> Result<Vec<ImageEffect>> {
+        serde_json::from_value(self.effects.clone()).context("failed to parse image effects")
+    }
+
+    /// Width of the derivatives this style produces.
+    ///
+    /// Taken from the last scale, crop or resize effect; `None` when no
+    /// effect fixes the width.
+    pub fn output_width(&self) -> Option<u32> {
+        self.effect_chain()
+            .ok()?
+            .iter()
+            .rev()
+            .find(|e| matches!(e.effect_type.as_str(), "scale" | "crop" | "resize"))?
+            .width
+            .map(clamp_dim)
+    }
+
+    /// Whether an effect sets the output format explicitly.
+    ///
+    /// Such styles are served in that format and never negotiated.
+    pub fn has_output_format(&self) -> bool {
+        self.effect_chain()
+            .is_ok_and(|effects| effects.iter().any(|e| e.output_format.is_some()))
+    }
+
+    /// MIME type of the derivatives this style produces.
+    pub fn content_type(&self) -> &'static str {
+        self.effect_chain()
+            .map_or(image::ImageFormat::Jpeg, |effects| {
+                resolve_output_format(&effects)
+            })
+            .to_mime_type()
+    }
+}
+
+/// Responsive image style: breakpoints mapped to image styles.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+pub struct ResponsiveImageStyle {
+    pub id: Uuid,
+    pub name: String,
+    pub label: String,
+    /// Image style of the `<img>` fallback.
+    pub fallback_style: String,
+    /// Breakpoints (array of [`ResponsiveBreakpoint`]), in source order.
+    pub breakpoints: serde_json::Value,
+    pub created: i64,
+    pub changed: i64,
+}
+
+/// One breakpoint of a responsive image style.
+///
+/// Browsers use the first breakpoint whose media query matches, so list
+/// the widest viewports first.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ResponsiveBreakpoint {
+    /// Media query, e.g. `(min-width: 960px)`. `None` matches any viewport.
+    #[serde(default)]
+    pub media: Option<String>,
+    /// Image styles offered at this breakpoint. Several styles with known
+    /// widths become a width-described `srcset`.
+    pub styles: Vec<String>,
+    /// `sizes` attribute for a width-described `srcset`.
+    #[serde(default)]
+    pub sizes: Option<String>,
+}
+
+/// A `<picture>` element built from a responsive image style.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ResponsivePicture {
+    /// Responsive image style name.
+    pub style: String,
+    /// Fallback `<img>` URL.
+    pub src: String,
+    pub sources: Vec<PictureSource>,
+    pub alt: String,
+    /// Width of the fallback image, if its style fixes one.
+    pub width: Option<u32>,
+}
+
+/// A `<source>` element of a responsive picture.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PictureSource {
+    pub media: Option<String>,
+    pub srcset: String,
+    pub sizes: Option<String>,
+}
+
+/// Alternative encodings served for derivatives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantFormat {
+    Avif,
+    Webp,
+}
+
+impl VariantFormat {
+    /// Pick the variant to serve for an `Accept` header.
+    ///
+    /// AVIF is preferred over WebP. Only explicitly listed types count;
+    /// `image/*` and `*/*` keep the style's own format.
+    pub fn negotiate(accept: &str) -> Option<Self> {
+        let accepted = |wanted: &str| {
+            accept.split(',').any(|range| {
+                let mut parts = range.split(';');
+                let media_type = parts.next().unwrap_or_default().trim();
+                let quality = parts
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                media_type.eq_ignore_ascii_case(wanted) && quality > 0.0
+            })
+        };
+        if accepted("image/avif") {
+            Some(Self::Avif)
+        } else if accepted("image/webp") {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+
+    /// The variant for a file extension.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "avif" => Some(Self::Avif),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    /// File extension appended to variant derivatives.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// MIME type of the variant.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    fn image_format(self) -> image::ImageFormat {
+        match self {
+            Self::Avif => image::ImageFormat::Avif,
+            Self::Webp => image::ImageFormat::WebP,
+        }
+    }
+}
+
+/// Split an explicit variant suffix off a derivative path.
+///
+/// `photo.jpg.webp` asks for the WebP variant of `photo.jpg`. A path
+/// whose only extension is `.webp` or `.avif` names the original itself.
+pub fn split_variant_suffix(path: &str) -> Option<(&str, VariantFormat)> {
+    let (original, extension) = path.rsplit_once('.')?;
+    let format = VariantFormat::from_extension(extension)?;
+    let file_name = original.rsplit('/').next().unwrap_or(original);
+    file_name.contains('.').then_some((original, format))
+}
+
+/// Synthesize a width-based image style from a `w{N}` style name.
+///
+/// Returns `None` if the name doesn't match the `w{N}` pattern or the width
+/// is out of range (1..=4096).
+pub fn synthesize_width_style(style_name: &str) -> Option<ImageStyle> {
+    let width_str = style_name.strip_prefix('w')?;
+    let width: u32 = width_str.parse().ok()?;
+    if width == 0 || width > MAX_DIMENSION {
+        return None;
+    }
+
+    Some(ImageStyle {
+        id: Uuid::nil(),
+        name: style_name.to_string(),
+        label: format!("Width {width}"),
+        effects: serde_json::json!([{"type": "scale", "width": width}]),
+        created: 0,
+        changed: 0,
+    })
+}
+
+/// URL of a derivative of an original file path.
+pub fn derivative_url(style_name: &str, original_path: &str) -> String {
+    format!(
+        "/files/styles/{style_name}/{}",
+        original_path.trim_start_matches('/')
+    )
+}
+
 /// Image style service.
 #[derive(Clone)]
 pub struct ImageStyleService {
@@ -100,6 +300,91 @@ impl ImageStyleService {
         }
     }
 
+    /// Load a style by name, falling back to a `w{N}` width style.
+    pub async fn resolve_style(&self, name: &str) -> Result<Option<ImageStyle>> {
+        match self.load_style(name).await? {
+            Some(style) => Ok(Some(style)),
+            None => Ok(synthesize_width_style(name)),
+        }
+    }
+
+    /// Load a responsive image style by name.
+    pub async fn load_responsive_style(&self, name: &str) -> Result<Option<ResponsiveImageStyle>> {
+        let style = sqlx::query_as::<_, ResponsiveImageStyle>(
+            r#"
+            SELECT id, name, label, fallback_style, breakpoints, created, changed
+            FROM responsive_image_style
+            WHERE name = $1
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await;
+
+        match style {
+            Ok(s) => Ok(s),
+            Err(e) => {
+                debug!(error = %e, "responsive_image_style table may not exist yet");
+                Ok(None)
+            }
+        }
+    }
+
+    /// List all responsive image styles.
+    pub async fn list_responsive_styles(&self) -> Result<Vec<ResponsiveImageStyle>> {
+        let styles = sqlx::query_as::<_, ResponsiveImageStyle>(
+            "SELECT id, name, label, fallback_style, breakpoints, created, changed FROM responsive_image_style ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await;
+
+        match styles {
+            Ok(s) => Ok(s),
+            Err(e) => {
+                debug!(error = %e, "responsive_image_style table may not exist yet");
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Build the `<picture>` of an original file for a responsive style.
+    ///
+    /// `original_path` is the file's path within storage, without the
+    /// storage scheme.
+    pub async fn responsive_picture(
+        &self,
+        style: &ResponsiveImageStyle,
+        original_path: &str,
+        alt: &str,
+    ) -> Result<ResponsivePicture> {
+        let breakpoints: Vec<ResponsiveBreakpoint> =
+            serde_json::from_value(style.breakpoints.clone())
+                .context("failed to parse responsive breakpoints")?;
+
+        let mut widths = HashMap::new();
+        let style_names = breakpoints
+            .iter()
+            .flat_map(|b| b.styles.iter())
+            .chain(std::iter::once(&style.fallback_style));
+        for name in style_names {
+            if !widths.contains_key(name) {
+                let width = self
+                    .resolve_style(name)
+                    .await?
+                    .and_then(|s| s.output_width());
+                widths.insert(name.clone(), width);
+            }
+        }
+
+        Ok(build_picture(
+            style,
+            &breakpoints,
+            original_path,
+            alt,
+            &widths,
+        ))
+    }
+
     /// Get the cache path for a derivative.
     ///
     /// Both `style_name` and `original_path` are validated to prevent
@@ -134,7 +419,15 @@ impl ImageStyleService {
     }
 
     /// Generate a derivative image by applying the style's effect chain.
-    pub fn process_image(&self, original_bytes: &[u8], style: &ImageStyle) -> Result<Vec<u8>> {
+    ///
+    /// A `variant` encodes the result in that format instead of the
+    /// style's own.
+    pub fn process_image(
+        &self,
+        original_bytes: &[u8],
+        style: &ImageStyle,
+        variant: Option<VariantFormat>,
+    ) -> Result<Vec<u8>> {
         // Guard against very large input files that could exhaust memory
         if original_bytes.len() > MAX_INPUT_SIZE {
             anyhow::bail!(
@@ -144,8 +437,7 @@ impl ImageStyleService {
             );
         }
 
-        let effects: Vec<ImageEffect> = serde_json::from_value(style.effects.clone())
-            .context("failed to parse image effects")?;
+        let effects = style.effect_chain()?;
 
         let mut img = image::load_from_memory(original_bytes).context("failed to load image")?;
 
@@ -153,9 +445,13 @@ impl ImageStyleService {
             img = apply_effect(img, effect);
         }
 
-        // Determine output format from the last effect that specifies one,
-        // or fall back to JPEG (reasonable default for derivatives).
-        let image_format = resolve_output_format(&effects);
+        // Determine output format from the requested variant, else the last
+        // effect that specifies one, or fall back to JPEG (reasonable
+        // default for derivatives).
+        let image_format = variant.map_or_else(
+            || resolve_output_format(&effects),
+            VariantFormat::image_format,
+        );
 
         let mut buf = Cursor::new(Vec::new());
         img.write_to(&mut buf, image_format)
@@ -266,6 +562,61 @@ impl ImageStyleService {
     }
 }
 
+/// Assemble a responsive picture from resolved style widths.
+///
+/// `widths` maps each image style name to its output width, if known.
+fn build_picture(
+    style: &ResponsiveImageStyle,
+    breakpoints: &[ResponsiveBreakpoint],
+    original_path: &str,
+    alt: &str,
+    widths: &HashMap<String, Option<u32>>,
+) -> ResponsivePicture {
+    let sources = breakpoints
+        .iter()
+        .filter_map(|breakpoint| {
+            Some(PictureSource {
+                media: breakpoint.media.clone(),
+                srcset: build_srcset(&breakpoint.styles, original_path, widths)?,
+                sizes: breakpoint.sizes.clone(),
+            })
+        })
+        .collect();
+
+    ResponsivePicture {
+        style: style.name.clone(),
+        src: derivative_url(&style.fallback_style, original_path),
+        sources,
+        alt: alt.to_string(),
+        width: widths.get(&style.fallback_style).copied().flatten(),
+    }
+}
+
+/// The `srcset` of a breakpoint.
+///
+/// Several styles are described by width; if any width is unknown, only
+/// the first style is offered.
+fn build_srcset(
+    styles: &[String],
+    original_path: &str,
+    widths: &HashMap<String, Option<u32>>,
+) -> Option<String> {
+    let first = styles.first()?;
+    if styles.len() > 1 {
+        let described: Option<Vec<String>> = styles
+            .iter()
+            .map(|name| {
+                let width = widths.get(name).copied().flatten()?;
+                Some(format!("{} {width}w", derivative_url(name, original_path)))
+            })
+            .collect();
+        if let Some(entries) = described {
+            return Some(entries.join(", "));
+        }
+    }
+    Some(derivative_url(first, original_path))
+}
+
 /// Normalize a path by resolving `..` components without filesystem access.
 fn normalize_path(path: &Path) -> PathBuf {
     let mut components = Vec::new();
@@ -611,6 +962,165 @@ mod tests {
         }
     }
 
+    #[test]
+    fn synthesize_width_style_valid() {
+        let style = synthesize_width_style("w800").unwrap();
+        assert_eq!(style.name, "w800");
+        let effects: Vec<serde_json::Value> = serde_json::from_value(style.effects).unwrap();
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0]["type"], "scale");
+        assert_eq!(effects[0]["width"], 800);
+    }
+
+    #[test]
+    fn synthesize_width_style_rejects_invalid() {
+        assert!(synthesize_width_style("thumbnail").is_none());
+        assert!(synthesize_width_style("w0").is_none());
+        assert!(synthesize_width_style("w9999").is_none());
+        assert!(synthesize_width_style("wabc").is_none());
+    }
+
+    #[test]
+    fn output_width_from_last_dimensional_effect() {
+        let style = make_style(serde_json::json!([
+            {"type": "scale", "width": 800},
+            {"type": "crop", "width": 400, "height": 300},
+            {"type": "desaturate"}
+        ]));
+        assert_eq!(style.output_width(), Some(400));
+        assert!(!style.has_output_format());
+        assert_eq!(style.content_type(), "image/jpeg");
+
+        let style = make_style(serde_json::json!([{"type": "scale", "height": 200}]));
+        assert_eq!(style.output_width(), None);
+
+        let style = make_style(serde_json::json!([
+            {"type": "scale", "width": 9000, "output_format": "png"}
+        ]));
+        assert_eq!(style.output_width(), Some(MAX_DIMENSION));
+        assert!(style.has_output_format());
+        assert_eq!(style.content_type(), "image/png");
+    }
+
+    #[test]
+    fn negotiate_prefers_avif_then_webp() {
+        assert_eq!(
+            VariantFormat::negotiate("image/avif,image/webp,image/*,*/*;q=0.8"),
+            Some(VariantFormat::Avif)
+        );
+        assert_eq!(
+            VariantFormat::negotiate("image/webp,*/*"),
+            Some(VariantFormat::Webp)
+        );
+        assert_eq!(
+            VariantFormat::negotiate("image/avif;q=0, image/webp;q=0.5"),
+            Some(VariantFormat::Webp)
+        );
+        assert_eq!(VariantFormat::negotiate("image/*,*/*;q=0.8"), None);
+        assert_eq!(VariantFormat::negotiate(""), None);
+    }
+
+    #[test]
+    fn variant_suffix_only_on_double_extensions() {
+        assert_eq!(
+            split_variant_suffix("2026/03/photo.jpg.webp"),
+            Some(("2026/03/photo.jpg", VariantFormat::Webp))
+        );
+        assert_eq!(
+            split_variant_suffix("photo.png.AVIF"),
+            Some(("photo.png", VariantFormat::Avif))
+        );
+        assert_eq!(split_variant_suffix("2026/03/photo.webp"), None);
+        assert_eq!(split_variant_suffix("dir.d/photo.webp"), None);
+        assert_eq!(split_variant_suffix("photo.jpg"), None);
+    }
+
+    #[test]
+    fn variant_overrides_style_format() {
+        let effects = vec![ImageEffect {
+            effect_type: "scale".to_string(),
+            width: Some(2),
+            height: None,
+            output_format: None,
+        }];
+        let mut img = image::load_from_memory(&test_png_bytes()).unwrap();
+        for effect in &effects {
+            img = apply_effect(img, effect);
+        }
+        let format = Some(VariantFormat::Webp).map_or_else(
+            || resolve_output_format(&effects),
+            VariantFormat::image_format,
+        );
+        let mut buf = Cursor::new(Vec::new());
+        img.write_to(&mut buf, format).unwrap();
+        let result = buf.into_inner();
+        assert_eq!(&result[8..12], b"WEBP");
+    }
+
+    fn responsive_style() -> (ResponsiveImageStyle, Vec<ResponsiveBreakpoint>) {
+        let breakpoints = serde_json::json!([
+            {"media": "(min-width: 960px)", "styles": ["large"]},
+            {"media": "(min-width: 480px)", "styles": ["w480", "w960"], "sizes": "100vw"},
+            {"styles": ["thumbnail", "unknown"]}
+        ]);
+        let style = ResponsiveImageStyle {
+            id: Uuid::nil(),
+            name: "hero".to_string(),
+            label: "Hero".to_string(),
+            fallback_style: "medium".to_string(),
+            breakpoints: breakpoints.clone(),
+            created: 0,
+            changed: 0,
+        };
+        (style, serde_json::from_value(breakpoints).unwrap())
+    }
+
+    #[test]
+    fn picture_sources_follow_breakpoints() {
+        let (style, breakpoints) = responsive_style();
+        let widths: HashMap<String, Option<u32>> = [
+            ("large", Some(1000)),
+            ("medium", Some(500)),
+            ("thumbnail", Some(100)),
+            ("w480", Some(480)),
+            ("w960", Some(960)),
+            ("unknown", None),
+        ]
+        .into_iter()
+        .map(|(name, width)| (name.to_string(), width))
+        .collect();
+
+        let picture = build_picture(
+            &style,
+            &breakpoints,
+            "/2026/03/photo.jpg",
+            "A photo",
+            &widths,
+        );
+        assert_eq!(picture.src, "/files/styles/medium/2026/03/photo.jpg");
+        assert_eq!(picture.width, Some(500));
+        assert_eq!(picture.sources.len(), 3);
+        assert_eq!(
+            picture.sources[0].media.as_deref(),
+            Some("(min-width: 960px)")
+        );
+        assert_eq!(
+            picture.sources[0].srcset,
+            "/files/styles/large/2026/03/photo.jpg"
+        );
+        assert_eq!(
+            picture.sources[1].srcset,
+            "/files/styles/w480/2026/03/photo.jpg 480w, /files/styles/w960/2026/03/photo.jpg 960w"
+        );
+        assert_eq!(picture.sources[1].sizes.as_deref(), Some("100vw"));
+        // An unknown width falls back to the first style alone.
+        assert_eq!(
+            picture.sources[2].srcset,
+            "/files/styles/thumbnail/2026/03/photo.jpg"
+        );
+        assert!(picture.sources[2].media.is_none());
+    }
+
     #[test]
     fn process_responsive_skips_widths_larger_than_original() {
         let src = test_png_100x80();
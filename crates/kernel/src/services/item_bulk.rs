@@ -0,0 +1,724 @@
+//! Item bulk operations.
+//!
+//! `POST /api/items/bulk` applies one operation to a list of items: the
+//! kernel operations below, or an operation a plugin declares under
+//! `[bulk_operations]` in its `.info.toml` and implements with
+//! `tap_item_bulk_operation`. A bulk run is a kernel batch operation
+//! ([`BULK_OPERATION`]) working through the items in chunks, saving progress
+//! after each chunk; cancelling the batch stops the run between chunks.
+//!
+//! Every item is checked against the requesting user's access first, so a
+//! run over a mixed selection changes what the user may change and reports
+//! the rest as denied. Kernel changes go through [`ItemService`], so taps,
+//! validation and cache invalidation behave as for manual edits.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use trovato_sdk::types::ItemBulkOperationResult;
+use uuid::Uuid;
+
+use crate::batch::{BatchService, BatchStatus};
+use crate::content::{ContentTypeRegistry, ItemService, can_administer_content};
+use crate::models::stage::LIVE_STAGE_ID;
+use crate::models::{Item, Stage, Tag, UpdateItem, User};
+use crate::plugin::PluginInfo;
+use crate::services::audit::AuditService;
+use crate::services::workspace;
+use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
+
+/// Batch operation type for bulk runs.
+pub const BULK_OPERATION: &str = "item_bulk";
+
+/// Maximum number of items in one bulk request.
+pub const MAX_BULK_ITEMS: usize = 1000;
+
+/// Items processed between progress saves and cancellation checks.
+const CHUNK_SIZE: usize = 50;
+
+/// Per-item errors kept in the outcome; further failures are only counted.
+const MAX_REPORTED_ERRORS: usize = 50;
+
+/// Tap applying plugin operations.
+const BULK_TAP: &str = "tap_item_bulk_operation";
+
+/// Kernel operations, in the order they are offered to editors.
+pub const KERNEL_OPERATIONS: [(&str, &str); 6] = [
+    ("publish", "Publish"),
+    ("unpublish", "Unpublish"),
+    ("delete", "Delete"),
+    ("change_author", "Change author"),
+    ("add_category", "Add category"),
+    ("move_stage", "Move to stage"),
+];
+
+/// A bulk request: `POST /api/items/bulk`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkRequest {
+    /// Operation name.
+    pub operation: String,
+    /// Items to operate on.
+    pub item_ids: Vec<Uuid>,
+    /// Operation parameters (e.g. `author_id` for `change_author`).
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// An operation applied to every item of a bulk run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkOperation {
+    Publish,
+    Unpublish,
+    Delete,
+    ChangeAuthor {
+        author_id: Uuid,
+    },
+    /// Add a category term to a field holding an array of term IDs.
+    AddCategory {
+        field: String,
+        tag_id: Uuid,
+    },
+    MoveStage {
+        stage_id: Uuid,
+    },
+    /// An operation declared by a plugin.
+    Plugin {
+        plugin: String,
+        name: String,
+        access: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct AuthorParams {
+    author_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct CategoryParams {
+    field: String,
+    tag_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct StageParams {
+    stage_id: Uuid,
+}
+
+impl BulkOperation {
+    /// Parse a kernel operation and its parameters.
+    ///
+    /// Returns `None` if `name` is not a kernel operation.
+    pub fn kernel(name: &str, params: &serde_json::Value) -> Result<Option<Self>> {
+        fn params_of<T: for<'de> Deserialize<'de>>(
+            name: &str,
+            params: &serde_json::Value,
+        ) -> Result<T> {
+            serde_json::from_value(params.clone())
+                .with_context(|| format!("invalid bulk operation: bad parameters for {name}"))
+        }
+
+        let operation = match name {
+            "publish" => Self::Publish,
+            "unpublish" => Self::Unpublish,
+            "delete" => Self::Delete,
+            "change_author" => {
+                let p: AuthorParams = params_of(name, params)?;
+                Self::ChangeAuthor {
+                    author_id: p.author_id,
+                }
+            }
+            "add_category" => {
+                let p: CategoryParams = params_of(name, params)?;
+                if p.field.is_empty() {
+                    bail!("invalid bulk operation: add_category needs a field");
+                }
+                Self::AddCategory {
+                    field: p.field,
+                    tag_id: p.tag_id,
+                }
+            }
+            "move_stage" => {
+                let p: StageParams = params_of(name, params)?;
+                Self::MoveStage {
+                    stage_id: p.stage_id,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(operation))
+    }
+
+    /// Find a plugin operation among `plugins` (name and manifest pairs).
+    ///
+    /// If several plugins declare `name`, the first by plugin name wins.
+    pub fn plugin<'a>(
+        name: &str,
+        plugins: impl IntoIterator<Item = (&'a str, &'a PluginInfo)>,
+    ) -> Option<Self> {
+        plugins
+            .into_iter()
+            .filter_map(|(plugin, info)| {
+                let config = info.bulk_operations.get(name)?;
+                Some((plugin, config.access.clone()))
+            })
+            .min_by(|a, b| a.0.cmp(b.0))
+            .map(|(plugin, access)| Self::Plugin {
+                plugin: plugin.to_string(),
+                name: name.to_string(),
+                access,
+            })
+    }
+
+    /// Operation name.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Publish => "publish",
+            Self::Unpublish => "unpublish",
+            Self::Delete => "delete",
+            Self::ChangeAuthor { .. } => "change_author",
+            Self::AddCategory { .. } => "add_category",
+            Self::MoveStage { .. } => "move_stage",
+            Self::Plugin { name, .. } => name,
+        }
+    }
+
+    /// Item access operation checked before each item is changed.
+    pub fn access(&self) -> &str {
+        match self {
+            Self::Delete => "delete",
+            Self::Plugin { access, .. } => access,
+            _ => "edit",
+        }
+    }
+
+    /// Whether `user` may start this operation at all.
+    ///
+    /// Changing authors and moving stages need
+    /// [`ADMINISTER_CONTENT_PERMISSION`](crate::content::ADMINISTER_CONTENT_PERMISSION);
+    /// every operation is still checked per item by [`Self::access`].
+    pub fn permitted(&self, user: &UserContext) -> bool {
+        match self {
+            Self::ChangeAuthor { .. } | Self::MoveStage { .. } => can_administer_content(user),
+            _ => true,
+        }
+    }
+
+    /// Check that the author, term or stage the operation names exists.
+    ///
+    /// Items are never moved to the live stage (they get there when their
+    /// stage is published), nor into another user's personal workspace.
+    pub async fn check_target(&self, pool: &PgPool, user: &UserContext) -> Result<()> {
+        match self {
+            Self::ChangeAuthor { author_id } => {
+                if User::find_by_id(pool, *author_id).await?.is_none() {
+                    bail!("invalid bulk operation: unknown author {author_id}");
+                }
+            }
+            Self::AddCategory { tag_id, .. } => {
+                if Tag::find_by_id(pool, *tag_id).await?.is_none() {
+                    bail!("invalid bulk operation: unknown category term {tag_id}");
+                }
+            }
+            Self::MoveStage { stage_id } => {
+                if *stage_id == LIVE_STAGE_ID {
+                    bail!("invalid bulk operation: items cannot be moved to the live stage");
+                }
+                if Stage::find_by_id(pool, *stage_id).await?.is_none() {
+                    bail!("invalid bulk operation: unknown stage {stage_id}");
+                }
+                if let Some(workspace) = workspace::find_by_stage(pool, *stage_id).await?
+                    && workspace.user_id != user.id
+                {
+                    bail!("invalid bulk operation: stage {stage_id} is another user's workspace");
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn audit_action(&self) -> String {
+        format!("item.bulk_{}", self.name())
+    }
+}
+
+/// Services a bulk run works through.
+#[derive(Clone)]
+pub struct BulkServices {
+    pub items: Arc<ItemService>,
+    pub batch: Arc<BatchService>,
+    pub content_types: Arc<ContentTypeRegistry>,
+    pub dispatcher: Arc<TapDispatcher>,
+    pub tap_services: RequestServices,
+    pub audit: Option<Arc<AuditService>>,
+}
+
+/// A failure reported for one item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkItemError {
+    pub item_id: Uuid,
+    pub error: String,
+}
+
+/// Counts reported when a bulk run finishes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkOutcome {
+    pub applied: u64,
+    /// Items that did not exist or needed no change.
+    pub skipped: u64,
+    /// Items the user may not change.
+    pub denied: u64,
+    pub failed: u64,
+    /// The first failures, with their reasons.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<BulkItemError>,
+    /// Whether the run stopped early because the batch was cancelled.
+    pub cancelled: bool,
+    /// Changed items with their content types, for follow-up cascades.
+    #[serde(skip)]
+    pub changed: Vec<(Uuid, String)>,
+}
+
+/// What happened to one item.
+enum ItemResult {
+    Applied,
+    Skipped,
+    Denied,
+    Failed(String),
+}
+
+/// Input of `tap_item_bulk_operation`.
+///
+/// SYNC: `ItemBulkOperationInput` in `crates/plugin-sdk/src/types.rs`.
+#[derive(Serialize)]
+struct BulkTapInput<'a> {
+    batch_id: Uuid,
+    operation: &'a str,
+    params: &'a serde_json::Value,
+    item: &'a Item,
+    user_id: Uuid,
+}
+
+/// Apply `operation` to `item_ids` as batch operation `batch_id`.
+///
+/// The batch is completed with the [`BulkOutcome`].
+pub async fn execute(
+    services: &BulkServices,
+    batch_id: Uuid,
+    operation: &BulkOperation,
+    item_ids: &[Uuid],
+    params: &serde_json::Value,
+    user: &UserContext,
+) -> Result<BulkOutcome> {
+    let total = item_ids.len() as u64;
+    let mut outcome = BulkOutcome::default();
+
+    for (chunk_index, chunk) in item_ids.chunks(CHUNK_SIZE).enumerate() {
+        if services
+            .batch
+            .get(batch_id)
+            .await?
+            .is_some_and(|op| op.status == BatchStatus::Cancelled)
+        {
+            outcome.cancelled = true;
+            break;
+        }
+        let done = (chunk_index * CHUNK_SIZE) as u64;
+        services
+            .batch
+            .update_progress(
+                batch_id,
+                done,
+                total,
+                Some(format!("{}: {done} of {total} items", operation.name())),
+            )
+            .await?;
+
+        for &item_id in chunk {
+            let result = match services.items.load(item_id).await {
+                Ok(Some(item)) => apply(services, batch_id, operation, &item, params, user)
+                    .await
+                    .map(|result| (result, item.item_type)),
+                Ok(None) => Ok((ItemResult::Skipped, String::new())),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok((ItemResult::Applied, item_type)) => {
+                    outcome.applied += 1;
+                    audit_item(services, batch_id, operation, item_id, &item_type, user).await;
+                    outcome.changed.push((item_id, item_type));
+                }
+                Ok((ItemResult::Skipped, _)) => outcome.skipped += 1,
+                Ok((ItemResult::Denied, _)) => outcome.denied += 1,
+                Ok((ItemResult::Failed(error), _)) => outcome.record_failure(item_id, error),
+                Err(e) => {
+                    warn!(item_id = %item_id, error = %e, "bulk operation failed for item");
+                    outcome.record_failure(item_id, e.to_string());
+                }
+            }
+        }
+    }
+
+    if !outcome.cancelled {
+        services
+            .batch
+            .complete(
+                batch_id,
+                Some(serde_json::to_value(&outcome).context("serialize bulk outcome")?),
+            )
+            .await?;
+    }
+
+    info!(
+        batch_id = %batch_id,
+        operation = %operation.name(),
+        applied = outcome.applied,
+        denied = outcome.denied,
+        failed = outcome.failed,
+        "item bulk operation finished"
+    );
+    Ok(outcome)
+}
+
+impl BulkOutcome {
+    fn record_failure(&mut self, item_id: Uuid, error: String) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(BulkItemError { item_id, error });
+        }
+    }
+}
+
+/// Apply the operation to one item the run has loaded.
+async fn apply(
+    services: &BulkServices,
+    batch_id: Uuid,
+    operation: &BulkOperation,
+    item: &Item,
+    params: &serde_json::Value,
+    user: &UserContext,
+) -> Result<ItemResult> {
+    if !services
+        .items
+        .check_access(item, operation.access(), user)
+        .await?
+    {
+        return Ok(ItemResult::Denied);
+    }
+
+    let items = &services.items;
+    let changed = match operation {
+        BulkOperation::Publish | BulkOperation::Unpublish => {
+            let status = i16::from(*operation == BulkOperation::Publish);
+            if item.status == status {
+                return Ok(ItemResult::Skipped);
+            }
+            let input = UpdateItem {
+                title: None,
+                status: Some(status),
+                promote: None,
+                sticky: None,
+                fields: None,
+                log: Some(format!("{} by bulk operation", past_tense(operation))),
+                status_meta: None,
+            };
+            items.update(item.id, input, user).await?.is_some()
+        }
+        BulkOperation::Delete => items.delete(item.id, user).await?,
+        BulkOperation::ChangeAuthor { author_id } => {
+            if item.author_id == *author_id {
+                return Ok(ItemResult::Skipped);
+            }
+            items
+                .change_author(item.id, *author_id, user)
+                .await?
+                .is_some()
+        }
+        BulkOperation::AddCategory { field, tag_id } => {
+            let has_field = services
+                .content_types
+                .get(&item.item_type)
+                .is_some_and(|t| t.fields.iter().any(|f| f.field_name == *field));
+            if !has_field {
+                return Ok(ItemResult::Failed(format!(
+                    "{} items have no field {field}",
+                    item.item_type
+                )));
+            }
+            let value = match with_tag(item.fields.get(field), *tag_id) {
+                Ok(Some(value)) => value,
+                Ok(None) => return Ok(ItemResult::Skipped),
+                Err(e) => return Ok(ItemResult::Failed(e.to_string())),
+            };
+            let mut fields = item.fields.clone();
+            if let Some(map) = fields.as_object_mut() {
+                map.insert(field.clone(), value);
+            }
+            let input = UpdateItem {
+                title: None,
+                status: None,
+                promote: None,
+                sticky: None,
+                fields: Some(fields),
+                log: Some("Category added by bulk operation".to_string()),
+                status_meta: None,
+            };
+            items.update(item.id, input, user).await?.is_some()
+        }
+        BulkOperation::MoveStage { stage_id } => {
+            if item.stage_id == *stage_id {
+                return Ok(ItemResult::Skipped);
+            }
+            items
+                .move_to_stage(item.id, *stage_id, user)
+                .await?
+                .is_some()
+        }
+        BulkOperation::Plugin { plugin, name, .. } => {
+            return apply_plugin(services, batch_id, plugin, name, item, params, user).await;
+        }
+    };
+
+    Ok(if changed {
+        ItemResult::Applied
+    } else {
+        ItemResult::Skipped
+    })
+}
+
+/// Pass one item to the declaring plugin's `tap_item_bulk_operation`.
+async fn apply_plugin(
+    services: &BulkServices,
+    batch_id: Uuid,
+    plugin: &str,
+    operation: &str,
+    item: &Item,
+    params: &serde_json::Value,
+    user: &UserContext,
+) -> Result<ItemResult> {
+    let input = BulkTapInput {
+        batch_id,
+        operation,
+        params,
+        item,
+        user_id: user.id,
+    };
+    let input_json = serde_json::to_string(&input).context("serialize bulk operation input")?;
+    let state = RequestState::new(user.clone(), services.tap_services.clone());
+
+    let Some(result) = services
+        .dispatcher
+        .dispatch_to_plugin(BULK_TAP, &input_json, plugin, state)
+        .await
+    else {
+        return Ok(ItemResult::Failed(format!("{BULK_TAP} failed")));
+    };
+    if result.output.trim().is_empty() {
+        return Ok(ItemResult::Applied);
+    }
+
+    let reply: ItemBulkOperationResult = match serde_json::from_str(&result.output) {
+        Ok(reply) => reply,
+        Err(e) => {
+            return Ok(ItemResult::Failed(format!(
+                "invalid {BULK_TAP} output: {e}"
+            )));
+        }
+    };
+    Ok(match reply {
+        ItemBulkOperationResult {
+            error: Some(error), ..
+        } => ItemResult::Failed(error),
+        ItemBulkOperationResult { skipped: true, .. } => ItemResult::Skipped,
+        _ => ItemResult::Applied,
+    })
+}
+
+/// Add `tag_id` to a category field value.
+///
+/// Term IDs are stored as an array of UUID strings; a missing field or a
+/// single ID becomes an array. Returns `None` if the term is already there.
+fn with_tag(value: Option<&serde_json::Value>, tag_id: Uuid) -> Result<Option<serde_json::Value>> {
+    let tag = serde_json::Value::String(tag_id.to_string());
+    let mut tags = match value {
+        None | Some(serde_json::Value::Null) => Vec::new(),
+        Some(serde_json::Value::String(s)) if s.is_empty() => Vec::new(),
+        Some(single @ serde_json::Value::String(_)) => vec![single.clone()],
+        Some(serde_json::Value::Array(tags)) => tags.clone(),
+        Some(_) => bail!("field does not hold category terms"),
+    };
+    if tags.contains(&tag) {
+        return Ok(None);
+    }
+    tags.push(tag);
+    Ok(Some(serde_json::Value::Array(tags)))
+}
+
+fn past_tense(operation: &BulkOperation) -> &'static str {
+    match operation {
+        BulkOperation::Publish => "Published",
+        _ => "Unpublished",
+    }
+}
+
+async fn audit_item(
+    services: &BulkServices,
+    batch_id: Uuid,
+    operation: &BulkOperation,
+    item_id: Uuid,
+    item_type: &str,
+    user: &UserContext,
+) {
+    let Some(audit) = &services.audit else {
+        return;
+    };
+    if let Err(e) = audit
+        .log(
+            &operation.audit_action(),
+            "item",
+            &item_id.to_string(),
+            Some(user.id),
+            "",
+            serde_json::json!({
+                "item_type": item_type,
+                "batch_id": batch_id,
+            }),
+        )
+        .await
+    {
+        warn!(item_id = %item_id, error = %e, "failed to audit bulk operation");
+    }
+}
+
+/// Operations offered by enabled plugins, keyed by name, with their labels.
+///
+/// Plugin operations shadowed by a kernel operation are left out.
+pub fn plugin_operations<'a>(
+    plugins: impl IntoIterator<Item = (&'a str, &'a PluginInfo)>,
+) -> HashMap<String, String> {
+    let mut operations = HashMap::new();
+    for (_, info) in plugins {
+        for (name, config) in &info.bulk_operations {
+            if KERNEL_OPERATIONS.iter().any(|(kernel, _)| kernel == name) {
+                continue;
+            }
+            operations
+                .entry(name.clone())
+                .or_insert_with(|| config.label.clone());
+        }
+    }
+    operations
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::Path;
+
+    #[test]
+    fn kernel_operations_parse_params() {
+        assert_eq!(
+            BulkOperation::kernel("publish", &json!(null)).unwrap(),
+            Some(BulkOperation::Publish)
+        );
+
+        let author = Uuid::now_v7();
+        assert_eq!(
+            BulkOperation::kernel("change_author", &json!({ "author_id": author })).unwrap(),
+            Some(BulkOperation::ChangeAuthor { author_id: author })
+        );
+        let err = BulkOperation::kernel("change_author", &json!({})).unwrap_err();
+        assert!(err.to_string().starts_with("invalid bulk operation"));
+        assert!(
+            BulkOperation::kernel("add_category", &json!({ "field": "", "tag_id": author }))
+                .is_err()
+        );
+
+        assert_eq!(BulkOperation::kernel("archive", &json!({})).unwrap(), None);
+        assert_eq!(BulkOperation::Delete.access(), "delete");
+        assert_eq!(
+            BulkOperation::ChangeAuthor { author_id: author }.access(),
+            "edit"
+        );
+    }
+
+    #[test]
+    fn author_and_stage_changes_need_administer_content() {
+        let editor = UserContext::authenticated(Uuid::now_v7(), vec!["edit any content".into()]);
+        let manager = UserContext::authenticated(
+            Uuid::now_v7(),
+            vec![crate::content::ADMINISTER_CONTENT_PERMISSION.into()],
+        );
+        let admin = UserContext::authenticated(Uuid::now_v7(), vec!["administer site".into()]);
+        let change_author = BulkOperation::ChangeAuthor {
+            author_id: Uuid::now_v7(),
+        };
+        let move_stage = BulkOperation::MoveStage {
+            stage_id: Uuid::now_v7(),
+        };
+
+        for operation in [&change_author, &move_stage] {
+            assert!(!operation.permitted(&editor));
+            assert!(!operation.permitted(&UserContext::anonymous()));
+            assert!(operation.permitted(&manager));
+            assert!(operation.permitted(&admin));
+        }
+        assert!(BulkOperation::Publish.permitted(&editor));
+    }
+
+    #[test]
+    fn plugin_operations_come_from_manifests() {
+        let manifest = |name: &str, access: &str| {
+            let toml = format!(
+                "name = \"{name}\"\ndescription = \"d\"\nversion = \"1.0.0\"\n\
+                 [taps]\nimplements = [\"tap_item_bulk_operation\"]\n\
+                 [bulk_operations.archive]\nlabel = \"Archive\"\naccess = \"{access}\"\n\
+                 [bulk_operations.publish]\nlabel = \"Publish later\"\n"
+            );
+            PluginInfo::parse_str(&toml, Path::new("test.toml")).unwrap()
+        };
+        let zeta = manifest("zeta", "edit");
+        let alpha = manifest("alpha", "delete");
+        let plugins = [("zeta", &zeta), ("alpha", &alpha)];
+
+        assert_eq!(
+            BulkOperation::plugin("archive", plugins),
+            Some(BulkOperation::Plugin {
+                plugin: "alpha".to_string(),
+                name: "archive".to_string(),
+                access: "delete".to_string(),
+            })
+        );
+        assert_eq!(BulkOperation::plugin("purge", plugins), None);
+
+        let offered = plugin_operations(plugins);
+        assert_eq!(offered.len(), 1);
+        assert_eq!(offered["archive"], "Archive");
+    }
+
+    #[test]
+    fn with_tag_appends_once() {
+        let tag = Uuid::now_v7();
+        let other = Uuid::now_v7().to_string();
+
+        assert_eq!(with_tag(None, tag).unwrap(), Some(json!([tag.to_string()])));
+        assert_eq!(
+            with_tag(Some(&json!(other)), tag).unwrap(),
+            Some(json!([other, tag.to_string()]))
+        );
+        assert_eq!(
+            with_tag(Some(&json!([tag.to_string()])), tag).unwrap(),
+            None
+        );
+        assert!(with_tag(Some(&json!({ "value": "x" })), tag).is_err());
+    }
+}
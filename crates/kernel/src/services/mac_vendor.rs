@@ -0,0 +1,360 @@
+//! MAC address vendor lookup.
+//!
+//! Maps the OUI of a MAC address (its first three octets) to the
+//! organization the IEEE assigned it to. A small table of common home and
+//! office vendors ships with the kernel (`data/oui.tsv`); the
+//! `refresh_mac_vendors` cron task replaces it with the full IEEE MA-L
+//! registry, stored in the `mac_vendor` table, once a month. Sites that
+//! cannot reach the IEEE keep using the bundled table.
+//!
+//! Lookups are answered from memory. Each instance reloads the table from
+//! Postgres once its copy is [`RELOAD_INTERVAL`] old, so a refresh on one
+//! instance reaches the others.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::models::SiteConfig;
+
+/// The IEEE MA-L registry.
+pub const IEEE_OUI_URL: &str = "https://standards-oui.ieee.org/oui/oui.csv";
+
+/// `site_config` key of the [`RefreshState`].
+const REFRESH_STATE_KEY: &str = "mac_vendor.refresh";
+
+/// How often the registry is downloaded (30 days).
+const REFRESH_INTERVAL_SECS: i64 = 30 * 86_400;
+
+/// How long to wait after a failed download before trying again (1 day).
+const RETRY_INTERVAL_SECS: i64 = 86_400;
+
+/// Maximum time allowed for the registry download.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Downloads with fewer assignments are treated as truncated. The registry
+/// holds well over 30,000.
+const MIN_REGISTRY_ENTRIES: usize = 10_000;
+
+/// How long an instance serves lookups from its in-memory copy.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// OUI to vendor name.
+type VendorTable = HashMap<String, String>;
+
+/// The table bundled with the kernel.
+static BUNDLED: LazyLock<Arc<VendorTable>> =
+    LazyLock::new(|| Arc::new(parse_bundled(include_str!("../../data/oui.tsv"))));
+
+/// This instance's copy of the vendor table.
+static LOADED: LazyLock<RwLock<Option<Loaded>>> = LazyLock::new(|| RwLock::new(None));
+
+/// A vendor table and when it was loaded.
+#[derive(Clone)]
+struct Loaded {
+    table: Arc<VendorTable>,
+    at: Instant,
+}
+
+/// Registry download bookkeeping, stored in `site_config`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct RefreshState {
+    /// Unix timestamp of the last successful download.
+    #[serde(default)]
+    refreshed_at: Option<i64>,
+    /// Unix timestamp of the last download attempt.
+    #[serde(default)]
+    attempted_at: Option<i64>,
+}
+
+impl RefreshState {
+    fn is_due(&self, now: i64) -> bool {
+        let older_than = |at: Option<i64>, secs: i64| at.is_none_or(|at| now - at >= secs);
+        older_than(self.refreshed_at, REFRESH_INTERVAL_SECS)
+            && older_than(self.attempted_at, RETRY_INTERVAL_SECS)
+    }
+}
+
+/// The OUI of a MAC address as six uppercase hex digits.
+///
+/// Accepts `aa:bb:cc:dd:ee:ff`, `aa-bb-cc-dd-ee-ff`, `aabb.ccdd.eeff` and
+/// bare hex. Returns `None` for malformed addresses and for locally
+/// administered (e.g. randomized) and multicast addresses, which carry no
+/// vendor.
+pub fn oui(mac: &str) -> Option<String> {
+    let hex: String = mac
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let first_octet = u8::from_str_radix(&hex[..2], 16).ok()?;
+    if first_octet & 0b11 != 0 {
+        return None;
+    }
+    Some(hex[..6].to_ascii_uppercase())
+}
+
+/// Vendor of a MAC address, if its OUI is known.
+pub async fn lookup(pool: &PgPool, mac: &str) -> Option<String> {
+    let oui = oui(mac)?;
+    table(pool).await.get(&oui).cloned()
+}
+
+/// The current vendor table, reloading it from Postgres when stale.
+///
+/// Falls back to the previous copy, or the bundled table, when the
+/// database cannot be read.
+async fn table(pool: &PgPool) -> Arc<VendorTable> {
+    let previous = LOADED.read().clone();
+    if let Some(loaded) = &previous
+        && loaded.at.elapsed() < RELOAD_INTERVAL
+    {
+        return loaded.table.clone();
+    }
+
+    let table = match load(pool).await {
+        Ok(table) if table.is_empty() => BUNDLED.clone(),
+        Ok(table) => Arc::new(table),
+        Err(e) => {
+            warn!(error = %e, "failed to load MAC vendors");
+            previous.map_or_else(|| BUNDLED.clone(), |loaded| loaded.table)
+        }
+    };
+    *LOADED.write() = Some(Loaded {
+        table: table.clone(),
+        at: Instant::now(),
+    });
+    table
+}
+
+/// Read the stored registry.
+async fn load(pool: &PgPool) -> Result<VendorTable> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT prefix, vendor FROM mac_vendor")
+        .fetch_all(pool)
+        .await
+        .context("failed to read mac_vendor")?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Download the registry if the last download is a month old.
+///
+/// A failed download is retried after a day rather than on every cron
+/// run. Returns the number of vendors stored, or `None` if no download
+/// was due.
+pub async fn refresh_if_due(pool: &PgPool, http: &reqwest::Client) -> Result<Option<usize>> {
+    let mut state: RefreshState = match SiteConfig::get(pool, REFRESH_STATE_KEY).await? {
+        Some(value) => serde_json::from_value(value).unwrap_or_default(),
+        None => RefreshState::default(),
+    };
+    let now = chrono::Utc::now().timestamp();
+    if !state.is_due(now) {
+        return Ok(None);
+    }
+
+    // Record the attempt first so a failing download is not retried on
+    // every cron run.
+    state.attempted_at = Some(now);
+    SiteConfig::set(pool, REFRESH_STATE_KEY, serde_json::to_value(state)?).await?;
+
+    let count = refresh(pool, http).await?;
+    state.refreshed_at = Some(now);
+    SiteConfig::set(pool, REFRESH_STATE_KEY, serde_json::to_value(state)?).await?;
+    Ok(Some(count))
+}
+
+/// Download the IEEE registry and replace the stored table.
+///
+/// Returns the number of vendors stored.
+pub async fn refresh(pool: &PgPool, http: &reqwest::Client) -> Result<usize> {
+    let csv = http
+        .get(IEEE_OUI_URL)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .context("failed to download the IEEE OUI registry")?
+        .text()
+        .await
+        .context("failed to read the IEEE OUI registry")?;
+
+    let table = parse_ieee_csv(&csv);
+    if table.len() < MIN_REGISTRY_ENTRIES {
+        bail!(
+            "IEEE OUI registry has only {} assignments, expected at least {MIN_REGISTRY_ENTRIES}",
+            table.len()
+        );
+    }
+
+    let (prefixes, vendors): (Vec<String>, Vec<String>) = table.into_iter().unzip();
+    let mut tx = pool.begin().await.context("failed to begin transaction")?;
+    sqlx::query("DELETE FROM mac_vendor")
+        .execute(&mut *tx)
+        .await
+        .context("failed to clear mac_vendor")?;
+    sqlx::query(
+        "INSERT INTO mac_vendor (prefix, vendor) SELECT * FROM UNNEST($1::text[], $2::text[])",
+    )
+    .bind(&prefixes)
+    .bind(&vendors)
+    .execute(&mut *tx)
+    .await
+    .context("failed to store MAC vendors")?;
+    tx.commit().await.context("failed to commit MAC vendors")?;
+
+    // Serve the new table on this instance right away.
+    *LOADED.write() = None;
+
+    info!(vendors = prefixes.len(), "refreshed MAC vendor registry");
+    Ok(prefixes.len())
+}
+
+/// Parse the bundled table: `OUI<TAB>vendor` lines, `#` comments.
+fn parse_bundled(text: &str) -> VendorTable {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(prefix, vendor)| (prefix.to_ascii_uppercase(), vendor.trim().to_string()))
+        .collect()
+}
+
+/// Parse the IEEE `oui.csv` registry
+/// (`Registry,Assignment,Organization Name,Organization Address`).
+fn parse_ieee_csv(text: &str) -> VendorTable {
+    csv_records(text)
+        .into_iter()
+        .filter_map(|record| match record.as_slice() {
+            [registry, assignment, vendor, ..]
+                if registry == "MA-L"
+                    && assignment.len() == 6
+                    && assignment.chars().all(|c| c.is_ascii_hexdigit())
+                    && !vendor.trim().is_empty() =>
+            {
+                Some((assignment.to_ascii_uppercase(), vendor.trim().to_string()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Split CSV text into records of fields, honouring quoted fields with
+/// embedded commas, doubled quotes and line breaks.
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !in_quotes => {}
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oui_accepts_common_notations() {
+        for mac in [
+            "b8:27:eb:12:34:56",
+            "B8-27-EB-12-34-56",
+            "b827.eb12.3456",
+            " b827eb123456 ",
+        ] {
+            assert_eq!(oui(mac).as_deref(), Some("B827EB"), "{mac}");
+        }
+        assert_eq!(oui("b8:27:eb:12:34"), None);
+        assert_eq!(oui("zz:27:eb:12:34:56"), None);
+    }
+
+    #[test]
+    fn randomized_and_multicast_addresses_have_no_oui() {
+        // Locally administered bit set, as on phones' private addresses.
+        assert_eq!(oui("da:a1:19:12:34:56"), None);
+        // Multicast bit set.
+        assert_eq!(oui("01:00:5e:00:00:01"), None);
+    }
+
+    #[test]
+    fn bundled_table_parses() {
+        assert_eq!(
+            BUNDLED.get("B827EB").map(String::as_str),
+            Some("Raspberry Pi Foundation")
+        );
+        assert!(BUNDLED.len() > 50);
+        assert!(BUNDLED.keys().all(|k| oui(&format!("{k}000000")).is_some()));
+    }
+
+    #[test]
+    fn ieee_csv_handles_quoted_fields() {
+        let csv = "Registry,Assignment,Organization Name,Organization Address\r\n\
+                   MA-L,002272,American Micro-Fuel Device Corp.,2181 Buchanan Loop Ferndale WA US 98248 \r\n\
+                   MA-L,00D0EF,IGT,\"9295 PROTOTYPE DRIVE, RENO NV US 89511 \"\r\n\
+                   MA-L,086195,\"Rockwell Automation, \"\"Inc.\"\"\",\"1 Allen-Bradley Dr.\nMayfield Heights OH US 44124 \"\r\n\
+                   MA-M,70B3D5001,Not an OUI,Somewhere\r\n";
+        let table = parse_ieee_csv(csv);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table["002272"], "American Micro-Fuel Device Corp.");
+        assert_eq!(table["00D0EF"], "IGT");
+        assert_eq!(table["086195"], "Rockwell Automation, \"Inc.\"");
+    }
+
+    #[test]
+    fn refresh_is_monthly_with_daily_retries() {
+        let day = 86_400;
+        let now = 100 * day;
+        assert!(RefreshState::default().is_due(now));
+
+        let fresh = RefreshState {
+            refreshed_at: Some(now - day),
+            attempted_at: Some(now - day),
+        };
+        assert!(!fresh.is_due(now));
+
+        let stale = RefreshState {
+            refreshed_at: Some(now - 31 * day),
+            attempted_at: Some(now - 31 * day),
+        };
+        assert!(stale.is_due(now));
+
+        let failed_today = RefreshState {
+            refreshed_at: Some(now - 31 * day),
+            attempted_at: Some(now - 3600),
+        };
+        assert!(!failed_today.is_due(now));
+    }
+}
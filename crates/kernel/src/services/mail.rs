@@ -0,0 +1,305 @@
+//! General outgoing mail service.
+//!
+//! Composes messages from theme templates under `templates/email/`, runs
+//! them through `tap_mail_alter`, and either queues them on the Redis
+//! `email:send` queue (drained by cron) or delivers them immediately.
+//!
+//! Templates resolve through theme suggestions: `email/{key}--{language}`
+//! is preferred over `email/{key}`, for both the `.txt` body (required) and
+//! the `.html` alternative (optional).
+//!
+//! Delivery goes through a [`MailTransport`]: SMTP, or a log transport for
+//! development (`MAIL_TRANSPORT=log`). With no transport configured,
+//! messages are dropped with a debug log.
+//!
+//! Account security mail (verification and password reset links) is sent
+//! directly by [`EmailService`] and does not pass through `tap_mail_alter`,
+//! so plugins never see those tokens.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::cron::{Queue, RedisQueue};
+use crate::services::email::EmailService;
+use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
+use crate::theme::ThemeEngine;
+
+/// Queue drained by the `process_queues` cron task.
+pub const MAIL_QUEUE: &str = "email:send";
+
+/// An outgoing message.
+///
+/// Also the input and output of `tap_mail_alter`: plugins may change any
+/// field, add `cc`/`bcc` recipients, or set `cancel` to suppress delivery.
+///
+/// SYNC: An identical struct exists in `crates/plugin-sdk/src/types.rs` for
+/// plugin-side deserialization. If you change fields here, update the SDK
+/// copy to match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MailMessage {
+    /// Message kind (template key, e.g. "comment_notification"); empty for
+    /// ad-hoc messages.
+    #[serde(default)]
+    pub key: String,
+    /// Primary recipient.
+    pub to: String,
+    /// Carbon-copy recipients.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cc: Vec<String>,
+    /// Blind carbon-copy recipients.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bcc: Vec<String>,
+    /// Reply-To address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    /// Subject line.
+    pub subject: String,
+    /// Plain text body. Accepts `body` for queue items written before the
+    /// mail service existed.
+    #[serde(alias = "body")]
+    pub text_body: String,
+    /// Optional HTML alternative.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+    /// Set by `tap_mail_alter` to suppress delivery.
+    #[serde(default)]
+    pub cancel: bool,
+}
+
+impl MailMessage {
+    /// Create a plain-text message.
+    pub fn new(to: &str, subject: &str, text_body: &str) -> Self {
+        Self {
+            key: String::new(),
+            to: to.to_string(),
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            reply_to: None,
+            subject: subject.to_string(),
+            text_body: text_body.to_string(),
+            html_body: None,
+            cancel: false,
+        }
+    }
+}
+
+/// How messages are delivered.
+pub enum MailTransport {
+    /// SMTP via lettre.
+    Smtp(Arc<EmailService>),
+    /// Log messages instead of sending them (development).
+    Log,
+}
+
+/// Outgoing mail service.
+pub struct MailService {
+    transport: Option<MailTransport>,
+    queue: Arc<RedisQueue>,
+    dispatcher: Arc<TapDispatcher>,
+    tap_services: RequestServices,
+}
+
+impl MailService {
+    /// Create a mail service.
+    pub fn new(
+        transport: Option<MailTransport>,
+        queue: Arc<RedisQueue>,
+        dispatcher: Arc<TapDispatcher>,
+        tap_services: RequestServices,
+    ) -> Self {
+        Self {
+            transport,
+            queue,
+            dispatcher,
+            tap_services,
+        }
+    }
+
+    /// Whether a transport is configured.
+    pub fn is_configured(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// Render a message from the `email/{key}` templates.
+    ///
+    /// `context` receives `subject`, so templates can use it as well.
+    pub fn compose(
+        &self,
+        theme: &ThemeEngine,
+        key: &str,
+        to: &str,
+        subject: &str,
+        language: Option<&str>,
+        context: &tera::Context,
+    ) -> Result<MailMessage> {
+        let mut context = context.clone();
+        context.insert("subject", subject);
+
+        let suggestions = template_suggestions(key, language);
+        let tera = theme.tera();
+
+        let txt = resolve_template(tera, &suggestions, "txt")
+            .with_context(|| format!("no text template for email {key}"))?;
+        let text_body = tera
+            .render(&txt, &context)
+            .with_context(|| format!("failed to render email template {txt}"))?;
+        let html_body = resolve_template(tera, &suggestions, "html")
+            .and_then(|html| tera.render(&html, &context).ok());
+
+        Ok(MailMessage {
+            key: key.to_string(),
+            text_body,
+            html_body,
+            ..MailMessage::new(to, subject, "")
+        })
+    }
+
+    /// Run `tap_mail_alter` and queue the message for cron delivery.
+    ///
+    /// Returns `false` if a plugin cancelled the message.
+    pub async fn queue(&self, message: MailMessage) -> Result<bool> {
+        let Some(message) = self.alter(message).await else {
+            return Ok(false);
+        };
+        let json = serde_json::to_string(&message).context("serialize mail message")?;
+        self.queue.push(MAIL_QUEUE, &json).await?;
+        debug!(to = %message.to, key = %message.key, "queued mail");
+        Ok(true)
+    }
+
+    /// Run `tap_mail_alter` and deliver the message now.
+    ///
+    /// Returns `false` if a plugin cancelled the message.
+    pub async fn send_now(&self, message: MailMessage) -> Result<bool> {
+        let Some(message) = self.alter(message).await else {
+            return Ok(false);
+        };
+        self.deliver(&message).await?;
+        Ok(true)
+    }
+
+    /// Deliver an already-altered message through the transport.
+    pub async fn deliver(&self, message: &MailMessage) -> Result<()> {
+        match &self.transport {
+            Some(MailTransport::Smtp(email)) => {
+                email.send_message(message).await?;
+                info!(to = %message.to, subject = %message.subject, "sent mail");
+            }
+            Some(MailTransport::Log) => {
+                info!(
+                    to = %message.to,
+                    cc = ?message.cc,
+                    bcc = ?message.bcc,
+                    key = %message.key,
+                    subject = %message.subject,
+                    body = %message.text_body,
+                    "mail (log transport)"
+                );
+            }
+            None => {
+                debug!(
+                    to = %message.to,
+                    subject = %message.subject,
+                    "mail transport not configured, dropping mail"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Pass a message through `tap_mail_alter`; `None` if cancelled.
+    async fn alter(&self, message: MailMessage) -> Option<MailMessage> {
+        let state = RequestState::new(UserContext::anonymous(), self.tap_services.clone());
+        let message = self
+            .dispatcher
+            .dispatch_alter("tap_mail_alter", message, state)
+            .await;
+
+        if message.cancel {
+            debug!(to = %message.to, key = %message.key, "mail cancelled by tap_mail_alter");
+            return None;
+        }
+        Some(message)
+    }
+}
+
+/// Template suggestions for a message key, most specific first.
+fn template_suggestions(key: &str, language: Option<&str>) -> Vec<String> {
+    let mut suggestions = Vec::with_capacity(2);
+    if let Some(lang) = language.filter(|l| !l.is_empty()) {
+        suggestions.push(format!("email/{key}--{lang}"));
+    }
+    suggestions.push(format!("email/{key}"));
+    suggestions
+}
+
+/// First existing `{suggestion}.{ext}` template.
+fn resolve_template(tera: &tera::Tera, suggestions: &[String], ext: &str) -> Option<String> {
+    suggestions
+        .iter()
+        .map(|s| format!("{s}.{ext}"))
+        .find(|name| tera.get_template(name).is_ok())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_prefer_language() {
+        assert_eq!(
+            template_suggestions("comment_notification", Some("de")),
+            vec![
+                "email/comment_notification--de".to_string(),
+                "email/comment_notification".to_string()
+            ]
+        );
+        assert_eq!(
+            template_suggestions("comment_notification", None),
+            vec!["email/comment_notification".to_string()]
+        );
+    }
+
+    #[test]
+    fn resolve_falls_back_to_base_template() {
+        let mut tera = tera::Tera::default();
+        tera.add_raw_template("email/welcome.txt", "Hi").unwrap();
+        tera.add_raw_template("email/welcome--fr.html", "<p>Salut</p>")
+            .unwrap();
+
+        let suggestions = template_suggestions("welcome", Some("fr"));
+        assert_eq!(
+            resolve_template(&tera, &suggestions, "txt").as_deref(),
+            Some("email/welcome.txt")
+        );
+        assert_eq!(
+            resolve_template(&tera, &suggestions, "html").as_deref(),
+            Some("email/welcome--fr.html")
+        );
+        assert!(resolve_template(&tera, &suggestions, "md").is_none());
+    }
+
+    #[test]
+    fn message_accepts_legacy_queue_items() {
+        let message: MailMessage =
+            serde_json::from_str(r#"{"to":"a@example.com","subject":"Hi","body":"Hello"}"#)
+                .unwrap();
+        assert_eq!(message, MailMessage::new("a@example.com", "Hi", "Hello"));
+    }
+
+    #[test]
+    fn message_roundtrip_keeps_alterations() {
+        let mut message = MailMessage::new("a@example.com", "Hi", "Hello");
+        message.bcc.push("audit@example.com".to_string());
+        message.cancel = true;
+
+        let json = serde_json::to_string(&message).unwrap();
+        let back: MailMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, message);
+    }
+}
@@ -0,0 +1,563 @@
+//! Two-factor authentication: TOTP authenticators and recovery codes.
+//!
+//! A user enrolls by adding the provisioning URI to an authenticator app
+//! (RFC 6238: HMAC-SHA1, 6 digits, 30-second steps) and confirming one code.
+//! Confirmation issues single-use recovery codes, stored as SHA-256 hashes.
+//!
+//! Once enrolled, a correct password at login is followed by a code
+//! challenge. Wrong codes count against the account lockout exactly like
+//! wrong passwords. A TOTP step is accepted at most once per user, so an
+//! observed code cannot be replayed within its validity window.
+//!
+//! Users with the [`ADMINISTER_MFA_PERMISSION`] choose which roles must use
+//! two-factor authentication (`site_config` key `mfa_required_roles`).
+//! Members of those roles are sent through enrollment at their next login.
+
+use anyhow::{Context, Result, bail};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::models::role::well_known::AUTHENTICATED_ROLE_ID;
+use crate::models::{Role, SiteConfig, User};
+
+/// Permission to configure enforcement and reset other users' 2FA.
+pub const ADMINISTER_MFA_PERMISSION: &str = "administer 2fa";
+
+/// `site_config` key holding the role IDs that must use 2FA.
+pub const MFA_REQUIRED_ROLES_KEY: &str = "mfa_required_roles";
+
+/// Number of digits in a TOTP code.
+const TOTP_DIGITS: u32 = 6;
+
+/// TOTP time step in seconds.
+const TOTP_STEP_SECS: i64 = 30;
+
+/// Steps of clock drift accepted on either side of the current one.
+const TOTP_SKEW_STEPS: i64 = 1;
+
+/// Secret length in bytes (160 bits, as recommended by RFC 4226).
+const SECRET_BYTES: usize = 20;
+
+/// Number of recovery codes issued at a time.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// RFC 4648 base32 alphabet.
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+// =============================================================================
+// TOTP
+// =============================================================================
+
+/// Encode bytes as unpadded base32.
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | u64::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Decode base32, ignoring case, spaces and padding.
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let upper = c.to_ascii_uppercase() as u8;
+        let value = BASE32_ALPHABET.iter().position(|&a| a == upper)? as u64;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Generate a new base32-encoded TOTP secret.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base32_encode(&bytes)
+}
+
+/// Compute the TOTP code for a time step.
+fn totp(key: &[u8], step: i64) -> Option<u32> {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).ok()?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation (RFC 4226 section 5.3).
+    let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    Some(binary % 10u32.pow(TOTP_DIGITS))
+}
+
+/// Check a TOTP code against a secret.
+///
+/// Accepts the current step and [`TOTP_SKEW_STEPS`] on either side, but
+/// only steps after `last_step` (the last one used). Returns the matching
+/// step, which the caller must record.
+pub fn verify_totp(secret: &str, code: &str, now: i64, last_step: i64) -> Option<i64> {
+    let code = normalize_code(code);
+    if code.len() != TOTP_DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = now.div_euclid(TOTP_STEP_SECS);
+
+    (current - TOTP_SKEW_STEPS..=current + TOTP_SKEW_STEPS)
+        .filter(|step| *step > last_step)
+        .find(|step| {
+            totp(&key, *step).is_some_and(|expected| {
+                let expected = format!("{expected:0width$}", width = TOTP_DIGITS as usize);
+                bool::from(expected.as_bytes().ct_eq(code.as_bytes()))
+            })
+        })
+}
+
+/// Build the `otpauth://` URI an authenticator app imports (usually as a QR code).
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={secret}&issuer={}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECS}",
+        urlencoding::encode(issuer),
+        urlencoding::encode(account),
+        urlencoding::encode(issuer),
+    )
+}
+
+// =============================================================================
+// Recovery codes
+// =============================================================================
+
+/// Strip the separators people type or paste into codes.
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Generate a fresh set of recovery codes (`xxxxx-xxxxx`).
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 7];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let code = base32_encode(&bytes).to_ascii_lowercase();
+            format!("{}-{}", &code[..5], &code[5..10])
+        })
+        .collect()
+}
+
+/// Hash a recovery code for storage or lookup.
+pub fn hash_recovery_code(code: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(normalize_code(code).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// =============================================================================
+// Storage
+// =============================================================================
+
+/// A user's TOTP authenticator.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UserMfa {
+    /// User the enrollment belongs to.
+    pub user_id: Uuid,
+    /// Base32 secret shared with the authenticator app.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// `false` until the user confirms a first code.
+    pub enabled: bool,
+    /// Last TOTP step accepted (replay protection).
+    #[serde(skip_serializing)]
+    pub last_step: i64,
+    /// Unix timestamp when enrollment started.
+    pub created: i64,
+    /// Unix timestamp of the last change.
+    pub changed: i64,
+}
+
+impl UserMfa {
+    /// Load a user's authenticator, enabled or pending.
+    pub async fn find(pool: &PgPool, user_id: Uuid) -> Result<Option<Self>> {
+        let mfa = sqlx::query_as::<_, Self>(
+            "SELECT user_id, secret, enabled, last_step, created, changed FROM user_mfa WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to load user_mfa")?;
+
+        Ok(mfa)
+    }
+
+    /// Whether the user has confirmed an authenticator.
+    pub async fn is_enabled(pool: &PgPool, user_id: Uuid) -> Result<bool> {
+        Ok(Self::find(pool, user_id).await?.is_some_and(|m| m.enabled))
+    }
+
+    /// Start (or restart) enrollment with a new secret.
+    ///
+    /// Fails if the user already has an enabled authenticator; it must be
+    /// disabled first.
+    pub async fn begin_enrollment(pool: &PgPool, user_id: Uuid) -> Result<Self> {
+        let now = chrono::Utc::now().timestamp();
+        let mfa = sqlx::query_as::<_, Self>(
+            r#"
+            INSERT INTO user_mfa (user_id, secret, enabled, last_step, created, changed)
+            VALUES ($1, $2, FALSE, 0, $3, $3)
+            ON CONFLICT (user_id) DO UPDATE
+                SET secret = EXCLUDED.secret, last_step = 0, created = EXCLUDED.created,
+                    changed = EXCLUDED.changed
+                WHERE user_mfa.enabled = FALSE
+            RETURNING user_id, secret, enabled, last_step, created, changed
+            "#,
+        )
+        .bind(user_id)
+        .bind(generate_secret())
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+        .context("failed to start 2FA enrollment")?;
+
+        match mfa {
+            Some(mfa) => Ok(mfa),
+            None => bail!("two-factor authentication is already enabled"),
+        }
+    }
+
+    /// Confirm enrollment with a code from the authenticator.
+    ///
+    /// Returns the new recovery codes, or `None` if there is no pending
+    /// enrollment or the code is wrong.
+    pub async fn confirm_enrollment(
+        pool: &PgPool,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<Option<Vec<String>>> {
+        let Some(mfa) = Self::find(pool, user_id).await? else {
+            return Ok(None);
+        };
+        if mfa.enabled {
+            return Ok(None);
+        }
+        let now = chrono::Utc::now().timestamp();
+        let Some(step) = verify_totp(&mfa.secret, code, now, mfa.last_step) else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "UPDATE user_mfa SET enabled = TRUE, last_step = $2, changed = $3 WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .bind(step)
+        .bind(now)
+        .execute(pool)
+        .await
+        .context("failed to enable 2FA")?;
+
+        Self::replace_recovery_codes(pool, user_id).await.map(Some)
+    }
+
+    /// Check a login code: a TOTP code or an unused recovery code.
+    ///
+    /// A matching TOTP step is recorded so it cannot be used again; a
+    /// matching recovery code is consumed.
+    pub async fn verify(pool: &PgPool, user_id: Uuid, code: &str) -> Result<bool> {
+        let Some(mfa) = Self::find(pool, user_id).await? else {
+            return Ok(false);
+        };
+        if !mfa.enabled {
+            return Ok(false);
+        }
+        let now = chrono::Utc::now().timestamp();
+
+        if let Some(step) = verify_totp(&mfa.secret, code, now, mfa.last_step) {
+            // The guard makes concurrent submissions of one code race to a
+            // single winner.
+            let result = sqlx::query(
+                "UPDATE user_mfa SET last_step = $2 WHERE user_id = $1 AND last_step < $2",
+            )
+            .bind(user_id)
+            .bind(step)
+            .execute(pool)
+            .await
+            .context("failed to record TOTP step")?;
+            return Ok(result.rows_affected() > 0);
+        }
+
+        let result = sqlx::query(
+            r#"
+            UPDATE user_mfa_recovery_code SET used = $3
+            WHERE user_id = $1 AND code_hash = $2 AND used IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(hash_recovery_code(code))
+        .bind(now)
+        .execute(pool)
+        .await
+        .context("failed to consume recovery code")?;
+
+        if result.rows_affected() > 0 {
+            tracing::info!(user_id = %user_id, "2FA recovery code used");
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Replace all recovery codes with a fresh set and return it.
+    pub async fn replace_recovery_codes(pool: &PgPool, user_id: Uuid) -> Result<Vec<String>> {
+        let codes = generate_recovery_codes();
+        let now = chrono::Utc::now().timestamp();
+
+        let mut tx = pool.begin().await.context("failed to begin transaction")?;
+        sqlx::query("DELETE FROM user_mfa_recovery_code WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .context("failed to delete recovery codes")?;
+        for code in &codes {
+            sqlx::query(
+                r#"
+                INSERT INTO user_mfa_recovery_code (id, user_id, code_hash, created)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(Uuid::now_v7())
+            .bind(user_id)
+            .bind(hash_recovery_code(code))
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("failed to store recovery code")?;
+        }
+        tx.commit()
+            .await
+            .context("failed to commit recovery codes")?;
+
+        Ok(codes)
+    }
+
+    /// Count unused recovery codes.
+    pub async fn remaining_recovery_codes(pool: &PgPool, user_id: Uuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_mfa_recovery_code WHERE user_id = $1 AND used IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .context("failed to count recovery codes")?;
+
+        Ok(count)
+    }
+
+    /// Remove a user's authenticator and recovery codes.
+    pub async fn disable(pool: &PgPool, user_id: Uuid) -> Result<bool> {
+        let mut tx = pool.begin().await.context("failed to begin transaction")?;
+        sqlx::query("DELETE FROM user_mfa_recovery_code WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .context("failed to delete recovery codes")?;
+        let result = sqlx::query("DELETE FROM user_mfa WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .context("failed to delete user_mfa")?;
+        tx.commit().await.context("failed to commit 2FA removal")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List users with an enabled authenticator, by username.
+    pub async fn list_enabled(pool: &PgPool) -> Result<Vec<MfaEnrollment>> {
+        let enrollments = sqlx::query_as::<_, MfaEnrollment>(
+            r#"
+            SELECT m.user_id, u.name, m.changed
+            FROM user_mfa m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.enabled = TRUE
+            ORDER BY u.name
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to list 2FA enrollments")?;
+
+        Ok(enrollments)
+    }
+}
+
+/// An enabled authenticator, for the admin overview.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MfaEnrollment {
+    pub user_id: Uuid,
+    /// Username.
+    pub name: String,
+    /// Unix timestamp when 2FA was enabled.
+    pub changed: i64,
+}
+
+// =============================================================================
+// Enforcement
+// =============================================================================
+
+/// Roles whose members must use 2FA.
+pub async fn required_roles(pool: &PgPool) -> Result<Vec<Uuid>> {
+    let roles = SiteConfig::get(pool, MFA_REQUIRED_ROLES_KEY)
+        .await?
+        .and_then(|v| serde_json::from_value::<Vec<Uuid>>(v).ok())
+        .unwrap_or_default();
+    Ok(roles)
+}
+
+/// Set the roles whose members must use 2FA.
+pub async fn set_required_roles(pool: &PgPool, roles: &[Uuid]) -> Result<()> {
+    SiteConfig::set(pool, MFA_REQUIRED_ROLES_KEY, serde_json::to_value(roles)?).await
+}
+
+/// Whether a user holds one of the required roles.
+///
+/// Requiring the authenticated role enforces 2FA for every account.
+pub async fn is_required_for(pool: &PgPool, user: &User) -> Result<bool> {
+    let required = required_roles(pool).await?;
+    if required.is_empty() {
+        return Ok(false);
+    }
+    let mut role_ids: Vec<Uuid> = Role::get_user_roles(pool, user.id)
+        .await?
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    role_ids.push(AUTHENTICATED_ROLE_ID);
+    Ok(requires_mfa(&required, &role_ids))
+}
+
+/// Whether any of `role_ids` is in `required`.
+fn requires_mfa(required: &[Uuid], role_ids: &[Uuid]) -> bool {
+    role_ids.iter().any(|id| required.contains(id))
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 appendix B SHA-1 secret ("12345678901234567890").
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn base32_round_trip() {
+        assert_eq!(base32_encode(b"12345678901234567890"), RFC_SECRET);
+        assert_eq!(base32_encode(b"f"), "MY");
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw 6ytb oi======").unwrap(), b"foobar");
+        assert!(base32_decode("not base32!").is_none());
+
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), SECRET_BYTES);
+    }
+
+    #[test]
+    fn totp_matches_rfc_6238_vectors() {
+        let key = base32_decode(RFC_SECRET).unwrap();
+        // Last six digits of the RFC's eight-digit values.
+        for (time, expected) in [
+            (59, 287_082),
+            (1_111_111_109, 81_804),
+            (1_234_567_890, 5_924),
+            (2_000_000_000, 279_037),
+        ] {
+            assert_eq!(
+                totp(&key, time / TOTP_STEP_SECS),
+                Some(expected),
+                "t={time}"
+            );
+        }
+    }
+
+    #[test]
+    fn verify_accepts_skew_and_rejects_replay() {
+        let now = 1_111_111_109;
+        let step = now / TOTP_STEP_SECS;
+        assert_eq!(verify_totp(RFC_SECRET, "081804", now, 0), Some(step));
+        assert_eq!(verify_totp(RFC_SECRET, "081 804", now + 30, 0), Some(step));
+        assert_eq!(verify_totp(RFC_SECRET, "081804", now + 90, 0), None);
+        assert_eq!(verify_totp(RFC_SECRET, "081804", now, step), None);
+        assert_eq!(verify_totp(RFC_SECRET, "81804", now, 0), None);
+        assert_eq!(verify_totp(RFC_SECRET, "abcdef", now, 0), None);
+    }
+
+    #[test]
+    fn recovery_codes_are_unique_and_hash_normalized() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(
+            codes
+                .iter()
+                .all(|c| c.len() == 11 && c.as_bytes()[5] == b'-')
+        );
+        let mut unique = codes.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), codes.len());
+
+        assert_eq!(
+            hash_recovery_code("abcde-fghij"),
+            hash_recovery_code(" ABCDE FGHIJ ")
+        );
+        assert_ne!(
+            hash_recovery_code("abcde-fghij"),
+            hash_recovery_code("abcde-fghik")
+        );
+    }
+
+    #[test]
+    fn provisioning_uri_escapes_labels() {
+        let uri = provisioning_uri("My Site", "ada@example.com", "ABC");
+        assert_eq!(
+            uri,
+            "otpauth://totp/My%20Site:ada%40example.com?secret=ABC&issuer=My%20Site&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn role_requirement() {
+        let editors = Uuid::from_u128(10);
+        assert!(!requires_mfa(&[], &[editors, AUTHENTICATED_ROLE_ID]));
+        assert!(requires_mfa(&[editors], &[editors, AUTHENTICATED_ROLE_ID]));
+        assert!(requires_mfa(
+            &[AUTHENTICATED_ROLE_ID],
+            &[AUTHENTICATED_ROLE_ID]
+        ));
+        assert!(!requires_mfa(&[editors], &[AUTHENTICATED_ROLE_ID]));
+    }
+}
@@ -9,16 +9,42 @@ pub mod ai_chat;
 pub mod ai_provider;
 pub mod ai_token_budget;
 pub mod audit;
+pub mod cascade;
 pub mod comment;
+pub mod comment_spam;
+pub mod contact;
+pub mod content_listing;
 pub mod content_lock;
+pub mod deprecation;
 pub mod email;
 pub mod email_templates;
+pub mod flag;
+pub mod goose_compare;
 pub mod image_style;
+pub mod item_bulk;
 pub mod locale;
+pub mod mac_vendor;
+pub mod mail;
+pub mod mfa;
 pub mod oauth;
+pub mod oidc;
+pub mod pagination;
+pub mod password_policy;
 pub mod pathauto;
+pub mod read_log;
+pub mod read_only;
 pub mod redirect;
+pub mod registration;
 pub mod role;
+pub mod saved_search;
+pub mod scheduled_publishing;
+pub mod scheduled_update;
+pub mod site;
+pub mod slug;
+pub mod status_report;
 pub mod tile;
 pub mod user;
+pub mod user_fields;
 pub mod vector_store;
+pub mod webhook;
+pub mod workspace;
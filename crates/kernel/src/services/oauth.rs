@@ -9,6 +9,8 @@ use sqlx::PgPool;
 use tracing::debug;
 use uuid::Uuid;
 
+use crate::redis_manager::RedisManager;
+
 /// JWT issuer claim value.
 const ISSUER: &str = "trovato";
 
@@ -345,7 +347,7 @@ impl OAuthService {
         redirect_uri: &str,
         code_challenge: Option<&str>,
         code_challenge_method: Option<&str>,
-        redis_client: &redis::Client,
+        redis_client: &RedisManager,
     ) -> Result<String> {
         // Use UUID v4 (122 bits of randomness) rather than v7 (which embeds a
         // predictable timestamp) per RFC 6749 §10.10 guidance on code entropy.
@@ -361,7 +363,7 @@ impl OAuthService {
         let json = serde_json::to_string(&data).context("failed to serialize auth code data")?;
 
         let mut conn = redis_client
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -396,10 +398,10 @@ impl OAuthService {
         client_secret: &str,
         redirect_uri: &str,
         code_verifier: &str,
-        redis_client: &redis::Client,
+        redis_client: &RedisManager,
     ) -> Result<TokenResponse> {
         let mut conn = redis_client
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -472,11 +474,11 @@ impl OAuthService {
     }
 
     /// Check if a token's JTI has been revoked (via Redis blocklist).
-    pub async fn is_revoked(&self, jti: &str, redis: &redis::Client) -> Result<bool> {
+    pub async fn is_revoked(&self, jti: &str, redis: &RedisManager) -> Result<bool> {
         Self::validate_jti(jti)?;
 
         let mut conn = redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
@@ -490,16 +492,11 @@ impl OAuthService {
     }
 
     /// Revoke a token by adding its JTI to the Redis blocklist.
-    pub async fn revoke_token(
-        &self,
-        jti: &str,
-        ttl_secs: u64,
-        redis: &redis::Client,
-    ) -> Result<()> {
+    pub async fn revoke_token(&self, jti: &str, ttl_secs: u64, redis: &RedisManager) -> Result<()> {
         Self::validate_jti(jti)?;
 
         let mut conn = redis
-            .get_multiplexed_async_connection()
+            .get()
             .await
             .context("failed to get Redis connection")?;
 
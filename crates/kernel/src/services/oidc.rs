@@ -0,0 +1,839 @@
+//! OpenID Connect client for logging in through external identity providers.
+//!
+//! Providers (Google, Keycloak, any OIDC-compliant issuer) are configured in
+//! `site_config` under `oidc_providers`. Like AI providers, the client
+//! secret is referenced by environment variable name and never stored.
+//!
+//! Login uses the authorization code flow with PKCE: [`OidcService::begin_login`]
+//! builds the authorization URL and the state kept in the session, and
+//! [`OidcService::finish_login`] exchanges the code and verifies the ID
+//! token against the provider's JWKS (signature, issuer, audience, expiry
+//! and nonce). External identities are linked to local accounts in the
+//! `user_identity` table by the provider's `sub` claim.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::models::SiteConfig;
+use crate::services::ai_provider::{validate_base_url, validate_env_var_name};
+
+/// Site config key holding the provider list.
+const CONFIG_KEY_PROVIDERS: &str = "oidc_providers";
+
+/// Session key holding the [`PendingLogin`] of a login in progress.
+pub const SESSION_OIDC_LOGIN: &str = "oidc_login";
+
+/// Seconds a started login may take before the callback is refused.
+pub const PENDING_LOGIN_TTL: i64 = 600;
+
+/// Longest provider machine name.
+const MAX_ID_LEN: usize = 64;
+
+/// How long discovery documents and key sets are cached.
+const METADATA_TTL: Duration = Duration::from_secs(3600);
+
+/// Longest generated username, leaving room for a numeric suffix.
+const MAX_USERNAME_BASE_LEN: usize = 50;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Grants a local role when a provider claim contains a value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoleMapping {
+    /// Value looked for in the provider's role claim (e.g. a group name).
+    pub claim_value: String,
+    /// Name of the local role granted.
+    pub role: String,
+}
+
+/// Persisted provider configuration (stored as JSONB in `site_config`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcProviderConfig {
+    /// Machine name used in the login URLs (`/auth/{id}/login`).
+    pub id: String,
+    /// Shown on the login button (e.g. "Google").
+    pub label: String,
+    /// Issuer URL; `{issuer}/.well-known/openid-configuration` must exist.
+    pub issuer: String,
+    /// Client ID registered with the provider.
+    pub client_id: String,
+    /// Name of the environment variable holding the client secret. Empty
+    /// for public clients, which rely on PKCE alone.
+    #[serde(default)]
+    pub client_secret_env: String,
+    /// Requested scopes; must include `openid`.
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    /// Link a login to an existing account with the same email address,
+    /// when the provider reports the address as verified. Off unless the
+    /// provider is trusted to verify addresses; administrator accounts are
+    /// never linked this way.
+    #[serde(default)]
+    pub link_by_email: bool,
+    /// Create an account for a login that matches none, as far as the
+    /// site's registration mode allows.
+    #[serde(default)]
+    pub allow_registration: bool,
+    /// Claim holding the user's groups or roles, as a dotted path into the
+    /// ID token (e.g. `groups` or `realm_access.roles`).
+    #[serde(default)]
+    pub role_claim: Option<String>,
+    /// Local roles granted from the role claim.
+    #[serde(default)]
+    pub role_map: Vec<RoleMapping>,
+    /// Whether the provider is offered on the login form.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec!["openid".into(), "email".into(), "profile".into()]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl OidcProviderConfig {
+    /// Check the configuration before it is saved.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() || self.id.len() > MAX_ID_LEN {
+            return Err(format!("id must be 1 to {MAX_ID_LEN} characters"));
+        }
+        if !self
+            .id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            return Err(
+                "id may only contain lowercase letters, digits, underscores and hyphens"
+                    .to_string(),
+            );
+        }
+        if self.label.trim().is_empty() {
+            return Err("label is required".to_string());
+        }
+        validate_base_url(&self.issuer)?;
+        if self.client_id.trim().is_empty() {
+            return Err("client_id is required".to_string());
+        }
+        validate_env_var_name(&self.client_secret_env)?;
+        if !self.scopes.iter().any(|s| s == "openid") {
+            return Err("scopes must include openid".to_string());
+        }
+        if !self.role_map.is_empty() && self.role_claim.as_deref().is_none_or(str::is_empty) {
+            return Err("role_claim is required when role_map is set".to_string());
+        }
+        if self
+            .role_map
+            .iter()
+            .any(|m| m.claim_value.is_empty() || m.role.is_empty())
+        {
+            return Err("role_map entries need a claim_value and a role".to_string());
+        }
+        Ok(())
+    }
+
+    /// The client secret from the environment, if one is configured.
+    pub fn client_secret(&self) -> Option<String> {
+        if self.client_secret_env.is_empty() {
+            return None;
+        }
+        std::env::var(&self.client_secret_env).ok()
+    }
+
+    /// Names of all roles the mapping manages.
+    pub fn managed_roles(&self) -> Vec<&str> {
+        let mut roles: Vec<&str> = self.role_map.iter().map(|m| m.role.as_str()).collect();
+        roles.sort_unstable();
+        roles.dedup();
+        roles
+    }
+
+    /// Names of the mapped roles the claims grant.
+    pub fn granted_roles(&self, claims: &serde_json::Value) -> Vec<&str> {
+        let Some(path) = self.role_claim.as_deref() else {
+            return Vec::new();
+        };
+        let values = claim_values(claims, path);
+        let mut roles: Vec<&str> = self
+            .role_map
+            .iter()
+            .filter(|m| values.iter().any(|v| *v == m.claim_value))
+            .map(|m| m.role.as_str())
+            .collect();
+        roles.sort_unstable();
+        roles.dedup();
+        roles
+    }
+}
+
+/// String values of a claim at a dotted path: a single string, or the
+/// strings of an array.
+fn claim_values<'a>(claims: &'a serde_json::Value, path: &str) -> Vec<&'a str> {
+    let pointer = format!("/{}", path.replace('.', "/"));
+    match claims.pointer(&pointer) {
+        Some(serde_json::Value::String(s)) => vec![s.as_str()],
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// =============================================================================
+// Login flow types
+// =============================================================================
+
+/// The parts of a provider's discovery document the login uses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// A login in progress, kept in the session until the callback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingLogin {
+    /// Provider machine name.
+    pub provider: String,
+    /// Sent as `state`; the callback must return it.
+    pub state: String,
+    /// Sent as `nonce`; the ID token must contain it.
+    pub nonce: String,
+    /// PKCE code verifier.
+    pub code_verifier: String,
+    /// Unix timestamp the login started.
+    pub started: i64,
+}
+
+impl PendingLogin {
+    /// Whether the login is too old to finish at `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        now - self.started > PENDING_LOGIN_TTL
+    }
+}
+
+/// Verified claims of a provider's ID token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    /// The provider's stable identifier of the user.
+    pub sub: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    /// A boolean, or the string `"true"` on some providers.
+    #[serde(default)]
+    email_verified: Option<serde_json::Value>,
+    #[serde(default)]
+    pub preferred_username: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    nonce: Option<String>,
+    /// All claims, for role mapping.
+    #[serde(skip)]
+    pub raw: serde_json::Value,
+}
+
+impl IdTokenClaims {
+    /// The email address, if the provider verified it.
+    pub fn verified_email(&self) -> Option<&str> {
+        let verified = match &self.email_verified {
+            Some(serde_json::Value::Bool(b)) => *b,
+            Some(serde_json::Value::String(s)) => s == "true",
+            _ => false,
+        };
+        self.email.as_deref().filter(|_| verified)
+    }
+
+    /// A username to give a new account, before making it unique: the
+    /// preferred username, else the email's local part, reduced to the
+    /// characters usernames allow.
+    pub fn username_base(&self) -> String {
+        let source = self
+            .preferred_username
+            .as_deref()
+            .or_else(|| self.email.as_deref().and_then(|m| m.split('@').next()))
+            .unwrap_or_default();
+        let base: String = source
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            .take(MAX_USERNAME_BASE_LEN)
+            .collect();
+        if base.len() < 2 {
+            "user".to_string()
+        } else {
+            base
+        }
+    }
+}
+
+/// An external identity linked to a local account.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UserIdentity {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub created: i64,
+    pub last_login: Option<i64>,
+}
+
+/// Random URL-safe value for `state`, `nonce` and the PKCE verifier.
+fn random_value() -> String {
+    let bytes: [u8; 32] = rand::random();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// S256 PKCE challenge of a verifier (RFC 7636).
+fn pkce_challenge(verifier: &str) -> String {
+    let digest = Sha256::digest(verifier.as_bytes());
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest.as_slice())
+}
+
+/// Build the authorization request URL.
+fn authorization_url(
+    metadata: &ProviderMetadata,
+    provider: &OidcProviderConfig,
+    redirect_uri: &str,
+    pending: &PendingLogin,
+) -> Result<String> {
+    let mut url = url::Url::parse(&metadata.authorization_endpoint)
+        .context("invalid authorization endpoint")?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("scope", &provider.scopes.join(" "))
+        .append_pair("state", &pending.state)
+        .append_pair("nonce", &pending.nonce)
+        .append_pair("code_challenge", &pkce_challenge(&pending.code_verifier))
+        .append_pair("code_challenge_method", "S256");
+    Ok(url.into())
+}
+
+// =============================================================================
+// Service
+// =============================================================================
+
+/// Service for OIDC provider configuration, login and linked identities.
+pub struct OidcService {
+    db: PgPool,
+    http: reqwest::Client,
+    /// Serializes read-modify-write operations on the provider list.
+    write_lock: Mutex<()>,
+    /// Discovery documents by issuer.
+    metadata: Cache<String, Arc<ProviderMetadata>>,
+    /// Signing keys by JWKS URI.
+    jwks: Cache<String, Arc<JwkSet>>,
+}
+
+impl OidcService {
+    /// Create a new OIDC service.
+    pub fn new(db: PgPool) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            http,
+            write_lock: Mutex::new(()),
+            metadata: Cache::builder()
+                .max_capacity(64)
+                .time_to_live(METADATA_TTL)
+                .build(),
+            jwks: Cache::builder()
+                .max_capacity(64)
+                .time_to_live(METADATA_TTL)
+                .build(),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Providers
+    // -------------------------------------------------------------------------
+
+    /// List all configured providers.
+    pub async fn list_providers(&self) -> Result<Vec<OidcProviderConfig>> {
+        let value = SiteConfig::get(&self.db, CONFIG_KEY_PROVIDERS)
+            .await
+            .context("failed to read oidc_providers config")?;
+        match value {
+            Some(v) => serde_json::from_value(v).context("failed to parse oidc_providers config"),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Providers offered on the login form.
+    pub async fn enabled_providers(&self) -> Result<Vec<OidcProviderConfig>> {
+        let mut providers = self.list_providers().await?;
+        providers.retain(|p| p.enabled);
+        Ok(providers)
+    }
+
+    /// Get an enabled provider by machine name.
+    pub async fn get_enabled(&self, id: &str) -> Result<Option<OidcProviderConfig>> {
+        let providers = self.enabled_providers().await?;
+        Ok(providers.into_iter().find(|p| p.id == id))
+    }
+
+    /// Create or update a provider (upsert by `config.id`).
+    pub async fn save_provider(&self, config: OidcProviderConfig) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+
+        let mut providers = self.list_providers().await?;
+        if let Some(existing) = providers.iter_mut().find(|p| p.id == config.id) {
+            *existing = config;
+        } else {
+            providers.push(config);
+        }
+
+        let value =
+            serde_json::to_value(&providers).context("failed to serialize oidc_providers")?;
+        SiteConfig::set(&self.db, CONFIG_KEY_PROVIDERS, value)
+            .await
+            .context("failed to save oidc_providers config")
+    }
+
+    /// Delete a provider. Returns `true` if found and removed.
+    ///
+    /// Linked identities are kept, so re-adding the provider restores them.
+    pub async fn delete_provider(&self, id: &str) -> Result<bool> {
+        let _guard = self.write_lock.lock().await;
+
+        let mut providers = self.list_providers().await?;
+        let len_before = providers.len();
+        providers.retain(|p| p.id != id);
+        if providers.len() == len_before {
+            return Ok(false);
+        }
+
+        let value =
+            serde_json::to_value(&providers).context("failed to serialize oidc_providers")?;
+        SiteConfig::set(&self.db, CONFIG_KEY_PROVIDERS, value)
+            .await
+            .context("failed to save oidc_providers config")?;
+        Ok(true)
+    }
+
+    // -------------------------------------------------------------------------
+    // Login
+    // -------------------------------------------------------------------------
+
+    /// Start a login: the URL to send the browser to, and the state to keep
+    /// in the session until the callback.
+    pub async fn begin_login(
+        &self,
+        provider: &OidcProviderConfig,
+        redirect_uri: &str,
+    ) -> Result<(String, PendingLogin)> {
+        let metadata = self.provider_metadata(provider).await?;
+        let pending = PendingLogin {
+            provider: provider.id.clone(),
+            state: random_value(),
+            nonce: random_value(),
+            code_verifier: random_value(),
+            started: chrono::Utc::now().timestamp(),
+        };
+        let url = authorization_url(&metadata, provider, redirect_uri, &pending)?;
+        Ok((url, pending))
+    }
+
+    /// Finish a login: exchange the authorization code and verify the ID
+    /// token it returns.
+    pub async fn finish_login(
+        &self,
+        provider: &OidcProviderConfig,
+        pending: &PendingLogin,
+        code: &str,
+        redirect_uri: &str,
+    ) -> Result<IdTokenClaims> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            id_token: String,
+        }
+
+        let metadata = self.provider_metadata(provider).await?;
+        let secret = provider.client_secret();
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", provider.client_id.as_str()),
+            ("code_verifier", pending.code_verifier.as_str()),
+        ];
+        if let Some(secret) = secret.as_deref() {
+            form.push(("client_secret", secret));
+        }
+
+        let response: TokenResponse = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .context("failed to reach token endpoint")?
+            .error_for_status()
+            .context("token endpoint rejected the code")?
+            .json()
+            .await
+            .context("failed to parse token response")?;
+
+        let claims = self
+            .verify_id_token(provider, &metadata, &response.id_token)
+            .await?;
+        if claims.nonce.as_deref() != Some(pending.nonce.as_str()) {
+            bail!("ID token nonce does not match");
+        }
+        Ok(claims)
+    }
+
+    /// Verify an ID token's signature and standard claims.
+    async fn verify_id_token(
+        &self,
+        provider: &OidcProviderConfig,
+        metadata: &ProviderMetadata,
+        token: &str,
+    ) -> Result<IdTokenClaims> {
+        let header = jsonwebtoken::decode_header(token).context("malformed ID token")?;
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            bail!("ID tokens signed with a shared secret are not supported");
+        }
+
+        let key = match self
+            .signing_key(metadata, header.kid.as_deref(), false)
+            .await?
+        {
+            Some(key) => key,
+            // The provider may have rotated its keys since they were cached.
+            None => self
+                .signing_key(metadata, header.kid.as_deref(), true)
+                .await?
+                .context("no matching signing key in the provider's JWKS")?,
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[provider.client_id.as_str()]);
+        validation.set_issuer(&[metadata.issuer.as_str()]);
+        let data = jsonwebtoken::decode::<serde_json::Value>(token, &key, &validation)
+            .context("invalid ID token")?;
+
+        let mut claims: IdTokenClaims =
+            serde_json::from_value(data.claims.clone()).context("invalid ID token claims")?;
+        claims.raw = data.claims;
+        Ok(claims)
+    }
+
+    /// The provider's discovery document, cached by issuer.
+    async fn provider_metadata(
+        &self,
+        provider: &OidcProviderConfig,
+    ) -> Result<Arc<ProviderMetadata>> {
+        if let Some(metadata) = self.metadata.get(&provider.issuer).await {
+            return Ok(metadata);
+        }
+
+        validate_base_url(&provider.issuer).map_err(anyhow::Error::msg)?;
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            provider.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .context("failed to fetch OIDC discovery document")?
+            .error_for_status()
+            .context("OIDC discovery document unavailable")?
+            .json()
+            .await
+            .context("failed to parse OIDC discovery document")?;
+        validate_base_url(&metadata.token_endpoint).map_err(anyhow::Error::msg)?;
+        validate_base_url(&metadata.jwks_uri).map_err(anyhow::Error::msg)?;
+
+        let metadata = Arc::new(metadata);
+        self.metadata
+            .insert(provider.issuer.clone(), metadata.clone())
+            .await;
+        Ok(metadata)
+    }
+
+    /// The key with ID `kid` (or the only key, without one) from the
+    /// provider's JWKS. `refresh` bypasses the cache.
+    async fn signing_key(
+        &self,
+        metadata: &ProviderMetadata,
+        kid: Option<&str>,
+        refresh: bool,
+    ) -> Result<Option<DecodingKey>> {
+        let cached = if refresh {
+            None
+        } else {
+            self.jwks.get(&metadata.jwks_uri).await
+        };
+        let jwks = match cached {
+            Some(jwks) => jwks,
+            None => {
+                let jwks: JwkSet = self
+                    .http
+                    .get(&metadata.jwks_uri)
+                    .send()
+                    .await
+                    .context("failed to fetch JWKS")?
+                    .error_for_status()
+                    .context("JWKS unavailable")?
+                    .json()
+                    .await
+                    .context("failed to parse JWKS")?;
+                let jwks = Arc::new(jwks);
+                self.jwks
+                    .insert(metadata.jwks_uri.clone(), jwks.clone())
+                    .await;
+                jwks
+            }
+        };
+
+        let jwk = match kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        };
+        jwk.map(|jwk| DecodingKey::from_jwk(jwk).context("unusable signing key"))
+            .transpose()
+    }
+
+    // -------------------------------------------------------------------------
+    // Identities
+    // -------------------------------------------------------------------------
+
+    /// Find the identity of a provider's subject.
+    pub async fn find_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>> {
+        sqlx::query_as::<_, UserIdentity>(
+            "SELECT * FROM user_identity WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&self.db)
+        .await
+        .context("failed to load user identity")
+    }
+
+    /// Link a provider's subject to a local account.
+    pub async fn link_identity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        claims: &IdTokenClaims,
+    ) -> Result<UserIdentity> {
+        let now = chrono::Utc::now().timestamp();
+        sqlx::query_as::<_, UserIdentity>(
+            r#"
+            INSERT INTO user_identity (id, user_id, provider, subject, email, created, last_login)
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(user_id)
+        .bind(provider)
+        .bind(&claims.sub)
+        .bind(&claims.email)
+        .bind(now)
+        .fetch_one(&self.db)
+        .await
+        .context("failed to link user identity")
+    }
+
+    /// Record a login through an identity.
+    pub async fn touch_identity(&self, id: Uuid, email: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "UPDATE user_identity SET last_login = $2, email = COALESCE($3, email) WHERE id = $1",
+        )
+        .bind(id)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(email)
+        .execute(&self.db)
+        .await
+        .context("failed to record identity login")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn provider() -> OidcProviderConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": "keycloak",
+            "label": "Company login",
+            "issuer": "https://sso.example.com/realms/main",
+            "client_id": "trovato",
+            "client_secret_env": "KEYCLOAK_SECRET",
+        }))
+        .unwrap()
+    }
+
+    fn claims(value: serde_json::Value) -> IdTokenClaims {
+        let mut claims: IdTokenClaims = serde_json::from_value(value.clone()).unwrap();
+        claims.raw = value;
+        claims
+    }
+
+    #[test]
+    fn provider_defaults_and_validation() {
+        let config = provider();
+        assert_eq!(config.scopes, vec!["openid", "email", "profile"]);
+        assert!(!config.link_by_email);
+        assert!(!config.allow_registration);
+        assert!(config.enabled);
+        assert_eq!(config.validate(), Ok(()));
+
+        let mut bad = provider();
+        bad.id = "Key Cloak".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = provider();
+        bad.scopes = vec!["email".to_string()];
+        assert!(bad.validate().is_err());
+
+        let mut bad = provider();
+        bad.issuer = "http://127.0.0.1:8080".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = provider();
+        bad.client_secret_env = "DATABASE_URL".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = provider();
+        bad.role_map = vec![RoleMapping {
+            claim_value: "editors".to_string(),
+            role: "editor".to_string(),
+        }];
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn role_mapping_reads_nested_and_flat_claims() {
+        let mut config = provider();
+        config.role_claim = Some("realm_access.roles".to_string());
+        config.role_map = vec![
+            RoleMapping {
+                claim_value: "cms-editors".to_string(),
+                role: "editor".to_string(),
+            },
+            RoleMapping {
+                claim_value: "cms-admins".to_string(),
+                role: "site admin".to_string(),
+            },
+        ];
+        assert_eq!(config.managed_roles(), vec!["editor", "site admin"]);
+
+        let nested = serde_json::json!({"realm_access": {"roles": ["cms-editors", "other"]}});
+        assert_eq!(config.granted_roles(&nested), vec!["editor"]);
+
+        config.role_claim = Some("hd".to_string());
+        let flat = serde_json::json!({"hd": "cms-admins"});
+        assert_eq!(config.granted_roles(&flat), vec!["site admin"]);
+        assert!(config.granted_roles(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn email_is_only_trusted_when_verified() {
+        let unverified = claims(serde_json::json!({"sub": "1", "email": "a@example.com"}));
+        assert_eq!(unverified.verified_email(), None);
+
+        let verified = claims(serde_json::json!({
+            "sub": "1", "email": "a@example.com", "email_verified": true
+        }));
+        assert_eq!(verified.verified_email(), Some("a@example.com"));
+
+        let as_string = claims(serde_json::json!({
+            "sub": "1", "email": "a@example.com", "email_verified": "true"
+        }));
+        assert_eq!(as_string.verified_email(), Some("a@example.com"));
+    }
+
+    #[test]
+    fn username_base_is_sanitized() {
+        let preferred = claims(serde_json::json!({"sub": "1", "preferred_username": "Jo Ann!"}));
+        assert_eq!(preferred.username_base(), "JoAnn");
+
+        let from_mail = claims(serde_json::json!({"sub": "1", "email": "ada.l@example.com"}));
+        assert_eq!(from_mail.username_base(), "ada.l");
+
+        let none = claims(serde_json::json!({"sub": "1"}));
+        assert_eq!(none.username_base(), "user");
+    }
+
+    #[test]
+    fn authorization_url_carries_pkce_and_state() {
+        let metadata = ProviderMetadata {
+            issuer: "https://sso.example.com/realms/main".to_string(),
+            authorization_endpoint: "https://sso.example.com/auth?kc_idp_hint=x".to_string(),
+            token_endpoint: "https://sso.example.com/token".to_string(),
+            jwks_uri: "https://sso.example.com/certs".to_string(),
+        };
+        let pending = PendingLogin {
+            provider: "keycloak".to_string(),
+            state: "s1".to_string(),
+            nonce: "n1".to_string(),
+            code_verifier: random_value(),
+            started: 1000,
+        };
+        let url = authorization_url(
+            &metadata,
+            &provider(),
+            "https://cms.example.com/auth/keycloak/callback",
+            &pending,
+        )
+        .unwrap();
+        let parsed = url::Url::parse(&url).unwrap();
+        let query: std::collections::HashMap<_, _> = parsed.query_pairs().into_owned().collect();
+
+        assert_eq!(query["kc_idp_hint"], "x");
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], "trovato");
+        assert_eq!(query["scope"], "openid email profile");
+        assert_eq!(query["state"], "s1");
+        assert_eq!(query["nonce"], "n1");
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert!(crate::services::oauth::verify_pkce(
+            &query["code_challenge"],
+            "S256",
+            &pending.code_verifier
+        ));
+
+        assert!(!pending.is_expired(1000 + PENDING_LOGIN_TTL));
+        assert!(pending.is_expired(1001 + PENDING_LOGIN_TTL));
+    }
+}
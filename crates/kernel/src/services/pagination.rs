@@ -0,0 +1,243 @@
+//! Central pagination policy.
+//!
+//! Every listing endpoint belongs to a [`PageClass`] with a default and a
+//! maximum page size. Requested sizes above the maximum are clamped rather
+//! than rejected; JSON endpoints report the clamp in a `warning` field so
+//! clients can notice that they asked for too much.
+//!
+//! The limits live in `site_config` under the key `pagination_policy`:
+//!
+//! ```json
+//! {
+//!   "search": { "default": 10, "max": 50 },
+//!   "gather": { "default": 10, "max": 100 },
+//!   "admin":  { "default": 25, "max": 100 },
+//!   "api":    { "default": 25, "max": 100 }
+//! }
+//! ```
+//!
+//! Missing classes keep their defaults.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::models::SiteConfig;
+
+/// `site_config` key holding [`PaginationPolicy`].
+pub const PAGINATION_POLICY_KEY: &str = "pagination_policy";
+
+/// Hard upper bound on any configured maximum.
+///
+/// Keeps a mistyped setting from re-opening unbounded queries.
+pub const ABSOLUTE_MAX_PAGE_SIZE: i64 = 1000;
+
+/// Kind of listing endpoint, each with its own limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageClass {
+    /// Full-text search (`/search`, `/api/search`, `/api/v1/search`).
+    Search,
+    /// Gather query execution.
+    Gather,
+    /// Admin listing pages.
+    Admin,
+    /// General JSON list APIs (`/api/items`, `/api/v1/...`).
+    Api,
+}
+
+/// Default and maximum page size for one [`PageClass`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageLimits {
+    /// Page size used when the request does not ask for one.
+    pub default: i64,
+    /// Largest page size a request may ask for.
+    pub max: i64,
+}
+
+impl PageLimits {
+    const fn new(default: i64, max: i64) -> Self {
+        Self { default, max }
+    }
+
+    /// Resolve a requested page size against these limits.
+    pub fn resolve(&self, requested: Option<i64>) -> PageSize {
+        let Some(requested) = requested else {
+            return PageSize {
+                limit: self.default,
+                warning: None,
+            };
+        };
+        if requested > self.max {
+            return PageSize {
+                limit: self.max,
+                warning: Some(format!(
+                    "requested page size {requested} exceeds the maximum of {}; returning {}",
+                    self.max, self.max
+                )),
+            };
+        }
+        PageSize {
+            limit: requested.max(1),
+            warning: None,
+        }
+    }
+
+    /// Replace out-of-range values so that `1 <= default <= max <= ABSOLUTE_MAX_PAGE_SIZE`.
+    fn normalized(self, fallback: Self) -> Self {
+        let max = if self.max < 1 {
+            fallback.max
+        } else {
+            self.max.min(ABSOLUTE_MAX_PAGE_SIZE)
+        };
+        let default = if self.default < 1 {
+            fallback.default
+        } else {
+            self.default
+        };
+        Self {
+            default: default.min(max),
+            max,
+        }
+    }
+}
+
+/// Effective page size for a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSize {
+    /// Number of rows to return.
+    pub limit: i64,
+    /// Set when the requested size was clamped to the maximum.
+    pub warning: Option<String>,
+}
+
+/// Page size limits for every [`PageClass`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaginationPolicy {
+    pub search: PageLimits,
+    pub gather: PageLimits,
+    pub admin: PageLimits,
+    pub api: PageLimits,
+}
+
+impl Default for PaginationPolicy {
+    fn default() -> Self {
+        Self {
+            search: PageLimits::new(10, 50),
+            gather: PageLimits::new(10, 100),
+            admin: PageLimits::new(25, 100),
+            api: PageLimits::new(25, 100),
+        }
+    }
+}
+
+impl PaginationPolicy {
+    /// Load the site-wide policy, falling back to defaults.
+    ///
+    /// An unreadable or invalid setting never fails the request: the
+    /// defaults are used and a warning is logged.
+    pub async fn load(pool: &PgPool) -> Self {
+        let policy = match SiteConfig::get(pool, PAGINATION_POLICY_KEY).await {
+            Ok(Some(value)) => serde_json::from_value::<Self>(value).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "invalid pagination_policy; using defaults");
+                Self::default()
+            }),
+            Ok(None) => Self::default(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load pagination_policy; using defaults");
+                Self::default()
+            }
+        };
+        policy.normalized()
+    }
+
+    /// Persist the site-wide policy.
+    pub async fn save(&self, pool: &PgPool) -> Result<()> {
+        SiteConfig::set(pool, PAGINATION_POLICY_KEY, serde_json::to_value(self)?).await
+    }
+
+    /// Limits for an endpoint class.
+    pub fn limits(&self, class: PageClass) -> PageLimits {
+        match class {
+            PageClass::Search => self.search,
+            PageClass::Gather => self.gather,
+            PageClass::Admin => self.admin,
+            PageClass::Api => self.api,
+        }
+    }
+
+    /// Resolve a requested page size for an endpoint class.
+    pub fn resolve(&self, class: PageClass, requested: Option<i64>) -> PageSize {
+        self.limits(class).resolve(requested)
+    }
+
+    fn normalized(self) -> Self {
+        let defaults = Self::default();
+        Self {
+            search: self.search.normalized(defaults.search),
+            gather: self.gather.normalized(defaults.gather),
+            admin: self.admin.normalized(defaults.admin),
+            api: self.api.normalized(defaults.api),
+        }
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_request_uses_default() {
+        let policy = PaginationPolicy::default();
+        let size = policy.resolve(PageClass::Search, None);
+        assert_eq!(size.limit, 10);
+        assert!(size.warning.is_none());
+    }
+
+    #[test]
+    fn oversized_request_is_clamped_with_warning() {
+        let policy = PaginationPolicy::default();
+        let size = policy.resolve(PageClass::Api, Some(100_000));
+        assert_eq!(size.limit, 100);
+        assert!(size.warning.unwrap().contains("100000"));
+    }
+
+    #[test]
+    fn small_requests_are_raised_to_one_silently() {
+        let policy = PaginationPolicy::default();
+        assert_eq!(
+            policy.resolve(PageClass::Admin, Some(0)),
+            PageSize {
+                limit: 1,
+                warning: None
+            }
+        );
+        assert_eq!(policy.resolve(PageClass::Admin, Some(40)).limit, 40);
+    }
+
+    #[test]
+    fn partial_config_keeps_other_defaults() {
+        let policy: PaginationPolicy =
+            serde_json::from_value(serde_json::json!({ "search": { "default": 20, "max": 30 } }))
+                .unwrap();
+        let policy = policy.normalized();
+        assert_eq!(policy.search, PageLimits::new(20, 30));
+        assert_eq!(policy.api, PaginationPolicy::default().api);
+    }
+
+    #[test]
+    fn normalization_fixes_out_of_range_values() {
+        let policy = PaginationPolicy {
+            search: PageLimits::new(0, 0),
+            gather: PageLimits::new(500, 200),
+            admin: PageLimits::new(25, 1_000_000),
+            ..PaginationPolicy::default()
+        }
+        .normalized();
+        assert_eq!(policy.search, PaginationPolicy::default().search);
+        assert_eq!(policy.gather, PageLimits::new(200, 200));
+        assert_eq!(policy.admin.max, ABSOLUTE_MAX_PAGE_SIZE);
+    }
+}
@@ -0,0 +1,197 @@
+//! Password policy enforcement.
+//!
+//! Checks new passwords against the [`PasswordPolicy`] entities that apply
+//! to an account, in registration, password change, and password reset.
+//! Length and character classes are checked locally; breached passwords are
+//! looked up with the Pwned Passwords k-anonymity range API, which only ever
+//! sees the first five hex digits of the password's SHA-1 hash. History is
+//! checked against the current hash and `password_history`.
+//!
+//! Expired passwords (`max_age_days`) are refused at login; the user sets a
+//! new one through password reset.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use sha1::{Digest, Sha1};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::models::password_policy::{PasswordPolicyViolated, PasswordRules, PasswordViolation};
+use crate::models::role::well_known::AUTHENTICATED_ROLE_ID;
+use crate::models::user::verify_hash;
+use crate::models::{PasswordPolicy, Role, User};
+
+/// Pwned Passwords range endpoint; the hash prefix is appended.
+const BREACH_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+
+/// Timeout for breach lookups. A slow lookup must not block sign-up.
+const BREACH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Length of the SHA-1 prefix sent to the range API.
+const HASH_PREFIX_LENGTH: usize = 5;
+
+/// Checks passwords against the policies in force for an account.
+pub struct PasswordPolicyService {
+    pool: PgPool,
+    http: reqwest::Client,
+}
+
+impl PasswordPolicyService {
+    /// Create a new password policy service.
+    pub fn new(pool: PgPool) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(BREACH_TIMEOUT)
+            .user_agent(concat!("Trovato/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { pool, http }
+    }
+
+    /// The combined rules for `user`, or for a new account when `None`.
+    pub async fn rules_for(&self, user: Option<&User>) -> Result<PasswordRules> {
+        let mut role_ids = vec![AUTHENTICATED_ROLE_ID];
+        if let Some(user) = user {
+            role_ids.extend(
+                Role::get_user_roles(&self.pool, user.id)
+                    .await?
+                    .into_iter()
+                    .map(|r| r.id),
+            );
+        }
+        let policies = PasswordPolicy::list_all(&self.pool).await?;
+        Ok(PasswordRules::combine(
+            policies.iter().filter(|p| p.applies_to(&role_ids)),
+        ))
+    }
+
+    /// Check a new password for `user` (`None` when registering).
+    ///
+    /// Fails with [`PasswordPolicyViolated`] listing every broken rule.
+    pub async fn check(&self, password: &str, user: Option<&User>) -> Result<()> {
+        let rules = self.rules_for(user).await?;
+        let mut violations = rules.check_composition(password);
+
+        if rules.breach_check && self.is_breached(password).await {
+            violations.push(PasswordViolation::new(
+                "breached",
+                "This password has appeared in a data breach. Choose a different one.",
+            ));
+        }
+
+        if let Some(user) = user
+            && rules.history_count > 0
+            && self.is_reused(user, password, rules.history_count).await?
+        {
+            violations.push(PasswordViolation::new(
+                "reused",
+                format!(
+                    "Password must differ from your last {} password(s).",
+                    rules.history_count
+                ),
+            ));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyViolated { violations }.into())
+        }
+    }
+
+    /// Whether the user's password is older than their policies allow.
+    pub async fn is_expired(&self, user: &User) -> Result<bool> {
+        let rules = self.rules_for(Some(user)).await?;
+        Ok(rules.is_expired(user.password_changed, chrono::Utc::now()))
+    }
+
+    /// Whether `password` matches the current or one of the `count` most
+    /// recent passwords of `user`.
+    async fn is_reused(&self, user: &User, password: &str, count: u32) -> Result<bool> {
+        if user.verify_password(password) {
+            return Ok(true);
+        }
+        let history = User::password_history(&self.pool, user.id, count.saturating_sub(1))
+            .await
+            .context("failed to load password history")?;
+        Ok(history.iter().any(|hash| verify_hash(hash, password)))
+    }
+
+    /// Look the password up in the breach list.
+    ///
+    /// Lookup failures let the password through: an unreachable third-party
+    /// API must not lock users out of registration and password reset.
+    async fn is_breached(&self, password: &str) -> bool {
+        let (prefix, suffix) = range_query(password);
+        let response = self
+            .http
+            .get(format!("{BREACH_RANGE_URL}{prefix}"))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        let body = match response {
+            Ok(response) => response.text().await,
+            Err(e) => Err(e),
+        };
+        match body {
+            Ok(body) => breach_count(&body, &suffix) > 0,
+            Err(e) => {
+                warn!(error = %e, "breached password lookup failed; skipping check");
+                false
+            }
+        }
+    }
+}
+
+/// Split the uppercase hex SHA-1 of `password` into the prefix sent to the
+/// range API and the suffix looked for in its response.
+fn range_query(password: &str) -> (String, String) {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = hash.split_at(HASH_PREFIX_LENGTH);
+    (prefix.to_string(), suffix.to_string())
+}
+
+/// Breach count for `suffix` in a range API response.
+///
+/// Lines are `SUFFIX:COUNT`; padding entries have a count of 0.
+fn breach_count(body: &str, suffix: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(s, _)| s.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_query_splits_sha1() {
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let (prefix, suffix) = range_query("password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+    }
+
+    #[test]
+    fn breach_count_finds_suffix() {
+        let body = "0018A45C4D1DEF81644B54AB7F969B88D65:10\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+                    FFFFF00000000000000000000000000000:0\r\n";
+        assert_eq!(
+            breach_count(body, "1E4C9B93F3F0682250B6CF8331B7EE68FD8"),
+            9_545_824
+        );
+        assert_eq!(
+            breach_count(body, "1e4c9b93f3f0682250b6cf8331b7ee68fd8"),
+            9_545_824
+        );
+        // Padding entries and absent suffixes are not breaches.
+        assert_eq!(breach_count(body, "FFFFF00000000000000000000000000000"), 0);
+        assert_eq!(breach_count(body, "ABCDEF"), 0);
+    }
+}
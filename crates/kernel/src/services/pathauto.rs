@@ -1,98 +1,292 @@
 //! Automatic path alias generation (Pathauto).
 //!
-//! Generates URL aliases from configurable patterns per content type.
-//! Patterns use tokens like `[title]`, `[type]`, `[yyyy]`, `[mm]`, `[dd]`.
+//! Generates URL aliases from [`AliasPattern`] config entities, one per
+//! content type (optionally per language). Patterns use tokens:
+//!
+//! - `[title]` / `[title-slug]` — item title, slugified for the item's language
+//! - `[type]` — content type machine name
+//! - `[langcode]` — item language
+//! - `[yyyy]`, `[mm]`, `[dd]` — created date
+//! - `[field_name]` — a field's text, slugified
+//! - `[field_name:name]` — name of the category term the field references
+//! - `[field_name:title]` — title of the item the field references
+//!
+//! For multi-value fields the first value is used. Aliases are created in
+//! the item's stage when it is saved, and made unique with numeric suffixes.
+
+use std::collections::HashMap;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Datelike, Utc};
+use serde_json::Value;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::models::SiteConfig;
 use crate::models::url_alias::{CreateUrlAlias, UrlAlias};
+use crate::models::{AliasPattern, Item, Tag};
+use crate::services::slug::{self, SlugOptions};
 
-/// Convert text into a URL-safe slug.
+/// Path prefixes that the alias middleware explicitly skips.
 ///
-/// Transforms to lowercase, replaces non-alphanumeric characters with hyphens,
-/// collapses consecutive hyphens, and trims leading/trailing hyphens.
+/// Patterns that expand to one of these prefixes generate aliases that are
+/// never resolved by the middleware, and may shadow system routes, so
+/// [`validate_pattern`] rejects them.
+pub const RESERVED_PATTERN_PREFIXES: &[&str] = &[
+    "admin/", "api/", "user/", "item/", "static/", "install/", "system/", "health",
+];
+
+/// Maximum length of a pattern.
+const MAX_PATTERN_LENGTH: usize = 255;
+
+/// Tokens expanded from the item itself.
+const ITEM_TOKENS: &[&str] = &[
+    "title",
+    "title-slug",
+    "type",
+    "langcode",
+    "yyyy",
+    "mm",
+    "dd",
+];
+
+/// Properties of referenced records usable in field tokens.
+const REFERENCE_PROPERTIES: &[&str] = &["name", "title"];
+
+/// Language of generated alias rows; alias lookups fall back to it for
+/// every language.
+const ALIAS_LANGUAGE: &str = "en";
+
+/// Items loaded per query by [`regenerate_aliases`].
+const REGENERATE_PAGE_SIZE: i64 = 500;
+
+/// Convert text into a URL-safe slug with the default [`SlugOptions`].
+///
+/// Language-neutral shorthand for [`slug::slugify`]: transliterates to
+/// ASCII, lowercases, joins words with hyphens, and truncates to 128
+/// characters at a word boundary.
 pub fn slugify(text: &str) -> String {
-    let slug: String = text
-        .to_lowercase()
-        .chars()
-        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
-        .collect();
-
-    // Collapse consecutive hyphens and trim
-    let mut result = String::with_capacity(slug.len());
-    let mut prev_was_hyphen = true; // Start true to skip leading hyphens
-    for c in slug.chars() {
-        if c == '-' {
-            if !prev_was_hyphen {
-                result.push('-');
-            }
-            prev_was_hyphen = true;
-        } else {
-            result.push(c);
-            prev_was_hyphen = false;
-        }
+    slug::slugify(text, "", &SlugOptions::default())
+}
+
+/// A `[field_*]` token: the field and the referenced property, if any.
+#[derive(Debug, PartialEq, Eq)]
+struct FieldToken<'a> {
+    field: &'a str,
+    property: Option<&'a str>,
+}
+
+/// Parse a field token (without brackets); `None` if it isn't one.
+fn parse_field_token(token: &str) -> Option<FieldToken<'_>> {
+    let (field, property) = match token.split_once(':') {
+        Some((field, property)) => (field, Some(property)),
+        None => (token, None),
+    };
+    let valid_field = field.starts_with("field_")
+        && field
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid_field || property.is_some_and(|p| !REFERENCE_PROPERTIES.contains(&p)) {
+        return None;
     }
+    Some(FieldToken { field, property })
+}
 
-    // Trim trailing hyphen
-    while result.ends_with('-') {
-        result.pop();
+/// The tokens of a pattern, without brackets.
+///
+/// Fails on unbalanced or nested brackets.
+fn pattern_tokens(pattern: &str) -> Result<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find(['[', ']']) {
+        anyhow::ensure!(&rest[open..=open] == "[", "unbalanced ']' in pattern");
+        let after = &rest[open + 1..];
+        let close = after
+            .find(['[', ']'])
+            .filter(|&i| &after[i..=i] == "]")
+            .context("unclosed '[' in pattern")?;
+        tokens.push(&after[..close]);
+        rest = &after[close + 1..];
     }
+    Ok(tokens)
+}
 
-    // Truncate to reasonable length
-    if result.len() > 128 {
-        // result is pure ASCII (alphanumerics + hyphens from the char map above),
-        // but use is_char_boundary defensively in case the logic ever changes.
-        let mut end = 128;
-        while end > 0 && !result.is_char_boundary(end) {
-            end -= 1;
-        }
-        // Find a clean break point (don't cut in middle of word)
-        let truncated = &result[..end];
-        if let Some(last_hyphen) = truncated.rfind('-') {
-            return truncated[..last_hyphen].to_string();
-        }
-        return truncated.to_string();
+/// Check a pattern before it is saved.
+///
+/// Rejects empty patterns, unknown tokens, and patterns whose leading
+/// segment is one of [`RESERVED_PATTERN_PREFIXES`].
+pub fn validate_pattern(pattern: &str) -> Result<()> {
+    let trimmed = pattern.trim().trim_start_matches('/');
+    anyhow::ensure!(!trimmed.is_empty(), "pattern is empty");
+    anyhow::ensure!(
+        pattern.len() <= MAX_PATTERN_LENGTH,
+        "pattern is longer than {MAX_PATTERN_LENGTH} characters"
+    );
+    anyhow::ensure!(
+        !RESERVED_PATTERN_PREFIXES
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix)),
+        "pattern begins with a reserved path prefix (e.g. admin/, api/, user/); \
+         the alias middleware skips these paths, so the alias would never resolve"
+    );
+    for token in pattern_tokens(trimmed)? {
+        anyhow::ensure!(
+            ITEM_TOKENS.contains(&token) || parse_field_token(token).is_some(),
+            "unknown token [{token}]"
+        );
     }
+    Ok(())
+}
 
-    result
+/// Item data a pattern is expanded with.
+#[derive(Debug, Clone)]
+pub struct TokenContext<'a> {
+    /// Item ID; stands in for a title that slugifies to nothing.
+    pub id: Uuid,
+    pub title: &'a str,
+    pub item_type: &'a str,
+    pub language: &'a str,
+    pub created: DateTime<Utc>,
+    /// Unslugified values of the pattern's field tokens, keyed by token
+    /// (without brackets). Missing tokens expand to nothing.
+    pub fields: HashMap<String, String>,
 }
 
-/// Expand a pattern by replacing tokens with values from an item.
-///
-/// Supported tokens:
-/// - `[title]` — item title, slugified
-/// - `[type]` — content type machine name (must be a valid machine name: `[a-z0-9_]`)
-/// - `[yyyy]` — four-digit year from created date
-/// - `[mm]` — two-digit month from created date
-/// - `[dd]` — two-digit day from created date
+impl<'a> TokenContext<'a> {
+    /// Context for an item, without field token values.
+    pub fn for_item(item: &'a Item) -> Self {
+        Self {
+            id: item.id,
+            title: &item.title,
+            item_type: &item.item_type,
+            language: &item.language,
+            created: DateTime::from_timestamp(item.created, 0).unwrap_or_else(Utc::now),
+            fields: HashMap::new(),
+        }
+    }
+}
+
+/// Expand a pattern by replacing its tokens with values from `context`.
 ///
-/// `[type]` is not slugified because machine names are validated at content type
-/// registration time via `is_valid_machine_name` and are already URL-safe.
-pub fn expand_pattern(
+/// Text values (title, fields, referenced names) are slugified using the
+/// rules for the item's language. `[type]` is not slugified because machine
+/// names are validated at content type registration time via
+/// `is_valid_machine_name` and are already URL-safe. A title with nothing
+/// to transliterate (e.g. written in CJK, Arabic or Hebrew script) expands
+/// to the item ID so the alias stays unique and stable. Unknown tokens and
+/// empty path segments (from tokens without a value) are dropped.
+pub fn expand_pattern(pattern: &str, context: &TokenContext<'_>, options: &SlugOptions) -> String {
+    let slug = |text: &str| slug::slugify(text, context.language, options);
+
+    let mut expanded = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']').map(|i| open + i) else {
+            break;
+        };
+        expanded.push_str(&rest[..open]);
+        let value = match &rest[open + 1..close] {
+            "title" | "title-slug" => match slug(context.title) {
+                title if title.is_empty() => context.id.to_string(),
+                title => title,
+            },
+            "type" => context.item_type.to_string(),
+            "langcode" => slug(context.language),
+            "yyyy" => format!("{:04}", context.created.year()),
+            "mm" => format!("{:02}", context.created.month()),
+            "dd" => format!("{:02}", context.created.day()),
+            token => context
+                .fields
+                .get(token)
+                .map(|text| slug(text))
+                .unwrap_or_default(),
+        };
+        expanded.push_str(&value);
+        rest = &rest[close + 1..];
+    }
+    expanded.push_str(rest);
+
+    let leading_slash = expanded.starts_with('/');
+    let path = expanded
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    if leading_slash {
+        format!("/{path}")
+    } else {
+        path
+    }
+}
+
+/// The first value of a field; multi-value fields are arrays.
+fn first_value(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(values) => values.first(),
+        Value::Null => None,
+        value => Some(value),
+    }
+}
+
+/// Text of a field value: a string, number, or `{"value": ...}` text field.
+fn field_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Object(obj) => obj.get("value").and_then(Value::as_str).map(String::from),
+        _ => None,
+    }
+}
+
+/// Target of a reference field value: a UUID or `{"target_id": ...}`.
+fn reference_target(value: &Value) -> Option<Uuid> {
+    match value {
+        Value::String(id) => id.parse().ok(),
+        Value::Object(obj) => obj.get("target_id")?.as_str()?.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Build the token context for an item, resolving the field tokens used
+/// by `pattern` (including referenced term names and item titles).
+pub async fn token_context<'a>(
+    pool: &PgPool,
     pattern: &str,
-    title: &str,
-    item_type: &str,
-    created: DateTime<Utc>,
-) -> String {
-    let slug = slugify(title);
-
-    pattern
-        .replace("[title]", &slug)
-        .replace("[type]", item_type)
-        .replace("[yyyy]", &format!("{:04}", created.year()))
-        .replace("[mm]", &format!("{:02}", created.month()))
-        .replace("[dd]", &format!("{:02}", created.day()))
+    item: &'a Item,
+) -> Result<TokenContext<'a>> {
+    let mut context = TokenContext::for_item(item);
+    for token in pattern_tokens(pattern).unwrap_or_default() {
+        let Some(FieldToken { field, property }) = parse_field_token(token) else {
+            continue;
+        };
+        let Some(value) = item.fields.get(field).and_then(first_value) else {
+            continue;
+        };
+        let text = match property {
+            None => field_text(value),
+            Some(property) => {
+                let Some(target) = reference_target(value) else {
+                    continue;
+                };
+                if property == "name" {
+                    Tag::find_by_id(pool, target).await?.map(|tag| tag.label)
+                } else {
+                    Item::find_by_id(pool, target).await?.map(|item| item.title)
+                }
+            }
+        };
+        if let Some(text) = text {
+            context.fields.insert(token.to_string(), text);
+        }
+    }
+    Ok(context)
 }
 
 /// Generate a unique alias for an item, handling duplicates with numeric suffixes.
 ///
 /// If `/blog/my-post` is taken, tries `/blog/my-post-1`, `/blog/my-post-2`, etc.
 /// Uses a single query to find existing aliases with matching prefix to avoid
-/// sequential lookups.
+/// sequential lookups. Aliases in all stages count, so publishing a stage
+/// can't produce duplicates.
 ///
 /// Rejects aliases containing path traversal sequences (`..`), double slashes,
 /// or control characters.
@@ -148,68 +342,66 @@ pub async fn generate_unique_alias(pool: &PgPool, base_alias: &str) -> Result<St
     Ok(format!("{base}-{fragment}"))
 }
 
-/// Look up the pathauto pattern for a content type from site config.
+/// Expand the item's pattern into its base alias (with a leading `/`).
 ///
-/// Returns `None` if no pattern is configured for this type.
-pub async fn get_pattern(pool: &PgPool, item_type: &str) -> Result<Option<String>> {
-    let patterns = SiteConfig::get(pool, "pathauto_patterns").await?;
+/// Returns `None` if no pattern applies or it expands to nothing (e.g. a
+/// pattern made only of field tokens without values).
+async fn expand_for_item(pool: &PgPool, item: &Item) -> Result<Option<String>> {
+    let Some(pattern) = AliasPattern::find_for(pool, &item.item_type, &item.language).await? else {
+        return Ok(None);
+    };
+
+    let context = token_context(pool, &pattern.pattern, item).await?;
+    let options = SlugOptions::load(pool).await?;
+    let expanded = expand_pattern(&pattern.pattern, &context, &options);
 
-    Ok(patterns.and_then(|v| v.get(item_type).and_then(|p| p.as_str().map(String::from))))
+    if expanded.trim_matches('/').is_empty() {
+        tracing::debug!(item_id = %item.id, "skipping pathauto: expanded pattern is empty");
+        return Ok(None);
+    }
+    if expanded.starts_with('/') {
+        Ok(Some(expanded))
+    } else {
+        Ok(Some(format!("/{expanded}")))
+    }
 }
 
-/// Automatically generate and create a URL alias for an item.
+/// Automatically generate and create a URL alias for an item in its stage.
 ///
 /// Does nothing if:
 /// - No pattern configured for this content type
-/// - An alias already exists for this item (manual override)
+/// - An alias already exists for this item in its stage (manual override)
 ///
 /// Returns the alias path if created, or `None` if skipped.
-pub async fn auto_alias_item(
-    pool: &PgPool,
-    item_id: Uuid,
-    title: &str,
-    item_type: &str,
-    created_ts: i64,
-) -> Result<Option<String>> {
-    let created = DateTime::from_timestamp(created_ts, 0).unwrap_or_else(Utc::now);
-    let Some(pattern) = get_pattern(pool, item_type).await? else {
-        return Ok(None);
-    };
-
-    let source = format!("/item/{item_id}");
+pub async fn auto_alias_item(pool: &PgPool, item: &Item) -> Result<Option<String>> {
+    let source = format!("/item/{}", item.id);
 
     // Don't overwrite manually-set aliases
-    let existing = UrlAlias::find_by_source(pool, &source).await?;
+    let existing =
+        UrlAlias::find_by_source_with_context(pool, &source, item.stage_id, ALIAS_LANGUAGE).await?;
     if !existing.is_empty() {
         return Ok(None);
     }
 
-    // Expand the pattern and generate a unique alias
-    let expanded = expand_pattern(&pattern, title, item_type, created);
-
-    // If the slug is empty (e.g. pure non-ASCII title), skip alias generation
-    if expanded.trim_matches('/').is_empty() {
-        tracing::debug!(item_id = %item_id, "skipping pathauto: expanded pattern is empty");
+    let Some(expanded) = expand_for_item(pool, item).await? else {
         return Ok(None);
-    }
-
+    };
     let alias = generate_unique_alias(pool, &expanded).await?;
 
-    // Create the alias
     UrlAlias::create(
         pool,
         CreateUrlAlias {
             source,
             alias: alias.clone(),
-            language: None,
-            stage_id: None,
+            language: Some(ALIAS_LANGUAGE.to_string()),
+            stage_id: Some(item.stage_id),
         },
     )
     .await
     .context("failed to create auto-generated alias")?;
 
     tracing::info!(
-        item_id = %item_id,
+        item_id = %item.id,
         alias = %alias,
         "auto-generated path alias"
     );
@@ -217,7 +409,7 @@ pub async fn auto_alias_item(
     Ok(Some(alias))
 }
 
-/// Update the URL alias for an item based on the pathauto pattern.
+/// Update the URL alias for an item in its stage based on its pattern.
 ///
 /// Does nothing if:
 /// - No pattern configured for this content type
@@ -227,36 +419,16 @@ pub async fn auto_alias_item(
 /// owns the alias for items of that type. Aliases set via the URL alias
 /// admin will be overwritten if a pattern exists. To keep a manual alias,
 /// remove the pathauto pattern for the content type.
-pub async fn update_alias_item(
-    pool: &PgPool,
-    item_id: Uuid,
-    title: &str,
-    item_type: &str,
-    created_ts: i64,
-) -> Result<Option<String>> {
-    let created = DateTime::from_timestamp(created_ts, 0).unwrap_or_else(Utc::now);
-    let Some(pattern) = get_pattern(pool, item_type).await? else {
-        return Ok(None);
-    };
-
-    let source = format!("/item/{item_id}");
-    let expanded = expand_pattern(&pattern, title, item_type, created);
-
-    // If the slug is empty (e.g. pure non-ASCII title), skip alias update
-    if expanded.trim_matches('/').is_empty() {
-        tracing::debug!(item_id = %item_id, "skipping pathauto update: expanded pattern is empty");
+pub async fn update_alias_item(pool: &PgPool, item: &Item) -> Result<Option<String>> {
+    let Some(base_alias) = expand_for_item(pool, item).await? else {
         return Ok(None);
-    }
-
-    let base_alias = if expanded.starts_with('/') {
-        expanded
-    } else {
-        format!("/{expanded}")
     };
 
     // Check if current alias already matches — no update needed.
     // An alias matches if it equals the base or is a suffixed variant (e.g. /blog/post-1).
-    let existing = UrlAlias::find_by_source(pool, &source).await?;
+    let source = format!("/item/{}", item.id);
+    let existing =
+        UrlAlias::find_by_source_with_context(pool, &source, item.stage_id, ALIAS_LANGUAGE).await?;
     let suffixed_prefix = format!("{base_alias}-");
     if existing
         .iter()
@@ -267,23 +439,17 @@ pub async fn update_alias_item(
 
     if existing.is_empty() {
         // No alias exists yet — delegate to auto_alias_item for creation
-        return auto_alias_item(pool, item_id, title, item_type, created_ts).await;
+        return auto_alias_item(pool, item).await;
     }
 
     // Alias exists but doesn't match current pattern — regenerate
     let alias = generate_unique_alias(pool, &base_alias).await?;
-    UrlAlias::upsert_for_source(
-        pool,
-        &source,
-        &alias,
-        crate::models::stage::LIVE_STAGE_ID,
-        "en",
-    )
-    .await
-    .context("failed to update auto-generated alias")?;
+    UrlAlias::upsert_for_source(pool, &source, &alias, item.stage_id, ALIAS_LANGUAGE)
+        .await
+        .context("failed to update auto-generated alias")?;
 
     tracing::info!(
-        item_id = %item_id,
+        item_id = %item.id,
         alias = %alias,
         "auto-updated path alias"
     );
@@ -291,12 +457,66 @@ pub async fn update_alias_item(
     Ok(Some(alias))
 }
 
+/// Regenerate the aliases of every item of a content type, in all stages.
+///
+/// Items are loaded in pages, so any number of items can be processed.
+/// Returns the number of aliases created or changed; failures for single
+/// items are logged and skipped.
+pub async fn regenerate_aliases(pool: &PgPool, item_type: &str) -> Result<u64> {
+    let mut count = 0;
+    let mut after = None;
+    loop {
+        let items = Item::list_by_type_after(pool, item_type, after, REGENERATE_PAGE_SIZE).await?;
+        let Some(last) = items.last() else {
+            break;
+        };
+        after = Some(last.id);
+
+        for item in &items {
+            match update_alias_item(pool, item).await {
+                Ok(Some(_)) => count += 1,
+                Ok(None) => {} // alias already matches or no pattern configured
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        item_id = %item.id,
+                        "failed to update alias during pathauto regeneration"
+                    );
+                }
+            }
+        }
+    }
+
+    tracing::info!(
+        item_type = %item_type,
+        aliases_updated = count,
+        "pathauto regeneration complete"
+    );
+    Ok(count)
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
 
+    fn context<'a>(
+        title: &'a str,
+        item_type: &'a str,
+        date: &str,
+        language: &'a str,
+    ) -> TokenContext<'a> {
+        TokenContext {
+            id: Uuid::nil(),
+            title,
+            item_type,
+            language,
+            created: DateTime::parse_from_rfc3339(date).unwrap().to_utc(),
+            fields: HashMap::new(),
+        }
+    }
+
     #[test]
     fn test_slugify_basic() {
         assert_eq!(slugify("Hello World"), "hello-world");
@@ -337,30 +557,36 @@ mod tests {
 
     #[test]
     fn test_expand_pattern_title_only() {
-        let dt = DateTime::parse_from_rfc3339("2026-02-20T12:00:00Z")
-            .unwrap()
-            .to_utc();
-        assert_eq!(expand_pattern("[title]", "My Post", "blog", dt), "my-post");
+        assert_eq!(
+            expand_pattern(
+                "[title]",
+                &context("My Post", "blog", "2026-02-20T12:00:00Z", "en"),
+                &SlugOptions::default()
+            ),
+            "my-post"
+        );
     }
 
     #[test]
     fn test_expand_pattern_with_type() {
-        let dt = DateTime::parse_from_rfc3339("2026-02-20T12:00:00Z")
-            .unwrap()
-            .to_utc();
         assert_eq!(
-            expand_pattern("[type]/[title]", "Hello World", "blog", dt),
+            expand_pattern(
+                "[type]/[title]",
+                &context("Hello World", "blog", "2026-02-20T12:00:00Z", "en"),
+                &SlugOptions::default()
+            ),
             "blog/hello-world"
         );
     }
 
     #[test]
     fn test_expand_pattern_with_dates() {
-        let dt = DateTime::parse_from_rfc3339("2026-03-15T12:00:00Z")
-            .unwrap()
-            .to_utc();
         assert_eq!(
-            expand_pattern("news/[yyyy]/[mm]/[title]", "Breaking News", "news", dt),
+            expand_pattern(
+                "news/[yyyy]/[mm]/[title]",
+                &context("Breaking News", "news", "2026-03-15T12:00:00Z", "en"),
+                &SlugOptions::default()
+            ),
             "news/2026/03/breaking-news"
         );
     }
@@ -387,17 +613,107 @@ mod tests {
 
     #[test]
     fn test_expand_pattern_all_tokens() {
-        let dt = DateTime::parse_from_rfc3339("2026-12-25T12:00:00Z")
-            .unwrap()
-            .to_utc();
         assert_eq!(
             expand_pattern(
                 "[type]/[yyyy]/[mm]/[dd]/[title]",
-                "Holiday Post",
-                "blog",
-                dt
+                &context("Holiday Post", "blog", "2026-12-25T12:00:00Z", "en"),
+                &SlugOptions::default()
             ),
             "blog/2026/12/25/holiday-post"
         );
     }
+
+    #[test]
+    fn test_expand_pattern_uses_item_language() {
+        let date = "2026-02-20T12:00:00Z";
+        let options = SlugOptions::default();
+        assert_eq!(
+            expand_pattern(
+                "[title]",
+                &context("Schöne Grüße", "blog", date, "de"),
+                &options
+            ),
+            "schoene-gruesse"
+        );
+        assert_eq!(
+            expand_pattern(
+                "[title]",
+                &context("Schöne Grüße", "blog", date, "en"),
+                &options
+            ),
+            "schone-grusse"
+        );
+    }
+
+    #[test]
+    fn test_slugify_transliterates() {
+        assert_eq!(slugify("Crème Brûlée"), "creme-brulee");
+    }
+
+    #[test]
+    fn test_expand_pattern_field_tokens() {
+        let mut ctx = context("Ferris Goes Async", "story", "2026-02-20T12:00:00Z", "en");
+        ctx.fields.insert(
+            "field_topic:name".to_string(),
+            "Systems Programming".to_string(),
+        );
+        let options = SlugOptions::default();
+        assert_eq!(
+            expand_pattern("/stories/[field_topic:name]/[title-slug]", &ctx, &options),
+            "/stories/systems-programming/ferris-goes-async"
+        );
+
+        // A token without a value leaves no empty segment behind.
+        ctx.fields.clear();
+        assert_eq!(
+            expand_pattern("/stories/[field_topic:name]/[title-slug]", &ctx, &options),
+            "/stories/ferris-goes-async"
+        );
+    }
+
+    #[test]
+    fn test_expand_pattern_untransliterable_title_uses_id() {
+        let date = "2026-02-20T12:00:00Z";
+        let options = SlugOptions::default();
+        for (title, language) in [("東京会議", "ja"), ("مؤتمر", "ar"), ("כנס", "he")] {
+            let mut ctx = context(title, "conference", date, language);
+            ctx.id = Uuid::parse_str("0192f5a4-3c1e-7a10-8b2f-6d4e5c3b2a19").unwrap();
+            assert_eq!(
+                expand_pattern("/conferences/[title]", &ctx, &options),
+                "/conferences/0192f5a4-3c1e-7a10-8b2f-6d4e5c3b2a19"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        assert!(validate_pattern("blog/[title-slug]").is_ok());
+        assert!(validate_pattern("/stories/[field_topic:name]/[yyyy]/[title]").is_ok());
+        assert!(validate_pattern("[field_venue:title]/[langcode]/[title]").is_ok());
+
+        assert!(validate_pattern("").is_err());
+        assert!(validate_pattern("blog/[author]").is_err());
+        assert!(validate_pattern("blog/[field_topic:label]").is_err());
+        assert!(validate_pattern("blog/[title").is_err());
+        assert!(validate_pattern("blog/title]").is_err());
+        assert!(validate_pattern("admin/[title]").is_err());
+        assert!(validate_pattern("/api/[title]").is_err());
+    }
+
+    #[test]
+    fn test_field_values() {
+        let text = serde_json::json!({"value": "Hello", "format": "plain_text"});
+        assert_eq!(field_text(&text).as_deref(), Some("Hello"));
+        assert_eq!(field_text(&serde_json::json!(42)).as_deref(), Some("42"));
+
+        let id = Uuid::now_v7();
+        let refs = serde_json::json!([{"target_id": id.to_string()}, {"target_id": "x"}]);
+        let first = first_value(&refs).unwrap();
+        assert_eq!(reference_target(first), Some(id));
+        assert_eq!(
+            reference_target(&serde_json::json!(id.to_string())),
+            Some(id)
+        );
+        assert!(first_value(&serde_json::json!([])).is_none());
+    }
 }
@@ -0,0 +1,328 @@
+//! Sampled read access logging for sensitive item types.
+//!
+//! The audit log records mutations only. Some sites also need to know who
+//! *viewed* sensitive content; logging every read would be far too
+//! expensive, so logging is opt-in per item type with a sampling rate.
+//! Settings live in `site_config` under `read_log`:
+//!
+//! ```json
+//! { "retention_days": 7, "types": { "case_file": 1.0, "report": 0.1 } }
+//! ```
+//!
+//! Sampled reads are queued to a background writer that inserts them in
+//! batches; when the queue is full, reads are dropped rather than delaying
+//! requests. Entries go to the `item_read_log` table and are purged by the
+//! `trovato_read_log` plugin's `tap_cron` once older than `retention_days`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::SiteConfig;
+
+/// `site_config` key holding [`ReadLogSettings`].
+pub const READ_LOG_SETTINGS_KEY: &str = "read_log";
+
+/// Default retention for read log entries, in days.
+pub const DEFAULT_RETENTION_DAYS: u32 = 7;
+
+/// Upper bound on retention; read logs are meant to be short-lived.
+pub const MAX_RETENTION_DAYS: u32 = 90;
+
+/// Maximum entries returned by a single report query.
+pub const MAX_REPORT_ENTRIES: i64 = 500;
+
+/// How long loaded settings are cached before re-reading `site_config`.
+const SETTINGS_TTL: Duration = Duration::from_secs(60);
+
+/// Sampled reads waiting for the writer; further reads are dropped.
+const WRITE_QUEUE_CAPACITY: usize = 1024;
+
+/// Maximum entries inserted per statement.
+const WRITE_BATCH_SIZE: usize = 100;
+
+/// Read logging settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadLogSettings {
+    /// Days to keep entries.
+    pub retention_days: u32,
+    /// Sampling rate (0.0–1.0) per item type. Absent types are not logged.
+    pub types: HashMap<String, f64>,
+}
+
+impl Default for ReadLogSettings {
+    fn default() -> Self {
+        Self {
+            retention_days: DEFAULT_RETENTION_DAYS,
+            types: HashMap::new(),
+        }
+    }
+}
+
+impl ReadLogSettings {
+    /// Check that retention and sampling rates are in range.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.retention_days == 0 || self.retention_days > MAX_RETENTION_DAYS {
+            return Err(format!(
+                "retention_days must be between 1 and {MAX_RETENTION_DAYS}"
+            ));
+        }
+        for (item_type, rate) in &self.types {
+            if !(0.0..=1.0).contains(rate) {
+                return Err(format!(
+                    "sample rate for {item_type} must be between 0.0 and 1.0"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sampling rate for an item type (0.0 if not configured).
+    pub fn sample_rate(&self, item_type: &str) -> f64 {
+        self.types.get(item_type).copied().unwrap_or(0.0)
+    }
+}
+
+/// A recorded read.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReadLogEntry {
+    /// Unique identifier (UUIDv7).
+    pub id: Uuid,
+    /// Item that was read.
+    pub item_id: Uuid,
+    /// Type of the item at read time.
+    pub item_type: String,
+    /// Reader, or `None` for anonymous visitors.
+    pub user_id: Option<Uuid>,
+    /// Client IP address, if valid.
+    pub ip_address: Option<String>,
+    /// Unix timestamp of the read.
+    pub created: i64,
+}
+
+/// Filter for read log reports.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReadLogFilter {
+    /// Restrict to reads of this item.
+    pub item_id: Option<Uuid>,
+    /// Restrict to reads by this user.
+    pub user_id: Option<Uuid>,
+    /// Only entries at or after this Unix timestamp.
+    pub since: Option<i64>,
+    /// Maximum entries (capped at [`MAX_REPORT_ENTRIES`]).
+    pub limit: Option<i64>,
+}
+
+/// Sampled read access logging service.
+#[derive(Clone)]
+pub struct ReadLogService {
+    pool: PgPool,
+    settings: Cache<(), Arc<ReadLogSettings>>,
+    writer: mpsc::Sender<ReadLogEntry>,
+}
+
+impl ReadLogService {
+    /// Create a new read log service and spawn its background writer.
+    pub fn new(pool: PgPool) -> Self {
+        let (writer, queue) = mpsc::channel(WRITE_QUEUE_CAPACITY);
+        tokio::spawn(write_entries(pool.clone(), queue));
+        Self {
+            pool,
+            settings: Cache::builder()
+                .max_capacity(1)
+                .time_to_live(SETTINGS_TTL)
+                .build(),
+            writer,
+        }
+    }
+
+    /// Current settings (cached).
+    pub async fn settings(&self) -> Result<Arc<ReadLogSettings>> {
+        if let Some(settings) = self.settings.get(&()).await {
+            return Ok(settings);
+        }
+        let settings = match SiteConfig::get(&self.pool, READ_LOG_SETTINGS_KEY).await? {
+            Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+                warn!(error = %e, "invalid read_log settings; logging disabled");
+                ReadLogSettings::default()
+            }),
+            None => ReadLogSettings::default(),
+        };
+        let settings = Arc::new(settings);
+        self.settings.insert((), settings.clone()).await;
+        Ok(settings)
+    }
+
+    /// Validate and persist settings.
+    pub async fn save_settings(&self, settings: &ReadLogSettings) -> Result<()> {
+        settings.validate().map_err(anyhow::Error::msg)?;
+        SiteConfig::set(
+            &self.pool,
+            READ_LOG_SETTINGS_KEY,
+            serde_json::to_value(settings)?,
+        )
+        .await?;
+        self.settings.invalidate(&()).await;
+        Ok(())
+    }
+
+    /// Queue a read for writing if the item type is configured and the
+    /// sample hits.
+    ///
+    /// Returns whether an entry was queued; sampled reads are dropped while
+    /// the write queue is full.
+    pub async fn record(
+        &self,
+        item_id: Uuid,
+        item_type: &str,
+        user_id: Option<Uuid>,
+        ip_address: &str,
+    ) -> Result<bool> {
+        let rate = self.settings().await?.sample_rate(item_type);
+        if !should_sample(rate, rand::random::<f64>()) {
+            return Ok(false);
+        }
+
+        let entry = ReadLogEntry {
+            id: Uuid::now_v7(),
+            item_id,
+            item_type: item_type.to_string(),
+            user_id,
+            ip_address: ip_address
+                .parse::<std::net::IpAddr>()
+                .ok()
+                .map(|ip| ip.to_string()),
+            created: chrono::Utc::now().timestamp(),
+        };
+        match self.writer.try_send(entry) {
+            Ok(()) => Ok(true),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                debug!(item_id = %item_id, "read log queue full; dropping read");
+                Ok(false)
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                anyhow::bail!("read log writer has stopped")
+            }
+        }
+    }
+
+    /// Query entries, newest first.
+    pub async fn query(&self, filter: &ReadLogFilter) -> Result<Vec<ReadLogEntry>> {
+        let limit = filter
+            .limit
+            .unwrap_or(MAX_REPORT_ENTRIES)
+            .clamp(1, MAX_REPORT_ENTRIES);
+
+        sqlx::query_as::<_, ReadLogEntry>(
+            r#"
+            SELECT id, item_id, item_type, user_id, ip_address, created
+            FROM item_read_log
+            WHERE ($1::uuid IS NULL OR item_id = $1)
+              AND ($2::uuid IS NULL OR user_id = $2)
+              AND ($3::bigint IS NULL OR created >= $3)
+            ORDER BY created DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(filter.item_id)
+        .bind(filter.user_id)
+        .bind(filter.since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to query read log")
+    }
+}
+
+/// Drain queued reads, inserting up to [`WRITE_BATCH_SIZE`] per statement.
+async fn write_entries(pool: PgPool, mut queue: mpsc::Receiver<ReadLogEntry>) {
+    let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+    while queue.recv_many(&mut batch, WRITE_BATCH_SIZE).await > 0 {
+        if let Err(e) = insert_entries(&pool, &batch).await {
+            warn!(error = %e, count = batch.len(), "failed to write read log");
+        }
+        batch.clear();
+    }
+}
+
+/// Insert a batch of entries in one statement.
+async fn insert_entries(pool: &PgPool, entries: &[ReadLogEntry]) -> Result<()> {
+    let ids: Vec<Uuid> = entries.iter().map(|e| e.id).collect();
+    let item_ids: Vec<Uuid> = entries.iter().map(|e| e.item_id).collect();
+    let item_types: Vec<&str> = entries.iter().map(|e| e.item_type.as_str()).collect();
+    let user_ids: Vec<Option<Uuid>> = entries.iter().map(|e| e.user_id).collect();
+    let ips: Vec<Option<&str>> = entries.iter().map(|e| e.ip_address.as_deref()).collect();
+    let created: Vec<i64> = entries.iter().map(|e| e.created).collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO item_read_log (id, item_id, item_type, user_id, ip_address, created)
+        SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::uuid[], $5::text[], $6::bigint[])
+        "#,
+    )
+    .bind(ids)
+    .bind(item_ids)
+    .bind(item_types)
+    .bind(user_ids)
+    .bind(ips)
+    .bind(created)
+    .execute(pool)
+    .await
+    .context("failed to write read log")?;
+
+    Ok(())
+}
+
+/// Decide whether a read is sampled, given a uniform roll in `[0, 1)`.
+fn should_sample(rate: f64, roll: f64) -> bool {
+    rate > 0.0 && roll < rate
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampling_respects_rate() {
+        assert!(!should_sample(0.0, 0.0));
+        assert!(should_sample(1.0, 0.999));
+        assert!(should_sample(0.25, 0.1));
+        assert!(!should_sample(0.25, 0.3));
+    }
+
+    #[test]
+    fn unconfigured_types_are_not_logged() {
+        let settings: ReadLogSettings =
+            serde_json::from_value(serde_json::json!({"types": {"case_file": 0.5}})).unwrap();
+        assert_eq!(settings.retention_days, DEFAULT_RETENTION_DAYS);
+        assert_eq!(settings.sample_rate("case_file"), 0.5);
+        assert_eq!(settings.sample_rate("blog"), 0.0);
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_values() {
+        let mut settings = ReadLogSettings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.types.insert("blog".to_string(), 1.5);
+        assert!(settings.validate().is_err());
+
+        settings.types.insert("blog".to_string(), 0.5);
+        settings.retention_days = 0;
+        assert!(settings.validate().is_err());
+
+        settings.retention_days = MAX_RETENTION_DAYS + 1;
+        assert!(settings.validate().is_err());
+    }
+}
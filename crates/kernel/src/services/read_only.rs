@@ -0,0 +1,284 @@
+//! Read-only mode and per-type write locks.
+//!
+//! During incident response or data migrations writes need to be frozen
+//! without taking the site down. Read-only mode can be forced for the
+//! process with `READ_ONLY=true` (or `trovato read-only on`, which persists
+//! it), and toggled at runtime through `site_config` under `read_only`:
+//!
+//! ```json
+//! { "enabled": false, "message": "Migration in progress", "locked_item_types": ["report"] }
+//! ```
+//!
+//! While read-only, the `enforce_read_only` middleware answers mutating
+//! requests with 503 and the message; reads, login, health checks and
+//! read-only POST endpoints keep working. Locked item types refuse writes
+//! through [`crate::content::ItemService`] even when the site is writable.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::models::SiteConfig;
+
+/// `site_config` key holding [`ReadOnlySettings`].
+pub const READ_ONLY_SETTINGS_KEY: &str = "read_only";
+
+/// Message shown when none is configured.
+pub const DEFAULT_MESSAGE: &str = "The site is temporarily read-only. Please try again later.";
+
+/// Maximum length of the explanation shown to clients.
+pub const MAX_MESSAGE_LENGTH: usize = 500;
+
+/// How long loaded settings are cached before re-reading `site_config`.
+///
+/// Kept short so toggling read-only from the CLI or another instance takes
+/// effect quickly.
+const SETTINGS_TTL: Duration = Duration::from_secs(5);
+
+/// POST endpoints that do not change state, or that must keep working so
+/// users can sign in and administrators can lift read-only mode.
+const EXEMPT_POST_PATHS: &[&str] = &[
+    "/user/login",
+    "/user/login/json",
+    "/user/login/mfa",
+    "/user/logout",
+    "/oauth/token",
+    "/api/gather/query",
+    "/api/v1/search/expand",
+    "/api/v1/search/summarize",
+    "/api/v1/search/followup",
+    "/api/block-editor/preview",
+    "/admin/read-only",
+];
+
+/// Read-only settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadOnlySettings {
+    /// Reject all mutating requests.
+    pub enabled: bool,
+    /// Explanation returned with rejected requests.
+    pub message: Option<String>,
+    /// Item types that refuse writes even when the site is writable.
+    pub locked_item_types: Vec<String>,
+}
+
+impl ReadOnlySettings {
+    /// Check the message length.
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .message
+            .as_ref()
+            .is_some_and(|m| m.len() > MAX_MESSAGE_LENGTH)
+        {
+            return Err(format!(
+                "message must be at most {MAX_MESSAGE_LENGTH} characters"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Explanation shown to clients, falling back to [`DEFAULT_MESSAGE`].
+    pub fn message(&self) -> &str {
+        self.message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or(DEFAULT_MESSAGE)
+    }
+
+    /// Whether writes to an item type are locked.
+    pub fn is_type_locked(&self, item_type: &str) -> bool {
+        self.locked_item_types.iter().any(|t| t == item_type)
+    }
+}
+
+/// Error returned when a write is refused by read-only mode or a type lock.
+///
+/// Converted to a 503 response by [`crate::error::AppError`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct WriteLocked {
+    /// Explanation for the client.
+    pub message: String,
+}
+
+/// Whether a request may proceed while the site is read-only.
+///
+/// Safe methods always pass; of the rest only [`EXEMPT_POST_PATHS`] and
+/// the key-authenticated cron trigger (which skips write tasks itself) do.
+pub fn is_exempt(method: &str, path: &str) -> bool {
+    if matches!(method, "GET" | "HEAD" | "OPTIONS") {
+        return true;
+    }
+    if method != "POST" {
+        return false;
+    }
+    let path = path.strip_suffix('/').unwrap_or(path);
+    EXEMPT_POST_PATHS.contains(&path) || path.starts_with("/cron/")
+}
+
+/// Read-only mode service.
+#[derive(Clone)]
+pub struct ReadOnlyService {
+    pool: PgPool,
+    /// Read-only regardless of settings (`READ_ONLY` environment variable).
+    forced: bool,
+    settings: Cache<(), Arc<ReadOnlySettings>>,
+}
+
+impl ReadOnlyService {
+    /// Create a new read-only service.
+    pub fn new(pool: PgPool, forced: bool) -> Self {
+        Self {
+            pool,
+            forced,
+            settings: Cache::builder()
+                .max_capacity(1)
+                .time_to_live(SETTINGS_TTL)
+                .build(),
+        }
+    }
+
+    /// Whether read-only mode is forced by configuration.
+    pub fn is_forced(&self) -> bool {
+        self.forced
+    }
+
+    /// Current settings (cached).
+    pub async fn settings(&self) -> Result<Arc<ReadOnlySettings>> {
+        if let Some(settings) = self.settings.get(&()).await {
+            return Ok(settings);
+        }
+        let settings = load_settings(&self.pool).await?;
+        let settings = Arc::new(settings);
+        self.settings.insert((), settings.clone()).await;
+        Ok(settings)
+    }
+
+    /// Validate and persist settings.
+    pub async fn save_settings(&self, settings: &ReadOnlySettings) -> Result<()> {
+        save_settings(&self.pool, settings).await?;
+        self.settings.invalidate(&()).await;
+        Ok(())
+    }
+
+    /// Whether the whole site is read-only.
+    pub async fn is_read_only(&self) -> Result<bool> {
+        Ok(self.forced || self.settings().await?.enabled)
+    }
+
+    /// The explanation to return if the site is read-only, `None` otherwise.
+    pub async fn read_only_message(&self) -> Result<Option<String>> {
+        let settings = self.settings().await?;
+        Ok((self.forced || settings.enabled).then(|| settings.message().to_string()))
+    }
+
+    /// Refuse writes to `item_type` if the site is read-only or the type is
+    /// locked.
+    pub async fn check_item_type(&self, item_type: &str) -> Result<()> {
+        let settings = self.settings().await?;
+        if self.forced || settings.enabled {
+            return Err(WriteLocked {
+                message: settings.message().to_string(),
+            }
+            .into());
+        }
+        if settings.is_type_locked(item_type) {
+            return Err(WriteLocked {
+                message: format!(
+                    "{item_type} items are locked for writing. {}",
+                    settings.message()
+                ),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Load settings directly from `site_config` (used by the CLI).
+pub async fn load_settings(pool: &PgPool) -> Result<ReadOnlySettings> {
+    Ok(match SiteConfig::get(pool, READ_ONLY_SETTINGS_KEY).await? {
+        Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+            warn!(error = %e, "invalid read_only settings; using defaults");
+            ReadOnlySettings::default()
+        }),
+        None => ReadOnlySettings::default(),
+    })
+}
+
+/// Validate and persist settings directly to `site_config` (used by the CLI).
+///
+/// Running instances pick the change up within the settings cache TTL.
+pub async fn save_settings(pool: &PgPool, settings: &ReadOnlySettings) -> Result<()> {
+    settings.validate().map_err(anyhow::Error::msg)?;
+    SiteConfig::set(
+        pool,
+        READ_ONLY_SETTINGS_KEY,
+        serde_json::to_value(settings)?,
+    )
+    .await
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_to_writable() {
+        let settings: ReadOnlySettings = serde_json::from_str("{}").unwrap();
+        assert!(!settings.enabled);
+        assert_eq!(settings.message(), DEFAULT_MESSAGE);
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn blank_message_falls_back_to_default() {
+        let mut settings = ReadOnlySettings {
+            message: Some("   ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(settings.message(), DEFAULT_MESSAGE);
+        settings.message = Some("Migrating to new storage".to_string());
+        assert_eq!(settings.message(), "Migrating to new storage");
+        settings.message = Some("x".repeat(MAX_MESSAGE_LENGTH + 1));
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn locks_are_per_type() {
+        let settings: ReadOnlySettings =
+            serde_json::from_value(serde_json::json!({"locked_item_types": ["report"]})).unwrap();
+        assert!(settings.is_type_locked("report"));
+        assert!(!settings.is_type_locked("page"));
+    }
+
+    #[test]
+    fn reads_and_sign_in_stay_available() {
+        assert!(is_exempt("GET", "/item/123"));
+        assert!(is_exempt("HEAD", "/health"));
+        assert!(is_exempt("OPTIONS", "/api/items"));
+        assert!(is_exempt("POST", "/user/login"));
+        assert!(is_exempt("POST", "/user/login/"));
+        assert!(is_exempt("POST", "/api/gather/query"));
+        assert!(is_exempt("POST", "/cron/secret-key"));
+        assert!(is_exempt("POST", "/admin/read-only"));
+    }
+
+    #[test]
+    fn writes_are_not_exempt() {
+        assert!(!is_exempt("POST", "/item/add/page"));
+        assert!(!is_exempt("PUT", "/api/item/123"));
+        assert!(!is_exempt("DELETE", "/api/item/123"));
+        assert!(!is_exempt("PATCH", "/user/login"));
+        assert!(!is_exempt("POST", "/user/register"));
+    }
+}
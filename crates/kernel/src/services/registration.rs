@@ -0,0 +1,185 @@
+//! Self-registration policy.
+//!
+//! The `site_config` key `user_registration` selects how visitors get
+//! accounts:
+//!
+//! - `closed` — no self-registration (the default);
+//! - `open` — anyone may register; the email verification link activates
+//!   the account;
+//! - `admin_approval` — as `open`, but once the address is verified the
+//!   account waits for an administrator to approve it;
+//! - `invite_only` — registration needs an invitation sent by an
+//!   administrator. The invitation link proves the address, so the account
+//!   is active at once.
+//!
+//! Invitations are honored in every mode except `closed`. Sites that
+//! predate the setting keep using the boolean `allow_user_registration`
+//! (`true` means `open`).
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::models::{SiteConfig, User};
+
+/// `site_config` key holding the [`RegistrationMode`].
+pub const REGISTRATION_MODE_KEY: &str = "user_registration";
+
+/// Boolean switch used before [`REGISTRATION_MODE_KEY`] existed.
+pub const LEGACY_REGISTRATION_KEY: &str = "allow_user_registration";
+
+/// `users.data` flag set on verified accounts awaiting approval.
+pub const PENDING_APPROVAL_FLAG: &str = "pending_approval";
+
+/// How visitors may create accounts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationMode {
+    /// No self-registration.
+    #[default]
+    Closed,
+    /// Anyone may register; email verification activates the account.
+    Open,
+    /// Verified accounts wait for an administrator's approval.
+    AdminApproval,
+    /// Only invited addresses may register.
+    InviteOnly,
+}
+
+impl RegistrationMode {
+    /// Resolve the mode from the stored setting and the legacy switch.
+    ///
+    /// Unrecognized values close registration rather than open it.
+    pub fn resolve(mode: Option<&serde_json::Value>, legacy: Option<&serde_json::Value>) -> Self {
+        match mode {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "invalid user_registration mode; registration closed");
+                Self::Closed
+            }),
+            None if legacy.and_then(serde_json::Value::as_bool) == Some(true) => Self::Open,
+            None => Self::Closed,
+        }
+    }
+
+    /// Load the site-wide mode.
+    ///
+    /// A database error closes registration and logs a warning.
+    pub async fn load(pool: &PgPool) -> Self {
+        let mode = match SiteConfig::get(pool, REGISTRATION_MODE_KEY).await {
+            Ok(mode) => mode,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load user_registration; registration closed");
+                return Self::Closed;
+            }
+        };
+        let legacy = if mode.is_none() {
+            SiteConfig::get(pool, LEGACY_REGISTRATION_KEY)
+                .await
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        Self::resolve(mode.as_ref(), legacy.as_ref())
+    }
+
+    /// Persist the site-wide mode.
+    pub async fn save(self, pool: &PgPool) -> Result<()> {
+        SiteConfig::set(pool, REGISTRATION_MODE_KEY, serde_json::to_value(self)?).await
+    }
+
+    /// Whether visitors may register without an invitation.
+    pub fn allows_uninvited(self) -> bool {
+        matches!(self, Self::Open | Self::AdminApproval)
+    }
+
+    /// Whether invitations may be redeemed.
+    pub fn allows_invitations(self) -> bool {
+        self != Self::Closed
+    }
+}
+
+/// Whether a verified account is waiting for approval.
+pub fn is_pending_approval(user: &User) -> bool {
+    user.data
+        .get(PENDING_APPROVAL_FLAG)
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// `data` with the approval flag set or removed.
+pub fn with_pending_approval(data: &serde_json::Value, pending: bool) -> serde_json::Value {
+    let mut map = data.as_object().cloned().unwrap_or_default();
+    if pending {
+        map.insert(PENDING_APPROVAL_FLAG.to_string(), true.into());
+    } else {
+        map.remove(PENDING_APPROVAL_FLAG);
+    }
+    serde_json::Value::Object(map)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn mode_resolves_from_setting() {
+        assert_eq!(
+            RegistrationMode::resolve(Some(&json!("admin_approval")), Some(&json!(false))),
+            RegistrationMode::AdminApproval
+        );
+        assert_eq!(
+            RegistrationMode::resolve(Some(&json!("invite_only")), None),
+            RegistrationMode::InviteOnly
+        );
+        assert_eq!(
+            RegistrationMode::resolve(Some(&json!("bogus")), Some(&json!(true))),
+            RegistrationMode::Closed
+        );
+    }
+
+    #[test]
+    fn mode_falls_back_to_legacy_switch() {
+        assert_eq!(
+            RegistrationMode::resolve(None, Some(&json!(true))),
+            RegistrationMode::Open
+        );
+        assert_eq!(
+            RegistrationMode::resolve(None, Some(&json!(false))),
+            RegistrationMode::Closed
+        );
+        assert_eq!(
+            RegistrationMode::resolve(None, None),
+            RegistrationMode::Closed
+        );
+    }
+
+    #[test]
+    fn mode_permissions() {
+        assert!(RegistrationMode::Open.allows_uninvited());
+        assert!(RegistrationMode::AdminApproval.allows_uninvited());
+        assert!(!RegistrationMode::InviteOnly.allows_uninvited());
+        assert!(RegistrationMode::InviteOnly.allows_invitations());
+        assert!(!RegistrationMode::Closed.allows_invitations());
+    }
+
+    #[test]
+    fn pending_flag_round_trip() {
+        let data = with_pending_approval(&json!({"theme": "dark"}), true);
+        assert_eq!(data["pending_approval"], true);
+        assert_eq!(data["theme"], "dark");
+
+        let data = with_pending_approval(&data, false);
+        assert!(data.get("pending_approval").is_none());
+        assert_eq!(data["theme"], "dark");
+
+        assert_eq!(
+            with_pending_approval(&serde_json::Value::Null, true),
+            json!({"pending_approval": true})
+        );
+    }
+}
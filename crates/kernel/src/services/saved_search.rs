@@ -0,0 +1,424 @@
+//! Saved searches and query alerts.
+//!
+//! Users save a search query, optionally restricted to item types. With
+//! `alert` set, the `trovato_saved_searches` plugin's `tap_cron` re-runs
+//! the query (via the `check-alerts` host function) and compares the
+//! matches with those of the previous check: new matches are mailed to the
+//! owner (template `email/saved_search_alert`) and, if the search has a
+//! webhook, posted there as a [`SavedSearchAlert`]. The first check of a
+//! search only records its current matches. The service only exists while
+//! the plugin is enabled.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::SiteConfig;
+use crate::search::{SearchResult, SearchService};
+use crate::services::mail::MailService;
+use crate::theme::ThemeEngine;
+
+/// Most saved searches per user.
+pub const MAX_SAVED_SEARCHES: i64 = 50;
+
+/// Matches compared per check; older matches are not tracked.
+pub const ALERT_MATCH_LIMIT: i64 = 50;
+
+/// Alert searches checked per cron run, least recently checked first.
+const ALERT_BATCH: i64 = 200;
+
+/// A saved search.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    /// Owner.
+    pub user_id: Uuid,
+    pub name: String,
+    /// Search terms.
+    pub query: String,
+    /// Item types to match; empty matches all types.
+    pub item_types: Vec<String>,
+    /// Whether new matches are sent to the owner.
+    pub alert: bool,
+    /// URL new matches are posted to.
+    pub webhook: Option<String>,
+    /// Unix timestamp of the last alert check.
+    pub last_checked: Option<i64>,
+    /// Unix timestamp the search was saved.
+    pub created: i64,
+}
+
+/// Fields of a saved search set by its owner.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SavedSearchInput {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub item_types: Vec<String>,
+    #[serde(default)]
+    pub alert: bool,
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+/// New matches of a saved search, as posted to its webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchAlert {
+    pub saved_search_id: Uuid,
+    pub name: String,
+    pub query: String,
+    pub matches: Vec<AlertMatch>,
+}
+
+/// An item newly matching a saved search.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertMatch {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub title: String,
+    /// Absolute URL of the item.
+    pub url: String,
+}
+
+/// An alert search due for checking, with its owner's address.
+#[derive(sqlx::FromRow)]
+struct AlertRow {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    query: String,
+    item_types: Vec<String>,
+    webhook: Option<String>,
+    last_results: Vec<Uuid>,
+    last_checked: Option<i64>,
+    mail: String,
+    language: Option<String>,
+}
+
+/// Whether `url` may receive alerts: an absolute `http` or `https` URL.
+pub fn is_valid_webhook_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+}
+
+/// Results of `current` that are not in `last`, in their order.
+fn new_matches<'a>(last: &[Uuid], current: &'a [SearchResult]) -> Vec<&'a SearchResult> {
+    let last: HashSet<&Uuid> = last.iter().collect();
+    current.iter().filter(|r| !last.contains(&r.id)).collect()
+}
+
+/// Saved search service.
+pub struct SavedSearchService {
+    pool: PgPool,
+    search: Arc<SearchService>,
+    mail: Arc<MailService>,
+    theme: Arc<ThemeEngine>,
+    site_url: String,
+}
+
+impl SavedSearchService {
+    /// Create a new saved search service.
+    pub fn new(
+        pool: PgPool,
+        search: Arc<SearchService>,
+        mail: Arc<MailService>,
+        theme: Arc<ThemeEngine>,
+        site_url: String,
+    ) -> Self {
+        Self {
+            pool,
+            search,
+            mail,
+            theme,
+            site_url,
+        }
+    }
+
+    /// A user's saved searches, newest first.
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<SavedSearch>> {
+        sqlx::query_as::<_, SavedSearch>(
+            r#"
+            SELECT id, user_id, name, query, item_types, alert, webhook, last_checked, created
+            FROM saved_search
+            WHERE user_id = $1
+            ORDER BY created DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list saved searches")
+    }
+
+    /// Load a saved search.
+    pub async fn load(&self, id: Uuid) -> Result<Option<SavedSearch>> {
+        sqlx::query_as::<_, SavedSearch>(
+            r#"
+            SELECT id, user_id, name, query, item_types, alert, webhook, last_checked, created
+            FROM saved_search
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to load saved search")
+    }
+
+    /// Number of searches a user has saved.
+    pub async fn count(&self, user_id: Uuid) -> Result<i64> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM saved_search WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .context("failed to count saved searches")
+    }
+
+    /// Save a search for a user.
+    pub async fn create(&self, user_id: Uuid, input: &SavedSearchInput) -> Result<SavedSearch> {
+        sqlx::query_as::<_, SavedSearch>(
+            r#"
+            INSERT INTO saved_search (id, user_id, name, query, item_types, alert, webhook, created)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, name, query, item_types, alert, webhook, last_checked, created
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(user_id)
+        .bind(&input.name)
+        .bind(&input.query)
+        .bind(&input.item_types)
+        .bind(input.alert)
+        .bind(&input.webhook)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to save search")
+    }
+
+    /// Replace a saved search's fields.
+    ///
+    /// A changed query or filter starts over: the next check only records
+    /// the matches.
+    pub async fn update(&self, id: Uuid, input: &SavedSearchInput) -> Result<Option<SavedSearch>> {
+        sqlx::query_as::<_, SavedSearch>(
+            r#"
+            UPDATE saved_search
+            SET last_results = CASE WHEN query = $3 AND item_types = $4
+                                    THEN last_results ELSE '{}' END,
+                last_checked = CASE WHEN query = $3 AND item_types = $4
+                                    THEN last_checked ELSE NULL END,
+                name = $2, query = $3, item_types = $4, alert = $5, webhook = $6
+            WHERE id = $1
+            RETURNING id, user_id, name, query, item_types, alert, webhook, last_checked, created
+            "#,
+        )
+        .bind(id)
+        .bind(&input.name)
+        .bind(&input.query)
+        .bind(&input.item_types)
+        .bind(input.alert)
+        .bind(&input.webhook)
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to update saved search")
+    }
+
+    /// Delete a saved search. Returns `false` if it did not exist.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM saved_search WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete saved search")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The newest items matching a saved search, as its owner sees them.
+    pub async fn matches(&self, saved: &SavedSearch) -> Result<Vec<SearchResult>> {
+        self.search
+            .newest_matches(
+                &saved.query,
+                &saved.item_types,
+                saved.user_id,
+                ALERT_MATCH_LIMIT,
+            )
+            .await
+    }
+
+    /// Re-run alert searches and notify owners of new matches.
+    ///
+    /// Searches of blocked users are skipped. A search whose query fails is
+    /// logged and retried on the next run. Returns the number of alerts
+    /// sent.
+    pub async fn check_alerts(&self, http: &reqwest::Client) -> Result<u64> {
+        let due = sqlx::query_as::<_, AlertRow>(
+            r#"
+            SELECT s.id, s.user_id, s.name, s.query, s.item_types, s.webhook,
+                   s.last_results, s.last_checked, u.mail, u.language
+            FROM saved_search s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.alert AND u.status = 1
+            ORDER BY s.last_checked NULLS FIRST
+            LIMIT $1
+            "#,
+        )
+        .bind(ALERT_BATCH)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load saved search alerts")?;
+
+        let mut sent = 0;
+        for search in due {
+            let current = match self
+                .search
+                .newest_matches(
+                    &search.query,
+                    &search.item_types,
+                    search.user_id,
+                    ALERT_MATCH_LIMIT,
+                )
+                .await
+            {
+                Ok(current) => current,
+                Err(e) => {
+                    warn!(saved_search = %search.id, error = %e, "saved search check failed");
+                    continue;
+                }
+            };
+
+            let new = new_matches(&search.last_results, &current);
+            if search.last_checked.is_some() && !new.is_empty() {
+                self.notify(&search, &new, http).await;
+                sent += 1;
+            }
+
+            let ids: Vec<Uuid> = current.iter().map(|r| r.id).collect();
+            sqlx::query(
+                "UPDATE saved_search SET last_results = $2, last_checked = $3 WHERE id = $1",
+            )
+            .bind(search.id)
+            .bind(&ids)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .context("failed to record saved search check")?;
+        }
+        Ok(sent)
+    }
+
+    /// Mail new matches to the owner and post them to the webhook.
+    /// Delivery failures are logged.
+    async fn notify(&self, search: &AlertRow, new: &[&SearchResult], http: &reqwest::Client) {
+        let alert = SavedSearchAlert {
+            saved_search_id: search.id,
+            name: search.name.clone(),
+            query: search.query.clone(),
+            matches: new
+                .iter()
+                .map(|r| AlertMatch {
+                    id: r.id,
+                    item_type: r.item_type.clone(),
+                    title: r.title.clone(),
+                    url: format!("{}/item/{}", self.site_url, r.id),
+                })
+                .collect(),
+        };
+        debug!(saved_search = %search.id, matches = alert.matches.len(), "saved search alert");
+
+        if !search.mail.is_empty() {
+            let site_name = SiteConfig::site_name(&self.pool)
+                .await
+                .unwrap_or_else(|_| "Trovato".to_string());
+            let subject = format!("New results for \"{}\" at {site_name}", search.name);
+            let search_url = format!(
+                "{}/search?q={}",
+                self.site_url,
+                urlencoding::encode(&search.query)
+            );
+
+            let mut context = tera::Context::new();
+            context.insert("site_name", &site_name);
+            context.insert("search_name", &search.name);
+            context.insert("query", &search.query);
+            context.insert("matches", &alert.matches);
+            context.insert("action_url", &search_url);
+
+            match self.mail.compose(
+                &self.theme,
+                "saved_search_alert",
+                &search.mail,
+                &subject,
+                search.language.as_deref(),
+                &context,
+            ) {
+                Ok(message) => {
+                    if let Err(e) = self.mail.queue(message).await {
+                        warn!(saved_search = %search.id, error = %e, "failed to queue saved search alert");
+                    }
+                }
+                Err(e) => {
+                    warn!(saved_search = %search.id, error = %e, "failed to render saved search alert");
+                }
+            }
+        }
+
+        if let Some(ref url) = search.webhook {
+            let result = http
+                .post(url)
+                .json(&alert)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status());
+            if let Err(e) = result {
+                warn!(saved_search = %search.id, error = %e, "failed to post saved search alert webhook");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn result(id: Uuid) -> SearchResult {
+        SearchResult {
+            id,
+            item_type: "article".to_string(),
+            title: "Article".to_string(),
+            rank: 0.5,
+            snippet: None,
+            fuzzy: false,
+        }
+    }
+
+    #[test]
+    fn new_matches_are_those_not_seen_last_time() {
+        let (a, b, c) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let current = vec![result(c), result(b), result(a)];
+
+        let new = new_matches(&[a, b], &current);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id, c);
+
+        assert!(new_matches(&[a, b, c], &current).is_empty());
+        assert_eq!(new_matches(&[], &current).len(), 3);
+    }
+
+    #[test]
+    fn webhooks_must_be_http_urls() {
+        assert!(is_valid_webhook_url("https://hooks.example.com/argus"));
+        assert!(is_valid_webhook_url("http://localhost:8080/alerts"));
+        assert!(!is_valid_webhook_url("ftp://example.com/alerts"));
+        assert!(!is_valid_webhook_url("/relative/path"));
+        assert!(!is_valid_webhook_url("javascript:alert(1)"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Scheduled publishing listing and schedule edits.
+//!
+//! The `trovato_scheduled_publishing` plugin publishes and unpublishes
+//! items from its cron tap, reading Unix timestamps from the
+//! `field_publish_on` and `field_unpublish_on` fields. This module lists the
+//! items still waiting for one of those changes and builds the field
+//! patches that reschedule or cancel them. Patches are saved with
+//! [`ItemService::patch`](crate::content::ItemService::patch), so item
+//! access, validation and revisions apply as for any other edit.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::content::{ItemValidationFailed, ItemViolation};
+
+/// Field holding the publish timestamp.
+pub const PUBLISH_ON_FIELD: &str = "field_publish_on";
+
+/// Field holding the unpublish timestamp.
+pub const UNPUBLISH_ON_FIELD: &str = "field_unpublish_on";
+
+/// An item with a pending publish or unpublish.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScheduledItem {
+    pub id: Uuid,
+    pub title: String,
+    #[sqlx(rename = "type")]
+    pub item_type: String,
+    pub status: i16,
+    pub stage_id: Uuid,
+    pub changed: i64,
+    /// Publish time, pending while the item is unpublished.
+    pub publish_on: Option<i64>,
+    /// Unpublish time.
+    pub unpublish_on: Option<i64>,
+    /// The earliest pending change.
+    pub next_on: i64,
+}
+
+/// Column the scheduled items listing is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleSort {
+    /// Earliest pending change.
+    #[default]
+    Next,
+    PublishOn,
+    UnpublishOn,
+    Title,
+    Type,
+    Changed,
+}
+
+impl ScheduleSort {
+    /// SQL expression sorted on; only ever one of these literals.
+    fn column(self) -> &'static str {
+        match self {
+            Self::Next => "next_on",
+            Self::PublishOn => "publish_on",
+            Self::UnpublishOn => "unpublish_on",
+            Self::Title => "lower(title)",
+            Self::Type => "type",
+            Self::Changed => "changed",
+        }
+    }
+}
+
+/// Filters and sort order for [`list`].
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleFilter {
+    /// Only items of this type.
+    pub item_type: Option<String>,
+    /// Only items in this stage.
+    pub stage: Option<Uuid>,
+    pub sort: ScheduleSort,
+    /// Sort descending.
+    pub desc: bool,
+}
+
+/// An item's publish and unpublish times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(default)]
+    pub publish_on: Option<i64>,
+    #[serde(default)]
+    pub unpublish_on: Option<i64>,
+}
+
+impl Schedule {
+    /// Read the schedule from item fields.
+    ///
+    /// Timestamps are numbers or numeric strings (as submitted by forms),
+    /// matching what the plugin's cron tap compares.
+    pub fn from_fields(fields: &serde_json::Value) -> Self {
+        Self {
+            publish_on: timestamp(fields.get(PUBLISH_ON_FIELD)),
+            unpublish_on: timestamp(fields.get(UNPUBLISH_ON_FIELD)),
+        }
+    }
+
+    /// Check that the item is unpublished after it is published.
+    pub fn validate(&self) -> Result<(), ItemValidationFailed> {
+        match (self.publish_on, self.unpublish_on) {
+            (Some(publish), Some(unpublish)) if unpublish <= publish => Err(ItemValidationFailed {
+                violations: vec![ItemViolation {
+                    field: UNPUBLISH_ON_FIELD.to_string(),
+                    message: "The unpublish date must be after the publish date.".to_string(),
+                    code: "unpublish_before_publish".to_string(),
+                }],
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Merge patch setting both fields; unset times remove their field.
+    pub fn to_patch(&self) -> serde_json::Value {
+        serde_json::json!({
+            PUBLISH_ON_FIELD: self.publish_on,
+            UNPUBLISH_ON_FIELD: self.unpublish_on,
+        })
+    }
+}
+
+/// Parse a timestamp field value.
+fn timestamp(value: Option<&serde_json::Value>) -> Option<i64> {
+    match value? {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Items with a pending publish or unpublish, and the total matching.
+///
+/// A publish is pending while the item is unpublished, an unpublish while
+/// it is published. Past times are included: the plugin's next cron run
+/// applies them.
+pub async fn list(
+    pool: &PgPool,
+    filter: &ScheduleFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<ScheduledItem>, i64)> {
+    // Timestamps as bigint, NULL when missing or not numeric.
+    let schedule = format!(
+        "SELECT id, title, type, status, stage_id, changed, \
+         CASE WHEN fields->>'{PUBLISH_ON_FIELD}' ~ '^-?[0-9]+$' \
+              THEN (fields->>'{PUBLISH_ON_FIELD}')::bigint END AS publish_on, \
+         CASE WHEN fields->>'{UNPUBLISH_ON_FIELD}' ~ '^-?[0-9]+$' \
+              THEN (fields->>'{UNPUBLISH_ON_FIELD}')::bigint END AS unpublish_on \
+         FROM item \
+         WHERE ($1::text IS NULL OR type = $1) AND ($2::uuid IS NULL OR stage_id = $2)"
+    );
+    let pending = "(status = 0 AND publish_on IS NOT NULL) \
+                   OR (status = 1 AND unpublish_on IS NOT NULL)";
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM ({schedule}) s WHERE {pending}"
+    ))
+    .bind(&filter.item_type)
+    .bind(filter.stage)
+    .fetch_one(pool)
+    .await
+    .context("failed to count scheduled items")?;
+
+    let direction = if filter.desc { "DESC" } else { "ASC" };
+    let items = sqlx::query_as::<_, ScheduledItem>(&format!(
+        "SELECT *, LEAST(CASE WHEN status = 0 THEN publish_on END, \
+                         CASE WHEN status = 1 THEN unpublish_on END) AS next_on \
+         FROM ({schedule}) s WHERE {pending} \
+         ORDER BY {} {direction} NULLS LAST, id \
+         LIMIT $3 OFFSET $4",
+        filter.sort.column()
+    ))
+    .bind(&filter.item_type)
+    .bind(filter.stage)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("failed to list scheduled items")?;
+
+    Ok((items, total))
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_reads_numbers_and_numeric_strings() {
+        let fields = serde_json::json!({
+            "field_publish_on": 1_800_000_000,
+            "field_unpublish_on": " 1800003600",
+        });
+        let schedule = Schedule::from_fields(&fields);
+        assert_eq!(schedule.publish_on, Some(1_800_000_000));
+        assert_eq!(schedule.unpublish_on, Some(1_800_003_600));
+
+        let empty = Schedule::from_fields(&serde_json::json!({"field_publish_on": ""}));
+        assert_eq!(empty, Schedule::default());
+    }
+
+    #[test]
+    fn unpublish_must_follow_publish() {
+        let schedule = |publish_on, unpublish_on| Schedule {
+            publish_on,
+            unpublish_on,
+        };
+        assert!(schedule(Some(100), Some(200)).validate().is_ok());
+        assert!(schedule(None, Some(100)).validate().is_ok());
+        assert!(schedule(Some(100), None).validate().is_ok());
+
+        let err = schedule(Some(200), Some(200)).validate().unwrap_err();
+        assert_eq!(err.violations[0].field, UNPUBLISH_ON_FIELD);
+    }
+
+    #[test]
+    fn patch_removes_unset_times() {
+        let patch = Schedule {
+            publish_on: Some(100),
+            unpublish_on: None,
+        }
+        .to_patch();
+        assert_eq!(patch["field_publish_on"], 100);
+        assert!(patch["field_unpublish_on"].is_null());
+        assert!(
+            patch
+                .as_object()
+                .unwrap()
+                .contains_key("field_unpublish_on")
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Scheduled field changes ("timed updates").
+//!
+//! Editors schedule a JSON merge patch of an item's fields for a later
+//! time, e.g. to swap a banner image on a given date. The
+//! `trovato_scheduled_updates` plugin's `tap_cron` applies due updates (via
+//! the `apply-due` host function) through [`ItemService::patch`] as the
+//! user who scheduled them, so `tap_item_presave`, `tap_item_validate` and
+//! `tap_item_update` run and the change gets a revision like any other
+//! edit. An update that cannot be applied (the scheduler lost edit access,
+//! a field no longer validates) is marked failed with the reason and not
+//! retried. The service only exists while the plugin is enabled.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::content::{ItemPatch, ItemService, ItemValidationFailed};
+use crate::models::User;
+use crate::permissions::PermissionService;
+use crate::tap::UserContext;
+
+/// Status of an update that has not run yet.
+pub const STATUS_PENDING: &str = "pending";
+
+/// Status of an update that was applied.
+pub const STATUS_APPLIED: &str = "applied";
+
+/// Status of an update that could not be applied.
+pub const STATUS_FAILED: &str = "failed";
+
+/// Most pending updates one item may have.
+pub const MAX_PENDING_PER_ITEM: i64 = 50;
+
+/// Longest label.
+pub const MAX_LABEL_LEN: usize = 255;
+
+/// Due updates applied per cron run, soonest first.
+const APPLY_BATCH: i64 = 100;
+
+/// A scheduled change of an item's fields.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScheduledUpdate {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    /// JSON merge patch applied to the item's fields.
+    pub fields: serde_json::Value,
+    /// Unix timestamp after which the update is applied.
+    pub update_at: i64,
+    pub label: Option<String>,
+    /// User the update is applied as.
+    pub scheduled_by: Option<Uuid>,
+    /// [`STATUS_PENDING`], [`STATUS_APPLIED`] or [`STATUS_FAILED`].
+    pub status: String,
+    /// Why applying failed.
+    pub error: Option<String>,
+    /// Unix timestamp the update was applied or failed.
+    pub processed: Option<i64>,
+    /// Unix timestamp when created.
+    pub created: i64,
+}
+
+/// Fields of a scheduled update set by the editor.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduledUpdateInput {
+    /// JSON merge patch for the item's fields.
+    pub fields: serde_json::Value,
+    /// Unix timestamp to apply the update at; must be in the future.
+    pub update_at: i64,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl ScheduledUpdateInput {
+    /// Check the patch and time. `field_names` are the fields defined by
+    /// the item's type; the patch may only touch those.
+    pub fn validate(&self, field_names: &[&str], now: i64) -> Result<(), String> {
+        let Some(patch) = self.fields.as_object().filter(|p| !p.is_empty()) else {
+            return Err("fields must be a non-empty JSON object".to_string());
+        };
+        if let Some(unknown) = patch.keys().find(|k| !field_names.contains(&k.as_str())) {
+            return Err(format!("the item has no field {unknown}"));
+        }
+        if self.update_at <= now {
+            return Err("update_at must be in the future".to_string());
+        }
+        if self.label.as_ref().is_some_and(|l| l.len() > MAX_LABEL_LEN) {
+            return Err(format!("label must be at most {MAX_LABEL_LEN} characters"));
+        }
+        Ok(())
+    }
+}
+
+/// Revision log message of an applied update.
+fn revision_log(label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("Scheduled update: {label}"),
+        None => "Scheduled update".to_string(),
+    }
+}
+
+/// Scheduled update service.
+pub struct ScheduledUpdateService {
+    pool: PgPool,
+    items: Arc<ItemService>,
+    permissions: PermissionService,
+}
+
+impl ScheduledUpdateService {
+    /// Create a new scheduled update service.
+    pub fn new(pool: PgPool, items: Arc<ItemService>, permissions: PermissionService) -> Self {
+        Self {
+            pool,
+            items,
+            permissions,
+        }
+    }
+
+    /// Load a scheduled update.
+    pub async fn load(&self, id: Uuid) -> Result<Option<ScheduledUpdate>> {
+        sqlx::query_as::<_, ScheduledUpdate>("SELECT * FROM scheduled_update WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("failed to load scheduled update")
+    }
+
+    /// An item's updates, pending and processed, latest first.
+    pub async fn list_for_item(&self, item_id: Uuid) -> Result<Vec<ScheduledUpdate>> {
+        sqlx::query_as::<_, ScheduledUpdate>(
+            "SELECT * FROM scheduled_update WHERE item_id = $1 ORDER BY update_at DESC, id DESC",
+        )
+        .bind(item_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list scheduled updates of item")
+    }
+
+    /// Pending updates of all items, soonest first.
+    pub async fn list_upcoming(&self, limit: i64, offset: i64) -> Result<Vec<ScheduledUpdate>> {
+        sqlx::query_as::<_, ScheduledUpdate>(
+            r#"
+            SELECT * FROM scheduled_update
+            WHERE status = 'pending'
+            ORDER BY update_at, id
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list upcoming scheduled updates")
+    }
+
+    /// Number of pending updates, of one item or of all items.
+    pub async fn count_pending(&self, item_id: Option<Uuid>) -> Result<i64> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM scheduled_update WHERE status = 'pending' AND ($1::uuid IS NULL OR item_id = $1)",
+        )
+        .bind(item_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to count scheduled updates")
+    }
+
+    /// Schedule an update of an item's fields.
+    pub async fn create(
+        &self,
+        item_id: Uuid,
+        input: &ScheduledUpdateInput,
+        scheduled_by: Uuid,
+    ) -> Result<ScheduledUpdate> {
+        sqlx::query_as::<_, ScheduledUpdate>(
+            r#"
+            INSERT INTO scheduled_update (id, item_id, fields, update_at, label, scheduled_by, created)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::now_v7())
+        .bind(item_id)
+        .bind(&input.fields)
+        .bind(input.update_at)
+        .bind(&input.label)
+        .bind(scheduled_by)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to schedule update")
+    }
+
+    /// Cancel a pending update.
+    ///
+    /// Returns `false` if it does not exist or already ran.
+    pub async fn cancel(&self, id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM scheduled_update WHERE id = $1 AND status = 'pending'")
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .context("failed to cancel scheduled update")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Apply updates due at `now`. Returns the number applied.
+    pub async fn apply_due(&self, now: i64) -> Result<u64> {
+        let due = sqlx::query_as::<_, ScheduledUpdate>(
+            r#"
+            SELECT * FROM scheduled_update
+            WHERE status = 'pending' AND update_at <= $1
+            ORDER BY update_at, id
+            LIMIT $2
+            "#,
+        )
+        .bind(now)
+        .bind(APPLY_BATCH)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to load due scheduled updates")?;
+
+        let mut applied = 0;
+        for update in due {
+            match self.apply(&update).await {
+                Ok(()) => {
+                    info!(update_id = %update.id, item_id = %update.item_id, "applied scheduled update");
+                    self.finish(update.id, STATUS_APPLIED, None, now).await?;
+                    applied += 1;
+                }
+                Err(error) => {
+                    warn!(update_id = %update.id, item_id = %update.item_id, error = %error, "scheduled update failed");
+                    self.finish(update.id, STATUS_FAILED, Some(&error), now)
+                        .await?;
+                }
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Apply one update as its scheduler. The error is the message stored
+    /// with the failed update.
+    async fn apply(&self, update: &ScheduledUpdate) -> Result<(), String> {
+        let user = self.scheduler_context(update.scheduled_by).await?;
+        let patch = ItemPatch {
+            fields: update.fields.clone(),
+            unmodified_since: None,
+            log: Some(revision_log(update.label.as_deref())),
+        };
+        match self.items.patch(update.item_id, patch, &user).await {
+            Ok(Some(item)) => {
+                if let Err(e) =
+                    crate::services::pathauto::update_alias_item(&self.pool, &item).await
+                {
+                    warn!(error = %e, item_id = %item.id, "pathauto alias update failed");
+                }
+                Ok(())
+            }
+            Ok(None) => Err("item no longer exists".to_string()),
+            Err(e) => Err(match e.downcast_ref::<ItemValidationFailed>() {
+                Some(failed) => failed.messages().join(" "),
+                None => format!("{e:#}"),
+            }),
+        }
+    }
+
+    /// The user context an update is applied with: the scheduler's current
+    /// permissions.
+    async fn scheduler_context(&self, user_id: Option<Uuid>) -> Result<UserContext, String> {
+        let user = match user_id {
+            Some(id) => User::find_by_id(&self.pool, id)
+                .await
+                .map_err(|e| format!("{e:#}"))?,
+            None => None,
+        };
+        let Some(user) = user.filter(|u| u.status == 1) else {
+            return Err("the user who scheduled the update is no longer active".to_string());
+        };
+        let mut permissions: Vec<String> = self
+            .permissions
+            .load_user_permissions(&user)
+            .await
+            .map_err(|e| format!("{e:#}"))?
+            .into_iter()
+            .collect();
+        if user.is_admin {
+            permissions.push("administer site".to_string());
+        }
+        Ok(UserContext::authenticated(user.id, permissions))
+    }
+
+    /// Record the outcome of an update.
+    async fn finish(&self, id: Uuid, status: &str, error: Option<&str>, now: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE scheduled_update SET status = $2, error = $3, processed = $4 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("failed to record scheduled update outcome")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn input(fields: serde_json::Value, update_at: i64) -> ScheduledUpdateInput {
+        ScheduledUpdateInput {
+            fields,
+            update_at,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn validate_accepts_future_patch_of_known_fields() {
+        let fields = ["field_banner", "field_body"];
+        let update = input(serde_json::json!({"field_banner": "file-2"}), 200);
+        assert_eq!(update.validate(&fields, 100), Ok(()));
+
+        // null removes a field and is a valid merge patch value.
+        let update = input(serde_json::json!({"field_banner": null}), 200);
+        assert_eq!(update.validate(&fields, 100), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_bad_input() {
+        let fields = ["field_banner"];
+        assert!(
+            input(serde_json::json!({}), 200)
+                .validate(&fields, 100)
+                .is_err()
+        );
+        assert!(
+            input(serde_json::json!([1]), 200)
+                .validate(&fields, 100)
+                .is_err()
+        );
+        assert_eq!(
+            input(serde_json::json!({"field_other": 1}), 200).validate(&fields, 100),
+            Err("the item has no field field_other".to_string())
+        );
+        assert!(
+            input(serde_json::json!({"field_banner": 1}), 100)
+                .validate(&fields, 100)
+                .is_err()
+        );
+
+        let mut long = input(serde_json::json!({"field_banner": 1}), 200);
+        long.label = Some("x".repeat(MAX_LABEL_LEN + 1));
+        assert!(long.validate(&fields, 100).is_err());
+    }
+
+    #[test]
+    fn revision_log_includes_label() {
+        assert_eq!(revision_log(None), "Scheduled update");
+        assert_eq!(
+            revision_log(Some("Summer banner")),
+            "Scheduled update: Summer banner"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Multisite: per-request site scope.
+//!
+//! With `TENANT_RESOLUTION_METHOD=host` the tenant middleware looks the
+//! request's `Host` header up in the `site` table and runs the rest of the
+//! request inside a [`SiteScope`]. While a scope is active:
+//!
+//! - config variables ([`crate::models::SiteConfig`]) are read from and
+//!   written to the site's tenant, falling back to the default tenant;
+//! - new items and files are owned by the site's tenant, and items of
+//!   other tenants are hidden from access checks and gathers;
+//! - cache keys are prefixed with the tenant, so sites never share
+//!   cached pages or values;
+//! - plugins switched off for the site are skipped by the tap dispatcher
+//!   and [`crate::state::AppState::is_plugin_enabled`].
+//!
+//! Outside a scope (single-site deployments, cron, CLI) everything runs as
+//! the default tenant, exactly as before.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use moka::future::Cache;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::site::{Site, normalize_host};
+use crate::models::tenant::DEFAULT_TENANT_ID;
+
+/// How long hostname lookups are cached.
+///
+/// Kept short so sites added or switched from the CLI take effect quickly.
+const SITE_TTL: Duration = Duration::from_secs(30);
+
+/// Maximum number of cached hostnames.
+const MAX_CACHED_HOSTS: u64 = 1_000;
+
+tokio::task_local! {
+    static SITE_SCOPE: SiteScope;
+}
+
+/// The site a request is being served for.
+#[derive(Debug, Clone)]
+pub struct SiteScope {
+    /// Tenant owning the site's config and content.
+    pub tenant_id: Uuid,
+    /// Plugins switched off for the site.
+    pub disabled_plugins: Arc<HashSet<String>>,
+}
+
+impl SiteScope {
+    /// Scope of the default tenant, with every plugin available.
+    pub fn default_site() -> Self {
+        Self {
+            tenant_id: DEFAULT_TENANT_ID,
+            disabled_plugins: Arc::default(),
+        }
+    }
+}
+
+/// Run `fut` inside a site scope.
+pub async fn scope<F: Future>(site: SiteScope, fut: F) -> F::Output {
+    SITE_SCOPE.scope(site, fut).await
+}
+
+/// The active site scope, if any.
+///
+/// Lets work that outlives the request task, such as a streamed response
+/// body, re-enter the scope with [`scope`].
+pub fn current() -> Option<SiteScope> {
+    SITE_SCOPE.try_with(SiteScope::clone).ok()
+}
+
+/// Whether the current task runs inside a site scope.
+///
+/// True for every request of a multisite deployment, including those
+/// served by the default tenant.
+pub fn in_scope() -> bool {
+    SITE_SCOPE.try_with(|_| ()).is_ok()
+}
+
+/// Tenant of the active site scope, if any.
+///
+/// Returns `None` outside a scope and for the default tenant, where
+/// nothing needs scoping.
+pub fn scoped_tenant_id() -> Option<Uuid> {
+    SITE_SCOPE
+        .try_with(|site| site.tenant_id)
+        .ok()
+        .filter(|id| *id != DEFAULT_TENANT_ID)
+}
+
+/// Tenant of the active site scope, or the default tenant.
+pub fn current_tenant_id() -> Uuid {
+    scoped_tenant_id().unwrap_or(DEFAULT_TENANT_ID)
+}
+
+/// Whether a plugin is available to the active site.
+///
+/// Always true outside a scope; deployment-wide enablement is checked
+/// separately.
+pub fn plugin_enabled(plugin: &str) -> bool {
+    SITE_SCOPE
+        .try_with(|site| !site.disabled_plugins.contains(plugin))
+        .unwrap_or(true)
+}
+
+/// Resolves hostnames to site scopes.
+#[derive(Clone)]
+pub struct SiteService {
+    pool: PgPool,
+    /// Tenant resolution method (`TENANT_RESOLUTION_METHOD`).
+    method: String,
+    hosts: Cache<String, Arc<SiteScope>>,
+}
+
+impl SiteService {
+    /// Create a new site service.
+    pub fn new(pool: PgPool, method: &str) -> Self {
+        Self {
+            pool,
+            method: method.to_string(),
+            hosts: Cache::builder()
+                .max_capacity(MAX_CACHED_HOSTS)
+                .time_to_live(SITE_TTL)
+                .build(),
+        }
+    }
+
+    /// The configured tenant resolution method.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Resolve a `Host` header value to a site scope.
+    ///
+    /// Unknown or malformed hostnames resolve to the default site.
+    pub async fn resolve(&self, host: &str) -> Result<Arc<SiteScope>> {
+        let Some(hostname) = normalize_host(host) else {
+            return Ok(Arc::new(SiteScope::default_site()));
+        };
+        if let Some(site) = self.hosts.get(&hostname).await {
+            return Ok(site);
+        }
+
+        let site = match Site::find_by_hostname(&self.pool, &hostname).await? {
+            Some(site) => SiteScope {
+                tenant_id: site.tenant_id,
+                disabled_plugins: Arc::new(
+                    Site::disabled_plugins(&self.pool, site.tenant_id)
+                        .await?
+                        .into_iter()
+                        .collect(),
+                ),
+            },
+            None => SiteScope::default_site(),
+        };
+        let site = Arc::new(site);
+        self.hosts.insert(hostname, site.clone()).await;
+        Ok(site)
+    }
+
+    /// Forget cached lookups after sites or plugin switches change.
+    pub fn invalidate(&self) {
+        self.hosts.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn defaults_apply_outside_a_scope() {
+        assert!(!in_scope());
+        assert_eq!(scoped_tenant_id(), None);
+        assert_eq!(current_tenant_id(), DEFAULT_TENANT_ID);
+        assert!(plugin_enabled("blog"));
+    }
+
+    #[tokio::test]
+    async fn scope_sets_tenant_and_plugins() {
+        let tenant_id = Uuid::now_v7();
+        let site = SiteScope {
+            tenant_id,
+            disabled_plugins: Arc::new(HashSet::from(["blog".to_string()])),
+        };
+
+        scope(site, async {
+            assert_eq!(scoped_tenant_id(), Some(tenant_id));
+            assert_eq!(current_tenant_id(), tenant_id);
+            assert!(!plugin_enabled("blog"));
+            assert!(plugin_enabled("media"));
+        })
+        .await;
+
+        scope(SiteScope::default_site(), async {
+            assert!(in_scope());
+            assert_eq!(scoped_tenant_id(), None);
+            assert_eq!(current_tenant_id(), DEFAULT_TENANT_ID);
+        })
+        .await;
+    }
+}
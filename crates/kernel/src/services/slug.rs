@@ -0,0 +1,466 @@
+//! Language-aware slug generation.
+//!
+//! Transliterates text to ASCII using per-language rules, optionally drops
+//! stopwords, and joins the remaining words with a configurable separator.
+//! Used by pathauto for `[title]` tokens and exposed to plugins through the
+//! `trovato:kernel/slug` host function.
+//!
+//! Site-wide options live in `site_config` under the key `slug_settings`:
+//!
+//! ```json
+//! { "separator": "-", "max_length": 128, "remove_stopwords": false }
+//! ```
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::models::SiteConfig;
+
+/// `site_config` key holding [`SlugOptions`].
+pub const SLUG_SETTINGS_KEY: &str = "slug_settings";
+
+/// Default maximum slug length in bytes.
+pub const DEFAULT_MAX_LENGTH: usize = 128;
+
+/// Hard upper bound on the configurable maximum length.
+const MAX_MAX_LENGTH: usize = 255;
+
+/// Separators accepted in [`SlugOptions::separator`].
+///
+/// Restricted to characters that are URL-safe and cannot form path
+/// traversal sequences.
+const ALLOWED_SEPARATORS: &[char] = &['-', '_'];
+
+/// Options controlling slug generation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlugOptions {
+    /// Character placed between words (`-` or `_`).
+    pub separator: char,
+    /// Maximum slug length; longer slugs are cut at a word boundary.
+    pub max_length: usize,
+    /// Drop common short words (articles, conjunctions) for the language.
+    pub remove_stopwords: bool,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        Self {
+            separator: '-',
+            max_length: DEFAULT_MAX_LENGTH,
+            remove_stopwords: false,
+        }
+    }
+}
+
+impl SlugOptions {
+    /// Load site-wide slug options, falling back to defaults.
+    ///
+    /// Invalid values (unknown separator, zero length) are replaced by
+    /// their defaults rather than rejected, so a bad setting never blocks
+    /// alias generation.
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        let options = match SiteConfig::get(pool, SLUG_SETTINGS_KEY).await? {
+            Some(value) => serde_json::from_value::<Self>(value).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "invalid slug_settings; using defaults");
+                Self::default()
+            }),
+            None => Self::default(),
+        };
+        Ok(options.normalized())
+    }
+
+    /// Persist site-wide slug options after validation.
+    pub async fn save(&self, pool: &PgPool) -> Result<()> {
+        self.validate().map_err(anyhow::Error::msg)?;
+        SiteConfig::set(pool, SLUG_SETTINGS_KEY, serde_json::to_value(self)?).await
+    }
+
+    /// Check that the options are within supported bounds.
+    pub fn validate(&self) -> Result<(), String> {
+        if !ALLOWED_SEPARATORS.contains(&self.separator) {
+            return Err(format!(
+                "separator must be one of: {}",
+                ALLOWED_SEPARATORS
+                    .iter()
+                    .map(char::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ));
+        }
+        if self.max_length == 0 || self.max_length > MAX_MAX_LENGTH {
+            return Err(format!("max_length must be between 1 and {MAX_MAX_LENGTH}"));
+        }
+        Ok(())
+    }
+
+    /// Replace out-of-range values with defaults.
+    fn normalized(mut self) -> Self {
+        if !ALLOWED_SEPARATORS.contains(&self.separator) {
+            self.separator = '-';
+        }
+        if self.max_length == 0 {
+            self.max_length = DEFAULT_MAX_LENGTH;
+        }
+        self.max_length = self.max_length.min(MAX_MAX_LENGTH);
+        self
+    }
+}
+
+/// Convert text into a URL-safe slug using the rules for `lang`.
+///
+/// `lang` is a language tag such as `"de"` or `"pt-BR"`; only the primary
+/// subtag is considered. An empty or unknown language uses the generic
+/// Latin/Cyrillic/Greek tables. Characters with no transliteration (e.g.
+/// CJK) are treated as word breaks, so a title written entirely in such a
+/// script yields an empty slug; pathauto then falls back to the item ID.
+pub fn slugify(text: &str, lang: &str, options: &SlugOptions) -> String {
+    let lang = primary_subtag(lang);
+    let ascii = transliterate(text, &lang);
+
+    let words: Vec<&str> = ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let words = if options.remove_stopwords {
+        let stopwords = stopwords(&lang);
+        let kept: Vec<&str> = words
+            .iter()
+            .copied()
+            .filter(|w| !stopwords.contains(w))
+            .collect();
+        // A title made only of stopwords keeps its words rather than vanishing.
+        if kept.is_empty() { words } else { kept }
+    } else {
+        words
+    };
+
+    let separator = options.separator.to_string();
+    let slug = words.join(&separator);
+    truncate_at_word(slug, options.separator, options.max_length)
+}
+
+/// Lowercase and transliterate text to ASCII for the given language.
+///
+/// Non-ASCII characters without a mapping are replaced by a space.
+pub fn transliterate(text: &str, lang: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.to_lowercase().chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else if is_combining_mark(c) {
+            // Decomposed accents (e.g. from "İ".to_lowercase()) are dropped
+            // so they don't split the word they belong to.
+        } else if let Some(s) = language_override(lang, c) {
+            out.push_str(s);
+        } else if let Some(s) = generic_transliteration(c) {
+            out.push_str(s);
+        } else {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+/// Extract the lowercase primary subtag from a language tag.
+fn primary_subtag(lang: &str) -> String {
+    lang.split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Cut a slug to at most `max_len` bytes, preferring a word boundary.
+fn truncate_at_word(mut slug: String, separator: char, max_len: usize) -> String {
+    if slug.len() <= max_len {
+        return slug;
+    }
+    // The slug is pure ASCII, but guard the boundary in case that changes.
+    let mut end = max_len;
+    while end > 0 && !slug.is_char_boundary(end) {
+        end -= 1;
+    }
+    let at_boundary = slug[end..].starts_with(separator);
+    if !at_boundary && let Some(pos) = slug[..end].rfind(separator) {
+        end = pos;
+    }
+    slug.truncate(end);
+    slug
+}
+
+/// Unicode combining diacritical marks (U+0300–U+036F).
+fn is_combining_mark(c: char) -> bool {
+    ('\u{0300}'..='\u{036f}').contains(&c)
+}
+
+/// Language-specific transliterations that differ from the generic table.
+fn language_override(lang: &str, c: char) -> Option<&'static str> {
+    let s = match (lang, c) {
+        // German umlauts expand rather than fold.
+        ("de", 'ä') => "ae",
+        ("de", 'ö') => "oe",
+        ("de", 'ü') => "ue",
+        // Danish and Norwegian.
+        ("da" | "nb" | "nn" | "no", 'å') => "aa",
+        ("da" | "nb" | "nn" | "no", 'ø') => "oe",
+        // Ukrainian uses different romanizations for shared Cyrillic letters.
+        ("uk", 'г') => "h",
+        ("uk", 'и') => "y",
+        // Bulgarian.
+        ("bg", 'щ') => "sht",
+        ("bg", 'ъ') => "a",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Generic transliteration of lowercase Latin, Cyrillic and Greek letters.
+fn generic_transliteration(c: char) -> Option<&'static str> {
+    let s = match c {
+        // Latin with diacritics
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĳ' => "ij",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        // Cyrillic (Russian-based romanization)
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'д' => "d",
+        'е' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' => "i",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        'і' => "i",
+        'ї' => "yi",
+        'є' => "ye",
+        'ґ' => "g",
+        'ђ' => "dj",
+        'ј' => "j",
+        'љ' => "lj",
+        'њ' => "nj",
+        'ћ' => "c",
+        'џ' => "dz",
+        // Greek
+        'α' | 'ά' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'έ' => "e",
+        'ζ' => "z",
+        'η' | 'ή' => "i",
+        'θ' => "th",
+        'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ό' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' | 'ώ' => "o",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Stopwords for a language (already transliterated to ASCII).
+fn stopwords(lang: &str) -> &'static [&'static str] {
+    match lang {
+        "de" => &[
+            "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "und", "oder", "von",
+            "zu", "mit", "im", "in", "am", "auf",
+        ],
+        "fr" => &[
+            "le", "la", "les", "l", "un", "une", "des", "de", "du", "d", "et", "ou", "a", "au",
+            "aux", "en",
+        ],
+        "es" => &[
+            "el", "la", "los", "las", "un", "una", "unos", "unas", "de", "del", "y", "o", "a",
+            "al", "en",
+        ],
+        "it" => &[
+            "il", "lo", "la", "i", "gli", "le", "un", "uno", "una", "di", "del", "della", "e", "o",
+            "a", "in",
+        ],
+        "nl" => &[
+            "de", "het", "een", "en", "of", "van", "in", "op", "met", "te",
+        ],
+        // English is the fallback for unknown languages.
+        _ => &[
+            "a", "an", "the", "and", "or", "of", "to", "in", "on", "at", "for", "with", "by", "is",
+        ],
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn slug(text: &str, lang: &str) -> String {
+        slugify(text, lang, &SlugOptions::default())
+    }
+
+    #[test]
+    fn generic_latin_folding() {
+        assert_eq!(slug("Café Crème", ""), "cafe-creme");
+        assert_eq!(slug("Łódź Straße", "pl"), "lodz-strasse");
+    }
+
+    #[test]
+    fn german_umlauts_expand() {
+        assert_eq!(slug("Über Größe", "de"), "ueber-groesse");
+        assert_eq!(slug("Über Größe", "de-AT"), "ueber-groesse");
+        // Without the German rule umlauts fold to the base letter.
+        assert_eq!(slug("Über Größe", "en"), "uber-grosse");
+    }
+
+    #[test]
+    fn cyrillic_per_language() {
+        assert_eq!(slug("Привет мир", "ru"), "privet-mir");
+        assert_eq!(slug("Київ", "uk"), "kyyiv");
+        assert_eq!(slug("Гора", "ru"), "gora");
+        assert_eq!(slug("Гора", "uk"), "hora");
+    }
+
+    #[test]
+    fn greek_transliteration() {
+        assert_eq!(slug("Αθήνα", "el"), "athina");
+    }
+
+    #[test]
+    fn untransliterable_script_is_empty() {
+        assert_eq!(slug("東京", "ja"), "");
+    }
+
+    #[test]
+    fn combining_marks_do_not_split_words() {
+        assert_eq!(slug("İstanbul", "tr"), "istanbul");
+    }
+
+    #[test]
+    fn custom_separator() {
+        let options = SlugOptions {
+            separator: '_',
+            ..SlugOptions::default()
+        };
+        assert_eq!(slugify("Hello World", "en", &options), "hello_world");
+    }
+
+    #[test]
+    fn stopword_removal() {
+        let options = SlugOptions {
+            remove_stopwords: true,
+            ..SlugOptions::default()
+        };
+        assert_eq!(
+            slugify("The Lord of the Rings", "en", &options),
+            "lord-rings"
+        );
+        assert_eq!(slugify("Der Herr der Ringe", "de", &options), "herr-ringe");
+        // All-stopword titles are kept intact.
+        assert_eq!(slugify("The And", "en", &options), "the-and");
+    }
+
+    #[test]
+    fn truncates_at_word_boundary() {
+        let options = SlugOptions {
+            max_length: 12,
+            ..SlugOptions::default()
+        };
+        assert_eq!(slugify("hello wonderful world", "", &options), "hello");
+        assert_eq!(slugify("hello world again", "", &options), "hello-world");
+        assert_eq!(slugify("abcdefghijklmnop", "", &options), "abcdefghijkl");
+    }
+
+    #[test]
+    fn validate_rejects_bad_options() {
+        let bad_sep = SlugOptions {
+            separator: '/',
+            ..SlugOptions::default()
+        };
+        assert!(bad_sep.validate().is_err());
+        let bad_len = SlugOptions {
+            max_length: 0,
+            ..SlugOptions::default()
+        };
+        assert!(bad_len.validate().is_err());
+        assert!(SlugOptions::default().validate().is_ok());
+    }
+
+    #[test]
+    fn options_deserialize_with_defaults() {
+        let options: SlugOptions =
+            serde_json::from_value(serde_json::json!({"remove_stopwords": true})).unwrap();
+        assert_eq!(options.separator, '-');
+        assert_eq!(options.max_length, DEFAULT_MAX_LENGTH);
+        assert!(options.remove_stopwords);
+    }
+
+    #[test]
+    fn normalized_replaces_invalid_values() {
+        let options = SlugOptions {
+            separator: '.',
+            max_length: 10_000,
+            remove_stopwords: false,
+        }
+        .normalized();
+        assert_eq!(options.separator, '-');
+        assert_eq!(options.max_length, MAX_MAX_LENGTH);
+    }
+}
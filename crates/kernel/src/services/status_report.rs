@@ -0,0 +1,315 @@
+//! Status report: requirement checks from the kernel and plugins.
+//!
+//! The kernel checks its own dependencies (database, migrations, Redis,
+//! cron, file storage); plugins add checks by implementing
+//! `tap_requirements`. The report is shown at `/admin/reports/status`
+//! and served as JSON for uptime monitors at
+//! `/admin/reports/status.json`.
+
+use anyhow::Result;
+use serde::Serialize;
+use tracing::warn;
+use trovato_sdk::types::{Requirement, RequirementSeverity};
+
+use crate::cron::LastCronRun;
+
+/// Source of the checks made by the kernel itself.
+pub const KERNEL_SOURCE: &str = "kernel";
+
+/// Cron not having run for this long is a warning.
+pub const CRON_WARNING_SECS: i64 = 60 * 60;
+
+/// Cron not having run for this long is an error.
+pub const CRON_ERROR_SECS: i64 = 24 * 60 * 60;
+
+/// A check together with where it came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusCheck {
+    /// `kernel` or the name of the plugin that made the check.
+    pub source: String,
+    /// The check itself.
+    #[serde(flatten)]
+    pub requirement: Requirement,
+}
+
+/// The full status report.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    /// Most severe result of all checks.
+    pub severity: RequirementSeverity,
+    /// Number of checks with an error.
+    pub errors: usize,
+    /// Number of checks with a warning.
+    pub warnings: usize,
+    /// When the report was built (Unix timestamp).
+    pub generated: i64,
+    /// Every check, most severe first.
+    pub checks: Vec<StatusCheck>,
+}
+
+impl StatusReport {
+    /// Build a report from kernel and plugin checks.
+    pub fn new(mut checks: Vec<StatusCheck>, generated: i64) -> Self {
+        checks.sort_by(|a, b| {
+            b.requirement
+                .severity
+                .cmp(&a.requirement.severity)
+                .then_with(|| (a.source != KERNEL_SOURCE).cmp(&(b.source != KERNEL_SOURCE)))
+                .then_with(|| a.source.cmp(&b.source))
+                .then_with(|| a.requirement.title.cmp(&b.requirement.title))
+        });
+        let count = |severity| {
+            checks
+                .iter()
+                .filter(|c| c.requirement.severity == severity)
+                .count()
+        };
+        Self {
+            severity: checks
+                .iter()
+                .map(|c| c.requirement.severity)
+                .max()
+                .unwrap_or_default(),
+            errors: count(RequirementSeverity::Error),
+            warnings: count(RequirementSeverity::Warning),
+            generated,
+            checks,
+        }
+    }
+
+    /// Whether any check failed with an error.
+    pub fn has_errors(&self) -> bool {
+        self.severity == RequirementSeverity::Error
+    }
+}
+
+/// Wrap kernel checks.
+pub fn kernel_checks(requirements: Vec<Requirement>) -> Vec<StatusCheck> {
+    requirements
+        .into_iter()
+        .map(|requirement| StatusCheck {
+            source: KERNEL_SOURCE.to_string(),
+            requirement,
+        })
+        .collect()
+}
+
+/// Parse `(plugin_name, output_json)` results of `tap_requirements`.
+///
+/// Output that doesn't parse becomes a warning for the plugin, so a
+/// broken check is visible on the report instead of silently missing.
+pub fn from_tap_results(results: Vec<(String, String)>) -> Vec<StatusCheck> {
+    let mut checks = Vec::new();
+    for (plugin, output) in results {
+        match serde_json::from_str::<Vec<Requirement>>(&output) {
+            Ok(requirements) => {
+                checks.extend(requirements.into_iter().map(|requirement| StatusCheck {
+                    source: plugin.clone(),
+                    requirement,
+                }));
+            }
+            Err(e) => {
+                warn!(plugin = %plugin, error = %e, "failed to parse tap_requirements response");
+                checks.push(StatusCheck {
+                    requirement: Requirement::warning("requirements", "Plugin checks")
+                        .value("invalid response")
+                        .description(format!(
+                            "The {plugin} plugin returned checks the kernel could not read."
+                        )),
+                    source: plugin,
+                });
+            }
+        }
+    }
+    checks
+}
+
+/// PostgreSQL connectivity.
+pub fn database_check(reachable: bool) -> Requirement {
+    if reachable {
+        Requirement::ok("database", "Database").value("connected")
+    } else {
+        Requirement::error("database", "Database")
+            .value("unreachable")
+            .description("PostgreSQL is not responding. Check DATABASE_URL and the server.")
+    }
+}
+
+/// Kernel migrations that have not been applied.
+pub fn migrations_check(pending: Result<Vec<String>>) -> Requirement {
+    match pending {
+        Ok(pending) if pending.is_empty() => {
+            Requirement::ok("migrations", "Database migrations").value("up to date")
+        }
+        Ok(pending) => Requirement::error("migrations", "Database migrations")
+            .value(format!("{} pending", pending.len()))
+            .description(format!(
+                "Restart the server to apply: {}.",
+                pending.join(", ")
+            )),
+        Err(e) => Requirement::warning("migrations", "Database migrations")
+            .value("unknown")
+            .description(format!("Could not read applied migrations: {e:#}")),
+    }
+}
+
+/// Redis connectivity.
+pub fn redis_check(reachable: bool) -> Requirement {
+    if reachable {
+        Requirement::ok("redis", "Redis").value("connected")
+    } else {
+        Requirement::error("redis", "Redis")
+            .value("unreachable")
+            .description("Sessions, cache and queues need Redis. Check REDIS_URL and the server.")
+    }
+}
+
+/// How long ago cron last ran.
+pub fn cron_check(last_run: Result<Option<LastCronRun>>, now: i64) -> Requirement {
+    let run = match last_run {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            return Requirement::warning("cron", "Cron")
+                .value("never run")
+                .description("Cron has not run yet. See /admin/reports/cron.");
+        }
+        Err(e) => {
+            return Requirement::warning("cron", "Cron")
+                .value("unknown")
+                .description(format!("Could not read the last cron run: {e:#}"));
+        }
+    };
+
+    let age = now.saturating_sub(run.timestamp).max(0);
+    let value = format!("last run {} ago on {}", format_age(age), run.hostname);
+    if age >= CRON_ERROR_SECS {
+        Requirement::error("cron", "Cron")
+            .value(value)
+            .description("Cron has stopped: scheduled tasks and queues are not processed.")
+    } else if age >= CRON_WARNING_SECS {
+        Requirement::warning("cron", "Cron")
+            .value(value)
+            .description("Cron has not run for over an hour. See /admin/reports/cron.")
+    } else {
+        Requirement::ok("cron", "Cron").value(value)
+    }
+}
+
+/// Whether the file storage backend accepts writes.
+pub fn file_storage_check(scheme: &str, writable: Result<()>) -> Requirement {
+    match writable {
+        Ok(()) => {
+            Requirement::ok("file_storage", "File storage").value(format!("{scheme} writable"))
+        }
+        Err(e) => Requirement::error("file_storage", "File storage")
+            .value(format!("{scheme} not writable"))
+            .description(format!("Uploads will fail: {e:#}")),
+    }
+}
+
+/// Format a duration in seconds as a short age ("5 minutes", "3 days").
+fn format_age(secs: i64) -> String {
+    let (n, unit) = match secs {
+        s if s < 60 => (s, "second"),
+        s if s < 60 * 60 => (s / 60, "minute"),
+        s if s < 24 * 60 * 60 => (s / (60 * 60), "hour"),
+        s => (s / (24 * 60 * 60), "day"),
+    };
+    if n == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{n} {unit}s")
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn last_run(timestamp: i64) -> LastCronRun {
+        LastCronRun {
+            timestamp,
+            hostname: "web1".to_string(),
+            result: "completed".to_string(),
+        }
+    }
+
+    #[test]
+    fn report_takes_the_worst_severity_and_sorts_it_first() {
+        let mut checks = kernel_checks(vec![database_check(true), redis_check(false)]);
+        checks.extend(from_tap_results(vec![(
+            "example".to_string(),
+            r#"[{"name":"api_key","title":"API key","severity":"warning"}]"#.to_string(),
+        )]));
+
+        let report = StatusReport::new(checks, 0);
+        assert_eq!(report.severity, RequirementSeverity::Error);
+        assert!(report.has_errors());
+        assert_eq!((report.errors, report.warnings), (1, 1));
+        assert_eq!(report.checks[0].requirement.name, "redis");
+        assert_eq!(report.checks[1].source, "example");
+    }
+
+    #[test]
+    fn empty_and_info_reports_are_ok() {
+        assert_eq!(
+            StatusReport::new(Vec::new(), 0).severity,
+            RequirementSeverity::Ok
+        );
+
+        let info = kernel_checks(vec![Requirement::new(
+            "version",
+            "Version",
+            RequirementSeverity::Info,
+        )]);
+        assert!(!StatusReport::new(info, 0).has_errors());
+    }
+
+    #[test]
+    fn malformed_plugin_output_becomes_a_warning() {
+        let checks = from_tap_results(vec![("broken".to_string(), "{".to_string())]);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].source, "broken");
+        assert_eq!(checks[0].requirement.severity, RequirementSeverity::Warning);
+    }
+
+    #[test]
+    fn pending_migrations_are_errors() {
+        assert_eq!(
+            migrations_check(Ok(Vec::new())).severity,
+            RequirementSeverity::Ok
+        );
+        let pending = migrations_check(Ok(vec!["20260101000001 add_table".to_string()]));
+        assert_eq!(pending.severity, RequirementSeverity::Error);
+        assert_eq!(pending.value.as_deref(), Some("1 pending"));
+        assert_eq!(
+            migrations_check(Err(anyhow::anyhow!("no table"))).severity,
+            RequirementSeverity::Warning
+        );
+    }
+
+    #[test]
+    fn cron_severity_follows_its_age() {
+        let now = 1_000_000;
+        let fresh = cron_check(Ok(Some(last_run(now - 120))), now);
+        assert_eq!(fresh.severity, RequirementSeverity::Ok);
+        assert_eq!(
+            fresh.value.as_deref(),
+            Some("last run 2 minutes ago on web1")
+        );
+
+        let stale = cron_check(Ok(Some(last_run(now - CRON_WARNING_SECS))), now);
+        assert_eq!(stale.severity, RequirementSeverity::Warning);
+
+        let stopped = cron_check(Ok(Some(last_run(now - CRON_ERROR_SECS))), now);
+        assert_eq!(stopped.severity, RequirementSeverity::Error);
+        assert_eq!(stopped.value.as_deref(), Some("last run 1 day ago on web1"));
+
+        assert_eq!(
+            cron_check(Ok(None), now).severity,
+            RequirementSeverity::Warning
+        );
+    }
+}
@@ -1,37 +1,187 @@
 //! Tile rendering service.
 //!
 //! Loads visible tiles for a given region/stage/path and renders them to HTML.
+//! Kernel tile types render directly; tiles of type `plugin:{block}` place a
+//! block declared by a plugin in `tap_block_info` and are rendered by that
+//! plugin's `tap_block_render`.
 
-use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
 use sqlx::PgPool;
+use tracing::warn;
+use trovato_sdk::types::{BlockDefinition, BlockRenderInput, BlockRenderOutput, CacheContext};
+use uuid::Uuid;
 
+use crate::cache::CacheLayer;
 use crate::models::tile::Tile;
 use crate::routes::helpers::html_escape;
+use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
+
+/// Theme regions tiles can be placed in, with their labels.
+pub const REGIONS: &[(&str, &str)] = &[
+    ("header", "Header"),
+    ("navigation", "Navigation"),
+    ("sidebar", "Sidebar"),
+    ("content_top", "Content top"),
+    ("content_bottom", "Content bottom"),
+    ("footer", "Footer"),
+];
+
+/// Tile type prefix for blocks declared by plugins.
+pub const PLUGIN_TILE_PREFIX: &str = "plugin:";
+
+/// Cache tag for rendered output of a tile.
+pub fn tile_tag(id: Uuid) -> String {
+    format!("tile:{id}")
+}
+
+/// A block definition together with its declaring plugin.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginBlock {
+    /// Plugin that declared the block.
+    pub plugin: String,
+    /// The definition as returned by the plugin.
+    #[serde(flatten)]
+    pub definition: BlockDefinition,
+}
+
+/// Plugin block definitions keyed by name.
+#[derive(Debug, Default)]
+pub struct BlockRegistry {
+    by_name: HashMap<String, PluginBlock>,
+}
+
+impl BlockRegistry {
+    /// Build the registry from `(plugin_name, output_json)` results of
+    /// `tap_block_info`.
+    ///
+    /// Results are expected in weight order. Malformed output is logged
+    /// and skipped; if two plugins declare the same name the first wins.
+    pub fn from_tap_results(results: Vec<(String, String)>) -> Self {
+        let mut by_name: HashMap<String, PluginBlock> = HashMap::new();
+        for (plugin, output) in results {
+            let definitions: Vec<BlockDefinition> = match serde_json::from_str(&output) {
+                Ok(defs) => defs,
+                Err(e) => {
+                    warn!(plugin = %plugin, error = %e, "failed to parse tap_block_info response");
+                    continue;
+                }
+            };
+            for definition in definitions {
+                if !crate::routes::helpers::is_valid_machine_name(&definition.name) {
+                    warn!(plugin = %plugin, block = %definition.name, "ignoring block with invalid name");
+                    continue;
+                }
+                if let Some(existing) = by_name.get(&definition.name) {
+                    warn!(
+                        block = %definition.name,
+                        plugin = %plugin,
+                        owner = %existing.plugin,
+                        "duplicate block definition ignored"
+                    );
+                    continue;
+                }
+                by_name.insert(
+                    definition.name.clone(),
+                    PluginBlock {
+                        plugin: plugin.clone(),
+                        definition,
+                    },
+                );
+            }
+        }
+        Self { by_name }
+    }
+
+    /// Look up a block by name.
+    pub fn get(&self, name: &str) -> Option<&PluginBlock> {
+        self.by_name.get(name)
+    }
+
+    /// All blocks, sorted by name.
+    pub fn list(&self) -> Vec<&PluginBlock> {
+        let mut blocks: Vec<_> = self.by_name.values().collect();
+        blocks.sort_by(|a, b| a.definition.name.cmp(&b.definition.name));
+        blocks
+    }
+}
+
+/// The page a region is rendered for.
+#[derive(Debug, Clone)]
+pub struct RegionContext {
+    /// Request path, matched against tile path rules.
+    pub path: String,
+    /// Language of the page.
+    pub language: String,
+    /// Content type of the item the page displays, if any.
+    pub item_type: Option<String>,
+    /// Role names of the visitor, matched against tile role rules.
+    pub user_roles: Vec<String>,
+    /// The visitor, passed to plugins rendering blocks.
+    pub user: UserContext,
+}
+
+/// Dispatches `tap_block_render` for plugin block tiles.
+struct PluginBlockRenderer {
+    dispatcher: Arc<TapDispatcher>,
+    services: RequestServices,
+    cache: CacheLayer,
+    blocks: BlockRegistry,
+}
 
 /// Service for loading and rendering tiles.
 pub struct TileService {
     db: PgPool,
+    plugin_blocks: Option<PluginBlockRenderer>,
 }
 
 impl TileService {
     /// Create a new tile service.
     pub fn new(db: PgPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            plugin_blocks: None,
+        }
     }
 
-    /// Get visible tiles for a region, filtered by path and role visibility rules.
+    /// Enable plugin block tiles, rendered through the given dispatcher and
+    /// cached in `cache` when the block declares a lifetime.
+    pub fn set_plugin_blocks(
+        &mut self,
+        dispatcher: Arc<TapDispatcher>,
+        services: RequestServices,
+        cache: CacheLayer,
+        blocks: BlockRegistry,
+    ) {
+        self.plugin_blocks = Some(PluginBlockRenderer {
+            dispatcher,
+            services,
+            cache,
+            blocks,
+        });
+    }
+
+    /// Plugin block definitions, if plugin blocks are enabled.
+    pub fn blocks(&self) -> Option<&BlockRegistry> {
+        self.plugin_blocks.as_ref().map(|r| &r.blocks)
+    }
+
+    /// Get visible tiles for a region, filtered by path, role and content
+    /// type visibility rules.
     pub async fn get_visible_tiles(
         &self,
         region: &str,
-        stage_id: uuid::Uuid,
-        path: &str,
-        user_roles: &[String],
+        stage_id: Uuid,
+        page: &RegionContext,
     ) -> Result<Vec<Tile>> {
         let tiles = Tile::list_by_region(&self.db, region, stage_id).await?;
 
         Ok(tiles
             .into_iter()
-            .filter(|t| t.is_visible(path, user_roles))
+            .filter(|t| t.is_visible(&page.path, &page.user_roles, page.item_type.as_deref()))
             .collect())
     }
 
@@ -44,37 +194,139 @@ impl TileService {
     pub async fn render_region(
         &self,
         region: &str,
-        stage_id: uuid::Uuid,
-        path: &str,
-        user_roles: &[String],
+        stage_id: Uuid,
+        page: &RegionContext,
     ) -> Result<String> {
-        let tiles = self
-            .get_visible_tiles(region, stage_id, path, user_roles)
-            .await?;
-        let html: String = tiles.iter().map(render_tile_html).collect();
+        let tiles = self.get_visible_tiles(region, stage_id, page).await?;
+        let mut html = String::new();
+        for tile in &tiles {
+            match tile.tile_type.strip_prefix(PLUGIN_TILE_PREFIX) {
+                Some(block) => html.push_str(&self.render_plugin_tile(tile, block, page).await),
+                None => html.push_str(&render_tile_html(tile)),
+            }
+        }
         Ok(html)
     }
-}
 
-/// Render a single tile to an HTML string (standalone, no database needed).
-fn render_tile_html(tile: &Tile) -> String {
-    let mut html = String::new();
+    /// Render a plugin block tile, or nothing if the block is gone, its
+    /// plugin failed, or the plugin hid it on this page.
+    async fn render_plugin_tile(&self, tile: &Tile, block: &str, page: &RegionContext) -> String {
+        let Some(renderer) = &self.plugin_blocks else {
+            return String::new();
+        };
+        let Some(block) = renderer.blocks.get(block) else {
+            return String::new();
+        };
+        let cache = &block.definition.cache;
+        let cache_key = cache
+            .max_age
+            .filter(|max_age| *max_age > 0)
+            .map(|_| block_cache_key(tile, &cache.contexts, page));
 
-    html.push_str(&format!(
-        "<div class=\"tile tile--{} tile--{}\" id=\"tile-{}\">\n",
-        html_escape(&tile.tile_type),
-        html_escape(&tile.machine_name),
-        html_escape(&tile.machine_name),
-    ));
+        let cached = match &cache_key {
+            Some(key) => renderer
+                .cache
+                .get(key)
+                .await
+                .and_then(|json| serde_json::from_str::<BlockRenderOutput>(&json).ok()),
+            None => None,
+        };
+        let output = match cached {
+            Some(output) => output,
+            None => {
+                let output = match self
+                    .dispatch_block_render(renderer, block, tile, page)
+                    .await
+                {
+                    Ok(Some(output)) => output,
+                    Ok(None) => return String::new(),
+                    Err(e) => {
+                        warn!(tile = %tile.machine_name, error = %e, "failed to render plugin block");
+                        return String::new();
+                    }
+                };
+                if let Some(key) = &cache_key
+                    && let Ok(json) = serde_json::to_string(&output)
+                {
+                    let own_tag = tile_tag(tile.id);
+                    let mut tags: Vec<&str> = output.tags.iter().map(String::as_str).collect();
+                    tags.push(&own_tag);
+                    renderer
+                        .cache
+                        .set(key, &json, cache.max_age.unwrap_or_default(), &tags)
+                        .await;
+                }
+                output
+            }
+        };
 
-    // Label
-    html.push_str(&format!(
-        "<h3 class=\"tile__title\">{}</h3>\n",
-        html_escape(&tile.label)
-    ));
+        // The page inherits the block's tags, lifetime and contexts.
+        for tag in &output.tags {
+            crate::cache::page::add_tag(tag.clone());
+        }
+        crate::cache::page::add_cache_metadata(cache);
+
+        if output.html.is_empty() {
+            return String::new();
+        }
+        wrap_tile_html(tile, &output.html)
+    }
+
+    /// Call `tap_block_render` on the block's plugin. `None` means the
+    /// plugin is disabled for this site or the call failed (already logged).
+    async fn dispatch_block_render(
+        &self,
+        renderer: &PluginBlockRenderer,
+        block: &PluginBlock,
+        tile: &Tile,
+        page: &RegionContext,
+    ) -> Result<Option<BlockRenderOutput>> {
+        let input = BlockRenderInput {
+            block: block.definition.name.clone(),
+            tile: tile.machine_name.clone(),
+            region: tile.region.clone(),
+            config: tile.config.clone(),
+            path: page.path.clone(),
+            language: page.language.clone(),
+            item_type: page.item_type.clone(),
+        };
+        let input_json =
+            serde_json::to_string(&input).context("failed to serialize block render input")?;
+        let state = RequestState::new(page.user.clone(), renderer.services.clone());
+
+        let Some(result) = renderer
+            .dispatcher
+            .dispatch_to_plugin("tap_block_render", &input_json, &block.plugin, state)
+            .await
+        else {
+            return Ok(None);
+        };
+        let output =
+            serde_json::from_str(&result.output).context("invalid tap_block_render response")?;
+        Ok(Some(output))
+    }
+}
+
+/// Cache key for a plugin block's output on one page.
+///
+/// Output is always kept per tile revision and path; declared contexts add
+/// the language, the visitor's roles, or the visitor.
+fn block_cache_key(tile: &Tile, contexts: &[CacheContext], page: &RegionContext) -> String {
+    let mut key = format!("block:{}:{}:{}", tile.id, tile.changed, page.path);
+    for context in contexts {
+        match context {
+            CacheContext::Language => key.push_str(&format!(":lang={}", page.language)),
+            CacheContext::Role => key.push_str(&format!(":roles={}", page.user_roles.join(","))),
+            CacheContext::User => key.push_str(&format!(":user={}", page.user.id)),
+        }
+    }
+    key
+}
 
+/// Render a single tile to an HTML string (standalone, no database needed).
+fn render_tile_html(tile: &Tile) -> String {
     // Body depends on tile_type
-    html.push_str("<div class=\"tile__content\">\n");
+    let mut html = String::new();
     match tile.tile_type.as_str() {
         "custom_html" => {
             let body = tile
@@ -124,6 +376,30 @@ fn render_tile_html(tile: &Tile) -> String {
             html.push_str("<p>Unknown tile type</p>");
         }
     }
+
+    wrap_tile_html(tile, &html)
+}
+
+/// Wrap a tile body in the tile markup and title.
+fn wrap_tile_html(tile: &Tile, body: &str) -> String {
+    let mut html = String::new();
+
+    html.push_str(&format!(
+        "<div class=\"tile tile--{} tile--{}\" id=\"tile-{}\">\n",
+        // `plugin:{block}` becomes `plugin-{block}` in the class name.
+        html_escape(&tile.tile_type.replace(':', "-")),
+        html_escape(&tile.machine_name),
+        html_escape(&tile.machine_name),
+    ));
+
+    // Label
+    html.push_str(&format!(
+        "<h3 class=\"tile__title\">{}</h3>\n",
+        html_escape(&tile.label)
+    ));
+
+    html.push_str("<div class=\"tile__content\">\n");
+    html.push_str(body);
     html.push_str("</div>\n</div>\n");
 
     html
@@ -221,4 +497,51 @@ mod tests {
         // JS is now in static/js/chat-widget.js, loaded via script src
         assert!(html.contains("chat-widget.js"));
     }
+
+    #[test]
+    fn plugin_tile_wraps_block_output() {
+        let tile = make_tile("plugin:recent_posts", serde_json::json!({}));
+        let html = wrap_tile_html(&tile, "<ul></ul>");
+        assert!(html.contains("tile--plugin-recent_posts"));
+        assert!(html.contains("<ul></ul>"));
+    }
+
+    #[test]
+    fn block_registry_first_declaration_wins() {
+        let registry = BlockRegistry::from_tap_results(vec![
+            (
+                "blog".to_string(),
+                r#"[{"name":"recent_posts","label":"Recent posts","cache":{"max_age":60}}]"#
+                    .to_string(),
+            ),
+            (
+                "other".to_string(),
+                r#"[{"name":"recent_posts","label":"Other"},{"name":"Bad Name","label":"B"}]"#
+                    .to_string(),
+            ),
+            ("broken".to_string(), "not json".to_string()),
+        ]);
+        let block = registry.get("recent_posts").unwrap();
+        assert_eq!(block.plugin, "blog");
+        assert_eq!(block.definition.cache.max_age, Some(60));
+        assert_eq!(registry.list().len(), 1);
+    }
+
+    #[test]
+    fn block_cache_key_varies_by_declared_contexts() {
+        let tile = make_tile("plugin:recent_posts", serde_json::json!({}));
+        let page = RegionContext {
+            path: "/blog".into(),
+            language: "de".into(),
+            item_type: None,
+            user_roles: vec!["anonymous user".into()],
+            user: UserContext::anonymous(),
+        };
+
+        let key = block_cache_key(&tile, &[], &page);
+        assert_eq!(key, format!("block:{}:0:/blog", tile.id));
+
+        let key = block_cache_key(&tile, &[CacheContext::Language, CacheContext::Role], &page);
+        assert!(key.ends_with(":lang=de:roles=anonymous user"));
+    }
 }
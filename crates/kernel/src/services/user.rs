@@ -1,18 +1,22 @@
 //! User service with tap integration and TTL-based caching.
 //!
 //! Centralizes user CRUD operations with automatic tap invocations
-//! for plugin taps (register, update, delete, login, logout) and
-//! an in-process cache for `find_by_id` lookups.
+//! for plugin taps (insert, register, update, delete, login, logout) and
+//! an in-process cache for `find_by_id` lookups. Also holds the profile
+//! field definitions collected from `tap_user_info`.
 
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
 use moka::sync::Cache;
+use parking_lot::RwLock;
 use sqlx::PgPool;
 use tracing::info;
+use trovato_sdk::types::FieldDefinition;
 use uuid::Uuid;
 
+use super::user_fields;
 use crate::models::{CreateUser, UpdateUser, User};
 use crate::tap::{RequestServices, RequestState, TapDispatcher, UserContext};
 
@@ -38,6 +42,8 @@ struct UserServiceInner {
     dispatcher: Arc<TapDispatcher>,
     tap_services: RequestServices,
     cache: Cache<Uuid, User>,
+    /// Profile fields declared by plugins via `tap_user_info`.
+    profile_fields: RwLock<Vec<FieldDefinition>>,
 }
 
 impl UserService {
@@ -57,10 +63,32 @@ impl UserService {
                     .max_capacity(MAX_CAPACITY)
                     .time_to_live(ttl)
                     .build(),
+                profile_fields: RwLock::new(Vec::new()),
             }),
         }
     }
 
+    /// Collect profile field definitions from plugins via `tap_user_info`.
+    pub async fn sync_profile_fields(&self) {
+        let state = RequestState::without_services(UserContext::anonymous());
+        let results = self
+            .inner
+            .dispatcher
+            .dispatch("tap_user_info", "{}", state)
+            .await;
+        let fields = user_fields::collect_definitions(&results);
+        info!(
+            count = fields.len(),
+            "user profile fields synced from plugins"
+        );
+        *self.inner.profile_fields.write() = fields;
+    }
+
+    /// Profile field definitions, in plugin weight order.
+    pub fn profile_fields(&self) -> Vec<FieldDefinition> {
+        self.inner.profile_fields.read().clone()
+    }
+
     /// Find a user by ID (cached).
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
         // Check cache first
@@ -87,17 +115,17 @@ impl UserService {
         User::find_by_mail(&self.inner.pool, mail).await
     }
 
-    /// Create a new active user with `tap_user_register` invocation.
+    /// Create a new active user with `tap_user_insert` invocation.
     pub async fn create(&self, input: CreateUser, acting_user: &UserContext) -> Result<User> {
         let user = User::create(&self.inner.pool, input).await?;
         self.inner.cache.insert(user.id, user.clone());
-        self.dispatch_tap("tap_user_register", user.id, acting_user)
+        self.dispatch_tap("tap_user_insert", user.id, acting_user)
             .await;
         info!(user_id = %user.id, name = %user.name, "user created");
         Ok(user)
     }
 
-    /// Create a new user with a specific status and `tap_user_register` invocation.
+    /// Create a new user with a specific status and `tap_user_insert` invocation.
     ///
     /// Use `status = 0` for inactive accounts pending email verification.
     pub async fn create_with_status(
@@ -108,12 +136,28 @@ impl UserService {
     ) -> Result<User> {
         let user = User::create_with_status(&self.inner.pool, input, status).await?;
         self.inner.cache.insert(user.id, user.clone());
-        self.dispatch_tap("tap_user_register", user.id, acting_user)
+        self.dispatch_tap("tap_user_insert", user.id, acting_user)
             .await;
         info!(user_id = %user.id, name = %user.name, status, "user created with status");
         Ok(user)
     }
 
+    /// Create a self-registered user.
+    ///
+    /// Dispatches `tap_user_insert` like every new account, then
+    /// `tap_user_register`, which only fires for self-registration.
+    pub async fn register(
+        &self,
+        input: CreateUser,
+        status: i16,
+        acting_user: &UserContext,
+    ) -> Result<User> {
+        let user = self.create_with_status(input, status, acting_user).await?;
+        self.dispatch_tap("tap_user_register", user.id, acting_user)
+            .await;
+        Ok(user)
+    }
+
     /// Update a user with `tap_user_update` invocation.
     pub async fn update(
         &self,
@@ -273,6 +317,8 @@ mod tests {
             consent_date: None,
             consent_version: None,
             data_retention_days: None,
+            password_changed: chrono::Utc::now(),
+            fields: serde_json::json!({}),
         }
     }
 
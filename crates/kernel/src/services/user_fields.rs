@@ -0,0 +1,316 @@
+//! Plugin-defined profile fields on user accounts.
+//!
+//! Plugins declare fields with `tap_user_info`, returning a list of
+//! [`FieldDefinition`]s (e.g. netgrasp linking a user to an `ng_person`
+//! item, argus storing notification preferences). Values live in the
+//! `users.fields` JSONB column, keyed by field name.
+//!
+//! Fields appear on the admin user form. A field whose settings contain
+//! `"user_editable": true` also appears on the user's own profile page.
+//!
+//! Form inputs are named `profile[<field_name>]` so they never collide with
+//! the core account inputs (`name`, `mail`, `password`, ...).
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+use trovato_sdk::types::{FieldDefinition, FieldType};
+use uuid::Uuid;
+
+use crate::content::compound::validate_required_fields;
+use crate::tap::TapResult;
+
+/// Settings key that exposes a field on the self-service profile page.
+pub const USER_EDITABLE_SETTING: &str = "user_editable";
+
+/// Whether the field type can be stored on a user account.
+///
+/// Structured editors (compound, blocks, page builder) and file uploads are
+/// item-only.
+fn is_supported(field_type: &FieldType) -> bool {
+    matches!(
+        field_type,
+        FieldType::Text { .. }
+            | FieldType::TextLong
+            | FieldType::Integer
+            | FieldType::Float
+            | FieldType::Boolean
+            | FieldType::RecordReference(_)
+            | FieldType::Date
+            | FieldType::Email
+    )
+}
+
+/// Whether users may edit the field on their own profile.
+pub fn is_user_editable(field: &FieldDefinition) -> bool {
+    field
+        .settings
+        .get(USER_EDITABLE_SETTING)
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Collect field definitions from `tap_user_info` results.
+///
+/// The first plugin (by tap weight) to declare a field name wins; later
+/// duplicates and unsupported field types are logged and dropped.
+pub fn collect_definitions(results: &[TapResult]) -> Vec<FieldDefinition> {
+    let mut fields: Vec<FieldDefinition> = Vec::new();
+
+    for result in results {
+        let definitions = match serde_json::from_str::<Vec<FieldDefinition>>(&result.output) {
+            Ok(definitions) => definitions,
+            Err(e) => {
+                tracing::warn!(
+                    plugin = %result.plugin_name,
+                    error = %e,
+                    "failed to parse tap_user_info response"
+                );
+                continue;
+            }
+        };
+
+        for def in definitions {
+            if !is_supported(&def.field_type) {
+                tracing::warn!(
+                    plugin = %result.plugin_name,
+                    field = %def.field_name,
+                    "unsupported field type for user profile; skipping"
+                );
+            } else if fields.iter().any(|f| f.field_name == def.field_name) {
+                tracing::warn!(
+                    plugin = %result.plugin_name,
+                    field = %def.field_name,
+                    "user profile field already declared by another plugin; skipping"
+                );
+            } else {
+                fields.push(def);
+            }
+        }
+    }
+
+    fields
+}
+
+/// Extract `profile[<field_name>]` inputs from a submitted form.
+pub fn form_values(form: &HashMap<String, String>) -> HashMap<String, String> {
+    form.iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix("profile[")?.strip_suffix(']')?;
+            Some((name.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+/// Validate submitted values and merge them into the stored fields.
+///
+/// Only `definitions` are touched: values for fields of disabled plugins
+/// are kept as they are. Empty inputs clear the field; unchecked boolean
+/// checkboxes store `false`. Returns every validation error at once.
+pub fn apply_form_values(
+    definitions: &[FieldDefinition],
+    submitted: &HashMap<String, String>,
+    existing: &Value,
+) -> Result<Value, Vec<String>> {
+    let mut fields: Map<String, Value> = existing.as_object().cloned().unwrap_or_default();
+    let mut errors = Vec::new();
+
+    for def in definitions {
+        let raw = submitted
+            .get(&def.field_name)
+            .map(|v| v.trim())
+            .unwrap_or("");
+
+        if matches!(def.field_type, FieldType::Boolean) {
+            fields.insert(def.field_name.clone(), Value::Bool(!raw.is_empty()));
+            continue;
+        }
+        if raw.is_empty() {
+            fields.remove(&def.field_name);
+            continue;
+        }
+
+        match parse_value(def, raw) {
+            Ok(value) => {
+                fields.insert(def.field_name.clone(), value);
+            }
+            Err(msg) => errors.push(msg),
+        }
+    }
+
+    errors.extend(validate_required_fields(&fields, definitions));
+
+    if errors.is_empty() {
+        Ok(Value::Object(fields))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Convert a non-empty form input to the stored JSON value.
+fn parse_value(def: &FieldDefinition, raw: &str) -> Result<Value, String> {
+    let label = &def.label;
+    match &def.field_type {
+        FieldType::Text { max_length } => {
+            if let Some(max) = max_length
+                && raw.chars().count() > *max
+            {
+                return Err(format!("{label} must be {max} characters or fewer."));
+            }
+            Ok(Value::String(raw.to_string()))
+        }
+        FieldType::Integer => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("{label} must be a whole number.")),
+        FieldType::Float => raw
+            .parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .map(Value::from)
+            .ok_or_else(|| format!("{label} must be a number.")),
+        FieldType::Date => NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map(|_| Value::String(raw.to_string()))
+            .map_err(|_| format!("{label} must be a date (YYYY-MM-DD).")),
+        FieldType::Email => {
+            if raw.contains('@') && !raw.contains(char::is_whitespace) {
+                Ok(Value::String(raw.to_string()))
+            } else {
+                Err(format!("{label} must be a valid email address."))
+            }
+        }
+        FieldType::RecordReference(_) => raw
+            .parse::<Uuid>()
+            .map(|id| Value::String(id.to_string()))
+            .map_err(|_| format!("{label} must be an item ID.")),
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn result(plugin: &str, output: Value) -> TapResult {
+        TapResult {
+            plugin_name: plugin.to_string(),
+            output: output.to_string(),
+        }
+    }
+
+    fn definitions() -> Vec<FieldDefinition> {
+        vec![
+            FieldDefinition::new("ng_person", FieldType::RecordReference("ng_person".into()))
+                .label("Person"),
+            FieldDefinition::new("digest_hour", FieldType::Integer).label("Digest hour"),
+            FieldDefinition::new("digest", FieldType::Boolean).label("Daily digest"),
+            FieldDefinition::new(
+                "nickname",
+                FieldType::Text {
+                    max_length: Some(5),
+                },
+            )
+            .label("Nickname")
+            .required(),
+        ]
+    }
+
+    fn submitted(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn first_declaration_wins_and_unsupported_types_are_dropped() {
+        let results = vec![
+            result(
+                "netgrasp",
+                serde_json::json!([{"field_name": "ng_person", "field_type": {"RecordReference": "ng_person"}, "label": "Person"}]),
+            ),
+            result(
+                "argus",
+                serde_json::json!([
+                    {"field_name": "ng_person", "field_type": "TextLong", "label": "Dup"},
+                    {"field_name": "layout", "field_type": "Blocks", "label": "Layout"},
+                    {"field_name": "alerts", "field_type": "Boolean", "label": "Alerts"}
+                ]),
+            ),
+            TapResult {
+                plugin_name: "broken".to_string(),
+                output: "not json".to_string(),
+            },
+        ];
+
+        let fields = collect_definitions(&results);
+        let names: Vec<&str> = fields.iter().map(|f| f.field_name.as_str()).collect();
+        assert_eq!(names, vec!["ng_person", "alerts"]);
+        assert_eq!(fields[0].label, "Person");
+    }
+
+    #[test]
+    fn form_values_strips_profile_prefix() {
+        let form = submitted(&[("profile[digest]", "1"), ("name", "alice")]);
+        let values = form_values(&form);
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["digest"], "1");
+    }
+
+    #[test]
+    fn valid_values_are_typed_and_merged() {
+        let person = Uuid::now_v7();
+        let existing = serde_json::json!({"legacy": "kept", "digest_hour": 7});
+        let fields = apply_form_values(
+            &definitions(),
+            &submitted(&[
+                ("ng_person", &person.to_string()),
+                ("nickname", "al"),
+                ("digest_hour", ""),
+            ]),
+            &existing,
+        )
+        .unwrap();
+
+        assert_eq!(fields["ng_person"], person.to_string());
+        assert_eq!(fields["nickname"], "al");
+        assert_eq!(fields["digest"], false);
+        assert_eq!(fields["legacy"], "kept");
+        assert!(fields.get("digest_hour").is_none());
+    }
+
+    #[test]
+    fn invalid_values_report_every_error() {
+        let errors = apply_form_values(
+            &definitions(),
+            &submitted(&[
+                ("ng_person", "not-a-uuid"),
+                ("digest_hour", "seven"),
+                ("nickname", "toolong"),
+            ]),
+            &Value::Null,
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 4);
+        assert!(errors.iter().any(|e| e == "Person must be an item ID."));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e == "Digest hour must be a whole number.")
+        );
+        assert!(errors.iter().any(|e| e.starts_with("Nickname must be 5")));
+        assert!(errors.iter().any(|e| e == "Nickname is required."));
+    }
+
+    #[test]
+    fn user_editable_comes_from_settings() {
+        let mut field = FieldDefinition::new("digest", FieldType::Boolean);
+        assert!(!is_user_editable(&field));
+        field.settings = serde_json::json!({ "user_editable": true });
+        assert!(is_user_editable(&field));
+    }
+}
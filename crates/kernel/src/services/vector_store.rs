@@ -44,6 +44,20 @@ pub struct SimilarityResult {
     pub field_name: String,
 }
 
+/// Format an embedding as a pgvector literal: `[1,2.5,3]`.
+///
+/// Bind the result as text and cast with `$n::vector`.
+pub fn vector_literal(embedding: &[f32]) -> String {
+    format!(
+        "[{}]",
+        embedding
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
 // ============================================================================
 // Trait
 // ============================================================================
@@ -140,15 +154,7 @@ impl VectorStore for PgVectorStore {
         }
 
         let dimensions = embedding.len() as i32;
-        // Format embedding as pgvector literal: [1.0, 2.0, 3.0]
-        let vector_str = format!(
-            "[{}]",
-            embedding
-                .iter()
-                .map(|f| f.to_string())
-                .collect::<Vec<_>>()
-                .join(",")
-        );
+        let vector_str = vector_literal(embedding);
 
         sqlx::query(
             r#"
@@ -183,14 +189,7 @@ impl VectorStore for PgVectorStore {
             return Ok(Vec::new());
         }
 
-        let vector_str = format!(
-            "[{}]",
-            embedding
-                .iter()
-                .map(|f| f.to_string())
-                .collect::<Vec<_>>()
-                .join(",")
-        );
+        let vector_str = vector_literal(embedding);
 
         let rows = sqlx::query_as::<_, (Uuid, f64, String)>(
             r#"
@@ -316,6 +315,12 @@ mod tests {
         assert_eq!(results[0].distance, 0.5);
     }
 
+    #[test]
+    fn vector_literal_formats_pgvector_text() {
+        assert_eq!(vector_literal(&[1.0, 2.5, -3.0]), "[1,2.5,-3]");
+        assert_eq!(vector_literal(&[]), "[]");
+    }
+
     #[tokio::test]
     async fn mock_store_delete_returns_zero() {
         let store = MockVectorStore;
@@ -0,0 +1,176 @@
+//! Inbound webhooks: hook secrets and call verification.
+//!
+//! Plugins declare the hooks they receive under `[webhooks]` in their
+//! `.info.toml` ([`WebhookConfig`]); calls arrive at
+//! `/api/hooks/{plugin}/{hook}`. Each hook's secret is the plugin variable
+//! `webhook_secret.{hook}` (stored in `site_config` as
+//! `plugin.{plugin}.webhook_secret.{hook}`), so the plugin can read or
+//! generate it, e.g. to hand it to a WebSub hub, and administrators can set
+//! it for senders such as CI systems. Hooks without a secret accept no calls.
+
+use anyhow::Result;
+use axum::http::{HeaderMap, header};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::Sha256;
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+
+use crate::models::SiteConfig;
+use crate::plugin::{WebhookConfig, WebhookVerify};
+
+/// Default header carrying a body signature.
+const DEFAULT_SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// Default header carrying a shared token.
+const DEFAULT_TOKEN_HEADER: &str = "x-webhook-token";
+
+/// `site_config` key of a hook's secret.
+pub fn secret_key(plugin: &str, hook: &str) -> String {
+    format!("plugin.{plugin}.webhook_secret.{hook}")
+}
+
+/// Load a hook's secret; `None` if unset or empty.
+pub async fn load_secret(pool: &PgPool, plugin: &str, hook: &str) -> Result<Option<String>> {
+    let secret = SiteConfig::get(pool, &secret_key(plugin, hook))
+        .await?
+        .and_then(|v| v.as_str().map(str::to_string));
+    Ok(secret.filter(|s| !s.is_empty()))
+}
+
+/// Check a call against the hook's secret.
+pub fn verify(config: &WebhookConfig, secret: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    match config.verify {
+        WebhookVerify::Signature => {
+            let name = config.header.as_deref().unwrap_or(DEFAULT_SIGNATURE_HEADER);
+            header_value(headers, name).is_some_and(|sig| verify_signature(secret, body, sig))
+        }
+        WebhookVerify::Token => {
+            let name = config.header.as_deref().unwrap_or(DEFAULT_TOKEN_HEADER);
+            let token = header_value(headers, name).or_else(|| {
+                header_value(headers, header::AUTHORIZATION.as_str())?.strip_prefix("Bearer ")
+            });
+            token.is_some_and(|t| bool::from(t.trim().as_bytes().ct_eq(secret.as_bytes())))
+        }
+    }
+}
+
+/// Verify an HMAC signature of the body: `sha256=<hex>`, `sha1=<hex>`, or a
+/// bare SHA-256 hex digest.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let (algorithm, digest) = signature
+        .trim()
+        .split_once('=')
+        .unwrap_or(("sha256", signature.trim()));
+    let Ok(digest) = hex::decode(digest) else {
+        return false;
+    };
+
+    match algorithm.to_ascii_lowercase().as_str() {
+        "sha256" => Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map(|mac| mac.chain_update(body).verify_slice(&digest).is_ok())
+            .unwrap_or(false),
+        "sha1" => Hmac::<Sha1>::new_from_slice(secret.as_bytes())
+            .map(|mac| mac.chain_update(body).verify_slice(&digest).is_ok())
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn config(verify: WebhookVerify, header: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            verify,
+            header: header.map(str::to_string),
+            methods: vec!["POST".to_string()],
+        }
+    }
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .unwrap()
+            .chain_update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn signature_accepts_prefixed_and_bare_digests() {
+        let body = br#"{"ref":"main"}"#;
+        let digest = sign("s3cret", body);
+        assert!(verify_signature(
+            "s3cret",
+            body,
+            &format!("sha256={digest}")
+        ));
+        assert!(verify_signature("s3cret", body, &digest));
+        assert!(!verify_signature(
+            "other",
+            body,
+            &format!("sha256={digest}")
+        ));
+        assert!(!verify_signature("s3cret", b"tampered", &digest));
+        assert!(!verify_signature("s3cret", body, &format!("md5={digest}")));
+        assert!(!verify_signature("s3cret", body, "sha256=not-hex"));
+
+        let sha1 = Hmac::<Sha1>::new_from_slice(b"s3cret")
+            .unwrap()
+            .chain_update(body);
+        let sha1 = hex::encode(sha1.finalize().into_bytes());
+        assert!(verify_signature("s3cret", body, &format!("sha1={sha1}")));
+    }
+
+    #[test]
+    fn verify_reads_configured_header() {
+        let body = b"payload";
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-hub-signature",
+            format!("sha256={}", sign("k", body)).parse().unwrap(),
+        );
+
+        assert!(verify(
+            &config(WebhookVerify::Signature, Some("X-Hub-Signature")),
+            "k",
+            &headers,
+            body
+        ));
+        assert!(!verify(
+            &config(WebhookVerify::Signature, None),
+            "k",
+            &headers,
+            body
+        ));
+    }
+
+    #[test]
+    fn token_accepts_header_or_bearer() {
+        let token = config(WebhookVerify::Token, None);
+        let mut headers = HeaderMap::new();
+        assert!(!verify(&token, "t0ken", &headers, b""));
+
+        headers.insert("x-webhook-token", "t0ken".parse().unwrap());
+        assert!(verify(&token, "t0ken", &headers, b""));
+        assert!(!verify(&token, "other", &headers, b""));
+
+        let mut bearer = HeaderMap::new();
+        bearer.insert(header::AUTHORIZATION, "Bearer t0ken".parse().unwrap());
+        assert!(verify(&token, "t0ken", &bearer, b""));
+    }
+
+    #[test]
+    fn secret_key_is_a_plugin_variable() {
+        assert_eq!(
+            secret_key("argus", "websub"),
+            "plugin.argus.webhook_secret.websub"
+        );
+    }
+}
@@ -0,0 +1,376 @@
+//! Personal workspaces: one private stage per editor.
+//!
+//! When enabled, every user with the [`USE_WORKSPACE_PERMISSION`] gets an
+//! internal stage of their own, created at their first login and named
+//! after them. The policy lives in `site_config` under
+//! `personal_workspaces`:
+//!
+//! ```json
+//! { "enabled": true, "activate_on_login": true, "max_items": 100, "expiry_days": 30 }
+//! ```
+//!
+//! `activate_on_login` makes the workspace the session's active stage.
+//! `max_items` caps the number of items staged in a workspace (0 = no
+//! limit). Workspaces unused for `expiry_days` (0 = never) are removed by
+//! the `cleanup_personal_stages` cron task together with their unpublished
+//! content.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::models::stage::{CreateStage, LIVE_STAGE_ID, Stage};
+use crate::models::{SiteConfig, User};
+
+/// Permission that entitles a user to a personal workspace.
+pub const USE_WORKSPACE_PERMISSION: &str = "use personal workspace";
+
+/// `site_config` key holding [`WorkspaceSettings`].
+pub const WORKSPACE_SETTINGS_KEY: &str = "personal_workspaces";
+
+/// Default cap on items staged in one workspace.
+pub const DEFAULT_MAX_ITEMS: u32 = 100;
+
+/// Default days without a login before a workspace expires.
+pub const DEFAULT_EXPIRY_DAYS: u32 = 30;
+
+/// Upper bound on `expiry_days` (one year).
+pub const MAX_EXPIRY_DAYS: u32 = 365;
+
+/// Maximum workspaces removed per cleanup run.
+const CLEANUP_BATCH: i64 = 50;
+
+/// Sort weight of workspace stages, after the shared stages.
+const WORKSPACE_STAGE_WEIGHT: i16 = 100;
+
+/// Personal workspace policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceSettings {
+    /// Whether workspaces are provisioned at all.
+    pub enabled: bool,
+    /// Make the workspace the active stage when its owner logs in.
+    pub activate_on_login: bool,
+    /// Maximum items staged in one workspace (0 = unlimited).
+    pub max_items: u32,
+    /// Days without a login before a workspace expires (0 = never).
+    pub expiry_days: u32,
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            activate_on_login: true,
+            max_items: DEFAULT_MAX_ITEMS,
+            expiry_days: DEFAULT_EXPIRY_DAYS,
+        }
+    }
+}
+
+impl WorkspaceSettings {
+    /// Check that the expiry is in range.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.expiry_days > MAX_EXPIRY_DAYS {
+            return Err(format!(
+                "expiry_days must be between 0 and {MAX_EXPIRY_DAYS}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether a workspace holding `count` items can take another one.
+    pub fn has_room(&self, count: i64) -> bool {
+        self.max_items == 0 || count < i64::from(self.max_items)
+    }
+}
+
+/// A personal workspace record.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Workspace {
+    /// The workspace's stage (category_tag.id).
+    pub stage_id: Uuid,
+    /// Owner of the workspace.
+    pub user_id: Uuid,
+    /// Unix timestamp when created.
+    pub created: i64,
+    /// Unix timestamp of the owner's last login.
+    pub last_used: i64,
+}
+
+/// Load the workspace policy, falling back to defaults if unset or invalid.
+pub async fn settings(pool: &PgPool) -> Result<WorkspaceSettings> {
+    Ok(match SiteConfig::get(pool, WORKSPACE_SETTINGS_KEY).await? {
+        Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+            warn!(error = %e, "invalid personal_workspaces settings; workspaces disabled");
+            WorkspaceSettings::default()
+        }),
+        None => WorkspaceSettings::default(),
+    })
+}
+
+/// Validate and persist the workspace policy.
+pub async fn save_settings(pool: &PgPool, settings: &WorkspaceSettings) -> Result<()> {
+    settings.validate().map_err(anyhow::Error::msg)?;
+    SiteConfig::set(
+        pool,
+        WORKSPACE_SETTINGS_KEY,
+        serde_json::to_value(settings).context("failed to serialize workspace settings")?,
+    )
+    .await
+}
+
+/// Stage machine name of a user's workspace.
+pub fn machine_name(user_id: Uuid) -> String {
+    format!("workspace_{}", user_id.simple())
+}
+
+/// Stage label of a user's workspace.
+pub fn label(user_name: &str) -> String {
+    format!("{user_name}'s workspace")
+}
+
+/// Find a user's workspace.
+pub async fn find_for_user(pool: &PgPool, user_id: Uuid) -> Result<Option<Workspace>> {
+    sqlx::query_as::<_, Workspace>(
+        "SELECT stage_id, user_id, created, last_used FROM stage_workspace WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .context("failed to fetch workspace")
+}
+
+/// Find the workspace backed by a stage.
+pub async fn find_by_stage(pool: &PgPool, stage_id: Uuid) -> Result<Option<Workspace>> {
+    sqlx::query_as::<_, Workspace>(
+        "SELECT stage_id, user_id, created, last_used FROM stage_workspace WHERE stage_id = $1",
+    )
+    .bind(stage_id)
+    .fetch_optional(pool)
+    .await
+    .context("failed to fetch workspace by stage")
+}
+
+/// Return a user's workspace, creating its stage if needed, and mark it used.
+///
+/// Safe to call repeatedly: an existing stage with the workspace machine
+/// name is adopted rather than duplicated.
+pub async fn provision(pool: &PgPool, user: &User) -> Result<Workspace> {
+    let now = chrono::Utc::now().timestamp();
+
+    if find_for_user(pool, user.id).await?.is_none() {
+        let name = machine_name(user.id);
+        let stage = match Stage::find_by_machine_name(pool, &name).await? {
+            Some(stage) => stage,
+            None => Stage::create(
+                pool,
+                CreateStage {
+                    label: label(&user.name),
+                    machine_name: name,
+                    description: Some(format!("Personal workspace of {}", user.name)),
+                    visibility: Some("internal".to_string()),
+                    is_default: Some(false),
+                    weight: Some(WORKSPACE_STAGE_WEIGHT),
+                },
+            )
+            .await
+            .context("failed to create workspace stage")?,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO stage_workspace (stage_id, user_id, created, last_used)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+        )
+        .bind(stage.id)
+        .bind(user.id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .context("failed to record workspace")?;
+
+        info!(user_id = %user.id, stage_id = %stage.id, "provisioned personal workspace");
+    }
+
+    sqlx::query_as::<_, Workspace>(
+        r#"
+        UPDATE stage_workspace SET last_used = $2 WHERE user_id = $1
+        RETURNING stage_id, user_id, created, last_used
+        "#,
+    )
+    .bind(user.id)
+    .bind(now)
+    .fetch_one(pool)
+    .await
+    .context("failed to update workspace last use")
+}
+
+/// Refuse to stage another item in a full workspace.
+///
+/// Stages that are not personal workspaces are never limited.
+pub async fn check_quota(pool: &PgPool, stage_id: Uuid) -> Result<()> {
+    if stage_id == LIVE_STAGE_ID || find_by_stage(pool, stage_id).await?.is_none() {
+        return Ok(());
+    }
+
+    let settings = settings(pool).await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item WHERE stage_id = $1")
+        .bind(stage_id)
+        .fetch_one(pool)
+        .await
+        .context("failed to count workspace items")?;
+
+    if !settings.has_room(count) {
+        bail!(
+            "personal workspace is full: {count} of {} items staged; publish or delete some first",
+            settings.max_items
+        );
+    }
+    Ok(())
+}
+
+/// Remove workspaces whose owners have not logged in within `expiry_days`.
+///
+/// Unpublished items, aliases, menu links, tiles, and pending deletions in
+/// an expired workspace are discarded with its stage. Returns the number
+/// of workspaces removed.
+pub async fn cleanup_expired(pool: &PgPool) -> Result<u64> {
+    let settings = settings(pool).await?;
+    if settings.expiry_days == 0 {
+        return Ok(0);
+    }
+    let cutoff = chrono::Utc::now().timestamp() - i64::from(settings.expiry_days) * 86400;
+
+    let expired: Vec<Workspace> = sqlx::query_as(
+        r#"
+        SELECT stage_id, user_id, created, last_used FROM stage_workspace
+        WHERE last_used < $1
+        ORDER BY last_used
+        LIMIT $2
+        "#,
+    )
+    .bind(cutoff)
+    .bind(CLEANUP_BATCH)
+    .fetch_all(pool)
+    .await
+    .context("failed to list expired workspaces")?;
+
+    let mut removed = 0;
+    for workspace in expired {
+        match remove_stage(pool, workspace.stage_id).await {
+            Ok(items) => {
+                info!(
+                    user_id = %workspace.user_id,
+                    stage_id = %workspace.stage_id,
+                    items,
+                    "removed expired personal workspace"
+                );
+                removed += 1;
+            }
+            Err(e) => warn!(
+                error = %e,
+                stage_id = %workspace.stage_id,
+                "failed to remove expired personal workspace"
+            ),
+        }
+    }
+    Ok(removed)
+}
+
+/// Delete a workspace stage and everything staged in it.
+///
+/// Returns the number of items discarded.
+async fn remove_stage(pool: &PgPool, stage_id: Uuid) -> Result<u64> {
+    if stage_id == LIVE_STAGE_ID {
+        bail!("cannot remove the live stage");
+    }
+
+    let mut tx = pool.begin().await.context("failed to start transaction")?;
+
+    let items = sqlx::query("DELETE FROM item WHERE stage_id = $1")
+        .bind(stage_id)
+        .execute(&mut *tx)
+        .await
+        .context("failed to delete workspace items")?
+        .rows_affected();
+
+    for table in [
+        "url_alias",
+        "menu_link",
+        "tile",
+        "config_stage_association",
+        "stage_deletion",
+    ] {
+        sqlx::query(&format!("DELETE FROM {table} WHERE stage_id = $1"))
+            .bind(stage_id)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("failed to delete workspace rows from {table}"))?;
+    }
+
+    // Cascades to stage_config and stage_workspace.
+    sqlx::query("DELETE FROM category_tag WHERE id = $1 AND category_id = 'stages'")
+        .bind(stage_id)
+        .execute(&mut *tx)
+        .await
+        .context("failed to delete workspace stage")?;
+
+    tx.commit().await.context("failed to commit transaction")?;
+    Ok(items)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_to_disabled() {
+        let settings: WorkspaceSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, WorkspaceSettings::default());
+        assert!(!settings.enabled);
+        assert!(settings.activate_on_login);
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn settings_reject_long_expiry() {
+        let settings = WorkspaceSettings {
+            expiry_days: MAX_EXPIRY_DAYS + 1,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn quota_counts_staged_items() {
+        let settings = WorkspaceSettings {
+            max_items: 2,
+            ..Default::default()
+        };
+        assert!(settings.has_room(1));
+        assert!(!settings.has_room(2));
+
+        let unlimited = WorkspaceSettings {
+            max_items: 0,
+            ..Default::default()
+        };
+        assert!(unlimited.has_room(10_000));
+    }
+
+    #[test]
+    fn machine_name_is_valid_and_unique_per_user() {
+        let a = machine_name(Uuid::now_v7());
+        let b = machine_name(Uuid::now_v7());
+        assert_ne!(a, b);
+        assert!(a.len() <= 64);
+        assert!(crate::routes::helpers::is_valid_machine_name(&a));
+        assert_eq!(label("ada"), "ada's workspace");
+    }
+}
@@ -1,27 +1,304 @@
-//! Session management using Redis.
+//! Session management.
+//!
+//! Sessions are stored in Redis by default. Redis sessions use a `fred` pool
+//! rather than the shared [`RedisManager`] (the store requires it),
+//! configured from the same [`RedisSettings`] so Sentinel and Cluster
+//! deployments work here too. `SESSION_STORE=postgres` keeps sessions in
+//! the `sessions` table instead; Redis is still required for cron locking,
+//! queues, and rate limiting.
+//!
+//! Lifetimes come from [`SessionSettings`]: the layer applies the default
+//! TTL, and [`apply_session_expiry`](crate::middleware::apply_session_expiry)
+//! re-applies the per-session lifetime ("remember me", fixed expiry) on each
+//! request, since tower-sessions does not persist a session's `Expiry`.
+//!
+//! [`RedisManager`]: crate::redis_manager::RedisManager
+
+use std::collections::HashMap;
+use std::str::FromStr;
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use fred::prelude::*;
+use sqlx::PgPool;
 use tower_sessions::cookie::SameSite;
-use tower_sessions::cookie::time::Duration;
+use tower_sessions::cookie::time::{Duration, OffsetDateTime};
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store::{self, SessionStore};
 use tower_sessions::{Expiry, SessionManagerLayer};
 use tower_sessions_redis_store::RedisStore;
 
+use crate::config::Config;
+use crate::redis_manager::RedisSettings;
+
 /// Default session expiry (24 hours).
 pub const DEFAULT_SESSION_EXPIRY_HOURS: i64 = 24;
 
 /// Extended session expiry for "remember me" (30 days).
-#[allow(dead_code)]
 pub const REMEMBER_ME_SESSION_EXPIRY_DAYS: i64 = 30;
 
-/// Create the session layer using Redis as the backend.
+/// Reconnect delay bounds for the session pool, in milliseconds.
+const RECONNECT_MIN_DELAY_MS: u32 = 250;
+const RECONNECT_MAX_DELAY_MS: u32 = 30_000;
+
+/// Where session records are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionBackend {
+    /// Redis (default).
+    Redis,
+    /// The `sessions` table in PostgreSQL.
+    Postgres,
+}
+
+impl FromStr for SessionBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "redis" => Ok(Self::Redis),
+            "postgres" | "postgresql" => Ok(Self::Postgres),
+            other => anyhow::bail!("unknown session store '{other}' (expected redis or postgres)"),
+        }
+    }
+}
+
+/// Session lifetime and storage settings.
+#[derive(Debug, Clone)]
+pub struct SessionSettings {
+    /// Where session records are stored.
+    pub backend: SessionBackend,
+    /// Lifetime of a regular session.
+    pub ttl: Duration,
+    /// Lifetime of a session created with "remember me" checked.
+    pub remember_me_ttl: Duration,
+    /// Whether activity extends the session (`true`) or the lifetime is
+    /// fixed from login (`false`).
+    pub sliding: bool,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            backend: SessionBackend::Redis,
+            ttl: Duration::hours(DEFAULT_SESSION_EXPIRY_HOURS),
+            remember_me_ttl: Duration::days(REMEMBER_ME_SESSION_EXPIRY_DAYS),
+            sliding: true,
+        }
+    }
+}
+
+impl SessionSettings {
+    /// Build settings from the application config.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self {
+            backend: config.session_store.parse()?,
+            ttl: Duration::hours(config.session_ttl_hours),
+            remember_me_ttl: Duration::days(config.session_remember_me_days),
+            sliding: config.session_sliding,
+        })
+    }
+
+    /// Expiry for an authenticated session.
+    ///
+    /// `authenticated_at` is the login time as a Unix timestamp; it anchors
+    /// the lifetime when sliding expiration is disabled.
+    pub fn expiry_for(&self, remember_me: bool, authenticated_at: Option<i64>) -> Expiry {
+        let ttl = if remember_me {
+            self.remember_me_ttl
+        } else {
+            self.ttl
+        };
+
+        if self.sliding {
+            return Expiry::OnInactivity(ttl);
+        }
+
+        match authenticated_at.and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok()) {
+            Some(start) => Expiry::AtDateTime(start + ttl),
+            None => Expiry::OnInactivity(ttl),
+        }
+    }
+}
+
+/// Session store used by the kernel: Redis or PostgreSQL.
+#[derive(Debug, Clone)]
+pub enum KernelSessionStore {
+    /// Sessions kept in Redis with a per-key expiry.
+    Redis(RedisStore<Pool>),
+    /// Sessions kept in the PostgreSQL `sessions` table.
+    Postgres(PgSessionStore),
+}
+
+#[async_trait]
+impl SessionStore for KernelSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        match self {
+            Self::Redis(store) => store.create(record).await,
+            Self::Postgres(store) => store.create(record).await,
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        match self {
+            Self::Redis(store) => store.save(record).await,
+            Self::Postgres(store) => store.save(record).await,
+        }
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        match self {
+            Self::Redis(store) => store.load(session_id).await,
+            Self::Postgres(store) => store.load(session_id).await,
+        }
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        match self {
+            Self::Redis(store) => store.delete(session_id).await,
+            Self::Postgres(store) => store.delete(session_id).await,
+        }
+    }
+}
+
+/// PostgreSQL-backed session store using the `sessions` table.
+///
+/// Expired rows are ignored on load and removed by the
+/// `cleanup_expired_sessions` cron task.
+#[derive(Debug, Clone)]
+pub struct PgSessionStore {
+    pool: PgPool,
+}
+
+impl PgSessionStore {
+    /// Create a store on the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn backend_error(e: sqlx::Error) -> session_store::Error {
+    session_store::Error::Backend(e.to_string())
+}
+
+#[async_trait]
+impl SessionStore for PgSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let data = serde_json::to_value(&record.data)
+            .map_err(|e| session_store::Error::Encode(e.to_string()))?;
+
+        // Regenerate the ID on the (astronomically unlikely) collision.
+        loop {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO sessions (id, data, expires, updated)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (id) DO NOTHING
+                "#,
+            )
+            .bind(record.id.to_string())
+            .bind(&data)
+            .bind(record.expiry_date.unix_timestamp())
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(backend_error)?;
+
+            if result.rows_affected() > 0 {
+                return Ok(());
+            }
+            record.id = Id::default();
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let data = serde_json::to_value(&record.data)
+            .map_err(|e| session_store::Error::Encode(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, data, expires, updated)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (id) DO UPDATE
+            SET data = EXCLUDED.data, expires = EXCLUDED.expires, updated = EXCLUDED.updated
+            "#,
+        )
+        .bind(record.id.to_string())
+        .bind(&data)
+        .bind(record.expiry_date.unix_timestamp())
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&self.pool)
+        .await
+        .map_err(backend_error)?;
+
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let row: Option<(serde_json::Value, i64)> =
+            sqlx::query_as("SELECT data, expires FROM sessions WHERE id = $1 AND expires > $2")
+                .bind(session_id.to_string())
+                .bind(chrono::Utc::now().timestamp())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(backend_error)?;
+
+        let Some((data, expires)) = row else {
+            return Ok(None);
+        };
+
+        let data: HashMap<String, serde_json::Value> = serde_json::from_value(data)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+        let expiry_date = OffsetDateTime::from_unix_timestamp(expires)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+
+        Ok(Some(Record {
+            id: *session_id,
+            data,
+            expiry_date,
+        }))
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        sqlx::query("DELETE FROM sessions WHERE id = $1")
+            .bind(session_id.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(backend_error)?;
+        Ok(())
+    }
+}
+
+/// Create the session layer for the configured backend.
 pub async fn create_session_layer(
-    redis_url: &str,
+    settings: &SessionSettings,
+    redis: &RedisSettings,
+    db: &PgPool,
     same_site: SameSite,
-) -> Result<SessionManagerLayer<RedisStore<Pool>>> {
-    let config = Config::from_url(redis_url).context("failed to parse Redis URL")?;
+) -> Result<SessionManagerLayer<KernelSessionStore>> {
+    let store = match settings.backend {
+        SessionBackend::Redis => KernelSessionStore::Redis(create_redis_store(redis).await?),
+        SessionBackend::Postgres => KernelSessionStore::Postgres(PgSessionStore::new(db.clone())),
+    };
+
+    let session_layer = SessionManagerLayer::new(store)
+        .with_secure(true) // Cookie only sent over HTTPS
+        .with_http_only(true) // Cookie not accessible via JavaScript
+        .with_same_site(same_site)
+        .with_expiry(Expiry::OnInactivity(settings.ttl));
+
+    Ok(session_layer)
+}
+
+/// Connect the Redis session store.
+async fn create_redis_store(redis: &RedisSettings) -> Result<RedisStore<Pool>> {
+    let config = redis.fred_config()?;
+
+    // Retry forever with exponential backoff, matching RedisManager.
+    let policy =
+        ReconnectPolicy::new_exponential(0, RECONNECT_MIN_DELAY_MS, RECONNECT_MAX_DELAY_MS, 2);
 
     let pool = Builder::from_config(config)
+        .set_policy(policy)
         .build_pool(1)
         .context("failed to create Redis pool")?;
 
@@ -29,15 +306,69 @@ pub async fn create_session_layer(
         .await
         .context("failed to connect to Redis for sessions")?;
 
-    let store = RedisStore::new(pool);
+    Ok(RedisStore::new(pool))
+}
 
-    let session_layer = SessionManagerLayer::new(store)
-        .with_secure(true) // Cookie only sent over HTTPS
-        .with_http_only(true) // Cookie not accessible via JavaScript
-        .with_same_site(same_site)
-        .with_expiry(Expiry::OnInactivity(Duration::hours(
-            DEFAULT_SESSION_EXPIRY_HOURS,
-        )));
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
 
-    Ok(session_layer)
+    #[test]
+    fn backend_parses() {
+        assert_eq!(
+            "redis".parse::<SessionBackend>().unwrap(),
+            SessionBackend::Redis
+        );
+        assert_eq!(
+            "Postgres".parse::<SessionBackend>().unwrap(),
+            SessionBackend::Postgres
+        );
+        assert!("memcached".parse::<SessionBackend>().is_err());
+    }
+
+    #[test]
+    fn sliding_expiry_uses_inactivity() {
+        let settings = SessionSettings::default();
+        assert_eq!(
+            settings.expiry_for(false, Some(0)),
+            Expiry::OnInactivity(Duration::hours(24))
+        );
+        assert_eq!(
+            settings.expiry_for(true, Some(0)),
+            Expiry::OnInactivity(Duration::days(30))
+        );
+    }
+
+    #[test]
+    fn fixed_expiry_anchors_to_login() {
+        let settings = SessionSettings {
+            sliding: false,
+            ..SessionSettings::default()
+        };
+        let login = 1_700_000_000;
+        let start = OffsetDateTime::from_unix_timestamp(login).unwrap();
+
+        assert_eq!(
+            settings.expiry_for(false, Some(login)),
+            Expiry::AtDateTime(start + Duration::hours(24))
+        );
+        assert_eq!(
+            settings.expiry_for(true, Some(login)),
+            Expiry::AtDateTime(start + Duration::days(30))
+        );
+    }
+
+    #[test]
+    fn fixed_expiry_without_login_time_falls_back() {
+        let settings = SessionSettings {
+            sliding: false,
+            ..SessionSettings::default()
+        };
+        assert_eq!(
+            settings.expiry_for(false, None),
+            Expiry::OnInactivity(Duration::hours(24))
+        );
+    }
 }
@@ -0,0 +1,436 @@
+//! Itemized list of what publishing a stage will change.
+//!
+//! Covers the same records as [`StageService::has_changes`](super::StageService::has_changes):
+//! staged items and their pending deletions, staged config revisions and
+//! config deletions, and the stage's URL aliases and menu links. A staged
+//! item is `modified` when its item group has a live copy and `added`
+//! otherwise; categories and tags are `added` when they are not live yet,
+//! other config is always `modified`. Each list is paginated with the same
+//! page size; [`ChangeCounts`] holds the totals.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config_storage::entity_types;
+use crate::models::stage::LIVE_STAGE_ID;
+
+/// Entity types in `stage_deletion` that are not config.
+const NON_CONFIG_DELETIONS: [&str; 3] = [
+    entity_types::ITEM,
+    entity_types::URL_ALIAS,
+    entity_types::MENU_LINK,
+];
+
+/// What publishing does to an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// New to live.
+    Added,
+    /// Replaces the live version.
+    Modified,
+    /// Removed from live.
+    Deleted,
+}
+
+impl ChangeKind {
+    fn parse(value: &str) -> Self {
+        match value {
+            "added" => Self::Added,
+            "deleted" => Self::Deleted,
+            _ => Self::Modified,
+        }
+    }
+}
+
+/// Number of changes of each kind in a stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct ChangeCounts {
+    pub items_added: i64,
+    pub items_modified: i64,
+    pub items_deleted: i64,
+    /// Staged config revisions and config deletions.
+    pub config: i64,
+    /// Staged and deleted URL aliases.
+    pub aliases: i64,
+    /// Staged and deleted menu links.
+    pub menu_links: i64,
+}
+
+impl ChangeCounts {
+    /// Whether publishing would change anything.
+    pub fn is_empty(&self) -> bool {
+        self.items_added
+            + self.items_modified
+            + self.items_deleted
+            + self.config
+            + self.aliases
+            + self.menu_links
+            == 0
+    }
+}
+
+/// A staged change to an item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemChange {
+    pub id: Uuid,
+    pub item_type: String,
+    pub title: String,
+    pub change: ChangeKind,
+    pub author_id: Uuid,
+    /// Author's user name, if the account still exists.
+    pub author_name: Option<String>,
+    /// Unix timestamp of the staged change (the deletion, for deletions).
+    pub changed: i64,
+}
+
+/// A staged change to a config entity.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub entity_type: String,
+    pub entity_id: String,
+    /// `label` or `name` of the entity's latest revision.
+    pub label: Option<String>,
+    pub change: ChangeKind,
+    /// Author of the staged revision, or who deleted the entity.
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub changed: Option<i64>,
+}
+
+/// A staged change to a URL alias.
+#[derive(Debug, Clone, Serialize)]
+pub struct AliasChange {
+    pub id: Uuid,
+    pub source: String,
+    pub alias: String,
+    pub language: String,
+    pub change: ChangeKind,
+}
+
+/// A staged change to a menu link.
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuLinkChange {
+    pub id: Uuid,
+    pub menu_name: String,
+    pub title: String,
+    pub path: String,
+    pub change: ChangeKind,
+}
+
+/// Everything publishing a stage will change, one page of each kind.
+#[derive(Debug, Clone, Serialize)]
+pub struct StageManifest {
+    pub stage_id: Uuid,
+    pub counts: ChangeCounts,
+    pub items: Vec<ItemChange>,
+    pub config: Vec<ConfigChange>,
+    pub aliases: Vec<AliasChange>,
+    pub menu_links: Vec<MenuLinkChange>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemChangeRow {
+    id: Uuid,
+    item_type: String,
+    title: String,
+    change: String,
+    author_id: Uuid,
+    author_name: Option<String>,
+    changed: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ConfigChangeRow {
+    entity_type: String,
+    entity_id: String,
+    label: Option<String>,
+    change: String,
+    author_id: Option<Uuid>,
+    author_name: Option<String>,
+    changed: Option<i64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct AliasChangeRow {
+    id: Uuid,
+    source: String,
+    alias: String,
+    language: String,
+    change: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct MenuLinkChangeRow {
+    id: Uuid,
+    menu_name: String,
+    title: String,
+    path: String,
+    change: String,
+}
+
+impl StageManifest {
+    /// Load the manifest of a stage, `limit` entries of each list from
+    /// `offset`.
+    pub async fn load(pool: &PgPool, stage_id: Uuid, limit: i64, offset: i64) -> Result<Self> {
+        let counts = if stage_id == LIVE_STAGE_ID {
+            ChangeCounts::default()
+        } else {
+            Self::counts(pool, stage_id).await?
+        };
+        if counts.is_empty() {
+            return Ok(Self {
+                stage_id,
+                counts,
+                items: Vec::new(),
+                config: Vec::new(),
+                aliases: Vec::new(),
+                menu_links: Vec::new(),
+            });
+        }
+
+        let items = sqlx::query_as::<_, ItemChangeRow>(
+            r#"
+            SELECT * FROM (
+                SELECT i.id, i.type AS item_type, i.title, i.author_id, u.name AS author_name,
+                       i.changed,
+                       CASE WHEN EXISTS (
+                           SELECT 1 FROM item l
+                           WHERE l.item_group_id = i.item_group_id AND l.stage_id = $2
+                       ) THEN 'modified' ELSE 'added' END AS change
+                FROM item i
+                LEFT JOIN users u ON u.id = i.author_id
+                WHERE i.stage_id = $1
+                UNION ALL
+                SELECT i.id, i.type, i.title, i.author_id, u.name, d.deleted_at, 'deleted'
+                FROM stage_deletion d
+                JOIN item i ON i.id::text = d.entity_id
+                LEFT JOIN users u ON u.id = i.author_id
+                WHERE d.stage_id = $1 AND d.entity_type = 'item'
+            ) changes
+            ORDER BY CASE change WHEN 'added' THEN 0 WHEN 'modified' THEN 1 ELSE 2 END,
+                     title, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(stage_id)
+        .bind(LIVE_STAGE_ID)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list staged item changes")?;
+
+        let config = sqlx::query_as::<_, ConfigChangeRow>(
+            r#"
+            SELECT * FROM (
+                SELECT a.entity_type, a.entity_id,
+                       COALESCE(r.data->>'label', r.data->>'name') AS label,
+                       CASE
+                           WHEN a.entity_type = 'category'
+                                AND NOT EXISTS (SELECT 1 FROM category c WHERE c.id = a.entity_id)
+                               THEN 'added'
+                           WHEN a.entity_type = 'tag'
+                                AND NOT EXISTS (SELECT 1 FROM category_tag t WHERE t.id::text = a.entity_id)
+                               THEN 'added'
+                           ELSE 'modified'
+                       END AS change,
+                       r.author_id, u.name AS author_name, r.created AS changed
+                FROM config_stage_association a
+                JOIN config_revision r ON r.id = a.target_revision_id
+                LEFT JOIN users u ON u.id = r.author_id
+                WHERE a.stage_id = $1
+                UNION ALL
+                SELECT d.entity_type, d.entity_id,
+                       (SELECT COALESCE(cr.data->>'label', cr.data->>'name')
+                        FROM config_revision cr
+                        WHERE cr.entity_type = d.entity_type AND cr.entity_id = d.entity_id
+                        ORDER BY cr.created DESC LIMIT 1),
+                       'deleted', d.deleted_by, u.name, d.deleted_at
+                FROM stage_deletion d
+                LEFT JOIN users u ON u.id = d.deleted_by
+                WHERE d.stage_id = $1 AND d.entity_type <> ALL($2)
+            ) changes
+            ORDER BY entity_type, entity_id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(stage_id)
+        .bind(&NON_CONFIG_DELETIONS[..])
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list staged config changes")?;
+
+        let aliases = sqlx::query_as::<_, AliasChangeRow>(
+            r#"
+            SELECT * FROM (
+                SELECT a.id, a.source, a.alias, a.language,
+                       CASE WHEN EXISTS (
+                           SELECT 1 FROM url_alias l
+                           WHERE l.source = a.source AND l.language = a.language
+                             AND l.stage_id = $2
+                       ) THEN 'modified' ELSE 'added' END AS change
+                FROM url_alias a
+                WHERE a.stage_id = $1
+                UNION ALL
+                SELECT a.id, a.source, a.alias, a.language, 'deleted'
+                FROM stage_deletion d
+                JOIN url_alias a ON a.id::text = d.entity_id
+                WHERE d.stage_id = $1 AND d.entity_type = 'url_alias'
+            ) changes
+            ORDER BY alias, language, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(stage_id)
+        .bind(LIVE_STAGE_ID)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list staged alias changes")?;
+
+        let menu_links = sqlx::query_as::<_, MenuLinkChangeRow>(
+            r#"
+            SELECT * FROM (
+                SELECT m.id, m.menu_name, m.title, m.path,
+                       CASE WHEN EXISTS (
+                           SELECT 1 FROM menu_link l
+                           WHERE l.menu_name = m.menu_name AND l.path = m.path
+                             AND l.stage_id = $2
+                       ) THEN 'modified' ELSE 'added' END AS change
+                FROM menu_link m
+                WHERE m.stage_id = $1
+                UNION ALL
+                SELECT m.id, m.menu_name, m.title, m.path, 'deleted'
+                FROM stage_deletion d
+                JOIN menu_link m ON m.id::text = d.entity_id
+                WHERE d.stage_id = $1 AND d.entity_type = 'menu_link'
+            ) changes
+            ORDER BY menu_name, title, id
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(stage_id)
+        .bind(LIVE_STAGE_ID)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("failed to list staged menu link changes")?;
+
+        Ok(Self {
+            stage_id,
+            counts,
+            items: items
+                .into_iter()
+                .map(|r| ItemChange {
+                    id: r.id,
+                    item_type: r.item_type,
+                    title: r.title,
+                    change: ChangeKind::parse(&r.change),
+                    author_id: r.author_id,
+                    author_name: r.author_name,
+                    changed: r.changed,
+                })
+                .collect(),
+            config: config
+                .into_iter()
+                .map(|r| ConfigChange {
+                    entity_type: r.entity_type,
+                    entity_id: r.entity_id,
+                    label: r.label,
+                    change: ChangeKind::parse(&r.change),
+                    author_id: r.author_id,
+                    author_name: r.author_name,
+                    changed: r.changed,
+                })
+                .collect(),
+            aliases: aliases
+                .into_iter()
+                .map(|r| AliasChange {
+                    id: r.id,
+                    source: r.source,
+                    alias: r.alias,
+                    language: r.language,
+                    change: ChangeKind::parse(&r.change),
+                })
+                .collect(),
+            menu_links: menu_links
+                .into_iter()
+                .map(|r| MenuLinkChange {
+                    id: r.id,
+                    menu_name: r.menu_name,
+                    title: r.title,
+                    path: r.path,
+                    change: ChangeKind::parse(&r.change),
+                })
+                .collect(),
+        })
+    }
+
+    /// Count a stage's changes of each kind.
+    pub async fn counts(pool: &PgPool, stage_id: Uuid) -> Result<ChangeCounts> {
+        sqlx::query_as::<_, ChangeCounts>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM item i
+                 WHERE i.stage_id = $1
+                   AND NOT EXISTS (SELECT 1 FROM item l
+                                   WHERE l.item_group_id = i.item_group_id AND l.stage_id = $2)
+                ) AS items_added,
+                (SELECT COUNT(*) FROM item i
+                 WHERE i.stage_id = $1
+                   AND EXISTS (SELECT 1 FROM item l
+                               WHERE l.item_group_id = i.item_group_id AND l.stage_id = $2)
+                ) AS items_modified,
+                (SELECT COUNT(*) FROM stage_deletion
+                 WHERE stage_id = $1 AND entity_type = 'item') AS items_deleted,
+                (SELECT COUNT(*) FROM config_stage_association WHERE stage_id = $1)
+                + (SELECT COUNT(*) FROM stage_deletion
+                   WHERE stage_id = $1 AND entity_type <> ALL($3)) AS config,
+                (SELECT COUNT(*) FROM url_alias WHERE stage_id = $1)
+                + (SELECT COUNT(*) FROM stage_deletion
+                   WHERE stage_id = $1 AND entity_type = 'url_alias') AS aliases,
+                (SELECT COUNT(*) FROM menu_link WHERE stage_id = $1)
+                + (SELECT COUNT(*) FROM stage_deletion
+                   WHERE stage_id = $1 AND entity_type = 'menu_link') AS menu_links
+            "#,
+        )
+        .bind(stage_id)
+        .bind(LIVE_STAGE_ID)
+        .bind(&NON_CONFIG_DELETIONS[..])
+        .fetch_one(pool)
+        .await
+        .context("failed to count stage changes")
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_kinds_round_trip_through_sql_labels() {
+        for kind in [ChangeKind::Added, ChangeKind::Modified, ChangeKind::Deleted] {
+            let label = serde_json::to_value(kind).unwrap();
+            assert_eq!(ChangeKind::parse(label.as_str().unwrap()), kind);
+        }
+    }
+
+    #[test]
+    fn counts_are_empty_only_without_changes() {
+        assert!(ChangeCounts::default().is_empty());
+        let counts = ChangeCounts {
+            menu_links: 1,
+            ..Default::default()
+        };
+        assert!(!counts.is_empty());
+    }
+}
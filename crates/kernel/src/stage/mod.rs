@@ -5,7 +5,8 @@
 //! dependencies are satisfied:
 //!
 //! 1. **Config Types/Fields**: Item type definitions (nothing depends on these)
-//! 2. **Categories**: Categories referenced by content items
+//! 2. **Categories**: Categories and tags staged through
+//!    [`StageAwareConfigStorage`](crate::config_storage::StageAwareConfigStorage)
 //! 3. **Items**: Content items (depend on types and categories)
 //! 4. **Dependents**: Menus, aliases, etc. (reference content)
 //!
@@ -17,9 +18,33 @@
 //! Before publishing, the system detects potential conflicts:
 //! - **Cross-stage conflicts**: Multiple stages have changes to the same entity
 //! - **Live-modified conflicts**: Live version was changed after staging began
+//!   (for tags, the live row's `changed` timestamp is compared too, since live
+//!   taxonomy edits don't record config revisions)
 //!
 //! Conflicts are reported but don't block publish (warn-only mode).
 //! Users can choose to Skip, Overwrite, or Cancel per conflict.
+//!
+//! ## Moderation
+//!
+//! Workflows with `require_publishable` set block publishing a stage that
+//! holds items of their types in a state that is not `publishable`.
+//!
+//! ## Scheduling
+//!
+//! A stage can be scheduled to publish at a future time; see [`schedule`].
+//!
+//! ## Change Manifest
+//!
+//! [`StageManifest`] itemizes what a publish would change, for review
+//! before publishing; see [`manifest`].
+
+mod manifest;
+mod schedule;
+
+pub use manifest::{
+    AliasChange, ChangeCounts, ChangeKind, ConfigChange, ItemChange, MenuLinkChange, StageManifest,
+};
+pub use schedule::{REPORT_LEAD_SECS, StagePublishSchedule, conflict_report};
 
 use anyhow::{Context, Result};
 use sqlx::{PgPool, Postgres, Row, Transaction};
@@ -27,9 +52,18 @@ use std::collections::HashMap;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use crate::cache::CacheLayer;
+use crate::cache::page::PAGE_TAG;
+use crate::cache::{CacheLayer, ITEM_LISTING_TAG};
+use crate::config_storage::{ConfigEntity, entity_types};
+use crate::models::Workflow;
 use crate::models::stage::{CreateStage, LIVE_STAGE_ID, Stage};
 
+/// Maximum unpublishable item titles listed in a refused publish's message.
+const MAX_REPORTED_UNPUBLISHABLE: usize = 10;
+
+/// Config entity types published by the categories phase.
+const TAXONOMY_ENTITY_TYPES: [&str; 2] = [entity_types::CATEGORY, entity_types::TAG];
+
 /// Identifies which publish phase is executing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PublishPhase {
@@ -274,7 +308,7 @@ type PublishPhaseFn<'a> = Box<dyn FnMut(&mut Transaction<'_, Postgres>) -> Resul
 pub struct PublishPhases<'a> {
     /// Phase 1: Publish config types (no-op in v1.0).
     pub config_types: PublishPhaseFn<'a>,
-    /// Phase 2: Publish categories (no-op in v1.0).
+    /// Phase 2: Publish categories.
     pub categories: PublishPhaseFn<'a>,
     /// Phase 3: Publish items (active in v1.0).
     pub items: PublishPhaseFn<'a>,
@@ -397,6 +431,62 @@ impl StageService {
         Stage::list_all(&self.pool).await
     }
 
+    // ── Publish scheduling ──
+
+    /// Schedule a stage to publish to live at `publish_at` (Unix timestamp).
+    ///
+    /// Replaces any publish already scheduled for the stage.
+    pub async fn schedule_publish(
+        &self,
+        stage_id: Uuid,
+        publish_at: i64,
+        scheduled_by: Option<Uuid>,
+    ) -> Result<StagePublishSchedule> {
+        if stage_id == LIVE_STAGE_ID {
+            anyhow::bail!("cannot schedule the live stage to publish");
+        }
+        let schedule =
+            StagePublishSchedule::upsert(&self.pool, stage_id, publish_at, scheduled_by).await?;
+        info!(stage_id = %stage_id, publish_at = publish_at, "scheduled stage publish");
+        Ok(schedule)
+    }
+
+    /// Cancel the scheduled publish of a stage.
+    ///
+    /// Returns `false` if none was scheduled.
+    pub async fn cancel_scheduled_publish(&self, stage_id: Uuid) -> Result<bool> {
+        StagePublishSchedule::delete(&self.pool, stage_id).await
+    }
+
+    /// Get the scheduled publish of a stage.
+    pub async fn scheduled_publish(&self, stage_id: Uuid) -> Result<Option<StagePublishSchedule>> {
+        StagePublishSchedule::find(&self.pool, stage_id).await
+    }
+
+    /// List all scheduled publishes, soonest first.
+    pub async fn list_scheduled_publishes(&self) -> Result<Vec<StagePublishSchedule>> {
+        StagePublishSchedule::list(&self.pool).await
+    }
+
+    /// Scheduled publishes whose time has come.
+    pub async fn due_scheduled_publishes(&self, now: i64) -> Result<Vec<StagePublishSchedule>> {
+        StagePublishSchedule::due(&self.pool, now).await
+    }
+
+    /// Scheduled publishes within [`REPORT_LEAD_SECS`] whose conflict report
+    /// is still to be sent.
+    pub async fn scheduled_publishes_awaiting_report(
+        &self,
+        now: i64,
+    ) -> Result<Vec<StagePublishSchedule>> {
+        StagePublishSchedule::awaiting_report(&self.pool, now).await
+    }
+
+    /// Record that the conflict report of a scheduled publish was sent.
+    pub async fn mark_publish_reported(&self, stage_id: Uuid, now: i64) -> Result<()> {
+        StagePublishSchedule::mark_reported(&self.pool, stage_id, now).await
+    }
+
     /// Check if a stage has any pending changes.
     pub async fn has_changes(&self, stage_id: Uuid) -> Result<bool> {
         if stage_id == LIVE_STAGE_ID {
@@ -437,6 +527,17 @@ impl StageService {
         Ok(deletion_count > 0)
     }
 
+    /// Itemize a stage's pending changes, `limit` entries of each kind
+    /// from `offset`.
+    pub async fn change_manifest(
+        &self,
+        stage_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<StageManifest> {
+        StageManifest::load(&self.pool, stage_id, limit, offset).await
+    }
+
     /// Detect conflicts before publishing a stage.
     ///
     /// Returns a list of conflicts found. Empty list means no conflicts.
@@ -455,6 +556,10 @@ impl StageService {
         let live_modified_config = self.detect_live_modified_config_conflicts(stage_id).await?;
         conflicts.extend(live_modified_config);
 
+        // Detect live-modified tag conflicts
+        let live_modified_tags = self.detect_live_modified_tag_conflicts(stage_id).await?;
+        conflicts.extend(live_modified_tags);
+
         // Detect cross-stage alias conflicts
         let alias_conflicts = self.detect_cross_stage_alias_conflicts(stage_id).await?;
         conflicts.extend(alias_conflicts);
@@ -472,15 +577,20 @@ impl StageService {
             SELECT
                 a.entity_type,
                 a.entity_id,
-                array_agg(DISTINCT b.stage_id) as other_stages
+                array_agg(DISTINCT b.stage_id) as other_stages,
+                COALESCE(c.label, t.label) as label
             FROM config_stage_association a
             JOIN config_stage_association b
                 ON a.entity_type = b.entity_type
                 AND a.entity_id = b.entity_id
                 AND b.stage_id != $1
                 AND b.stage_id != $2
+            LEFT JOIN category c
+                ON a.entity_type = 'category' AND c.id = a.entity_id
+            LEFT JOIN category_tag t
+                ON a.entity_type = 'tag' AND t.id::text = a.entity_id
             WHERE a.stage_id = $1
-            GROUP BY a.entity_type, a.entity_id
+            GROUP BY a.entity_type, a.entity_id, c.label, t.label
             "#,
         )
         .bind(stage_id)
@@ -494,12 +604,17 @@ impl StageService {
             let entity_type: String = row.get("entity_type");
             let entity_id: String = row.get("entity_id");
             let other_stages: Vec<Uuid> = row.get("other_stages");
+            let label: Option<String> = row.get("label");
 
-            conflicts.push(ConflictInfo::new(
+            let conflict = ConflictInfo::new(
                 &entity_type,
                 &entity_id,
                 ConflictType::CrossStage { other_stages },
-            ));
+            );
+            conflicts.push(match label {
+                Some(label) => conflict.with_label(taxonomy_label(&entity_type, &label)),
+                None => conflict,
+            });
         }
 
         Ok(conflicts)
@@ -561,6 +676,62 @@ impl StageService {
         Ok(conflicts)
     }
 
+    /// Detect tags staged or deleted in this stage whose live row changed
+    /// afterwards.
+    ///
+    /// Live taxonomy edits update `category_tag.changed` without recording a
+    /// config revision, so [`Self::detect_live_modified_config_conflicts`]
+    /// cannot see them.
+    async fn detect_live_modified_tag_conflicts(
+        &self,
+        stage_id: Uuid,
+    ) -> Result<Vec<ConflictInfo>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT staged.entity_id, staged.staged_at, t.changed as live_changed, t.label
+            FROM (
+                SELECT csa.entity_id, cr.created as staged_at
+                FROM config_stage_association csa
+                JOIN config_revision cr ON cr.id = csa.target_revision_id
+                WHERE csa.stage_id = $1 AND csa.entity_type = $2
+                UNION ALL
+                SELECT entity_id, deleted_at as staged_at
+                FROM stage_deletion
+                WHERE stage_id = $1 AND entity_type = $2
+            ) staged
+            JOIN category_tag t ON t.id::text = staged.entity_id
+            WHERE t.changed > staged.staged_at
+            "#,
+        )
+        .bind(stage_id)
+        .bind(entity_types::TAG)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to detect live-modified tag conflicts")?;
+
+        let mut conflicts = Vec::new();
+        for row in rows {
+            let entity_id: String = row.get("entity_id");
+            let staged_at: i64 = row.get("staged_at");
+            let live_changed: i64 = row.get("live_changed");
+            let label: String = row.get("label");
+
+            conflicts.push(
+                ConflictInfo::new(
+                    entity_types::TAG,
+                    &entity_id,
+                    ConflictType::LiveModified {
+                        staged_at,
+                        live_changed,
+                    },
+                )
+                .with_label(taxonomy_label(entity_types::TAG, &label)),
+            );
+        }
+
+        Ok(conflicts)
+    }
+
     /// Detect URL aliases in this stage that conflict with aliases in other stages.
     async fn detect_cross_stage_alias_conflicts(
         &self,
@@ -606,8 +777,40 @@ impl StageService {
         Ok(conflicts)
     }
 
+    /// Items in a stage that moderation forbids publishing.
+    ///
+    /// Only workflows with `require_publishable` set are considered; items
+    /// of their types must be in a `publishable` state. Returns the ID and
+    /// title of each offending item.
+    pub async fn unpublishable_items(&self, stage_id: Uuid) -> Result<Vec<(Uuid, String)>> {
+        let mut unready = Vec::new();
+        for workflow in Workflow::list_all(&self.pool).await? {
+            if !workflow.require_publishable || workflow.item_types.is_empty() {
+                continue;
+            }
+            let rows: Vec<(Uuid, String, Option<String>)> = sqlx::query_as(
+                "SELECT id, title, moderation_state FROM item \
+                 WHERE stage_id = $1 AND type = ANY($2) ORDER BY title",
+            )
+            .bind(stage_id)
+            .bind(&workflow.item_types)
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to load moderated stage items")?;
+
+            unready.extend(
+                rows.into_iter()
+                    .filter(|(_, _, state)| !workflow.is_publishable(state.as_deref()))
+                    .map(|(id, title, _)| (id, title)),
+            );
+        }
+        Ok(unready)
+    }
+
     /// Publish a stage with explicit conflict resolution.
     ///
+    /// Publishing is refused if moderation requires items to be publishable
+    /// and some are not (see [`Self::unpublishable_items`]).
     /// If conflicts are detected and resolution is Cancel, the publish is aborted.
     /// Otherwise, entities are published according to the resolution strategy.
     pub async fn publish_with_resolution(
@@ -623,6 +826,24 @@ impl StageService {
             ));
         }
 
+        let unready = self.unpublishable_items(stage_id).await?;
+        if !unready.is_empty() {
+            let titles: Vec<&str> = unready
+                .iter()
+                .take(MAX_REPORTED_UNPUBLISHABLE)
+                .map(|(_, title)| title.as_str())
+                .collect();
+            return Ok(PublishResult::failure(
+                stage_id,
+                PublishPhase::Items,
+                format!(
+                    "{} item(s) are not in a publishable moderation state: {}",
+                    unready.len(),
+                    titles.join(", ")
+                ),
+            ));
+        }
+
         // Detect conflicts first
         let conflicts = self.detect_conflicts(stage_id).await?;
 
@@ -680,8 +901,22 @@ impl StageService {
         // Phase 1: Config types (no-op in v1.0)
         debug!("executing phase 1: config_types (no-op)");
 
-        // Phase 2: Categories (no-op in v1.0)
-        debug!("executing phase 2: categories (no-op)");
+        // Phase 2: Categories and tags
+        debug!("executing phase 2: categories");
+        let config_published = match publish_categories_default(&mut tx, stage_id).await {
+            Ok(count) => count,
+            Err(e) => {
+                warn!(error = %e, "categories phase failed, rolling back");
+                tx.rollback()
+                    .await
+                    .context("failed to rollback after categories phase failure")?;
+                return Ok(PublishResult::failure(
+                    stage_id,
+                    PublishPhase::Categories,
+                    e.to_string(),
+                ));
+            }
+        };
 
         // Phase 3: Items
         debug!("executing phase 3: items");
@@ -721,13 +956,17 @@ impl StageService {
             stage_id = %stage_id,
             items_published = %items_to_publish,
             items_deleted = %items_to_delete,
+            config_published = %config_published,
             dependents_published = %dependents_published,
             conflicts = %conflicts.len(),
             "stage publish completed"
         );
 
-        // Cache invalidation AFTER transaction commits
+        // Cache invalidation AFTER transaction commits. Publishing changes
+        // live content, so live listings and cached pages are stale too.
         self.cache.invalidate_stage(stage_id).await;
+        self.cache.invalidate_tag(ITEM_LISTING_TAG).await;
+        self.cache.invalidate_tag(PAGE_TAG).await;
 
         let mut result = PublishResult::success_with_conflicts(
             stage_id,
@@ -735,11 +974,182 @@ impl StageService {
             items_to_delete,
             conflicts,
         );
+        result.config_published = config_published;
         result.dependents_published = dependents_published;
         Ok(result)
     }
 }
 
+/// Conflict label of a category or tag, e.g. "Tag: Rust".
+fn taxonomy_label(entity_type: &str, label: &str) -> String {
+    match entity_type {
+        entity_types::CATEGORY => format!("Category: {label}"),
+        _ => format!("Tag: {label}"),
+    }
+}
+
+/// Default categories publish phase: applies staged category and tag
+/// revisions to the live tables and processes their deletions.
+///
+/// Categories are written before tags so new tags can reference them, and
+/// tags are deleted before categories. Tags new to live become roots; tag
+/// parents are not staged. Returns the number of entities written or deleted.
+async fn publish_categories_default(
+    tx: &mut Transaction<'_, Postgres>,
+    stage_id: Uuid,
+) -> Result<i64> {
+    let now = chrono::Utc::now().timestamp();
+    let mut total: i64 = 0;
+
+    let revisions: Vec<serde_json::Value> = sqlx::query_scalar(
+        r#"
+        SELECT cr.data
+        FROM config_stage_association csa
+        JOIN config_revision cr ON cr.id = csa.target_revision_id
+        WHERE csa.stage_id = $1 AND csa.entity_type = ANY($2)
+        "#,
+    )
+    .bind(stage_id)
+    .bind(&TAXONOMY_ENTITY_TYPES[..])
+    .fetch_all(&mut **tx)
+    .await
+    .context("failed to load staged categories")?;
+
+    let mut categories = Vec::new();
+    let mut tags = Vec::new();
+    for data in revisions {
+        match serde_json::from_value(data).context("failed to deserialize staged category")? {
+            ConfigEntity::Category(category) => categories.push(category),
+            ConfigEntity::Tag(tag) => tags.push(tag),
+            _ => {}
+        }
+    }
+
+    for category in &categories {
+        sqlx::query(
+            r#"
+            INSERT INTO category (id, label, description, hierarchy, weight)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (id) DO UPDATE
+            SET label = EXCLUDED.label,
+                description = EXCLUDED.description,
+                hierarchy = EXCLUDED.hierarchy,
+                weight = EXCLUDED.weight
+            "#,
+        )
+        .bind(&category.id)
+        .bind(&category.label)
+        .bind(&category.description)
+        .bind(category.hierarchy)
+        .bind(category.weight)
+        .execute(&mut **tx)
+        .await
+        .with_context(|| format!("failed to publish category {}", category.id))?;
+    }
+
+    for tag in &tags {
+        sqlx::query(
+            r#"
+            INSERT INTO category_tag (id, category_id, label, description, slug, weight, created, changed)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+            ON CONFLICT (id) DO UPDATE
+            SET category_id = EXCLUDED.category_id,
+                label = EXCLUDED.label,
+                description = EXCLUDED.description,
+                slug = EXCLUDED.slug,
+                weight = EXCLUDED.weight,
+                changed = EXCLUDED.changed
+            "#,
+        )
+        .bind(tag.id)
+        .bind(&tag.category_id)
+        .bind(&tag.label)
+        .bind(&tag.description)
+        .bind(&tag.slug)
+        .bind(tag.weight)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .with_context(|| format!("failed to publish tag {}", tag.id))?;
+
+        // Tags new to live start as roots
+        sqlx::query(
+            r#"
+            INSERT INTO category_tag_hierarchy (tag_id, parent_id)
+            SELECT $1, NULL
+            WHERE NOT EXISTS (SELECT 1 FROM category_tag_hierarchy WHERE tag_id = $1)
+            "#,
+        )
+        .bind(tag.id)
+        .execute(&mut **tx)
+        .await
+        .context("failed to insert published tag hierarchy")?;
+    }
+
+    total += (categories.len() + tags.len()) as i64;
+    debug!(
+        categories = categories.len(),
+        tags = tags.len(),
+        "published staged categories and tags"
+    );
+
+    let tag_deletions = sqlx::query(
+        r#"
+        DELETE FROM category_tag
+        WHERE id::text IN (
+            SELECT entity_id FROM stage_deletion
+            WHERE stage_id = $1 AND entity_type = $2
+        )
+        "#,
+    )
+    .bind(stage_id)
+    .bind(entity_types::TAG)
+    .execute(&mut **tx)
+    .await
+    .context("failed to delete staged tag deletions")?;
+    total += tag_deletions.rows_affected() as i64;
+
+    let category_deletions = sqlx::query(
+        r#"
+        DELETE FROM category
+        WHERE id IN (
+            SELECT entity_id FROM stage_deletion
+            WHERE stage_id = $1 AND entity_type = $2
+        )
+        "#,
+    )
+    .bind(stage_id)
+    .bind(entity_types::CATEGORY)
+    .execute(&mut **tx)
+    .await
+    .context("failed to delete staged category deletions")?;
+    total += category_deletions.rows_affected() as i64;
+    debug!(
+        categories = %category_deletions.rows_affected(),
+        tags = %tag_deletions.rows_affected(),
+        "deleted categories and tags from deletion records"
+    );
+
+    // Clean up the stage's category and tag records
+    sqlx::query(
+        "DELETE FROM config_stage_association WHERE stage_id = $1 AND entity_type = ANY($2)",
+    )
+    .bind(stage_id)
+    .bind(&TAXONOMY_ENTITY_TYPES[..])
+    .execute(&mut **tx)
+    .await
+    .context("failed to clean up staged category associations")?;
+
+    sqlx::query("DELETE FROM stage_deletion WHERE stage_id = $1 AND entity_type = ANY($2)")
+        .bind(stage_id)
+        .bind(&TAXONOMY_ENTITY_TYPES[..])
+        .execute(&mut **tx)
+        .await
+        .context("failed to clean up category deletion records")?;
+
+    Ok(total)
+}
+
 /// Default items publish phase: moves staged items to live and processes deletions.
 ///
 /// **Known gap (S2-5):** This phase does not consider `item_group_id` for
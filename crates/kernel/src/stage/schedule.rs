@@ -0,0 +1,263 @@
+//! Scheduled stage publishes.
+//!
+//! A stage can carry one pending publish time. The
+//! `publish_scheduled_stages` cron task publishes the stage to live once
+//! that time has passed, and [`REPORT_LEAD_SECS`] beforehand mails the
+//! pre-publish conflict report to whoever scheduled it.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::ConflictInfo;
+use crate::models::stage::Stage;
+
+/// How long before a scheduled publish the conflict report is sent (1 hour).
+pub const REPORT_LEAD_SECS: i64 = 60 * 60;
+
+/// A pending publish of a stage.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct StagePublishSchedule {
+    /// Stage to publish.
+    pub stage_id: Uuid,
+    /// Unix timestamp after which the stage is published.
+    pub publish_at: i64,
+    /// User who scheduled the publish.
+    pub scheduled_by: Option<Uuid>,
+    /// Unix timestamp the conflict report was sent, if it has been.
+    pub report_sent_at: Option<i64>,
+    /// Unix timestamp the schedule was created or last changed.
+    pub created: i64,
+}
+
+impl StagePublishSchedule {
+    /// Schedule a stage publish, replacing any existing schedule.
+    ///
+    /// Rescheduling clears the sent report so it goes out again before the
+    /// new time.
+    pub async fn upsert(
+        pool: &PgPool,
+        stage_id: Uuid,
+        publish_at: i64,
+        scheduled_by: Option<Uuid>,
+    ) -> Result<Self> {
+        let schedule = sqlx::query_as::<_, StagePublishSchedule>(
+            r#"
+            INSERT INTO stage_publish_schedule (stage_id, publish_at, scheduled_by, created)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (stage_id) DO UPDATE
+            SET publish_at = EXCLUDED.publish_at,
+                scheduled_by = EXCLUDED.scheduled_by,
+                report_sent_at = NULL,
+                created = EXCLUDED.created
+            RETURNING *
+            "#,
+        )
+        .bind(stage_id)
+        .bind(publish_at)
+        .bind(scheduled_by)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_one(pool)
+        .await
+        .context("failed to schedule stage publish")?;
+
+        Ok(schedule)
+    }
+
+    /// Find the pending publish of a stage.
+    pub async fn find(pool: &PgPool, stage_id: Uuid) -> Result<Option<Self>> {
+        sqlx::query_as::<_, StagePublishSchedule>(
+            "SELECT * FROM stage_publish_schedule WHERE stage_id = $1",
+        )
+        .bind(stage_id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to load stage publish schedule")
+    }
+
+    /// List all pending publishes, soonest first.
+    pub async fn list(pool: &PgPool) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, StagePublishSchedule>(
+            "SELECT * FROM stage_publish_schedule ORDER BY publish_at, stage_id",
+        )
+        .fetch_all(pool)
+        .await
+        .context("failed to list stage publish schedules")
+    }
+
+    /// Remove the pending publish of a stage.
+    ///
+    /// Returns `false` if none was scheduled.
+    pub async fn delete(pool: &PgPool, stage_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM stage_publish_schedule WHERE stage_id = $1")
+            .bind(stage_id)
+            .execute(pool)
+            .await
+            .context("failed to delete stage publish schedule")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Publishes whose time is at or before `now`, soonest first.
+    pub async fn due(pool: &PgPool, now: i64) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, StagePublishSchedule>(
+            "SELECT * FROM stage_publish_schedule WHERE publish_at <= $1 ORDER BY publish_at, stage_id",
+        )
+        .bind(now)
+        .fetch_all(pool)
+        .await
+        .context("failed to load due stage publishes")
+    }
+
+    /// Future publishes within [`REPORT_LEAD_SECS`] of `now` whose conflict
+    /// report has not been sent.
+    pub async fn awaiting_report(pool: &PgPool, now: i64) -> Result<Vec<Self>> {
+        sqlx::query_as::<_, StagePublishSchedule>(
+            r#"
+            SELECT * FROM stage_publish_schedule
+            WHERE report_sent_at IS NULL
+              AND publish_at > $1
+              AND publish_at <= $1 + $2
+            ORDER BY publish_at, stage_id
+            "#,
+        )
+        .bind(now)
+        .bind(REPORT_LEAD_SECS)
+        .fetch_all(pool)
+        .await
+        .context("failed to load stage publishes awaiting a report")
+    }
+
+    /// Record that the conflict report was sent.
+    pub async fn mark_reported(pool: &PgPool, stage_id: Uuid, now: i64) -> Result<()> {
+        sqlx::query("UPDATE stage_publish_schedule SET report_sent_at = $2 WHERE stage_id = $1")
+            .bind(stage_id)
+            .bind(now)
+            .execute(pool)
+            .await
+            .context("failed to record stage conflict report")?;
+
+        Ok(())
+    }
+}
+
+/// Subject and body of the pre-publish conflict report.
+///
+/// Lists the conflicts that publishing will overwrite and the items whose
+/// moderation state would refuse the publish outright.
+pub fn conflict_report(
+    stage: &Stage,
+    schedule: &StagePublishSchedule,
+    conflicts: &[ConflictInfo],
+    unpublishable: &[(Uuid, String)],
+) -> (String, String) {
+    let when = chrono::DateTime::from_timestamp(schedule.publish_at, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| schedule.publish_at.to_string());
+    let subject = format!("Stage \"{}\" publishes at {when}", stage.label);
+
+    let mut body = format!(
+        "The stage \"{}\" ({}) is scheduled to publish to live at {when}.\n",
+        stage.label, stage.machine_name
+    );
+
+    if conflicts.is_empty() && unpublishable.is_empty() {
+        body.push_str("\nNo conflicts were found.\n");
+    }
+
+    if !conflicts.is_empty() {
+        body.push_str(&format!(
+            "\n{} conflict(s) will be overwritten:\n",
+            conflicts.len()
+        ));
+        for conflict in conflicts {
+            let name = conflict.label.as_deref().unwrap_or(&conflict.entity_id);
+            body.push_str(&format!(
+                "- {} {name}: {}\n",
+                conflict.entity_type, conflict.conflict_type
+            ));
+        }
+    }
+
+    if !unpublishable.is_empty() {
+        body.push_str(&format!(
+            "\n{} item(s) are not in a publishable moderation state; the publish will fail unless they are moderated:\n",
+            unpublishable.len()
+        ));
+        for (_, title) in unpublishable {
+            body.push_str(&format!("- {title}\n"));
+        }
+    }
+
+    body.push_str("\nCancel or reschedule the publish from the stage administration.\n");
+
+    (subject, body)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::models::stage::StageVisibility;
+    use crate::stage::ConflictType;
+
+    fn stage() -> Stage {
+        Stage {
+            id: Uuid::now_v7(),
+            label: "Spring launch".to_string(),
+            description: None,
+            machine_name: "spring_launch".to_string(),
+            visibility: StageVisibility::Internal,
+            is_default: false,
+            weight: 0,
+            created: 0,
+            changed: 0,
+        }
+    }
+
+    fn schedule(stage: &Stage) -> StagePublishSchedule {
+        StagePublishSchedule {
+            stage_id: stage.id,
+            publish_at: 1_800_000_000,
+            scheduled_by: None,
+            report_sent_at: None,
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn report_without_conflicts() {
+        let stage = stage();
+        let (subject, body) = conflict_report(&stage, &schedule(&stage), &[], &[]);
+        assert_eq!(
+            subject,
+            "Stage \"Spring launch\" publishes at 2027-01-15 08:00 UTC"
+        );
+        assert!(body.contains("No conflicts were found."));
+    }
+
+    #[test]
+    fn report_lists_conflicts_and_unpublishable_items() {
+        let stage = stage();
+        let conflicts = vec![
+            ConflictInfo::new(
+                "item",
+                "abc",
+                ConflictType::LiveModified {
+                    staged_at: 1,
+                    live_changed: 2,
+                },
+            )
+            .with_label("About us"),
+        ];
+        let unpublishable = vec![(Uuid::now_v7(), "Draft post".to_string())];
+
+        let (_, body) = conflict_report(&stage, &schedule(&stage), &conflicts, &unpublishable);
+        assert!(body.contains("1 conflict(s) will be overwritten"));
+        assert!(body.contains("- item About us: live was modified"));
+        assert!(body.contains("- Draft post"));
+        assert!(!body.contains("No conflicts were found."));
+    }
+}
@@ -4,7 +4,6 @@ use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
 
 use anyhow::{Context, Result};
-use redis::Client as RedisClient;
 use sqlx::PgPool;
 
 use tracing::{error, info, warn};
@@ -16,11 +15,14 @@ use crate::middleware::language::{
 use crate::batch::BatchService;
 use crate::cache::CacheLayer;
 use crate::config::{CacheConfig, Config};
-use crate::config_storage::{ConfigStorage, DirectConfigStorage, StageAwareConfigStorage};
+use crate::config_storage::{
+    CachedConfigStorage, ConfigStorage, DirectConfigStorage, StageAwareConfigStorage,
+    TranslatedConfigStorage,
+};
 use crate::content::{ContentTypeRegistry, ItemService};
-use crate::cron::CronService;
+use crate::cron::{CronService, RedisQueue};
 use crate::db;
-use crate::file::{FileService, LocalFileStorage};
+use crate::file::{FileService, storage_for_backend};
 use crate::form::FormService;
 use crate::gather::{
     CategoryService, GatherExtensionDeclaration, GatherExtensionRegistry, GatherService,
@@ -33,12 +35,17 @@ use crate::permissions::PermissionService;
 use crate::plugin::{
     PluginConfig, PluginRuntime, migration as plugin_migration, status as plugin_status,
 };
+use crate::redis_manager::{RedisManager, RedisSettings};
 use crate::search::SearchService;
 use crate::services;
+use crate::services::status_report::StatusReport;
 use crate::stage::StageService;
-use crate::tap::{RequestServices, TapDispatcher, TapRegistry};
+use crate::tap::{RequestServices, RequestState, TapDispatcher, TapRegistry, UserContext};
 use crate::theme::ThemeEngine;
 
+/// How often read replicas are pinged to decide whether they get traffic.
+const REPLICA_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
 /// Shared application state.
 ///
 /// Wrapped in Arc internally so Clone is cheap.
@@ -51,12 +58,21 @@ struct AppStateInner {
     /// PostgreSQL connection pool.
     db: PgPool,
 
+    /// Primary pool plus read replicas for read-only paths.
+    db_pools: db::DbPools,
+
     /// Maximum database connections configured for the pool.
     db_pool_max_connections: u32,
 
     /// Path to plugins directory on disk.
     plugins_dir: PathBuf,
 
+    /// Directory for backup archives.
+    backup_dir: PathBuf,
+
+    /// Primary database URL, for `pg_dump` during backups.
+    database_url: String,
+
     /// Set of enabled plugin names (mutable via admin UI).
     ///
     /// Uses `parking_lot::RwLock` rather than `std::sync::RwLock` because:
@@ -64,8 +80,8 @@ struct AppStateInner {
     /// - Shorter critical sections avoid blocking Tokio worker threads.
     enabled_plugins: parking_lot::RwLock<std::collections::HashSet<String>>,
 
-    /// Redis client for sessions and caching.
-    redis: RedisClient,
+    /// Shared Redis connection manager.
+    redis: RedisManager,
 
     /// Two-tier cache layer (Moka L1 + Redis L2).
     cache: CacheLayer,
@@ -159,12 +175,24 @@ struct AppStateInner {
     /// Frozen at startup: changing the default language requires a restart.
     default_language: String,
 
+    /// Public base URL of the site (from `SITE_URL`), without trailing slash.
+    site_url: String,
+
+    /// Reverse proxies whose forwarding headers are trusted (from `TRUSTED_PROXIES`).
+    trusted_proxies: Vec<crate::lockout::IpNetwork>,
+
+    /// Anonymous page cache lifetime in seconds (`None` when disabled).
+    page_cache_ttl: Option<u64>,
+
     /// User service for user CRUD with tap integration and caching.
     users: Arc<services::user::UserService>,
 
     /// Role service for role/permission management with cache invalidation.
     roles: Arc<services::role::RoleService>,
 
+    /// External identity providers for OIDC login.
+    oidc: Arc<services::oidc::OidcService>,
+
     /// Tile rendering service.
     tiles: Arc<services::tile::TileService>,
 
@@ -172,6 +200,21 @@ struct AppStateInner {
     /// Email delivery service (available when SMTP_HOST is configured).
     email: Option<Arc<services::email::EmailService>>,
 
+    /// Outgoing mail: templates, `tap_mail_alter`, queued delivery.
+    mail: Arc<services::mail::MailService>,
+
+    /// Password policy checks for registration, change, and reset.
+    password_policy: Arc<services::password_policy::PasswordPolicyService>,
+
+    /// Usage tracking for deprecated API shapes.
+    deprecations: Arc<services::deprecation::DeprecationService>,
+
+    /// Read-only mode and per-type write locks.
+    read_only: Arc<services::read_only::ReadOnlyService>,
+
+    /// Hostname to site resolution for multisite deployments.
+    sites: Arc<services::site::SiteService>,
+
     // --- Optional services (available when their plugins are enabled) ---
     /// Audit logging service.
     audit: Option<Arc<services::audit::AuditService>>,
@@ -179,6 +222,9 @@ struct AppStateInner {
     /// Content lock service.
     content_lock: Option<Arc<services::content_lock::ContentLockService>>,
 
+    /// Item flags (promote, sticky, plugin and admin flags).
+    flags: Option<Arc<services::flag::FlagService>>,
+
     /// Image style service.
     image_styles: Option<Arc<services::image_style::ImageStyleService>>,
 
@@ -188,6 +234,15 @@ struct AppStateInner {
     /// Locale service.
     locale: Option<Arc<services::locale::LocaleService>>,
 
+    /// Sampled read access logging for opted-in item types.
+    read_log: Option<Arc<services::read_log::ReadLogService>>,
+
+    /// Saved searches and their alerts.
+    saved_searches: Option<Arc<services::saved_search::SavedSearchService>>,
+
+    /// Scheduled field changes of items.
+    scheduled_updates: Option<Arc<services::scheduled_update::ScheduledUpdateService>>,
+
     /// Redirect lookup cache (available when redirects plugin is enabled).
     redirect_cache: Option<Arc<services::redirect::RedirectCache>>,
 
@@ -216,31 +271,45 @@ impl AppState {
             .await
             .context("failed to run migrations")?;
 
-        // Create Redis client
-        let redis = RedisClient::open(config.redis_url.as_str())
-            .context("failed to create Redis client")?;
+        // Read replicas for read-only paths. Checked once before serving
+        // traffic, then periodically so failed replicas drop out and
+        // recovered ones rejoin.
+        let db_pools = db::DbPools::new(
+            db.clone(),
+            db::create_replica_pools(config).context("failed to configure read replicas")?,
+        );
+        if db_pools.has_replicas() {
+            db_pools.check_replicas().await;
+            let pools = db_pools.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(REPLICA_CHECK_INTERVAL);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    pools.check_replicas().await;
+                }
+            });
+        }
+
+        // Create the shared Redis connection manager
+        let redis_settings =
+            RedisSettings::from_config(config).context("invalid Redis configuration")?;
+        let redis = RedisManager::new(redis_settings)
+            .context("failed to create Redis connection manager")?;
 
         // Test Redis connection
-        let mut conn = redis
-            .get_multiplexed_async_connection()
-            .await
-            .context("failed to connect to Redis")?;
+        let mut conn = redis.get().await.context("failed to connect to Redis")?;
 
         redis::cmd("PING")
             .query_async::<String>(&mut conn)
             .await
             .context("Redis PING failed")?;
 
-        // Create config storage
-        // This is the central interface for all config entity access.
-        // Post-MVP, we can swap this with StageAwareConfigStorage for stage awareness.
-        let config_storage: Arc<dyn ConfigStorage> = Arc::new(DirectConfigStorage::new(db.clone()));
-
         // Create permission service
         let permissions = PermissionService::new(db.clone(), cache_config.ttl_permissions);
 
         // Create lockout service
-        let lockout = LockoutService::new(redis.clone());
+        let lockout = LockoutService::new(redis.clone(), db.clone());
 
         // Discover plugins on disk (parse info.toml without compiling WASM)
         let discovered = PluginRuntime::discover_plugins(&config.plugins_dir).await;
@@ -296,7 +365,10 @@ impl AppState {
         let enabled_set: std::collections::HashSet<String> = enabled_names.into_iter().collect();
 
         // Create plugin runtime and load only enabled plugins
-        let plugin_config = PluginConfig::default();
+        let plugin_config = PluginConfig {
+            cache_dir: config.plugin_cache_dir.clone(),
+            ..Default::default()
+        };
         let mut plugin_runtime =
             PluginRuntime::new(&plugin_config).context("failed to create plugin runtime")?;
 
@@ -330,13 +402,31 @@ impl AppState {
         // Create tap registry
         let tap_registry = Arc::new(TapRegistry::from_plugins(&plugin_runtime));
 
+        // Create metrics
+        let metrics = Arc::new(Metrics::new());
+        metrics.record_plugin_module_loads(&plugin_runtime);
+
         // Create tap dispatcher
-        let tap_dispatcher = Arc::new(TapDispatcher::new(
-            plugin_runtime.clone(),
-            tap_registry.clone(),
-        ));
+        let tap_dispatcher = Arc::new(
+            TapDispatcher::new(plugin_runtime.clone(), tap_registry.clone())
+                .with_metrics(metrics.clone()),
+        );
 
-        use crate::tap::{RequestServices, RequestState, UserContext};
+        // Create config storage
+        // This is the central interface for all config entity access.
+        // Saves are checked by plugins through tap_config_validate, and
+        // reads are cached in memory until a save or delete.
+        let config_storage: Arc<dyn ConfigStorage> = Arc::new(
+            CachedConfigStorage::new(
+                Arc::new(
+                    DirectConfigStorage::new(db.clone())
+                        .with_read_pools(db_pools.clone())
+                        .with_validation(tap_dispatcher.clone()),
+                ),
+                cache_config.ttl_config,
+            )
+            .with_metrics(metrics.clone()),
+        );
 
         // Dispatch tap_install for enabled plugins that haven't had it called yet.
         // This covers both auto-installed plugins (first server start after adding
@@ -360,6 +450,7 @@ impl AppState {
                             tap_install_db.clone(),
                             None,
                             None,
+                            None,
                             http.clone(),
                         ),
                     );
@@ -463,11 +554,12 @@ impl AppState {
 
         // Create gather service and load queries
         let gather = GatherService::new(
-            db.clone(),
+            db_pools.clone(),
             categories.clone(),
             gather_extensions,
             cache_config.ttl_gather_queries,
             config.gather_max_page_size,
+            metrics.clone(),
         );
         gather
             .load_queries()
@@ -528,10 +620,10 @@ impl AppState {
         ));
 
         // Create cache layer (Moka L1 + Redis L2)
-        let cache = CacheLayer::new(redis.clone());
+        let cache = CacheLayer::new(redis.clone()).with_metrics(metrics.clone());
 
         // Create search service
-        let search = Arc::new(SearchService::new(db.clone()));
+        let search = Arc::new(SearchService::new(db.clone()).with_read_pools(db_pools.clone()));
 
         // Create AI provider service
         let ai_providers = Arc::new(services::ai_provider::AiProviderService::new(db.clone()));
@@ -559,32 +651,93 @@ impl AppState {
             tap_http,
         );
 
-        // Create item service (needs tap_services for presave/insert/update taps)
-        let items = Arc::new(ItemService::new(
+        // Audit logging is provided by the audit_log plugin. Created before the
+        // item service so status changes can be recorded in the audit trail.
+        let audit = if enabled_set.contains("trovato_audit_log") {
+            Some(Arc::new(services::audit::AuditService::new(db.clone())))
+        } else {
+            None
+        };
+
+        // Read-only mode: forced by config, or toggled at runtime via site_config.
+        let read_only = Arc::new(services::read_only::ReadOnlyService::new(
             db.clone(),
-            tap_dispatcher.clone(),
-            tap_services.clone(),
-            cache_config.ttl_items,
+            config.read_only,
         ));
+        if config.read_only {
+            tracing::warn!("READ_ONLY is set; mutating requests will be rejected");
+        }
 
-        // Create file service with local storage
-        let file_storage = Arc::new(LocalFileStorage::new(
-            &config.uploads_dir,
-            &config.files_url,
-        ));
+        // Create item service (needs tap_services for presave/insert/update taps)
+        let items = Arc::new(
+            ItemService::new(
+                db_pools.clone(),
+                tap_dispatcher.clone(),
+                tap_services.clone(),
+                content_types.clone(),
+                audit.clone(),
+                read_only.clone(),
+                cache_config.ttl_items,
+                cache_config.reference_depth,
+            )
+            .with_metrics(metrics.clone()),
+        );
+
+        let flags = if enabled_set.contains("trovato_flags") {
+            Some(Arc::new(services::flag::FlagService::new(
+                db.clone(),
+                items.clone(),
+                tap_dispatcher.clone(),
+                tap_services.clone(),
+            )))
+        } else {
+            None
+        };
+
+        // Due updates are applied by the scheduled updates plugin's tap_cron.
+        let scheduled_updates = if enabled_set.contains("trovato_scheduled_updates") {
+            Some(Arc::new(
+                services::scheduled_update::ScheduledUpdateService::new(
+                    db.clone(),
+                    items.clone(),
+                    permissions.clone(),
+                ),
+            ))
+        } else {
+            None
+        };
+
+        // Create file service with the configured storage backend
+        let file_storage = storage_for_backend(config, &config.file_storage)
+            .await
+            .context("failed to configure file storage")?;
         let files = Arc::new(FileService::new(db.clone(), file_storage));
 
         // Create cron service with file service for proper cleanup
         let mut cron = CronService::with_file_service(redis.clone(), db.clone(), files.clone());
 
-        // Create metrics
-        let metrics = Arc::new(Metrics::new());
-
         // Create rate limiter
         let rate_limiter = Arc::new(RateLimiter::new(redis.clone(), RateLimitConfig::default()));
 
-        // Create batch service
-        let batch = Arc::new(BatchService::new(redis.clone()));
+        // Create batch service, with plugin batches from tap_batch_define
+        let mut batch = BatchService::new(redis.clone());
+        {
+            let define_state = RequestState::without_services(UserContext::anonymous());
+            let define_results = tap_dispatcher
+                .dispatch("tap_batch_define", "{}", define_state)
+                .await;
+            let definitions = crate::batch::BatchDefinitionRegistry::from_tap_results(
+                define_results
+                    .into_iter()
+                    .map(|r| (r.plugin_name, r.output))
+                    .collect(),
+            );
+            // Batch steps run on behalf of the system, not the starting admin.
+            let mut batch_services = tap_services.clone();
+            batch_services.background = true;
+            batch.set_plugin_batches(tap_dispatcher.clone(), batch_services, definitions);
+        }
+        let batch = Arc::new(batch);
 
         // Create stage service
         let stage = Arc::new(StageService::new(db.clone(), cache.clone()));
@@ -596,6 +749,7 @@ impl AppState {
             tap_services.clone(),
             cache_config.ttl_users,
         ));
+        users.sync_profile_fields().await;
 
         // Create role service (depends on permission service for cache invalidation)
         let roles = Arc::new(services::role::RoleService::new(
@@ -603,8 +757,29 @@ impl AppState {
             permissions.clone(),
         ));
 
-        // Create tile service
-        let tiles = Arc::new(services::tile::TileService::new(db.clone()));
+        let oidc = Arc::new(services::oidc::OidcService::new(db.clone()));
+
+        // Create tile service, with plugin blocks from tap_block_info
+        let mut tiles = services::tile::TileService::new(db.clone());
+        {
+            let info_state = RequestState::without_services(UserContext::anonymous());
+            let info_results = tap_dispatcher
+                .dispatch("tap_block_info", "{}", info_state)
+                .await;
+            let blocks = services::tile::BlockRegistry::from_tap_results(
+                info_results
+                    .into_iter()
+                    .map(|r| (r.plugin_name, r.output))
+                    .collect(),
+            );
+            tiles.set_plugin_blocks(
+                tap_dispatcher.clone(),
+                tap_services.clone(),
+                cache.clone(),
+                blocks,
+            );
+        }
+        let tiles = Arc::new(tiles);
 
         // Build language negotiator chain (languages were loaded earlier for locale)
         let mut language_negotiators: Vec<Arc<dyn LanguageNegotiator>> = vec![
@@ -638,13 +813,61 @@ impl AppState {
             }
         });
 
-        // Initialize optional services based on enabled plugins
-        let audit = if enabled_set.contains("trovato_audit_log") {
-            Some(Arc::new(services::audit::AuditService::new(db.clone())))
+        // Mail service: SMTP when configured, or the log transport for dev.
+        let mail_transport = match config.mail_transport.as_deref() {
+            Some("log") => Some(services::mail::MailTransport::Log),
+            _ => email.clone().map(services::mail::MailTransport::Smtp),
+        };
+        let mail = Arc::new(services::mail::MailService::new(
+            mail_transport,
+            Arc::new(RedisQueue::new(redis.clone())),
+            tap_dispatcher.clone(),
+            tap_services.clone(),
+        ));
+
+        // Alerts are checked by the saved searches plugin's tap_cron.
+        let saved_searches = if enabled_set.contains("trovato_saved_searches") {
+            Some(Arc::new(services::saved_search::SavedSearchService::new(
+                db.clone(),
+                search.clone(),
+                mail.clone(),
+                theme.clone(),
+                config.site_url.trim_end_matches('/').to_string(),
+            )))
         } else {
             None
         };
 
+        let password_policy = Arc::new(services::password_policy::PasswordPolicyService::new(
+            db.clone(),
+        ));
+
+        // Multisite: hostnames are resolved to sites by the tenant middleware.
+        let sites = Arc::new(services::site::SiteService::new(
+            db.clone(),
+            &config.tenant_resolution_method,
+        ));
+
+        // Deprecated API usage is counted in memory and flushed periodically.
+        let deprecations = Arc::new(services::deprecation::DeprecationService::new(
+            db.clone(),
+            config.deprecation_headers,
+        ));
+        {
+            let deprecations = deprecations.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(services::deprecation::FLUSH_INTERVAL);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = deprecations.flush().await {
+                        tracing::warn!(error = %e, "failed to flush deprecation usage");
+                    }
+                }
+            });
+        }
+
+        // Initialize optional services based on enabled plugins
         let content_lock = if enabled_set.contains("trovato_content_locking") {
             Some(Arc::new(services::content_lock::ContentLockService::new(
                 db.clone(),
@@ -653,6 +876,15 @@ impl AppState {
             None
         };
 
+        // Read logging is also opt-in per item type via site_config.
+        let read_log = if enabled_set.contains("trovato_read_log") {
+            Some(Arc::new(services::read_log::ReadLogService::new(
+                db.clone(),
+            )))
+        } else {
+            None
+        };
+
         let image_styles = if enabled_set.contains("trovato_image_styles") {
             Some(Arc::new(services::image_style::ImageStyleService::new(
                 db.clone(),
@@ -705,11 +937,23 @@ impl AppState {
 
         // Wire plugin services into cron
         cron.set_plugin_services(content_lock.clone(), audit.clone());
-        cron.set_email_service(email.clone());
+        cron.set_mail_service(mail.clone());
+        if let Some(ref saved_searches) = saved_searches {
+            cron.set_saved_search_service(saved_searches.clone());
+        }
+        if let Some(ref scheduled_updates) = scheduled_updates {
+            cron.set_scheduled_update_service(scheduled_updates.clone());
+        }
         cron.set_tap_dispatcher(tap_dispatcher.clone());
+        cron.set_batch_service(batch.clone());
+        cron.set_read_only_service(read_only.clone());
+        cron.set_stage_service(stage.clone());
+        cron.set_content_types(content_types.clone());
         cron.set_ai_providers(ai_providers.clone());
         cron.set_ai_budgets(ai_budgets.clone());
+        cron.set_cache(cache.clone());
         cron.set_pagefind_enabled(enabled_set.contains("trovato_search"));
+        cron.set_jitter_secs(config.cron_jitter_secs);
         let cron = Arc::new(cron);
 
         // Spawn background cache reload tasks for collection caches.
@@ -748,8 +992,11 @@ impl AppState {
         Ok(Self {
             inner: Arc::new(AppStateInner {
                 db,
+                db_pools,
                 db_pool_max_connections: config.database_max_connections,
                 plugins_dir: config.plugins_dir.clone(),
+                backup_dir: config.backup_dir.clone(),
+                database_url: config.database_url.clone(),
                 enabled_plugins: parking_lot::RwLock::new(enabled_set.clone()),
                 redis,
                 cache,
@@ -780,15 +1027,28 @@ impl AppState {
                 language_negotiators,
                 known_languages,
                 default_language,
+                site_url: config.site_url.trim_end_matches('/').to_string(),
+                trusted_proxies: config.trusted_proxies.clone(),
+                page_cache_ttl: Some(cache_config.ttl_pages.as_secs()).filter(|&s| s > 0),
                 users,
                 roles,
+                oidc,
                 tiles,
                 email,
+                mail,
+                password_policy,
+                deprecations,
+                read_only,
+                sites,
                 audit,
                 content_lock,
+                flags,
                 image_styles,
                 oauth,
                 locale,
+                read_log,
+                saved_searches,
+                scheduled_updates,
                 redirect_cache: if enabled_set.contains("trovato_redirects") {
                     Some(Arc::new(services::redirect::RedirectCache::new()))
                 } else {
@@ -815,6 +1075,11 @@ impl AppState {
         &self.inner.db
     }
 
+    /// Get the primary pool plus read replicas.
+    pub fn db_pools(&self) -> &db::DbPools {
+        &self.inner.db_pools
+    }
+
     /// Get the configured maximum database pool connections.
     pub fn db_pool_max_connections(&self) -> u32 {
         self.inner.db_pool_max_connections
@@ -825,9 +1090,22 @@ impl AppState {
         &self.inner.plugins_dir
     }
 
+    /// Get the backup archive directory.
+    pub fn backup_dir(&self) -> &std::path::Path {
+        &self.inner.backup_dir
+    }
+
+    /// Get the primary database URL.
+    pub fn database_url(&self) -> &str {
+        &self.inner.database_url
+    }
+
     /// Check if a plugin is enabled at runtime.
+    ///
+    /// Inside a multisite request this also honours the site's plugin
+    /// switches (see [`services::site`]).
     pub fn is_plugin_enabled(&self, plugin: &str) -> bool {
-        self.inner.enabled_plugins.read().contains(plugin)
+        self.inner.enabled_plugins.read().contains(plugin) && services::site::plugin_enabled(plugin)
     }
 
     /// Get a snapshot of the enabled plugin names.
@@ -852,8 +1130,8 @@ impl AppState {
         }
     }
 
-    /// Get the Redis client.
-    pub fn redis(&self) -> &RedisClient {
+    /// Get the shared Redis connection manager.
+    pub fn redis(&self) -> &RedisManager {
         &self.inner.redis
     }
 
@@ -946,7 +1224,10 @@ impl AppState {
             self.inner.config_storage.clone()
         } else {
             // Non-live stages use stage-aware storage
-            let direct = Arc::new(DirectConfigStorage::new(self.inner.db.clone()));
+            let direct = Arc::new(
+                DirectConfigStorage::new(self.inner.db.clone())
+                    .with_validation(self.inner.tap_dispatcher.clone()),
+            );
             Arc::new(StageAwareConfigStorage::new(
                 direct,
                 self.inner.db.clone(),
@@ -955,6 +1236,26 @@ impl AppState {
         }
     }
 
+    /// Get config storage that reads config translated into a language.
+    ///
+    /// Item type labels, category names and string variables come back with
+    /// the `trovato_config_translation` overlays for `language` applied. The
+    /// default language, or a disabled plugin, gets the plain storage. Pass
+    /// the negotiated [`ResolvedLanguage`](crate::middleware::language::ResolvedLanguage).
+    pub fn config_storage_for_language(&self, language: &str) -> Arc<dyn ConfigStorage> {
+        if language == self.default_language()
+            || !self.is_plugin_enabled("trovato_config_translation")
+        {
+            self.inner.config_storage.clone()
+        } else {
+            Arc::new(TranslatedConfigStorage::new(
+                self.inner.config_storage.clone(),
+                self.inner.db.clone(),
+                language,
+            ))
+        }
+    }
+
     /// Get the search service.
     pub fn search(&self) -> &Arc<SearchService> {
         &self.inner.search
@@ -1020,6 +1321,25 @@ impl AppState {
         &self.inner.default_language
     }
 
+    /// Get the public base URL of the site, without trailing slash.
+    ///
+    /// Used where absolute URLs are required (feeds, emails).
+    pub fn site_url(&self) -> &str {
+        &self.inner.site_url
+    }
+
+    /// Get the reverse proxies whose forwarding headers are trusted.
+    ///
+    /// See [`crate::middleware::resolve_client_ip`].
+    pub fn trusted_proxies(&self) -> &[crate::lockout::IpNetwork] {
+        &self.inner.trusted_proxies
+    }
+
+    /// Get the anonymous page cache lifetime in seconds, if enabled.
+    pub fn page_cache_ttl(&self) -> Option<u64> {
+        self.inner.page_cache_ttl
+    }
+
     /// Get the user service.
     pub fn users(&self) -> &Arc<services::user::UserService> {
         &self.inner.users
@@ -1030,6 +1350,11 @@ impl AppState {
         &self.inner.roles
     }
 
+    /// Get the OIDC login service.
+    pub fn oidc(&self) -> &Arc<services::oidc::OidcService> {
+        &self.inner.oidc
+    }
+
     /// Get the tile service.
     pub fn tiles(&self) -> &Arc<services::tile::TileService> {
         &self.inner.tiles
@@ -1040,6 +1365,53 @@ impl AppState {
         self.inner.email.as_ref()
     }
 
+    /// Get the mail service.
+    pub fn mail(&self) -> &Arc<services::mail::MailService> {
+        &self.inner.mail
+    }
+
+    /// Get the item flag service (if flags plugin is enabled).
+    pub fn flags(&self) -> Option<&Arc<services::flag::FlagService>> {
+        self.inner.flags.as_ref()
+    }
+
+    /// Get the read log service (if read_log plugin is enabled).
+    pub fn read_log(&self) -> Option<&Arc<services::read_log::ReadLogService>> {
+        self.inner.read_log.as_ref()
+    }
+
+    /// Get the saved search service (if saved searches plugin is enabled).
+    pub fn saved_searches(&self) -> Option<&Arc<services::saved_search::SavedSearchService>> {
+        self.inner.saved_searches.as_ref()
+    }
+
+    /// Get the scheduled update service (if scheduled updates plugin is enabled).
+    pub fn scheduled_updates(
+        &self,
+    ) -> Option<&Arc<services::scheduled_update::ScheduledUpdateService>> {
+        self.inner.scheduled_updates.as_ref()
+    }
+
+    /// Get the password policy service.
+    pub fn password_policy(&self) -> &Arc<services::password_policy::PasswordPolicyService> {
+        &self.inner.password_policy
+    }
+
+    /// Get the deprecated API usage service.
+    pub fn deprecations(&self) -> &Arc<services::deprecation::DeprecationService> {
+        &self.inner.deprecations
+    }
+
+    /// Get the read-only mode service.
+    pub fn read_only(&self) -> &Arc<services::read_only::ReadOnlyService> {
+        &self.inner.read_only
+    }
+
+    /// Get the multisite resolution service.
+    pub fn sites(&self) -> &Arc<services::site::SiteService> {
+        &self.inner.sites
+    }
+
     /// Get the audit service (if audit_log plugin is enabled).
     pub fn audit(&self) -> Option<&Arc<services::audit::AuditService>> {
         self.inner.audit.as_ref()
@@ -1108,14 +1480,7 @@ impl AppState {
 
     /// Check if Redis is healthy.
     pub async fn redis_healthy(&self) -> bool {
-        let Ok(mut conn) = self.inner.redis.get_multiplexed_async_connection().await else {
-            return false;
-        };
-
-        redis::cmd("PING")
-            .query_async::<String>(&mut conn)
-            .await
-            .is_ok()
+        self.inner.redis.ping().await
     }
 
     /// Build a structured health report for load balancers and monitoring.
@@ -1128,10 +1493,23 @@ impl AppState {
             let active = size.saturating_sub(idle);
             let max = self.inner.db_pool_max_connections;
             let pct = if max > 0 { (active * 100) / max } else { 0 };
+            let unhealthy_replicas: Vec<String> = self
+                .inner
+                .db_pools
+                .status(true)
+                .into_iter()
+                .filter(|p| !p.healthy)
+                .map(|p| p.name)
+                .collect();
             if pct >= 80 {
                 ServiceHealth::Degraded(format!(
                     "pool utilization at {pct}% ({active}/{max} connections active)"
                 ))
+            } else if !unhealthy_replicas.is_empty() {
+                ServiceHealth::Degraded(format!(
+                    "read replicas unavailable: {}",
+                    unhealthy_replicas.join(", ")
+                ))
             } else {
                 ServiceHealth::Healthy
             }
@@ -1159,12 +1537,22 @@ impl AppState {
                 "content_lock".to_string(),
                 opt_health(&self.inner.content_lock),
             ),
+            ("flags".to_string(), opt_health(&self.inner.flags)),
             (
                 "image_styles".to_string(),
                 opt_health(&self.inner.image_styles),
             ),
             ("oauth".to_string(), opt_health(&self.inner.oauth)),
             ("locale".to_string(), opt_health(&self.inner.locale)),
+            ("read_log".to_string(), opt_health(&self.inner.read_log)),
+            (
+                "saved_searches".to_string(),
+                opt_health(&self.inner.saved_searches),
+            ),
+            (
+                "scheduled_updates".to_string(),
+                opt_health(&self.inner.scheduled_updates),
+            ),
             (
                 "redirects".to_string(),
                 opt_health(&self.inner.redirect_cache),
@@ -1201,6 +1589,48 @@ impl AppState {
             circuit_breakers,
         }
     }
+
+    /// Run the kernel's requirement checks and `tap_requirements`.
+    ///
+    /// Plugin checks run as `user`, so they can query on the user's behalf.
+    pub async fn status_report(&self, user: UserContext) -> StatusReport {
+        use services::status_report as report;
+
+        let (postgres, redis) = tokio::join!(self.postgres_healthy(), self.redis_healthy());
+        let migrations = db::pending_migrations(&self.inner.db).await;
+        let cron = self.inner.cron.last_run().await;
+
+        let storage = self.inner.files.storage();
+        let probe = format!("{}://.status-report-probe", storage.scheme());
+        let writable = match storage.write(&probe, b"ok").await {
+            Ok(()) => storage.delete(&probe).await,
+            Err(e) => Err(e),
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let mut checks = report::kernel_checks(vec![
+            report::database_check(postgres),
+            report::migrations_check(migrations),
+            report::redis_check(redis),
+            report::cron_check(cron, now),
+            report::file_storage_check(storage.scheme(), writable),
+        ]);
+
+        let state = RequestState::new(user, self.inner.tap_services.clone());
+        let results = self
+            .inner
+            .tap_dispatcher
+            .dispatch("tap_requirements", "{}", state)
+            .await;
+        checks.extend(report::from_tap_results(
+            results
+                .into_iter()
+                .map(|r| (r.plugin_name, r.output))
+                .collect(),
+        ));
+
+        StatusReport::new(checks, now)
+    }
 }
 
 /// Map an optional service to health status.
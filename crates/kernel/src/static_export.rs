@@ -0,0 +1,361 @@
+//! Static HTML export of the published live stage.
+//!
+//! `trovato export static <dir>` lets tiny sites be hosted as plain files
+//! while Trovato stays the editing backend. Every public page is rendered
+//! through the regular router as an anonymous visitor, so the output uses
+//! the same theme, taps, aliases, and access checks as a live request:
+//!
+//! - the front page, published live items (`/item/{id}` and their aliases),
+//!   author pages and feeds, and gather routes without path parameters
+//! - `sitemap.xml` (plus its sub-sitemaps) and `robots.txt`
+//! - permanent uploaded files and the `static/` directory, including a
+//!   freshly built Pagefind index when the Pagefind CLI is available
+//!
+//! HTML pages are written as `{path}/index.html` so they resolve on any
+//! static host; other responses keep their path. Absolute links use
+//! `SITE_URL`, which should be set to the static site's address.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::http::{Request, StatusCode, header};
+use tower::ServiceExt;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::file::FileStatus;
+use crate::models::stage::LIVE_STAGE_ID;
+use crate::state::AppState;
+
+/// Largest response body written to disk (uploaded files included).
+const MAX_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+/// Files fetched per page when exporting uploads.
+const FILE_BATCH_SIZE: i64 = 500;
+
+/// Outcome of a static export.
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    /// Pages and feeds written.
+    pub pages: usize,
+    /// Uploaded and static files written or copied.
+    pub files: usize,
+    /// Paths that did not render, with their status code.
+    pub skipped: Vec<(String, u16)>,
+}
+
+/// A rendered response.
+struct Rendered {
+    content_type: String,
+    body: Bytes,
+}
+
+/// Export the published live stage to `out_dir`.
+///
+/// `app` must be the site router with session and language negotiation
+/// layers; requests are made without a session cookie.
+pub async fn export_static(state: &AppState, app: Router, out_dir: &Path) -> Result<ExportSummary> {
+    tokio::fs::create_dir_all(out_dir)
+        .await
+        .with_context(|| format!("failed to create {}", out_dir.display()))?;
+
+    let mut summary = ExportSummary::default();
+
+    let mut paths = collect_paths(state).await?;
+    if let Ok(sitemap) = fetch(&app, "/sitemap.xml").await? {
+        paths.extend(sitemap_pages(&String::from_utf8_lossy(&sitemap.body)));
+    }
+
+    info!(pages = paths.len(), dir = %out_dir.display(), "exporting static pages");
+    for path in &paths {
+        match fetch(&app, path).await? {
+            Ok(rendered) => {
+                let Some(file) = output_file(out_dir, path, &rendered.content_type) else {
+                    warn!(path = %path, "skipping path that cannot be written as a file");
+                    continue;
+                };
+                write_file(&file, &rendered.body).await?;
+                summary.pages += 1;
+            }
+            Err(status) => {
+                debug!(path = %path, status = %status, "skipping page");
+                summary.skipped.push((path.clone(), status.as_u16()));
+            }
+        }
+    }
+
+    summary.files += export_uploads(state, &app, out_dir, &mut summary.skipped).await?;
+
+    // Rebuild the Pagefind index so the copy below ships it in sync with
+    // the exported pages. Sites without the CLI keep their last index.
+    if let Err(e) = crate::cron::build_pagefind_index(state.db()).await {
+        warn!(error = %e, "pagefind index not rebuilt; exporting the existing index");
+    }
+    let static_dir = std::env::var("STATIC_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("./static"));
+    if tokio::fs::try_exists(&static_dir).await.unwrap_or(false) {
+        summary.files += copy_dir(&static_dir, &out_dir.join("static")).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Public paths to render: front page, published items and their aliases,
+/// authors, and parameterless gather routes.
+async fn collect_paths(state: &AppState) -> Result<BTreeSet<String>> {
+    let mut paths: BTreeSet<String> = ["/", "/robots.txt"].map(String::from).into();
+
+    let item_ids: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM item WHERE status = 1 AND stage_id = $1")
+            .bind(LIVE_STAGE_ID)
+            .fetch_all(state.db())
+            .await
+            .context("failed to list published items")?;
+    let sources: Vec<String> = item_ids.iter().map(|id| format!("/item/{id}")).collect();
+
+    let aliases: Vec<String> =
+        sqlx::query_scalar("SELECT alias FROM url_alias WHERE stage_id = $1 AND source = ANY($2)")
+            .bind(LIVE_STAGE_ID)
+            .bind(&sources)
+            .fetch_all(state.db())
+            .await
+            .context("failed to list item aliases")?;
+
+    let authors: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT u.name
+        FROM users u
+        JOIN item i ON i.author_id = u.id
+        WHERE i.status = 1 AND i.stage_id = $1
+        "#,
+    )
+    .bind(LIVE_STAGE_ID)
+    .fetch_all(state.db())
+    .await
+    .context("failed to list authors")?;
+
+    paths.extend(sources);
+    paths.extend(aliases);
+    for name in authors {
+        let name = urlencoding::encode(&name);
+        paths.insert(format!("/author/{name}"));
+        paths.insert(format!("/author/{name}/feed"));
+    }
+    for query in state.gather().list_queries() {
+        paths.extend(
+            query
+                .display
+                .routes
+                .iter()
+                .filter(|route| route.path.starts_with('/') && !route.path.contains('{'))
+                .map(|route| route.path.clone()),
+        );
+    }
+
+    Ok(paths)
+}
+
+/// Fetch permanent uploads through the file route.
+async fn export_uploads(
+    state: &AppState,
+    app: &Router,
+    out_dir: &Path,
+    skipped: &mut Vec<(String, u16)>,
+) -> Result<usize> {
+    let mut written = 0;
+    let mut offset = 0;
+    loop {
+        let files = state
+            .files()
+            .list_by_status(Some(FileStatus::Permanent), FILE_BATCH_SIZE, offset)
+            .await?;
+        for file in &files {
+            let Some(relative) = file.uri.strip_prefix("local://") else {
+                continue;
+            };
+            let path = format!("/files/{relative}");
+            match fetch(app, &path).await? {
+                Ok(rendered) => {
+                    if let Some(target) = output_file(out_dir, &path, &rendered.content_type) {
+                        write_file(&target, &rendered.body).await?;
+                        written += 1;
+                    }
+                }
+                Err(status) => skipped.push((path, status.as_u16())),
+            }
+        }
+        if (files.len() as i64) < FILE_BATCH_SIZE {
+            break;
+        }
+        offset += FILE_BATCH_SIZE;
+    }
+    Ok(written)
+}
+
+/// Render `path` as an anonymous GET request.
+///
+/// Returns the status code for anything other than 200 OK.
+async fn fetch(app: &Router, path: &str) -> Result<std::result::Result<Rendered, StatusCode>> {
+    let Ok(request) = Request::get(path).body(Body::empty()) else {
+        return Ok(Err(StatusCode::BAD_REQUEST));
+    };
+    let response = app
+        .clone()
+        .oneshot(request)
+        .await
+        .context("router failed")?;
+    if response.status() != StatusCode::OK {
+        return Ok(Err(response.status()));
+    }
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), MAX_BODY_BYTES)
+        .await
+        .with_context(|| format!("failed to read response for {path}"))?;
+    Ok(Ok(Rendered { content_type, body }))
+}
+
+/// File for a URL path: `{path}/index.html` for HTML, the path itself
+/// otherwise.
+///
+/// Returns `None` for paths that would escape `out_dir` or can't be
+/// represented as a file (a non-HTML response at a directory path).
+fn output_file(out_dir: &Path, url_path: &str, content_type: &str) -> Option<PathBuf> {
+    let trimmed = url_path.trim_matches('/');
+    let mut file = out_dir.to_path_buf();
+    for segment in trimmed.split('/').filter(|s| !s.is_empty()) {
+        let decoded = urlencoding::decode(segment).ok()?;
+        if decoded.contains(['\\', '\0', '/']) {
+            return None;
+        }
+        match Path::new(&*decoded).components().next() {
+            Some(Component::Normal(_)) => file.push(&*decoded),
+            _ => return None,
+        }
+    }
+
+    let is_html = content_type.starts_with("text/html");
+    if is_html && !trimmed.ends_with(".html") {
+        file.push("index.html");
+    } else if trimmed.is_empty() || url_path.ends_with('/') {
+        return None;
+    }
+    Some(file)
+}
+
+/// Extract sub-sitemap paths (`/sitemap/...`) from a sitemap index.
+fn sitemap_pages(xml: &str) -> Vec<String> {
+    xml.split("<loc>")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("</loc>").map(|(loc, _)| loc.trim()))
+        .filter_map(|loc| loc.find("/sitemap/").map(|i| loc[i..].to_string()))
+        .collect()
+}
+
+async fn write_file(path: &Path, body: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    tokio::fs::write(path, body)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Recursively copy a directory, skipping in-progress Pagefind builds.
+async fn copy_dir(from: &Path, to: &Path) -> Result<usize> {
+    let mut copied = 0;
+    let mut pending = vec![(from.to_path_buf(), to.to_path_buf())];
+    while let Some((src, dst)) = pending.pop() {
+        tokio::fs::create_dir_all(&dst)
+            .await
+            .with_context(|| format!("failed to create {}", dst.display()))?;
+        let mut entries = tokio::fs::read_dir(&src)
+            .await
+            .with_context(|| format!("failed to read {}", src.display()))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            if name.to_string_lossy().starts_with(".pagefind_build_") {
+                continue;
+            }
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                pending.push((entry.path(), dst.join(&name)));
+            } else if file_type.is_file() {
+                tokio::fs::copy(entry.path(), dst.join(&name))
+                    .await
+                    .with_context(|| format!("failed to copy {}", entry.path().display()))?;
+                copied += 1;
+            }
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_pages_become_index_files() {
+        let out = Path::new("/out");
+        assert_eq!(
+            output_file(out, "/", "text/html; charset=utf-8"),
+            Some(PathBuf::from("/out/index.html"))
+        );
+        assert_eq!(
+            output_file(out, "/about-us", "text/html"),
+            Some(PathBuf::from("/out/about-us/index.html"))
+        );
+        assert_eq!(
+            output_file(out, "/author/jo%20ann/", "text/html"),
+            Some(PathBuf::from("/out/author/jo ann/index.html"))
+        );
+    }
+
+    #[test]
+    fn other_responses_keep_their_path() {
+        let out = Path::new("/out");
+        assert_eq!(
+            output_file(out, "/sitemap.xml", "application/xml"),
+            Some(PathBuf::from("/out/sitemap.xml"))
+        );
+        assert_eq!(
+            output_file(out, "/author/jo/feed", "application/rss+xml"),
+            Some(PathBuf::from("/out/author/jo/feed"))
+        );
+        assert_eq!(output_file(out, "/", "application/json"), None);
+    }
+
+    #[test]
+    fn paths_cannot_escape_the_output_directory() {
+        let out = Path::new("/out");
+        assert_eq!(output_file(out, "/../etc/passwd", "text/plain"), None);
+        assert_eq!(output_file(out, "/files/%2e%2e/secret", "text/plain"), None);
+        assert_eq!(output_file(out, "/files/a%2Fb", "text/plain"), None);
+    }
+
+    #[test]
+    fn sitemap_index_lists_sub_sitemaps() {
+        let xml = r#"<sitemapindex>
+            <sitemap><loc>https://example.com/sitemap/items-1.xml</loc></sitemap>
+            <sitemap><loc> https://example.com/sitemap/items-2.xml </loc></sitemap>
+            <sitemap><loc>https://example.com/about</loc></sitemap>
+        </sitemapindex>"#;
+        assert_eq!(
+            sitemap_pages(xml),
+            vec!["/sitemap/items-1.xml", "/sitemap/items-2.xml"]
+        );
+    }
+}
@@ -2,15 +2,24 @@
 //!
 //! The dispatcher calls all plugins implementing a tap, collecting their results.
 //! Errors are logged and skipped, allowing other plugins to continue.
+//!
+//! It also fans out events plugins dispatch with `host::dispatch_event` to
+//! the other plugins' `tap_event`, refusing events that would loop.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use tracing::{debug, error, warn};
+use trovato_sdk::types::{EVENT_MAX_DEPTH, PluginEvent};
 use wasmtime::{Instance, Store, TypedFunc};
 
-use super::{RequestState, TapHandler, TapRegistry};
+use super::memo::{self, MemoKey};
+use super::{RequestState, TapHandler, TapRegistry, trace};
+use crate::host;
+use crate::metrics::Metrics;
 use crate::plugin::{PluginRuntime, PluginState, WasmtimeExt};
+use crate::profiling::{self, Phase};
 
 /// Background tap names that may make many network or DB calls.
 ///
@@ -21,6 +30,7 @@ const BACKGROUND_TAPS: &[&str] = &[
     "tap_cron",
     "tap_queue_worker",
     "tap_queue_info",
+    "tap_batch_process",
 ];
 
 /// Result from a single tap invocation.
@@ -33,15 +43,30 @@ pub struct TapResult {
 }
 
 /// Dispatcher for invoking taps across plugins.
+///
+/// Cheap to clone; each tap call's store holds a clone so the plugin can
+/// dispatch events.
+#[derive(Clone)]
 pub struct TapDispatcher {
     runtime: Arc<PluginRuntime>,
     registry: Arc<TapRegistry>,
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl TapDispatcher {
     /// Create a new tap dispatcher.
     pub fn new(runtime: Arc<PluginRuntime>, registry: Arc<TapRegistry>) -> Self {
-        Self { runtime, registry }
+        Self {
+            runtime,
+            registry,
+            metrics: None,
+        }
+    }
+
+    /// Record plugin event metrics.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
     }
 
     /// Get the tap registry for handler introspection.
@@ -49,6 +74,24 @@ impl TapDispatcher {
         &self.registry
     }
 
+    /// Handlers for a tap, without plugins switched off for the current site.
+    fn handlers(&self, tap_name: &str) -> Vec<&TapHandler> {
+        self.registry
+            .get_handlers(tap_name)
+            .iter()
+            .filter(|h| crate::services::site::plugin_enabled(&h.plugin.info.name))
+            .collect()
+    }
+
+    /// Plugins implementing a tap and enabled for the current site, in
+    /// weight order.
+    pub fn plugin_names(&self, tap_name: &str) -> Vec<String> {
+        self.handlers(tap_name)
+            .into_iter()
+            .map(|h| h.plugin.info.name.clone())
+            .collect()
+    }
+
     /// Dispatch a tap to all implementing plugins.
     ///
     /// Calls each plugin's tap function in weight order, collecting results.
@@ -67,7 +110,7 @@ impl TapDispatcher {
         input_json: &str,
         state: RequestState,
     ) -> Vec<TapResult> {
-        let handlers = self.registry.get_handlers(tap_name);
+        let handlers = self.handlers(tap_name);
         if handlers.is_empty() {
             debug!(tap = %tap_name, "no handlers registered for tap");
             return Vec::new();
@@ -75,7 +118,7 @@ impl TapDispatcher {
 
         let mut results = Vec::with_capacity(handlers.len());
 
-        for handler in handlers {
+        for &handler in &handlers {
             match self
                 .invoke_handler(tap_name, input_json, handler, state.clone())
                 .await
@@ -107,6 +150,61 @@ impl TapDispatcher {
         results
     }
 
+    /// Dispatch an alter tap, chaining the value through plugins in weight order.
+    ///
+    /// Each plugin receives the value as altered by the previous one. Empty
+    /// or `{}` output leaves the value unchanged, as does output that fails
+    /// to deserialize (logged) or a failed invocation.
+    pub async fn dispatch_alter<T>(&self, tap_name: &str, value: T, state: RequestState) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let handlers = self.handlers(tap_name);
+        let mut value = value;
+
+        for handler in handlers {
+            let input_json = match serde_json::to_string(&value) {
+                Ok(json) => json,
+                Err(e) => {
+                    error!(tap = %tap_name, error = %e, "failed to serialize alter input");
+                    return value;
+                }
+            };
+
+            let output = match self
+                .invoke_handler(tap_name, &input_json, handler, state.clone())
+                .await
+            {
+                Ok(output) => output,
+                Err(e) => {
+                    error!(
+                        plugin = %handler.plugin.info.name,
+                        tap = %tap_name,
+                        error = %e,
+                        "tap invocation failed"
+                    );
+                    continue;
+                }
+            };
+
+            if output.is_empty() || output == "{}" {
+                continue;
+            }
+
+            match serde_json::from_str::<T>(&output) {
+                Ok(altered) => value = altered,
+                Err(e) => warn!(
+                    plugin = %handler.plugin.info.name,
+                    tap = %tap_name,
+                    error = %e,
+                    "failed to parse alter output"
+                ),
+            }
+        }
+
+        value
+    }
+
     /// Dispatch a tap and expect exactly one result.
     ///
     /// Useful for taps where only one plugin should respond.
@@ -139,9 +237,9 @@ impl TapDispatcher {
         plugin_name: &str,
         state: RequestState,
     ) -> Option<TapResult> {
-        let handlers = self.registry.get_handlers(tap_name);
-        let handler = handlers
-            .iter()
+        let handler = self
+            .handlers(tap_name)
+            .into_iter()
             .find(|h| h.plugin.info.name == plugin_name)?;
 
         match self
@@ -164,19 +262,144 @@ impl TapDispatcher {
         }
     }
 
-    /// Invoke a single handler.
+    /// Deliver a plugin event to every other plugin implementing `tap_event`.
+    ///
+    /// `state` is the dispatching tap's request state; the event is added
+    /// to its event chain for the handlers, so an event already being
+    /// handled further up the chain, or one nested [`EVENT_MAX_DEPTH`]
+    /// deep, is refused with `None`. Otherwise returns the number of
+    /// plugins that handled the event without error.
+    pub async fn dispatch_event(
+        &self,
+        source: &str,
+        name: &str,
+        payload: serde_json::Value,
+        state: RequestState,
+    ) -> Option<usize> {
+        if !event_allowed(&state.events, name) {
+            warn!(
+                plugin = %source,
+                event = %name,
+                chain = ?state.events,
+                "refusing plugin event that would loop"
+            );
+            self.record_event(name, "refused");
+            return None;
+        }
+        self.record_event(name, "dispatched");
+
+        let event = PluginEvent {
+            name: name.to_string(),
+            source: source.to_string(),
+            payload,
+        };
+        let input_json = match serde_json::to_string(&event) {
+            Ok(json) => json,
+            Err(e) => {
+                error!(event = %name, error = %e, "failed to serialize plugin event");
+                return Some(0);
+            }
+        };
+
+        let mut state = state;
+        state.events.push(name.to_string());
+
+        let mut delivered = 0;
+        for handler in self
+            .handlers("tap_event")
+            .into_iter()
+            .filter(|h| h.plugin.info.name != source)
+        {
+            match self
+                .invoke_handler("tap_event", &input_json, handler, state.clone())
+                .await
+            {
+                Ok(_) => {
+                    delivered += 1;
+                    self.record_event(name, "delivered");
+                }
+                Err(e) => {
+                    error!(
+                        plugin = %handler.plugin.info.name,
+                        event = %name,
+                        error = %e,
+                        "plugin event handler failed"
+                    );
+                    self.record_event(name, "failed");
+                }
+            }
+        }
+
+        debug!(plugin = %source, event = %name, delivered, "plugin event dispatched");
+        Some(delivered)
+    }
+
+    fn record_event(&self, name: &str, outcome: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_plugin_event(name, outcome);
+        }
+    }
+
+    /// Invoke a single handler, recording it in the request's tap trace.
     async fn invoke_handler(
         &self,
         tap_name: &str,
         input_json: &str,
         handler: &TapHandler,
         state: RequestState,
+    ) -> Result<String> {
+        let _tap_timer = profiling::Timer::start(Phase::Tap);
+        let memo_key = memo_key(tap_name, input_json, handler, &state);
+        if let Some(ref key) = memo_key
+            && let Some(output) = memo::get(key)
+        {
+            trace::record_memoized(
+                tap_name,
+                &handler.plugin.info.name,
+                input_json.len(),
+                &output,
+            );
+            return Ok(output);
+        }
+
+        let result = if trace::is_active() {
+            let start = Instant::now();
+            let result = self
+                .call_handler(tap_name, input_json, handler, state)
+                .await;
+            trace::record(
+                tap_name,
+                &handler.plugin.info.name,
+                input_json.len(),
+                start.elapsed(),
+                &result,
+            );
+            result
+        } else {
+            self.call_handler(tap_name, input_json, handler, state)
+                .await
+        };
+
+        if let (Some(key), Ok(output)) = (memo_key, &result) {
+            memo::insert(key, output);
+        }
+        result
+    }
+
+    /// Instantiate the plugin and call its tap export.
+    async fn call_handler(
+        &self,
+        tap_name: &str,
+        input_json: &str,
+        handler: &TapHandler,
+        state: RequestState,
     ) -> Result<String> {
         let plugin = &handler.plugin;
         let engine = self.runtime.engine();
 
         // Create combined plugin state with WASI and request state
-        let plugin_state = PluginState::new(state, plugin.info.name.clone());
+        let plugin_state =
+            PluginState::new(state, plugin.info.name.clone()).with_dispatcher(self.clone());
 
         // Create a new Store with plugin state
         let mut store = Store::new(engine, plugin_state);
@@ -192,11 +415,11 @@ impl TapDispatcher {
             10
         });
 
-        // Instantiate the module
+        // Instantiate the module, linked on the plugin's first call
         let instance = self
             .runtime
-            .linker()
-            .instantiate_async(&mut store, &plugin.module)
+            .instance_pre(plugin)?
+            .instantiate_async(&mut store)
             .await
             .into_anyhow()
             .with_context(|| format!("failed to instantiate plugin '{}'", plugin.info.name))?;
@@ -205,12 +428,41 @@ impl TapDispatcher {
         let func = get_tap_function(&instance, &mut store, tap_name)?;
 
         // Allocate input in WASM memory and call the function
-        let output = call_tap_function(&instance, &mut store, func, input_json).await?;
+        let output = call_tap_function(&instance, &mut store, func, input_json).await;
+
+        // Discard writes of a transaction the tap did not commit, whether
+        // it trapped, returned an error or returned normally
+        host::rollback_open_transaction(store.data_mut(), tap_name).await;
+        let output = output?;
 
-        Ok(output)
+        // Substitute output built through the render host functions
+        Ok(store.data_mut().render.resolve(output))
     }
 }
 
+/// Memo key for an invocation whose result may be reused in this request.
+///
+/// `None` outside a memo scope, for taps not in [`memo::MEMOIZED_TAPS`],
+/// and for taps the plugin opted out of memoization.
+fn memo_key(
+    tap_name: &str,
+    input_json: &str,
+    handler: &TapHandler,
+    state: &RequestState,
+) -> Option<MemoKey> {
+    let info = &handler.plugin.info;
+    (memo::is_active() && memo::MEMOIZED_TAPS.contains(&tap_name) && info.taps.memoize(tap_name))
+        .then(|| MemoKey::new(&info.name, tap_name, state.user.id, input_json))
+}
+
+/// Whether an event may be dispatched from within the event chain `chain`.
+///
+/// Refuses an event that is already being handled, which would otherwise
+/// bounce between plugins forever, and chains [`EVENT_MAX_DEPTH`] deep.
+fn event_allowed(chain: &[String], name: &str) -> bool {
+    chain.len() < EVENT_MAX_DEPTH && !chain.iter().any(|e| e == name)
+}
+
 /// Get a tap function from a WASM instance.
 fn get_tap_function(
     instance: &Instance,
@@ -240,9 +492,9 @@ async fn call_tap_function(
         .get_memory(&mut *store, "memory")
         .context("plugin missing memory export")?;
 
-    // Simple memory protocol: write input at offset 0, output at offset 65536
+    // Simple memory protocol: write input at offset 0; the export returns
+    // where it left its output
     let input_offset = 0i32;
-    let _output_offset = 65536i32;
     let input_bytes = input_json.as_bytes();
 
     // Write input to memory
@@ -329,6 +581,42 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn event_chain_refuses_loops_and_deep_nesting() {
+        let chain = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert!(event_allowed(&[], "a"));
+        assert!(event_allowed(&chain(&["a", "b"]), "c"));
+        assert!(!event_allowed(&chain(&["a", "b"]), "a"));
+        assert!(!event_allowed(&chain(&["a", "b", "c", "d"]), "e"));
+    }
+
+    #[tokio::test]
+    async fn dispatch_event_without_handlers() {
+        let runtime = Arc::new(PluginRuntime::new(&PluginConfig::default()).unwrap());
+        let registry = Arc::new(TapRegistry::from_plugins(&runtime));
+        let metrics = Arc::new(Metrics::new());
+        let dispatcher = TapDispatcher::new(runtime, registry).with_metrics(metrics.clone());
+
+        let delivered = dispatcher
+            .dispatch_event("argus", "a", serde_json::json!({}), RequestState::default())
+            .await;
+        assert_eq!(delivered, Some(0));
+
+        let mut state = RequestState::default();
+        state.events.push("a".to_string());
+        let refused = dispatcher
+            .dispatch_event("argus", "a", serde_json::json!({}), state)
+            .await;
+        assert_eq!(refused, None);
+
+        let output = metrics.encode();
+        assert!(
+            output.contains(r#"trovato_plugin_events_total{event="a",outcome="dispatched"} 1"#)
+        );
+        assert!(output.contains(r#"trovato_plugin_events_total{event="a",outcome="refused"} 1"#));
+    }
+
     #[test]
     fn registry_accessor_returns_same_registry() {
         let runtime = Arc::new(PluginRuntime::new(&PluginConfig::default()).unwrap());
@@ -0,0 +1,165 @@
+//! Request-scoped tap result memoization.
+//!
+//! A page often invokes the same tap with the same input several times:
+//! `tap_item_access` for an item listed in two blocks, `tap_menu` for each
+//! menu rendered. Every HTTP request runs inside a memo scope (see
+//! `middleware::tap_memo`), and within it the dispatcher keeps each
+//! successful result of a [`MEMOIZED_TAPS`] tap, keyed by plugin, tap, user
+//! and input, and reuses it instead of invoking the plugin again.
+//!
+//! Plugins whose output varies between identical calls opt a tap out in
+//! their `.info.toml`:
+//!
+//! ```toml
+//! [taps.options.tap_block_render]
+//! memoize = false
+//! ```
+//!
+//! Outside a scope (cron, batch, queue workers) nothing is stored.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Taps whose results may be reused within a request.
+///
+/// Only taps without side effects belong here: a memoized call does not
+/// reach the plugin at all.
+pub const MEMOIZED_TAPS: &[&str] = &[
+    "tap_block_info",
+    "tap_block_render",
+    "tap_comment_access",
+    "tap_field_access",
+    "tap_item_access",
+    "tap_item_info",
+    "tap_item_view",
+    "tap_item_view_alter",
+    "tap_menu",
+    "tap_perm",
+    "tap_preprocess_item",
+    "tap_theme",
+];
+
+/// Most results kept per request; later results are not memoized.
+const MAX_ENTRIES: usize = 1024;
+
+/// Largest output memoized, in bytes.
+const MAX_OUTPUT_BYTES: usize = 256 * 1024;
+
+tokio::task_local! {
+    static TAP_MEMO: RefCell<HashMap<MemoKey, String>>;
+}
+
+/// Identifies one tap invocation.
+///
+/// The input is kept as a SHA-256 digest so large inputs (rendered items)
+/// are not held twice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct MemoKey {
+    plugin: String,
+    tap: String,
+    user: Uuid,
+    input: [u8; 32],
+}
+
+impl MemoKey {
+    /// Key for `plugin` handling `tap` with `input` on behalf of `user`.
+    pub(super) fn new(plugin: &str, tap: &str, user: Uuid, input: &str) -> Self {
+        Self {
+            plugin: plugin.to_string(),
+            tap: tap.to_string(),
+            user,
+            input: Sha256::digest(input.as_bytes()).into(),
+        }
+    }
+}
+
+/// Whether tap results are being memoized for the current task.
+pub fn is_active() -> bool {
+    TAP_MEMO.try_with(|_| ()).is_ok()
+}
+
+/// The memoized output for `key`, if any.
+pub(super) fn get(key: &MemoKey) -> Option<String> {
+    TAP_MEMO
+        .try_with(|memo| memo.borrow().get(key).cloned())
+        .ok()
+        .flatten()
+}
+
+/// Memoize `output` for `key` in the current scope, if any.
+pub(super) fn insert(key: MemoKey, output: &str) {
+    if output.len() > MAX_OUTPUT_BYTES {
+        return;
+    }
+    // Err means no memo scope is active.
+    let _ = TAP_MEMO.try_with(|memo| {
+        let mut memo = memo.borrow_mut();
+        if memo.len() < MAX_ENTRIES {
+            memo.insert(key, output.to_string());
+        }
+    });
+}
+
+/// Run a future with its own tap memo, dropped when the future completes.
+pub async fn scope<F: Future>(fut: F) -> F::Output {
+    TAP_MEMO.scope(RefCell::new(HashMap::new()), fut).await
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn key(plugin: &str, input: &str) -> MemoKey {
+        MemoKey::new(plugin, "tap_item_access", Uuid::nil(), input)
+    }
+
+    #[tokio::test]
+    async fn results_are_reused_within_a_scope() {
+        scope(async {
+            assert!(is_active());
+            assert_eq!(get(&key("blog", "{}")), None);
+            insert(key("blog", "{}"), r#""Grant""#);
+            assert_eq!(get(&key("blog", "{}")).as_deref(), Some(r#""Grant""#));
+            // Other plugins and inputs are separate entries.
+            assert_eq!(get(&key("media", "{}")), None);
+            assert_eq!(get(&key("blog", r#"{"a":1}"#)), None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn users_do_not_share_results() {
+        scope(async {
+            insert(key("blog", "{}"), r#""Grant""#);
+            let other = MemoKey::new("blog", "tap_item_access", Uuid::now_v7(), "{}");
+            assert_eq!(get(&other), None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn nothing_is_kept_outside_a_scope() {
+        assert!(!is_active());
+        insert(key("blog", "{}"), r#""Grant""#);
+        assert_eq!(get(&key("blog", "{}")), None);
+
+        // Each scope starts empty.
+        scope(async { insert(key("blog", "{}"), r#""Grant""#) }).await;
+        scope(async { assert_eq!(get(&key("blog", "{}")), None) }).await;
+    }
+
+    #[tokio::test]
+    async fn large_outputs_are_not_kept() {
+        scope(async {
+            insert(key("blog", "{}"), &"x".repeat(MAX_OUTPUT_BYTES + 1));
+            assert_eq!(get(&key("blog", "{}")), None);
+        })
+        .await;
+    }
+}
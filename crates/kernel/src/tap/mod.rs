@@ -4,8 +4,10 @@
 //! all plugins that implement it are called in weight order (lower = higher priority).
 
 mod dispatcher;
+pub mod memo;
 mod registry;
 mod request_state;
+pub mod trace;
 
 pub use dispatcher::{TapDispatcher, TapResult};
 pub use registry::{TapHandler, TapRegistry};
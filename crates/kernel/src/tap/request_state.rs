@@ -13,6 +13,8 @@ use crate::cache::CacheLayer;
 use crate::lockout::LockoutService;
 use crate::services::ai_provider::AiProviderService;
 use crate::services::ai_token_budget::AiTokenBudgetService;
+use crate::services::saved_search::SavedSearchService;
+use crate::services::scheduled_update::ScheduledUpdateService;
 
 /// User context for the current request.
 #[derive(Debug, Clone)]
@@ -69,7 +71,7 @@ impl Default for UserContext {
 pub struct RequestServices {
     /// Database connection pool.
     pub db: PgPool,
-    /// Two-tier cache (Moka L1 + Redis L2). None in test/background contexts.
+    /// Two-tier cache (Moka L1 + Redis L2). None in test contexts.
     pub cache: Option<CacheLayer>,
     /// Lockout service for rate limiting (None in background/cron contexts).
     pub lockout: Option<Arc<LockoutService>>,
@@ -79,6 +81,15 @@ pub struct RequestServices {
     pub ai_budgets: Option<Arc<AiTokenBudgetService>>,
     /// Shared HTTP client for outbound requests from plugins.
     pub http: reqwest::Client,
+    /// Saved search service for alert checks from `tap_cron` (None outside
+    /// cron or when the saved searches plugin is disabled).
+    pub saved_searches: Option<Arc<SavedSearchService>>,
+    /// Scheduled update service for applying due updates from `tap_cron`
+    /// (None outside cron or when the scheduled updates plugin is disabled).
+    pub scheduled_updates: Option<Arc<ScheduledUpdateService>>,
+    /// Whether this is a background context (cron, batch) acting on behalf
+    /// of the system rather than a user. Grants unrestricted item queries.
+    pub background: bool,
 }
 
 impl RequestServices {
@@ -99,23 +110,32 @@ impl RequestServices {
             ai_providers,
             ai_budgets,
             http,
+            saved_searches: None,
+            scheduled_updates: None,
+            background: false,
         }
     }
 
-    /// Create services for background tasks (cron, batch) — no lockout or cache.
+    /// Create services for background tasks (cron, batch) — no lockout.
+    ///
+    /// The cache layer lets plugins keep values between cron runs.
     pub fn for_background(
         db: PgPool,
+        cache: Option<CacheLayer>,
         ai_providers: Option<Arc<AiProviderService>>,
         ai_budgets: Option<Arc<AiTokenBudgetService>>,
         http: reqwest::Client,
     ) -> Self {
         Self {
             db,
-            cache: None,
+            cache,
             lockout: None,
             ai_providers,
             ai_budgets,
             http,
+            saved_searches: None,
+            scheduled_updates: None,
+            background: true,
         }
     }
 }
@@ -135,6 +155,18 @@ impl std::fmt::Debug for RequestServices {
                 &self.ai_budgets.as_ref().map(|_| "AiTokenBudgetService"),
             )
             .field("http", &"reqwest::Client")
+            .field(
+                "saved_searches",
+                &self.saved_searches.as_ref().map(|_| "SavedSearchService"),
+            )
+            .field(
+                "scheduled_updates",
+                &self
+                    .scheduled_updates
+                    .as_ref()
+                    .map(|_| "ScheduledUpdateService"),
+            )
+            .field("background", &self.background)
             .finish()
     }
 }
@@ -159,6 +191,11 @@ pub struct RequestState {
     pub user: UserContext,
     /// Per-request key-value store for plugin communication.
     pub context: HashMap<String, String>,
+    /// Plugin events being handled, outermost first (see
+    /// [`TapDispatcher::dispatch_event`](super::TapDispatcher::dispatch_event)).
+    /// Taps dispatched on a plugin's behalf, such as `tap_ng_classify`,
+    /// are recorded here as well.
+    pub events: Vec<String>,
     /// Shared services.
     services: Option<RequestServices>,
 }
@@ -169,6 +206,7 @@ impl RequestState {
         Self {
             user,
             context: HashMap::new(),
+            events: Vec::new(),
             services: Some(services),
         }
     }
@@ -178,6 +216,7 @@ impl RequestState {
         Self {
             user,
             context: HashMap::new(),
+            events: Vec::new(),
             services: None,
         }
     }
@@ -0,0 +1,174 @@
+//! Per-request tap execution trace.
+//!
+//! When an admin requests a trace (see `middleware::tap_trace`), the
+//! request runs inside a trace scope and the dispatcher records every tap
+//! invocation: plugin, input size, duration, and a short summary of the
+//! result. Recording outside a scope is a no-op.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Longest result summary kept per invocation, in characters.
+const SUMMARY_MAX_CHARS: usize = 200;
+
+tokio::task_local! {
+    static TAP_TRACE: RefCell<Vec<TapTraceEntry>>;
+}
+
+/// One tap invocation on one plugin.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TapTraceEntry {
+    /// Tap name (e.g. "tap_item_view").
+    pub tap: String,
+    /// Plugin that handled the tap.
+    pub plugin: String,
+    /// Size of the JSON input in bytes.
+    pub input_bytes: usize,
+    /// Wall-clock time of the invocation in milliseconds.
+    pub duration_ms: f64,
+    /// Whether the invocation returned output.
+    pub ok: bool,
+    /// Whether the output was reused from an earlier identical invocation
+    /// in this request instead of calling the plugin.
+    pub memoized: bool,
+    /// Size of the output in bytes (0 on error).
+    pub output_bytes: usize,
+    /// Start of the output, or the error message.
+    pub summary: String,
+}
+
+/// Whether the current request is being traced.
+///
+/// Lets the dispatcher skip timing work for ordinary requests.
+pub fn is_active() -> bool {
+    TAP_TRACE.try_with(|_| ()).is_ok()
+}
+
+/// Record an invocation in the current trace scope, if any.
+pub fn record(
+    tap: &str,
+    plugin: &str,
+    input_bytes: usize,
+    duration: Duration,
+    result: &anyhow::Result<String>,
+) {
+    let (ok, output_bytes, summary) = match result {
+        Ok(output) => (true, output.len(), summarize(output)),
+        Err(e) => (false, 0, summarize(&format!("{e:#}"))),
+    };
+    let entry = TapTraceEntry {
+        tap: tap.to_string(),
+        plugin: plugin.to_string(),
+        input_bytes,
+        duration_ms: duration.as_secs_f64() * 1000.0,
+        ok,
+        memoized: false,
+        output_bytes,
+        summary,
+    };
+    // Err means no trace scope is active.
+    let _ = TAP_TRACE.try_with(|trace| trace.borrow_mut().push(entry));
+}
+
+/// Record an invocation answered from the request's tap memo.
+pub fn record_memoized(tap: &str, plugin: &str, input_bytes: usize, output: &str) {
+    let _ = TAP_TRACE.try_with(|trace| {
+        trace.borrow_mut().push(TapTraceEntry {
+            tap: tap.to_string(),
+            plugin: plugin.to_string(),
+            input_bytes,
+            duration_ms: 0.0,
+            ok: true,
+            memoized: true,
+            output_bytes: output.len(),
+            summary: summarize(output),
+        });
+    });
+}
+
+/// Run a future inside a trace scope, returning its output and the tap
+/// invocations recorded while it ran, in call order.
+pub async fn collect_trace<F: Future>(fut: F) -> (F::Output, Vec<TapTraceEntry>) {
+    TAP_TRACE
+        .scope(RefCell::new(Vec::new()), async {
+            let output = fut.await;
+            let trace = TAP_TRACE.with(|trace| trace.take());
+            (output, trace)
+        })
+        .await
+}
+
+/// Truncate to [`SUMMARY_MAX_CHARS`], marking the cut with an ellipsis.
+fn summarize(text: &str) -> String {
+    match text.char_indices().nth(SUMMARY_MAX_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_invocations_in_order() {
+        let ((), trace) = collect_trace(async {
+            assert!(is_active());
+            record(
+                "tap_item_view",
+                "blog",
+                12,
+                Duration::from_millis(2),
+                &Ok("{}".to_string()),
+            );
+            record(
+                "tap_item_view",
+                "media",
+                12,
+                Duration::from_millis(1),
+                &Err(anyhow::anyhow!("boom")),
+            );
+        })
+        .await;
+
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[0].plugin, "blog");
+        assert!(trace[0].ok);
+        assert_eq!(trace[0].output_bytes, 2);
+        assert!(!trace[1].ok);
+        assert_eq!(trace[1].summary, "boom");
+    }
+
+    #[tokio::test]
+    async fn memoized_invocations_are_marked() {
+        let ((), trace) = collect_trace(async {
+            record_memoized("tap_menu", "blog", 2, "[]");
+        })
+        .await;
+
+        assert_eq!(trace.len(), 1);
+        assert!(trace[0].ok && trace[0].memoized);
+        assert_eq!(trace[0].duration_ms, 0.0);
+        assert_eq!(trace[0].output_bytes, 2);
+    }
+
+    #[tokio::test]
+    async fn record_outside_scope_is_noop() {
+        assert!(!is_active());
+        record("tap_cron", "blog", 0, Duration::ZERO, &Ok(String::new()));
+        let ((), trace) = collect_trace(async {}).await;
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn long_output_is_truncated() {
+        let summary = summarize(&"é".repeat(SUMMARY_MAX_CHARS + 10));
+        assert_eq!(summary.chars().count(), SUMMARY_MAX_CHARS + 1);
+        assert!(summary.ends_with('…'));
+    }
+}
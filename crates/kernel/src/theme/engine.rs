@@ -10,6 +10,8 @@ use tracing::debug;
 
 use crate::content::FilterPipeline;
 use crate::form::Form;
+use crate::profiling::{self, Phase};
+use crate::services::image_style::ResponsivePicture;
 use crate::services::locale::LocaleService;
 
 use super::render::RenderTreeConsumer;
@@ -560,11 +562,13 @@ impl ThemeEngine {
         element: &RenderElement,
         context: &mut tera::Context,
     ) -> Result<String> {
+        let _render_timer = profiling::Timer::start(Phase::Render);
         self.render_consumer.render(&self.tera, element, context)
     }
 
     /// Render an item using template suggestions.
     pub fn render_item(&self, item: &Item, element: &RenderElement) -> Result<String> {
+        let _render_timer = profiling::Timer::start(Phase::Render);
         let suggestions = Self::item_suggestions(item);
         let suggestion_refs: Vec<&str> = suggestions.iter().map(|s| s.as_str()).collect();
 
@@ -626,6 +630,15 @@ impl ThemeEngine {
             .context("failed to render form template")
     }
 
+    /// Render a form's elements without the surrounding `<form>`.
+    ///
+    /// Used for elements plugins add to forms the kernel lays out with its
+    /// own templates.
+    pub fn render_form_elements_html(&self, form: &Form) -> Result<String> {
+        let mut context = tera::Context::new();
+        self.render_form_elements(form, &mut context)
+    }
+
     /// Render form elements to HTML.
     fn render_form_elements(&self, form: &Form, context: &mut tera::Context) -> Result<String> {
         let empty = std::collections::HashMap::new();
@@ -763,6 +776,7 @@ impl ThemeEngine {
         content: &str,
         context: &mut tera::Context,
     ) -> Result<String> {
+        let _render_timer = profiling::Timer::start(Phase::Render);
         let suggestions = Self::page_suggestions(path);
         let suggestion_refs: Vec<&str> = suggestions.iter().map(|s| s.as_str()).collect();
 
@@ -795,6 +809,32 @@ impl ThemeEngine {
         crate::content::page_builder::render_puck_page(&page, &self.tera)
     }
 
+    /// Render a responsive image as a `<picture>` element.
+    ///
+    /// Uses `elements/responsive-image--{style}` if the theme provides it,
+    /// else `elements/responsive-image`.
+    pub fn render_responsive_image(&self, picture: &ResponsivePicture) -> Result<String> {
+        let safe_style: String = picture
+            .style
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        let suggestions = [
+            format!("elements/responsive-image--{safe_style}"),
+            "elements/responsive-image".to_string(),
+        ];
+        let suggestion_refs: Vec<&str> = suggestions.iter().map(|s| s.as_str()).collect();
+        let template = self
+            .resolve_template(&suggestion_refs)
+            .unwrap_or_else(|| "elements/responsive-image.html".to_string());
+
+        let mut context = tera::Context::new();
+        context.insert("picture", picture);
+        self.tera
+            .render(&template, &context)
+            .context("failed to render responsive image template")
+    }
+
     /// Clear the suggestion cache (useful for development hot-reload).
     pub fn clear_cache(&self) {
         self.suggestion_cache.clear();
@@ -1083,6 +1123,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn responsive_image_renders_picture_template() {
+        use crate::services::image_style::PictureSource;
+
+        let mut engine = ThemeEngine::empty().unwrap();
+        engine
+            .tera_mut()
+            .add_raw_template(
+                "elements/responsive-image.html",
+                include_str!("../../../../templates/elements/responsive-image.html"),
+            )
+            .unwrap();
+        let picture = ResponsivePicture {
+            style: "hero".to_string(),
+            src: "/files/styles/medium/photo.jpg".to_string(),
+            sources: vec![PictureSource {
+                media: Some("(min-width: 960px)".to_string()),
+                srcset: "/files/styles/w480/photo.jpg 480w".to_string(),
+                sizes: Some("100vw".to_string()),
+            }],
+            alt: "A \"photo\"".to_string(),
+            width: Some(500),
+        };
+
+        let html = engine.render_responsive_image(&picture).unwrap();
+        assert!(html.contains("<picture class=\"responsive-image responsive-image--hero\">"));
+        assert!(html.contains("media=\"(min-width: 960px)\""), "{html}");
+        assert!(html.contains("sizes=\"100vw\""), "{html}");
+        assert!(html.contains("width=\"500\""), "{html}");
+        assert!(html.contains("alt=\"A &quot;photo&quot;\""), "{html}");
+    }
+
     #[test]
     fn responsive_image_filter_empty_url() {
         let mut tera = Tera::default();
@@ -5,6 +5,7 @@
 
 use trovato_kernel::cache::CacheLayer;
 use trovato_kernel::models::stage::LIVE_STAGE_ID;
+use trovato_kernel::redis_manager::{RedisManager, RedisSettings};
 use uuid::Uuid;
 
 #[test]
@@ -57,8 +58,8 @@ fn test_stage_key_empty_key() {
 async fn test_cache_layer_creation() {
     // This test verifies the CacheLayer can be created
     // Actual caching tests require Redis
-    let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
-    let cache = CacheLayer::new(client);
+    let redis = RedisManager::new(RedisSettings::standalone("redis://127.0.0.1:6379")).unwrap();
+    let cache = CacheLayer::new(redis);
 
     let stats = cache.stats().await;
     assert_eq!(stats.l1_entry_count, 0);
@@ -111,6 +111,13 @@ impl TestApp {
             unsafe { std::env::set_var("DATABASE_MAX_CONNECTIONS", "25") };
         }
 
+        // Requests carry a mock loopback peer address; trusting it lets
+        // tests pick their client IP (and rate limit bucket) with
+        // `X-Forwarded-For`.
+        if std::env::var("TRUSTED_PROXIES").is_err() {
+            unsafe { std::env::set_var("TRUSTED_PROXIES", "127.0.0.1") };
+        }
+
         // Create config from environment
         let config = Config::from_env().expect("Failed to load config");
 
@@ -122,8 +129,12 @@ impl TestApp {
         let db = state.db().clone();
 
         // Create session layer
+        let session_settings = trovato_kernel::session::SessionSettings::from_config(&config)
+            .expect("Invalid session settings");
         let session_layer = trovato_kernel::session::create_session_layer(
-            &config.redis_url,
+            &session_settings,
+            state.redis().settings(),
+            &db,
             tower_sessions::cookie::SameSite::Strict,
         )
         .await
@@ -140,19 +151,26 @@ impl TestApp {
             .merge(trovato_kernel::routes::front::router())
             .merge(trovato_kernel::routes::install::router())
             .merge(trovato_kernel::routes::auth::router())
+            .merge(trovato_kernel::routes::author::router())
             .merge(trovato_kernel::routes::admin::router())
             .merge(trovato_kernel::routes::password_reset::router())
             .merge(trovato_kernel::routes::health::router())
             .merge(trovato_kernel::routes::item::router())
+            .merge(trovato_kernel::routes::jsonapi::router())
+            .merge(trovato_kernel::routes::menu::router())
             .merge(trovato_kernel::routes::gather::router())
             .merge(trovato_kernel::routes::gather_admin::router())
             .merge(trovato_kernel::routes::plugin_admin::router())
             .merge(trovato_kernel::routes::search::router())
+            .merge(trovato_kernel::routes::mfa::router())
             .merge(trovato_kernel::routes::cron::router())
+            .merge(trovato_kernel::routes::deprecation::router())
+            .merge(trovato_kernel::routes::read_only::router())
             .merge(trovato_kernel::routes::file::router())
             .merge(trovato_kernel::routes::metrics::router())
             .merge(trovato_kernel::routes::batch::router())
             .merge(trovato_kernel::routes::api_token::router())
+            .merge(trovato_kernel::routes::api_people::router())
             .merge(trovato_kernel::routes::api_ai_assist::router())
             .merge(trovato_kernel::routes::api_chat::router())
             .merge(trovato_kernel::routes::api_search::router())
@@ -182,13 +200,20 @@ impl TestApp {
                 }
             })
             // Middleware layers (must match main.rs ordering):
-            // TraceLayer → session → negotiate_language → routes
+            // TraceLayer → session → session_expiry → negotiate_language → routes
             .layer(axum::middleware::from_fn_with_state(
                 state.clone(),
                 trovato_kernel::middleware::negotiate_language,
             ))
+            .layer(axum::middleware::from_fn_with_state(
+                session_settings,
+                trovato_kernel::middleware::apply_session_expiry,
+            ))
             .layer(session_layer)
             .layer(tower_http::trace::TraceLayer::new_for_http())
+            .layer(axum::extract::connect_info::MockConnectInfo(
+                std::net::SocketAddr::from(([127, 0, 0, 1], 0)),
+            ))
             .with_state(state.clone());
 
         // Pre-warm all pool connections on SHARED_RT so that no connection
@@ -4,6 +4,7 @@
 //! Tests for Phase 6A scheduled operations.
 
 use trovato_kernel::cron::{LastCronRun, RedisQueue};
+use trovato_kernel::redis_manager::{RedisManager, RedisSettings};
 
 #[test]
 fn test_last_cron_run_serde() {
@@ -24,8 +25,8 @@ fn test_last_cron_run_serde() {
 
 #[test]
 fn test_queue_key_format() {
-    let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
-    let queue = RedisQueue::new(client);
+    let redis = RedisManager::new(RedisSettings::standalone("redis://127.0.0.1:6379")).unwrap();
+    let queue = RedisQueue::new(redis);
 
     // Test that queue creation doesn't panic
     assert!(std::mem::size_of_val(&queue) > 0);
@@ -267,6 +267,8 @@ fn query_display_custom() {
         footer: None,
         canonical_url: None,
         routes: Vec::new(),
+        archive: None,
+        exports: None,
     };
 
     assert_eq!(display.format, DisplayFormat::Grid);
@@ -537,6 +539,8 @@ fn gate_test_recent_articles_query_definition() {
             footer: None,
             canonical_url: None,
             routes: Vec::new(),
+            archive: None,
+            exports: None,
         },
         plugin: "trovato_blog".to_string(),
         created: chrono::Utc::now().timestamp(),
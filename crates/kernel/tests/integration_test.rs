@@ -37,7 +37,7 @@ use common::{TestApp, extract_cookies, run_test, shared_app};
 /// enable registration take a read lock so they can run concurrently.
 static REGISTRATION_LOCK: RwLock<()> = RwLock::const_new(());
 
-/// Tests that overwrite `pathauto_patterns` in `site_config`.
+/// Tests that configure pathauto alias patterns.
 static PATHAUTO_LOCK: Mutex<()> = Mutex::const_new(());
 
 /// Tests that toggle plugin enabled/disabled state take a write lock;
@@ -165,6 +165,109 @@ fn login_with_valid_credentials_returns_success() {
     });
 }
 
+#[test]
+fn spoofed_forwarding_header_does_not_dodge_ip_lockout() {
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
+
+    run_test(async {
+        let app = shared_app().await;
+
+        // A client connecting directly (not through a trusted proxy) whose
+        // address is already locked out.
+        let unique_id = uuid::Uuid::now_v7().simple().to_string();
+        let peer_ip = format!("203.0.113.{}", (unique_id.as_bytes()[0] % 254) + 1);
+        let peer: SocketAddr = format!("{peer_ip}:40000").parse().unwrap();
+        app.state.rate_limiter().reset("login", &peer_ip).await.ok();
+        let settings = app.state.lockout().settings().await;
+        for _ in 0..settings.ip_max_attempts {
+            app.state
+                .lockout()
+                .record_failed_ip_attempt(&peer_ip)
+                .await
+                .unwrap();
+        }
+
+        // Claiming a fresh address in the forwarding headers must not get
+        // it a new bucket.
+        let response = app
+            .request(
+                Request::post("/user/login/json")
+                    .extension(ConnectInfo(peer))
+                    .header("content-type", "application/json")
+                    .header("x-forwarded-for", "10.250.1.1")
+                    .header("x-real-ip", "10.250.1.1")
+                    .body(Body::from(
+                        json!({
+                            "username": format!("nobody_{}", &unique_id[..12]),
+                            "password": "wrong-password"
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(
+            response.status(),
+            StatusCode::TOO_MANY_REQUESTS,
+            "the locked peer address should still be refused"
+        );
+
+        app.state
+            .lockout()
+            .clear(trovato_kernel::lockout::LockoutScope::Ip, &peer_ip)
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+fn oidc_login_goes_through_second_factor() {
+    use std::sync::Arc;
+    use tower_sessions::{MemoryStore, Session};
+    use trovato_kernel::routes::auth::SESSION_USER_ID;
+    use trovato_kernel::routes::oidc::login_verified_account;
+    use trovato_kernel::services::mfa::UserMfa;
+
+    run_test(async {
+        let app = shared_app().await;
+
+        let unique_id = uuid::Uuid::now_v7().simple().to_string();
+        let username = format!("oidc_mfa_{}", &unique_id[..12]);
+        app.create_test_user(&username, "testpass123", &format!("{username}@test.com"))
+            .await;
+        let user = app
+            .state
+            .users()
+            .find_by_name(&username)
+            .await
+            .unwrap()
+            .unwrap();
+        let store = Arc::new(MemoryStore::default());
+
+        // Without 2FA the verified login establishes the session.
+        let session = Session::new(None, store.clone(), None);
+        let response = login_verified_account(&app.state, &session, &user, "keycloak").await;
+        assert_eq!(response.headers()["location"], "/");
+        let logged_in: Option<uuid::Uuid> = session.get(SESSION_USER_ID).await.unwrap();
+        assert_eq!(logged_in, Some(user.id));
+
+        // With 2FA enabled the login continues to the code challenge.
+        UserMfa::begin_enrollment(&app.db, user.id).await.unwrap();
+        sqlx::query("UPDATE user_mfa SET enabled = TRUE WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&app.db)
+            .await
+            .unwrap();
+        let session = Session::new(None, store, None);
+        let response = login_verified_account(&app.state, &session, &user, "keycloak").await;
+        assert_eq!(response.headers()["location"], "/user/login/mfa");
+        let logged_in: Option<uuid::Uuid> = session.get(SESSION_USER_ID).await.unwrap();
+        assert_eq!(logged_in, None);
+    });
+}
+
 // =============================================================================
 // Content Type Admin Tests (Phase 5)
 // =============================================================================
@@ -2756,150 +2859,493 @@ fn e2e_admin_remove_search_config() {
 }
 
 #[test]
-fn e2e_admin_reindex_content_type() {
+fn e2e_admin_delete_field_drops_search_config_and_reports_data() {
     run_test(async {
         let _lock = SEARCH_CONFIG_LOCK.lock().await;
         let app = shared_app().await;
 
+        let type_name = "page";
+        let field_name = "type_update_field";
+
         let cookies = app
-            .create_and_login_admin("admin_search_4", "password123", "search4@test.com")
+            .create_and_login_admin("admin_type_update", "password123", "typeupdate@test.com")
             .await;
 
-        // Fetch CSRF token (use /admin/people which always has CSRF tokens)
+        // Add the field through the admin UI so the registry cache is updated
+        let fields_response = app
+            .request_with_cookies(
+                Request::get(format!("/admin/structure/types/{type_name}/fields"))
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        let fields_cookies = extract_cookies(&fields_response);
+        let cookies = if fields_cookies.is_empty() {
+            cookies
+        } else {
+            fields_cookies
+        };
+        let fields_html = response_text(fields_response).await;
+        let csrf_token = extract_csrf_token(&fields_html).expect("CSRF token for field form");
+        let form_build_id = extract_form_build_id(&fields_html).unwrap_or_default();
+
+        app.request_with_cookies(
+            Request::post(format!("/admin/structure/types/{type_name}/fields/add"))
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from(format!(
+                    "_token={csrf_token}&_form_build_id={form_build_id}&label=Type+Update&name={field_name}&field_type=text_long"
+                )))
+                .unwrap(),
+            &cookies,
+        )
+        .await;
+
+        // An item with data for the field, and a search config for it
+        let item_id = uuid::Uuid::now_v7();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item (id, type, title, author_id, status, fields, created, changed) VALUES ($1, 'page', 'Type Update', $2, 1, $3, $4, $4)",
+        )
+        .bind(item_id)
+        .bind(uuid::Uuid::nil())
+        .bind(serde_json::json!({ field_name: "orphaned value" }))
+        .bind(now)
+        .execute(&app.db)
+        .await
+        .expect("Failed to create test content");
+        sqlx::query(
+            "INSERT INTO search_field_config (id, bundle, field_name, weight) VALUES ($1, $2, $3, 'C') ON CONFLICT (bundle, field_name) DO NOTHING",
+        )
+        .bind(uuid::Uuid::now_v7())
+        .bind(type_name)
+        .bind(field_name)
+        .execute(&app.db)
+        .await
+        .expect("Failed to create search config");
+
+        // Delete the field
         let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/admin/people").await;
+        let response = app
+            .request_with_cookies(
+                Request::post(format!(
+                    "/admin/structure/types/{type_name}/fields/{field_name}/delete"
+                ))
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(csrf_form_body(&csrf_token))
+                .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
 
-        // Test reindex for 'page' content type (always exists)
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM search_field_config WHERE bundle = $1 AND field_name = $2)",
+        )
+        .bind(type_name)
+        .bind(field_name)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+        assert!(
+            !exists,
+            "search config for a removed field should be dropped"
+        );
+
+        // The fields page reports the item still holding data
         let response = app
             .request_with_cookies(
-                Request::post("/admin/structure/types/page/search/reindex")
-                    .header("content-type", "application/x-www-form-urlencoded")
-                    .body(csrf_form_body(&csrf_token))
+                Request::get(format!("/admin/structure/types/{type_name}/fields"))
+                    .body(Body::empty())
                     .unwrap(),
                 &cookies,
             )
             .await;
-
-        let resp_status = response.status();
+        let html = response_text(response).await;
         assert!(
-            resp_status == StatusCode::SEE_OTHER || resp_status == StatusCode::OK,
-            "Expected redirect or success, got {resp_status}"
+            html.contains(&format!("<code>{field_name}</code> (1 item)")),
+            "fields page should report orphaned field data"
         );
+
+        sqlx::query("DELETE FROM item WHERE id = $1")
+            .bind(item_id)
+            .execute(&app.db)
+            .await
+            .ok();
     });
 }
 
-// =============================================================================
-// Admin Auth Guard Tests
-// =============================================================================
-
 #[test]
-fn e2e_admin_pages_require_login() {
+fn e2e_admin_bulk_unpublish_records_status_reason() {
     run_test(async {
         let app = shared_app().await;
 
-        // All these core admin routes should redirect to login when not authenticated.
-        // Only non-gated routes are listed here; plugin-gated routes (e.g. categories,
-        // comments) may return 404 if the plugin is disabled, which is correct but
-        // would make this test fragile.
-        let admin_routes = vec![
-            "/admin/people",
-            "/admin/people/roles",
-            "/admin/people/permissions",
-            "/admin/content",
-            "/admin/structure/types",
-            "/admin/content/files",
-        ];
-
-        for route in admin_routes {
-            let response = app
-                .request(Request::get(route).body(Body::empty()).unwrap())
-                .await;
+        let cookies = app
+            .create_and_login_admin(
+                "admin_status_reason",
+                "password123",
+                "statusreason@test.com",
+            )
+            .await;
 
-            assert_eq!(
-                response.status(),
-                StatusCode::SEE_OTHER,
-                "Route {route} should redirect when not logged in"
-            );
-        }
-    });
-}
+        let item_id = uuid::Uuid::now_v7();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item (id, type, title, author_id, status, fields, created, changed) VALUES ($1, 'page', 'Status Reason Item', $2, 1, '{}', $3, $3)",
+        )
+        .bind(item_id)
+        .bind(uuid::Uuid::nil())
+        .bind(now)
+        .execute(&app.db)
+        .await
+        .expect("Failed to create test content");
 
-// =============================================================================
-// Static File Tests
-// =============================================================================
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/admin/people").await;
+        let response = app
+            .request_with_cookies(
+                Request::post("/admin/content/bulk")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from(format!(
+                        "_token={csrf_token}&action=unpublish&ids%5B%5D={item_id}&reason=legal_hold&note=Pending+counsel"
+                    )))
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
 
-#[test]
-fn e2e_static_file_serves_js() {
-    run_test(async {
-        let app = shared_app().await;
+        let (status, reason, note): (i16, Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT new_status, reason_code, note FROM item_status_change WHERE item_id = $1",
+        )
+        .bind(item_id)
+        .fetch_one(&app.db)
+        .await
+        .expect("status change should be recorded");
+        assert_eq!(status, 0);
+        assert_eq!(reason.as_deref(), Some("legal_hold"));
+        assert_eq!(note.as_deref(), Some("Pending counsel"));
 
+        // The content listing filters by the latest status reason
         let response = app
-            .request(
-                Request::get("/static/js/file-upload.js")
+            .request_with_cookies(
+                Request::get("/admin/content?reason=legal_hold")
                     .body(Body::empty())
                     .unwrap(),
+                &cookies,
             )
             .await;
-
-        let status = response.status();
-        assert_eq!(
-            status,
-            StatusCode::OK,
-            "Expected 200 OK for static JS file, got {status}"
-        );
-
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-        assert!(
-            content_type.contains("javascript"),
-            "Content-Type should be JavaScript, got {content_type}"
-        );
-    });
-}
-
-#[test]
-fn e2e_static_file_returns_404_for_missing() {
-    run_test(async {
-        let app = shared_app().await;
+        let html = response_text(response).await;
+        assert!(html.contains("Status Reason Item"));
 
         let response = app
-            .request(
-                Request::get("/static/nonexistent.js")
+            .request_with_cookies(
+                Request::get("/admin/content?reason=correction")
                     .body(Body::empty())
                     .unwrap(),
+                &cookies,
             )
             .await;
+        let html = response_text(response).await;
+        assert!(!html.contains("Status Reason Item"));
 
-        assert_eq!(
-            response.status(),
-            StatusCode::NOT_FOUND,
-            "Missing file should return 404"
-        );
+        sqlx::query("DELETE FROM item WHERE id = $1")
+            .bind(item_id)
+            .execute(&app.db)
+            .await
+            .ok();
     });
 }
 
-// =============================================================================
-// Batch API Tests (Phase 6D)
-// =============================================================================
-
 #[test]
-fn e2e_batch_create_operation() {
+fn bulk_author_and_stage_changes_are_refused() {
     run_test(async {
         let app = shared_app().await;
+        let unique = uuid::Uuid::now_v7().simple().to_string();
+        let bulk = |operation: &str, item_id: uuid::Uuid, params: Value| {
+            json!({ "operation": operation, "item_ids": [item_id], "params": params }).to_string()
+        };
 
-        let response = app
-            .request(
-                Request::post("/api/batch")
-                    .header("content-type", "application/json")
-                    .body(Body::from(
-                        json!({
-                            "operation_type": "test_operation",
-                            "params": {"key": "value"}
-                        })
-                        .to_string(),
-                    ))
+        let item_id = uuid::Uuid::now_v7();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item (id, type, title, author_id, status, fields, created, changed) VALUES ($1, 'page', 'Bulk Denial Item', $2, 0, '{}', $3, $3)",
+        )
+        .bind(item_id)
+        .bind(uuid::Uuid::nil())
+        .bind(now)
+        .execute(&app.db)
+        .await
+        .expect("Failed to create test content");
+
+        // Without "administer content", neither operation starts.
+        let editor = format!("bulk_editor_{}", &unique[..12]);
+        let cookies = app
+            .create_and_login_user(&editor, "password123", &format!("{editor}@test.com"))
+            .await;
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/user/profile").await;
+        for (operation, params) in [
+            ("change_author", json!({ "author_id": uuid::Uuid::nil() })),
+            (
+                "move_stage",
+                json!({ "stage_id": trovato_kernel::models::stage::LIVE_STAGE_ID }),
+            ),
+        ] {
+            let response = app
+                .request_with_cookies(
+                    Request::post("/api/items/bulk")
+                        .header("content-type", "application/json")
+                        .header("X-CSRF-Token", &csrf_token)
+                        .body(Body::from(bulk(operation, item_id, params)))
+                        .unwrap(),
+                    &cookies,
+                )
+                .await;
+            assert_eq!(
+                response.status(),
+                StatusCode::FORBIDDEN,
+                "{operation} should need administer content"
+            );
+        }
+
+        // Even admins cannot move items straight to live or into someone
+        // else's personal workspace.
+        let admin = format!("bulk_admin_{}", &unique[..12]);
+        let cookies = app
+            .create_and_login_admin(&admin, "password123", &format!("{admin}@test.com"))
+            .await;
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/admin/people").await;
+        let owner = trovato_kernel::models::User::find_by_name(&app.db, &editor)
+            .await
+            .unwrap()
+            .unwrap();
+        let workspace = trovato_kernel::services::workspace::provision(&app.db, &owner)
+            .await
+            .unwrap();
+        for stage_id in [
+            trovato_kernel::models::stage::LIVE_STAGE_ID,
+            workspace.stage_id,
+        ] {
+            let response = app
+                .request_with_cookies(
+                    Request::post("/api/items/bulk")
+                        .header("content-type", "application/json")
+                        .header("X-CSRF-Token", &csrf_token)
+                        .body(Body::from(bulk(
+                            "move_stage",
+                            item_id,
+                            json!({ "stage_id": stage_id }),
+                        )))
+                        .unwrap(),
+                    &cookies,
+                )
+                .await;
+            assert_eq!(
+                response.status(),
+                StatusCode::BAD_REQUEST,
+                "moving to stage {stage_id} should be refused"
+            );
+        }
+
+        sqlx::query("DELETE FROM item WHERE id = $1")
+            .bind(item_id)
+            .execute(&app.db)
+            .await
+            .ok();
+    });
+}
+
+#[test]
+fn e2e_admin_reindex_content_type() {
+    run_test(async {
+        let _lock = SEARCH_CONFIG_LOCK.lock().await;
+        let app = shared_app().await;
+
+        let cookies = app
+            .create_and_login_admin("admin_search_4", "password123", "search4@test.com")
+            .await;
+
+        // Fetch CSRF token (use /admin/people which always has CSRF tokens)
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/admin/people").await;
+
+        // Test reindex for 'page' content type (always exists)
+        let response = app
+            .request_with_cookies(
+                Request::post("/admin/structure/types/page/search/reindex")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(csrf_form_body(&csrf_token))
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+
+        let resp_status = response.status();
+        assert!(
+            resp_status == StatusCode::SEE_OTHER || resp_status == StatusCode::OK,
+            "Expected redirect or success, got {resp_status}"
+        );
+    });
+}
+
+#[test]
+fn e2e_admin_search_status() {
+    run_test(async {
+        let _lock = SEARCH_CONFIG_LOCK.lock().await;
+        let app = shared_app().await;
+
+        // Anonymous users are refused
+        let response = app
+            .request(
+                Request::get("/admin/config/search/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert!(
+            response.status() == StatusCode::SEE_OTHER
+                || response.status() == StatusCode::FORBIDDEN,
+            "Search status should require admin, got: {}",
+            response.status()
+        );
+
+        let cookies = app
+            .create_and_login_admin("admin_search_5", "password123", "search5@test.com")
+            .await;
+        let response = app
+            .request_with_cookies(
+                Request::get("/admin/config/search/status")
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        let bundles = body["bundles"]
+            .as_array()
+            .expect("bundles should be an array");
+        let pending: i64 = bundles
+            .iter()
+            .map(|b| b["pending"].as_i64().expect("pending should be a number"))
+            .sum();
+        assert_eq!(body["pending"].as_i64(), Some(pending));
+        assert!(body["queue_length"].is_u64());
+    });
+}
+
+// =============================================================================
+// Admin Auth Guard Tests
+// =============================================================================
+
+#[test]
+fn e2e_admin_pages_require_login() {
+    run_test(async {
+        let app = shared_app().await;
+
+        // All these core admin routes should redirect to login when not authenticated.
+        // Only non-gated routes are listed here; plugin-gated routes (e.g. categories,
+        // comments) may return 404 if the plugin is disabled, which is correct but
+        // would make this test fragile.
+        let admin_routes = vec![
+            "/admin/people",
+            "/admin/people/roles",
+            "/admin/people/permissions",
+            "/admin/content",
+            "/admin/structure/types",
+            "/admin/content/files",
+        ];
+
+        for route in admin_routes {
+            let response = app
+                .request(Request::get(route).body(Body::empty()).unwrap())
+                .await;
+
+            assert_eq!(
+                response.status(),
+                StatusCode::SEE_OTHER,
+                "Route {route} should redirect when not logged in"
+            );
+        }
+    });
+}
+
+// =============================================================================
+// Static File Tests
+// =============================================================================
+
+#[test]
+fn e2e_static_file_serves_js() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::get("/static/js/file-upload.js")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+
+        let status = response.status();
+        assert_eq!(
+            status,
+            StatusCode::OK,
+            "Expected 200 OK for static JS file, got {status}"
+        );
+
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        assert!(
+            content_type.contains("javascript"),
+            "Content-Type should be JavaScript, got {content_type}"
+        );
+    });
+}
+
+#[test]
+fn e2e_static_file_returns_404_for_missing() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::get("/static/nonexistent.js")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(
+            response.status(),
+            StatusCode::NOT_FOUND,
+            "Missing file should return 404"
+        );
+    });
+}
+
+// =============================================================================
+// Batch API Tests (Phase 6D)
+// =============================================================================
+
+#[test]
+fn e2e_batch_create_operation() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::post("/api/batch")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({
+                            "operation_type": "test_operation",
+                            "params": {"key": "value"}
+                        })
+                        .to_string(),
+                    ))
                     .unwrap(),
             )
             .await;
@@ -3300,58 +3746,200 @@ fn e2e_api_get_item_not_found() {
     });
 }
 
-// =============================================================================
-// Comment API Tests
-// =============================================================================
-
 #[test]
-fn e2e_api_list_comments_for_nonexistent_item() {
+fn e2e_jsonapi_lists_content_types() {
     run_test(async {
         let app = shared_app().await;
 
-        let fake_id = uuid::Uuid::now_v7();
         let response = app
             .request(
-                Request::get(format!("/api/item/{fake_id}/comments"))
+                Request::get("/jsonapi/content_type")
                     .body(Body::empty())
                     .unwrap(),
             )
             .await;
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "application/vnd.api+json"
+        );
+
+        let body = response_json(response).await;
+        let data = body["data"].as_array().unwrap();
+        assert!(data.iter().any(|resource| resource["id"] == "page"));
+        assert!(
+            data.iter()
+                .all(|resource| resource["type"] == "content_type")
+        );
     });
 }
 
 #[test]
-fn e2e_api_create_comment_requires_auth() {
+fn e2e_jsonapi_list_items_returns_resources() {
     run_test(async {
         let app = shared_app().await;
-        app.ensure_plugin_enabled("trovato_comments").await;
 
-        let fake_id = uuid::Uuid::now_v7();
         let response = app
             .request(
-                Request::post(format!("/api/item/{fake_id}/comments"))
-                    .header("content-type", "application/json")
-                    .body(Body::from(
-                        serde_json::to_string(&json!({
-                            "body": "Test comment"
-                        }))
-                        .unwrap(),
-                    ))
+                Request::get("/jsonapi/item/page?sort=-changed&page[size]=5")
+                    .body(Body::empty())
                     .unwrap(),
             )
             .await;
 
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        let data = body["data"].as_array().unwrap();
+        assert!(data.len() <= 5);
+        for resource in data {
+            assert_eq!(resource["type"], "item--page");
+            assert!(resource["attributes"]["title"].is_string());
+        }
+        assert!(body["links"]["self"].is_string());
     });
 }
 
 #[test]
-fn e2e_api_comment_crud() {
+fn e2e_jsonapi_list_items_applies_view_grants() {
     run_test(async {
         let app = shared_app().await;
-        app.ensure_plugin_enabled("trovato_comments").await;
+
+        // Far-future change times keep both items at the top of the listing.
+        let changed = Utc::now().timestamp() + 1_000_000_000;
+        let open_id = uuid::Uuid::now_v7();
+        let restricted_id = uuid::Uuid::now_v7();
+        for (id, title) in [
+            (open_id, "Open Grant Item"),
+            (restricted_id, "Restricted Grant Item"),
+        ] {
+            sqlx::query(
+                "INSERT INTO item (id, type, title, author_id, status, fields, created, changed) VALUES ($1, 'page', $2, $3, 1, '{}', $4, $4)",
+            )
+            .bind(id)
+            .bind(title)
+            .bind(uuid::Uuid::nil())
+            .bind(changed)
+            .execute(&app.db)
+            .await
+            .unwrap();
+        }
+        trovato_kernel::models::ItemAccess::replace(
+            &app.db,
+            restricted_id,
+            &[trovato_sdk::types::ItemGrant::user(uuid::Uuid::now_v7()).allow_view()],
+        )
+        .await
+        .unwrap();
+
+        let response = app
+            .request(
+                Request::get("/jsonapi/item/page?sort=-changed&page[size]=5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|resource| resource["id"].as_str())
+            .collect();
+        assert!(ids.contains(&open_id.to_string().as_str()));
+        assert!(!ids.contains(&restricted_id.to_string().as_str()));
+
+        // Clean up
+        sqlx::query("DELETE FROM item WHERE id = ANY($1)")
+            .bind(vec![open_id, restricted_id])
+            .execute(&app.db)
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+fn e2e_jsonapi_rejects_unknown_filter_and_type() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::get("/jsonapi/item/page?filter[field_missing]=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = response_json(response).await;
+        assert_eq!(body["errors"][0]["status"], "400");
+
+        let response = app
+            .request(
+                Request::get("/jsonapi/item/no_such_type")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    });
+}
+
+// =============================================================================
+// Comment API Tests
+// =============================================================================
+
+#[test]
+fn e2e_api_list_comments_for_nonexistent_item() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let fake_id = uuid::Uuid::now_v7();
+        let response = app
+            .request(
+                Request::get(format!("/api/item/{fake_id}/comments"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    });
+}
+
+#[test]
+fn e2e_api_create_comment_requires_auth() {
+    run_test(async {
+        let app = shared_app().await;
+        app.ensure_plugin_enabled("trovato_comments").await;
+
+        let fake_id = uuid::Uuid::now_v7();
+        let response = app
+            .request(
+                Request::post(format!("/api/item/{fake_id}/comments"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::to_string(&json!({
+                            "body": "Test comment"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    });
+}
+
+#[test]
+fn e2e_api_comment_crud() {
+    run_test(async {
+        let app = shared_app().await;
+        app.ensure_plugin_enabled("trovato_comments").await;
 
         // Create a user and login
         let cookies = app
@@ -5026,13 +5614,6 @@ fn pathauto_generates_alias_on_item_create() {
         let unique_id = uuid::Uuid::now_v7().simple().to_string();
         let type_name = format!("pa_{}", &unique_id[..8]);
 
-        // Save existing patterns so we can restore them after the test
-        let saved_patterns: Option<Value> =
-            trovato_kernel::models::SiteConfig::get(&app.db, "pathauto_patterns")
-                .await
-                .ok()
-                .flatten();
-
         // Create content type
         sqlx::query(
             "INSERT INTO item_type (type, label, description, plugin, settings)
@@ -5045,11 +5626,12 @@ fn pathauto_generates_alias_on_item_create() {
         .expect("Failed to create content type");
 
         // Configure pathauto pattern for this content type
-        let mut patterns = saved_patterns.clone().unwrap_or_else(|| json!({}));
-        patterns[&type_name] = json!("test/[title]");
-        trovato_kernel::models::SiteConfig::set(&app.db, "pathauto_patterns", patterns)
-            .await
-            .unwrap();
+        trovato_kernel::models::AliasPattern::upsert(
+            &app.db,
+            &trovato_kernel::models::AliasPattern::new(&type_name, None, "test/[title]"),
+        )
+        .await
+        .unwrap();
 
         // Clean up stale aliases from prior test runs
         sqlx::query("DELETE FROM url_alias WHERE alias LIKE '/test/my-test-article%'")
@@ -5076,11 +5658,13 @@ fn pathauto_generates_alias_on_item_create() {
     .expect("Failed to create item");
 
         // Call pathauto
-        let result = trovato_kernel::services::pathauto::auto_alias_item(
-            &app.db, item_id, title, &type_name, now,
-        )
-        .await
-        .expect("pathauto should succeed");
+        let item = trovato_kernel::models::Item::find_by_id(&app.db, item_id)
+            .await
+            .unwrap()
+            .expect("item should exist");
+        let result = trovato_kernel::services::pathauto::auto_alias_item(&app.db, &item)
+            .await
+            .expect("pathauto should succeed");
 
         assert!(result.is_some(), "Should generate an alias");
         let alias = result.unwrap();
@@ -5114,18 +5698,9 @@ fn pathauto_generates_alias_on_item_create() {
             .execute(&app.db)
             .await
             .ok();
-
-        // Restore original pathauto patterns
-        match saved_patterns {
-            Some(v) => trovato_kernel::models::SiteConfig::set(&app.db, "pathauto_patterns", v)
-                .await
-                .ok(),
-            None => sqlx::query("DELETE FROM site_config WHERE key = 'pathauto_patterns'")
-                .execute(&app.db)
-                .await
-                .ok()
-                .map(|_| ()),
-        };
+        trovato_kernel::models::AliasPattern::delete(&app.db, &type_name)
+            .await
+            .ok();
     });
 }
 
@@ -5137,13 +5712,6 @@ fn pathauto_skips_when_alias_exists() {
         let unique_id = uuid::Uuid::now_v7().simple().to_string();
         let type_name = format!("pa2_{}", &unique_id[..8]);
 
-        // Save existing patterns so we can restore them after the test
-        let saved_patterns: Option<Value> =
-            trovato_kernel::models::SiteConfig::get(&app.db, "pathauto_patterns")
-                .await
-                .ok()
-                .flatten();
-
         // Create content type
         sqlx::query(
             "INSERT INTO item_type (type, label, description, plugin, settings)
@@ -5155,12 +5723,13 @@ fn pathauto_skips_when_alias_exists() {
         .await
         .unwrap();
 
-        // Configure pattern (merge into existing)
-        let mut patterns = saved_patterns.clone().unwrap_or_else(|| json!({}));
-        patterns[&type_name] = json!("skip/[title]");
-        trovato_kernel::models::SiteConfig::set(&app.db, "pathauto_patterns", patterns)
-            .await
-            .unwrap();
+        // Configure pattern
+        trovato_kernel::models::AliasPattern::upsert(
+            &app.db,
+            &trovato_kernel::models::AliasPattern::new(&type_name, None, "skip/[title]"),
+        )
+        .await
+        .unwrap();
 
         // Create item
         let item_id = uuid::Uuid::now_v7();
@@ -5187,15 +5756,13 @@ fn pathauto_skips_when_alias_exists() {
             .unwrap();
 
         // Pathauto should skip because alias already exists
-        let result = trovato_kernel::services::pathauto::auto_alias_item(
-            &app.db,
-            item_id,
-            "Skip Test",
-            &type_name,
-            now,
-        )
-        .await
-        .expect("pathauto should succeed");
+        let item = trovato_kernel::models::Item::find_by_id(&app.db, item_id)
+            .await
+            .unwrap()
+            .expect("item should exist");
+        let result = trovato_kernel::services::pathauto::auto_alias_item(&app.db, &item)
+            .await
+            .expect("pathauto should succeed");
 
         assert!(
             result.is_none(),
@@ -5218,18 +5785,9 @@ fn pathauto_skips_when_alias_exists() {
             .execute(&app.db)
             .await
             .ok();
-
-        // Restore original pathauto patterns
-        match saved_patterns {
-            Some(v) => trovato_kernel::models::SiteConfig::set(&app.db, "pathauto_patterns", v)
-                .await
-                .ok(),
-            None => sqlx::query("DELETE FROM site_config WHERE key = 'pathauto_patterns'")
-                .execute(&app.db)
-                .await
-                .ok()
-                .map(|_| ()),
-        };
+        trovato_kernel::models::AliasPattern::delete(&app.db, &type_name)
+            .await
+            .ok();
     });
 }
 
@@ -5237,19 +5795,31 @@ fn pathauto_skips_when_alias_exists() {
 fn pathauto_returns_none_without_pattern() {
     run_test(async {
         let app = shared_app().await;
-        let item_id = uuid::Uuid::now_v7();
         let now = Utc::now().timestamp();
+        let item = trovato_kernel::models::Item {
+            id: uuid::Uuid::now_v7(),
+            current_revision_id: None,
+            item_type: "nonexistent_type_xyz".to_string(),
+            title: "No Pattern".to_string(),
+            author_id: uuid::Uuid::nil(),
+            status: 1,
+            created: now,
+            changed: now,
+            promote: 0,
+            sticky: 0,
+            fields: json!({}),
+            stage_id: trovato_kernel::models::stage::LIVE_STAGE_ID,
+            language: "en".to_string(),
+            item_group_id: uuid::Uuid::now_v7(),
+            retention_days: None,
+            moderation_state: None,
+            tenant_id: trovato_kernel::models::DEFAULT_TENANT_ID,
+        };
 
         // Call pathauto with a type that has no pattern configured
-        let result = trovato_kernel::services::pathauto::auto_alias_item(
-            &app.db,
-            item_id,
-            "No Pattern",
-            "nonexistent_type_xyz",
-            now,
-        )
-        .await
-        .expect("pathauto should succeed even without pattern");
+        let result = trovato_kernel::services::pathauto::auto_alias_item(&app.db, &item)
+            .await
+            .expect("pathauto should succeed even without pattern");
 
         assert!(
             result.is_none(),
@@ -7753,3 +8323,802 @@ fn e2e_ai_chat_rate_limit_returns_429() {
         trovato_kernel::routes::api_chat::clear_chat_rate_limits();
     });
 }
+
+// =============================================================================
+// Menu Tree API Tests
+// =============================================================================
+
+#[test]
+fn menu_tree_api_returns_main_menu_with_active_trail() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::get("/api/menu/main?path=/")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["name"], "main");
+        let items = body["items"].as_array().unwrap();
+        let home = items.iter().find(|i| i["path"] == "/").unwrap();
+        assert_eq!(home["active"], true);
+        assert_eq!(body["active_trail"], json!(["/"]));
+    });
+}
+
+#[test]
+fn menu_tree_api_unknown_menu_returns_404() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::get("/api/menu/no_such_menu")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    });
+}
+
+// =============================================================================
+// Multisite Tests
+// =============================================================================
+
+#[test]
+fn multisite_scopes_config_and_items_by_host() {
+    use trovato_kernel::content::item_query::{self, ItemQueryAccess};
+    use trovato_kernel::models::stage::LIVE_STAGE_ID;
+    use trovato_kernel::models::{Item, Site, SiteConfig, Tenant, site::SitePlugin};
+    use trovato_kernel::services::site;
+
+    run_test(async {
+        let app = shared_app().await;
+        let suffix = uuid::Uuid::now_v7().simple().to_string();
+        let machine_name = format!("site_{}", &suffix[suffix.len() - 12..]);
+        let hostname = format!("{machine_name}.example.test");
+
+        let tenant = Tenant::create(&app.db, "Multisite Test", &machine_name)
+            .await
+            .unwrap();
+        Site::upsert(&app.db, &hostname.to_uppercase(), tenant.id)
+            .await
+            .unwrap();
+        Site::set_plugin_enabled(&app.db, tenant.id, "trovato_blog", false)
+            .await
+            .unwrap();
+
+        // Hostnames resolve with port and case ignored; unknown ones get the default.
+        let sites = app.state.sites();
+        let scope = sites.resolve(&format!("{hostname}:3000")).await.unwrap();
+        assert_eq!(scope.tenant_id, tenant.id);
+        assert!(scope.disabled_plugins.contains("trovato_blog"));
+        let unknown = sites.resolve("unknown.example.test").await.unwrap();
+        assert_eq!(unknown.tenant_id, trovato_kernel::models::DEFAULT_TENANT_ID);
+
+        let key = format!("multisite_test_{suffix}");
+        SiteConfig::set(&app.db, &key, json!("default"))
+            .await
+            .unwrap();
+
+        let item_id = site::scope((*scope).clone(), async {
+            // Keys the site does not override fall back to the default tenant.
+            assert_eq!(
+                SiteConfig::get(&app.db, &key).await.unwrap(),
+                Some(json!("default"))
+            );
+            SiteConfig::set(&app.db, &key, json!("site")).await.unwrap();
+            assert_eq!(
+                SiteConfig::get(&app.db, &key).await.unwrap(),
+                Some(json!("site"))
+            );
+            assert!(!app.state.is_plugin_enabled("trovato_blog"));
+
+            let item = Item::create(
+                &app.db,
+                trovato_kernel::models::CreateItem {
+                    item_type: "page".to_string(),
+                    title: format!("Multisite page {suffix}"),
+                    author_id: uuid::Uuid::nil(),
+                    status: Some(1),
+                    promote: Some(1),
+                    sticky: Some(1),
+                    fields: None,
+                    stage_id: None,
+                    language: None,
+                    log: None,
+                },
+            )
+            .await
+            .unwrap();
+
+            // Item queries only see the site's own items.
+            let listed = item_query::build(
+                &trovato_sdk::types::ItemQuery::new(),
+                vec![LIVE_STAGE_ID],
+                ItemQueryAccess::Unrestricted,
+                None,
+            )
+            .unwrap()
+            .fetch(&app.db)
+            .await
+            .unwrap();
+            assert_eq!(
+                listed.iter().map(|i| i.id).collect::<Vec<_>>(),
+                vec![item.id]
+            );
+            let by_type = Item::list_by_type(&app.db, "page").await.unwrap();
+            assert_eq!(
+                by_type.iter().map(|i| i.id).collect::<Vec<_>>(),
+                vec![item.id]
+            );
+            let published = Item::list_published(&app.db, None, 10, 0).await.unwrap();
+            assert_eq!(
+                published.iter().map(|i| i.id).collect::<Vec<_>>(),
+                vec![item.id]
+            );
+            item.id
+        })
+        .await;
+
+        // The default site keeps its own value and sees the item's owner.
+        assert_eq!(
+            SiteConfig::get(&app.db, &key).await.unwrap(),
+            Some(json!("default"))
+        );
+        let item = Item::find_by_id(&app.db, item_id).await.unwrap().unwrap();
+        assert_eq!(item.tenant_id, tenant.id);
+        let listed = item_query::build(
+            &trovato_sdk::types::ItemQuery::new(),
+            vec![LIVE_STAGE_ID],
+            ItemQueryAccess::Unrestricted,
+            None,
+        )
+        .unwrap()
+        .fetch(&app.db)
+        .await
+        .unwrap();
+        assert!(listed.iter().all(|i| i.id != item_id));
+
+        // Neither the front page nor the type listing API leak it.
+        let response = app
+            .request(Request::get("/").body(Body::empty()).unwrap())
+            .await;
+        let html = response_text(response).await;
+        assert!(!html.contains(&format!("Multisite page {suffix}")));
+        let response = app
+            .request(Request::get("/api/items/page").body(Body::empty()).unwrap())
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let listed = response_json(response).await;
+        assert!(
+            listed
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|i| i["id"] != json!(item_id))
+        );
+        let plugins: Vec<SitePlugin> = Site::plugins(&app.db, tenant.id).await.unwrap();
+        assert_eq!(plugins.len(), 1);
+        assert!(!plugins[0].enabled);
+
+        // Clean up
+        Item::delete(&app.db, item_id).await.unwrap();
+        sqlx::query("DELETE FROM site_config WHERE key = $1")
+            .bind(&key)
+            .execute(&app.db)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM tenant WHERE id = $1")
+            .bind(tenant.id)
+            .execute(&app.db)
+            .await
+            .unwrap();
+    });
+}
+
+// =============================================================================
+// Login Lockout Admin Tests
+// =============================================================================
+
+#[test]
+fn admin_lockouts_lists_settings_and_locks() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let cookies = app
+            .create_and_login_admin("lockout_admin", "password123", "lockout_admin@test.com")
+            .await;
+
+        let response = app
+            .request_with_cookies(
+                Request::get("/admin/people/lockouts")
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert!(body["settings"]["max_attempts"].as_u64().unwrap() >= 1);
+        assert!(body["settings"]["ip_allowlist"].is_array());
+        assert!(body["lockouts"].is_array());
+    });
+}
+
+#[test]
+fn admin_lockouts_requires_admin() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(
+                Request::get("/admin/people/lockouts")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    });
+}
+
+// =============================================================================
+// People Administration API Tests
+// =============================================================================
+
+#[test]
+fn api_roles_crud_and_assignment() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let cookies = app
+            .create_and_login_admin("roles_api_admin", "password123", "roles_api@test.com")
+            .await;
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/").await;
+
+        let role_name = format!("api-role-{}", uuid::Uuid::now_v7().simple());
+        let response = app
+            .request_with_cookies(
+                Request::post("/api/roles")
+                    .header("content-type", "application/json")
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::from(
+                        json!({"name": role_name, "permissions": ["access content"]}).to_string(),
+                    ))
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let role = response_json(response).await;
+        let role_id = role["id"].as_str().unwrap().to_string();
+        assert_eq!(role["name"], role_name.as_str());
+        assert_eq!(role["permissions"], json!(["access content"]));
+
+        // Duplicate names conflict
+        let response = app
+            .request_with_cookies(
+                Request::post("/api/roles")
+                    .header("content-type", "application/json")
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::from(json!({"name": role_name}).to_string()))
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let user_id = trovato_kernel::models::User::find_by_name(&app.db, "roles_api_admin")
+            .await
+            .unwrap()
+            .unwrap()
+            .id;
+        let response = app
+            .request_with_cookies(
+                Request::put(format!("/api/users/{user_id}/roles/{role_id}"))
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = app
+            .request_with_cookies(
+                Request::get(format!("/api/users/{user_id}/roles"))
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let roles = response_json(response).await;
+        assert!(
+            roles
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|r| r["id"] == role_id.as_str())
+        );
+
+        let response = app
+            .request_with_cookies(
+                Request::delete(format!("/api/roles/{role_id}"))
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    });
+}
+
+#[test]
+fn api_roles_rejects_renaming_builtin_roles() {
+    use trovato_kernel::models::role::well_known::{ANONYMOUS_ROLE_ID, AUTHENTICATED_ROLE_ID};
+
+    run_test(async {
+        let app = shared_app().await;
+
+        let cookies = app
+            .create_and_login_admin("roles_rename_admin", "password123", "roles_rename@test.com")
+            .await;
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/").await;
+
+        for role_id in [ANONYMOUS_ROLE_ID, AUTHENTICATED_ROLE_ID] {
+            let response = app
+                .request_with_cookies(
+                    Request::patch(format!("/api/roles/{role_id}"))
+                        .header("content-type", "application/json")
+                        .header("X-CSRF-Token", &csrf_token)
+                        .body(Body::from(json!({"name": "renamed builtin"}).to_string()))
+                        .unwrap(),
+                    &cookies,
+                )
+                .await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let role = app
+                .state
+                .roles()
+                .find_by_id(role_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_ne!(role.name, "renamed builtin");
+        }
+    });
+}
+
+#[test]
+fn api_invitations_create_and_revoke() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let cookies = app
+            .create_and_login_admin("invite_admin", "password123", "invite_admin@test.com")
+            .await;
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/").await;
+
+        let mail = format!("invitee-{}@test.com", uuid::Uuid::now_v7().simple());
+        let response = app
+            .request_with_cookies(
+                Request::post("/api/invitations")
+                    .header("content-type", "application/json")
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::from(json!({"mail": mail}).to_string()))
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let invitation = response_json(response).await;
+        assert_eq!(invitation["mail"], mail.as_str());
+        assert!(invitation.get("token_hash").is_none());
+        let invitation_id = invitation["id"].as_str().unwrap().to_string();
+
+        let response = app
+            .request_with_cookies(
+                Request::delete(format!("/api/invitations/{invitation_id}"))
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    });
+}
+
+#[test]
+fn api_roles_requires_admin() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let response = app
+            .request(Request::get("/api/roles").body(Body::empty()).unwrap())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    });
+}
+
+#[test]
+fn author_page_requires_published_content() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let name = format!(
+            "author_{}",
+            &uuid::Uuid::now_v7().simple().to_string()[..16]
+        );
+        app.create_test_user(&name, "password123", &format!("{name}@test.com"))
+            .await;
+        let author_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM users WHERE name = $1")
+            .bind(&name)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+
+        // An author without published content is not listed.
+        let response = app
+            .request(
+                Request::get(format!("/author/{name}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item (id, type, title, author_id, status, fields, created, changed) VALUES ($1, 'page', 'Author Item', $2, 1, '{}', $3, $3)",
+        )
+        .bind(uuid::Uuid::now_v7())
+        .bind(author_id)
+        .bind(now)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+        let response = app
+            .request(
+                Request::get(format!("/author/{name}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response_text(response).await.contains("Author Item"));
+    });
+}
+
+#[test]
+fn item_translation_api_round_trip() {
+    run_test(async {
+        let app = shared_app().await;
+        app.ensure_plugin_enabled("trovato_content_translation")
+            .await;
+        let unique = uuid::Uuid::now_v7().simple().to_string();
+
+        // A German item translated into the default language, so the
+        // translation language is one the site knows.
+        sqlx::query(
+            "INSERT INTO language (id, label, weight, is_default, direction) \
+             VALUES ('de', 'German', 10, false, 'ltr') ON CONFLICT (id) DO NOTHING",
+        )
+        .execute(&app.db)
+        .await
+        .unwrap();
+        let item_id = uuid::Uuid::now_v7();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item (id, type, title, author_id, status, fields, created, changed, language) \
+             VALUES ($1, 'page', 'Ubersetzung', $2, 1, '{}', $3, $3, 'de')",
+        )
+        .bind(item_id)
+        .bind(uuid::Uuid::nil())
+        .bind(now)
+        .execute(&app.db)
+        .await
+        .unwrap();
+        let url = format!("/api/item/{item_id}/translations/en");
+
+        let admin = format!("tr_admin_{}", &unique[..12]);
+        let cookies = app
+            .create_and_login_admin(&admin, "password123", &format!("{admin}@test.com"))
+            .await;
+        let (cookies, csrf_token) = fetch_csrf_token(app, &cookies, "/admin/people").await;
+        let put = |body: Value| {
+            Request::put(url.as_str())
+                .header("content-type", "application/json")
+                .header("X-CSRF-Token", &csrf_token)
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // Fields the item type doesn't have are rejected like a patch.
+        let response = app
+            .request_with_cookies(
+                put(json!({ "title": "Translation", "fields": { "no_such_field": "x" } })),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = app
+            .request_with_cookies(
+                put(json!({ "title": "Translation", "fields": { "body": { "value": "Hello" } } })),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .request_with_cookies(
+                Request::get(url.as_str()).body(Body::empty()).unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["title"], "Translation");
+
+        // The item page links its translation, and search shows the
+        // translated title.
+        let response = app
+            .request_with_cookies(
+                Request::get(format!("/item/{item_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        let html = response_text(response).await;
+        assert!(html.contains("hreflang=\"de\""), "missing source hreflang");
+        assert!(
+            html.contains("hreflang=\"en\""),
+            "missing translation hreflang"
+        );
+        let titles = app
+            .state
+            .items()
+            .translated_titles(&[item_id], "en")
+            .await
+            .unwrap();
+        assert_eq!(
+            titles.get(&item_id).map(String::as_str),
+            Some("Translation")
+        );
+
+        // A translator without edit access to the item can't change it.
+        let role_id: uuid::Uuid =
+            sqlx::query_scalar("INSERT INTO roles (id, name) VALUES ($1, $2) RETURNING id")
+                .bind(uuid::Uuid::now_v7())
+                .bind(format!("translator_{}", &unique[..12]))
+                .fetch_one(&app.db)
+                .await
+                .unwrap();
+        sqlx::query(
+            "INSERT INTO role_permissions (role_id, permission) VALUES ($1, 'translate content')",
+        )
+        .bind(role_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+        let translator = format!("translator_{}", &unique[..12]);
+        let translator_cookies = app
+            .create_and_login_user(
+                &translator,
+                "password123",
+                &format!("{translator}@test.com"),
+            )
+            .await;
+        sqlx::query(
+            "INSERT INTO user_roles (user_id, role_id) \
+             SELECT id, $2 FROM users WHERE LOWER(name) = LOWER($1)",
+        )
+        .bind(&translator)
+        .bind(role_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+        app.state.permissions().invalidate_all();
+        let (translator_cookies, translator_csrf) =
+            fetch_csrf_token(app, &translator_cookies, "/user/profile").await;
+        let response = app
+            .request_with_cookies(
+                Request::put(url.as_str())
+                    .header("content-type", "application/json")
+                    .header("X-CSRF-Token", &translator_csrf)
+                    .body(Body::from(json!({ "title": "Hijacked" }).to_string()))
+                    .unwrap(),
+                &translator_cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = app
+            .request_with_cookies(
+                Request::delete(url.as_str())
+                    .header("X-CSRF-Token", &translator_csrf)
+                    .body(Body::empty())
+                    .unwrap(),
+                &translator_cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Deleting removes the translation, its hreflang link and its
+        // search title.
+        let response = app
+            .request_with_cookies(
+                Request::delete(url.as_str())
+                    .header("X-CSRF-Token", &csrf_token)
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app
+            .request_with_cookies(
+                Request::get(url.as_str()).body(Body::empty()).unwrap(),
+                &cookies,
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app
+            .request_with_cookies(
+                Request::get(format!("/item/{item_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+                &cookies,
+            )
+            .await;
+        assert!(!response_text(response).await.contains("hreflang="));
+        let titles = app
+            .state
+            .items()
+            .translated_titles(&[item_id], "en")
+            .await
+            .unwrap();
+        assert!(titles.is_empty());
+
+        sqlx::query("DELETE FROM roles WHERE id = $1")
+            .bind(role_id)
+            .execute(&app.db)
+            .await
+            .ok();
+        app.state.permissions().invalidate_all();
+    });
+}
+
+#[test]
+fn author_pages_and_front_page_respect_item_grants() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let suffix = uuid::Uuid::now_v7().simple().to_string();
+        let name = format!("grant_author_{}", &suffix[..12]);
+        app.create_test_user(&name, "password123", &format!("{name}@test.com"))
+            .await;
+        let author_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM users WHERE name = $1")
+            .bind(&name)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+
+        let open_title = format!("Open Author Item {suffix}");
+        let restricted_title = format!("Restricted Author Item {suffix}");
+        let open_id = uuid::Uuid::now_v7();
+        let restricted_id = uuid::Uuid::now_v7();
+        let now = Utc::now().timestamp() + 1_000_000_000;
+        for (id, title) in [(open_id, &open_title), (restricted_id, &restricted_title)] {
+            sqlx::query(
+                "INSERT INTO item (id, type, title, author_id, status, promote, sticky, fields, created, changed) VALUES ($1, 'page', $2, $3, 1, 1, 1, '{}', $4, $4)",
+            )
+            .bind(id)
+            .bind(title)
+            .bind(author_id)
+            .bind(now)
+            .execute(&app.db)
+            .await
+            .unwrap();
+        }
+        trovato_kernel::models::ItemAccess::replace(
+            &app.db,
+            restricted_id,
+            &[trovato_sdk::types::ItemGrant::user(uuid::Uuid::now_v7()).allow_view()],
+        )
+        .await
+        .unwrap();
+
+        for path in [
+            format!("/author/{name}"),
+            format!("/author/{name}/feed"),
+            "/".to_string(),
+        ] {
+            let response = app
+                .request(Request::get(&path).body(Body::empty()).unwrap())
+                .await;
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+            let body = response_text(response).await;
+            if path != "/" {
+                assert!(
+                    body.contains(&open_title),
+                    "{path} should list the open item"
+                );
+            }
+            assert!(
+                !body.contains(&restricted_title),
+                "{path} should not list the restricted item"
+            );
+        }
+
+        // Clean up
+        sqlx::query("DELETE FROM item WHERE id = ANY($1)")
+            .bind(vec![open_id, restricted_id])
+            .execute(&app.db)
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+fn sitemap_lists_published_items_of_included_types() {
+    run_test(async {
+        let app = shared_app().await;
+
+        let item_id = uuid::Uuid::now_v7();
+        let now = Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO item (id, type, title, author_id, status, fields, created, changed) VALUES ($1, 'page', 'Sitemap Item', $2, 1, '{}', $3, $3)",
+        )
+        .bind(item_id)
+        .bind(uuid::Uuid::nil())
+        .bind(now)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+        async fn sitemap(app: &TestApp) -> String {
+            app.state
+                .cache()
+                .invalidate_tag(trovato_kernel::cache::ITEM_LISTING_TAG)
+                .await;
+            let response = app
+                .request(Request::get("/sitemap.xml").body(Body::empty()).unwrap())
+                .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            response_text(response).await
+        }
+
+        assert!(sitemap(app).await.contains(&format!("/item/{item_id}")));
+
+        // Excluded types are left out.
+        trovato_kernel::models::SiteConfig::set(
+            &app.db,
+            "sitemap",
+            json!({"types": {"page": {"include": false}}}),
+        )
+        .await
+        .unwrap();
+        let xml = sitemap(app).await;
+        sqlx::query("DELETE FROM site_config WHERE key = 'sitemap'")
+            .execute(&app.db)
+            .await
+            .unwrap();
+        assert!(!xml.contains(&format!("/item/{item_id}")));
+    });
+}
@@ -6,7 +6,7 @@
 use trovato_kernel::content::{FilterPipeline, FormBuilder};
 use trovato_kernel::models::stage::LIVE_STAGE_ID;
 use trovato_kernel::models::{
-    CreateItem, CreateItemType, Item, ItemRevision, ItemType, UpdateItem,
+    CreateItem, CreateItemType, DEFAULT_TENANT_ID, Item, ItemRevision, ItemType, UpdateItem,
 };
 use trovato_kernel::tap::{RequestState, UserContext};
 use trovato_sdk::types::{
@@ -69,6 +69,8 @@ fn test_content_type() -> ContentTypeDefinition {
         label: "Blog Post".to_string(),
         description: "A blog article".to_string(),
         title_label: None,
+        cache: None,
+        field_groups: Vec::new(),
         fields: vec![
             FieldDefinition {
                 field_name: "body".to_string(),
@@ -78,6 +80,13 @@ fn test_content_type() -> ContentTypeDefinition {
                 cardinality: 1,
                 settings: serde_json::json!({}),
                 personal_data: false,
+                track_history: false,
+                search_weight: None,
+                widget: None,
+                display: None,
+                field_group: None,
+                default_value: None,
+                constraints: Default::default(),
             },
             FieldDefinition {
                 field_name: "summary".to_string(),
@@ -89,6 +98,13 @@ fn test_content_type() -> ContentTypeDefinition {
                 cardinality: 1,
                 settings: serde_json::json!({}),
                 personal_data: false,
+                track_history: false,
+                search_weight: None,
+                widget: None,
+                display: None,
+                field_group: None,
+                default_value: None,
+                constraints: Default::default(),
             },
             FieldDefinition {
                 field_name: "featured".to_string(),
@@ -98,8 +114,17 @@ fn test_content_type() -> ContentTypeDefinition {
                 cardinality: 1,
                 settings: serde_json::json!({}),
                 personal_data: false,
+                track_history: false,
+                search_weight: None,
+                widget: None,
+                display: None,
+                field_group: None,
+                default_value: None,
+                constraints: Default::default(),
             },
         ],
+        extends: None,
+        template: false,
     }
 }
 
@@ -163,6 +188,8 @@ fn form_builder_edit_form_includes_values() {
         language: "en".to_string(),
         item_group_id: Uuid::now_v7(),
         retention_days: None,
+        moderation_state: None,
+        tenant_id: DEFAULT_TENANT_ID,
     };
 
     let form = builder.build_edit_form(&item, "/item/123/edit");
@@ -202,6 +229,8 @@ fn item_status_checks() {
         language: "en".to_string(),
         item_group_id: Uuid::now_v7(),
         retention_days: None,
+        moderation_state: None,
+        tenant_id: DEFAULT_TENANT_ID,
     };
 
     assert!(item.is_published());
@@ -238,6 +267,7 @@ fn update_item_input_partial() {
         sticky: None,
         fields: None,
         log: Some("Changed title".to_string()),
+        status_meta: None,
     };
 
     assert!(input.title.is_some());
@@ -560,6 +590,8 @@ fn comprehensive_content_type() -> ContentTypeDefinition {
         label: "Test Content".to_string(),
         description: "Tests all field types".to_string(),
         title_label: None,
+        cache: None,
+        field_groups: Vec::new(),
         fields: vec![
             FieldDefinition::new("body", FieldType::TextLong)
                 .label("Body")
@@ -580,6 +612,8 @@ fn comprehensive_content_type() -> ContentTypeDefinition {
                 .label("Related Article"),
             FieldDefinition::new("attachment", FieldType::File).label("Attachment"),
         ],
+        extends: None,
+        template: false,
     }
 }
 
@@ -654,6 +688,8 @@ fn form_builder_edit_form_populates_all_types() {
         language: "en".to_string(),
         item_group_id: Uuid::now_v7(),
         retention_days: None,
+        moderation_state: None,
+        tenant_id: DEFAULT_TENANT_ID,
     };
 
     let form = builder.build_edit_form(&item, "/item/123/edit");
@@ -694,6 +730,8 @@ fn form_builder_escapes_html_in_values() {
         language: "en".to_string(),
         item_group_id: Uuid::now_v7(),
         retention_days: None,
+        moderation_state: None,
+        tenant_id: DEFAULT_TENANT_ID,
     };
 
     let form = builder.build_edit_form(&item, "/item/123/edit");
@@ -769,6 +807,8 @@ fn item_status_unpublished() {
         language: "en".to_string(),
         item_group_id: Uuid::now_v7(),
         retention_days: None,
+        moderation_state: None,
+        tenant_id: DEFAULT_TENANT_ID,
     };
 
     assert!(!item.is_published());
@@ -821,6 +861,7 @@ fn update_item_with_all_fields() {
         sticky: Some(0),
         fields: Some(serde_json::json!({"body": {"value": "Updated"}})),
         log: Some("Major revision".to_string()),
+        status_meta: None,
     };
 
     assert!(input.title.is_some());
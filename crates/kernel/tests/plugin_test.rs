@@ -31,7 +31,7 @@ use std::sync::Arc;
 use trovato_kernel::host;
 use trovato_kernel::menu::MenuRegistry;
 use trovato_kernel::plugin::{
-    PluginConfig, PluginInfo, PluginRuntime, PluginState, resolve_load_order,
+    PluginCapabilities, PluginConfig, PluginInfo, PluginRuntime, PluginState, resolve_load_order,
 };
 use trovato_kernel::tap::{RequestState, TapDispatcher, TapRegistry, UserContext};
 use uuid::Uuid;
@@ -54,6 +54,7 @@ fn create_runtime_custom_config() {
     let config = PluginConfig {
         max_instances: 100,
         max_memory_pages: 256,
+        ..Default::default()
     };
     let runtime = PluginRuntime::new(&config);
     assert!(
@@ -434,7 +435,7 @@ fn host_functions_register_all() {
     let engine = Engine::new(&config).unwrap();
     let mut linker: Linker<PluginState> = Linker::new(&engine);
 
-    let result = host::register_all(&mut linker);
+    let result = host::register_all(&mut linker, &PluginCapabilities::all());
     assert!(
         result.is_ok(),
         "Failed to register host functions: {:?}",
@@ -545,6 +546,9 @@ fn dependency_resolution_no_deps() {
             dependencies: vec![],
             taps: TapConfig::default(),
             migrations: trovato_kernel::plugin::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
         },
     );
     plugins.insert(
@@ -558,6 +562,9 @@ fn dependency_resolution_no_deps() {
             dependencies: vec![],
             taps: TapConfig::default(),
             migrations: trovato_kernel::plugin::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
         },
     );
 
@@ -582,6 +589,9 @@ fn dependency_resolution_with_deps() {
             dependencies: vec![],
             taps: TapConfig::default(),
             migrations: trovato_kernel::plugin::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
         },
     );
     plugins.insert(
@@ -595,6 +605,9 @@ fn dependency_resolution_with_deps() {
             dependencies: vec!["base".to_string()],
             taps: TapConfig::default(),
             migrations: trovato_kernel::plugin::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
         },
     );
 
@@ -623,6 +636,9 @@ fn dependency_resolution_circular() {
             dependencies: vec!["b".to_string()],
             taps: TapConfig::default(),
             migrations: trovato_kernel::plugin::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
         },
     );
     plugins.insert(
@@ -636,6 +652,9 @@ fn dependency_resolution_circular() {
             dependencies: vec!["a".to_string()],
             taps: TapConfig::default(),
             migrations: trovato_kernel::plugin::MigrationConfig::default(),
+            webhooks: HashMap::new(),
+            capabilities: PluginCapabilities::default(),
+            bulk_operations: HashMap::new(),
         },
     );
 
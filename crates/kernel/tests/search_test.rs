@@ -20,6 +20,7 @@ fn test_search_results_serde() {
                 title: "Test Page".to_string(),
                 rank: 0.5,
                 snippet: Some("<mark>test</mark> content here".to_string()),
+                fuzzy: false,
             },
             SearchResult {
                 id: Uuid::now_v7(),
@@ -27,6 +28,7 @@ fn test_search_results_serde() {
                 title: "Blog Post".to_string(),
                 rank: 0.3,
                 snippet: None,
+                fuzzy: true,
             },
         ],
         total: 2,
@@ -42,6 +44,7 @@ fn test_search_results_serde() {
     assert_eq!(parsed.results.len(), 2);
     assert_eq!(parsed.results[0].title, "Test Page");
     assert_eq!(parsed.results[1].title, "Blog Post");
+    assert!(parsed.results[1].fuzzy);
 }
 
 #[test]
@@ -55,6 +58,7 @@ fn test_search_result_fields() {
         title: "My Article".to_string(),
         rank: 0.75,
         snippet: Some("Article content".to_string()),
+        fuzzy: false,
     };
 
     assert_eq!(result.id, id);
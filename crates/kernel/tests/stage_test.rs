@@ -10,7 +10,10 @@ use uuid::Uuid;
 mod common;
 use common::{run_test, shared_app};
 
+use trovato_kernel::ConfigEntity;
+use trovato_kernel::config_storage::entity_types;
 use trovato_kernel::models::stage::{CreateStage, LIVE_STAGE_ID, Stage};
+use trovato_kernel::models::{Category, CreateTag, Tag};
 use trovato_kernel::{ConflictResolution, ConflictType, PublishPhase};
 
 /// Create a test stage in the DB and return its UUID.
@@ -798,3 +801,247 @@ fn stage_has_changes_with_config() {
         cleanup_stage(app, stage_id).await;
     });
 }
+
+/// Test scheduling, listing and cancelling a stage publish.
+#[test]
+fn stage_schedule_publish_lifecycle() {
+    run_test(async {
+        let app = shared_app().await;
+        let stage_id = create_test_stage(app, "scheduled").await;
+        let now = chrono::Utc::now().timestamp();
+
+        // Live can't be scheduled
+        assert!(
+            app.stage()
+                .schedule_publish(LIVE_STAGE_ID, now + 60, None)
+                .await
+                .is_err()
+        );
+
+        // Within the report lead time: awaiting a report but not yet due
+        let schedule = app
+            .stage()
+            .schedule_publish(stage_id, now + 60, None)
+            .await
+            .expect("schedule");
+        assert_eq!(schedule.publish_at, now + 60);
+        assert!(schedule.report_sent_at.is_none());
+
+        let awaiting = app
+            .stage()
+            .scheduled_publishes_awaiting_report(now)
+            .await
+            .expect("awaiting report");
+        assert!(awaiting.iter().any(|s| s.stage_id == stage_id));
+        let due = app.stage().due_scheduled_publishes(now).await.expect("due");
+        assert!(!due.iter().any(|s| s.stage_id == stage_id));
+
+        app.stage()
+            .mark_publish_reported(stage_id, now)
+            .await
+            .expect("mark reported");
+        let awaiting = app
+            .stage()
+            .scheduled_publishes_awaiting_report(now)
+            .await
+            .expect("awaiting report");
+        assert!(!awaiting.iter().any(|s| s.stage_id == stage_id));
+
+        // Rescheduling replaces the schedule and clears the sent report
+        let schedule = app
+            .stage()
+            .schedule_publish(stage_id, now - 1, None)
+            .await
+            .expect("reschedule");
+        assert!(schedule.report_sent_at.is_none());
+        let due = app.stage().due_scheduled_publishes(now).await.expect("due");
+        assert!(due.iter().any(|s| s.stage_id == stage_id));
+
+        // Cancelling removes it
+        assert!(
+            app.stage()
+                .cancel_scheduled_publish(stage_id)
+                .await
+                .expect("cancel")
+        );
+        assert!(
+            app.stage()
+                .scheduled_publish(stage_id)
+                .await
+                .expect("load")
+                .is_none()
+        );
+        assert!(
+            !app.stage()
+                .cancel_scheduled_publish(stage_id)
+                .await
+                .expect("cancel again")
+        );
+
+        cleanup_stage(app, stage_id).await;
+    });
+}
+
+/// Test that publishing applies staged categories and tags to live.
+#[test]
+fn stage_publish_applies_staged_categories() {
+    run_test(async {
+        let app = shared_app().await;
+        let stage_id = create_test_stage(app, "taxo").await;
+        let storage = app.state.config_storage_for_stage(stage_id);
+
+        let suffix = &Uuid::now_v7().simple().to_string()[..8];
+        let category_id = format!("taxo_{suffix}");
+        let now = chrono::Utc::now().timestamp();
+
+        // A live tag the stage deletes, in a live category
+        let doomed = Tag::create(
+            &app.db,
+            CreateTag {
+                category_id: "tags".to_string(),
+                label: format!("Doomed {suffix}"),
+                description: None,
+                slug: None,
+                weight: None,
+                parent_ids: None,
+            },
+        )
+        .await
+        .expect("create live tag");
+
+        // Stage a new category with one tag, and the deletion
+        storage
+            .save(&ConfigEntity::Category(Category {
+                id: category_id.clone(),
+                label: "Staged Category".to_string(),
+                description: None,
+                hierarchy: 0,
+                weight: 0,
+            }))
+            .await
+            .expect("stage category");
+        let staged_tag = Tag {
+            id: Uuid::now_v7(),
+            category_id: category_id.clone(),
+            label: "Staged Tag".to_string(),
+            description: None,
+            slug: Some(format!("staged-{suffix}")),
+            weight: 0,
+            created: now,
+            changed: now,
+        };
+        storage
+            .save(&ConfigEntity::Tag(staged_tag.clone()))
+            .await
+            .expect("stage tag");
+        storage
+            .delete(entity_types::TAG, &doomed.id.to_string())
+            .await
+            .expect("stage tag deletion");
+
+        // Live is untouched until publish
+        assert!(
+            !Category::exists(&app.db, &category_id)
+                .await
+                .expect("exists")
+        );
+        assert!(
+            Tag::find_by_id(&app.db, doomed.id)
+                .await
+                .expect("find")
+                .is_some()
+        );
+
+        let result = app.stage().publish(stage_id).await.expect("publish");
+        assert!(result.success, "publish failed: {:?}", result.error_message);
+        assert_eq!(result.config_published, 3);
+
+        assert!(
+            Category::exists(&app.db, &category_id)
+                .await
+                .expect("exists")
+        );
+        let published = Tag::find_by_id(&app.db, staged_tag.id)
+            .await
+            .expect("find")
+            .expect("staged tag should be live");
+        assert_eq!(published.label, "Staged Tag");
+        let roots = Tag::get_roots(&app.db, &category_id).await.expect("roots");
+        assert_eq!(roots.len(), 1, "published tag should be a root");
+        assert!(
+            Tag::find_by_id(&app.db, doomed.id)
+                .await
+                .expect("find")
+                .is_none()
+        );
+        assert!(!app.stage().has_changes(stage_id).await.expect("check"));
+
+        // Clean up
+        Category::delete(&app.db, &category_id).await.ok();
+        cleanup_stage(app, stage_id).await;
+    });
+}
+
+/// Test that a live edit to a staged tag is reported as a conflict.
+#[test]
+fn conflict_detection_live_modified_tag() {
+    run_test(async {
+        let app = shared_app().await;
+        let stage_id = create_test_stage(app, "tag_mod").await;
+        let storage = app.state.config_storage_for_stage(stage_id);
+
+        let tag = Tag::create(
+            &app.db,
+            CreateTag {
+                category_id: "tags".to_string(),
+                label: format!("Contested {}", &Uuid::now_v7().simple().to_string()[..8]),
+                description: None,
+                slug: None,
+                weight: None,
+                parent_ids: None,
+            },
+        )
+        .await
+        .expect("create live tag");
+
+        storage
+            .save(&ConfigEntity::Tag(Tag {
+                label: "Staged label".to_string(),
+                ..tag.clone()
+            }))
+            .await
+            .expect("stage tag");
+
+        // Live changes after staging
+        sqlx::query("UPDATE category_tag SET changed = changed + 3600 WHERE id = $1")
+            .bind(tag.id)
+            .execute(&app.db)
+            .await
+            .expect("touch live tag");
+
+        let conflicts = app
+            .stage()
+            .detect_conflicts(stage_id)
+            .await
+            .expect("detect should work");
+        let conflict = conflicts
+            .iter()
+            .find(|c| c.entity_type == "tag" && c.entity_id == tag.id.to_string())
+            .expect("tag conflict");
+        assert!(matches!(
+            conflict.conflict_type,
+            ConflictType::LiveModified { .. }
+        ));
+        let expected = format!("Tag: {}", tag.label);
+        assert_eq!(conflict.label.as_deref(), Some(expected.as_str()));
+
+        // Clean up
+        sqlx::query("DELETE FROM config_stage_association WHERE stage_id = $1")
+            .bind(stage_id)
+            .execute(&app.db)
+            .await
+            .ok();
+        Tag::delete(&app.db, tag.id).await.ok();
+        cleanup_stage(app, stage_id).await;
+    });
+}
@@ -21,6 +21,7 @@ use trovato_kernel::gather::{
     DisplayFormat, FilterOperator, FilterValue, GatherQuery, PagerConfig, PagerStyle,
     QueryDefinition, QueryDisplay, QueryFilter, QuerySort, SortDirection,
 };
+use trovato_kernel::models::AliasPattern;
 use trovato_kernel::models::stage::LIVE_STAGE_ID;
 
 mod common;
@@ -117,6 +118,8 @@ async fn seed_tutorial_data(app: &'static common::TestApp) {
                             footer: None,
                             canonical_url: None,
                             routes: Vec::new(),
+                            archive: None,
+                            exports: None,
                         },
                         plugin: "core".to_string(),
                         created: now,
@@ -843,9 +846,27 @@ fn test_part01_step04_gather_status_filter() {
 // - A second regeneration call skips items whose alias already matches
 // =============================================================================
 
-/// Serializes Step 5 tests that write to `site_config.pathauto_patterns`.
+/// Serializes Step 5 tests that write the conference alias pattern.
 static STEP5_PATHAUTO_LOCK: Mutex<()> = Mutex::const_new(());
 
+/// Set the tutorial's conference pattern, as Step 5 instructs.
+async fn set_conference_pattern(app: &common::TestApp) {
+    AliasPattern::upsert(
+        &app.db,
+        &AliasPattern::new("conference", None, "conferences/[title]"),
+    )
+    .await
+    .expect("failed to set pathauto pattern");
+}
+
+/// Run `update_alias_item` for an item, as "Regenerate aliases" does.
+async fn update_alias(app: &common::TestApp, id: uuid::Uuid) -> anyhow::Result<Option<String>> {
+    let item = trovato_kernel::models::Item::find_by_id(&app.db, id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("item {id} not found"))?;
+    trovato_kernel::services::pathauto::update_alias_item(&app.db, &item).await
+}
+
 #[test]
 fn test_part01_step05_pathauto_generates_conference_aliases() {
     run_test(async {
@@ -853,21 +874,14 @@ fn test_part01_step05_pathauto_generates_conference_aliases() {
         let app = shared_app().await;
         seed_tutorial_data(app).await;
 
-        // Snapshot original patterns so we can restore them after the test.
-        let original_patterns =
-            trovato_kernel::models::SiteConfig::get(&app.db, "pathauto_patterns")
-                .await
-                .expect("failed to read pathauto patterns");
+        // Snapshot the original pattern so we can restore it after the test.
+        let original_pattern = AliasPattern::find_by_id(&app.db, "conference")
+            .await
+            .expect("failed to read pathauto pattern");
 
         // Set the conference pathauto pattern (equivalent to Option B config import
         // or saving the form in Option A).
-        trovato_kernel::models::SiteConfig::set(
-            &app.db,
-            "pathauto_patterns",
-            serde_json::json!({"conference": "conferences/[title]"}),
-        )
-        .await
-        .expect("failed to set pathauto pattern");
+        set_conference_pattern(app).await;
 
         // Clear any existing conference aliases so update_alias_item creates
         // fresh ones rather than short-circuiting on an already-matching alias.
@@ -879,8 +893,8 @@ fn test_part01_step05_pathauto_generates_conference_aliases() {
         // Use the three hand-created tutorial conferences (no field_source_id).
         // After Part 2, the DB may have thousands of imported conferences; only
         // test pathauto on the original three.
-        let conferences: Vec<(uuid::Uuid, String, i64)> = sqlx::query_as(
-            "SELECT id, title, created FROM item WHERE type = 'conference' \
+        let conferences: Vec<(uuid::Uuid, String)> = sqlx::query_as(
+            "SELECT id, title FROM item WHERE type = 'conference' \
              AND (fields->>'field_source_id' IS NULL OR fields->>'field_source_id' = '') \
              AND title IN ('RustConf 2026', 'EuroRust 2026', 'WasmCon Online 2026') \
              ORDER BY title",
@@ -898,16 +912,10 @@ fn test_part01_step05_pathauto_generates_conference_aliases() {
         // Call update_alias_item for each — this is exactly what the admin
         // "Regenerate aliases" button invokes for every item of the type.
         let mut generated = std::collections::HashMap::new();
-        for (id, title, created) in &conferences {
-            let alias = trovato_kernel::services::pathauto::update_alias_item(
-                &app.db,
-                *id,
-                title,
-                "conference",
-                *created,
-            )
-            .await
-            .expect("update_alias_item should succeed");
+        for (id, title) in &conferences {
+            let alias = update_alias(app, *id)
+                .await
+                .expect("update_alias_item should succeed");
             assert!(
                 alias.is_some(),
                 "update_alias_item should generate an alias for '{title}'"
@@ -931,16 +939,16 @@ fn test_part01_step05_pathauto_generates_conference_aliases() {
             "WasmCon alias must match tutorial"
         );
 
-        // Restore original pathauto patterns.
-        match original_patterns {
-            Some(v) => trovato_kernel::models::SiteConfig::set(&app.db, "pathauto_patterns", v)
+        // Restore the original pathauto pattern.
+        match original_pattern {
+            Some(pattern) => AliasPattern::upsert(&app.db, &pattern)
                 .await
-                .expect("failed to restore pathauto patterns"),
-            None => sqlx::query("DELETE FROM site_config WHERE key = 'pathauto_patterns'")
-                .execute(&app.db)
+                .map(|_| ())
+                .expect("failed to restore pathauto pattern"),
+            None => AliasPattern::delete(&app.db, "conference")
                 .await
                 .map(|_| ())
-                .expect("failed to remove pathauto patterns"),
+                .expect("failed to remove pathauto pattern"),
         }
     });
 }
@@ -953,13 +961,7 @@ fn test_part01_step05_aliases_resolvable_by_middleware_lookup() {
         seed_tutorial_data(app).await;
 
         // Configure pattern and clear existing aliases.
-        trovato_kernel::models::SiteConfig::set(
-            &app.db,
-            "pathauto_patterns",
-            serde_json::json!({"conference": "conferences/[title]"}),
-        )
-        .await
-        .expect("failed to set pathauto pattern");
+        set_conference_pattern(app).await;
 
         sqlx::query("DELETE FROM url_alias WHERE alias LIKE '/conferences/%'")
             .execute(&app.db)
@@ -967,22 +969,16 @@ fn test_part01_step05_aliases_resolvable_by_middleware_lookup() {
             .expect("failed to clear conference aliases");
 
         // Regenerate aliases for all three conferences.
-        let conferences: Vec<(uuid::Uuid, String, i64)> =
-            sqlx::query_as("SELECT id, title, created FROM item WHERE type = 'conference'")
+        let conferences: Vec<uuid::Uuid> =
+            sqlx::query_scalar("SELECT id FROM item WHERE type = 'conference'")
                 .fetch_all(&app.db)
                 .await
                 .expect("failed to fetch conferences");
 
-        for (id, title, created) in &conferences {
-            trovato_kernel::services::pathauto::update_alias_item(
-                &app.db,
-                *id,
-                title,
-                "conference",
-                *created,
-            )
-            .await
-            .expect("update_alias_item should succeed");
+        for id in &conferences {
+            update_alias(app, *id)
+                .await
+                .expect("update_alias_item should succeed");
         }
 
         // Verify each expected alias is findable via find_by_alias_with_context,
@@ -1019,10 +1015,9 @@ fn test_part01_step05_aliases_resolvable_by_middleware_lookup() {
         }
 
         // Restore pathauto config.
-        sqlx::query("DELETE FROM site_config WHERE key = 'pathauto_patterns'")
-            .execute(&app.db)
+        AliasPattern::delete(&app.db, "conference")
             .await
-            .expect("failed to remove pathauto patterns");
+            .expect("failed to remove pathauto pattern");
     });
 }
 
@@ -1034,61 +1029,40 @@ fn test_part01_step05_regenerate_skips_already_matching_alias() {
         seed_tutorial_data(app).await;
 
         // Configure pattern and clear existing aliases.
-        trovato_kernel::models::SiteConfig::set(
-            &app.db,
-            "pathauto_patterns",
-            serde_json::json!({"conference": "conferences/[title]"}),
-        )
-        .await
-        .expect("failed to set pathauto pattern");
+        set_conference_pattern(app).await;
 
         sqlx::query("DELETE FROM url_alias WHERE alias LIKE '/conferences/%'")
             .execute(&app.db)
             .await
             .expect("failed to clear conference aliases");
 
-        let row: (uuid::Uuid, String, i64) = sqlx::query_as(
-            "SELECT id, title, created FROM item WHERE type = 'conference' AND title = 'RustConf 2026'",
+        let id: uuid::Uuid = sqlx::query_scalar(
+            "SELECT id FROM item WHERE type = 'conference' AND title = 'RustConf 2026'",
         )
         .fetch_one(&app.db)
         .await
         .expect("RustConf 2026 must exist");
 
-        let (id, title, created) = row;
-
         // First call: creates the alias.
-        let first = trovato_kernel::services::pathauto::update_alias_item(
-            &app.db,
-            id,
-            &title,
-            "conference",
-            created,
-        )
-        .await
-        .expect("first update_alias_item should succeed");
+        let first = update_alias(app, id)
+            .await
+            .expect("first update_alias_item should succeed");
         assert!(first.is_some(), "first call should create the alias");
 
         // Second call on the same item: alias already matches — must return None.
         // Tutorial: "Items whose alias already matches the current pattern are
         // skipped automatically."
-        let second = trovato_kernel::services::pathauto::update_alias_item(
-            &app.db,
-            id,
-            &title,
-            "conference",
-            created,
-        )
-        .await
-        .expect("second update_alias_item should succeed");
+        let second = update_alias(app, id)
+            .await
+            .expect("second update_alias_item should succeed");
         assert!(
             second.is_none(),
             "second call should return None (alias already matches)"
         );
 
         // Restore pathauto config.
-        sqlx::query("DELETE FROM site_config WHERE key = 'pathauto_patterns'")
-            .execute(&app.db)
+        AliasPattern::delete(&app.db, "conference")
             .await
-            .expect("failed to remove pathauto patterns");
+            .expect("failed to remove pathauto pattern");
     });
 }
@@ -43,6 +43,7 @@ pub async fn run_gather(
         current_user_id: Some(user_ctx.id),
         url_args: HashMap::new(),
         language: None,
+        archive: None,
     };
 
     let result = state
@@ -208,6 +208,7 @@ pub async fn update_item(
         sticky: None,
         fields: params.fields,
         log: params.log.or(Some("Updated via MCP".to_string())),
+        status_meta: None,
     };
 
     // ItemService::update loads the item, checks "edit" access via
@@ -275,6 +276,7 @@ fn item_summary(item: &Item) -> serde_json::Value {
 mod tests {
     use super::*;
     use rmcp::model::ErrorCode;
+    use trovato_kernel::models::DEFAULT_TENANT_ID;
 
     // =========================================================================
     // parse_uuid
@@ -390,6 +392,8 @@ mod tests {
             language: "en".to_string(),
             item_group_id: Uuid::new_v4(),
             retention_days: None,
+            moderation_state: None,
+            tenant_id: DEFAULT_TENANT_ID,
         }
     }
 
@@ -16,6 +16,9 @@ use syn::{FnArg, ItemFn, PatType, parse_macro_input};
 /// 4. Serializes the result to JSON
 /// 5. Returns ptr<<32|len encoding
 ///
+/// The output buffer grows to fit the serialized result, so large outputs
+/// (big `tap_item_info` sets, long render trees) are returned intact.
+///
 /// # Example
 ///
 /// ```ignore
@@ -128,28 +131,10 @@ fn expand_tap(input_fn: ItemFn) -> proc_macro2::TokenStream {
         #[unsafe(no_mangle)]
         #fn_vis extern "C" fn #fn_name(ptr: i32, len: i32) -> i64 {
             // Helper to write output and return ptr<<32|len.
-            // Errors (UTF-8, deserialization, overflow) are returned through the
+            // Errors (UTF-8, deserialization) are returned through the
             // normal encoding — the host detects them by parsing for {"error": ...}.
             fn write_output(s: &str) -> i64 {
-                static mut OUTPUT_BUFFER: [u8; 65536] = [0u8; 65536];
-                let bytes = s.as_bytes();
-                if bytes.len() > 65536 {
-                    let error = b"{\"error\": \"output exceeds 64KB buffer\"}";
-                    // SAFETY: WASM is single-threaded; no concurrent access.
-                    // The host reads this buffer synchronously before the next call.
-                    unsafe {
-                        OUTPUT_BUFFER[..error.len()].copy_from_slice(error);
-                        let ptr = OUTPUT_BUFFER.as_ptr() as i64;
-                        return (ptr << 32) | (error.len() as i64);
-                    }
-                }
-                // SAFETY: WASM is single-threaded; no concurrent access.
-                // The host reads this buffer synchronously before the next call.
-                unsafe {
-                    OUTPUT_BUFFER[..bytes.len()].copy_from_slice(bytes);
-                    let ptr = OUTPUT_BUFFER.as_ptr() as i64;
-                    (ptr << 32) | (bytes.len() as i64)
-                }
+                trovato_sdk::abi::write_output(s, false)
             }
 
             #wrapper_body
@@ -249,27 +234,7 @@ fn expand_tap_result(input_fn: ItemFn) -> proc_macro2::TokenStream {
         #fn_vis extern "C" fn #fn_name(ptr: i32, len: i32) -> i64 {
             // Errors are signaled via negative length encoding (is_error = true).
             fn write_output(s: &str, is_error: bool) -> i64 {
-                static mut OUTPUT_BUFFER: [u8; 65536] = [0u8; 65536];
-                let bytes = s.as_bytes();
-                if bytes.len() > 65536 {
-                    let error = b"{\"error\": \"output exceeds 64KB buffer\"}";
-                    // SAFETY: WASM is single-threaded; no concurrent access.
-                    // The host reads this buffer synchronously before the next call.
-                    unsafe {
-                        OUTPUT_BUFFER[..error.len()].copy_from_slice(error);
-                        let ptr = OUTPUT_BUFFER.as_ptr() as i64;
-                        let len = -(error.len() as i64);
-                        return (ptr << 32) | (len & 0xFFFFFFFF);
-                    }
-                }
-                // SAFETY: WASM is single-threaded; no concurrent access.
-                // The host reads this buffer synchronously before the next call.
-                unsafe {
-                    OUTPUT_BUFFER[..bytes.len()].copy_from_slice(bytes);
-                    let ptr = OUTPUT_BUFFER.as_ptr() as i64;
-                    let len = if is_error { -(bytes.len() as i64) } else { bytes.len() as i64 };
-                    (ptr << 32) | (len & 0xFFFFFFFF)
-                }
+                trovato_sdk::abi::write_output(s, is_error)
             }
 
             #wrapper_body
@@ -334,11 +299,15 @@ mod tests {
     }
 
     #[test]
-    fn tap_generates_overflow_check() {
+    fn tap_output_is_not_size_limited() {
         let output = expand_tap_str("fn my_tap(input: String) -> String { input }");
         assert!(
-            output.contains("output exceeds 64KB"),
-            "should check for buffer overflow"
+            output.contains("abi :: write_output"),
+            "should write output through the SDK buffer"
+        );
+        assert!(
+            !output.contains("OUTPUT_BUFFER"),
+            "should not use a fixed-size static buffer"
         );
     }
 
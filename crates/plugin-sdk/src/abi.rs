@@ -0,0 +1,66 @@
+//! Tap export ABI used by the `plugin_tap` macros.
+//!
+//! A tap export returns its output as `ptr << 32 | len` pointing into WASM
+//! memory, with a negative length marking an error. The output is kept in a
+//! growable buffer until the next tap call, so the host can read it after
+//! the export returns and outputs of any size round-trip intact.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static OUTPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Store tap output for the host and return its `ptr << 32 | len` encoding.
+///
+/// Output longer than `i32::MAX` bytes cannot be encoded and is replaced by
+/// an error.
+pub fn write_output(output: &str, is_error: bool) -> i64 {
+    let (output, is_error) = if i32::try_from(output.len()).is_ok() {
+        (output, is_error)
+    } else {
+        ("{\"error\": \"output too large\"}", true)
+    };
+    OUTPUT.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        buffer.clear();
+        buffer.extend_from_slice(output.as_bytes());
+        let len = buffer.len() as i64;
+        let len = if is_error { -len } else { len };
+        ((buffer.as_ptr() as i64) << 32) | (len & 0xFFFF_FFFF)
+    })
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn stored_output() -> String {
+        OUTPUT.with(|buffer| String::from_utf8(buffer.borrow().clone()).unwrap())
+    }
+
+    #[test]
+    fn megabyte_output_round_trips() {
+        let rows: Vec<String> = (0..1024).map(|i| format!("{i:01024}")).collect();
+        let json = serde_json::to_string(&rows).unwrap();
+        assert!(json.len() > 1024 * 1024);
+
+        let encoded = write_output(&json, false);
+        assert_eq!(encoded & 0xFFFF_FFFF, json.len() as i64);
+        let ptr = OUTPUT.with(|buffer| buffer.borrow().as_ptr() as i64);
+        assert_eq!(encoded >> 32, (ptr << 32) >> 32);
+
+        let decoded: Vec<String> = serde_json::from_str(&stored_output()).unwrap();
+        assert_eq!(decoded, rows);
+    }
+
+    #[test]
+    fn errors_use_negative_length() {
+        let error = "{\"error\": \"boom\"}";
+        let encoded = write_output(error, true);
+        assert_eq!((encoded & 0xFFFF_FFFF) as u32 as i32, -(error.len() as i32));
+        assert_eq!(stored_output(), error);
+    }
+}
@@ -0,0 +1,415 @@
+//! Form definitions exchanged with the form taps.
+//!
+//! `tap_form_alter` receives the [`Form`] the kernel is about to render and
+//! returns it, modified. Handlers run in weight order, each seeing the form
+//! as altered by the previous one. Item forms have the ID
+//! `item_add_{type}` or `item_edit_{type}`; configuration forms start with
+//! `config_` (the site settings form is `config_site`).
+//!
+//! Item and configuration forms are laid out by their own templates. The
+//! kernel describes their fields as elements holding the current values and
+//! renders every element a plugin adds after them; on item forms, plugins
+//! may also set a `prefix` or `suffix` on the title and fields. Added
+//! elements are filled in with stored or submitted values of the same name.
+//! Their values are passed to `tap_form_validate` and `tap_form_submit`;
+//! on item forms, those whose name does not start with `_` are also saved
+//! as item fields.
+//!
+//! SYNC: field names and types must match `crates/kernel/src/form/types.rs`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// A complete form definition.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Form {
+    pub form_id: String,
+    /// Unique ID of this form instance.
+    #[serde(default)]
+    pub form_build_id: String,
+    #[serde(default)]
+    pub action: String,
+    #[serde(default)]
+    pub method: String,
+    /// Elements keyed by name.
+    #[serde(default)]
+    pub elements: BTreeMap<String, FormElement>,
+    /// CSRF token; plugins should leave it alone.
+    #[serde(default)]
+    pub token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Value>,
+    #[serde(default)]
+    pub dirty: bool,
+}
+
+impl Form {
+    /// Whether this is an item add or edit form.
+    pub fn is_item_form(&self) -> bool {
+        self.item_type().is_some()
+    }
+
+    /// Content type of an item add or edit form.
+    pub fn item_type(&self) -> Option<&str> {
+        self.form_id
+            .strip_prefix("item_add_")
+            .or_else(|| self.form_id.strip_prefix("item_edit_"))
+    }
+
+    /// Add an element, replacing any element of the same name.
+    pub fn add_element(&mut self, name: impl Into<String>, element: FormElement) {
+        self.elements.insert(name.into(), element);
+    }
+
+    /// Get a mutable reference to an element.
+    pub fn element_mut(&mut self, name: &str) -> Option<&mut FormElement> {
+        self.elements.get_mut(name)
+    }
+}
+
+/// A form element definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormElement {
+    /// Element type with type-specific configuration.
+    #[serde(flatten)]
+    pub element_type: ElementType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<Value>,
+    #[serde(default)]
+    pub required: bool,
+    /// Sort weight (lower = appears first).
+    #[serde(default)]
+    pub weight: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<Value>,
+    /// Child elements of fieldsets and containers.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub children: BTreeMap<String, FormElement>,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    /// Markup displayed before the element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Markup displayed after the element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ajax: Option<AjaxConfig>,
+}
+
+impl FormElement {
+    fn new(element_type: ElementType) -> Self {
+        Self {
+            element_type,
+            title: None,
+            description: None,
+            default_value: None,
+            required: false,
+            weight: 0,
+            attributes: None,
+            children: BTreeMap::new(),
+            disabled: false,
+            placeholder: None,
+            prefix: None,
+            suffix: None,
+            ajax: None,
+        }
+    }
+
+    /// Single-line text input.
+    pub fn textfield() -> Self {
+        Self::new(ElementType::Textfield { max_length: None })
+    }
+
+    /// Multi-line text input.
+    pub fn textarea(rows: u32) -> Self {
+        Self::new(ElementType::Textarea { rows })
+    }
+
+    /// Drop-down list of `(value, label)` options.
+    pub fn select(options: Vec<(String, String)>) -> Self {
+        Self::new(ElementType::Select {
+            options,
+            multiple: false,
+        })
+    }
+
+    /// Single checkbox.
+    pub fn checkbox() -> Self {
+        Self::new(ElementType::Checkbox)
+    }
+
+    /// Radio buttons for `(value, label)` options.
+    pub fn radio(options: Vec<(String, String)>) -> Self {
+        Self::new(ElementType::Radio { options })
+    }
+
+    /// Hidden input.
+    pub fn hidden() -> Self {
+        Self::new(ElementType::Hidden)
+    }
+
+    /// Group of child elements.
+    pub fn fieldset() -> Self {
+        Self::new(ElementType::Fieldset {
+            collapsible: false,
+            collapsed: false,
+        })
+    }
+
+    /// Display-only markup.
+    pub fn markup(value: impl Into<String>) -> Self {
+        Self::new(ElementType::Markup {
+            value: value.into(),
+        })
+    }
+
+    /// Set the label.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the help text.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the initial value.
+    pub fn default_value(mut self, value: impl Into<Value>) -> Self {
+        self.default_value = Some(value.into());
+        self
+    }
+
+    /// Mark the element as required.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set the sort weight.
+    pub fn weight(mut self, weight: i32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set the placeholder text.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Add a child element (for fieldsets).
+    pub fn child(mut self, name: impl Into<String>, element: FormElement) -> Self {
+        self.children.insert(name.into(), element);
+        self
+    }
+
+    /// Type name as used in the `type` key (e.g. `"textfield"`).
+    pub fn type_name(&self) -> &'static str {
+        match self.element_type {
+            ElementType::Textfield { .. } => "textfield",
+            ElementType::Textarea { .. } => "textarea",
+            ElementType::Select { .. } => "select",
+            ElementType::Checkbox => "checkbox",
+            ElementType::Checkboxes { .. } => "checkboxes",
+            ElementType::Radio { .. } => "radio",
+            ElementType::Hidden => "hidden",
+            ElementType::Password => "password",
+            ElementType::File => "file",
+            ElementType::Submit { .. } => "submit",
+            ElementType::Fieldset { .. } => "fieldset",
+            ElementType::Markup { .. } => "markup",
+            ElementType::Container => "container",
+        }
+    }
+}
+
+/// Element types with their type-specific configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ElementType {
+    Textfield {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_length: Option<usize>,
+    },
+    Textarea {
+        rows: u32,
+    },
+    Select {
+        options: Vec<(String, String)>,
+        #[serde(default)]
+        multiple: bool,
+    },
+    Checkbox,
+    Checkboxes {
+        options: Vec<(String, String)>,
+    },
+    Radio {
+        options: Vec<(String, String)>,
+    },
+    Hidden,
+    Password,
+    File,
+    Submit {
+        value: String,
+    },
+    Fieldset {
+        #[serde(default)]
+        collapsible: bool,
+        #[serde(default)]
+        collapsed: bool,
+    },
+    Markup {
+        value: String,
+    },
+    Container,
+}
+
+/// AJAX callback configuration of an element.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AjaxConfig {
+    pub callback: String,
+    #[serde(default = "default_ajax_event")]
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapper: Option<String>,
+    #[serde(default = "default_true")]
+    pub progress: bool,
+}
+
+fn default_ajax_event() -> String {
+    "click".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Input to `tap_form_validate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormValidateInput {
+    pub form_id: String,
+    /// Submitted values by element name.
+    #[serde(default)]
+    pub values: serde_json::Map<String, Value>,
+}
+
+/// Output of `tap_form_validate`; no errors accepts the submission.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FormValidateResult {
+    #[serde(default)]
+    pub errors: Vec<FormError>,
+}
+
+impl FormValidateResult {
+    /// Reject the submission with an error about an element.
+    pub fn field_error(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.errors.push(FormError {
+            field: Some(field.into()),
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Reject the submission with an error about the whole form.
+    pub fn form_error(mut self, message: impl Into<String>) -> Self {
+        self.errors.push(FormError {
+            field: None,
+            message: message.into(),
+        });
+        self
+    }
+}
+
+/// A validation error returned by `tap_form_validate`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormError {
+    /// Element the error is about; `None` for the whole form.
+    #[serde(default)]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Input to `tap_form_submit`, sent once a submission has been accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormSubmitInput {
+    pub form_id: String,
+    #[serde(default)]
+    pub values: serde_json::Map<String, Value>,
+    /// The saved item, on item forms.
+    #[serde(default)]
+    pub item_id: Option<Uuid>,
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn form_round_trips_kernel_json() {
+        let json = serde_json::json!({
+            "form_id": "item_edit_page",
+            "form_build_id": "b1",
+            "action": "/admin/content/1/edit",
+            "method": "post",
+            "token": "t",
+            "dirty": false,
+            "elements": {
+                "title": {"type": "textfield", "title": "Title", "required": true, "weight": 0, "disabled": false},
+                "field_tags": {"type": "select", "options": [["a", "A"]], "multiple": true, "required": false, "weight": 1, "disabled": false},
+                "help": {"type": "markup", "value": "<p>Hi</p>", "required": false, "weight": 2, "disabled": false},
+            }
+        });
+        let form: Form = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(form.item_type(), Some("page"));
+        assert_eq!(form.elements["field_tags"].type_name(), "select");
+        assert_eq!(serde_json::to_value(&form).unwrap(), json);
+    }
+
+    #[test]
+    fn added_elements_serialize_with_type_tag() {
+        let mut form = Form {
+            form_id: "config_site".into(),
+            ..Default::default()
+        };
+        assert!(!form.is_item_form());
+        form.add_element(
+            "_schedule",
+            FormElement::fieldset()
+                .title("Schedule")
+                .child("publish_on", FormElement::textfield().weight(1)),
+        );
+        let json = serde_json::to_value(&form).unwrap();
+        assert_eq!(json["elements"]["_schedule"]["type"], "fieldset");
+        assert_eq!(
+            json["elements"]["_schedule"]["children"]["publish_on"]["type"],
+            "textfield"
+        );
+    }
+
+    #[test]
+    fn validate_result_collects_errors() {
+        let result = FormValidateResult::default()
+            .field_error("title", "Too short.")
+            .form_error("Try again.");
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["errors"][0]["field"], "title");
+        assert!(json["errors"][1]["field"].is_null());
+    }
+}
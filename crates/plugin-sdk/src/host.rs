@@ -2,11 +2,16 @@
 //!
 //! These functions are only usable when compiled for `wasm32` targets.
 //! On native targets, stub implementations are provided for testing.
+//!
+//! Fallible wrappers return a [`HostError`] decoded from the host's
+//! negative return code (see [`crate::host_errors`]).
+
+use crate::host_errors::HostError;
 
 /// Maximum output buffer size for host function results (256KB).
 ///
 /// If a host function fills the entire buffer, the SDK returns
-/// [`crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED`] rather than
+/// [`HostError::Other`] with [`crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED`] rather than
 /// silently returning truncated data. Plugins should reduce result set
 /// size (add SQL LIMIT) or paginate.
 #[cfg(target_arch = "wasm32")]
@@ -31,6 +36,25 @@ unsafe extern "C" {
         out_ptr: i32,
         out_max_len: i32,
     ) -> i32;
+
+    #[link_name = "begin"]
+    fn __db_begin() -> i32;
+
+    #[link_name = "commit"]
+    fn __db_commit() -> i32;
+
+    #[link_name = "rollback"]
+    fn __db_rollback() -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/item-api")]
+unsafe extern "C" {
+    #[link_name = "item-query"]
+    fn __item_query(query_ptr: i32, query_len: i32, out_ptr: i32, out_max_len: i32) -> i32;
+
+    #[link_name = "save-item"]
+    fn __save_item(item_ptr: i32, item_len: i32, out_ptr: i32, out_max_len: i32) -> i32;
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -40,6 +64,36 @@ unsafe extern "C" {
     fn __ai_request(req_ptr: i32, req_len: i32, out_ptr: i32, out_max_len: i32) -> i32;
 }
 
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/cache-api")]
+unsafe extern "C" {
+    #[link_name = "get"]
+    fn __cache_get(
+        bin_ptr: i32,
+        bin_len: i32,
+        key_ptr: i32,
+        key_len: i32,
+        out_ptr: i32,
+        out_max_len: i32,
+    ) -> i32;
+
+    #[link_name = "set"]
+    fn __cache_set(
+        bin_ptr: i32,
+        bin_len: i32,
+        key_ptr: i32,
+        key_len: i32,
+        value_ptr: i32,
+        value_len: i32,
+        tags_ptr: i32,
+        tags_len: i32,
+        ttl_secs: i32,
+    ) -> i32;
+
+    #[link_name = "invalidate-tag"]
+    fn __cache_invalidate_tag(tag_ptr: i32, tag_len: i32);
+}
+
 #[cfg(target_arch = "wasm32")]
 #[link(wasm_import_module = "trovato:kernel/http")]
 unsafe extern "C" {
@@ -100,6 +154,77 @@ unsafe extern "C" {
     fn __variables_set(name_ptr: i32, name_len: i32, value_ptr: i32, value_len: i32) -> i32;
 }
 
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/slug")]
+unsafe extern "C" {
+    #[link_name = "slugify"]
+    fn __slugify(
+        text_ptr: i32,
+        text_len: i32,
+        lang_ptr: i32,
+        lang_len: i32,
+        out_ptr: i32,
+        out_max_len: i32,
+    ) -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/vector")]
+unsafe extern "C" {
+    #[link_name = "embedding-store"]
+    fn __embedding_store(id_ptr: i32, id_len: i32, vec_ptr: i32, vec_len: i32) -> i32;
+
+    #[link_name = "embedding-search"]
+    fn __embedding_search(
+        vec_ptr: i32,
+        vec_len: i32,
+        k: i32,
+        out_ptr: i32,
+        out_max_len: i32,
+    ) -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/render")]
+unsafe extern "C" {
+    #[link_name = "begin"]
+    fn __render_begin() -> i32;
+
+    #[link_name = "append"]
+    fn __render_append(handle: i32, element_ptr: i32, element_len: i32) -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/events")]
+unsafe extern "C" {
+    #[link_name = "dispatch"]
+    fn __events_dispatch(name_ptr: i32, name_len: i32, payload_ptr: i32, payload_len: i32) -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/device")]
+unsafe extern "C" {
+    #[link_name = "mac-vendor"]
+    fn __device_mac_vendor(mac_ptr: i32, mac_len: i32, out_ptr: i32, out_max_len: i32) -> i32;
+
+    #[link_name = "classify"]
+    fn __device_classify(device_ptr: i32, device_len: i32, out_ptr: i32, out_max_len: i32) -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/saved-search")]
+unsafe extern "C" {
+    #[link_name = "check-alerts"]
+    fn __saved_search_check_alerts() -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "trovato:kernel/scheduled-update")]
+unsafe extern "C" {
+    #[link_name = "apply-due"]
+    fn __scheduled_update_apply_due() -> i32;
+}
+
 // --------------------------------------------------------------------------
 // Ergonomic wrappers
 // --------------------------------------------------------------------------
@@ -110,11 +235,13 @@ unsafe extern "C" {
 ///
 /// # Errors
 ///
-/// Returns the host error code (negative i32) on failure.
+/// Returns the decoded [`HostError`] on failure. A statement the database
+/// rejects is [`HostError::QueryError`] with its SQLSTATE (so unique
+/// violations can be told apart); DDL is [`HostError::PermissionDenied`].
 #[cfg(target_arch = "wasm32")]
-pub fn execute_raw(sql: &str, params: &[serde_json::Value]) -> Result<u64, i32> {
-    let params_json =
-        serde_json::to_string(params).map_err(|_| crate::host_errors::ERR_SDK_SERIALIZE)?;
+pub fn execute_raw(sql: &str, params: &[serde_json::Value]) -> Result<u64, HostError> {
+    let params_json = serde_json::to_string(params)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
     let result = unsafe {
         __db_execute_raw(
             sql.as_ptr() as i32,
@@ -124,7 +251,7 @@ pub fn execute_raw(sql: &str, params: &[serde_json::Value]) -> Result<u64, i32>
         )
     };
     if result < 0 {
-        Err(result as i32)
+        Err(HostError::from_code(result as i32))
     } else {
         Ok(result as u64)
     }
@@ -136,11 +263,12 @@ pub fn execute_raw(sql: &str, params: &[serde_json::Value]) -> Result<u64, i32>
 ///
 /// # Errors
 ///
-/// Returns the host error code (negative i32) on failure.
+/// Returns the decoded [`HostError`] on failure; failed statements are
+/// reported as for [`execute_raw`].
 #[cfg(target_arch = "wasm32")]
-pub fn query_raw(sql: &str, params: &[serde_json::Value]) -> Result<String, i32> {
-    let params_json =
-        serde_json::to_string(params).map_err(|_| crate::host_errors::ERR_SDK_SERIALIZE)?;
+pub fn query_raw(sql: &str, params: &[serde_json::Value]) -> Result<String, HostError> {
+    let params_json = serde_json::to_string(params)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
     let mut buf = vec![0u8; MAX_OUTPUT_BUFFER];
     let result = unsafe {
         __db_query_raw(
@@ -153,14 +281,179 @@ pub fn query_raw(sql: &str, params: &[serde_json::Value]) -> Result<String, i32>
         )
     };
     if result < 0 {
-        Err(result)
+        Err(HostError::from_code(result))
+    } else {
+        let len = result as usize;
+        if len >= MAX_OUTPUT_BUFFER {
+            return Err(HostError::from_code(
+                crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED,
+            ));
+        }
+        buf.truncate(len);
+        String::from_utf8(buf).map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))
+    }
+}
+
+/// Run `f` inside a database transaction.
+///
+/// Statements `f` runs through [`execute_raw`] and [`query_raw`] are
+/// committed together if it returns `Ok` and rolled back if it returns
+/// `Err`. A failed statement does not abort the transaction, so `f` may
+/// handle the error and carry on. If the tap traps or returns without
+/// committing, the kernel rolls the transaction back.
+///
+/// Only database host calls take part: items saved with [`save_item`],
+/// cache writes, queued jobs and HTTP requests are not undone.
+///
+/// # Errors
+///
+/// Returns `f`'s error, or the [`HostError`] from starting or committing
+/// the transaction. Transactions do not nest: calling this from inside `f`
+/// fails with [`crate::host_errors::ERR_TRANSACTION_OPEN`].
+pub fn transaction<T, E: From<HostError>>(f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    begin_transaction()?;
+    match f() {
+        Ok(value) => {
+            commit_transaction()?;
+            Ok(value)
+        }
+        Err(e) => {
+            // The kernel rolls back at the end of the tap if this fails.
+            let _ = rollback_transaction();
+            Err(e)
+        }
+    }
+}
+
+/// Start a database transaction for the rest of this tap call.
+///
+/// Prefer [`transaction`], which commits or rolls back for you.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure, including when a
+/// transaction is already open.
+#[cfg(target_arch = "wasm32")]
+pub fn begin_transaction() -> Result<(), HostError> {
+    let result = unsafe { __db_begin() };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Commit the open database transaction.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure, including when no
+/// transaction is open. A transaction that fails to commit is rolled back.
+#[cfg(target_arch = "wasm32")]
+pub fn commit_transaction() -> Result<(), HostError> {
+    let result = unsafe { __db_commit() };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Roll back the open database transaction.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure, including when no
+/// transaction is open.
+#[cfg(target_arch = "wasm32")]
+pub fn rollback_transaction() -> Result<(), HostError> {
+    let result = unsafe { __db_rollback() };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Query items through the kernel with stage resolution and access checks.
+///
+/// Prefer this over [`query_raw`] for reading items: the kernel builds the
+/// SQL, restricts results to the requested stage (overlaid on live), and
+/// hides unpublished content from non-admin users. At most
+/// [`crate::types::ITEM_QUERY_MAX_LIMIT`] items are returned per call.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure. [`HostError::Other`] with
+/// [`crate::host_errors::ERR_INVALID_IDENTIFIER`] means a field or sort
+/// name was invalid or the stage does not exist.
+#[cfg(target_arch = "wasm32")]
+pub fn item_query(query: &crate::types::ItemQuery) -> Result<Vec<crate::types::Item>, HostError> {
+    let query_json = serde_json::to_string(query)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    let mut buf = vec![0u8; MAX_OUTPUT_BUFFER];
+    let result = unsafe {
+        __item_query(
+            query_json.as_ptr() as i32,
+            query_json.len() as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
     } else {
         let len = result as usize;
         if len >= MAX_OUTPUT_BUFFER {
-            return Err(crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED);
+            return Err(HostError::from_code(
+                crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED,
+            ));
         }
         buf.truncate(len);
-        String::from_utf8(buf).map_err(|_| crate::host_errors::ERR_SDK_UTF8)
+        let json = String::from_utf8(buf)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))?;
+        serde_json::from_str(&json)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_DESERIALIZE))
+    }
+}
+
+/// Create or update an item through the kernel.
+///
+/// `item` is a JSON object with `type`, `title`, `status` and `fields`;
+/// include a non-nil `id` to update that item instead. The item is saved
+/// directly, without dispatching item taps, so this is safe to call from
+/// inside a tap handler. Returns `None` if the item to update does not exist.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure.
+#[cfg(target_arch = "wasm32")]
+pub fn save_item(item: &serde_json::Value) -> Result<Option<crate::types::Item>, HostError> {
+    let item_json = serde_json::to_string(item)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    let mut buf = vec![0u8; MAX_OUTPUT_BUFFER];
+    let result = unsafe {
+        __save_item(
+            item_json.as_ptr() as i32,
+            item_json.len() as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        let len = result as usize;
+        if len >= MAX_OUTPUT_BUFFER {
+            return Err(HostError::from_code(
+                crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED,
+            ));
+        }
+        buf.truncate(len);
+        let json = String::from_utf8(buf)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))?;
+        serde_json::from_str(&json)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_DESERIALIZE))
     }
 }
 
@@ -172,14 +465,15 @@ pub fn query_raw(sql: &str, params: &[serde_json::Value]) -> Result<String, i32>
 ///
 /// # Errors
 ///
-/// Returns the host error code (negative i32) on failure. See
-/// [`crate::host_errors`] for HTTP-specific error codes (`ERR_HTTP_*`).
+/// Returns the decoded [`HostError`] on failure: [`HostError::Timeout`] when
+/// the request timed out, otherwise [`HostError::Other`] with one of the
+/// `ERR_HTTP_*` codes in [`crate::host_errors`].
 #[cfg(target_arch = "wasm32")]
 pub fn http_request(
     request: &crate::types::HttpRequest,
-) -> Result<crate::types::HttpResponse, i32> {
-    let request_json =
-        serde_json::to_string(request).map_err(|_| crate::host_errors::ERR_SDK_SERIALIZE)?;
+) -> Result<crate::types::HttpResponse, HostError> {
+    let request_json = serde_json::to_string(request)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
     let mut buf = vec![0u8; MAX_OUTPUT_BUFFER];
     let result = unsafe {
         __http_request(
@@ -190,15 +484,19 @@ pub fn http_request(
         )
     };
     if result < 0 {
-        Err(result)
+        Err(HostError::from_code(result))
     } else {
         let len = result as usize;
         if len >= MAX_OUTPUT_BUFFER {
-            return Err(crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED);
+            return Err(HostError::from_code(
+                crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED,
+            ));
         }
         buf.truncate(len);
-        let json = String::from_utf8(buf).map_err(|_| crate::host_errors::ERR_SDK_UTF8)?;
-        serde_json::from_str(&json).map_err(|_| crate::host_errors::ERR_SDK_DESERIALIZE)
+        let json = String::from_utf8(buf)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))?;
+        serde_json::from_str(&json)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_DESERIALIZE))
     }
 }
 
@@ -210,12 +508,12 @@ pub fn http_request(
 ///
 /// # Errors
 ///
-/// Returns a negative error code if the kernel rejects the push (bad JSON,
-/// DB error, etc.).
+/// Returns a [`HostError`] if the kernel rejects the push (bad JSON, DB
+/// error, etc.).
 #[cfg(target_arch = "wasm32")]
-pub fn queue_push(queue_name: &str, payload: &serde_json::Value) -> Result<(), i32> {
-    let payload_json =
-        serde_json::to_string(payload).map_err(|_| crate::host_errors::ERR_SDK_SERIALIZE)?;
+pub fn queue_push(queue_name: &str, payload: &serde_json::Value) -> Result<(), HostError> {
+    let payload_json = serde_json::to_string(payload)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
     let result = unsafe {
         __queue_push(
             queue_name.as_ptr() as i32,
@@ -224,12 +522,16 @@ pub fn queue_push(queue_name: &str, payload: &serde_json::Value) -> Result<(), i
             payload_json.len() as i32,
         )
     };
-    if result < 0 { Err(result) } else { Ok(()) }
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
 }
 
 /// Push a job onto a named plugin queue (stub for native testing, always succeeds).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn queue_push(_queue_name: &str, _payload: &serde_json::Value) -> Result<(), i32> {
+pub fn queue_push(_queue_name: &str, _payload: &serde_json::Value) -> Result<(), HostError> {
     Ok(())
 }
 
@@ -248,10 +550,10 @@ pub fn current_user_id() -> String {
     String::from_utf8(buf).unwrap_or_default()
 }
 
-/// Get the current user's ID (stub for native testing, returns empty).
+/// Get the current user's ID (native: delegates to the installed [`NativeHost`]).
 #[cfg(not(target_arch = "wasm32"))]
 pub fn current_user_id() -> String {
-    String::new()
+    with_native_host(|host| host.current_user_id())
 }
 
 /// Check if the current user has a specific permission.
@@ -266,10 +568,10 @@ pub fn current_user_has_permission(permission: &str) -> bool {
     result == 1
 }
 
-/// Check permission (stub for native testing, always returns true).
+/// Check permission (native: delegates to the installed [`NativeHost`]).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn current_user_has_permission(_permission: &str) -> bool {
-    true
+pub fn current_user_has_permission(permission: &str) -> bool {
+    with_native_host(|host| host.current_user_has_permission(permission))
 }
 
 /// Get a site variable by name, with a default fallback.
@@ -279,9 +581,9 @@ pub fn current_user_has_permission(_permission: &str) -> bool {
 ///
 /// # Errors
 ///
-/// Returns a negative error code on host function failure.
+/// Returns a [`HostError`] on host function failure.
 #[cfg(target_arch = "wasm32")]
-pub fn variables_get(name: &str, default: &str) -> Result<String, i32> {
+pub fn variables_get(name: &str, default: &str) -> Result<String, HostError> {
     let mut buf = vec![0u8; MAX_OUTPUT_BUFFER];
     let result = unsafe {
         __variables_get(
@@ -294,30 +596,32 @@ pub fn variables_get(name: &str, default: &str) -> Result<String, i32> {
         )
     };
     if result < 0 {
-        Err(result)
+        Err(HostError::from_code(result))
     } else {
         let len = result as usize;
         if len >= MAX_OUTPUT_BUFFER {
-            return Err(crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED);
+            return Err(HostError::from_code(
+                crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED,
+            ));
         }
         buf.truncate(len);
-        String::from_utf8(buf).map_err(|_| crate::host_errors::ERR_SDK_UTF8)
+        String::from_utf8(buf).map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))
     }
 }
 
-/// Get a site variable (stub for native testing, returns default).
+/// Get a site variable (native: delegates to the installed [`NativeHost`]).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn variables_get(_name: &str, default: &str) -> Result<String, i32> {
-    Ok(default.to_string())
+pub fn variables_get(name: &str, default: &str) -> Result<String, HostError> {
+    with_native_host(|host| host.variables_get(name, default))
 }
 
 /// Set a site variable.
 ///
 /// # Errors
 ///
-/// Returns a negative error code on failure, 0 on success.
+/// Returns a [`HostError`] on failure.
 #[cfg(target_arch = "wasm32")]
-pub fn variables_set(name: &str, value: &str) -> Result<(), i32> {
+pub fn variables_set(name: &str, value: &str) -> Result<(), HostError> {
     let result = unsafe {
         __variables_set(
             name.as_ptr() as i32,
@@ -326,15 +630,254 @@ pub fn variables_set(name: &str, value: &str) -> Result<(), i32> {
             value.len() as i32,
         )
     };
-    if result < 0 { Err(result) } else { Ok(()) }
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Set a site variable (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn variables_set(name: &str, value: &str) -> Result<(), HostError> {
+    with_native_host(|host| host.variables_set(name, value))
+}
+
+/// Convert text into a URL-safe slug using the kernel's slug rules.
+///
+/// `lang` is a language tag (e.g. `"de"`, `"pt-BR"`) selecting the
+/// transliteration and stopword rules; pass `""` for language-neutral
+/// rules. The site's configured separator, maximum length and stopword
+/// setting apply, so slugs match those generated by pathauto.
+///
+/// # Errors
+///
+/// Returns a [`HostError`] on host function failure.
+#[cfg(target_arch = "wasm32")]
+pub fn slugify(text: &str, lang: &str) -> Result<String, HostError> {
+    // Slugs are capped well below this by the kernel.
+    let mut buf = vec![0u8; 1024];
+    let result = unsafe {
+        __slugify(
+            text.as_ptr() as i32,
+            text.len() as i32,
+            lang.as_ptr() as i32,
+            lang.len() as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        buf.truncate(result as usize);
+        String::from_utf8(buf).map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))
+    }
 }
 
-/// Set a site variable (stub for native testing, always succeeds).
+/// Slugify text (stub for native testing).
+///
+/// Lowercases ASCII alphanumerics and joins them with hyphens; no
+/// transliteration is performed.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn variables_set(_name: &str, _value: &str) -> Result<(), i32> {
+pub fn slugify(text: &str, _lang: &str) -> Result<String, HostError> {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    Ok(slug)
+}
+
+/// Store (or replace) the embedding vector of an item.
+///
+/// Vectors are kept in a per-plugin namespace backed by pgvector; only
+/// vectors with the same number of dimensions are compared by
+/// [`embedding_search`]. Embeddings are removed with the item.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure. [`HostError::NotFound`]
+/// means the item does not exist. [`HostError::Other`] with
+/// [`crate::host_errors::ERR_VECTOR_UNAVAILABLE`] means pgvector is not
+/// installed; with [`crate::host_errors::ERR_VECTOR_INVALID`], the vector
+/// is empty, too large, or not finite.
+#[cfg(target_arch = "wasm32")]
+pub fn embedding_store(item_id: uuid::Uuid, vector: &[f32]) -> Result<(), HostError> {
+    let id = item_id.to_string();
+    let vector_json = serde_json::to_string(vector)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    let result = unsafe {
+        __embedding_store(
+            id.as_ptr() as i32,
+            id.len() as i32,
+            vector_json.as_ptr() as i32,
+            vector_json.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Find the `k` items whose stored embeddings are nearest to `vector`.
+///
+/// Searches only this plugin's namespace and the live stage, nearest
+/// first. Non-admin callers only see published items. `k` is capped at
+/// [`crate::types::EMBEDDING_SEARCH_MAX_K`].
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure.
+#[cfg(target_arch = "wasm32")]
+pub fn embedding_search(
+    vector: &[f32],
+    k: u32,
+) -> Result<Vec<crate::types::EmbeddingMatch>, HostError> {
+    let vector_json = serde_json::to_string(vector)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    // At most EMBEDDING_SEARCH_MAX_K small entries; well under 64KB.
+    let mut buf = vec![0u8; 64 * 1024];
+    let result = unsafe {
+        __embedding_search(
+            vector_json.as_ptr() as i32,
+            vector_json.len() as i32,
+            k.min(i32::MAX as u32) as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        buf.truncate(result as usize);
+        let json = String::from_utf8(buf)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))?;
+        serde_json::from_str(&json)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_DESERIALIZE))
+    }
+}
+
+/// Store an embedding (stub for native testing, always succeeds).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn embedding_store(_item_id: uuid::Uuid, _vector: &[f32]) -> Result<(), HostError> {
     Ok(())
 }
 
+/// Search embeddings (stub for native testing, always returns no matches).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn embedding_search(
+    _vector: &[f32],
+    _k: u32,
+) -> Result<Vec<crate::types::EmbeddingMatch>, HostError> {
+    Ok(Vec::new())
+}
+
+/// Get a value from the kernel cache.
+///
+/// Keys are namespaced by plugin and `bin`, so plugins cannot read each
+/// other's entries. Returns `None` on a miss or when no cache is
+/// available.
+#[cfg(target_arch = "wasm32")]
+pub fn cache_get(bin: &str, key: &str) -> Option<String> {
+    let mut buf = vec![0u8; crate::types::CACHE_MAX_VALUE_BYTES];
+    let result = unsafe {
+        __cache_get(
+            bin.as_ptr() as i32,
+            bin.len() as i32,
+            key.as_ptr() as i32,
+            key.len() as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        return None;
+    }
+    buf.truncate(result as usize);
+    String::from_utf8(buf).ok()
+}
+
+/// Store a value in the kernel cache.
+///
+/// `tags` are namespaced like keys; pass them to [`cache_invalidate_tag`]
+/// to drop related entries. A `ttl_secs` of 0 uses the kernel default
+/// (5 minutes); longer TTLs are capped at
+/// [`crate::types::CACHE_MAX_TTL_SECS`]. Without a cache (e.g. in tests)
+/// the value is silently not stored.
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure. [`HostError::QuotaExceeded`]
+/// means the plugin already holds [`crate::types::CACHE_MAX_ENTRIES`]
+/// entries; [`HostError::Other`] with
+/// [`crate::host_errors::ERR_CACHE_ENTRY_TOO_LARGE`] means the entry
+/// exceeds the `CACHE_MAX_*` limits in [`crate::types`].
+#[cfg(target_arch = "wasm32")]
+pub fn cache_set(
+    bin: &str,
+    key: &str,
+    value: &str,
+    tags: &[&str],
+    ttl_secs: u32,
+) -> Result<(), HostError> {
+    let tags_json = serde_json::to_string(tags)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    let result = unsafe {
+        __cache_set(
+            bin.as_ptr() as i32,
+            bin.len() as i32,
+            key.as_ptr() as i32,
+            key.len() as i32,
+            value.as_ptr() as i32,
+            value.len() as i32,
+            tags_json.as_ptr() as i32,
+            tags_json.len() as i32,
+            ttl_secs.min(i32::MAX as u32) as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Invalidate all of this plugin's cache entries carrying `tag`.
+#[cfg(target_arch = "wasm32")]
+pub fn cache_invalidate_tag(tag: &str) {
+    unsafe {
+        __cache_invalidate_tag(tag.as_ptr() as i32, tag.len() as i32);
+    }
+}
+
+/// Get a cached value (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn cache_get(bin: &str, key: &str) -> Option<String> {
+    with_native_host(|host| host.cache_get(bin, key))
+}
+
+/// Store a cached value (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn cache_set(
+    bin: &str,
+    key: &str,
+    value: &str,
+    tags: &[&str],
+    ttl_secs: u32,
+) -> Result<(), HostError> {
+    with_native_host(|host| host.cache_set(bin, key, value, tags, ttl_secs))
+}
+
+/// Invalidate a cache tag (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn cache_invalidate_tag(tag: &str) {
+    with_native_host(|host| host.cache_invalidate_tag(tag));
+}
+
 /// Log a message through the kernel's tracing system.
 ///
 /// Valid levels: `"trace"`, `"debug"`, `"info"`, `"warn"`, `"error"`.
@@ -366,12 +909,16 @@ pub fn log(level: &str, plugin_name: &str, message: &str) {
 ///
 /// # Errors
 ///
-/// Returns the host error code (negative i32) on failure. See
-/// [`crate::host_errors`] for AI-specific error codes.
+/// Returns the decoded [`HostError`] on failure. Rate limits and exhausted
+/// token budgets are [`HostError::QuotaExceeded`] and a missing AI
+/// permission is [`HostError::PermissionDenied`]; other failures are
+/// [`HostError::Other`] with an `ERR_AI_*` code from [`crate::host_errors`].
 #[cfg(target_arch = "wasm32")]
-pub fn ai_request(request: &crate::types::AiRequest) -> Result<crate::types::AiResponse, i32> {
-    let request_json =
-        serde_json::to_string(request).map_err(|_| crate::host_errors::ERR_SDK_SERIALIZE)?;
+pub fn ai_request(
+    request: &crate::types::AiRequest,
+) -> Result<crate::types::AiResponse, HostError> {
+    let request_json = serde_json::to_string(request)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
     let mut buf = vec![0u8; MAX_OUTPUT_BUFFER];
     let result = unsafe {
         __ai_request(
@@ -382,15 +929,207 @@ pub fn ai_request(request: &crate::types::AiRequest) -> Result<crate::types::AiR
         )
     };
     if result < 0 {
-        Err(result)
+        Err(HostError::from_code(result))
     } else {
         let len = result as usize;
         if len >= MAX_OUTPUT_BUFFER {
-            return Err(crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED);
+            return Err(HostError::from_code(
+                crate::host_errors::ERR_SDK_OUTPUT_BUFFER_EXCEEDED,
+            ));
         }
         buf.truncate(len);
-        let json = String::from_utf8(buf).map_err(|_| crate::host_errors::ERR_SDK_UTF8)?;
-        serde_json::from_str(&json).map_err(|_| crate::host_errors::ERR_SDK_DESERIALIZE)
+        let json = String::from_utf8(buf)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))?;
+        serde_json::from_str(&json)
+            .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_DESERIALIZE))
+    }
+}
+
+/// Open a render handle for building tap output on the host.
+///
+/// Append elements with [`render_append`] (or
+/// [`crate::render::RenderHandle::append`]) and return the handle from the
+/// tap; the kernel substitutes a `container` holding the appended elements
+/// in order. Use this for very large output, e.g. a page listing hundreds
+/// of items, so the whole tree is never held in plugin memory at once.
+///
+/// # Errors
+///
+/// Returns [`HostError::QuotaExceeded`] once the tap has opened
+/// [`crate::types::RENDER_MAX_HANDLES`] handles.
+#[cfg(target_arch = "wasm32")]
+pub fn render_begin() -> Result<crate::render::RenderHandle, HostError> {
+    let result = unsafe { __render_begin() };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(crate::render::RenderHandle::from_id(result))
+    }
+}
+
+/// Append a render element, as JSON, to a handle from [`render_begin`].
+///
+/// # Errors
+///
+/// Returns the decoded [`HostError`] on failure.
+/// [`HostError::QuotaExceeded`] means a `RENDER_MAX_*` limit in
+/// [`crate::types`] was reached; [`HostError::Other`] with
+/// [`crate::host_errors::ERR_PARAM_DESERIALIZE`] means `element_json` is
+/// not a render element.
+#[cfg(target_arch = "wasm32")]
+pub fn render_append(
+    handle: crate::render::RenderHandle,
+    element_json: &str,
+) -> Result<(), HostError> {
+    let result = unsafe {
+        __render_append(
+            handle.id(),
+            element_json.as_ptr() as i32,
+            element_json.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(())
+    }
+}
+
+/// Dispatch an event to the other plugins.
+///
+/// Every other enabled plugin implementing `tap_event` receives a
+/// [`crate::types::PluginEvent`] carrying `name`, this plugin's name and
+/// `payload`, in weight order, before this call returns. Handlers may
+/// dispatch events of their own, up to [`crate::types::EVENT_MAX_DEPTH`]
+/// deep; an event is never delivered again while it is being handled.
+///
+/// Returns the number of plugins the event was delivered to.
+///
+/// # Errors
+///
+/// Returns [`HostError::Other`] with
+/// [`crate::host_errors::ERR_EVENT_LOOP`] if the event is already being
+/// handled further up the chain or the chain is too deep, and
+/// [`crate::host_errors::ERR_EVENT_INVALID`] if `name` fails
+/// [`crate::types::is_valid_event_name`] or the payload exceeds
+/// [`crate::types::EVENT_MAX_PAYLOAD_BYTES`].
+#[cfg(target_arch = "wasm32")]
+pub fn dispatch_event(name: &str, payload: &serde_json::Value) -> Result<u32, HostError> {
+    let payload_json = serde_json::to_string(payload)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    let result = unsafe {
+        __events_dispatch(
+            name.as_ptr() as i32,
+            name.len() as i32,
+            payload_json.as_ptr() as i32,
+            payload_json.len() as i32,
+        )
+    };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(result as u32)
+    }
+}
+
+/// Vendor of a MAC address, looked up by its OUI in the IEEE registry.
+///
+/// Returns `None` for unknown vendors and for locally administered
+/// (randomized) addresses.
+///
+/// # Errors
+///
+/// Returns a [`HostError`] on host function failure.
+#[cfg(target_arch = "wasm32")]
+pub fn mac_vendor(mac: &str) -> Result<Option<String>, HostError> {
+    // Registry organization names are far shorter.
+    let mut buf = vec![0u8; 1024];
+    let result = unsafe {
+        __device_mac_vendor(
+            mac.as_ptr() as i32,
+            mac.len() as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        return Err(HostError::from_code(result));
+    }
+    buf.truncate(result as usize);
+    let vendor = String::from_utf8(buf)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_UTF8))?;
+    Ok((!vendor.is_empty()).then_some(vendor))
+}
+
+/// Let other plugins refine a device type guess.
+///
+/// Sends `device` through `tap_ng_classify`; every plugin implementing it
+/// may replace `device_type`, in weight order. Returns the device as left
+/// by the last handler.
+///
+/// # Errors
+///
+/// Returns [`HostError::Other`] with [`crate::host_errors::ERR_EVENT_LOOP`]
+/// when called from a `tap_ng_classify` handler, or another [`HostError`]
+/// on host function failure.
+#[cfg(target_arch = "wasm32")]
+pub fn classify_device(
+    device: &crate::types::DeviceFingerprint,
+) -> Result<crate::types::DeviceFingerprint, HostError> {
+    let device_json = serde_json::to_string(device)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_SERIALIZE))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let result = unsafe {
+        __device_classify(
+            device_json.as_ptr() as i32,
+            device_json.len() as i32,
+            buf.as_mut_ptr() as i32,
+            buf.len() as i32,
+        )
+    };
+    if result < 0 {
+        return Err(HostError::from_code(result));
+    }
+    buf.truncate(result as usize);
+    serde_json::from_slice(&buf)
+        .map_err(|_| HostError::from_code(crate::host_errors::ERR_SDK_DESERIALIZE))
+}
+
+/// Re-run alert-enabled saved searches and notify owners of new matches.
+///
+/// Only available from `tap_cron` while the `trovato_saved_searches`
+/// plugin is enabled. Returns the number of alerts sent.
+///
+/// # Errors
+///
+/// Returns [`HostError::Other`] with [`crate::host_errors::ERR_NO_SERVICES`]
+/// outside cron, or another [`HostError`] if the check fails.
+#[cfg(target_arch = "wasm32")]
+pub fn check_saved_search_alerts() -> Result<u32, HostError> {
+    let result = unsafe { __saved_search_check_alerts() };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(result as u32)
+    }
+}
+
+/// Apply the scheduled field changes whose time has passed.
+///
+/// Only available from `tap_cron` while the `trovato_scheduled_updates`
+/// plugin is enabled. Returns the number of updates applied.
+///
+/// # Errors
+///
+/// Returns [`HostError::Other`] with [`crate::host_errors::ERR_NO_SERVICES`]
+/// outside cron, or another [`HostError`] if applying them fails.
+#[cfg(target_arch = "wasm32")]
+pub fn apply_scheduled_updates() -> Result<u32, HostError> {
+    let result = unsafe { __scheduled_update_apply_due() };
+    if result < 0 {
+        Err(HostError::from_code(result))
+    } else {
+        Ok(result as u32)
     }
 }
 
@@ -398,11 +1137,178 @@ pub fn ai_request(request: &crate::types::AiRequest) -> Result<crate::types::AiR
 // Native stubs for testing — no actual DB access
 // --------------------------------------------------------------------------
 
+/// Host function implementations used on native targets.
+///
+/// Every method defaults to the plain stub behaviour (empty results,
+/// defaults echoed back, all permissions granted). Tests install an
+/// implementation with [`set_native_host`] to back host calls with
+/// in-memory state; `trovato-test-utils` provides `MockHost`.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait NativeHost {
+    /// Backs [`execute_raw`].
+    fn execute_raw(&self, _sql: &str, _params: &[serde_json::Value]) -> Result<u64, HostError> {
+        Ok(0)
+    }
+
+    /// Backs [`query_raw`]; returns a JSON array of row objects.
+    fn query_raw(&self, _sql: &str, _params: &[serde_json::Value]) -> Result<String, HostError> {
+        Ok("[]".to_string())
+    }
+
+    /// Backs [`begin_transaction`].
+    fn begin_transaction(&self) -> Result<(), HostError> {
+        Ok(())
+    }
+
+    /// Backs [`commit_transaction`].
+    fn commit_transaction(&self) -> Result<(), HostError> {
+        Ok(())
+    }
+
+    /// Backs [`rollback_transaction`].
+    fn rollback_transaction(&self) -> Result<(), HostError> {
+        Ok(())
+    }
+
+    /// Backs [`item_query`].
+    fn item_query(
+        &self,
+        _query: &crate::types::ItemQuery,
+    ) -> Result<Vec<crate::types::Item>, HostError> {
+        Ok(Vec::new())
+    }
+
+    /// Backs [`save_item`].
+    fn save_item(
+        &self,
+        _item: &serde_json::Value,
+    ) -> Result<Option<crate::types::Item>, HostError> {
+        Ok(None)
+    }
+
+    /// Backs [`variables_get`].
+    fn variables_get(&self, _name: &str, default: &str) -> Result<String, HostError> {
+        Ok(default.to_string())
+    }
+
+    /// Backs [`variables_set`].
+    fn variables_set(&self, _name: &str, _value: &str) -> Result<(), HostError> {
+        Ok(())
+    }
+
+    /// Backs [`cache_get`]; the stub always misses.
+    fn cache_get(&self, _bin: &str, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// Backs [`cache_set`].
+    fn cache_set(
+        &self,
+        _bin: &str,
+        _key: &str,
+        _value: &str,
+        _tags: &[&str],
+        _ttl_secs: u32,
+    ) -> Result<(), HostError> {
+        Ok(())
+    }
+
+    /// Backs [`cache_invalidate_tag`].
+    fn cache_invalidate_tag(&self, _tag: &str) {}
+
+    /// Backs [`current_user_id`].
+    fn current_user_id(&self) -> String {
+        String::new()
+    }
+
+    /// Backs [`current_user_has_permission`].
+    fn current_user_has_permission(&self, _permission: &str) -> bool {
+        true
+    }
+
+    /// Backs [`render_begin`]; the stub always returns handle 0.
+    fn render_begin(&self) -> Result<crate::render::RenderHandle, HostError> {
+        Ok(crate::render::RenderHandle::from_id(0))
+    }
+
+    /// Backs [`render_append`]; the stub discards the element.
+    fn render_append(
+        &self,
+        _handle: crate::render::RenderHandle,
+        _element_json: &str,
+    ) -> Result<(), HostError> {
+        Ok(())
+    }
+
+    /// Backs [`dispatch_event`]; the stub delivers to no plugins.
+    fn dispatch_event(&self, _name: &str, _payload: &serde_json::Value) -> Result<u32, HostError> {
+        Ok(0)
+    }
+
+    /// Backs [`mac_vendor`]; the stub knows no vendors.
+    fn mac_vendor(&self, _mac: &str) -> Result<Option<String>, HostError> {
+        Ok(None)
+    }
+
+    /// Backs [`classify_device`]; the stub returns the device unchanged.
+    fn classify_device(
+        &self,
+        device: &crate::types::DeviceFingerprint,
+    ) -> Result<crate::types::DeviceFingerprint, HostError> {
+        Ok(device.clone())
+    }
+
+    /// Backs [`check_saved_search_alerts`]; the stub sends no alerts.
+    fn check_saved_search_alerts(&self) -> Result<u32, HostError> {
+        Ok(0)
+    }
+
+    /// Backs [`apply_scheduled_updates`]; the stub applies none.
+    fn apply_scheduled_updates(&self) -> Result<u32, HostError> {
+        Ok(0)
+    }
+}
+
+/// The stub host used when no [`NativeHost`] is installed.
+#[cfg(not(target_arch = "wasm32"))]
+struct StubHost;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NativeHost for StubHost {}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static NATIVE_HOST: std::cell::RefCell<Option<std::rc::Rc<dyn NativeHost>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Install a host for native host calls on the current thread.
+///
+/// Pass `None` to restore the stub behaviour. Returns the previously
+/// installed host so callers can restore it. The host is thread-local,
+/// so parallel tests do not see each other's state.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_native_host(
+    host: Option<std::rc::Rc<dyn NativeHost>>,
+) -> Option<std::rc::Rc<dyn NativeHost>> {
+    NATIVE_HOST.with(|slot| slot.replace(host))
+}
+
+/// Run `f` against the installed host, or the stub host if none.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_native_host<R>(f: impl FnOnce(&dyn NativeHost) -> R) -> R {
+    // Clone the handle out so the host may itself call back into the SDK.
+    match NATIVE_HOST.with(|slot| slot.borrow().clone()) {
+        Some(host) => f(host.as_ref()),
+        None => f(&StubHost),
+    }
+}
+
 /// Make an outbound HTTP request (stub for native testing, returns mock 200).
 #[cfg(not(target_arch = "wasm32"))]
 pub fn http_request(
     _request: &crate::types::HttpRequest,
-) -> Result<crate::types::HttpResponse, i32> {
+) -> Result<crate::types::HttpResponse, HostError> {
     Ok(crate::types::HttpResponse {
         status: 200,
         headers: std::collections::HashMap::new(),
@@ -410,21 +1316,100 @@ pub fn http_request(
     })
 }
 
-/// Execute a DML statement (stub for native testing, always returns 0).
+/// Execute a DML statement (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn execute_raw(sql: &str, params: &[serde_json::Value]) -> Result<u64, HostError> {
+    with_native_host(|host| host.execute_raw(sql, params))
+}
+
+/// Execute a SELECT query (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn query_raw(sql: &str, params: &[serde_json::Value]) -> Result<String, HostError> {
+    with_native_host(|host| host.query_raw(sql, params))
+}
+
+/// Start a transaction (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn begin_transaction() -> Result<(), HostError> {
+    with_native_host(|host| host.begin_transaction())
+}
+
+/// Commit a transaction (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn commit_transaction() -> Result<(), HostError> {
+    with_native_host(|host| host.commit_transaction())
+}
+
+/// Roll back a transaction (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn rollback_transaction() -> Result<(), HostError> {
+    with_native_host(|host| host.rollback_transaction())
+}
+
+/// Query items (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn item_query(query: &crate::types::ItemQuery) -> Result<Vec<crate::types::Item>, HostError> {
+    with_native_host(|host| host.item_query(query))
+}
+
+/// Save an item (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_item(item: &serde_json::Value) -> Result<Option<crate::types::Item>, HostError> {
+    with_native_host(|host| host.save_item(item))
+}
+
+/// Open a render handle (native: delegates to the installed [`NativeHost`]).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn execute_raw(_sql: &str, _params: &[serde_json::Value]) -> Result<u64, i32> {
-    Ok(0)
+pub fn render_begin() -> Result<crate::render::RenderHandle, HostError> {
+    with_native_host(|host| host.render_begin())
 }
 
-/// Execute a SELECT query (stub for native testing, always returns empty array).
+/// Append a render element (native: delegates to the installed [`NativeHost`]).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn query_raw(_sql: &str, _params: &[serde_json::Value]) -> Result<String, i32> {
-    Ok("[]".to_string())
+pub fn render_append(
+    handle: crate::render::RenderHandle,
+    element_json: &str,
+) -> Result<(), HostError> {
+    with_native_host(|host| host.render_append(handle, element_json))
+}
+
+/// Dispatch an event (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn dispatch_event(name: &str, payload: &serde_json::Value) -> Result<u32, HostError> {
+    with_native_host(|host| host.dispatch_event(name, payload))
+}
+
+/// Look up a MAC vendor (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn mac_vendor(mac: &str) -> Result<Option<String>, HostError> {
+    with_native_host(|host| host.mac_vendor(mac))
+}
+
+/// Classify a device (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn classify_device(
+    device: &crate::types::DeviceFingerprint,
+) -> Result<crate::types::DeviceFingerprint, HostError> {
+    with_native_host(|host| host.classify_device(device))
+}
+
+/// Check saved search alerts (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn check_saved_search_alerts() -> Result<u32, HostError> {
+    with_native_host(|host| host.check_saved_search_alerts())
+}
+
+/// Apply due scheduled updates (native: delegates to the installed [`NativeHost`]).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn apply_scheduled_updates() -> Result<u32, HostError> {
+    with_native_host(|host| host.apply_scheduled_updates())
 }
 
 /// Make an AI request (stub for native testing, returns a mock response).
 #[cfg(not(target_arch = "wasm32"))]
-pub fn ai_request(_request: &crate::types::AiRequest) -> Result<crate::types::AiResponse, i32> {
+pub fn ai_request(
+    _request: &crate::types::AiRequest,
+) -> Result<crate::types::AiResponse, HostError> {
     Ok(crate::types::AiResponse {
         content: "Mock AI response".to_string(),
         model: "test-model".to_string(),
@@ -460,6 +1445,18 @@ mod tests {
         assert_eq!(result.unwrap(), "[]");
     }
 
+    #[test]
+    fn item_query_stub_returns_empty() {
+        let query = crate::types::ItemQuery::new().item_type("blog");
+        assert!(item_query(&query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn save_item_stub_returns_none() {
+        let item = serde_json::json!({"type": "blog", "title": "Hello"});
+        assert!(save_item(&item).unwrap().is_none());
+    }
+
     #[test]
     fn execute_raw_with_params() {
         let params = vec![serde_json::json!(42), serde_json::json!("hello")];
@@ -488,6 +1485,131 @@ mod tests {
         assert!(variables_set("some.key", "value").is_ok());
     }
 
+    #[test]
+    fn cache_stubs_miss_and_succeed() {
+        assert!(cache_set("feeds", "etag", "\"abc\"", &["feed"], 3600).is_ok());
+        assert_eq!(cache_get("feeds", "etag"), None);
+        cache_invalidate_tag("feed");
+    }
+
+    #[test]
+    fn render_stubs_succeed() {
+        let handle = render_begin().unwrap();
+        handle
+            .append(&crate::render::markup("p", "Hello").build())
+            .unwrap();
+    }
+
+    #[test]
+    fn dispatch_event_stub_delivers_to_nobody() {
+        let payload = serde_json::json!({"item_id": "abc"});
+        assert_eq!(
+            dispatch_event("argus.article_analyzed", &payload).unwrap(),
+            0
+        );
+    }
+
+    struct CountingHost;
+
+    impl NativeHost for CountingHost {
+        fn execute_raw(&self, _sql: &str, params: &[serde_json::Value]) -> Result<u64, HostError> {
+            Ok(params.len() as u64)
+        }
+
+        fn current_user_id(&self) -> String {
+            "user-1".to_string()
+        }
+    }
+
+    #[test]
+    fn installed_native_host_backs_host_calls() {
+        let previous = set_native_host(Some(std::rc::Rc::new(CountingHost)));
+        assert!(previous.is_none());
+
+        let params = vec![serde_json::json!(1), serde_json::json!(2)];
+        assert_eq!(execute_raw("DELETE FROM foo", &params).unwrap(), 2);
+        assert_eq!(current_user_id(), "user-1");
+        // Unoverridden methods keep the stub behaviour.
+        assert_eq!(query_raw("SELECT 1", &[]).unwrap(), "[]");
+
+        set_native_host(None);
+        assert_eq!(execute_raw("DELETE FROM foo", &params).unwrap(), 0);
+    }
+
+    struct FailingHost;
+
+    impl NativeHost for FailingHost {
+        fn query_raw(
+            &self,
+            _sql: &str,
+            _params: &[serde_json::Value],
+        ) -> Result<String, HostError> {
+            Err(HostError::QueryError {
+                code: Some("23505".to_string()),
+            })
+        }
+    }
+
+    /// Records transaction calls in order.
+    #[derive(Default)]
+    struct TransactionLog(std::cell::RefCell<Vec<&'static str>>);
+
+    impl NativeHost for TransactionLog {
+        fn begin_transaction(&self) -> Result<(), HostError> {
+            self.0.borrow_mut().push("begin");
+            Ok(())
+        }
+
+        fn commit_transaction(&self) -> Result<(), HostError> {
+            self.0.borrow_mut().push("commit");
+            Ok(())
+        }
+
+        fn rollback_transaction(&self) -> Result<(), HostError> {
+            self.0.borrow_mut().push("rollback");
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn transaction_commits_on_ok_and_rolls_back_on_err() {
+        let log = std::rc::Rc::new(TransactionLog::default());
+        set_native_host(Some(log.clone()));
+
+        let value = transaction(|| Ok::<_, HostError>(7)).unwrap();
+        let err = transaction(|| Err::<(), _>(HostError::NotFound)).unwrap_err();
+        set_native_host(None);
+
+        assert_eq!(value, 7);
+        assert_eq!(err, HostError::NotFound);
+        assert_eq!(*log.0.borrow(), ["begin", "commit", "begin", "rollback"]);
+    }
+
+    #[test]
+    fn native_host_errors_reach_the_caller() {
+        set_native_host(Some(std::rc::Rc::new(FailingHost)));
+        let err = query_raw("SELECT 1", &[]).unwrap_err();
+        set_native_host(None);
+
+        assert_eq!(
+            err,
+            HostError::QueryError {
+                code: Some("23505".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn embedding_stubs_succeed() {
+        embedding_store(uuid::Uuid::nil(), &[0.1, 0.2]).unwrap();
+        assert!(embedding_search(&[0.1, 0.2], 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn slugify_stub_hyphenates_ascii() {
+        assert_eq!(slugify("Hello, World!", "en").unwrap(), "hello-world");
+    }
+
     #[test]
     fn ai_request_stub_returns_mock() {
         use crate::types::{AiMessage, AiOperationType, AiRequest, AiRequestOptions};
@@ -5,7 +5,8 @@
 //! non-negative values indicate success.
 //!
 //! Use the constants below instead of raw integer literals when implementing
-//! or consuming host functions.
+//! host functions. Plugins get the codes decoded into a [`HostError`] by the
+//! wrappers in [`crate::host`].
 //!
 //! # Standard Error Codes
 //!
@@ -15,6 +16,8 @@
 //! | `-2` | [`ERR_PARAM1_READ`] | First parameter read failed (UTF-8 / OOB) |
 //! | `-3` | [`ERR_PARAM2_OR_OUTPUT`] | Second param or output write failed |
 //! | `-4` | [`ERR_PARAM3_READ`] | Third parameter read failed (DB extra params) |
+//! | `-16` | [`ERR_NOT_FOUND`] | A record the call refers to does not exist |
+//! | `≤ -1000000000` | [`ERR_SQL_STATE_BASE`] | Database error carrying a SQLSTATE (see [`encode_sql_state`]) |
 //! | `≥ 0` | — | Success: bytes written, rows affected, or boolean flag |
 //!
 //! # Per-API Details
@@ -48,6 +51,21 @@
 //!   - `-1`: memory missing, `-2`: SQL read failed, `-3`: params read failed
 //!   - `≥ 0`: rows affected
 //!
+//! - **`begin() → i32`**
+//!   - `-10`: database unavailable, `-12`: transaction could not be started,
+//!     `-80`: the tap already has an open transaction
+//!   - `0`: success
+//!
+//! - **`commit() → i32`** / **`rollback() → i32`**
+//!   - `-81`: the tap has no open transaction
+//!   - `0`: success
+//!
+//! `query-raw` and `execute-raw` report a failed statement as its encoded
+//! SQLSTATE, falling back to `-12` when the database gave none. So does
+//! `commit` when the database rejects the transaction. `execute-raw`
+//! rejects transaction control statements (`BEGIN`, `COMMIT`, `SAVEPOINT`,
+//! ...) with `-11`.
+//!
 //! ## Item API (`trovato:item-api/*`)
 //!
 //! - **`get-item(id_ptr, id_len, out_ptr, out_max_len) → i32`**
@@ -66,6 +84,11 @@
 //!   - `-1`: memory missing, `-2`: query JSON read failed, `-3`: output write failed
 //!   - `≥ 0`: bytes written (JSON array of items)
 //!
+//! - **`item-query(query_ptr, query_len, out_ptr, out_max_len) → i32`**
+//!   - `-1`: memory missing, `-2`: query JSON read failed, `-3`: output write failed,
+//!     `-14`: query JSON invalid, `-15`: invalid field/sort name or unknown stage
+//!   - `≥ 0`: bytes written (JSON array of items visible to the caller)
+//!
 //! ## Request Context (`trovato:request-context/*`)
 //!
 //! - **`get(key_ptr, key_len, out_ptr, out_max_len) → i32`**
@@ -81,7 +104,14 @@
 //!   - `-1`: memory missing or cache miss
 //!   - `≥ 0`: bytes written
 //!
-//! - **`set(…) → void`** / **`invalidate-tag(…) → void`**
+//! - **`set(bin_ptr, bin_len, key_ptr, key_len, value_ptr, value_len, tags_ptr, tags_len, ttl_secs) → i32`**
+//!   - `-1`: memory missing, `-2`: bin or key read failed, `-3`: value read failed,
+//!     `-4`: tags read failed, `-14`: tags JSON invalid,
+//!     `-50`: key, value or tag list over the entry limits,
+//!     `-51`: plugin's cache entry quota reached
+//!   - `0`: success (also when no cache is available)
+//!
+//! - **`invalidate-tag(tag_ptr, tag_len) → void`**
 //!   - Silent no-op on memory or read failure
 //!
 //! ## User API (`trovato:user-api/*`)
@@ -133,6 +163,62 @@
 //!   - `-33`: response body too large for output buffer
 //!   - `≥ 0`: bytes written (JSON [`crate::types::HttpResponse`])
 //!
+//! ## Slug (`trovato:kernel/slug`)
+//!
+//! - **`slugify(text_ptr, text_len, lang_ptr, lang_len, out_ptr, out_max_len) → i32`**
+//!   - `-1`: memory missing, `-2`: text read failed, `-3`: language read or output write failed
+//!   - `≥ 0`: bytes written (may be `0` if nothing transliterable remains)
+//!
+//! ## Vector API (`trovato:kernel/vector`)
+//!
+//! - **`embedding-store(id_ptr, id_len, vec_ptr, vec_len) → i32`**
+//!   - `-1`: memory missing, `-2`: item ID read failed, `-3`: vector read failed,
+//!     `-12` or a SQLSTATE: database error, `-14`: vector JSON invalid,
+//!     `-16`: item does not exist,
+//!     `-40`: pgvector not available, `-41`: vector empty, too large, or not finite
+//!   - `0`: success
+//!
+//! - **`embedding-search(vec_ptr, vec_len, k, out_ptr, out_max_len) → i32`**
+//!   - `-1`: memory missing, `-2`: vector read failed, `-3`: output write failed,
+//!     `-14`: vector JSON invalid, `-40`: pgvector not available, `-41`: invalid vector
+//!   - `≥ 0`: bytes written (JSON array of [`crate::types::EmbeddingMatch`])
+//!
+//! ## Render (`trovato:kernel/render`)
+//!
+//! - **`begin() → i32`**
+//!   - `-61`: the tap already opened [`crate::types::RENDER_MAX_HANDLES`] handles
+//!   - `≥ 0`: the new handle ID
+//!
+//! - **`append(handle, element_ptr, element_len) → i32`**
+//!   - `-1`: memory missing, `-2`: element read failed,
+//!     `-14`: element JSON is not a render element, `-60`: unknown handle,
+//!     `-61`: element or byte limit reached (`RENDER_MAX_*` in [`crate::types`])
+//!   - `0`: success
+//!
+//! ## Events (`trovato:kernel/events`)
+//!
+//! - **`dispatch(name_ptr, name_len, payload_ptr, payload_len) → i32`**
+//!   - `-1`: memory missing, `-2`: name read failed, `-3`: payload read failed,
+//!     `-10`: events unavailable, `-14`: payload JSON invalid,
+//!     `-70`: the event is already being handled further up the chain or
+//!     [`crate::types::EVENT_MAX_DEPTH`] was reached,
+//!     `-71`: invalid event name or payload over
+//!     [`crate::types::EVENT_MAX_PAYLOAD_BYTES`]
+//!   - `≥ 0`: number of plugins the event was delivered to
+//!
+//! ## Device (`trovato:kernel/device`)
+//!
+//! - **`mac-vendor(mac_ptr, mac_len, out_ptr, out_max_len) → i32`**
+//!   - `-1`: memory missing, `-2`: MAC read failed, `-3`: output write failed,
+//!     `-10`: database unavailable
+//!   - `≥ 0`: bytes written (vendor name; `0`: unknown vendor)
+//!
+//! - **`classify(device_ptr, device_len, out_ptr, out_max_len) → i32`**
+//!   - `-1`: memory missing, `-2`: device read failed, `-3`: output write failed,
+//!     `-10`: taps unavailable, `-14`: device JSON invalid,
+//!     `-70`: called from a `tap_ng_classify` handler
+//!   - `≥ 0`: bytes written (JSON [`crate::types::DeviceFingerprint`])
+//!
 //! ## SDK-side Errors (client-side, before/after WASM boundary)
 //!
 //! These errors are produced by the SDK wrapper functions in `host.rs`, not by host functions:
@@ -173,6 +259,52 @@ pub const ERR_PARAM_DESERIALIZE: i32 = -14;
 /// Invalid table or column name (must match `[a-zA-Z_][a-zA-Z0-9_]*`).
 pub const ERR_INVALID_IDENTIFIER: i32 = -15;
 
+/// A record the call refers to does not exist.
+pub const ERR_NOT_FOUND: i32 = -16;
+
+/// Base of the SQLSTATE range.
+///
+/// A database error with SQLSTATE `s` is returned as
+/// `ERR_SQL_STATE_BASE - base36(s)`, so `23505` (unique violation) crosses
+/// the boundary as a single `i32`. See [`encode_sql_state`].
+pub const ERR_SQL_STATE_BASE: i32 = -1_000_000_000;
+
+/// Number of distinct SQLSTATEs: five characters from `[0-9A-Z]`.
+const SQL_STATE_COUNT: i32 = 36 * 36 * 36 * 36 * 36;
+
+/// SQLSTATE of a statement cancelled by `statement_timeout`.
+const SQL_STATE_QUERY_CANCELED: &str = "57014";
+
+/// Encode a SQLSTATE as a host error code.
+///
+/// Returns `None` unless `state` is five characters from `[0-9A-Z]`.
+pub fn encode_sql_state(state: &str) -> Option<i32> {
+    if state.len() != 5 {
+        return None;
+    }
+    let mut n: i32 = 0;
+    for c in state.chars() {
+        let digit = c.to_digit(36).filter(|_| !c.is_ascii_lowercase())?;
+        n = n * 36 + digit as i32;
+    }
+    Some(ERR_SQL_STATE_BASE - n)
+}
+
+/// Decode a host error code produced by [`encode_sql_state`].
+pub fn decode_sql_state(code: i32) -> Option<String> {
+    let mut n = ERR_SQL_STATE_BASE.checked_sub(code)?;
+    if !(0..SQL_STATE_COUNT).contains(&n) {
+        return None;
+    }
+    let mut state = [b'0'; 5];
+    for slot in state.iter_mut().rev() {
+        let digit = char::from_digit((n % 36) as u32, 36)?;
+        *slot = digit.to_ascii_uppercase() as u8;
+        n /= 36;
+    }
+    String::from_utf8(state.to_vec()).ok()
+}
+
 // =============================================================================
 // AI API errors (`trovato:kernel/ai-api`)
 // =============================================================================
@@ -219,6 +351,64 @@ pub const ERR_HTTP_INVALID_URL: i32 = -32;
 /// Response body too large for the output buffer.
 pub const ERR_HTTP_RESPONSE_TOO_LARGE: i32 = -33;
 
+// =============================================================================
+// Vector API errors (`trovato:kernel/vector`)
+// =============================================================================
+
+/// pgvector is not installed, so embeddings cannot be stored or searched.
+pub const ERR_VECTOR_UNAVAILABLE: i32 = -40;
+
+/// Vector is empty, exceeds [`crate::types::EMBEDDING_MAX_DIMENSIONS`], or
+/// contains NaN or infinite values.
+pub const ERR_VECTOR_INVALID: i32 = -41;
+
+// =============================================================================
+// Cache API errors (`trovato:kernel/cache-api`)
+// =============================================================================
+
+/// Cache entry rejected: the key, value or tag list exceeds the per-entry
+/// limits.
+pub const ERR_CACHE_ENTRY_TOO_LARGE: i32 = -50;
+
+/// Cache entry rejected: the plugin already holds its maximum number of
+/// cache entries.
+pub const ERR_CACHE_QUOTA_EXCEEDED: i32 = -51;
+
+// =============================================================================
+// Render errors (`trovato:kernel/render`)
+// =============================================================================
+
+/// The render handle was not opened by this tap call.
+pub const ERR_RENDER_INVALID_HANDLE: i32 = -60;
+
+/// Render output rejected: the tap reached one of the `RENDER_MAX_*` limits
+/// in [`crate::types`].
+pub const ERR_RENDER_LIMIT_EXCEEDED: i32 = -61;
+
+// =============================================================================
+// Event errors (`trovato:kernel/events`)
+// =============================================================================
+
+/// Event refused to prevent a loop: it is already being handled further up
+/// the dispatch chain, or the chain is [`crate::types::EVENT_MAX_DEPTH`]
+/// events deep. Also returned by `classify` when called from a
+/// `tap_ng_classify` handler.
+pub const ERR_EVENT_LOOP: i32 = -70;
+
+/// Event rejected: the name is not a valid event name or the payload
+/// exceeds [`crate::types::EVENT_MAX_PAYLOAD_BYTES`].
+pub const ERR_EVENT_INVALID: i32 = -71;
+
+// =============================================================================
+// Database transaction errors (`trovato:kernel/db`)
+// =============================================================================
+
+/// `begin` called while the tap already has an open transaction.
+pub const ERR_TRANSACTION_OPEN: i32 = -80;
+
+/// `commit` or `rollback` called without an open transaction.
+pub const ERR_NO_TRANSACTION: i32 = -81;
+
 // =============================================================================
 // SDK-side errors (client-side, before/after crossing WASM boundary)
 // =============================================================================
@@ -238,3 +428,137 @@ pub const ERR_SDK_DESERIALIZE: i32 = -102;
 /// was larger. The returned data would be truncated and invalid.
 /// Plugins should reduce their result set (add LIMIT) or paginate.
 pub const ERR_SDK_OUTPUT_BUFFER_EXCEEDED: i32 = -103;
+
+// =============================================================================
+// Typed errors
+// =============================================================================
+
+/// A failed host function call, as returned by the wrappers in
+/// [`crate::host`].
+///
+/// Codes with a meaning plugins commonly act on get their own variant;
+/// everything else is kept as [`HostError::Other`] with the raw code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostError {
+    /// A record the call refers to does not exist.
+    NotFound,
+    /// The statement or operation is not allowed for this plugin or user
+    /// (DDL through `execute_raw`, missing AI permission).
+    PermissionDenied,
+    /// The database rejected the statement. `code` is its SQLSTATE when
+    /// the kernel reported one, e.g. `"23505"` for a unique violation.
+    QueryError { code: Option<String> },
+    /// The statement or request timed out.
+    Timeout,
+    /// A rate limit, budget or storage quota was reached.
+    QuotaExceeded,
+    /// Any other failure; `code` is one of the `ERR_*` constants.
+    Other { code: i32 },
+}
+
+impl HostError {
+    /// Decode a negative host function return code.
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            ERR_NOT_FOUND => Self::NotFound,
+            ERR_DDL_REJECTED | ERR_AI_PERMISSION_DENIED => Self::PermissionDenied,
+            ERR_SQL_FAILED => Self::QueryError { code: None },
+            ERR_HTTP_TIMEOUT => Self::Timeout,
+            ERR_AI_RATE_LIMITED
+            | ERR_AI_BUDGET_EXCEEDED
+            | ERR_CACHE_QUOTA_EXCEEDED
+            | ERR_RENDER_LIMIT_EXCEEDED => Self::QuotaExceeded,
+            _ => match decode_sql_state(code) {
+                Some(state) if state == SQL_STATE_QUERY_CANCELED => Self::Timeout,
+                Some(state) => Self::QueryError { code: Some(state) },
+                None => Self::Other { code },
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for HostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "not found"),
+            Self::PermissionDenied => write!(f, "permission denied"),
+            Self::QueryError { code: Some(code) } => write!(f, "query failed (SQLSTATE {code})"),
+            Self::QueryError { code: None } => write!(f, "query failed"),
+            Self::Timeout => write!(f, "timed out"),
+            Self::QuotaExceeded => write!(f, "quota exceeded"),
+            Self::Other { code } => write!(f, "host error {code}"),
+        }
+    }
+}
+
+impl std::error::Error for HostError {}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sql_states_round_trip() {
+        for state in ["00000", "23505", "57014", "42P01", "ZZZZZ"] {
+            let code = encode_sql_state(state).unwrap();
+            assert!(code <= ERR_SQL_STATE_BASE, "{state} encoded as {code}");
+            assert_eq!(decode_sql_state(code).as_deref(), Some(state));
+        }
+    }
+
+    #[test]
+    fn malformed_sql_states_are_not_encoded() {
+        assert_eq!(encode_sql_state("2350"), None);
+        assert_eq!(encode_sql_state("23505X"), None);
+        assert_eq!(encode_sql_state("42p01"), None);
+        assert_eq!(encode_sql_state("23-05"), None);
+        assert_eq!(decode_sql_state(ERR_SQL_FAILED), None);
+    }
+
+    #[test]
+    fn codes_decode_to_typed_errors() {
+        assert_eq!(HostError::from_code(ERR_NOT_FOUND), HostError::NotFound);
+        assert_eq!(
+            HostError::from_code(ERR_DDL_REJECTED),
+            HostError::PermissionDenied
+        );
+        assert_eq!(
+            HostError::from_code(ERR_SQL_FAILED),
+            HostError::QueryError { code: None }
+        );
+        assert_eq!(
+            HostError::from_code(encode_sql_state("23505").unwrap()),
+            HostError::QueryError {
+                code: Some("23505".to_string())
+            }
+        );
+        assert_eq!(
+            HostError::from_code(encode_sql_state("57014").unwrap()),
+            HostError::Timeout
+        );
+        assert_eq!(HostError::from_code(ERR_HTTP_TIMEOUT), HostError::Timeout);
+        assert_eq!(
+            HostError::from_code(ERR_CACHE_QUOTA_EXCEEDED),
+            HostError::QuotaExceeded
+        );
+        assert_eq!(
+            HostError::from_code(ERR_RENDER_LIMIT_EXCEEDED),
+            HostError::QuotaExceeded
+        );
+        assert_eq!(
+            HostError::from_code(ERR_SDK_UTF8),
+            HostError::Other { code: ERR_SDK_UTF8 }
+        );
+    }
+
+    #[test]
+    fn host_errors_display_their_cause() {
+        let unique = HostError::QueryError {
+            code: Some("23505".to_string()),
+        };
+        assert_eq!(unique.to_string(), "query failed (SQLSTATE 23505)");
+        assert_eq!(HostError::Other { code: -33 }.to_string(), "host error -33");
+    }
+}
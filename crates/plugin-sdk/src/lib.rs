@@ -4,6 +4,9 @@
 //! Plugins depend on this crate and use its proc macros and builder APIs
 //! to interact with the Kernel across the WASM boundary.
 
+#[doc(hidden)]
+pub mod abi;
+pub mod form;
 pub mod host;
 pub mod host_errors;
 pub mod render;
@@ -17,6 +20,8 @@ pub use trovato_sdk_macros::{plugin_tap, plugin_tap_result};
 pub use serde_json;
 
 pub mod prelude {
+    pub use crate::form;
+    pub use crate::host_errors::HostError;
     pub use crate::render;
     pub use crate::types::*;
     pub use crate::{plugin_tap, plugin_tap_result};
@@ -2,11 +2,19 @@
 //!
 //! Plugins return structured JSON render elements (never raw HTML).
 //! The Kernel sanitizes and renders these via Tera templates.
+//!
+//! Taps with very large output can build it on the host instead of holding
+//! the whole tree in plugin memory: open a [`RenderHandle`] with
+//! [`crate::host::render_begin`], append elements to it, and return the
+//! handle. The kernel replaces the returned handle with a `container`
+//! holding the appended elements in order.
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::BTreeMap;
 
+use crate::host_errors::{self, HostError};
+
 /// A render element in the JSON render tree.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RenderElement {
@@ -189,3 +197,55 @@ pub fn link(href: &str, text: &str) -> ElementBuilder {
     b.attrs.insert("href".into(), Value::String(href.into()));
     b
 }
+
+/// A render output being built on the host.
+///
+/// Serializes as `{"#render_handle": id}`; returning it from a tap tells
+/// the kernel to substitute the elements appended to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderHandle {
+    #[serde(rename = "#render_handle")]
+    id: i32,
+}
+
+impl RenderHandle {
+    /// Wrap a handle ID issued by the host.
+    ///
+    /// Only [`crate::host::NativeHost`] implementations need this; plugins
+    /// get handles from [`crate::host::render_begin`].
+    pub fn from_id(id: i32) -> Self {
+        Self { id }
+    }
+
+    /// The host's ID for this handle.
+    pub fn id(self) -> i32 {
+        self.id
+    }
+
+    /// Append an element to the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns the decoded [`HostError`] from [`crate::host::render_append`].
+    pub fn append(self, element: &RenderElement) -> Result<(), HostError> {
+        let json = serde_json::to_string(element)
+            .map_err(|_| HostError::from_code(host_errors::ERR_SDK_SERIALIZE))?;
+        crate::host::render_append(self, &json)
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_handle_serializes_as_marker() {
+        let handle = RenderHandle::from_id(3);
+        assert_eq!(
+            serde_json::to_value(handle).unwrap(),
+            serde_json::json!({"#render_handle": 3})
+        );
+    }
+}
@@ -216,6 +216,201 @@ pub struct ContentTypeDefinition {
     #[serde(default)]
     pub title_label: Option<String>,
     pub fields: Vec<FieldDefinition>,
+    /// HTTP caching metadata for pages displaying items of this type.
+    ///
+    /// `None` leaves caching to the site defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheMetadata>,
+    /// Groups that fields are placed in (see [`FieldDefinition::field_group`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub field_groups: Vec<FieldGroup>,
+    /// Machine name of a type or template whose fields this type inherits
+    /// (see [`ContentTypeDefinition::extends`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// A field template: other types extend it, but it is not registered
+    /// as a content type itself.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub template: bool,
+}
+
+impl ContentTypeDefinition {
+    /// Inherit the fields of `base`, another type or template declared by
+    /// any enabled plugin.
+    ///
+    /// The kernel resolves inheritance in `sync_from_plugins`: base fields
+    /// come first, and a field of this type with the same name replaces the
+    /// base field in place. Field groups merge the same way, and an unset
+    /// title label or cache metadata is taken from the base.
+    pub fn extends(mut self, base: &str) -> Self {
+        self.extends = Some(base.to_string());
+        self
+    }
+
+    /// Declare this definition a field template rather than a content type.
+    pub fn as_template(mut self) -> Self {
+        self.template = true;
+        self
+    }
+
+    /// This definition with the fields, field groups and defaults of its
+    /// (already resolved) base merged in.
+    pub fn inherit(mut self, base: &ContentTypeDefinition) -> Self {
+        let mut fields = base.fields.clone();
+        for field in self.fields {
+            match fields.iter_mut().find(|f| f.field_name == field.field_name) {
+                Some(inherited) => *inherited = field,
+                None => fields.push(field),
+            }
+        }
+        self.fields = fields;
+
+        let mut groups = base.field_groups.clone();
+        for group in self.field_groups {
+            match groups.iter_mut().find(|g| g.name == group.name) {
+                Some(inherited) => *inherited = group,
+                None => groups.push(group),
+            }
+        }
+        self.field_groups = groups;
+
+        if self.title_label.is_none() {
+            self.title_label.clone_from(&base.title_label);
+        }
+        if self.cache.is_none() {
+            self.cache.clone_from(&base.cache);
+        }
+        self
+    }
+}
+
+/// A group of fields, rendered as a fieldset on forms and a wrapper on
+/// item pages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldGroup {
+    /// Machine name referenced by [`FieldDefinition::field_group`].
+    pub name: String,
+    pub label: String,
+    /// Groups are ordered by weight, lightest first.
+    #[serde(default)]
+    pub weight: i32,
+    /// Render the group collapsed on forms.
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+/// How pages displaying a content type may be cached.
+///
+/// When a page displays items of several types, the shortest lifetimes and
+/// the union of the contexts apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheMetadata {
+    /// Lifetime in seconds for the page cache and `Cache-Control: max-age`.
+    /// `None` keeps the site default; `0` disables caching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+    /// Lifetime in seconds for shared caches (`Surrogate-Control`).
+    /// Defaults to `max_age`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_max_age: Option<u64>,
+    /// Request properties the rendered output varies by.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contexts: Vec<CacheContext>,
+}
+
+/// A request property that cached output varies by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheContext {
+    /// Negotiated language (`Vary: Accept-Language`).
+    Language,
+    /// The visitor's roles; anonymous and logged-in visitors differ
+    /// (`Vary: Cookie`).
+    Role,
+    /// The individual visitor; only private caches may store the page.
+    User,
+}
+
+/// Input to `tap_item_type_update`: a content type before and after an
+/// administrator changed it (label, settings, or fields), or after a plugin
+/// sync changed its fields (for example because a base type it
+/// [extends](ContentTypeDefinition::extends) changed).
+///
+/// Dispatched after the change is saved. Items keep stored values for
+/// removed fields; plugins that need to migrate or purge that data should
+/// do so here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemTypeUpdate {
+    /// Definition before the change.
+    pub old: ContentTypeDefinition,
+    /// Definition after the change.
+    pub new: ContentTypeDefinition,
+}
+
+impl ItemTypeUpdate {
+    /// Fields present in `new` but not in `old`.
+    pub fn added_fields(&self) -> Vec<&str> {
+        field_names_missing_from(&self.new, &self.old)
+    }
+
+    /// Fields present in `old` but not in `new`.
+    pub fn removed_fields(&self) -> Vec<&str> {
+        field_names_missing_from(&self.old, &self.new)
+    }
+
+    /// Fields present in both whose field type changed.
+    ///
+    /// Stored values are not converted; they keep the old type's shape.
+    pub fn retyped_fields(&self) -> Vec<&str> {
+        self.new
+            .fields
+            .iter()
+            .filter(|f| {
+                self.old.fields.iter().any(|g| {
+                    g.field_name == f.field_name
+                        && serde_json::to_value(&g.field_type).ok()
+                            != serde_json::to_value(&f.field_type).ok()
+                })
+            })
+            .map(|f| f.field_name.as_str())
+            .collect()
+    }
+}
+
+/// Names of the fields of `a` that `b` does not have.
+fn field_names_missing_from<'a>(
+    a: &'a ContentTypeDefinition,
+    b: &ContentTypeDefinition,
+) -> Vec<&'a str> {
+    a.fields
+        .iter()
+        .filter(|f| !b.fields.iter().any(|g| g.field_name == f.field_name))
+        .map(|f| f.field_name.as_str())
+        .collect()
+}
+
+/// Full-text search weight for an indexed field.
+///
+/// Maps directly to PostgreSQL `setweight()` classes: `A` is the highest
+/// relevance (the item title always uses `A`), `D` the lowest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchWeight {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl SearchWeight {
+    /// The weight as the single character stored in `search_field_config`.
+    pub fn as_char(self) -> char {
+        match self {
+            Self::A => 'A',
+            Self::B => 'B',
+            Self::C => 'C',
+            Self::D => 'D',
+        }
+    }
 }
 
 /// A single field definition within a content type.
@@ -237,12 +432,238 @@ pub struct FieldDefinition {
     /// for deletion/anonymization. Default `false` for backward compatibility.
     #[serde(default)]
     pub personal_data: bool,
+
+    /// Whether changes to this field are recorded in the per-field history log.
+    ///
+    /// Intended for sensitive fields (ownership, thresholds) where a compact
+    /// "who changed what, when" timeline is needed without diffing full
+    /// revisions. Default `false`.
+    #[serde(default)]
+    pub track_history: bool,
+
+    /// Full-text search weight for this field.
+    ///
+    /// When set, the kernel indexes the field in the item's `search_vector`
+    /// with this weight. Synced into `search_field_config` on startup;
+    /// weights configured manually by an administrator take precedence.
+    #[serde(default)]
+    pub search_weight: Option<SearchWeight>,
+
+    /// Form widget; `None` uses the default widget for the field type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget: Option<FieldWidget>,
+
+    /// How the field is displayed per view mode; `None` shows it on the
+    /// item page with the default formatter and hides it in teasers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<FieldDisplay>,
+
+    /// Machine name of the [`FieldGroup`] the field belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_group: Option<String>,
+
+    /// Value stored when an item is created without one.
+    ///
+    /// Administrator-configured field default rules take precedence. The
+    /// value is also prefilled on add forms.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<serde_json::Value>,
+
+    /// Constraints the kernel enforces when an item is saved.
+    #[serde(default, skip_serializing_if = "FieldConstraints::is_empty")]
+    pub constraints: FieldConstraints,
 }
 
 fn default_cardinality() -> i32 {
     1
 }
 
+/// Value constraints of a field, enforced kernel-side at item save.
+///
+/// Empty values are not checked (use `required` for that). Each value of a
+/// multi-value field is checked on its own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldConstraints {
+    /// Smallest allowed value of an `Integer` or `Float` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Largest allowed value of an `Integer` or `Float` field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// The only values a text field may hold; empty allows any value.
+    /// Forms offer them as a select list.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+    /// Regular expression the whole value of a text field must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl FieldConstraints {
+    /// Whether no constraint is set.
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none()
+            && self.max.is_none()
+            && self.allowed_values.is_empty()
+            && self.pattern.is_none()
+    }
+}
+
+/// The form widget used to edit a field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldWidget {
+    #[serde(rename = "type")]
+    pub widget_type: WidgetType,
+    /// Widget settings: `placeholder`, `rows` (textarea), `options`
+    /// (select and radios: a list of values or `{value, label}` objects).
+    #[serde(default)]
+    pub settings: serde_json::Value,
+}
+
+/// Form widget types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WidgetType {
+    /// Single-line text input.
+    Textfield,
+    /// Multi-line text area.
+    Textarea,
+    /// Drop-down list of `options`.
+    Select,
+    /// Radio buttons for `options`.
+    Radios,
+    /// Single checkbox.
+    Checkbox,
+    /// Hidden input; the value is kept but not shown.
+    Hidden,
+    /// The default widget for the field type (also used for unknown types).
+    #[serde(other)]
+    Default,
+}
+
+/// A view mode items are displayed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewMode {
+    /// The item's own page.
+    Full,
+    /// Short form used in listings.
+    Teaser,
+}
+
+/// Per-view-mode display settings of a field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDisplay {
+    #[serde(default)]
+    pub full: FieldFormatter,
+    #[serde(default = "FieldFormatter::hidden")]
+    pub teaser: FieldFormatter,
+}
+
+impl Default for FieldDisplay {
+    fn default() -> Self {
+        Self {
+            full: FieldFormatter::default(),
+            teaser: FieldFormatter::hidden(),
+        }
+    }
+}
+
+/// How a field value is rendered in one view mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldFormatter {
+    #[serde(default)]
+    pub formatter: FormatterType,
+    #[serde(default)]
+    pub label: LabelDisplay,
+    /// Fields are ordered by weight, lightest first; ties keep the
+    /// definition order.
+    #[serde(default)]
+    pub weight: i32,
+    /// Formatter settings: `trim_length` for `trimmed` (default 200);
+    /// `responsive_style` and `alt` for `responsive_image`.
+    #[serde(default)]
+    pub settings: serde_json::Value,
+}
+
+impl Default for FieldFormatter {
+    fn default() -> Self {
+        Self::new(FormatterType::Default)
+    }
+}
+
+impl FieldFormatter {
+    pub fn new(formatter: FormatterType) -> Self {
+        Self {
+            formatter,
+            label: LabelDisplay::default(),
+            weight: 0,
+            settings: serde_json::Value::Object(Default::default()),
+        }
+    }
+
+    /// A formatter that does not render the field.
+    pub fn hidden() -> Self {
+        Self::new(FormatterType::Hidden)
+    }
+
+    pub fn label(mut self, label: LabelDisplay) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn weight(mut self, weight: i32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set one formatter setting.
+    pub fn setting(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        if let serde_json::Value::Object(ref mut settings) = self.settings {
+            settings.insert(key.to_string(), value.into());
+        } else {
+            self.settings = serde_json::json!({ key: value.into() });
+        }
+        self
+    }
+
+    /// Whether the field is rendered at all.
+    pub fn is_visible(&self) -> bool {
+        self.formatter != FormatterType::Hidden
+    }
+}
+
+/// Field formatters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatterType {
+    /// Render the value in its text format (or type-specific rendering).
+    #[default]
+    Default,
+    /// Render the value as plain text, dropping markup.
+    Plain,
+    /// Plain text cut to `trim_length` characters.
+    Trimmed,
+    /// An image file as a `<picture>` of the `responsive_style` setting's
+    /// responsive image style (requires `trovato_image_styles`).
+    ResponsiveImage,
+    /// Do not render the field.
+    Hidden,
+}
+
+/// Where a field's label is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelDisplay {
+    /// On its own line above the value.
+    Above,
+    /// Before the value on the same line.
+    #[default]
+    Inline,
+    /// Not shown.
+    Hidden,
+}
+
 impl FieldDefinition {
     pub fn new(name: &str, field_type: FieldType) -> Self {
         Self {
@@ -253,6 +674,13 @@ impl FieldDefinition {
             cardinality: 1,
             settings: serde_json::Value::Object(Default::default()),
             personal_data: false,
+            track_history: false,
+            search_weight: None,
+            widget: None,
+            display: None,
+            field_group: None,
+            default_value: None,
+            constraints: FieldConstraints::default(),
         }
     }
 
@@ -270,6 +698,112 @@ impl FieldDefinition {
         self.cardinality = n;
         self
     }
+
+    /// Record every change to this field in the field history log.
+    pub fn track_history(mut self) -> Self {
+        self.track_history = true;
+        self
+    }
+
+    /// Index this field in full-text search with the given weight.
+    pub fn search_weight(mut self, weight: SearchWeight) -> Self {
+        self.search_weight = Some(weight);
+        self
+    }
+
+    /// Edit this field with `widget` and its settings.
+    pub fn widget(mut self, widget: WidgetType, settings: serde_json::Value) -> Self {
+        self.widget = Some(FieldWidget {
+            widget_type: widget,
+            settings,
+        });
+        self
+    }
+
+    /// Set how this field is displayed per view mode.
+    pub fn display(mut self, display: FieldDisplay) -> Self {
+        self.display = Some(display);
+        self
+    }
+
+    /// Place this field in the [`FieldGroup`] named `group`.
+    pub fn group(mut self, group: &str) -> Self {
+        self.field_group = Some(group.into());
+        self
+    }
+
+    /// Store `value` when an item is created without this field.
+    pub fn default_value(mut self, value: impl Into<serde_json::Value>) -> Self {
+        self.default_value = Some(value.into());
+        self
+    }
+
+    /// Require numeric values of at least `min`.
+    pub fn min(mut self, min: f64) -> Self {
+        self.constraints.min = Some(min);
+        self
+    }
+
+    /// Require numeric values of at most `max`.
+    pub fn max(mut self, max: f64) -> Self {
+        self.constraints.max = Some(max);
+        self
+    }
+
+    /// Restrict the field to an enumeration of `values`.
+    pub fn allowed_values(mut self, values: &[&str]) -> Self {
+        self.constraints.allowed_values = values.iter().map(|v| (*v).to_string()).collect();
+        self
+    }
+
+    /// Require values to match the regular expression `pattern` in full.
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.constraints.pattern = Some(pattern.into());
+        self
+    }
+
+    /// The widget type used on forms.
+    pub fn widget_type(&self) -> WidgetType {
+        self.widget
+            .as_ref()
+            .map_or(WidgetType::Default, |w| w.widget_type)
+    }
+
+    /// The formatter for `mode`, or `None` if the field is hidden there.
+    pub fn formatter(&self, mode: ViewMode) -> Option<FieldFormatter> {
+        let display = self.display.clone().unwrap_or_default();
+        let formatter = match mode {
+            ViewMode::Full => display.full,
+            ViewMode::Teaser => display.teaser,
+        };
+        formatter.is_visible().then_some(formatter)
+    }
+
+    /// What happens to items holding this record reference when the
+    /// referenced item goes away: `unpublish`, `delete`, `restrict` (refuse
+    /// to delete the referenced item) or `set_null` (remove the reference).
+    ///
+    /// Administrators can change the rule on the field's edit form.
+    pub fn on_delete(mut self, rule: &str) -> Self {
+        if let serde_json::Value::Object(settings) = &mut self.settings {
+            settings.insert("cascade".into(), serde_json::Value::from(rule));
+        } else {
+            self.settings = serde_json::json!({ "cascade": rule });
+        }
+        self
+    }
+
+    /// Let users edit this `tap_user_info` field on their own profile page.
+    ///
+    /// Without it, only administrators see the field.
+    pub fn user_editable(mut self) -> Self {
+        if let serde_json::Value::Object(settings) = &mut self.settings {
+            settings.insert("user_editable".into(), serde_json::Value::Bool(true));
+        } else {
+            self.settings = serde_json::json!({ "user_editable": true });
+        }
+        self
+    }
 }
 
 /// Input for `tap_item_access`.
@@ -296,6 +830,11 @@ pub struct ItemAccessInput {
     #[serde(default)]
     pub user_permissions: Vec<String>,
 
+    /// Names of the user's roles, including the built-in "anonymous user"
+    /// or "authenticated user" role.
+    #[serde(default)]
+    pub user_roles: Vec<String>,
+
     /// Stage UUID (None if item has no explicit stage).
     #[serde(default)]
     pub stage_id: Option<Uuid>,
@@ -305,6 +844,349 @@ pub struct ItemAccessInput {
     pub stage_machine_name: Option<String>,
 }
 
+impl ItemAccessInput {
+    /// Whether the user holds `permission`.
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.user_permissions.iter().any(|p| p == permission)
+    }
+
+    /// Whether the user has the role named `role`.
+    pub fn has_role(&self, role: &str) -> bool {
+        self.user_roles.iter().any(|r| r == role)
+    }
+
+    /// Whether the user is the item's author (never true for anonymous).
+    pub fn is_author(&self) -> bool {
+        self.user_authenticated && self.user_id == self.author_id
+    }
+}
+
+/// Input for `tap_transition`.
+///
+/// Sent by the kernel after an item moves between moderation states. The
+/// item's `status` already reflects the target state.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/content/item_service.rs`.
+/// The kernel serializes its copy; plugins deserialize this one. Both must have
+/// the same fields and serde attributes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitionInput {
+    pub item_id: Uuid,
+    pub item_type: String,
+    /// Workflow machine name.
+    pub workflow: String,
+    /// State the item left.
+    pub from: String,
+    /// State the item entered.
+    pub to: String,
+    /// User who made the transition.
+    pub user_id: Uuid,
+}
+
+/// Input for `tap_item_validate`.
+///
+/// Sent by the kernel before an item is created or updated, after
+/// `tap_item_presave` has run, so plugins see the payload that would be
+/// saved. Return an empty list to accept it.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/content/item_service.rs`.
+/// The kernel serializes its copy; plugins deserialize this one. Both must have
+/// the same fields and serde attributes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemValidateInput {
+    /// Item ID (`None` while the item is being created).
+    #[serde(default)]
+    pub item_id: Option<Uuid>,
+    pub item_type: String,
+    pub title: String,
+    /// Field values keyed by field machine name.
+    pub fields: serde_json::Value,
+    pub status: i16,
+    /// User saving the item.
+    pub user_id: Uuid,
+}
+
+/// Input for `tap_item_clone`.
+///
+/// Sent by the kernel before a copy of an item is created. The copy
+/// starts unpublished with the source's fields (or, when copied into
+/// another language, its translation); return an [`ItemCloneResult`] to
+/// change them.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/content/item_service.rs`.
+/// The kernel serializes its copy; plugins deserialize this one. Both must have
+/// the same fields and serde attributes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemCloneInput {
+    /// The item being copied.
+    pub source_id: Uuid,
+    pub item_type: String,
+    /// Title of the copy.
+    pub title: String,
+    /// Field values of the copy, keyed by field machine name.
+    pub fields: serde_json::Value,
+    /// Stage the copy is created in.
+    pub stage_id: Uuid,
+    /// Language of the copy.
+    pub language: String,
+    /// User making the copy.
+    pub user_id: Uuid,
+}
+
+/// Field changes returned from `tap_item_clone`.
+///
+/// Changes from all plugins are applied in dispatch order; a `null` value
+/// removes the field from the copy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemCloneResult {
+    /// Replacement field values keyed by field machine name.
+    #[serde(default)]
+    pub fields: HashMap<String, serde_json::Value>,
+}
+
+impl ItemCloneResult {
+    /// No changes: the copy keeps the source's fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace a field value in the copy.
+    pub fn set(mut self, field: &str, value: serde_json::Value) -> Self {
+        self.fields.insert(field.to_string(), value);
+        self
+    }
+
+    /// Remove a field from the copy.
+    pub fn remove(mut self, field: &str) -> Self {
+        self.fields
+            .insert(field.to_string(), serde_json::Value::Null);
+        self
+    }
+}
+
+/// A validation failure returned from `tap_item_validate`.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/content/item_service.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemViolation {
+    /// Field machine name, or `title`.
+    pub field: String,
+    /// Human-readable explanation shown to the editor.
+    pub message: String,
+    /// Machine-readable code (e.g., "required", "invalid_isbn").
+    pub code: String,
+}
+
+impl ItemViolation {
+    /// Create a violation for a field.
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+            code: code.to_string(),
+        }
+    }
+}
+
+/// Input for `tap_config_validate`.
+///
+/// Sent by the kernel before a config entity (a content type, a site
+/// variable, ...) is saved from the admin UI or a config import. Return an
+/// empty list to accept it.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/config_storage/validation.rs`.
+/// The kernel serializes its copy; plugins deserialize this one. Both must have
+/// the same fields and serde attributes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEntityInput {
+    /// Entity type (e.g., "item_type", "variable").
+    pub entity_type: String,
+    /// Entity ID (type machine name, variable key, ...).
+    pub id: String,
+    /// The entity as it would be saved, in its config export shape.
+    pub data: serde_json::Value,
+}
+
+/// A validation failure returned from `tap_config_validate`.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/config_storage/validation.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigViolation {
+    /// Offending key within the entity data (e.g., "value", "settings.threshold").
+    pub field: String,
+    /// Human-readable explanation shown to the administrator.
+    pub message: String,
+    /// Machine-readable code (e.g., "out_of_range").
+    pub code: String,
+}
+
+impl ConfigViolation {
+    /// Create a violation for a key of the entity data.
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+            code: code.to_string(),
+        }
+    }
+}
+
+/// Input for `tap_comment_spam_check`.
+///
+/// Sent by the kernel before a comment is saved, once the honeypot and
+/// time traps have passed. Plugins typically forward it to an external
+/// service (Akismet and the like) with the host HTTP functions.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/services/comment_spam.rs`.
+/// The kernel serializes its copy; plugins deserialize this one. Both must have
+/// the same fields and serde attributes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentSpamCheckInput {
+    /// Item being commented on.
+    pub item_id: Uuid,
+    /// Comment being replied to, if any.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub author_mail: String,
+    pub body: String,
+    /// IP address the comment was posted from.
+    pub client_ip: String,
+    /// `User-Agent` header of the request, if sent.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// Verdict of one spam checker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamVerdict {
+    /// Looks legitimate.
+    Ham,
+    /// Looks like spam.
+    Spam,
+}
+
+/// Result returned from `tap_comment_spam_check`.
+///
+/// A comment is treated as spam when any plugin says so; the site's spam
+/// settings decide whether it is then rejected, held for moderation or
+/// published with a flag.
+///
+/// SYNC: Deserialized by the kernel in `crates/kernel/src/services/comment_spam.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommentSpamCheckResult {
+    pub verdict: SpamVerdict,
+    /// Checker-specific confidence, shown to moderators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// Why the comment was judged spam, shown to moderators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl CommentSpamCheckResult {
+    /// The comment looks legitimate.
+    pub fn ham() -> Self {
+        Self {
+            verdict: SpamVerdict::Ham,
+            score: None,
+            reason: None,
+        }
+    }
+
+    /// The comment looks like spam.
+    pub fn spam(reason: impl Into<String>) -> Self {
+        Self {
+            verdict: SpamVerdict::Spam,
+            score: None,
+            reason: Some(reason.into()),
+        }
+    }
+
+    /// Attach the checker's score.
+    pub fn with_score(mut self, score: f64) -> Self {
+        self.score = Some(score);
+        self
+    }
+}
+
+/// Grant realm matching everyone; use gid `"0"`.
+pub const GRANT_REALM_ALL: &str = "all";
+
+/// Grant realm matching holders of a role; the gid is the role ID.
+pub const GRANT_REALM_ROLE: &str = "role";
+
+/// Grant realm matching a single user; the gid is the user ID.
+pub const GRANT_REALM_USER: &str = "user";
+
+/// An access grant returned from `tap_item_grants`.
+///
+/// `tap_item_grants` receives the saved item and returns the grants for it.
+/// The kernel stores them in the `item_access` table, so listings and search
+/// can filter on them in bulk instead of asking `tap_item_access` per item.
+/// An item with grants is visible only to users matching one of its view
+/// grants; an item without grants falls back to the regular access checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemGrant {
+    /// Realm: [`GRANT_REALM_ALL`], [`GRANT_REALM_ROLE`] or [`GRANT_REALM_USER`].
+    pub realm: String,
+    /// Grant ID within the realm.
+    pub gid: String,
+    #[serde(default)]
+    pub view: bool,
+    #[serde(default)]
+    pub update: bool,
+    #[serde(default)]
+    pub delete: bool,
+}
+
+impl ItemGrant {
+    /// Grant for everyone, with no operations yet.
+    pub fn all() -> Self {
+        Self::new(GRANT_REALM_ALL, "0")
+    }
+
+    /// Grant for holders of a role.
+    pub fn role(role_id: Uuid) -> Self {
+        Self::new(GRANT_REALM_ROLE, &role_id.to_string())
+    }
+
+    /// Grant for a single user.
+    pub fn user(user_id: Uuid) -> Self {
+        Self::new(GRANT_REALM_USER, &user_id.to_string())
+    }
+
+    fn new(realm: &str, gid: &str) -> Self {
+        Self {
+            realm: realm.to_string(),
+            gid: gid.to_string(),
+            view: false,
+            update: false,
+            delete: false,
+        }
+    }
+
+    /// Allow viewing.
+    pub fn allow_view(mut self) -> Self {
+        self.view = true;
+        self
+    }
+
+    /// Allow editing.
+    pub fn allow_update(mut self) -> Self {
+        self.update = true;
+        self
+    }
+
+    /// Allow deleting.
+    pub fn allow_delete(mut self) -> Self {
+        self.delete = true;
+        self
+    }
+}
+
 /// Access control result from `tap_item_access`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AccessResult {
@@ -429,18 +1311,358 @@ impl PermissionDefinition {
     }
 }
 
-/// Input for `tap_cron`.
+/// Largest `state` a `tap_cron` handler may return, in serialized bytes.
+///
+/// Larger state is discarded by the kernel and the previous state kept.
+pub const CRON_STATE_MAX_BYTES: usize = 64 * 1024;
+
+/// Input for `tap_cron`.
+///
+/// Sent by the kernel during each cron cycle to plugins that implement
+/// the `tap_cron` hook. Plugins can use the timestamps to implement
+/// interval-based scheduling (e.g., "run only every 5 minutes").
+///
+/// A handler keeps state between runs by returning a JSON object with a
+/// `state` key; the kernel stores it per plugin and passes it back as
+/// [`state`](Self::state) on the next run. Returning `"state": null`
+/// clears it, and output without a `state` key keeps the previous value.
+///
+/// SYNC: The kernel serializes this as
+/// `{"timestamp": <unix_ts>, "last_run": <unix_ts>|null, "state": <json>|null}`
+/// in `crates/kernel/src/cron/mod.rs`. Both sides must agree on the format.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CronInput {
+    /// Unix timestamp (seconds) when the cron cycle started.
+    pub timestamp: i64,
+    /// Cycle timestamp of this plugin's last successful `tap_cron` run;
+    /// `None` on the first run.
+    #[serde(default)]
+    pub last_run: Option<i64>,
+    /// State returned by the last run, at most [`CRON_STATE_MAX_BYTES`].
+    #[serde(default)]
+    pub state: Option<serde_json::Value>,
+}
+
+/// Input for `tap_event`.
+///
+/// Sent to every other enabled plugin implementing `tap_event` when a
+/// plugin calls [`crate::host::dispatch_event`]. Handlers match on `name`
+/// and ignore events they do not know.
+///
+/// SYNC: Serialized by the kernel in `crates/kernel/src/tap/dispatcher.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginEvent {
+    /// Event name, e.g. `"argus.article_analyzed"`.
+    pub name: String,
+    /// Plugin that dispatched the event.
+    pub source: String,
+    /// Event payload, as passed to `dispatch_event`.
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+/// A multi-step batch declared by a plugin via `tap_batch_define`.
+///
+/// Batches are started by an administrator (`POST /api/batch/plugin/{name}`)
+/// and then driven by the kernel, which calls `tap_batch_process` on the
+/// declaring plugin repeatedly until a step reports `finished`. Work that
+/// does not fit in the starting request continues on later cron cycles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchDefinition {
+    /// Machine name, unique across plugins (e.g., "argus_reembed").
+    pub name: String,
+    /// Human-readable label.
+    pub label: String,
+    /// Optional description shown to administrators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl BatchDefinition {
+    /// Create a batch definition.
+    pub fn new(name: &str, label: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            description: None,
+        }
+    }
+
+    /// Set the description.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+}
+
+/// Input for `tap_batch_process`: one step of a running batch.
+///
+/// SYNC: Serialized by the kernel in `crates/kernel/src/batch/service.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchStepInput {
+    /// Batch operation ID.
+    pub batch_id: String,
+    /// Batch definition name.
+    pub name: String,
+    /// Parameters supplied when the batch was started.
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// Plugin-owned state returned by the previous step (`null` on the first).
+    #[serde(default)]
+    pub sandbox: serde_json::Value,
+    /// Items processed so far.
+    pub processed: u64,
+    /// Total items reported by the previous step (0 if unknown).
+    pub total: u64,
+}
+
+/// Result of one `tap_batch_process` step.
+///
+/// The kernel persists `processed`, `total` and `sandbox` after every step,
+/// so a batch interrupted mid-way resumes from the last completed step.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchStepResult {
+    /// Items processed so far (cumulative).
+    pub processed: u64,
+    /// Total items to process (0 if unknown).
+    #[serde(default)]
+    pub total: u64,
+    /// State to pass to the next step.
+    #[serde(default)]
+    pub sandbox: serde_json::Value,
+    /// Whether the batch is complete.
+    #[serde(default)]
+    pub finished: bool,
+    /// Progress message shown while the batch runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Final result stored on the operation when `finished` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+}
+
+/// Maximum number of items returned by a single [`ItemQuery`].
+pub const ITEM_QUERY_MAX_LIMIT: i64 = 100;
+
+/// Structured item query executed by the kernel via [`crate::host::item_query`].
+///
+/// Unlike `query_raw`, the kernel builds the SQL itself: results are
+/// restricted to the requested stage (plus live), non-admin callers only
+/// see published items they may access, and every value is bound as a
+/// parameter. Background contexts (cron, batch) are unrestricted.
+///
+/// SYNC: Deserialized by the kernel in `crates/kernel/src/content/item_query.rs`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ItemQuery {
+    /// Restrict to one content type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+    /// Restrict to a publication status (0 or 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<i16>,
+    /// Stage machine name or UUID. Items in the live stage are always
+    /// included; `None` queries the live stage only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// Field predicates, combined with AND.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ItemFieldPredicate>,
+    /// Sort order; defaults to `changed` descending.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sort: Vec<ItemQuerySort>,
+    /// Maximum items (default and cap: [`ITEM_QUERY_MAX_LIMIT`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    /// Items to skip.
+    #[serde(default)]
+    pub offset: i64,
+}
+
+impl ItemQuery {
+    /// Create an empty query (live stage, newest changes first).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to a content type.
+    pub fn item_type(mut self, item_type: &str) -> Self {
+        self.item_type = Some(item_type.to_string());
+        self
+    }
+
+    /// Restrict to a publication status.
+    pub fn status(mut self, status: i16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Query a stage (machine name or UUID) overlaid on live.
+    pub fn stage(mut self, stage: &str) -> Self {
+        self.stage = Some(stage.to_string());
+        self
+    }
+
+    /// Add a field predicate.
+    pub fn field(mut self, field: &str, op: ItemQueryOp, value: serde_json::Value) -> Self {
+        self.fields.push(ItemFieldPredicate {
+            field: field.to_string(),
+            op,
+            value,
+        });
+        self
+    }
+
+    /// Add a sort key.
+    pub fn sort(mut self, field: &str, direction: SortDirection) -> Self {
+        self.sort.push(ItemQuerySort {
+            field: field.to_string(),
+            direction,
+        });
+        self
+    }
+
+    /// Set the maximum number of items.
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the number of items to skip.
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// A predicate on a JSONB field of an item.
+///
+/// Number values compare numerically, strings compare as text, and booleans
+/// support only `eq`/`ne`. `exists`/`not_exists` ignore `value`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemFieldPredicate {
+    /// Field machine name (e.g., "field_publish_on").
+    pub field: String,
+    /// Comparison operator.
+    pub op: ItemQueryOp,
+    /// Value to compare against.
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+/// Comparison operator for [`ItemFieldPredicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemQueryOp {
+    /// Equal.
+    Eq,
+    /// Not equal.
+    Ne,
+    /// Less than.
+    Lt,
+    /// Less than or equal.
+    Lte,
+    /// Greater than.
+    Gt,
+    /// Greater than or equal.
+    Gte,
+    /// Field is present and not null.
+    Exists,
+    /// Field is absent or null.
+    NotExists,
+}
+
+/// A sort key for [`ItemQuery`].
+///
+/// `field` is an item column (`created`, `changed`, `title`, `status`,
+/// `sticky`, `promote`) or a JSONB field name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemQuerySort {
+    /// Column or field to sort by.
+    pub field: String,
+    /// Sort direction.
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    /// Ascending.
+    Asc,
+    /// Descending.
+    #[default]
+    Desc,
+}
+
+/// Maximum number of matches returned by [`crate::host::embedding_search`].
+pub const EMBEDDING_SEARCH_MAX_K: u32 = 100;
+
+/// Maximum dimensions of an embedding accepted by
+/// [`crate::host::embedding_store`].
+pub const EMBEDDING_MAX_DIMENSIONS: usize = 4096;
+
+/// Maximum combined length in bytes of the bin and key passed to
+/// [`crate::host::cache_set`].
+pub const CACHE_MAX_KEY_BYTES: usize = 256;
+
+/// Maximum length in bytes of a value stored by [`crate::host::cache_set`].
+pub const CACHE_MAX_VALUE_BYTES: usize = 64 * 1024;
+
+/// Maximum number of tags attached to one cache entry.
+pub const CACHE_MAX_TAGS: usize = 16;
+
+/// Maximum number of live cache entries a plugin may hold.
+pub const CACHE_MAX_ENTRIES: u64 = 1000;
+
+/// Maximum TTL in seconds of a cache entry (one day).
+pub const CACHE_MAX_TTL_SECS: u32 = 86_400;
+
+/// Maximum number of render handles a tap may open with
+/// [`crate::host::render_begin`].
+pub const RENDER_MAX_HANDLES: usize = 16;
+
+/// Maximum number of elements appended to one render handle.
+pub const RENDER_MAX_ELEMENTS: usize = 10_000;
+
+/// Maximum combined size in bytes of the element JSON appended during one
+/// tap call, across all handles.
+pub const RENDER_MAX_BYTES: usize = 8 * 1024 * 1024;
+
+/// Maximum number of events a dispatch chain may nest: an event handler
+/// dispatching an event whose handler dispatches another, and so on.
+pub const EVENT_MAX_DEPTH: usize = 4;
+
+/// Maximum length in bytes of an event name.
+pub const EVENT_MAX_NAME_BYTES: usize = 64;
+
+/// Maximum length in bytes of an event payload's JSON.
 ///
-/// Sent by the kernel during each cron cycle to plugins that implement
-/// the `tap_cron` hook. Plugins can use the timestamp to implement
-/// interval-based scheduling (e.g., "run only every 5 minutes").
+/// Leaves room for the rest of the [`PluginEvent`] within the 64 KB tap
+/// input limit.
+pub const EVENT_MAX_PAYLOAD_BYTES: usize = 60 * 1024;
+
+/// Whether `name` is a valid event name for [`crate::host::dispatch_event`].
 ///
-/// SYNC: The kernel serializes this as `{"timestamp": <unix_ts>}` in
-/// `crates/kernel/src/cron/mod.rs`. Both sides must agree on the format.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CronInput {
-    /// Unix timestamp (seconds) when the cron cycle started.
-    pub timestamp: i64,
+/// Names are 1 to [`EVENT_MAX_NAME_BYTES`] lowercase ASCII letters, digits,
+/// `_`, `.`, `:` or `-`, e.g. `argus.article_analyzed`.
+pub fn is_valid_event_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= EVENT_MAX_NAME_BYTES
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"_.:-".contains(&b))
+}
+
+/// An item whose stored embedding is close to a search vector.
+///
+/// SYNC: Serialized by the kernel in `crates/kernel/src/search/vector.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingMatch {
+    /// Matching item.
+    pub item_id: Uuid,
+    /// Cosine distance (0.0 = identical direction, 2.0 = opposite).
+    pub distance: f64,
 }
 
 /// An outbound HTTP request made through the kernel's HTTP host function.
@@ -707,6 +1929,415 @@ pub enum AiRequestDecision {
     Deny(String),
 }
 
+/// An outgoing mail message: input and output of `tap_mail_alter`.
+///
+/// Handlers run in weight order, each receiving the previous handler's
+/// result. Return the altered message (or `{}` to leave it unchanged); add
+/// recipients via `cc`/`bcc`, or set `cancel` to suppress delivery.
+///
+/// SYNC: An identical struct exists in `crates/kernel/src/services/mail.rs`.
+/// Both must have the same fields and serde attributes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MailMessage {
+    /// Message kind (template key, e.g. "comment_notification"); empty for
+    /// ad-hoc messages.
+    #[serde(default)]
+    pub key: String,
+    /// Primary recipient.
+    pub to: String,
+    /// Carbon-copy recipients.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cc: Vec<String>,
+    /// Blind carbon-copy recipients.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bcc: Vec<String>,
+    /// Reply-To address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    /// Subject line.
+    pub subject: String,
+    /// Plain text body.
+    #[serde(alias = "body")]
+    pub text_body: String,
+    /// Optional HTML alternative.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+    /// Set to suppress delivery.
+    #[serde(default)]
+    pub cancel: bool,
+}
+
+/// An inbound webhook call: input of `tap_webhook_receive`.
+///
+/// The kernel accepts calls at `/api/hooks/{plugin}/{hook}` for the hooks a
+/// plugin declares under `[webhooks]` in its `.info.toml`, checks them
+/// against the hook's secret, and passes them to the declaring plugin only.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookRequest {
+    /// Hook name from the URL.
+    pub hook: String,
+    /// HTTP method (`GET` or `POST`).
+    pub method: String,
+    /// Request headers with lowercase names; repeated headers are joined
+    /// with `", "`.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Query string parameters.
+    #[serde(default)]
+    pub query: HashMap<String, String>,
+    /// Raw request body, exactly as signed by the sender.
+    #[serde(default)]
+    pub body: String,
+    /// Whether the call was verified against the hook secret. Only `GET`
+    /// calls to signature-verified hooks (such as WebSub intent
+    /// verification) arrive unverified.
+    #[serde(default)]
+    pub verified: bool,
+}
+
+/// Reply to a webhook call: output of `tap_webhook_receive`.
+///
+/// Returning `{}` answers `200 OK` with an empty body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookResponse {
+    /// HTTP status code (default 200).
+    #[serde(default = "default_webhook_status")]
+    pub status: u16,
+    /// Response body.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+    /// Response `Content-Type` (default `text/plain`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+fn default_webhook_status() -> u16 {
+    200
+}
+
+impl Default for WebhookResponse {
+    fn default() -> Self {
+        Self {
+            status: default_webhook_status(),
+            body: String::new(),
+            content_type: None,
+        }
+    }
+}
+
+impl WebhookResponse {
+    /// Reply with a status code and no body.
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            ..Self::default()
+        }
+    }
+
+    /// Reply `200 OK` with a plain text body (e.g. a WebSub challenge).
+    pub fn text(body: impl Into<String>) -> Self {
+        Self {
+            body: body.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// One item of a plugin bulk operation: input of `tap_item_bulk_operation`.
+///
+/// Plugins declare their operations under `[bulk_operations]` in their
+/// `.info.toml`. The kernel checks the requesting user's access to each
+/// item and passes the accessible ones to the declaring plugin only, one
+/// call per item.
+///
+/// SYNC: Serialized by the kernel in `crates/kernel/src/services/item_bulk.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemBulkOperationInput {
+    /// Batch operation ID of the bulk run.
+    pub batch_id: String,
+    /// Operation name.
+    pub operation: String,
+    /// Parameters supplied with the bulk request.
+    #[serde(default)]
+    pub params: serde_json::Value,
+    /// The item to operate on.
+    pub item: Item,
+    /// User who requested the operation.
+    pub user_id: Uuid,
+}
+
+/// Outcome for one item: output of `tap_item_bulk_operation`.
+///
+/// Returning `{}` counts the item as changed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemBulkOperationResult {
+    /// The item needed no change (e.g. it was already archived).
+    #[serde(default)]
+    pub skipped: bool,
+    /// Why the operation failed for this item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ItemBulkOperationResult {
+    /// The item was changed.
+    pub fn applied() -> Self {
+        Self::default()
+    }
+
+    /// The item needed no change.
+    pub fn skipped() -> Self {
+        Self {
+            skipped: true,
+            error: None,
+        }
+    }
+
+    /// The operation failed for this item.
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            skipped: false,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// A flag set or cleared on an item: input of `tap_flag`.
+///
+/// Sent after the change is stored. `count` is the item's count for the
+/// flag afterwards: 0 or 1 for global flags, the number of users who set
+/// it for per-user flags.
+///
+/// SYNC: Serialized by the kernel in `crates/kernel/src/services/flag.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlagInput {
+    /// Flag name (e.g. `bookmark`).
+    pub flag: String,
+    /// `global` or `user`.
+    pub scope: String,
+    pub item_id: Uuid,
+    pub item_type: String,
+    /// User who set or cleared the flag.
+    pub user_id: Uuid,
+    /// Whether the flag was set (`false`: cleared).
+    pub flagged: bool,
+    pub count: i64,
+}
+
+/// A network device being classified: input and output of
+/// `tap_ng_classify`.
+///
+/// Sent through [`crate::host::classify_device`] with the caller's own
+/// `device_type` guess. Handlers are chained in weight order; each sees the
+/// guess as left by the previous one and may replace it.
+///
+/// SYNC: Deserialized by the kernel in `crates/kernel/src/host/device.rs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceFingerprint {
+    /// MAC address as reported by the network.
+    pub mac: String,
+    /// Vendor of the MAC address (see [`crate::host::mac_vendor`]).
+    #[serde(default)]
+    pub vendor: Option<String>,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub os_family: Option<String>,
+    /// Device type guess (e.g. `printer`, `phone`), `None` if unknown.
+    #[serde(default)]
+    pub device_type: Option<String>,
+}
+
+/// A block a plugin can render into a page region: output of
+/// `tap_block_info`.
+///
+/// Administrators place blocks as tiles (type `plugin:{name}`) and choose
+/// their region and visibility. The kernel calls `tap_block_render` on the
+/// declaring plugin to render each placed tile.
+///
+/// SYNC: Deserialized by the kernel in `crates/kernel/src/services/tile.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockDefinition {
+    /// Machine name, unique across plugins (e.g., "recent_posts").
+    pub name: String,
+    /// Human-readable label.
+    pub label: String,
+    /// Optional description shown to administrators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// How long rendered output may be reused, per tile and page path.
+    /// Without a `max_age` the block is rendered on every page view.
+    #[serde(default)]
+    pub cache: CacheMetadata,
+}
+
+impl BlockDefinition {
+    /// Create a block definition.
+    pub fn new(name: &str, label: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            label: label.to_string(),
+            description: None,
+            cache: CacheMetadata::default(),
+        }
+    }
+
+    /// Set the description.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Cache rendered output for `max_age` seconds, varying by `contexts`.
+    pub fn cache(mut self, max_age: u64, contexts: &[CacheContext]) -> Self {
+        self.cache = CacheMetadata {
+            max_age: Some(max_age),
+            shared_max_age: None,
+            contexts: contexts.to_vec(),
+        };
+        self
+    }
+}
+
+/// Input for `tap_block_render`: one placed block on one page.
+///
+/// SYNC: Serialized by the kernel in `crates/kernel/src/services/tile.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRenderInput {
+    /// Block name from [`BlockDefinition::name`].
+    pub block: String,
+    /// Machine name of the tile placing the block.
+    pub tile: String,
+    /// Region the tile is placed in.
+    pub region: String,
+    /// Tile configuration set by the administrator.
+    #[serde(default)]
+    pub config: serde_json::Value,
+    /// Path of the page being rendered.
+    pub path: String,
+    /// Language of the page being rendered.
+    pub language: String,
+    /// Content type of the item, when the page displays one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<String>,
+}
+
+/// Output of `tap_block_render`.
+///
+/// Returning empty `html` hides the block on this page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockRenderOutput {
+    /// Block body. The kernel wraps it in the tile markup and title.
+    #[serde(default)]
+    pub html: String,
+    /// Cache tags of content the block displays (e.g. `item:{id}`), so
+    /// that saving the content invalidates cached copies of the block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl BlockRenderOutput {
+    /// Render `html` with no cache tags.
+    pub fn html(html: impl Into<String>) -> Self {
+        Self {
+            html: html.into(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Hide the block on this page.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Add a cache tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+}
+
+/// Severity of a status report check.
+///
+/// Ordered from least to most severe; the report's overall severity is the
+/// most severe of its checks.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementSeverity {
+    /// Informational only, never a problem.
+    Info,
+    /// The requirement is met.
+    #[default]
+    Ok,
+    /// Something should be looked at, but the site works.
+    Warning,
+    /// The site, or part of it, does not work.
+    Error,
+}
+
+/// A check on the status report at `/admin/reports/status`: output of
+/// `tap_requirements`.
+///
+/// SYNC: Deserialized by the kernel in `crates/kernel/src/services/status_report.rs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Requirement {
+    /// Machine name, unique within the plugin (e.g., "api_key").
+    pub name: String,
+    /// Human-readable title.
+    pub title: String,
+    /// How serious the result is.
+    #[serde(default)]
+    pub severity: RequirementSeverity,
+    /// Short current value (e.g., "configured", "3 days ago").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// What is wrong and how to fix it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl Requirement {
+    /// Create a check with the given severity.
+    pub fn new(name: &str, title: &str, severity: RequirementSeverity) -> Self {
+        Self {
+            name: name.to_string(),
+            title: title.to_string(),
+            severity,
+            value: None,
+            description: None,
+        }
+    }
+
+    /// Create a met requirement.
+    pub fn ok(name: &str, title: &str) -> Self {
+        Self::new(name, title, RequirementSeverity::Ok)
+    }
+
+    /// Create a warning.
+    pub fn warning(name: &str, title: &str) -> Self {
+        Self::new(name, title, RequirementSeverity::Warning)
+    }
+
+    /// Create an error.
+    pub fn error(name: &str, title: &str) -> Self {
+        Self::new(name, title, RequirementSeverity::Error)
+    }
+
+    /// Set the current value.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Set the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
@@ -717,20 +2348,187 @@ mod tests {
     fn cron_input_round_trip() {
         let input = CronInput {
             timestamp: 1_700_000_000,
+            last_run: Some(1_699_999_940),
+            state: Some(serde_json::json!({"cursor": 7})),
         };
         let json = serde_json::to_string(&input).unwrap();
-        assert_eq!(json, r#"{"timestamp":1700000000}"#);
+        assert_eq!(
+            json,
+            r#"{"timestamp":1700000000,"last_run":1699999940,"state":{"cursor":7}}"#
+        );
 
         let parsed: CronInput = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.timestamp, 1_700_000_000);
+        assert_eq!(parsed.last_run, Some(1_699_999_940));
+        assert_eq!(parsed.state, Some(serde_json::json!({"cursor": 7})));
     }
 
     #[test]
     fn cron_input_deserializes_from_kernel_format() {
         // The kernel serializes CronInput directly; plugins must be able to parse it
-        let kernel_json = r#"{"timestamp":1234567890}"#;
+        let kernel_json = r#"{"timestamp":1234567890,"last_run":null,"state":null}"#;
         let input: CronInput = serde_json::from_str(kernel_json).unwrap();
         assert_eq!(input.timestamp, 1_234_567_890);
+        assert_eq!(input.last_run, None);
+
+        // Older kernels send only the timestamp.
+        let input: CronInput = serde_json::from_str(r#"{"timestamp":1}"#).unwrap();
+        assert!(input.state.is_none());
+    }
+
+    #[test]
+    fn event_names_are_validated() {
+        assert!(is_valid_event_name("argus.article_analyzed"));
+        assert!(is_valid_event_name("item:saved-2"));
+        assert!(!is_valid_event_name(""));
+        assert!(!is_valid_event_name("Article Analyzed"));
+        assert!(!is_valid_event_name(&"a".repeat(EVENT_MAX_NAME_BYTES + 1)));
+
+        let event: PluginEvent =
+            serde_json::from_str(r#"{"name":"argus.article_analyzed","source":"argus"}"#).unwrap();
+        assert!(event.payload.is_null());
+    }
+
+    #[test]
+    fn item_validate_input_deserializes_from_kernel_format() {
+        let kernel_json = r#"{"item_type":"book","title":"Dune","fields":{"isbn":"x"},"status":0,"user_id":"00000000-0000-0000-0000-000000000000"}"#;
+        let input: ItemValidateInput = serde_json::from_str(kernel_json).unwrap();
+        assert!(input.item_id.is_none());
+        assert_eq!(input.fields["isbn"], "x");
+
+        let violations = vec![ItemViolation::new(
+            "isbn",
+            "invalid_isbn",
+            "Not a valid ISBN",
+        )];
+        let json = serde_json::to_value(&violations).unwrap();
+        assert_eq!(json[0]["field"], "isbn");
+        assert_eq!(json[0]["code"], "invalid_isbn");
+    }
+
+    #[test]
+    fn item_access_input_role_and_permission_checks() {
+        let kernel_json = r#"{"item_id":"00000000-0000-0000-0000-000000000000","item_type":"blog","author_id":"00000000-0000-0000-0000-000000000001","operation":"edit","user_id":"00000000-0000-0000-0000-000000000001","user_authenticated":true,"user_permissions":["edit own blog content"],"user_roles":["authenticated user","writer"]}"#;
+        let input: ItemAccessInput = serde_json::from_str(kernel_json).unwrap();
+        assert!(input.is_author());
+        assert!(input.has_permission("edit own blog content"));
+        assert!(!input.has_permission("edit blog content"));
+        assert!(input.has_role("writer"));
+        assert!(!input.has_role("editor"));
+
+        // Older kernels send neither roles nor authentication state.
+        let legacy_json = r#"{"item_id":"00000000-0000-0000-0000-000000000000","item_type":"blog","author_id":"00000000-0000-0000-0000-000000000000","operation":"view","user_id":"00000000-0000-0000-0000-000000000000"}"#;
+        let legacy: ItemAccessInput = serde_json::from_str(legacy_json).unwrap();
+        assert!(legacy.user_roles.is_empty());
+        assert!(!legacy.is_author());
+    }
+
+    #[test]
+    fn item_clone_result_marks_removed_fields_null() {
+        let kernel_json = r#"{"source_id":"00000000-0000-0000-0000-000000000000","item_type":"page","title":"About","fields":{"field_publish_on":1},"stage_id":"00000000-0000-0000-0000-000000000000","language":"fr","user_id":"00000000-0000-0000-0000-000000000000"}"#;
+        let input: ItemCloneInput = serde_json::from_str(kernel_json).unwrap();
+        assert_eq!(input.language, "fr");
+
+        let result = ItemCloneResult::new()
+            .remove("field_publish_on")
+            .set("field_image", serde_json::json!("file-2"));
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json["fields"]["field_publish_on"].is_null());
+        assert_eq!(json["fields"]["field_image"], "file-2");
+    }
+
+    #[test]
+    fn comment_spam_check_round_trip() {
+        let kernel_json = r#"{"item_id":"00000000-0000-0000-0000-000000000000","author_id":"00000000-0000-0000-0000-000000000000","author_name":"ann","author_mail":"ann@example.com","body":"Buy now","client_ip":"192.0.2.1"}"#;
+        let input: CommentSpamCheckInput = serde_json::from_str(kernel_json).unwrap();
+        assert_eq!(input.parent_id, None);
+        assert_eq!(input.user_agent, None);
+
+        let json =
+            serde_json::to_string(&CommentSpamCheckResult::spam("link farm").with_score(0.9))
+                .unwrap();
+        assert_eq!(
+            json,
+            r#"{"verdict":"spam","score":0.9,"reason":"link farm"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&CommentSpamCheckResult::ham()).unwrap(),
+            r#"{"verdict":"ham"}"#
+        );
+    }
+
+    #[test]
+    fn batch_step_result_defaults() {
+        let result: BatchStepResult = serde_json::from_str(r#"{"processed":10}"#).unwrap();
+        assert_eq!(result.processed, 10);
+        assert_eq!(result.total, 0);
+        assert!(!result.finished);
+        assert!(result.sandbox.is_null());
+    }
+
+    #[test]
+    fn batch_definition_builder() {
+        let def = BatchDefinition::new("argus_reembed", "Re-embed articles")
+            .description("Recompute embeddings for all articles");
+        let json = serde_json::to_value(&def).unwrap();
+        assert_eq!(json["name"], "argus_reembed");
+        assert_eq!(json["description"], "Recompute embeddings for all articles");
+    }
+
+    #[test]
+    fn block_definition_builder() {
+        let def = BlockDefinition::new("recent_posts", "Recent posts")
+            .cache(300, &[CacheContext::Language]);
+        let json = serde_json::to_value(&def).unwrap();
+        assert_eq!(json["name"], "recent_posts");
+        assert_eq!(json["cache"]["max_age"], 300);
+        assert_eq!(json["cache"]["contexts"][0], "language");
+
+        let parsed: BlockDefinition = serde_json::from_str(r#"{"name":"x","label":"X"}"#).unwrap();
+        assert_eq!(parsed.cache, CacheMetadata::default());
+    }
+
+    #[test]
+    fn requirement_builder() {
+        let req = Requirement::warning("api_key", "API key")
+            .value("missing")
+            .description("Set plugin.example.api_key.");
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["severity"], "warning");
+        assert_eq!(json["value"], "missing");
+
+        let parsed: Requirement = serde_json::from_str(r#"{"name":"x","title":"X"}"#).unwrap();
+        assert_eq!(parsed.severity, RequirementSeverity::Ok);
+        assert!(RequirementSeverity::Info < RequirementSeverity::Ok);
+        assert!(RequirementSeverity::Warning < RequirementSeverity::Error);
+    }
+
+    #[test]
+    fn item_query_builder_serializes_compactly() {
+        let query = ItemQuery::new()
+            .item_type("article")
+            .status(0)
+            .field(
+                "field_publish_on",
+                ItemQueryOp::Lte,
+                serde_json::json!(1_700_000_000),
+            )
+            .sort("changed", SortDirection::Desc)
+            .limit(1);
+        let json = serde_json::to_value(&query).unwrap();
+        assert_eq!(json["item_type"], "article");
+        assert_eq!(json["fields"][0]["op"], "lte");
+        assert_eq!(json["sort"][0]["direction"], "desc");
+        assert!(json.get("stage").is_none());
+    }
+
+    #[test]
+    fn item_query_defaults() {
+        let query: ItemQuery = serde_json::from_str(r#"{"sort":[{"field":"title"}]}"#).unwrap();
+        assert!(query.item_type.is_none());
+        assert!(query.fields.is_empty());
+        assert_eq!(query.sort[0].direction, SortDirection::Desc);
+        assert_eq!(query.offset, 0);
     }
 
     // ---- HTTP types ----
@@ -987,6 +2785,11 @@ mod tests {
             !def.personal_data,
             "missing personal_data should default to false"
         );
+        assert!(
+            !def.track_history,
+            "missing track_history should default to false"
+        );
+        assert!(def.search_weight.is_none());
         assert!(def.required);
     }
 
@@ -1005,6 +2808,80 @@ mod tests {
         assert!(def.personal_data);
     }
 
+    #[test]
+    fn field_definition_search_weight_roundtrip() {
+        let def = FieldDefinition::new("field_summary", FieldType::TextLong)
+            .search_weight(SearchWeight::B);
+        let json = serde_json::to_string(&def).unwrap();
+        assert!(json.contains(r#""search_weight":"B""#));
+        let parsed: FieldDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.search_weight, Some(SearchWeight::B));
+        assert_eq!(SearchWeight::B.as_char(), 'B');
+    }
+
+    #[test]
+    fn field_definition_ui_metadata_roundtrip() {
+        let def = FieldDefinition::new("field_kind", FieldType::Text { max_length: None })
+            .widget(
+                WidgetType::Select,
+                serde_json::json!({"options": ["a", "b"]}),
+            )
+            .display(FieldDisplay {
+                full: FieldFormatter::default().label(LabelDisplay::Above),
+                teaser: FieldFormatter::new(FormatterType::Plain).weight(2),
+            })
+            .group("details");
+        let json = serde_json::to_value(&def).unwrap();
+        assert_eq!(json["widget"]["type"], "select");
+        assert_eq!(json["display"]["teaser"]["formatter"], "plain");
+        assert_eq!(json["field_group"], "details");
+
+        let parsed: FieldDefinition = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.widget, def.widget);
+        assert_eq!(parsed.display, def.display);
+        assert_eq!(parsed.widget_type(), WidgetType::Select);
+        assert_eq!(parsed.formatter(ViewMode::Teaser).unwrap().weight, 2);
+
+        // Unset metadata is not serialized, keeping stored settings small.
+        let plain = serde_json::to_value(FieldDefinition::new("f", FieldType::Integer)).unwrap();
+        assert!(plain.get("widget").is_none());
+        assert!(plain.get("display").is_none());
+    }
+
+    #[test]
+    fn formatter_settings_serialize_with_formatter() {
+        let formatter = FieldFormatter::new(FormatterType::ResponsiveImage)
+            .setting("responsive_style", "content")
+            .setting("alt", "Cover");
+        let json = serde_json::to_value(&formatter).unwrap();
+        assert_eq!(json["formatter"], "responsive_image");
+        assert_eq!(json["settings"]["responsive_style"], "content");
+        assert_eq!(json["settings"]["alt"], "Cover");
+    }
+
+    #[test]
+    fn field_display_defaults_show_full_and_hide_teaser() {
+        let def = FieldDefinition::new("f", FieldType::TextLong);
+        assert_eq!(def.widget_type(), WidgetType::Default);
+        assert_eq!(
+            def.formatter(ViewMode::Full),
+            Some(FieldFormatter::default())
+        );
+        assert_eq!(def.formatter(ViewMode::Teaser), None);
+
+        // A display that only configures the full view keeps teasers hidden.
+        let display: FieldDisplay =
+            serde_json::from_str(r#"{"full": {"label": "hidden"}}"#).unwrap();
+        assert_eq!(display.full.label, LabelDisplay::Hidden);
+        assert!(!display.teaser.is_visible());
+    }
+
+    #[test]
+    fn unknown_widget_type_falls_back_to_default() {
+        let widget: FieldWidget = serde_json::from_str(r#"{"type": "color_picker"}"#).unwrap();
+        assert_eq!(widget.widget_type, WidgetType::Default);
+    }
+
     #[test]
     fn no_deny_unknown_fields_on_item() {
         // Verify Item does not use #[serde(deny_unknown_fields)] which
@@ -1048,4 +2925,137 @@ mod tests {
             "FieldDefinition must accept unknown fields: {result:?}"
         );
     }
+
+    #[test]
+    fn item_type_update_lists_added_and_removed_fields() {
+        let def = |fields: &[&str]| ContentTypeDefinition {
+            machine_name: "article".to_string(),
+            label: "Article".to_string(),
+            description: String::new(),
+            title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
+            fields: fields
+                .iter()
+                .map(|f| FieldDefinition::new(f, FieldType::TextLong))
+                .collect(),
+            extends: None,
+            template: false,
+        };
+        let update = ItemTypeUpdate {
+            old: def(&["field_body", "field_summary"]),
+            new: def(&["field_body", "field_image"]),
+        };
+        assert_eq!(update.added_fields(), vec!["field_image"]);
+        assert_eq!(update.removed_fields(), vec!["field_summary"]);
+
+        let json = serde_json::to_string(&update).unwrap();
+        let back: ItemTypeUpdate = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.removed_fields(), vec!["field_summary"]);
+    }
+
+    #[test]
+    fn item_type_update_lists_retyped_fields() {
+        let def = |field_type: FieldType| ContentTypeDefinition {
+            machine_name: "event".to_string(),
+            label: "Event".to_string(),
+            description: String::new(),
+            title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
+            fields: vec![
+                FieldDefinition::new("field_start", field_type),
+                FieldDefinition::new("field_body", FieldType::TextLong),
+            ],
+            extends: None,
+            template: false,
+        };
+        let update = ItemTypeUpdate {
+            old: def(FieldType::Integer),
+            new: def(FieldType::Date),
+        };
+        assert_eq!(update.retyped_fields(), vec!["field_start"]);
+        assert!(update.added_fields().is_empty());
+    }
+
+    #[test]
+    fn content_type_inherits_base_fields_and_defaults() {
+        let base = ContentTypeDefinition {
+            machine_name: "timed".to_string(),
+            label: "Timed".to_string(),
+            description: String::new(),
+            title_label: Some("Name".to_string()),
+            cache: None,
+            field_groups: vec![FieldGroup {
+                name: "when".to_string(),
+                label: "When".to_string(),
+                weight: 0,
+                collapsed: false,
+            }],
+            fields: vec![
+                FieldDefinition::new("field_start", FieldType::Integer).required(),
+                FieldDefinition::new("field_end", FieldType::Integer),
+            ],
+            extends: None,
+            template: true,
+        };
+        let child = ContentTypeDefinition {
+            machine_name: "session".to_string(),
+            label: "Session".to_string(),
+            description: String::new(),
+            title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
+            fields: vec![
+                FieldDefinition::new("field_room", FieldType::Text { max_length: None }),
+                FieldDefinition::new("field_end", FieldType::Integer).required(),
+            ],
+            extends: None,
+            template: false,
+        }
+        .extends("timed")
+        .inherit(&base);
+
+        let names: Vec<&str> = child.fields.iter().map(|f| f.field_name.as_str()).collect();
+        assert_eq!(names, vec!["field_start", "field_end", "field_room"]);
+        assert!(child.fields[1].required);
+        assert_eq!(child.field_groups.len(), 1);
+        assert_eq!(child.title_label.as_deref(), Some("Name"));
+        assert!(!child.template);
+
+        let json = serde_json::to_value(&child).unwrap();
+        assert_eq!(json["extends"], "timed");
+        assert!(json.get("template").is_none());
+        let back: ContentTypeDefinition = serde_json::from_value(json).unwrap();
+        assert_eq!(back.extends.as_deref(), Some("timed"));
+    }
+
+    #[test]
+    fn mail_message_roundtrip() {
+        let json = r#"{"key":"comment_notification","to":"a@example.com","subject":"Hi","text_body":"Hello"}"#;
+        let mut message: MailMessage = serde_json::from_str(json).unwrap();
+        assert!(message.cc.is_empty());
+        assert!(!message.cancel);
+
+        message.bcc.push("audit@example.com".to_string());
+        let back: MailMessage =
+            serde_json::from_str(&serde_json::to_string(&message).unwrap()).unwrap();
+        assert_eq!(back, message);
+    }
+
+    #[test]
+    fn webhook_response_defaults_to_ok() {
+        let response: WebhookResponse = serde_json::from_str("{}").unwrap();
+        assert_eq!(response, WebhookResponse::default());
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            serde_json::to_string(&WebhookResponse::text("abc")).unwrap(),
+            r#"{"status":200,"body":"abc"}"#
+        );
+
+        let request: WebhookRequest =
+            serde_json::from_str(r#"{"hook":"ci","method":"POST"}"#).unwrap();
+        assert!(request.body.is_empty());
+        assert!(!request.verified);
+    }
 }
@@ -4,6 +4,15 @@
 //!
 //! Helpers for integration testing: test fixtures, mock builders,
 //! and assertion utilities for content system testing.
+//!
+//! [`MockHost`] and [`MemoryConfigStorage`] replace Postgres/Redis-backed
+//! services so plugin taps and kernel services can be tested in memory.
+
+mod memory_config;
+mod mock_host;
+
+pub use memory_config::MemoryConfigStorage;
+pub use mock_host::{ExecutedStatement, MockHost, MockHostGuard};
 
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
@@ -104,6 +113,33 @@ impl TestItem {
         self.stage_id = stage_id.to_string();
         self
     }
+
+    /// Convert to the SDK item plugins receive.
+    ///
+    /// A stage of `"live"` (or any non-UUID) maps to the live stage.
+    pub fn to_sdk_item(&self) -> trovato_sdk::types::Item {
+        let fields = self
+            .fields
+            .as_object()
+            .map(|obj| obj.clone().into_iter().collect())
+            .unwrap_or_default();
+        trovato_sdk::types::Item {
+            id: self.id,
+            item_type: self.item_type.clone(),
+            title: self.title.clone(),
+            fields,
+            status: i32::from(self.status),
+            author_id: self.author_id,
+            current_revision_id: None,
+            stage_id: self
+                .stage_id
+                .parse()
+                .unwrap_or_else(|_| trovato_sdk::types::live_stage_id()),
+            created: 0,
+            changed: 0,
+            language: None,
+        }
+    }
 }
 
 /// Create a test user context.
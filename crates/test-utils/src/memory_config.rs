@@ -0,0 +1,196 @@
+//! In-memory [`ConfigStorage`] for kernel unit tests.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use trovato_kernel::{ConfigEntity, ConfigFilter, ConfigStorage};
+
+/// A [`ConfigStorage`] backed by a map, for tests that need config
+/// without Postgres.
+///
+/// Entities are listed in ID order. Filters match against the entity's
+/// serialized fields: strings compare as-is, other values by their JSON
+/// text (so `with_field("is_default", "true")` works for booleans).
+#[derive(Debug, Default)]
+pub struct MemoryConfigStorage {
+    entities: RwLock<BTreeMap<(String, String), ConfigEntity>>,
+}
+
+impl MemoryConfigStorage {
+    /// Create an empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a storage pre-populated with entities.
+    pub fn with_entities(entities: impl IntoIterator<Item = ConfigEntity>) -> Self {
+        let storage = Self::new();
+        {
+            let mut map = storage.entities.write().unwrap();
+            for entity in entities {
+                map.insert(key(&entity), entity);
+            }
+        }
+        storage
+    }
+
+    /// Number of stored entities across all types.
+    pub fn len(&self) -> usize {
+        self.entities.read().unwrap().len()
+    }
+
+    /// Whether the storage holds no entities.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn key(entity: &ConfigEntity) -> (String, String) {
+    (entity.entity_type().to_string(), entity.id())
+}
+
+fn matches_filter(entity: &ConfigEntity, field: &str, expected: &str) -> bool {
+    let Ok(value) = serde_json::to_value(entity) else {
+        return false;
+    };
+    match value.get("data").and_then(|data| data.get(field)) {
+        Some(serde_json::Value::String(s)) => s == expected,
+        Some(other) => other.to_string() == expected,
+        None => false,
+    }
+}
+
+#[async_trait]
+impl ConfigStorage for MemoryConfigStorage {
+    async fn load(&self, entity_type: &str, id: &str) -> Result<Option<ConfigEntity>> {
+        let map = self.entities.read().unwrap();
+        Ok(map.get(&(entity_type.to_string(), id.to_string())).cloned())
+    }
+
+    async fn save(&self, entity: &ConfigEntity) -> Result<()> {
+        self.entities
+            .write()
+            .unwrap()
+            .insert(key(entity), entity.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, entity_type: &str, id: &str) -> Result<bool> {
+        let removed = self
+            .entities
+            .write()
+            .unwrap()
+            .remove(&(entity_type.to_string(), id.to_string()));
+        Ok(removed.is_some())
+    }
+
+    async fn list(
+        &self,
+        entity_type: &str,
+        filter: Option<&ConfigFilter>,
+    ) -> Result<Vec<ConfigEntity>> {
+        let map = self.entities.read().unwrap();
+        let mut entities: Vec<ConfigEntity> = map
+            .iter()
+            .filter(|((t, _), _)| t == entity_type)
+            .map(|(_, entity)| entity.clone())
+            .collect();
+
+        if let Some(f) = filter {
+            if let (Some(field), Some(value)) = (&f.field, &f.value) {
+                entities.retain(|entity| matches_filter(entity, field, value));
+            }
+            if let Some(offset) = f.offset {
+                entities = entities.into_iter().skip(offset).collect();
+            }
+            if let Some(limit) = f.limit {
+                entities.truncate(limit);
+            }
+        }
+        Ok(entities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trovato_kernel::entity_types;
+
+    fn variable(key: &str, value: serde_json::Value) -> ConfigEntity {
+        ConfigEntity::Variable {
+            key: key.to_string(),
+            value,
+        }
+    }
+
+    #[tokio::test]
+    async fn save_load_delete_round_trip() {
+        let storage = MemoryConfigStorage::new();
+        storage
+            .save(&variable("site_name", serde_json::json!("Trovato")))
+            .await
+            .unwrap();
+
+        let loaded = storage
+            .load(entity_types::VARIABLE, "site_name")
+            .await
+            .unwrap();
+        assert!(matches!(loaded, Some(ConfigEntity::Variable { .. })));
+        assert!(
+            storage
+                .exists(entity_types::VARIABLE, "site_name")
+                .await
+                .unwrap()
+        );
+
+        assert!(
+            storage
+                .delete(entity_types::VARIABLE, "site_name")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !storage
+                .delete(entity_types::VARIABLE, "site_name")
+                .await
+                .unwrap()
+        );
+        assert!(storage.is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_filters_by_type_field_and_page() {
+        let storage = MemoryConfigStorage::with_entities([
+            variable("a", serde_json::json!(1)),
+            variable("b", serde_json::json!(2)),
+            variable("c", serde_json::json!(2)),
+        ]);
+
+        let all = storage.list(entity_types::VARIABLE, None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(
+            storage
+                .list(entity_types::ROLE, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let twos = ConfigFilter::new().with_field("value", "2");
+        let matched = storage
+            .list(entity_types::VARIABLE, Some(&twos))
+            .await
+            .unwrap();
+        let ids: Vec<String> = matched.iter().map(ConfigEntity::id).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+
+        let page = ConfigFilter::new().with_offset(1).with_limit(1);
+        let paged = storage
+            .list(entity_types::VARIABLE, Some(&page))
+            .await
+            .unwrap();
+        assert_eq!(paged[0].id(), "b");
+    }
+}
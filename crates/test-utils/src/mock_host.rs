@@ -0,0 +1,767 @@
+//! In-memory implementation of the plugin SDK host functions.
+//!
+//! [`MockHost`] backs the SDK's native host calls so plugin tap functions
+//! can be exercised end-to-end in unit tests: `item_query` filters real
+//! items, `save_item` creates and updates them, `execute_raw` is recorded,
+//! `query_raw` answers from canned rows, and variables and cache entries
+//! round-trip. Elements appended to render handles are kept per handle,
+//! dispatched events are recorded, and MAC vendors, device
+//! classification, saved search alert checks and scheduled update runs
+//! answer from canned values.
+//!
+//! ```ignore
+//! let host = MockHost::new()
+//!     .with_item(test_item("blog", "Hello").to_sdk_item())
+//!     .with_query_result("FROM comment", vec![json!({"cid": 1})])
+//!     .install();
+//!
+//! let out = my_plugin::tap_cron(input);
+//! assert_eq!(host.executed().len(), 1);
+//! ```
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::rc::Rc;
+
+use serde_json::Value as JsonValue;
+use trovato_sdk::host::{self, NativeHost};
+use trovato_sdk::host_errors::{self, HostError};
+use trovato_sdk::render::RenderHandle;
+use trovato_sdk::types::{
+    DeviceFingerprint, ITEM_QUERY_MAX_LIMIT, Item, ItemFieldPredicate, ItemQuery, ItemQueryOp,
+    SortDirection, is_valid_event_name, live_stage_id,
+};
+use uuid::Uuid;
+
+/// A DML statement passed to `execute_raw`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutedStatement {
+    /// SQL text as passed by the plugin.
+    pub sql: String,
+    /// Bound parameters, in placeholder order.
+    pub params: Vec<JsonValue>,
+}
+
+/// A value stored with `cache_set`.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    value: String,
+    tags: Vec<String>,
+}
+
+/// In-memory host for plugin unit tests.
+///
+/// Canned `query_raw` and `execute_raw` responses are matched by SQL
+/// substring, first registered wins. Unmatched queries return no rows and
+/// unmatched statements report zero affected rows, like the plain stubs.
+#[derive(Debug, Default)]
+pub struct MockHost {
+    items: RefCell<Vec<Item>>,
+    query_results: Vec<(String, Vec<JsonValue>)>,
+    execute_results: Vec<(String, u64)>,
+    executed: RefCell<Vec<ExecutedStatement>>,
+    variables: RefCell<HashMap<String, String>>,
+    cache: RefCell<HashMap<(String, String), CacheEntry>>,
+    renders: RefCell<Vec<Vec<JsonValue>>>,
+    events: RefCell<Vec<(String, JsonValue)>>,
+    mac_vendors: HashMap<String, String>,
+    device_classifier: Option<fn(&mut DeviceFingerprint)>,
+    saved_search_alerts: u32,
+    scheduled_updates: u32,
+    user_id: Option<Uuid>,
+    permissions: Option<HashSet<String>>,
+}
+
+impl MockHost {
+    /// Create an empty host: no items, anonymous user, all permissions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item visible to `item_query`.
+    pub fn with_item(self, item: Item) -> Self {
+        self.items.borrow_mut().push(item);
+        self
+    }
+
+    /// Answer `query_raw` calls whose SQL contains `sql_fragment`.
+    pub fn with_query_result(mut self, sql_fragment: &str, rows: Vec<JsonValue>) -> Self {
+        self.query_results.push((sql_fragment.to_string(), rows));
+        self
+    }
+
+    /// Report `rows_affected` for `execute_raw` calls whose SQL contains
+    /// `sql_fragment`.
+    pub fn with_execute_result(mut self, sql_fragment: &str, rows_affected: u64) -> Self {
+        self.execute_results
+            .push((sql_fragment.to_string(), rows_affected));
+        self
+    }
+
+    /// Set a site variable.
+    pub fn with_variable(self, name: &str, value: &str) -> Self {
+        self.variables
+            .borrow_mut()
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Add a cache entry (without tags).
+    pub fn with_cache_entry(self, bin: &str, key: &str, value: &str) -> Self {
+        self.cache.borrow_mut().insert(
+            (bin.to_string(), key.to_string()),
+            CacheEntry {
+                value: value.to_string(),
+                tags: Vec::new(),
+            },
+        );
+        self
+    }
+
+    /// Act as an authenticated user with exactly these permissions.
+    pub fn with_user(mut self, user_id: Uuid, permissions: &[&str]) -> Self {
+        self.user_id = Some(user_id);
+        self.permissions = Some(permissions.iter().map(|p| p.to_string()).collect());
+        self
+    }
+
+    /// Answer `mac_vendor` for MAC addresses starting with `oui` (six hex
+    /// digits).
+    pub fn with_mac_vendor(mut self, oui: &str, vendor: &str) -> Self {
+        self.mac_vendors
+            .insert(oui.to_ascii_uppercase(), vendor.to_string());
+        self
+    }
+
+    /// Refine devices passed to `classify_device` with `classify`, as a
+    /// `tap_ng_classify` handler would.
+    pub fn with_device_classifier(mut self, classify: fn(&mut DeviceFingerprint)) -> Self {
+        self.device_classifier = Some(classify);
+        self
+    }
+
+    /// Report `sent` alerts from `check_saved_search_alerts`.
+    pub fn with_saved_search_alerts(mut self, sent: u32) -> Self {
+        self.saved_search_alerts = sent;
+        self
+    }
+
+    /// Report `applied` updates from `apply_scheduled_updates`.
+    pub fn with_scheduled_updates(mut self, applied: u32) -> Self {
+        self.scheduled_updates = applied;
+        self
+    }
+
+    /// Install as the SDK host for the current thread.
+    ///
+    /// The previous host is restored when the returned guard is dropped.
+    pub fn install(self) -> MockHostGuard {
+        let host = Rc::new(self);
+        let previous = host::set_native_host(Some(host.clone()));
+        MockHostGuard { host, previous }
+    }
+
+    /// Add an item after construction (e.g., between tap calls).
+    pub fn insert_item(&self, item: Item) {
+        self.items.borrow_mut().push(item);
+    }
+
+    /// Snapshot of the current items.
+    pub fn items(&self) -> Vec<Item> {
+        self.items.borrow().clone()
+    }
+
+    /// Statements passed to `execute_raw`, in call order.
+    pub fn executed(&self) -> Vec<ExecutedStatement> {
+        self.executed.borrow().clone()
+    }
+
+    /// Current value of a site variable.
+    pub fn variable(&self, name: &str) -> Option<String> {
+        self.variables.borrow().get(name).cloned()
+    }
+
+    /// Current value of a cache entry.
+    pub fn cache_entry(&self, bin: &str, key: &str) -> Option<String> {
+        self.cache
+            .borrow()
+            .get(&(bin.to_string(), key.to_string()))
+            .map(|entry| entry.value.clone())
+    }
+
+    /// Elements appended to a render handle, in order.
+    pub fn rendered(&self, handle: RenderHandle) -> Vec<JsonValue> {
+        usize::try_from(handle.id())
+            .ok()
+            .and_then(|index| self.renders.borrow().get(index).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Events passed to `dispatch_event` as `(name, payload)`, in call order.
+    pub fn dispatched_events(&self) -> Vec<(String, JsonValue)> {
+        self.events.borrow().clone()
+    }
+
+    fn matches(&self, item: &Item, query: &ItemQuery) -> bool {
+        if query
+            .item_type
+            .as_deref()
+            .is_some_and(|t| t != item.item_type)
+        {
+            return false;
+        }
+        if query.status.is_some_and(|s| i32::from(s) != item.status) {
+            return false;
+        }
+        // Live items are always visible; staged items only for their stage.
+        if item.stage_id != live_stage_id() {
+            let in_stage = query
+                .stage
+                .as_deref()
+                .and_then(|s| s.parse::<Uuid>().ok())
+                .is_some_and(|s| s == item.stage_id);
+            if !in_stage {
+                return false;
+            }
+        }
+        query
+            .fields
+            .iter()
+            .all(|predicate| field_matches(item, predicate))
+    }
+}
+
+impl NativeHost for MockHost {
+    fn execute_raw(&self, sql: &str, params: &[JsonValue]) -> Result<u64, HostError> {
+        self.executed.borrow_mut().push(ExecutedStatement {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+        });
+        Ok(self
+            .execute_results
+            .iter()
+            .find(|(fragment, _)| sql.contains(fragment.as_str()))
+            .map_or(0, |(_, rows)| *rows))
+    }
+
+    fn query_raw(&self, sql: &str, _params: &[JsonValue]) -> Result<String, HostError> {
+        let rows = self
+            .query_results
+            .iter()
+            .find(|(fragment, _)| sql.contains(fragment.as_str()))
+            .map(|(_, rows)| rows.clone())
+            .unwrap_or_default();
+        Ok(JsonValue::Array(rows).to_string())
+    }
+
+    fn item_query(&self, query: &ItemQuery) -> Result<Vec<Item>, HostError> {
+        let mut items: Vec<Item> = self
+            .items
+            .borrow()
+            .iter()
+            .filter(|item| self.matches(item, query))
+            .cloned()
+            .collect();
+
+        if query.sort.is_empty() {
+            items.sort_by_key(|item| std::cmp::Reverse(item.changed));
+        } else {
+            items.sort_by(|a, b| {
+                query
+                    .sort
+                    .iter()
+                    .map(|key| {
+                        let ord = compare_sort_key(a, b, &key.field);
+                        match key.direction {
+                            SortDirection::Asc => ord,
+                            SortDirection::Desc => ord.reverse(),
+                        }
+                    })
+                    .find(|ord| *ord != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        let limit = query
+            .limit
+            .unwrap_or(ITEM_QUERY_MAX_LIMIT)
+            .clamp(1, ITEM_QUERY_MAX_LIMIT) as usize;
+        Ok(items
+            .into_iter()
+            .skip(query.offset.max(0) as usize)
+            .take(limit)
+            .collect())
+    }
+
+    fn save_item(&self, item: &JsonValue) -> Result<Option<Item>, HostError> {
+        let title = item.get("title").and_then(|v| v.as_str());
+        let status = item.get("status").and_then(|v| v.as_i64());
+        let fields: Option<HashMap<String, JsonValue>> = item
+            .get("fields")
+            .and_then(|v| v.as_object())
+            .map(|obj| obj.clone().into_iter().collect());
+
+        let existing_id = item
+            .get("id")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<Uuid>().ok())
+            .filter(|id| !id.is_nil());
+        let mut items = self.items.borrow_mut();
+
+        if let Some(id) = existing_id {
+            let Some(existing) = items.iter_mut().find(|i| i.id == id) else {
+                return Ok(None);
+            };
+            if let Some(title) = title {
+                existing.title = title.to_string();
+            }
+            if let Some(status) = status {
+                existing.status = status as i32;
+            }
+            if let Some(fields) = fields {
+                existing.fields = fields;
+            }
+            return Ok(Some(existing.clone()));
+        }
+
+        let item_type = item
+            .get("type")
+            .or(item.get("item_type"))
+            .and_then(|v| v.as_str())
+            .ok_or(HostError::Other {
+                code: host_errors::ERR_PARAM_DESERIALIZE,
+            })?;
+        let created = Item {
+            id: Uuid::now_v7(),
+            item_type: item_type.to_string(),
+            title: title.unwrap_or("Untitled").to_string(),
+            fields: fields.unwrap_or_default(),
+            status: status.unwrap_or(0) as i32,
+            author_id: self.user_id.unwrap_or_default(),
+            current_revision_id: None,
+            stage_id: live_stage_id(),
+            created: 0,
+            changed: 0,
+            language: None,
+        };
+        items.push(created.clone());
+        Ok(Some(created))
+    }
+
+    fn variables_get(&self, name: &str, default: &str) -> Result<String, HostError> {
+        Ok(self.variable(name).unwrap_or_else(|| default.to_string()))
+    }
+
+    fn variables_set(&self, name: &str, value: &str) -> Result<(), HostError> {
+        self.variables
+            .borrow_mut()
+            .insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn cache_get(&self, bin: &str, key: &str) -> Option<String> {
+        self.cache_entry(bin, key)
+    }
+
+    fn cache_set(
+        &self,
+        bin: &str,
+        key: &str,
+        value: &str,
+        tags: &[&str],
+        _ttl_secs: u32,
+    ) -> Result<(), HostError> {
+        self.cache.borrow_mut().insert(
+            (bin.to_string(), key.to_string()),
+            CacheEntry {
+                value: value.to_string(),
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+            },
+        );
+        Ok(())
+    }
+
+    fn cache_invalidate_tag(&self, tag: &str) {
+        self.cache
+            .borrow_mut()
+            .retain(|_, entry| !entry.tags.iter().any(|t| t == tag));
+    }
+
+    fn current_user_id(&self) -> String {
+        self.user_id.map(|id| id.to_string()).unwrap_or_default()
+    }
+
+    fn current_user_has_permission(&self, permission: &str) -> bool {
+        self.permissions
+            .as_ref()
+            .is_none_or(|perms| perms.contains(permission))
+    }
+
+    fn render_begin(&self) -> Result<RenderHandle, HostError> {
+        let mut renders = self.renders.borrow_mut();
+        renders.push(Vec::new());
+        Ok(RenderHandle::from_id(renders.len() as i32 - 1))
+    }
+
+    fn render_append(&self, handle: RenderHandle, element_json: &str) -> Result<(), HostError> {
+        let element: JsonValue =
+            serde_json::from_str(element_json).map_err(|_| HostError::Other {
+                code: host_errors::ERR_PARAM_DESERIALIZE,
+            })?;
+        let mut renders = self.renders.borrow_mut();
+        let elements = usize::try_from(handle.id())
+            .ok()
+            .and_then(|index| renders.get_mut(index))
+            .ok_or(HostError::Other {
+                code: host_errors::ERR_RENDER_INVALID_HANDLE,
+            })?;
+        elements.push(element);
+        Ok(())
+    }
+
+    fn dispatch_event(&self, name: &str, payload: &JsonValue) -> Result<u32, HostError> {
+        if !is_valid_event_name(name) {
+            return Err(HostError::Other {
+                code: host_errors::ERR_EVENT_INVALID,
+            });
+        }
+        self.events
+            .borrow_mut()
+            .push((name.to_string(), payload.clone()));
+        Ok(0)
+    }
+
+    fn mac_vendor(&self, mac: &str) -> Result<Option<String>, HostError> {
+        let oui: String = mac
+            .chars()
+            .filter(char::is_ascii_hexdigit)
+            .take(6)
+            .collect::<String>()
+            .to_ascii_uppercase();
+        Ok(self.mac_vendors.get(&oui).cloned())
+    }
+
+    fn classify_device(&self, device: &DeviceFingerprint) -> Result<DeviceFingerprint, HostError> {
+        let mut device = device.clone();
+        if let Some(classify) = self.device_classifier {
+            classify(&mut device);
+        }
+        Ok(device)
+    }
+
+    fn check_saved_search_alerts(&self) -> Result<u32, HostError> {
+        Ok(self.saved_search_alerts)
+    }
+
+    fn apply_scheduled_updates(&self) -> Result<u32, HostError> {
+        Ok(self.scheduled_updates)
+    }
+}
+
+/// An installed [`MockHost`]; uninstalls it on drop.
+pub struct MockHostGuard {
+    host: Rc<MockHost>,
+    previous: Option<Rc<dyn NativeHost>>,
+}
+
+impl Deref for MockHostGuard {
+    type Target = MockHost;
+
+    fn deref(&self) -> &MockHost {
+        &self.host
+    }
+}
+
+impl Drop for MockHostGuard {
+    fn drop(&mut self) {
+        host::set_native_host(self.previous.take());
+    }
+}
+
+/// Evaluate a field predicate the way the kernel's `item_query` does:
+/// numbers numerically, strings as text, booleans only for `eq`/`ne`.
+fn field_matches(item: &Item, predicate: &ItemFieldPredicate) -> bool {
+    let value = item.fields.get(&predicate.field).filter(|v| !v.is_null());
+    let Some(value) = value else {
+        return predicate.op == ItemQueryOp::NotExists;
+    };
+
+    let ord = match (&predicate.value, value) {
+        (JsonValue::Number(expected), actual) => {
+            let actual = match actual {
+                JsonValue::Number(n) => n.as_f64(),
+                JsonValue::String(s) => s.parse::<f64>().ok(),
+                _ => None,
+            };
+            match (actual, expected.as_f64()) {
+                (Some(a), Some(e)) => a.partial_cmp(&e),
+                _ => None,
+            }
+        }
+        (JsonValue::String(expected), actual) => {
+            Some(json_text(actual).as_str().cmp(expected.as_str()))
+        }
+        (JsonValue::Bool(expected), JsonValue::Bool(actual)) => {
+            return match predicate.op {
+                ItemQueryOp::Eq => actual == expected,
+                ItemQueryOp::Ne => actual != expected,
+                ItemQueryOp::Exists => true,
+                _ => false,
+            };
+        }
+        _ => None,
+    };
+
+    match predicate.op {
+        ItemQueryOp::Exists => true,
+        ItemQueryOp::NotExists => false,
+        ItemQueryOp::Eq => ord == Some(Ordering::Equal),
+        ItemQueryOp::Ne => ord.is_some_and(|o| o != Ordering::Equal),
+        ItemQueryOp::Lt => ord == Some(Ordering::Less),
+        ItemQueryOp::Lte => ord.is_some_and(|o| o != Ordering::Greater),
+        ItemQueryOp::Gt => ord == Some(Ordering::Greater),
+        ItemQueryOp::Gte => ord.is_some_and(|o| o != Ordering::Less),
+    }
+}
+
+/// Compare two items by a column or JSONB field name.
+fn compare_sort_key(a: &Item, b: &Item, field: &str) -> Ordering {
+    match field {
+        "created" => a.created.cmp(&b.created),
+        "changed" => a.changed.cmp(&b.changed),
+        "title" => a.title.cmp(&b.title),
+        "status" => a.status.cmp(&b.status),
+        _ => {
+            let text = |item: &Item| item.fields.get(field).map(json_text);
+            text(a).cmp(&text(b))
+        }
+    }
+}
+
+/// Text form of a JSON value, matching Postgres `->>`.
+fn json_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_item;
+    use serde_json::json;
+
+    #[test]
+    fn save_item_creates_and_updates() {
+        let host = MockHost::new().install();
+
+        let created = host::save_item(&json!({
+            "type": "argus_entity",
+            "title": "Ada Lovelace",
+            "status": 1,
+            "fields": {"field_canonical_name": "Ada Lovelace"},
+        }))
+        .unwrap()
+        .unwrap();
+        assert_eq!(created.item_type, "argus_entity");
+        assert_eq!(host.items().len(), 1);
+
+        let updated = host::save_item(&json!({"id": created.id.to_string(), "title": "Ada"}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.title, "Ada");
+        assert_eq!(updated.fields["field_canonical_name"], "Ada Lovelace");
+
+        let missing = host::save_item(&json!({"id": Uuid::now_v7().to_string()})).unwrap();
+        assert!(missing.is_none());
+        assert!(host::save_item(&json!({"title": "No type"})).is_err());
+    }
+
+    #[test]
+    fn item_query_filters_in_memory_items() {
+        let _host = MockHost::new()
+            .with_item(test_item("blog", "Published").to_sdk_item())
+            .with_item(test_item("blog", "Draft").unpublished().to_sdk_item())
+            .with_item(test_item("page", "About").to_sdk_item())
+            .install();
+
+        let query = ItemQuery::new().item_type("blog").status(1);
+        let items = host::item_query(&query).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].title, "Published");
+    }
+
+    #[test]
+    fn item_query_applies_field_predicates_and_sort() {
+        let _host = MockHost::new()
+            .with_item(
+                test_item("event", "Late")
+                    .with_field("capacity", json!(300))
+                    .to_sdk_item(),
+            )
+            .with_item(
+                test_item("event", "Early")
+                    .with_field("capacity", json!(50))
+                    .to_sdk_item(),
+            )
+            .with_item(test_item("event", "Unknown").to_sdk_item())
+            .install();
+
+        let query = ItemQuery::new()
+            .field("capacity", ItemQueryOp::Gte, json!(10))
+            .sort("capacity", SortDirection::Asc);
+        let titles: Vec<String> = host::item_query(&query)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.title)
+            .collect();
+        // Text ordering, like `fields->>'capacity'` in Postgres.
+        assert_eq!(titles, vec!["Late", "Early"]);
+    }
+
+    #[test]
+    fn staged_items_only_visible_in_their_stage() {
+        let stage = Uuid::now_v7();
+        let _host = MockHost::new()
+            .with_item(
+                test_item("page", "Staged")
+                    .with_stage(&stage.to_string())
+                    .to_sdk_item(),
+            )
+            .install();
+
+        assert!(host::item_query(&ItemQuery::new()).unwrap().is_empty());
+        let staged = ItemQuery::new().stage(&stage.to_string());
+        assert_eq!(host::item_query(&staged).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn raw_sql_is_recorded_and_answered() {
+        let host = MockHost::new()
+            .with_query_result("FROM comment", vec![json!({"cid": 1})])
+            .with_execute_result("DELETE FROM comment", 3)
+            .install();
+
+        let rows = host::query_raw("SELECT cid FROM comment", &[]).unwrap();
+        assert_eq!(rows, r#"[{"cid":1}]"#);
+        assert_eq!(host::query_raw("SELECT 1", &[]).unwrap(), "[]");
+
+        let affected = host::execute_raw("DELETE FROM comment WHERE cid = $1", &[json!(1)]);
+        assert_eq!(affected.unwrap(), 3);
+        assert_eq!(host.executed()[0].params, vec![json!(1)]);
+    }
+
+    #[test]
+    fn variables_and_user_round_trip() {
+        let user = Uuid::now_v7();
+        let host = MockHost::new()
+            .with_variable("site_name", "Trovato")
+            .with_user(user, &["access content"])
+            .install();
+
+        assert_eq!(host::variables_get("site_name", "x").unwrap(), "Trovato");
+        host::variables_set("slogan", "CMS").unwrap();
+        assert_eq!(host.variable("slogan").as_deref(), Some("CMS"));
+        assert_eq!(host::current_user_id(), user.to_string());
+        assert!(host::current_user_has_permission("access content"));
+        assert!(!host::current_user_has_permission("administer site"));
+    }
+
+    #[test]
+    fn cache_round_trips_and_invalidates_by_tag() {
+        let host = MockHost::new()
+            .with_cache_entry("search", "checked", "42")
+            .install();
+
+        assert_eq!(host::cache_get("search", "checked").as_deref(), Some("42"));
+        host::cache_set("feeds", "a", "\"etag-a\"", &["feeds"], 3600).unwrap();
+        host::cache_set("feeds", "b", "\"etag-b\"", &[], 3600).unwrap();
+        host::cache_invalidate_tag("feeds");
+        assert_eq!(host::cache_get("feeds", "a"), None);
+        assert_eq!(
+            host.cache_entry("feeds", "b").as_deref(),
+            Some("\"etag-b\"")
+        );
+    }
+
+    #[test]
+    fn render_handles_collect_appended_elements() {
+        let host = MockHost::new().install();
+
+        let handle = host::render_begin().unwrap();
+        handle
+            .append(&trovato_sdk::render::markup("p", "First").build())
+            .unwrap();
+        host::render_append(handle, r##"{"#type": "markup", "#value": "Second"}"##).unwrap();
+
+        let rendered = host.rendered(handle);
+        assert_eq!(rendered.len(), 2);
+        assert_eq!(rendered[0]["#value"], "First");
+        assert_eq!(rendered[1]["#value"], "Second");
+
+        let unknown = RenderHandle::from_id(7);
+        assert_eq!(
+            host::render_append(unknown, "{}").unwrap_err(),
+            HostError::Other {
+                code: host_errors::ERR_RENDER_INVALID_HANDLE
+            }
+        );
+    }
+
+    #[test]
+    fn dispatched_events_are_recorded() {
+        let host = MockHost::new().install();
+
+        let payload = serde_json::json!({"item_id": "abc"});
+        host::dispatch_event("argus.article_analyzed", &payload).unwrap();
+        assert_eq!(
+            host::dispatch_event("Not Valid", &payload).unwrap_err(),
+            HostError::Other {
+                code: host_errors::ERR_EVENT_INVALID
+            }
+        );
+
+        assert_eq!(
+            host.dispatched_events(),
+            vec![("argus.article_analyzed".to_string(), payload)]
+        );
+    }
+
+    #[test]
+    fn device_lookups_use_canned_values() {
+        let _host = MockHost::new()
+            .with_mac_vendor("b827eb", "Raspberry Pi Foundation")
+            .with_device_classifier(|device| device.device_type = Some("computer".into()))
+            .install();
+
+        assert_eq!(
+            host::mac_vendor("B8:27:EB:12:34:56").unwrap().as_deref(),
+            Some("Raspberry Pi Foundation")
+        );
+        assert_eq!(host::mac_vendor("00:11:22:33:44:55").unwrap(), None);
+
+        let device = DeviceFingerprint {
+            mac: "b8:27:eb:12:34:56".into(),
+            ..DeviceFingerprint::default()
+        };
+        assert_eq!(
+            host::classify_device(&device)
+                .unwrap()
+                .device_type
+                .as_deref(),
+            Some("computer")
+        );
+    }
+
+    #[test]
+    fn guard_restores_stub_on_drop() {
+        {
+            let _host = MockHost::new().with_variable("k", "v").install();
+            assert_eq!(host::variables_get("k", "default").unwrap(), "v");
+        }
+        assert_eq!(host::variables_get("k", "default").unwrap(), "default");
+    }
+}
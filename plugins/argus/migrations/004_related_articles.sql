@@ -0,0 +1,64 @@
+-- Argus gather query: articles related to a given article by embedding
+-- similarity. Uses the `argus_related` filter declared in tap_gather_extend
+-- (kernel `vector_similar` handler); the source article comes from the
+-- `item` URL argument, e.g. /gather/argus_related_articles?item={id}.
+-- Matches nothing when pgvector is unavailable or the article has no
+-- embedding.
+-- Forward-only migration; no rollback. Kernel tables are guaranteed to exist.
+
+INSERT INTO gather_query (query_id, label, description, definition, display, plugin, created, changed)
+VALUES (
+    'argus_related_articles',
+    'Related Articles',
+    'Published articles nearest to an article by embedding similarity',
+    '{
+        "base_table": "item",
+        "item_type": "argus_article",
+        "fields": [],
+        "filters": [
+            {
+                "field": "status",
+                "operator": "equals",
+                "value": 1,
+                "exposed": false,
+                "exposed_label": null
+            },
+            {
+                "field": "id",
+                "operator": {"custom": "argus_related"},
+                "value": {"url_arg": "item"},
+                "exposed": false,
+                "exposed_label": null
+            }
+        ],
+        "sorts": [
+            {
+                "field": "fields.field_relevance_score",
+                "direction": "desc",
+                "nulls": null
+            }
+        ],
+        "relationships": [],
+        "includes": {}
+    }'::jsonb,
+    '{
+        "format": "list",
+        "items_per_page": 10,
+        "pager": {
+            "enabled": false,
+            "style": "full",
+            "show_count": false
+        },
+        "empty_text": "No related articles.",
+        "header": null,
+        "footer": null
+    }'::jsonb,
+    'argus',
+    EXTRACT(EPOCH FROM NOW())::bigint,
+    EXTRACT(EPOCH FROM NOW())::bigint
+)
+ON CONFLICT (query_id) DO UPDATE SET
+    definition = EXCLUDED.definition,
+    display = EXCLUDED.display,
+    plugin = EXCLUDED.plugin,
+    changed = EXCLUDED.changed;
@@ -0,0 +1,15 @@
+-- Argus article↔entity relations, filled by entity extraction in
+-- tap_item_insert. `mentions` is the number of times the entity's canonical
+-- name or aliases appear in the article. Rows go away with either item.
+-- Forward-only migration; no rollback. Kernel tables are guaranteed to exist.
+
+CREATE TABLE IF NOT EXISTS argus_article_entity (
+    article_id UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+    entity_id UUID NOT NULL REFERENCES item(id) ON DELETE CASCADE,
+    mentions INTEGER NOT NULL DEFAULT 1,
+    created BIGINT NOT NULL,
+    PRIMARY KEY (article_id, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_argus_article_entity_entity
+    ON argus_article_entity (entity_id);
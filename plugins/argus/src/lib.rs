@@ -3,7 +3,25 @@
 //! News intelligence use case: 7 content types for articles, stories, topics,
 //! feeds, entities, reactions, and discussions. Validates composite gather
 //! responses via includes.
+//!
+//! Article embeddings are mirrored from `field_vector_embedding` into the
+//! kernel's pgvector store on save, which powers the
+//! `argus_related_articles` gather (nearest articles by cosine distance).
+//!
+//! New articles are scanned for named entities. Known `argus_entity` items
+//! (canonical name and aliases) act as a gazetteer; unknown names come from
+//! a capitalisation heuristic, or from an external NER service when the
+//! `argus_ner_endpoint` variable is set. New names become `argus_entity`
+//! items and every match is recorded in `argus_article_entity`.
+//!
+//! A cron pass clusters recent articles into `argus_story` items by topic
+//! and embedding similarity, and deactivates stories that stopped receiving
+//! articles.
 
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use trovato_sdk::host;
 use trovato_sdk::prelude::*;
 
 /// The 7 Argus content types.
@@ -22,16 +40,23 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
             label: "Article".into(),
             description: "Fetched news article with analysis".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_url", FieldType::Text { max_length: None })
                     .required()
                     .label("URL"),
-                FieldDefinition::new("field_content", FieldType::TextLong).label("Content"),
+                FieldDefinition::new("field_content", FieldType::TextLong)
+                    .label("Content")
+                    .search_weight(SearchWeight::C),
                 FieldDefinition::new("field_relevance_score", FieldType::Float)
                     .label("Relevance Score"),
-                FieldDefinition::new("field_summary", FieldType::TextLong).label("Summary"),
+                FieldDefinition::new("field_summary", FieldType::TextLong)
+                    .label("Summary")
+                    .search_weight(SearchWeight::B),
                 FieldDefinition::new("field_critical_analysis", FieldType::TextLong)
                     .label("Critical Analysis"),
+                // JSON array of floats; indexed into the kernel vector store on save.
                 FieldDefinition::new("field_vector_embedding", FieldType::TextLong)
                     .label("Vector Embedding"),
                 FieldDefinition::new(
@@ -43,56 +68,73 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     "field_topic_id",
                     FieldType::RecordReference("argus_topic".into()),
                 )
-                .label("Topic"),
+                .label("Topic")
+                .on_delete("set_null"),
                 FieldDefinition::new(
                     "field_story_id",
                     FieldType::RecordReference("argus_story".into()),
                 )
                 .label("Story"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "argus_story".into(),
             label: "Story".into(),
             description: "Aggregated narrative from multiple articles".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_summary", FieldType::TextLong)
                     .required()
-                    .label("Summary"),
+                    .label("Summary")
+                    .search_weight(SearchWeight::B),
                 FieldDefinition::new("field_source_attribution", FieldType::TextLong)
                     .label("Source Attribution"),
                 FieldDefinition::new(
                     "field_topic_id",
                     FieldType::RecordReference("argus_topic".into()),
                 )
-                .label("Topic"),
+                .label("Topic")
+                .on_delete("set_null"),
                 FieldDefinition::new("field_article_count", FieldType::Integer)
                     .label("Article Count"),
                 FieldDefinition::new("field_relevance_score", FieldType::Float)
                     .label("Relevance Score"),
                 FieldDefinition::new("field_active", FieldType::Boolean).label("Active"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "argus_topic".into(),
             label: "Topic".into(),
             description: "Monitored topic with relevance criteria".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_name", FieldType::Text { max_length: None })
                     .required()
                     .label("Name"),
                 FieldDefinition::new("field_relevance_prompt", FieldType::TextLong)
                     .label("Relevance Prompt"),
-                FieldDefinition::new("field_threshold", FieldType::Float).label("Threshold"),
+                FieldDefinition::new("field_threshold", FieldType::Float)
+                    .label("Threshold")
+                    .track_history(),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "argus_feed".into(),
             label: "Feed".into(),
             description: "RSS/Atom feed source".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_url", FieldType::Text { max_length: None })
                     .required()
@@ -105,27 +147,36 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                 FieldDefinition::new("field_health_status", FieldType::Text { max_length: None })
                     .label("Health Status"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "argus_entity".into(),
             label: "Entity".into(),
             description: "Named entity (person, org, place) extracted from articles".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_canonical_name", FieldType::Text { max_length: None })
                     .required()
                     .label("Canonical Name"),
                 FieldDefinition::new("field_aliases", FieldType::TextLong).label("Aliases"),
                 FieldDefinition::new("field_type", FieldType::Text { max_length: None })
-                    .label("Entity Type"),
+                    .label("Entity Type")
+                    .allowed_values(ENTITY_TYPES),
                 FieldDefinition::new("field_description", FieldType::TextLong).label("Description"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "argus_reaction".into(),
             label: "Reaction".into(),
             description: "User reaction to content".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_user_id", FieldType::Text { max_length: None })
                     .required()
@@ -137,12 +188,16 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     .required()
                     .label("Reaction Type"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "argus_discussion".into(),
             label: "Discussion".into(),
             description: "Threaded discussion on a story".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new(
                     "field_story_id",
@@ -162,6 +217,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     .required()
                     .label("Content"),
             ],
+            extends: None,
+            template: false,
         },
     ]
 }
@@ -200,11 +257,1148 @@ pub fn tap_menu() -> Vec<MenuDefinition> {
     ]
 }
 
+/// Notification preferences stored on user accounts.
+#[plugin_tap]
+pub fn tap_user_info() -> Vec<FieldDefinition> {
+    vec![
+        FieldDefinition::new("argus_notify_stories", FieldType::Boolean)
+            .label("Email me when a followed story is updated")
+            .user_editable(),
+        FieldDefinition::new("argus_digest_hour", FieldType::Integer)
+            .label("Daily digest hour (0-23, blank for none)")
+            .user_editable(),
+    ]
+}
+
+/// Number of articles returned by the related articles gather.
+const RELATED_ARTICLE_LIMIT: u32 = 10;
+
+/// Gather extensions: `argus_related` matches the articles nearest to the
+/// article given as the filter value, using this plugin's embeddings.
+#[plugin_tap]
+pub fn tap_gather_extend() -> serde_json::Value {
+    serde_json::json!({
+        "filters": [{
+            "name": "argus_related",
+            "handler": "vector_similar",
+            "config": { "namespace": "argus", "limit": RELATED_ARTICLE_LIMIT },
+        }],
+    })
+}
+
+/// Index a new article's embedding and extract its entities.
+#[plugin_tap_result]
+pub fn tap_item_insert(item: Item) -> Result<(), String> {
+    sync_embedding(&item);
+    extract_entities(&item);
+    Ok(())
+}
+
+/// Re-index an article's embedding after it changes.
+#[plugin_tap_result]
+pub fn tap_item_update(item: Item) -> Result<(), String> {
+    sync_embedding(&item);
+    Ok(())
+}
+
+/// Copy an article's `field_vector_embedding` into the kernel vector store.
+///
+/// Failures (e.g. pgvector not installed) are logged and never block the save.
+fn sync_embedding(item: &Item) {
+    if item.item_type != "argus_article" {
+        return;
+    }
+    let Some(vector) = item
+        .fields
+        .get("field_vector_embedding")
+        .and_then(parse_embedding)
+    else {
+        return;
+    };
+
+    if let Err(e) = host::embedding_store(item.id, &vector) {
+        host::log(
+            "warn",
+            "argus",
+            &format!("failed to store embedding for {}: {e}", item.id),
+        );
+    }
+}
+
+/// Parse an embedding field value: a JSON array of numbers, either stored
+/// directly, as a string, or as a text value (`{"value": "[...]"}`).
+fn parse_embedding(value: &serde_json::Value) -> Option<Vec<f32>> {
+    match value {
+        serde_json::Value::Array(_) => serde_json::from_value(value.clone()).ok(),
+        serde_json::Value::String(s) => serde_json::from_str(s).ok(),
+        serde_json::Value::Object(obj) => obj.get("value").and_then(parse_embedding),
+        _ => None,
+    }
+    .filter(|v: &Vec<f32>| !v.is_empty())
+}
+
+/// Variable holding the URL of an external NER service.
+///
+/// When set, article text is POSTed as `{"text": "..."}` and the service
+/// answers `{"entities": [{"name": "...", "type": "person"}]}`. If the call
+/// fails, the built-in heuristic is used instead.
+const NER_ENDPOINT_VAR: &str = "argus_ner_endpoint";
+
+/// Timeout for the external NER request.
+const NER_TIMEOUT_MS: u32 = 10_000;
+
+/// Maximum number of known entities loaded into the gazetteer.
+const GAZETTEER_LIMIT: i64 = 2000;
+
+/// Maximum number of new entities created from a single article.
+const MAX_NEW_ENTITIES: usize = 25;
+
+/// Words that start a capitalised run without being part of a name.
+const LEADING_STOPWORDS: &[&str] = &[
+    "a", "after", "an", "and", "as", "at", "but", "dr", "for", "he", "her", "his", "if", "in",
+    "it", "its", "mr", "mrs", "ms", "on", "she", "that", "the", "their", "they", "this", "we",
+    "when", "while", "with",
+];
+
+/// Lowercase words allowed inside a name ("Bank of England").
+const NAME_CONNECTORS: &[&str] = &[
+    "al", "bin", "da", "de", "del", "der", "la", "of", "van", "von",
+];
+
+/// Capitalised single words that are not entities.
+const SINGLE_WORD_STOPWORDS: &[&str] = &[
+    "april",
+    "august",
+    "december",
+    "february",
+    "friday",
+    "i",
+    "january",
+    "july",
+    "june",
+    "march",
+    "monday",
+    "november",
+    "october",
+    "saturday",
+    "september",
+    "sunday",
+    "thursday",
+    "tuesday",
+    "wednesday",
+];
+
+/// Types an `argus_entity` may have; an empty type means unknown.
+const ENTITY_TYPES: &[&str] = &["person", "organization", "place", "other"];
+
+/// Map an entity type from the NER service onto [`ENTITY_TYPES`].
+fn normalize_entity_type(raw: &str) -> String {
+    match raw.trim().to_lowercase().as_str() {
+        "" => "",
+        "person" | "per" => "person",
+        "organization" | "organisation" | "org" => "organization",
+        "place" | "location" | "loc" | "gpe" => "place",
+        _ => "other",
+    }
+    .to_string()
+}
+
+/// A name mentioned in an article.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct Mention {
+    name: String,
+    /// Entity type (person, organization, place, ...); empty if unknown.
+    #[serde(default, rename = "type")]
+    entity_type: String,
+}
+
+/// An existing `argus_entity` with its normalised canonical name and aliases.
+#[derive(Debug, Clone, PartialEq)]
+struct KnownEntity {
+    id: Uuid,
+    names: Vec<String>,
+}
+
+/// Entities found in one article.
+#[derive(Debug, Default, PartialEq)]
+struct Resolution {
+    /// Existing entities and their mention counts.
+    linked: Vec<(Uuid, usize)>,
+    /// Names to create as new entities, with mention counts.
+    new: Vec<(Mention, usize)>,
+}
+
+/// Extract entities from a new article, creating unknown ones and
+/// recording the article's relations.
+///
+/// Failures are logged and never block the save.
+fn extract_entities(item: &Item) {
+    if item.item_type != "argus_article" {
+        return;
+    }
+    let text = article_text(item);
+    if text.trim().is_empty() {
+        return;
+    }
+
+    let Some(known) = load_known_entities() else {
+        return;
+    };
+    let mentions = ner_mentions(&text).unwrap_or_else(|| pattern_mentions(&text));
+    let resolution = resolve(&text, &mentions, &known);
+
+    let mut linked = resolution.linked;
+    for (mention, count) in &resolution.new {
+        if let Some(id) = create_entity(mention) {
+            linked.push((id, *count));
+        }
+    }
+    // Link the article to all of its entities or none of them.
+    let recorded = host::transaction(|| {
+        linked
+            .iter()
+            .try_for_each(|(entity_id, count)| record_relation(item.id, *entity_id, *count))
+    });
+    if let Err(e) = recorded {
+        host::log(
+            "warn",
+            "argus",
+            &format!("failed to link article {} to its entities: {e}", item.id),
+        );
+        return;
+    }
+
+    host::log(
+        "info",
+        "argus",
+        &format!(
+            "article {}: {} entities ({} new)",
+            item.id,
+            linked.len(),
+            resolution.new.len()
+        ),
+    );
+}
+
+/// Text of an article to scan: title, summary and content.
+fn article_text(item: &Item) -> String {
+    let mut text = item.title.clone();
+    for field in ["field_summary", "field_content"] {
+        if let Some(value) = item.fields.get(field).and_then(field_text) {
+            text.push('\n');
+            text.push_str(value);
+        }
+    }
+    text
+}
+
+/// Text of a field value stored either as a string or as `{"value": "..."}`.
+fn field_text(value: &serde_json::Value) -> Option<&str> {
+    match value {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Object(obj) => obj.get("value").and_then(|v| v.as_str()),
+        _ => None,
+    }
+}
+
+/// Lowercase `name` and reduce it to words separated by single spaces.
+///
+/// Punctuation is dropped, so "U.S." and "U S" compare equal.
+fn normalize_name(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split an aliases field into names (one per line, or comma/semicolon separated).
+fn parse_aliases(text: &str) -> Vec<&str> {
+    text.split(['\n', ',', ';'])
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// Load existing entities for the gazetteer.
+///
+/// Returns `None` if the query fails, so no duplicates are created.
+fn load_known_entities() -> Option<Vec<KnownEntity>> {
+    #[derive(Deserialize)]
+    struct Row {
+        id: String,
+        title: String,
+        canonical: Option<serde_json::Value>,
+        aliases: Option<serde_json::Value>,
+    }
+
+    let json = match host::query_raw(
+        "SELECT id::text AS id, title, fields->'field_canonical_name' AS canonical, \
+         fields->'field_aliases' AS aliases FROM item \
+         WHERE type = 'argus_entity' ORDER BY created LIMIT $1",
+        &[serde_json::json!(GAZETTEER_LIMIT)],
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            host::log("warn", "argus", &format!("failed to load entities: {e}"));
+            return None;
+        }
+    };
+    let rows: Vec<Row> = serde_json::from_str(&json).ok()?;
+
+    Some(
+        rows.into_iter()
+            .filter_map(|row| {
+                let id = row.id.parse().ok()?;
+                let canonical = row
+                    .canonical
+                    .as_ref()
+                    .and_then(field_text)
+                    .unwrap_or(&row.title);
+                let aliases = row.aliases.as_ref().and_then(field_text).unwrap_or("");
+                let names = std::iter::once(canonical)
+                    .chain(parse_aliases(aliases))
+                    .map(normalize_name)
+                    .filter(|n| !n.is_empty())
+                    .collect();
+                Some(KnownEntity { id, names })
+            })
+            .collect(),
+    )
+}
+
+/// Ask the configured NER service for the article's entities.
+fn ner_mentions(text: &str) -> Option<Vec<Mention>> {
+    let endpoint = host::variables_get(NER_ENDPOINT_VAR, "")
+        .ok()
+        .filter(|url| !url.is_empty())?;
+    let body = serde_json::json!({ "text": text }).to_string();
+    let request = HttpRequest::post(endpoint, body)
+        .header("Content-Type", "application/json")
+        .timeout(NER_TIMEOUT_MS);
+
+    match host::http_request(&request) {
+        Ok(response) if (200..300).contains(&response.status) => {
+            let mentions = parse_ner_response(&response.body);
+            if mentions.is_none() {
+                host::log("warn", "argus", "NER service returned an invalid response");
+            }
+            mentions
+        }
+        Ok(response) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!("NER service returned HTTP {}", response.status),
+            );
+            None
+        }
+        Err(e) => {
+            host::log("warn", "argus", &format!("NER request failed: {e}"));
+            None
+        }
+    }
+}
+
+/// Parse `{"entities": [{"name": "...", "type": "..."}]}`.
+fn parse_ner_response(body: &str) -> Option<Vec<Mention>> {
+    #[derive(Deserialize)]
+    struct NerResponse {
+        entities: Vec<Mention>,
+    }
+
+    serde_json::from_str::<NerResponse>(body)
+        .ok()
+        .map(|r| r.entities)
+}
+
+/// Find likely names: runs of capitalised words and acronyms.
+///
+/// A run may contain lowercase connectors ("Bank of England"). Single
+/// capitalised words are only kept mid-sentence, where capitalisation
+/// signals a name rather than grammar.
+fn pattern_mentions(text: &str) -> Vec<Mention> {
+    let mut runs: Vec<(Vec<&str>, bool)> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut connector: Option<&str> = None;
+    let mut run_starts_sentence = false;
+    let mut sentence_start = true;
+
+    for line in text.lines() {
+        for raw in line.split_whitespace() {
+            let word = raw.trim_matches(|c: char| !c.is_alphanumeric());
+            let tail = raw.trim_end_matches(['"', '\'', ')', '\u{201d}', '\u{2019}']);
+            let ends_sentence = tail.ends_with(['.', '!', '?']) && !is_acronym(word);
+            let ends_clause = ends_sentence || tail.ends_with([',', ';', ':']);
+
+            if word.is_empty() {
+                end_run(&mut runs, &mut current, &mut connector, run_starts_sentence);
+            } else if is_capitalized(word) {
+                if current.is_empty() {
+                    run_starts_sentence = sentence_start;
+                } else if let Some(c) = connector.take() {
+                    current.push(c);
+                }
+                current.push(word);
+            } else if !current.is_empty()
+                && connector.is_none()
+                && NAME_CONNECTORS.contains(&word.to_lowercase().as_str())
+            {
+                connector = Some(word);
+            } else {
+                end_run(&mut runs, &mut current, &mut connector, run_starts_sentence);
+            }
+
+            if ends_clause {
+                end_run(&mut runs, &mut current, &mut connector, run_starts_sentence);
+            }
+            sentence_start = ends_sentence;
+        }
+        end_run(&mut runs, &mut current, &mut connector, run_starts_sentence);
+        sentence_start = true;
+    }
+
+    let mut seen = HashSet::new();
+    runs.into_iter()
+        .filter_map(|(words, starts_sentence)| {
+            let skip = words
+                .iter()
+                .take_while(|w| LEADING_STOPWORDS.contains(&w.to_lowercase().as_str()))
+                .count();
+            let words = &words[skip..];
+            let keep = match words {
+                [] => false,
+                [word] => {
+                    is_acronym(word)
+                        || (!(starts_sentence && skip == 0)
+                            && word.chars().count() > 1
+                            && !SINGLE_WORD_STOPWORDS.contains(&word.to_lowercase().as_str()))
+                }
+                _ => true,
+            };
+            keep.then(|| words.join(" "))
+        })
+        .filter(|name| seen.insert(normalize_name(name)))
+        .map(|name| Mention {
+            name,
+            entity_type: String::new(),
+        })
+        .collect()
+}
+
+/// Close the current run of capitalised words, if any.
+fn end_run<'a>(
+    runs: &mut Vec<(Vec<&'a str>, bool)>,
+    current: &mut Vec<&'a str>,
+    connector: &mut Option<&'a str>,
+    starts_sentence: bool,
+) {
+    if !current.is_empty() {
+        runs.push((std::mem::take(current), starts_sentence));
+    }
+    *connector = None;
+}
+
+/// Whether a word starts with an uppercase letter.
+fn is_capitalized(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// Whether a word is an acronym such as "NATO" or "U.S".
+fn is_acronym(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    (2..=6).contains(&letters.len())
+        && letters.iter().all(|c| c.is_uppercase())
+        && word.chars().all(|c| c.is_alphabetic() || c == '.')
+}
+
+/// Occurrences of a normalised name in normalised, space-padded text.
+fn count_mentions(padded_text: &str, name: &str) -> usize {
+    if name.is_empty() {
+        return 0;
+    }
+    padded_text.matches(&format!(" {name} ")).count()
+}
+
+/// Match mentions against known entities and pick the names to create.
+///
+/// Known entities count as mentioned when any of their names appears in
+/// the text, whether or not the extractor found it. Mentions matching a
+/// known name are linked to that entity; the rest become new entities,
+/// except single words that are part of a longer name in the same article
+/// ("Obama" after "Barack Obama").
+fn resolve(text: &str, mentions: &[Mention], known: &[KnownEntity]) -> Resolution {
+    let padded = format!(" {} ", normalize_name(text));
+    let mut resolution = Resolution::default();
+
+    for entity in known {
+        let count: usize = entity
+            .names
+            .iter()
+            .map(|n| count_mentions(&padded, n))
+            .sum();
+        if count > 0 {
+            resolution.linked.push((entity.id, count));
+        }
+    }
+
+    let mut candidates: Vec<(String, &Mention)> = Vec::new();
+    for mention in mentions {
+        let name = normalize_name(&mention.name);
+        if name.is_empty() || candidates.iter().any(|(n, _)| *n == name) {
+            continue;
+        }
+        match known.iter().find(|e| e.names.contains(&name)) {
+            Some(entity) => {
+                if !resolution.linked.iter().any(|(id, _)| *id == entity.id) {
+                    resolution.linked.push((entity.id, 1));
+                }
+            }
+            None => candidates.push((name, mention)),
+        }
+    }
+
+    let mut longer_name_words: HashSet<&str> = HashSet::new();
+    for name in candidates.iter().map(|(n, _)| n.as_str()).chain(
+        resolution
+            .linked
+            .iter()
+            .filter_map(|(id, _)| known.iter().find(|e| e.id == *id))
+            .flat_map(|e| e.names.iter().map(String::as_str)),
+    ) {
+        if name.contains(' ') {
+            longer_name_words.extend(name.split(' '));
+        }
+    }
+
+    resolution.new = candidates
+        .iter()
+        .filter(|(name, _)| name.contains(' ') || !longer_name_words.contains(name.as_str()))
+        .take(MAX_NEW_ENTITIES)
+        .map(|(name, mention)| {
+            let mention = Mention {
+                name: mention.name.trim().to_string(),
+                entity_type: normalize_entity_type(&mention.entity_type),
+            };
+            (mention, count_mentions(&padded, name).max(1))
+        })
+        .collect();
+    resolution
+}
+
+/// Create a published `argus_entity` for a new name.
+fn create_entity(mention: &Mention) -> Option<Uuid> {
+    let item = serde_json::json!({
+        "type": "argus_entity",
+        "title": mention.name,
+        "status": 1,
+        "fields": {
+            "field_canonical_name": mention.name,
+            "field_type": mention.entity_type,
+        },
+    });
+    match host::save_item(&item) {
+        Ok(Some(entity)) => Some(entity.id),
+        Ok(None) => None,
+        Err(e) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!("failed to create entity \"{}\": {e}", mention.name),
+            );
+            None
+        }
+    }
+}
+
+/// Record that an article mentions an entity `mentions` times.
+fn record_relation(article_id: Uuid, entity_id: Uuid, mentions: usize) -> Result<(), HostError> {
+    host::execute_raw(
+        "INSERT INTO argus_article_entity (article_id, entity_id, mentions, created) \
+         VALUES ($1::uuid, $2::uuid, $3, EXTRACT(EPOCH FROM NOW())::bigint) \
+         ON CONFLICT (article_id, entity_id) DO UPDATE SET mentions = EXCLUDED.mentions",
+        &[
+            serde_json::json!(article_id.to_string()),
+            serde_json::json!(entity_id.to_string()),
+            serde_json::json!(mentions),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Variable overriding how many hours back the clustering pass looks.
+const CLUSTER_WINDOW_VAR: &str = "argus_cluster_window_hours";
+
+/// Default clustering window: articles from the last two days.
+const DEFAULT_CLUSTER_WINDOW_HOURS: i64 = 48;
+
+/// Cosine similarity an article needs to join a story, for topics
+/// without a `field_threshold`.
+const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.8;
+
+/// Maximum number of articles clustered per cron run.
+const CLUSTER_ARTICLE_LIMIT: i64 = 1000;
+
+/// Articles needed before a new story is created.
+const MIN_STORY_ARTICLES: usize = 2;
+
+/// Articles whose summaries make up a story summary.
+const STORY_SUMMARY_ARTICLES: usize = 3;
+
+/// A recent article considered for clustering.
+#[derive(Debug, Clone, PartialEq)]
+struct ClusterArticle {
+    id: Uuid,
+    title: String,
+    topic: Option<Uuid>,
+    story: Option<Uuid>,
+    embedding: Option<Vec<f32>>,
+    summary: String,
+    relevance: f64,
+    url: String,
+    fields: serde_json::Value,
+}
+
+/// An active `argus_story`.
+#[derive(Debug, Clone, PartialEq)]
+struct ActiveStory {
+    id: Uuid,
+    title: String,
+    fields: serde_json::Value,
+    /// Articles referencing the story, in or out of the window.
+    article_count: i64,
+}
+
+/// Articles grouped into one story.
+#[derive(Debug, Clone, PartialEq)]
+struct Cluster {
+    /// Existing story, or `None` for a story still to be created.
+    story: Option<Uuid>,
+    topic: Option<Uuid>,
+    /// Indices into the clustered articles, oldest first.
+    articles: Vec<usize>,
+    /// Sum of member embeddings; cosine similarity ignores its length.
+    centroid: Vec<f32>,
+}
+
+/// Story clustering results of one cron run.
+#[derive(Debug, Default, PartialEq)]
+struct ClusterReport {
+    stories_created: usize,
+    stories_updated: usize,
+    stories_deactivated: usize,
+    articles_assigned: usize,
+}
+
+/// Group recent articles into stories.
+///
+/// Articles published within the clustering window join the most similar
+/// story of the same topic when their embedding's cosine similarity reaches
+/// the topic's `field_threshold`; otherwise they seed a new cluster, which
+/// becomes a story once it has [`MIN_STORY_ARTICLES`]. Stories gaining
+/// articles get a regenerated summary, attribution and article count.
+/// Active stories without any article in the window are deactivated.
+#[plugin_tap]
+pub fn tap_cron(input: CronInput) -> serde_json::Value {
+    let hours = host::variables_get(CLUSTER_WINDOW_VAR, "")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(DEFAULT_CLUSTER_WINDOW_HOURS);
+    let since = input.timestamp - hours * 3600;
+
+    let report = cluster_stories(since).unwrap_or_default();
+    serde_json::json!({
+        "stories_created": report.stories_created,
+        "stories_updated": report.stories_updated,
+        "stories_deactivated": report.stories_deactivated,
+        "articles_assigned": report.articles_assigned,
+    })
+}
+
+/// Run one clustering pass over articles created since `since`.
+///
+/// Returns `None` if the articles or stories could not be loaded; nothing
+/// is changed in that case.
+fn cluster_stories(since: i64) -> Option<ClusterReport> {
+    let stories = load_active_stories()?;
+    // Articles of stories an editor deactivated stay where they are.
+    let articles: Vec<ClusterArticle> = load_recent_articles(since)?
+        .into_iter()
+        .filter(|a| a.story.is_none_or(|id| stories.iter().any(|s| s.id == id)))
+        .collect();
+    let thresholds = load_topic_thresholds();
+
+    let mut report = ClusterReport::default();
+    let clusters = cluster_articles(&articles, &thresholds);
+    for cluster in &clusters {
+        let new_members: Vec<&ClusterArticle> = cluster
+            .articles
+            .iter()
+            .map(|i| &articles[*i])
+            .filter(|a| a.story.is_none())
+            .collect();
+        if new_members.is_empty() {
+            continue;
+        }
+        let members: Vec<&ClusterArticle> =
+            cluster.articles.iter().map(|i| &articles[*i]).collect();
+        let existing = cluster
+            .story
+            .and_then(|id| stories.iter().find(|s| s.id == id));
+        if existing.is_none() && members.len() < MIN_STORY_ARTICLES {
+            continue;
+        }
+
+        let Some(story_id) = save_story(existing, cluster.topic, &members, new_members.len())
+        else {
+            continue;
+        };
+        if existing.is_some() {
+            report.stories_updated += 1;
+        } else {
+            report.stories_created += 1;
+        }
+        for article in new_members {
+            if assign_article(article, story_id) {
+                report.articles_assigned += 1;
+            }
+        }
+    }
+
+    for story in &stories {
+        if !clusters.iter().any(|c| c.story == Some(story.id)) && deactivate_story(story) {
+            report.stories_deactivated += 1;
+        }
+    }
+
+    if report != ClusterReport::default() {
+        host::log(
+            "info",
+            "argus",
+            &format!(
+                "story clustering: {} created, {} updated, {} deactivated, {} articles assigned",
+                report.stories_created,
+                report.stories_updated,
+                report.stories_deactivated,
+                report.articles_assigned
+            ),
+        );
+    }
+    Some(report)
+}
+
+/// Cluster articles (oldest first) by topic and embedding similarity.
+///
+/// Articles already in a story seed that story's cluster. Other articles
+/// need an embedding; each joins the most similar cluster of its topic if
+/// the similarity reaches the topic's threshold, or starts a new cluster.
+fn cluster_articles(articles: &[ClusterArticle], thresholds: &[(Uuid, f32)]) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+
+    for (index, article) in articles.iter().enumerate() {
+        let Some(story) = article.story else {
+            continue;
+        };
+        let position = match clusters.iter().position(|c| c.story == Some(story)) {
+            Some(position) => position,
+            None => {
+                clusters.push(Cluster {
+                    story: Some(story),
+                    topic: article.topic,
+                    articles: Vec::new(),
+                    centroid: Vec::new(),
+                });
+                clusters.len() - 1
+            }
+        };
+        add_to_cluster(&mut clusters[position], index, article);
+    }
+
+    for (index, article) in articles.iter().enumerate() {
+        if article.story.is_some() {
+            continue;
+        }
+        let Some(embedding) = &article.embedding else {
+            continue;
+        };
+        let threshold = article
+            .topic
+            .and_then(|topic| thresholds.iter().find(|(id, _)| *id == topic))
+            .map_or(DEFAULT_SIMILARITY_THRESHOLD, |(_, t)| *t);
+
+        let best = clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.topic == article.topic)
+            .map(|(position, c)| (position, cosine_similarity(&c.centroid, embedding)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((position, _)) => add_to_cluster(&mut clusters[position], index, article),
+            None => {
+                let mut cluster = Cluster {
+                    story: None,
+                    topic: article.topic,
+                    articles: Vec::new(),
+                    centroid: Vec::new(),
+                };
+                add_to_cluster(&mut cluster, index, article);
+                clusters.push(cluster);
+            }
+        }
+    }
+
+    clusters
+}
+
+/// Add an article to a cluster, folding its embedding into the centroid.
+fn add_to_cluster(cluster: &mut Cluster, index: usize, article: &ClusterArticle) {
+    cluster.articles.push(index);
+    let Some(embedding) = &article.embedding else {
+        return;
+    };
+    if cluster.centroid.is_empty() {
+        cluster.centroid = embedding.clone();
+    } else if cluster.centroid.len() == embedding.len() {
+        for (sum, value) in cluster.centroid.iter_mut().zip(embedding) {
+            *sum += value;
+        }
+    }
+}
+
+/// Cosine similarity of two vectors; 0 for empty or mismatched vectors.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Item ID stored in a record reference field, either as a string or as
+/// `{"target_id": "..."}`.
+fn reference_id(value: &serde_json::Value) -> Option<Uuid> {
+    match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Object(obj) => obj.get("target_id").and_then(reference_id),
+        _ => None,
+    }
+}
+
+/// A number stored directly, as a string, or as `{"value": ...}`.
+fn field_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        serde_json::Value::Object(obj) => obj.get("value").and_then(field_number),
+        _ => None,
+    }
+}
+
+/// Load published articles created since `since`, oldest first.
+fn load_recent_articles(since: i64) -> Option<Vec<ClusterArticle>> {
+    #[derive(Deserialize)]
+    struct Row {
+        id: String,
+        title: String,
+        fields: serde_json::Value,
+    }
+
+    let json = match host::query_raw(
+        "SELECT id::text AS id, title, fields FROM item \
+         WHERE type = 'argus_article' AND status = 1 AND stage_id = $1::uuid \
+         AND created >= $2 ORDER BY created, id LIMIT $3",
+        &[
+            serde_json::json!(LIVE_STAGE_UUID),
+            serde_json::json!(since),
+            serde_json::json!(CLUSTER_ARTICLE_LIMIT),
+        ],
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!("failed to load articles for clustering: {e}"),
+            );
+            return None;
+        }
+    };
+    let rows: Vec<Row> = serde_json::from_str(&json).ok()?;
+
+    Some(
+        rows.into_iter()
+            .filter_map(|row| {
+                let field = |name: &str| row.fields.get(name);
+                Some(ClusterArticle {
+                    id: row.id.parse().ok()?,
+                    topic: field("field_topic_id").and_then(reference_id),
+                    story: field("field_story_id").and_then(reference_id),
+                    embedding: field("field_vector_embedding").and_then(parse_embedding),
+                    summary: field("field_summary")
+                        .and_then(field_text)
+                        .unwrap_or_default()
+                        .to_string(),
+                    relevance: field("field_relevance_score")
+                        .and_then(field_number)
+                        .unwrap_or(0.0),
+                    url: field("field_url")
+                        .and_then(field_text)
+                        .unwrap_or_default()
+                        .to_string(),
+                    title: row.title,
+                    fields: row.fields,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Load active stories with the number of articles referencing each.
+fn load_active_stories() -> Option<Vec<ActiveStory>> {
+    #[derive(Deserialize)]
+    struct Row {
+        id: String,
+        title: String,
+        fields: serde_json::Value,
+        article_count: i64,
+    }
+
+    let json = match host::query_raw(
+        "SELECT s.id::text AS id, s.title, s.fields, \
+         (SELECT COUNT(*) FROM item a WHERE a.type = 'argus_article' \
+          AND COALESCE(a.fields #>> '{field_story_id,target_id}', a.fields ->> 'field_story_id') \
+              = s.id::text) AS article_count \
+         FROM item s WHERE s.type = 'argus_story' AND s.fields ->> 'field_active' IN ('true', '1')",
+        &[],
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!("failed to load stories for clustering: {e}"),
+            );
+            return None;
+        }
+    };
+    let rows: Vec<Row> = serde_json::from_str(&json).ok()?;
+
+    Some(
+        rows.into_iter()
+            .filter_map(|row| {
+                Some(ActiveStory {
+                    id: row.id.parse().ok()?,
+                    title: row.title,
+                    fields: row.fields,
+                    article_count: row.article_count,
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Similarity thresholds of topics that set `field_threshold`.
+///
+/// Values are cosine similarities; anything outside `0..=1` is ignored.
+fn load_topic_thresholds() -> Vec<(Uuid, f32)> {
+    #[derive(Deserialize)]
+    struct Row {
+        id: String,
+        threshold: Option<serde_json::Value>,
+    }
+
+    let Ok(json) = host::query_raw(
+        "SELECT id::text AS id, fields -> 'field_threshold' AS threshold FROM item \
+         WHERE type = 'argus_topic'",
+        &[],
+    ) else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Vec<Row>>(&json)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|row| {
+            let threshold = row.threshold.as_ref().and_then(field_number)?;
+            ((0.0..=1.0).contains(&threshold)).then_some((row.id.parse().ok()?, threshold as f32))
+        })
+        .collect()
+}
+
+/// Summary of a story: the first sentence of the most relevant articles'
+/// summaries (or their titles), most relevant first.
+fn story_summary(members: &[&ClusterArticle]) -> String {
+    let mut ranked: Vec<&&ClusterArticle> = members.iter().collect();
+    ranked.sort_by(|a, b| b.relevance.total_cmp(&a.relevance));
+    ranked
+        .into_iter()
+        .take(STORY_SUMMARY_ARTICLES)
+        .map(|a| {
+            let text = if a.summary.trim().is_empty() {
+                a.title.trim()
+            } else {
+                a.summary.trim()
+            };
+            first_sentence(text)
+        })
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Text up to and including the first sentence-ending punctuation.
+fn first_sentence(text: &str) -> &str {
+    text.char_indices()
+        .find(|(i, c)| {
+            matches!(c, '.' | '!' | '?')
+                && text[i + c.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map_or(text, |(i, c)| &text[..i + c.len_utf8()])
+}
+
+/// Source attribution: the distinct hosts of the members' URLs.
+fn source_attribution(members: &[&ClusterArticle]) -> String {
+    let mut hosts: Vec<&str> = Vec::new();
+    for article in members {
+        let host = article
+            .url
+            .split_once("://")
+            .map_or(article.url.as_str(), |(_, rest)| rest)
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default();
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        if !host.is_empty() && !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    if hosts.is_empty() {
+        String::new()
+    } else {
+        format!("Sources: {}", hosts.join(", "))
+    }
+}
+
+/// Create a story for a new cluster, or update an existing story that
+/// gained `added` articles. Returns the story ID.
+fn save_story(
+    existing: Option<&ActiveStory>,
+    topic: Option<Uuid>,
+    members: &[&ClusterArticle],
+    added: usize,
+) -> Option<Uuid> {
+    let lead = members
+        .iter()
+        .max_by(|a, b| a.relevance.total_cmp(&b.relevance))?;
+    let mut fields = existing
+        .map(|s| s.fields.clone())
+        .filter(serde_json::Value::is_object)
+        .unwrap_or_else(|| serde_json::json!({}));
+    let count = existing.map_or(0, |s| s.article_count) + added as i64;
+    let max_relevance = members.iter().map(|a| a.relevance).fold(0.0, f64::max);
+
+    fields["field_summary"] = serde_json::json!(story_summary(members));
+    fields["field_source_attribution"] = serde_json::json!(source_attribution(members));
+    fields["field_article_count"] = serde_json::json!(count);
+    fields["field_relevance_score"] = serde_json::json!(max_relevance);
+    fields["field_active"] = serde_json::json!(true);
+    if let Some(topic) = topic {
+        fields["field_topic_id"] = serde_json::json!(topic.to_string());
+    }
+
+    let mut story = serde_json::json!({
+        "type": "argus_story",
+        "title": existing.map_or(lead.title.as_str(), |s| s.title.as_str()),
+        "status": 1,
+        "fields": fields,
+    });
+    if let Some(existing) = existing {
+        story["id"] = serde_json::json!(existing.id.to_string());
+    }
+
+    match host::save_item(&story) {
+        Ok(Some(saved)) => Some(saved.id),
+        Ok(None) => None,
+        Err(e) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!("failed to save story \"{}\": {e}", lead.title),
+            );
+            None
+        }
+    }
+}
+
+/// Point an article's `field_story_id` at a story.
+fn assign_article(article: &ClusterArticle, story_id: Uuid) -> bool {
+    let mut fields = article.fields.clone();
+    fields["field_story_id"] = serde_json::json!(story_id.to_string());
+    let update = serde_json::json!({
+        "id": article.id.to_string(),
+        "title": article.title,
+        "fields": fields,
+    });
+    match host::save_item(&update) {
+        Ok(saved) => saved.is_some(),
+        Err(e) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!(
+                    "failed to assign article {} to story {story_id}: {e}",
+                    article.id
+                ),
+            );
+            false
+        }
+    }
+}
+
+/// Mark a story without recent articles inactive.
+fn deactivate_story(story: &ActiveStory) -> bool {
+    let mut fields = story.fields.clone();
+    fields["field_active"] = serde_json::json!(false);
+    let update = serde_json::json!({
+        "id": story.id.to_string(),
+        "title": story.title,
+        "fields": fields,
+    });
+    match host::save_item(&update) {
+        Ok(saved) => saved.is_some(),
+        Err(e) => {
+            host::log(
+                "warn",
+                "argus",
+                &format!("failed to deactivate story {}: {e}", story.id),
+            );
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use trovato_test_utils::{MockHost, test_item};
+
+    #[test]
+    fn user_info_fields_are_user_editable() {
+        let fields = __inner_tap_user_info();
+        assert_eq!(fields.len(), 2);
+        assert!(fields.iter().all(|f| f.settings["user_editable"] == true));
+    }
 
     #[test]
     fn item_info_returns_seven_types() {
@@ -244,6 +1438,132 @@ mod tests {
         assert_eq!(menus[1].path, "/feeds");
     }
 
+    #[test]
+    fn gather_extend_declares_related_filter() {
+        let decl = __inner_tap_gather_extend();
+        assert_eq!(decl["filters"][0]["name"], "argus_related");
+        assert_eq!(decl["filters"][0]["handler"], "vector_similar");
+        assert_eq!(decl["filters"][0]["config"]["namespace"], "argus");
+    }
+
+    #[test]
+    fn parse_embedding_accepts_stored_formats() {
+        let expected = Some(vec![0.5, -1.0]);
+        assert_eq!(parse_embedding(&serde_json::json!([0.5, -1.0])), expected);
+        assert_eq!(parse_embedding(&serde_json::json!("[0.5, -1.0]")), expected);
+        assert_eq!(
+            parse_embedding(&serde_json::json!({"value": "[0.5,-1]", "format": "plain_text"})),
+            expected
+        );
+    }
+
+    #[test]
+    fn parse_embedding_rejects_garbage() {
+        assert_eq!(parse_embedding(&serde_json::json!("not a vector")), None);
+        assert_eq!(parse_embedding(&serde_json::json!([])), None);
+        assert_eq!(parse_embedding(&serde_json::json!(["a"])), None);
+        assert_eq!(parse_embedding(&serde_json::json!(42)), None);
+    }
+
+    fn names(mentions: &[Mention]) -> Vec<&str> {
+        mentions.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn normalize_name_drops_punctuation_and_case() {
+        assert_eq!(
+            normalize_name("  U.S. Federal  Reserve "),
+            "u s federal reserve"
+        );
+        assert_eq!(normalize_name("O'Brien"), "o brien");
+        assert_eq!(normalize_name("..."), "");
+    }
+
+    #[test]
+    fn parse_aliases_splits_lines_and_separators() {
+        assert_eq!(
+            parse_aliases("the Fed\nFederal Reserve; FRB, \n"),
+            vec!["the Fed", "Federal Reserve", "FRB"]
+        );
+    }
+
+    #[test]
+    fn pattern_mentions_finds_names_and_acronyms() {
+        let text = "The European Central Bank raised rates on Tuesday. \
+                    Officials at the Bank of England said NATO and Lagarde agreed.";
+        let found = pattern_mentions(text);
+        assert_eq!(
+            names(&found),
+            vec![
+                "European Central Bank",
+                "Bank of England",
+                "NATO",
+                "Lagarde"
+            ]
+        );
+    }
+
+    #[test]
+    fn pattern_mentions_skips_sentence_initial_words() {
+        let found = pattern_mentions("Markets fell. However, traders in Tokyo stayed calm.");
+        assert_eq!(names(&found), vec!["Tokyo"]);
+    }
+
+    #[test]
+    fn resolve_links_known_entities_by_alias() {
+        let fed = Uuid::nil();
+        let known = vec![KnownEntity {
+            id: fed,
+            names: vec!["federal reserve".into(), "the fed".into()],
+        }];
+        let text = "The Fed held rates. Officials said Powell and the Federal Reserve would wait.";
+        let mentions = pattern_mentions(text);
+        let resolution = resolve(text, &mentions, &known);
+        assert_eq!(resolution.linked, vec![(fed, 2)]);
+        assert_eq!(
+            names(
+                &resolution
+                    .new
+                    .iter()
+                    .map(|(m, _)| m.clone())
+                    .collect::<Vec<_>>()
+            ),
+            vec!["Powell"]
+        );
+    }
+
+    #[test]
+    fn resolve_dedupes_partial_names() {
+        let text =
+            "Officials met Jerome Powell on Monday. Aides said Powell spoke with POWELL staff.";
+        let mentions = pattern_mentions(text);
+        let resolution = resolve(text, &mentions, &[]);
+        assert_eq!(resolution.new.len(), 1);
+        assert_eq!(resolution.new[0].0.name, "Jerome Powell");
+        assert_eq!(resolution.new[0].1, 1);
+    }
+
+    #[test]
+    fn parse_ner_response_reads_entities() {
+        let mentions = parse_ner_response(
+            r#"{"entities":[{"name":"Ada Lovelace","type":"person"},{"name":"London"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(mentions[0].entity_type, "person");
+        assert_eq!(mentions[1].entity_type, "");
+        assert!(parse_ner_response("[]").is_none());
+    }
+
+    #[test]
+    fn entity_types_are_normalized() {
+        assert_eq!(normalize_entity_type(" PERSON "), "person");
+        assert_eq!(normalize_entity_type("ORG"), "organization");
+        assert_eq!(normalize_entity_type("GPE"), "place");
+        assert_eq!(normalize_entity_type("event"), "other");
+        assert_eq!(normalize_entity_type(""), "");
+        assert!(ENTITY_TYPES.contains(&normalize_entity_type("loc").as_str()));
+    }
+
     #[test]
     fn perm_format_matches_kernel_fallback() {
         let perms = __inner_tap_perm();
@@ -255,4 +1575,173 @@ mod tests {
             );
         }
     }
+
+    fn article(title: &str, topic: Option<Uuid>, embedding: &[f32]) -> ClusterArticle {
+        ClusterArticle {
+            id: Uuid::now_v7(),
+            title: title.into(),
+            topic,
+            story: None,
+            embedding: Some(embedding.to_vec()),
+            summary: String::new(),
+            relevance: 0.0,
+            url: String::new(),
+            fields: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn cosine_similarity_handles_edge_cases() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[], &[1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn cluster_articles_groups_by_topic_and_similarity() {
+        let energy = Uuid::now_v7();
+        let strict = Uuid::now_v7();
+        let articles = vec![
+            article("Oil rises", Some(energy), &[1.0, 0.0]),
+            article("Oil climbs", Some(energy), &[0.95, 0.1]),
+            article("Chip shortage", Some(energy), &[0.0, 1.0]),
+            // Same direction, different topic: never merged across topics.
+            article("Oil rises again", Some(strict), &[1.0, 0.0]),
+            article("Oil up", Some(strict), &[0.9, 0.3]),
+        ];
+        let clusters = cluster_articles(&articles, &[(strict, 0.99)]);
+
+        let members: Vec<Vec<usize>> = clusters.iter().map(|c| c.articles.clone()).collect();
+        assert_eq!(members, vec![vec![0, 1], vec![2], vec![3], vec![4]]);
+        assert!(clusters.iter().all(|c| c.story.is_none()));
+    }
+
+    #[test]
+    fn cluster_articles_extends_existing_stories() {
+        let story = Uuid::now_v7();
+        let mut seeded = article("Summit opens", None, &[0.0, 1.0]);
+        seeded.story = Some(story);
+        let mut no_embedding = article("Summit live blog", None, &[]);
+        no_embedding.embedding = None;
+        let articles = vec![
+            article("Summit day two", None, &[0.1, 1.0]),
+            seeded,
+            no_embedding,
+        ];
+
+        let clusters = cluster_articles(&articles, &[]);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].story, Some(story));
+        assert_eq!(clusters[0].articles, vec![1, 0]);
+    }
+
+    #[test]
+    fn story_summary_uses_most_relevant_first_sentences() {
+        let mut a = article("Low", None, &[1.0]);
+        a.summary = "Minor update. More detail.".into();
+        a.relevance = 0.2;
+        let mut b = article("High relevance headline", None, &[1.0]);
+        b.relevance = 0.9;
+        assert_eq!(
+            story_summary(&[&a, &b]),
+            "High relevance headline Minor update."
+        );
+        assert_eq!(
+            first_sentence("Rates rose 0.5 points"),
+            "Rates rose 0.5 points"
+        );
+        assert_eq!(first_sentence("Done! Next."), "Done!");
+    }
+
+    #[test]
+    fn source_attribution_lists_distinct_hosts() {
+        let mut a = article("A", None, &[1.0]);
+        a.url = "https://www.reuters.com/world/x".into();
+        let mut b = article("B", None, &[1.0]);
+        b.url = "https://reuters.com/markets?id=1".into();
+        let mut c = article("C", None, &[1.0]);
+        c.url = "http://bbc.co.uk".into();
+        assert_eq!(
+            source_attribution(&[&a, &b, &c]),
+            "Sources: reuters.com, bbc.co.uk"
+        );
+        assert_eq!(source_attribution(&[]), "");
+    }
+
+    #[test]
+    fn tap_cron_creates_stories_and_deactivates_stale_ones() {
+        let first = Uuid::now_v7();
+        let second = Uuid::now_v7();
+        let stale = Uuid::now_v7();
+        let row = |id: Uuid, title: &str, embedding: &str| {
+            serde_json::json!({
+                "id": id.to_string(),
+                "title": title,
+                "fields": {
+                    "field_vector_embedding": embedding,
+                    "field_summary": format!("{title}. Details follow."),
+                    "field_url": "https://example.com/a",
+                },
+            })
+        };
+        let host = MockHost::new()
+            .with_query_result(
+                "AND created >= $2",
+                vec![
+                    row(first, "Storm hits coast", "[1.0, 0.0]"),
+                    row(second, "Storm moves inland", "[0.9, 0.1]"),
+                ],
+            )
+            .with_query_result(
+                "AS article_count",
+                vec![serde_json::json!({
+                    "id": stale.to_string(),
+                    "title": "Old story",
+                    "fields": {"field_active": true, "field_summary": "Old."},
+                    "article_count": 4,
+                })],
+            )
+            .with_item(
+                test_item("argus_article", "Storm hits coast")
+                    .with_id(first)
+                    .to_sdk_item(),
+            )
+            .with_item(
+                test_item("argus_article", "Storm moves inland")
+                    .with_id(second)
+                    .to_sdk_item(),
+            )
+            .with_item(
+                test_item("argus_story", "Old story")
+                    .with_id(stale)
+                    .to_sdk_item(),
+            )
+            .install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        });
+        assert_eq!(result["stories_created"], 1);
+        assert_eq!(result["articles_assigned"], 2);
+        assert_eq!(result["stories_deactivated"], 1);
+
+        let items = host.items();
+        let story = items
+            .iter()
+            .find(|i| i.item_type == "argus_story" && i.id != stale)
+            .unwrap();
+        assert_eq!(story.fields["field_article_count"], 2);
+        assert_eq!(story.fields["field_active"], true);
+        assert_eq!(
+            story.fields["field_source_attribution"],
+            "Sources: example.com"
+        );
+        let assigned = items.iter().find(|i| i.id == first).unwrap();
+        assert_eq!(assigned.fields["field_story_id"], story.id.to_string());
+        let old = items.iter().find(|i| i.id == stale).unwrap();
+        assert_eq!(old.fields["field_active"], false);
+    }
 }
@@ -17,6 +17,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
             label: "Test Run".into(),
             description: "Load test execution with aggregate metrics".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new(
                     "field_target_site_id",
@@ -34,12 +36,16 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                 FieldDefinition::new("field_aggregate_metrics", FieldType::TextLong)
                     .label("Aggregate Metrics"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "goose_scenario".into(),
             label: "Scenario".into(),
             description: "Load test scenario definition".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_name", FieldType::Text { max_length: None })
                     .required()
@@ -56,12 +62,16 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                 FieldDefinition::new("field_task_config", FieldType::TextLong)
                     .label("Task Configuration"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "goose_endpoint_result".into(),
             label: "Endpoint Result".into(),
             description: "Per-endpoint metrics from a test run".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new(
                     "field_test_run_id",
@@ -86,12 +96,16 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                 FieldDefinition::new("field_p99", FieldType::Float).label("p99 (ms)"),
                 FieldDefinition::new("field_rps", FieldType::Float).label("Requests Per Second"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "goose_site".into(),
             label: "Site".into(),
             description: "Target site for load testing".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_name", FieldType::Text { max_length: None })
                     .required()
@@ -103,19 +117,27 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                 FieldDefinition::new("field_environment", FieldType::Text { max_length: None })
                     .label("Environment"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "goose_comparison".into(),
             label: "Comparison".into(),
             description: "Side-by-side comparison of test runs".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("field_name", FieldType::Text { max_length: None })
                     .required()
                     .label("Name"),
                 FieldDefinition::new("field_run_ids", FieldType::TextLong).label("Run IDs"),
                 FieldDefinition::new("field_annotations", FieldType::TextLong).label("Annotations"),
+                FieldDefinition::new("field_report", FieldType::TextLong)
+                    .label("Comparison Report"),
             ],
+            extends: None,
+            template: false,
         },
     ]
 }
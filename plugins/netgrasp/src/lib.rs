@@ -2,11 +2,23 @@
 //!
 //! Network monitoring use case: 6 content types for devices, people, events,
 //! presence sessions, IP history, and location tracking.
+//!
+//! Devices are fingerprinted as they are saved: a missing vendor is looked
+//! up from the MAC address, and a missing device type is guessed from the
+//! hostname and vendor, then offered to other plugins through
+//! `tap_ng_classify`.
 
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use trovato_sdk::host;
 use trovato_sdk::prelude::*;
 
 /// The 6 Netgrasp content types.
 ///
+/// Events, presence sessions, IP history and locations all record something
+/// about a device; they extend the `ng_device_record` field template for
+/// their shared `device_id` reference.
+///
 /// Field naming: Uses bare field names (e.g., `mac`, `display_name`) rather
 /// than the `field_` prefix convention. This matches the data and gather query
 /// field references from the original kernel implementation. New plugins should
@@ -28,6 +40,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
             label: "Device".into(),
             description: "Network device tracked by Netgrasp".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("mac", FieldType::Text { max_length: None })
                     .required()
@@ -42,23 +56,30 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     .label("Device Type"),
                 FieldDefinition::new("os_family", FieldType::Text { max_length: None })
                     .label("OS Family"),
-                FieldDefinition::new("state", FieldType::Text { max_length: None }).label("State"),
+                FieldDefinition::new("state", FieldType::Text { max_length: None })
+                    .label("State")
+                    .allowed_values(&["online", "offline", "unknown"]),
                 FieldDefinition::new("last_ip", FieldType::Text { max_length: None })
                     .label("Last IP"),
                 FieldDefinition::new("current_ap", FieldType::Text { max_length: None })
                     .label("Current AP"),
                 FieldDefinition::new("owner_id", FieldType::RecordReference("ng_person".into()))
-                    .label("Owner"),
+                    .label("Owner")
+                    .track_history(),
                 FieldDefinition::new("hidden", FieldType::Boolean).label("Hidden"),
                 FieldDefinition::new("notify", FieldType::Boolean).label("Notify"),
                 FieldDefinition::new("baseline", FieldType::Boolean).label("Baseline"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "ng_person".into(),
             label: "Person".into(),
             description: "Person associated with network devices".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("name", FieldType::Text { max_length: None })
                     .required()
@@ -67,16 +88,32 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                 FieldDefinition::new("notification_prefs", FieldType::Text { max_length: None })
                     .label("Notification Preferences"),
             ],
+            extends: None,
+            template: false,
         },
         ContentTypeDefinition {
-            machine_name: "ng_event".into(),
-            label: "Event".into(),
-            description: "Network event (device seen, new device, etc.)".into(),
+            machine_name: "ng_device_record".into(),
+            label: "Device Record".into(),
+            description: "Fields shared by records about a device".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
                 FieldDefinition::new("device_id", FieldType::RecordReference("ng_device".into()))
                     .required()
                     .label("Device"),
+            ],
+            extends: None,
+            template: true,
+        },
+        ContentTypeDefinition {
+            machine_name: "ng_event".into(),
+            label: "Event".into(),
+            description: "Network event (device seen, new device, etc.)".into(),
+            title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
+            fields: vec![
                 FieldDefinition::new("event_type", FieldType::Text { max_length: None })
                     .required()
                     .label("Event Type"),
@@ -85,31 +122,33 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     .label("Timestamp"),
                 FieldDefinition::new("details", FieldType::TextLong).label("Details"),
             ],
+            extends: Some("ng_device_record".into()),
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "ng_presence".into(),
             label: "Presence Session".into(),
             description: "Device presence session (online period)".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
-                FieldDefinition::new("device_id", FieldType::RecordReference("ng_device".into()))
-                    .required()
-                    .label("Device"),
                 FieldDefinition::new("start_time", FieldType::Integer)
                     .required()
                     .label("Start Time"),
                 FieldDefinition::new("end_time", FieldType::Integer).label("End Time"),
             ],
+            extends: Some("ng_device_record".into()),
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "ng_ip_history".into(),
             label: "IP History".into(),
             description: "Historical IP address assignments for devices".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
-                FieldDefinition::new("device_id", FieldType::RecordReference("ng_device".into()))
-                    .required()
-                    .label("Device"),
                 FieldDefinition::new("ip_address", FieldType::Text { max_length: None })
                     .required()
                     .label("IP Address"),
@@ -118,16 +157,17 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     .label("First Seen"),
                 FieldDefinition::new("last_seen", FieldType::Integer).label("Last Seen"),
             ],
+            extends: Some("ng_device_record".into()),
+            template: false,
         },
         ContentTypeDefinition {
             machine_name: "ng_location".into(),
             label: "Location History".into(),
             description: "Device location history".into(),
             title_label: None,
+            cache: None,
+            field_groups: Vec::new(),
             fields: vec![
-                FieldDefinition::new("device_id", FieldType::RecordReference("ng_device".into()))
-                    .required()
-                    .label("Device"),
                 FieldDefinition::new("location", FieldType::Text { max_length: None })
                     .required()
                     .label("Location"),
@@ -136,6 +176,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
                     .label("Start Time"),
                 FieldDefinition::new("end_time", FieldType::Integer).label("End Time"),
             ],
+            extends: Some("ng_device_record".into()),
+            template: false,
         },
     ]
 }
@@ -173,15 +215,204 @@ pub fn tap_menu() -> Vec<MenuDefinition> {
     ]
 }
 
+// ---- Device fingerprinting (tap_item_presave) ----
+
+/// Device types guessed from hostname words, checked before the vendor: a
+/// hostname names the device, while the vendor may only have made its
+/// network chip. A word matches when it starts with the hint.
+const HOSTNAME_HINTS: &[(&str, &str)] = &[
+    ("iphone", "phone"),
+    ("android", "phone"),
+    ("galaxy", "phone"),
+    ("pixel", "phone"),
+    ("ipad", "tablet"),
+    ("macbook", "computer"),
+    ("imac", "computer"),
+    ("desktop", "computer"),
+    ("laptop", "computer"),
+    ("appletv", "media_player"),
+    ("chromecast", "media_player"),
+    ("firetv", "media_player"),
+    ("roku", "media_player"),
+    ("xbox", "game_console"),
+    ("playstation", "game_console"),
+    ("ps4", "game_console"),
+    ("ps5", "game_console"),
+    ("nintendo", "game_console"),
+    ("printer", "printer"),
+    ("brn", "printer"),
+    ("epson", "printer"),
+    ("homepod", "speaker"),
+    ("sonos", "speaker"),
+    ("camera", "camera"),
+];
+
+/// Device types guessed from the (lowercased) vendor name.
+const VENDOR_HINTS: &[(&str, &str)] = &[
+    ("vmware", "virtual_machine"),
+    ("pcs systemtechnik", "virtual_machine"),
+    ("xensource", "virtual_machine"),
+    ("raspberry pi", "computer"),
+    ("espressif", "iot"),
+    ("philips lighting", "iot"),
+    ("lifi labs", "iot"),
+    ("nest labs", "iot"),
+    ("belkin", "iot"),
+    ("sonos", "speaker"),
+    ("synology", "nas"),
+    ("ubiquiti", "network"),
+    ("cisco", "network"),
+    ("netgear", "network"),
+    ("tp-link", "network"),
+    ("nintendo", "game_console"),
+    ("sony interactive", "game_console"),
+    ("roku", "media_player"),
+    ("seiko epson", "printer"),
+    ("brother industries", "printer"),
+    ("canon", "printer"),
+    ("axis communications", "camera"),
+    ("hikvision", "camera"),
+];
+
+/// Presave input: the kernel serializes `{item_type, title, fields,
+/// status}`, not a full Item.
+#[derive(Debug, Serialize, Deserialize)]
+struct PresaveInput {
+    item_type: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    fields: Map<String, Value>,
+    #[serde(default)]
+    status: i32,
+}
+
+/// Fill in the vendor and device type of devices being saved.
+///
+/// Values already set, by the scanner or an administrator, are kept.
+#[plugin_tap]
+pub fn tap_item_presave(input_json: String) -> String {
+    let mut input: PresaveInput = match serde_json::from_str(&input_json) {
+        Ok(v) => v,
+        Err(_) => return input_json,
+    };
+    if input.item_type != "ng_device" || !fingerprint_device(&mut input.fields) {
+        return input_json;
+    }
+    serde_json::to_string(&input).unwrap_or(input_json)
+}
+
+/// Fill in a device's missing `vendor` and `device_type` fields.
+///
+/// Returns whether a field was set.
+fn fingerprint_device(fields: &mut Map<String, Value>) -> bool {
+    let Some(mac) = text_field(fields, "mac") else {
+        return false;
+    };
+    let mut changed = false;
+
+    let mut vendor = text_field(fields, "vendor");
+    if vendor.is_none() {
+        match host::mac_vendor(&mac) {
+            Ok(Some(found)) => {
+                fields.insert("vendor".into(), Value::String(found.clone()));
+                vendor = Some(found);
+                changed = true;
+            }
+            Ok(None) => {}
+            Err(e) => host::log(
+                "warn",
+                "netgrasp",
+                &format!("MAC vendor lookup failed for {mac}: {e}"),
+            ),
+        }
+    }
+
+    if text_field(fields, "device_type").is_some() {
+        return changed;
+    }
+    let hostname = text_field(fields, "hostname");
+    let device = DeviceFingerprint {
+        device_type: guess_device_type(hostname.as_deref(), vendor.as_deref()).map(String::from),
+        mac,
+        vendor,
+        hostname,
+        os_family: text_field(fields, "os_family"),
+    };
+    let device = match host::classify_device(&device) {
+        Ok(classified) => classified,
+        Err(e) => {
+            host::log(
+                "warn",
+                "netgrasp",
+                &format!("device classification failed for {}: {e}", device.mac),
+            );
+            device
+        }
+    };
+    if let Some(device_type) = device.device_type.filter(|t| !t.is_empty()) {
+        fields.insert("device_type".into(), Value::String(device_type));
+        changed = true;
+    }
+    changed
+}
+
+/// A non-blank text field.
+fn text_field(fields: &Map<String, Value>, name: &str) -> Option<String> {
+    fields
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Guess a device type from its hostname and MAC vendor.
+fn guess_device_type(hostname: Option<&str>, vendor: Option<&str>) -> Option<&'static str> {
+    let from_hostname = hostname.and_then(|hostname| {
+        let hostname = hostname.to_lowercase();
+        hostname
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find_map(|word| {
+                HOSTNAME_HINTS
+                    .iter()
+                    .find(|(hint, _)| word.starts_with(hint))
+                    .map(|&(_, device_type)| device_type)
+            })
+    });
+    from_hostname.or_else(|| {
+        let vendor = vendor?.to_lowercase();
+        VENDOR_HINTS
+            .iter()
+            .find(|(hint, _)| vendor.contains(hint))
+            .map(|&(_, device_type)| device_type)
+    })
+}
+
 #[cfg(test)]
 // Tests are allowed to use unwrap/expect freely.
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use trovato_test_utils::MockHost;
+
+    fn presave(fields: Value) -> Value {
+        let input = serde_json::json!({
+            "item_type": "ng_device",
+            "title": "Device",
+            "fields": fields,
+            "status": 1,
+        });
+        let output = __inner_tap_item_presave(input.to_string());
+        serde_json::from_str::<Value>(&output).unwrap()["fields"].clone()
+    }
 
     #[test]
     fn item_info_returns_six_types() {
-        let types = __inner_tap_item_info();
+        let types: Vec<ContentTypeDefinition> = __inner_tap_item_info()
+            .into_iter()
+            .filter(|t| !t.template)
+            .collect();
         assert_eq!(types.len(), 6);
         let names: Vec<&str> = types.iter().map(|t| t.machine_name.as_str()).collect();
         assert!(names.contains(&"ng_device"));
@@ -202,6 +433,23 @@ mod tests {
         assert_eq!(device.fields.len(), 13);
     }
 
+    #[test]
+    fn device_records_extend_template() {
+        let types = __inner_tap_item_info();
+        let template = types
+            .iter()
+            .find(|t| t.machine_name == "ng_device_record")
+            .unwrap();
+        assert!(template.template);
+        assert_eq!(template.fields[0].field_name, "device_id");
+
+        for name in ["ng_event", "ng_presence", "ng_ip_history", "ng_location"] {
+            let def = types.iter().find(|t| t.machine_name == name).unwrap();
+            assert_eq!(def.extends.as_deref(), Some("ng_device_record"));
+            assert!(def.fields.iter().all(|f| f.field_name != "device_id"));
+        }
+    }
+
     #[test]
     fn perm_returns_twenty_four_permissions() {
         let perms = __inner_tap_perm();
@@ -216,6 +464,85 @@ mod tests {
         assert_eq!(menus[1].path, "/events");
     }
 
+    #[test]
+    fn presave_fills_vendor_and_device_type() {
+        let _host = MockHost::new()
+            .with_mac_vendor("B827EB", "Raspberry Pi Foundation")
+            .install();
+
+        let fields = presave(serde_json::json!({"mac": "b8:27:eb:01:02:03"}));
+        assert_eq!(fields["vendor"], "Raspberry Pi Foundation");
+        assert_eq!(fields["device_type"], "computer");
+
+        // The hostname beats the vendor.
+        let fields = presave(serde_json::json!({
+            "mac": "b8:27:eb:01:02:03",
+            "hostname": "Living-Room-Chromecast",
+        }));
+        assert_eq!(fields["device_type"], "media_player");
+    }
+
+    #[test]
+    fn presave_keeps_existing_values() {
+        let _host = MockHost::new()
+            .with_mac_vendor("B827EB", "Raspberry Pi Foundation")
+            .install();
+
+        let fields = presave(serde_json::json!({
+            "mac": "b8:27:eb:01:02:03",
+            "vendor": "Custom",
+            "device_type": "server",
+        }));
+        assert_eq!(fields["vendor"], "Custom");
+        assert_eq!(fields["device_type"], "server");
+
+        // Other content types pass through untouched.
+        let input = r#"{"item_type":"ng_person","fields":{"mac":"b8:27:eb:01:02:03"}}"#;
+        assert_eq!(__inner_tap_item_presave(input.to_string()), input);
+    }
+
+    #[test]
+    fn presave_applies_other_plugins_classification() {
+        let _host = MockHost::new()
+            .with_device_classifier(|device| {
+                if device.hostname.as_deref() == Some("thermostat") {
+                    device.device_type = Some("iot".into());
+                }
+            })
+            .install();
+
+        let fields = presave(serde_json::json!({
+            "mac": "02:11:22:33:44:55",
+            "hostname": "thermostat",
+        }));
+        assert_eq!(fields["device_type"], "iot");
+        assert!(fields.get("vendor").is_none());
+    }
+
+    #[test]
+    fn device_type_guesses() {
+        assert_eq!(
+            guess_device_type(Some("Alices-iPhone"), None),
+            Some("phone")
+        );
+        assert_eq!(
+            guess_device_type(Some("BRN3C2AF4123456"), None),
+            Some("printer")
+        );
+        assert_eq!(
+            guess_device_type(Some("DESKTOP-4F2K9"), Some("Intel Corporate")),
+            Some("computer")
+        );
+        assert_eq!(
+            guess_device_type(None, Some("Sonos, Inc.")),
+            Some("speaker")
+        );
+        assert_eq!(
+            guess_device_type(Some("host-17"), Some("Intel Corporate")),
+            None
+        );
+    }
+
     #[test]
     fn perm_format_matches_kernel_fallback() {
         let perms = __inner_tap_perm();
@@ -124,11 +124,11 @@ fn load_field_rules() -> Vec<FieldRule> {
         &[serde_json::json!("trovato_ai.field_rules")],
     ) {
         Ok(json) => json,
-        Err(code) => {
+        Err(e) => {
             host::log(
                 "debug",
                 "trovato_ai",
-                &format!("field rules query returned error code {code}"),
+                &format!("field rules query failed: {e}"),
             );
             return Vec::new();
         }
@@ -287,13 +287,13 @@ pub fn tap_item_presave(input_json: String) -> String {
                     );
                 }
             }
-            Err(code) => {
+            Err(e) => {
                 host::log(
                     "warn",
                     "trovato_ai",
                     &format!(
-                        "ai_request failed for {}.{}: error {}",
-                        rule.item_type, rule.target_field, code
+                        "ai_request failed for {}.{}: {}",
+                        rule.item_type, rule.target_field, e
                     ),
                 );
             }
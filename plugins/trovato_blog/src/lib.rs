@@ -15,6 +15,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
         label: "Blog Post".into(),
         description: "A blog entry with body and tags".into(),
         title_label: None,
+        cache: None,
+        field_groups: Vec::new(),
         fields: vec![
             FieldDefinition::new("field_body", FieldType::TextLong)
                 .required()
@@ -26,6 +28,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
             .cardinality(-1)
             .label("Tags"),
         ],
+        extends: None,
+        template: false,
     }]
 }
 
@@ -33,11 +37,20 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
 ///
 /// Uses standard CRUD permissions matching the kernel fallback format.
 /// "edit blog content" / "delete blog content" serve as "edit any" / "delete any"
-/// permissions. Author access ("own" semantics) is handled by `tap_item_access`
-/// below, which grants access when user == author.
+/// permissions. "edit own blog content" / "delete own blog content" are checked
+/// by `tap_item_access` below for authors.
 #[plugin_tap]
 pub fn tap_perm() -> Vec<PermissionDefinition> {
-    PermissionDefinition::crud_for_type("blog")
+    let mut perms = PermissionDefinition::crud_for_type("blog");
+    perms.push(PermissionDefinition::new(
+        "edit own blog content",
+        "Edit own blog posts",
+    ));
+    perms.push(PermissionDefinition::new(
+        "delete own blog content",
+        "Delete own blog posts",
+    ));
+    perms
 }
 
 /// Access control for blog posts — implements "own" semantics.
@@ -47,29 +60,31 @@ pub fn tap_perm() -> Vec<PermissionDefinition> {
 /// 2. `tap_item_access` → this function (below)
 /// 3. Permission fallback → checks `"{operation} blog content"`
 ///
-/// This tap grants access when user == author, providing "own" semantics:
-/// - Authors can view their own unpublished drafts
-/// - Authors can edit and delete their own posts
+/// This tap grants authors access to their own posts:
+/// - Authors can always view their own unpublished drafts
+/// - Authors can edit or delete their own posts with "edit own blog content"
+///   / "delete own blog content"
 ///
 /// Non-authors fall through to the kernel permission fallback, which checks
 /// "edit blog content" / "delete blog content" — the "any" equivalent.
-///
-/// Note: The WASM boundary prevents checking user permissions inside taps
-/// (ItemAccessInput has no permission data). Author access is therefore
-/// unconditional — any authenticated author can edit/delete their own posts.
 #[plugin_tap]
 pub fn tap_item_access(input: ItemAccessInput) -> AccessResult {
-    if input.item_type != "blog" {
+    if input.item_type != "blog" || !input.is_author() {
         return AccessResult::Neutral;
     }
 
-    // Author can always access their own posts (view drafts, edit, delete)
-    if input.user_id == input.author_id {
-        return AccessResult::Grant;
+    let allowed = match input.operation.as_str() {
+        "view" => true,
+        "edit" => input.has_permission("edit own blog content"),
+        "delete" => input.has_permission("delete own blog content"),
+        _ => false,
+    };
+    if allowed {
+        AccessResult::Grant
+    } else {
+        // Defer to kernel permission fallback ("edit blog content", etc.)
+        AccessResult::Neutral
     }
-
-    // Non-authors: defer to kernel permission fallback ("edit blog content", etc.)
-    AccessResult::Neutral
 }
 
 /// Menu routes provided by the blog plugin.
@@ -99,9 +114,9 @@ mod tests {
     }
 
     #[test]
-    fn perm_returns_four_permissions() {
+    fn perm_returns_six_permissions() {
         let perms = __inner_tap_perm();
-        assert_eq!(perms.len(), 4); // 4 per type × 1 type (view/create/edit/delete)
+        assert_eq!(perms.len(), 6); // view/create/edit/delete + edit own/delete own
     }
 
     #[test]
@@ -129,7 +144,11 @@ mod tests {
             operation: operation.into(),
             user_id,
             user_authenticated: true,
-            user_permissions: vec![],
+            user_permissions: vec![
+                "edit own blog content".into(),
+                "delete own blog content".into(),
+            ],
+            user_roles: vec!["authenticated user".into()],
             stage_id: None,
             stage_machine_name: None,
         }
@@ -168,6 +187,26 @@ mod tests {
         assert_eq!(__inner_tap_item_access(input), AccessResult::Grant);
     }
 
+    #[test]
+    fn access_neutral_for_author_without_own_permission() {
+        let author = Uuid::nil();
+        let mut input = access_input("blog", "edit", author, author);
+        input.user_permissions.clear();
+        assert_eq!(__inner_tap_item_access(input), AccessResult::Neutral);
+
+        // Drafts stay viewable to their author.
+        let mut input = access_input("blog", "view", author, author);
+        input.user_permissions.clear();
+        assert_eq!(__inner_tap_item_access(input), AccessResult::Grant);
+    }
+
+    #[test]
+    fn access_neutral_for_anonymous() {
+        let mut input = access_input("blog", "view", Uuid::nil(), Uuid::nil());
+        input.user_authenticated = false;
+        assert_eq!(__inner_tap_item_access(input), AccessResult::Neutral);
+    }
+
     #[test]
     fn menu_returns_two_routes() {
         let menus = __inner_tap_menu();
@@ -95,11 +95,11 @@ pub fn tap_form_validate(input_json: String) -> String {
                 r#"{"errors":["CAPTCHA verification error. Please try again."]}"#.to_string()
             }
         }
-        Err(code) => {
+        Err(e) => {
             host::log(
                 "error",
                 "trovato_captcha",
-                &format!("Turnstile HTTP request failed with code {code}"),
+                &format!("Turnstile HTTP request failed: {e}"),
             );
             r#"{"errors":["CAPTCHA service unavailable. Please try again later."]}"#.to_string()
         }
@@ -0,0 +1,26 @@
+-- Key config translations by property so each translatable string
+-- (item type label, category name, variable value) is stored and
+-- updated on its own. Existing string entries in `data` are carried over.
+-- Forward-only migration; no rollback.
+
+ALTER TABLE config_translation RENAME TO config_translation_legacy;
+
+CREATE TABLE config_translation (
+    entity_type VARCHAR(255) NOT NULL,
+    entity_id VARCHAR(255) NOT NULL,
+    language VARCHAR(12) NOT NULL,
+    property VARCHAR(64) NOT NULL,
+    value TEXT NOT NULL,
+    changed BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (entity_type, entity_id, language, property)
+);
+
+CREATE INDEX idx_config_translation_type_language
+    ON config_translation (entity_type, language);
+
+INSERT INTO config_translation (entity_type, entity_id, language, property, value)
+SELECT l.entity_type, l.entity_id, l.language, d.key, d.value #>> '{}'
+FROM config_translation_legacy l, jsonb_each(l.data) d
+WHERE jsonb_typeof(d.value) = 'string';
+
+DROP TABLE config_translation_legacy;
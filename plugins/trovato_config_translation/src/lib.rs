@@ -1,6 +1,11 @@
 //! Config translation plugin for Trovato.
 //!
 //! Provides configuration entity translation with language overlay.
+//!
+//! Translations live in the `config_translation` table, one row per
+//! translated property. The kernel serves the admin UI at
+//! `/admin/config/translate` and applies the overlays for the negotiated
+//! language through `TranslatedConfigStorage`.
 
 use trovato_sdk::prelude::*;
 
@@ -0,0 +1,33 @@
+//! Contact form plugin for Trovato.
+//!
+//! The kernel serves the form at `/contact` and the admin screens at
+//! `/admin/structure/contact` and `/admin/content/contact`; all of them are
+//! gated on this plugin. The form stays hidden until a category exists.
+//!
+//! Implements `tap_menu` to link the public form.
+
+use trovato_sdk::prelude::*;
+
+/// Register the public contact form link.
+#[plugin_tap]
+pub fn tap_menu() -> Vec<MenuDefinition> {
+    vec![
+        MenuDefinition::new("/contact", "Contact")
+            .callback("contact_form")
+            .permission("access content"),
+    ]
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_links_contact_form() {
+        let menus = __inner_tap_menu();
+        assert_eq!(menus.len(), 1);
+        assert_eq!(menus[0].path, "/contact");
+    }
+}
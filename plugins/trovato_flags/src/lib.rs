@@ -0,0 +1,51 @@
+//! Item flags plugin for Trovato.
+//!
+//! The kernel serves `/api/flags` and the per-item flag routes while this
+//! plugin is enabled. It declares the per-user `bookmark` flag; other
+//! plugins declare more under `[flags]`, and administrators add their own
+//! through `/api/flags`.
+//!
+//! Implements `tap_flag` to log flag changes.
+
+use trovato_sdk::host;
+use trovato_sdk::prelude::*;
+
+/// Log each flag set or cleared.
+#[plugin_tap_result]
+pub fn tap_flag(input: FlagInput) -> Result<(), String> {
+    host::log("debug", "trovato_flags", &change_message(&input));
+    Ok(())
+}
+
+/// Describe a flag change for the log.
+fn change_message(input: &FlagInput) -> String {
+    let action = if input.flagged { "set" } else { "cleared" };
+    format!(
+        "{} {action} on item {} by user {} (count {})",
+        input.flag, input.item_id, input.user_id, input.count
+    )
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_message_names_flag_and_action() {
+        let input = FlagInput {
+            flag: "bookmark".to_string(),
+            scope: "user".to_string(),
+            item_id: Uuid::nil(),
+            item_type: "page".to_string(),
+            user_id: Uuid::nil(),
+            flagged: false,
+            count: 0,
+        };
+        let message = change_message(&input);
+        assert!(message.starts_with("bookmark cleared on item"));
+        assert!(message.ends_with("(count 0)"));
+        assert!(__inner_tap_flag(input).is_ok());
+    }
+}
@@ -0,0 +1,19 @@
+-- Create responsive_image_style table and seed a default style.
+-- Forward-only migration; no rollback.
+--
+-- breakpoints is a JSON array of {"media", "styles", "sizes"} objects in
+-- <source> order; fallback_style is the image style of the <img>.
+
+CREATE TABLE IF NOT EXISTS responsive_image_style (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    label VARCHAR(255) NOT NULL,
+    fallback_style VARCHAR(255) NOT NULL,
+    breakpoints JSONB NOT NULL DEFAULT '[]',
+    created BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())::bigint,
+    changed BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())::bigint
+);
+
+INSERT INTO responsive_image_style (id, name, label, fallback_style, breakpoints, created, changed) VALUES
+(gen_random_uuid(), 'content', 'Content width', 'medium', '[{"media": "(min-width: 768px)", "styles": ["medium", "large"], "sizes": "(min-width: 1200px) 1000px, 100vw"}, {"styles": ["w320", "medium"], "sizes": "100vw"}]'::jsonb, EXTRACT(EPOCH FROM NOW())::bigint, EXTRACT(EPOCH FROM NOW())::bigint)
+ON CONFLICT (name) DO NOTHING;
@@ -1,7 +1,8 @@
 //! Image styles plugin for Trovato.
 //!
 //! Provides on-demand image derivative generation with configurable
-//! effect chains (scale, crop, resize, desaturate).
+//! effect chains (scale, crop, resize, desaturate), and responsive image
+//! styles that map breakpoints to image styles.
 
 use trovato_sdk::prelude::*;
 
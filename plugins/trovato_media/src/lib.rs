@@ -12,6 +12,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
         label: "Media".into(),
         description: "A media entity with file, alt text, caption, and credit".into(),
         title_label: None,
+        cache: None,
+        field_groups: Vec::new(),
         fields: vec![
             FieldDefinition::new("field_file", FieldType::File)
                 .required()
@@ -22,6 +24,8 @@ pub fn tap_item_info() -> Vec<ContentTypeDefinition> {
             FieldDefinition::new("field_credit", FieldType::Text { max_length: None })
                 .label("Credit"),
         ],
+        extends: None,
+        template: false,
     }]
 }
 
@@ -98,6 +102,7 @@ mod tests {
             user_id,
             user_authenticated: true,
             user_permissions: vec![],
+            user_roles: vec![],
             stage_id: None,
             stage_machine_name: None,
         }
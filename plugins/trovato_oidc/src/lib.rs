@@ -0,0 +1,9 @@
+//! OpenID Connect login plugin for Trovato.
+//!
+//! The kernel serves the provider login and callback routes
+//! (`/auth/{provider}/login`, `/auth/{provider}/callback`) and the provider
+//! configuration API (`/admin/config/oidc-providers`); all of them are gated
+//! on this plugin, and the login form only offers provider buttons while it
+//! is enabled.
+//!
+//! The plugin implements no taps.
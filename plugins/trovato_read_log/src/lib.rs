@@ -0,0 +1,86 @@
+//! Read access logging plugin for Trovato.
+//!
+//! The kernel samples reads of opted-in item types into `item_read_log`
+//! while this plugin is enabled; the report and settings routes are gated
+//! on it too. Settings live in the `read_log` site_config key.
+//!
+//! Implements `tap_cron` to purge entries older than the configured
+//! `retention_days`.
+
+use trovato_sdk::host;
+use trovato_sdk::prelude::*;
+
+/// Retention used when `read_log` has no `retention_days` (kernel default).
+const DEFAULT_RETENTION_DAYS: i64 = 7;
+
+/// Upper bound on retention, matching the kernel's settings validation.
+const MAX_RETENTION_DAYS: i64 = 90;
+
+/// Delete entries older than the configured retention, clamped to range.
+const CLEANUP_SQL: &str = "DELETE FROM item_read_log WHERE created < $1 - 86400 * \
+    LEAST(GREATEST(COALESCE((SELECT (value->>'retention_days')::bigint \
+    FROM site_config WHERE key = 'read_log'), $2), 1), $3)";
+
+/// Purge read log entries past their retention.
+#[plugin_tap]
+pub fn tap_cron(input: CronInput) -> serde_json::Value {
+    match host::execute_raw(
+        CLEANUP_SQL,
+        &[
+            serde_json::json!(input.timestamp),
+            serde_json::json!(DEFAULT_RETENTION_DAYS),
+            serde_json::json!(MAX_RETENTION_DAYS),
+        ],
+    ) {
+        Ok(deleted) => {
+            if deleted > 0 {
+                host::log(
+                    "info",
+                    "trovato_read_log",
+                    &format!("purged {deleted} read log entries"),
+                );
+            }
+            serde_json::json!({"deleted": deleted})
+        }
+        Err(e) => {
+            host::log(
+                "warn",
+                "trovato_read_log",
+                &format!("read log cleanup failed: {e}"),
+            );
+            serde_json::json!({"error": "failed to purge read log"})
+        }
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use trovato_test_utils::MockHost;
+
+    #[test]
+    fn tap_cron_purges_past_retention() {
+        let host = MockHost::new()
+            .with_execute_result("DELETE FROM item_read_log", 3)
+            .install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        });
+        assert_eq!(result["deleted"], 3);
+
+        let executed = host.executed();
+        assert_eq!(executed.len(), 1);
+        assert_eq!(
+            executed[0].params,
+            vec![
+                serde_json::json!(1_700_000_000),
+                serde_json::json!(DEFAULT_RETENTION_DAYS),
+                serde_json::json!(MAX_RETENTION_DAYS),
+            ]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Saved searches plugin for Trovato.
+//!
+//! The kernel's saved search service and `/api/saved-searches` routes are
+//! only available while this plugin is enabled.
+//!
+//! Implements `tap_cron` to check alert-enabled searches for new matches;
+//! the kernel sends the mail and webhook alerts.
+
+use trovato_sdk::host;
+use trovato_sdk::prelude::*;
+
+/// Check saved search alerts.
+#[plugin_tap]
+pub fn tap_cron(_input: CronInput) -> serde_json::Value {
+    match host::check_saved_search_alerts() {
+        Ok(sent) => {
+            if sent > 0 {
+                host::log(
+                    "info",
+                    "trovato_saved_searches",
+                    &format!("sent {sent} saved search alerts"),
+                );
+            }
+            serde_json::json!({"sent": sent})
+        }
+        Err(e) => {
+            host::log(
+                "warn",
+                "trovato_saved_searches",
+                &format!("saved search alert check failed: {e}"),
+            );
+            serde_json::json!({"error": "failed to check saved search alerts"})
+        }
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use trovato_test_utils::MockHost;
+
+    #[test]
+    fn tap_cron_checks_alerts() {
+        let _host = MockHost::new().with_saved_search_alerts(2).install();
+
+        let result = __inner_tap_cron(CronInput::default());
+        assert_eq!(result["sent"], 2);
+    }
+}
@@ -4,8 +4,19 @@
 //! field_publish_on and field_unpublish_on JSONB fields.
 //!
 //! Implements `tap_cron` to process scheduled publish/unpublish
-//! operations each cron cycle using DB host functions.
+//! operations each cron cycle using the item query and DB host functions.
+//! Only live-stage items are processed unless staged content is enabled
+//! via the `scheduled_publishing.include_staged` variable.
+//!
+//! Implements `tap_item_clone` so copies of an item don't inherit its
+//! schedule, and `tap_item_validate` so an item can't be unpublished
+//! before it is published.
+//!
+//! Implements `tap_form_alter` to add the schedule to item forms, and
+//! `tap_item_presave` to store the timestamps submitted there as numbers.
 
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use trovato_sdk::host;
 use trovato_sdk::prelude::*;
 
@@ -27,35 +38,275 @@ pub fn tap_menu() -> Vec<MenuDefinition> {
     ]
 }
 
+/// Fields holding a schedule.
+const SCHEDULE_FIELDS: &[&str] = &["field_publish_on", "field_unpublish_on"];
+
+/// Clear the schedule on a copy of an item.
+///
+/// A copy starts unpublished; keeping the source's dates would publish or
+/// unpublish it behind the editor's back.
+#[plugin_tap]
+pub fn tap_item_clone(input: ItemCloneInput) -> ItemCloneResult {
+    SCHEDULE_FIELDS
+        .iter()
+        .filter(|field| input.fields.get(**field).is_some())
+        .fold(ItemCloneResult::new(), |result, field| result.remove(field))
+}
+
+/// Reject schedule times that are not timestamps, and an unpublish time
+/// that is not after the publish time.
+///
+/// Times are Unix timestamps, as numbers or the numeric strings submitted
+/// by forms; the order check only applies when both are set.
+#[plugin_tap]
+pub fn tap_item_validate(input: ItemValidateInput) -> Vec<ItemViolation> {
+    let timestamp = |field: &str| match input.fields.get(field) {
+        Some(serde_json::Value::Number(n)) => n.as_i64(),
+        Some(serde_json::Value::String(s)) => s.trim().parse::<i64>().ok(),
+        _ => None,
+    };
+    let invalid: Vec<ItemViolation> = SCHEDULE_FIELDS
+        .iter()
+        .filter(|field| {
+            matches!(input.fields.get(**field), Some(Value::String(s))
+                if !s.trim().is_empty() && s.trim().parse::<i64>().is_err())
+        })
+        .map(|field| {
+            ItemViolation::new(
+                field,
+                "invalid_timestamp",
+                "Schedule times must be Unix timestamps.",
+            )
+        })
+        .collect();
+    if !invalid.is_empty() {
+        return invalid;
+    }
+    match (
+        timestamp("field_publish_on"),
+        timestamp("field_unpublish_on"),
+    ) {
+        (Some(publish), Some(unpublish)) if unpublish <= publish => vec![ItemViolation::new(
+            "field_unpublish_on",
+            "unpublish_before_publish",
+            "The unpublish date must be after the publish date.",
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Add publish and unpublish times to item forms.
+///
+/// The times are entered as Unix timestamps into elements named after the
+/// schedule fields, so the kernel saves them with the item and shows the
+/// stored values when the item is edited. Content types declaring the
+/// fields themselves already have them on the form.
+#[plugin_tap]
+pub fn tap_form_alter(mut form: form::Form) -> form::Form {
+    if !form.is_item_form()
+        || SCHEDULE_FIELDS
+            .iter()
+            .any(|f| form.elements.contains_key(*f))
+        || !host::current_user_has_permission("schedule publishing")
+    {
+        return form;
+    }
+
+    let time = |label: &str| {
+        form::FormElement::textfield()
+            .title(label)
+            .placeholder("Unix timestamp")
+    };
+    form.add_element(
+        "_schedule",
+        form::FormElement::fieldset()
+            .title("Schedule")
+            .description("Leave empty to publish or unpublish by hand.")
+            .weight(100)
+            .child("field_publish_on", time("Publish on").weight(0))
+            .child("field_unpublish_on", time("Unpublish on").weight(1)),
+    );
+    form
+}
+
+/// Store the schedule times submitted by item forms as numbers.
+///
+/// Forms submit strings; cron compares the fields numerically. Empty
+/// times are cleared.
+#[plugin_tap]
+pub fn tap_item_presave(input: PresaveInput) -> Value {
+    let mut fields = Map::new();
+    for field in SCHEDULE_FIELDS {
+        if let Some(Value::String(s)) = input.fields.get(*field) {
+            let s = s.trim();
+            if s.is_empty() {
+                fields.insert(field.to_string(), Value::Null);
+            } else if let Ok(timestamp) = s.parse::<i64>() {
+                fields.insert(field.to_string(), Value::from(timestamp));
+            }
+        }
+    }
+    if fields.is_empty() {
+        return serde_json::json!({});
+    }
+    serde_json::json!({ "fields": fields })
+}
+
+/// The part of the `tap_item_presave` input this plugin reads.
+#[derive(Debug, Deserialize)]
+pub struct PresaveInput {
+    #[serde(default)]
+    fields: Map<String, Value>,
+}
+
+/// Site variable: also process items in non-live stages ("true"/"false").
+const INCLUDE_STAGED_VAR: &str = "scheduled_publishing.include_staged";
+
+/// Site variable: URL that receives a JSON summary of each run that
+/// changed anything. Empty disables notification.
+const NOTIFY_URL_VAR: &str = "scheduled_publishing.notify_url";
+
+/// An item whose status was changed by a cron run.
+#[derive(Debug, Clone, Serialize)]
+struct Transition {
+    id: Uuid,
+    title: String,
+    stage_id: Uuid,
+}
+
 /// Process scheduled publish/unpublish operations.
 ///
 /// Called each cron cycle. Publishes items where `field_publish_on` <= now
-/// and unpublishes items where `field_unpublish_on` <= now.
+/// and unpublishes items where `field_unpublish_on` <= now. Only live-stage
+/// items are touched unless `scheduled_publishing.include_staged` is
+/// `"true"`, in which case staged items change status within their own
+/// stage (the stage itself is not published).
+///
+/// Due items are found with [`host::item_query`]; at most
+/// `ITEM_QUERY_MAX_LIMIT` of each are processed per stage per cycle and the
+/// remainder on the next. Runs that change anything are POSTed to
+/// `scheduled_publishing.notify_url` when set.
 #[plugin_tap]
 pub fn tap_cron(input: CronInput) -> serde_json::Value {
     let now = input.timestamp;
 
-    let published = host::execute_raw(
-        "UPDATE item SET status = 1, changed = $1 \
-         WHERE status = 0 \
-         AND fields->>'field_publish_on' IS NOT NULL \
-         AND (fields->>'field_publish_on') ~ '^[0-9]+$' \
-         AND (fields->>'field_publish_on')::bigint <= $1",
-        &[serde_json::json!(now)],
-    )
-    .unwrap_or(0);
-
-    let unpublished = host::execute_raw(
-        "UPDATE item SET status = 0, changed = $1 \
-         WHERE status = 1 \
-         AND fields->>'field_unpublish_on' IS NOT NULL \
-         AND (fields->>'field_unpublish_on') ~ '^[0-9]+$' \
-         AND (fields->>'field_unpublish_on')::bigint <= $1",
-        &[serde_json::json!(now)],
-    )
-    .unwrap_or(0);
-
-    serde_json::json!({"published": published, "unpublished": unpublished})
+    let mut stages = vec![live_stage_id()];
+    if host::variables_get(INCLUDE_STAGED_VAR, "false").is_ok_and(|v| v == "true") {
+        stages.extend(staged_with_schedules());
+    }
+
+    let mut published = Vec::new();
+    let mut unpublished = Vec::new();
+    for stage in &stages {
+        published.extend(apply_due(*stage, 0, 1, "field_publish_on", now));
+        unpublished.extend(apply_due(*stage, 1, 0, "field_unpublish_on", now));
+    }
+
+    if !published.is_empty() || !unpublished.is_empty() {
+        notify(now, &published, &unpublished);
+    }
+
+    serde_json::json!({"published": published.len(), "unpublished": unpublished.len()})
+}
+
+/// Non-live stages holding items with a publish or unpublish date.
+fn staged_with_schedules() -> Vec<Uuid> {
+    #[derive(Deserialize)]
+    struct Row {
+        stage_id: String,
+    }
+
+    let Ok(json) = host::query_raw(
+        "SELECT DISTINCT stage_id::text AS stage_id FROM item \
+         WHERE stage_id <> $1::uuid \
+         AND (fields ? 'field_publish_on' OR fields ? 'field_unpublish_on')",
+        &[serde_json::json!(LIVE_STAGE_UUID)],
+    ) else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Vec<Row>>(&json)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|row| row.stage_id.parse().ok())
+        .collect()
+}
+
+/// Move items in `stage` with status `from` whose `field` timestamp is due
+/// to status `to`.
+///
+/// Returns the items updated.
+fn apply_due(stage: Uuid, from: i16, to: i16, field: &str, now: i64) -> Vec<Transition> {
+    let mut query = ItemQuery::new()
+        .status(from)
+        .field(field, ItemQueryOp::Lte, serde_json::json!(now))
+        .sort(field, SortDirection::Asc);
+    if stage != live_stage_id() {
+        query = query.stage(&stage.to_string());
+    }
+    let Ok(items) = host::item_query(&query) else {
+        return Vec::new();
+    };
+
+    items
+        .into_iter()
+        // A stage query overlays live; live items are handled on their own pass.
+        .filter(|item| item.stage_id == stage)
+        .filter(|item| {
+            // Guard on the old status and stage so a concurrent edit or a
+            // move between stages is not overwritten.
+            host::execute_raw(
+                "UPDATE item SET status = $1, changed = $2 \
+                 WHERE id = $3::uuid AND status = $4 AND stage_id = $5::uuid",
+                &[
+                    serde_json::json!(to),
+                    serde_json::json!(now),
+                    serde_json::json!(item.id.to_string()),
+                    serde_json::json!(from),
+                    serde_json::json!(stage.to_string()),
+                ],
+            )
+            .is_ok_and(|rows| rows > 0)
+        })
+        .map(|item| Transition {
+            id: item.id,
+            title: item.title,
+            stage_id: item.stage_id,
+        })
+        .collect()
+}
+
+/// Log the run and POST it to the notification URL, if configured.
+fn notify(now: i64, published: &[Transition], unpublished: &[Transition]) {
+    host::log(
+        "info",
+        "trovato_scheduled_publishing",
+        &format!(
+            "published {} and unpublished {} scheduled items",
+            published.len(),
+            unpublished.len()
+        ),
+    );
+
+    let url = match host::variables_get(NOTIFY_URL_VAR, "") {
+        Ok(url) if !url.is_empty() => url,
+        _ => return,
+    };
+    let body = serde_json::json!({
+        "event": "scheduled_publishing.run",
+        "timestamp": now,
+        "published": published,
+        "unpublished": unpublished,
+    });
+    let request = HttpRequest::post(url, body.to_string())
+        .header("Content-Type", "application/json")
+        .timeout(10_000);
+    if let Err(e) = host::http_request(&request) {
+        host::log(
+            "warn",
+            "trovato_scheduled_publishing",
+            &format!("notification request failed: {e}"),
+        );
+    }
 }
 
 #[cfg(test)]
@@ -63,6 +314,7 @@ pub fn tap_cron(input: CronInput) -> serde_json::Value {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use trovato_test_utils::MockHost;
 
     #[test]
     fn perm_returns_one_permission() {
@@ -77,14 +329,216 @@ mod tests {
         assert_eq!(menus[0].path, "/admin/content/scheduled");
     }
 
+    #[test]
+    fn tap_item_clone_clears_schedule() {
+        let input = ItemCloneInput {
+            source_id: Uuid::now_v7(),
+            item_type: "page".into(),
+            title: "Launch".into(),
+            fields: serde_json::json!({"field_publish_on": 100, "field_body": "x"}),
+            stage_id: live_stage_id(),
+            language: "en".into(),
+            user_id: Uuid::now_v7(),
+        };
+        let result = __inner_tap_item_clone(input);
+        assert_eq!(result.fields.len(), 1);
+        assert!(result.fields["field_publish_on"].is_null());
+    }
+
+    #[test]
+    fn tap_item_validate_requires_unpublish_after_publish() {
+        let validate = |fields: serde_json::Value| {
+            __inner_tap_item_validate(ItemValidateInput {
+                item_id: None,
+                item_type: "page".into(),
+                title: "Launch".into(),
+                fields,
+                status: 0,
+                user_id: Uuid::now_v7(),
+            })
+        };
+
+        let violations =
+            validate(serde_json::json!({"field_publish_on": 200, "field_unpublish_on": "100"}));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "field_unpublish_on");
+
+        assert!(
+            validate(serde_json::json!({"field_publish_on": "100", "field_unpublish_on": 200}))
+                .is_empty()
+        );
+        assert!(validate(serde_json::json!({"field_unpublish_on": 100})).is_empty());
+    }
+
+    #[test]
+    fn tap_item_validate_rejects_non_timestamps() {
+        let violations = __inner_tap_item_validate(ItemValidateInput {
+            item_id: None,
+            item_type: "page".into(),
+            title: "Launch".into(),
+            fields: serde_json::json!({"field_publish_on": "tomorrow", "field_unpublish_on": ""}),
+            status: 0,
+            user_id: Uuid::now_v7(),
+        });
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "field_publish_on");
+    }
+
+    fn item_form(form_id: &str) -> form::Form {
+        let mut form = form::Form {
+            form_id: form_id.into(),
+            ..Default::default()
+        };
+        form.add_element("title", form::FormElement::textfield());
+        form
+    }
+
+    #[test]
+    fn tap_form_alter_adds_schedule_to_item_forms() {
+        let _host = MockHost::new().install();
+        let form = __inner_tap_form_alter(item_form("item_edit_page"));
+        let schedule = &form.elements["_schedule"];
+        assert_eq!(schedule.type_name(), "fieldset");
+        assert!(schedule.children.contains_key("field_publish_on"));
+        assert!(schedule.children.contains_key("field_unpublish_on"));
+
+        let form = __inner_tap_form_alter(item_form("config_site"));
+        assert!(!form.elements.contains_key("_schedule"));
+    }
+
+    #[test]
+    fn tap_form_alter_respects_permission_and_declared_fields() {
+        {
+            let _host = MockHost::new()
+                .with_user(Uuid::now_v7(), &["access content"])
+                .install();
+            let form = __inner_tap_form_alter(item_form("item_add_page"));
+            assert!(!form.elements.contains_key("_schedule"));
+        }
+
+        let _host = MockHost::new().install();
+        let mut declared = item_form("item_add_event");
+        declared.add_element("field_publish_on", form::FormElement::hidden());
+        let form = __inner_tap_form_alter(declared);
+        assert!(!form.elements.contains_key("_schedule"));
+    }
+
+    #[test]
+    fn tap_item_presave_stores_numbers() {
+        let output = __inner_tap_item_presave(PresaveInput {
+            fields: serde_json::from_value(serde_json::json!({
+                "field_publish_on": " 1700000000 ",
+                "field_unpublish_on": "",
+                "field_body": "x",
+            }))
+            .unwrap(),
+        });
+        assert_eq!(output["fields"]["field_publish_on"], 1_700_000_000);
+        assert!(output["fields"]["field_unpublish_on"].is_null());
+        assert!(output["fields"].get("field_body").is_none());
+
+        let output = __inner_tap_item_presave(PresaveInput {
+            fields: serde_json::from_value(serde_json::json!({"field_publish_on": 100})).unwrap(),
+        });
+        assert_eq!(output, serde_json::json!({}));
+    }
+
     #[test]
     fn tap_cron_returns_counts() {
         let input = CronInput {
             timestamp: 1_700_000_000,
+            ..Default::default()
         };
         let result = __inner_tap_cron(input);
         // Stub host functions return 0 for both
         assert_eq!(result["published"], 0);
         assert_eq!(result["unpublished"], 0);
     }
+
+    fn scheduled(title: &str, field: &str, at: i64) -> trovato_test_utils::TestItem {
+        trovato_test_utils::test_item("page", title).with_field(field, serde_json::json!(at))
+    }
+
+    #[test]
+    fn tap_cron_skips_staged_items_by_default() {
+        let stage = Uuid::now_v7();
+        let host = MockHost::new()
+            .with_item(
+                scheduled("Live", "field_publish_on", 100)
+                    .unpublished()
+                    .to_sdk_item(),
+            )
+            .with_item(
+                scheduled("Staged", "field_publish_on", 100)
+                    .unpublished()
+                    .with_stage(&stage.to_string())
+                    .to_sdk_item(),
+            )
+            .with_execute_result("UPDATE item", 1)
+            .install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 200,
+            ..Default::default()
+        });
+        assert_eq!(result["published"], 1);
+
+        let executed = host.executed();
+        assert_eq!(executed.len(), 1);
+        assert!(executed[0].sql.contains("stage_id = $5::uuid"));
+        assert_eq!(executed[0].params[4], serde_json::json!(LIVE_STAGE_UUID));
+    }
+
+    #[test]
+    fn tap_cron_processes_staged_items_when_enabled() {
+        let stage = Uuid::now_v7();
+        let host = MockHost::new()
+            .with_variable(INCLUDE_STAGED_VAR, "true")
+            .with_query_result(
+                "SELECT DISTINCT stage_id",
+                vec![serde_json::json!({"stage_id": stage.to_string()})],
+            )
+            .with_item(
+                scheduled("Staged", "field_unpublish_on", 100)
+                    .with_stage(&stage.to_string())
+                    .to_sdk_item(),
+            )
+            .with_execute_result("UPDATE item", 1)
+            .install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 200,
+            ..Default::default()
+        });
+        assert_eq!(result["published"], 0);
+        assert_eq!(result["unpublished"], 1);
+        assert_eq!(
+            host.executed()[0].params[4],
+            serde_json::json!(stage.to_string())
+        );
+    }
+
+    #[test]
+    fn tap_cron_ignores_items_not_yet_due_or_changed_concurrently() {
+        let _host = MockHost::new()
+            .with_item(
+                scheduled("Future", "field_publish_on", 500)
+                    .unpublished()
+                    .to_sdk_item(),
+            )
+            .with_item(
+                scheduled("Edited", "field_publish_on", 100)
+                    .unpublished()
+                    .to_sdk_item(),
+            )
+            // The status guard matched no row: someone else changed it.
+            .with_execute_result("UPDATE item", 0)
+            .install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 200,
+            ..Default::default()
+        });
+        assert_eq!(result["published"], 0);
+    }
 }
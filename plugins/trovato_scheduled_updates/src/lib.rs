@@ -0,0 +1,52 @@
+//! Scheduled updates ("timed updates") plugin for Trovato.
+//!
+//! The kernel's scheduled update service, the `/api/item/{id}/scheduled-updates`
+//! API and the `/admin/content/timed-updates` listing are only available
+//! while this plugin is enabled.
+//!
+//! Implements `tap_cron` to apply the updates that are due; the kernel
+//! applies each one as the user who scheduled it.
+
+use trovato_sdk::host;
+use trovato_sdk::prelude::*;
+
+/// Apply due scheduled updates.
+#[plugin_tap]
+pub fn tap_cron(_input: CronInput) -> serde_json::Value {
+    match host::apply_scheduled_updates() {
+        Ok(applied) => {
+            if applied > 0 {
+                host::log(
+                    "info",
+                    "trovato_scheduled_updates",
+                    &format!("applied {applied} scheduled updates"),
+                );
+            }
+            serde_json::json!({"applied": applied})
+        }
+        Err(e) => {
+            host::log(
+                "warn",
+                "trovato_scheduled_updates",
+                &format!("applying scheduled updates failed: {e}"),
+            );
+            serde_json::json!({"error": "failed to apply scheduled updates"})
+        }
+    }
+}
+
+#[cfg(test)]
+// Tests are allowed to use unwrap/expect freely.
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use trovato_test_utils::MockHost;
+
+    #[test]
+    fn tap_cron_applies_due_updates() {
+        let _host = MockHost::new().with_scheduled_updates(3).install();
+
+        let result = __inner_tap_cron(CronInput::default());
+        assert_eq!(result["applied"], 3);
+    }
+}
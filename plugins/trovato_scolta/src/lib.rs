@@ -40,7 +40,7 @@ pub fn tap_menu() -> Vec<MenuDefinition> {
 /// based on the original query and site context. Returns a JSON array of
 /// suggested terms.
 #[allow(dead_code)] // called by plugin route callback at runtime
-fn expand_query(query: &str, site_description: &str) -> Result<String, i32> {
+fn expand_query(query: &str, site_description: &str) -> Result<String, HostError> {
     let prompt = format!(
         "Given a search query on a website described as: \"{site_description}\"\n\n\
          Original query: \"{query}\"\n\n\
@@ -79,7 +79,7 @@ fn expand_query(query: &str, site_description: &str) -> Result<String, i32> {
 /// produces a concise natural-language summary highlighting the most
 /// relevant findings.
 #[allow(dead_code)] // called by plugin route callback at runtime
-fn summarize_results(results_json: &str, query: &str) -> Result<String, i32> {
+fn summarize_results(results_json: &str, query: &str) -> Result<String, HostError> {
     let prompt = format!(
         "The user searched for: \"{query}\"\n\n\
          Here are the search results:\n{results_json}\n\n\
@@ -116,7 +116,7 @@ fn summarize_results(results_json: &str, query: &str) -> Result<String, i32> {
 /// Takes a conversation history (JSON array of messages), a new question,
 /// and search context to continue an interactive search dialogue.
 #[allow(dead_code)] // called by plugin route callback at runtime
-fn follow_up(conversation_json: &str, question: &str, context: &str) -> Result<String, i32> {
+fn follow_up(conversation_json: &str, question: &str, context: &str) -> Result<String, HostError> {
     let mut messages = vec![AiMessage::system(
         "You are a helpful search assistant. Use the provided context to answer \
          follow-up questions about search results. Be concise and cite specific \
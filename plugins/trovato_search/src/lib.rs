@@ -6,33 +6,44 @@
 
 use trovato_sdk::host;
 use trovato_sdk::prelude::*;
-use trovato_sdk::types::LIVE_STAGE_UUID;
+
+/// Key in the cron state remembering the newest change already checked.
+const CHECKED_KEY: &str = "checked_max_changed";
 
 /// Check for content changes and request a Pagefind index rebuild if needed.
 ///
 /// Compares `MAX(changed)` of published live-stage items against the
 /// stored `last_indexed_at` timestamp. If content is newer, sets
 /// `rebuild_requested = true` so the kernel cron task picks it up.
+///
+/// The newest change already checked is kept in the cron state, so runs
+/// with no new content skip the index status query.
 #[plugin_tap]
-pub fn tap_cron(_input: CronInput) -> serde_json::Value {
+pub fn tap_cron(input: CronInput) -> serde_json::Value {
     // Get the most recent change timestamp for published live-stage items
-    let max_changed_json = host::query_raw(
-        "SELECT COALESCE(MAX(changed), 0) as max_changed \
-         FROM item WHERE status = 1 AND stage_id = $1::uuid",
-        &[serde_json::json!(LIVE_STAGE_UUID)],
+    let latest = host::item_query(
+        &ItemQuery::new()
+            .status(1)
+            .sort("changed", SortDirection::Desc)
+            .limit(1),
     );
 
-    let max_changed: i64 = match max_changed_json {
-        Ok(json_str) => {
-            let rows: Vec<serde_json::Value> = serde_json::from_str(&json_str).unwrap_or_default();
-            rows.first()
-                .and_then(|r| r.get("max_changed"))
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0)
-        }
+    let max_changed: i64 = match latest {
+        Ok(items) => items.first().map(|item| item.changed).unwrap_or(0),
         Err(_) => return serde_json::json!({"error": "failed to query max changed"}),
     };
 
+    // Nothing changed since the last check: either the index was current
+    // or a rebuild was already requested.
+    let checked = input
+        .state
+        .as_ref()
+        .and_then(|state| state.get(CHECKED_KEY))
+        .and_then(|v| v.as_i64());
+    if checked == Some(max_changed) {
+        return serde_json::json!({"rebuild_requested": false, "max_changed": max_changed, "unchanged": true});
+    }
+
     // Get the last indexed timestamp
     let status_json = host::query_raw(
         "SELECT last_indexed_at, rebuild_requested \
@@ -59,13 +70,21 @@ pub fn tap_cron(_input: CronInput) -> serde_json::Value {
 
     // If content is newer than last index and no rebuild already pending
     if max_changed > last_indexed_at && !already_requested {
-        let _ = host::execute_raw(
+        let requested = host::execute_raw(
             "UPDATE pagefind_index_status SET rebuild_requested = true WHERE id = 1",
             &[],
-        );
-        serde_json::json!({"rebuild_requested": true, "max_changed": max_changed, "last_indexed_at": last_indexed_at})
+        )
+        .is_ok();
+        // Only remember the change once the rebuild was requested, so a
+        // failed update is retried next run.
+        let state = if requested {
+            serde_json::json!({CHECKED_KEY: max_changed})
+        } else {
+            input.state.unwrap_or(serde_json::Value::Null)
+        };
+        serde_json::json!({"rebuild_requested": true, "max_changed": max_changed, "last_indexed_at": last_indexed_at, "state": state})
     } else {
-        serde_json::json!({"rebuild_requested": false, "max_changed": max_changed, "last_indexed_at": last_indexed_at})
+        serde_json::json!({"rebuild_requested": false, "max_changed": max_changed, "last_indexed_at": last_indexed_at, "state": {CHECKED_KEY: max_changed}})
     }
 }
 
@@ -74,11 +93,13 @@ pub fn tap_cron(_input: CronInput) -> serde_json::Value {
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use trovato_test_utils::MockHost;
 
     #[test]
     fn tap_cron_returns_status() {
         let input = CronInput {
             timestamp: 1_700_000_000,
+            ..Default::default()
         };
         let result = __inner_tap_cron(input);
         // Stub host functions return errors, so we get the error path
@@ -87,4 +108,44 @@ mod tests {
             "Should return either error or rebuild status"
         );
     }
+
+    fn item_changed_at(changed: i64) -> Item {
+        let mut item = trovato_test_utils::test_item("page", "Page").to_sdk_item();
+        item.changed = changed;
+        item
+    }
+
+    #[test]
+    fn tap_cron_requests_rebuild_and_remembers_check() {
+        let host = MockHost::new()
+            .with_item(item_changed_at(500))
+            .with_query_result(
+                "FROM pagefind_index_status",
+                vec![serde_json::json!({"last_indexed_at": 100, "rebuild_requested": false})],
+            )
+            .install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 600,
+            last_run: Some(540),
+            state: Some(serde_json::json!({CHECKED_KEY: 400})),
+        });
+        assert_eq!(result["rebuild_requested"], true);
+        assert_eq!(host.executed().len(), 1);
+        assert_eq!(result["state"][CHECKED_KEY], 500);
+    }
+
+    #[test]
+    fn tap_cron_skips_status_query_when_unchanged() {
+        let host = MockHost::new().with_item(item_changed_at(500)).install();
+
+        let result = __inner_tap_cron(CronInput {
+            timestamp: 600,
+            last_run: Some(540),
+            state: Some(serde_json::json!({CHECKED_KEY: 500})),
+        });
+        assert_eq!(result["unchanged"], true);
+        assert!(result.get("state").is_none());
+        assert!(host.executed().is_empty());
+    }
 }
@@ -53,11 +53,11 @@ pub fn tap_item_view(item: Item) -> String {
         &[serde_json::json!(series_title)],
     ) {
         Ok(json) => json,
-        Err(code) => {
+        Err(e) => {
             host::log(
                 "warn",
                 "trovato_series",
-                &format!("Series query failed with code {code}"),
+                &format!("Series query failed: {e}"),
             );
             return String::new();
         }
@@ -117,6 +117,7 @@
                 el.style.display = 'none';
             });
             var target = document.getElementById('fields-' + type);
+            if (!target && type.indexOf('plugin:') === 0) target = document.getElementById('fields-plugin');
             if (target) target.style.display = 'block';
         }
 